@@ -0,0 +1,181 @@
+#!/usr/bin/env python3
+"""Writes `shaping-test-font.ttf`, a font for testing text shaping.
+
+The font has no outlines, only the metrics and substitutions that shaping depends on:
+Latin `e` with a combining acute accent, and the Arabic letters beh and alef with their
+joining forms, and a fatha. Each glyph has an advance of its own, so that the glyphs a
+string is shaped into can be told apart by their advances.
+"""
+
+import os
+import struct
+
+UNITS_PER_EM = 1024
+
+# (name, code point, advance), in the order of the glyph indices.
+GLYPHS = [
+    (".notdef", None, 500),
+    ("space", 0x0020, 250),
+    ("e", 0x0065, 500),
+    ("acutecomb", 0x0301, 200),
+    ("beh", 0x0628, 600),
+    ("beh.init", None, 610),
+    ("beh.medi", None, 620),
+    ("beh.fina", None, 630),
+    ("alef", 0x0627, 300),
+    ("alef.fina", None, 310),
+    ("fatha", 0x064E, 150),
+]
+GLYPH = {name: index for index, (name, _, _) in enumerate(GLYPHS)}
+
+# The single substitutions of the Arabic joining features, sorted by feature tag.
+FEATURES = [
+    (b"fina", {"beh": "beh.fina", "alef": "alef.fina"}),
+    (b"init", {"beh": "beh.init"}),
+    (b"medi", {"beh": "beh.medi"}),
+]
+
+
+def head():
+    return struct.pack(
+        ">IIIIHHqqhhhhHHhhh",
+        0x00010000,  # version
+        0x00010000,  # fontRevision
+        0,  # checksumAdjustment
+        0x5F0F3CF5,  # magicNumber
+        0,  # flags
+        UNITS_PER_EM,
+        0,  # created
+        0,  # modified
+        0, 0, 0, 0,  # xMin, yMin, xMax, yMax
+        0,  # macStyle
+        8,  # lowestRecPPEM
+        2,  # fontDirectionHint
+        0,  # indexToLocFormat
+        0,  # glyphDataFormat
+    )
+
+
+def hhea():
+    return struct.pack(
+        ">IhhhHhhhhhh8xhH",
+        0x00010000,  # version
+        800,  # ascender
+        -200,  # descender
+        0,  # lineGap
+        max(advance for _, _, advance in GLYPHS),
+        0, 0, 0,  # minLeftSideBearing, minRightSideBearing, xMaxExtent
+        1, 0, 0,  # caretSlopeRise, caretSlopeRun, caretOffset
+        0,  # metricDataFormat
+        len(GLYPHS),  # numberOfHMetrics
+    )
+
+
+def maxp():
+    # Version 0.5, which has no TrueType outline limits.
+    return struct.pack(">IH", 0x00005000, len(GLYPHS))
+
+
+def hmtx():
+    return b"".join(struct.pack(">Hh", advance, 0) for _, _, advance in GLYPHS)
+
+
+def cmap():
+    groups = sorted(
+        (code_point, index)
+        for index, (_, code_point, _) in enumerate(GLYPHS)
+        if code_point is not None
+    )
+    subtable = struct.pack(">HHIII", 12, 0, 16 + 12 * len(groups), 0, len(groups))
+    subtable += b"".join(struct.pack(">III", c, c, index) for c, index in groups)
+    # A single Windows Unicode full repertoire subtable.
+    return struct.pack(">HHHHI", 0, 1, 3, 10, 12) + subtable
+
+
+def coverage(glyphs):
+    return struct.pack(">HH", 1, len(glyphs)) + b"".join(
+        struct.pack(">H", glyph) for glyph in glyphs
+    )
+
+
+def single_substitution(substitutions):
+    pairs = sorted(
+        (GLYPH[source], GLYPH[target]) for source, target in substitutions.items()
+    )
+    header_size = 6 + 2 * len(pairs)
+    subtable = struct.pack(">HHH", 2, header_size, len(pairs))
+    subtable += b"".join(struct.pack(">H", target) for _, target in pairs)
+    return subtable + coverage([source for source, _ in pairs])
+
+
+def gsub():
+    # ScriptList: the `arab` script, whose default language system has every feature.
+    lang_sys = struct.pack(">HHH", 0, 0xFFFF, len(FEATURES))
+    lang_sys += b"".join(struct.pack(">H", index) for index in range(len(FEATURES)))
+    script = struct.pack(">HH", 4, 0) + lang_sys
+    script_list = struct.pack(">H4sH", 1, b"arab", 8) + script
+
+    # FeatureList: feature `i` applies lookup `i`.
+    feature_list = struct.pack(">H", len(FEATURES))
+    offset = 2 + 6 * len(FEATURES)
+    for tag, _ in FEATURES:
+        feature_list += struct.pack(">4sH", tag, offset)
+        offset += 6
+    for index in range(len(FEATURES)):
+        feature_list += struct.pack(">HHH", 0, 1, index)
+
+    # LookupList: a single substitution lookup for each feature.
+    lookups = []
+    for _, substitutions in FEATURES:
+        lookup = struct.pack(">HHHH", 1, 0, 1, 8) + single_substitution(substitutions)
+        lookups.append(lookup)
+    lookup_list = struct.pack(">H", len(lookups))
+    offset = 2 + 2 * len(lookups)
+    for lookup in lookups:
+        lookup_list += struct.pack(">H", offset)
+        offset += len(lookup)
+    lookup_list += b"".join(lookups)
+
+    header_size = 10
+    return (
+        struct.pack(
+            ">HHHHH",
+            1,
+            0,
+            header_size,
+            header_size + len(script_list),
+            header_size + len(script_list) + len(feature_list),
+        )
+        + script_list
+        + feature_list
+        + lookup_list
+    )
+
+
+def font():
+    tables = sorted(
+        {
+            b"GSUB": gsub(),
+            b"cmap": cmap(),
+            b"head": head(),
+            b"hhea": hhea(),
+            b"hmtx": hmtx(),
+            b"maxp": maxp(),
+        }.items()
+    )
+    data = struct.pack(">IHHHH", 0x00010000, len(tables), 64, 2, len(tables) * 16 - 64)
+    offset = 12 + 16 * len(tables)
+    body = b""
+    for tag, table in tables:
+        # Tables are padded to a multiple of four bytes, which their checksums are summed from.
+        padded = table.ljust((len(table) + 3) // 4 * 4, b"\0")
+        checksum = sum(struct.unpack(">%dI" % (len(padded) // 4), padded)) & 0xFFFFFFFF
+        data += struct.pack(">4sIII", tag, checksum, offset + len(body), len(table))
+        body += padded
+    return data + body
+
+
+if __name__ == "__main__":
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "shaping-test-font.ttf")
+    with open(path, "wb") as file:
+        file.write(font())
@@ -0,0 +1,34 @@
+//! Measures the AVM2 interpreter on numeric inner loops.
+//!
+//! The movie runs an integer-heavy and a Number-heavy loop in its first frame script.
+//! Building the player happens outside of the measurement, so only running the frame is timed.
+//! The source of the movie is next to it in `benches/swfs/avm2_arithmetic`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::PlayerBuilder;
+
+const SWF: &[u8] = include_bytes!("swfs/avm2_arithmetic/test.swf");
+
+fn arithmetic_loops(c: &mut Criterion) {
+    c.bench_function("avm2_arithmetic_loops", |b| {
+        b.iter_batched(
+            || {
+                let movie = SwfMovie::from_data(SWF, None, None).expect("Bench movie is valid");
+                let player = PlayerBuilder::new().with_movie(movie).build();
+                while !player
+                    .lock()
+                    .expect("Player is not poisoned")
+                    .preload(&mut ExecutionLimit::exhausted())
+                {}
+                player
+            },
+            |player| player.lock().expect("Player is not poisoned").run_frame(),
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+criterion_group!(benches, arithmetic_loops);
+criterion_main!(benches);
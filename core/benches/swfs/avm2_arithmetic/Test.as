@@ -0,0 +1,29 @@
+package {
+    public class Test {
+    }
+}
+
+// Integer-heavy inner loop, like the collision grids of tile based games.
+function integerLoop() {
+    var hash = 0;
+    for (var i = 0; i < 300000; i++) {
+        var x = i % 640;
+        var y = (i - x) / 640;
+        hash = (hash * 31 + x * 7 - y) % 65521;
+    }
+    return hash;
+}
+
+// Number-heavy inner loop, like a particle integration step.
+function numberLoop() {
+    var position = 0.0;
+    var velocity = 1.5;
+    for (var i = 0; i < 300000; i++) {
+        velocity = velocity * 0.999 + 0.01;
+        position = position + velocity * 0.5;
+    }
+    return position;
+}
+
+trace(integerLoop());
+trace(numberLoop());
@@ -28,9 +28,14 @@ pub use debug::VariableDumper;
 pub use error::Error;
 pub use function::ExecutionReason;
 pub use globals::context_menu::make_context_menu_state;
-pub use globals::shared_object::flush;
+pub use globals::local_connection::{
+    dispatch_status as dispatch_local_connection_status,
+    receive_message as receive_local_connection_message,
+};
+pub use globals::shared_object::{resolve_pending_flush, write_to_storage};
 pub use globals::sound::start as start_sound;
-pub use globals::system::SystemProperties;
+pub use globals::system::{PlayerType, SandboxType, SystemProperties};
+pub use globals::xml_socket::dispatch_event as dispatch_xml_socket_event;
 pub use object::array_object::ArrayObject;
 pub use object::script_object::ScriptObject;
 pub use object::sound_object::SoundObject;
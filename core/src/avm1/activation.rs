@@ -16,7 +16,6 @@ use crate::vminterface::Instantiator;
 use crate::{avm_error, avm_warn};
 use gc_arena::{Gc, GcCell, MutationContext};
 use indexmap::IndexMap;
-use instant::Instant;
 use rand::Rng;
 use ruffle_render::bounding_box::BoundingBox;
 use smallvec::SmallVec;
@@ -1100,9 +1099,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             *self.context.time_offset += 1;
         }
 
-        let time = Instant::now()
-            .duration_since(self.context.start_time)
-            .as_millis() as u32;
+        let time = self.context.clock.elapsed().as_millis() as u32;
         let result = time.wrapping_add(*self.context.time_offset);
         self.context.avm1.push(result.into());
         Ok(FrameControl::Continue)
@@ -1159,7 +1156,6 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             fscommand::handle(fscommand, &fsargs, self)?;
         } else {
             self.context
-                .navigator
                 .navigate_to_url(url.to_owned(), target.into_owned(), None);
         }
 
@@ -1296,7 +1292,6 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         };
 
         self.context
-            .navigator
             .navigate_to_url(url.to_string(), target.to_string(), vars);
 
         Ok(FrameControl::Continue)
@@ -2055,9 +2050,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             _ if use_bitmap_downsamping => StageQuality::Best,
             _ => StageQuality::High,
         };
-        self.context
-            .stage
-            .set_quality(self.context.gc_context, new_quality);
+        let stage = self.context.stage;
+        stage.set_quality(&mut self.context, new_quality);
         self.context
             .stage
             .set_use_bitmap_downsampling(self.context.gc_context, use_bitmap_downsamping);
@@ -2306,7 +2300,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                         Request::get(format!("{url}?{qstring}"))
                     }
                     NavigationMethod::Get => Request::get(format!("{url}&{qstring}")),
-                    NavigationMethod::Post => Request::post(
+                    method => Request::request(
+                        method,
                         url.to_utf8_lossy().into_owned(),
                         Some((
                             qstring.as_bytes().to_owned(),
@@ -20,6 +20,7 @@ pub fn parse(url: &WStr) -> Option<&WStr> {
     }
 }
 
+/// Handle an FSCommand of the movie, warning if nothing handled it.
 pub fn handle<'gc>(
     command: &WStr,
     args: &WStr,
@@ -28,11 +29,7 @@ pub fn handle<'gc>(
     let command = command.to_utf8_lossy();
     let args = args.to_utf8_lossy();
 
-    if !activation
-        .context
-        .external_interface
-        .invoke_fs_command(&command, &args)
-    {
+    if !activation.context.fs_command(&command, &args) {
         avm_warn!(activation, "Unhandled FSCommand: {}", command);
     }
     Ok(())
@@ -323,6 +323,14 @@ pub enum ExecutionName<'gc> {
 }
 
 impl<'gc> Executable<'gc> {
+    /// The scope that ActionScript functions were defined in.
+    pub fn scope(&self) -> Option<Gc<'gc, Scope<'gc>>> {
+        match self {
+            Executable::Native(_) => None,
+            Executable::Action(af) => Some(af.scope()),
+        }
+    }
+
     /// Execute the given code.
     ///
     /// Execution is not guaranteed to have completed when this function
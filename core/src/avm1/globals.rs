@@ -36,7 +36,7 @@ pub mod gradient_bevel_filter;
 pub mod gradient_glow_filter;
 mod key;
 mod load_vars;
-mod local_connection;
+pub(crate) mod local_connection;
 mod math;
 mod matrix;
 pub(crate) mod mouse;
@@ -61,6 +61,7 @@ mod transform;
 mod video;
 mod xml;
 mod xml_node;
+pub(crate) mod xml_socket;
 
 const GLOBAL_DECLS: &[Declaration] = declare_properties! {
     "trace" => method(trace; DONT_ENUM);
@@ -551,6 +552,8 @@ pub fn create_globals<'gc>(
 
     let xml_proto = xml::create_proto(gc_context, xmlnode_proto, function_proto);
 
+    let xml_socket_proto = xml_socket::create_proto(gc_context, object_proto, function_proto);
+
     let string_proto = string::create_proto(gc_context, object_proto, function_proto);
     let number_proto = number::create_proto(gc_context, object_proto, function_proto);
     let boolean_proto = boolean::create_proto(gc_context, object_proto, function_proto);
@@ -680,6 +683,13 @@ pub fn create_globals<'gc>(
         function_proto,
         xml_proto,
     );
+    let xml_socket = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(xml_socket::constructor),
+        constructor_to_fn!(xml_socket::constructor),
+        function_proto,
+        xml_socket_proto,
+    );
     let string = string::create_string_object(gc_context, string_proto, function_proto);
     let number = number::create_number_object(gc_context, number_proto, function_proto);
     let boolean = boolean::create_boolean_object(gc_context, boolean_proto, function_proto);
@@ -971,6 +981,12 @@ pub fn create_globals<'gc>(
     );
     globals.define_value(gc_context, "XMLNode", xmlnode.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "XML", xml.into(), Attribute::DONT_ENUM);
+    globals.define_value(
+        gc_context,
+        "XMLSocket",
+        xml_socket.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(gc_context, "String", string.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Number", number.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Boolean", boolean.into(), Attribute::DONT_ENUM);
@@ -2,7 +2,8 @@ use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::object::NativeObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Activation, Error, Object, ScriptObject, TObject, Value};
-use crate::locale::{get_current_date_time, get_timezone};
+use crate::clock::Clock;
+use crate::locale::get_timezone;
 use crate::string::AvmString;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::fmt;
@@ -68,9 +69,9 @@ impl Date {
         )
     }
 
-    /// Create from current date and time.
-    fn now() -> Self {
-        Self(get_current_date_time().timestamp_millis() as f64)
+    /// Create from the current date and time of the player's clock.
+    fn now(clock: &Clock) -> Self {
+        Self(clock.now().timestamp_millis() as f64)
     }
 
     /// Get milliseconds since epoch.
@@ -360,7 +361,7 @@ fn constructor<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let date = match args[..] {
         [] => {
-            let date = Date::now();
+            let date = Date::now(&activation.context.clock);
             if activation.swf_version() > 7 {
                 Date(date.time().round())
             } else {
@@ -392,7 +393,7 @@ fn function<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(AvmString::new_utf8(
         activation.context.gc_context,
-        Date::now().local().to_string(),
+        Date::now(&activation.context.clock).local().to_string(),
     )
     .into())
 }
@@ -3,14 +3,22 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, Value};
-use crate::external::{Callback, Value as ExternalValue};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::display_object::TDisplayObject;
+use crate::external::{escape_xml, unescape_xml, Callback, Value as ExternalValue};
+use crate::string::AvmString;
 use gc_arena::MutationContext;
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "available" => property(get_available; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "addCallback" => method(add_callback; DONT_ENUM | DONT_DELETE | READ_ONLY);
     "call" => method(call; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "_toXML" => method(to_xml; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "_objectToXML" => method(to_xml; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "_arrayToXML" => method(to_xml; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "_argumentsToXML" => method(arguments_to_xml; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "_escapeXML" => method(escape_xml_method; DONT_ENUM | DONT_DELETE | READ_ONLY);
+    "_unescapeXML" => method(unescape_xml_method; DONT_ENUM | DONT_DELETE | READ_ONLY);
 };
 
 pub fn get_available<'gc>(
@@ -18,7 +26,12 @@ pub fn get_available<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(activation.context.external_interface.available().into())
+    let movie = activation.base_clip().movie();
+    Ok(activation
+        .context
+        .external_interface
+        .available(&movie)
+        .into())
 }
 
 pub fn add_callback<'gc>(
@@ -58,10 +71,11 @@ pub fn call<'gc>(
     }
 
     let name = args.get(0).unwrap().coerce_to_string(activation)?;
+    let movie = activation.base_clip().movie();
     if let Some(method) = activation
         .context
         .external_interface
-        .get_method_for(&name.to_utf8_lossy())
+        .get_method_for(&name.to_utf8_lossy(), &movie)
     {
         let mut external_args = Vec::with_capacity(args.len() - 1);
         for arg in &args[1..] {
@@ -75,6 +89,68 @@ pub fn call<'gc>(
     }
 }
 
+/// Implements `ExternalInterface._toXML`, which encodes a value like the arguments of `call`.
+pub fn to_xml<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let xml = ExternalValue::from_avm1(activation, value)?.to_xml();
+    Ok(AvmString::new_utf8(activation.context.gc_context, xml).into())
+}
+
+/// Implements `ExternalInterface._argumentsToXML`, which encodes the elements of an array from
+/// the given index on as an `<arguments>` list.
+pub fn arguments_to_xml<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let start = match args.get(1) {
+        Some(index) => index.coerce_to_i32(activation)?.max(0) as usize,
+        None => 0,
+    };
+    let mut xml = "<arguments>".to_string();
+    if let Some(Value::Object(arguments)) = args.get(0) {
+        let length = arguments.length(activation)?.max(0) as usize;
+        for i in start..length {
+            let argument = arguments.get_element(activation, i as i32);
+            xml.push_str(&ExternalValue::from_avm1(activation, argument)?.to_xml());
+        }
+    }
+    xml.push_str("</arguments>");
+    Ok(AvmString::new_utf8(activation.context.gc_context, xml).into())
+}
+
+/// Implements `ExternalInterface._escapeXML`
+pub fn escape_xml_method<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let text = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let escaped = escape_xml(&text.to_utf8_lossy());
+    Ok(AvmString::new_utf8(activation.context.gc_context, escaped).into())
+}
+
+/// Implements `ExternalInterface._unescapeXML`
+pub fn unescape_xml_method<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let text = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let unescaped = unescape_xml(&text.to_utf8_lossy());
+    Ok(AvmString::new_utf8(activation.context.gc_context, unescaped).into())
+}
+
 pub fn create_external_interface_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
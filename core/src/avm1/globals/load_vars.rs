@@ -186,11 +186,9 @@ fn send<'gc>(
         );
     }
 
-    activation.context.navigator.navigate_to_url(
-        url.to_string(),
-        window,
-        Some((method, form_values)),
-    );
+    activation
+        .context
+        .navigate_to_url(url.to_string(), window, Some((method, form_values)));
 
     Ok(true.into())
 }
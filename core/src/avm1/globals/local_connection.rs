@@ -2,43 +2,188 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::function::ExecutionReason;
+use crate::avm1::globals::shared_object::{deserialize_value, serialize_value};
+use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, Value};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::backend::local_connection::LocalConnectionMessage;
+use crate::context::ActionType;
 use crate::display_object::TDisplayObject;
+use crate::local_connection::{
+    connect_name, decode_arguments, encode_arguments, movie_domain, send_name,
+    LocalConnectionManager, LocalConnectionObject, MAX_ARGUMENTS_SIZE, RESERVED_METHOD_NAMES,
+};
 use crate::string::AvmString;
+use flash_lso::types::Value as AmfValue;
 use gc_arena::MutationContext;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "domain" => method(domain; DONT_DELETE | READ_ONLY);
+    "connect" => method(connect; DONT_DELETE | DONT_ENUM);
+    "close" => method(close; DONT_DELETE | DONT_ENUM);
+    "send" => method(send; DONT_DELETE | DONT_ENUM);
 };
 
+/// The domain of the movie running the current code.
+fn current_domain(activation: &mut Activation<'_, '_>) -> Option<String> {
+    let movie = activation.base_clip().movie();
+    movie_domain(movie.url())
+}
+
 pub fn domain<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let movie = activation.base_clip().movie();
-
-    let domain = if let Some(url) = movie.url() {
-        if let Ok(url) = url::Url::parse(url) {
-            if url.scheme() == "file" {
-                "localhost".into()
-            } else if let Some(domain) = url.domain() {
-                AvmString::new_utf8(activation.context.gc_context, domain)
-            } else {
-                // no domain?
-                "localhost".into()
-            }
-        } else {
+    match current_domain(activation) {
+        Some(domain) => Ok(AvmString::new_utf8(activation.context.gc_context, domain).into()),
+        None => {
             tracing::error!("LocalConnection::domain: Unable to parse movie URL");
-            return Ok(Value::Null);
+            Ok(Value::Null)
         }
-    } else {
-        // No URL (loading local data).
-        "localhost".into()
+    }
+}
+
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(Value::String(name)) => name.to_utf8_lossy().into_owned(),
+        _ => return Ok(false.into()),
+    };
+    if name.is_empty() || name.contains(':') {
+        return Ok(false.into());
+    }
+
+    let object = LocalConnectionObject::Avm1(this);
+    if activation
+        .context
+        .local_connection_manager
+        .is_connected(object)
+    {
+        return Ok(false.into());
+    }
+
+    let domain = current_domain(activation).unwrap_or_default();
+    let connected = LocalConnectionManager::connect(
+        &mut activation.context,
+        connect_name(&domain, &name),
+        object,
+    );
+    Ok(connected.into())
+}
+
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    LocalConnectionManager::close(&mut activation.context, LocalConnectionObject::Avm1(this));
+    Ok(Value::Undefined)
+}
+
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (name, method_name) = match args {
+        [Value::String(name), Value::String(method_name), ..] => (
+            name.to_utf8_lossy().into_owned(),
+            method_name.to_utf8_lossy().into_owned(),
+        ),
+        _ => return Ok(false.into()),
     };
+    if name.is_empty() || method_name.is_empty() || RESERVED_METHOD_NAMES.contains(&&*method_name) {
+        return Ok(false.into());
+    }
+
+    let arguments: Vec<_> = args[2..]
+        .iter()
+        .map(|arg| serialize_value(activation, *arg).unwrap_or(AmfValue::Undefined))
+        .collect();
+    let arguments = encode_arguments(arguments);
+    if arguments.len() > MAX_ARGUMENTS_SIZE {
+        return Ok(false.into());
+    }
+
+    let domain = current_domain(activation).unwrap_or_default();
+    let message = LocalConnectionMessage {
+        connection_name: send_name(&domain, &name),
+        method_name,
+        sender_domain: domain,
+        arguments,
+    };
+    LocalConnectionManager::send(
+        &mut activation.context,
+        LocalConnectionObject::Avm1(this),
+        message,
+    );
+    Ok(true.into())
+}
+
+/// Calls the method named by a message received on the connection of `this`.
+///
+/// Messages from other domains are only accepted if the `allowDomain` handler of the
+/// connection returns `true` for the domain of the sender.
+pub fn receive_message<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    message: LocalConnectionMessage,
+) -> Result<(), Error<'gc>> {
+    if current_domain(activation).as_ref() != Some(&message.sender_domain) {
+        let sender_domain =
+            AvmString::new_utf8(activation.context.gc_context, message.sender_domain);
+        let allowed = this.call_method(
+            "allowDomain".into(),
+            &[sender_domain.into()],
+            activation,
+            ExecutionReason::Special,
+        )?;
+        if !allowed.as_bool(activation.swf_version()) {
+            return Ok(());
+        }
+    }
+
+    let args: Vec<_> = decode_arguments(&message.arguments)
+        .iter()
+        .map(|value| deserialize_value(activation, value))
+        .collect();
+    let method_name = AvmString::new_utf8(activation.context.gc_context, message.method_name);
+    this.call_method(method_name, &args, activation, ExecutionReason::Special)?;
+    Ok(())
+}
+
+/// Calls the `onStatus` handler of `this` with the outcome of a message it sent.
+pub fn dispatch_status<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    delivered: bool,
+) {
+    let level = if delivered { "status" } else { "error" };
+    let info = ScriptObject::new(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    info.define_value(
+        activation.context.gc_context,
+        "level",
+        level.into(),
+        Attribute::empty(),
+    );
 
-    Ok(Value::String(domain))
+    activation.context.action_queue.queue_action(
+        activation.context.stage.root_clip(),
+        ActionType::Method {
+            object: this,
+            name: "onStatus",
+            args: vec![info.into()],
+        },
+        false,
+    );
 }
 
 pub fn constructor<'gc>(
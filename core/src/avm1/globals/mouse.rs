@@ -15,8 +15,9 @@ pub fn show_mouse<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let was_visible = activation.context.ui.mouse_visible();
-    activation.context.ui.set_mouse_visible(true);
+    let context = &mut activation.context;
+    let was_visible = !context.cursors.is_hidden();
+    context.cursors.set_hidden(false, context.ui);
     Ok(if was_visible { 0 } else { 1 }.into())
 }
 
@@ -25,8 +26,9 @@ pub fn hide_mouse<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let was_visible = activation.context.ui.mouse_visible();
-    activation.context.ui.set_mouse_visible(false);
+    let context = &mut activation.context;
+    let was_visible = !context.cursors.is_hidden();
+    context.cursors.set_hidden(true, context.ui);
     Ok(if was_visible { 0 } else { 1 }.into())
 }
 
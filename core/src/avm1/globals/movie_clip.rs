@@ -76,6 +76,7 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "getInstanceAtDepth" => method(mc_method!(get_instance_at_depth); DONT_ENUM | DONT_DELETE | VERSION_7);
     "getNextHighestDepth" => method(mc_method!(get_next_highest_depth); DONT_ENUM | DONT_DELETE | VERSION_7);
     "getRect" => method(mc_method!(get_rect); DONT_ENUM | DONT_DELETE | VERSION_8);
+    "getSWFVersion" => method(mc_method!(get_swf_version); DONT_ENUM | DONT_DELETE | VERSION_7);
     "getURL" => method(mc_method!(get_url); DONT_ENUM | DONT_DELETE);
     "globalToLocal" => method(mc_method!(global_to_local); DONT_ENUM | DONT_DELETE);
     "gotoAndPlay" => method(mc_method!(goto_and_play); DONT_ENUM | DONT_DELETE);
@@ -928,6 +929,20 @@ fn get_next_highest_depth<'gc>(
     }
 }
 
+/// Implements `MovieClip.getSWFVersion`, the version of the SWF that the clip comes from.
+fn get_swf_version<'gc>(
+    movie_clip: MovieClip<'gc>,
+    _activation: &mut Activation<'_, 'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if movie_clip.loaded_image() {
+        // Clips that loaded an image have no SWF version.
+        Ok((-1).into())
+    } else {
+        Ok(movie_clip.movie().version().into())
+    }
+}
+
 fn goto_and_play<'gc>(
     movie_clip: MovieClip<'gc>,
     activation: &mut Activation<'_, 'gc>,
@@ -1264,7 +1279,6 @@ pub fn get_url<'gc>(
 
         activation
             .context
-            .navigator
             .navigate_to_url(url.to_string(), window, vars_method);
     }
 
@@ -129,6 +129,7 @@ pub fn set_focus<'gc>(
             if let Some(display_object) = obj.as_display_object() {
                 if display_object.is_focusable() {
                     tracker.set(Some(display_object), &mut activation.context);
+                    tracker.highlight(activation.context.gc_context);
                 }
                 // [NA] Note: The documentation says true is success and false is failure,
                 // but from testing this seems to be opposite.
@@ -7,12 +7,13 @@ use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, ScriptObject, TObject, Value};
 use crate::avm1_stub;
+use crate::context::ActionType;
 use crate::display_object::TDisplayObject;
+use crate::local_storage::{load_shared_object, shared_object_path, FlushStatus};
 use crate::string::AvmString;
 use flash_lso::types::Value as AmfValue;
 use flash_lso::types::{AMFVersion, Element, Lso};
 use gc_arena::MutationContext;
-use std::borrow::Cow;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "clear" => method(clear; DONT_ENUM | DONT_DELETE);
@@ -55,7 +56,7 @@ pub fn get_disk_usage<'gc>(
 }
 
 /// Serialize a Value to an AmfValue
-fn serialize_value<'gc>(
+pub fn serialize_value<'gc>(
     activation: &mut Activation<'_, 'gc>,
     elem: Value<'gc>,
 ) -> Option<AmfValue> {
@@ -111,7 +112,7 @@ fn recursive_serialize<'gc>(
 }
 
 /// Deserialize a AmfValue to a Value
-fn deserialize_value<'gc>(activation: &mut Activation<'_, 'gc>, val: &AmfValue) -> Value<'gc> {
+pub fn deserialize_value<'gc>(activation: &mut Activation<'_, 'gc>, val: &AmfValue) -> Value<'gc> {
     match val {
         AmfValue::Null => Value::Null,
         AmfValue::Undefined => Value::Undefined,
@@ -217,120 +218,26 @@ pub fn get_local<'gc>(
     _this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // TODO: It appears that Flash does some kind of escaping here:
-    // the name "foo\uD800" correspond to a file named "fooE#FB#FB#D.sol".
-
     let name = args
         .get(0)
         .unwrap_or(&Value::Undefined)
         .coerce_to_string(activation)?;
-    let name = name.to_utf8_lossy();
-
-    const INVALID_CHARS: &str = "~%&\\;:\"',<>?# ";
-    if name.contains(|c| INVALID_CHARS.contains(c)) {
-        tracing::error!("SharedObject::get_local: Invalid character in name");
-        return Ok(Value::Null);
-    }
-
-    let movie = activation.base_clip().movie();
-
-    let mut movie_url = if let Some(url) = movie.url() {
-        if let Ok(url) = url::Url::parse(url) {
-            url
-        } else {
-            tracing::error!("SharedObject::get_local: Unable to parse movie URL");
-            return Ok(Value::Null);
-        }
-    } else {
-        // No URL (loading local data). Use a dummy URL to allow SharedObjects to work.
-        url::Url::parse("file://localhost").unwrap()
+    let local_path = match args.get(1) {
+        Some(Value::String(local_path)) => Some(local_path.to_utf8_lossy()),
+        _ => None,
     };
-    movie_url.set_query(None);
-    movie_url.set_fragment(None);
-
     let secure = args
         .get(2)
         .unwrap_or(&Value::Undefined)
         .as_bool(activation.swf_version());
 
-    // Secure parameter disallows using the shared object from non-HTTPS.
-    if secure && movie_url.scheme() != "https" {
-        tracing::warn!(
-            "SharedObject.get_local: Tried to load a secure shared object from non-HTTPS origin"
-        );
-        return Ok(Value::Null);
-    }
-
-    // Shared objects are sandboxed per-domain.
-    // By default, they are keyed based on the SWF URL, but the `localHost` parameter can modify this path.
-    let mut movie_path = movie_url.path();
-    // Remove leading/trailing slashes.
-    movie_path = movie_path.strip_prefix('/').unwrap_or(movie_path);
-    movie_path = movie_path.strip_suffix('/').unwrap_or(movie_path);
-
-    let movie_host = if movie_url.scheme() == "file" {
-        // Remove drive letter on Windows (TODO: move this logic into DiskStorageBackend?)
-        if let [_, b':', b'/', ..] = movie_path.as_bytes() {
-            movie_path = &movie_path[3..];
-        }
-        "localhost"
-    } else {
-        movie_url.host_str().unwrap_or_default()
-    };
-
-    let local_path = if let Some(Value::String(local_path)) = args.get(1) {
-        // Empty local path always fails.
-        if local_path.is_empty() {
-            return Ok(Value::Null);
-        }
-
-        // Remove leading/trailing slashes.
-        let mut local_path = local_path.to_utf8_lossy();
-        if local_path.ends_with('/') {
-            match &mut local_path {
-                Cow::Owned(p) => {
-                    p.pop();
-                }
-                Cow::Borrowed(p) => *p = &p[..p.len() - 1],
-            }
-        }
-        if local_path.starts_with('/') {
-            match &mut local_path {
-                Cow::Owned(p) => {
-                    p.remove(0);
-                }
-                Cow::Borrowed(p) => *p = &p[1..],
-            }
-        }
-
-        // Verify that local_path is a prefix of the SWF path.
-        if movie_path.starts_with(local_path.as_ref())
-            && (local_path.is_empty()
-                || movie_path.len() == local_path.len()
-                || movie_path[local_path.len()..].starts_with('/'))
-        {
-            local_path
-        } else {
-            tracing::warn!("SharedObject.get_local: localPath parameter does not match SWF path");
-            return Ok(Value::Null);
-        }
-    } else {
-        Cow::Borrowed(movie_path)
+    let name = name.to_utf8_lossy();
+    let movie = activation.base_clip().movie();
+    let full_name = match shared_object_path(movie.url(), &name, local_path.as_deref(), secure) {
+        Some(full_name) => full_name,
+        None => return Ok(Value::Null),
     };
 
-    // Final SO path: foo.com/folder/game.swf/SOName
-    // SOName may be a path containing slashes. In this case, prefix with # to mimic Flash Player behavior.
-    let prefix = if name.contains('/') { "#" } else { "" };
-    let full_name = format!("{movie_host}/{local_path}/{prefix}{name}");
-
-    // Avoid any paths with `..` to prevent SWFs from crawling the file system on desktop.
-    // Flash will generally fail to save shared objects with a path component starting with `.`,
-    // so let's disallow them altogether.
-    if full_name.split('/').any(|s| s.starts_with('.')) {
-        tracing::error!("SharedObject.get_local: Invalid path with .. segments");
-        return Ok(Value::Null);
-    }
-
     // Check if this is referencing an existing shared object
     if let Some(so) = activation.context.avm1_shared_objects.get(&full_name) {
         return Ok((*so).into());
@@ -353,8 +260,15 @@ pub fn get_local<'gc>(
     let mut data = Value::Undefined;
 
     // Load the data object from storage if it existed prior
-    if let Some(saved) = activation.context.storage.get(&full_name) {
-        if let Ok(lso) = flash_lso::read::Reader::default().parse(&saved) {
+    let saved = load_shared_object(
+        activation.context.storage,
+        &full_name,
+        movie.url(),
+        &name,
+        local_path.as_deref(),
+    );
+    if let Some(saved) = saved {
+        if let Some(lso) = crate::local_storage::read_lso(&full_name, &saved) {
             data = deserialize_lso(activation, &lso)?.into();
         }
     }
@@ -473,11 +387,11 @@ pub fn connect<'gc>(
     Ok(Value::Undefined)
 }
 
-pub fn flush<'gc>(
+/// Serializes the data of a shared object into the bytes of a `.sol` file.
+fn serialize_data<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
+) -> Result<Vec<u8>, Error<'gc>> {
     let data = this.get("data", activation)?.coerce_to_object(activation);
 
     let this_obj = this.as_shared_object().unwrap();
@@ -487,17 +401,94 @@ pub fn flush<'gc>(
     recursive_serialize(activation, data, &mut elements);
     let mut lso = Lso::new(
         elements,
-        &name
-            .split('/')
-            .last()
-            .map(|e| e.to_string())
-            .unwrap_or_else(|| "<unknown>".to_string()),
+        crate::local_storage::sol_name(&name),
         AMFVersion::AMF0,
     );
 
-    let bytes = flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default();
+    Ok(flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default())
+}
+
+/// Writes the data of a shared object to storage, regardless of the storage quota.
+///
+/// This is used when the player shuts down, so that no data is lost.
+pub fn write_to_storage<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+) -> Result<bool, Error<'gc>> {
+    let bytes = serialize_data(activation, this)?;
+    let name = this.as_shared_object().unwrap().get_name();
+    Ok(activation.context.storage.put(&name, &bytes))
+}
+
+/// Finishes a flush that was waiting for the user to grant a larger storage quota,
+/// and notifies the shared object via `onStatus`.
+pub fn resolve_pending_flush<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    granted: bool,
+) -> Result<(), Error<'gc>> {
+    let success = granted && write_to_storage(activation, this)?;
+    let (code, level) = if success {
+        ("SharedObject.Flush.Success", "status")
+    } else {
+        ("SharedObject.Flush.Failed", "error")
+    };
+
+    let info = ScriptObject::new(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    info.define_value(
+        activation.context.gc_context,
+        "code",
+        code.into(),
+        Attribute::empty(),
+    );
+    info.define_value(
+        activation.context.gc_context,
+        "level",
+        level.into(),
+        Attribute::empty(),
+    );
+
+    activation.context.action_queue.queue_action(
+        activation.context.stage.root_clip(),
+        ActionType::Method {
+            object: this,
+            name: "onStatus",
+            args: vec![info.into()],
+        },
+        false,
+    );
+    Ok(())
+}
+
+pub fn flush<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let min_disk_space = match args.get(0) {
+        Some(value) => value.coerce_to_i32(activation)?.max(0) as usize,
+        None => 0,
+    };
+
+    let bytes = serialize_data(activation, this)?;
+    let name = this.as_shared_object().unwrap().get_name();
+
+    let dialog_manager = &mut activation.context.dialog_manager;
+    let dialogs = &mut activation.context.dialogs;
+    let status = activation.context.storage_quota.request(
+        activation.context.storage,
+        &name,
+        bytes.len().max(min_disk_space),
+        |request| dialog_manager.show_storage_quota_prompt(*dialogs, request),
+    );
 
-    Ok(activation.context.storage.put(&name, &bytes).into())
+    Ok(match status {
+        FlushStatus::Flushed => activation.context.storage.put(&name, &bytes).into(),
+        FlushStatus::Pending => "pending".into(),
+    })
 }
 
 pub fn get_size<'gc>(
@@ -6,6 +6,7 @@ use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::runtime::Avm1;
 use crate::avm1::{ScriptObject, TObject, Value};
 use crate::avm1_stub;
+use crate::config::PlayerRuntime;
 use bitflags::bitflags;
 use core::fmt;
 use gc_arena::MutationContext;
@@ -40,7 +41,7 @@ impl fmt::Display for CpuArchitecture {
 }
 
 /// Available type of sandbox for a given SWF
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SandboxType {
     Remote,
     LocalWithFile,
@@ -202,7 +203,7 @@ impl fmt::Display for ScreenColor {
     }
 }
 /// The type of the player
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerType {
     StandAlone,
     External,
@@ -272,6 +273,8 @@ pub struct SystemProperties {
     pub capabilities: SystemCapabilities,
     /// The type of the player
     pub player_type: PlayerType,
+    /// The runtime the player presents itself as
+    pub player_runtime: PlayerRuntime,
     /// The type of screen available to the player
     pub screen_color: ScreenColor,
     /// The language of the host os
@@ -405,6 +408,7 @@ impl Default for SystemProperties {
             use_codepage: false,
             capabilities: SystemCapabilities::empty(),
             player_type: PlayerType::StandAlone,
+            player_runtime: PlayerRuntime::FlashPlayer,
             screen_color: ScreenColor::Color,
             // TODO: note for fp <7 this should be the locale and the ui lang for >= 7, on windows
             language: Language::English,
@@ -4,8 +4,10 @@ use crate::avm1::object::Object;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{ScriptObject, Value};
 use crate::avm1_stub;
+use crate::display_object::TDisplayObject;
 use crate::string::AvmString;
 use gc_arena::MutationContext;
+use std::sync::Arc;
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "PolicyFileResolver" => method(policy_file_resolver);
@@ -17,30 +19,55 @@ const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "chooseLocalSwfPath" => property(get_choose_local_swf_path);
 };
 
+/// Lets the movies from the given domains script the calling movie.
+fn allow_domains<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    args: &[Value<'gc>],
+    insecure: bool,
+) -> Result<(), Error<'gc>> {
+    let movie = activation.base_clip().movie();
+    for domain in args {
+        let domain = domain.coerce_to_string(activation)?;
+        activation.context.policy_manager.allow_domain(
+            movie.url(),
+            &domain.to_utf8_lossy(),
+            insecure,
+        );
+    }
+    Ok(())
+}
+
 fn allow_domain<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "System.security", "allowDomain");
+    allow_domains(activation, args, false)?;
     Ok(Value::Undefined)
 }
 
 fn allow_insecure_domain<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "System.security", "allowInsecureDomain");
+    allow_domains(activation, args, true)?;
     Ok(Value::Undefined)
 }
 
 fn load_policy_file<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "System.security", "loadPolicyFile");
+    let url = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    activation
+        .context
+        .policy_manager
+        .load_policy_file(activation.context.swf.url(), &url.to_utf8_lossy());
     Ok(Value::Undefined)
 }
 
@@ -58,11 +85,17 @@ fn get_sandbox_type<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(AvmString::new_utf8(
-        activation.context.gc_context,
-        activation.context.system.sandbox_type.to_string(),
-    )
-    .into())
+    let movie = activation.base_clip().movie();
+    // The root movie's sandbox also accounts for the URL it pretends to be loaded from.
+    let sandbox_type = if Arc::ptr_eq(&movie, activation.context.swf) {
+        activation.context.system.sandbox_type
+    } else {
+        activation
+            .context
+            .policy_manager
+            .sandbox_type(movie.url(), movie.use_network_sandbox())
+    };
+    Ok(AvmString::new_utf8(activation.context.gc_context, sandbox_type.to_string()).into())
 }
 
 fn get_choose_local_swf_path<'gc>(
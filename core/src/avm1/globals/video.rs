@@ -3,9 +3,44 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::object::Object;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::value::Value;
-use crate::avm1::ScriptObject;
+use crate::avm1::{ScriptObject, TObject};
+use crate::display_object::{TDisplayObject, Video};
 use gc_arena::MutationContext;
+use swf::VideoDeblocking;
+
+macro_rules! video_getter {
+    ($name:ident) => {
+        |activation, this, _args| {
+            if let Some(display_object) = this.as_display_object() {
+                if let Some(video) = display_object.as_video() {
+                    return $name(video, activation);
+                }
+            }
+            Ok(Value::Undefined)
+        }
+    };
+}
+
+macro_rules! video_setter {
+    ($name:ident) => {
+        |activation, this, args| {
+            if let Some(display_object) = this.as_display_object() {
+                if let Some(video) = display_object.as_video() {
+                    let value = args.get(0).unwrap_or(&Value::Undefined).clone();
+                    $name(video, activation, value)?;
+                }
+            }
+            Ok(Value::Undefined)
+        }
+    };
+}
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "smoothing" => property(video_getter!(smoothing), video_setter!(set_smoothing));
+    "deblocking" => property(video_getter!(deblocking), video_setter!(set_deblocking));
+};
 
 /// Implements `Video`
 pub fn constructor<'gc>(
@@ -19,8 +54,46 @@ pub fn constructor<'gc>(
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
-    _fn_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
 ) -> Object<'gc> {
     let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
     object.into()
 }
+
+fn smoothing<'gc>(
+    this: Video<'gc>,
+    _activation: &mut Activation<'_, 'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.smoothing().into())
+}
+
+fn set_smoothing<'gc>(
+    this: Video<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let smoothing = value.as_bool(activation.swf_version());
+    this.set_smoothing(activation.context.gc_context, smoothing);
+    Ok(())
+}
+
+fn deblocking<'gc>(
+    this: Video<'gc>,
+    _activation: &mut Activation<'_, 'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((this.deblocking() as i32).into())
+}
+
+fn set_deblocking<'gc>(
+    this: Video<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let deblocking = u8::try_from(value.coerce_to_i32(activation)?)
+        .ok()
+        .and_then(VideoDeblocking::from_u8)
+        .unwrap_or(VideoDeblocking::UseVideoPacketValue);
+    this.set_deblocking(&mut activation.context, deblocking);
+    Ok(())
+}
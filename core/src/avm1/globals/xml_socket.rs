@@ -0,0 +1,135 @@
+//! XMLSocket class
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::ExecutionReason;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::display_object::TDisplayObject;
+use crate::socket::{movie_host, SocketEvent, SocketKind, SocketManager, SocketObject};
+use crate::string::AvmString;
+use gc_arena::MutationContext;
+
+/// How long a connection may take to be established, in milliseconds.
+const CONNECT_TIMEOUT: u32 = 20000;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "send" => method(send; DONT_ENUM | DONT_DELETE);
+    "onData" => method(on_data; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let host = match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Undefined | Value::Null => movie_host(activation.base_clip().movie().url()),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+    let port = match u16::try_from(port) {
+        Ok(port) if port != 0 => port,
+        _ => return Ok(false.into()),
+    };
+
+    SocketManager::connect(
+        &mut activation.context,
+        SocketObject::Avm1(this),
+        host,
+        port,
+        SocketKind::Xml,
+        CONNECT_TIMEOUT,
+    );
+    Ok(true.into())
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    SocketManager::close(&mut activation.context, SocketObject::Avm1(this));
+    Ok(Value::Undefined)
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let data = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let mut data = data.to_utf8_lossy().into_owned().into_bytes();
+    data.push(0);
+    SocketManager::send(&mut activation.context, SocketObject::Avm1(this), data);
+    Ok(Value::Undefined)
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Default implementation parses the message and forwards it to onXML.
+    let src = args.get(0).copied().unwrap_or(Value::Undefined);
+    let xml_constructor = activation.context.avm1.prototypes().xml_constructor;
+    let xml = xml_constructor.construct(activation, &[src])?;
+    this.call_method(
+        "onXML".into(),
+        &[xml],
+        activation,
+        ExecutionReason::FunctionCall,
+    )?;
+    Ok(Value::Undefined)
+}
+
+/// Calls the handler of `this` matching something that happened to its connection.
+pub fn dispatch_event<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    event: SocketEvent,
+) -> Result<(), Error<'gc>> {
+    let (name, args): (_, Vec<Value<'gc>>) = match event {
+        SocketEvent::Connect => ("onConnect", vec![true.into()]),
+        SocketEvent::IoError { .. }
+        | SocketEvent::Timeout { .. }
+        | SocketEvent::SecurityError { .. } => ("onConnect", vec![false.into()]),
+        SocketEvent::Message(message) => {
+            let message = AvmString::new_utf8(activation.context.gc_context, message);
+            ("onData", vec![message.into()])
+        }
+        SocketEvent::Close => ("onClose", vec![]),
+        // XML sockets only receive complete messages.
+        SocketEvent::Data(_) | SocketEvent::Datagram(_) | SocketEvent::Accept(_) => return Ok(()),
+    };
+
+    this.call_method(name.into(), &args, activation, ExecutionReason::Special)?;
+    Ok(())
+}
@@ -143,6 +143,26 @@ impl<'gc> ScriptObject<'gc> {
             })
             .collect()
     }
+
+    /// The objects stored in the properties of this object, including hidden
+    /// properties and the getters and setters of virtual properties.
+    pub fn stored_objects(&self) -> Vec<(AvmString<'gc>, Object<'gc>)> {
+        self.0
+            .read()
+            .properties
+            .iter()
+            .flat_map(|(k, p)| {
+                let data = match p.data() {
+                    Value::Object(object) => Some(object),
+                    _ => None,
+                };
+                [data, p.getter(), p.setter()]
+                    .into_iter()
+                    .flatten()
+                    .map(move |object| (k, object))
+            })
+            .collect()
+    }
 }
 
 impl<'gc> TObject<'gc> for ScriptObject<'gc> {
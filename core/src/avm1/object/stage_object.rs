@@ -7,7 +7,7 @@ use crate::avm1::{Object, ObjectPtr, ScriptObject, TObject, Value};
 use crate::avm_warn;
 use crate::context::UpdateContext;
 use crate::display_object::{
-    DisplayObject, EditText, MovieClip, TDisplayObject, TDisplayObjectContainer,
+    DisplayObject, EditText, MovieClip, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
 };
 use crate::string::{AvmString, WStr};
 use crate::types::Percent;
@@ -693,25 +693,40 @@ fn set_high_quality<'gc>(
         } else {
             StageQuality::High
         };
-        activation
-            .context
-            .stage
-            .set_quality(activation.context.gc_context, quality);
+        let stage = activation.context.stage;
+        stage.set_quality(&mut activation.context, quality);
     }
     Ok(())
 }
 
-fn focus_rect<'gc>(activation: &mut Activation<'_, 'gc>, _this: DisplayObject<'gc>) -> Value<'gc> {
-    avm_warn!(activation, "Unimplemented property _focusrect");
-    Value::Null
+fn focus_rect<'gc>(activation: &mut Activation<'_, 'gc>, this: DisplayObject<'gc>) -> Value<'gc> {
+    // On the root of a level, `_focusrect` is the global setting.
+    if this.is_root() {
+        return activation.context.stage.stage_focus_rect().into();
+    }
+    match this.as_interactive().and_then(|this| this.focus_rect()) {
+        Some(focus_rect) => focus_rect.into(),
+        None => Value::Null,
+    }
 }
 
 fn set_focus_rect<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    this: DisplayObject<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    avm_warn!(activation, "Unimplemented property _focusrect");
+    let focus_rect = match val {
+        Value::Undefined | Value::Null => None,
+        val => Some(val.as_bool(activation.swf_version())),
+    };
+    if this.is_root() {
+        activation
+            .context
+            .stage
+            .set_stage_focus_rect(activation.context.gc_context, focus_rect.unwrap_or(true));
+    } else if let Some(this) = this.as_interactive() {
+        this.set_focus_rect(activation.context.gc_context, focus_rect);
+    }
     Ok(())
 }
 
@@ -745,10 +760,8 @@ fn set_quality<'gc>(
     val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
     if let Ok(quality) = val.coerce_to_string(activation)?.parse() {
-        activation
-            .context
-            .stage
-            .set_quality(activation.context.gc_context, quality);
+        let stage = activation.context.stage;
+        stage.set_quality(&mut activation.context, quality);
     }
     Ok(())
 }
@@ -22,16 +22,21 @@ macro_rules! avm_debug {
 }
 
 pub mod activation;
-mod amf;
+pub mod amf;
 mod array;
 pub mod bytearray;
 mod call_stack;
 mod class;
 mod domain;
+mod e4x;
 pub mod error;
 mod events;
 mod function;
 pub mod globals;
+mod inline_cache;
+#[cfg(feature = "avm2_jit")]
+mod jit;
+mod metadata;
 mod method;
 mod multiname;
 mod namespace;
@@ -96,6 +101,9 @@ pub struct Avm2<'gc> {
     #[collect(require_static)]
     native_instance_init_table: &'static [Option<(&'static str, NativeMethodImpl)>],
 
+    #[collect(require_static)]
+    native_call_handler_table: &'static [Option<(&'static str, NativeMethodImpl)>],
+
     /// A list of objects which are capable of recieving broadcasts.
     ///
     /// Certain types of events are "broadcast events" that are emitted on all
@@ -106,6 +114,25 @@ pub struct Avm2<'gc> {
     /// collector does not support weak references.
     broadcast_list: FnvHashMap<AvmString<'gc>, Vec<Object<'gc>>>,
 
+    /// Classes registered with `flash.net.registerClassAlias`, by alias.
+    ///
+    /// AMF uses these names to serialize typed objects.
+    alias_to_class_map: FnvHashMap<AvmString<'gc>, ClassObject<'gc>>,
+
+    /// The alias registered for each class in `alias_to_class_map`.
+    class_to_alias_map: FnvHashMap<ClassObject<'gc>, AvmString<'gc>>,
+
+    /// The URI set with `default xml namespace`, if any.
+    ///
+    /// This is dynamically scoped: it is restored when the method that set it returns, and
+    /// methods called from it see the caller's namespace until they set their own.
+    default_xml_namespace: Option<AvmString<'gc>>,
+
+    /// The compiler for hot loops, if it's enabled.
+    #[cfg(feature = "avm2_jit")]
+    #[collect(require_static)]
+    jit: Option<jit::Jit>,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -124,7 +151,14 @@ impl<'gc> Avm2<'gc> {
             native_method_table: Default::default(),
             native_instance_allocator_table: Default::default(),
             native_instance_init_table: Default::default(),
+            native_call_handler_table: Default::default(),
             broadcast_list: Default::default(),
+            alias_to_class_map: Default::default(),
+            class_to_alias_map: Default::default(),
+            default_xml_namespace: None,
+
+            #[cfg(feature = "avm2_jit")]
+            jit: None,
 
             #[cfg(feature = "avm_debug")]
             debug_output: false,
@@ -144,6 +178,29 @@ impl<'gc> Avm2<'gc> {
         self.system_classes.as_ref().unwrap()
     }
 
+    /// Register a class under an alias, for use by AMF serialization.
+    pub fn register_class_alias(&mut self, name: AvmString<'gc>, class_object: ClassObject<'gc>) {
+        self.alias_to_class_map.insert(name, class_object);
+        self.class_to_alias_map.insert(class_object, name);
+    }
+
+    pub fn get_class_by_alias(&self, name: AvmString<'gc>) -> Option<ClassObject<'gc>> {
+        self.alias_to_class_map.get(&name).copied()
+    }
+
+    pub fn get_alias_by_class(&self, class_object: ClassObject<'gc>) -> Option<AvmString<'gc>> {
+        self.class_to_alias_map.get(&class_object).copied()
+    }
+
+    /// The URI of the default namespace of XML elements, set with `default xml namespace`.
+    pub fn default_xml_namespace(&self) -> Option<AvmString<'gc>> {
+        self.default_xml_namespace
+    }
+
+    pub fn set_default_xml_namespace(&mut self, uri: Option<AvmString<'gc>>) {
+        self.default_xml_namespace = uri;
+    }
+
     /// Run a script's initializer method.
     pub fn run_script_initializer(
         script: Script<'gc>,
@@ -173,7 +230,9 @@ impl<'gc> Avm2<'gc> {
                     .context
                     .avm2
                     .push_global_init(init_activation.context.gc_context);
+                let default_xml_namespace = init_activation.context.avm2.default_xml_namespace;
                 let r = init_activation.run_actions(method);
+                init_activation.context.avm2.default_xml_namespace = default_xml_namespace;
                 init_activation
                     .context
                     .avm2
@@ -439,4 +498,15 @@ impl<'gc> Avm2<'gc> {
 
     #[cfg(not(feature = "avm_debug"))]
     pub const fn set_show_debug_output(&self, _visible: bool) {}
+
+    /// Compile hot loops to machine code from now on.
+    #[cfg(feature = "avm2_jit")]
+    pub fn enable_jit(&mut self) {
+        if self.jit.is_none() {
+            match jit::Jit::new() {
+                Ok(jit) => self.jit = Some(jit),
+                Err(e) => tracing::warn!("Unable to start the AVM2 JIT: {}", e),
+            }
+        }
+    }
 }
@@ -3,12 +3,17 @@
 use crate::avm2::array::ArrayStorage;
 use crate::avm2::class::Class;
 use crate::avm2::domain::Domain;
+use crate::avm2::e4x::{self, xml_nodes};
 use crate::avm2::error::type_error;
+#[cfg(feature = "avm2_jit")]
+use crate::avm2::jit::LoopExit;
 use crate::avm2::method::{BytecodeMethod, Method, ParamConfig};
 use crate::avm2::object::{
     ArrayObject, ByteArrayObject, ClassObject, FunctionObject, NamespaceObject, ScriptObject,
+    XmlListObject,
 };
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Property;
 use crate::avm2::scope::{search_scope_stack, Scope, ScopeChain};
 use crate::avm2::script::Script;
 use crate::avm2::value::Value;
@@ -16,13 +21,16 @@ use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
 use crate::avm2::{value, Avm2, Error};
+use crate::avm2_stub_method;
 use crate::context::UpdateContext;
-use crate::string::{AvmString, WStr, WString};
+use crate::string::AvmString;
 use crate::swf::extensions::ReadSwfExt;
+use crate::tag_utils::SwfMovie;
 use gc_arena::{Gc, GcCell};
 use smallvec::SmallVec;
 use std::borrow::Cow;
 use std::cmp::{min, Ordering};
+use std::sync::Arc;
 use swf::avm2::read::Reader;
 use swf::avm2::types::{
     Class as AbcClass, Exception, Index, Method as AbcMethod, MethodFlags as AbcMethodFlags,
@@ -626,6 +634,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         self.caller_domain
     }
 
+    /// Returns the movie of the original AS3 caller, falling back to the
+    /// root movie if the caller's domain isn't one of a movie.
+    pub fn caller_movie(&self) -> Arc<SwfMovie> {
+        self.context
+            .library
+            .movie_for_avm2_domain(self.caller_domain)
+            .unwrap_or_else(|| self.context.swf.clone())
+    }
+
     /// Returns the global scope of this activation.
     ///
     /// The global scope refers to scope at the bottom of the
@@ -804,6 +821,14 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let name = method
             .translation_unit()
             .pool_maybe_uninitialized_multiname(index, self.context.gc_context)?;
+        self.initialize_multiname(name)
+    }
+
+    /// Fills in the runtime parameters of a multiname from the constant pool, if it has any.
+    fn initialize_multiname(
+        &mut self,
+        name: Gc<'gc, Multiname<'gc>>,
+    ) -> Result<Gc<'gc, Multiname<'gc>>, Error<'gc>> {
         if name.has_lazy_component() {
             let name = name.fill_with_runtime_params(self)?;
             Ok(Gc::allocate(self.context.gc_context, name))
@@ -812,6 +837,28 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         }
     }
 
+    /// Resolves `multiname` against the vtable of `object`.
+    ///
+    /// If `site` is the offset of an instruction with a static name, the lookup goes through the
+    /// inline cache of that instruction.
+    fn resolve_trait(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        site: Option<usize>,
+        object: Object<'gc>,
+        multiname: &Multiname<'gc>,
+    ) -> Option<Property> {
+        let vtable = object.vtable()?;
+        match site {
+            Some(site) => {
+                method
+                    .inline_caches
+                    .get_trait(self.context.gc_context, site, vtable, multiname)
+            }
+            None => vtable.get_trait(multiname),
+        }
+    }
+
     /// Retrieve a static, or non-runtime, multiname from the current constant
     /// pool.
     ///
@@ -872,12 +919,25 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let mut reader = Reader::new(&body.code);
 
         let val = loop {
+            #[cfg(feature = "avm2_jit")]
+            let instruction_start = reader.pos(&body.code);
+
             let result = self.do_next_opcode(method, &mut reader, &body.code);
             match result {
                 Ok(FrameControl::Return(value)) => break Ok(value),
                 Ok(FrameControl::Continue) => {}
                 Err(e) => break Err(e),
             }
+
+            // Jumping backwards means we're at the header of a loop, which may be hot.
+            #[cfg(feature = "avm2_jit")]
+            if reader.pos(&body.code) <= instruction_start {
+                match self.run_compiled_loop(method, &mut reader, &body.code) {
+                    Ok(FrameControl::Return(value)) => break Ok(value),
+                    Ok(FrameControl::Continue) => {}
+                    Err(e) => break Err(e),
+                }
+            }
         };
 
         self.clear_stack();
@@ -885,6 +945,49 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         val
     }
 
+    /// Run the loop whose header the reader is at with compiled code, if it's hot.
+    ///
+    /// The reader is moved to wherever the compiled code left the loop.
+    #[cfg(feature = "avm2_jit")]
+    fn run_compiled_loop<'b>(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        reader: &mut Reader<'b>,
+        full_data: &'b [u8],
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        // Compiled code starts with an empty stack.
+        if self.context.avm2.stack.len() != self.stack_depth {
+            return Ok(FrameControl::Continue);
+        }
+
+        let Some(jit) = self.context.avm2.jit.as_mut() else {
+            return Ok(FrameControl::Continue);
+        };
+
+        let offset = reader.pos(full_data);
+        let Some(exit) = jit.run_loop(&method, offset, &mut self.local_registers.0) else {
+            return Ok(FrameControl::Continue);
+        };
+
+        if self.context.update_start.elapsed() >= self.context.max_execution_duration {
+            return Err(
+                "A script in this movie has taken too long to execute and has been terminated."
+                    .into(),
+            );
+        }
+
+        match exit {
+            LoopExit::Resume { offset, stack } => {
+                for value in stack {
+                    self.push_stack(value);
+                }
+                reader.seek_absolute(full_data, offset);
+                Ok(FrameControl::Continue)
+            }
+            LoopExit::Return(value) => Ok(FrameControl::Return(value)),
+        }
+    }
+
     /// If a local exception handler exists for the error, use it to handle
     /// the error. Otherwise pass the error down the stack.
     fn handle_err<'b>(
@@ -982,13 +1085,13 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::Call { num_args } => self.op_call(num_args),
                 Op::CallMethod { index, num_args } => self.op_call_method(index, num_args),
                 Op::CallProperty { index, num_args } => {
-                    self.op_call_property(method, index, num_args)
+                    self.op_call_property(method, instruction_start, index, num_args)
                 }
                 Op::CallPropLex { index, num_args } => {
                     self.op_call_prop_lex(method, index, num_args)
                 }
                 Op::CallPropVoid { index, num_args } => {
-                    self.op_call_prop_void(method, index, num_args)
+                    self.op_call_prop_void(method, instruction_start, index, num_args)
                 }
                 Op::CallStatic { index, num_args } => self.op_call_static(method, index, num_args),
                 Op::CallSuper { index, num_args } => self.op_call_super(method, index, num_args),
@@ -997,9 +1100,11 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 }
                 Op::ReturnValue => self.op_return_value(),
                 Op::ReturnVoid => self.op_return_void(),
-                Op::GetProperty { index } => self.op_get_property(method, index),
-                Op::SetProperty { index } => self.op_set_property(method, index),
-                Op::InitProperty { index } => self.op_init_property(method, index),
+                Op::GetProperty { index } => self.op_get_property(method, instruction_start, index),
+                Op::SetProperty { index } => self.op_set_property(method, instruction_start, index),
+                Op::InitProperty { index } => {
+                    self.op_init_property(method, instruction_start, index)
+                }
                 Op::DeleteProperty { index } => self.op_delete_property(method, index),
                 Op::GetSuper { index } => self.op_get_super(method, index),
                 Op::SetSuper { index } => self.op_set_super(method, index),
@@ -1129,6 +1234,9 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 ),
                 Op::Coerce { index } => self.op_coerce(method, index),
                 Op::CheckFilter => self.op_check_filter(),
+                Op::GetDescendants { index } => self.op_get_descendants(method, index),
+                Op::Dxns { index } => self.op_dxns(method, index),
+                Op::DxnsLate => self.op_dxns_late(),
                 Op::Si8 => self.op_si8(),
                 Op::Si16 => self.op_si16(),
                 Op::Si32 => self.op_si32(),
@@ -1327,16 +1435,20 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_call_property(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
+        site: usize,
         index: Index<AbcMultiname>,
         arg_count: u32,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let args = self.pop_stack_args(arg_count);
-        let multiname = self.pool_multiname_and_initialize(method, index)?;
+        let multiname = self.pool_maybe_uninitialized_multiname(method, index)?;
+        let site = (!multiname.has_lazy_component()).then_some(site);
+        let multiname = self.initialize_multiname(multiname)?;
         let receiver = self
             .pop_stack()
             .coerce_to_receiver(self, Some(&multiname))?;
 
-        let value = receiver.call_property(&multiname, &args, self)?;
+        let property = self.resolve_trait(method, site, receiver, &multiname);
+        let value = receiver.call_resolved_property(property, &multiname, &args, self)?;
 
         self.push_stack(value);
 
@@ -1369,16 +1481,20 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_call_prop_void(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
+        site: usize,
         index: Index<AbcMultiname>,
         arg_count: u32,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let args = self.pop_stack_args(arg_count);
-        let multiname = self.pool_multiname_and_initialize(method, index)?;
+        let multiname = self.pool_maybe_uninitialized_multiname(method, index)?;
+        let site = (!multiname.has_lazy_component()).then_some(site);
+        let multiname = self.initialize_multiname(multiname)?;
         let receiver = self
             .pop_stack()
             .coerce_to_receiver(self, Some(&multiname))?;
 
-        receiver.call_property(&multiname, &args, self)?;
+        let property = self.resolve_trait(method, site, receiver, &multiname);
+        receiver.call_resolved_property(property, &multiname, &args, self)?;
 
         Ok(FrameControl::Continue)
     }
@@ -1455,6 +1571,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_get_property(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
+        site: usize,
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let multiname = self.pool_maybe_uninitialized_multiname(method, index)?;
@@ -1463,7 +1580,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         if !multiname.has_lazy_component() {
             let object = self.pop_stack();
             let object = object.coerce_to_receiver(self, Some(&multiname))?;
-            let value = object.get_property(&multiname, self)?;
+            let property = self.resolve_trait(method, Some(site), object, &multiname);
+            let value = object.get_resolved_property(property, &multiname, self)?;
             self.push_stack(value);
             return Ok(FrameControl::Continue);
         }
@@ -1502,6 +1620,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_set_property(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
+        site: usize,
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.pop_stack();
@@ -1511,7 +1630,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         if !multiname.has_lazy_component() {
             let object = self.pop_stack();
             let mut object = object.coerce_to_receiver(self, Some(&multiname))?;
-            object.set_property(&multiname, value, self)?;
+            let property = self.resolve_trait(method, Some(site), object, &multiname);
+            object.set_resolved_property(property, &multiname, value, self)?;
             return Ok(FrameControl::Continue);
         }
 
@@ -1551,15 +1671,19 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_init_property(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
+        site: usize,
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.pop_stack();
-        let multiname = self.pool_multiname_and_initialize(method, index)?;
+        let multiname = self.pool_maybe_uninitialized_multiname(method, index)?;
+        let site = (!multiname.has_lazy_component()).then_some(site);
+        let multiname = self.initialize_multiname(multiname)?;
         let mut object = self
             .pop_stack()
             .coerce_to_receiver(self, Some(&multiname))?;
 
-        object.init_property(&multiname, value, self)?;
+        let property = self.resolve_trait(method, site, object, &multiname);
+        object.init_resolved_property(property, &multiname, value, self)?;
 
         Ok(FrameControl::Continue)
     }
@@ -2095,15 +2219,50 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
+    /// Adds one to a value, keeping `int` values as integers when the result fits.
+    fn increment_value(&mut self, value: Value<'gc>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(match value {
+            Value::Integer(n) => match n.checked_add(1) {
+                Some(n) => n.into(),
+                None => (f64::from(n) + 1.0).into(),
+            },
+            value => (value.coerce_to_number(self)? + 1.0).into(),
+        })
+    }
+
+    /// Subtracts one from a value, keeping `int` values as integers when the result fits.
+    fn decrement_value(&mut self, value: Value<'gc>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(match value {
+            Value::Integer(n) => match n.checked_sub(1) {
+                Some(n) => n.into(),
+                None => (f64::from(n) - 1.0).into(),
+            },
+            value => (value.coerce_to_number(self)? - 1.0).into(),
+        })
+    }
+
     fn op_add(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value2 = self.pop_stack();
         let value1 = self.pop_stack();
 
-        // TODO: Special handling required for `Date` and ECMA-357/E4X `XML`
+        // Adding XML values makes a list of the nodes of both
+        if let (Some(nodes1), Some(nodes2)) = (xml_nodes(value1), xml_nodes(value2)) {
+            let nodes = nodes1.into_iter().chain(nodes2).collect();
+            let list = XmlListObject::new(self, nodes, None, None);
+            self.push_stack(list);
+
+            return Ok(FrameControl::Continue);
+        }
+
+        // TODO: Special handling required for `Date`
         let sum_value = match (value1, value2) {
-            // note: with not-yet-guaranteed assumption that Integer < 1<<28, this won't overflow.
-            (Value::Integer(n1), Value::Integer(n2)) => (n1 + n2).into(),
+            (Value::Integer(n1), Value::Integer(n2)) => match n1.checked_add(n2) {
+                Some(sum) => sum.into(),
+                None => (f64::from(n1) + f64::from(n2)).into(),
+            },
             (Value::Number(n1), Value::Number(n2)) => (n1 + n2).into(),
+            (Value::Integer(n1), Value::Number(n2)) => (f64::from(n1) + n2).into(),
+            (Value::Number(n1), Value::Integer(n2)) => (n1 + f64::from(n2)).into(),
             (Value::String(s), value2) => Value::String(AvmString::concat(
                 self.context.gc_context,
                 s,
@@ -2145,7 +2304,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let value2 = self.pop_stack().coerce_to_i32(self)?;
         let value1 = self.pop_stack().coerce_to_i32(self)?;
 
-        self.push_stack(value1 + value2);
+        self.push_stack(value1.wrapping_add(value2));
 
         Ok(FrameControl::Continue)
     }
@@ -2186,9 +2345,10 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     }
 
     fn op_declocal(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value = self.local_register(index)?.coerce_to_number(self)?;
+        let value = self.local_register(index)?;
+        let value = self.decrement_value(value)?;
 
-        self.set_local_register(index, value - 1.0)?;
+        self.set_local_register(index, value)?;
 
         Ok(FrameControl::Continue)
     }
@@ -2196,15 +2356,16 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_declocal_i(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.local_register(index)?.coerce_to_i32(self)?;
 
-        self.set_local_register(index, value - 1)?;
+        self.set_local_register(index, value.wrapping_sub(1))?;
 
         Ok(FrameControl::Continue)
     }
 
     fn op_decrement(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value = self.pop_stack().coerce_to_number(self)?;
+        let value = self.pop_stack();
+        let value = self.decrement_value(value)?;
 
-        self.push_stack(value - 1.0);
+        self.push_stack(value);
 
         Ok(FrameControl::Continue)
     }
@@ -2212,7 +2373,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_decrement_i(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.pop_stack().coerce_to_i32(self)?;
 
-        self.push_stack(value - 1);
+        self.push_stack(value.wrapping_sub(1));
 
         Ok(FrameControl::Continue)
     }
@@ -2227,9 +2388,10 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     }
 
     fn op_inclocal(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value = self.local_register(index)?.coerce_to_number(self)?;
+        let value = self.local_register(index)?;
+        let value = self.increment_value(value)?;
 
-        self.set_local_register(index, value + 1.0)?;
+        self.set_local_register(index, value)?;
 
         Ok(FrameControl::Continue)
     }
@@ -2237,15 +2399,16 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_inclocal_i(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.local_register(index)?.coerce_to_i32(self)?;
 
-        self.set_local_register(index, value + 1)?;
+        self.set_local_register(index, value.wrapping_add(1))?;
 
         Ok(FrameControl::Continue)
     }
 
     fn op_increment(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value = self.pop_stack().coerce_to_number(self)?;
+        let value = self.pop_stack();
+        let value = self.increment_value(value)?;
 
-        self.push_stack(value + 1.0);
+        self.push_stack(value);
 
         Ok(FrameControl::Continue)
     }
@@ -2253,7 +2416,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_increment_i(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.pop_stack().coerce_to_i32(self)?;
 
-        self.push_stack(value + 1);
+        self.push_stack(value.wrapping_add(1));
 
         Ok(FrameControl::Continue)
     }
@@ -2268,19 +2431,43 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     }
 
     fn op_modulo(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value2 = self.pop_stack().coerce_to_number(self)?;
-        let value1 = self.pop_stack().coerce_to_number(self)?;
+        let value2 = self.pop_stack();
+        let value1 = self.pop_stack();
+
+        let mod_value: Value<'gc> = match (value1, value2) {
+            // A negative dividend may produce -0, which only a Number can hold.
+            (Value::Integer(n1), Value::Integer(n2)) if n1 >= 0 && n2 != 0 => (n1 % n2).into(),
+            _ => {
+                let value2 = value2.coerce_to_number(self)?;
+                let value1 = value1.coerce_to_number(self)?;
+                (value1 % value2).into()
+            }
+        };
 
-        self.push_stack(value1 % value2);
+        self.push_stack(mod_value);
 
         Ok(FrameControl::Continue)
     }
 
     fn op_multiply(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value2 = self.pop_stack().coerce_to_number(self)?;
-        let value1 = self.pop_stack().coerce_to_number(self)?;
+        let value2 = self.pop_stack();
+        let value1 = self.pop_stack();
+
+        let product_value: Value<'gc> = match (value1, value2) {
+            // A zero product with a negative factor is -0, which only a Number can hold.
+            (Value::Integer(n1), Value::Integer(n2)) => match n1.checked_mul(n2) {
+                Some(product) if product != 0 || (n1 >= 0 && n2 >= 0) => product.into(),
+                _ => (f64::from(n1) * f64::from(n2)).into(),
+            },
+            (Value::Number(n1), Value::Number(n2)) => (n1 * n2).into(),
+            _ => {
+                let value2 = value2.coerce_to_number(self)?;
+                let value1 = value1.coerce_to_number(self)?;
+                (value1 * value2).into()
+            }
+        };
 
-        self.push_stack(value1 * value2);
+        self.push_stack(product_value);
 
         Ok(FrameControl::Continue)
     }
@@ -2289,15 +2476,20 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let value2 = self.pop_stack().coerce_to_i32(self)?;
         let value1 = self.pop_stack().coerce_to_i32(self)?;
 
-        self.push_stack(value1 * value2);
+        self.push_stack(value1.wrapping_mul(value2));
 
         Ok(FrameControl::Continue)
     }
 
     fn op_negate(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let value1 = self.pop_stack().coerce_to_number(self)?;
+        let value1: Value<'gc> = match self.pop_stack() {
+            // Negating 0 gives -0, and negating `int.MIN_VALUE` overflows; only a Number
+            // can hold either result.
+            Value::Integer(n) if n != 0 && n != i32::MIN => (-n).into(),
+            value => (-value.coerce_to_number(self)?).into(),
+        };
 
-        self.push_stack(-value1);
+        self.push_stack(value1);
 
         Ok(FrameControl::Continue)
     }
@@ -2305,7 +2497,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_negate_i(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value1 = self.pop_stack().coerce_to_i32(self)?;
 
-        self.push_stack(-value1);
+        self.push_stack(value1.wrapping_neg());
 
         Ok(FrameControl::Continue)
     }
@@ -2324,8 +2516,10 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let value1 = self.pop_stack();
 
         let sub_value: Value<'gc> = match (value1, value2) {
-            // note: with not-yet-guaranteed assumption that Integer < 1<<28, this won't underflow.
-            (Value::Integer(n1), Value::Integer(n2)) => (n1 - n2).into(),
+            (Value::Integer(n1), Value::Integer(n2)) => match n1.checked_sub(n2) {
+                Some(difference) => difference.into(),
+                None => (f64::from(n1) - f64::from(n2)).into(),
+            },
             (Value::Number(n1), Value::Number(n2)) => (n1 - n2).into(),
             _ => {
                 let value2 = value2.coerce_to_number(self)?;
@@ -2343,7 +2537,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let value2 = self.pop_stack().coerce_to_i32(self)?;
         let value1 = self.pop_stack().coerce_to_i32(self)?;
 
-        self.push_stack(value1 - value2);
+        self.push_stack(value1.wrapping_sub(value2));
 
         Ok(FrameControl::Continue)
     }
@@ -2861,7 +3055,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                             "object"
                         }
                     }
-                    Object::XmlObject(_) => {
+                    Object::XmlObject(_) | Object::XmlListObject(_) => {
                         if is_not_subclass {
                             "xml"
                         } else {
@@ -2879,28 +3073,83 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
+    /// Implements `Op::GetDescendants`
+    fn op_get_descendants(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let multiname = self.pool_multiname_and_initialize(method, index)?;
+        let object = self.pop_stack();
+
+        if let Some(proxy) = object.as_object().and_then(|o| o.as_proxy_object()) {
+            let descendants = proxy.get_descendants(&multiname, self)?;
+            self.push_stack(descendants);
+
+            return Ok(FrameControl::Continue);
+        }
+
+        let nodes = match xml_nodes(object) {
+            Some(nodes) => nodes,
+            None => {
+                let object = object.coerce_to_object(self)?;
+                return Err(Error::AvmError(type_error(
+                    self,
+                    &format!(
+                        "Error #1016: Descendants operator (..) not supported on type {}.",
+                        object.instance_of_class_name(self.context.gc_context)
+                    ),
+                    1016,
+                )?));
+            }
+        };
+
+        let name = e4x::node_name(self.context.gc_context, &multiname);
+        let mut descendants = Vec::new();
+        for node in nodes {
+            node.descendants(&name, &mut descendants);
+        }
+        let list = XmlListObject::new(self, descendants, None, None);
+        self.push_stack(list);
+
+        Ok(FrameControl::Continue)
+    }
+
+    /// Implements `Op::Dxns`
+    fn op_dxns(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<String>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let uri = self.pool_string(&method, index)?;
+        self.set_default_xml_namespace(uri);
+
+        Ok(FrameControl::Continue)
+    }
+
+    /// Implements `Op::DxnsLate`
+    fn op_dxns_late(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        // Namespace objects convert to their URI.
+        let uri = self.pop_stack().coerce_to_string(self)?;
+        self.set_default_xml_namespace(uri);
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn set_default_xml_namespace(&mut self, uri: AvmString<'gc>) {
+        // The default namespace is used when parsing markup, but not yet when looking up
+        // properties of XML objects by unqualified names.
+        avm2_stub_method!(self, "XML", "default xml namespace", "for property names");
+
+        let uri = if uri.is_empty() { None } else { Some(uri) };
+        self.context.avm2.set_default_xml_namespace(uri);
+    }
+
     /// Implements `Op::EscXAttr`
     fn op_esc_xattr(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         let s = self.pop_stack().coerce_to_string(self)?;
 
-        // Implementation of `EscapeAttributeValue` from ECMA-357(10.2.1.2)
-        let mut r = WString::with_capacity(s.len(), s.is_wide());
-        for c in &s {
-            let escape: &[u8] = match u8::try_from(c) {
-                Ok(b'"') => b"&quot;",
-                Ok(b'<') => b"&lt;",
-                Ok(b'&') => b"&amp;",
-                Ok(b'\x0A') => b"&#xA;",
-                Ok(b'\x0D') => b"&#xD;",
-                Ok(b'\x09') => b"&#x9;",
-                _ => {
-                    r.push(c);
-                    continue;
-                }
-            };
-
-            r.push_str(WStr::from_units(escape));
-        }
+        let r = e4x::escape_attribute_value(&s);
         self.push_stack(AvmString::new(self.context.gc_context, r));
 
         Ok(FrameControl::Continue)
@@ -2911,21 +3160,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         let s = self.pop_stack().coerce_to_string(self)?;
 
         // contrary to the avmplus documentation, this escapes the value on the top of the stack using EscapeElementValue from ECMA-357 *NOT* EscapeAttributeValue.
-        // Implementation of `EscapeElementValue` from ECMA-357(10.2.1.1)
-        let mut r = WString::with_capacity(s.len(), s.is_wide());
-        for c in &s {
-            let escape: &[u8] = match u8::try_from(c) {
-                Ok(b'<') => b"&lt;",
-                Ok(b'>') => b"&gt;",
-                Ok(b'&') => b"&amp;",
-                _ => {
-                    r.push(c);
-                    continue;
-                }
-            };
-
-            r.push_str(WStr::from_units(escape));
-        }
+        let r = e4x::escape_element_value(&s);
         self.push_stack(AvmString::new(self.context.gc_context, r));
 
         Ok(FrameControl::Continue)
@@ -3094,7 +3329,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             .as_bytearray()
             .ok_or_else(|| "Unable to get bytearray storage".to_string())?;
         let val = dm.read_at(2, address)?;
-        self.push_stack(u16::from_le_bytes(val.try_into().unwrap()));
+        self.push_stack(u16::from_le_bytes((&*val).try_into().unwrap()));
 
         Ok(FrameControl::Continue)
     }
@@ -3108,7 +3343,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             .as_bytearray()
             .ok_or_else(|| "Unable to get bytearray storage".to_string())?;
         let val = dm.read_at(4, address)?;
-        self.push_stack(i32::from_le_bytes(val.try_into().unwrap()));
+        self.push_stack(i32::from_le_bytes((&*val).try_into().unwrap()));
         Ok(FrameControl::Continue)
     }
 
@@ -3121,7 +3356,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             .as_bytearray()
             .ok_or_else(|| "Unable to get bytearray storage".to_string())?;
         let val = dm.read_at(4, address)?;
-        self.push_stack(f32::from_le_bytes(val.try_into().unwrap()));
+        self.push_stack(f32::from_le_bytes((&*val).try_into().unwrap()));
 
         Ok(FrameControl::Continue)
     }
@@ -3135,7 +3370,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             .as_bytearray()
             .ok_or_else(|| "Unable to get bytearray storage".to_string())?;
         let val = dm.read_at(8, address)?;
-        self.push_stack(f64::from_le_bytes(val.try_into().unwrap()));
+        self.push_stack(f64::from_le_bytes((&*val).try_into().unwrap()));
         Ok(FrameControl::Continue)
     }
 
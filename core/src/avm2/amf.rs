@@ -1,36 +1,1046 @@
+//! Conversions between AVM2 values and AMF.
+//!
+//! AMF3 is read and written directly on the bytes of a `ByteArray` object, as
+//! `ByteArray.readObject` and `ByteArray.writeObject` do. This lets
+//! `IExternalizable` objects read and write their own data in the middle of the
+//! stream, and keeps the references between the values it contains. AMF0 goes
+//! through the `flash_lso` crate.
+
 use crate::avm2::bytearray::ByteArrayStorage;
-use crate::avm2::object::{ByteArrayObject, TObject};
-use crate::avm2::ArrayObject;
-use crate::avm2::ArrayStorage;
-use crate::avm2::Multiname;
-use crate::avm2::{Activation, Error, Object, Value};
+use crate::avm2::error::{reference_error, type_error};
+use crate::avm2::object::{ByteArrayObject, ClassObject, Object, TObject, VectorObject};
+use crate::avm2::traits::TraitKind;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::{Activation, ArrayObject, ArrayStorage, Error, Multiname, QName, Value};
 use crate::string::AvmString;
 use enumset::EnumSet;
-use flash_lso::types::{AMFVersion, Element, Lso};
-use flash_lso::types::{Attribute, ClassDefinition, Value as AmfValue};
+use flash_lso::types::{Attribute, ClassDefinition, Element, Lso, Value as AmfValue};
+use fnv::FnvHashMap;
+
+const UNDEFINED: u8 = 0x00;
+const NULL: u8 = 0x01;
+const FALSE: u8 = 0x02;
+const TRUE: u8 = 0x03;
+const INTEGER: u8 = 0x04;
+const DOUBLE: u8 = 0x05;
+const STRING: u8 = 0x06;
+const XML_DOCUMENT: u8 = 0x07;
+const DATE: u8 = 0x08;
+const ARRAY: u8 = 0x09;
+const OBJECT: u8 = 0x0A;
+const XML: u8 = 0x0B;
+const BYTE_ARRAY: u8 = 0x0C;
+const VECTOR_INT: u8 = 0x0D;
+const VECTOR_UINT: u8 = 0x0E;
+const VECTOR_DOUBLE: u8 = 0x0F;
+const VECTOR_OBJECT: u8 = 0x10;
+const DICTIONARY: u8 = 0x11;
+
+/// The range of integers that can be written with the `INTEGER` marker.
+const INTEGER_RANGE: std::ops::RangeInclusive<i32> = -(1 << 28)..=(1 << 28) - 1;
+
+/// The layout of the objects of a class, as written in the stream.
+struct ObjectTraits<'gc> {
+    /// The alias the class was written with; empty for anonymous objects.
+    class_name: AvmString<'gc>,
+
+    /// Whether the object writes its own data via `IExternalizable`.
+    externalizable: bool,
+
+    /// Whether dynamic properties follow the sealed ones.
+    dynamic: bool,
+
+    /// The names of the properties that are written for every object.
+    sealed: Vec<AvmString<'gc>>,
+}
+
+/// Collect the properties of a class that AMF serializes for each instance.
+///
+/// These are the public variables and read-write accessors of the class and
+/// its superclasses, minus the ones marked `[Transient]`.
+fn sealed_properties<'gc>(class: ClassObject<'gc>) -> Vec<AvmString<'gc>> {
+    let mut classes = Vec::new();
+    let mut current = Some(class);
+    while let Some(class) = current {
+        classes.push(class);
+        current = class.superclass_object();
+    }
+
+    let mut variables = Vec::new();
+    let mut getters = Vec::new();
+    let mut setters = Vec::new();
+    for class in classes.into_iter().rev() {
+        let definition = class.inner_class_definition();
+        let definition = definition.read();
+        for instance_trait in definition.instance_traits() {
+            let name = instance_trait.name();
+            let is_transient = instance_trait
+                .metadata()
+                .iter()
+                .any(|metadata| &metadata.name() == b"Transient");
+            if !name.namespace().is_public() || is_transient {
+                continue;
+            }
+
+            let list = match instance_trait.kind() {
+                TraitKind::Slot { .. } => &mut variables,
+                TraitKind::Getter { .. } => &mut getters,
+                TraitKind::Setter { .. } => &mut setters,
+                _ => continue,
+            };
+            let name = name.local_name();
+            if !list.contains(&name) {
+                list.push(name);
+            }
+        }
+    }
+
+    variables.extend(getters.into_iter().filter(|name| setters.contains(name)));
+    variables
+}
+
+/// Collect the enumerable properties of an object as key-value pairs.
+fn enumerable_properties<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    object: Object<'gc>,
+) -> Result<Vec<(Value<'gc>, Value<'gc>)>, Error<'gc>> {
+    let mut properties = Vec::new();
+    let mut last_index = object.get_next_enumerant(0, activation)?;
+    while let Some(index) = last_index {
+        let name = object.get_enumerant_name(index, activation)?;
+        let value = object.get_enumerant_value(index, activation)?;
+        properties.push((name, value));
+        last_index = object.get_next_enumerant(index, activation)?;
+    }
+
+    Ok(properties)
+}
+
+/// Whether a value is left out when it is the value of a property.
+fn is_skipped(value: Value<'_>) -> bool {
+    match value {
+        Value::Object(object) => object.as_executable().is_some(),
+        _ => false,
+    }
+}
+
+/// Writes AMF3 to the end of a `ByteArray`.
+///
+/// Strings, objects and class layouts that occur more than once are written
+/// as references to their first occurrence, so shared and cyclic structures
+/// survive a round trip.
+pub struct Amf3Writer<'gc> {
+    target: Object<'gc>,
+    strings: FnvHashMap<AvmString<'gc>, u32>,
+    objects: FnvHashMap<Object<'gc>, u32>,
+    traits: FnvHashMap<ClassObject<'gc>, u32>,
+}
+
+impl<'gc> Amf3Writer<'gc> {
+    /// Create a writer for the given `ByteArray` object.
+    pub fn new(target: Object<'gc>) -> Self {
+        Self {
+            target,
+            strings: Default::default(),
+            objects: Default::default(),
+            traits: Default::default(),
+        }
+    }
+
+    fn write_bytes(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        bytes: &[u8],
+    ) -> Result<(), Error<'gc>> {
+        self.target
+            .as_bytearray_mut(activation.context.gc_context)
+            .ok_or("Cannot write AMF to a non-ByteArray")?
+            .write_bytes(bytes)
+    }
+
+    fn write_u8(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        value: u8,
+    ) -> Result<(), Error<'gc>> {
+        self.write_bytes(activation, &[value])
+    }
+
+    /// Write a variable length 29-bit integer.
+    fn write_u29(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        value: u32,
+    ) -> Result<(), Error<'gc>> {
+        let value = value & 0x1FFF_FFFF;
+        if value < 0x80 {
+            self.write_bytes(activation, &[value as u8])
+        } else if value < 0x4000 {
+            self.write_bytes(activation, &[(value >> 7) as u8 | 0x80, value as u8 & 0x7F])
+        } else if value < 0x20_0000 {
+            self.write_bytes(
+                activation,
+                &[
+                    (value >> 14) as u8 | 0x80,
+                    (value >> 7) as u8 | 0x80,
+                    value as u8 & 0x7F,
+                ],
+            )
+        } else {
+            self.write_bytes(
+                activation,
+                &[
+                    (value >> 22) as u8 | 0x80,
+                    (value >> 15) as u8 | 0x80,
+                    (value >> 8) as u8 | 0x80,
+                    value as u8,
+                ],
+            )
+        }
+    }
+
+    fn write_f64(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        value: f64,
+    ) -> Result<(), Error<'gc>> {
+        self.write_bytes(activation, &value.to_be_bytes())
+    }
+
+    /// Write a string, or a reference to an earlier occurrence of it.
+    fn write_string(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        string: AvmString<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        // The empty string is never sent by reference.
+        if string.is_empty() {
+            return self.write_u29(activation, 1);
+        }
+
+        if let Some(index) = self.strings.get(&string) {
+            return self.write_u29(activation, index << 1);
+        }
+        self.strings.insert(string, self.strings.len() as u32);
+
+        let utf8 = string.to_utf8_lossy();
+        self.write_u29(activation, ((utf8.len() as u32) << 1) | 1)?;
+        self.write_bytes(activation, utf8.as_bytes())
+    }
+
+    /// Write the marker of an object, followed by a reference to it if it was
+    /// already written.
+    ///
+    /// Returns `true` if a reference was written; otherwise, the object is
+    /// remembered and its contents must follow.
+    fn write_object_marker(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        marker: u8,
+        object: Object<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        self.write_u8(activation, marker)?;
+        if let Some(index) = self.objects.get(&object) {
+            self.write_u29(activation, index << 1)?;
+            return Ok(true);
+        }
+
+        self.objects.insert(object, self.objects.len() as u32);
+        Ok(false)
+    }
+
+    /// Write a single value.
+    pub fn write_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        value: Value<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match value {
+            Value::Undefined => self.write_u8(activation, UNDEFINED),
+            Value::Null => self.write_u8(activation, NULL),
+            Value::Bool(false) => self.write_u8(activation, FALSE),
+            Value::Bool(true) => self.write_u8(activation, TRUE),
+            Value::Integer(i) if INTEGER_RANGE.contains(&i) => {
+                self.write_u8(activation, INTEGER)?;
+                self.write_u29(activation, i as u32)
+            }
+            Value::Integer(i) => {
+                self.write_u8(activation, DOUBLE)?;
+                self.write_f64(activation, i.into())
+            }
+            Value::Number(n) => {
+                self.write_u8(activation, DOUBLE)?;
+                self.write_f64(activation, n)
+            }
+            Value::String(s) => {
+                self.write_u8(activation, STRING)?;
+                self.write_string(activation, s)
+            }
+            Value::Object(object) => self.write_object(activation, object),
+        }
+    }
+
+    fn write_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let primitive = object.as_primitive().map(|primitive| *primitive);
+        if let Some(primitive) = primitive {
+            return self.write_value(activation, primitive);
+        }
+
+        if object.as_executable().is_some() || object.as_class_object().is_some() {
+            return self.write_u8(activation, UNDEFINED);
+        }
 
-/// Serialize a Value to an AmfValue
+        if let Some(date) = object.as_date_object() {
+            if !self.write_object_marker(activation, DATE, object)? {
+                let time = date
+                    .date_time()
+                    .map(|date_time| date_time.timestamp_millis() as f64)
+                    .unwrap_or(f64::NAN);
+                self.write_u29(activation, 1)?;
+                self.write_f64(activation, time)?;
+            }
+            return Ok(());
+        }
+
+        if let Some(xml) = object.as_xml_object() {
+            if !self.write_object_marker(activation, XML, object)? {
+                let string = xml.xml_to_xml_string(activation)?.to_utf8_lossy();
+                self.write_u29(activation, ((string.len() as u32) << 1) | 1)?;
+                self.write_bytes(activation, string.as_bytes())?;
+            }
+            return Ok(());
+        }
+
+        let bytes = object.as_bytearray().map(|bytes| bytes.bytes().to_vec());
+        if let Some(bytes) = bytes {
+            if !self.write_object_marker(activation, BYTE_ARRAY, object)? {
+                self.write_u29(activation, ((bytes.len() as u32) << 1) | 1)?;
+                self.write_bytes(activation, &bytes)?;
+            }
+            return Ok(());
+        }
+
+        let length = object.as_array_storage().map(|array| array.length());
+        if let Some(length) = length {
+            if !self.write_object_marker(activation, ARRAY, object)? {
+                self.write_array(activation, object, length)?;
+            }
+            return Ok(());
+        }
+
+        let is_vector = object.as_vector_storage().is_some();
+        if is_vector {
+            return self.write_vector(activation, object);
+        }
+
+        if object.as_dictionary_object().is_some() {
+            if !self.write_object_marker(activation, DICTIONARY, object)? {
+                let entries = enumerable_properties(activation, object)?;
+                self.write_u29(activation, ((entries.len() as u32) << 1) | 1)?;
+                // Weak keys are not supported.
+                self.write_u8(activation, 0)?;
+                for (key, value) in entries {
+                    self.write_value(activation, key)?;
+                    self.write_value(activation, value)?;
+                }
+            }
+            return Ok(());
+        }
+
+        if !self.write_object_marker(activation, OBJECT, object)? {
+            self.write_typed_object(activation, object)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_array(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        array: Object<'gc>,
+        length: usize,
+    ) -> Result<(), Error<'gc>> {
+        self.write_u29(activation, ((length as u32) << 1) | 1)?;
+
+        // Properties that aren't indices of the array go before its elements.
+        for (name, value) in enumerable_properties(activation, array)? {
+            let name = name.coerce_to_string(activation)?;
+            let is_index = name.parse::<usize>().map_or(false, |index| index < length);
+            if is_index || is_skipped(value) {
+                continue;
+            }
+
+            self.write_string(activation, name)?;
+            self.write_value(activation, value)?;
+        }
+        self.write_string(activation, "".into())?;
+
+        for index in 0..length {
+            let value = array
+                .as_array_storage()
+                .and_then(|array| array.get(index))
+                .unwrap_or(Value::Undefined);
+            self.write_value(activation, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_vector(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let (value_type, is_fixed, values) = match object.as_vector_storage() {
+            Some(vector) => (
+                vector.value_type(),
+                vector.is_fixed(),
+                vector.iter().collect::<Vec<_>>(),
+            ),
+            None => return Ok(()),
+        };
+
+        let classes = activation.avm2().classes();
+        let marker = if value_type == classes.int {
+            VECTOR_INT
+        } else if value_type == classes.uint {
+            VECTOR_UINT
+        } else if value_type == classes.number {
+            VECTOR_DOUBLE
+        } else {
+            VECTOR_OBJECT
+        };
+
+        if self.write_object_marker(activation, marker, object)? {
+            return Ok(());
+        }
+
+        self.write_u29(activation, ((values.len() as u32) << 1) | 1)?;
+        self.write_u8(activation, is_fixed as u8)?;
+        match marker {
+            VECTOR_INT => {
+                for value in values {
+                    let value = value.coerce_to_i32(activation)?;
+                    self.write_bytes(activation, &value.to_be_bytes())?;
+                }
+            }
+            VECTOR_UINT => {
+                for value in values {
+                    let value = value.coerce_to_u32(activation)?;
+                    self.write_bytes(activation, &value.to_be_bytes())?;
+                }
+            }
+            VECTOR_DOUBLE => {
+                for value in values {
+                    let value = value.coerce_to_number(activation)?;
+                    self.write_f64(activation, value)?;
+                }
+            }
+            _ => {
+                let type_name = self.class_name(activation, value_type);
+                self.write_string(activation, type_name)?;
+                for value in values {
+                    self.write_value(activation, value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the enumerable properties of an object as the body of a `.sol`
+    /// file: a name, a value and a zero byte for each of them.
+    pub fn write_sol_properties(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        for (name, value) in enumerable_properties(activation, object)? {
+            if is_skipped(value) {
+                continue;
+            }
+
+            let name = name.coerce_to_string(activation)?;
+            self.write_string(activation, name)?;
+            self.write_value(activation, value)?;
+            self.write_u8(activation, 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// The name a class is written with: its alias if it has one, or its
+    /// qualified name otherwise.
+    fn class_name(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        class: ClassObject<'gc>,
+    ) -> AvmString<'gc> {
+        if let Some(alias) = activation.avm2().get_alias_by_class(class) {
+            return alias;
+        }
+
+        let name = class.inner_class_definition().read().generic_name();
+        name.to_qualified_name(activation.context.gc_context)
+    }
+
+    fn write_typed_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let class = object
+            .instance_of()
+            .unwrap_or_else(|| activation.avm2().classes().object);
+        let is_plain_object = class == activation.avm2().classes().object;
+        let class_name = if is_plain_object {
+            "".into()
+        } else {
+            activation
+                .avm2()
+                .get_alias_by_class(class)
+                .unwrap_or_else(|| "".into())
+        };
+
+        let iexternalizable = activation.avm2().classes().iexternalizable;
+        if object.is_of_type(iexternalizable, activation) {
+            if let Some(index) = self.traits.get(&class) {
+                self.write_u29(activation, (index << 2) | 0b01)?;
+            } else {
+                self.traits.insert(class, self.traits.len() as u32);
+                self.write_u29(activation, 0b0111)?;
+                self.write_string(activation, class_name)?;
+            }
+
+            object.call_property(
+                &Multiname::public("writeExternal"),
+                &[self.target.into()],
+                activation,
+            )?;
+            return Ok(());
+        }
+
+        let sealed = if is_plain_object {
+            Vec::new()
+        } else {
+            sealed_properties(class)
+        };
+        let dynamic = !class.inner_class_definition().read().is_sealed();
+
+        if let Some(index) = self.traits.get(&class) {
+            self.write_u29(activation, (index << 2) | 0b01)?;
+        } else {
+            self.traits.insert(class, self.traits.len() as u32);
+            self.write_u29(
+                activation,
+                ((sealed.len() as u32) << 4) | ((dynamic as u32) << 3) | 0b011,
+            )?;
+            self.write_string(activation, class_name)?;
+            for name in &sealed {
+                self.write_string(activation, *name)?;
+            }
+        }
+
+        for name in sealed {
+            let value = object.get_property(&Multiname::public(name), activation)?;
+            self.write_value(activation, value)?;
+        }
+
+        if dynamic {
+            for (name, value) in enumerable_properties(activation, object)? {
+                if is_skipped(value) {
+                    continue;
+                }
+
+                let name = name.coerce_to_string(activation)?;
+                self.write_string(activation, name)?;
+                self.write_value(activation, value)?;
+            }
+            self.write_string(activation, "".into())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads AMF3 from the current position of a `ByteArray`.
+pub struct Amf3Reader<'gc> {
+    source: Object<'gc>,
+    strings: Vec<AvmString<'gc>>,
+    objects: Vec<Value<'gc>>,
+    traits: Vec<ObjectTraits<'gc>>,
+}
+
+impl<'gc> Amf3Reader<'gc> {
+    /// Create a reader for the given `ByteArray` object.
+    pub fn new(source: Object<'gc>) -> Self {
+        Self {
+            source,
+            strings: Vec::new(),
+            objects: Vec::new(),
+            traits: Vec::new(),
+        }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, Error<'gc>> {
+        let bytes = self
+            .source
+            .as_bytearray()
+            .ok_or("Cannot read AMF from a non-ByteArray")?;
+        Ok(bytes.read_bytes(length)?.to_vec())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error<'gc>> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Read a variable length 29-bit integer.
+    fn read_u29(&mut self) -> Result<u32, Error<'gc>> {
+        let mut value = 0;
+        for _ in 0..3 {
+            let byte = self.read_u8()? as u32;
+            value = (value << 7) | (byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Ok((value << 8) | self.read_u8()? as u32)
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Error<'gc>> {
+        let bytes = self.read_bytes(8)?;
+        Ok(f64::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_utf8(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        length: usize,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let bytes = self.read_bytes(length)?;
+        Ok(AvmString::new_utf8_bytes(
+            activation.context.gc_context,
+            &bytes,
+        ))
+    }
+
+    fn read_string(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let header = self.read_u29()?;
+        if header & 1 == 0 {
+            return self.string_reference(header >> 1);
+        }
+
+        let string = self.read_utf8(activation, (header >> 1) as usize)?;
+        if !string.is_empty() {
+            self.strings.push(string);
+        }
+
+        Ok(string)
+    }
+
+    fn string_reference(&self, index: u32) -> Result<AvmString<'gc>, Error<'gc>> {
+        self.strings
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| format!("Invalid AMF3 string reference {index}").into())
+    }
+
+    /// Read the header of an object, resolving it if it is a reference.
+    ///
+    /// Otherwise, returns the header value with the reference bit removed.
+    fn read_object_header(&mut self) -> Result<Result<u32, Value<'gc>>, Error<'gc>> {
+        let header = self.read_u29()?;
+        if header & 1 == 1 {
+            return Ok(Ok(header >> 1));
+        }
+
+        let index = header >> 1;
+        match self.objects.get(index as usize) {
+            Some(object) => Ok(Err(*object)),
+            None => Err(format!("Invalid AMF3 object reference {index}").into()),
+        }
+    }
+
+    /// Reserve the reference index of the object being read.
+    fn reserve_object(&mut self) -> usize {
+        self.objects.push(Value::Undefined);
+        self.objects.len() - 1
+    }
+
+    /// Look up the class written under the given name.
+    fn class_by_name(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        name: AvmString<'gc>,
+    ) -> Option<ClassObject<'gc>> {
+        if let Some(class) = activation.avm2().get_class_by_alias(name) {
+            return Some(class);
+        }
+
+        let qname = QName::from_qualified_name(name, activation.context.gc_context);
+        activation
+            .domain()
+            .get_defined_value(activation, qname)
+            .ok()
+            .and_then(|class| class.as_object())
+            .and_then(|class| class.as_class_object())
+    }
+
+    /// Read the body of a `.sol` file, setting each property it contains on
+    /// the given object.
+    pub fn read_sol_properties(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        mut object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        while self
+            .source
+            .as_bytearray()
+            .map_or(false, |bytes| bytes.bytes_available() > 0)
+        {
+            let name = self.read_string(activation)?;
+            let value = self.read_value(activation)?;
+            // Each property is followed by a padding byte.
+            self.read_u8()?;
+            object.set_property(&Multiname::public(name), value, activation)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a single value.
+    pub fn read_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let marker = self.read_u8()?;
+        Ok(match marker {
+            UNDEFINED => Value::Undefined,
+            NULL => Value::Null,
+            FALSE => false.into(),
+            TRUE => true.into(),
+            INTEGER => {
+                // Sign-extend from 29 bits.
+                let value = ((self.read_u29()? << 3) as i32) >> 3;
+                value.into()
+            }
+            DOUBLE => self.read_f64()?.into(),
+            STRING => self.read_string(activation)?.into(),
+            XML | XML_DOCUMENT => {
+                let length = match self.read_object_header()? {
+                    Ok(length) => length,
+                    Err(object) => return Ok(object),
+                };
+                let index = self.reserve_object();
+                let string = self.read_utf8(activation, length as usize)?;
+                let xml = activation
+                    .avm2()
+                    .classes()
+                    .xml
+                    .construct(activation, &[string.into()])?;
+                self.objects[index] = xml.into();
+                xml.into()
+            }
+            DATE => {
+                if let Err(object) = self.read_object_header()? {
+                    return Ok(object);
+                }
+                let index = self.reserve_object();
+                let time = self.read_f64()?;
+                let date = activation
+                    .avm2()
+                    .classes()
+                    .date
+                    .construct(activation, &[time.into()])?;
+                self.objects[index] = date.into();
+                date.into()
+            }
+            ARRAY => {
+                let length = match self.read_object_header()? {
+                    Ok(length) => length,
+                    Err(object) => return Ok(object),
+                };
+                let mut array = ArrayObject::empty(activation)?;
+                self.objects.push(array.into());
+
+                loop {
+                    let name = self.read_string(activation)?;
+                    if name.is_empty() {
+                        break;
+                    }
+
+                    let value = self.read_value(activation)?;
+                    array.set_property(&Multiname::public(name), value, activation)?;
+                }
+
+                for index in 0..length as usize {
+                    let value = self.read_value(activation)?;
+                    if let Some(mut storage) =
+                        array.as_array_storage_mut(activation.context.gc_context)
+                    {
+                        storage.set(index, value);
+                    }
+                }
+
+                array.into()
+            }
+            OBJECT => self.read_object(activation)?,
+            BYTE_ARRAY => {
+                let length = match self.read_object_header()? {
+                    Ok(length) => length,
+                    Err(object) => return Ok(object),
+                };
+                let index = self.reserve_object();
+                let bytes = self.read_bytes(length as usize)?;
+                let storage = ByteArrayStorage::from_vec(bytes);
+                let bytearray = ByteArrayObject::from_storage(activation, storage)?;
+                self.objects[index] = bytearray.into();
+                bytearray.into()
+            }
+            VECTOR_INT | VECTOR_UINT | VECTOR_DOUBLE | VECTOR_OBJECT => {
+                self.read_vector(activation, marker)?
+            }
+            DICTIONARY => {
+                let length = match self.read_object_header()? {
+                    Ok(length) => length,
+                    Err(object) => return Ok(object),
+                };
+                let weak_keys = self.read_u8()? != 0;
+                let mut dictionary = activation
+                    .avm2()
+                    .classes()
+                    .dictionary
+                    .construct(activation, &[weak_keys.into()])?;
+                self.objects.push(dictionary.into());
+
+                for _ in 0..length {
+                    let key = self.read_value(activation)?;
+                    let value = self.read_value(activation)?;
+                    match (key, dictionary.as_dictionary_object()) {
+                        (Value::Object(key), Some(dictionary)) => {
+                            dictionary.set_property_by_object(
+                                key,
+                                value,
+                                activation.context.gc_context,
+                            );
+                        }
+                        (key, _) => {
+                            let name = key.coerce_to_string(activation)?;
+                            dictionary.set_property(&Multiname::public(name), value, activation)?;
+                        }
+                    }
+                }
+
+                dictionary.into()
+            }
+            _ => return Err(format!("Unknown AMF3 type marker {marker:#04x}").into()),
+        })
+    }
+
+    fn read_vector(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        marker: u8,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let length = match self.read_object_header()? {
+            Ok(length) => length as usize,
+            Err(object) => return Ok(object),
+        };
+        let index = self.reserve_object();
+        let is_fixed = self.read_u8()? != 0;
+
+        let classes = activation.avm2().classes();
+        let (value_type, values) = match marker {
+            VECTOR_INT => {
+                let bytes = self.read_bytes(length * 4)?;
+                let values = bytes
+                    .chunks_exact(4)
+                    .map(|chunk| i32::from_be_bytes(chunk.try_into().unwrap()).into())
+                    .collect();
+                (classes.int, values)
+            }
+            VECTOR_UINT => {
+                let bytes = self.read_bytes(length * 4)?;
+                let values = bytes
+                    .chunks_exact(4)
+                    .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()).into())
+                    .collect();
+                (classes.uint, values)
+            }
+            VECTOR_DOUBLE => {
+                let bytes = self.read_bytes(length * 8)?;
+                let values = bytes
+                    .chunks_exact(8)
+                    .map(|chunk| f64::from_be_bytes(chunk.try_into().unwrap()).into())
+                    .collect();
+                (classes.number, values)
+            }
+            _ => {
+                let object_class = classes.object;
+                let type_name = self.read_string(activation)?;
+                let value_type = if type_name.is_empty() || &type_name == b"*" {
+                    object_class
+                } else {
+                    self.class_by_name(activation, type_name)
+                        .unwrap_or(object_class)
+                };
+
+                // The vector must exist before its elements are read, as they
+                // may refer back to it.
+                let storage = VectorStorage::new(0, false, value_type, activation);
+                let vector = VectorObject::from_vector(storage, activation)?;
+                self.objects[index] = vector.into();
+
+                for _ in 0..length {
+                    let value = self
+                        .read_value(activation)?
+                        .coerce_to_type(activation, value_type)?;
+                    if let Some(mut storage) =
+                        vector.as_vector_storage_mut(activation.context.gc_context)
+                    {
+                        storage.push(value, activation)?;
+                    }
+                }
+
+                if let Some(mut storage) =
+                    vector.as_vector_storage_mut(activation.context.gc_context)
+                {
+                    storage.set_is_fixed(is_fixed);
+                }
+
+                return Ok(vector.into());
+            }
+        };
+
+        let storage = VectorStorage::from_values(values, is_fixed, value_type, activation);
+        let vector = VectorObject::from_vector(storage, activation)?;
+        self.objects[index] = vector.into();
+        Ok(vector.into())
+    }
+
+    fn read_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let header = match self.read_object_header()? {
+            Ok(header) => header,
+            Err(object) => return Ok(object),
+        };
+
+        let traits_index = if header & 1 == 0 {
+            let index = (header >> 1) as usize;
+            if index >= self.traits.len() {
+                return Err(format!("Invalid AMF3 traits reference {index}").into());
+            }
+            index
+        } else {
+            let externalizable = header & 0b10 != 0;
+            let dynamic = header & 0b100 != 0;
+            let sealed_count = header >> 3;
+            let class_name = self.read_string(activation)?;
+            let mut sealed = Vec::new();
+            if !externalizable {
+                for _ in 0..sealed_count {
+                    sealed.push(self.read_string(activation)?);
+                }
+            }
+
+            self.traits.push(ObjectTraits {
+                class_name,
+                externalizable,
+                dynamic,
+                sealed,
+            });
+            self.traits.len() - 1
+        };
+
+        let class_name = self.traits[traits_index].class_name;
+        let externalizable = self.traits[traits_index].externalizable;
+        let class = if class_name.is_empty() {
+            None
+        } else {
+            activation.avm2().get_class_by_alias(class_name)
+        };
+
+        let index = self.reserve_object();
+        if externalizable {
+            let class = match class {
+                Some(class) => class,
+                None => {
+                    return Err(Error::AvmError(reference_error(
+                        activation,
+                        &format!("Error #1014: Class {class_name} could not be found."),
+                        1014,
+                    )?));
+                }
+            };
+
+            let object = class.construct(activation, &[])?;
+            self.objects[index] = object.into();
+
+            let iexternalizable = activation.avm2().classes().iexternalizable;
+            if !object.is_of_type(iexternalizable, activation) {
+                return Err(Error::AvmError(type_error(
+                    activation,
+                    &format!("Error #2173: Unable to read object in stream. The class {class_name} does not implement flash.utils.IExternalizable but is aliased to an externalizable class."),
+                    2173,
+                )?));
+            }
+
+            object.call_property(
+                &Multiname::public("readExternal"),
+                &[self.source.into()],
+                activation,
+            )?;
+            return Ok(object.into());
+        }
+
+        let class = class.unwrap_or_else(|| activation.avm2().classes().object);
+        let mut object = class.construct(activation, &[])?;
+        self.objects[index] = object.into();
+
+        let sealed = self.traits[traits_index].sealed.clone();
+        for name in sealed {
+            let value = self.read_value(activation)?;
+            object.set_property(&Multiname::public(name), value, activation)?;
+        }
+
+        if self.traits[traits_index].dynamic {
+            loop {
+                let name = self.read_string(activation)?;
+                if name.is_empty() {
+                    break;
+                }
+
+                let value = self.read_value(activation)?;
+                object.set_property(&Multiname::public(name), value, activation)?;
+            }
+        }
+
+        Ok(object.into())
+    }
+}
+
+/// Serialize a Value to an AMF0 value.
 pub fn serialize_value<'gc>(
     activation: &mut Activation<'_, 'gc>,
     elem: Value<'gc>,
-    amf_version: AMFVersion,
 ) -> Option<AmfValue> {
     match elem {
         Value::Undefined => Some(AmfValue::Undefined),
         Value::Null => Some(AmfValue::Null),
         Value::Bool(b) => Some(AmfValue::Bool(b)),
         Value::Number(f) => Some(AmfValue::Number(f)),
-        Value::Integer(num) => {
-            // NOTE - we should really be converting `Value::Integer` to `Value::Number`
-            // whenever it's outside this range, instead of performing this during AMF serialization.
-            // Integers are unsupported in AMF0, and must be converted to Number regardless of whether
-            // it can be represented as an integer.
-            if amf_version == AMFVersion::AMF0 || num >= (1 << 28) || num < -(1 << 28) {
-                Some(AmfValue::Number(num as f64))
-            } else {
-                Some(AmfValue::Integer(num))
-            }
-        }
+        // Integers are unsupported in AMF0.
+        Value::Integer(num) => Some(AmfValue::Number(num as f64)),
         Value::String(s) => Some(AmfValue::String(s.to_string())),
         Value::Object(o) => {
             // TODO: Find a more general rule for which object types should be skipped,
@@ -41,7 +1051,7 @@ pub fn serialize_value<'gc>(
                 Some(AmfValue::Undefined)
             } else if let Some(array) = o.as_array_storage() {
                 let mut values = Vec::new();
-                recursive_serialize(activation, o, &mut values, amf_version).unwrap();
+                recursive_serialize(activation, o, &mut values).unwrap();
 
                 let mut dense = vec![];
                 let mut sparse = vec![];
@@ -59,6 +1069,12 @@ pub fn serialize_value<'gc>(
                     let len = sparse.len() as u32;
                     Some(AmfValue::ECMAArray(dense, sparse, len))
                 }
+            } else if let Some(bytes) = o.as_bytearray() {
+                Some(AmfValue::ByteArray(bytes.bytes().to_vec()))
+            } else if let Some(xml) = o.as_xml_object() {
+                xml.xml_to_xml_string(activation)
+                    .ok()
+                    .map(|xml_string| AmfValue::XML(xml_string.to_string(), true))
             } else if let Some(date) = o.as_date_object() {
                 date.date_time()
                     .map(|date_time| AmfValue::Date(date_time.timestamp_millis() as f64, None))
@@ -68,7 +1084,7 @@ pub fn serialize_value<'gc>(
                     .map_or(false, |c| c == activation.avm2().classes().object);
                 if is_object {
                     let mut object_body = Vec::new();
-                    recursive_serialize(activation, o, &mut object_body, amf_version).unwrap();
+                    recursive_serialize(activation, o, &mut object_body).unwrap();
                     Some(AmfValue::Object(
                         object_body,
                         Some(ClassDefinition {
@@ -89,12 +1105,11 @@ pub fn serialize_value<'gc>(
     }
 }
 
-/// Serialize an Object and any children to a AMF object
+/// Serialize an Object and any children to AMF0 elements.
 pub fn recursive_serialize<'gc>(
     activation: &mut Activation<'_, 'gc>,
     obj: Object<'gc>,
     elements: &mut Vec<Element>,
-    amf_version: AMFVersion,
 ) -> Result<(), Error<'gc>> {
     let mut last_index = obj.get_next_enumerant(0, activation)?;
     while let Some(index) = last_index {
@@ -103,7 +1118,7 @@ pub fn recursive_serialize<'gc>(
             .coerce_to_string(activation)?;
         let value = obj.get_property(&Multiname::public(name), activation)?;
 
-        if let Some(value) = serialize_value(activation, value, amf_version) {
+        if let Some(value) = serialize_value(activation, value) {
             elements.push(Element::new(name.to_utf8_lossy(), value));
         }
         last_index = obj.get_next_enumerant(index, activation)?;
@@ -111,7 +1126,7 @@ pub fn recursive_serialize<'gc>(
     Ok(())
 }
 
-/// Deserialize a AmfValue to a Value
+/// Deserialize an AMF0 value, which may embed AMF3 values, to a Value.
 pub fn deserialize_value<'gc>(
     activation: &mut Activation<'_, 'gc>,
     val: &AmfValue,
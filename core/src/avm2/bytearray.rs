@@ -8,6 +8,8 @@ use std::cmp;
 use std::fmt::{self, Display, Formatter};
 use std::io::prelude::*;
 use std::io::{self, Read, SeekFrom};
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 #[derive(Clone, Collect, Debug, Copy, PartialEq, Eq)]
 #[collect(no_drop)]
@@ -58,11 +60,82 @@ pub enum ObjectEncoding {
     Amf3 = 3,
 }
 
-#[derive(Clone, Collect, Debug)]
+/// Memory that the ByteArrays of several workers share.
+pub type SharedBytes = Arc<RwLock<Vec<u8>>>;
+
+#[derive(Debug)]
+enum Bytes {
+    Owned(Vec<u8>),
+
+    /// The ByteArray is shareable and was passed to another worker, which sees its changes.
+    Shared(SharedBytes),
+}
+
+/// The contents of a ByteArray, or part of them.
+///
+/// The memory of a shared ByteArray stays locked for reading as long as this is held.
+pub enum BytesRef<'a> {
+    Owned(&'a [u8]),
+    Shared(RwLockReadGuard<'a, Vec<u8>>, Range<usize>),
+}
+
+impl BytesRef<'_> {
+    /// Narrows these bytes down to `range`, which must be within them.
+    fn slice(self, range: Range<usize>) -> Self {
+        match self {
+            BytesRef::Owned(bytes) => BytesRef::Owned(&bytes[range]),
+            BytesRef::Shared(guard, outer) => {
+                BytesRef::Shared(guard, outer.start + range.start..outer.start + range.end)
+            }
+        }
+    }
+}
+
+impl Deref for BytesRef<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            BytesRef::Owned(bytes) => bytes,
+            BytesRef::Shared(guard, range) => &guard[range.clone()],
+        }
+    }
+}
+
+/// The contents of a ByteArray, borrowed to be changed.
+///
+/// The memory of a shared ByteArray stays locked as long as this is held.
+pub enum BytesMut<'a> {
+    Owned(&'a mut Vec<u8>),
+    Shared(RwLockWriteGuard<'a, Vec<u8>>),
+}
+
+impl Deref for BytesMut<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        match self {
+            BytesMut::Owned(bytes) => bytes,
+            BytesMut::Shared(guard) => guard,
+        }
+    }
+}
+
+impl DerefMut for BytesMut<'_> {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        match self {
+            BytesMut::Owned(bytes) => bytes,
+            BytesMut::Shared(guard) => guard,
+        }
+    }
+}
+
+#[derive(Collect, Debug)]
 #[collect(no_drop)]
 pub struct ByteArrayStorage {
     /// Underlying ByteArray
-    bytes: Vec<u8>,
+    #[collect(require_static)]
+    bytes: Bytes,
 
     /// The current position to read/write from
     position: Cell<usize>,
@@ -78,7 +151,7 @@ impl ByteArrayStorage {
     /// Create a new ByteArrayStorage
     pub fn new() -> ByteArrayStorage {
         ByteArrayStorage {
-            bytes: Vec::new(),
+            bytes: Bytes::Owned(Vec::new()),
             position: Cell::new(0),
             endian: Endian::Big,
             object_encoding: ObjectEncoding::Amf3,
@@ -88,13 +161,35 @@ impl ByteArrayStorage {
     /// Create a new ByteArrayStorage using an already existing vector
     pub fn from_vec(bytes: Vec<u8>) -> ByteArrayStorage {
         ByteArrayStorage {
-            bytes,
+            bytes: Bytes::Owned(bytes),
             position: Cell::new(0),
             endian: Endian::Big,
             object_encoding: ObjectEncoding::Amf3,
         }
     }
 
+    /// Create a new ByteArrayStorage using memory shared with other workers
+    pub fn from_shared(bytes: SharedBytes) -> ByteArrayStorage {
+        ByteArrayStorage {
+            bytes: Bytes::Shared(bytes),
+            ..Self::new()
+        }
+    }
+
+    /// Shares the memory of this ByteArray, so that it can be passed to other workers.
+    ///
+    /// Changes made through any of the workers are seen by all others.
+    pub fn share(&mut self) -> SharedBytes {
+        match &mut self.bytes {
+            Bytes::Shared(shared) => shared.clone(),
+            Bytes::Owned(bytes) => {
+                let shared = Arc::new(RwLock::new(std::mem::take(bytes)));
+                self.bytes = Bytes::Shared(shared.clone());
+                shared
+            }
+        }
+    }
+
     /// Write bytes at the next position in the ByteArray, growing if needed.
     #[inline]
     pub fn write_bytes<'gc>(&mut self, buf: &[u8]) -> Result<(), Error<'gc>> {
@@ -112,7 +207,7 @@ impl ByteArrayStorage {
 
     /// Reads any amount of bytes from the current position in the ByteArray
     #[inline]
-    pub fn read_bytes<'gc>(&self, amnt: usize) -> Result<&[u8], Error<'gc>> {
+    pub fn read_bytes<'gc>(&self, amnt: usize) -> Result<BytesRef<'_>, Error<'gc>> {
         let bytes = self.read_at(amnt, self.position.get())?;
         self.position.set(self.position.get() + amnt);
         Ok(bytes)
@@ -120,11 +215,13 @@ impl ByteArrayStorage {
 
     /// Reads any amount of bytes at any offset in the ByteArray
     #[inline]
-    pub fn read_at<'gc>(&self, amnt: usize, offset: usize) -> Result<&[u8], Error<'gc>> {
-        self.bytes
-            .get(offset..)
-            .and_then(|bytes| bytes.get(..amnt))
-            .ok_or_else(|| "EOFError: Reached EOF".into())
+    pub fn read_at<'gc>(&self, amnt: usize, offset: usize) -> Result<BytesRef<'_>, Error<'gc>> {
+        let bytes = self.bytes();
+        let end = offset
+            .checked_add(amnt)
+            .filter(|end| *end <= bytes.len())
+            .ok_or("EOFError: Reached EOF")?;
+        Ok(bytes.slice(offset..end))
     }
 
     /// Write bytes at any offset in the ByteArray
@@ -133,13 +230,11 @@ impl ByteArrayStorage {
         let new_len = offset
             .checked_add(buf.len())
             .ok_or("RangeError: Cannot overflow usize")?;
-        if self.len() < new_len {
-            self.set_length(new_len);
+        let mut bytes = self.bytes_mut();
+        if bytes.len() < new_len {
+            bytes.resize(new_len, 0);
         }
-        self.bytes
-            .get_mut(offset..new_len)
-            .expect("ByteArray write out of bounds")
-            .copy_from_slice(buf);
+        bytes[offset..new_len].copy_from_slice(buf);
         Ok(())
     }
 
@@ -150,7 +245,7 @@ impl ByteArrayStorage {
         buf: &[u8],
         offset: usize,
     ) -> Result<(), Error<'gc>> {
-        self.bytes
+        self.bytes_mut()
             .get_mut(offset..)
             .and_then(|bytes| bytes.get_mut(..buf.len()))
             .ok_or("RangeError: The specified range is invalid")?
@@ -166,40 +261,51 @@ impl ByteArrayStorage {
         amnt: usize,
         offset: usize,
     ) -> Result<(), Error<'gc>> {
+        let mut bytes = self.bytes_mut();
+
         // First verify that reading from `start` to `amnt` is valid
         let end = start
             .checked_add(amnt)
-            .filter(|result| *result <= self.len())
+            .filter(|result| *result <= bytes.len())
             .ok_or("RangeError: Reached EOF")?;
 
         // Second we resize our underlying buffer to ensure that writing `amnt` from `offset` is valid.
         let new_len = offset
             .checked_add(amnt)
             .ok_or("RangeError: Cannot overflow usize")?;
-        if self.len() < new_len {
-            self.set_length(new_len);
+        if bytes.len() < new_len {
+            bytes.resize(new_len, 0);
         }
 
-        self.bytes.copy_within(start..end, offset);
+        bytes.copy_within(start..end, offset);
         Ok(())
     }
 
     /// Compress the ByteArray into a temporary buffer.
     pub fn compress(&mut self, algorithm: CompressionAlgorithm) -> Vec<u8> {
+        let bytes = self.bytes();
         let mut buffer = Vec::new();
         let error: Option<Box<dyn std::error::Error>> = match algorithm {
             CompressionAlgorithm::Zlib => {
-                let mut encoder = ZlibEncoder::new(&*self.bytes, Compression::fast());
+                let mut encoder = ZlibEncoder::new(&*bytes, Compression::fast());
                 encoder.read_to_end(&mut buffer).err().map(|e| e.into())
             }
             CompressionAlgorithm::Deflate => {
-                let mut encoder = DeflateEncoder::new(&*self.bytes, Compression::fast());
+                let mut encoder = DeflateEncoder::new(&*bytes, Compression::fast());
                 encoder.read_to_end(&mut buffer).err().map(|e| e.into())
             }
             #[cfg(feature = "lzma")]
-            CompressionAlgorithm::Lzma => lzma_rs::lzma_compress(&mut &*self.bytes, &mut buffer)
-                .err()
-                .map(|e| e.into()),
+            CompressionAlgorithm::Lzma => {
+                use lzma_rs::compress::{Options, UnpackedSize};
+                // Flash writes the uncompressed length into the LZMA header instead of
+                // marking it as unknown, and content parsing the header relies on it.
+                let options = Options {
+                    unpacked_size: UnpackedSize::WriteToHeader(Some(bytes.len() as u64)),
+                };
+                lzma_rs::lzma_compress_with_options(&mut &*bytes, &mut buffer, &options)
+                    .err()
+                    .map(|e| e.into())
+            }
             #[cfg(not(feature = "lzma"))]
             CompressionAlgorithm::Lzma => Some("Ruffle was not compiled with LZMA support".into()),
         };
@@ -213,18 +319,19 @@ impl ByteArrayStorage {
 
     /// Decompress the ByteArray into a temporary buffer.
     pub fn decompress(&mut self, algorithm: CompressionAlgorithm) -> Option<Vec<u8>> {
+        let bytes = self.bytes();
         let mut buffer = Vec::new();
         let error: Option<Box<dyn std::error::Error>> = match algorithm {
             CompressionAlgorithm::Zlib => {
-                let mut decoder = ZlibDecoder::new(&*self.bytes);
+                let mut decoder = ZlibDecoder::new(&*bytes);
                 decoder.read_to_end(&mut buffer).err().map(|e| e.into())
             }
             CompressionAlgorithm::Deflate => {
-                let mut decoder = DeflateDecoder::new(&*self.bytes);
+                let mut decoder = DeflateDecoder::new(&*bytes);
                 decoder.read_to_end(&mut buffer).err().map(|e| e.into())
             }
             #[cfg(feature = "lzma")]
-            CompressionAlgorithm::Lzma => lzma_rs::lzma_decompress(&mut &*self.bytes, &mut buffer)
+            CompressionAlgorithm::Lzma => lzma_rs::lzma_decompress(&mut &*bytes, &mut buffer)
                 .err()
                 .map(|e| e.into()),
             #[cfg(not(feature = "lzma"))]
@@ -238,7 +345,7 @@ impl ByteArrayStorage {
         }
     }
 
-    pub fn read_utf<'gc>(&self) -> Result<&[u8], Error<'gc>> {
+    pub fn read_utf<'gc>(&self) -> Result<BytesRef<'_>, Error<'gc>> {
         let len = self.read_unsigned_short()?;
         let val = self.read_bytes(len.into())?;
         Ok(val)
@@ -249,7 +356,7 @@ impl ByteArrayStorage {
     }
 
     pub fn read_boolean<'gc>(&self) -> Result<bool, Error<'gc>> {
-        Ok(self.read_bytes(1)? != [0])
+        Ok(self.read_bytes(1)?[0] != 0)
     }
 
     // Writes a UTF String into the buffer, with its length as a prefix
@@ -264,51 +371,70 @@ impl ByteArrayStorage {
 
     #[inline]
     pub fn clear(&mut self) {
-        self.bytes.clear();
+        self.bytes_mut().clear();
         self.position.set(0)
     }
 
     #[inline]
     pub fn shrink_to_fit(&mut self) {
-        self.bytes.shrink_to_fit()
+        self.bytes_mut().shrink_to_fit()
     }
 
     #[inline]
     pub fn set_length(&mut self, new_len: usize) {
-        self.bytes.resize(new_len, 0);
+        self.bytes_mut().resize(new_len, 0);
     }
 
     pub fn get(&self, pos: usize) -> Option<u8> {
-        self.bytes.get(pos).copied()
+        self.bytes().get(pos).copied()
     }
 
     pub fn set(&mut self, item: usize, value: u8) {
-        if self.len() < (item + 1) {
-            self.bytes.resize(item + 1, 0)
+        let mut bytes = self.bytes_mut();
+        if bytes.len() < (item + 1) {
+            bytes.resize(item + 1, 0)
         }
 
-        *self.bytes.get_mut(item).unwrap() = value;
+        bytes[item] = value;
     }
 
     pub fn delete(&mut self, item: usize) {
-        if let Some(i) = self.bytes.get_mut(item) {
+        if let Some(i) = self.bytes_mut().get_mut(item) {
             *i = 0;
         }
     }
 
+    /// The contents of the ByteArray.
+    ///
+    /// If the ByteArray is shared, other workers can't change it until the result is dropped.
     #[inline]
-    pub fn bytes(&self) -> &[u8] {
-        &self.bytes
+    pub fn bytes(&self) -> BytesRef<'_> {
+        match &self.bytes {
+            Bytes::Owned(bytes) => BytesRef::Owned(bytes),
+            Bytes::Shared(shared) => {
+                let guard = shared.read().unwrap_or_else(PoisonError::into_inner);
+                let len = guard.len();
+                BytesRef::Shared(guard, 0..len)
+            }
+        }
     }
 
+    /// The contents of the ByteArray, to be changed.
+    ///
+    /// If the ByteArray is shared, other workers can't access it until the result is dropped.
     #[inline]
-    pub fn bytes_mut(&mut self) -> &mut [u8] {
-        &mut self.bytes
+    pub fn bytes_mut(&mut self) -> BytesMut<'_> {
+        match &mut self.bytes {
+            Bytes::Owned(bytes) => BytesMut::Owned(bytes),
+            Bytes::Shared(shared) => {
+                BytesMut::Shared(shared.write().unwrap_or_else(PoisonError::into_inner))
+            }
+        }
     }
 
     #[inline]
     pub fn len(&self) -> usize {
-        self.bytes.len()
+        self.bytes().len()
     }
 
     #[inline]
@@ -368,7 +494,7 @@ impl Read for ByteArrayStorage {
             .map_err(|_| {
                 io::Error::new(io::ErrorKind::Other, "Failed to read from ByteArrayStorage")
             })?;
-        buf[..bytes.len()].copy_from_slice(bytes);
+        buf[..bytes.len()].copy_from_slice(&bytes);
         Ok(bytes.len())
     }
 }
@@ -426,15 +552,15 @@ macro_rules! impl_read{
         impl ByteArrayStorage {
             $( pub fn $method_name<'gc> (&self) -> Result<$data_type, Error<'gc>> {
                 Ok(match self.endian {
-                    Endian::Big => <$data_type>::from_be_bytes(self.read_bytes($size)?.try_into().unwrap()),
-                    Endian::Little => <$data_type>::from_le_bytes(self.read_bytes($size)?.try_into().unwrap())
+                    Endian::Big => <$data_type>::from_be_bytes((&*self.read_bytes($size)?).try_into().unwrap()),
+                    Endian::Little => <$data_type>::from_le_bytes((&*self.read_bytes($size)?).try_into().unwrap())
                 })
              } )*
 
              $( pub fn $at_method_name<'gc> (&self, offset: usize) -> Result<$data_type, Error<'gc>> {
                 Ok(match self.endian {
-                    Endian::Big => <$data_type>::from_be_bytes(self.read_at($size, offset)?.try_into().unwrap()),
-                    Endian::Little => <$data_type>::from_le_bytes(self.read_at($size, offset)?.try_into().unwrap())
+                    Endian::Big => <$data_type>::from_be_bytes((&*self.read_at($size, offset)?).try_into().unwrap()),
+                    Endian::Little => <$data_type>::from_le_bytes((&*self.read_at($size, offset)?).try_into().unwrap())
                 })
              } )*
         }
@@ -444,8 +570,83 @@ macro_rules! impl_read{
 impl_write!(write_float f32, write_double f64, write_int i32, write_unsigned_int u32, write_short i16, write_unsigned_short u16);
 impl_read!(read_float read_float_at 4; f32, read_double read_double_at 8; f64, read_int read_int_at 4; i32, read_unsigned_int read_unsigned_int_at 4; u32, read_short read_short_at 2; i16, read_unsigned_short read_unsigned_short_at 2; u16, read_byte read_byte_at 1; i8, read_unsigned_byte read_unsigned_byte_at 1; u8);
 
+impl Clone for ByteArrayStorage {
+    /// Copies the ByteArray. The copy doesn't share the memory of a shared ByteArray.
+    fn clone(&self) -> Self {
+        Self {
+            bytes: Bytes::Owned(self.bytes().to_vec()),
+            position: self.position.clone(),
+            endian: self.endian,
+            object_encoding: self.object_encoding,
+        }
+    }
+}
+
 impl Default for ByteArrayStorage {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(algorithm: CompressionAlgorithm) {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 7) as u8).collect();
+        let mut bytes = ByteArrayStorage::from_vec(data.clone());
+        let compressed = bytes.compress(algorithm);
+        assert!(!compressed.is_empty());
+        assert_ne!(compressed, data);
+
+        let mut bytes = ByteArrayStorage::from_vec(compressed);
+        assert_eq!(bytes.decompress(algorithm), Some(data));
+    }
+
+    #[test]
+    fn zlib_round_trip() {
+        round_trip(CompressionAlgorithm::Zlib);
+    }
+
+    #[test]
+    fn deflate_round_trip() {
+        round_trip(CompressionAlgorithm::Deflate);
+    }
+
+    #[cfg(feature = "lzma")]
+    #[test]
+    fn lzma_round_trip() {
+        round_trip(CompressionAlgorithm::Lzma);
+    }
+
+    #[cfg(feature = "lzma")]
+    #[test]
+    fn lzma_header_has_length() {
+        let mut bytes = ByteArrayStorage::from_vec(vec![1, 2, 3, 4]);
+        let compressed = bytes.compress(CompressionAlgorithm::Lzma);
+        assert_eq!(&compressed[5..13], &4u64.to_le_bytes());
+    }
+
+    #[test]
+    fn shared_memory() {
+        let mut bytes = ByteArrayStorage::from_vec(vec![1, 2, 3]);
+        let mut other = ByteArrayStorage::from_shared(bytes.share());
+        other.set(1, 5);
+        assert_eq!(&*bytes.bytes(), &[1, 5, 3]);
+
+        bytes.set_length(5);
+        assert_eq!(other.len(), 5);
+        assert_eq!(&*other.read_at(2, 2).unwrap(), &[3, 0]);
+
+        // Copies have memory of their own.
+        let mut copy = other.clone();
+        copy.set(0, 9);
+        assert_eq!(bytes.get(0), Some(1));
+    }
+
+    #[test]
+    fn invalid_data_fails_to_decompress() {
+        let mut bytes = ByteArrayStorage::from_vec(vec![1, 2, 3, 4]);
+        assert_eq!(bytes.decompress(CompressionAlgorithm::Zlib), None);
+    }
+}
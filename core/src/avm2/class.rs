@@ -1,6 +1,8 @@
 //! AVM2 classes
 
 use crate::avm2::activation::Activation;
+use crate::avm2::globals::NS_VECTOR;
+use crate::avm2::metadata::Metadata;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{ClassObject, Object};
 use crate::avm2::script::TranslationUnit;
@@ -152,6 +154,9 @@ pub struct Class<'gc> {
     /// System defined classes are allowed to have illegal trait configurations
     /// without throwing a VerifyError.
     is_system: bool,
+
+    /// The metadata tags declared on this class.
+    metadata: Vec<Metadata<'gc>>,
 }
 
 impl<'gc> Class<'gc> {
@@ -196,6 +201,7 @@ impl<'gc> Class<'gc> {
                 ),
                 traits_loaded: true,
                 is_system: true,
+                metadata: Vec::new(),
             },
         )
     }
@@ -288,6 +294,7 @@ impl<'gc> Class<'gc> {
         attributes.set(ClassAttributes::INTERFACE, abc_instance.is_interface);
 
         let mut instance_allocator = None;
+        let mut call_handler = None;
 
         // When loading a class from our playerglobal, grab the corresponding native
         // allocator function from the table (which may be `None`)
@@ -308,6 +315,9 @@ impl<'gc> Class<'gc> {
                 );
                 native_instance_init = method;
             }
+
+            call_handler = activation.avm2().native_call_handler_table[class_index as usize]
+                .map(|(name, ptr)| Method::from_builtin(ptr, name, activation.context.gc_context));
         }
 
         Ok(GcCell::allocate(
@@ -325,7 +335,7 @@ impl<'gc> Class<'gc> {
                 instance_traits: Vec::new(),
                 class_init,
                 class_initializer_called: false,
-                call_handler: None,
+                call_handler,
                 class_traits: Vec::new(),
                 specialized_class_init: Method::from_builtin(
                     |_, _, _| Ok(Value::Undefined),
@@ -334,6 +344,7 @@ impl<'gc> Class<'gc> {
                 ),
                 traits_loaded: false,
                 is_system: false,
+                metadata: Vec::new(),
             },
         ))
     }
@@ -505,6 +516,7 @@ impl<'gc> Class<'gc> {
                 class_traits: Vec::new(),
                 traits_loaded: true,
                 is_system: false,
+                metadata: Vec::new(),
             },
         ))
     }
@@ -513,6 +525,19 @@ impl<'gc> Class<'gc> {
         self.name
     }
 
+    /// The name user code knows this class by.
+    ///
+    /// Specializations of `Vector` are stored under internal names such as
+    /// `Vector$int`, but are reported as `Vector` everywhere else; the type
+    /// parameter is available through `params`.
+    pub fn generic_name(&self) -> QName<'gc> {
+        if self.params.is_empty() {
+            self.name
+        } else {
+            QName::new(Namespace::package(NS_VECTOR), "Vector")
+        }
+    }
+
     pub fn set_name(&mut self, name: QName<'gc>) {
         self.name = name;
     }
@@ -685,6 +710,32 @@ impl<'gc> Class<'gc> {
         }
     }
     #[inline(never)]
+    pub fn define_ns_builtin_instance_properties(
+        &mut self,
+        mc: MutationContext<'gc, '_>,
+        ns: &'static str,
+        items: &[(
+            &'static str,
+            Option<NativeMethodImpl>,
+            Option<NativeMethodImpl>,
+        )],
+    ) {
+        for &(name, getter, setter) in items {
+            if let Some(getter) = getter {
+                self.define_instance_trait(Trait::from_getter(
+                    QName::new(Namespace::Namespace(ns.into()), name),
+                    Method::from_builtin(getter, name, mc),
+                ));
+            }
+            if let Some(setter) = setter {
+                self.define_instance_trait(Trait::from_setter(
+                    QName::new(Namespace::Namespace(ns.into()), name),
+                    Method::from_builtin(setter, name, mc),
+                ));
+            }
+        }
+    }
+    #[inline(never)]
     pub fn define_public_slot_number_instance_traits(
         &mut self,
         items: &[(&'static str, Option<f64>)],
@@ -824,6 +875,15 @@ impl<'gc> Class<'gc> {
         self.specialized_class_init = specialized_init;
     }
 
+    /// The metadata tags declared on this class.
+    pub fn metadata(&self) -> &[Metadata<'gc>] {
+        &self.metadata
+    }
+
+    pub fn set_metadata(&mut self, metadata: Vec<Metadata<'gc>>) {
+        self.metadata = metadata;
+    }
+
     pub fn interfaces(&self) -> &[Multiname<'gc>] {
         &self.interfaces
     }
@@ -82,6 +82,17 @@ impl<'gc> Domain<'gc> {
         self.0.read().parent
     }
 
+    /// The scripts that exported the definitions of this domain, not counting
+    /// the definitions of its parents.
+    pub fn defining_scripts(self) -> Vec<Script<'gc>> {
+        self.0
+            .read()
+            .defs
+            .iter()
+            .map(|(_, _, script)| *script)
+            .collect()
+    }
+
     /// Determine if something has been defined within the current domain.
     pub fn has_definition(self, name: QName<'gc>) -> bool {
         let read = self.0.read();
@@ -147,15 +158,25 @@ impl<'gc> Domain<'gc> {
 
     /// Export a definition from a script into the current application domain.
     ///
-    /// This returns an error if the name is already defined in the current or
-    /// any parent domains.
+    /// This returns an error if the name is already defined in the current
+    /// domain. Names defined by a parent domain keep the parent's definition,
+    /// as in Flash Player.
     pub fn export_definition(
         &mut self,
         name: QName<'gc>,
         script: Script<'gc>,
         mc: MutationContext<'gc, '_>,
     ) -> Result<(), Error<'gc>> {
-        if self.has_definition(name) {
+        let parent = self.0.read().parent;
+        if parent.map_or(false, |parent| parent.has_definition(name)) {
+            tracing::debug!(
+                "Keeping the parent domain's definition of {}",
+                name.to_qualified_name(mc)
+            );
+            return Ok(());
+        }
+
+        if self.0.read().defs.contains_key(name) {
             return Err(format!(
                 "VerifyError: Attempted to redefine existing name {}",
                 name.local_name()
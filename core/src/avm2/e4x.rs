@@ -0,0 +1,1251 @@
+//! E4X (ECMAScript for XML) node tree
+//!
+//! This is the document model behind the `XML` and `XMLList` classes. Unlike the AVM1 XML DOM,
+//! nodes don't have script objects of their own: an `XML` object is a handle to a node, and an
+//! `XMLList` is a list of nodes, so that the same node may be reached from many objects.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::type_error;
+use crate::avm2::object::TObject;
+use crate::avm2::value::Value;
+use crate::avm2::{Error, Multiname, Namespace};
+use crate::string::{AvmString, WStr, WString};
+use gc_arena::{Collect, GcCell, MutationContext};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fmt;
+
+/// The URI of the namespace bound to the `xml` prefix in every document.
+const XML_NAMESPACE_URI: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// A node of an XML tree.
+#[derive(Copy, Clone, Collect)]
+#[collect(no_drop)]
+pub struct E4XNode<'gc>(GcCell<'gc, E4XNodeData<'gc>>);
+
+impl fmt::Debug for E4XNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("E4XNode")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct E4XNodeData<'gc> {
+    parent: Option<E4XNode<'gc>>,
+
+    /// The namespace of an element or attribute, or `None` if it isn't in any namespace.
+    namespace: Option<E4XNamespace<'gc>>,
+
+    /// The name of an element, attribute or processing instruction.
+    local_name: Option<AvmString<'gc>>,
+
+    kind: E4XNodeKind<'gc>,
+}
+
+/// A namespace, as declared in a document or given to a node.
+#[derive(Copy, Clone, Collect, Debug, PartialEq, Eq)]
+#[collect(no_drop)]
+pub struct E4XNamespace<'gc> {
+    /// The prefix bound to the namespace. An empty prefix is the default namespace, and `None`
+    /// means that no prefix was chosen.
+    pub prefix: Option<AvmString<'gc>>,
+
+    pub uri: AvmString<'gc>,
+}
+
+impl<'gc> E4XNamespace<'gc> {
+    pub fn new(prefix: Option<AvmString<'gc>>, uri: AvmString<'gc>) -> Self {
+        Self { prefix, uri }
+    }
+
+    /// Returns the prefix to write this namespace with, where an empty prefix is the default
+    /// namespace.
+    fn output_prefix(&self) -> AvmString<'gc> {
+        self.prefix.unwrap_or_default()
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+enum E4XNodeKind<'gc> {
+    Text(AvmString<'gc>),
+    CData(AvmString<'gc>),
+    Comment(AvmString<'gc>),
+    ProcessingInstruction(AvmString<'gc>),
+    Attribute(AvmString<'gc>),
+    Element {
+        attributes: Vec<E4XNode<'gc>>,
+        children: Vec<E4XNode<'gc>>,
+
+        /// The namespaces declared on this element with `xmlns` attributes.
+        namespaces: Vec<E4XNamespace<'gc>>,
+    },
+}
+
+/// The settings of the `XML` class that affect parsing and printing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XmlSettings {
+    pub ignore_comments: bool,
+    pub ignore_processing_instructions: bool,
+    pub ignore_whitespace: bool,
+    pub pretty_printing: bool,
+    pub pretty_indent: usize,
+}
+
+impl Default for XmlSettings {
+    fn default() -> Self {
+        Self {
+            ignore_comments: true,
+            ignore_processing_instructions: true,
+            ignore_whitespace: true,
+            pretty_printing: true,
+            pretty_indent: 2,
+        }
+    }
+}
+
+impl XmlSettings {
+    /// Reads the settings from the static properties of the `XML` class.
+    pub fn from_xml_class<'gc>(activation: &mut Activation<'_, 'gc>) -> Result<Self, Error<'gc>> {
+        let xml_class = activation.avm2().classes().xml;
+        let get = |name: &'static str, activation: &mut Activation<'_, 'gc>| {
+            xml_class.get_property(&Multiname::public(name), activation)
+        };
+
+        Ok(Self {
+            ignore_comments: get("ignoreComments", activation)?.coerce_to_boolean(),
+            ignore_processing_instructions: get("ignoreProcessingInstructions", activation)?
+                .coerce_to_boolean(),
+            ignore_whitespace: get("ignoreWhitespace", activation)?.coerce_to_boolean(),
+            pretty_printing: get("prettyPrinting", activation)?.coerce_to_boolean(),
+            pretty_indent: get("prettyIndent", activation)?
+                .coerce_to_i32(activation)?
+                .max(0) as usize,
+        })
+    }
+}
+
+/// Whether a character is whitespace, as defined by the XML specification.
+fn is_xml_whitespace(c: u16) -> bool {
+    matches!(c, 0x20 | 0x09 | 0x0d | 0x0a)
+}
+
+fn malformed_error<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
+    match type_error(
+        activation,
+        "Error #1090: XML parser failure: element is malformed.",
+        1090,
+    ) {
+        Ok(error) => Error::AvmError(error),
+        Err(error) => error,
+    }
+}
+
+/// Splits a qualified name like `prefix:name` into its prefix and local name.
+fn split_qualified_name(name: &WStr) -> (Option<&WStr>, &WStr) {
+    match name.find(b':') {
+        Some(index) => (Some(&name[..index]), &name[index + 1..]),
+        None => (None, name),
+    }
+}
+
+/// Implements `EscapeElementValue` from ECMA-357 (10.2.1.1).
+pub fn escape_element_value(s: &WStr) -> WString {
+    let mut r = WString::with_capacity(s.len(), s.is_wide());
+    for c in s {
+        let escape: &[u8] = match u8::try_from(c) {
+            Ok(b'<') => b"&lt;",
+            Ok(b'>') => b"&gt;",
+            Ok(b'&') => b"&amp;",
+            _ => {
+                r.push(c);
+                continue;
+            }
+        };
+
+        r.push_str(WStr::from_units(escape));
+    }
+    r
+}
+
+/// Implements `EscapeAttributeValue` from ECMA-357 (10.2.1.2).
+pub fn escape_attribute_value(s: &WStr) -> WString {
+    let mut r = WString::with_capacity(s.len(), s.is_wide());
+    for c in s {
+        let escape: &[u8] = match u8::try_from(c) {
+            Ok(b'"') => b"&quot;",
+            Ok(b'<') => b"&lt;",
+            Ok(b'&') => b"&amp;",
+            Ok(b'\x0A') => b"&#xA;",
+            Ok(b'\x0D') => b"&#xD;",
+            Ok(b'\x09') => b"&#x9;",
+            _ => {
+                r.push(c);
+                continue;
+            }
+        };
+
+        r.push_str(WStr::from_units(escape));
+    }
+    r
+}
+
+impl<'gc> E4XNode<'gc> {
+    fn new(
+        mc: MutationContext<'gc, '_>,
+        parent: Option<E4XNode<'gc>>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: Option<AvmString<'gc>>,
+        kind: E4XNodeKind<'gc>,
+    ) -> Self {
+        Self(GcCell::allocate(
+            mc,
+            E4XNodeData {
+                parent,
+                namespace,
+                local_name,
+                kind,
+            },
+        ))
+    }
+
+    /// Creates an element without attributes or children.
+    pub fn element(
+        mc: MutationContext<'gc, '_>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: AvmString<'gc>,
+        parent: Option<E4XNode<'gc>>,
+    ) -> Self {
+        Self::new(
+            mc,
+            parent,
+            namespace,
+            Some(local_name),
+            E4XNodeKind::Element {
+                attributes: Vec::new(),
+                children: Vec::new(),
+                namespaces: Vec::new(),
+            },
+        )
+    }
+
+    pub fn text(
+        mc: MutationContext<'gc, '_>,
+        text: AvmString<'gc>,
+        parent: Option<E4XNode<'gc>>,
+    ) -> Self {
+        Self::new(mc, parent, None, None, E4XNodeKind::Text(text))
+    }
+
+    pub fn attribute(
+        mc: MutationContext<'gc, '_>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: AvmString<'gc>,
+        value: AvmString<'gc>,
+        parent: Option<E4XNode<'gc>>,
+    ) -> Self {
+        Self::new(
+            mc,
+            parent,
+            namespace,
+            Some(local_name),
+            E4XNodeKind::Attribute(value),
+        )
+    }
+
+    /// Parses XML markup into a list of top-level nodes.
+    ///
+    /// Unlike a document, the markup may have any number of top-level nodes, including text.
+    /// Elements without a prefix that aren't in a declared default namespace are put in the one
+    /// set with `default xml namespace`, as if the markup was wrapped in an element declaring it.
+    pub fn parse(
+        activation: &mut Activation<'_, 'gc>,
+        markup: &WStr,
+        settings: &XmlSettings,
+    ) -> Result<Vec<Self>, Error<'gc>> {
+        let mc = activation.context.gc_context;
+        let default_namespace = activation
+            .context
+            .avm2
+            .default_xml_namespace()
+            .map(|uri| E4XNamespace::new(Some("".into()), uri));
+        let markup = markup.to_utf8_lossy();
+        let mut parser = Reader::from_str(&markup);
+        parser.check_end_names(true);
+
+        let mut buf = Vec::new();
+        let mut top_level = Vec::new();
+        let mut open_elements: Vec<E4XNode<'gc>> = Vec::new();
+
+        loop {
+            buf.clear();
+            let event = match parser.read_event(&mut buf) {
+                Ok(event) => event,
+                Err(quick_xml::Error::EndEventMismatch { expected, .. }) => {
+                    let message = format!(
+                        "Error #1085: The element type \"{expected}\" must be terminated by the matching end-tag \"</{expected}>\"."
+                    );
+                    return Err(Error::AvmError(type_error(activation, &message, 1085)?));
+                }
+                Err(_) => return Err(malformed_error(activation)),
+            };
+
+            let parent = open_elements.last().copied();
+            let node = match event {
+                Event::Start(start) => {
+                    let element =
+                        Self::from_start_event(activation, &start, parent, default_namespace)?;
+                    open_elements.push(element);
+                    element
+                }
+                Event::Empty(start) => {
+                    Self::from_start_event(activation, &start, parent, default_namespace)?
+                }
+                Event::End(_) => {
+                    open_elements.pop();
+                    continue;
+                }
+                Event::Text(text) => {
+                    let text = match text.unescaped() {
+                        Ok(text) => WString::from_utf8(&String::from_utf8_lossy(&text)),
+                        Err(_) => return Err(malformed_error(activation)),
+                    };
+                    let text = if settings.ignore_whitespace {
+                        text.trim_matches(is_xml_whitespace)
+                    } else {
+                        &text
+                    };
+                    if text.is_empty() {
+                        continue;
+                    }
+                    Self::text(mc, AvmString::new(mc, text), parent)
+                }
+                Event::CData(text) => {
+                    let text = AvmString::new_utf8_bytes(mc, text.escaped());
+                    Self::new(mc, parent, None, None, E4XNodeKind::CData(text))
+                }
+                Event::Comment(text) if !settings.ignore_comments => {
+                    let text = AvmString::new_utf8_bytes(mc, text.escaped());
+                    Self::new(mc, parent, None, None, E4XNodeKind::Comment(text))
+                }
+                Event::PI(text) if !settings.ignore_processing_instructions => {
+                    let text = WString::from_utf8(&String::from_utf8_lossy(text.escaped()));
+                    let (name, value) = match text.find(is_xml_whitespace) {
+                        Some(index) => (&text[..index], text[index..].trim_start()),
+                        None => (&text[..], WStr::empty()),
+                    };
+                    Self::new(
+                        mc,
+                        parent,
+                        None,
+                        Some(AvmString::new(mc, name)),
+                        E4XNodeKind::ProcessingInstruction(AvmString::new(mc, value)),
+                    )
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+
+            match parent {
+                Some(parent) => parent.push_child(mc, node),
+                None => top_level.push(node),
+            }
+        }
+
+        if !open_elements.is_empty() {
+            return Err(malformed_error(activation));
+        }
+
+        Ok(top_level)
+    }
+
+    /// Creates an element from its start tag, resolving the namespaces of its name and
+    /// attributes.
+    fn from_start_event(
+        activation: &mut Activation<'_, 'gc>,
+        start: &BytesStart<'_>,
+        parent: Option<E4XNode<'gc>>,
+        default_namespace: Option<E4XNamespace<'gc>>,
+    ) -> Result<Self, Error<'gc>> {
+        let mc = activation.context.gc_context;
+        let name = WString::from_utf8(&String::from_utf8_lossy(start.name()));
+        let (prefix, local_name) = split_qualified_name(&name);
+        let element = Self::element(mc, None, AvmString::new(mc, local_name), parent);
+
+        let mut attributes = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = match attribute {
+                Ok(attribute) => attribute,
+                Err(_) => return Err(malformed_error(activation)),
+            };
+            let key = WString::from_utf8(&String::from_utf8_lossy(attribute.key));
+            let value = match attribute.unescaped_value() {
+                Ok(value) => AvmString::new_utf8_bytes(mc, &value),
+                Err(_) => return Err(malformed_error(activation)),
+            };
+
+            if &key == b"xmlns" {
+                element.add_namespace(mc, E4XNamespace::new(Some("".into()), value));
+            } else if let Some(declared_prefix) = key.strip_prefix(WStr::from_units(b"xmlns:")) {
+                let declared_prefix = AvmString::new(mc, declared_prefix);
+                element.add_namespace(mc, E4XNamespace::new(Some(declared_prefix), value));
+            } else {
+                attributes.push((key, value));
+            }
+        }
+
+        let namespace = match prefix {
+            Some(prefix) => element.resolve_prefix(mc, prefix),
+            None => element
+                .resolve_prefix(mc, WStr::empty())
+                .or(default_namespace),
+        };
+        if prefix.is_some() && namespace.is_none() {
+            let message = format!(
+                "Error #1083: The prefix \"{}\" for element \"{}\" is not bound.",
+                prefix.unwrap_or_default(),
+                local_name
+            );
+            return Err(Error::AvmError(type_error(activation, &message, 1083)?));
+        }
+        element.0.write(mc).namespace = namespace.filter(|namespace| !namespace.uri.is_empty());
+
+        for (key, value) in attributes {
+            let (prefix, local_name) = split_qualified_name(&key);
+            // Attributes without a prefix are never in the default namespace.
+            let namespace = match prefix {
+                Some(prefix) => match element.resolve_prefix(mc, prefix) {
+                    Some(namespace) => Some(namespace),
+                    None => {
+                        let message = format!(
+                            "Error #1083: The prefix \"{prefix}\" for element \"{local_name}\" is not bound."
+                        );
+                        return Err(Error::AvmError(type_error(activation, &message, 1083)?));
+                    }
+                },
+                None => None,
+            };
+            let attribute = Self::attribute(
+                mc,
+                namespace,
+                AvmString::new(mc, local_name),
+                value,
+                Some(element),
+            );
+            element.push_attribute(mc, attribute);
+        }
+
+        Ok(element)
+    }
+
+    /// Finds the namespace bound to a prefix on this element or its ancestors.
+    fn resolve_prefix(
+        self,
+        mc: MutationContext<'gc, '_>,
+        prefix: &WStr,
+    ) -> Option<E4XNamespace<'gc>> {
+        if prefix == WStr::from_units(b"xml") {
+            return Some(E4XNamespace::new(
+                Some("xml".into()),
+                AvmString::new_utf8(mc, XML_NAMESPACE_URI),
+            ));
+        }
+
+        let mut node = Some(self);
+        while let Some(current) = node {
+            if let E4XNodeKind::Element { namespaces, .. } = &current.0.read().kind {
+                if let Some(namespace) = namespaces
+                    .iter()
+                    .find(|namespace| namespace.output_prefix().as_wstr() == prefix)
+                {
+                    return Some(*namespace);
+                }
+            }
+            node = current.parent();
+        }
+        None
+    }
+
+    pub fn ptr_eq(a: E4XNode<'gc>, b: E4XNode<'gc>) -> bool {
+        GcCell::ptr_eq(a.0, b.0)
+    }
+
+    pub fn parent(self) -> Option<E4XNode<'gc>> {
+        self.0.read().parent
+    }
+
+    pub fn set_parent(self, mc: MutationContext<'gc, '_>, parent: Option<E4XNode<'gc>>) {
+        self.0.write(mc).parent = parent;
+    }
+
+    pub fn local_name(self) -> Option<AvmString<'gc>> {
+        self.0.read().local_name
+    }
+
+    pub fn set_local_name(self, mc: MutationContext<'gc, '_>, local_name: AvmString<'gc>) {
+        self.0.write(mc).local_name = Some(local_name);
+    }
+
+    pub fn namespace(self) -> Option<E4XNamespace<'gc>> {
+        self.0.read().namespace
+    }
+
+    pub fn set_namespace(self, mc: MutationContext<'gc, '_>, namespace: Option<E4XNamespace<'gc>>) {
+        self.0.write(mc).namespace = namespace.filter(|namespace| !namespace.uri.is_empty());
+    }
+
+    /// The URI of the namespace of this node, which is empty if it isn't in a namespace.
+    pub fn namespace_uri(self) -> AvmString<'gc> {
+        self.0
+            .read()
+            .namespace
+            .map(|namespace| namespace.uri)
+            .unwrap_or_default()
+    }
+
+    /// Returns the namespaces declared on this element.
+    pub fn namespace_declarations(self) -> Vec<E4XNamespace<'gc>> {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { namespaces, .. } => namespaces.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the namespaces in scope on this node, the innermost declarations first.
+    pub fn in_scope_namespaces(self) -> Vec<E4XNamespace<'gc>> {
+        let mut result: Vec<E4XNamespace<'gc>> = Vec::new();
+        let mut node = Some(self);
+        while let Some(current) = node {
+            for namespace in current.namespace_declarations() {
+                if !result
+                    .iter()
+                    .any(|found| found.output_prefix() == namespace.output_prefix())
+                {
+                    result.push(namespace);
+                }
+            }
+            node = current.parent();
+        }
+        result
+    }
+
+    /// Declares a namespace on this element, replacing any declaration with the same prefix.
+    pub fn add_namespace(self, mc: MutationContext<'gc, '_>, namespace: E4XNamespace<'gc>) {
+        if let E4XNodeKind::Element { namespaces, .. } = &mut self.0.write(mc).kind {
+            if let Some(prefix) = namespace.prefix {
+                namespaces.retain(|declared| declared.prefix != Some(prefix));
+            } else if namespaces
+                .iter()
+                .any(|declared| declared.uri == namespace.uri)
+            {
+                return;
+            }
+            namespaces.push(namespace);
+        }
+    }
+
+    /// Removes the declaration of a namespace from this element.
+    pub fn remove_namespace(self, mc: MutationContext<'gc, '_>, uri: AvmString<'gc>) {
+        if let E4XNodeKind::Element { namespaces, .. } = &mut self.0.write(mc).kind {
+            namespaces.retain(|declared| declared.uri != uri);
+        }
+    }
+
+    pub fn is_element(self) -> bool {
+        matches!(self.0.read().kind, E4XNodeKind::Element { .. })
+    }
+
+    pub fn is_attribute(self) -> bool {
+        matches!(self.0.read().kind, E4XNodeKind::Attribute(_))
+    }
+
+    pub fn is_text(self) -> bool {
+        matches!(
+            self.0.read().kind,
+            E4XNodeKind::Text(_) | E4XNodeKind::CData(_)
+        )
+    }
+
+    pub fn is_comment(self) -> bool {
+        matches!(self.0.read().kind, E4XNodeKind::Comment(_))
+    }
+
+    pub fn is_processing_instruction(self) -> bool {
+        matches!(self.0.read().kind, E4XNodeKind::ProcessingInstruction(_))
+    }
+
+    /// Implements `XML.nodeKind`.
+    pub fn node_kind(self) -> &'static str {
+        match self.0.read().kind {
+            E4XNodeKind::Text(_) | E4XNodeKind::CData(_) => "text",
+            E4XNodeKind::Comment(_) => "comment",
+            E4XNodeKind::ProcessingInstruction(_) => "processing-instruction",
+            E4XNodeKind::Attribute(_) => "attribute",
+            E4XNodeKind::Element { .. } => "element",
+        }
+    }
+
+    /// The value of a text, comment, processing instruction or attribute node.
+    pub fn value(self) -> Option<AvmString<'gc>> {
+        match self.0.read().kind {
+            E4XNodeKind::Text(value)
+            | E4XNodeKind::CData(value)
+            | E4XNodeKind::Comment(value)
+            | E4XNodeKind::ProcessingInstruction(value)
+            | E4XNodeKind::Attribute(value) => Some(value),
+            E4XNodeKind::Element { .. } => None,
+        }
+    }
+
+    /// Changes the value of a node that isn't an element.
+    pub fn set_value(self, mc: MutationContext<'gc, '_>, new_value: AvmString<'gc>) {
+        match &mut self.0.write(mc).kind {
+            E4XNodeKind::Text(value)
+            | E4XNodeKind::CData(value)
+            | E4XNodeKind::Comment(value)
+            | E4XNodeKind::ProcessingInstruction(value)
+            | E4XNodeKind::Attribute(value) => *value = new_value,
+            E4XNodeKind::Element { .. } => {}
+        }
+    }
+
+    pub fn children(self) -> Vec<E4XNode<'gc>> {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { children, .. } => children.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn attributes(self) -> Vec<E4XNode<'gc>> {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { attributes, .. } => attributes.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn length(self) -> usize {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { children, .. } => children.len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns the position of this node among the children of its parent.
+    pub fn child_index(self) -> Option<usize> {
+        let parent = self.parent()?;
+        let read = parent.0.read();
+        match &read.kind {
+            E4XNodeKind::Element { children, .. } => children
+                .iter()
+                .position(|child| E4XNode::ptr_eq(*child, self)),
+            _ => None,
+        }
+    }
+
+    fn push_child(self, mc: MutationContext<'gc, '_>, child: E4XNode<'gc>) {
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            children.push(child);
+        }
+    }
+
+    fn push_attribute(self, mc: MutationContext<'gc, '_>, attribute: E4XNode<'gc>) {
+        if let E4XNodeKind::Element { attributes, .. } = &mut self.0.write(mc).kind {
+            attributes.push(attribute);
+        }
+    }
+
+    /// Makes `child` a child of this element at the given position, detaching it from the
+    /// element it's in first.
+    ///
+    /// Attributes can't be children, and are ignored.
+    pub fn insert_child(self, mc: MutationContext<'gc, '_>, index: usize, child: E4XNode<'gc>) {
+        if !self.is_element() || child.is_attribute() {
+            return;
+        }
+        let mut index = index;
+        if let Some(old_parent) = child.parent() {
+            if E4XNode::ptr_eq(old_parent, self) {
+                if let Some(old_index) = child.child_index() {
+                    if old_index < index {
+                        index -= 1;
+                    }
+                }
+            }
+            old_parent.remove_child(mc, child);
+        }
+        child.set_parent(mc, Some(self));
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            children.insert(index.min(children.len()), child);
+        }
+    }
+
+    pub fn append_child(self, mc: MutationContext<'gc, '_>, child: E4XNode<'gc>) {
+        self.insert_child(mc, self.length(), child);
+    }
+
+    /// Removes a child or attribute of this element.
+    pub fn remove_child(self, mc: MutationContext<'gc, '_>, child: E4XNode<'gc>) {
+        if let E4XNodeKind::Element {
+            children,
+            attributes,
+            ..
+        } = &mut self.0.write(mc).kind
+        {
+            children.retain(|node| !E4XNode::ptr_eq(*node, child));
+            attributes.retain(|node| !E4XNode::ptr_eq(*node, child));
+        }
+        child.set_parent(mc, None);
+    }
+
+    /// Removes all children of this element.
+    pub fn remove_children(self, mc: MutationContext<'gc, '_>) {
+        for child in self.children() {
+            self.remove_child(mc, child);
+        }
+    }
+
+    /// Replaces the child at `index` with the given nodes.
+    pub fn replace_child(
+        self,
+        mc: MutationContext<'gc, '_>,
+        index: usize,
+        replacements: &[E4XNode<'gc>],
+    ) {
+        let children = self.children();
+        let old = match children.get(index) {
+            Some(old) => *old,
+            None => return,
+        };
+        for (offset, replacement) in replacements.iter().enumerate() {
+            self.insert_child(mc, index + offset, *replacement);
+        }
+        if !replacements
+            .iter()
+            .any(|replacement| E4XNode::ptr_eq(*replacement, old))
+        {
+            self.remove_child(mc, old);
+        }
+    }
+
+    /// Implements `XML.normalize`, which merges adjacent text nodes and removes empty ones in
+    /// this element and its descendants.
+    pub fn normalize(self, mc: MutationContext<'gc, '_>) {
+        let mut previous_text: Option<E4XNode<'gc>> = None;
+        for child in self.children() {
+            if child.is_element() {
+                child.normalize(mc);
+                previous_text = None;
+                continue;
+            }
+
+            let is_plain_text = matches!(child.0.read().kind, E4XNodeKind::Text(_));
+            if !is_plain_text {
+                previous_text = None;
+                continue;
+            }
+
+            let text = child.value().unwrap_or_default();
+            match previous_text {
+                Some(previous) => {
+                    let merged = AvmString::concat(mc, previous.value().unwrap_or_default(), text);
+                    previous.set_value(mc, merged);
+                    self.remove_child(mc, child);
+                }
+                None if text.is_empty() => self.remove_child(mc, child),
+                None => previous_text = Some(child),
+            }
+        }
+    }
+
+    /// Sets the value of the attribute with the given name, creating it if it doesn't exist.
+    ///
+    /// Any other attributes with the same name are removed.
+    pub fn set_attribute(
+        self,
+        mc: MutationContext<'gc, '_>,
+        name: &Multiname<'gc>,
+        value: AvmString<'gc>,
+    ) {
+        if !self.is_element() {
+            return;
+        }
+        let matching: Vec<_> = self
+            .attributes()
+            .into_iter()
+            .filter(|attribute| attribute.matches_name(name))
+            .collect();
+        match matching.split_first() {
+            Some((first, rest)) => {
+                first.set_value(mc, value);
+                for attribute in rest {
+                    self.remove_child(mc, *attribute);
+                }
+            }
+            None => {
+                if let Some(local_name) = name.local_name() {
+                    let attribute = E4XNode::attribute(
+                        mc,
+                        namespace_of_name(name),
+                        local_name,
+                        value,
+                        Some(self),
+                    );
+                    self.push_attribute(mc, attribute);
+                }
+            }
+        }
+    }
+
+    /// Returns whether this node is matched by a property name, like `xml.name` or `xml.@name`.
+    ///
+    /// A wildcard name (`*`) matches nodes of any kind, but otherwise only elements and
+    /// attributes match.
+    pub fn matches_name(self, name: &Multiname<'gc>) -> bool {
+        let local_name = match name.local_name() {
+            Some(local_name) => local_name,
+            None => return name.is_attribute() == self.is_attribute(),
+        };
+        if name.is_attribute() != self.is_attribute()
+            || (!self.is_attribute() && !self.is_element())
+            || self.local_name() != Some(local_name)
+        {
+            return false;
+        }
+
+        let uri = self.namespace_uri();
+        name.namespace_set()
+            .iter()
+            .any(|namespace| match namespace {
+                Namespace::Any => true,
+                Namespace::Namespace(namespace_uri) => *namespace_uri == uri,
+                _ => false,
+            })
+    }
+
+    /// Collects the children (or attributes, for attribute names) matching a property name.
+    pub fn get_matching(self, name: &Multiname<'gc>) -> Vec<E4XNode<'gc>> {
+        let nodes = if name.is_attribute() {
+            self.attributes()
+        } else {
+            self.children()
+        };
+        nodes
+            .into_iter()
+            .filter(|node| node.matches_name(name))
+            .collect()
+    }
+
+    /// Collects the descendants matching a property name, as used by the `..` operator.
+    pub fn descendants(self, name: &Multiname<'gc>, out: &mut Vec<E4XNode<'gc>>) {
+        if name.is_attribute() {
+            out.extend(
+                self.attributes()
+                    .into_iter()
+                    .filter(|attribute| attribute.matches_name(name)),
+            );
+        }
+        for child in self.children() {
+            if !name.is_attribute() && child.matches_name(name) {
+                out.push(child);
+            }
+            child.descendants(name, out);
+        }
+    }
+
+    /// Makes a copy of this node and all of its attributes and children, without a parent.
+    pub fn deep_copy(self, mc: MutationContext<'gc, '_>) -> Self {
+        self.deep_copy_with_parent(mc, None)
+    }
+
+    fn deep_copy_with_parent(
+        self,
+        mc: MutationContext<'gc, '_>,
+        parent: Option<E4XNode<'gc>>,
+    ) -> Self {
+        let read = self.0.read();
+        let kind = match &read.kind {
+            E4XNodeKind::Text(value) => E4XNodeKind::Text(*value),
+            E4XNodeKind::CData(value) => E4XNodeKind::CData(*value),
+            E4XNodeKind::Comment(value) => E4XNodeKind::Comment(*value),
+            E4XNodeKind::ProcessingInstruction(value) => E4XNodeKind::ProcessingInstruction(*value),
+            E4XNodeKind::Attribute(value) => E4XNodeKind::Attribute(*value),
+            E4XNodeKind::Element { namespaces, .. } => E4XNodeKind::Element {
+                attributes: Vec::new(),
+                children: Vec::new(),
+                namespaces: namespaces.clone(),
+            },
+        };
+        let copy = Self::new(mc, parent, read.namespace, read.local_name, kind);
+        drop(read);
+
+        for attribute in self.attributes() {
+            let attribute = attribute.deep_copy_with_parent(mc, Some(copy));
+            copy.push_attribute(mc, attribute);
+        }
+        for child in self.children() {
+            let child = child.deep_copy_with_parent(mc, Some(copy));
+            copy.push_child(mc, child);
+        }
+        copy
+    }
+
+    /// Implements `XML.hasSimpleContent`.
+    pub fn has_simple_content(self) -> bool {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { children, .. } => {
+                !children.iter().any(|child| child.is_element())
+            }
+            E4XNodeKind::Comment(_) | E4XNodeKind::ProcessingInstruction(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Implements `XML.hasComplexContent`.
+    pub fn has_complex_content(self) -> bool {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { children, .. } => {
+                children.iter().any(|child| child.is_element())
+            }
+            _ => false,
+        }
+    }
+
+    /// Compares two nodes by value, as used by the `==` operator.
+    pub fn equals(self, other: E4XNode<'gc>) -> bool {
+        if E4XNode::ptr_eq(self, other) {
+            return true;
+        }
+        if self.node_kind() != other.node_kind()
+            || self.local_name() != other.local_name()
+            || self.namespace_uri() != other.namespace_uri()
+            || self.value() != other.value()
+        {
+            return false;
+        }
+
+        let attributes = self.attributes();
+        let other_attributes = other.attributes();
+        if attributes.len() != other_attributes.len()
+            || !attributes.iter().all(|attribute| {
+                other_attributes
+                    .iter()
+                    .any(|other_attribute| attribute.equals(*other_attribute))
+            })
+        {
+            return false;
+        }
+
+        let children = self.children();
+        let other_children = other.children();
+        children.len() == other_children.len()
+            && children
+                .iter()
+                .zip(other_children.iter())
+                .all(|(child, other_child)| child.equals(*other_child))
+    }
+
+    /// Implements the `ToString` conversion of ECMA-357 (10.1.1).
+    pub fn xml_to_string(
+        self,
+        mc: MutationContext<'gc, '_>,
+        settings: &XmlSettings,
+    ) -> AvmString<'gc> {
+        match &self.0.read().kind {
+            E4XNodeKind::Text(value)
+            | E4XNodeKind::CData(value)
+            | E4XNodeKind::Attribute(value) => {
+                return *value;
+            }
+            E4XNodeKind::Element { children, .. } if self.has_simple_content() => {
+                let mut text = WString::new();
+                for child in children {
+                    if let E4XNodeKind::Text(value) | E4XNodeKind::CData(value) =
+                        &child.0.read().kind
+                    {
+                        text.push_str(value);
+                    }
+                }
+                return AvmString::new(mc, text);
+            }
+            _ => {}
+        }
+        self.xml_to_xml_string(mc, settings)
+    }
+
+    /// Implements the `ToXMLString` conversion of ECMA-357 (10.2.1).
+    pub fn xml_to_xml_string(
+        self,
+        mc: MutationContext<'gc, '_>,
+        settings: &XmlSettings,
+    ) -> AvmString<'gc> {
+        let mut out = WString::new();
+        self.write_xml_string(&mut out, settings, 0, &mut Vec::new());
+        AvmString::new(mc, out)
+    }
+
+    fn write_xml_string(
+        self,
+        out: &mut WString,
+        settings: &XmlSettings,
+        indent: usize,
+        declared: &mut Vec<E4XNamespace<'gc>>,
+    ) {
+        if settings.pretty_printing {
+            for _ in 0..indent {
+                out.push_byte(b' ');
+            }
+        }
+
+        let read = self.0.read();
+        let (attributes, children, namespaces) = match &read.kind {
+            E4XNodeKind::Text(value) => {
+                if settings.pretty_printing {
+                    out.push_str(&escape_element_value(value.trim_matches(is_xml_whitespace)));
+                } else {
+                    out.push_str(&escape_element_value(value));
+                }
+                return;
+            }
+            E4XNodeKind::CData(value) => {
+                out.push_str(WStr::from_units(b"<![CDATA["));
+                out.push_str(value);
+                out.push_str(WStr::from_units(b"]]>"));
+                return;
+            }
+            E4XNodeKind::Attribute(value) => {
+                out.push_str(&escape_attribute_value(value));
+                return;
+            }
+            E4XNodeKind::Comment(value) => {
+                out.push_str(WStr::from_units(b"<!--"));
+                out.push_str(value);
+                out.push_str(WStr::from_units(b"-->"));
+                return;
+            }
+            E4XNodeKind::ProcessingInstruction(value) => {
+                out.push_str(WStr::from_units(b"<?"));
+                out.push_str(&read.local_name.unwrap_or_default());
+                if !value.is_empty() {
+                    out.push_byte(b' ');
+                    out.push_str(value);
+                }
+                out.push_str(WStr::from_units(b"?>"));
+                return;
+            }
+            E4XNodeKind::Element {
+                attributes,
+                children,
+                namespaces,
+            } => (attributes, children, namespaces),
+        };
+
+        let declared_len = declared.len();
+
+        // Declare the namespaces of this element, and any namespace used by its name or
+        // attributes that isn't declared by an ancestor.
+        let mut declarations: Vec<E4XNamespace<'gc>> = Vec::new();
+        let used = read.namespace.into_iter().chain(
+            attributes
+                .iter()
+                .filter_map(|attribute| attribute.namespace()),
+        );
+        for namespace in namespaces.iter().copied().chain(used) {
+            let is_declared = declared.iter().rev().any(|declared| {
+                declared.output_prefix() == namespace.output_prefix()
+                    && declared.uri == namespace.uri
+            }) || declarations
+                .iter()
+                .any(|declared| declared.output_prefix() == namespace.output_prefix());
+            if !is_declared {
+                declarations.push(namespace);
+            }
+        }
+        declared.extend(declarations.iter().copied());
+
+        out.push_byte(b'<');
+        write_qualified_name(out, read.namespace, read.local_name);
+        for namespace in &declarations {
+            out.push_str(WStr::from_units(b" xmlns"));
+            let prefix = namespace.output_prefix();
+            if !prefix.is_empty() {
+                out.push_byte(b':');
+                out.push_str(&prefix);
+            }
+            out.push_str(WStr::from_units(b"=\""));
+            out.push_str(&escape_attribute_value(&namespace.uri));
+            out.push_byte(b'"');
+        }
+        for attribute in attributes {
+            out.push_byte(b' ');
+            write_qualified_name(out, attribute.namespace(), attribute.local_name());
+            out.push_str(WStr::from_units(b"=\""));
+            out.push_str(&escape_attribute_value(
+                &attribute.value().unwrap_or_default(),
+            ));
+            out.push_byte(b'"');
+        }
+
+        if children.is_empty() {
+            out.push_str(WStr::from_units(b"/>"));
+            declared.truncate(declared_len);
+            return;
+        }
+        out.push_byte(b'>');
+
+        let indent_children = children.len() > 1 || (children.len() == 1 && !children[0].is_text());
+        let pretty = settings.pretty_printing && indent_children;
+        let child_indent = if pretty {
+            indent + settings.pretty_indent
+        } else {
+            0
+        };
+        for child in children {
+            if pretty {
+                out.push_byte(b'\n');
+            }
+            child.write_xml_string(out, settings, child_indent, declared);
+        }
+        if pretty {
+            out.push_byte(b'\n');
+            for _ in 0..indent {
+                out.push_byte(b' ');
+            }
+        }
+
+        out.push_str(WStr::from_units(b"</"));
+        write_qualified_name(out, read.namespace, read.local_name);
+        out.push_byte(b'>');
+
+        declared.truncate(declared_len);
+    }
+}
+
+/// Writes the name of an element or attribute, with the prefix of its namespace.
+fn write_qualified_name<'gc>(
+    out: &mut WString,
+    namespace: Option<E4XNamespace<'gc>>,
+    local_name: Option<AvmString<'gc>>,
+) {
+    if let Some(prefix) = namespace.and_then(|namespace| namespace.prefix) {
+        if !prefix.is_empty() {
+            out.push_str(&prefix);
+            out.push_byte(b':');
+        }
+    }
+    out.push_str(&local_name.unwrap_or_default());
+}
+
+/// Turns a property name into the name of the nodes it refers to.
+///
+/// Names given as strings are written like in markup, so `xml["@id"]` refers to the attribute
+/// `id`, and `xml["*"]` to children of any name.
+pub fn node_name<'gc>(mc: MutationContext<'gc, '_>, name: &Multiname<'gc>) -> Multiname<'gc> {
+    let mut node_name = name.clone();
+    if let Some(local_name) = name.local_name() {
+        let mut local = &local_name[..];
+        if !name.is_attribute() {
+            if let Some(attribute_name) = local.strip_prefix(b'@') {
+                node_name.set_is_attribute(true);
+                local = attribute_name;
+            }
+        }
+
+        if local == WStr::from_units(b"*") {
+            node_name = node_name.with_local_name(None);
+        } else if local.len() != local_name.len() {
+            node_name = node_name.with_local_name(Some(AvmString::new(mc, local)));
+        }
+    }
+    node_name
+}
+
+/// Returns the nodes of an XML or XMLList value, or `None` for other values.
+pub fn xml_nodes<'gc>(value: Value<'gc>) -> Option<Vec<E4XNode<'gc>>> {
+    let object = value.as_object()?;
+    if let Some(xml) = object.as_xml_object() {
+        Some(vec![xml.node()])
+    } else {
+        object.as_xml_list_object().map(|list| list.children())
+    }
+}
+
+/// Returns the namespace a node created for a property name should be in.
+///
+/// Names written in code are looked up in many namespaces at once, so only a name with a single,
+/// explicit namespace, like `ns::name`, puts the new node in a namespace.
+pub fn namespace_of_name<'gc>(name: &Multiname<'gc>) -> Option<E4XNamespace<'gc>> {
+    match name.namespace_set() {
+        [Namespace::Namespace(uri)] if !uri.is_empty() => Some(E4XNamespace::new(None, *uri)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gc_arena::rootless_arena;
+
+    fn name<'gc>(mc: MutationContext<'gc, '_>, s: &'static str) -> AvmString<'gc> {
+        AvmString::new_utf8(mc, s)
+    }
+
+    #[test]
+    fn pretty_printing() {
+        rootless_arena(|mc| {
+            let root = E4XNode::element(mc, None, name(mc, "root"), None);
+            let item = E4XNode::element(mc, None, name(mc, "item"), None);
+            item.set_attribute(mc, &Multiname::public("id"), name(mc, "a&\"b\""));
+            item.append_child(mc, E4XNode::text(mc, name(mc, "1 < 2"), None));
+            root.append_child(mc, item);
+            root.append_child(mc, E4XNode::element(mc, None, name(mc, "empty"), None));
+
+            let settings = XmlSettings::default();
+            assert_eq!(
+                root.xml_to_xml_string(mc, &settings).to_string(),
+                "<root>\n  <item id=\"a&amp;&quot;b&quot;\">1 &lt; 2</item>\n  <empty/>\n</root>"
+            );
+            assert_eq!(item.xml_to_string(mc, &settings).to_string(), "1 < 2");
+
+            let settings = XmlSettings {
+                pretty_printing: false,
+                ..Default::default()
+            };
+            assert_eq!(
+                root.xml_to_xml_string(mc, &settings).to_string(),
+                "<root><item id=\"a&amp;&quot;b&quot;\">1 &lt; 2</item><empty/></root>"
+            );
+        })
+    }
+
+    #[test]
+    fn tree_mutation() {
+        rootless_arena(|mc| {
+            let root = E4XNode::element(mc, None, name(mc, "root"), None);
+            let a = E4XNode::element(mc, None, name(mc, "a"), None);
+            let b = E4XNode::element(mc, None, name(mc, "b"), None);
+            root.append_child(mc, a);
+            root.append_child(mc, b);
+
+            // Moving a child within its parent keeps a single copy of it.
+            root.insert_child(mc, 0, b);
+            assert_eq!(root.length(), 2);
+            assert_eq!(b.child_index(), Some(0));
+            assert_eq!(a.child_index(), Some(1));
+
+            let copy = root.deep_copy(mc);
+            assert!(copy.equals(root));
+            assert!(!E4XNode::ptr_eq(copy.children()[0], b));
+
+            root.remove_child(mc, a);
+            assert_eq!(root.length(), 1);
+            assert!(a.parent().is_none());
+            assert!(!copy.equals(root));
+
+            assert_eq!(root.get_matching(&Multiname::public("b")).len(), 1);
+            assert_eq!(root.get_matching(&Multiname::public("a")).len(), 0);
+        })
+    }
+}
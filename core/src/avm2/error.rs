@@ -31,6 +31,17 @@ static_assertions::assert_eq_size!(Result<Value<'_>, Error<'_>>, [u8; 24]);
 #[cfg(target_pointer_width = "64")]
 static_assertions::assert_eq_size!(Result<Value<'_>, Error<'_>>, [u8; 32]);
 
+#[inline(never)]
+#[cold]
+pub fn error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    message: &str,
+    code: u32,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let class = activation.avm2().classes().error;
+    error_constructor(activation, class, message, code)
+}
+
 #[inline(never)]
 #[cold]
 pub fn range_error<'gc>(
@@ -53,6 +64,17 @@ pub fn argument_error<'gc>(
     error_constructor(activation, class, message, code)
 }
 
+#[inline(never)]
+#[cold]
+pub fn syntax_error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    message: &str,
+    code: u32,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let class = activation.avm2().classes().syntaxerror;
+    error_constructor(activation, class, message, code)
+}
+
 #[inline(never)]
 #[cold]
 pub fn type_error<'gc>(
@@ -86,6 +108,17 @@ pub fn verify_error<'gc>(
     error_constructor(activation, class, message, code)
 }
 
+#[inline(never)]
+#[cold]
+pub fn security_error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    message: &str,
+    code: u32,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let class = activation.avm2().classes().securityerror;
+    error_constructor(activation, class, message, code)
+}
+
 #[inline(never)]
 #[cold]
 pub fn io_error<'gc>(
@@ -97,6 +130,17 @@ pub fn io_error<'gc>(
     error_constructor(activation, class, message, code)
 }
 
+#[inline(never)]
+#[cold]
+pub fn illegal_operation_error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    message: &str,
+    code: u32,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let class = activation.avm2().classes().illegaloperationerror;
+    error_constructor(activation, class, message, code)
+}
+
 #[inline(never)]
 #[cold]
 pub fn eof_error<'gc>(
@@ -277,6 +277,16 @@ impl<'gc> DispatchList<'gc> {
         false
     }
 
+    /// Yield every event handler on this dispatch list, with the event type it
+    /// handles.
+    pub fn iter_all_handlers(&self) -> impl '_ + Iterator<Item = (AvmString<'gc>, Object<'gc>)> {
+        self.0.iter().flat_map(|(event, sheaf)| {
+            sheaf
+                .values()
+                .flat_map(move |set| set.iter().map(move |eh| (*event, eh.handler)))
+        })
+    }
+
     /// Yield the event handlers on this dispatch list for a given event.
     ///
     /// Event handlers will be yielded in the order they are intended to be
@@ -91,6 +91,14 @@ impl<'gc> Executable<'gc> {
         }
     }
 
+    /// The scope this method was defined in.
+    pub fn scope(&self) -> ScopeChain<'gc> {
+        match self {
+            Executable::Native(bm) => bm.scope,
+            Executable::Action(bm) => bm.scope,
+        }
+    }
+
     /// Execute a method.
     ///
     /// The function will either be called directly if it is a Rust builtin, or
@@ -168,7 +176,13 @@ impl<'gc> Executable<'gc> {
                     .context
                     .avm2
                     .push_call(activation.context.gc_context, self.clone());
-                activation.run_actions(bm.method)
+                let default_xml_namespace = activation.context.avm2.default_xml_namespace();
+                let ret = activation.run_actions(bm.method);
+                activation
+                    .context
+                    .avm2
+                    .set_default_xml_namespace(default_xml_namespace);
+                ret
             }
         };
         activation
@@ -72,6 +72,7 @@ pub struct SystemClasses<'gc> {
     pub shape: ClassObject<'gc>,
     pub textfield: ClassObject<'gc>,
     pub textformat: ClassObject<'gc>,
+    pub font: ClassObject<'gc>,
     pub graphics: ClassObject<'gc>,
     pub loaderinfo: ClassObject<'gc>,
     pub bytearray: ClassObject<'gc>,
@@ -89,8 +90,11 @@ pub struct SystemClasses<'gc> {
     pub mouseevent: ClassObject<'gc>,
     pub progressevent: ClassObject<'gc>,
     pub textevent: ClassObject<'gc>,
+    pub contextmenuevent: ClassObject<'gc>,
+    pub touchevent: ClassObject<'gc>,
     pub errorevent: ClassObject<'gc>,
     pub ioerrorevent: ClassObject<'gc>,
+    pub netstatusevent: ClassObject<'gc>,
     pub securityerrorevent: ClassObject<'gc>,
     pub transform: ClassObject<'gc>,
     pub colortransform: ClassObject<'gc>,
@@ -100,21 +104,41 @@ pub struct SystemClasses<'gc> {
     pub rectangle: ClassObject<'gc>,
     pub keyboardevent: ClassObject<'gc>,
     pub point: ClassObject<'gc>,
+    pub error: ClassObject<'gc>,
     pub rangeerror: ClassObject<'gc>,
     pub referenceerror: ClassObject<'gc>,
     pub argumenterror: ClassObject<'gc>,
     pub typeerror: ClassObject<'gc>,
+    pub syntaxerror: ClassObject<'gc>,
     pub verifyerror: ClassObject<'gc>,
+    pub securityerror: ClassObject<'gc>,
     pub ioerror: ClassObject<'gc>,
     pub eoferror: ClassObject<'gc>,
     pub uncaughterrorevents: ClassObject<'gc>,
     pub statictext: ClassObject<'gc>,
     pub textlinemetrics: ClassObject<'gc>,
+    pub textsnapshot: ClassObject<'gc>,
+    pub textline: ClassObject<'gc>,
     pub stage3d: ClassObject<'gc>,
+    pub stagevideo: ClassObject<'gc>,
+    pub id3info: ClassObject<'gc>,
+    pub stagevideoavailabilityevent: ClassObject<'gc>,
+    pub stagevideoevent: ClassObject<'gc>,
+    pub sampledataevent: ClassObject<'gc>,
+    pub statusevent: ClassObject<'gc>,
+    pub activityevent: ClassObject<'gc>,
+    pub microphone: ClassObject<'gc>,
+    pub camera: ClassObject<'gc>,
     pub context3d: ClassObject<'gc>,
     pub indexbuffer3d: ClassObject<'gc>,
     pub vertexbuffer3d: ClassObject<'gc>,
     pub program3d: ClassObject<'gc>,
+    pub filereference: ClassObject<'gc>,
+    pub socket: ClassObject<'gc>,
+    pub worker: ClassObject<'gc>,
+    pub messagechannel: ClassObject<'gc>,
+    pub dictionary: ClassObject<'gc>,
+    pub iexternalizable: ClassObject<'gc>,
 }
 
 impl<'gc> SystemClasses<'gc> {
@@ -157,6 +181,7 @@ impl<'gc> SystemClasses<'gc> {
             shape: object,
             textfield: object,
             textformat: object,
+            font: object,
             graphics: object,
             loaderinfo: object,
             bytearray: object,
@@ -174,8 +199,11 @@ impl<'gc> SystemClasses<'gc> {
             mouseevent: object,
             progressevent: object,
             textevent: object,
+            contextmenuevent: object,
+            touchevent: object,
             errorevent: object,
             ioerrorevent: object,
+            netstatusevent: object,
             securityerrorevent: object,
             transform: object,
             colortransform: object,
@@ -185,21 +213,41 @@ impl<'gc> SystemClasses<'gc> {
             rectangle: object,
             keyboardevent: object,
             point: object,
+            error: object,
             rangeerror: object,
             referenceerror: object,
             argumenterror: object,
             typeerror: object,
+            syntaxerror: object,
             verifyerror: object,
+            securityerror: object,
             ioerror: object,
             eoferror: object,
             uncaughterrorevents: object,
             statictext: object,
             textlinemetrics: object,
+            textsnapshot: object,
+            textline: object,
             stage3d: object,
+            stagevideo: object,
+            id3info: object,
+            stagevideoavailabilityevent: object,
+            stagevideoevent: object,
+            sampledataevent: object,
+            statusevent: object,
+            activityevent: object,
+            microphone: object,
+            camera: object,
             context3d: object,
             indexbuffer3d: object,
             vertexbuffer3d: object,
             program3d: object,
+            filereference: object,
+            socket: object,
+            worker: object,
+            messagechannel: object,
+            dictionary: object,
+            iexternalizable: object,
         }
     }
 }
@@ -569,7 +617,12 @@ pub fn load_player_globals<'gc>(
         flash::text::textformat::create_class(mc),
         script
     );
-    class(activation, flash::text::font::create_class(mc), script)?;
+    avm2_system_class!(
+        font,
+        activation,
+        flash::text::font::create_class(mc),
+        script
+    );
 
     // Inside this call, the macro `avm2_system_classes_playerglobal`
     // triggers classloading. Therefore, we run `load_playerglobal`
@@ -597,6 +650,7 @@ fn load_playerglobal<'gc>(
     activation.avm2().native_method_table = native::NATIVE_METHOD_TABLE;
     activation.avm2().native_instance_allocator_table = native::NATIVE_INSTANCE_ALLOCATOR_TABLE;
     activation.avm2().native_instance_init_table = native::NATIVE_INSTANCE_INIT_TABLE;
+    activation.avm2().native_call_handler_table = native::NATIVE_CALL_HANDLER_TABLE;
 
     let movie =
         SwfMovie::from_data(PLAYERGLOBAL, None, None).expect("playerglobal.swf should be valid");
@@ -638,8 +692,11 @@ fn load_playerglobal<'gc>(
         script,
         [
             ("", "ArgumentError", argumenterror),
+            ("", "Error", error),
             ("", "RangeError", rangeerror),
             ("", "ReferenceError", referenceerror),
+            ("", "SecurityError", securityerror),
+            ("", "SyntaxError", syntaxerror),
             ("", "TypeError", typeerror),
             ("", "VerifyError", verifyerror),
             ("", "XML", xml),
@@ -660,22 +717,46 @@ fn load_playerglobal<'gc>(
             ("flash.errors", "EOFError", eoferror),
             ("flash.events", "Event", event),
             ("flash.events", "TextEvent", textevent),
+            ("flash.events", "ContextMenuEvent", contextmenuevent),
             ("flash.events", "ErrorEvent", errorevent),
             ("flash.events", "KeyboardEvent", keyboardevent),
             ("flash.events", "ProgressEvent", progressevent),
             ("flash.events", "SecurityErrorEvent", securityerrorevent),
             ("flash.events", "IOErrorEvent", ioerrorevent),
+            ("flash.events", "NetStatusEvent", netstatusevent),
             ("flash.events", "MouseEvent", mouseevent),
+            ("flash.events", "TouchEvent", touchevent),
             ("flash.events", "FullScreenEvent", fullscreenevent),
             ("flash.events", "UncaughtErrorEvents", uncaughterrorevents),
+            (
+                "flash.events",
+                "StageVideoAvailabilityEvent",
+                stagevideoavailabilityevent
+            ),
+            ("flash.events", "StageVideoEvent", stagevideoevent),
+            ("flash.events", "SampleDataEvent", sampledataevent),
+            ("flash.events", "StatusEvent", statusevent),
+            ("flash.events", "ActivityEvent", activityevent),
             ("flash.geom", "Matrix", matrix),
             ("flash.geom", "Point", point),
             ("flash.geom", "Rectangle", rectangle),
             ("flash.geom", "Transform", transform),
             ("flash.geom", "ColorTransform", colortransform),
+            ("flash.media", "StageVideo", stagevideo),
+            ("flash.media", "ID3Info", id3info),
+            ("flash.media", "Microphone", microphone),
+            ("flash.media", "Camera", camera),
+            ("flash.net", "FileReference", filereference),
+            ("flash.net", "Socket", socket),
+            ("flash.system", "Worker", worker),
+            ("flash.system", "MessageChannel", messagechannel),
             ("flash.utils", "ByteArray", bytearray),
+            ("flash.utils", "Dictionary", dictionary),
+            ("flash.utils", "IExternalizable", iexternalizable),
             ("flash.text", "StaticText", statictext),
             ("flash.text", "TextLineMetrics", textlinemetrics),
+            ("flash.text", "TextSnapshot", textsnapshot),
+            ("flash.text.engine", "TextLine", textline),
         ]
     );
 
@@ -1,4 +1,107 @@
 package {
     [Ruffle(InstanceAllocator)]
-    public final dynamic class XML {}
-}
\ No newline at end of file
+    [Ruffle(CallHandler)]
+    public final dynamic class XML {
+        public static var ignoreComments:Boolean = true;
+        public static var ignoreProcessingInstructions:Boolean = true;
+        public static var ignoreWhitespace:Boolean = true;
+        public static var prettyPrinting:Boolean = true;
+        public static var prettyIndent:int = 2;
+
+        public static function settings():Object {
+            return {
+                ignoreComments: XML.ignoreComments,
+                ignoreProcessingInstructions: XML.ignoreProcessingInstructions,
+                ignoreWhitespace: XML.ignoreWhitespace,
+                prettyPrinting: XML.prettyPrinting,
+                prettyIndent: XML.prettyIndent
+            };
+        }
+
+        public static function setSettings(settings:Object = null):void {
+            if (settings == null) {
+                settings = XML.defaultSettings();
+            }
+
+            if (settings.hasOwnProperty("ignoreComments")) {
+                XML.ignoreComments = settings.ignoreComments;
+            }
+            if (settings.hasOwnProperty("ignoreProcessingInstructions")) {
+                XML.ignoreProcessingInstructions = settings.ignoreProcessingInstructions;
+            }
+            if (settings.hasOwnProperty("ignoreWhitespace")) {
+                XML.ignoreWhitespace = settings.ignoreWhitespace;
+            }
+            if (settings.hasOwnProperty("prettyPrinting")) {
+                XML.prettyPrinting = settings.prettyPrinting;
+            }
+            if (settings.hasOwnProperty("prettyIndent")) {
+                XML.prettyIndent = settings.prettyIndent;
+            }
+        }
+
+        public static function defaultSettings():Object {
+            return {
+                ignoreComments: true,
+                ignoreProcessingInstructions: true,
+                ignoreWhitespace: true,
+                prettyPrinting: true,
+                prettyIndent: 2
+            };
+        }
+
+        public function XML(value:* = undefined) {
+            this.init(value);
+        }
+
+        private native function init(value:*):void;
+
+        AS3 native function addNamespace(ns:*):XML;
+        AS3 native function appendChild(child:*):XML;
+        AS3 native function attribute(attributeName:*):XMLList;
+        AS3 native function attributes():XMLList;
+        AS3 native function child(propertyName:*):XMLList;
+        AS3 native function childIndex():int;
+        AS3 native function children():XMLList;
+        AS3 native function comments():XMLList;
+        AS3 native function contains(value:*):Boolean;
+        AS3 native function copy():XML;
+        AS3 native function descendants(name:* = "*"):XMLList;
+        AS3 native function elements(name:* = "*"):XMLList;
+        AS3 native function hasComplexContent():Boolean;
+        AS3 native function hasSimpleContent():Boolean;
+        AS3 native function inScopeNamespaces():Array;
+        AS3 native function insertChildAfter(child1:*, child2:*):*;
+        AS3 native function insertChildBefore(child1:*, child2:*):*;
+        AS3 native function localName():Object;
+        AS3 native function name():Object;
+        AS3 native function namespace(prefix:* = null):*;
+        AS3 native function namespaceDeclarations():Array;
+        AS3 native function nodeKind():String;
+        AS3 native function normalize():XML;
+        AS3 native function parent():*;
+        AS3 native function prependChild(value:*):XML;
+        AS3 native function processingInstructions(name:* = "*"):XMLList;
+        AS3 native function removeNamespace(ns:*):XML;
+        AS3 native function replace(propertyName:*, value:*):XML;
+        AS3 native function setChildren(value:*):XML;
+        AS3 native function setLocalName(name:*):void;
+        AS3 native function setName(name:*):void;
+        AS3 native function setNamespace(ns:*):void;
+        AS3 native function text():XMLList;
+        AS3 native function toString():String;
+        AS3 native function toXMLString():String;
+
+        AS3 function length():int {
+            return 1;
+        }
+
+        AS3 function valueOf():XML {
+            return this;
+        }
+
+        AS3 function toJSON(k:String):* {
+            return "XML";
+        }
+    }
+}
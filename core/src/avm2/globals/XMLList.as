@@ -1,4 +1,38 @@
 package {
     [Ruffle(InstanceAllocator)]
-    public final dynamic class XMLList {}
-}
\ No newline at end of file
+    [Ruffle(CallHandler)]
+    public final dynamic class XMLList {
+        public function XMLList(value:* = undefined) {
+            this.init(value);
+        }
+
+        private native function init(value:*):void;
+
+        AS3 native function attribute(attributeName:*):XMLList;
+        AS3 native function attributes():XMLList;
+        AS3 native function child(propertyName:*):XMLList;
+        AS3 native function children():XMLList;
+        AS3 native function comments():XMLList;
+        AS3 native function contains(value:*):Boolean;
+        AS3 native function copy():XMLList;
+        AS3 native function descendants(name:* = "*"):XMLList;
+        AS3 native function elements(name:* = "*"):XMLList;
+        AS3 native function hasComplexContent():Boolean;
+        AS3 native function hasSimpleContent():Boolean;
+        AS3 native function length():int;
+        AS3 native function normalize():XMLList;
+        AS3 native function parent():*;
+        AS3 native function processingInstructions(name:* = "*"):XMLList;
+        AS3 native function text():XMLList;
+        AS3 native function toString():String;
+        AS3 native function toXMLString():String;
+
+        AS3 function valueOf():XMLList {
+            return this;
+        }
+
+        AS3 function toJSON(k:String):* {
+            return "XMLList";
+        }
+    }
+}
@@ -9,7 +9,7 @@ use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
-use crate::locale::{get_current_date_time, get_timezone};
+use crate::locale::get_timezone;
 use crate::string::{utils as string_utils, AvmString, WStr};
 use chrono::{DateTime, Datelike, Duration, FixedOffset, LocalResult, TimeZone, Timelike, Utc};
 use gc_arena::{GcCell, MutationContext};
@@ -281,7 +281,10 @@ pub fn instance_init<'gc>(
                     }
                 }
             } else {
-                date.set_date_time(activation.context.gc_context, Some(get_current_date_time()))
+                date.set_date_time(
+                    activation.context.gc_context,
+                    Some(activation.context.clock.now()),
+                )
             }
         }
     }
@@ -0,0 +1,62 @@
+package fl.text {
+    import flash.display.DisplayObject;
+    import flash.display.DisplayObjectContainer;
+    import flash.geom.Rectangle;
+
+    // Flash Professional adds the TLF text of a timeline through this class, with the bounds
+    // and markup of each field.
+    public class RuntimeManager {
+        private static var _singleton: RuntimeManager = null;
+
+        public static function getSingleton(): RuntimeManager {
+            if (_singleton == null) {
+                _singleton = new RuntimeManager();
+            }
+            return _singleton;
+        }
+
+        // Creates the field named `instanceName` in `container`, in place of its placeholder.
+        public function addInstance(container: DisplayObjectContainer, instanceName: String, bounds: Rectangle, tlfMarkup: *, ...rest): TLFTextField {
+            var field: TLFTextField = new TLFTextField();
+            if (instanceName != null) {
+                field.name = instanceName;
+            }
+            if (bounds != null) {
+                field.x = bounds.x;
+                field.y = bounds.y;
+                field.setSize(bounds.width, bounds.height);
+            }
+            if (tlfMarkup != null) {
+                field.applyMarkup(tlfMarkup is XML ? tlfMarkup : new XML(tlfMarkup));
+            }
+
+            var index: int = container.numChildren;
+            if (instanceName != null) {
+                var placeholder: DisplayObject = container.getChildByName(instanceName);
+                if (placeholder != null && placeholder !== field) {
+                    field.x += placeholder.x;
+                    field.y += placeholder.y;
+                    index = container.getChildIndex(placeholder);
+                    container.removeChild(placeholder);
+                }
+            }
+            container.addChildAt(field, index);
+
+            if (instanceName != null) {
+                try {
+                    container[instanceName] = field;
+                } catch (e: Error) {
+                    // The container has no property for the field.
+                }
+            }
+            return field;
+        }
+
+        // The fields are laid out as they're added.
+        public function initInstances(...rest): void {
+        }
+
+        public function removeInstances(...rest): void {
+        }
+    }
+}
@@ -0,0 +1,737 @@
+package fl.text {
+    import flash.display.Sprite;
+    import flash.geom.Rectangle;
+    import flash.text.TextField;
+    import flash.text.TextFieldAutoSize;
+    import flash.text.TextFieldType;
+    import flash.text.TextFormat;
+    import flash.text.TextLineMetrics;
+
+    // Movies made with Flash Professional CS5 and later create TLF text as `TLFTextField`s,
+    // which need the Text Layout Framework library that Adobe served as a signed runtime
+    // shared library. This stands in for them with a `TextField` that shows the text of the
+    // TLF markup with its basic formatting, editable where the markup allows it.
+    public class TLFTextField extends Sprite {
+        use namespace AS3;
+
+        private var _field: TextField;
+        private var _width: Number = 100;
+        private var _height: Number = 100;
+        private var _tlfMarkup: String = "";
+        private var _paddingLeft: Number = 2;
+        private var _paddingRight: Number = 2;
+        private var _paddingTop: Number = 2;
+        private var _paddingBottom: Number = 2;
+        private var _verticalAlign: String = "top";
+        private var _direction: String = "ltr";
+        private var _blockProgression: String = "tb";
+        private var _columnCount: Object = "auto";
+        private var _columnGap: Object = 20;
+        private var _columnWidth: Object = "auto";
+        private var _firstBaselineOffset: Object = "auto";
+        private var _lineBreak: String = "toFit";
+        private var _backgroundAlpha: Number = 1;
+        private var _borderAlpha: Number = 1;
+        private var _borderWidth: Number = 1;
+        private var _pointScrollH: int = 0;
+        private var _pointScrollV: int = 0;
+        private var _useRichTextClipboard: Boolean = false;
+
+        public function TLFTextField() {
+            this._field = new TextField();
+            this._field.multiline = true;
+            this._field.wordWrap = true;
+            this.addChild(this._field);
+            this.layout();
+        }
+
+        public static function isFontCompatible(fontName: String, fontStyle: String): Boolean {
+            return false;
+        }
+
+        // The `TextField` properties, which are passed on to the field.
+
+        public function get alwaysShowSelection(): Boolean {
+            return this._field.alwaysShowSelection;
+        }
+
+        public function set alwaysShowSelection(value: Boolean): void {
+            this._field.alwaysShowSelection = value;
+        }
+
+        public function get antiAliasType(): String {
+            return this._field.antiAliasType;
+        }
+
+        public function set antiAliasType(value: String): void {
+            this._field.antiAliasType = value;
+        }
+
+        public function get autoSize(): String {
+            return this._field.autoSize;
+        }
+
+        public function set autoSize(value: String): void {
+            this._field.autoSize = value;
+            this.layout();
+        }
+
+        public function get background(): Boolean {
+            return this._field.background;
+        }
+
+        public function set background(value: Boolean): void {
+            this._field.background = value;
+        }
+
+        public function get backgroundColor(): uint {
+            return this._field.backgroundColor;
+        }
+
+        public function set backgroundColor(value: uint): void {
+            this._field.backgroundColor = value;
+        }
+
+        public function get border(): Boolean {
+            return this._field.border;
+        }
+
+        public function set border(value: Boolean): void {
+            this._field.border = value;
+        }
+
+        public function get borderColor(): uint {
+            return this._field.borderColor;
+        }
+
+        public function set borderColor(value: uint): void {
+            this._field.borderColor = value;
+        }
+
+        public function get bottomScrollV(): int {
+            return this._field.bottomScrollV;
+        }
+
+        public function get caretIndex(): int {
+            return this._field.caretIndex;
+        }
+
+        public function get condenseWhite(): Boolean {
+            return this._field.condenseWhite;
+        }
+
+        public function set condenseWhite(value: Boolean): void {
+            this._field.condenseWhite = value;
+        }
+
+        public function get defaultTextFormat(): TextFormat {
+            return this._field.defaultTextFormat;
+        }
+
+        public function set defaultTextFormat(value: TextFormat): void {
+            this._field.defaultTextFormat = value;
+        }
+
+        public function get displayAsPassword(): Boolean {
+            return this._field.displayAsPassword;
+        }
+
+        public function set displayAsPassword(value: Boolean): void {
+            this._field.displayAsPassword = value;
+        }
+
+        public function get embedFonts(): Boolean {
+            return this._field.embedFonts;
+        }
+
+        public function set embedFonts(value: Boolean): void {
+            this._field.embedFonts = value;
+        }
+
+        public function get gridFitType(): String {
+            return this._field.gridFitType;
+        }
+
+        public function set gridFitType(value: String): void {
+            this._field.gridFitType = value;
+        }
+
+        public function get htmlText(): String {
+            return this._field.htmlText;
+        }
+
+        public function set htmlText(value: String): void {
+            this._field.htmlText = value;
+            this.layout();
+        }
+
+        public function get length(): int {
+            return this._field.length;
+        }
+
+        public function get maxChars(): int {
+            return this._field.maxChars;
+        }
+
+        public function set maxChars(value: int): void {
+            this._field.maxChars = value;
+        }
+
+        public function get maxScrollH(): int {
+            return this._field.maxScrollH;
+        }
+
+        public function get maxScrollV(): int {
+            return this._field.maxScrollV;
+        }
+
+        public function get mouseWheelEnabled(): Boolean {
+            return this._field.mouseWheelEnabled;
+        }
+
+        public function set mouseWheelEnabled(value: Boolean): void {
+            this._field.mouseWheelEnabled = value;
+        }
+
+        public function get multiline(): Boolean {
+            return this._field.multiline;
+        }
+
+        public function set multiline(value: Boolean): void {
+            this._field.multiline = value;
+        }
+
+        public function get numLines(): int {
+            return this._field.numLines;
+        }
+
+        public function get restrict(): String {
+            return this._field.restrict;
+        }
+
+        public function set restrict(value: String): void {
+            this._field.restrict = value;
+        }
+
+        public function get scrollH(): int {
+            return this._field.scrollH;
+        }
+
+        public function set scrollH(value: int): void {
+            this._field.scrollH = value;
+        }
+
+        public function get scrollV(): int {
+            return this._field.scrollV;
+        }
+
+        public function set scrollV(value: int): void {
+            this._field.scrollV = value;
+        }
+
+        public function get selectable(): Boolean {
+            return this._field.selectable;
+        }
+
+        public function set selectable(value: Boolean): void {
+            this._field.selectable = value;
+        }
+
+        public function get selectionBeginIndex(): int {
+            return this._field.selectionBeginIndex;
+        }
+
+        public function get selectionEndIndex(): int {
+            return this._field.selectionEndIndex;
+        }
+
+        public function get sharpness(): Number {
+            return this._field.sharpness;
+        }
+
+        public function set sharpness(value: Number): void {
+            this._field.sharpness = value;
+        }
+
+        public function get text(): String {
+            return this._field.text;
+        }
+
+        public function set text(value: String): void {
+            this._field.text = value;
+            this.layout();
+        }
+
+        public function get textColor(): uint {
+            return this._field.textColor;
+        }
+
+        public function set textColor(value: uint): void {
+            this._field.textColor = value;
+        }
+
+        public function get textHeight(): Number {
+            return this._field.textHeight;
+        }
+
+        public function get textWidth(): Number {
+            return this._field.textWidth;
+        }
+
+        public function get thickness(): Number {
+            return this._field.thickness;
+        }
+
+        public function set thickness(value: Number): void {
+            this._field.thickness = value;
+        }
+
+        public function get type(): String {
+            return this._field.type;
+        }
+
+        public function set type(value: String): void {
+            this._field.type = value;
+        }
+
+        public function get wordWrap(): Boolean {
+            return this._field.wordWrap;
+        }
+
+        public function set wordWrap(value: Boolean): void {
+            this._field.wordWrap = value;
+            this.layout();
+        }
+
+        // The TLF properties, of which the ones that a `TextField` can't show are only kept.
+
+        public function get backgroundAlpha(): Number {
+            return this._backgroundAlpha;
+        }
+
+        public function set backgroundAlpha(value: Number): void {
+            this._backgroundAlpha = value;
+        }
+
+        public function get blockIndent(): Number {
+            var format: TextFormat = this._field.defaultTextFormat;
+            return format.blockIndent != null ? Number(format.blockIndent) : 0;
+        }
+
+        public function set blockIndent(value: Number): void {
+            var format: TextFormat = new TextFormat();
+            format.blockIndent = value;
+            this._field.setTextFormat(format);
+            format = this._field.defaultTextFormat;
+            format.blockIndent = value;
+            this._field.defaultTextFormat = format;
+            this.layout();
+        }
+
+        public function get blockProgression(): String {
+            return this._blockProgression;
+        }
+
+        public function set blockProgression(value: String): void {
+            this._blockProgression = value;
+        }
+
+        public function get borderAlpha(): Number {
+            return this._borderAlpha;
+        }
+
+        public function set borderAlpha(value: Number): void {
+            this._borderAlpha = value;
+        }
+
+        public function get borderWidth(): Number {
+            return this._borderWidth;
+        }
+
+        public function set borderWidth(value: Number): void {
+            this._borderWidth = value;
+        }
+
+        public function get columnCount(): Object {
+            return this._columnCount;
+        }
+
+        public function set columnCount(value: Object): void {
+            this._columnCount = value;
+        }
+
+        public function get columnGap(): Object {
+            return this._columnGap;
+        }
+
+        public function set columnGap(value: Object): void {
+            this._columnGap = value;
+        }
+
+        public function get columnWidth(): Object {
+            return this._columnWidth;
+        }
+
+        public function set columnWidth(value: Object): void {
+            this._columnWidth = value;
+        }
+
+        public function get direction(): String {
+            return this._direction;
+        }
+
+        public function set direction(value: String): void {
+            this._direction = value;
+        }
+
+        public function get firstBaselineOffset(): Object {
+            return this._firstBaselineOffset;
+        }
+
+        public function set firstBaselineOffset(value: Object): void {
+            this._firstBaselineOffset = value;
+        }
+
+        public function get lineBreak(): String {
+            return this._lineBreak;
+        }
+
+        public function set lineBreak(value: String): void {
+            this._lineBreak = value;
+            this.wordWrap = value != "explicit";
+        }
+
+        public function get paddingBottom(): Object {
+            return this._paddingBottom;
+        }
+
+        public function set paddingBottom(value: Object): void {
+            this._paddingBottom = Number(value);
+            this.layout();
+        }
+
+        public function get paddingLeft(): Object {
+            return this._paddingLeft;
+        }
+
+        public function set paddingLeft(value: Object): void {
+            this._paddingLeft = Number(value);
+            this.layout();
+        }
+
+        public function get paddingRight(): Object {
+            return this._paddingRight;
+        }
+
+        public function set paddingRight(value: Object): void {
+            this._paddingRight = Number(value);
+            this.layout();
+        }
+
+        public function get paddingTop(): Object {
+            return this._paddingTop;
+        }
+
+        public function set paddingTop(value: Object): void {
+            this._paddingTop = Number(value);
+            this.layout();
+        }
+
+        public function get pointScrollH(): int {
+            return this._pointScrollH;
+        }
+
+        public function set pointScrollH(value: int): void {
+            this._pointScrollH = value;
+        }
+
+        public function get pointScrollV(): int {
+            return this._pointScrollV;
+        }
+
+        public function set pointScrollV(value: int): void {
+            this._pointScrollV = value;
+        }
+
+        // The TLF text flow isn't available, since the framework isn't.
+        public function get textFlow(): Object {
+            return null;
+        }
+
+        public function get tlfMarkup(): String {
+            return this._tlfMarkup;
+        }
+
+        public function set tlfMarkup(value: String): void {
+            this._tlfMarkup = value;
+            this.applyMarkup(new XML(value));
+        }
+
+        public function get useRichTextClipboard(): Boolean {
+            return this._useRichTextClipboard;
+        }
+
+        public function set useRichTextClipboard(value: Boolean): void {
+            this._useRichTextClipboard = value;
+        }
+
+        public function get verticalAlign(): String {
+            return this._verticalAlign;
+        }
+
+        public function set verticalAlign(value: String): void {
+            this._verticalAlign = value;
+            this.layout();
+        }
+
+        // The `TextField` methods.
+
+        public function appendText(newText: String): void {
+            this._field.appendText(newText);
+            this.layout();
+        }
+
+        public function getCharBoundaries(charIndex: int): Rectangle {
+            var bounds: Rectangle = this._field.getCharBoundaries(charIndex);
+            if (bounds != null) {
+                bounds.offset(this._field.x, this._field.y);
+            }
+            return bounds;
+        }
+
+        public function getCharIndexAtPoint(x: Number, y: Number): int {
+            return this._field.getCharIndexAtPoint(x - this._field.x, y - this._field.y);
+        }
+
+        public function getFirstCharInParagraph(charIndex: int): int {
+            return this._field.getFirstCharInParagraph(charIndex);
+        }
+
+        public function getLineIndexAtPoint(x: Number, y: Number): int {
+            return this._field.getLineIndexAtPoint(x - this._field.x, y - this._field.y);
+        }
+
+        public function getLineIndexOfChar(charIndex: int): int {
+            return this._field.getLineIndexOfChar(charIndex);
+        }
+
+        public function getLineLength(lineIndex: int): int {
+            return this._field.getLineLength(lineIndex);
+        }
+
+        public function getLineMetrics(lineIndex: int): TextLineMetrics {
+            return this._field.getLineMetrics(lineIndex);
+        }
+
+        public function getLineOffset(lineIndex: int): int {
+            return this._field.getLineOffset(lineIndex);
+        }
+
+        public function getLineText(lineIndex: int): String {
+            return this._field.getLineText(lineIndex);
+        }
+
+        public function getParagraphLength(charIndex: int): int {
+            return this._field.getParagraphLength(charIndex);
+        }
+
+        public function getTextFormat(beginIndex: int = -1, endIndex: int = -1): TextFormat {
+            return this._field.getTextFormat(beginIndex, endIndex);
+        }
+
+        public function replaceSelectedText(value: String): void {
+            this._field.replaceSelectedText(value);
+            this.layout();
+        }
+
+        public function replaceText(beginIndex: int, endIndex: int, newText: String): void {
+            this._field.replaceText(beginIndex, endIndex, newText);
+            this.layout();
+        }
+
+        public function setSelection(beginIndex: int, endIndex: int): void {
+            this._field.setSelection(beginIndex, endIndex);
+        }
+
+        public function setTextFormat(format: TextFormat, beginIndex: int = -1, endIndex: int = -1): void {
+            this._field.setTextFormat(format, beginIndex, endIndex);
+            this.layout();
+        }
+
+        // Sets the size of the field's container, which Flash Professional gives TLF text.
+        internal function setSize(width: Number, height: Number): void {
+            this._width = width;
+            this._height = height;
+            this.layout();
+        }
+
+        // Fits the field into the container, inside its padding.
+        private function layout(): void {
+            // Text fields already keep 2 pixels between their bounds and their text.
+            this._field.x = this._paddingLeft - 2;
+            if (this._field.autoSize == TextFieldAutoSize.NONE) {
+                this._field.width = Math.max(this._width - this._paddingLeft - this._paddingRight + 4, 0);
+                this._field.height = Math.max(this._height - this._paddingTop - this._paddingBottom + 4, 0);
+            }
+
+            var y: Number = this._paddingTop - 2;
+            var space: Number = this._height - this._paddingTop - this._paddingBottom - this._field.textHeight - 4;
+            if (space > 0 && this._verticalAlign == "middle") {
+                y += space / 2;
+            } else if (space > 0 && this._verticalAlign == "bottom") {
+                y += space;
+            }
+            this._field.y = y;
+        }
+
+        // Shows the text of TLF markup, which is either a `TextFlow` or the `tlfTextObject`
+        // that Flash Professional wraps around it.
+        internal function applyMarkup(markup: XML): void {
+            var textFlow: XML = markup;
+            if (markup.localName() == "tlfTextObject") {
+                var textType: String = attribute(markup, "type");
+                var editPolicy: String = attribute(markup, "editPolicy");
+                this.type = textType == "Input" || editPolicy == "readWrite" ? TextFieldType.INPUT : TextFieldType.DYNAMIC;
+                this.selectable = editPolicy != "readOnly";
+                if (textType == "Point") {
+                    this.wordWrap = false;
+                    this.autoSize = TextFieldAutoSize.LEFT;
+                }
+                textFlow = null;
+                for each (var child: XML in markup.children()) {
+                    if (child.localName() == "TextFlow") {
+                        textFlow = child;
+                        break;
+                    }
+                }
+                if (textFlow == null) {
+                    return;
+                }
+            }
+
+            var value: String;
+            if ((value = attribute(textFlow, "paddingLeft")) != null) {
+                this._paddingLeft = Number(value);
+            }
+            if ((value = attribute(textFlow, "paddingRight")) != null) {
+                this._paddingRight = Number(value);
+            }
+            if ((value = attribute(textFlow, "paddingTop")) != null) {
+                this._paddingTop = Number(value);
+            }
+            if ((value = attribute(textFlow, "paddingBottom")) != null) {
+                this._paddingBottom = Number(value);
+            }
+            if ((value = attribute(textFlow, "verticalAlign")) != null) {
+                this._verticalAlign = value;
+            }
+            if ((value = attribute(textFlow, "columnCount")) != null) {
+                this._columnCount = value;
+            }
+            if ((value = attribute(textFlow, "direction")) != null) {
+                this._direction = value;
+            }
+            if ((value = attribute(textFlow, "blockProgression")) != null) {
+                this._blockProgression = value;
+            }
+            if ((value = attribute(textFlow, "lineBreak")) != null) {
+                this.lineBreak = value;
+            }
+
+            this._field.htmlText = toHtml(textFlow);
+            this.layout();
+        }
+
+        // Converts TLF markup into the HTML that text fields understand.
+        private static function toHtml(node: XML): String {
+            if (node.nodeKind() == "text") {
+                return escapeHtml(node.toString());
+            }
+            if (node.nodeKind() != "element") {
+                return "";
+            }
+
+            var name: String = String(node.localName());
+            var inner: String = "";
+            for each (var child: XML in node.children()) {
+                inner += toHtml(child);
+            }
+
+            switch (name) {
+                case "br":
+                    return "<br>";
+                case "tab":
+                    return "\t";
+                case "img":
+                case "graphic":
+                    return "";
+                case "a":
+                    var href: String = attribute(node, "href");
+                    var target: String = attribute(node, "target");
+                    inner = "<a href=\"" + escapeHtml(href != null ? href : "") + "\"" +
+                        (target != null ? " target=\"" + escapeHtml(target) + "\"" : "") + ">" + inner + "</a>";
+                    break;
+                case "p":
+                    var align: String = attribute(node, "textAlign");
+                    if (align == "start" || align == "justify") {
+                        align = "left";
+                    } else if (align == "end") {
+                        align = "right";
+                    }
+                    inner = "<p" + (align != null ? " align=\"" + align + "\"" : "") + ">" + inner + "</p>";
+                    break;
+            }
+            return formatted(node, inner);
+        }
+
+        // Wraps HTML in the tags for the character formats of a TLF element.
+        private static function formatted(node: XML, html: String): String {
+            var font: String = "";
+            var value: String;
+            if ((value = attribute(node, "fontFamily")) != null) {
+                font += " face=\"" + escapeHtml(value.split(",")[0]) + "\"";
+            }
+            if ((value = attribute(node, "fontSize")) != null) {
+                font += " size=\"" + Number(value) + "\"";
+            }
+            if ((value = attribute(node, "color")) != null) {
+                font += " color=\"#" + ("00000" + parseColor(value).toString(16)).substr(-6) + "\"";
+            }
+            if ((value = attribute(node, "trackingRight")) != null) {
+                font += " letterSpacing=\"" + parseFloat(value) + "\"";
+            }
+            if ((value = attribute(node, "kerning")) != null) {
+                font += " kerning=\"" + (value == "off" ? 0 : 1) + "\"";
+            }
+            if (font != "") {
+                html = "<font" + font + ">" + html + "</font>";
+            }
+            if (attribute(node, "fontWeight") == "bold") {
+                html = "<b>" + html + "</b>";
+            }
+            if (attribute(node, "fontStyle") == "italic") {
+                html = "<i>" + html + "</i>";
+            }
+            if (attribute(node, "textDecoration") == "underline") {
+                html = "<u>" + html + "</u>";
+            }
+            return html;
+        }
+
+        private static function parseColor(value: String): uint {
+            if (value.charAt(0) == "#") {
+                return uint(parseInt(value.substr(1), 16));
+            }
+            return uint(value);
+        }
+
+        private static function attribute(node: XML, name: String): String {
+            var list: XMLList = node.attribute(name);
+            return list.length() > 0 ? list.toString() : null;
+        }
+
+        private static function escapeHtml(text: String): String {
+            return text.split("&").join("&amp;").split("<").join("&lt;").split(">").join("&gt;").split("\"").join("&quot;");
+        }
+    }
+}
@@ -0,0 +1,107 @@
+package flash.desktop {
+    public class Clipboard {
+        private static var _generalClipboard: Clipboard = null;
+
+        // The data by format, or the functions returning it for the formats set with
+        // `setDataHandler`.
+        private var _data: Object = {};
+
+        // Whether this is the clipboard of the operating system, whose text is shared with
+        // other applications.
+        private var _isGeneral: Boolean = false;
+
+        public function Clipboard() {
+        }
+
+        public static function get generalClipboard(): Clipboard {
+            if (_generalClipboard == null) {
+                _generalClipboard = new Clipboard();
+                _generalClipboard._isGeneral = true;
+            }
+            return _generalClipboard;
+        }
+
+        public function get formats(): Array {
+            var formats = [];
+            if (this._isGeneral && readText() != "") {
+                formats.push(ClipboardFormats.TEXT_FORMAT);
+            }
+            for (var format in this._data) {
+                formats.push(format);
+            }
+            return formats;
+        }
+
+        public function clear(): void {
+            this._data = {};
+            if (this._isGeneral) {
+                writeText("");
+            }
+        }
+
+        public function clearData(format: String): void {
+            if (this._isGeneral && format == ClipboardFormats.TEXT_FORMAT) {
+                writeText("");
+            } else {
+                delete this._data[format];
+            }
+        }
+
+        public function getData(format: String, transferMode: String = "originalPreferred"): Object {
+            if (this._isGeneral && format == ClipboardFormats.TEXT_FORMAT) {
+                return readText();
+            }
+            if (!(format in this._data)) {
+                return null;
+            }
+            var data = this._data[format];
+            if (data is Function) {
+                data = data();
+                this._data[format] = data;
+            }
+            return data;
+        }
+
+        public function hasFormat(format: String): Boolean {
+            return this.formats.indexOf(format) >= 0;
+        }
+
+        public function setData(format: String, data: Object, serializable: Boolean = true): Boolean {
+            if (!isSupportedFormat(format)) {
+                return false;
+            }
+            if (this._isGeneral && format == ClipboardFormats.TEXT_FORMAT) {
+                writeText(String(data));
+            } else {
+                this._data[format] = data;
+            }
+            return true;
+        }
+
+        public function setDataHandler(format: String, handler: Function, serializable: Boolean = true): Boolean {
+            if (!isSupportedFormat(format)) {
+                return false;
+            }
+            if (this._isGeneral && format == ClipboardFormats.TEXT_FORMAT) {
+                // Other applications can't call back into the movie for the text.
+                writeText(String(handler()));
+            } else {
+                this._data[format] = handler;
+            }
+            return true;
+        }
+
+        // Outside of AIR, only text formats can be put on the clipboard. The standalone player
+        // takes the place of AIR.
+        private static function isSupportedFormat(format: String): Boolean {
+            return isDesktop() ||
+                format == ClipboardFormats.TEXT_FORMAT ||
+                format == ClipboardFormats.HTML_FORMAT ||
+                format == ClipboardFormats.RICH_TEXT_FORMAT;
+        }
+
+        private static native function isDesktop(): Boolean;
+        private static native function readText(): String;
+        private static native function writeText(text: String): void;
+    }
+}
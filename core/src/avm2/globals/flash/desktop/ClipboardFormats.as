@@ -8,6 +8,15 @@ package flash.desktop
 
     public class ClipboardFormats
     {
+        // Bitmap data (AIR only).
+        public static const BITMAP_FORMAT:String = "air:bitmap";
+
+        // An array of files (AIR only).
+        public static const FILE_LIST_FORMAT:String = "air:file list";
+
+        // An array of file promises (AIR only).
+        public static const FILE_PROMISE_LIST_FORMAT:String = "air:file promise list";
+
         // HTML data.
         public static const HTML_FORMAT:String = "air:html";
 
@@ -17,5 +26,8 @@ package flash.desktop
         // String data.
         public static const TEXT_FORMAT:String = "air:text";
 
+        // A URL string (AIR only).
+        public static const URL_FORMAT:String = "air:url";
+
     }
 }
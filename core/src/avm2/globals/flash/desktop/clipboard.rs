@@ -0,0 +1,39 @@
+//! `flash.desktop.Clipboard` native methods
+
+use crate::avm1::PlayerType;
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::string::AvmString;
+
+/// Implements `Clipboard.isDesktop`
+pub fn is_desktop<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((activation.context.system.player_type == PlayerType::StandAlone).into())
+}
+
+/// Implements `Clipboard.readText`
+pub fn read_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let text = activation.context.ui.clipboard_content();
+    Ok(AvmString::new_utf8(activation.context.gc_context, text).into())
+}
+
+/// Implements `Clipboard.writeText`
+pub fn write_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let text = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+    activation.context.ui.set_clipboard_content(text);
+    Ok(Value::Undefined)
+}
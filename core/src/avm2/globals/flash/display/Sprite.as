@@ -0,0 +1,5 @@
+// This is a stub - the actual class is defined in `sprite.rs`
+package flash.display {
+    public class Sprite extends DisplayObjectContainer {
+    }
+}
@@ -640,6 +640,20 @@ pub fn set_tab_children<'gc>(
 }
 
 /// Construct `DisplayObjectContainer`'s class.
+/// Implements `DisplayObjectContainer.textSnapshot`
+pub fn text_snapshot<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let snapshot_class = activation.avm2().classes().textsnapshot;
+        return Ok(snapshot_class.construct(activation, &[this.into()])?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
         QName::new(
@@ -679,6 +693,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
             Some(set_mouse_children),
         ),
         ("tabChildren", Some(tab_children), Some(set_tab_children)),
+        ("textSnapshot", Some(text_snapshot), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
@@ -197,28 +197,42 @@ pub fn set_tab_index<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `InteractiveObject.focusRect`'s getter.
 pub fn focus_rect<'gc>(
-    activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.display.InteractiveObject", "focusRect");
-    Ok(Value::Null)
+    if let Some(int) = this
+        .and_then(|t| t.as_display_object())
+        .and_then(|dobj| dobj.as_interactive())
+    {
+        return Ok(int.focus_rect().map_or(Value::Null, Value::Bool));
+    }
+
+    Ok(Value::Undefined)
 }
 
+/// Implements `InteractiveObject.focusRect`'s setter.
 pub fn set_focus_rect<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
+    this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // NOTE: all values other than true or null are converted to false. (false/null do differ)
-
-    // let's only warn on true, as games sometimes just set focusRect to false for some reason.
-    if matches!(args.get(0), Some(Value::Bool(true))) {
-        avm2_stub_setter!(activation, "flash.display.InteractiveObject", "focusRect");
+    if let Some(int) = this
+        .and_then(|t| t.as_display_object())
+        .and_then(|dobj| dobj.as_interactive())
+    {
+        // NOTE: all values other than true or null are converted to false. (false/null do differ)
+        let value = match args.get(0) {
+            Some(Value::Null) => None,
+            Some(Value::Bool(true)) => Some(true),
+            _ => Some(false),
+        };
+        int.set_focus_rect(activation.context.gc_context, value);
     }
 
-    Ok(Value::Null)
+    Ok(Value::Undefined)
 }
 
 /// Construct `InteractiveObject`'s class.
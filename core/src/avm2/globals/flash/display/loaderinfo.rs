@@ -3,6 +3,7 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::bytearray::Endian;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::error::security_error;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{loaderinfo_allocator, DomainObject, LoaderStream, Object, TObject};
 use crate::avm2::value::Value;
@@ -12,6 +13,7 @@ use crate::avm2::QName;
 use crate::avm2::{AvmString, Error};
 use crate::avm2_stub_getter;
 use crate::display_object::TDisplayObject;
+use crate::tag_utils::SwfMovie;
 use gc_arena::{GcCell, MutationContext};
 use swf::{write_swf, Compression};
 
@@ -158,7 +160,7 @@ pub fn bytes_loaded<'gc>(
 
 /// `content` getter
 pub fn content<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -168,7 +170,21 @@ pub fn content<'gc>(
             .and_then(|o| o.as_loader_stream())
         {
             match &*loader_stream {
-                LoaderStream::Swf(_, root) | LoaderStream::NotYetLoaded(_, Some(root), _) => {
+                LoaderStream::Swf(movie, root)
+                | LoaderStream::NotYetLoaded(movie, Some(root), _) => {
+                    let caller = activation.caller_movie();
+                    if !activation
+                        .context
+                        .policy_manager
+                        .allows_scripting(movie.url(), caller.url())
+                    {
+                        let message = format!(
+                            "Error #2121: Security sandbox violation: LoaderInfo.content: {} cannot access {}. This may be worked around by calling Security.allowDomain.",
+                            caller.url().unwrap_or_default(),
+                            movie.url().unwrap_or_default()
+                        );
+                        return Err(Error::AvmError(security_error(activation, &message, 2121)?));
+                    }
                     return Ok(root.object2());
                 }
                 _ => {
@@ -264,14 +280,54 @@ pub fn is_url_inaccessible<'gc>(
     Ok(false.into())
 }
 
+/// Returns whether the loaded movie and the movie that loaded it may script each other, in the
+/// given direction.
+fn allows_scripting<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    child_to_parent: bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this
+            .as_loader_info_object()
+            .and_then(|o| o.as_loader_stream())
+        {
+            let root = match &*loader_stream {
+                LoaderStream::NotYetLoaded(swf, _, _) => swf,
+                LoaderStream::Swf(root, _) => root,
+            };
+            let (target, accessor) = if child_to_parent {
+                (root.loader_url(), root.url())
+            } else {
+                (root.url(), root.loader_url())
+            };
+            return Ok(activation
+                .context
+                .policy_manager
+                .allows_scripting(target, accessor)
+                .into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// `childAllowsParent` getter
+pub fn child_allows_parent<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    allows_scripting(activation, this, false)
+}
+
 /// `parentAllowsChild` getter
 pub fn parent_allows_child<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.display.LoaderInfo", "parentAllowsChild");
-    Ok(false.into())
+    allows_scripting(activation, this, true)
 }
 
 /// `swfVersion` getter
@@ -299,6 +355,66 @@ pub fn swf_version<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Reads a flag from the FileAttributes tag of the loaded SWF.
+fn file_attribute<'gc>(
+    this: Option<Object<'gc>>,
+    flag: fn(&SwfMovie) -> bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this
+            .as_loader_info_object()
+            .and_then(|o| o.as_loader_stream())
+        {
+            match &*loader_stream {
+                LoaderStream::NotYetLoaded(_, _, _) => {
+                    return Err(INSUFFICIENT.into());
+                }
+                LoaderStream::Swf(movie, _) => {
+                    return Ok(flag(movie).into());
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// `__ruffle__.useDirectBlit` getter
+pub fn use_direct_blit<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    file_attribute(this, SwfMovie::use_direct_blit)
+}
+
+/// `__ruffle__.useGPU` getter
+pub fn use_gpu<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    file_attribute(this, SwfMovie::use_gpu)
+}
+
+/// `__ruffle__.hasMetadata` getter
+pub fn has_metadata<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    file_attribute(this, SwfMovie::has_metadata)
+}
+
+/// `__ruffle__.useNetworkSandbox` getter
+pub fn use_network_sandbox<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    file_attribute(this, SwfMovie::use_network_sandbox)
+}
+
 /// `url` getter
 pub fn url<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -536,6 +652,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("applicationDomain", Some(application_domain), None),
         ("bytesLoaded", Some(bytes_loaded), None),
         ("bytesTotal", Some(bytes_total), None),
+        ("childAllowsParent", Some(child_allows_parent), None),
         ("content", Some(content), None),
         ("contentType", Some(content_type), None),
         ("frameRate", Some(frame_rate), None),
@@ -554,5 +671,18 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
+    // Flash Player does not expose the FileAttributes flags to content.
+    const RUFFLE_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("useDirectBlit", Some(use_direct_blit), None),
+        ("useGPU", Some(use_gpu), None),
+        ("hasMetadata", Some(has_metadata), None),
+        ("useNetworkSandbox", Some(use_network_sandbox), None),
+    ];
+    write.define_ns_builtin_instance_properties(mc, "__ruffle__", RUFFLE_INSTANCE_PROPERTIES);
+
     class
 }
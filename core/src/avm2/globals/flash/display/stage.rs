@@ -2,10 +2,12 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::events::eventdispatcher;
 use crate::avm2::method::{Method, NativeMethodImpl};
-use crate::avm2::object::{Object, TObject};
+use crate::avm2::object::{Object, TObject, VectorObject};
 use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
@@ -466,6 +468,7 @@ pub fn set_focus<'gc>(
         val => {
             if let Some(dobj) = val.as_object().and_then(|o| o.as_display_object()) {
                 focus.set(Some(dobj), &mut activation.context);
+                focus.highlight(activation.context.gc_context);
             } else {
                 return Err("Cannot set focus to non-DisplayObject".into());
             }
@@ -695,10 +698,8 @@ pub fn set_quality<'gc>(
         .coerce_to_string(activation)?
         .parse()
     {
-        activation
-            .context
-            .stage
-            .set_quality(activation.context.gc_context, quality);
+        let stage = activation.context.stage;
+        stage.set_quality(&mut activation.context, quality);
     }
     Ok(Value::Undefined)
 }
@@ -726,6 +727,59 @@ pub fn stage3ds<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implement `stageVideos`'s getter
+pub fn stage_videos<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_stage())
+    {
+        let storage = VectorStorage::from_values(
+            stage
+                .stage_videos()
+                .iter()
+                .map(|obj| Value::Object(*obj))
+                .collect(),
+            true,
+            activation.avm2().classes().stagevideo,
+            activation,
+        );
+        let stage_videos_vector = VectorObject::from_vector(storage, activation)?;
+        return Ok(stage_videos_vector.into());
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage.addEventListener`.
+///
+/// The stage tells every new `stageVideoAvailability` listener whether stage video is available.
+pub fn add_event_listener<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    eventdispatcher::add_event_listener(activation, this, args)?;
+
+    if let Some(stage) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_stage())
+    {
+        let event_type = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        if &event_type == b"stageVideoAvailability" {
+            stage.queue_stage_video_availability(activation.context.gc_context);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Stage.fullScreenSourceRect's getter
 pub fn full_screen_source_rect<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -822,6 +876,14 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         }
     }
 
+    write.define_instance_trait(
+        Trait::from_method(
+            QName::new(Namespace::public(), "addEventListener"),
+            Method::from_builtin(add_event_listener, "addEventListener", mc),
+        )
+        .with_override(),
+    );
+
     const PUBLIC_INSTANCE_PROPERTIES: &[(
         &str,
         Option<NativeMethodImpl>,
@@ -860,6 +922,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ),
         ("quality", Some(quality), Some(set_quality)),
         ("stage3Ds", Some(stage3ds), None),
+        ("stageVideos", Some(stage_videos), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
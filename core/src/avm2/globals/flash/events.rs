@@ -5,3 +5,4 @@ pub mod eventdispatcher;
 pub mod gesture_event;
 pub mod ieventdispatcher;
 pub mod mouse_event;
+pub mod touch_event;
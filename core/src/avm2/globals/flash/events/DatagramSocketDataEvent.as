@@ -0,0 +1,33 @@
+package flash.events {
+    import flash.utils.ByteArray;
+
+    public class DatagramSocketDataEvent extends Event {
+        public static const DATA:String = "data";
+
+        public var srcAddress:String;
+        public var srcPort:int;
+        public var dstAddress:String;
+        public var dstPort:int;
+        public var data:ByteArray;
+
+        public function DatagramSocketDataEvent(type:String, bubbles:Boolean = false, cancelable:Boolean = false, srcAddress:String = "", srcPort:int = 0, dstAddress:String = "", dstPort:int = 0, data:ByteArray = null)
+        {
+            super(type,bubbles,cancelable);
+            this.srcAddress = srcAddress;
+            this.srcPort = srcPort;
+            this.dstAddress = dstAddress;
+            this.dstPort = dstPort;
+            this.data = data;
+        }
+
+        override public function clone() : Event
+        {
+            return new DatagramSocketDataEvent(this.type,this.bubbles,this.cancelable,this.srcAddress,this.srcPort,this.dstAddress,this.dstPort,this.data);
+        }
+
+        override public function toString() : String
+        {
+            return this.formatToString("DatagramSocketDataEvent","type","bubbles","cancelable","eventPhase","srcAddress","srcPort","dstAddress","dstPort");
+        }
+    }
+}
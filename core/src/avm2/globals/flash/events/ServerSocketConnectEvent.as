@@ -0,0 +1,25 @@
+package flash.events {
+    import flash.net.Socket;
+
+    public class ServerSocketConnectEvent extends Event {
+        public static const CONNECT:String = "connect";
+
+        public var socket:Socket;
+
+        public function ServerSocketConnectEvent(type:String, bubbles:Boolean = false, cancelable:Boolean = false, socket:Socket = null)
+        {
+            super(type,bubbles,cancelable);
+            this.socket = socket;
+        }
+
+        override public function clone() : Event
+        {
+            return new ServerSocketConnectEvent(this.type,this.bubbles,this.cancelable,this.socket);
+        }
+
+        override public function toString() : String
+        {
+            return this.formatToString("ServerSocketConnectEvent","type","bubbles","cancelable","eventPhase","socket");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+package flash.events
+{
+    import flash.display.InteractiveObject;
+    import __ruffle__.log_warn;
+
+    public class TouchEvent extends Event
+    {
+        public static const TOUCH_BEGIN:String = "touchBegin";
+        public static const TOUCH_END:String = "touchEnd";
+        public static const TOUCH_MOVE:String = "touchMove";
+        public static const TOUCH_OUT:String = "touchOut";
+        public static const TOUCH_OVER:String = "touchOver";
+        public static const TOUCH_ROLL_OUT:String = "touchRollOut";
+        public static const TOUCH_ROLL_OVER:String = "touchRollOver";
+        public static const TOUCH_TAP:String = "touchTap";
+        public static const PROXIMITY_BEGIN:String = "proximityBegin";
+        public static const PROXIMITY_END:String = "proximityEnd";
+        public static const PROXIMITY_MOVE:String = "proximityMove";
+        public static const PROXIMITY_OUT:String = "proximityOut";
+        public static const PROXIMITY_OVER:String = "proximityOver";
+        public static const PROXIMITY_ROLL_OUT:String = "proximityRollOut";
+        public static const PROXIMITY_ROLL_OVER:String = "proximityRollOver";
+
+        // A unique identification number assigned to the touch point.
+        public var touchPointID: int;
+
+        // Whether the first point of contact is mapped to mouse events.
+        public var isPrimaryTouchPoint: Boolean;
+
+        public var localX: Number;
+        public var localY: Number;
+
+        // Width and height of the contact area.
+        public var sizeX: Number;
+        public var sizeY: Number;
+
+        // A value between 0.0 and 1.0 indicating force of the contact with the device.
+        public var pressure: Number;
+
+        public var relatedObject: InteractiveObject;
+        public var ctrlKey: Boolean;
+        public var altKey: Boolean;
+        public var shiftKey: Boolean;
+        public var commandKey: Boolean;
+        public var controlKey: Boolean;
+        public var isRelatedObjectInaccessible: Boolean;
+
+        public function TouchEvent(type:String,
+                                   bubbles:Boolean = true,
+                                   cancelable:Boolean = false,
+                                   touchPointID:int = 0,
+                                   isPrimaryTouchPoint:Boolean = false,
+                                   localX:Number = 0/0,
+                                   localY:Number = 0/0,
+                                   sizeX:Number = 0/0,
+                                   sizeY:Number = 0/0,
+                                   pressure:Number = 0/0,
+                                   relatedObject:InteractiveObject = null,
+                                   ctrlKey:Boolean = false,
+                                   altKey:Boolean = false,
+                                   shiftKey:Boolean = false,
+                                   commandKey:Boolean = false,
+                                   controlKey:Boolean = false)
+        {
+            super(type,bubbles,cancelable);
+            this.touchPointID = touchPointID;
+            this.isPrimaryTouchPoint = isPrimaryTouchPoint;
+            this.localX = localX;
+            this.localY = localY;
+            this.sizeX = sizeX;
+            this.sizeY = sizeY;
+            this.pressure = pressure;
+            this.relatedObject = relatedObject;
+            this.ctrlKey = ctrlKey;
+            this.altKey = altKey;
+            this.shiftKey = shiftKey;
+            this.commandKey = commandKey;
+            this.controlKey = controlKey;
+            this.isRelatedObjectInaccessible = false; // unimplemented
+        }
+
+        override public function clone() : Event
+        {
+            return new TouchEvent(this.type,this.bubbles,this.cancelable,this.touchPointID,this.isPrimaryTouchPoint,this.localX,this.localY,this.sizeX,this.sizeY,this.pressure,this.relatedObject,this.ctrlKey,this.altKey,this.shiftKey,this.commandKey,this.controlKey);
+        }
+
+        override public function toString() : String
+        {
+            return this.formatToString("TouchEvent","type","bubbles","cancelable","eventPhase","touchPointID","isPrimaryTouchPoint","localX","localY","stageX","stageY","sizeX","sizeY","pressure","relatedObject","ctrlKey","altKey","shiftKey");
+        }
+
+        public function updateAfterEvent():void {
+            log_warn("TouchEvent.updateAfterEvent is not yet implemented");
+        }
+
+        public native function get stageX() : Number;
+        public native function get stageY() : Number;
+    }
+}
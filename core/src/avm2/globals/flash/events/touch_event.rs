@@ -0,0 +1,4 @@
+//! `flash.events.TouchEvent` builtin
+
+// Touch events have the same `localX` and `localY` as mouse events, converted the same way.
+pub use super::mouse_event::{get_stage_x, get_stage_y};
@@ -3,6 +3,8 @@ package flash.external
    public final class ExternalInterface
    {
       public static native function get available(): Boolean;
+
+      public static native function get objectID(): String;
       
       public static native function addCallback(functionName: String, closure: Function) : void;
       
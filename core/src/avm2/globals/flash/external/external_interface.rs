@@ -1,11 +1,29 @@
+use crate::avm2::error::error;
 use crate::avm2::{Activation, Error, Object, Value};
 use crate::external::{Callback, Value as ExternalValue};
+use crate::string::AvmString;
+
+/// The error raised when the player isn't embedded in a container that answers calls.
+fn unavailable_error<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
+    match error(
+        activation,
+        "Error #2067: The ExternalInterface is not available in this container. ExternalInterface requires Internet Explorer ActiveX, Firefox, Mozilla 1.7.5 and greater, or other browsers that support NPRuntime.",
+        2067,
+    ) {
+        Ok(error) => Error::AvmError(error),
+        Err(e) => e,
+    }
+}
 
 pub fn call<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    let movie = activation.caller_movie();
+    if !activation.context.external_interface.available(&movie) {
+        return Err(unavailable_error(activation));
+    }
     if args.is_empty() {
         return Ok(Value::Null);
     }
@@ -14,11 +32,11 @@ pub fn call<'gc>(
     if let Some(method) = activation
         .context
         .external_interface
-        .get_method_for(&name.to_utf8_lossy())
+        .get_method_for(&name.to_utf8_lossy(), &movie)
     {
         let mut external_args = Vec::with_capacity(args.len() - 1);
         for arg in &args[1..] {
-            external_args.push(ExternalValue::from_avm2(arg.to_owned()));
+            external_args.push(ExternalValue::from_avm2(activation, arg.to_owned())?);
         }
         Ok(method
             .call(&mut activation.context, &external_args)
@@ -33,7 +51,23 @@ pub fn get_available<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(activation.context.external_interface.available().into())
+    let movie = activation.caller_movie();
+    Ok(activation
+        .context
+        .external_interface
+        .available(&movie)
+        .into())
+}
+
+pub fn get_object_id<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match activation.context.external_interface.object_id() {
+        Some(id) => Ok(AvmString::new_utf8(activation.context.gc_context, id).into()),
+        None => Ok(Value::Null),
+    }
 }
 
 pub fn add_callback<'gc>(
@@ -41,6 +75,10 @@ pub fn add_callback<'gc>(
     _this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    let movie = activation.caller_movie();
+    if !activation.context.external_interface.available(&movie) {
+        return Err(unavailable_error(activation));
+    }
     if args.len() < 2 {
         return Ok(Value::Undefined);
     }
@@ -48,11 +86,17 @@ pub fn add_callback<'gc>(
     let name = args.get(0).unwrap().coerce_to_string(activation)?;
     let method = args.get(1).unwrap();
 
-    if let Value::Object(method) = method {
-        activation
+    match method {
+        Value::Object(method) => activation
+            .context
+            .external_interface
+            .add_callback(name.to_string(), Callback::Avm2 { method: *method }),
+        // Passing `null` removes the callback.
+        Value::Null | Value::Undefined => activation
             .context
             .external_interface
-            .add_callback(name.to_string(), Callback::Avm2 { method: *method });
+            .remove_callback(&name.to_utf8_lossy()),
+        _ => {}
     }
     Ok(Value::Undefined)
 }
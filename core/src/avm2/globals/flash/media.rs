@@ -1,7 +1,10 @@
 //! `flash.media` namespace
 
+pub mod camera;
+pub mod microphone;
 pub mod sound;
 pub mod soundchannel;
 pub mod soundmixer;
 pub mod soundtransform;
+pub mod stage_video;
 pub mod video;
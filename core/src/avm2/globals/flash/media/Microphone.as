@@ -0,0 +1,48 @@
+package flash.media {
+    import flash.events.EventDispatcher;
+
+    [Ruffle(InstanceAllocator)]
+    public final class Microphone extends EventDispatcher {
+        public var codec:String = SoundCodec.NELLYMOSER;
+        public var enableVAD:Boolean = true;
+        public var encodeQuality:int = 6;
+        public var framesPerPacket:int = 2;
+        public var noiseSuppressionLevel:int = -30;
+
+        private var _useEchoSuppression:Boolean = false;
+
+        public static native function get names():Array;
+
+        public static function get isSupported():Boolean {
+            return Microphone.names.length > 0;
+        }
+
+        public static native function getMicrophone(index:int = -1):Microphone;
+
+        public static function getEnhancedMicrophone(index:int = -1):Microphone {
+            return Microphone.getMicrophone(index);
+        }
+
+        public native function get activityLevel():Number;
+        public native function get gain():Number;
+        public native function set gain(value:Number):void;
+        public native function get index():int;
+        public native function get muted():Boolean;
+        public native function get name():String;
+        public native function get rate():int;
+        public native function set rate(value:int):void;
+        public native function get silenceLevel():Number;
+        public native function get silenceTimeout():int;
+
+        public function get useEchoSuppression():Boolean {
+            return this._useEchoSuppression;
+        }
+
+        public native function setLoopBack(state:Boolean = true):void;
+        public native function setSilenceLevel(silenceLevel:Number, timeout:int = -1):void;
+
+        public function setUseEchoSuppression(useEchoSuppression:Boolean):void {
+            this._useEchoSuppression = useEchoSuppression;
+        }
+    }
+}
@@ -0,0 +1,32 @@
+package flash.media {
+    import flash.events.EventDispatcher;
+    import flash.geom.Point;
+    import flash.geom.Rectangle;
+    import flash.net.NetStream;
+
+    [Ruffle(InstanceAllocator)]
+    public class StageVideo extends EventDispatcher {
+        public native function attachNetStream(netStream:NetStream):void;
+
+        public native function get viewPort():Rectangle;
+        public native function set viewPort(rect:Rectangle):void;
+
+        public native function get videoWidth():int;
+        public native function get videoHeight():int;
+
+        public function get colorSpaces():Vector.<String> {
+            var colorSpaces:Vector.<String> = new Vector.<String>();
+            colorSpaces.push("BT.601");
+            return colorSpaces;
+        }
+
+        public native function get depth():int;
+        public native function set depth(value:int):void;
+
+        public native function get pan():Point;
+        public native function set pan(value:Point):void;
+
+        public native function get zoom():Point;
+        public native function set zoom(value:Point):void;
+    }
+}
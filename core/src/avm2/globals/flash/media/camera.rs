@@ -0,0 +1,416 @@
+//! `flash.media.Camera` native function definitions
+
+use crate::avm2::error::type_error;
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, ArrayObject, ArrayStorage, Error, Object, Value};
+use crate::bitmap::bitmap_data::Color;
+use crate::capture::{CaptureManager, PrivacyPermission};
+use crate::string::AvmString;
+
+pub use crate::avm2::object::camera_allocator;
+
+/// Implements `Camera.names`
+pub fn get_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let names = activation.context.ui.camera_names();
+    let storage: ArrayStorage<'gc> = names
+        .into_iter()
+        .map(|name| AvmString::new_utf8(activation.context.gc_context, name))
+        .collect();
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
+}
+
+/// Implements `Camera.getCamera`
+pub fn get_camera<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // The name is actually the index of the camera as a string, and `null` selects
+    // the default camera, which is listed first.
+    let index = match args.get(0).cloned().unwrap_or(Value::Null) {
+        Value::Null | Value::Undefined => 0,
+        name => match name.coerce_to_string(activation)?.parse::<usize>() {
+            Ok(index) => index,
+            Err(_) => return Ok(Value::Null),
+        },
+    };
+    let names = activation.context.ui.camera_names();
+    let name = match names.into_iter().nth(index) {
+        Some(name) => name,
+        None => return Ok(Value::Null),
+    };
+
+    if let Some(camera) = activation.context.capture_manager.camera(index) {
+        return Ok(Object::from(camera).into());
+    }
+
+    let camera_class = activation.avm2().classes().camera;
+    let object = camera_class.construct(activation, &[])?;
+    let camera = object
+        .as_camera()
+        .ok_or("Camera should be a CameraObject")?;
+    {
+        let mut camera = camera.camera_mut(activation.context.gc_context);
+        camera.index = index;
+        camera.name = name;
+    }
+    activation.context.capture_manager.add_camera(camera);
+    CaptureManager::request_permission(&mut activation.context);
+
+    Ok(object.into())
+}
+
+/// Implements `Camera.activityLevel`
+pub fn get_activity_level<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().activity_level.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.bandwidth`
+pub fn get_bandwidth<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().bandwidth.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.currentFPS`
+pub fn get_current_fps<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().current_fps.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.fps`
+pub fn get_fps<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().fps.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.height`
+pub fn get_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().height.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.index`
+pub fn get_index<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok((camera.camera().index as i32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.keyFrameInterval`
+pub fn get_key_frame_interval<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().key_frame_interval.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.loopback`
+pub fn get_loopback<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().loopback.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.motionLevel`
+pub fn get_motion_level<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().motion_level.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.motionTimeout`
+pub fn get_motion_timeout<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().motion_timeout.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.muted`
+pub fn get_muted<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let permission = activation.context.capture_manager.permission();
+    Ok((permission != PrivacyPermission::Granted).into())
+}
+
+/// Implements `Camera.name`
+pub fn get_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let name = camera.camera().name.clone();
+        return Ok(AvmString::new_utf8(activation.context.gc_context, name).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.quality`
+pub fn get_quality<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().quality.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.width`
+pub fn get_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        return Ok(camera.camera().width.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.drawToBitmapData`
+pub fn draw_to_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let destination = match args.get(0).cloned().unwrap_or(Value::Null) {
+            Value::Object(object) => object.as_bitmap_data_wrapper(),
+            _ => None,
+        };
+        let destination = match destination {
+            Some(destination) => destination,
+            None => {
+                return Err(Error::AvmError(type_error(
+                    activation,
+                    "Error #2007: Parameter destination must be non-null.",
+                    2007,
+                )?))
+            }
+        };
+
+        let camera = camera.camera();
+        let image = match camera.image() {
+            Some(image) => image,
+            None => return Ok(Value::Undefined),
+        };
+        let destination = destination.sync();
+        let mut destination = destination.write(activation.context.gc_context);
+        let width = image.width.min(destination.width());
+        let height = image.height.min(destination.height());
+        for y in 0..height {
+            for x in 0..width {
+                let i = ((y * image.width + x) * 4) as usize;
+                if let Some([r, g, b]) = image.rgba.get(i..i + 3) {
+                    destination.set_pixel32_raw(x, y, Color::argb(255, *r, *g, *b));
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setKeyFrameInterval`
+pub fn set_key_frame_interval<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let interval = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        camera
+            .camera_mut(activation.context.gc_context)
+            .key_frame_interval = interval.clamp(1, 300);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setLoopback`
+pub fn set_loopback<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let compress = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Bool(false))
+            .coerce_to_boolean();
+        camera.camera_mut(activation.context.gc_context).loopback = compress;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setMode`
+pub fn set_mode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let width = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let height = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let fps = args
+            .get(2)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+
+        let mut camera = camera.camera_mut(activation.context.gc_context);
+        // Invalid values keep the current mode.
+        if width > 0 && height > 0 {
+            camera.width = width as u32;
+            camera.height = height as u32;
+        }
+        if fps > 0.0 {
+            camera.fps = fps;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setMotionLevel`
+pub fn set_motion_level<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let motion_level = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let timeout = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Integer(2000))
+            .coerce_to_i32(activation)?;
+
+        let mut camera = camera.camera_mut(activation.context.gc_context);
+        camera.motion_level = motion_level.clamp(0, 100);
+        camera.motion_timeout = timeout.max(0);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Camera.setQuality`
+pub fn set_quality<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(camera) = this.and_then(|this| this.as_camera()) {
+        let bandwidth = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let quality = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        let mut camera = camera.camera_mut(activation.context.gc_context);
+        camera.bandwidth = bandwidth.max(0);
+        camera.quality = quality.clamp(0, 100);
+    }
+
+    Ok(Value::Undefined)
+}
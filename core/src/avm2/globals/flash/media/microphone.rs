@@ -0,0 +1,259 @@
+//! `flash.media.Microphone` native function definitions
+
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, ArrayObject, ArrayStorage, Error, Object, Value};
+use crate::capture::{CaptureManager, PrivacyPermission};
+use crate::string::AvmString;
+
+pub use crate::avm2::object::microphone_allocator;
+
+/// Implements `Microphone.names`
+pub fn get_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let names = activation.context.audio.microphone_names();
+    let storage: ArrayStorage<'gc> = names
+        .into_iter()
+        .map(|name| AvmString::new_utf8(activation.context.gc_context, name))
+        .collect();
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
+}
+
+/// Implements `Microphone.getMicrophone`
+pub fn get_microphone<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let index = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Integer(-1))
+        .coerce_to_i32(activation)?;
+    let names = activation.context.audio.microphone_names();
+    // -1 selects the default microphone, which is listed first.
+    let index = if index < 0 { 0 } else { index as usize };
+    let name = match names.into_iter().nth(index) {
+        Some(name) => name,
+        None => return Ok(Value::Null),
+    };
+
+    if let Some(microphone) = activation.context.capture_manager.microphone(index) {
+        return Ok(Object::from(microphone).into());
+    }
+
+    let microphone_class = activation.avm2().classes().microphone;
+    let object = microphone_class.construct(activation, &[])?;
+    let microphone = object
+        .as_microphone()
+        .ok_or("Microphone should be a MicrophoneObject")?;
+    {
+        let mut microphone = microphone.microphone_mut(activation.context.gc_context);
+        microphone.index = index;
+        microphone.name = name;
+    }
+    activation
+        .context
+        .capture_manager
+        .add_microphone(microphone);
+    CaptureManager::request_permission(&mut activation.context);
+
+    Ok(object.into())
+}
+
+/// Implements `Microphone.activityLevel`
+pub fn get_activity_level<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        return Ok(microphone.microphone().activity_level.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.gain`'s getter
+pub fn get_gain<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        return Ok(microphone.microphone().gain.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.gain`'s setter
+pub fn set_gain<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        let gain = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        if !gain.is_nan() {
+            microphone
+                .microphone_mut(activation.context.gc_context)
+                .gain = gain.clamp(0.0, 100.0);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.index`
+pub fn get_index<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        return Ok((microphone.microphone().index as i32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.muted`
+pub fn get_muted<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let permission = activation.context.capture_manager.permission();
+    Ok((permission != PrivacyPermission::Granted).into())
+}
+
+/// Implements `Microphone.name`
+pub fn get_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        let name = microphone.microphone().name.clone();
+        return Ok(AvmString::new_utf8(activation.context.gc_context, name).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.rate`'s getter
+pub fn get_rate<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        return Ok(microphone.microphone().rate.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.rate`'s setter
+pub fn set_rate<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        let rate = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        microphone
+            .microphone_mut(activation.context.gc_context)
+            .set_rate(rate);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.silenceLevel`
+pub fn get_silence_level<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        return Ok(microphone.microphone().silence_level.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.silenceTimeout`
+pub fn get_silence_timeout<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        return Ok(microphone.microphone().silence_timeout.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.setLoopBack`
+pub fn set_loop_back<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        let state = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Bool(true))
+            .coerce_to_boolean();
+        microphone
+            .microphone_mut(activation.context.gc_context)
+            .loopback = state;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Microphone.setSilenceLevel`
+pub fn set_silence_level<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(microphone) = this.and_then(|this| this.as_microphone()) {
+        let silence_level = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        let timeout = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Integer(-1))
+            .coerce_to_i32(activation)?;
+
+        let mut microphone = microphone.microphone_mut(activation.context.gc_context);
+        if !silence_level.is_nan() {
+            microphone.silence_level = silence_level.clamp(0.0, 100.0);
+        }
+        // A negative timeout keeps the current one.
+        if timeout >= 0 {
+            microphone.silence_timeout = timeout;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
@@ -2,8 +2,12 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::error::{argument_error, type_error};
+use crate::avm2::globals::flash::events::eventdispatcher::has_event_listener;
 use crate::avm2::method::{Method, NativeMethodImpl};
-use crate::avm2::object::{sound_allocator, Object, SoundChannelObject, TObject};
+use crate::avm2::object::{
+    sound_allocator, Object, QueuedPlay, SoundChannelObject, SoundObject, TObject,
+};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
@@ -11,10 +15,11 @@ use crate::avm2::Namespace;
 use crate::avm2::QName;
 use crate::backend::navigator::Request;
 use crate::character::Character;
+use crate::compatibility::MediaKind;
 use crate::display_object::SoundTransform;
 use crate::{avm2_stub_getter, avm2_stub_method};
 use gc_arena::{GcCell, MutationContext};
-use swf::{SoundEvent, SoundInfo};
+use swf::{AudioCompression, SoundEvent, SoundFormat, SoundInfo};
 
 /// Implements `flash.media.Sound`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -62,12 +67,33 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Sound.bytesLoaded`
+pub fn bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(sound_object) = this.and_then(|this| this.as_sound_object()) {
+        if let Some((loaded, _)) = sound_object.load_progress() {
+            return Ok(loaded.into());
+        }
+    }
+
+    bytes_total(activation, this, args)
+}
+
 /// Implements `Sound.bytesTotal`
 pub fn bytes_total<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(sound_object) = this.and_then(|this| this.as_sound_object()) {
+        if let Some((_, total)) = sound_object.load_progress() {
+            return Ok(total.into());
+        }
+    }
+
     if let Some(sound) = this.and_then(|this| this.as_sound()) {
         if let Some(length) = activation.context.audio.get_sound_size(sound) {
             return Ok((length).into());
@@ -77,25 +103,66 @@ pub fn bytes_total<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Sound.id3`
+pub fn id3<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(sound_object) = this.and_then(|this| this.as_sound_object()) {
+        if let Some(id3) = sound_object.id3() {
+            return Ok(id3.into());
+        }
+
+        // Sounds without any ID3 metadata have an empty `ID3Info`.
+        let id3 = activation
+            .avm2()
+            .classes()
+            .id3info
+            .construct(activation, &[])?;
+        sound_object.set_id3(activation.context.gc_context, id3);
+        return Ok(id3.into());
+    }
+
+    Ok(Value::Null)
+}
+
 /// Implements `Sound.isBuffering`
 pub fn is_buffering<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(sound) = this.and_then(|this| this.as_sound()) {
+        return Ok(activation.context.audio.is_sound_buffering(sound).into());
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `Sound.isURLInaccessible`
+pub fn is_url_inaccessible<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.media.Sound", "isBuffering");
-    //STUB: We do not yet support network-loaded sounds.
+    avm2_stub_getter!(activation, "flash.media.Sound", "isURLInaccessible");
     Ok(false.into())
 }
 
 /// Implements `Sound.url`
 pub fn url<'gc>(
-    activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.media.Sound", "url");
-    //STUB: We do not yet support network-loaded sounds.
+    if let Some(url) = this
+        .and_then(|this| this.as_sound_object())
+        .and_then(|sound_object| sound_object.url())
+    {
+        return Ok(url.into());
+    }
+
     Ok(Value::Null)
 }
 
@@ -120,50 +187,41 @@ pub fn play<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        if let Some(sound_object) = this.as_sound_object() {
+            if sound_object.is_waiting_for_data() {
+                return play_queued(activation, sound_object, args);
+            }
+        }
+
+        if this.as_sound().is_none() {
+            return play_dynamic(activation, this, args);
+        }
+    }
+
     if let Some(sound) = this.and_then(|this| this.as_sound()) {
-        let position = args
-            .get(0)
-            .cloned()
-            .unwrap_or_else(|| 0.0.into())
-            .coerce_to_number(activation)?;
-        let num_loops = args
-            .get(1)
-            .cloned()
-            .unwrap_or_else(|| 0.into())
-            .coerce_to_i32(activation)?;
+        let (position, sound_info) = play_settings(activation, args)?;
         let sound_transform = args.get(2).cloned().unwrap_or(Value::Null).as_object();
 
+        // The duration of a sound that is still loading only covers what was loaded so far.
+        let is_loading = this
+            .and_then(|this| this.as_sound_object())
+            .and_then(|sound_object| sound_object.load_progress())
+            .map_or(false, |(loaded, total)| total == 0 || loaded < total);
         if let Some(duration) = activation.context.audio.get_sound_duration(sound) {
-            if position > duration {
+            if position > duration && !is_loading {
                 return Ok(Value::Null);
             }
         }
 
-        let in_sample = if position > 0.0 {
-            Some((position / 1000.0 * 44100.0) as u32)
-        } else {
-            None
-        };
-
-        let sound_info = SoundInfo {
-            event: SoundEvent::Start,
-            in_sample,
-            out_sample: None,
-            num_loops: num_loops.max(1) as u16,
-            envelope: None,
-        };
-
-        if let Some(instance) = activation
-            .context
-            .start_sound(sound, &sound_info, None, None)
-        {
+        if let Some(instance) = activation.context.start_avm2_sound(sound, &sound_info) {
+            let sound_channel = SoundChannelObject::from_sound_instance(activation, instance)?;
             if let Some(sound_transform) = sound_transform {
                 let st = SoundTransform::from_avm2_object(activation, sound_transform)?;
+                sound_channel.set_sound_transform(activation.context.gc_context, st.clone());
                 activation.context.set_local_sound_transform(instance, st);
             }
 
-            let sound_channel = SoundChannelObject::from_sound_instance(activation, instance)?;
-
             activation
                 .context
                 .attach_avm2_sound_channel(instance, sound_channel);
@@ -175,14 +233,169 @@ pub fn play<'gc>(
     Ok(Value::Null)
 }
 
+/// Reads the start time and number of loops arguments of `Sound.play`.
+///
+/// Returns the start time in milliseconds, along with the settings to play the sound with.
+fn play_settings<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    args: &[Value<'gc>],
+) -> Result<(f64, SoundInfo), Error<'gc>> {
+    let position = args
+        .get(0)
+        .cloned()
+        .unwrap_or_else(|| 0.0.into())
+        .coerce_to_number(activation)?;
+    let num_loops = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| 0.into())
+        .coerce_to_i32(activation)?;
+
+    let in_sample = if position > 0.0 {
+        Some((position / 1000.0 * 44100.0) as u32)
+    } else {
+        None
+    };
+
+    let sound_info = SoundInfo {
+        event: SoundEvent::Start,
+        in_sample,
+        out_sample: None,
+        num_loops: num_loops.max(1) as u16,
+        envelope: None,
+    };
+    Ok((position, sound_info))
+}
+
+/// Implements `Sound.play` for a sound that is loading, but didn't receive any data yet.
+///
+/// The sound starts playing once it does.
+fn play_queued<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    sound_object: SoundObject<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (_, settings) = play_settings(activation, args)?;
+    let sound_transform = match args.get(2).cloned().unwrap_or(Value::Null).as_object() {
+        Some(sound_transform) => Some(SoundTransform::from_avm2_object(
+            activation,
+            sound_transform,
+        )?),
+        None => None,
+    };
+
+    let sound_channel = SoundChannelObject::without_sound_instance(activation)?;
+    if let Some(sound_transform) = sound_transform {
+        sound_channel.set_sound_transform(activation.context.gc_context, sound_transform);
+    }
+    sound_object.queue_play(
+        activation.context.gc_context,
+        QueuedPlay {
+            settings,
+            sound_channel,
+        },
+    );
+    Ok(sound_channel.into())
+}
+
+/// Implements `Sound.play` for a sound without any data, whose samples are instead generated by
+/// its `sampleData` handlers.
+fn play_dynamic<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let has_sample_data_listener =
+        has_event_listener(activation, Some(this), &["sampleData".into()])?.coerce_to_boolean();
+    if !has_sample_data_listener {
+        return Ok(Value::Null);
+    }
+
+    // The start time and number of loops are ignored for dynamic sounds.
+    let sound_transform = args.get(2).cloned().unwrap_or(Value::Null).as_object();
+
+    if let Some(instance) = activation.context.start_dynamic_sound(this) {
+        let sound_channel = SoundChannelObject::from_sound_instance(activation, instance)?;
+        if let Some(sound_transform) = sound_transform {
+            let st = SoundTransform::from_avm2_object(activation, sound_transform)?;
+            sound_channel.set_sound_transform(activation.context.gc_context, st.clone());
+            activation.context.set_local_sound_transform(instance, st);
+        }
+
+        activation
+            .context
+            .attach_avm2_sound_channel(instance, sound_channel);
+
+        return Ok(sound_channel.into());
+    }
+
+    Ok(Value::Null)
+}
+
 /// `Sound.extract`
 pub fn extract<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(activation, "flash.media.Sound", "extract");
-    Ok(Value::Undefined)
+    let sound_object = match this.and_then(|this| this.as_sound_object()) {
+        Some(sound_object) => sound_object,
+        None => return Ok(0.into()),
+    };
+    let target = match args.get(0).cloned().unwrap_or(Value::Null).as_object() {
+        Some(target) => target,
+        None => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #2007: Parameter target must be non-null.",
+                2007,
+            )?))
+        }
+    };
+    let length = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+    let start_position = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| (-1).into())
+        .coerce_to_number(activation)?;
+
+    let sound = match sound_object.as_sound() {
+        Some(sound) => sound,
+        None => return Ok(0.into()),
+    };
+    let start_position = if start_position < 0.0 {
+        sound_object.extract_position()
+    } else {
+        start_position as u32
+    };
+    let length = if length > 0.0 { length as u32 } else { 0 };
+
+    let samples = match activation
+        .context
+        .audio
+        .extract_sound(sound, start_position, length)
+    {
+        Ok(samples) => samples,
+        Err(e) => {
+            tracing::error!("Sound.extract: Couldn't decode sound: {}", e);
+            Vec::new()
+        }
+    };
+
+    if let Some(mut target) = target.as_bytearray_mut(activation.context.gc_context) {
+        for [left, right] in &samples {
+            target.write_float(*left)?;
+            target.write_float(*right)?;
+        }
+    }
+
+    let num_samples = samples.len() as u32;
+    sound_object.set_extract_position(activation.context.gc_context, start_position + num_samples);
+    Ok(num_samples.into())
 }
 
 /// `Sound.close`
@@ -212,6 +425,11 @@ pub fn load<'gc>(
             .get_property(&Multiname::public("url"), activation)?
             .coerce_to_string(activation)?;
 
+        if let Some(sound_object) = this.as_sound_object() {
+            sound_object.set_url(activation.context.gc_context, url);
+            sound_object.set_load_progress(activation.context.gc_context, 0, 0);
+        }
+
         // TODO: context parameter currently unused.
         let _sound_context = args.get(1);
         if _sound_context.is_some() {
@@ -229,27 +447,162 @@ pub fn load<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Returns the `ByteArray` passed as the first argument of the `Sound.load*FromByteArray` methods.
+fn bytes_arg<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    args: &[Value<'gc>],
+) -> Result<Object<'gc>, Error<'gc>> {
+    match args.get(0).cloned().unwrap_or(Value::Null).as_object() {
+        Some(bytes) if bytes.as_bytearray().is_some() => Ok(bytes),
+        _ => Err(Error::AvmError(type_error(
+            activation,
+            "Error #2007: Parameter bytes must be non-null.",
+            2007,
+        )?)),
+    }
+}
+
 /// `Sound.loadCompressedDataFromByteArray`
 pub fn load_compressed_data_from_byte_array<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(
-        activation,
-        "flash.media.Sound",
-        "loadCompressedDataFromByteArray"
-    );
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let bytes = bytes_arg(activation, args)?;
+    let bytes_length = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)? as usize;
+
+    let handle = {
+        let bytes = bytes.as_bytearray().unwrap();
+        let data = bytes.read_bytes(bytes_length)?;
+        activation.context.audio.register_mp3(&data)
+    };
+    match handle {
+        Ok(handle) => {
+            this.set_sound(activation.context.gc_context, handle);
+            if let Some(sound_object) = this.as_sound_object() {
+                sound_object.set_extract_position(activation.context.gc_context, 0);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Sound.loadCompressedDataFromByteArray: {}", e);
+            activation
+                .context
+                .compatibility
+                .decoder_failed(MediaKind::Audio, &e);
+        }
+    }
+
     Ok(Value::Undefined)
 }
 
 /// `Sound.loadPCMFromByteArray`
 pub fn load_pcm_from_byte_array<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(activation, "flash.media.Sound", "loadPCMFromByteArray");
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let bytes = bytes_arg(activation, args)?;
+    let num_samples = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let format = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| "float".into())
+        .coerce_to_string(activation)?;
+    let is_stereo = args
+        .get(3)
+        .cloned()
+        .unwrap_or_else(|| true.into())
+        .coerce_to_boolean();
+    let sample_rate = args
+        .get(4)
+        .cloned()
+        .unwrap_or_else(|| 44100.into())
+        .coerce_to_number(activation)?;
+
+    let is_float = if &format == b"float" {
+        true
+    } else if &format == b"short" {
+        false
+    } else {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2008: Parameter format must be one of the accepted values.",
+            2008,
+        )?));
+    };
+    if !(1.0..=f64::from(u16::MAX)).contains(&sample_rate) {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2008: Parameter sampleRate must be one of the accepted values.",
+            2008,
+        )?));
+    }
+
+    // The samples are converted to 16-bit little-endian PCM, which is what the audio
+    // backends expect of uncompressed sounds.
+    let channels = if is_stereo { 2 } else { 1 };
+    let value_size = if is_float { 4 } else { 2 };
+    let (num_samples, data) = {
+        let bytes = bytes.as_bytearray().unwrap();
+        // Only the samples that are actually there are loaded.
+        let available = bytes.bytes_available() / (channels * value_size);
+        let num_samples = num_samples.min(u32::try_from(available).unwrap_or(u32::MAX));
+        let num_values = num_samples as usize * channels;
+        let mut data = Vec::with_capacity(num_values * 2);
+        for _ in 0..num_values {
+            let value = if is_float {
+                (bytes.read_float()?.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16
+            } else {
+                bytes.read_short()?
+            };
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        (num_samples, data)
+    };
+
+    let swf_sound = swf::Sound {
+        id: 0,
+        format: SoundFormat {
+            compression: AudioCompression::Uncompressed,
+            sample_rate: sample_rate as u16,
+            is_stereo,
+            is_16_bit: true,
+        },
+        num_samples,
+        data: &data,
+    };
+    match activation.context.audio.register_sound(&swf_sound) {
+        Ok(handle) => {
+            this.set_sound(activation.context.gc_context, handle);
+            if let Some(sound_object) = this.as_sound_object() {
+                sound_object.set_extract_position(activation.context.gc_context, 0);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Sound.loadPCMFromByteArray: {}", e);
+            activation
+                .context
+                .compatibility
+                .decoder_failed(MediaKind::Audio, &e);
+        }
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -276,10 +629,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         Option<NativeMethodImpl>,
         Option<NativeMethodImpl>,
     )] = &[
-        ("bytesLoaded", Some(bytes_total), None),
+        ("bytesLoaded", Some(bytes_loaded), None),
         ("bytesTotal", Some(bytes_total), None),
+        ("id3", Some(id3), None),
         ("isBuffering", Some(is_buffering), None),
-        ("isURLInaccessible", Some(is_buffering), None),
+        ("isURLInaccessible", Some(is_url_inaccessible), None),
         ("url", Some(url), None),
         ("length", Some(length), None),
     ];
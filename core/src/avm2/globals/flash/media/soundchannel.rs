@@ -88,15 +88,9 @@ pub fn sound_transform<'gc>(
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(instance) = this
-        .and_then(|this| this.as_sound_channel())
-        .and_then(|channel| channel.instance())
-    {
-        let dobj_st = activation.context.local_sound_transform(instance).cloned();
-
-        if let Some(dobj_st) = dobj_st {
-            return Ok(dobj_st.into_avm2_object(activation)?.into());
-        }
+    if let Some(channel) = this.and_then(|this| this.as_sound_channel()) {
+        let dobj_st = channel.sound_transform();
+        return Ok(dobj_st.into_avm2_object(activation)?.into());
     }
 
     Ok(Value::Undefined)
@@ -108,10 +102,7 @@ pub fn set_sound_transform<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(instance) = this
-        .and_then(|this| this.as_sound_channel())
-        .and_then(|channel| channel.instance())
-    {
+    if let Some(channel) = this.and_then(|this| this.as_sound_channel()) {
         let as3_st = args
             .get(0)
             .cloned()
@@ -119,9 +110,12 @@ pub fn set_sound_transform<'gc>(
             .coerce_to_object(activation)?;
         let dobj_st = SoundTransform::from_avm2_object(activation, as3_st)?;
 
-        activation
-            .context
-            .set_local_sound_transform(instance, dobj_st);
+        channel.set_sound_transform(activation.context.gc_context, dobj_st.clone());
+        if let Some(instance) = channel.instance() {
+            activation
+                .context
+                .set_local_sound_transform(instance, dobj_st);
+        }
     }
 
     Ok(Value::Undefined)
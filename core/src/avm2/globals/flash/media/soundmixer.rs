@@ -5,6 +5,7 @@ use std::cell::RefMut;
 use crate::avm2::activation::Activation;
 use crate::avm2::bytearray::ByteArrayStorage;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::error::type_error;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::Object;
 use crate::avm2::object::TObject;
@@ -134,7 +135,16 @@ pub fn compute_spectrum<'gc>(
     _this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let arg0 = args[0].as_object().unwrap();
+    let arg0 = match args.get(0).cloned().unwrap_or(Value::Null).as_object() {
+        Some(arg0) => arg0,
+        None => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #2007: Parameter outputArray must be non-null.",
+                2007,
+            )?))
+        }
+    };
     let mut bytearray: RefMut<ByteArrayStorage> = arg0
         .as_bytearray_mut(activation.context.gc_context)
         .unwrap();
@@ -0,0 +1,216 @@
+//! `flash.media.StageVideo` native methods
+
+use crate::avm2::globals::flash::display::displayobject::object_to_rectangle;
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, Error, Multiname, Object, Value};
+use crate::{avm2_stub_getter, avm2_stub_setter};
+
+pub use crate::avm2::object::stage_video_allocator;
+
+/// Implements `StageVideo.attachNetStream`.
+pub fn attach_net_stream<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let stream = match args.get(0) {
+            Some(Value::Object(object)) => Some(
+                object
+                    .as_netstream()
+                    .ok_or("StageVideo.attachNetStream: argument is not a NetStream")?
+                    .stream(),
+            ),
+            _ => None,
+        };
+
+        stage_video.set_stream(activation.context.gc_context, stream);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.viewPort`'s getter.
+pub fn get_view_port<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let view_port = stage_video.view_port();
+        let args = &[
+            view_port.x_min.to_pixels().into(),
+            view_port.y_min.to_pixels().into(),
+            view_port.width().to_pixels().into(),
+            view_port.height().to_pixels().into(),
+        ];
+        return Ok(activation
+            .avm2()
+            .classes()
+            .rectangle
+            .construct(activation, args)?
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.viewPort`'s setter.
+pub fn set_view_port<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        if let Some(rectangle) = args.get(0).and_then(|value| value.as_object()) {
+            let view_port = object_to_rectangle(activation, rectangle)?;
+            stage_video.set_view_port(activation.context.gc_context, view_port);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.videoWidth`'s getter.
+pub fn get_video_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let size = stage_video.stream().and_then(|stream| stream.video_size());
+        return Ok(size.map_or(0, |(width, _)| width).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.videoHeight`'s getter.
+pub fn get_video_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let size = stage_video.stream().and_then(|stream| stream.video_size());
+        return Ok(size.map_or(0, |(_, height)| height).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.depth`'s getter.
+pub fn get_depth<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        return Ok(stage_video.depth().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.depth`'s setter.
+pub fn set_depth<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let depth = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        stage_video.set_depth(activation.context.gc_context, depth);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.pan`'s getter.
+pub fn get_pan<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    avm2_stub_getter!(activation, "flash.media.StageVideo", "pan");
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        return point_to_object(activation, stage_video.pan());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.pan`'s setter.
+pub fn set_pan<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    avm2_stub_setter!(activation, "flash.media.StageVideo", "pan");
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let pan = object_to_point(activation, args.get(0))?;
+        stage_video.set_pan(activation.context.gc_context, pan);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.zoom`'s getter.
+pub fn get_zoom<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    avm2_stub_getter!(activation, "flash.media.StageVideo", "zoom");
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        return point_to_object(activation, stage_video.zoom());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `StageVideo.zoom`'s setter.
+pub fn set_zoom<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    avm2_stub_setter!(activation, "flash.media.StageVideo", "zoom");
+    if let Some(stage_video) = this.and_then(|this| this.as_stage_video()) {
+        let zoom = object_to_point(activation, args.get(0))?;
+        stage_video.set_zoom(activation.context.gc_context, zoom);
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn point_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    (x, y): (f64, f64),
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation
+        .avm2()
+        .classes()
+        .point
+        .construct(activation, &[x.into(), y.into()])?
+        .into())
+}
+
+fn object_to_point<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Option<&Value<'gc>>,
+) -> Result<(f64, f64), Error<'gc>> {
+    let point = value
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(activation)?;
+    let x = point
+        .get_property(&Multiname::public("x"), activation)?
+        .coerce_to_number(activation)?;
+    let y = point
+        .get_property(&Multiname::public("y"), activation)?
+        .coerce_to_number(activation)?;
+    Ok((x, y))
+}
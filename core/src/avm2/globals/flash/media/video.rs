@@ -2,23 +2,42 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
-use crate::avm2::method::Method;
-use crate::avm2::object::Object;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
+use crate::display_object::{TDisplayObject, Video};
 use gc_arena::{GcCell, MutationContext};
+use swf::VideoDeblocking;
 
 /// Implements `flash.media.Video`'s instance constructor.
 pub fn instance_init<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
         activation.super_init(this, &[])?;
+
+        if this.as_display_object().is_none() {
+            let width = args
+                .get(0)
+                .cloned()
+                .unwrap_or_else(|| 320.into())
+                .coerce_to_i32(activation)?;
+            let height = args
+                .get(1)
+                .cloned()
+                .unwrap_or_else(|| 240.into())
+                .coerce_to_i32(activation)?;
+
+            let new_do = Video::new_with_avm2(&mut activation.context, width, height, this);
+
+            this.init_display_object(activation.context.gc_context, new_do.into());
+        }
     }
 
     Ok(Value::Undefined)
@@ -33,6 +52,178 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Video.attachNetStream`.
+pub fn attach_net_stream<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        let stream = match args.get(0) {
+            Some(Value::Object(object)) => Some(
+                object
+                    .as_netstream()
+                    .ok_or("Video.attachNetStream: argument is not a NetStream")?
+                    .stream(),
+            ),
+            _ => None,
+        };
+
+        video.attach_netstream(&mut activation.context, stream);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Video.attachCamera`.
+pub fn attach_camera<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        let camera = match args.get(0) {
+            Some(Value::Object(object)) => Some(
+                object
+                    .as_camera()
+                    .ok_or("Video.attachCamera: argument is not a Camera")?,
+            ),
+            _ => None,
+        };
+
+        video.attach_camera(&mut activation.context, camera);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Video.clear`.
+pub fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        video.clear(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Video.videoWidth`.
+pub fn video_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        return Ok(video.video_size().0.into());
+    }
+
+    Ok(0.into())
+}
+
+/// Implements `Video.videoHeight`.
+pub fn video_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        return Ok(video.video_size().1.into());
+    }
+
+    Ok(0.into())
+}
+
+/// Implements `Video.smoothing`'s getter.
+pub fn smoothing<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        return Ok(video.smoothing().into());
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `Video.smoothing`'s setter.
+pub fn set_smoothing<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        let smoothing = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        video.set_smoothing(activation.context.gc_context, smoothing);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Video.deblocking`'s getter.
+pub fn deblocking<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        return Ok((video.deblocking() as i32).into());
+    }
+
+    Ok(0.into())
+}
+
+/// Implements `Video.deblocking`'s setter.
+pub fn set_deblocking<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_video())
+    {
+        let deblocking = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation)?;
+        let deblocking = u8::try_from(deblocking)
+            .ok()
+            .and_then(VideoDeblocking::from_u8)
+            .unwrap_or(VideoDeblocking::UseVideoPacketValue);
+        video.set_deblocking(&mut activation.context, deblocking);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `Video`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -50,5 +241,24 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     write.set_attributes(ClassAttributes::SEALED);
 
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("videoWidth", Some(video_width), None),
+        ("videoHeight", Some(video_height), None),
+        ("smoothing", Some(smoothing), Some(set_smoothing)),
+        ("deblocking", Some(deblocking), Some(set_deblocking)),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("attachCamera", attach_camera),
+        ("attachNetStream", attach_net_stream),
+        ("clear", clear),
+    ];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
     class
 }
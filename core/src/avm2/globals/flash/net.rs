@@ -1,11 +1,88 @@
 //! `flash.net` namespace
 
+use crate::avm2::error::{reference_error, type_error};
 use crate::avm2::object::TObject;
 use crate::avm2::{Activation, Error, Multiname, Object, Value};
 
+pub mod datagram_socket;
+pub mod file_reference;
+pub mod file_reference_list;
+pub mod local_connection;
+pub mod net_connection;
+pub mod net_stream;
 pub mod object_encoding;
+pub mod server_socket;
 pub mod shared_object;
+pub mod socket;
 pub mod url_loader;
+pub mod url_stream;
+pub mod xml_socket;
+
+/// Implements `flash.net.registerClassAlias`
+pub fn register_class_alias<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0).unwrap_or(&Value::Null) {
+        Value::Null | Value::Undefined => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #2007: Parameter aliasName must be non-null.",
+                2007,
+            )?));
+        }
+        name => name.coerce_to_string(activation)?,
+    };
+    let class_object = match args
+        .get(1)
+        .and_then(|class| class.as_object())
+        .and_then(|class| class.as_class_object())
+    {
+        Some(class_object) => class_object,
+        None => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #2007: Parameter classObject must be non-null.",
+                2007,
+            )?));
+        }
+    };
+
+    activation
+        .context
+        .avm2
+        .register_class_alias(name, class_object);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.net.getClassByAlias`
+pub fn get_class_by_alias<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0).unwrap_or(&Value::Null) {
+        Value::Null | Value::Undefined => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #2007: Parameter aliasName must be non-null.",
+                2007,
+            )?));
+        }
+        name => name.coerce_to_string(activation)?,
+    };
+
+    match activation.avm2().get_class_by_alias(name) {
+        Some(class_object) => Ok(class_object.into()),
+        None => Err(Error::AvmError(reference_error(
+            activation,
+            &format!("Error #1014: Class {name} could not be found."),
+            1014,
+        )?)),
+    }
+}
 
 /// Implements `flash.net.navigateToURL`
 pub fn navigate_to_url<'gc>(
@@ -29,7 +106,6 @@ pub fn navigate_to_url<'gc>(
 
     activation
         .context
-        .navigator
         .navigate_to_url(url.to_string(), target.to_string(), None);
 
     Ok(Value::Undefined)
@@ -0,0 +1,116 @@
+package flash.net {
+    import flash.errors.IllegalOperationError;
+    import flash.errors.IOError;
+    import flash.events.DatagramSocketDataEvent;
+    import flash.events.EventDispatcher;
+    import flash.utils.ByteArray;
+
+
+    public class DatagramSocket extends EventDispatcher {
+        // The remote end that the socket is connected to, if any.
+        private var _remoteAddress: String = null;
+        private var _remotePort: int = 0;
+
+        public static native function get isSupported(): Boolean;
+
+        public function DatagramSocket() {
+        }
+
+        public native function get bound(): Boolean;
+        public native function get localAddress(): String;
+        public native function get localPort(): int;
+
+        public function get connected(): Boolean {
+            return this._remoteAddress != null;
+        }
+
+        public function get remoteAddress(): String {
+            return this._remoteAddress;
+        }
+
+        public function get remotePort(): int {
+            return this._remotePort;
+        }
+
+        public function bind(localPort: int = 0, localAddress: String = "0.0.0.0"): void {
+            if (this.bound) {
+                throw new IllegalOperationError("Error #2037: Functions called in incorrect sequence, or earlier call was unsuccessful.", 2037);
+            }
+            checkPort(localPort);
+
+            if (!this.bindSocket(localPort, localAddress)) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        // Only the datagrams of the remote end are received once connected.
+        public function connect(remoteAddress: String, remotePort: int): void {
+            if (this.connected) {
+                throw new IllegalOperationError("Error #2037: Functions called in incorrect sequence, or earlier call was unsuccessful.", 2037);
+            }
+            checkPort(remotePort);
+
+            if (!this.bound) {
+                this.bind();
+            }
+            this._remoteAddress = remoteAddress;
+            this._remotePort = remotePort;
+        }
+
+        public function receive(): void {
+            if (!this.receiveDatagrams()) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        public function send(bytes: ByteArray, offset: uint = 0, length: uint = 0, address: String = null, port: int = 0): void {
+            if (this.connected) {
+                if (address != null) {
+                    throw new IllegalOperationError("Error #2037: Functions called in incorrect sequence, or earlier call was unsuccessful.", 2037);
+                }
+                address = this._remoteAddress;
+                port = this._remotePort;
+            } else if (address == null) {
+                throw new IllegalOperationError("Error #2037: Functions called in incorrect sequence, or earlier call was unsuccessful.", 2037);
+            }
+            checkPort(port);
+            if (offset > bytes.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+
+            if (!this.bound) {
+                this.bind();
+            }
+            var datagram: ByteArray = new ByteArray();
+            datagram.writeBytes(bytes, offset, length);
+            this.sendDatagram(datagram, address, port);
+        }
+
+        public function close(): void {
+            if (!this.closeSocket()) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+            this._remoteAddress = null;
+            this._remotePort = 0;
+        }
+
+        private static function checkPort(port: int): void {
+            if (port < 0 || port > 65535) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+        }
+
+        private native function bindSocket(port: int, address: String): Boolean;
+        private native function receiveDatagrams(): Boolean;
+        private native function sendDatagram(data: ByteArray, address: String, port: int): void;
+        private native function closeSocket(): Boolean;
+
+        ruffle function receiveDatagram(data: ByteArray, address: String, port: int): void {
+            if (this.connected && (address != this._remoteAddress || port != this._remotePort)) {
+                return;
+            }
+
+            this.dispatchEvent(new DatagramSocketDataEvent(DatagramSocketDataEvent.DATA, false, false, address, port, this.localAddress, this.localPort, data));
+        }
+    }
+}
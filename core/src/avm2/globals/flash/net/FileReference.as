@@ -1,89 +1,103 @@
 package flash.net
 {
+    import flash.errors.IllegalOperationError;
+    import flash.events.Event;
     import flash.events.EventDispatcher;
+    import flash.events.ProgressEvent;
     import flash.utils.ByteArray;
+
+
     public class FileReference extends EventDispatcher
     {
-        private var _creationDate: Date;
         private var _creator: String;
         private var _data: ByteArray;
-        private var _extension: String;
-        private var _modificationDate: Date;
-        private var _name: String;
         private static var _permissionStatus: String;
-        private var _size: Number;
-        private var _type: String;
+
+        // Set when the user picks a file, or a location to save to.
+        ruffle var _creationDate: Date;
+        ruffle var _extension: String;
+        ruffle var _modificationDate: Date;
+        ruffle var _name: String;
+        ruffle var _size: Number;
+        ruffle var _type: String;
+
+        // The contents of the picked file, exposed as `data` once loaded.
+        ruffle var _fileData: ByteArray;
 
         public function FileReference() {
-            
+
         }
 
         public function get creationDate(): Date {
-            return this._creationDate;
-        }   
+            return this.ruffle::_creationDate;
+        }
 
         public function get creator(): String {
-            retunr this._creator;
-        }   
+            return this._creator;
+        }
 
         public function get data(): ByteArray {
             return this._data;
-        }   
+        }
 
         public function get extension(): String {
-            return this._extension;
-        }   
+            return this.ruffle::_extension;
+        }
 
         public function get modificationDate(): Date {
-            return this._modificationDate;
-        }   
+            return this.ruffle::_modificationDate;
+        }
 
         public function get name(): String {
-            retunr this._name;
-        }   
+            return this.ruffle::_name;
+        }
 
         public static function get permissionStatus(): String {
             return FileReference._permissionStatus;
-        }   
+        }
 
         public function get size(): Number {
-            return this._size;
-        }   
+            return this.ruffle::_size;
+        }
 
         public function get type(): String {
-            return this._type;
-        }   
+            return this.ruffle::_type;
+        }
 
-        public function browse(typeFilter:Array = null):Boolean {
-            return false;
-        }   
+        public native function browse(typeFilter:Array = null):Boolean;
 
-        public function cancel():void { 
+        public function cancel():void {
             throw new Error("FileReference.cancel() is not yet implemented!");
-        }   
+        }
 
-        public function download(request:URLRequest, defaultFileName:String = null):void {  
+        public function download(request:URLRequest, defaultFileName:String = null):void {
             throw new Error("FileReference.download() is not yet implemented!");
-        }   
+        }
+
+        public function load():void {
+            var fileData: ByteArray = this.ruffle::_fileData;
+            if (fileData == null) {
+                throw new IllegalOperationError("Error #2037: Functions called in incorrect sequence, or earlier call was unsuccessful.", 2037);
+            }
 
-        public function load():void {   
-            throw new Error("FileReference.load() is not yet implemented!");
-        }   
+            this._data = fileData;
+            this.dispatchEvent(new Event(Event.OPEN));
+            this.dispatchEvent(new ProgressEvent(ProgressEvent.PROGRESS, false, false, fileData.length, fileData.length));
+            this.dispatchEvent(new Event(Event.COMPLETE));
+        }
 
-        public function requestPermission():void {  
+        public function requestPermission():void {
             throw new Error("FileReference.requestPermission() is not yet implemented!");
-        }   
+        }
 
-        public function save(data:*, defaultFileName:String = null):void {  
-            throw new Error("FileReference.save() is not yet implemented!");
-        }   
+        public native function save(data:*, defaultFileName:String = null):void;
 
-        public function upload(request:URLRequest, uploadDataFieldName:String = "Filedata", testUpload:Boolean = false):void {  
+        public function upload(request:URLRequest, uploadDataFieldName:String = "Filedata", testUpload:Boolean = false):void {
             throw new Error("FileReference.upload() is not yet implemented!");
-        }   
+        }
 
-        public function uploadUnencoded(request:URLRequest):void {  
+        public function uploadUnencoded(request:URLRequest):void {
             throw new Error("FileReference.uploadUnencoded() is not yet implemented!");
         }
     }
-}
\ No newline at end of file
+}
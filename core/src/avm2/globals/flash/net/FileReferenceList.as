@@ -1,22 +1,22 @@
 package flash.net
 {
     import flash.events.EventDispatcher;
+
+
     public class FileReferenceList extends EventDispatcher
     {
-        private var _fileList:Array;
+        // The `FileReference`s of the files picked by the user.
+        ruffle var _fileList:Array;
+
         public function FileReferenceList()
         {
-            _fileList = new Array();
         }
 
         public function get fileList():Array
         {
-            return this._fileList;
+            return this.ruffle::_fileList;
         }
 
-        public function browse(typeFilter:Array = null):Boolean
-        {
-            throw new Error("FileReferenceList.browse() is not yet implemented!");
-        }
+        public native function browse(typeFilter:Array = null):Boolean;
     }
-}
\ No newline at end of file
+}
@@ -1,30 +1,53 @@
 package flash.net {
+    import flash.events.AsyncErrorEvent;
     import flash.events.EventDispatcher;
-    import __ruffle__.log_warn;
+    import flash.events.StatusEvent;
+
 
-    // NOTE: this entire class is a stub.
-    // Thankfully (hopefully) a lot of code like Mochicrypt doesn't actually require this to... well do anything.
     public class LocalConnection extends EventDispatcher {
 
         public var client: Object;
 
+        // The domains allowed to send messages to this connection, besides its own.
+        private var _allowedDomains: Array = [];
+
         public function LocalConnection() {
             this.client = this;
         }
 
-        public function get domain():String {
-            // FIXME - implement this - this is unrelated to the messaging functionality.
-            return "localhost";
+        public native function get domain():String;
+
+        public native function close(): void;
+        public native function connect(connectionName:String): void;
+        public native function send(connectionName: String, methodName: String, ... arguments): void;
+
+        public function allowDomain(... domains): void {
+            for each (var domain: * in domains) {
+                this._allowedDomains.push(String(domain));
+            }
         }
 
-        public function close(): void {}
-        public function connect(connectionName:String): void {
-            log_warn("LocalConnection.connect is not implemented");
+        public function allowInsecureDomain(... domains): void {
+            this.allowDomain.apply(this, domains);
         }
 
-        public function send(connectionName: String, methodName: String, ... arguments): void {}
+        ruffle function receiveMessage(senderDomain: String, methodName: String, args: Array): void {
+            if (senderDomain != this.domain &&
+                this._allowedDomains.indexOf(senderDomain) == -1 &&
+                this._allowedDomains.indexOf("*") == -1) {
+                return;
+            }
 
-        public function allowDomain(... domains): void {}
-        public function allowInsecureDomain(... domains): void {}
+            try {
+                this.client[methodName].apply(this.client, args);
+            } catch (error: Error) {
+                var text: String = "Error #2095: flash.net.LocalConnection was unable to invoke callback " + methodName + ".";
+                this.dispatchEvent(new AsyncErrorEvent(AsyncErrorEvent.ASYNC_ERROR, false, false, text, error));
+            }
+        }
+
+        ruffle function dispatchStatus(delivered: Boolean): void {
+            this.dispatchEvent(new StatusEvent(StatusEvent.STATUS, false, false, null, delivered ? "status" : "error"));
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,64 @@
+package flash.net {
+    import flash.events.EventDispatcher;
+    import flash.events.NetStatusEvent;
+    import __ruffle__.log_warn;
+
+    [Ruffle(InstanceAllocator)]
+    public class NetConnection extends EventDispatcher {
+        public var client: Object;
+        public var objectEncoding: uint = 3;
+
+        private var _connected: Boolean = false;
+        private var _uri: String = null;
+
+        public function NetConnection() {
+            this.client = this;
+        }
+
+        public function get connected():Boolean {
+            return this._connected || this.isRtmpConnected();
+        }
+
+        public function get uri():String {
+            return this._uri;
+        }
+
+        public function connect(command:String, ... arguments):void {
+            // "Connections" to nothing are used for progressive downloads of
+            // video files, and RTMP connections for streams of media servers.
+            if (command == null || command == "null") {
+                this._connected = true;
+                this._uri = "null";
+                this.dispatchEvent(new NetStatusEvent(NetStatusEvent.NET_STATUS, false, false, {
+                    "code": "NetConnection.Connect.Success",
+                    "level": "status"
+                }));
+            } else if (this.connectRtmp(command)) {
+                // The result is reported once the server answers.
+                this._uri = command;
+            } else {
+                log_warn("NetConnection.connect is only implemented for RTMP servers");
+                this._uri = command;
+                this.dispatchEvent(new NetStatusEvent(NetStatusEvent.NET_STATUS, false, false, {
+                    "code": "NetConnection.Connect.Failed",
+                    "level": "error"
+                }));
+            }
+        }
+
+        public function close():void {
+            this.closeRtmp();
+            if (this._connected) {
+                this._connected = false;
+                this.dispatchEvent(new NetStatusEvent(NetStatusEvent.NET_STATUS, false, false, {
+                    "code": "NetConnection.Connect.Closed",
+                    "level": "status"
+                }));
+            }
+        }
+
+        private native function connectRtmp(url:String):Boolean;
+        private native function isRtmpConnected():Boolean;
+        private native function closeRtmp():void;
+    }
+}
@@ -0,0 +1,33 @@
+package flash.net {
+    import flash.events.EventDispatcher;
+    import flash.utils.ByteArray;
+
+    [Ruffle(InstanceAllocator)]
+    public class NetStream extends EventDispatcher {
+        public var checkPolicyFile: Boolean = false;
+        public var client: Object;
+
+        public function NetStream(connection:NetConnection, peerID:String = "connectToFMS") {
+            this.client = this;
+            this.setConnection(connection);
+        }
+
+        private native function setConnection(connection:NetConnection):void;
+
+        public native function get bufferLength():Number;
+        public native function get bufferTime():Number;
+        public native function set bufferTime(bufferTime:Number):void;
+        public native function get bytesLoaded():uint;
+        public native function get bytesTotal():uint;
+        public native function get time():Number;
+
+        public native function play(... arguments):void;
+        public native function pause():void;
+        public native function resume():void;
+        public native function togglePause():void;
+        public native function seek(offset:Number):void;
+        public native function close():void;
+        public native function appendBytes(bytes:ByteArray):void;
+        public native function appendBytesAction(netStreamAppendBytesAction:String):void;
+    }
+}
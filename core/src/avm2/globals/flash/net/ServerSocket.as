@@ -0,0 +1,57 @@
+package flash.net {
+    import flash.errors.IllegalOperationError;
+    import flash.errors.IOError;
+    import flash.events.EventDispatcher;
+    import flash.events.ServerSocketConnectEvent;
+
+
+    public class ServerSocket extends EventDispatcher {
+        public static native function get isSupported(): Boolean;
+
+        public function ServerSocket() {
+        }
+
+        public native function get bound(): Boolean;
+        public native function get listening(): Boolean;
+        public native function get localAddress(): String;
+        public native function get localPort(): int;
+
+        public function bind(localPort: int = 0, localAddress: String = "0.0.0.0"): void {
+            if (this.bound) {
+                throw new IllegalOperationError("Error #2037: Functions called in incorrect sequence, or earlier call was unsuccessful.", 2037);
+            }
+            if (localPort < 0 || localPort > 65535) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+
+            if (!this.bindSocket(localPort, localAddress)) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        // The backlog of pending connections is left to the system.
+        public function listen(backlog: int = 0): void {
+            if (backlog < 0) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+
+            if (!this.listenSocket()) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        public function close(): void {
+            if (!this.closeSocket()) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        private native function bindSocket(port: int, address: String): Boolean;
+        private native function listenSocket(): Boolean;
+        private native function closeSocket(): Boolean;
+
+        ruffle function receiveSocket(socket: Socket): void {
+            this.dispatchEvent(new ServerSocketConnectEvent(ServerSocketConnectEvent.CONNECT, false, false, socket));
+        }
+    }
+}
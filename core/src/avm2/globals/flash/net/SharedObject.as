@@ -10,12 +10,10 @@ package flash.net
       public function SharedObject()
       {
          this.data = {};
+         this.objectEncoding = defaultObjectEncoding;
       }
 
-      // NOTE: We currently always use AMF3 serialization.
-      // If you implement the `defaultObjectEncoding` or `objectEncoding`,
-      // you will need to adjust the serialization and deserialization code
-      // to work with AMF0.
+      public static var defaultObjectEncoding:uint = 3;
 
       native public static function getLocal(name:String, localPath:String = null, secure:Boolean = false): SharedObject;
 
@@ -29,6 +27,9 @@ package flash.net
       // note: this is supposed to be a read-only property
       public var size: uint;
 
+      // The AMF version used when this object is flushed to disk.
+      public var objectEncoding: uint;
+
       ruffle var _ruffleName: String;
    }
 }
@@ -0,0 +1,255 @@
+package flash.net {
+    import flash.errors.IOError;
+    import flash.events.Event;
+    import flash.events.EventDispatcher;
+    import flash.events.IOErrorEvent;
+    import flash.events.ProgressEvent;
+    import flash.events.SecurityErrorEvent;
+    import flash.utils.ByteArray;
+    import flash.utils.IDataInput;
+    import flash.utils.IDataOutput;
+
+
+    public class Socket extends EventDispatcher implements IDataInput, IDataOutput {
+        // The data received and not read yet.
+        private var _input: ByteArray = new ByteArray();
+
+        // The data written since the last flush.
+        private var _output: ByteArray = new ByteArray();
+
+        private var _timeout: uint = 20000;
+
+        public function Socket(host: String = null, port: int = 0) {
+            if (host != null) {
+                this.connect(host, port);
+            }
+        }
+
+        public native function get connected(): Boolean;
+
+        public function get bytesAvailable(): uint {
+            return this._input.bytesAvailable;
+        }
+
+        public function get endian(): String {
+            return this._input.endian;
+        }
+
+        public function set endian(value: String): void {
+            this._input.endian = value;
+            this._output.endian = value;
+        }
+
+        public function get objectEncoding(): uint {
+            return this._input.objectEncoding;
+        }
+
+        public function set objectEncoding(value: uint): void {
+            this._input.objectEncoding = value;
+            this._output.objectEncoding = value;
+        }
+
+        public function get timeout(): uint {
+            return this._timeout;
+        }
+
+        public function set timeout(value: uint): void {
+            this._timeout = value;
+        }
+
+        public function connect(host: String, port: int): void {
+            if (port < 0 || port > 65535) {
+                throw new SecurityError("Error #2003: Invalid socket port number specified.", 2003);
+            }
+
+            this._input.clear();
+            this._output.clear();
+            this.connectSocket(host, port, this._timeout);
+        }
+
+        public function close(): void {
+            if (!this.closeSocket()) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        // Data is only sent when flushed, as on Windows.
+        public function flush(): void {
+            this.checkConnected();
+            if (this._output.length > 0) {
+                this.sendData(this._output);
+                this._output.clear();
+            }
+        }
+
+        private native function connectSocket(host: String, port: int, timeout: uint): void;
+        private native function closeSocket(): Boolean;
+        private native function sendData(data: ByteArray): void;
+
+        private function checkConnected(): void {
+            if (!this.connected) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        public function readBoolean(): Boolean {
+            this.checkConnected();
+            return this._input.readBoolean();
+        }
+
+        public function readByte(): int {
+            this.checkConnected();
+            return this._input.readByte();
+        }
+
+        public function readBytes(bytes: ByteArray, offset: uint = 0, length: uint = 0): void {
+            this.checkConnected();
+            this._input.readBytes(bytes, offset, length);
+        }
+
+        public function readDouble(): Number {
+            this.checkConnected();
+            return this._input.readDouble();
+        }
+
+        public function readFloat(): Number {
+            this.checkConnected();
+            return this._input.readFloat();
+        }
+
+        public function readInt(): int {
+            this.checkConnected();
+            return this._input.readInt();
+        }
+
+        public function readMultiByte(length: uint, charSet: String): String {
+            this.checkConnected();
+            return this._input.readMultiByte(length, charSet);
+        }
+
+        public function readObject(): * {
+            this.checkConnected();
+            return this._input.readObject();
+        }
+
+        public function readShort(): int {
+            this.checkConnected();
+            return this._input.readShort();
+        }
+
+        public function readUnsignedByte(): uint {
+            this.checkConnected();
+            return this._input.readUnsignedByte();
+        }
+
+        public function readUnsignedInt(): uint {
+            this.checkConnected();
+            return this._input.readUnsignedInt();
+        }
+
+        public function readUnsignedShort(): uint {
+            this.checkConnected();
+            return this._input.readUnsignedShort();
+        }
+
+        public function readUTF(): String {
+            this.checkConnected();
+            return this._input.readUTF();
+        }
+
+        public function readUTFBytes(length: uint): String {
+            this.checkConnected();
+            return this._input.readUTFBytes(length);
+        }
+
+        public function writeBoolean(value: Boolean): void {
+            this.checkConnected();
+            this._output.writeBoolean(value);
+        }
+
+        public function writeByte(value: int): void {
+            this.checkConnected();
+            this._output.writeByte(value);
+        }
+
+        public function writeBytes(bytes: ByteArray, offset: uint = 0, length: uint = 0): void {
+            this.checkConnected();
+            this._output.writeBytes(bytes, offset, length);
+        }
+
+        public function writeDouble(value: Number): void {
+            this.checkConnected();
+            this._output.writeDouble(value);
+        }
+
+        public function writeFloat(value: Number): void {
+            this.checkConnected();
+            this._output.writeFloat(value);
+        }
+
+        public function writeInt(value: int): void {
+            this.checkConnected();
+            this._output.writeInt(value);
+        }
+
+        public function writeMultiByte(value: String, charSet: String): void {
+            this.checkConnected();
+            this._output.writeMultiByte(value, charSet);
+        }
+
+        public function writeObject(object: *): void {
+            this.checkConnected();
+            this._output.writeObject(object);
+        }
+
+        public function writeShort(value: int): void {
+            this.checkConnected();
+            this._output.writeShort(value);
+        }
+
+        public function writeUnsignedInt(value: uint): void {
+            this.checkConnected();
+            this._output.writeUnsignedInt(value);
+        }
+
+        public function writeUTF(value: String): void {
+            this.checkConnected();
+            this._output.writeUTF(value);
+        }
+
+        public function writeUTFBytes(value: String): void {
+            this.checkConnected();
+            this._output.writeUTFBytes(value);
+        }
+
+        ruffle function receiveData(data: ByteArray): void {
+            // Drop the data that was read already, so that the buffer doesn't keep growing.
+            if (this._input.bytesAvailable == 0) {
+                this._input.clear();
+            }
+
+            var position: uint = this._input.position;
+            this._input.position = this._input.length;
+            this._input.writeBytes(data);
+            this._input.position = position;
+
+            this.dispatchEvent(new ProgressEvent(ProgressEvent.SOCKET_DATA, false, false, data.length, 0));
+        }
+
+        ruffle function dispatchConnect(): void {
+            this.dispatchEvent(new Event(Event.CONNECT));
+        }
+
+        ruffle function dispatchClose(): void {
+            this.dispatchEvent(new Event(Event.CLOSE));
+        }
+
+        ruffle function dispatchIOError(text: String): void {
+            this.dispatchEvent(new IOErrorEvent(IOErrorEvent.IO_ERROR, false, false, text, 2031));
+        }
+
+        ruffle function dispatchSecurityError(text: String): void {
+            this.dispatchEvent(new SecurityErrorEvent(SecurityErrorEvent.SECURITY_ERROR, false, false, text, 2048));
+        }
+    }
+}
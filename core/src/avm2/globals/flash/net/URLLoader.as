@@ -1,9 +1,16 @@
 package flash.net {
 	import flash.events.EventDispatcher;
+	import flash.events.HTTPStatusEvent;
+	import flash.events.ProgressEvent;
 	import flash.net.URLRequest;
+	import flash.net.URLRequestHeader;
+
+
 	public class URLLoader extends EventDispatcher {
 		public var data: *;
 		public var dataFormat: String = "text";
+		public var bytesLoaded: uint = 0;
+		public var bytesTotal: uint = 0;
 
 		public function URLLoader(request:URLRequest = null) {
 			if (request != null) {
@@ -11,19 +18,29 @@ package flash.net {
 			}
 		}
 
-		// FIXME - this should be a normal property for consistency with Flash
-		public function get bytesTotal():uint {
-			if (this.data) {
-				return this.data.length;
-			}
-			return 0;
+		public function load(request:URLRequest):void {
+			this.bytesLoaded = 0;
+			this.bytesTotal = 0;
+			this.loadRequest(request);
 		}
 
-		// FIXME - this should be a normal property for consistency with Flash
-		public function get bytesLoaded():uint {
-			// TODO - update this as the download progresses
-			return this.bytesTotal
+		private native function loadRequest(request:URLRequest):void;
+
+		ruffle function receiveProgress(bytesLoaded:Number, bytesTotal:Number):void {
+			this.bytesLoaded = bytesLoaded;
+			this.bytesTotal = bytesTotal;
+			this.dispatchEvent(new ProgressEvent(ProgressEvent.PROGRESS, false, false, bytesLoaded, bytesTotal));
+		}
+
+		// The headers are given as a flat list of names and values.
+		ruffle function dispatchHttpStatus(type:String, status:int, url:String, headers:Array):void {
+			var event:HTTPStatusEvent = new HTTPStatusEvent(type, false, false, status);
+			event.responseURL = url;
+			event.responseHeaders = [];
+			for (var i:int = 0; i + 1 < headers.length; i += 2) {
+				event.responseHeaders.push(new URLRequestHeader(headers[i], headers[i + 1]));
+			}
+			this.dispatchEvent(event);
 		}
-		public native function load(request:URLRequest):void;
 	}
 }
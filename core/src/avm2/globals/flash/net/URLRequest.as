@@ -1,18 +1,17 @@
 package flash.net {
-
-	import __ruffle__.log_warn;
-
 	public final class URLRequest {
 		// NOTE - when implementing properties (e.g. `contentType`, `data`, etc.)
-		// be sure to also check for them in `URLLoader`
+		// be sure to also check for them in `request_from_url_request`
 
 		// FIXME - this should be a getter/setter for consistency with Flash
 		public var url:String;
-		private var _contentType: String = "application/x-www-form-urlencoded"; // ignored
+		private var _contentType: String = "application/x-www-form-urlencoded";
 
 		public var digest:String;
+		public var followRedirects:Boolean = true;
 		private var _method:String = URLRequestMethod.GET;
 		private var _data:Object;
+		private var _requestHeaders:Array = [];
 
 		public function URLRequest(url:String = null) {
 			this.url = url;
@@ -32,21 +31,23 @@ package flash.net {
 		}
 
 		public function set data(newData:Object):void {
-			if (newData !== null) {
-				log_warn("URLRequest.data setter is not yet implemented");
-			}
 			this._data = newData;
 		}
 
 		public function set contentType(value:String):void {
-			if (value !== this._contentType) {
-				log_warn("URLRequest.contentType setter is not yet implemented");
-			}
 			this._contentType = value;
 		}
 
 		public function get contentType():String {
 			return this._contentType;
 		}
+
+		public function get requestHeaders():Array {
+			return this._requestHeaders;
+		}
+
+		public function set requestHeaders(value:Array):void {
+			this._requestHeaders = value;
+		}
 	}
 }
@@ -0,0 +1,172 @@
+package flash.net {
+    import flash.errors.IOError;
+    import flash.events.Event;
+    import flash.events.EventDispatcher;
+    import flash.events.IOErrorEvent;
+    import flash.events.ProgressEvent;
+    import flash.events.SecurityErrorEvent;
+    import flash.utils.ByteArray;
+    import flash.utils.IDataInput;
+
+
+    public class URLStream extends EventDispatcher implements IDataInput {
+        // The data received and not read yet.
+        private var _input: ByteArray = new ByteArray();
+
+        private var _connected: Boolean = false;
+
+        public function URLStream() {
+        }
+
+        public function get connected(): Boolean {
+            return this._connected;
+        }
+
+        public function get bytesAvailable(): uint {
+            return this._input.bytesAvailable;
+        }
+
+        public function get endian(): String {
+            return this._input.endian;
+        }
+
+        public function set endian(value: String): void {
+            this._input.endian = value;
+        }
+
+        public function get objectEncoding(): uint {
+            return this._input.objectEncoding;
+        }
+
+        public function set objectEncoding(value: uint): void {
+            this._input.objectEncoding = value;
+        }
+
+        public function load(request: URLRequest): void {
+            if (this._connected) {
+                this.cancelLoad();
+            }
+
+            this._input.clear();
+            this._connected = true;
+            this.loadStream(request);
+        }
+
+        public function close(): void {
+            this.checkConnected();
+            this._connected = false;
+            this.cancelLoad();
+        }
+
+        private native function loadStream(request: URLRequest): void;
+        private native function cancelLoad(): void;
+
+        private function checkConnected(): void {
+            if (!this._connected) {
+                throw new IOError("Error #2029: This URLStream object does not have a stream opened.", 2029);
+            }
+        }
+
+        public function readBoolean(): Boolean {
+            this.checkConnected();
+            return this._input.readBoolean();
+        }
+
+        public function readByte(): int {
+            this.checkConnected();
+            return this._input.readByte();
+        }
+
+        public function readBytes(bytes: ByteArray, offset: uint = 0, length: uint = 0): void {
+            this.checkConnected();
+            this._input.readBytes(bytes, offset, length);
+        }
+
+        public function readDouble(): Number {
+            this.checkConnected();
+            return this._input.readDouble();
+        }
+
+        public function readFloat(): Number {
+            this.checkConnected();
+            return this._input.readFloat();
+        }
+
+        public function readInt(): int {
+            this.checkConnected();
+            return this._input.readInt();
+        }
+
+        public function readMultiByte(length: uint, charSet: String): String {
+            this.checkConnected();
+            return this._input.readMultiByte(length, charSet);
+        }
+
+        public function readObject(): * {
+            this.checkConnected();
+            return this._input.readObject();
+        }
+
+        public function readShort(): int {
+            this.checkConnected();
+            return this._input.readShort();
+        }
+
+        public function readUnsignedByte(): uint {
+            this.checkConnected();
+            return this._input.readUnsignedByte();
+        }
+
+        public function readUnsignedInt(): uint {
+            this.checkConnected();
+            return this._input.readUnsignedInt();
+        }
+
+        public function readUnsignedShort(): uint {
+            this.checkConnected();
+            return this._input.readUnsignedShort();
+        }
+
+        public function readUTF(): String {
+            this.checkConnected();
+            return this._input.readUTF();
+        }
+
+        public function readUTFBytes(length: uint): String {
+            this.checkConnected();
+            return this._input.readUTFBytes(length);
+        }
+
+        ruffle function receiveData(data: ByteArray, bytesLoaded: Number, bytesTotal: Number): void {
+            // Drop the data that was read already, so that the buffer doesn't keep growing.
+            if (this._input.bytesAvailable == 0) {
+                this._input.clear();
+            }
+
+            var position: uint = this._input.position;
+            this._input.position = this._input.length;
+            this._input.writeBytes(data);
+            this._input.position = position;
+
+            this.dispatchEvent(new ProgressEvent(ProgressEvent.PROGRESS, false, false, bytesLoaded, bytesTotal));
+        }
+
+        ruffle function dispatchOpen(): void {
+            this.dispatchEvent(new Event(Event.OPEN));
+        }
+
+        ruffle function dispatchComplete(): void {
+            this.dispatchEvent(new Event(Event.COMPLETE));
+        }
+
+        ruffle function dispatchIOError(): void {
+            this._connected = false;
+            this.dispatchEvent(new IOErrorEvent(IOErrorEvent.IO_ERROR, false, false, "Error #2032: Stream Error", 2032));
+        }
+
+        ruffle function dispatchSecurityError(text: String): void {
+            this._connected = false;
+            this.dispatchEvent(new SecurityErrorEvent(SecurityErrorEvent.SECURITY_ERROR, false, false, text, 2048));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+package flash.net {
+    import flash.errors.IOError;
+    import flash.events.DataEvent;
+    import flash.events.Event;
+    import flash.events.EventDispatcher;
+    import flash.events.IOErrorEvent;
+    import flash.events.SecurityErrorEvent;
+
+
+    public class XMLSocket extends EventDispatcher {
+        private var _timeout: int = 20000;
+
+        public function XMLSocket(host: String = null, port: int = 0) {
+            if (host != null) {
+                this.connect(host, port);
+            }
+        }
+
+        public native function get connected(): Boolean;
+
+        public function get timeout(): int {
+            return this._timeout;
+        }
+
+        public function set timeout(value: int): void {
+            this._timeout = value;
+        }
+
+        public function connect(host: String, port: int): void {
+            if (port < 0 || port > 65535) {
+                throw new SecurityError("Error #2003: Invalid socket port number specified.", 2003);
+            }
+
+            this.connectSocket(host, port, this._timeout);
+        }
+
+        public function close(): void {
+            if (!this.closeSocket()) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+        }
+
+        // The object is sent as a string, terminated by a zero byte.
+        public function send(object: *): void {
+            if (!this.connected) {
+                throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+            }
+
+            this.sendMessage(String(object));
+        }
+
+        private native function connectSocket(host: String, port: int, timeout: int): void;
+        private native function closeSocket(): Boolean;
+        private native function sendMessage(message: String): void;
+
+        ruffle function receiveMessage(message: String): void {
+            this.dispatchEvent(new DataEvent(DataEvent.DATA, false, false, message));
+        }
+
+        ruffle function dispatchConnect(): void {
+            this.dispatchEvent(new Event(Event.CONNECT));
+        }
+
+        ruffle function dispatchClose(): void {
+            this.dispatchEvent(new Event(Event.CLOSE));
+        }
+
+        ruffle function dispatchIOError(text: String): void {
+            this.dispatchEvent(new IOErrorEvent(IOErrorEvent.IO_ERROR, false, false, text, 2031));
+        }
+
+        ruffle function dispatchSecurityError(text: String): void {
+            this.dispatchEvent(new SecurityErrorEvent(SecurityErrorEvent.SECURITY_ERROR, false, false, text, 2048));
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! `flash.net.DatagramSocket` builtin/prototype
+
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::backend::navigator::Datagram;
+use crate::socket::{SocketManager, SocketObject};
+use crate::string::AvmString;
+
+/// Implements `DatagramSocket.isSupported`'s getter
+pub fn get_is_supported<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(SocketManager::is_air_supported(&activation.context).into())
+}
+
+/// Implements `DatagramSocket.bound`'s getter
+pub fn get_bound<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bound = match this {
+        Some(this) => activation
+            .context
+            .socket_manager
+            .datagram_local_address(SocketObject::Avm2(this))
+            .is_some(),
+        None => false,
+    };
+    Ok(bound.into())
+}
+
+/// Implements `DatagramSocket.localAddress`'s getter
+pub fn get_local_address<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let address = this.and_then(|this| {
+        activation
+            .context
+            .socket_manager
+            .datagram_local_address(SocketObject::Avm2(this))
+            .map(|(address, _)| address.to_string())
+    });
+    Ok(match address {
+        Some(address) => AvmString::new_utf8(activation.context.gc_context, address).into(),
+        None => Value::Null,
+    })
+}
+
+/// Implements `DatagramSocket.localPort`'s getter
+pub fn get_local_port<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let port = this
+        .and_then(|this| {
+            activation
+                .context
+                .socket_manager
+                .datagram_local_address(SocketObject::Avm2(this))
+        })
+        .map_or(0, |(_, port)| port);
+    Ok(port.into())
+}
+
+/// Implements `DatagramSocket.bindSocket`
+pub fn bind_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let port = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let address = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+
+    match SocketManager::bind_datagram(
+        &mut activation.context,
+        SocketObject::Avm2(this),
+        &address,
+        port as u16,
+    ) {
+        Ok(()) => Ok(true.into()),
+        Err(e) => {
+            tracing::warn!("Unable to bind datagram socket: {}", e);
+            Ok(false.into())
+        }
+    }
+}
+
+/// Implements `DatagramSocket.receiveDatagrams`
+pub fn receive_datagrams<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let receiving = match this {
+        Some(this) => {
+            SocketManager::receive_datagrams(&mut activation.context, SocketObject::Avm2(this))
+        }
+        None => false,
+    };
+    Ok(receiving.into())
+}
+
+/// Implements `DatagramSocket.sendDatagram`
+pub fn send_datagram<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let data = args
+        .get(0)
+        .and_then(|data| data.as_object())
+        .and_then(|data| data.as_bytearray().map(|bytes| bytes.bytes().to_vec()))
+        .unwrap_or_default();
+    let address = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+    let port = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    let datagram = Datagram {
+        address,
+        port: port as u16,
+        data,
+    };
+    SocketManager::send_datagram(&mut activation.context, SocketObject::Avm2(this), datagram);
+    Ok(Value::Undefined)
+}
+
+/// Implements `DatagramSocket.closeSocket`
+pub fn close_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let closed = match this {
+        Some(this) => SocketManager::close(&mut activation.context, SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(closed.into())
+}
@@ -0,0 +1,211 @@
+//! `flash.net.FileReference` native function definitions
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::error::{argument_error, illegal_operation_error};
+use crate::avm2::object::{ByteArrayObject, TObject};
+use crate::avm2::{Activation, Avm2, Error, EventObject, Multiname, Namespace, Object, Value};
+use crate::backend::dialog::{Dialog, DialogResult, FileFilter, PickedFile};
+use crate::dialog::DialogPurpose;
+use crate::string::AvmString;
+
+/// Implements `FileReference.browse`
+pub fn browse<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let filters = file_filters(activation, args.get(0))?;
+        return show_file_dialog(
+            activation,
+            Dialog::OpenFiles {
+                filters,
+                multiple: false,
+            },
+            DialogPurpose::Browse(this),
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `FileReference.save`
+pub fn save<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let data = match args.get(0) {
+            Some(Value::Object(data)) => match data.as_bytearray() {
+                Some(bytes) => bytes.bytes().to_vec(),
+                None => Value::Object(*data)
+                    .coerce_to_string(activation)?
+                    .to_utf8_lossy()
+                    .into_owned()
+                    .into_bytes(),
+            },
+            Some(Value::Null | Value::Undefined) | None => {
+                return Err(Error::AvmError(argument_error(
+                    activation,
+                    "Error #2007: Parameter data must be non-null.",
+                    2007,
+                )?));
+            }
+            Some(data) => data
+                .coerce_to_string(activation)?
+                .to_utf8_lossy()
+                .into_owned()
+                .into_bytes(),
+        };
+        let default_name = match args.get(1) {
+            Some(Value::Null | Value::Undefined) | None => String::new(),
+            Some(name) => name.coerce_to_string(activation)?.to_string(),
+        };
+
+        show_file_dialog(
+            activation,
+            Dialog::SaveFile { default_name, data },
+            DialogPurpose::Save(this),
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// Shows a file picker, unless one is open already.
+pub fn show_file_dialog<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    dialog: Dialog,
+    purpose: DialogPurpose<'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if activation.context.dialog_manager.has_file_dialog() {
+        return Err(Error::AvmError(illegal_operation_error(
+            activation,
+            "Error #2041: Only one file browsing session may be performed at a time.",
+            2041,
+        )?));
+    }
+    activation
+        .context
+        .dialog_manager
+        .show(activation.context.dialogs, dialog, purpose);
+    Ok(true.into())
+}
+
+/// Reads the array of `FileFilter`s passed to `browse`.
+pub fn file_filters<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    filters: Option<&Value<'gc>>,
+) -> Result<Vec<FileFilter>, Error<'gc>> {
+    let filters: Vec<Value<'gc>> = match filters {
+        Some(Value::Object(filters)) => match filters.as_array_storage() {
+            Some(filters) => filters.iter().flatten().collect(),
+            None => return Ok(Vec::new()),
+        },
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut file_filters = Vec::with_capacity(filters.len());
+    for filter in filters {
+        let filter = filter.coerce_to_object(activation)?;
+        let description = filter
+            .get_property(&Multiname::public("description"), activation)?
+            .coerce_to_string(activation)?;
+        let extension = filter
+            .get_property(&Multiname::public("extension"), activation)?
+            .coerce_to_string(activation)?;
+        file_filters.push(FileFilter::from_flash(
+            &description.to_utf8_lossy(),
+            &extension.to_utf8_lossy(),
+        ));
+    }
+    Ok(file_filters)
+}
+
+/// Sets the properties of a `FileReference` to describe a picked file.
+pub fn set_file<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    mut this: Object<'gc>,
+    file: PickedFile,
+) -> Result<(), Error<'gc>> {
+    let extension = match file.extension() {
+        Some(extension) => AvmString::new_utf8(activation.context.gc_context, extension).into(),
+        None => Value::Null,
+    };
+    let modification_date = match file.modified {
+        Some(modified) => activation
+            .avm2()
+            .classes()
+            .date
+            .construct(activation, &[modified.into()])?
+            .into(),
+        None => Value::Null,
+    };
+    let name = AvmString::new_utf8(activation.context.gc_context, &file.name);
+    let size = file.data.len() as f64;
+    let data = ByteArrayObject::from_storage(activation, ByteArrayStorage::from_vec(file.data))?;
+
+    let ruffle = Namespace::Namespace("__ruffle__".into());
+    let properties = [
+        ("_modificationDate", modification_date),
+        ("_extension", extension),
+        ("_type", extension),
+        ("_name", name.into()),
+        ("_size", size.into()),
+        ("_fileData", data.into()),
+    ];
+    for (property, value) in properties {
+        this.set_property(&Multiname::new(ruffle, property), value, activation)?;
+    }
+    Ok(())
+}
+
+/// Dispatches a simple event, such as `select` or `cancel`, to a file dialog's target.
+pub fn dispatch_event<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    target: Object<'gc>,
+    event_type: &'static str,
+) -> Result<(), Error<'gc>> {
+    let event = EventObject::bare_default_event(&mut activation.context, event_type);
+    Avm2::dispatch_event(&mut activation.context, event, target)?;
+    Ok(())
+}
+
+/// Applies the result of the file picker shown by `FileReference.browse`.
+pub fn resolve_browse<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    result: DialogResult,
+) -> Result<(), Error<'gc>> {
+    let file = match result {
+        DialogResult::Files(files) => files.into_iter().next(),
+        _ => None,
+    };
+    match file {
+        Some(file) => {
+            set_file(activation, this, file)?;
+            dispatch_event(activation, this, "select")
+        }
+        None => dispatch_event(activation, this, "cancel"),
+    }
+}
+
+/// Applies the result of the file picker shown by `FileReference.save`.
+pub fn resolve_save<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    mut this: Object<'gc>,
+    result: DialogResult,
+) -> Result<(), Error<'gc>> {
+    match result {
+        DialogResult::Saved { name } => {
+            let name = AvmString::new_utf8(activation.context.gc_context, name);
+            this.set_property(
+                &Multiname::new(Namespace::Namespace("__ruffle__".into()), "_name"),
+                name.into(),
+                activation,
+            )?;
+            dispatch_event(activation, this, "select")?;
+            dispatch_event(activation, this, "complete")
+        }
+        _ => dispatch_event(activation, this, "cancel"),
+    }
+}
@@ -0,0 +1,64 @@
+//! `flash.net.FileReferenceList` native function definitions
+
+use crate::avm2::globals::flash::net::file_reference::{
+    dispatch_event, file_filters, set_file, show_file_dialog,
+};
+use crate::avm2::object::TObject;
+use crate::avm2::{
+    Activation, ArrayObject, ArrayStorage, Error, Multiname, Namespace, Object, Value,
+};
+use crate::backend::dialog::{Dialog, DialogResult};
+use crate::dialog::DialogPurpose;
+
+/// Implements `FileReferenceList.browse`
+pub fn browse<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let filters = file_filters(activation, args.get(0))?;
+        return show_file_dialog(
+            activation,
+            Dialog::OpenFiles {
+                filters,
+                multiple: true,
+            },
+            DialogPurpose::BrowseList(this),
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+/// Applies the result of the file picker shown by `FileReferenceList.browse`, creating a
+/// `FileReference` for each picked file.
+pub fn resolve_browse<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    mut this: Object<'gc>,
+    result: DialogResult,
+) -> Result<(), Error<'gc>> {
+    let files = match result {
+        DialogResult::Files(files) if !files.is_empty() => files,
+        _ => return dispatch_event(activation, this, "cancel"),
+    };
+
+    let mut file_references = Vec::with_capacity(files.len());
+    for file in files {
+        let file_reference = activation
+            .avm2()
+            .classes()
+            .filereference
+            .construct(activation, &[])?;
+        set_file(activation, file_reference, file)?;
+        file_references.push(Some(file_reference.into()));
+    }
+    let file_list =
+        ArrayObject::from_storage(activation, ArrayStorage::from_storage(file_references))?;
+    this.set_property(
+        &Multiname::new(Namespace::Namespace("__ruffle__".into()), "_fileList"),
+        file_list.into(),
+        activation,
+    )?;
+
+    dispatch_event(activation, this, "select")
+}
@@ -0,0 +1,211 @@
+//! `flash.net.LocalConnection` builtin/prototype
+
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::error::{argument_error, type_error};
+use crate::avm2::object::{ArrayObject, TObject};
+use crate::avm2::{Activation, Error, Multiname, Namespace, Object, Value};
+use crate::backend::local_connection::LocalConnectionMessage;
+use crate::local_connection::{
+    connect_name, decode_arguments, encode_arguments, movie_domain, send_name,
+    LocalConnectionManager, LocalConnectionObject, MAX_ARGUMENTS_SIZE, RESERVED_METHOD_NAMES,
+};
+use crate::string::AvmString;
+use flash_lso::types::Value as AmfValue;
+
+/// The domain of the movie, or `None` if its URL can't be parsed.
+fn current_domain(activation: &mut Activation<'_, '_>) -> Option<String> {
+    movie_domain(activation.context.swf.url())
+}
+
+/// Coerces a parameter to a non-empty string, raising the errors Flash does otherwise.
+fn string_parameter<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    args: &[Value<'gc>],
+    index: usize,
+    name: &str,
+) -> Result<String, Error<'gc>> {
+    let value = match args.get(index).unwrap_or(&Value::Null) {
+        Value::Null | Value::Undefined => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                &format!("Error #2007: Parameter {name} must be non-null."),
+                2007,
+            )?));
+        }
+        value => value.coerce_to_string(activation)?,
+    };
+    if value.is_empty() {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            &format!("Error #2085: Parameter {name} must be non-empty string."),
+            2085,
+        )?));
+    }
+    Ok(value.to_utf8_lossy().into_owned())
+}
+
+/// Implements `LocalConnection.domain`'s getter
+pub fn get_domain<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match current_domain(activation) {
+        Some(domain) => Ok(AvmString::new_utf8(activation.context.gc_context, domain).into()),
+        None => {
+            tracing::error!("LocalConnection::domain: Unable to parse movie URL");
+            Ok(Value::Null)
+        }
+    }
+}
+
+/// Implements `LocalConnection.connect`
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let name = string_parameter(activation, args, 0, "connectionName")?;
+    if name.contains(':') {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2004: One of the parameters is invalid.",
+            2004,
+        )?));
+    }
+
+    let object = LocalConnectionObject::Avm2(this);
+    let domain = current_domain(activation).unwrap_or_default();
+    if activation
+        .context
+        .local_connection_manager
+        .is_connected(object)
+        || !LocalConnectionManager::connect(
+            &mut activation.context,
+            connect_name(&domain, &name),
+            object,
+        )
+    {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2082: Connect failed because the object is already connected.",
+            2082,
+        )?));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `LocalConnection.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        if !LocalConnectionManager::close(
+            &mut activation.context,
+            LocalConnectionObject::Avm2(this),
+        ) {
+            return Err(Error::AvmError(argument_error(
+                activation,
+                "Error #2083: Close failed because the object is not connected.",
+                2083,
+            )?));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `LocalConnection.send`
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let name = string_parameter(activation, args, 0, "connectionName")?;
+    let method_name = string_parameter(activation, args, 1, "methodName")?;
+    if RESERVED_METHOD_NAMES.contains(&&*method_name) {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2004: One of the parameters is invalid.",
+            2004,
+        )?));
+    }
+
+    let arguments: Vec<_> = args
+        .get(2..)
+        .unwrap_or_default()
+        .iter()
+        .map(|arg| {
+            crate::avm2::amf::serialize_value(activation, *arg).unwrap_or(AmfValue::Undefined)
+        })
+        .collect();
+    let arguments = encode_arguments(arguments);
+    if arguments.len() > MAX_ARGUMENTS_SIZE {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2084: The AMF encoding of the arguments cannot exceed 40K.",
+            2084,
+        )?));
+    }
+
+    let domain = current_domain(activation).unwrap_or_default();
+    let message = LocalConnectionMessage {
+        connection_name: send_name(&domain, &name),
+        method_name,
+        sender_domain: domain,
+        arguments,
+    };
+    LocalConnectionManager::send(
+        &mut activation.context,
+        LocalConnectionObject::Avm2(this),
+        message,
+    );
+    Ok(Value::Undefined)
+}
+
+/// Calls the method named by a message received on the connection of `this`, on its client.
+pub fn receive_message<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    message: LocalConnectionMessage,
+) -> Result<(), Error<'gc>> {
+    let mut args = Vec::new();
+    for value in decode_arguments(&message.arguments) {
+        args.push(crate::avm2::amf::deserialize_value(activation, &value)?);
+    }
+    let args = ArrayObject::from_storage(activation, ArrayStorage::from_args(&args))?;
+    let sender_domain = AvmString::new_utf8(activation.context.gc_context, message.sender_domain);
+    let method_name = AvmString::new_utf8(activation.context.gc_context, message.method_name);
+
+    this.call_property(
+        &Multiname::new(Namespace::Namespace("__ruffle__".into()), "receiveMessage"),
+        &[sender_domain.into(), method_name.into(), args.into()],
+        activation,
+    )?;
+    Ok(())
+}
+
+/// Dispatches the `status` event reporting the outcome of a message sent by `this`.
+pub fn dispatch_status<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    delivered: bool,
+) -> Result<(), Error<'gc>> {
+    this.call_property(
+        &Multiname::new(Namespace::Namespace("__ruffle__".into()), "dispatchStatus"),
+        &[delivered.into()],
+        activation,
+    )?;
+    Ok(())
+}
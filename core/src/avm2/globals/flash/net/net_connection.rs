@@ -0,0 +1,50 @@
+//! `flash.net.NetConnection` native function definitions
+
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, Error, Object, Value};
+
+pub use crate::avm2::object::net_connection_allocator;
+
+pub fn connect_rtmp<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(connection) = this.and_then(|this| this.as_netconnection()) {
+        let url = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let connected = connection
+            .connection()
+            .connect(&mut activation.context, &url.to_utf8_lossy());
+        return Ok(connected.into());
+    }
+
+    Ok(false.into())
+}
+
+pub fn is_rtmp_connected<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(connection) = this.and_then(|this| this.as_netconnection()) {
+        return Ok(connection.connection().is_connected().into());
+    }
+
+    Ok(false.into())
+}
+
+pub fn close_rtmp<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(connection) = this.and_then(|this| this.as_netconnection()) {
+        connection.connection().close(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,268 @@
+//! `flash.net.NetStream` native function definitions
+
+use crate::avm2::error::argument_error;
+use crate::avm2::object::TObject;
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::streams::AppendBytesAction;
+use crate::string::WStr;
+
+pub use crate::avm2::object::net_stream_allocator;
+
+pub fn get_buffer_length<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        return Ok(stream.stream().buffer_length().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_buffer_time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        return Ok(stream.stream().buffer_time().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        let buffer_time = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        stream
+            .stream()
+            .set_buffer_time(&mut activation.context, buffer_time);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_bytes_loaded<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        return Ok((stream.stream().bytes_loaded() as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_bytes_total<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        return Ok((stream.stream().bytes_total() as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        return Ok(stream.stream().time().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn play<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        let url = match args.get(0) {
+            Some(Value::Null) | Some(Value::Undefined) | None => {
+                // `play(null)` switches the stream to data generation mode.
+                stream
+                    .stream()
+                    .play_data_generation(&mut activation.context);
+                return Ok(Value::Undefined);
+            }
+            Some(url) => url.coerce_to_string(activation)?,
+        };
+
+        if let Some(connection) = stream
+            .connection()
+            .filter(|connection| connection.is_connected())
+        {
+            // Streams of RTMP connections are played from the server.
+            let start = match args.get(1) {
+                Some(start) => start.coerce_to_number(activation)?,
+                None => -2.0,
+            };
+            connection.play(
+                &mut activation.context,
+                stream.stream(),
+                url.to_utf8_lossy().into_owned(),
+                start,
+            );
+            return Ok(Value::Undefined);
+        }
+
+        stream
+            .stream()
+            .play(&mut activation.context, url.to_string());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_connection<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        let connection = args
+            .get(0)
+            .and_then(|connection| connection.as_object())
+            .and_then(|connection| connection.as_netconnection())
+            .map(|connection| connection.connection());
+        stream.set_connection(activation.context.gc_context, connection);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn pause<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        stream.stream().pause(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn resume<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        stream.stream().resume(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn toggle_pause<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        stream.stream().toggle_paused(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn seek<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        let offset = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_number(activation)?;
+        stream
+            .stream()
+            .seek(&mut activation.context, offset * 1000.0);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        stream.stream().close(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn append_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        let bytes = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        if let Some(bytearray) = bytes.as_bytearray() {
+            let data = bytearray.bytes().to_vec();
+            drop(bytearray);
+            stream.stream().append_bytes(&mut activation.context, &data);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn append_bytes_action<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(stream) = this.and_then(|this| this.as_netstream()) {
+        let action = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let action = if action == WStr::from_units(b"resetBegin") {
+            AppendBytesAction::ResetBegin
+        } else if action == WStr::from_units(b"resetSeek") {
+            AppendBytesAction::ResetSeek
+        } else if action == WStr::from_units(b"endSequence") {
+            AppendBytesAction::EndSequence
+        } else {
+            return Err(Error::AvmError(argument_error(
+                activation,
+                "Error #2004: One of the parameters is invalid.",
+                2004,
+            )?));
+        };
+        stream
+            .stream()
+            .append_bytes_action(&mut activation.context, action);
+    }
+
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,143 @@
+//! `flash.net.ServerSocket` builtin/prototype
+
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::socket::{SocketManager, SocketObject};
+use crate::string::AvmString;
+
+/// Implements `ServerSocket.isSupported`'s getter
+pub fn get_is_supported<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(SocketManager::is_air_supported(&activation.context).into())
+}
+
+/// Implements `ServerSocket.bound`'s getter
+pub fn get_bound<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bound = match this {
+        Some(this) => activation
+            .context
+            .socket_manager
+            .listener_local_address(SocketObject::Avm2(this))
+            .is_some(),
+        None => false,
+    };
+    Ok(bound.into())
+}
+
+/// Implements `ServerSocket.listening`'s getter
+pub fn get_listening<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let listening = match this {
+        Some(this) => activation
+            .context
+            .socket_manager
+            .is_listening(SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(listening.into())
+}
+
+/// Implements `ServerSocket.localAddress`'s getter
+pub fn get_local_address<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let address = this.and_then(|this| {
+        activation
+            .context
+            .socket_manager
+            .listener_local_address(SocketObject::Avm2(this))
+            .map(|(address, _)| address.to_string())
+    });
+    Ok(match address {
+        Some(address) => AvmString::new_utf8(activation.context.gc_context, address).into(),
+        None => Value::Null,
+    })
+}
+
+/// Implements `ServerSocket.localPort`'s getter
+pub fn get_local_port<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let port = this
+        .and_then(|this| {
+            activation
+                .context
+                .socket_manager
+                .listener_local_address(SocketObject::Avm2(this))
+        })
+        .map_or(0, |(_, port)| port);
+    Ok(port.into())
+}
+
+/// Implements `ServerSocket.bindSocket`
+pub fn bind_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let port = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let address = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+
+    match SocketManager::bind_listener(
+        &mut activation.context,
+        SocketObject::Avm2(this),
+        &address,
+        port as u16,
+    ) {
+        Ok(()) => Ok(true.into()),
+        Err(e) => {
+            tracing::warn!("Unable to bind server socket: {}", e);
+            Ok(false.into())
+        }
+    }
+}
+
+/// Implements `ServerSocket.listenSocket`
+pub fn listen_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let listening = match this {
+        Some(this) => SocketManager::listen(&mut activation.context, SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(listening.into())
+}
+
+/// Implements `ServerSocket.closeSocket`
+pub fn close_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let closed = match this {
+        Some(this) => SocketManager::close(&mut activation.context, SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(closed.into())
+}
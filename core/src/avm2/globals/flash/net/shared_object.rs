@@ -1,34 +1,35 @@
 //! `flash.net.SharedObject` builtin/prototype
 
-use crate::avm2::object::TObject;
+use crate::avm2::amf::{Amf3Reader, Amf3Writer};
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::error::error;
+use crate::avm2::object::{ByteArrayObject, TObject};
 use crate::avm2::Multiname;
-use crate::avm2::{Activation, Error, Namespace, Object, Value};
+use crate::avm2::{Activation, Avm2, Error, Namespace, Object, Value};
 use crate::avm2_stub_method;
 use crate::display_object::DisplayObject;
 use crate::display_object::TDisplayObject;
+use crate::local_storage::{
+    load_shared_object, parse_sol, read_lso, shared_object_path, sol_name, write_sol, FlushStatus,
+    SolHeader,
+};
 use crate::string::AvmString;
 use flash_lso::types::{AMFVersion, Lso};
-use std::borrow::Cow;
 
 pub fn get_local<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // TODO: It appears that Flash does some kind of escaping here:
-    // the name "foo\uD800" correspond to a file named "fooE#FB#FB#D.sol".
-
     let name = args
         .get(0)
         .unwrap_or(&Value::Undefined)
         .coerce_to_string(activation)?;
-    let name = name.to_utf8_lossy();
-
-    const INVALID_CHARS: &str = "~%&\\;:\"',<>?# ";
-    if name.contains(|c| INVALID_CHARS.contains(c)) {
-        tracing::error!("SharedObject::get_local: Invalid character in name");
-        return Ok(Value::Null);
-    }
+    let local_path = match args.get(1) {
+        Some(Value::String(local_path)) => Some(local_path.to_utf8_lossy()),
+        _ => None,
+    };
+    let secure = args.get(2).unwrap_or(&Value::Undefined).coerce_to_boolean();
 
     let movie = if let DisplayObject::MovieClip(movie) = activation.context.stage.root_clip() {
         movie
@@ -37,100 +38,13 @@ pub fn get_local<'gc>(
         return Ok(Value::Null);
     };
 
-    let mut movie_url = if let Some(url) = movie.movie().url().map(|u| u.to_owned()) {
-        if let Ok(url) = url::Url::parse(&url) {
-            url
-        } else {
-            tracing::error!("SharedObject::get_local: Unable to parse movie URL");
-            return Ok(Value::Null);
-        }
-    } else {
-        // No URL (loading local data). Use a dummy URL to allow SharedObjects to work.
-        url::Url::parse("file://localhost").unwrap()
-    };
-    movie_url.set_query(None);
-    movie_url.set_fragment(None);
-
-    let secure = args.get(2).unwrap_or(&Value::Undefined).coerce_to_boolean();
-
-    // Secure parameter disallows using the shared object from non-HTTPS.
-    if secure && movie_url.scheme() != "https" {
-        tracing::warn!(
-            "SharedObject.get_local: Tried to load a secure shared object from non-HTTPS origin"
-        );
-        return Ok(Value::Null);
-    }
-
-    // Shared objects are sandboxed per-domain.
-    // By default, they are keyed based on the SWF URL, but the `localHost` parameter can modify this path.
-    let mut movie_path = movie_url.path();
-    // Remove leading/trailing slashes.
-    movie_path = movie_path.strip_prefix('/').unwrap_or(movie_path);
-    movie_path = movie_path.strip_suffix('/').unwrap_or(movie_path);
-
-    let movie_host = if movie_url.scheme() == "file" {
-        // Remove drive letter on Windows (TODO: move this logic into DiskStorageBackend?)
-        if let [_, b':', b'/', ..] = movie_path.as_bytes() {
-            movie_path = &movie_path[3..];
-        }
-        "localhost"
-    } else {
-        movie_url.host_str().unwrap_or_default()
-    };
-
-    let local_path = if let Some(Value::String(local_path)) = args.get(1) {
-        // Empty local path always fails.
-        if local_path.is_empty() {
-            return Ok(Value::Null);
-        }
-
-        // Remove leading/trailing slashes.
-        let mut local_path = local_path.to_utf8_lossy();
-        if local_path.ends_with('/') {
-            match &mut local_path {
-                Cow::Owned(p) => {
-                    p.pop();
-                }
-                Cow::Borrowed(p) => *p = &p[..p.len() - 1],
-            }
-        }
-        if local_path.starts_with('/') {
-            match &mut local_path {
-                Cow::Owned(p) => {
-                    p.remove(0);
-                }
-                Cow::Borrowed(p) => *p = &p[1..],
-            }
-        }
-
-        // Verify that local_path is a prefix of the SWF path.
-        if movie_path.starts_with(local_path.as_ref())
-            && (local_path.is_empty()
-                || movie_path.len() == local_path.len()
-                || movie_path[local_path.len()..].starts_with('/'))
-        {
-            local_path
-        } else {
-            tracing::warn!("SharedObject.get_local: localPath parameter does not match SWF path");
-            return Ok(Value::Null);
-        }
-    } else {
-        Cow::Borrowed(movie_path)
+    let name = name.to_utf8_lossy();
+    let movie = movie.movie();
+    let full_name = match shared_object_path(movie.url(), &name, local_path.as_deref(), secure) {
+        Some(full_name) => full_name,
+        None => return Ok(Value::Null),
     };
 
-    // Final SO path: foo.com/folder/game.swf/SOName
-    // SOName may be a path containing slashes. In this case, prefix with # to mimic Flash Player behavior.
-    let prefix = if name.contains('/') { "#" } else { "" };
-    let full_name = format!("{movie_host}/{local_path}/{prefix}{name}");
-
-    // Avoid any paths with `..` to prevent SWFs from crawling the file system on desktop.
-    // Flash will generally fail to save shared objects with a path component starting with `.`,
-    // so let's disallow them altogether.
-    if full_name.split('/').any(|s| s.starts_with('.')) {
-        tracing::error!("SharedObject.get_local: Invalid path with .. segments");
-        return Ok(Value::Null);
-    }
-
     // Check if this is referencing an existing shared object
     if let Some(so) = activation.context.avm2_shared_objects.get(&full_name) {
         return Ok((*so).into());
@@ -151,9 +65,21 @@ pub fn get_local<'gc>(
     let mut data = Value::Undefined;
 
     // Load the data object from storage if it existed prior
-    if let Some(saved) = activation.context.storage.get(&full_name) {
-        if let Ok(lso) = flash_lso::read::Reader::default().parse(&saved) {
-            data = crate::avm2::amf::deserialize_lso(activation, &lso)?.into();
+    let saved = load_shared_object(
+        activation.context.storage,
+        &full_name,
+        movie.url(),
+        &name,
+        local_path.as_deref(),
+    );
+    if let Some(saved) = saved {
+        if let Some((saved_data, amf_version)) = deserialize_data(activation, &full_name, &saved)? {
+            data = saved_data.into();
+            this.set_property(
+                &Multiname::public("objectEncoding"),
+                amf_version.into(),
+                activation,
+            )?;
         }
     }
 
@@ -176,37 +102,171 @@ pub fn get_local<'gc>(
     Ok(this.into())
 }
 
+/// Returns the full name of a shared object, e.g. `localhost/game.swf/save`.
+fn shared_object_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+) -> Result<String, Error<'gc>> {
+    let ruffle_name = Multiname::new(Namespace::Namespace("__ruffle__".into()), "_ruffleName");
+    let name = this
+        .get_property(&ruffle_name, activation)?
+        .coerce_to_string(activation)?;
+    Ok(name.to_utf8_lossy().into_owned())
+}
+
+/// Reads the data object of a shared object from the bytes of its `.sol` file.
+///
+/// Returns the data along with the AMF version it was encoded with.
+fn deserialize_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    name: &str,
+    bytes: &[u8],
+) -> Result<Option<(Object<'gc>, u32)>, Error<'gc>> {
+    let header = parse_sol(bytes);
+
+    // AMF3 data is read like `ByteArray.readObject` does, so that references,
+    // aliased classes and `IExternalizable` objects are restored.
+    if let Some((_, body)) = header.filter(|(header, _)| header.amf_version == 3) {
+        let storage = ByteArrayStorage::from_vec(body.to_vec());
+        let body = ByteArrayObject::from_storage(activation, storage)?;
+        let data = activation
+            .avm2()
+            .classes()
+            .object
+            .construct(activation, &[])?;
+        return match Amf3Reader::new(body).read_sol_properties(activation, data) {
+            Ok(()) => Ok(Some((data, 3))),
+            Err(_) => {
+                tracing::warn!("SharedObject: Couldn't read the data of {name}");
+                Ok(None)
+            }
+        };
+    }
+
+    // Anything else is AMF0, or the JSON that older versions of Ruffle stored.
+    match read_lso(name, bytes) {
+        Some(lso) => {
+            let data = crate::avm2::amf::deserialize_lso(activation, &lso)?;
+            Ok(Some((data, 0)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Serializes the data of a shared object into the bytes of a `.sol` file.
+fn serialize_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    name: &str,
+) -> Result<Vec<u8>, Error<'gc>> {
+    let data = this
+        .get_property(&Multiname::public("data"), activation)?
+        .coerce_to_object(activation)?;
+    let object_encoding = this
+        .get_property(&Multiname::public("objectEncoding"), activation)?
+        .coerce_to_u32(activation)?;
+
+    if object_encoding == 0 {
+        let mut elements = Vec::new();
+        crate::avm2::amf::recursive_serialize(activation, data, &mut elements)?;
+        let mut lso = Lso::new(elements, sol_name(name), AMFVersion::AMF0);
+        return Ok(flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default());
+    }
+
+    let body = ByteArrayObject::from_storage(activation, ByteArrayStorage::new())?;
+    Amf3Writer::new(body).write_sol_properties(activation, data)?;
+    let body = body
+        .as_bytearray()
+        .map(|body| body.bytes().to_vec())
+        .unwrap_or_default();
+    let header = SolHeader {
+        name: sol_name(name).to_string(),
+        amf_version: 3,
+    };
+
+    Ok(write_sol(&header, &body))
+}
+
+/// Writes the data of a shared object to storage, regardless of the storage quota.
+///
+/// This is used when the player shuts down, so that no data is lost.
+pub fn write_to_storage<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+) -> Result<bool, Error<'gc>> {
+    let name = shared_object_name(activation, this)?;
+    let bytes = serialize_data(activation, this, &name)?;
+    Ok(activation.context.storage.put(&name, &bytes))
+}
+
+/// Finishes a flush that was waiting for the user to grant a larger storage quota,
+/// and dispatches a `netStatus` event on the shared object.
+pub fn resolve_pending_flush<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    granted: bool,
+) -> Result<(), Error<'gc>> {
+    let success = granted && write_to_storage(activation, this)?;
+    let (code, level) = if success {
+        ("SharedObject.Flush.Success", "status")
+    } else {
+        ("SharedObject.Flush.Failed", "error")
+    };
+
+    let mut info = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    info.set_property(&Multiname::public("code"), code.into(), activation)?;
+    info.set_property(&Multiname::public("level"), level.into(), activation)?;
+
+    let event = activation.avm2().classes().netstatusevent.construct(
+        activation,
+        &["netStatus".into(), false.into(), false.into(), info.into()],
+    )?;
+    Avm2::dispatch_event(&mut activation.context, event, this)?;
+    Ok(())
+}
+
 pub fn flush<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
-        let data = this
-            .get_property(&Multiname::public("data"), activation)?
-            .coerce_to_object(activation)?;
+        let min_disk_space = args
+            .get(0)
+            .unwrap_or(&Value::Integer(0))
+            .coerce_to_i32(activation)?
+            .max(0) as usize;
 
-        let ruffle_name = Multiname::new(Namespace::Namespace("__ruffle__".into()), "_ruffleName");
-        let name = this
-            .get_property(&ruffle_name, activation)?
-            .coerce_to_string(activation)?;
-        let name = name.to_utf8_lossy();
+        let name = shared_object_name(activation, this)?;
+        let bytes = serialize_data(activation, this, &name)?;
 
-        let mut elements = Vec::new();
-        crate::avm2::amf::recursive_serialize(activation, data, &mut elements, AMFVersion::AMF3)?;
-        let mut lso = Lso::new(
-            elements,
-            &name
-                .split('/')
-                .last()
-                .map(|e| e.to_string())
-                .unwrap_or_else(|| "<unknown>".to_string()),
-            AMFVersion::AMF3,
+        let dialog_manager = &mut activation.context.dialog_manager;
+        let dialogs = &mut activation.context.dialogs;
+        let status = activation.context.storage_quota.request(
+            activation.context.storage,
+            &name,
+            bytes.len().max(min_disk_space),
+            |request| dialog_manager.show_storage_quota_prompt(*dialogs, request),
         );
 
-        let bytes = flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default();
-
-        return Ok(activation.context.storage.put(&name, &bytes).into());
+        return match status {
+            FlushStatus::Flushed => {
+                if activation.context.storage.put(&name, &bytes) {
+                    Ok("flushed".into())
+                } else {
+                    Err(Error::AvmError(error(
+                        activation,
+                        "Error #2130: Unable to flush SharedObject.",
+                        2130,
+                    )?))
+                }
+            }
+            FlushStatus::Pending => Ok("pending".into()),
+        };
     }
     Ok(Value::Undefined)
 }
@@ -0,0 +1,158 @@
+//! `flash.net.Socket` builtin/prototype
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::object::{ByteArrayObject, TObject};
+use crate::avm2::{Activation, Error, Multiname, Namespace, Object, Value};
+use crate::policy_file;
+use crate::socket::{movie_host, SocketEvent, SocketKind, SocketManager, SocketObject};
+use crate::string::AvmString;
+
+/// Implements `Socket.connected`'s getter
+pub fn get_connected<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let connected = match this {
+        Some(this) => activation
+            .context
+            .socket_manager
+            .is_connected(SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(connected.into())
+}
+
+/// Implements `Socket.connectSocket`
+pub fn connect_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let host = match args.get(0).unwrap_or(&Value::Null) {
+        Value::Null | Value::Undefined => movie_host(activation.context.swf.url()),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let timeout = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    SocketManager::connect(
+        &mut activation.context,
+        SocketObject::Avm2(this),
+        host,
+        port as u16,
+        SocketKind::Binary,
+        timeout,
+    );
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.closeSocket`
+pub fn close_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let closed = match this {
+        Some(this) => SocketManager::close(&mut activation.context, SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(closed.into())
+}
+
+/// Implements `Socket.sendData`
+pub fn send_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let data = args
+        .get(0)
+        .and_then(|data| data.as_object())
+        .and_then(|data| data.as_bytearray().map(|bytes| bytes.bytes().to_vec()));
+    if let Some(data) = data {
+        SocketManager::send(&mut activation.context, SocketObject::Avm2(this), data);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Reports something that happened to the connection of `this` with the matching event.
+///
+/// This is shared by `Socket`, `XMLSocket`, `DatagramSocket` and `ServerSocket`, which handle the
+/// same internal methods.
+pub fn dispatch_event<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    event: SocketEvent,
+) -> Result<(), Error<'gc>> {
+    let (name, args): (_, Vec<Value<'gc>>) = match event {
+        SocketEvent::Connect => ("dispatchConnect", vec![]),
+        SocketEvent::Data(data) => {
+            let data = ByteArrayObject::from_storage(activation, ByteArrayStorage::from_vec(data))?;
+            ("receiveData", vec![data.into()])
+        }
+        SocketEvent::Message(message) => {
+            let message = AvmString::new_utf8(activation.context.gc_context, message);
+            ("receiveMessage", vec![message.into()])
+        }
+        SocketEvent::Datagram(datagram) => {
+            let data = ByteArrayObject::from_storage(
+                activation,
+                ByteArrayStorage::from_vec(datagram.data),
+            )?;
+            let address = AvmString::new_utf8(activation.context.gc_context, datagram.address);
+            (
+                "receiveDatagram",
+                vec![data.into(), address.into(), datagram.port.into()],
+            )
+        }
+        SocketEvent::Accept(accepted) => {
+            let socket = activation
+                .avm2()
+                .classes()
+                .socket
+                .construct(activation, &[])?;
+            SocketManager::adopt(
+                &mut activation.context,
+                SocketObject::Avm2(socket),
+                accepted,
+            );
+            ("receiveSocket", vec![socket.into()])
+        }
+        SocketEvent::Close => ("dispatchClose", vec![]),
+        SocketEvent::IoError { host, .. } => {
+            let text = format!("Error #2031: Socket Error. URL: {host}");
+            let text = AvmString::new_utf8(activation.context.gc_context, text);
+            ("dispatchIOError", vec![text.into()])
+        }
+        SocketEvent::Timeout { host, port } | SocketEvent::SecurityError { host, port } => {
+            let text = policy_file::violation_message(
+                activation.context.swf.url(),
+                &format!("{host}:{port}"),
+            );
+            let text = AvmString::new_utf8(activation.context.gc_context, text);
+            ("dispatchSecurityError", vec![text.into()])
+        }
+    };
+
+    this.call_property(
+        &Multiname::new(Namespace::Namespace("__ruffle__".into()), name),
+        &args,
+        activation,
+    )?;
+    Ok(())
+}
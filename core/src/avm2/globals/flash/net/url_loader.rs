@@ -1,15 +1,18 @@
 //! `flash.net.URLLoader` native function definitions
 
 use crate::avm2::activation::Activation;
-use crate::avm2::object::TObject;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::object::{ArrayObject, TObject};
 use crate::avm2::value::Value;
-use crate::avm2::Multiname;
-use crate::avm2::{Error, Object};
+use crate::avm2::{Avm2, Error, Object};
+use crate::avm2::{Multiname, Namespace};
 use crate::backend::navigator::{NavigationMethod, Request};
 use crate::loader::DataFormat;
+use crate::policy_file;
+use crate::string::AvmString;
 
-/// Native function definition for `URLLoader.load`
-pub fn load<'gc>(
+/// Native function definition for `URLLoader.loadRequest`
+pub fn load_request<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
@@ -40,12 +43,11 @@ pub fn load<'gc>(
     Ok(Value::Undefined)
 }
 
-fn spawn_fetch<'gc>(
+/// Builds the request described by a `URLRequest`.
+pub fn request_from_url_request<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    loader_object: Object<'gc>,
     url_request: &Object<'gc>,
-    data_format: DataFormat,
-) -> Result<Value<'gc>, Error<'gc>> {
+) -> Result<Request, Error<'gc>> {
     let url = url_request
         .get_property(&Multiname::public("url"), activation)?
         .coerce_to_string(activation)?;
@@ -54,14 +56,83 @@ fn spawn_fetch<'gc>(
         .get_property(&Multiname::public("method"), activation)?
         .coerce_to_string(activation)?;
 
-    let method = NavigationMethod::from_method_str(&method_str).unwrap_or_else(|| {
+    let mut method = NavigationMethod::from_http_method_str(&method_str).unwrap_or_else(|| {
         tracing::error!("Unknown HTTP method type {:?}", method_str);
         NavigationMethod::Get
     });
 
-    // FIXME - set options from the `URLRequest`
-    let request = Request::request(method, url.to_string(), None);
+    let content_type = url_request
+        .get_property(&Multiname::public("contentType"), activation)?
+        .coerce_to_string(activation)?;
+
+    let body = match url_request.get_property(&Multiname::public("data"), activation)? {
+        Value::Undefined | Value::Null => None,
+        Value::Object(data) if data.as_bytearray().is_some() => data
+            .as_bytearray()
+            .map(|bytes| (bytes.bytes().to_vec(), content_type.to_string())),
+        // Anything else, including `URLVariables`, is sent in its string form.
+        data => {
+            let data = data.coerce_to_string(activation)?.to_string();
+            Some((data.into_bytes(), content_type.to_string()))
+        }
+    };
+
+    let mut url = url.to_string();
+    let body = match (method, body) {
+        // Flash Player sends POST requests without any data as GET requests.
+        (NavigationMethod::Post, None) => {
+            method = NavigationMethod::Get;
+            None
+        }
+        // Requests without a body take the data as their query string instead.
+        (NavigationMethod::Get | NavigationMethod::Head, Some((data, _))) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url.push(separator);
+            url.push_str(&String::from_utf8_lossy(&data));
+            None
+        }
+        (_, body) => body,
+    };
+
+    let request_headers =
+        url_request.get_property(&Multiname::public("requestHeaders"), activation)?;
+    let request_headers: Vec<Value<'gc>> = match request_headers {
+        Value::Object(request_headers) => match request_headers.as_array_storage() {
+            Some(request_headers) => request_headers.iter().flatten().collect(),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
 
+    let mut headers = Vec::with_capacity(request_headers.len());
+    for header in request_headers {
+        let header = header.coerce_to_object(activation)?;
+        let name = header
+            .get_property(&Multiname::public("name"), activation)?
+            .coerce_to_string(activation)?;
+        let value = header
+            .get_property(&Multiname::public("value"), activation)?
+            .coerce_to_string(activation)?;
+        headers.push((name.to_string(), value.to_string()));
+    }
+
+    let follow_redirects = url_request
+        .get_property(&Multiname::public("followRedirects"), activation)?
+        .coerce_to_boolean();
+
+    let mut request = Request::request(method, url, body);
+    request.set_headers(headers);
+    request.set_follow_redirects(follow_redirects);
+    Ok(request)
+}
+
+fn spawn_fetch<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    loader_object: Object<'gc>,
+    url_request: &Object<'gc>,
+    data_format: DataFormat,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let request = request_from_url_request(activation, url_request)?;
     let future = activation.context.load_manager.load_data_into_url_loader(
         activation.context.player.clone(),
         loader_object,
@@ -71,3 +142,73 @@ fn spawn_fetch<'gc>(
     activation.context.navigator.spawn_future(future);
     Ok(Value::Undefined)
 }
+
+/// Reports that `bytes_loaded` bytes of `bytes_total` were downloaded by `this` so far.
+pub fn dispatch_progress<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    bytes_loaded: usize,
+    bytes_total: Option<usize>,
+) -> Result<(), Error<'gc>> {
+    // Flash reports an unknown length as 0.
+    let bytes_total = bytes_total.unwrap_or_default();
+    this.call_property(
+        &Multiname::new(Namespace::Namespace("__ruffle__".into()), "receiveProgress"),
+        &[(bytes_loaded as f64).into(), (bytes_total as f64).into()],
+        activation,
+    )?;
+    Ok(())
+}
+
+/// Reports the HTTP status of the response to the download of `this`, with an event of type
+/// `event_type`.
+pub fn dispatch_http_status<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    event_type: &'static str,
+    status: u16,
+    url: &str,
+    headers: &[(String, String)],
+) -> Result<(), Error<'gc>> {
+    // The headers are handed over as a flat list of names and values.
+    let headers: Vec<Value<'gc>> = headers
+        .iter()
+        .flat_map(|(name, value)| [name, value])
+        .map(|text| AvmString::new_utf8(activation.context.gc_context, text).into())
+        .collect();
+    let headers = ArrayObject::from_storage(activation, ArrayStorage::from_args(&headers))?;
+    let url = AvmString::new_utf8(activation.context.gc_context, url);
+
+    this.call_property(
+        &Multiname::new(
+            Namespace::Namespace("__ruffle__".into()),
+            "dispatchHttpStatus",
+        ),
+        &[event_type.into(), status.into(), url.into(), headers.into()],
+        activation,
+    )?;
+    Ok(())
+}
+
+/// Reports that the movie may not load data from `url` into `this`.
+pub fn dispatch_security_error<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    url: &str,
+) -> Result<(), Error<'gc>> {
+    let text = policy_file::violation_message(activation.context.swf.url(), url);
+    let text = AvmString::new_utf8(activation.context.gc_context, text);
+    let security_error_evt_cls = activation.avm2().classes().securityerrorevent;
+    let event = security_error_evt_cls.construct(
+        activation,
+        &[
+            "securityError".into(),
+            false.into(),
+            false.into(),
+            text.into(),
+            2048.into(),
+        ],
+    )?;
+    Avm2::dispatch_event(&mut activation.context, event, this)?;
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! `flash.net.URLStream` native function definitions
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::globals::flash::net::url_loader::request_from_url_request;
+use crate::avm2::object::{ByteArrayObject, TObject};
+use crate::avm2::{Activation, Error, Multiname, Namespace, Object, Value};
+use crate::policy_file;
+use crate::string::AvmString;
+
+/// Something that happened to the download of a `URLStream`.
+pub enum UrlStreamEvent {
+    /// The download started.
+    Open,
+
+    /// Data was received, making `bytes_loaded` bytes of `bytes_total` so far.
+    Data {
+        data: Vec<u8>,
+        bytes_loaded: usize,
+        bytes_total: Option<usize>,
+    },
+
+    /// The download completed.
+    Complete,
+
+    /// The download failed.
+    IoError,
+
+    /// The movie may not load data from the given URL.
+    SecurityError(String),
+}
+
+/// Implements `URLStream.loadStream`
+pub fn load_stream<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (this, request) = match (this, args.get(0)) {
+        (Some(this), Some(Value::Object(request))) => (this, request),
+        _ => return Ok(Value::Undefined),
+    };
+
+    let request = request_from_url_request(activation, request)?;
+    let future = activation.context.load_manager.load_url_stream(
+        activation.context.player.clone(),
+        this,
+        request,
+    );
+    activation.context.navigator.spawn_future(future);
+    Ok(Value::Undefined)
+}
+
+/// Implements `URLStream.cancelLoad`
+pub fn cancel_load<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation.context.load_manager.cancel_url_stream(this);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Reports something that happened to the download of `this` with the matching event.
+pub fn dispatch_event<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    event: UrlStreamEvent,
+) -> Result<(), Error<'gc>> {
+    let (name, args): (_, Vec<Value<'gc>>) = match event {
+        UrlStreamEvent::Open => ("dispatchOpen", vec![]),
+        UrlStreamEvent::Data {
+            data,
+            bytes_loaded,
+            bytes_total,
+        } => {
+            let data = ByteArrayObject::from_storage(activation, ByteArrayStorage::from_vec(data))?;
+            // Flash reports an unknown length as 0.
+            let bytes_total = bytes_total.unwrap_or_default();
+            (
+                "receiveData",
+                vec![
+                    data.into(),
+                    (bytes_loaded as f64).into(),
+                    (bytes_total as f64).into(),
+                ],
+            )
+        }
+        UrlStreamEvent::Complete => ("dispatchComplete", vec![]),
+        UrlStreamEvent::IoError => ("dispatchIOError", vec![]),
+        UrlStreamEvent::SecurityError(url) => {
+            let text = policy_file::violation_message(activation.context.swf.url(), &url);
+            let text = AvmString::new_utf8(activation.context.gc_context, text);
+            ("dispatchSecurityError", vec![text.into()])
+        }
+    };
+
+    this.call_property(
+        &Multiname::new(Namespace::Namespace("__ruffle__".into()), name),
+        &args,
+        activation,
+    )?;
+    Ok(())
+}
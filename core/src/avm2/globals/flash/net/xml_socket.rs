@@ -0,0 +1,87 @@
+//! `flash.net.XMLSocket` builtin/prototype
+
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::socket::{movie_host, SocketKind, SocketManager, SocketObject};
+
+/// Implements `XMLSocket.connected`'s getter
+pub fn get_connected<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let connected = match this {
+        Some(this) => activation
+            .context
+            .socket_manager
+            .is_connected(SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(connected.into())
+}
+
+/// Implements `XMLSocket.connectSocket`
+pub fn connect_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let host = match args.get(0).unwrap_or(&Value::Null) {
+        Value::Null | Value::Undefined => movie_host(activation.context.swf.url()),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let timeout = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    SocketManager::connect(
+        &mut activation.context,
+        SocketObject::Avm2(this),
+        host,
+        port as u16,
+        SocketKind::Xml,
+        timeout.max(0) as u32,
+    );
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.closeSocket`
+pub fn close_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let closed = match this {
+        Some(this) => SocketManager::close(&mut activation.context, SocketObject::Avm2(this)),
+        None => false,
+    };
+    Ok(closed.into())
+}
+
+/// Implements `XMLSocket.sendMessage`
+pub fn send_message<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let message = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let mut data = message.to_utf8_lossy().into_owned().into_bytes();
+    data.push(0);
+    SocketManager::send(&mut activation.context, SocketObject::Avm2(this), data);
+    Ok(Value::Undefined)
+}
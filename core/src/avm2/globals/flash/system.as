@@ -0,0 +1,3 @@
+package flash.system {
+    public native function fscommand(command:String, args:String = ""):void;
+}
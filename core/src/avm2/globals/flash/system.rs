@@ -1,6 +1,36 @@
 //! `flash.system` namespace
 #![allow(clippy::module_inception)]
 
+use crate::avm2::{Activation, Error, Object, Value};
+
 pub mod application_domain;
+pub mod message_channel;
 pub mod security;
 pub mod system;
+pub mod worker;
+pub mod worker_domain;
+
+/// Implements `flash.system.fscommand`
+pub fn fscommand<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let command = args
+        .get(0)
+        .ok_or("fscommand: not enough arguments")?
+        .coerce_to_string(activation)?;
+    let fs_args = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    let command = command.to_utf8_lossy();
+    if !activation
+        .context
+        .fs_command(&command, &fs_args.to_utf8_lossy())
+    {
+        tracing::warn!("Unhandled FSCommand: {}", command);
+    }
+    Ok(Value::Undefined)
+}
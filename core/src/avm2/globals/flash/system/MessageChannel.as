@@ -0,0 +1,26 @@
+package flash.system
+{
+    import flash.events.EventDispatcher;
+
+
+    public final class MessageChannel extends EventDispatcher
+    {
+        // The ID of the channel in the worker group of the player.
+        ruffle var _id: int;
+
+        public function MessageChannel() {
+            // Channels are only created by `Worker.createMessageChannel`, and for the
+            // channels handed over by other workers.
+        }
+
+        public native function get messageAvailable(): Boolean;
+
+        public native function get state(): String;
+
+        public native function close(): void;
+
+        public native function receive(blockUntilReceived: Boolean = false): *;
+
+        public native function send(arg: *, queueLimit: int = -1): void;
+    }
+}
@@ -0,0 +1,35 @@
+package flash.system
+{
+    import flash.events.EventDispatcher;
+
+    namespace ruffle = "__ruffle__";
+
+    public final class Worker extends EventDispatcher
+    {
+        // The ID of the worker in the worker group of the player.
+        ruffle var _id: int;
+
+        public function Worker() {
+            // Workers are only created by `WorkerDomain.createWorker`, and for the
+            // workers handed over by other workers.
+        }
+
+        public static native function get current(): Worker;
+
+        public static native function get isSupported(): Boolean;
+
+        public native function get isPrimordial(): Boolean;
+
+        public native function get state(): String;
+
+        public native function createMessageChannel(receiver: Worker): MessageChannel;
+
+        public native function getSharedProperty(key: String): *;
+
+        public native function setSharedProperty(key: String, value: *): void;
+
+        public native function start(): void;
+
+        public native function terminate(): Boolean;
+    }
+}
@@ -0,0 +1,28 @@
+package flash.system
+{
+    import flash.utils.ByteArray;
+
+    public final class WorkerDomain
+    {
+        private static var _current: WorkerDomain;
+
+        public function WorkerDomain() {
+
+        }
+
+        public static function get current(): WorkerDomain {
+            if (_current == null) {
+                _current = new WorkerDomain();
+            }
+            return _current;
+        }
+
+        public static function get isSupported(): Boolean {
+            return true;
+        }
+
+        public native function createWorker(swf: ByteArray, giveAppPrivileges: Boolean = false): Worker;
+
+        public native function listWorkers(): Vector.<Worker>;
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::domain::Domain;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{appdomain_allocator, DomainObject, Object, TObject};
 use crate::avm2::value::Value;
@@ -15,10 +16,19 @@ use gc_arena::{GcCell, MutationContext};
 pub fn instance_init<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
         activation.super_init(this, &[])?;
+
+        let parent = args
+            .get(0)
+            .and_then(|parent| parent.as_object())
+            .and_then(|parent| parent.as_application_domain());
+        if let (Object::DomainObject(this), Some(parent)) = (this, parent) {
+            let domain = Domain::movie_domain(activation, parent);
+            this.set_domain(activation.context.gc_context, domain);
+        }
     }
 
     Ok(Value::Undefined)
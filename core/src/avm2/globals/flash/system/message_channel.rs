@@ -0,0 +1,108 @@
+//! `flash.system.MessageChannel` native methods
+
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::string::AvmString;
+use crate::worker::{ChannelId, WorkerManager};
+
+/// Returns the channel of `this`.
+fn channel_id<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+) -> Result<Option<ChannelId>, Error<'gc>> {
+    match this {
+        Some(this) => Ok(Some(WorkerManager::channel_id(activation, this)?)),
+        None => Ok(None),
+    }
+}
+
+/// Implements `MessageChannel.messageAvailable`
+pub fn get_message_available<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = channel_id(activation, this)? {
+        let group = activation.context.worker_manager.group().lock();
+        return Ok(group.message_available(id).into());
+    }
+    Ok(false.into())
+}
+
+/// Implements `MessageChannel.state`
+pub fn get_state<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = channel_id(activation, this)? {
+        let state = activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .channel_state(id);
+        return Ok(AvmString::from(state.as_str()).into());
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = channel_id(activation, this)? {
+        activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .close_channel(id);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.receive`
+pub fn receive<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = channel_id(activation, this)? {
+        let block = args.get(0).map_or(false, |block| block.coerce_to_boolean());
+        if let Some(message) = WorkerManager::receive(&mut activation.context, id, block) {
+            return WorkerManager::deserialize(activation, &message);
+        }
+    }
+    Ok(Value::Null)
+}
+
+/// Implements `MessageChannel.send`
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = channel_id(activation, this)? {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let queue_limit = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| (-1).into())
+            .coerce_to_i32(activation)?;
+        let value = WorkerManager::serialize(activation, value)?;
+        let sent = activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .send(id, value, queue_limit);
+        if !sent {
+            tracing::warn!(
+                "MessageChannel.send: message dropped, as the channel is closed or full"
+            );
+        }
+    }
+    Ok(Value::Undefined)
+}
@@ -6,40 +6,75 @@ use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2_stub_getter;
 use crate::string::AvmString;
+use std::sync::Arc;
 
 pub fn get_sandbox_type<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let sandbox_type = activation.context.system.sandbox_type.to_string();
-    return Ok(AvmString::new_utf8(activation.context.gc_context, sandbox_type).into());
+    let movie = activation.caller_movie();
+    // The root movie's sandbox also accounts for the URL it pretends to be loaded from.
+    let sandbox_type = if Arc::ptr_eq(&movie, activation.context.swf) {
+        activation.context.system.sandbox_type
+    } else {
+        activation
+            .context
+            .policy_manager
+            .sandbox_type(movie.url(), movie.use_network_sandbox())
+    };
+    Ok(AvmString::new_utf8(activation.context.gc_context, sandbox_type.to_string()).into())
+}
+
+/// Lets the movies from the given domains script the calling movie.
+fn allow_domains<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    args: &[Value<'gc>],
+    insecure: bool,
+) -> Result<(), Error<'gc>> {
+    let movie = activation.caller_movie();
+    for domain in args {
+        let domain = domain.coerce_to_string(activation)?;
+        activation.context.policy_manager.allow_domain(
+            movie.url(),
+            &domain.to_utf8_lossy(),
+            insecure,
+        );
+    }
+    Ok(())
 }
 
 pub fn allow_domain<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.system.Security", "allowDomain");
+    allow_domains(activation, args, false)?;
     Ok(Value::Undefined)
 }
 
 pub fn allow_insecure_domain<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.system.Security", "allowInsecureDomain");
+    allow_domains(activation, args, true)?;
     Ok(Value::Undefined)
 }
 
 pub fn load_policy_file<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.system.Security", "loadPolicyFile");
+    let url = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    activation
+        .context
+        .policy_manager
+        .load_policy_file(activation.context.swf.url(), &url.to_utf8_lossy());
     Ok(Value::Undefined)
 }
 
@@ -0,0 +1,169 @@
+//! `flash.system.Worker` native methods
+
+use crate::avm2::error::argument_error;
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::string::AvmString;
+use crate::worker::{WorkerId, WorkerManager};
+
+/// Returns the worker of `this`.
+fn worker_id<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+) -> Result<Option<WorkerId>, Error<'gc>> {
+    match this {
+        Some(this) => Ok(Some(WorkerManager::worker_id(activation, this)?)),
+        None => Ok(None),
+    }
+}
+
+/// Implements `Worker.current`
+pub fn get_current<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let current = activation.context.worker_manager.current();
+    Ok(WorkerManager::worker_object(activation, current)?.into())
+}
+
+/// Implements `Worker.isSupported`
+pub fn get_is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Workers run on threads of their own.
+    Ok(cfg!(not(target_family = "wasm")).into())
+}
+
+/// Implements `Worker.isPrimordial`
+pub fn get_is_primordial<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = worker_id(activation, this)? {
+        return Ok(id.is_primordial().into());
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.state`
+pub fn get_state<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = worker_id(activation, this)? {
+        let state = activation.context.worker_manager.group().lock().state(id);
+        return Ok(AvmString::from(state.as_str()).into());
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.createMessageChannel`
+pub fn create_message_channel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(sender) = worker_id(activation, this)? {
+        let receiver = match args.get(0) {
+            Some(Value::Object(receiver)) => WorkerManager::worker_id(activation, *receiver)?,
+            _ => {
+                return Err(Error::AvmError(argument_error(
+                    activation,
+                    "Error #2007: Parameter receiver must be non-null.",
+                    2007,
+                )?));
+            }
+        };
+        let channel = activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .create_channel(sender, receiver);
+        return Ok(WorkerManager::channel_object(activation, channel)?.into());
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.getSharedProperty`
+pub fn get_shared_property<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = worker_id(activation, this)? {
+        let key = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+        let value = activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .shared_property(id, &key);
+        if let Some(value) = value {
+            return WorkerManager::deserialize(activation, &value);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.setSharedProperty`
+pub fn set_shared_property<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = worker_id(activation, this)? {
+        let key = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?
+            .to_string();
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+        let value = WorkerManager::serialize(activation, value)?;
+        activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .set_shared_property(id, key, value);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.start`
+pub fn start<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = worker_id(activation, this)? {
+        WorkerManager::start(&mut activation.context, id);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.terminate`
+pub fn terminate<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(id) = worker_id(activation, this)? {
+        let was_running = activation
+            .context
+            .worker_manager
+            .group()
+            .lock()
+            .terminate(id);
+        return Ok(was_running.into());
+    }
+    Ok(false.into())
+}
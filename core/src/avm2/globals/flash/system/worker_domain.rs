@@ -0,0 +1,74 @@
+//! `flash.system.WorkerDomain` native methods
+
+use crate::avm2::error::argument_error;
+use crate::avm2::object::{TObject, VectorObject};
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::{Activation, Error, Object, Value};
+use crate::tag_utils::SwfMovie;
+use crate::worker::WorkerManager;
+
+/// Implements `WorkerDomain.createWorker`
+pub fn create_worker<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let swf = match args.get(0) {
+        Some(Value::Object(swf)) => *swf,
+        _ => {
+            return Err(Error::AvmError(argument_error(
+                activation,
+                "Error #2007: Parameter swf must be non-null.",
+                2007,
+            )?));
+        }
+    };
+    let movie = match swf.as_bytearray() {
+        Some(bytes) => SwfMovie::from_data(
+            &bytes.bytes(),
+            activation.context.swf.url().map(str::to_string),
+            activation.context.swf.loader_url().map(str::to_string),
+        ),
+        None => return Ok(Value::Null),
+    };
+    let movie = match movie {
+        Ok(movie) => movie,
+        Err(e) => {
+            tracing::error!("WorkerDomain.createWorker: invalid SWF: {}", e);
+            return Err(Error::AvmError(argument_error(
+                activation,
+                "Error #2004: One of the parameters is invalid.",
+                2004,
+            )?));
+        }
+    };
+
+    let id = activation
+        .context
+        .worker_manager
+        .group()
+        .lock()
+        .create_worker(movie);
+    Ok(WorkerManager::worker_object(activation, id)?.into())
+}
+
+/// Implements `WorkerDomain.listWorkers`
+pub fn list_workers<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let ids = activation
+        .context
+        .worker_manager
+        .group()
+        .lock()
+        .running_workers();
+    let mut workers = Vec::with_capacity(ids.len());
+    for id in ids {
+        workers.push(WorkerManager::worker_object(activation, id)?.into());
+    }
+    let worker_class = activation.avm2().classes().worker;
+    let storage = VectorStorage::from_values(workers, false, worker_class, activation);
+    Ok(VectorObject::from_vector(storage, activation)?.into())
+}
@@ -1,6 +1,8 @@
 //! `flash.text` namespace
 
+pub mod engine;
 pub mod font;
 pub mod static_text;
+pub mod text_snapshot;
 pub mod textfield;
 pub mod textformat;
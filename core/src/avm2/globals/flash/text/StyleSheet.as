@@ -0,0 +1,148 @@
+package flash.text {
+    import flash.events.EventDispatcher;
+
+    public dynamic class StyleSheet extends EventDispatcher {
+        // The style objects, by lowercase selector.
+        private var _styles: Object = {};
+
+        public function StyleSheet() {
+        }
+
+        public function get styleNames(): Array {
+            var names = [];
+            for (var name in this._styles) {
+                names.push(name);
+            }
+            return names;
+        }
+
+        public function clear(): void {
+            this._styles = {};
+        }
+
+        public function getStyle(styleName: String): Object {
+            var style = this._styles[styleName.toLowerCase()];
+            if (style == null) {
+                return null;
+            }
+            return copy(style);
+        }
+
+        public function setStyle(styleName: String, styleObject: Object): void {
+            if (styleObject == null) {
+                delete this._styles[styleName.toLowerCase()];
+            } else {
+                this._styles[styleName.toLowerCase()] = copy(styleObject);
+            }
+        }
+
+        public function parseCSS(CSSText: String): void {
+            var css = CSSText.replace(/\/\*[\s\S]*?\*\//g, "");
+            var rule = /([^{}]+)\{([^}]*)\}/g;
+            var match;
+            while ((match = rule.exec(css)) != null) {
+                var style = {};
+                var declarations = match[2].split(";");
+                for each (var declaration in declarations) {
+                    var colon = declaration.indexOf(":");
+                    if (colon < 0) {
+                        continue;
+                    }
+                    var property = trim(declaration.substring(0, colon));
+                    var value = trim(declaration.substring(colon + 1));
+                    if (property.length == 0) {
+                        continue;
+                    }
+                    // `font-size` is given as `fontSize`, like in the style objects.
+                    property = property.replace(/-([a-z])/g, function(m, letter, ...rest) {
+                        return letter.toUpperCase();
+                    });
+                    style[property] = value;
+                }
+
+                var selectors = match[1].split(",");
+                for each (var selector in selectors) {
+                    selector = trim(selector);
+                    if (selector.length == 0) {
+                        continue;
+                    }
+                    var existing = this._styles[selector.toLowerCase()] || {};
+                    for (var name in style) {
+                        existing[name] = style[name];
+                    }
+                    this._styles[selector.toLowerCase()] = existing;
+                }
+            }
+        }
+
+        public function transform(formatObject: Object): TextFormat {
+            if (formatObject == null) {
+                return null;
+            }
+
+            var format = new TextFormat();
+            for (var name in formatObject) {
+                var value = String(formatObject[name]);
+                switch (name) {
+                    case "color":
+                        if (value.charAt(0) == "#") {
+                            format.color = parseInt(value.substring(1), 16);
+                        }
+                        break;
+                    case "fontFamily":
+                        format.font = value;
+                        break;
+                    case "fontSize":
+                        var size = parseInt(value);
+                        if (!isNaN(size)) {
+                            format.size = size;
+                        }
+                        break;
+                    case "fontStyle":
+                        format.italic = value == "italic";
+                        break;
+                    case "fontWeight":
+                        format.bold = value == "bold";
+                        break;
+                    case "kerning":
+                        format.kerning = value == "true";
+                        break;
+                    case "leading":
+                        format.leading = parseInt(value);
+                        break;
+                    case "letterSpacing":
+                        format.letterSpacing = parseFloat(value);
+                        break;
+                    case "marginLeft":
+                        format.leftMargin = parseInt(value);
+                        break;
+                    case "marginRight":
+                        format.rightMargin = parseInt(value);
+                        break;
+                    case "textAlign":
+                        format.align = value;
+                        break;
+                    case "textDecoration":
+                        format.underline = value == "underline";
+                        break;
+                    case "textIndent":
+                        format.indent = parseInt(value);
+                        break;
+                }
+            }
+            return format;
+        }
+
+        private static function copy(object: Object): Object {
+            var result = {};
+            for (var name in object) {
+                result[name] = object[name];
+            }
+            return result;
+        }
+
+        private static function trim(value: String): String {
+            return value.replace(/^\s+|\s+$/g, "");
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// This is a stub - the actual class is defined in `textfield.rs`
+package flash.text {
+    import flash.display.InteractiveObject;
+
+    public class TextField extends InteractiveObject {
+    }
+}
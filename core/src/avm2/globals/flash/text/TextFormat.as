@@ -0,0 +1,5 @@
+// This is a stub - the actual class is defined in `textformat.rs`
+package flash.text {
+    public class TextFormat {
+    }
+}
@@ -0,0 +1,60 @@
+package flash.text {
+    import flash.display.DisplayObjectContainer;
+
+    // A snapshot reads the static text among the children of a container, in depth order, as
+    // one string of characters.
+    public class TextSnapshot {
+        private var _container: DisplayObjectContainer;
+
+        public function TextSnapshot(container: DisplayObjectContainer = null) {
+            this._container = container;
+        }
+
+        public function get charCount(): int {
+            return this.countChars(this._container);
+        }
+
+        public function findText(beginIndex: int, textToFind: String, caseSensitive: Boolean): int {
+            var text: String = this.getText(0, this.charCount);
+            if (!caseSensitive) {
+                text = text.toLowerCase();
+                textToFind = textToFind.toLowerCase();
+            }
+            return text.indexOf(textToFind, beginIndex);
+        }
+
+        public function getSelected(beginIndex: int, endIndex: int): Boolean {
+            return this.readSelected(this._container, beginIndex, endIndex);
+        }
+
+        public function getSelectedText(includeLineEndings: Boolean = false): String {
+            return this.readSelectedText(this._container, includeLineEndings);
+        }
+
+        public function getText(beginIndex: int, endIndex: int, includeLineEndings: Boolean = false): String {
+            return this.readText(this._container, beginIndex, endIndex, includeLineEndings);
+        }
+
+        public native function getTextRunInfo(beginIndex: int, endIndex: int): Array;
+
+        public function hitTestTextNearPos(x: Number, y: Number, maxDistance: Number = 0): Number {
+            return this.hitTestChars(this._container, x, y, maxDistance);
+        }
+
+        public function setSelectColor(hexColor: uint = 0xFFFF00): void {
+            this.writeSelectColor(this._container, hexColor);
+        }
+
+        public function setSelected(beginIndex: int, endIndex: int, select: Boolean): void {
+            this.writeSelected(this._container, beginIndex, endIndex, select);
+        }
+
+        private native function countChars(container: DisplayObjectContainer): int;
+        private native function readSelected(container: DisplayObjectContainer, beginIndex: int, endIndex: int): Boolean;
+        private native function readSelectedText(container: DisplayObjectContainer, includeLineEndings: Boolean): String;
+        private native function readText(container: DisplayObjectContainer, beginIndex: int, endIndex: int, includeLineEndings: Boolean): String;
+        private native function hitTestChars(container: DisplayObjectContainer, x: Number, y: Number, maxDistance: Number): Number;
+        private native function writeSelectColor(container: DisplayObjectContainer, hexColor: uint): void;
+        private native function writeSelected(container: DisplayObjectContainer, beginIndex: int, endIndex: int, select: Boolean): void;
+    }
+}
@@ -0,0 +1,4 @@
+//! `flash.text.engine` namespace
+
+pub mod text_block;
+pub mod text_line;
@@ -0,0 +1,112 @@
+package flash.text.engine {
+    import flash.events.EventDispatcher;
+
+    public class ContentElement {
+        public static const GRAPHIC_ELEMENT: uint = 0xFDEF;
+
+        internal var _elementFormat: ElementFormat;
+        internal var _eventMirror: EventDispatcher;
+        internal var _textRotation: String;
+
+        // The block this element is the content of, if it's not part of a group.
+        internal var _textBlock: TextBlock = null;
+
+        internal var _groupElement: GroupElement = null;
+
+        public var userData: *;
+
+        public function ContentElement(elementFormat: ElementFormat = null, eventMirror: EventDispatcher = null, textRotation: String = "rotate0") {
+            if (Object(this).constructor === ContentElement) {
+                throw new ArgumentError("Error #2012: ContentElement$ class cannot be instantiated.", 2012);
+            }
+            this._elementFormat = elementFormat;
+            this._eventMirror = eventMirror;
+            this._textRotation = textRotation;
+        }
+
+        public function get elementFormat(): ElementFormat {
+            return this._elementFormat;
+        }
+
+        public function set elementFormat(value: ElementFormat): void {
+            this._elementFormat = value;
+            this.invalidate();
+        }
+
+        public function get eventMirror(): EventDispatcher {
+            return this._eventMirror;
+        }
+
+        public function set eventMirror(value: EventDispatcher): void {
+            this._eventMirror = value;
+        }
+
+        public function get textRotation(): String {
+            return this._textRotation;
+        }
+
+        public function set textRotation(value: String): void {
+            this._textRotation = value;
+        }
+
+        public function get groupElement(): GroupElement {
+            return this._groupElement;
+        }
+
+        public function get textBlock(): TextBlock {
+            if (this._groupElement != null) {
+                return this._groupElement.textBlock;
+            }
+            return this._textBlock;
+        }
+
+        public function get textBlockBeginIndex(): int {
+            if (this._groupElement != null) {
+                return this._groupElement.textBlockBeginIndex + this._groupElement.offsetOf(this);
+            }
+            return 0;
+        }
+
+        public function get rawText(): String {
+            return null;
+        }
+
+        public function get text(): String {
+            return this.rawText;
+        }
+
+        // The text laid out for this element, which is as long as `rawText`.
+        internal function get layoutText(): String {
+            var text: String = this.rawText;
+            return text != null ? text : "";
+        }
+
+        // The element format in effect, which is inherited from the groups.
+        internal function get effectiveFormat(): ElementFormat {
+            if (this._elementFormat != null) {
+                return this._elementFormat;
+            }
+            if (this._groupElement != null) {
+                return this._groupElement.effectiveFormat;
+            }
+            return null;
+        }
+
+        // Marks the lines laid out from the text of this element as invalid.
+        internal function invalidate(): void {
+            var block: TextBlock = this.textBlock;
+            if (block != null) {
+                block.invalidateLines();
+            }
+        }
+
+        // Adds the runs of text of this element, as [beginIndex, endIndex, ElementFormat] triples
+        // relative to the start of the block.
+        internal function collectRuns(beginIndex: int, runs: Array): void {
+            var length: int = this.layoutText.length;
+            if (length > 0) {
+                runs.push([beginIndex, beginIndex + length, this.effectiveFormat]);
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+package flash.text.engine {
+    import flash.errors.IllegalOperationError;
+    import flash.geom.Rectangle;
+
+    public final class ElementFormat {
+        private var _alignmentBaseline: String;
+        private var _alpha: Number;
+        private var _baselineShift: Number;
+        private var _breakOpportunity: String;
+        private var _color: uint;
+        private var _digitCase: String;
+        private var _digitWidth: String;
+        private var _dominantBaseline: String;
+        private var _fontDescription: FontDescription;
+        private var _fontSize: Number;
+        private var _kerning: String;
+        private var _ligatureLevel: String;
+        private var _locale: String;
+        private var _textRotation: String;
+        private var _trackingLeft: Number;
+        private var _trackingRight: Number;
+        private var _typographicCase: String;
+
+        public var locked: Boolean = false;
+
+        public function ElementFormat(fontDescription: FontDescription = null,
+                                      fontSize: Number = 12,
+                                      color: uint = 0,
+                                      alpha: Number = 1,
+                                      textRotation: String = "auto",
+                                      dominantBaseline: String = "roman",
+                                      alignmentBaseline: String = "useDominantBaseline",
+                                      baselineShift: Number = 0,
+                                      kerning: String = "on",
+                                      trackingRight: Number = 0,
+                                      trackingLeft: Number = 0,
+                                      locale: String = "en",
+                                      breakOpportunity: String = "auto",
+                                      digitCase: String = "default",
+                                      digitWidth: String = "default",
+                                      ligatureLevel: String = "common",
+                                      typographicCase: String = "default") {
+            this._fontDescription = fontDescription != null ? fontDescription : new FontDescription();
+            this._fontSize = fontSize;
+            this._color = color;
+            this._alpha = alpha;
+            this._textRotation = textRotation;
+            this._dominantBaseline = dominantBaseline;
+            this._alignmentBaseline = alignmentBaseline;
+            this._baselineShift = baselineShift;
+            this._kerning = kerning;
+            this._trackingRight = trackingRight;
+            this._trackingLeft = trackingLeft;
+            this._locale = locale;
+            this._breakOpportunity = breakOpportunity;
+            this._digitCase = digitCase;
+            this._digitWidth = digitWidth;
+            this._ligatureLevel = ligatureLevel;
+            this._typographicCase = typographicCase;
+        }
+
+        public function get alignmentBaseline(): String {
+            return this._alignmentBaseline;
+        }
+
+        public function set alignmentBaseline(value: String): void {
+            this.checkLocked();
+            this._alignmentBaseline = value;
+        }
+
+        public function get alpha(): Number {
+            return this._alpha;
+        }
+
+        public function set alpha(value: Number): void {
+            this.checkLocked();
+            this._alpha = value;
+        }
+
+        public function get baselineShift(): Number {
+            return this._baselineShift;
+        }
+
+        public function set baselineShift(value: Number): void {
+            this.checkLocked();
+            this._baselineShift = value;
+        }
+
+        public function get breakOpportunity(): String {
+            return this._breakOpportunity;
+        }
+
+        public function set breakOpportunity(value: String): void {
+            this.checkLocked();
+            this._breakOpportunity = value;
+        }
+
+        public function get color(): uint {
+            return this._color;
+        }
+
+        public function set color(value: uint): void {
+            this.checkLocked();
+            this._color = value;
+        }
+
+        public function get digitCase(): String {
+            return this._digitCase;
+        }
+
+        public function set digitCase(value: String): void {
+            this.checkLocked();
+            this._digitCase = value;
+        }
+
+        public function get digitWidth(): String {
+            return this._digitWidth;
+        }
+
+        public function set digitWidth(value: String): void {
+            this.checkLocked();
+            this._digitWidth = value;
+        }
+
+        public function get dominantBaseline(): String {
+            return this._dominantBaseline;
+        }
+
+        public function set dominantBaseline(value: String): void {
+            this.checkLocked();
+            this._dominantBaseline = value;
+        }
+
+        public function get fontDescription(): FontDescription {
+            return this._fontDescription;
+        }
+
+        public function set fontDescription(value: FontDescription): void {
+            this.checkLocked();
+            this._fontDescription = value;
+        }
+
+        public function get fontSize(): Number {
+            return this._fontSize;
+        }
+
+        public function set fontSize(value: Number): void {
+            this.checkLocked();
+            this._fontSize = value;
+        }
+
+        public function get kerning(): String {
+            return this._kerning;
+        }
+
+        public function set kerning(value: String): void {
+            this.checkLocked();
+            this._kerning = value;
+        }
+
+        public function get ligatureLevel(): String {
+            return this._ligatureLevel;
+        }
+
+        public function set ligatureLevel(value: String): void {
+            this.checkLocked();
+            this._ligatureLevel = value;
+        }
+
+        public function get locale(): String {
+            return this._locale;
+        }
+
+        public function set locale(value: String): void {
+            this.checkLocked();
+            this._locale = value;
+        }
+
+        public function get textRotation(): String {
+            return this._textRotation;
+        }
+
+        public function set textRotation(value: String): void {
+            this.checkLocked();
+            this._textRotation = value;
+        }
+
+        public function get trackingLeft(): Number {
+            return this._trackingLeft;
+        }
+
+        public function set trackingLeft(value: Number): void {
+            this.checkLocked();
+            this._trackingLeft = value;
+        }
+
+        public function get trackingRight(): Number {
+            return this._trackingRight;
+        }
+
+        public function set trackingRight(value: Number): void {
+            this.checkLocked();
+            this._trackingRight = value;
+        }
+
+        public function get typographicCase(): String {
+            return this._typographicCase;
+        }
+
+        public function set typographicCase(value: String): void {
+            this.checkLocked();
+            this._typographicCase = value;
+        }
+
+        public function clone(): ElementFormat {
+            return new ElementFormat(this._fontDescription.clone(), this._fontSize, this._color, this._alpha,
+                                     this._textRotation, this._dominantBaseline, this._alignmentBaseline,
+                                     this._baselineShift, this._kerning, this._trackingRight, this._trackingLeft,
+                                     this._locale, this._breakOpportunity, this._digitCase, this._digitWidth,
+                                     this._ligatureLevel, this._typographicCase);
+        }
+
+        // The metrics are estimated from the font size, in the proportions of common Latin fonts.
+        public function getFontMetrics(): FontMetrics {
+            var size: Number = this._fontSize;
+            var emBox: Rectangle = new Rectangle(0, -0.8 * size, size, size);
+            return new FontMetrics(emBox, -0.3 * size, 0.05 * size, 0.1 * size, 0.05 * size,
+                                   0.15 * size, 0.6, -0.35 * size, 0.6);
+        }
+
+        private function checkLocked(): void {
+            if (this.locked) {
+                throw new IllegalOperationError("Error #2185: The ElementFormat object is locked and cannot be modified.", 2185);
+            }
+        }
+    }
+}
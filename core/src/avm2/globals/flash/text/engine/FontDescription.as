@@ -0,0 +1,101 @@
+package flash.text.engine {
+    import flash.errors.IllegalOperationError;
+
+    public final class FontDescription {
+        private var _fontName: String;
+        private var _fontWeight: String;
+        private var _fontPosture: String;
+        private var _fontLookup: String;
+        private var _renderingMode: String;
+        private var _cffHinting: String;
+
+        public var locked: Boolean = false;
+
+        public function FontDescription(fontName: String = "_serif",
+                                        fontWeight: String = "normal",
+                                        fontPosture: String = "normal",
+                                        fontLookup: String = "device",
+                                        renderingMode: String = "cff",
+                                        cffHinting: String = "horizontalStem") {
+            this._fontName = fontName;
+            this._fontWeight = fontWeight;
+            this._fontPosture = fontPosture;
+            this._fontLookup = fontLookup;
+            this._renderingMode = renderingMode;
+            this._cffHinting = cffHinting;
+        }
+
+        public static function isFontCompatible(fontName: String, fontWeight: String, fontPosture: String): Boolean {
+            return false;
+        }
+
+        public static function isDeviceFontCompatible(fontName: String, fontWeight: String, fontPosture: String): Boolean {
+            return true;
+        }
+
+        public function get fontName(): String {
+            return this._fontName;
+        }
+
+        public function set fontName(value: String): void {
+            this.checkLocked();
+            this._fontName = value;
+        }
+
+        public function get fontWeight(): String {
+            return this._fontWeight;
+        }
+
+        public function set fontWeight(value: String): void {
+            this.checkLocked();
+            this._fontWeight = value;
+        }
+
+        public function get fontPosture(): String {
+            return this._fontPosture;
+        }
+
+        public function set fontPosture(value: String): void {
+            this.checkLocked();
+            this._fontPosture = value;
+        }
+
+        public function get fontLookup(): String {
+            return this._fontLookup;
+        }
+
+        public function set fontLookup(value: String): void {
+            this.checkLocked();
+            this._fontLookup = value;
+        }
+
+        public function get renderingMode(): String {
+            return this._renderingMode;
+        }
+
+        public function set renderingMode(value: String): void {
+            this.checkLocked();
+            this._renderingMode = value;
+        }
+
+        public function get cffHinting(): String {
+            return this._cffHinting;
+        }
+
+        public function set cffHinting(value: String): void {
+            this.checkLocked();
+            this._cffHinting = value;
+        }
+
+        public function clone(): FontDescription {
+            return new FontDescription(this._fontName, this._fontWeight, this._fontPosture,
+                                       this._fontLookup, this._renderingMode, this._cffHinting);
+        }
+
+        private function checkLocked(): void {
+            if (this.locked) {
+                throw new IllegalOperationError("Error #2185: The FontDescription object is locked and cannot be modified.", 2185);
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+package flash.text.engine {
+    import flash.display.DisplayObject;
+    import flash.events.EventDispatcher;
+
+    public final class GraphicElement extends ContentElement {
+        public var graphic: DisplayObject;
+        public var elementWidth: Number;
+        public var elementHeight: Number;
+
+        public function GraphicElement(graphic: DisplayObject = null, elementWidth: Number = 15.0, elementHeight: Number = 15.0,
+                                       elementFormat: ElementFormat = null, eventMirror: EventDispatcher = null, textRotation: String = "rotate0") {
+            super(elementFormat, eventMirror, textRotation);
+            this.graphic = graphic;
+            this.elementWidth = elementWidth;
+            this.elementHeight = elementHeight;
+        }
+
+        override public function get rawText(): String {
+            return String.fromCharCode(ContentElement.GRAPHIC_ELEMENT);
+        }
+
+        // Graphics aren't drawn yet, so they take the room of a space.
+        override internal function get layoutText(): String {
+            return " ";
+        }
+    }
+}
@@ -0,0 +1,103 @@
+package flash.text.engine {
+    import flash.events.EventDispatcher;
+
+    public final class GroupElement extends ContentElement {
+        private var _elements: Vector.<ContentElement> = new Vector.<ContentElement>();
+
+        public function GroupElement(elements: Vector.<ContentElement> = null, elementFormat: ElementFormat = null, eventMirror: EventDispatcher = null, textRotation: String = "rotate0") {
+            super(elementFormat, eventMirror, textRotation);
+            this.setElements(elements);
+        }
+
+        public function get elementCount(): int {
+            return this._elements.length;
+        }
+
+        public function getElementAt(index: int): ContentElement {
+            if (index < 0 || index >= this._elements.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+            return this._elements[index];
+        }
+
+        public function getElementIndex(element: ContentElement): int {
+            return this._elements.indexOf(element);
+        }
+
+        public function getElementAtCharIndex(charIndex: int): ContentElement {
+            var offset: int = 0;
+            for each (var element: ContentElement in this._elements) {
+                var length: int = element.layoutText.length;
+                if (charIndex >= offset && charIndex < offset + length) {
+                    return element;
+                }
+                offset += length;
+            }
+            return null;
+        }
+
+        public function setElements(value: Vector.<ContentElement>): void {
+            this.replaceElements(0, this._elements.length, value);
+        }
+
+        public function replaceElements(beginIndex: int, endIndex: int, newElements: Vector.<ContentElement>): Vector.<ContentElement> {
+            if (beginIndex < 0 || endIndex < beginIndex || endIndex > this._elements.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+            var removed: Vector.<ContentElement> = this._elements.splice(beginIndex, endIndex - beginIndex);
+            for each (var element: ContentElement in removed) {
+                element._groupElement = null;
+            }
+            if (newElements != null) {
+                for (var i: int = 0; i < newElements.length; i++) {
+                    var newElement: ContentElement = newElements[i];
+                    if (newElement._groupElement != null || newElement._textBlock != null) {
+                        throw new ArgumentError("Error #2004: One of the parameters is invalid.", 2004);
+                    }
+                    newElement._groupElement = this;
+                    this._elements.splice(beginIndex + i, 0, newElement);
+                }
+            }
+            this.invalidate();
+            return removed;
+        }
+
+        override public function get rawText(): String {
+            var text: String = "";
+            for each (var element: ContentElement in this._elements) {
+                var elementText: String = element.rawText;
+                if (elementText != null) {
+                    text += elementText;
+                }
+            }
+            return text;
+        }
+
+        override internal function get layoutText(): String {
+            var text: String = "";
+            for each (var element: ContentElement in this._elements) {
+                text += element.layoutText;
+            }
+            return text;
+        }
+
+        // The index of the text of `child` in the text of this group.
+        internal function offsetOf(child: ContentElement): int {
+            var offset: int = 0;
+            for each (var element: ContentElement in this._elements) {
+                if (element === child) {
+                    break;
+                }
+                offset += element.layoutText.length;
+            }
+            return offset;
+        }
+
+        override internal function collectRuns(beginIndex: int, runs: Array): void {
+            for each (var element: ContentElement in this._elements) {
+                element.collectRuns(beginIndex, runs);
+                beginIndex += element.layoutText.length;
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+package flash.text.engine {
+    public final class SpaceJustifier extends TextJustifier {
+        public var letterSpacing: Boolean;
+        public var minimumSpacing: Number = 0.5;
+        public var optimumSpacing: Number = 1.0;
+        public var maximumSpacing: Number = 1.5;
+
+        public function SpaceJustifier(locale: String = "en", lineJustification: String = "unjustified", letterSpacing: Boolean = false) {
+            super(locale, lineJustification);
+            this.letterSpacing = letterSpacing;
+        }
+
+        override public function clone(): TextJustifier {
+            var justifier: SpaceJustifier = new SpaceJustifier(this.locale, this.lineJustification, this.letterSpacing);
+            justifier.minimumSpacing = this.minimumSpacing;
+            justifier.optimumSpacing = this.optimumSpacing;
+            justifier.maximumSpacing = this.maximumSpacing;
+            return justifier;
+        }
+    }
+}
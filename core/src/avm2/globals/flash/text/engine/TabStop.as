@@ -0,0 +1,13 @@
+package flash.text.engine {
+    public final class TabStop {
+        public var alignment: String;
+        public var position: Number;
+        public var decimalAlignmentToken: String;
+
+        public function TabStop(alignment: String = "start", position: Number = 0.0, decimalAlignmentToken: String = "") {
+            this.alignment = alignment;
+            this.position = position;
+            this.decimalAlignmentToken = decimalAlignmentToken;
+        }
+    }
+}
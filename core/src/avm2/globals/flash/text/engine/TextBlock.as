@@ -0,0 +1,270 @@
+package flash.text.engine {
+    public final class TextBlock {
+        private var _content: ContentElement = null;
+        private var _firstLine: TextLine = null;
+        private var _lastLine: TextLine = null;
+        internal var _firstInvalidLine: TextLine = null;
+        private var _textLineCreationResult: String = null;
+
+        public var applyNonLinearFontScaling: Boolean;
+        public var baselineFontDescription: FontDescription;
+        public var baselineFontSize: Number;
+        public var baselineZero: String;
+        public var bidiLevel: int;
+        public var lineRotation: String;
+        public var tabStops: Vector.<TabStop>;
+        public var textJustifier: TextJustifier;
+        public var userData: *;
+
+        public function TextBlock(content: ContentElement = null, tabStops: Vector.<TabStop> = null, textJustifier: TextJustifier = null, lineRotation: String = "rotate0", baselineZero: String = "roman", bidiLevel: int = 0, applyNonLinearFontScaling: Boolean = true, baselineFontDescription: FontDescription = null, baselineFontSize: Number = 12) {
+            if (content != null) {
+                this.content = content;
+            }
+            this.tabStops = tabStops;
+            this.textJustifier = textJustifier != null ? textJustifier : TextJustifier.getJustifierForLocale("en");
+            this.lineRotation = lineRotation;
+            this.baselineZero = baselineZero;
+            this.bidiLevel = bidiLevel;
+            this.applyNonLinearFontScaling = applyNonLinearFontScaling;
+            this.baselineFontDescription = baselineFontDescription;
+            this.baselineFontSize = baselineFontSize;
+        }
+
+        public function get content(): ContentElement {
+            return this._content;
+        }
+
+        public function set content(value: ContentElement): void {
+            if (value != null && (value._groupElement != null || (value._textBlock != null && value._textBlock !== this))) {
+                throw new ArgumentError("Error #2004: One of the parameters is invalid.", 2004);
+            }
+            if (this._content != null) {
+                this._content._textBlock = null;
+            }
+            this._content = value;
+            if (value != null) {
+                value._textBlock = this;
+            }
+            this.invalidateLines();
+        }
+
+        public function get firstLine(): TextLine {
+            return this._firstLine;
+        }
+
+        public function get lastLine(): TextLine {
+            return this._lastLine;
+        }
+
+        public function get firstInvalidLine(): TextLine {
+            return this._firstInvalidLine;
+        }
+
+        public function get textLineCreationResult(): String {
+            return this._textLineCreationResult;
+        }
+
+        public function createTextLine(previousLine: TextLine = null, width: Number = 1000000, lineOffset: Number = 0.0, fitSomething: Boolean = false): TextLine {
+            return this.layoutNextLine(null, previousLine, width, fitSomething);
+        }
+
+        public function recreateTextLine(textLine: TextLine, previousLine: TextLine = null, width: Number = 1000000, lineOffset: Number = 0.0, fitSomething: Boolean = false): TextLine {
+            if (textLine == null) {
+                throw new TypeError("Error #2007: Parameter textLine must be non-null.", 2007);
+            }
+            if (textLine._textBlock != null && textLine._textBlock !== this) {
+                textLine._textBlock.releaseLines(textLine, textLine);
+            }
+            return this.layoutNextLine(textLine, previousLine, width, fitSomething);
+        }
+
+        private function layoutNextLine(textLine: TextLine, previousLine: TextLine, width: Number, fitSomething: Boolean): TextLine {
+            if (previousLine != null && previousLine._textBlock !== this) {
+                throw new ArgumentError("Error #2004: One of the parameters is invalid.", 2004);
+            }
+            if (width < 0 || width > TextLine.MAX_LINE_WIDTH) {
+                throw new ArgumentError("Error #2004: One of the parameters is invalid.", 2004);
+            }
+
+            var beginIndex: int = previousLine != null ? previousLine._textBlockBeginIndex + previousLine.rawTextLength : 0;
+            var text: String = this._content != null ? this._content.layoutText : "";
+            if (beginIndex >= text.length) {
+                this._textLineCreationResult = TextLineCreationResult.COMPLETE;
+                return null;
+            }
+
+            var runs: Array = [];
+            var textRuns: Array = [];
+            this._content.collectRuns(0, runs);
+            for each (var run: Array in runs) {
+                var runBegin: int = Math.max(run[0], beginIndex) - beginIndex;
+                var runEnd: int = run[1] - beginIndex;
+                if (runEnd > runBegin) {
+                    textRuns.push(runProperties(runBegin, runEnd, run[2]));
+                }
+            }
+
+            var line: TextLine = this.layoutLine(text.substring(beginIndex), textRuns, width, fitSomething, textLine);
+            if (line == null) {
+                this._textLineCreationResult = TextLineCreationResult.INSUFFICIENT_WIDTH;
+                return null;
+            }
+
+            // Lines after the previous one are no longer valid, and are replaced by this one.
+            if (previousLine != null && previousLine._nextLine != null && previousLine._nextLine !== line) {
+                this.releaseLines(previousLine._nextLine, this._lastLine);
+            } else if (previousLine == null && this._firstLine != null && this._firstLine !== line) {
+                this.releaseLines(this._firstLine, this._lastLine);
+            }
+
+            line._textBlock = this;
+            line._textBlockBeginIndex = beginIndex;
+            line._specifiedWidth = width;
+            line._validity = TextLineValidity.VALID;
+            line._previousLine = previousLine;
+            line._nextLine = null;
+            if (previousLine != null) {
+                previousLine._nextLine = line;
+            } else {
+                this._firstLine = line;
+            }
+            this._lastLine = line;
+            if (this._firstInvalidLine === line) {
+                this._firstInvalidLine = null;
+            }
+            this._textLineCreationResult = TextLineCreationResult.SUCCESS;
+            return line;
+        }
+
+        // Lays out as much of `text` as fits into `width`, into `line` or a new line if it's null.
+        // Returns null if nothing fits and `fitSomething` is false.
+        private native function layoutLine(text: String, runs: Array, width: Number, fitSomething: Boolean, line: TextLine): TextLine;
+
+        // Turns a run of an element format into the properties the native layout understands:
+        // [beginIndex, endIndex, font, size, color, bold, italic, kerning, letterSpacing].
+        private static function runProperties(beginIndex: int, endIndex: int, format: ElementFormat): Array {
+            if (format == null) {
+                format = new ElementFormat();
+            }
+            var font: FontDescription = format.fontDescription;
+            return [
+                beginIndex,
+                endIndex,
+                font.fontName,
+                format.fontSize,
+                format.color,
+                font.fontWeight == FontWeight.BOLD,
+                font.fontPosture == FontPosture.ITALIC,
+                format.kerning != Kerning.OFF,
+                format.trackingRight
+            ];
+        }
+
+        public function releaseLines(firstLine: TextLine, lastLine: TextLine): void {
+            if (firstLine == null || lastLine == null || firstLine._textBlock !== this || lastLine._textBlock !== this) {
+                throw new ArgumentError("Error #2004: One of the parameters is invalid.", 2004);
+            }
+            var before: TextLine = firstLine._previousLine;
+            var after: TextLine = lastLine._nextLine;
+            var line: TextLine = firstLine;
+            while (line != null) {
+                var next: TextLine = line._nextLine;
+                line._textBlock = null;
+                line._previousLine = null;
+                line._nextLine = null;
+                line._validity = TextLineValidity.INVALID;
+                if (this._firstInvalidLine === line) {
+                    this._firstInvalidLine = null;
+                }
+                if (line === lastLine) {
+                    break;
+                }
+                line = next;
+            }
+
+            if (before != null) {
+                before._nextLine = after;
+            } else {
+                this._firstLine = after;
+            }
+            if (after != null) {
+                after._previousLine = before;
+            } else {
+                this._lastLine = before;
+            }
+        }
+
+        public function releaseLineCreationData(): void {
+        }
+
+        public function getTextLineAtCharIndex(charIndex: int): TextLine {
+            var text: String = this._content != null ? this._content.layoutText : "";
+            if (charIndex < 0 || charIndex >= text.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+            for (var line: TextLine = this._firstLine; line != null; line = line._nextLine) {
+                if (charIndex >= line._textBlockBeginIndex && charIndex < line._textBlockBeginIndex + line.rawTextLength) {
+                    return line;
+                }
+            }
+            return null;
+        }
+
+        public function findNextAtomBoundary(afterCharIndex: int): int {
+            this.checkCharIndex(afterCharIndex);
+            return afterCharIndex + 1;
+        }
+
+        public function findPreviousAtomBoundary(beforeCharIndex: int): int {
+            this.checkCharIndex(beforeCharIndex);
+            return beforeCharIndex - 1;
+        }
+
+        public function findNextWordBoundary(afterCharIndex: int): int {
+            this.checkCharIndex(afterCharIndex);
+            var text: String = this._content.layoutText;
+            var index: int = afterCharIndex + 1;
+            while (index < text.length && !isWordBoundary(text, index)) {
+                index++;
+            }
+            return index;
+        }
+
+        public function findPreviousWordBoundary(beforeCharIndex: int): int {
+            this.checkCharIndex(beforeCharIndex);
+            var text: String = this._content.layoutText;
+            var index: int = beforeCharIndex - 1;
+            while (index > 0 && !isWordBoundary(text, index)) {
+                index--;
+            }
+            return Math.max(index, 0);
+        }
+
+        public function dump(): String {
+            return "";
+        }
+
+        // Whether a word starts or ends between the characters at `index - 1` and `index`.
+        internal static function isWordBoundary(text: String, index: int): Boolean {
+            return isSpace(text.charAt(index - 1)) != isSpace(text.charAt(index));
+        }
+
+        private static function isSpace(char: String): Boolean {
+            return char == " " || char == "\t" || char == "\n" || char == "\r" || char == "\u3000";
+        }
+
+        private function checkCharIndex(charIndex: int): void {
+            var text: String = this._content != null ? this._content.layoutText : "";
+            if (charIndex < 0 || charIndex >= text.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+        }
+
+        internal function invalidateLines(): void {
+            for (var line: TextLine = this._firstLine; line != null; line = line._nextLine) {
+                line._validity = TextLineValidity.INVALID;
+            }
+            this._firstInvalidLine = this._firstLine;
+        }
+    }
+}
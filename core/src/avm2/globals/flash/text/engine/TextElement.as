@@ -0,0 +1,34 @@
+package flash.text.engine {
+    import flash.events.EventDispatcher;
+
+    public final class TextElement extends ContentElement {
+        private var _text: String;
+
+        public function TextElement(text: String = null, elementFormat: ElementFormat = null, eventMirror: EventDispatcher = null, textRotation: String = "rotate0") {
+            super(elementFormat, eventMirror, textRotation);
+            this._text = text;
+        }
+
+        override public function get rawText(): String {
+            return this._text;
+        }
+
+        override public function get text(): String {
+            return this._text;
+        }
+
+        public function set text(value: String): void {
+            this._text = value;
+            this.invalidate();
+        }
+
+        public function replaceText(beginIndex: int, endIndex: int, newText: String): void {
+            var text: String = this._text != null ? this._text : "";
+            if (beginIndex < 0 || endIndex < beginIndex || endIndex > text.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+            this._text = text.substring(0, beginIndex) + (newText != null ? newText : "") + text.substring(endIndex);
+            this.invalidate();
+        }
+    }
+}
@@ -0,0 +1,168 @@
+package flash.text.engine {
+    import flash.display.DisplayObject;
+    import flash.display.DisplayObjectContainer;
+
+    // Lines are laid out and drawn by a TextField child, which `TextBlock.createTextLine` adds.
+    [Ruffle(NativeInstanceInit)]
+    public final class TextLine extends DisplayObjectContainer {
+        public static const MAX_LINE_WIDTH: int = 1000000;
+
+        internal var _textBlock: TextBlock = null;
+        internal var _previousLine: TextLine = null;
+        internal var _nextLine: TextLine = null;
+        internal var _textBlockBeginIndex: int = 0;
+        internal var _specifiedWidth: Number = 0;
+        internal var _validity: String = TextLineValidity.VALID;
+
+        public var userData: *;
+
+        public function TextLine() {
+            throw new ArgumentError("Error #2012: TextLine$ class cannot be instantiated.", 2012);
+        }
+
+        public native function get ascent(): Number;
+        public native function get descent(): Number;
+        public native function get textWidth(): Number;
+        public native function get textHeight(): Number;
+        public native function get rawTextLength(): int;
+
+        public function get textBlock(): TextBlock {
+            return this._textBlock;
+        }
+
+        public function get textBlockBeginIndex(): int {
+            return this._textBlockBeginIndex;
+        }
+
+        public function get previousLine(): TextLine {
+            return this._previousLine;
+        }
+
+        public function get nextLine(): TextLine {
+            return this._nextLine;
+        }
+
+        public function get specifiedWidth(): Number {
+            return this._specifiedWidth;
+        }
+
+        public function get unjustifiedTextWidth(): Number {
+            return this.textWidth;
+        }
+
+        public function get totalAscent(): Number {
+            return this.ascent;
+        }
+
+        public function get totalDescent(): Number {
+            return this.descent;
+        }
+
+        public function get totalHeight(): Number {
+            return this.ascent + this.descent;
+        }
+
+        public function get hasGraphicElement(): Boolean {
+            return false;
+        }
+
+        public function get hasTabs(): Boolean {
+            return false;
+        }
+
+        public function get validity(): String {
+            return this._validity;
+        }
+
+        public function set validity(value: String): void {
+            if (value != TextLineValidity.VALID && value != TextLineValidity.INVALID &&
+                    value != TextLineValidity.POSSIBLY_INVALID && value != TextLineValidity.STATIC) {
+                throw new ArgumentError("Error #2008: Parameter validity must be one of the accepted values.", 2008);
+            }
+            this._validity = value;
+            if (value == TextLineValidity.INVALID && this._textBlock != null &&
+                    (this._textBlock.firstInvalidLine == null ||
+                     this._textBlock.firstInvalidLine._textBlockBeginIndex > this._textBlockBeginIndex)) {
+                this._textBlock._firstInvalidLine = this;
+            }
+        }
+
+        // Each character is an atom of its own.
+        public function get atomCount(): int {
+            return this.rawTextLength;
+        }
+
+        public function getAtomIndexAtCharIndex(charIndex: int): int {
+            var index: int = charIndex - this._textBlockBeginIndex;
+            if (index < 0 || index >= this.rawTextLength) {
+                return -1;
+            }
+            return index;
+        }
+
+        public function getAtomTextBlockBeginIndex(atomIndex: int): int {
+            this.checkAtomIndex(atomIndex);
+            return this._textBlockBeginIndex + atomIndex;
+        }
+
+        public function getAtomTextBlockEndIndex(atomIndex: int): int {
+            this.checkAtomIndex(atomIndex);
+            return this._textBlockBeginIndex + atomIndex + 1;
+        }
+
+        public function getAtomBidiLevel(atomIndex: int): int {
+            this.checkAtomIndex(atomIndex);
+            return 0;
+        }
+
+        public function getAtomTextRotation(atomIndex: int): String {
+            this.checkAtomIndex(atomIndex);
+            return TextRotation.ROTATE_0;
+        }
+
+        public function getAtomWordBoundaryOnLeft(atomIndex: int): Boolean {
+            this.checkAtomIndex(atomIndex);
+            if (atomIndex == 0 || this._textBlock == null || this._textBlock.content == null) {
+                return true;
+            }
+            var text: String = this._textBlock.content.layoutText;
+            var index: int = this._textBlockBeginIndex + atomIndex;
+            return TextBlock.isWordBoundary(text, index);
+        }
+
+        public function getAtomGraphic(atomIndex: int): DisplayObject {
+            this.checkAtomIndex(atomIndex);
+            return null;
+        }
+
+        public function getBaselinePosition(baseline: String): Number {
+            switch (baseline) {
+                case TextBaseline.ROMAN:
+                    return 0;
+                case TextBaseline.ASCENT:
+                case TextBaseline.IDEOGRAPHIC_TOP:
+                    return -this.ascent;
+                case TextBaseline.DESCENT:
+                case TextBaseline.IDEOGRAPHIC_BOTTOM:
+                    return this.descent;
+                case TextBaseline.IDEOGRAPHIC_CENTER:
+                    return (this.descent - this.ascent) / 2;
+                default:
+                    throw new ArgumentError("Error #2008: Parameter baseline must be one of the accepted values.", 2008);
+            }
+        }
+
+        public function flushAtomData(): void {
+        }
+
+        public function dump(): String {
+            return "";
+        }
+
+        private function checkAtomIndex(atomIndex: int): void {
+            if (atomIndex < 0 || atomIndex >= this.rawTextLength) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+        }
+    }
+}
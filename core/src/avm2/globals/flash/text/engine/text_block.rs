@@ -0,0 +1,131 @@
+//! `flash.text.engine.TextBlock` native methods
+
+use crate::avm2::object::StageObject;
+use crate::avm2::{Activation, Error, Object, TObject, Value};
+use crate::display_object::{
+    AutoSizeMode, EditText, MovieClip, TDisplayObject, TDisplayObjectContainer,
+};
+use crate::html::TextFormat;
+use crate::string::WStr;
+use crate::tag_utils::SwfMovie;
+use std::sync::Arc;
+use swf::Color;
+
+/// Implements `TextBlock.layoutLine`, which lays out the first line of `text` in a text field
+/// and puts the field into a `TextLine`.
+pub fn layout_line<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let text = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let runs = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(activation)?;
+    let width = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation)?;
+    let fit_something = args.get(3).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    let line = args.get(4).and_then(|line| line.as_object());
+
+    let movie = Arc::new(SwfMovie::empty(activation.context.swf.version()));
+    let field = EditText::new(
+        &mut activation.context,
+        movie.clone(),
+        0.0,
+        0.0,
+        width + EditText::INTERNAL_PADDING * 2.0,
+        0.0,
+    );
+    field.set_word_wrap(true, &mut activation.context);
+    field.set_selectable(false, &mut activation.context);
+    field.set_text(&text, &mut activation.context);
+
+    let run_count = runs.as_array_storage().map_or(0, |runs| runs.length());
+    for i in 0..run_count {
+        let run = runs
+            .as_array_storage()
+            .and_then(|runs| runs.get(i))
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let property = |i| {
+            run.as_array_storage()
+                .and_then(|run| run.get(i))
+                .unwrap_or(Value::Undefined)
+        };
+        let begin = property(0).coerce_to_i32(activation)?.max(0) as usize;
+        let end = property(1).coerce_to_i32(activation)?.max(0) as usize;
+        let text_format = TextFormat {
+            font: Some(property(2).coerce_to_string(activation)?.as_wstr().into()),
+            size: Some(property(3).coerce_to_number(activation)?),
+            color: Some(Color::from_rgb(
+                property(4).coerce_to_u32(activation)?,
+                0xFF,
+            )),
+            bold: Some(property(5).coerce_to_boolean()),
+            italic: Some(property(6).coerce_to_boolean()),
+            kerning: Some(property(7).coerce_to_boolean()),
+            letter_spacing: Some(property(8).coerce_to_number(activation)?),
+            ..Default::default()
+        };
+        field.set_text_format(begin, end, text_format, &mut activation.context);
+    }
+
+    let metrics = match field.layout_metrics(Some(0)) {
+        Some(metrics) => metrics,
+        None => return Ok(Value::Null),
+    };
+    if !fit_something && metrics.width.to_pixels() > width {
+        return Ok(Value::Null);
+    }
+
+    // The spaces that end a line, and a line break, are part of it.
+    let mut end = field.line_end(0).unwrap_or_default();
+    while matches!(text.get(end), Some(c) if c == b' ' as u16 || c == b'\t' as u16) {
+        end += 1;
+    }
+    if matches!(text.get(end), Some(c) if c == b'\n' as u16 || c == b'\r' as u16) {
+        end += 1;
+    }
+    end = end.clamp(1, text.len());
+    field.replace_text(end, text.len(), WStr::empty(), &mut activation.context);
+    field.set_autosize(AutoSizeMode::Left, &mut activation.context);
+
+    // The origin of a line is on the baseline of its text.
+    let ascent = metrics.ascent.to_pixels();
+    field.set_x(activation.context.gc_context, -EditText::INTERNAL_PADDING);
+    field.set_y(
+        activation.context.gc_context,
+        -(ascent + EditText::INTERNAL_PADDING),
+    );
+
+    let line = match line {
+        Some(line) => line,
+        None => {
+            let mut clip = MovieClip::new(movie, activation.context.gc_context);
+            let class = activation.avm2().classes().textline;
+            let object = StageObject::for_display_object_childless(activation, clip.into(), class)?;
+            clip.set_object2(activation.context.gc_context, object.into());
+            object.into()
+        }
+    };
+
+    if let Some(mut container) = line
+        .as_display_object()
+        .and_then(|line| line.as_container())
+    {
+        let children: Vec<_> = container.iter_render_list().collect();
+        for child in children {
+            container.remove_child(&mut activation.context, child);
+        }
+        container.insert_at_index(&mut activation.context, field.into(), 0);
+        field.construct_frame(&mut activation.context);
+    }
+
+    Ok(line.into())
+}
@@ -0,0 +1,87 @@
+//! `flash.text.engine.TextLine` native methods
+
+use crate::avm2::{Activation, Error, Object, TObject, Value};
+use crate::display_object::{EditText, TDisplayObject, TDisplayObjectContainer};
+use crate::html::LayoutMetrics;
+
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation.super_init(this, args)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Returns the text field that draws the text of a line.
+fn line_text_field<'gc>(this: Option<Object<'gc>>) -> Option<EditText<'gc>> {
+    this?
+        .as_display_object()?
+        .as_container()?
+        .child_by_index(0)?
+        .as_edit_text()
+}
+
+fn line_metrics(this: Option<Object<'_>>) -> Option<LayoutMetrics> {
+    line_text_field(this)?.layout_metrics(Some(0))
+}
+
+/// Implements `TextLine.ascent`
+pub fn get_ascent<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(line_metrics(this)
+        .map_or(0.0, |metrics| metrics.ascent.to_pixels())
+        .into())
+}
+
+/// Implements `TextLine.descent`
+pub fn get_descent<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(line_metrics(this)
+        .map_or(0.0, |metrics| metrics.descent.to_pixels())
+        .into())
+}
+
+/// Implements `TextLine.textWidth`
+pub fn get_text_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(line_metrics(this)
+        .map_or(0.0, |metrics| metrics.width.to_pixels())
+        .into())
+}
+
+/// Implements `TextLine.textHeight`
+pub fn get_text_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(line_metrics(this)
+        .map_or(0.0, |metrics| {
+            (metrics.ascent + metrics.descent).to_pixels()
+        })
+        .into())
+}
+
+/// Implements `TextLine.rawTextLength`
+pub fn get_raw_text_length<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(line_text_field(this)
+        .map_or(0, |field| field.text_length() as i32)
+        .into())
+}
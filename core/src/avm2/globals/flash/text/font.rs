@@ -3,7 +3,7 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::{Method, NativeMethodImpl};
-use crate::avm2::object::{Object, TObject};
+use crate::avm2::object::{font_allocator, FontObject, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
@@ -11,6 +11,7 @@ use crate::avm2::QName;
 use crate::avm2::{ArrayObject, ArrayStorage, Error};
 use crate::avm2_stub_getter;
 use crate::character::Character;
+use crate::font::{Font, FontDescriptor};
 use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
@@ -22,6 +23,25 @@ pub fn instance_init<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
         activation.super_init(this, &[])?;
+
+        if this.as_font().is_none() {
+            if let Some((movie, character_id)) = this.instance_of().and_then(|this| {
+                activation
+                    .context
+                    .library
+                    .avm2_class_registry()
+                    .class_symbol(this)
+            }) {
+                if let Some(Character::Font(font)) = activation
+                    .context
+                    .library
+                    .library_for_movie_mut(movie)
+                    .character_by_id(character_id)
+                {
+                    this.set_font(activation.context.gc_context, *font);
+                }
+            }
+        }
     }
 
     Ok(Value::Undefined)
@@ -42,59 +62,31 @@ pub fn font_name<'gc>(
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
-        if let Some(Character::Font(font)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            return Ok(AvmString::new_utf8(
-                activation.context.gc_context,
-                font.descriptor().class(),
-            )
-            .into());
-        }
+    if let Some(font) = this.and_then(|this| this.as_font()) {
+        return Ok(
+            AvmString::new_utf8(activation.context.gc_context, font.descriptor().class()).into(),
+        );
     }
 
-    Ok(Value::Undefined)
+    Ok(Value::Null)
 }
 
 /// Implements `Font.fontStyle`
 pub fn font_style<'gc>(
-    activation: &mut Activation<'_, 'gc>,
+    _activation: &mut Activation<'_, 'gc>,
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
-        if let Some(Character::Font(font)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            return match (font.descriptor().bold(), font.descriptor().italic()) {
-                (false, false) => Ok("regular".into()),
-                (false, true) => Ok("italic".into()),
-                (true, false) => Ok("bold".into()),
-                (true, true) => Ok("boldItalic".into()),
-            };
-        }
+    if let Some(font) = this.and_then(|this| this.as_font()) {
+        return match (font.descriptor().bold(), font.descriptor().italic()) {
+            (false, false) => Ok("regular".into()),
+            (false, true) => Ok("italic".into()),
+            (true, false) => Ok("bold".into()),
+            (true, true) => Ok("boldItalic".into()),
+        };
     }
 
-    Ok(Value::Undefined)
+    Ok(Value::Null)
 }
 
 /// Implements `Font.fontType`
@@ -103,25 +95,16 @@ pub fn font_type<'gc>(
     this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
-        if let Some(Character::Font(_)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            //TODO: How do we distinguish between CFF and non-CFF embedded fonts?
-            return Ok("embedded".into());
+    if let Some(font) = this.and_then(|this| this.as_font()) {
+        if activation.context.library.is_device_font(font) {
+            return Ok("device".into());
         }
+
+        //TODO: How do we distinguish between CFF and non-CFF embedded fonts?
+        return Ok("embedded".into());
     }
 
-    Ok(Value::Undefined)
+    Ok(Value::Null)
 }
 
 /// Implements `Font.hasGlyphs`
@@ -130,40 +113,72 @@ pub fn has_glyphs<'gc>(
     this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some((movie, character_id)) = this.and_then(|this| this.instance_of()).and_then(|this| {
-        activation
-            .context
-            .library
-            .avm2_class_registry()
-            .class_symbol(this)
-    }) {
+    if let Some(mut font) = this.and_then(|this| this.as_font()) {
         let my_str = args
             .get(0)
             .cloned()
             .unwrap_or(Value::Undefined)
             .coerce_to_string(activation)?;
 
-        if let Some(Character::Font(font)) = activation
-            .context
-            .library
-            .library_for_movie_mut(movie)
-            .character_by_id(character_id)
-        {
-            return Ok(font.has_glyphs_for_str(&my_str).into());
+        // Device fonts listed by `enumerateFonts` have no glyphs until they're loaded.
+        if activation.context.library.is_device_font(font) {
+            let descriptor = font.descriptor().clone();
+            if let Some(device_font) = activation.context.device_font(
+                descriptor.class(),
+                descriptor.bold(),
+                descriptor.italic(),
+            ) {
+                font = device_font;
+            }
         }
+
+        return Ok(font.has_glyphs_for_str(&my_str).into());
     }
 
-    Ok(Value::Undefined)
+    Ok(false.into())
 }
 
 /// `Font.enumerateFonts`
 pub fn enumerate_fonts<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.text.Font", "enumerateFonts");
-    Ok(ArrayObject::from_storage(activation, ArrayStorage::new(0))?.into())
+    let enumerate_device_fonts = args
+        .get(0)
+        .cloned()
+        .unwrap_or_else(|| false.into())
+        .coerce_to_boolean();
+
+    let mut fonts = activation.context.library.embedded_fonts();
+    if enumerate_device_fonts {
+        let mut device_fonts: Vec<_> = activation
+            .context
+            .ui
+            .device_fonts()
+            .into_iter()
+            .map(|font| FontDescriptor::from_parts(&font.name, font.is_bold, font.is_italic))
+            .collect();
+        device_fonts.sort();
+        device_fonts.dedup();
+        if device_fonts.is_empty() {
+            // Without any fonts on the system, device text is drawn with the built-in font.
+            fonts.extend(activation.context.library.device_font());
+        }
+        for descriptor in device_fonts {
+            fonts.push(Font::from_device_font_name(
+                activation.context.gc_context,
+                descriptor,
+            ));
+        }
+    }
+
+    let mut storage = ArrayStorage::new(0);
+    for font in fonts {
+        storage.push(FontObject::from_font(activation, font)?.into());
+    }
+
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
 }
 
 /// `Font.registerFont`
@@ -189,6 +204,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     let mut write = class.write(mc);
 
     write.set_attributes(ClassAttributes::SEALED);
+    write.set_instance_allocator(font_allocator);
 
     const PUBLIC_INSTANCE_PROPERTIES: &[(
         &str,
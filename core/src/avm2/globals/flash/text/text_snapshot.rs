@@ -0,0 +1,245 @@
+//! `flash.text.TextSnapshot` native methods
+
+use crate::avm2::{Activation, ArrayObject, Error, Object, TObject, Value};
+use crate::avm2_stub_method;
+use crate::display_object::{DisplayObject, TDisplayObjectContainer, Text, TextChar};
+use crate::string::{AvmString, WString};
+use std::ops::Range;
+use swf::{Color, Twips};
+
+/// A character of the static text of a container.
+struct SnapshotChar<'gc> {
+    text: Text<'gc>,
+
+    /// The index of the text among the static texts of the container.
+    text_index: usize,
+
+    /// The index of the character in its text.
+    index: usize,
+
+    char: TextChar,
+}
+
+/// Read the characters of the static texts among the children of a container, in depth order.
+fn snapshot_chars<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    container: &Value<'gc>,
+) -> Vec<SnapshotChar<'gc>> {
+    let container = match container
+        .as_object()
+        .and_then(|container| container.as_display_object())
+        .and_then(|container| container.as_container())
+    {
+        Some(container) => container,
+        None => return vec![],
+    };
+
+    let texts = container
+        .iter_render_list()
+        .filter_map(|child| match child {
+            DisplayObject::Text(text) => Some(text),
+            _ => None,
+        });
+    let mut chars = vec![];
+    for (text_index, text) in texts.enumerate() {
+        for (index, char) in text.chars(&activation.context).into_iter().enumerate() {
+            chars.push(SnapshotChar {
+                text,
+                text_index,
+                index,
+                char,
+            });
+        }
+    }
+    chars
+}
+
+/// Clamp a range of characters given by ActionScript to the characters of a snapshot.
+fn char_range<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    begin: &Value<'gc>,
+    end: &Value<'gc>,
+    count: usize,
+) -> Result<Range<usize>, Error<'gc>> {
+    let begin = (begin.coerce_to_i32(activation)?.max(0) as usize).min(count);
+    let end = (end.coerce_to_i32(activation)?.max(0) as usize).clamp(begin, count);
+    Ok(begin..end)
+}
+
+/// Join characters into a string, with newlines between the lines of text if asked to.
+fn chars_to_string<'a, 'gc: 'a>(
+    chars: impl Iterator<Item = &'a SnapshotChar<'gc>>,
+    include_line_endings: bool,
+) -> WString {
+    let mut string = WString::new();
+    let mut last_line = None;
+    for c in chars {
+        let line = (c.text_index, c.char.line);
+        if include_line_endings && last_line.map_or(false, |last_line| last_line != line) {
+            string.push_byte(b'\n');
+        }
+        string.push(c.char.code);
+        last_line = Some(line);
+    }
+    string
+}
+
+/// Implements `TextSnapshot.charCount`
+pub fn count_chars<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    Ok(snapshot_chars(activation, container).len().into())
+}
+
+/// Implements `TextSnapshot.getSelected`
+pub fn read_selected<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    let chars = snapshot_chars(activation, container);
+    let range = char_range(
+        activation,
+        args.get(1).unwrap_or(&Value::Undefined),
+        args.get(2).unwrap_or(&Value::Undefined),
+        chars.len(),
+    )?;
+    Ok(chars[range]
+        .iter()
+        .any(|c| c.text.is_selected(c.index))
+        .into())
+}
+
+/// Implements `TextSnapshot.getSelectedText`
+pub fn read_selected_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    let include_line_endings = args.get(1).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    let chars = snapshot_chars(activation, container);
+    let text = chars_to_string(
+        chars.iter().filter(|c| c.text.is_selected(c.index)),
+        include_line_endings,
+    );
+    Ok(AvmString::new(activation.context.gc_context, text).into())
+}
+
+/// Implements `TextSnapshot.getText`
+pub fn read_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    let chars = snapshot_chars(activation, container);
+    let range = char_range(
+        activation,
+        args.get(1).unwrap_or(&Value::Undefined),
+        args.get(2).unwrap_or(&Value::Undefined),
+        chars.len(),
+    )?;
+    let include_line_endings = args.get(3).unwrap_or(&Value::Undefined).coerce_to_boolean();
+    let text = chars_to_string(chars[range].iter(), include_line_endings);
+    Ok(AvmString::new(activation.context.gc_context, text).into())
+}
+
+/// Implements `TextSnapshot.getTextRunInfo`
+pub fn get_text_run_info<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    avm2_stub_method!(activation, "flash.text.TextSnapshot", "getTextRunInfo");
+    Ok(ArrayObject::empty(activation)?.into())
+}
+
+/// Implements `TextSnapshot.hitTestTextNearPos`, which finds the character closest to a point
+/// in the coordinates of the container, up to `maxDistance` pixels away.
+pub fn hit_test_chars<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    let x = Twips::from_pixels(
+        args.get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(activation)?,
+    );
+    let y = Twips::from_pixels(
+        args.get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(activation)?,
+    );
+    let max_distance = args
+        .get(3)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation)?;
+
+    let distance = |c: &SnapshotChar<'_>| {
+        let bounds = &c.char.bounds;
+        let dx = (bounds.x_min - x).max(x - bounds.x_max).max(Twips::ZERO);
+        let dy = (bounds.y_min - y).max(y - bounds.y_max).max(Twips::ZERO);
+        dx.to_pixels().hypot(dy.to_pixels())
+    };
+    let nearest = snapshot_chars(activation, container)
+        .iter()
+        .enumerate()
+        .map(|(index, c)| (index, distance(c)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+    Ok(nearest.map_or(-1.0, |(index, _)| index as f64).into())
+}
+
+/// Implements `TextSnapshot.setSelectColor`
+pub fn write_select_color<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    let color = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let color = Color::from_rgb(color, 0xFF);
+
+    let mut last_text = None;
+    for c in snapshot_chars(activation, container) {
+        if last_text != Some(c.text_index) {
+            c.text
+                .set_select_color(activation.context.gc_context, color.clone());
+            last_text = Some(c.text_index);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextSnapshot.setSelected`
+pub fn write_selected<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let container = args.get(0).unwrap_or(&Value::Undefined);
+    let chars = snapshot_chars(activation, container);
+    let range = char_range(
+        activation,
+        args.get(1).unwrap_or(&Value::Undefined),
+        args.get(2).unwrap_or(&Value::Undefined),
+        chars.len(),
+    )?;
+    let select = args.get(3).unwrap_or(&Value::Undefined).coerce_to_boolean();
+
+    for c in &chars[range] {
+        c.text
+            .set_selected(activation.context.gc_context, c.index..c.index + 1, select);
+    }
+    Ok(Value::Undefined)
+}
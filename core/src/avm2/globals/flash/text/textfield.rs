@@ -10,7 +10,7 @@ use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
 use crate::display_object::{AutoSizeMode, EditText, TDisplayObject, TextSelection};
-use crate::html::TextFormat;
+use crate::html::{StyleSheet, TextFormat};
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
 use crate::{avm2_stub_getter, avm2_stub_setter};
@@ -1295,6 +1295,102 @@ pub fn set_restrict<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn style_sheet<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .style_sheet_object()
+            .map_or(Value::Null, |object| object.into()));
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_style_sheet<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let style_sheet = match args.get(0).cloned().unwrap_or(Value::Null) {
+            Value::Undefined | Value::Null => None,
+            value => {
+                // The styles are read once, as HTML text is styled when it's set.
+                let object = value.coerce_to_object(activation)?;
+                let names = object
+                    .get_property(&Multiname::public("styleNames"), activation)?
+                    .coerce_to_object(activation)?;
+                let length = names.as_array_storage().map_or(0, |v| v.length());
+
+                let mut style_sheet = StyleSheet::new();
+                for i in 0..length {
+                    let name = names.get_property(
+                        &Multiname::public(AvmString::new_utf8(
+                            activation.context.gc_context,
+                            i.to_string(),
+                        )),
+                        activation,
+                    )?;
+                    let style = object.call_property(
+                        &Multiname::public("getStyle"),
+                        &[name],
+                        activation,
+                    )?;
+                    let format = object.call_property(
+                        &Multiname::public("transform"),
+                        &[style],
+                        activation,
+                    )?;
+
+                    let format = format
+                        .as_object()
+                        .and_then(|format| format.as_text_format().map(|tf| (*tf).clone()));
+                    if let Some(format) = format {
+                        style_sheet.set_style(&name.coerce_to_string(activation)?, format);
+                    }
+                }
+
+                Some((style_sheet, object))
+            }
+        };
+
+        this.set_style_sheet(activation.context.gc_context, style_sheet);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_image_reference<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let id = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        return Ok(this
+            .image_reference(&id)
+            .map_or(Value::Null, |image| image.object2()));
+    }
+
+    Ok(Value::Null)
+}
+
 /// Construct `TextField`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -1348,6 +1444,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("scrollH", Some(scroll_h), Some(set_scroll_h)),
         ("scrollV", Some(scroll_v), Some(set_scroll_v)),
         ("selectable", Some(selectable), Some(set_selectable)),
+        ("styleSheet", Some(style_sheet), Some(set_style_sheet)),
         ("text", Some(text), Some(set_text)),
         ("textColor", Some(text_color), Some(set_text_color)),
         ("textHeight", Some(text_height), None),
@@ -1374,6 +1471,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("setSelection", set_selection),
         ("setTextFormat", set_text_format),
         ("getLineMetrics", get_line_metrics),
+        ("getImageReference", get_image_reference),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
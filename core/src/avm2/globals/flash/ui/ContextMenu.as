@@ -20,10 +20,27 @@ package flash.ui
             return this._builtInItems;
         }
 
+        public function clone(): ContextMenu
+        {
+            var menu: ContextMenu = new ContextMenu();
+            var builtIns: ContextMenuBuiltInItems = menu.builtInItems;
+            builtIns.forwardAndBack = this._builtInItems.forwardAndBack;
+            builtIns.loop = this._builtInItems.loop;
+            builtIns.play = this._builtInItems.play;
+            builtIns.print = this._builtInItems.print;
+            builtIns.quality = this._builtInItems.quality;
+            builtIns.rewind = this._builtInItems.rewind;
+            builtIns.save = this._builtInItems.save;
+            builtIns.zoom = this._builtInItems.zoom;
+            for each (var item: ContextMenuItem in this.customItems) {
+                menu.customItems.push(item.clone());
+            }
+            return menu;
+        }
+
         public static function get isSupported() : Boolean
         {
-            // TODO: return true when implementation actually affects the context menu
-            return false;
+            return true;
         }
     }
 }
@@ -1,6 +1,39 @@
 package flash.ui {
+    import flash.display.BitmapData;
+    import flash.geom.Point;
+
     public final class Mouse {
         public static native function hide(): void;
         public static native function show(): void;
+
+        public static native function get cursor(): String;
+        public static native function set cursor(value: String): void;
+
+        public static function get supportsCursor(): Boolean {
+            return true;
+        }
+
+        public static function get supportsNativeCursor(): Boolean {
+            return true;
+        }
+
+        public static function registerCursor(name: String, cursor: MouseCursorData): void {
+            if (cursor.data == null || cursor.data.length == 0) {
+                throw new ArgumentError("Error #2007: Parameter data must be non-null.", 2007);
+            }
+            var hotSpot: Point = cursor.hotSpot;
+            for each (var frame: BitmapData in cursor.data) {
+                if (hotSpot.x < 0 || hotSpot.y < 0 || hotSpot.x >= frame.width || hotSpot.y >= frame.height) {
+                    throw new ArgumentError("Error #2008: Parameter hotSpot must be one of the accepted values.", 2008);
+                }
+            }
+            registerBitmapCursor(name, cursor.data, hotSpot.x, hotSpot.y, cursor.frameRate);
+        }
+
+        public static native function unregisterCursor(name: String): void;
+
+        private static native function registerBitmapCursor(
+            name: String, frames: Vector.<BitmapData>, hotSpotX: Number, hotSpotY: Number, frameRate: Number
+        ): void;
     }
 }
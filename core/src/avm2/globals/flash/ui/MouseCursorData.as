@@ -0,0 +1,40 @@
+package flash.ui {
+    import flash.display.BitmapData;
+    import flash.geom.Point;
+
+    public final class MouseCursorData {
+        private var _data: Vector.<BitmapData>;
+        private var _hotSpot: Point = new Point(0, 0);
+        private var _frameRate: Number = 0;
+
+        public function MouseCursorData() {
+        }
+
+        // The frames of the cursor. Cursors have at most 32x32 pixels.
+        public function get data(): Vector.<BitmapData> {
+            return this._data;
+        }
+
+        public function set data(value: Vector.<BitmapData>): void {
+            this._data = value;
+        }
+
+        // The point of the frames placed at the mouse position.
+        public function get hotSpot(): Point {
+            return this._hotSpot;
+        }
+
+        public function set hotSpot(value: Point): void {
+            this._hotSpot = value;
+        }
+
+        // How many frames are shown per second.
+        public function get frameRate(): Number {
+            return this._frameRate;
+        }
+
+        public function set frameRate(value: Number): void {
+            this._frameRate = value;
+        }
+    }
+}
@@ -1,6 +1,21 @@
 package flash.ui {
     public final class Multitouch {
-        // FIXME - implement a setter and gett
-        public static var inputMode:String = MultitouchInputMode.GESTURE;
+        public static native function get inputMode(): String;
+        public static native function set inputMode(value: String): void;
+
+        public static native function get maxTouchPoints(): int;
+
+        public static function get supportsTouchEvents(): Boolean {
+            return maxTouchPoints > 0;
+        }
+
+        // Gestures aren't recognized yet.
+        public static function get supportsGestureEvents(): Boolean {
+            return false;
+        }
+
+        public static function get supportedGestures(): Vector.<String> {
+            return null;
+        }
     }
-}
\ No newline at end of file
+}
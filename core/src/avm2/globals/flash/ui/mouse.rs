@@ -1,16 +1,21 @@
 //! `flash.ui.Mouse` builtin
 
 use crate::avm2::activation::Activation;
-use crate::avm2::object::Object;
+use crate::avm2::error::argument_error;
+use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::cursor::BitmapCursor;
+use crate::string::AvmString;
+use ruffle_render::bitmap::{Bitmap, BitmapFormat};
 
 pub fn hide<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    activation.context.ui.set_mouse_visible(false);
+    let context = &mut activation.context;
+    context.cursors.set_hidden(true, context.ui);
     Ok(Value::Undefined)
 }
 
@@ -19,6 +24,127 @@ pub fn show<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    activation.context.ui.set_mouse_visible(true);
+    let context = &mut activation.context;
+    context.cursors.set_hidden(false, context.ui);
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.cursor`'s getter
+pub fn get_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let cursor = activation.context.cursors.cursor().to_string();
+    Ok(AvmString::new_utf8(activation.context.gc_context, cursor).into())
+}
+
+/// Implements `Mouse.cursor`'s setter
+pub fn set_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let cursor = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+    let context = &mut activation.context;
+    if !context.cursors.is_known(&cursor) {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2008: Parameter cursor must be one of the accepted values.",
+            2008,
+        )?));
+    }
+    context
+        .cursors
+        .set_cursor(cursor, context.clock.elapsed(), context.ui);
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.registerBitmapCursor`
+pub fn register_bitmap_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+    let hot_spot_x = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation)?;
+    let hot_spot_y = args
+        .get(3)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation)?;
+    let frame_rate = args
+        .get(4)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation)?;
+
+    let bitmap_datas: Vec<_> = args
+        .get(1)
+        .and_then(|frames| frames.as_object())
+        .and_then(|frames| {
+            frames.as_vector_storage().map(|storage| {
+                storage
+                    .iter()
+                    .filter_map(|frame| frame.as_object()?.as_bitmap_data_wrapper())
+                    .collect()
+            })
+        })
+        .unwrap_or_default();
+
+    let mut frames = Vec::with_capacity(bitmap_datas.len());
+    for bitmap_data in bitmap_datas {
+        let bitmap_data = bitmap_data.sync();
+        let bitmap_data = bitmap_data.read();
+        let bitmap = Bitmap::new(
+            bitmap_data.width(),
+            bitmap_data.height(),
+            BitmapFormat::Rgba,
+            bitmap_data.pixels_rgba(),
+        );
+        match activation.context.renderer.register_bitmap(bitmap) {
+            Ok(handle) => frames.push(handle),
+            Err(e) => tracing::warn!("Failed to register bitmap for cursor {}: {}", name, e),
+        }
+    }
+
+    let cursor = BitmapCursor {
+        frames,
+        hot_spot: (hot_spot_x, hot_spot_y),
+        frame_rate: if frame_rate.is_finite() {
+            frame_rate.max(0.0)
+        } else {
+            0.0
+        },
+    };
+    let context = &mut activation.context;
+    context
+        .cursors
+        .register(name, cursor, context.clock.elapsed(), context.ui);
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mouse.unregisterCursor`
+pub fn unregister_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string();
+    let context = &mut activation.context;
+    context.cursors.unregister(&name, context.ui);
     Ok(Value::Undefined)
 }
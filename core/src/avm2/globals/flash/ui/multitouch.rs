@@ -0,0 +1,53 @@
+//! `flash.ui.Multitouch` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::touch::MultitouchInputMode;
+
+/// Implements `Multitouch.inputMode`'s getter
+pub fn get_input_mode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation
+        .context
+        .touch_manager
+        .input_mode()
+        .as_str()
+        .into())
+}
+
+/// Implements `Multitouch.inputMode`'s setter
+pub fn set_input_mode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Ok(input_mode) = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?
+        .to_string()
+        .parse::<MultitouchInputMode>()
+    {
+        activation.context.touch_manager.set_input_mode(input_mode);
+    } else {
+        return Err(
+            "ArgumentError: Error #2008: Parameter inputMode must be one of the accepted values."
+                .into(),
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `Multitouch.maxTouchPoints`'s getter
+pub fn get_max_touch_points<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.ui.max_touch_points().into())
+}
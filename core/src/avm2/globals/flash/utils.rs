@@ -1,11 +1,14 @@
 //! `flash.utils` namespace
 
-use crate::avm2::object::TObject;
-use crate::avm2::QName;
-use crate::avm2::{Activation, Error, Object, Value};
-use crate::string::AvmString;
-use crate::string::WString;
-use instant::Instant;
+use crate::avm2::class::Class;
+use crate::avm2::e4x::escape_attribute_value;
+use crate::avm2::metadata::Metadata;
+use crate::avm2::method::{Method, ParamConfig};
+use crate::avm2::object::{ClassObject, TObject};
+use crate::avm2::traits::{Trait, TraitKind};
+use crate::avm2::{Activation, Error, Multiname, Namespace, Object, QName, Value};
+use crate::string::{AvmString, WStr, WString};
+use gc_arena::{GcCell, MutationContext};
 use std::fmt::Write;
 
 pub mod byte_array;
@@ -22,10 +25,7 @@ pub fn get_timer<'gc>(
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok((Instant::now()
-        .duration_since(activation.context.start_time)
-        .as_millis() as u32)
-        .into())
+    Ok((activation.context.clock.elapsed().as_millis() as u32).into())
 }
 
 /// Implements `flash.utils.setInterval`
@@ -187,6 +187,56 @@ pub fn unescape_multi_byte<'gc>(
     Ok(v.into())
 }
 
+/// Returns the fully qualified name of a class, including the type parameter
+/// of specialized `Vector` classes, e.g. `__AS3__.vec::Vector.<String>`.
+fn qualified_class_name<'gc>(
+    class: GcCell<'gc, Class<'gc>>,
+    mc: MutationContext<'gc, '_>,
+) -> AvmString<'gc> {
+    let class = class.read();
+    let name = class.generic_name().to_qualified_name(mc);
+    let param = match class.params().first() {
+        Some(param) => *param,
+        None => return name,
+    };
+
+    let mut buf = WString::from(name.as_wstr());
+    buf.push_str(WStr::from_units(b".<"));
+    buf.push_str(&qualified_class_name(param, mc));
+    buf.push_char('>');
+    AvmString::new(mc, buf)
+}
+
+/// Returns the fully qualified name of a type, as written in trait and
+/// method signatures.
+fn qualified_type_name<'gc>(
+    type_name: &Multiname<'gc>,
+    mc: MutationContext<'gc, '_>,
+) -> AvmString<'gc> {
+    let local_name = match type_name.local_name() {
+        Some(local_name) if !type_name.is_any() => local_name,
+        _ => return "*".into(),
+    };
+    let uri = type_name
+        .namespace_set()
+        .first()
+        .map(|ns| ns.as_uri())
+        .unwrap_or_default();
+
+    let mut buf = WString::new();
+    if !uri.is_empty() {
+        buf.push_str(&uri);
+        buf.push_str(WStr::from_units(b"::"));
+    }
+    buf.push_str(&local_name);
+    if let Some(param) = type_name.params().first() {
+        buf.push_str(WStr::from_units(b".<"));
+        buf.push_str(&qualified_type_name(param, mc));
+        buf.push_char('>');
+    }
+    AvmString::new(mc, buf)
+}
+
 /// Implements `flash.utils.getQualifiedClassName`
 pub fn get_qualified_class_name<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -210,12 +260,11 @@ pub fn get_qualified_class_name<'gc>(
         },
     };
 
-    Ok(class
-        .inner_class_definition()
-        .read()
-        .name()
-        .to_qualified_name(activation.context.gc_context)
-        .into())
+    Ok(qualified_class_name(
+        class.inner_class_definition(),
+        activation.context.gc_context,
+    )
+    .into())
 }
 
 /// Implements `flash.utils.getQualifiedSuperclassName`
@@ -224,10 +273,11 @@ pub fn get_qualified_superclass_name<'gc>(
     _this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let obj = args
-        .get(0)
-        .unwrap_or(&Value::Undefined)
-        .coerce_to_object(activation)?;
+    let val = args.get(0).copied().unwrap_or(Value::Undefined);
+    if matches!(val, Value::Null | Value::Undefined) {
+        return Ok(Value::Null);
+    }
+    let obj = val.coerce_to_object(activation)?;
 
     let class = match obj.as_class_object() {
         Some(class) => class,
@@ -238,28 +288,461 @@ pub fn get_qualified_superclass_name<'gc>(
     };
 
     if let Some(super_class) = class.superclass_object() {
-        Ok(super_class
-            .inner_class_definition()
-            .read()
-            .name()
-            .to_qualified_name(activation.context.gc_context)
-            .into())
+        Ok(qualified_class_name(
+            super_class.inner_class_definition(),
+            activation.context.gc_context,
+        )
+        .into())
     } else {
         Ok(Value::Null)
     }
 }
 
+/// Look up a definition by its fully qualified name.
+///
+/// Names of specialized vectors, like `__AS3__.vec::Vector.<String>`, are
+/// resolved by applying the type parameter to the generic class.
+fn definition_by_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    name: AvmString<'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    if let Some(param_start) = name.find(WStr::from_units(b".<")) {
+        if name.ends_with(WStr::from_units(b">")) {
+            let base_name = AvmString::new(mc, &name[..param_start]);
+            let param_name = AvmString::new(mc, &name[param_start + 2..name.len() - 1]);
+
+            let base = definition_by_name(activation, base_name)?;
+            let param = if &param_name == b"*" {
+                Value::Null
+            } else {
+                definition_by_name(activation, param_name)?
+            };
+
+            let base = base
+                .as_object()
+                .ok_or_else(|| Error::from("Cannot specialize null or undefined"))?;
+            return Ok(base.apply(activation, &[param])?.into());
+        }
+    }
+
+    let appdomain = activation.caller_domain();
+    let qname = QName::from_qualified_name(name, mc);
+    appdomain.get_defined_value(activation, qname)
+}
+
 /// Implements native method `flash.utils.getDefinitionByName`
 pub fn get_definition_by_name<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let appdomain = activation.caller_domain();
     let name = args
         .get(0)
         .unwrap_or(&Value::Undefined)
         .coerce_to_string(activation)?;
-    let qname = QName::from_qualified_name(name, activation.context.gc_context);
-    appdomain.get_defined_value(activation, qname)
+    definition_by_name(activation, name)
+}
+
+/// Appends ` name="value"` to an XML tag, escaping the value.
+fn push_xml_attribute(out: &mut WString, name: &str, value: &WStr) {
+    out.push_char(' ');
+    out.push_utf8(name);
+    out.push_str(WStr::from_units(b"=\""));
+    out.push_str(&escape_attribute_value(value));
+    out.push_char('"');
+}
+
+/// Appends a `<metadata>` element for each metadata tag.
+fn push_metadata(out: &mut WString, metadata: &[Metadata<'_>]) {
+    for tag in metadata {
+        out.push_utf8("<metadata");
+        push_xml_attribute(out, "name", &tag.name());
+        if tag.items().is_empty() {
+            out.push_utf8("/>");
+            continue;
+        }
+        out.push_char('>');
+        for (key, value) in tag.items() {
+            out.push_utf8("<arg");
+            push_xml_attribute(out, "key", key);
+            push_xml_attribute(out, "value", value);
+            out.push_utf8("/>");
+        }
+        out.push_utf8("</metadata>");
+    }
+}
+
+/// Appends a `<parameter>` element for each parameter of a method.
+fn push_parameters<'gc>(
+    out: &mut WString,
+    signature: &[ParamConfig<'gc>],
+    mc: MutationContext<'gc, '_>,
+) {
+    for (index, param) in signature.iter().enumerate() {
+        out.push_utf8("<parameter");
+        push_xml_attribute(
+            out,
+            "index",
+            WStr::from_units((index + 1).to_string().as_bytes()),
+        );
+        push_xml_attribute(
+            out,
+            "type",
+            &qualified_type_name(&param.param_type_name, mc),
+        );
+        let optional: &[u8] = if param.default_value.is_some() {
+            b"true"
+        } else {
+            b"false"
+        };
+        push_xml_attribute(out, "optional", WStr::from_units(optional));
+        out.push_utf8("/>");
+    }
+}
+
+/// The return type of a method, or `*` for native methods, which don't
+/// record one.
+fn return_type_name<'gc>(method: &Method<'gc>, mc: MutationContext<'gc, '_>) -> AvmString<'gc> {
+    match method {
+        Method::Bytecode(method) => qualified_type_name(&method.return_type, mc),
+        Method::Native(_) => "*".into(),
+    }
+}
+
+/// Whether a trait should be listed by `describeType`.
+///
+/// Only public traits, and traits in user-defined namespaces, are visible.
+fn is_described(name: QName<'_>) -> bool {
+    name.namespace().is_namespace() && name.namespace() != Namespace::as3_namespace()
+}
+
+/// A trait as described by `describeType`. Getters and setters of the same
+/// name are merged into a single accessor.
+enum DescribedTrait<'gc> {
+    Variable(Trait<'gc>),
+    Accessor {
+        name: QName<'gc>,
+        readable: bool,
+        writable: bool,
+        type_name: AvmString<'gc>,
+        declared_by: AvmString<'gc>,
+        metadata: Vec<Metadata<'gc>>,
+    },
+    Method(Trait<'gc>, AvmString<'gc>),
+}
+
+impl<'gc> DescribedTrait<'gc> {
+    fn name(&self) -> QName<'gc> {
+        match self {
+            Self::Variable(t) | Self::Method(t, _) => t.name(),
+            Self::Accessor { name, .. } => *name,
+        }
+    }
+}
+
+/// Collects the visible traits of a class and, if `inherited` is set, of
+/// its superclasses.
+///
+/// Traits overridden by a subclass are only listed once, as declared by the
+/// subclass.
+fn collect_traits<'gc>(
+    class: ClassObject<'gc>,
+    instance: bool,
+    inherited: bool,
+    mc: MutationContext<'gc, '_>,
+) -> Vec<DescribedTrait<'gc>> {
+    let mut described: Vec<DescribedTrait<'gc>> = Vec::new();
+    let mut class = Some(class);
+    while let Some(current) = class {
+        let definition = current.inner_class_definition();
+        let declared_by = qualified_class_name(definition, mc);
+        let traits = if instance {
+            definition.read().instance_traits().to_vec()
+        } else {
+            definition.read().class_traits().to_vec()
+        };
+
+        for trait_ in traits {
+            let name = trait_.name();
+            if !is_described(name) {
+                continue;
+            }
+
+            let existing = described.iter().position(|d| d.name() == name);
+            match trait_.kind() {
+                TraitKind::Slot { .. } | TraitKind::Const { .. } if existing.is_none() => {
+                    described.push(DescribedTrait::Variable(trait_.clone()));
+                }
+                TraitKind::Method { .. } if existing.is_none() => {
+                    described.push(DescribedTrait::Method(trait_.clone(), declared_by));
+                }
+                TraitKind::Getter { method, .. } | TraitKind::Setter { method, .. } => {
+                    let is_getter = matches!(trait_.kind(), TraitKind::Getter { .. });
+                    let type_name = if is_getter {
+                        return_type_name(method, mc)
+                    } else {
+                        method
+                            .signature()
+                            .first()
+                            .map(|param| qualified_type_name(&param.param_type_name, mc))
+                            .unwrap_or_else(|| "*".into())
+                    };
+
+                    match existing.map(|index| &mut described[index]) {
+                        Some(DescribedTrait::Accessor {
+                            readable, writable, ..
+                        }) => {
+                            *readable |= is_getter;
+                            *writable |= !is_getter;
+                        }
+                        Some(_) => {}
+                        None => described.push(DescribedTrait::Accessor {
+                            name,
+                            readable: is_getter,
+                            writable: !is_getter,
+                            type_name,
+                            declared_by,
+                            metadata: trait_.metadata().to_vec(),
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        class = if inherited {
+            current.superclass_object()
+        } else {
+            None
+        };
+    }
+
+    described
+}
+
+/// Appends the description of the traits of a class.
+fn push_traits<'gc>(
+    out: &mut WString,
+    traits: &[DescribedTrait<'gc>],
+    mc: MutationContext<'gc, '_>,
+) {
+    for described in traits {
+        let name = described.name();
+        let uri = name.namespace().as_uri();
+        match described {
+            DescribedTrait::Variable(trait_) => {
+                let (tag, type_name) = match trait_.kind() {
+                    TraitKind::Const { type_name, .. } => ("<constant", type_name),
+                    TraitKind::Slot { type_name, .. } => ("<variable", type_name),
+                    _ => continue,
+                };
+                out.push_utf8(tag);
+                push_xml_attribute(out, "name", &name.local_name());
+                if !uri.is_empty() {
+                    push_xml_attribute(out, "uri", &uri);
+                }
+                push_xml_attribute(out, "type", &qualified_type_name(type_name, mc));
+                if trait_.metadata().is_empty() {
+                    out.push_utf8("/>");
+                } else {
+                    out.push_char('>');
+                    push_metadata(out, trait_.metadata());
+                    out.push_utf8(&tag.replace('<', "</"));
+                    out.push_char('>');
+                }
+            }
+            DescribedTrait::Accessor {
+                readable,
+                writable,
+                type_name,
+                declared_by,
+                metadata,
+                ..
+            } => {
+                let access = match (readable, writable) {
+                    (true, true) => "readwrite",
+                    (true, false) => "readonly",
+                    _ => "writeonly",
+                };
+                out.push_utf8("<accessor");
+                push_xml_attribute(out, "name", &name.local_name());
+                if !uri.is_empty() {
+                    push_xml_attribute(out, "uri", &uri);
+                }
+                push_xml_attribute(out, "access", WStr::from_units(access.as_bytes()));
+                push_xml_attribute(out, "type", type_name);
+                push_xml_attribute(out, "declaredBy", declared_by);
+                if metadata.is_empty() {
+                    out.push_utf8("/>");
+                } else {
+                    out.push_char('>');
+                    push_metadata(out, metadata);
+                    out.push_utf8("</accessor>");
+                }
+            }
+            DescribedTrait::Method(trait_, declared_by) => {
+                let method = match trait_.as_method() {
+                    Some(method) => method,
+                    None => continue,
+                };
+                out.push_utf8("<method");
+                push_xml_attribute(out, "name", &name.local_name());
+                if !uri.is_empty() {
+                    push_xml_attribute(out, "uri", &uri);
+                }
+                push_xml_attribute(out, "declaredBy", declared_by);
+                push_xml_attribute(out, "returnType", &return_type_name(&method, mc));
+                if method.signature().is_empty() && trait_.metadata().is_empty() {
+                    out.push_utf8("/>");
+                } else {
+                    out.push_char('>');
+                    push_parameters(out, method.signature(), mc);
+                    push_metadata(out, trait_.metadata());
+                    out.push_utf8("</method>");
+                }
+            }
+        }
+    }
+}
+
+/// Appends the superclasses, interfaces, constructor and instance traits of
+/// a class; the contents of the description of its instances.
+fn push_instance_description<'gc>(
+    out: &mut WString,
+    class: ClassObject<'gc>,
+    mc: MutationContext<'gc, '_>,
+) {
+    let mut superclass = class.superclass_object();
+    while let Some(current) = superclass {
+        out.push_utf8("<extendsClass");
+        push_xml_attribute(
+            out,
+            "type",
+            &qualified_class_name(current.inner_class_definition(), mc),
+        );
+        out.push_utf8("/>");
+        superclass = current.superclass_object();
+    }
+
+    let mut interfaces: Vec<ClassObject<'gc>> = Vec::new();
+    let mut current = Some(class);
+    while let Some(cls) = current {
+        let mut pending = cls.interfaces();
+        while let Some(interface) = pending.pop() {
+            if interfaces.iter().any(|i| Object::ptr_eq(*i, interface)) {
+                continue;
+            }
+            pending.extend(interface.interfaces());
+            interfaces.push(interface);
+        }
+        current = cls.superclass_object();
+    }
+    for interface in interfaces {
+        out.push_utf8("<implementsInterface");
+        push_xml_attribute(
+            out,
+            "type",
+            &qualified_class_name(interface.inner_class_definition(), mc),
+        );
+        out.push_utf8("/>");
+    }
+
+    let constructor = class.inner_class_definition().read().instance_init();
+    if !constructor.signature().is_empty() {
+        out.push_utf8("<constructor>");
+        push_parameters(out, constructor.signature(), mc);
+        out.push_utf8("</constructor>");
+    }
+
+    push_traits(out, &collect_traits(class, true, true, mc), mc);
+}
+
+/// Builds the markup describing a value for `describeType`.
+fn type_description<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Result<WString, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    let mut out = WString::new();
+
+    let object = match value {
+        Value::Null | Value::Undefined => {
+            let name = if matches!(value, Value::Null) {
+                "null"
+            } else {
+                "void"
+            };
+            out.push_utf8("<type");
+            push_xml_attribute(&mut out, "name", WStr::from_units(name.as_bytes()));
+            out.push_utf8(" isDynamic=\"false\" isFinal=\"true\" isStatic=\"false\"/>");
+            return Ok(out);
+        }
+        value => value.coerce_to_object(activation)?,
+    };
+
+    if let Some(class) = object.as_class_object() {
+        let definition = class.inner_class_definition();
+        let class_class = activation.avm2().classes().class;
+
+        out.push_utf8("<type");
+        push_xml_attribute(&mut out, "name", &qualified_class_name(definition, mc));
+        push_xml_attribute(&mut out, "base", WStr::from_units(b"Class"));
+        out.push_utf8(" isDynamic=\"true\" isFinal=\"true\" isStatic=\"true\">");
+        out.push_utf8("<extendsClass type=\"Class\"/><extendsClass type=\"Object\"/>");
+
+        let mut traits = collect_traits(class, false, false, mc);
+        traits.extend(collect_traits(class_class, true, true, mc));
+        push_traits(&mut out, &traits, mc);
+        push_metadata(&mut out, definition.read().metadata());
+
+        out.push_utf8("<factory");
+        push_xml_attribute(&mut out, "type", &qualified_class_name(definition, mc));
+        out.push_char('>');
+        push_instance_description(&mut out, class, mc);
+        out.push_utf8("</factory></type>");
+        return Ok(out);
+    }
+
+    let class = match object.instance_of() {
+        Some(class) => class,
+        None => activation.avm2().classes().object,
+    };
+    let definition = class.inner_class_definition();
+
+    out.push_utf8("<type");
+    push_xml_attribute(&mut out, "name", &qualified_class_name(definition, mc));
+    if let Some(superclass) = class.superclass_object() {
+        push_xml_attribute(
+            &mut out,
+            "base",
+            &qualified_class_name(superclass.inner_class_definition(), mc),
+        );
+    }
+    let flag = |set: bool| WStr::from_units(if set { b"true" as &[u8] } else { b"false" });
+    push_xml_attribute(&mut out, "isDynamic", flag(!definition.read().is_sealed()));
+    push_xml_attribute(&mut out, "isFinal", flag(definition.read().is_final()));
+    push_xml_attribute(&mut out, "isStatic", flag(false));
+    out.push_char('>');
+    push_instance_description(&mut out, class, mc);
+    push_metadata(&mut out, definition.read().metadata());
+    out.push_utf8("</type>");
+
+    Ok(out)
+}
+
+/// Implements `flash.utils.describeType`
+pub fn describe_type<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let description = type_description(activation, value)?;
+    let description = AvmString::new(activation.context.gc_context, description);
+
+    let xml_class = activation.avm2().classes().xml;
+    Ok(xml_class
+        .construct(activation, &[description.into()])?
+        .into())
 }
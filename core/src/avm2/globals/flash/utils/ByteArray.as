@@ -1,4 +1,6 @@
 package flash.utils {
+	namespace ruffle = "__ruffle__";
+
 	[Ruffle(InstanceAllocator)]
 	public class ByteArray implements IDataInput, IDataOutput {
 		private static var _defaultObjectEncoding:uint = 3;
@@ -24,6 +26,19 @@ package flash.utils {
 		public native function get position():uint;
 		public native function set position(value:uint):void;
 
+		ruffle var _shareable:Boolean = false;
+
+		public function get shareable():Boolean {
+			return this.ruffle::_shareable;
+		}
+
+		// A shareable byte array can't be made unshareable again.
+		public function set shareable(value:Boolean):void {
+			if (value) {
+				this.ruffle::_shareable = true;
+			}
+		}
+
 		public function ByteArray() {
 			this.init();
 			this.objectEncoding = _defaultObjectEncoding;
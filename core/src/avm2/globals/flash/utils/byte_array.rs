@@ -1,4 +1,5 @@
 use crate::avm2::activation::Activation;
+use crate::avm2::amf::{Amf3Reader, Amf3Writer};
 use crate::avm2::bytearray::{Endian, ObjectEncoding};
 pub use crate::avm2::object::byte_array_allocator;
 use crate::avm2::object::{Object, TObject};
@@ -9,7 +10,6 @@ use crate::string::AvmString;
 use encoding_rs::Encoding;
 use encoding_rs::UTF_8;
 use flash_lso::amf0::read::AMF0Decoder;
-use flash_lso::amf3::read::AMF3Decoder;
 use flash_lso::types::{AMFVersion, Element};
 
 /// Implements `flash.utils.ByteArray`'s instance constructor.
@@ -92,18 +92,21 @@ pub fn write_bytes<'gc>(
             let ba_read = bytearray
                 .as_bytearray()
                 .ok_or("ArgumentError: Parameter must be a bytearray")?;
-            let to_write = ba_read.read_at(
-                // If length is 0, lets read the remaining bytes of ByteArray from the supplied offset
-                if length != 0 {
-                    length
-                } else {
-                    ba_read.len().saturating_sub(offset)
-                },
-                offset,
-            )?;
+            let to_write = ba_read
+                .read_at(
+                    // If length is 0, lets read the remaining bytes of ByteArray from the supplied offset
+                    if length != 0 {
+                        length
+                    } else {
+                        ba_read.len().saturating_sub(offset)
+                    },
+                    offset,
+                )?
+                // Both may still share memory with another worker, which can't be locked twice.
+                .to_vec();
 
             if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
-                bytearray.write_bytes(to_write)?;
+                bytearray.write_bytes(&to_write)?;
             }
         } else if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
             // The ByteArray we are reading from is the same as the ByteArray we are writing to,
@@ -142,20 +145,23 @@ pub fn read_bytes<'gc>(
 
         if !Object::ptr_eq(this, bytearray) {
             if let Some(bytearray_read) = this.as_bytearray() {
-                let to_write = bytearray_read.read_bytes(
-                    // If length is 0, lets read the remaining bytes of ByteArray
-                    if length != 0 {
-                        length
-                    } else {
-                        bytearray_read.bytes_available()
-                    },
-                )?;
+                let to_write = bytearray_read
+                    .read_bytes(
+                        // If length is 0, lets read the remaining bytes of ByteArray
+                        if length != 0 {
+                            length
+                        } else {
+                            bytearray_read.bytes_available()
+                        },
+                    )?
+                    // Both may still share memory with another worker, which can't be locked twice.
+                    .to_vec();
 
                 let mut ba_write = bytearray
                     .as_bytearray_mut(activation.context.gc_context)
                     .ok_or("ArgumentError: Parameter must be a bytearray")?;
 
-                ba_write.write_at(to_write, offset)?;
+                ba_write.write_at(&to_write, offset)?;
             }
         } else if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
             let amnt = if length != 0 {
@@ -199,7 +205,7 @@ pub fn read_utf<'gc>(
         if let Some(bytearray) = this.as_bytearray() {
             return Ok(AvmString::new_utf8_bytes(
                 activation.context.gc_context,
-                bytearray.read_utf()?,
+                &bytearray.read_utf()?,
             )
             .into());
         }
@@ -214,9 +220,11 @@ pub fn to_string<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
         if let Some(bytearray) = this.as_bytearray() {
-            return Ok(
-                AvmString::new_utf8_bytes(activation.context.gc_context, bytearray.bytes()).into(),
-            );
+            return Ok(AvmString::new_utf8_bytes(
+                activation.context.gc_context,
+                &bytearray.bytes(),
+            )
+            .into());
         }
     }
 
@@ -482,7 +490,7 @@ pub fn read_utf_bytes<'gc>(
                 .coerce_to_u32(activation)?;
             return Ok(AvmString::new_utf8(
                 activation.context.gc_context,
-                String::from_utf8_lossy(bytearray.read_bytes(len as usize)?),
+                String::from_utf8_lossy(&bytearray.read_bytes(len as usize)?),
             )
             .into());
         }
@@ -654,7 +662,7 @@ pub fn read_multi_byte<'gc>(
             let bytes = bytearray.read_bytes(len as usize)?;
             let encoder =
                 Encoding::for_label(charset_label.to_utf8_lossy().as_bytes()).unwrap_or(UTF_8);
-            let (decoded_str, _, _) = encoder.decode(bytes);
+            let (decoded_str, _, _) = encoder.decode(&bytes);
             return Ok(AvmString::new_utf8(activation.context.gc_context, decoded_str).into());
         }
     }
@@ -694,13 +702,17 @@ pub fn compress<'gc>(
             let algorithm = match algorithm.parse() {
                 Ok(algorithm) => algorithm,
                 Err(_) => {
-                    return Err(Error::AvmError(crate::avm2::error::io_error(
+                    return Err(Error::AvmError(crate::avm2::error::argument_error(
                         activation,
-                        "Error #2058: There was an error decompressing the data.",
-                        2058,
+                        "Error #2008: Parameter algorithm must be one of the accepted values.",
+                        2008,
                     )?))
                 }
             };
+            // Compressing or uncompressing nothing leaves the array empty.
+            if bytearray.len() == 0 {
+                return Ok(Value::Undefined);
+            }
             let buffer = bytearray.compress(algorithm);
             bytearray.clear();
             bytearray.write_bytes(&buffer)?;
@@ -724,13 +736,17 @@ pub fn uncompress<'gc>(
             let algorithm = match algorithm.parse() {
                 Ok(algorithm) => algorithm,
                 Err(_) => {
-                    return Err(Error::AvmError(crate::avm2::error::io_error(
+                    return Err(Error::AvmError(crate::avm2::error::argument_error(
                         activation,
-                        "Error #2058: There was an error decompressing the data.",
-                        2058,
+                        "Error #2008: Parameter algorithm must be one of the accepted values.",
+                        2008,
                     )?))
                 }
             };
+            // Compressing or uncompressing nothing leaves the array empty.
+            if bytearray.len() == 0 {
+                return Ok(Value::Undefined);
+            }
             let buffer = match bytearray.decompress(algorithm) {
                 Some(buffer) => buffer,
                 None => {
@@ -743,6 +759,7 @@ pub fn uncompress<'gc>(
             };
             bytearray.clear();
             bytearray.write_bytes(&buffer)?;
+            bytearray.set_position(0);
         }
     }
 
@@ -755,33 +772,32 @@ pub fn read_object<'gc>(
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
-        if let Some(bytearray) = this.as_bytearray() {
-            let bytes = bytearray.read_at(bytearray.bytes_available(), bytearray.position())?;
-            let (bytes_left, value) = match bytearray.object_encoding() {
-                ObjectEncoding::Amf0 => {
+        let object_encoding = this.as_bytearray().map(|b| b.object_encoding());
+        match object_encoding {
+            Some(ObjectEncoding::Amf0) => {
+                let (bytes_left, amf) = {
+                    let bytearray = this.as_bytearray().unwrap();
+                    let bytes =
+                        bytearray.read_at(bytearray.bytes_available(), bytearray.position())?;
                     let mut decoder = AMF0Decoder::default();
                     let (extra, amf) = decoder
-                        .parse_single_element(bytes)
+                        .parse_single_element(&bytes)
                         .map_err(|_| "Error: Invalid object")?;
-                    (
-                        extra.len(),
-                        crate::avm2::amf::deserialize_value(activation, &amf)?,
-                    )
-                }
-                ObjectEncoding::Amf3 => {
-                    let mut decoder = AMF3Decoder::default();
-                    let (extra, amf) = decoder
-                        .parse_single_element(bytes)
-                        .map_err(|_| "Error: Invalid object")?;
-                    (
-                        extra.len(),
-                        crate::avm2::amf::deserialize_value(activation, &amf)?,
-                    )
-                }
-            };
+                    (extra.len(), amf)
+                };
+                let value = crate::avm2::amf::deserialize_value(activation, &amf)?;
 
-            bytearray.set_position(bytearray.len() - bytes_left);
-            return Ok(value);
+                if let Some(bytearray) = this.as_bytearray() {
+                    bytearray.set_position(bytearray.len() - bytes_left);
+                }
+                return Ok(value);
+            }
+            // AMF3 is read straight from the array, so that `IExternalizable`
+            // objects can read their own data.
+            Some(ObjectEncoding::Amf3) => {
+                return Amf3Reader::new(this).read_value(activation);
+            }
+            None => {}
         }
     }
 
@@ -794,27 +810,30 @@ pub fn write_object<'gc>(
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(this) = this {
-        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
-            let obj = args.get(0).cloned().unwrap_or(Value::Undefined);
-            let amf_version = match bytearray.object_encoding() {
-                ObjectEncoding::Amf0 => AMFVersion::AMF0,
-                ObjectEncoding::Amf3 => AMFVersion::AMF3,
-            };
-            if let Some(amf) = crate::avm2::amf::serialize_value(activation, obj, amf_version) {
+        let obj = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let object_encoding = this.as_bytearray().map(|b| b.object_encoding());
+        if object_encoding == Some(ObjectEncoding::Amf3) {
+            return Amf3Writer::new(this)
+                .write_value(activation, obj)
+                .map(|_| Value::Undefined);
+        }
+
+        if object_encoding.is_some() {
+            let amf_version = AMFVersion::AMF0;
+            if let Some(amf) = crate::avm2::amf::serialize_value(activation, obj) {
                 let element = Element::new("", amf);
                 let mut lso = flash_lso::types::Lso::new(vec![element], "", amf_version);
                 let bytes = flash_lso::write::write_to_bytes(&mut lso)
                     .map_err(|_| "Failed to serialize object")?;
                 // This is kind of hacky: We need to strip out the header and any padding so that we only write
                 // the value. In the future, there should be a method to do this in the flash_lso crate.
-                let element_padding = match amf_version {
-                    AMFVersion::AMF0 => 8,
-                    AMFVersion::AMF3 => 7,
-                };
-                bytearray.write_bytes(
-                    &bytes[flash_lso::write::header_length(&lso.header) + element_padding
-                        ..bytes.len() - 1],
-                )?;
+                let element_padding = 8;
+                if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+                    bytearray.write_bytes(
+                        &bytes[flash_lso::write::header_length(&lso.header) + element_padding
+                            ..bytes.len() - 1],
+                    )?;
+                }
             }
         }
     }
@@ -1,17 +1,25 @@
 use crate::avm2::activation::Activation;
-use crate::avm2::object::Object;
+use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 
 pub use crate::avm2::object::proxy_allocator;
-use crate::avm2_stub_method;
 
+/// Implements `flash_proxy::isAttribute`.
+///
+/// Names passed to the other `flash_proxy` methods remember whether the
+/// property was accessed as an attribute, e.g. `proxy.@name`.
 pub fn is_attribute<'gc>(
-    activation: &mut Activation<'_, 'gc>,
+    _activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // yes, this is supposed to be implemented
-    avm2_stub_method!(activation, "flash.utils.Proxy", "isAttribute");
-    Ok(false.into())
+    let is_attribute = args
+        .get(0)
+        .and_then(|name| name.as_object())
+        .and_then(|name| name.as_qname_object())
+        .map(|name| name.is_attribute())
+        .unwrap_or(false);
+
+    Ok(is_attribute.into())
 }
@@ -20,8 +20,10 @@ include "flash/accessibility/AccessibilityProperties.as"
 include "flash/crypto.as"
 include "flash/utils/IDataInput.as"
 include "flash/utils/IDataOutput.as"
+include "flash/utils/IExternalizable.as"
 include "flash/utils/ByteArray.as"
 include "flash/utils/Dictionary.as"
+include "flash/desktop/Clipboard.as"
 include "flash/desktop/ClipboardFormats.as"
 include "flash/desktop/ClipboardTransferMode.as"
 include "flash/display/ActionScriptVersion.as"
@@ -91,6 +93,7 @@ include "flash/events/AVHTTPStatusEvent.as"
 include "flash/events/AVPauseAtPeriodEndEvent.as"
 include "flash/events/ContextMenuEvent.as"
 include "flash/events/DataEvent.as"
+include "flash/events/DatagramSocketDataEvent.as"
 include "flash/events/DRMAuthenticationCompleteEvent.as"
 include "flash/events/DRMAuthenticationErrorEvent.as"
 include "flash/events/DRMLicenseRequestEvent.as"
@@ -108,6 +111,7 @@ include "flash/events/NetStatusEvent.as"
 include "flash/events/ProgressEvent.as"
 include "flash/events/SampleDataEvent.as"
 include "flash/events/SecurityErrorEvent.as"
+include "flash/events/ServerSocketConnectEvent.as"
 include "flash/events/ShaderEvent.as"
 include "flash/events/SoftKeyboardEvent.as"
 include "flash/events/SoftKeyboardTrigger.as"
@@ -118,6 +122,7 @@ include "flash/events/SyncEvent.as"
 include "flash/events/ThrottleEvent.as"
 include "flash/events/ThrottleType.as"
 include "flash/events/TimerEvent.as"
+include "flash/events/TouchEvent.as"
 include "flash/events/UncaughtErrorEvent.as"
 include "flash/events/UncaughtErrorEvents.as"
 include "flash/events/VideoEvent.as"
@@ -156,11 +161,15 @@ include "flash/globalization/LastOperationStatus.as"
 include "flash/globalization/NationalDigitsType.as"
 include "flash/media/AudioDecoder.as"
 include "flash/media/AudioOutputChangeReason.as"
+include "flash/media/Camera.as"
 include "flash/media/H264Level.as"
 include "flash/media/H264Profile.as"
+include "flash/media/ID3Info.as"
+include "flash/media/Microphone.as"
 include "flash/media/MicrophoneEnhancedMode.as"
 include "flash/media/SoundCodec.as"
 include "flash/media/SoundLoaderContext.as"
+include "flash/media/StageVideo.as"
 include "flash/media/StageVideoAvailability.as"
 include "flash/media/StageVideoAvailabilityReason.as"
 include "flash/media/VideoCodec.as"
@@ -169,31 +178,41 @@ include "flash/media/VideoStatus.as"
 include "flash/external/ExternalInterface.as"
 
 include "flash/net.as"
+include "flash/net/DatagramSocket.as"
 include "flash/net/FileFilter.as"
 include "flash/net/FileReference.as"
 include "flash/net/FileReferenceList.as"
 include "flash/net/IDynamicPropertyOutput.as"
 include "flash/net/IDynamicPropertyWriter.as"
 include "flash/net/LocalConnection.as"
+include "flash/net/NetConnection.as"
 include "flash/net/NetGroupReceiveMode.as"
 include "flash/net/NetGroupReplicationStrategy.as"
 include "flash/net/NetGroupSendMode.as"
 include "flash/net/NetGroupSendResult.as"
+include "flash/net/NetStream.as"
+include "flash/net/NetStreamAppendBytesAction.as"
 include "flash/net/ObjectEncoding.as"
+include "flash/net/ServerSocket.as"
 include "flash/net/SharedObject.as"
 include "flash/net/SharedObjectFlushStatus.as"
+include "flash/net/Socket.as"
 include "flash/net/URLLoader.as"
 include "flash/net/URLLoaderDataFormat.as"
 include "flash/net/URLRequest.as"
 include "flash/net/URLRequestHeader.as"
 include "flash/net/URLRequestMethod.as"
+include "flash/net/URLStream.as"
 include "flash/net/URLVariables.as"
+include "flash/net/XMLSocket.as"
 
 include "flash/printing/PrintJobOrientation.as"
 include "flash/security/CertificateStatus.as"
+include "flash/system.as"
 include "flash/system/Capabilities.as"
 include "flash/system/IMEConversionMode.as"
 include "flash/system/LoaderContext.as"
+include "flash/system/MessageChannel.as"
 include "flash/system/MessageChannelState.as"
 include "flash/system/Security.as"
 include "flash/system/SecurityDomain.as"
@@ -201,12 +220,15 @@ include "flash/system/SecurityPanel.as"
 include "flash/system/System.as"
 include "flash/system/SystemUpdaterType.as"
 include "flash/system/TouchscreenType.as"
+include "flash/system/Worker.as"
+include "flash/system/WorkerDomain.as"
 include "flash/system/WorkerState.as"
 include "flash/text/AntiAliasType.as"
 include "flash/text/FontStyle.as"
 include "flash/text/FontType.as"
 include "flash/text/GridFitType.as"
 include "flash/text/StaticText.as"
+include "flash/text/StyleSheet.as"
 include "flash/text/TextColorType.as"
 include "flash/text/TextDisplayMode.as"
 include "flash/text/TextExtent.as"
@@ -215,21 +237,35 @@ include "flash/text/TextFieldType.as"
 include "flash/text/TextFormatAlign.as"
 include "flash/text/TextInteractionMode.as"
 include "flash/text/TextLineMetrics.as"
+include "flash/text/TextSnapshot.as"
 
 include "flash/text/engine/BreakOpportunity.as"
 include "flash/text/engine/CFFHinting.as"
+include "flash/text/engine/ContentElement.as"
 include "flash/text/engine/DigitCase.as"
 include "flash/text/engine/DigitWidth.as"
+include "flash/text/engine/TextJustifier.as"
+include "flash/text/engine/EastAsianJustifier.as"
+include "flash/text/engine/ElementFormat.as"
+include "flash/text/engine/FontDescription.as"
 include "flash/text/engine/FontLookup.as"
+include "flash/text/engine/FontMetrics.as"
 include "flash/text/engine/FontPosture.as"
 include "flash/text/engine/FontWeight.as"
+include "flash/text/engine/GraphicElement.as"
+include "flash/text/engine/GroupElement.as"
 include "flash/text/engine/JustificationStyle.as"
 include "flash/text/engine/Kerning.as"
 include "flash/text/engine/LigatureLevel.as"
 include "flash/text/engine/LineJustification.as"
 include "flash/text/engine/RenderingMode.as"
+include "flash/text/engine/SpaceJustifier.as"
 include "flash/text/engine/TabAlignment.as"
+include "flash/text/engine/TabStop.as"
 include "flash/text/engine/TextBaseline.as"
+include "flash/text/engine/TextBlock.as"
+include "flash/text/engine/TextElement.as"
+include "flash/text/engine/TextLine.as"
 include "flash/text/engine/TextLineCreationResult.as"
 include "flash/text/engine/TextLineValidity.as"
 include "flash/text/engine/TextRotation.as"
@@ -244,6 +280,7 @@ include "flash/ui/KeyboardType.as"
 include "flash/ui/KeyLocation.as"
 include "flash/ui/Mouse.as"
 include "flash/ui/MouseCursor.as"
+include "flash/ui/MouseCursorData.as"
 // `MultitouchInputMode` needs to come before `Multitouch`, since `Multitouch` uses constants from
 // `MultitouchInputMode`.
 include "flash/ui/MultitouchInputMode.as"
@@ -256,3 +293,8 @@ include "flash/utils/Endian.as"
 include "flash/utils/Timer.as"
 include "XML.as"
 include "XMLList.as"
+
+// Stand-ins for the TLF classes of Flash Professional, which need a library that movies
+// can no longer load.
+include "fl/text/TLFTextField.as"
+include "fl/text/RuntimeManager.as"
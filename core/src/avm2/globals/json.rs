@@ -2,17 +2,14 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::array::ArrayStorage;
+use crate::avm2::error::{syntax_error, type_error};
 use crate::avm2::globals::array::ArrayIter;
 use crate::avm2::object::{ArrayObject, FunctionObject, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
-use crate::ecma_conversions::f64_to_wrapping_i32;
-use crate::string::{AvmString, Units};
-use serde::Serialize;
-use serde_json::{Map as JsonObject, Value as JsonValue};
-use std::borrow::Cow;
-use std::ops::Deref;
+use crate::string::{AvmString, WStr, WString};
+use serde_json::Value as JsonValue;
 
 fn deserialize_json_inner<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -25,8 +22,10 @@ fn deserialize_json_inner<'gc>(
         JsonValue::Bool(b) => b.into(),
         JsonValue::Number(number) => {
             let number = number.as_f64().unwrap();
-            if number.fract() == 0.0 {
-                f64_to_wrapping_i32(number).into()
+            // Integers are parsed as `int` when they fit in one.
+            let int = number as i32;
+            if f64::from(int) == number && (int != 0 || number.is_sign_positive()) {
+                int.into()
             } else {
                 number.into()
             }
@@ -34,12 +33,12 @@ fn deserialize_json_inner<'gc>(
         JsonValue::Object(js_obj) => {
             let obj_class = activation.avm2().classes().object;
             let mut obj = obj_class.construct(activation, &[])?;
-            for entry in js_obj.iter() {
-                let key = AvmString::new_utf8(activation.context.gc_context, entry.0);
-                let val = deserialize_json_inner(activation, entry.1.clone(), reviver)?;
+            for (key, val) in js_obj {
+                let key = AvmString::new_utf8(activation.context.gc_context, key);
+                let val = deserialize_json_inner(activation, val, reviver)?;
                 let mapped_val = match reviver {
                     None => val,
-                    Some(reviver) => reviver.call(None, &[key.into(), val], activation)?,
+                    Some(reviver) => reviver.call(Some(obj), &[key.into(), val], activation)?,
                 };
                 if matches!(mapped_val, Value::Undefined) {
                     obj.delete_property(activation, &Multiname::public(key))?;
@@ -50,17 +49,26 @@ fn deserialize_json_inner<'gc>(
             obj.into()
         }
         JsonValue::Array(js_arr) => {
-            let mut arr: Vec<Option<Value<'gc>>> = Vec::with_capacity(js_arr.len());
-            for (key, val) in js_arr.iter().enumerate() {
-                let val = deserialize_json_inner(activation, val.clone(), reviver)?;
+            let storage = ArrayStorage::new(js_arr.len());
+            let array = ArrayObject::from_storage(activation, storage)?;
+            for (index, val) in js_arr.into_iter().enumerate() {
+                let val = deserialize_json_inner(activation, val, reviver)?;
                 let mapped_val = match reviver {
                     None => val,
-                    Some(reviver) => reviver.call(None, &[key.into(), val], activation)?,
+                    Some(reviver) => {
+                        let key =
+                            AvmString::new_utf8(activation.context.gc_context, index.to_string());
+                        reviver.call(Some(array), &[key.into(), val], activation)?
+                    }
                 };
-                arr.push(Some(mapped_val));
+                // Like object properties, elements revived to `undefined` are deleted.
+                if !matches!(mapped_val, Value::Undefined) {
+                    array
+                        .as_array_storage_mut(activation.context.gc_context)
+                        .expect("Array should have array storage")
+                        .set(index, mapped_val);
+                }
             }
-            let storage = ArrayStorage::from_storage(arr);
-            let array = ArrayObject::from_storage(activation, storage)?;
             array.into()
         }
     })
@@ -74,26 +82,40 @@ fn deserialize_json<'gc>(
     let val = deserialize_json_inner(activation, json, reviver)?;
     match reviver {
         None => Ok(val),
-        Some(reviver) => reviver.call(None, &["".into(), val], activation),
+        Some(reviver) => {
+            // The top-level value is revived as the "" property of a new holder object.
+            let obj_class = activation.avm2().classes().object;
+            let mut holder = obj_class.construct(activation, &[])?;
+            holder.set_property(&Multiname::public(""), val, activation)?;
+            reviver.call(Some(holder), &["".into(), val], activation)
+        }
     }
 }
 
 enum Replacer<'gc> {
     Function(FunctionObject<'gc>),
-    PropList(ArrayObject<'gc>),
+    PropList(Object<'gc>),
 }
 
 struct AvmSerializer<'gc> {
     /// This object stack will be used to detect circular references and return an error instead of a panic.
     obj_stack: Vec<Object<'gc>>,
     replacer: Option<Replacer<'gc>>,
+
+    /// The string that each nesting level is indented with. Empty for compact output.
+    gap: WString,
+
+    /// The indentation of the current nesting level.
+    indent: WString,
 }
 
 impl<'gc> AvmSerializer<'gc> {
-    fn new(replacer: Option<Replacer<'gc>>) -> Self {
+    fn new(replacer: Option<Replacer<'gc>>, gap: WString) -> Self {
         Self {
             obj_stack: Vec::new(),
             replacer,
+            gap,
+            indent: WString::new(),
         }
     }
 
@@ -124,7 +146,10 @@ impl<'gc> AvmSerializer<'gc> {
                 .and_then(|obj| obj.as_function_object());
             if let Some(to_json) = to_json {
                 let key = key();
-                (Some(key), to_json.call(None, &[key.into()], activation)?)
+                (
+                    Some(key),
+                    to_json.call(Some(obj), &[key.into()], activation)?,
+                )
             } else {
                 (None, value)
             }
@@ -140,47 +165,111 @@ impl<'gc> AvmSerializer<'gc> {
         }
     }
 
-    fn serialize_object(
-        &mut self,
+    /// Returns the names of the properties of an object that are serialized.
+    ///
+    /// These are the names in the replacer's property list if there is one. Otherwise, these are
+    /// the public variables and getters of the object's class, followed by its enumerable dynamic
+    /// properties.
+    fn property_names(
+        &self,
         activation: &mut Activation<'_, 'gc>,
         obj: Object<'gc>,
-    ) -> Result<JsonValue, Error<'gc>> {
-        let mut js_obj = JsonObject::new();
-        // If the user supplied a PropList, we use that to find properties on the object.
+    ) -> Result<Vec<AvmString<'gc>>, Error<'gc>> {
+        let mut names = Vec::new();
         if let Some(Replacer::PropList(props)) = self.replacer {
-            let mut iter = ArrayIter::new(activation, props.into())?;
+            let mut iter = ArrayIter::new(activation, props)?;
             while let Some(r) = iter.next(activation) {
-                let item = r?.1;
-                let key = item.coerce_to_string(activation)?;
-                let value = obj.get_property(&Multiname::public(key), activation)?;
-                let mapped = self.map_value(activation, || key, value)?;
-                if !matches!(mapped, Value::Undefined) {
-                    js_obj.insert(
-                        key.to_utf8_lossy().into_owned(),
-                        self.serialize_value(activation, mapped)?,
-                    );
+                let name = r?.1.coerce_to_string(activation)?;
+                if !names.contains(&name) {
+                    names.push(name);
                 }
             }
         } else {
+            if let Some(vtable) = obj.vtable() {
+                names.extend(vtable.public_properties());
+            }
             for i in 1.. {
-                // TODO: We should get more than just enumerable properties
                 match obj.get_enumerant_name(i, activation)? {
                     Value::Undefined => break,
-                    name_val => {
-                        let name = name_val.coerce_to_string(activation)?;
-                        let value = obj.get_property(&Multiname::public(name), activation)?;
-                        let mapped = self.map_value(activation, || name, value)?;
-                        if !matches!(mapped, Value::Undefined) {
-                            js_obj.insert(
-                                name.to_utf8_lossy().into_owned(),
-                                self.serialize_value(activation, mapped)?,
-                            );
-                        }
-                    }
+                    name => names.push(name.coerce_to_string(activation)?),
                 }
             }
         }
-        Ok(JsonValue::Object(js_obj))
+        Ok(names)
+    }
+
+    /// Enters an object or array, returning the indentation to restore when leaving it.
+    fn enter(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        obj: Object<'gc>,
+    ) -> Result<WString, Error<'gc>> {
+        if self.obj_stack.contains(&obj) {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #1129: Cyclic structure cannot be converted to JSON string.",
+                1129,
+            )?));
+        }
+        self.obj_stack.push(obj);
+        let stepback = self.indent.clone();
+        self.indent.push_str(&self.gap);
+        Ok(stepback)
+    }
+
+    fn leave(&mut self, stepback: WString) {
+        self.obj_stack
+            .pop()
+            .expect("Stack underflow during JSON serialization");
+        self.indent = stepback;
+    }
+
+    /// Joins the serialized members of an object or array, indenting them if necessary.
+    fn join(&self, open: u8, members: Vec<WString>, close: u8, stepback: &WStr) -> WString {
+        let mut output = WString::new();
+        output.push_byte(open);
+        if !members.is_empty() {
+            for (i, member) in members.iter().enumerate() {
+                if i > 0 {
+                    output.push_byte(b',');
+                }
+                if !self.gap.is_empty() {
+                    output.push_byte(b'\n');
+                    output.push_str(&self.indent);
+                }
+                output.push_str(member);
+            }
+            if !self.gap.is_empty() {
+                output.push_byte(b'\n');
+                output.push_str(stepback);
+            }
+        }
+        output.push_byte(close);
+        output
+    }
+
+    fn serialize_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        obj: Object<'gc>,
+    ) -> Result<WString, Error<'gc>> {
+        let stepback = self.enter(activation, obj)?;
+        let mut members = Vec::new();
+        for name in self.property_names(activation, obj)? {
+            let value = obj.get_property(&Multiname::public(name), activation)?;
+            if let Some(value) = self.serialize_property(activation, || name, value)? {
+                let mut member = quote(&name);
+                member.push_byte(b':');
+                if !self.gap.is_empty() {
+                    member.push_byte(b' ');
+                }
+                member.push_str(&value);
+                members.push(member);
+            }
+        }
+        let output = self.join(b'{', members, b'}', &stepback);
+        self.leave(stepback);
+        Ok(output)
     }
 
     /// Serializes any object that can be iterated using an ArrayIter (like Array, Vector, etc).
@@ -189,52 +278,70 @@ impl<'gc> AvmSerializer<'gc> {
         &mut self,
         activation: &mut Activation<'_, 'gc>,
         iterable: Object<'gc>,
-    ) -> Result<JsonValue, Error<'gc>> {
-        let mut js_arr = Vec::new();
+    ) -> Result<WString, Error<'gc>> {
+        let stepback = self.enter(activation, iterable)?;
+        let mut members = Vec::new();
         let mut iter = ArrayIter::new(activation, iterable)?;
         while let Some(r) = iter.next(activation) {
             let (i, item) = r?;
             let mc = activation.context.gc_context;
-            let mapped =
-                self.map_value(activation, || AvmString::new_utf8(mc, i.to_string()), item)?;
-            js_arr.push(self.serialize_value(activation, mapped)?);
+            let value = self.serialize_property(
+                activation,
+                || AvmString::new_utf8(mc, i.to_string()),
+                item,
+            )?;
+            members.push(value.unwrap_or_else(|| WString::from_utf8("null")));
         }
-        Ok(JsonValue::Array(js_arr))
+        let output = self.join(b'[', members, b']', &stepback);
+        self.leave(stepback);
+        Ok(output)
+    }
+
+    /// Serializes a property value after mapping it.
+    ///
+    /// Returns `None` for values that can't be represented in JSON, such as `undefined` and
+    /// functions. These are skipped in objects, and written as `null` in arrays.
+    fn serialize_property(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        key: impl Fn() -> AvmString<'gc>,
+        value: Value<'gc>,
+    ) -> Result<Option<WString>, Error<'gc>> {
+        let mapped = self.map_value(activation, key, value)?;
+        self.serialize_value(activation, mapped)
     }
 
     fn serialize_value(
         &mut self,
         activation: &mut Activation<'_, 'gc>,
         value: Value<'gc>,
-    ) -> Result<JsonValue, Error<'gc>> {
-        Ok(match value {
-            Value::Null => JsonValue::Null,
-            Value::Undefined => JsonValue::Null,
-            Value::Integer(i) => JsonValue::from(i),
-            Value::Number(n) => JsonValue::from(n),
-            Value::Bool(b) => JsonValue::from(b),
-            Value::String(s) => JsonValue::from(s.to_utf8_lossy().deref()),
+    ) -> Result<Option<WString>, Error<'gc>> {
+        Ok(Some(match value {
+            Value::Undefined => return Ok(None),
+            Value::Null => WString::from_utf8("null"),
+            Value::Bool(b) => WString::from_utf8(if b { "true" } else { "false" }),
+            Value::Integer(i) => WString::from_utf8_owned(i.to_string()),
+            Value::Number(n) if !n.is_finite() => WString::from_utf8("null"),
+            Value::Number(_) => WString::from(value.coerce_to_string(activation)?.as_wstr()),
+            Value::String(s) => quote(&s),
             Value::Object(obj) => {
                 // special case for boxed primitives
-                if let Some(prim) = obj.as_primitive() {
-                    return self.serialize_value(activation, *prim);
+                let prim = obj.as_primitive().map(|prim| *prim);
+                if let Some(prim) = prim {
+                    return self.serialize_value(activation, prim);
                 }
-                if self.obj_stack.contains(&obj) {
-                    return Err("TypeError: Error #1129: Cyclic structure cannot be converted to JSON string.".into());
+                if obj.as_function_object().is_some() {
+                    return Ok(None);
                 }
-                self.obj_stack.push(obj);
-                let value = if obj.is_of_type(activation.avm2().classes().array, activation) {
-                    // TODO: Vectors
+                if obj.is_of_type(activation.avm2().classes().array, activation)
+                    || obj.as_vector_storage().is_some()
+                {
                     self.serialize_iterable(activation, obj)?
                 } else {
                     self.serialize_object(activation, obj)?
-                };
-                self.obj_stack
-                    .pop()
-                    .expect("Stack underflow during JSON serialization");
-                value
+                }
             }
-        })
+        }))
     }
 
     /// Same thing as serialize_value, but maps the value before calling it.
@@ -242,10 +349,30 @@ impl<'gc> AvmSerializer<'gc> {
         &mut self,
         activation: &mut Activation<'_, 'gc>,
         value: Value<'gc>,
-    ) -> Result<JsonValue, Error<'gc>> {
-        let mapped = self.map_value(activation, || "".into(), value)?;
-        self.serialize_value(activation, mapped)
+    ) -> Result<Option<WString>, Error<'gc>> {
+        self.serialize_property(activation, || "".into(), value)
+    }
+}
+
+/// Quotes a string for JSON, escaping the characters that need it.
+fn quote(s: &WStr) -> WString {
+    let mut output = WString::new();
+    output.push_byte(b'"');
+    for c in s.iter() {
+        match u8::try_from(c) {
+            Ok(b'"') => output.push_str(WStr::from_units(b"\\\"")),
+            Ok(b'\\') => output.push_str(WStr::from_units(b"\\\\")),
+            Ok(0x08) => output.push_str(WStr::from_units(b"\\b")),
+            Ok(0x0C) => output.push_str(WStr::from_units(b"\\f")),
+            Ok(b'\n') => output.push_str(WStr::from_units(b"\\n")),
+            Ok(b'\r') => output.push_str(WStr::from_units(b"\\r")),
+            Ok(b'\t') => output.push_str(WStr::from_units(b"\\t")),
+            Ok(c) if c < 0x20 => output.push_utf8(&format!("\\u{:04x}", c)),
+            _ => output.push(c),
+        }
     }
+    output.push_byte(b'"');
+    output
 }
 
 /// Implements `JSON.parse`.
@@ -259,8 +386,16 @@ pub fn parse<'gc>(
         .unwrap_or(&Value::Undefined)
         .coerce_to_string(activation)?;
     let reviver = args.get(1).unwrap_or(&Value::Undefined).as_object();
-    let parsed = serde_json::from_str(&input.to_utf8_lossy())
-        .map_err(|_| "SyntaxError: Error #1132: Invalid JSON parse input.")?;
+    let parsed = match serde_json::from_str(&input.to_utf8_lossy()) {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            return Err(Error::AvmError(syntax_error(
+                activation,
+                "Error #1132: Invalid JSON parse input.",
+                1132,
+            )?))
+        }
+    };
     deserialize_json(activation, parsed, reviver)
 }
 
@@ -273,53 +408,38 @@ pub fn stringify<'gc>(
     let val = args.get(0).unwrap_or(&Value::Undefined);
     let replacer = args.get(1).unwrap_or(&Value::Undefined).as_object();
     let spaces = args.get(2).unwrap_or(&Value::Undefined);
-    let replacer = replacer.map(|replacer| {
-        if let Some(func) = replacer.as_function_object() {
-            Ok(Replacer::Function(func))
-        } else if let Some(arr) = replacer.as_array_object() {
-            Ok(Replacer::PropList(arr))
-        } else {
-            Err("TypeError: Error #1131: Replacer argument to JSON stringifier must be an array or a two parameter function.")
+    let replacer = match replacer {
+        None => None,
+        Some(replacer) => {
+            if let Some(func) = replacer.as_function_object() {
+                Some(Replacer::Function(func))
+            } else if replacer.as_array_object().is_some() {
+                Some(Replacer::PropList(replacer))
+            } else {
+                return Err(Error::AvmError(type_error(
+                    activation,
+                    "Error #1131: Replacer argument to JSON stringifier must be an array or a two parameter function.",
+                    1131,
+                )?));
+            }
         }
-    }).transpose()?;
+    };
 
     // NOTE: We do not coerce to a string or to a number, the value must already be a string or number.
-    let indent = if let Value::String(s) = spaces {
-        if s.is_empty() {
-            None
-        } else {
-            // We can only use the first 10 characters.
-            let indent = &s[..s.len().min(10)];
-            let indent_bytes = match indent.units() {
-                Units::Bytes(units) => Cow::Borrowed(units),
-                Units::Wide(_) => Cow::Owned(indent.to_utf8_lossy().into_owned().into_bytes()),
-            };
-            Some(indent_bytes)
-        }
+    let gap = if let Value::String(s) = spaces {
+        // We can only use the first 10 characters.
+        WString::from(&s[..s.len().min(10)])
     } else {
         let indent_size = spaces
             .as_number(activation.context.gc_context)
             .unwrap_or(0.0)
-            .clamp(0.0, 10.0) as u16;
-        if indent_size == 0 {
-            None
-        } else {
-            Some(Cow::Owned(b" ".repeat(indent_size.into())))
-        }
+            .clamp(0.0, 10.0) as usize;
+        WString::from_utf8_owned(" ".repeat(indent_size))
     };
 
-    let mut serializer = AvmSerializer::new(replacer);
-    let json = serializer.serialize(activation, *val)?;
-    let result = match indent {
-        Some(indent) => {
-            let mut result = Vec::with_capacity(128);
-            let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent);
-            let mut serializer = serde_json::Serializer::with_formatter(&mut result, formatter);
-            json.serialize(&mut serializer)
-                .expect("JSON serialization cannot fail");
-            result
-        }
-        None => serde_json::to_vec(&json).expect("JSON serialization cannot fail"),
-    };
-    Ok(AvmString::new_utf8_bytes(activation.context.gc_context, &result).into())
+    let mut serializer = AvmSerializer::new(replacer, gap);
+    match serializer.serialize(activation, *val)? {
+        Some(json) => Ok(AvmString::new(activation.context.gc_context, json).into()),
+        None => Ok(Value::Undefined),
+    }
 }
@@ -2,33 +2,145 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
-use crate::avm2::method::Method;
-use crate::avm2::object::{namespace_allocator, Object};
+use crate::avm2::error::type_error;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::object::{namespace_allocator, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
-use crate::avm2_stub_constructor;
+use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
+/// Returns the URI of a value given to the `Namespace` constructor.
+fn uri_of<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Result<AvmString<'gc>, Error<'gc>> {
+    if let Value::Object(object) = value {
+        if let Some(namespace) = object.as_namespace() {
+            return Ok(namespace.as_uri());
+        }
+        if let Some(qname) = object.as_qname_object() {
+            if let Some(qname) = qname.qname() {
+                return Ok(qname.namespace().as_uri());
+            }
+        }
+    }
+
+    value.coerce_to_string(activation)
+}
+
 /// Implements `Namespace`'s instance initializer.
 pub fn instance_init<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_constructor!(activation, "Namespace");
-    Err("Namespace constructor is a stub.".into())
+    let this = match this.and_then(|this| this.as_namespace_object()) {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+
+    let (prefix, uri) = match args {
+        [] => (Some("".into()), "".into()),
+        [Value::Object(object)] if object.as_namespace_object().is_some() => {
+            let namespace = object.as_namespace_object().unwrap();
+            let uri = namespace.as_namespace().unwrap().as_uri();
+            (namespace.prefix(), uri)
+        }
+        [uri] => {
+            let uri = uri_of(activation, *uri)?;
+            let prefix = if uri.is_empty() {
+                Some("".into())
+            } else {
+                None
+            };
+            (prefix, uri)
+        }
+        [prefix, uri, ..] => {
+            let uri = uri_of(activation, *uri)?;
+            let prefix = match prefix {
+                Value::Undefined => None,
+                prefix => Some(prefix.coerce_to_string(activation)?),
+            };
+            if uri.is_empty() && !prefix.unwrap_or_default().is_empty() {
+                return Err(Error::AvmError(type_error(
+                    activation,
+                    &format!(
+                        "Error #1098: Illegal prefix {} for no namespace.",
+                        prefix.unwrap_or_default()
+                    ),
+                    1098,
+                )?));
+            }
+            (prefix, uri)
+        }
+    };
+
+    this.init_namespace(
+        activation.context.gc_context,
+        Namespace::Namespace(uri),
+        prefix,
+    );
+
+    Ok(Value::Undefined)
 }
 
+/// Implements `Namespace(...)`, which returns namespaces unchanged.
 fn class_call<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [Value::Object(object)] = args {
+        if object.as_namespace_object().is_some() {
+            return Ok(args[0]);
+        }
+    }
+
+    let namespace_class = activation.avm2().classes().namespace;
+    Ok(namespace_class.construct(activation, args)?.into())
+}
+
+/// Implements `Namespace.prefix`'s getter
+pub fn prefix<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_constructor!(activation, "Namespace");
-    Err("Namespace constructor is a stub.".into())
+    if let Some(this) = this.and_then(|t| t.as_namespace_object()) {
+        if let Some(prefix) = this.prefix() {
+            return Ok(prefix.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Namespace.uri`'s getter
+pub fn uri<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        if let Some(namespace) = this.as_namespace() {
+            return Ok(namespace.as_uri().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Namespace.AS3::toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    uri(activation, this, args)
 }
 
 /// Implements `Namespace`'s native instance initializer.
@@ -76,5 +188,16 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         mc,
     ));
 
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("prefix", Some(prefix), None), ("uri", Some(uri), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const AS3_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] =
+        &[("toString", to_string), ("valueOf", to_string)];
+    write.define_as3_builtin_instance_methods(mc, AS3_INSTANCE_METHODS);
+
     class
 }
@@ -12,10 +12,13 @@ include "flash/display/BitmapData.as"
 include "flash/display/DisplayObject.as"
 include "flash/display/InteractiveObject.as"
 include "flash/display/DisplayObjectContainer.as"
+include "flash/display/Sprite.as"
 
 include "flash/display/LoaderInfo.as"
 include "flash/events/EventDispatcher.as"
 include "flash/system/ApplicationDomain.as"
+include "flash/text/TextField.as"
+include "flash/text/TextFormat.as"
 include "Function.as"
 include "Number.as"
 include "String.as"
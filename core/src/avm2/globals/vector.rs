@@ -207,6 +207,8 @@ pub fn specialized_class_init<'gc>(
             ("push", push),
             ("shift", shift),
             ("unshift", unshift),
+            ("insertAt", insert_at),
+            ("removeAt", remove_at),
             ("reverse", reverse),
             ("slice", slice),
             ("sort", sort),
@@ -318,6 +320,7 @@ pub fn concat<'gc>(
         } else {
             return Err("Not a vector-structured object".into());
         };
+        new_vector_storage.set_is_fixed(false);
 
         let my_class = this
             .instance_of()
@@ -328,17 +331,7 @@ pub fn concat<'gc>(
             let arg_obj = arg
                 .as_object()
                 .ok_or("Cannot concat Vector with null or undefined")?;
-            let arg_class = arg_obj
-                .instance_of_class_definition()
-                .ok_or("TypeError: Tried to concat from a bare object")?;
-            if !arg.is_of_type(activation, my_class) {
-                return Err(format!(
-                    "TypeError: Cannot coerce argument of type {:?} to argument of type {:?}",
-                    arg_class.read().name(),
-                    my_class.inner_class_definition().read().name()
-                )
-                .into());
-            }
+            arg.coerce_to_type(activation, my_class)?;
 
             let old_vec = arg_obj.as_vector_storage();
             let old_vec: Vec<Value<'gc>> = if let Some(old_vec) = old_vec {
@@ -348,20 +341,6 @@ pub fn concat<'gc>(
             };
 
             for val in old_vec {
-                if let Ok(val_obj) = val.coerce_to_object(activation) {
-                    if !val.is_of_type(activation, val_class) {
-                        let other_val_class = val_obj
-                            .instance_of_class_definition()
-                            .ok_or("TypeError: Tried to concat a bare object into a Vector")?;
-                        return Err(format!(
-                            "TypeError: Cannot coerce Vector value of type {:?} to type {:?}",
-                            other_val_class.read().name(),
-                            val_class.inner_class_definition().read().name()
-                        )
-                        .into());
-                    }
-                }
-
                 let coerced_val = val.coerce_to_type(activation, val_class)?;
                 new_vector_storage.push(coerced_val, activation)?;
             }
@@ -994,8 +973,8 @@ pub fn splice<'gc>(
                 to_coerce.push(value.coerce_to_type(activation, value_type)?);
             }
 
-            let new_vs =
-                VectorStorage::from_values(vs.splice(start..end, to_coerce)?, false, value_type);
+            let removed = vs.splice(start..end, to_coerce, activation)?;
+            let new_vs = VectorStorage::from_values(removed, false, value_type, activation);
             let new_vector = VectorObject::from_vector(new_vs, activation)?;
 
             return Ok(new_vector.into());
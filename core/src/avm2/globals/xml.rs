@@ -1,3 +1,808 @@
 //! XML builtin and prototype
 
+use crate::avm2::activation::Activation;
+use crate::avm2::e4x::{self, E4XNamespace, E4XNode, XmlSettings};
+use crate::avm2::error::type_error;
+use crate::avm2::object::{
+    ArrayObject, NamespaceObject, Object, QNameObject, TObject, XmlListObject, XmlObject,
+};
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayStorage, Error, Multiname, Namespace, QName};
+
 pub use crate::avm2::object::xml_allocator;
+
+/// Returns the XML object `this` refers to.
+fn this_xml<'gc>(this: Option<Object<'gc>>) -> Result<XmlObject<'gc>, Error<'gc>> {
+    this.and_then(|this| this.as_xml_object())
+        .ok_or_else(|| "XML method called on a non-XML object".into())
+}
+
+/// Parses markup, or converts another value to the nodes it stands for, for use as the content
+/// of an element.
+///
+/// Values other than XML objects and lists are converted to strings, and parsed like in the
+/// `XML` constructor, so that text becomes a text node.
+pub fn nodes_from_content<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Result<Vec<E4XNode<'gc>>, Error<'gc>> {
+    if let Some(nodes) = e4x::xml_nodes(value) {
+        return Ok(nodes);
+    }
+
+    let markup = value.coerce_to_string(activation)?;
+    let settings = XmlSettings::from_xml_class(activation)?;
+    let nodes = E4XNode::parse(activation, &markup, &settings)?;
+    if nodes.is_empty() && !markup.is_empty() {
+        // Whitespace-only text is ignored by the parser, but should still be kept.
+        return Ok(vec![E4XNode::text(
+            activation.context.gc_context,
+            markup,
+            None,
+        )]);
+    }
+    Ok(nodes)
+}
+
+/// Converts an argument naming nodes, like the argument of `XML.child`, to a multiname.
+///
+/// `QName` objects give the namespace of the nodes, and `*` matches any name.
+pub fn name_from_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+    is_attribute: bool,
+) -> Result<Multiname<'gc>, Error<'gc>> {
+    let qname = value
+        .as_object()
+        .and_then(|object| object.as_qname_object())
+        .and_then(|qname| qname.qname().map(|qname| *qname));
+    let (namespace, local_name) = match qname {
+        Some(qname) => (qname.namespace(), qname.local_name()),
+        None => (Namespace::public(), value.coerce_to_string(activation)?),
+    };
+
+    let mut name = if &local_name == b"*" {
+        Multiname::any()
+    } else {
+        Multiname::new(namespace, local_name)
+    };
+    name.set_is_attribute(is_attribute);
+    Ok(name)
+}
+
+/// Converts a `Namespace` or `QName` object, or a URI, to the namespace of a node.
+pub fn e4x_namespace_from_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    value: Value<'gc>,
+) -> Result<E4XNamespace<'gc>, Error<'gc>> {
+    if let Some(object) = value.as_object() {
+        if let Some(namespace) = object.as_namespace_object() {
+            let uri = namespace.as_namespace().unwrap().as_uri();
+            return Ok(E4XNamespace::new(namespace.prefix(), uri));
+        }
+        if let Some(qname) = object
+            .as_qname_object()
+            .and_then(|qname| qname.qname().map(|q| *q))
+        {
+            return Ok(E4XNamespace::new(None, qname.namespace().as_uri()));
+        }
+    }
+
+    let uri = value.coerce_to_string(activation)?;
+    Ok(E4XNamespace::new(None, uri))
+}
+
+fn namespace_object<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    namespace: E4XNamespace<'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(NamespaceObject::from_prefix_and_uri(activation, namespace.prefix, namespace.uri)?.into())
+}
+
+fn namespace_array<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    namespaces: Vec<E4XNamespace<'gc>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut values = Vec::with_capacity(namespaces.len());
+    for namespace in namespaces {
+        values.push(Some(namespace_object(activation, namespace)?));
+    }
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_storage(values))?.into())
+}
+
+fn list_of<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: XmlObject<'gc>,
+    children: Vec<E4XNode<'gc>>,
+    name: Option<Multiname<'gc>>,
+) -> Value<'gc> {
+    XmlListObject::new(activation, children, Some(this.into()), name).into()
+}
+
+/// Implements `XML`'s constructor.
+pub fn init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let mc = activation.context.gc_context;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+
+    let nodes = match value {
+        Value::Undefined | Value::Null => Vec::new(),
+        value => match e4x::xml_nodes(value) {
+            Some(nodes) => nodes.into_iter().map(|node| node.deep_copy(mc)).collect(),
+            None => {
+                let markup = value.coerce_to_string(activation)?;
+                let settings = XmlSettings::from_xml_class(activation)?;
+                E4XNode::parse(activation, &markup, &settings)?
+            }
+        },
+    };
+
+    let node = match nodes.as_slice() {
+        [] => E4XNode::text(mc, "".into(), None),
+        [node] => *node,
+        _ => {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #1088: The markup in the document following the root element must be well-formed.",
+                1088,
+            )?))
+        }
+    };
+    node.set_parent(mc, None);
+    this.set_node(mc, node);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML(value)`, which returns XML objects unchanged.
+pub fn call_handler<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(Value::Object(object)) = args.get(0) {
+        if object.as_xml_object().is_some() {
+            return Ok(args[0]);
+        }
+    }
+
+    let xml_class = activation.avm2().classes().xml;
+    Ok(xml_class.construct(activation, args)?.into())
+}
+
+/// Implements `XML.addNamespace`
+pub fn add_namespace<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let namespace = e4x_namespace_from_value(activation, value)?;
+    this.node()
+        .add_namespace(activation.context.gc_context, namespace);
+    Ok(this.into())
+}
+
+/// Implements `XML.appendChild`
+pub fn append_child<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let node = this.node();
+    for child in nodes_from_content(activation, value)? {
+        node.append_child(activation.context.gc_context, child);
+    }
+    Ok(this.into())
+}
+
+/// Implements `XML.attribute`
+pub fn attribute<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let name = name_from_value(activation, value, true)?;
+    Ok(this.get_matching(&name, activation).into())
+}
+
+/// Implements `XML.attributes`
+pub fn attributes<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let mut name = Multiname::any();
+    name.set_is_attribute(true);
+    Ok(this.get_matching(&name, activation).into())
+}
+
+/// Implements `XML.child`
+pub fn child<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+
+    // A number selects the child at that position.
+    if let Value::Integer(_) | Value::Number(_) = value {
+        let index = value.coerce_to_number(activation)?;
+        let children = this.node().children();
+        let child = if index >= 0.0 && index.fract() == 0.0 {
+            children.get(index as usize).copied()
+        } else {
+            None
+        };
+        return Ok(list_of(activation, this, child.into_iter().collect(), None));
+    }
+
+    let name = name_from_value(activation, value, false)?;
+    Ok(this.get_matching(&name, activation).into())
+}
+
+/// Implements `XML.childIndex`
+pub fn child_index<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    if node.is_attribute() {
+        return Ok((-1).into());
+    }
+    Ok(node
+        .child_index()
+        .map(|index| index as i32)
+        .unwrap_or(-1)
+        .into())
+}
+
+/// Implements `XML.children`
+pub fn children<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    Ok(this.get_matching(&Multiname::any(), activation).into())
+}
+
+/// Implements `XML.comments`
+pub fn comments<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let comments = this
+        .node()
+        .children()
+        .into_iter()
+        .filter(|child| child.is_comment())
+        .collect();
+    Ok(list_of(activation, this, comments, None))
+}
+
+/// Implements `XML.contains`
+pub fn contains<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    Ok(match e4x::xml_nodes(value).as_deref() {
+        Some([other]) => node.equals(*other),
+        _ => false,
+    }
+    .into())
+}
+
+/// Implements `XML.copy`
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    let copy = node.deep_copy(activation.context.gc_context);
+    Ok(XmlObject::new(copy, activation).into())
+}
+
+/// Implements `XML.descendants`
+pub fn descendants<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or_else(|| "*".into());
+    let name = name_from_value(activation, value, false)?;
+    let name = e4x::node_name(activation.context.gc_context, &name);
+    let mut descendants = Vec::new();
+    this.node().descendants(&name, &mut descendants);
+    Ok(XmlListObject::new(activation, descendants, None, None).into())
+}
+
+/// Implements `XML.elements`
+pub fn elements<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or_else(|| "*".into());
+    let name = name_from_value(activation, value, false)?;
+    let elements = this
+        .node()
+        .children()
+        .into_iter()
+        .filter(|child| child.is_element() && child.matches_name(&name))
+        .collect();
+    Ok(list_of(activation, this, elements, Some(name)))
+}
+
+/// Implements `XML.hasComplexContent`
+pub fn has_complex_content<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_xml(this)?.node().has_complex_content().into())
+}
+
+/// Implements `XML.hasSimpleContent`
+pub fn has_simple_content<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_xml(this)?.node().has_simple_content().into())
+}
+
+/// Implements `XML.inScopeNamespaces`
+pub fn in_scope_namespaces<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let namespaces = this_xml(this)?.node().in_scope_namespaces();
+    namespace_array(activation, namespaces)
+}
+
+/// Inserts the nodes of `value` among the children of `this`, next to `reference`.
+fn insert_child_next_to<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    after: bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let node = this.node();
+    if !node.is_element() {
+        return Ok(Value::Undefined);
+    }
+
+    let reference = args.get(0).copied().unwrap_or(Value::Undefined);
+    let value = args.get(1).copied().unwrap_or(Value::Undefined);
+
+    let index = match reference {
+        // Without a reference child, nodes are inserted at the start or the end.
+        Value::Null | Value::Undefined => {
+            if after {
+                0
+            } else {
+                node.length()
+            }
+        }
+        reference => {
+            let reference = match e4x::xml_nodes(reference).as_deref() {
+                Some([reference]) => *reference,
+                _ => return Ok(Value::Undefined),
+            };
+            let index = node
+                .children()
+                .iter()
+                .position(|child| E4XNode::ptr_eq(*child, reference));
+            match index {
+                Some(index) if after => index + 1,
+                Some(index) => index,
+                None => return Ok(Value::Undefined),
+            }
+        }
+    };
+
+    let mc = activation.context.gc_context;
+    for (offset, child) in nodes_from_content(activation, value)?
+        .into_iter()
+        .enumerate()
+    {
+        node.insert_child(mc, index + offset, child);
+    }
+    Ok(this.into())
+}
+
+/// Implements `XML.insertChildAfter`
+pub fn insert_child_after<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    insert_child_next_to(activation, this, args, true)
+}
+
+/// Implements `XML.insertChildBefore`
+pub fn insert_child_before<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    insert_child_next_to(activation, this, args, false)
+}
+
+/// Implements `XML.localName`
+pub fn local_name<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_xml(this)?
+        .node()
+        .local_name()
+        .map(Value::from)
+        .unwrap_or(Value::Null))
+}
+
+/// Implements `XML.name`
+pub fn name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    match node.local_name() {
+        Some(local_name) => {
+            let namespace = Namespace::Namespace(node.namespace_uri());
+            Ok(QNameObject::from_qname(activation, QName::new(namespace, local_name))?.into())
+        }
+        None => Ok(Value::Null),
+    }
+}
+
+/// Implements `XML.namespace`
+pub fn namespace<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+
+    match args.get(0).copied().unwrap_or(Value::Null) {
+        Value::Null | Value::Undefined => {
+            if !node.is_element() && !node.is_attribute() {
+                return Ok(Value::Null);
+            }
+            let namespace = node
+                .namespace()
+                .unwrap_or_else(|| E4XNamespace::new(Some("".into()), "".into()));
+            // Find the prefix the namespace is declared with, if it has none of its own.
+            let namespace = if namespace.prefix.is_none() {
+                node.in_scope_namespaces()
+                    .into_iter()
+                    .find(|declared| declared.uri == namespace.uri)
+                    .unwrap_or(namespace)
+            } else {
+                namespace
+            };
+            namespace_object(activation, namespace)
+        }
+        prefix => {
+            let prefix = prefix.coerce_to_string(activation)?;
+            let namespace = node
+                .in_scope_namespaces()
+                .into_iter()
+                .find(|declared| declared.prefix == Some(prefix));
+            match namespace {
+                Some(namespace) => namespace_object(activation, namespace),
+                None => Ok(Value::Undefined),
+            }
+        }
+    }
+}
+
+/// Implements `XML.namespaceDeclarations`
+pub fn namespace_declarations<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let namespaces = this_xml(this)?.node().namespace_declarations();
+    namespace_array(activation, namespaces)
+}
+
+/// Implements `XML.nodeKind`
+pub fn node_kind<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_xml(this)?.node().node_kind().into())
+}
+
+/// Implements `XML.normalize`
+pub fn normalize<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    this.node().normalize(activation.context.gc_context);
+    Ok(this.into())
+}
+
+/// Implements `XML.parent`
+pub fn parent<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this_xml(this)?.node().parent() {
+        Some(parent) => Ok(XmlObject::new(parent, activation).into()),
+        None => Ok(Value::Undefined),
+    }
+}
+
+/// Implements `XML.prependChild`
+pub fn prepend_child<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let node = this.node();
+    for (index, child) in nodes_from_content(activation, value)?
+        .into_iter()
+        .enumerate()
+    {
+        node.insert_child(activation.context.gc_context, index, child);
+    }
+    Ok(this.into())
+}
+
+/// Implements `XML.processingInstructions`
+pub fn processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or_else(|| "*".into());
+    let name = name_from_value(activation, value, false)?;
+    let instructions = this
+        .node()
+        .children()
+        .into_iter()
+        .filter(|child| {
+            child.is_processing_instruction()
+                && name
+                    .local_name()
+                    .map_or(true, |local_name| child.local_name() == Some(local_name))
+        })
+        .collect();
+    Ok(list_of(activation, this, instructions, None))
+}
+
+/// Implements `XML.removeNamespace`
+pub fn remove_namespace<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let namespace = e4x_namespace_from_value(activation, value)?;
+    this.node()
+        .remove_namespace(activation.context.gc_context, namespace.uri);
+    Ok(this.into())
+}
+
+/// Implements `XML.replace`
+pub fn replace<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let node = this.node();
+    if !node.is_element() {
+        return Ok(this.into());
+    }
+
+    let property_name = args.get(0).copied().unwrap_or(Value::Undefined);
+    let value = args.get(1).copied().unwrap_or(Value::Undefined);
+    let replacements = nodes_from_content(activation, value)?;
+    let mc = activation.context.gc_context;
+
+    let index = match property_name {
+        Value::Integer(_) | Value::Number(_) => {
+            let index = property_name.coerce_to_number(activation)?;
+            if index < 0.0 || index.fract() != 0.0 {
+                return Ok(this.into());
+            }
+            index as usize
+        }
+        property_name => {
+            // Only the first matching child is replaced, and the others are removed.
+            let name = name_from_value(activation, property_name, false)?;
+            let name = e4x::node_name(mc, &name);
+            let matching = node.get_matching(&name);
+            let (first, rest) = match matching.split_first() {
+                Some(split) => split,
+                None => return Ok(this.into()),
+            };
+            for child in rest {
+                node.remove_child(mc, *child);
+            }
+            first.child_index().unwrap_or_default()
+        }
+    };
+
+    if index >= node.length() {
+        for child in replacements {
+            node.append_child(mc, child);
+        }
+    } else {
+        node.replace_child(mc, index, &replacements);
+    }
+    Ok(this.into())
+}
+
+/// Implements `XML.setChildren`
+pub fn set_children<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let children = nodes_from_content(activation, value)?;
+    let node = this.node();
+    let mc = activation.context.gc_context;
+    node.remove_children(mc);
+    for child in children {
+        node.append_child(mc, child);
+    }
+    Ok(this.into())
+}
+
+/// Implements `XML.setLocalName`
+pub fn set_local_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let local_name = match value.as_object().and_then(|o| o.as_qname_object()) {
+        Some(qname) => qname.qname().map(|qname| qname.local_name()),
+        None => Some(value.coerce_to_string(activation)?),
+    };
+
+    if let Some(local_name) = local_name {
+        if node.local_name().is_some() {
+            node.set_local_name(activation.context.gc_context, local_name);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.setName`
+pub fn set_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    if node.local_name().is_none() {
+        return Ok(Value::Undefined);
+    }
+
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let qname = value
+        .as_object()
+        .and_then(|o| o.as_qname_object())
+        .and_then(|qname| qname.qname().map(|qname| *qname));
+    let mc = activation.context.gc_context;
+    match qname {
+        Some(qname) => {
+            node.set_local_name(mc, qname.local_name());
+            let uri = qname.namespace().as_uri();
+            let prefix = node
+                .in_scope_namespaces()
+                .into_iter()
+                .find(|declared| declared.uri == uri)
+                .and_then(|declared| declared.prefix);
+            node.set_namespace(mc, Some(E4XNamespace::new(prefix, uri)));
+        }
+        None => {
+            let local_name = value.coerce_to_string(activation)?;
+            node.set_local_name(mc, local_name);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.setNamespace`
+pub fn set_namespace<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let node = this_xml(this)?.node();
+    if !node.is_element() && !node.is_attribute() {
+        return Ok(Value::Undefined);
+    }
+
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let namespace = e4x_namespace_from_value(activation, value)?;
+    let mc = activation.context.gc_context;
+    node.set_namespace(mc, Some(namespace));
+    if namespace.prefix.is_some() {
+        let declaring_node = if node.is_attribute() {
+            node.parent()
+        } else {
+            Some(node)
+        };
+        if let Some(declaring_node) = declaring_node {
+            declaring_node.add_namespace(mc, namespace);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.text`
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_xml(this)?;
+    let text = this
+        .node()
+        .children()
+        .into_iter()
+        .filter(|child| child.is_text())
+        .collect();
+    Ok(list_of(activation, this, text, None))
+}
+
+/// Implements `XML.toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_xml(this)?.xml_to_string(activation)?.into())
+}
+
+/// Implements `XML.toXMLString`
+pub fn to_xml_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_xml(this)?.xml_to_xml_string(activation)?.into())
+}
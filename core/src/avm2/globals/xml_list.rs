@@ -1,4 +1,355 @@
 //! XMLList builtin and prototype
 
-// XMLList currently uses the same instance allocator as XML
-pub use crate::avm2::object::xml_allocator as xml_list_allocator;
+use crate::avm2::activation::Activation;
+use crate::avm2::e4x::{self, E4XNode, XmlSettings};
+use crate::avm2::globals::xml::name_from_value;
+use crate::avm2::object::{Object, TObject, XmlListObject, XmlObject};
+use crate::avm2::value::Value;
+use crate::avm2::{Error, Multiname};
+use crate::string::AvmString;
+
+pub use crate::avm2::object::xml_list_allocator;
+
+/// Returns the XMLList object `this` refers to.
+fn this_list<'gc>(this: Option<Object<'gc>>) -> Result<XmlListObject<'gc>, Error<'gc>> {
+    this.and_then(|this| this.as_xml_list_object())
+        .ok_or_else(|| "XMLList method called on a non-XMLList object".into())
+}
+
+/// Collects the children of every item of `this` that `predicate` accepts, into a new list.
+fn filter_children<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: XmlListObject<'gc>,
+    predicate: impl Fn(E4XNode<'gc>) -> bool,
+) -> Value<'gc> {
+    let children = this
+        .children()
+        .into_iter()
+        .flat_map(|item| item.children())
+        .filter(|child| predicate(*child))
+        .collect();
+    XmlListObject::new(activation, children, Some(this.into()), None).into()
+}
+
+/// Implements `XMLList`'s constructor.
+pub fn init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let mc = activation.context.gc_context;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+
+    let children = match value {
+        Value::Undefined | Value::Null => Vec::new(),
+        value => match e4x::xml_nodes(value) {
+            Some(nodes) => nodes.into_iter().map(|node| node.deep_copy(mc)).collect(),
+            None => {
+                let markup = value.coerce_to_string(activation)?;
+                let settings = XmlSettings::from_xml_class(activation)?;
+                E4XNode::parse(activation, &markup, &settings)?
+            }
+        },
+    };
+    this.set_children(mc, children);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLList(value)`, which returns XMLList objects unchanged.
+pub fn call_handler<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(Value::Object(object)) = args.get(0) {
+        if object.as_xml_list_object().is_some() {
+            return Ok(args[0]);
+        }
+    }
+
+    let xml_list_class = activation.avm2().classes().xml_list;
+    Ok(xml_list_class.construct(activation, args)?.into())
+}
+
+/// Implements `XMLList.attribute`
+pub fn attribute<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let name = name_from_value(activation, value, true)?;
+    Ok(this.get_matching(&name, activation).into())
+}
+
+/// Implements `XMLList.attributes`
+pub fn attributes<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let mut name = Multiname::any();
+    name.set_is_attribute(true);
+    Ok(this.get_matching(&name, activation).into())
+}
+
+/// Implements `XMLList.child`
+pub fn child<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+
+    // A number selects the child at that position of every item.
+    if let Value::Integer(_) | Value::Number(_) = value {
+        let index = value.coerce_to_number(activation)?;
+        let children = if index >= 0.0 && index.fract() == 0.0 {
+            this.children()
+                .into_iter()
+                .filter_map(|item| item.children().get(index as usize).copied())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        return Ok(XmlListObject::new(activation, children, Some(this.into()), None).into());
+    }
+
+    let name = name_from_value(activation, value, false)?;
+    Ok(this.get_matching(&name, activation).into())
+}
+
+/// Implements `XMLList.children`
+pub fn children<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    Ok(this.get_matching(&Multiname::any(), activation).into())
+}
+
+/// Implements `XMLList.comments`
+pub fn comments<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    Ok(filter_children(activation, this, |child| {
+        child.is_comment()
+    }))
+}
+
+/// Implements `XMLList.contains`
+pub fn contains<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let value = args.get(0).copied().unwrap_or(Value::Undefined);
+    let other = match e4x::xml_nodes(value).as_deref() {
+        Some([other]) => *other,
+        _ => return Ok(false.into()),
+    };
+    Ok(this
+        .children()
+        .into_iter()
+        .any(|item| item.equals(other))
+        .into())
+}
+
+/// Implements `XMLList.copy`
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let mc = activation.context.gc_context;
+    let children = this
+        .children()
+        .into_iter()
+        .map(|item| item.deep_copy(mc))
+        .collect();
+    Ok(XmlListObject::new(activation, children, None, None).into())
+}
+
+/// Implements `XMLList.descendants`
+pub fn descendants<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let value = args.get(0).copied().unwrap_or_else(|| "*".into());
+    let name = name_from_value(activation, value, false)?;
+    let name = e4x::node_name(activation.context.gc_context, &name);
+    let mut descendants = Vec::new();
+    for item in this.children() {
+        item.descendants(&name, &mut descendants);
+    }
+    Ok(XmlListObject::new(activation, descendants, None, None).into())
+}
+
+/// Implements `XMLList.elements`
+pub fn elements<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let value = args.get(0).copied().unwrap_or_else(|| "*".into());
+    let name = name_from_value(activation, value, false)?;
+    Ok(filter_children(activation, this, |child| {
+        child.is_element() && child.matches_name(&name)
+    }))
+}
+
+/// Implements `XMLList.hasComplexContent`
+pub fn has_complex_content<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let children = this_list(this)?.children();
+    Ok(match children.as_slice() {
+        [item] => item.has_complex_content(),
+        children => children.iter().any(|item| item.is_element()),
+    }
+    .into())
+}
+
+/// Implements `XMLList.hasSimpleContent`
+pub fn has_simple_content<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let children = this_list(this)?.children();
+    Ok(match children.as_slice() {
+        [item] => item.has_simple_content(),
+        children => !children.iter().any(|item| item.is_element()),
+    }
+    .into())
+}
+
+/// Implements `XMLList.length`
+pub fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_list(this)?.length().into())
+}
+
+/// Implements `XMLList.normalize`
+pub fn normalize<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let mc = activation.context.gc_context;
+    let mut children: Vec<E4XNode<'gc>> = Vec::new();
+    for item in this.children() {
+        if item.is_element() {
+            item.normalize(mc);
+            children.push(item);
+        } else if item.is_text() {
+            // Adjacent text items are merged, and empty ones dropped.
+            let text = item.value().unwrap_or_default();
+            match children.last() {
+                Some(last) if last.is_text() => {
+                    let merged = last.value().unwrap_or_default();
+                    last.set_value(mc, AvmString::concat(mc, merged, text));
+                    if let Some(parent) = item.parent() {
+                        parent.remove_child(mc, item);
+                    }
+                }
+                _ if text.is_empty() => {}
+                _ => children.push(item),
+            }
+        } else {
+            children.push(item);
+        }
+    }
+    this.set_children(mc, children);
+    Ok(this.into())
+}
+
+/// Implements `XMLList.parent`
+///
+/// Returns the parent shared by every item, or `undefined` if the items have different parents.
+pub fn parent<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let children = this_list(this)?.children();
+    let parent = match children.first().and_then(|item| item.parent()) {
+        Some(parent) => parent,
+        None => return Ok(Value::Undefined),
+    };
+
+    let shared = children.iter().all(|item| {
+        item.parent()
+            .map_or(false, |other| E4XNode::ptr_eq(other, parent))
+    });
+    if shared {
+        Ok(XmlObject::new(parent, activation).into())
+    } else {
+        Ok(Value::Undefined)
+    }
+}
+
+/// Implements `XMLList.processingInstructions`
+pub fn processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    let value = args.get(0).copied().unwrap_or_else(|| "*".into());
+    let name = name_from_value(activation, value, false)?;
+    Ok(filter_children(activation, this, |child| {
+        child.is_processing_instruction()
+            && name
+                .local_name()
+                .map_or(true, |local_name| child.local_name() == Some(local_name))
+    }))
+}
+
+/// Implements `XMLList.text`
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_list(this)?;
+    Ok(filter_children(activation, this, |child| child.is_text()))
+}
+
+/// Implements `XMLList.toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_list(this)?.xml_to_string(activation)?.into())
+}
+
+/// Implements `XMLList.toXMLString`
+pub fn to_xml_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_list(this)?.xml_to_xml_string(activation)?.into())
+}
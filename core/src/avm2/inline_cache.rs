@@ -0,0 +1,157 @@
+//! Inline caches for property lookups
+//!
+//! Every `getproperty`, `setproperty`, `initproperty`, `callproperty` and `callpropvoid`
+//! instruction with a static name remembers what its name resolved to on the vtables of the
+//! objects it was last used with. When the instruction runs again on an object with one of these
+//! vtables, the trait is reused instead of resolving the multiname again.
+
+use crate::avm2::property::Property;
+use crate::avm2::vtable::VTable;
+use crate::avm2::Multiname;
+use fnv::FnvHashMap;
+use gc_arena::{Collect, CollectionContext, GcCell, MutationContext};
+
+/// The number of vtables an instruction may be cached for before it stops caching.
+const MAX_POLYMORPHISM: usize = 4;
+
+/// The inline caches of all instructions of a method, keyed by the offset of the instruction.
+#[derive(Collect, Clone, Copy)]
+#[collect(no_drop)]
+pub struct InlineCaches<'gc>(GcCell<'gc, InlineCachesData<'gc>>);
+
+#[derive(Default)]
+struct InlineCachesData<'gc> {
+    sites: FnvHashMap<usize, InlineCache<'gc>>,
+}
+
+unsafe impl<'gc> Collect for InlineCachesData<'gc> {
+    #[inline]
+    fn trace(&self, cc: CollectionContext) {
+        for cache in self.sites.values() {
+            cache.trace(cc);
+        }
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+enum InlineCache<'gc> {
+    /// The instruction was used on a few vtables, and the result of each lookup is cached.
+    Polymorphic(Vec<CacheEntry<'gc>>),
+
+    /// The instruction was used on too many vtables to be worth caching.
+    Megamorphic,
+}
+
+#[derive(Collect, Clone, Copy)]
+#[collect(no_drop)]
+struct CacheEntry<'gc> {
+    vtable: VTable<'gc>,
+
+    /// The generation of `vtable` when the lookup was made, as traits may be added to it later.
+    generation: u32,
+
+    /// The resolved trait, or `None` if the name isn't a trait of the vtable.
+    property: Option<Property>,
+}
+
+impl<'gc> InlineCaches<'gc> {
+    pub fn new(mc: MutationContext<'gc, '_>) -> Self {
+        Self(GcCell::allocate(mc, InlineCachesData::default()))
+    }
+
+    /// Resolves `multiname` against `vtable` for the instruction at offset `site`.
+    ///
+    /// The multiname must be the static name used by that instruction, since the cache only
+    /// remembers vtables.
+    pub fn get_trait(
+        self,
+        mc: MutationContext<'gc, '_>,
+        site: usize,
+        vtable: VTable<'gc>,
+        multiname: &Multiname<'gc>,
+    ) -> Option<Property> {
+        let generation = vtable.generation();
+        match self.0.read().sites.get(&site) {
+            Some(InlineCache::Polymorphic(entries)) => {
+                if let Some(entry) = entries.iter().find(|entry| {
+                    VTable::ptr_eq(entry.vtable, vtable) && entry.generation == generation
+                }) {
+                    return entry.property;
+                }
+            }
+            Some(InlineCache::Megamorphic) => return vtable.get_trait(multiname),
+            None => {}
+        }
+
+        let property = vtable.get_trait(multiname);
+
+        let mut write = self.0.write(mc);
+        let cache = write
+            .sites
+            .entry(site)
+            .or_insert_with(|| InlineCache::Polymorphic(Vec::new()));
+        if let InlineCache::Polymorphic(entries) = cache {
+            // Forget the lookups made before the vtable got new traits.
+            entries.retain(|entry| !VTable::ptr_eq(entry.vtable, vtable));
+            if entries.len() < MAX_POLYMORPHISM {
+                entries.push(CacheEntry {
+                    vtable,
+                    generation,
+                    property,
+                });
+            } else {
+                *cache = InlineCache::Megamorphic;
+            }
+        }
+
+        property
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::{Namespace, QName};
+    use gc_arena::rootless_arena;
+
+    #[test]
+    fn polymorphic_lookups() {
+        rootless_arena(|mc| {
+            let caches = InlineCaches::new(mc);
+            let name = Multiname::public("e");
+            let catch_vtable = VTable::newcatch(mc, &QName::new(Namespace::public(), "e"));
+            let empty_vtables: Vec<_> = (0..MAX_POLYMORPHISM).map(|_| VTable::empty(mc)).collect();
+
+            for _ in 0..2 {
+                assert!(matches!(
+                    caches.get_trait(mc, 0, catch_vtable, &name),
+                    Some(Property::Slot { slot_id: 1 })
+                ));
+            }
+
+            // Going past `MAX_POLYMORPHISM` vtables stops caching, but lookups still work.
+            for vtable in &empty_vtables {
+                assert!(caches.get_trait(mc, 0, *vtable, &name).is_none());
+            }
+            assert!(matches!(
+                caches.0.read().sites.get(&0),
+                Some(InlineCache::Megamorphic)
+            ));
+            assert!(matches!(
+                caches.get_trait(mc, 0, catch_vtable, &name),
+                Some(Property::Slot { slot_id: 1 })
+            ));
+
+            // Other instructions have their own cache.
+            assert!(matches!(
+                caches.get_trait(mc, 1, catch_vtable, &name),
+                Some(Property::Slot { slot_id: 1 })
+            ));
+            assert!(matches!(
+                caches.0.read().sites.get(&1),
+                Some(InlineCache::Polymorphic(entries)) if entries.len() == 1
+            ));
+        })
+    }
+}
@@ -0,0 +1,240 @@
+//! Baseline JIT compiler for hot loops
+//!
+//! The interpreter counts how many times each loop of a method jumps back to its header. Once a
+//! loop is hot, the instructions reachable from its header are compiled to machine code, which
+//! the interpreter runs whenever it jumps back to that header from then on.
+//!
+//! Compiled code only works on numbers held in local registers and on the stack. When it reaches
+//! anything else, it deoptimizes: the registers and stack are written back, and the interpreter
+//! carries on from that instruction.
+
+mod codegen;
+mod region;
+
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::Value;
+use codegen::{Compiler, JitFrame, LoopFn, EXIT_RETURN_VALUE, EXIT_RETURN_VOID};
+use fnv::FnvHashMap;
+use smallvec::SmallVec;
+use std::cell::RefCell;
+
+/// The number of times a loop jumps back to its header before it is compiled.
+const HOT_LOOP_THRESHOLD: u32 = 1000;
+
+/// The number of runs of a compiled loop after which it is dropped if most of them left the
+/// compiled code before getting back to the header.
+const MIN_RUNS_TO_JUDGE: u32 = 32;
+
+/// Compiles hot loops, and runs them.
+pub struct Jit {
+    compiler: Compiler,
+}
+
+/// How a compiled loop was left.
+pub enum LoopExit<'gc> {
+    /// The interpreter continues at `offset`, after pushing `stack`.
+    Resume {
+        offset: usize,
+        stack: SmallVec<[Value<'gc>; 4]>,
+    },
+
+    /// The method returned.
+    Return(Value<'gc>),
+}
+
+/// The loops of a method that have been jumped back to.
+#[derive(Clone, Default)]
+pub struct MethodJit {
+    loops: RefCell<FnvHashMap<usize, LoopState>>,
+}
+
+#[derive(Clone)]
+enum LoopState {
+    /// The loop is interpreted, and has jumped back this many times.
+    Warming(u32),
+
+    Compiled(CompiledLoop),
+
+    /// The loop couldn't be compiled, or wasn't worth it.
+    Interpreted,
+}
+
+#[derive(Clone)]
+struct CompiledLoop {
+    function: LoopFn,
+
+    /// The registers that must hold numbers to run the compiled code.
+    numeric: Box<[u32]>,
+
+    max_stack: usize,
+
+    /// The number of times the compiled code was about to run.
+    runs: u32,
+
+    /// The number of those runs that didn't get back to the header, including the runs that
+    /// didn't start because a register held something other than a number.
+    fruitless_runs: u32,
+}
+
+fn is_number(value: &Value<'_>) -> bool {
+    matches!(value, Value::Number(_) | Value::Integer(_))
+}
+
+/// The value of `n` as an `i32`, if it is exactly one.
+fn to_integer(n: f64) -> Option<i32> {
+    let i = n as i32;
+    // Negative zero isn't an integer.
+    if f64::from(i) == n && (i != 0 || n.is_sign_positive()) {
+        Some(i)
+    } else {
+        None
+    }
+}
+
+impl Jit {
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            compiler: Compiler::new()?,
+        })
+    }
+
+    /// Notes that `method` jumped back to the loop header at `offset`, and runs the loop with
+    /// compiled code if it's hot.
+    ///
+    /// `registers` are the local registers of the method, and the stack must be empty. Returns
+    /// `None` if the interpreter should run the loop.
+    pub fn run_loop<'gc>(
+        &mut self,
+        method: &BytecodeMethod<'gc>,
+        offset: usize,
+        registers: &mut [Value<'gc>],
+    ) -> Option<LoopExit<'gc>> {
+        let mut loops = method.jit.loops.borrow_mut();
+        let state = loops.entry(offset).or_insert(LoopState::Warming(0));
+        if let LoopState::Warming(count) = state {
+            *count += 1;
+            if *count < HOT_LOOP_THRESHOLD {
+                return None;
+            }
+
+            *state = match self.compile(method, offset, registers) {
+                Some(compiled) => LoopState::Compiled(compiled),
+                None => LoopState::Interpreted,
+            };
+        }
+
+        let LoopState::Compiled(compiled) = state else {
+            return None;
+        };
+
+        compiled.runs += 1;
+        let exit = Self::run_compiled(compiled, registers);
+        if !matches!(exit, Some((_, true))) {
+            compiled.fruitless_runs += 1;
+        }
+
+        if compiled.runs >= MIN_RUNS_TO_JUDGE && compiled.fruitless_runs * 2 > compiled.runs {
+            tracing::debug!(
+                "Interpreting loop at {} of {} again, as its compiled code keeps leaving early",
+                offset,
+                method.method_name()
+            );
+            *state = LoopState::Interpreted;
+        }
+
+        exit.map(|(exit, _)| exit)
+    }
+
+    fn compile(
+        &mut self,
+        method: &BytecodeMethod<'_>,
+        offset: usize,
+        registers: &[Value<'_>],
+    ) -> Option<CompiledLoop> {
+        let body = method.body()?;
+        let numeric: Vec<bool> = registers.iter().map(is_number).collect();
+        let region = region::select(&body.code, &method.abc.constant_pool, offset, &numeric)?;
+        match self.compiler.compile(&region) {
+            Ok(function) => Some(CompiledLoop {
+                function,
+                numeric: region.numeric.into(),
+                max_stack: region.max_stack,
+                runs: 0,
+                fruitless_runs: 0,
+            }),
+            Err(e) => {
+                tracing::warn!(
+                    "Unable to compile loop at {} of {}: {}",
+                    offset,
+                    method.method_name(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Runs a compiled loop, if the registers hold the numbers it needs.
+    ///
+    /// Also returns whether the compiled code jumped back to the loop header.
+    fn run_compiled<'gc>(
+        compiled: &CompiledLoop,
+        registers: &mut [Value<'gc>],
+    ) -> Option<(LoopExit<'gc>, bool)> {
+        if !compiled
+            .numeric
+            .iter()
+            .all(|register| is_number(&registers[*register as usize]))
+        {
+            return None;
+        }
+
+        let mut numbers: SmallVec<[f64; 16]> = registers
+            .iter()
+            .map(|value| match value {
+                Value::Number(n) => *n,
+                Value::Integer(i) => f64::from(*i),
+                _ => f64::NAN,
+            })
+            .collect();
+        let mut assigned: SmallVec<[u8; 16]> = smallvec![0; registers.len()];
+        let mut stack: SmallVec<[f64; 8]> = smallvec![0.0; compiled.max_stack];
+        let mut frame = JitFrame {
+            registers: numbers.as_mut_ptr(),
+            assigned: assigned.as_mut_ptr(),
+            stack: stack.as_mut_ptr(),
+            stack_height: 0,
+            resume_offset: 0,
+            return_value: 0.0,
+            back_edges: 0,
+        };
+
+        // SAFETY: The frame has room for every register of the method, and for the deepest stack
+        // of the loop.
+        let code = unsafe { (compiled.function)(&mut frame) };
+
+        for ((register, number), assigned) in registers.iter_mut().zip(numbers).zip(assigned) {
+            if assigned != 0 {
+                *register = match (&*register, to_integer(number)) {
+                    // An `int` register stays one as long as its value does.
+                    (Value::Integer(_), Some(i)) => Value::Integer(i),
+                    _ => Value::Number(number),
+                };
+            }
+        }
+
+        let exit = match code {
+            EXIT_RETURN_VALUE => LoopExit::Return(Value::Number(frame.return_value)),
+            EXIT_RETURN_VOID => LoopExit::Return(Value::Undefined),
+            _ => LoopExit::Resume {
+                offset: frame.resume_offset as usize,
+                stack: stack[..frame.stack_height as usize]
+                    .iter()
+                    .map(|n| Value::Number(*n))
+                    .collect(),
+            },
+        };
+
+        Some((exit, frame.back_edges > 0))
+    }
+}
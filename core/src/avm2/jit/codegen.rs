@@ -0,0 +1,757 @@
+//! Machine code generation for regions, with Cranelift
+
+use super::region::{BinaryOp, Condition, JitOp, Region, UnaryOp};
+use crate::ecma_conversions::f64_to_wrapping_i32;
+use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
+use cranelift_codegen::ir::{types, AbiParam, Block, FuncRef, InstBuilder, MemFlags, Value};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, FuncId, Linkage, Module};
+use std::mem::{offset_of, ManuallyDrop};
+
+/// The compiled code left the loop, and the interpreter continues at `JitFrame::resume_offset`.
+pub const EXIT_RESUME: u32 = 0;
+
+/// The method returned `JitFrame::return_value`.
+pub const EXIT_RETURN_VALUE: u32 = 1;
+
+/// The method returned without a value.
+pub const EXIT_RETURN_VOID: u32 = 2;
+
+/// How many times compiled code may jump back before it returns to the interpreter, which then
+/// checks whether the script has been running for too long.
+pub const BACK_EDGE_BUDGET: i64 = 1 << 16;
+
+/// The state shared between a compiled loop and the code that runs it.
+#[repr(C)]
+pub struct JitFrame {
+    /// All local registers of the method, as numbers.
+    ///
+    /// Only the registers the loop needs to hold numbers are meaningful on entry.
+    pub registers: *mut f64,
+
+    /// For every local register, whether the compiled code wrote to it.
+    pub assigned: *mut u8,
+
+    /// The stack left by the compiled code when it exits.
+    pub stack: *mut f64,
+
+    /// The number of values in `stack`.
+    pub stack_height: u32,
+
+    /// The offset of the instruction the interpreter continues at.
+    pub resume_offset: u32,
+
+    /// The value returned by the method.
+    pub return_value: f64,
+
+    /// The number of times the compiled code jumped back.
+    pub back_edges: i64,
+}
+
+/// A compiled loop.
+pub type LoopFn = unsafe extern "C" fn(*mut JitFrame) -> u32;
+
+extern "C" fn runtime_to_int32(value: f64) -> i32 {
+    f64_to_wrapping_i32(value)
+}
+
+extern "C" fn runtime_modulo(dividend: f64, divisor: f64) -> f64 {
+    dividend % divisor
+}
+
+/// Compiles regions to machine code for the host.
+pub struct Compiler {
+    /// The module holding the code of every loop compiled so far.
+    ///
+    /// The code is freed with the compiler, which outlives all methods that run it.
+    module: ManuallyDrop<JITModule>,
+
+    builder_context: FunctionBuilderContext,
+
+    to_int32: FuncId,
+
+    modulo: FuncId,
+}
+
+impl Compiler {
+    /// Sets up a compiler for the host, failing if Cranelift can't generate code for it.
+    pub fn new() -> Result<Self, String> {
+        let mut flags = settings::builder();
+        flags.set("opt_level", "speed").map_err(|e| e.to_string())?;
+        flags
+            .set("use_colocated_libcalls", "false")
+            .map_err(|e| e.to_string())?;
+        flags.set("is_pic", "false").map_err(|e| e.to_string())?;
+        let isa = cranelift_native::builder()?
+            .finish(settings::Flags::new(flags))
+            .map_err(|e| e.to_string())?;
+
+        let mut builder = JITBuilder::with_isa(isa, default_libcall_names());
+        builder.symbol("ruffle_jit_to_int32", runtime_to_int32 as *const u8);
+        builder.symbol("ruffle_jit_modulo", runtime_modulo as *const u8);
+        let mut module = JITModule::new(builder);
+
+        let mut signature = module.make_signature();
+        signature.params.push(AbiParam::new(types::F64));
+        signature.returns.push(AbiParam::new(types::I32));
+        let to_int32 = module
+            .declare_function("ruffle_jit_to_int32", Linkage::Import, &signature)
+            .map_err(|e| e.to_string())?;
+
+        let mut signature = module.make_signature();
+        signature.params.push(AbiParam::new(types::F64));
+        signature.params.push(AbiParam::new(types::F64));
+        signature.returns.push(AbiParam::new(types::F64));
+        let modulo = module
+            .declare_function("ruffle_jit_modulo", Linkage::Import, &signature)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            module: ManuallyDrop::new(module),
+            builder_context: FunctionBuilderContext::new(),
+            to_int32,
+            modulo,
+        })
+    }
+
+    /// Generates the code of a region.
+    pub fn compile(&mut self, region: &Region) -> Result<LoopFn, String> {
+        let pointer = self.module.target_config().pointer_type();
+        let mut context = self.module.make_context();
+        context.func.signature.params.push(AbiParam::new(pointer));
+        context
+            .func
+            .signature
+            .returns
+            .push(AbiParam::new(types::I32));
+
+        let mut builder = FunctionBuilder::new(&mut context.func, &mut self.builder_context);
+        let to_int32 = self
+            .module
+            .declare_func_in_func(self.to_int32, builder.func);
+        let modulo = self.module.declare_func_in_func(self.modulo, builder.func);
+        let blocks = region
+            .instructions
+            .iter()
+            .map(|_| builder.create_block())
+            .collect();
+
+        let entry = builder.create_block();
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+        let frame = builder.block_params(entry)[0];
+        let flags = MemFlags::trusted();
+        let registers = builder.ins().load(
+            pointer,
+            flags,
+            frame,
+            offset_of!(JitFrame, registers) as i32,
+        );
+        let assigned =
+            builder
+                .ins()
+                .load(pointer, flags, frame, offset_of!(JitFrame, assigned) as i32);
+        let stack = builder
+            .ins()
+            .load(pointer, flags, frame, offset_of!(JitFrame, stack) as i32);
+
+        let mut emitter = Emitter {
+            builder,
+            region,
+            blocks,
+            frame,
+            registers,
+            assigned,
+            stack,
+            register_vars: vec![None; region.num_registers],
+            assigned_vars: vec![None; region.num_registers],
+            stack_vars: Vec::new(),
+            back_edges: Variable::from_u32(0),
+            to_int32,
+            modulo,
+        };
+        emitter.emit_entry();
+        for index in 0..region.instructions.len() {
+            emitter.emit_instruction(index);
+        }
+
+        emitter.builder.seal_all_blocks();
+        emitter.builder.finalize();
+
+        let id = self
+            .module
+            .declare_anonymous_function(&context.func.signature)
+            .map_err(|e| e.to_string())?;
+        self.module
+            .define_function(id, &mut context)
+            .map_err(|e| e.to_string())?;
+        self.module.clear_context(&mut context);
+        self.module
+            .finalize_definitions()
+            .map_err(|e| e.to_string())?;
+
+        let code = self.module.get_finalized_function(id);
+        // SAFETY: The function was generated with the signature of `LoopFn`.
+        Ok(unsafe { std::mem::transmute::<*const u8, LoopFn>(code) })
+    }
+}
+
+impl Drop for Compiler {
+    fn drop(&mut self) {
+        // SAFETY: The compiler is only dropped with the interpreter, after which none of the
+        // compiled loops run again.
+        unsafe { ManuallyDrop::take(&mut self.module).free_memory() }
+    }
+}
+
+/// Translates the instructions of a region into a Cranelift function.
+struct Emitter<'a, 'b> {
+    builder: FunctionBuilder<'b>,
+    region: &'a Region,
+
+    /// The block starting each instruction of the region.
+    blocks: Vec<Block>,
+
+    frame: Value,
+    registers: Value,
+    assigned: Value,
+    stack: Value,
+
+    /// The variable holding each local register the region uses.
+    register_vars: Vec<Option<Variable>>,
+
+    /// The variable telling whether the compiled code wrote to each local register.
+    assigned_vars: Vec<Option<Variable>>,
+
+    /// The variable holding each stack slot.
+    stack_vars: Vec<Variable>,
+
+    /// The variable counting how many times the code jumped back.
+    back_edges: Variable,
+
+    to_int32: FuncRef,
+    modulo: FuncRef,
+}
+
+impl<'a, 'b> Emitter<'a, 'b> {
+    /// Loads the local registers and jumps to the loop header.
+    fn emit_entry(&mut self) {
+        let mut next_var = 0;
+        let mut new_var = |builder: &mut FunctionBuilder, ty| {
+            let var = Variable::from_u32(next_var);
+            next_var += 1;
+            builder.declare_var(var, ty);
+            var
+        };
+
+        self.back_edges = new_var(&mut self.builder, types::I64);
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        self.builder.def_var(self.back_edges, zero);
+
+        for &register in &self.region.registers {
+            let var = new_var(&mut self.builder, types::F64);
+            let value = self.builder.ins().load(
+                types::F64,
+                MemFlags::trusted(),
+                self.registers,
+                register as i32 * 8,
+            );
+            self.builder.def_var(var, value);
+            self.register_vars[register as usize] = Some(var);
+        }
+
+        for &register in &self.region.assigned {
+            let var = new_var(&mut self.builder, types::I8);
+            let zero = self.builder.ins().iconst(types::I8, 0);
+            self.builder.def_var(var, zero);
+            self.assigned_vars[register as usize] = Some(var);
+        }
+
+        for _ in 0..self.region.max_stack {
+            let var = new_var(&mut self.builder, types::F64);
+            let zero = self.builder.ins().f64const(0.0);
+            self.builder.def_var(var, zero);
+            self.stack_vars.push(var);
+        }
+
+        self.builder.ins().jump(self.blocks[self.region.entry], &[]);
+    }
+
+    fn emit_instruction(&mut self, index: usize) {
+        let instruction = &self.region.instructions[index];
+        let (offset, next, op) = (instruction.offset, instruction.next, instruction.op);
+        let height = instruction.stack_height;
+        self.builder.switch_to_block(self.blocks[index]);
+
+        let height = match op {
+            JitOp::Push(value) => {
+                let value = self.builder.ins().f64const(value);
+                self.push(height, value)
+            }
+            JitOp::Pop => height - 1,
+            JitOp::Dup => {
+                let value = self.peek(height, 0);
+                self.push(height, value)
+            }
+            JitOp::Swap => {
+                let top = self.peek(height, 0);
+                let below = self.peek(height, 1);
+                self.builder.def_var(self.stack_vars[height - 2], top);
+                self.builder.def_var(self.stack_vars[height - 1], below);
+                height
+            }
+            JitOp::GetLocal(register) => {
+                let value = self.builder.use_var(self.register_var(register));
+                self.push(height, value)
+            }
+            JitOp::SetLocal(register) => {
+                let value = self.peek(height, 0);
+                self.set_register(register, value);
+                height - 1
+            }
+            JitOp::IncLocal(register) | JitOp::DecLocal(register) => {
+                let value = self.builder.use_var(self.register_var(register));
+                let delta = if matches!(op, JitOp::IncLocal(_)) {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let delta = self.builder.ins().f64const(delta);
+                let value = self.builder.ins().fadd(value, delta);
+                self.set_register(register, value);
+                height
+            }
+            JitOp::IncLocalI(register) | JitOp::DecLocalI(register) => {
+                let value = self.builder.use_var(self.register_var(register));
+                let value = self.wrap_to_int(value);
+                let delta = if matches!(op, JitOp::IncLocalI(_)) {
+                    1
+                } else {
+                    -1
+                };
+                let value = self.builder.ins().iadd_imm(value, delta);
+                let value = self.builder.ins().fcvt_from_sint(types::F64, value);
+                self.set_register(register, value);
+                height
+            }
+            JitOp::Unary(op) => {
+                let value = self.peek(height, 0);
+                let value = self.unary(op, value);
+                self.builder.def_var(self.stack_vars[height - 1], value);
+                height
+            }
+            JitOp::Binary(op) => {
+                let left = self.peek(height, 1);
+                let right = self.peek(height, 0);
+                let value = self.binary(op, left, right);
+                self.builder.def_var(self.stack_vars[height - 2], value);
+                height - 1
+            }
+            JitOp::Jump(target) => {
+                self.goto(offset, target, height);
+                return;
+            }
+            JitOp::Branch(condition, target) => {
+                let (condition, height) = self.condition(condition, height);
+                let taken = self.builder.create_block();
+                let not_taken = self.builder.create_block();
+                self.builder
+                    .ins()
+                    .brif(condition, taken, &[], not_taken, &[]);
+                self.builder.switch_to_block(taken);
+                self.goto(offset, target, height);
+                self.builder.switch_to_block(not_taken);
+                self.goto(offset, next, height);
+                return;
+            }
+            JitOp::ReturnValue => {
+                let value = self.peek(height, 0);
+                self.builder.ins().store(
+                    MemFlags::trusted(),
+                    value,
+                    self.frame,
+                    offset_of!(JitFrame, return_value) as i32,
+                );
+                self.leave(EXIT_RETURN_VALUE);
+                return;
+            }
+            JitOp::ReturnVoid => {
+                self.leave(EXIT_RETURN_VOID);
+                return;
+            }
+            JitOp::Nop => height,
+            JitOp::Exit => {
+                self.exit(offset, height);
+                return;
+            }
+        };
+
+        self.goto(offset, next, height);
+    }
+
+    fn register_var(&self, register: u32) -> Variable {
+        self.register_vars[register as usize].expect("register used by the region")
+    }
+
+    fn set_register(&mut self, register: u32, value: Value) {
+        self.builder.def_var(self.register_var(register), value);
+        let assigned = self.assigned_vars[register as usize].expect("register written by region");
+        let one = self.builder.ins().iconst(types::I8, 1);
+        self.builder.def_var(assigned, one);
+    }
+
+    /// Returns the value `depth` slots below the top of a stack of `height` values.
+    fn peek(&mut self, height: usize, depth: usize) -> Value {
+        self.builder.use_var(self.stack_vars[height - 1 - depth])
+    }
+
+    /// Pushes a value on a stack of `height` values, and returns the new height.
+    fn push(&mut self, height: usize, value: Value) -> usize {
+        self.builder.def_var(self.stack_vars[height], value);
+        height + 1
+    }
+
+    /// Converts a number to an `int` with ECMAScript wrapping.
+    fn wrap_to_int(&mut self, value: Value) -> Value {
+        // Numbers well within the range of an `i64` wrap by truncating them, the rest are
+        // left to the runtime.
+        let fast = self.builder.create_block();
+        let slow = self.builder.create_block();
+        let done = self.builder.create_block();
+        self.builder.append_block_param(done, types::I32);
+
+        let magnitude = self.builder.ins().fabs(value);
+        let limit = self.builder.ins().f64const((1u64 << 63) as f64);
+        let in_range = self.builder.ins().fcmp(FloatCC::LessThan, magnitude, limit);
+        self.builder.ins().brif(in_range, fast, &[], slow, &[]);
+
+        self.builder.switch_to_block(fast);
+        let wide = self.builder.ins().fcvt_to_sint_sat(types::I64, value);
+        let narrow = self.builder.ins().ireduce(types::I32, wide);
+        self.builder.ins().jump(done, &[narrow]);
+
+        self.builder.switch_to_block(slow);
+        let call = self.builder.ins().call(self.to_int32, &[value]);
+        let narrow = self.builder.inst_results(call)[0];
+        self.builder.ins().jump(done, &[narrow]);
+
+        self.builder.switch_to_block(done);
+        self.builder.block_params(done)[0]
+    }
+
+    fn unary(&mut self, op: UnaryOp, value: Value) -> Value {
+        match op {
+            UnaryOp::Negate => self.builder.ins().fneg(value),
+            UnaryOp::Increment | UnaryOp::Decrement => {
+                let delta = if op == UnaryOp::Increment { 1.0 } else { -1.0 };
+                let delta = self.builder.ins().f64const(delta);
+                self.builder.ins().fadd(value, delta)
+            }
+            UnaryOp::ToUint => {
+                let value = self.wrap_to_int(value);
+                self.builder.ins().fcvt_from_uint(types::F64, value)
+            }
+            _ => {
+                let value = self.wrap_to_int(value);
+                let value = match op {
+                    UnaryOp::NegateI => self.builder.ins().ineg(value),
+                    UnaryOp::IncrementI => self.builder.ins().iadd_imm(value, 1),
+                    UnaryOp::DecrementI => self.builder.ins().iadd_imm(value, -1),
+                    UnaryOp::BitNot => self.builder.ins().bnot(value),
+                    _ => value,
+                };
+                self.builder.ins().fcvt_from_sint(types::F64, value)
+            }
+        }
+    }
+
+    fn binary(&mut self, op: BinaryOp, left: Value, right: Value) -> Value {
+        match op {
+            BinaryOp::Add => self.builder.ins().fadd(left, right),
+            BinaryOp::Subtract => self.builder.ins().fsub(left, right),
+            BinaryOp::Multiply => self.builder.ins().fmul(left, right),
+            BinaryOp::Divide => self.builder.ins().fdiv(left, right),
+            BinaryOp::Modulo => {
+                let call = self.builder.ins().call(self.modulo, &[left, right]);
+                self.builder.inst_results(call)[0]
+            }
+            _ => {
+                let left = self.wrap_to_int(left);
+                let right = self.wrap_to_int(right);
+                // Shifts only use the low five bits of their amount, as Cranelift does.
+                let value = match op {
+                    BinaryOp::AddI => self.builder.ins().iadd(left, right),
+                    BinaryOp::SubtractI => self.builder.ins().isub(left, right),
+                    BinaryOp::MultiplyI => self.builder.ins().imul(left, right),
+                    BinaryOp::BitAnd => self.builder.ins().band(left, right),
+                    BinaryOp::BitOr => self.builder.ins().bor(left, right),
+                    BinaryOp::BitXor => self.builder.ins().bxor(left, right),
+                    BinaryOp::LShift => self.builder.ins().ishl(left, right),
+                    BinaryOp::RShift => self.builder.ins().sshr(left, right),
+                    _ => {
+                        let value = self.builder.ins().ushr(left, right);
+                        return self.builder.ins().fcvt_from_uint(types::F64, value);
+                    }
+                };
+                self.builder.ins().fcvt_from_sint(types::F64, value)
+            }
+        }
+    }
+
+    /// Evaluates the condition of a branch, and returns it with the stack height after it.
+    fn condition(&mut self, condition: Condition, height: usize) -> (Value, usize) {
+        let (left, right, height) = match condition {
+            Condition::True | Condition::False => {
+                let value = self.peek(height, 0);
+                let zero = self.builder.ins().f64const(0.0);
+                (value, zero, height - 1)
+            }
+            _ => {
+                let left = self.peek(height, 1);
+                let right = self.peek(height, 0);
+                (left, right, height - 2)
+            }
+        };
+
+        // NaN compares unordered, which makes the negated comparisons true.
+        let cc = match condition {
+            Condition::True => FloatCC::OrderedNotEqual,
+            Condition::False => FloatCC::UnorderedOrEqual,
+            Condition::Equal => FloatCC::Equal,
+            Condition::NotEqual => FloatCC::NotEqual,
+            Condition::LessThan => FloatCC::LessThan,
+            Condition::LessEqual => FloatCC::LessThanOrEqual,
+            Condition::GreaterThan => FloatCC::GreaterThan,
+            Condition::GreaterEqual => FloatCC::GreaterThanOrEqual,
+            Condition::NotLessThan => FloatCC::UnorderedOrGreaterThanOrEqual,
+            Condition::NotLessEqual => FloatCC::UnorderedOrGreaterThan,
+            Condition::NotGreaterThan => FloatCC::UnorderedOrLessThanOrEqual,
+            Condition::NotGreaterEqual => FloatCC::UnorderedOrLessThan,
+        };
+
+        (self.builder.ins().fcmp(cc, left, right), height)
+    }
+
+    /// Continues at `target` from the instruction at `offset`, leaving the compiled code if the
+    /// target is outside the region or the back-edge budget is spent.
+    fn goto(&mut self, offset: usize, target: usize, height: usize) {
+        let Some(index) = self.region.instruction_at(target) else {
+            self.exit(target, height);
+            return;
+        };
+
+        if target > offset {
+            self.builder.ins().jump(self.blocks[index], &[]);
+            return;
+        }
+
+        let back_edges = self.builder.use_var(self.back_edges);
+        let back_edges = self.builder.ins().iadd_imm(back_edges, 1);
+        self.builder.def_var(self.back_edges, back_edges);
+        let spent = self.builder.ins().icmp_imm(
+            IntCC::SignedGreaterThanOrEqual,
+            back_edges,
+            BACK_EDGE_BUDGET,
+        );
+        let out_of_budget = self.builder.create_block();
+        self.builder
+            .ins()
+            .brif(spent, out_of_budget, &[], self.blocks[index], &[]);
+        self.builder.switch_to_block(out_of_budget);
+        self.exit(target, height);
+    }
+
+    /// Writes back the registers and stack, and leaves for the interpreter to continue at
+    /// `resume_offset`.
+    fn exit(&mut self, resume_offset: usize, height: usize) {
+        let flags = MemFlags::trusted();
+        for &register in &self.region.assigned {
+            let value = self.builder.use_var(self.register_var(register));
+            self.builder
+                .ins()
+                .store(flags, value, self.registers, register as i32 * 8);
+            let assigned = self.assigned_vars[register as usize].expect("register written");
+            let assigned = self.builder.use_var(assigned);
+            self.builder
+                .ins()
+                .store(flags, assigned, self.assigned, register as i32);
+        }
+
+        for slot in 0..height {
+            let value = self.builder.use_var(self.stack_vars[slot]);
+            self.builder
+                .ins()
+                .store(flags, value, self.stack, slot as i32 * 8);
+        }
+
+        let height = self.builder.ins().iconst(types::I32, height as i64);
+        self.builder.ins().store(
+            flags,
+            height,
+            self.frame,
+            offset_of!(JitFrame, stack_height) as i32,
+        );
+        let resume_offset = self.builder.ins().iconst(types::I32, resume_offset as i64);
+        self.builder.ins().store(
+            flags,
+            resume_offset,
+            self.frame,
+            offset_of!(JitFrame, resume_offset) as i32,
+        );
+        self.leave(EXIT_RESUME);
+    }
+
+    /// Records the number of back-edges taken, and returns `code`.
+    fn leave(&mut self, code: u32) {
+        let back_edges = self.builder.use_var(self.back_edges);
+        self.builder.ins().store(
+            MemFlags::trusted(),
+            back_edges,
+            self.frame,
+            offset_of!(JitFrame, back_edges) as i32,
+        );
+        let code = self.builder.ins().iconst(types::I32, code as i64);
+        self.builder.ins().return_(&[code]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::jit::region;
+    use swf::avm2::types::ConstantPool;
+
+    fn constants(doubles: Vec<f64>) -> ConstantPool {
+        ConstantPool {
+            ints: vec![],
+            uints: vec![],
+            doubles,
+            strings: vec![],
+            namespaces: vec![],
+            namespace_sets: vec![],
+            multinames: vec![],
+        }
+    }
+
+    /// Compiles the loop at `entry`, and runs it with `registers`, of which the first is `this`.
+    fn run(
+        code: &[u8],
+        constants: &ConstantPool,
+        entry: usize,
+        registers: &mut [f64],
+    ) -> (u32, JitFrame, Vec<u8>, Vec<f64>) {
+        let numeric: Vec<bool> = (0..registers.len()).map(|i| i != 0).collect();
+        let region = region::select(code, constants, entry, &numeric).expect("loop to compile");
+        let mut compiler = Compiler::new().unwrap();
+        let function = compiler.compile(&region).unwrap();
+        let mut assigned = vec![0; registers.len()];
+        let mut stack = vec![0.0; region.max_stack];
+        let mut frame = JitFrame {
+            registers: registers.as_mut_ptr(),
+            assigned: assigned.as_mut_ptr(),
+            stack: stack.as_mut_ptr(),
+            stack_height: 0,
+            resume_offset: 0,
+            return_value: 0.0,
+            back_edges: 0,
+        };
+        let code = unsafe { function(&mut frame) };
+        stack.truncate(frame.stack_height as usize);
+        (code, frame, assigned, stack)
+    }
+
+    // var sum = 0; for (var i:int = 0; i < n; i++) { sum += i; } return sum;
+    const SUM_LOOP: &[u8] = &[
+        0x24, 0x00, // 0: pushbyte 0
+        0xd6, // 2: setlocal2
+        0x24, 0x00, // 3: pushbyte 0
+        0xd7, // 5: setlocal3
+        0x10, 0x07, 0x00, 0x00, // 6: jump 17
+        0x09, // 10: label
+        0xd3, // 11: getlocal3
+        0xd2, // 12: getlocal2
+        0xa0, // 13: add
+        0xd7, // 14: setlocal3
+        0xc2, 0x02, // 15: inclocal_i 2
+        0xd2, // 17: getlocal2
+        0xd1, // 18: getlocal1
+        0x15, 0xf3, 0xff, 0xff, // 19: iflt 10
+        0xd3, // 23: getlocal3
+        0x48, // 24: returnvalue
+    ];
+
+    #[test]
+    fn sum_loop() {
+        let mut registers = [f64::NAN, 100.0, 1.0, 0.0];
+        let (code, frame, _, _) = run(SUM_LOOP, &constants(vec![]), 10, &mut registers);
+        assert_eq!(code, EXIT_RETURN_VALUE);
+        assert_eq!(frame.return_value, (0..100).sum::<i32>() as f64);
+        assert_eq!(frame.back_edges, 98);
+    }
+
+    #[test]
+    fn exits_at_unsupported_instructions() {
+        // The same loop, calling something with `this` after updating the sum.
+        let mut code = SUM_LOOP.to_vec();
+        code[15] = 0xd0; // getlocal0
+        code[16] = 0x29; // pop
+
+        let mut registers = [f64::NAN, 100.0, 5.0, 7.0];
+        let (exit, frame, assigned, stack) = run(&code, &constants(vec![]), 10, &mut registers);
+        assert_eq!(exit, EXIT_RESUME);
+        assert_eq!(frame.resume_offset, 15);
+        assert!(stack.is_empty());
+        assert_eq!(registers[3], 12.0);
+        assert_eq!(assigned, [0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn exits_when_the_budget_is_spent() {
+        let mut registers = [f64::NAN, 1e9, 0.0, 0.0];
+        let (exit, frame, _, _) = run(SUM_LOOP, &constants(vec![]), 10, &mut registers);
+        assert_eq!(exit, EXIT_RESUME);
+        assert_eq!(frame.resume_offset, 10);
+        assert_eq!(frame.back_edges, BACK_EDGE_BUDGET);
+        assert_eq!(registers[2], BACK_EDGE_BUDGET as f64);
+    }
+
+    #[test]
+    fn int_conversions_wrap() {
+        let code = [
+            0x09, // 0: label
+            0x2f, 0x01, // 1: pushdouble 4294967297.5
+            0x73, // 3: convert_i
+            0x2f, 0x02, // 4: pushdouble 1e20
+            0x73, // 6: convert_i
+            0xa0, // 7: add
+            0x2f, 0x03, // 8: pushdouble -1
+            0x74, // 10: convert_u
+            0xa0, // 11: add
+            0x48, // 12: returnvalue
+        ];
+        let constants = constants(vec![4294967297.5, 1e20, -1.0]);
+        let (exit, frame, _, _) = run(&code, &constants, 0, &mut [f64::NAN]);
+        assert_eq!(exit, EXIT_RETURN_VALUE);
+        let expected = 1.0 + f64::from(f64_to_wrapping_i32(1e20)) + 4294967295.0;
+        assert_eq!(frame.return_value, expected);
+    }
+
+    #[test]
+    fn negated_comparisons_take_nan() {
+        let code = [
+            0x09, // 0: label
+            0x28, // 1: pushnan
+            0x24, 0x00, // 2: pushbyte 0
+            0x0c, 0x03, 0x00, 0x00, // 4: ifnlt 11
+            0x24, 0x01, // 8: pushbyte 1
+            0x48, // 10: returnvalue
+            0x24, 0x02, // 11: pushbyte 2
+            0x48, // 13: returnvalue
+        ];
+        let (exit, frame, _, _) = run(&code, &constants(vec![]), 0, &mut [f64::NAN]);
+        assert_eq!(exit, EXIT_RETURN_VALUE);
+        assert_eq!(frame.return_value, 2.0);
+    }
+}
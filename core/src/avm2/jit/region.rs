@@ -0,0 +1,488 @@
+//! Selection of the instructions a compiled loop runs
+//!
+//! A region starts at a loop header and covers every instruction reachable from it. Values are
+//! only ever numbers inside a region: instructions that would need anything else, such as reading
+//! a local register that doesn't hold a number, or calling a method, become exits that hand the
+//! loop back to the interpreter.
+
+use fnv::FnvHashMap;
+use swf::avm2::read::Reader;
+use swf::avm2::types::{ConstantPool, Op};
+use swf::extensions::ReadSwfExt;
+
+/// An operation on the number at the top of the stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Negate,
+    Increment,
+    Decrement,
+    NegateI,
+    IncrementI,
+    DecrementI,
+    BitNot,
+    ToInt,
+    ToUint,
+}
+
+/// An operation on the two numbers at the top of the stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    AddI,
+    SubtractI,
+    MultiplyI,
+    BitAnd,
+    BitOr,
+    BitXor,
+    LShift,
+    RShift,
+    URShift,
+}
+
+/// The condition of a conditional branch.
+///
+/// `True` and `False` test the number at the top of the stack; the others compare the two
+/// numbers at the top of the stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Condition {
+    True,
+    False,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessEqual,
+    GreaterThan,
+    GreaterEqual,
+    NotLessThan,
+    NotLessEqual,
+    NotGreaterThan,
+    NotGreaterEqual,
+}
+
+impl Condition {
+    fn operands(self) -> usize {
+        match self {
+            Condition::True | Condition::False => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// An instruction as it runs in compiled code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JitOp {
+    Push(f64),
+    Pop,
+    Dup,
+    Swap,
+    GetLocal(u32),
+    SetLocal(u32),
+    IncLocal(u32),
+    DecLocal(u32),
+    IncLocalI(u32),
+    DecLocalI(u32),
+    Unary(UnaryOp),
+    Binary(BinaryOp),
+    Jump(usize),
+    Branch(Condition, usize),
+    ReturnValue,
+    ReturnVoid,
+    Nop,
+
+    /// Leave the compiled code, and interpret the loop from this instruction on.
+    Exit,
+}
+
+impl JitOp {
+    /// The number of values this instruction takes off the stack, and puts on it.
+    fn stack_effect(self) -> (usize, usize) {
+        match self {
+            JitOp::Push(_) | JitOp::GetLocal(_) => (0, 1),
+            JitOp::Pop | JitOp::SetLocal(_) | JitOp::ReturnValue => (1, 0),
+            JitOp::Dup => (1, 2),
+            JitOp::Swap => (2, 2),
+            JitOp::Unary(_) => (1, 1),
+            JitOp::Binary(_) => (2, 1),
+            JitOp::Branch(condition, _) => (condition.operands(), 0),
+            JitOp::IncLocal(_)
+            | JitOp::DecLocal(_)
+            | JitOp::IncLocalI(_)
+            | JitOp::DecLocalI(_)
+            | JitOp::Jump(_)
+            | JitOp::ReturnVoid
+            | JitOp::Nop
+            | JitOp::Exit => (0, 0),
+        }
+    }
+
+    /// The local register this instruction reads or writes, if any.
+    fn register(self) -> Option<u32> {
+        match self {
+            JitOp::GetLocal(index)
+            | JitOp::SetLocal(index)
+            | JitOp::IncLocal(index)
+            | JitOp::DecLocal(index)
+            | JitOp::IncLocalI(index)
+            | JitOp::DecLocalI(index) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+/// An instruction of a region.
+#[derive(Clone, Debug)]
+pub struct RegionInstruction {
+    /// The offset of the instruction in the method body.
+    pub offset: usize,
+
+    /// The offset of the instruction that follows it.
+    pub next: usize,
+
+    pub op: JitOp,
+
+    /// The number of values on the stack when the instruction starts.
+    pub stack_height: usize,
+}
+
+/// The instructions of a loop that are compiled together.
+#[derive(Clone, Debug)]
+pub struct Region {
+    /// The instructions reachable from the loop header, ordered by offset.
+    ///
+    /// The first one of them isn't necessarily the loop header.
+    pub instructions: Vec<RegionInstruction>,
+
+    /// The index of the loop header in `instructions`.
+    pub entry: usize,
+
+    /// The number of local registers of the method.
+    pub num_registers: usize,
+
+    /// The most values the stack holds at once.
+    pub max_stack: usize,
+
+    /// The registers the compiled code reads or writes.
+    pub registers: Vec<u32>,
+
+    /// The registers the compiled code writes.
+    pub assigned: Vec<u32>,
+
+    /// The registers that must hold numbers when the loop is entered.
+    pub numeric: Vec<u32>,
+}
+
+impl Region {
+    /// Finds the instruction at `offset`.
+    pub fn instruction_at(&self, offset: usize) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&offset, |instruction| instruction.offset)
+            .ok()
+    }
+}
+
+/// What is known at the start of an instruction.
+#[derive(Clone, PartialEq)]
+struct State {
+    stack_height: usize,
+
+    /// Which local registers hold numbers.
+    numeric: Vec<bool>,
+}
+
+impl State {
+    /// Combines the state of another path to the same instruction into this one.
+    ///
+    /// Returns `None` if the paths disagree on the stack height, which the ABC verifier forbids,
+    /// and `Some(true)` if this state changed.
+    fn merge(&mut self, other: &State) -> Option<bool> {
+        if self.stack_height != other.stack_height {
+            return None;
+        }
+
+        let mut changed = false;
+        for (numeric, other) in self.numeric.iter_mut().zip(&other.numeric) {
+            if *numeric && !*other {
+                *numeric = false;
+                changed = true;
+            }
+        }
+
+        Some(changed)
+    }
+}
+
+/// Decodes every instruction of a method body, up to the first one that can't be read.
+fn decode(code: &[u8]) -> Vec<(usize, usize, Op)> {
+    let mut reader = Reader::new(code);
+    let mut instructions = Vec::new();
+    loop {
+        let offset = reader.pos(code);
+        if offset >= code.len() {
+            break;
+        }
+
+        match reader.read_op() {
+            Ok(op) => instructions.push((offset, reader.pos(code), op)),
+            Err(_) => break,
+        }
+    }
+
+    instructions
+}
+
+/// Computes where a branch goes, clamping it like `Reader::seek` does.
+fn branch_target(code: &[u8], next: usize, offset: i32) -> usize {
+    let target = next as isize + offset as isize;
+    if target < 0 {
+        code.len()
+    } else {
+        (target as usize).min(code.len())
+    }
+}
+
+/// Translates an instruction without looking at the values it works on.
+///
+/// Returns `JitOp::Exit` for everything compiled code doesn't run.
+fn translate(op: &Op, code: &[u8], next: usize, constants: &ConstantPool) -> JitOp {
+    let target = |offset: i32| branch_target(code, next, offset);
+    match *op {
+        Op::PushByte { value } => JitOp::Push(f64::from(value as i8)),
+        Op::PushShort { value } => JitOp::Push(f64::from(value)),
+        Op::PushInt { ref value } => match value.0 {
+            0 => JitOp::Push(0.0),
+            index => match constants.ints.get(index as usize - 1) {
+                Some(value) => JitOp::Push(f64::from(*value)),
+                None => JitOp::Exit,
+            },
+        },
+        Op::PushUint { ref value } => match value.0 {
+            0 => JitOp::Push(0.0),
+            index => match constants.uints.get(index as usize - 1) {
+                Some(value) => JitOp::Push(f64::from(*value)),
+                None => JitOp::Exit,
+            },
+        },
+        Op::PushDouble { ref value } => match value.0 {
+            0 => JitOp::Push(f64::NAN),
+            index => match constants.doubles.get(index as usize - 1) {
+                Some(value) => JitOp::Push(*value),
+                None => JitOp::Exit,
+            },
+        },
+        Op::PushNaN => JitOp::Push(f64::NAN),
+        Op::Pop => JitOp::Pop,
+        Op::Dup => JitOp::Dup,
+        Op::Swap => JitOp::Swap,
+        Op::GetLocal { index } => JitOp::GetLocal(index),
+        Op::SetLocal { index } => JitOp::SetLocal(index),
+        Op::IncLocal { index } => JitOp::IncLocal(index),
+        Op::DecLocal { index } => JitOp::DecLocal(index),
+        Op::IncLocalI { index } => JitOp::IncLocalI(index),
+        Op::DecLocalI { index } => JitOp::DecLocalI(index),
+        Op::Negate => JitOp::Unary(UnaryOp::Negate),
+        Op::Increment => JitOp::Unary(UnaryOp::Increment),
+        Op::Decrement => JitOp::Unary(UnaryOp::Decrement),
+        Op::NegateI => JitOp::Unary(UnaryOp::NegateI),
+        Op::IncrementI => JitOp::Unary(UnaryOp::IncrementI),
+        Op::DecrementI => JitOp::Unary(UnaryOp::DecrementI),
+        Op::BitNot => JitOp::Unary(UnaryOp::BitNot),
+        Op::ConvertI | Op::CoerceI => JitOp::Unary(UnaryOp::ToInt),
+        Op::ConvertU | Op::CoerceU => JitOp::Unary(UnaryOp::ToUint),
+        Op::ConvertD | Op::CoerceD => JitOp::Nop,
+        Op::Add => JitOp::Binary(BinaryOp::Add),
+        Op::Subtract => JitOp::Binary(BinaryOp::Subtract),
+        Op::Multiply => JitOp::Binary(BinaryOp::Multiply),
+        Op::Divide => JitOp::Binary(BinaryOp::Divide),
+        Op::Modulo => JitOp::Binary(BinaryOp::Modulo),
+        Op::AddI => JitOp::Binary(BinaryOp::AddI),
+        Op::SubtractI => JitOp::Binary(BinaryOp::SubtractI),
+        Op::MultiplyI => JitOp::Binary(BinaryOp::MultiplyI),
+        Op::BitAnd => JitOp::Binary(BinaryOp::BitAnd),
+        Op::BitOr => JitOp::Binary(BinaryOp::BitOr),
+        Op::BitXor => JitOp::Binary(BinaryOp::BitXor),
+        Op::LShift => JitOp::Binary(BinaryOp::LShift),
+        Op::RShift => JitOp::Binary(BinaryOp::RShift),
+        Op::URShift => JitOp::Binary(BinaryOp::URShift),
+        Op::Jump { offset } => JitOp::Jump(target(offset)),
+        Op::IfTrue { offset } => JitOp::Branch(Condition::True, target(offset)),
+        Op::IfFalse { offset } => JitOp::Branch(Condition::False, target(offset)),
+        // Strict and loose equality are the same for two numbers.
+        Op::IfEq { offset } | Op::IfStrictEq { offset } => {
+            JitOp::Branch(Condition::Equal, target(offset))
+        }
+        Op::IfNe { offset } | Op::IfStrictNe { offset } => {
+            JitOp::Branch(Condition::NotEqual, target(offset))
+        }
+        Op::IfLt { offset } => JitOp::Branch(Condition::LessThan, target(offset)),
+        Op::IfLe { offset } => JitOp::Branch(Condition::LessEqual, target(offset)),
+        Op::IfGt { offset } => JitOp::Branch(Condition::GreaterThan, target(offset)),
+        Op::IfGe { offset } => JitOp::Branch(Condition::GreaterEqual, target(offset)),
+        Op::IfNlt { offset } => JitOp::Branch(Condition::NotLessThan, target(offset)),
+        Op::IfNle { offset } => JitOp::Branch(Condition::NotLessEqual, target(offset)),
+        Op::IfNgt { offset } => JitOp::Branch(Condition::NotGreaterThan, target(offset)),
+        Op::IfNge { offset } => JitOp::Branch(Condition::NotGreaterEqual, target(offset)),
+        Op::ReturnValue => JitOp::ReturnValue,
+        Op::ReturnVoid => JitOp::ReturnVoid,
+        Op::Label
+        | Op::Nop
+        | Op::Bkpt
+        | Op::BkptLine { .. }
+        | Op::Debug { .. }
+        | Op::DebugFile { .. }
+        | Op::DebugLine { .. } => JitOp::Nop,
+        _ => JitOp::Exit,
+    }
+}
+
+/// Refines the translation of an instruction with what is known when it starts.
+fn specialize(op: JitOp, state: &State) -> JitOp {
+    if let Some(index) = op.register() {
+        match state.numeric.get(index as usize) {
+            // A register that is only written may hold anything beforehand.
+            Some(_) if matches!(op, JitOp::SetLocal(_)) => {}
+            Some(true) => {}
+            _ => return JitOp::Exit,
+        }
+    }
+
+    let (pops, _) = op.stack_effect();
+    if state.stack_height < pops {
+        // Let the interpreter deal with code that underflows the stack.
+        return JitOp::Exit;
+    }
+
+    op
+}
+
+/// Selects the region of the loop with the header at `entry`.
+///
+/// `numeric` tells which local registers hold numbers when the loop is entered. Returns `None` if
+/// there's nothing worth compiling, such as a loop header the compiled code would immediately
+/// leave at.
+pub fn select(
+    code: &[u8],
+    constants: &ConstantPool,
+    entry: usize,
+    numeric: &[bool],
+) -> Option<Region> {
+    let decoded = decode(code);
+    let index_of: FnvHashMap<usize, usize> = decoded
+        .iter()
+        .enumerate()
+        .map(|(index, (offset, _, _))| (*offset, index))
+        .collect();
+    let translated: Vec<JitOp> = decoded
+        .iter()
+        .map(|(_, next, op)| translate(op, code, *next, constants))
+        .collect();
+
+    let entry_index = *index_of.get(&entry)?;
+    let mut states: Vec<Option<State>> = vec![None; decoded.len()];
+    states[entry_index] = Some(State {
+        stack_height: 0,
+        numeric: numeric.to_vec(),
+    });
+
+    // Find out what holds at the start of every reachable instruction, following the paths
+    // through the region until nothing changes anymore.
+    let mut worklist = vec![entry_index];
+    while let Some(index) = worklist.pop() {
+        let mut state = states[index].clone()?;
+        let op = specialize(translated[index], &state);
+        let (pops, pushes) = op.stack_effect();
+        state.stack_height = state.stack_height - pops + pushes;
+        match op {
+            JitOp::SetLocal(register) => state.numeric[register as usize] = true,
+            JitOp::Exit | JitOp::ReturnValue | JitOp::ReturnVoid => continue,
+            _ => {}
+        }
+
+        let next = decoded[index].1;
+        let successors: &[usize] = match op {
+            JitOp::Jump(target) => &[target],
+            JitOp::Branch(_, target) => &[target, next],
+            _ => &[next],
+        };
+
+        for successor in successors {
+            // Branches to the middle of an instruction leave the compiled code.
+            let Some(&successor) = index_of.get(successor) else {
+                continue;
+            };
+
+            match &mut states[successor] {
+                Some(existing) => {
+                    if existing.merge(&state)? {
+                        worklist.push(successor);
+                    }
+                }
+                empty => {
+                    *empty = Some(state.clone());
+                    worklist.push(successor);
+                }
+            }
+        }
+    }
+
+    let entry_op = specialize(translated[entry_index], states[entry_index].as_ref()?);
+    if entry_op == JitOp::Exit {
+        return None;
+    }
+
+    let mut instructions = Vec::new();
+    let mut new_entry = 0;
+    let mut max_stack = 0;
+    let mut registers = Vec::new();
+    let mut assigned = Vec::new();
+    for (index, ((offset, next, _), state)) in decoded.iter().zip(&states).enumerate() {
+        let Some(state) = state else {
+            continue;
+        };
+
+        let op = specialize(translated[index], state);
+        let (pops, pushes) = op.stack_effect();
+        max_stack = max_stack.max(state.stack_height.max(state.stack_height - pops + pushes));
+        if let Some(register) = op.register() {
+            registers.push(register);
+            if !matches!(op, JitOp::GetLocal(_)) {
+                assigned.push(register);
+            }
+        }
+
+        if index == entry_index {
+            new_entry = instructions.len();
+        }
+        instructions.push(RegionInstruction {
+            offset: *offset,
+            next: *next,
+            op,
+            stack_height: state.stack_height,
+        });
+    }
+
+    registers.sort_unstable();
+    registers.dedup();
+    assigned.sort_unstable();
+    assigned.dedup();
+
+    let entry_state = states[entry_index].as_ref()?;
+    let entry_numeric = registers
+        .iter()
+        .copied()
+        .filter(|register| entry_state.numeric[*register as usize])
+        .collect();
+
+    Some(Region {
+        instructions,
+        entry: new_entry,
+        num_registers: numeric.len(),
+        max_stack,
+        registers,
+        assigned,
+        numeric: entry_numeric,
+    })
+}
@@ -0,0 +1,65 @@
+//! Metadata attached to traits and classes
+
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use gc_arena::{Collect, MutationContext};
+use swf::avm2::types::{Index, Metadata as AbcMetadata};
+
+/// A metadata tag, like `[Inject(name="foo")]`, as written above a trait in
+/// the source.
+///
+/// The VM itself does not use metadata; it is only kept around for
+/// reflection with `describeType`.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Metadata<'gc> {
+    /// The name of the tag, e.g. `Inject`.
+    name: AvmString<'gc>,
+
+    /// The key-value pairs of the tag. Items written without a key have an
+    /// empty key.
+    items: Vec<(AvmString<'gc>, AvmString<'gc>)>,
+}
+
+impl<'gc> Metadata<'gc> {
+    /// Load the metadata tags at the given indices of an ABC file.
+    pub fn from_abc_indices(
+        unit: TranslationUnit<'gc>,
+        indices: &[Index<AbcMetadata>],
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Vec<Self>, Error<'gc>> {
+        if indices.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let abc = unit.abc();
+        let mut metadata = Vec::with_capacity(indices.len());
+        for index in indices {
+            let abc_metadata = abc
+                .metadata
+                .get(index.0 as usize)
+                .ok_or_else(|| format!("Unknown metadata index {}", index.0))?;
+
+            let name = unit.pool_string(abc_metadata.name.0, mc)?;
+            let mut items = Vec::with_capacity(abc_metadata.items.len());
+            for item in &abc_metadata.items {
+                let key = unit.pool_string(item.key.0, mc)?;
+                let value = unit.pool_string(item.value.0, mc)?;
+                items.push((key, value));
+            }
+
+            metadata.push(Self { name, items });
+        }
+
+        Ok(metadata)
+    }
+
+    pub fn name(&self) -> AvmString<'gc> {
+        self.name
+    }
+
+    pub fn items(&self) -> &[(AvmString<'gc>, AvmString<'gc>)] {
+        &self.items
+    }
+}
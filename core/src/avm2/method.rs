@@ -1,6 +1,9 @@
 //! AVM2 methods
 
 use crate::avm2::activation::Activation;
+use crate::avm2::inline_cache::InlineCaches;
+#[cfg(feature = "avm2_jit")]
+use crate::avm2::jit::MethodJit;
 use crate::avm2::object::Object;
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::value::{abc_default_value, Value};
@@ -128,6 +131,14 @@ pub struct BytecodeMethod<'gc> {
     /// A free-standing function corresponds to the `Function` trait type, and
     /// is instantiated with the `newfunction` opcode.
     pub is_function: bool,
+
+    /// The inline caches of the property accesses made by this method.
+    pub inline_caches: InlineCaches<'gc>,
+
+    /// The hot loops of this method, and their compiled code.
+    #[cfg(feature = "avm2_jit")]
+    #[collect(require_static)]
+    pub jit: MethodJit,
 }
 
 impl<'gc> BytecodeMethod<'gc> {
@@ -162,6 +173,9 @@ impl<'gc> BytecodeMethod<'gc> {
                         signature,
                         return_type,
                         is_function,
+                        inline_caches: InlineCaches::new(activation.context.gc_context),
+                        #[cfg(feature = "avm2_jit")]
+                        jit: MethodJit::default(),
                     });
                 }
             }
@@ -175,6 +189,9 @@ impl<'gc> BytecodeMethod<'gc> {
             signature,
             return_type: Multiname::any(),
             is_function,
+            inline_caches: InlineCaches::new(activation.context.gc_context),
+            #[cfg(feature = "avm2_jit")]
+            jit: MethodJit::default(),
         })
     }
 
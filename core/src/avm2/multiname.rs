@@ -58,6 +58,8 @@ bitflags! {
         /// Whether the name needs to be read at runtime before use
         /// This should only be set when lazy-initialized in Activation.
         const HAS_LAZY_NAME = 1 << 1;
+        /// Whether this name refers to an XML attribute, as in `xml.@name`.
+        const ATTRIBUTE = 1 << 2;
     }
 }
 
@@ -101,6 +103,15 @@ impl<'gc> Multiname<'gc> {
         self.has_lazy_ns() || self.has_lazy_name()
     }
 
+    #[inline(always)]
+    pub fn is_attribute(&self) -> bool {
+        self.flags.contains(MultinameFlags::ATTRIBUTE)
+    }
+
+    pub fn set_is_attribute(&mut self, is_attribute: bool) {
+        self.flags.set(MultinameFlags::ATTRIBUTE, is_attribute);
+    }
+
     /// Read a namespace set from the ABC constant pool, and return a list of
     /// copied namespaces.
     fn abc_namespace_set(
@@ -148,7 +159,7 @@ impl<'gc> Multiname<'gc> {
         let abc = translation_unit.abc();
         let abc_multiname = Self::resolve_multiname_index(&abc, multiname_index)?;
 
-        Ok(match abc_multiname {
+        let mut multiname = match abc_multiname {
             AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
                 Self {
                     ns: NamespaceSet::single(Namespace::from_abc_namespace(
@@ -218,7 +229,20 @@ impl<'gc> Multiname<'gc> {
                 }
                 base
             }
-        })
+        };
+
+        if matches!(
+            abc_multiname,
+            AbcMultiname::QNameA { .. }
+                | AbcMultiname::RTQNameA { .. }
+                | AbcMultiname::RTQNameLA
+                | AbcMultiname::MultinameA { .. }
+                | AbcMultiname::MultinameLA { .. }
+        ) {
+            multiname.set_is_attribute(true);
+        }
+
+        Ok(multiname)
     }
 
     #[inline(never)]
@@ -246,7 +270,8 @@ impl<'gc> Multiname<'gc> {
             let name_value = activation.pop_stack();
 
             if let Value::Object(name_obj) = name_value {
-                if let Some(result) = self.try_replace_with_qname(name_obj, activation) {
+                if let Some(mut result) = self.try_replace_with_qname(name_obj, activation) {
+                    result.set_is_attribute(self.is_attribute());
                     return Ok(result);
                 }
             }
@@ -268,7 +293,7 @@ impl<'gc> Multiname<'gc> {
             ns,
             name,
             params: self.params.clone(),
-            flags: Default::default(),
+            flags: self.flags & MultinameFlags::ATTRIBUTE,
         })
     }
 
@@ -317,6 +342,18 @@ impl<'gc> Multiname<'gc> {
         }
     }
 
+    /// Returns this name with another local name, keeping its namespaces and flags.
+    ///
+    /// A local name of `None` matches any name.
+    pub fn with_local_name(&self, name: Option<AvmString<'gc>>) -> Self {
+        Self {
+            ns: self.ns,
+            name,
+            params: self.params.clone(),
+            flags: self.flags,
+        }
+    }
+
     pub fn namespace_set(&self) -> &[Namespace<'gc>] {
         match &self.ns {
             NamespaceSet::Single(ns) => std::slice::from_ref(ns),
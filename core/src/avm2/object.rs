@@ -19,6 +19,7 @@ use crate::avm2::QName;
 use crate::backend::audio::{SoundHandle, SoundInstanceHandle};
 use crate::bitmap::bitmap_data::{BitmapData, BitmapDataWrapper};
 use crate::display_object::DisplayObject;
+use crate::font::Font;
 use crate::html::TextFormat;
 use crate::string::AvmString;
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -30,6 +31,7 @@ use std::hash::{Hash, Hasher};
 mod array_object;
 mod bitmapdata_object;
 mod bytearray_object;
+mod camera_object;
 mod class_object;
 mod context3d_object;
 mod date_object;
@@ -38,10 +40,14 @@ mod dispatch_object;
 mod domain_object;
 mod error_object;
 mod event_object;
+mod font_object;
 mod function_object;
 mod index_buffer_3d_object;
 mod loaderinfo_object;
+mod microphone_object;
 mod namespace_object;
+mod netconnection_object;
+mod netstream_object;
 mod primitive_object;
 mod program_3d_object;
 mod proxy_object;
@@ -52,14 +58,17 @@ mod sound_object;
 mod soundchannel_object;
 mod stage3d_object;
 mod stage_object;
+mod stagevideo_object;
 mod textformat_object;
 mod vector_object;
 mod vertex_buffer_3d_object;
+mod xml_list_object;
 mod xml_object;
 
 pub use crate::avm2::object::array_object::{array_allocator, ArrayObject};
 pub use crate::avm2::object::bitmapdata_object::{bitmapdata_allocator, BitmapDataObject};
 pub use crate::avm2::object::bytearray_object::{byte_array_allocator, ByteArrayObject};
+pub use crate::avm2::object::camera_object::{camera_allocator, CameraObject};
 pub use crate::avm2::object::class_object::ClassObject;
 pub use crate::avm2::object::context3d_object::Context3DObject;
 pub use crate::avm2::object::date_object::{date_allocator, DateObject};
@@ -68,25 +77,33 @@ pub use crate::avm2::object::dispatch_object::DispatchObject;
 pub use crate::avm2::object::domain_object::{appdomain_allocator, DomainObject};
 pub use crate::avm2::object::error_object::{error_allocator, ErrorObject};
 pub use crate::avm2::object::event_object::{event_allocator, EventObject};
+pub use crate::avm2::object::font_object::{font_allocator, FontObject};
 pub use crate::avm2::object::function_object::{function_allocator, FunctionObject};
 pub use crate::avm2::object::index_buffer_3d_object::IndexBuffer3DObject;
 pub use crate::avm2::object::loaderinfo_object::{
     loaderinfo_allocator, LoaderInfoObject, LoaderStream,
 };
+pub use crate::avm2::object::microphone_object::{microphone_allocator, MicrophoneObject};
 pub use crate::avm2::object::namespace_object::{namespace_allocator, NamespaceObject};
+pub use crate::avm2::object::netconnection_object::{
+    net_connection_allocator, NetConnectionObject,
+};
+pub use crate::avm2::object::netstream_object::{net_stream_allocator, NetStreamObject};
 pub use crate::avm2::object::primitive_object::{primitive_allocator, PrimitiveObject};
 pub use crate::avm2::object::program_3d_object::Program3DObject;
 pub use crate::avm2::object::proxy_object::{proxy_allocator, ProxyObject};
 pub use crate::avm2::object::qname_object::{qname_allocator, QNameObject};
 pub use crate::avm2::object::regexp_object::{regexp_allocator, RegExpObject};
 pub use crate::avm2::object::script_object::{ScriptObject, ScriptObjectData};
-pub use crate::avm2::object::sound_object::{sound_allocator, SoundObject};
+pub use crate::avm2::object::sound_object::{sound_allocator, QueuedPlay, SoundObject};
 pub use crate::avm2::object::soundchannel_object::{soundchannel_allocator, SoundChannelObject};
 pub use crate::avm2::object::stage3d_object::{stage_3d_allocator, Stage3DObject};
 pub use crate::avm2::object::stage_object::{stage_allocator, StageObject};
+pub use crate::avm2::object::stagevideo_object::{stage_video_allocator, StageVideoObject};
 pub use crate::avm2::object::textformat_object::{textformat_allocator, TextFormatObject};
 pub use crate::avm2::object::vector_object::{vector_allocator, VectorObject};
 pub use crate::avm2::object::vertex_buffer_3d_object::VertexBuffer3DObject;
+pub use crate::avm2::object::xml_list_object::{xml_list_allocator, XmlListObject};
 pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
 
 /// Represents an object that can be directly interacted with by the AVM2
@@ -106,6 +123,7 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         EventObject(EventObject<'gc>),
         DispatchObject(DispatchObject<'gc>),
         XmlObject(XmlObject<'gc>),
+        XmlListObject(XmlListObject<'gc>),
         RegExpObject(RegExpObject<'gc>),
         ByteArrayObject(ByteArrayObject<'gc>),
         LoaderInfoObject(LoaderInfoObject<'gc>),
@@ -113,6 +131,7 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         VectorObject(VectorObject<'gc>),
         SoundObject(SoundObject<'gc>),
         SoundChannelObject(SoundChannelObject<'gc>),
+        FontObject(FontObject<'gc>),
         BitmapDataObject(BitmapDataObject<'gc>),
         DateObject(DateObject<'gc>),
         DictionaryObject(DictionaryObject<'gc>),
@@ -121,10 +140,15 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         ProxyObject(ProxyObject<'gc>),
         ErrorObject(ErrorObject<'gc>),
         Stage3DObject(Stage3DObject<'gc>),
+        StageVideoObject(StageVideoObject<'gc>),
         Context3DObject(Context3DObject<'gc>),
         IndexBuffer3DObject(IndexBuffer3DObject<'gc>),
         VertexBuffer3DObject(VertexBuffer3DObject<'gc>),
         Program3DObject(Program3DObject<'gc>),
+        NetStreamObject(NetStreamObject<'gc>),
+        NetConnectionObject(NetConnectionObject<'gc>),
+        MicrophoneObject(MicrophoneObject<'gc>),
+        CameraObject(CameraObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -154,13 +178,30 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This corresponds directly to the AVM2 operation `getproperty`, with the
     /// exception that it does not special-case object lookups on dictionary
     /// structured objects.
-    #[allow(unused_mut)] //Not unused.
     fn get_property(
+        self,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.get_resolved_property(property, multiname, activation)
+    }
+
+    /// Retrieve a property by Multiname lookup, given the trait the name resolves to on the
+    /// vtable of this object.
+    ///
+    /// This method should not be overridden.
+    ///
+    /// This lets the interpreter skip the name resolution of `get_property` when it already
+    /// knows the trait, such as from an inline cache.
+    #[allow(unused_mut)] //Not unused.
+    fn get_resolved_property(
         mut self,
+        property: Option<Property>,
         multiname: &Multiname<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 self.base().get_slot(slot_id)
             }
@@ -216,7 +257,22 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.set_resolved_property(property, multiname, value, activation)
+    }
+
+    /// Set a property by Multiname lookup, given the trait the name resolves to on the vtable
+    /// of this object.
+    ///
+    /// This method should not be overridden.
+    fn set_resolved_property(
+        &mut self,
+        property: Option<Property>,
+        multiname: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) => {
                 let value = self
                     .vtable()
@@ -269,7 +325,22 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.init_resolved_property(property, multiname, value, activation)
+    }
+
+    /// Initialize a property by Multiname lookup, given the trait the name resolves to on the
+    /// vtable of this object.
+    ///
+    /// This method should not be overridden.
+    fn init_resolved_property(
+        &mut self,
+        property: Option<Property>,
+        multiname: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 let value = self
                     .vtable()
@@ -320,14 +391,29 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This method should not be overridden.
     ///
     /// This corresponds directly to the `callproperty` operation in AVM2.
-    #[allow(unused_mut)]
     fn call_property(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.call_resolved_property(property, multiname, arguments, activation)
+    }
+
+    /// Call a named property on the object, given the trait the name resolves to on the
+    /// vtable of this object.
+    ///
+    /// This method should not be overridden.
+    #[allow(unused_mut)]
+    fn call_resolved_property(
         mut self,
+        property: Option<Property>,
         multiname: &Multiname<'gc>,
         arguments: &[Value<'gc>],
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 let obj = self.base().get_slot(slot_id)?.as_callable(
                     activation,
@@ -926,6 +1012,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Unwrap this object as a `NamespaceObject`
+    fn as_namespace_object(&self) -> Option<NamespaceObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object as a `QNameObject`
     fn as_qname_object(self) -> Option<QNameObject<'gc>> {
         None
@@ -1053,6 +1144,21 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This does nothing if the object is not a sound.
     fn set_sound(self, _mc: MutationContext<'gc, '_>, _sound: SoundHandle) {}
 
+    /// Unwrap this object as a sound.
+    fn as_sound_object(self) -> Option<SoundObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object's font.
+    fn as_font(self) -> Option<Font<'gc>> {
+        None
+    }
+
+    /// Associate the object with a particular font.
+    ///
+    /// This does nothing if the object is not a font.
+    fn set_font(self, _mc: MutationContext<'gc, '_>, _font: Font<'gc>) {}
+
     /// Unwrap this object's sound instance handle.
     fn as_sound_channel(self) -> Option<SoundChannelObject<'gc>> {
         None
@@ -1110,7 +1216,16 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
-    fn as_xml(&self) -> Option<XmlObject<'gc>> {
+    fn as_xml_object(&self) -> Option<XmlObject<'gc>> {
+        None
+    }
+
+    fn as_xml_list_object(&self) -> Option<XmlListObject<'gc>> {
+        None
+    }
+
+    /// Get this object as a `ProxyObject`, if it is one.
+    fn as_proxy_object(&self) -> Option<ProxyObject<'gc>> {
         None
     }
 
@@ -1133,6 +1248,26 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     fn as_stage_3d(&self) -> Option<Stage3DObject<'gc>> {
         None
     }
+
+    fn as_stage_video(&self) -> Option<StageVideoObject<'gc>> {
+        None
+    }
+
+    fn as_netstream(&self) -> Option<NetStreamObject<'gc>> {
+        None
+    }
+
+    fn as_netconnection(&self) -> Option<NetConnectionObject<'gc>> {
+        None
+    }
+
+    fn as_microphone(&self) -> Option<MicrophoneObject<'gc>> {
+        None
+    }
+
+    fn as_camera(&self) -> Option<CameraObject<'gc>> {
+        None
+    }
 }
 
 pub enum ObjectPtr {}
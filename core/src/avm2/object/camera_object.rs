@@ -0,0 +1,83 @@
+//! Object representation for Camera objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::capture::Camera;
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates Camera objects.
+pub fn camera_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(CameraObject(GcCell::allocate(
+        activation.context.gc_context,
+        CameraObjectData {
+            base,
+            camera: Camera::default(),
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct CameraObject<'gc>(GcCell<'gc, CameraObjectData<'gc>>);
+
+impl fmt::Debug for CameraObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CameraObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+impl<'gc> CameraObject<'gc> {
+    pub fn camera(&self) -> Ref<Camera> {
+        Ref::map(self.0.read(), |read| &read.camera)
+    }
+
+    pub fn camera_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<Camera> {
+        RefMut::map(self.0.write(mc), |write| &mut write.camera)
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct CameraObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The settings and capture state of this camera.
+    #[collect(require_static)]
+    camera: Camera,
+}
+
+impl<'gc> TObject<'gc> for CameraObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn as_camera(&self) -> Option<CameraObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -81,6 +81,16 @@ impl<'gc> DictionaryObject<'gc> {
         self.0.write(mc).object_space.remove(&name);
     }
 
+    /// The entries of this dictionary that have objects as keys.
+    pub fn object_entries(self) -> Vec<(Object<'gc>, Value<'gc>)> {
+        self.0
+            .read()
+            .object_space
+            .iter()
+            .map(|(key, value)| (*key, *value))
+            .collect()
+    }
+
     pub fn has_property_by_object(self, name: Object<'gc>) -> bool {
         self.0.read().object_space.get(&name).is_some()
     }
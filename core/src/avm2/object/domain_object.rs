@@ -15,7 +15,10 @@ pub fn appdomain_allocator<'gc>(
     class: ClassObject<'gc>,
     activation: &mut Activation<'_, 'gc>,
 ) -> Result<Object<'gc>, Error<'gc>> {
-    let domain = activation.domain();
+    // `new ApplicationDomain()` makes a child of the system domain. The
+    // constructor replaces it when it's given another parent.
+    let parent_domain = activation.avm2().global_domain();
+    let domain = Domain::movie_domain(activation, parent_domain);
     let base = ScriptObjectData::new(class);
 
     Ok(DomainObject(GcCell::allocate(
@@ -69,6 +72,11 @@ impl<'gc> DomainObject<'gc> {
 
         Ok(this)
     }
+
+    /// Replace the domain this object holds.
+    pub fn set_domain(self, mc: MutationContext<'gc, '_>, domain: Domain<'gc>) {
+        self.0.write(mc).domain = domain;
+    }
 }
 
 impl<'gc> TObject<'gc> for DomainObject<'gc> {
@@ -0,0 +1,104 @@
+//! Object representation for fonts
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::font::Font;
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates Font objects.
+pub fn font_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(FontObject(GcCell::allocate(
+        activation.context.gc_context,
+        FontObjectData { base, font: None },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct FontObject<'gc>(GcCell<'gc, FontObjectData<'gc>>);
+
+impl fmt::Debug for FontObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct FontObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The font this object holds.
+    font: Option<Font<'gc>>,
+}
+
+impl<'gc> FontObject<'gc> {
+    /// Convert a bare font into it's object representation.
+    ///
+    /// This is used for fonts that aren't associated with a `Font` subclass,
+    /// such as device fonts.
+    pub fn from_font(
+        activation: &mut Activation<'_, 'gc>,
+        font: Font<'gc>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let class = activation.avm2().classes().font;
+        let base = ScriptObjectData::new(class);
+
+        let mut font_object: Object<'gc> = FontObject(GcCell::allocate(
+            activation.context.gc_context,
+            FontObjectData {
+                base,
+                font: Some(font),
+            },
+        ))
+        .into();
+        font_object.install_instance_slots(activation);
+
+        class.call_native_init(Some(font_object), &[], activation)?;
+
+        Ok(font_object)
+    }
+}
+
+impl<'gc> TObject<'gc> for FontObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn as_font(self) -> Option<Font<'gc>> {
+        self.0.read().font
+    }
+
+    /// Associate the object with a particular font.
+    ///
+    /// This does nothing if the object is not a font.
+    fn set_font(self, mc: MutationContext<'gc, '_>, font: Font<'gc>) {
+        self.0.write(mc).font = Some(font);
+    }
+}
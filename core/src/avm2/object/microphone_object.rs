@@ -0,0 +1,83 @@
+//! Object representation for Microphone objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::capture::Microphone;
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates Microphone objects.
+pub fn microphone_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(MicrophoneObject(GcCell::allocate(
+        activation.context.gc_context,
+        MicrophoneObjectData {
+            base,
+            microphone: Microphone::default(),
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct MicrophoneObject<'gc>(GcCell<'gc, MicrophoneObjectData<'gc>>);
+
+impl fmt::Debug for MicrophoneObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MicrophoneObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+impl<'gc> MicrophoneObject<'gc> {
+    pub fn microphone(&self) -> Ref<Microphone> {
+        Ref::map(self.0.read(), |read| &read.microphone)
+    }
+
+    pub fn microphone_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<Microphone> {
+        RefMut::map(self.0.write(mc), |write| &mut write.microphone)
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct MicrophoneObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The settings and capture state of this microphone.
+    #[collect(require_static)]
+    microphone: Microphone,
+}
+
+impl<'gc> TObject<'gc> for MicrophoneObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn as_microphone(&self) -> Option<MicrophoneObject<'gc>> {
+        Some(*self)
+    }
+}
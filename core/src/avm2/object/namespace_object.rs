@@ -6,6 +6,7 @@ use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Namespace;
+use crate::string::AvmString;
 use core::fmt;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
@@ -22,6 +23,7 @@ pub fn namespace_allocator<'gc>(
         NamespaceObjectData {
             base,
             namespace: Namespace::public(),
+            prefix: None,
         },
     ))
     .into())
@@ -48,6 +50,11 @@ pub struct NamespaceObjectData<'gc> {
 
     /// The namespace name this object is associated with.
     namespace: Namespace<'gc>,
+
+    /// The prefix of the namespace, as used in XML.
+    ///
+    /// `None` means the prefix is undefined, and an empty prefix is the default namespace.
+    prefix: Option<AvmString<'gc>>,
 }
 
 impl<'gc> NamespaceObject<'gc> {
@@ -61,7 +68,11 @@ impl<'gc> NamespaceObject<'gc> {
 
         let mut this: Object<'gc> = NamespaceObject(GcCell::allocate(
             activation.context.gc_context,
-            NamespaceObjectData { base, namespace },
+            NamespaceObjectData {
+                base,
+                namespace,
+                prefix: None,
+            },
         ))
         .into();
         this.install_instance_slots(activation);
@@ -70,6 +81,38 @@ impl<'gc> NamespaceObject<'gc> {
 
         Ok(this)
     }
+
+    /// Box a namespace with a prefix into an object.
+    pub fn from_prefix_and_uri(
+        activation: &mut Activation<'_, 'gc>,
+        prefix: Option<AvmString<'gc>>,
+        uri: AvmString<'gc>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let this = Self::from_namespace(activation, Namespace::Namespace(uri))?;
+        if let Some(namespace_object) = this.as_namespace_object() {
+            namespace_object
+                .0
+                .write(activation.context.gc_context)
+                .prefix = prefix;
+        }
+
+        Ok(this)
+    }
+
+    pub fn prefix(&self) -> Option<AvmString<'gc>> {
+        self.0.read().prefix
+    }
+
+    pub fn init_namespace(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        namespace: Namespace<'gc>,
+        prefix: Option<AvmString<'gc>>,
+    ) {
+        let mut write = self.0.write(mc);
+        write.namespace = namespace;
+        write.prefix = prefix;
+    }
 }
 
 impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
@@ -96,4 +139,8 @@ impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
     fn as_namespace(&self) -> Option<Ref<Namespace<'gc>>> {
         Some(Ref::map(self.0.read(), |s| &s.namespace))
     }
+
+    fn as_namespace_object(&self) -> Option<Self> {
+        Some(*self)
+    }
 }
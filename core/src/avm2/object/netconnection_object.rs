@@ -0,0 +1,79 @@
+//! Object representation for NetConnection objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::rtmp::RtmpConnection;
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates NetConnection objects.
+pub fn net_connection_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+    let connection = RtmpConnection::new(activation.context.gc_context, None);
+
+    let this: Object<'gc> = NetConnectionObject(GcCell::allocate(
+        activation.context.gc_context,
+        NetConnectionObjectData { base, connection },
+    ))
+    .into();
+    connection.set_avm_object(activation.context.gc_context, this);
+
+    Ok(this)
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct NetConnectionObject<'gc>(GcCell<'gc, NetConnectionObjectData<'gc>>);
+
+impl fmt::Debug for NetConnectionObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NetConnectionObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+impl<'gc> NetConnectionObject<'gc> {
+    pub fn connection(self) -> RtmpConnection<'gc> {
+        self.0.read().connection
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct NetConnectionObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The RTMP connection of this object, used when connecting to a server.
+    connection: RtmpConnection<'gc>,
+}
+
+impl<'gc> TObject<'gc> for NetConnectionObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_netconnection(&self) -> Option<NetConnectionObject<'gc>> {
+        Some(*self)
+    }
+}
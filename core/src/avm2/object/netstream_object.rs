@@ -0,0 +1,100 @@
+//! Object representation for NetStream objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::rtmp::RtmpConnection;
+use crate::streams::NetStream;
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates NetStream objects.
+pub fn net_stream_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+    let stream = NetStream::new(activation.context.gc_context, None);
+
+    let this: Object<'gc> = NetStreamObject(GcCell::allocate(
+        activation.context.gc_context,
+        NetStreamObjectData {
+            base,
+            stream,
+            connection: None,
+        },
+    ))
+    .into();
+    stream.set_avm_object(activation.context.gc_context, this);
+
+    Ok(this)
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct NetStreamObject<'gc>(GcCell<'gc, NetStreamObjectData<'gc>>);
+
+impl fmt::Debug for NetStreamObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NetStreamObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+impl<'gc> NetStreamObject<'gc> {
+    pub fn stream(self) -> NetStream<'gc> {
+        self.0.read().stream
+    }
+
+    /// The RTMP connection of the `NetConnection` this stream was created with.
+    pub fn connection(self) -> Option<RtmpConnection<'gc>> {
+        self.0.read().connection
+    }
+
+    pub fn set_connection(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        connection: Option<RtmpConnection<'gc>>,
+    ) {
+        self.0.write(gc_context).connection = connection;
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct NetStreamObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The stream played by this object.
+    stream: NetStream<'gc>,
+
+    /// The RTMP connection of the `NetConnection` this stream was created with.
+    connection: Option<RtmpConnection<'gc>>,
+}
+
+impl<'gc> TObject<'gc> for NetStreamObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_netstream(&self) -> Option<NetStreamObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -46,6 +46,68 @@ pub struct ProxyObjectData<'gc> {
     base: ScriptObjectData<'gc>,
 }
 
+impl<'gc> ProxyObject<'gc> {
+    /// Box the name a property is accessed with into the `QName` passed to
+    /// the `flash_proxy` methods.
+    ///
+    /// Returns `None` if the name has no local name, or only namespaces that
+    /// can't be represented by a `QName`.
+    fn proxy_name(
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Option<Value<'gc>>, Error<'gc>> {
+        // NOTE: This is incorrect behavior.
+        // `QName` should instead store the whole multiname's namespace set,
+        // so that it can be used to index other objects using the same
+        // namespace set.
+        if let Some(local_name) = multiname.local_name() {
+            for namespace in multiname.namespace_set() {
+                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
+                    let qname =
+                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
+                    if let Some(qname) = qname.as_qname_object() {
+                        qname.set_is_attribute(
+                            activation.context.gc_context,
+                            multiname.is_attribute(),
+                        );
+                    }
+
+                    return Ok(Some(qname.into()));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Call one of the `flash_proxy` methods of this object.
+    fn call_proxy_method(
+        self,
+        method: &'static str,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        self.call_property(
+            &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), method),
+            arguments,
+            activation,
+        )
+    }
+
+    /// Implements the descendants operator (`..`) by calling
+    /// `flash_proxy::getDescendants`.
+    pub fn get_descendants(
+        self,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match Self::proxy_name(multiname, activation)? {
+            Some(name) => self.call_proxy_method("getDescendants", &[name], activation),
+            None => Ok(Value::Undefined),
+        }
+    }
+}
+
 impl<'gc> TObject<'gc> for ProxyObject<'gc> {
     fn base(&self) -> Ref<ScriptObjectData<'gc>> {
         Ref::map(self.0.read(), |read| &read.base)
@@ -68,23 +130,8 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         multiname: &Multiname<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    return self.call_property(
-                        &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "getProperty"),
-                        &[qname.into()],
-                        activation,
-                    );
-                }
-            }
+        if let Some(name) = Self::proxy_name(multiname, activation)? {
+            return self.call_proxy_method("getProperty", &[name], activation);
         }
 
         if !self
@@ -104,25 +151,10 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    self.call_property(
-                        &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "setProperty"),
-                        &[qname.into(), value],
-                        activation,
-                    )?;
+        if let Some(name) = Self::proxy_name(multiname, activation)? {
+            self.call_proxy_method("setProperty", &[name, value], activation)?;
 
-                    return Ok(());
-                }
-            }
+            return Ok(());
         }
 
         if !self
@@ -146,29 +178,11 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         arguments: &[Value<'gc>],
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
+        if let Some(name) = Self::proxy_name(multiname, activation)? {
+            let mut args = vec![name];
+            args.extend_from_slice(arguments);
 
-                    let mut args = vec![qname.into()];
-                    args.extend_from_slice(arguments);
-
-                    return self.call_property(
-                        &Multiname::new(
-                            Namespace::Namespace(NS_FLASH_PROXY.into()),
-                            "callProperty",
-                        ),
-                        &args[..],
-                        activation,
-                    );
-                }
-            }
+            return self.call_proxy_method("callProperty", &args[..], activation);
         }
 
         Err(format!(
@@ -183,28 +197,10 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         activation: &mut Activation<'_, 'gc>,
         multiname: &Multiname<'gc>,
     ) -> Result<bool, Error<'gc>> {
-        // NOTE: This is incorrect behavior.
-        // `QName` should instead store the whole multiname's namespace set,
-        // so that it can be used to index other objects using the same
-        // namespace set.
-        if let Some(local_name) = multiname.local_name() {
-            for namespace in multiname.namespace_set() {
-                if namespace.is_any() || namespace.is_public() || namespace.is_namespace() {
-                    let qname =
-                        QNameObject::from_qname(activation, QName::new(*namespace, local_name))?;
-
-                    return Ok(self
-                        .call_property(
-                            &Multiname::new(
-                                Namespace::Namespace(NS_FLASH_PROXY.into()),
-                                "deleteProperty",
-                            ),
-                            &[qname.into()],
-                            activation,
-                        )?
-                        .coerce_to_boolean());
-                }
-            }
+        if let Some(name) = Self::proxy_name(multiname, activation)? {
+            return Ok(self
+                .call_proxy_method("deleteProperty", &[name], activation)?
+                .coerce_to_boolean());
         }
 
         // Unknown properties on a dynamic class delete successfully.
@@ -219,13 +215,14 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         activation: &mut Activation<'_, 'gc>,
         name: &Multiname<'gc>,
     ) -> Result<bool, Error<'gc>> {
+        let local_name = match name.local_name() {
+            Some(local_name) => local_name,
+            None => return Ok(false),
+        };
+
+        // this should probably pass the multiname as-is? See above
         Ok(self
-            .call_property(
-                &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "hasProperty"),
-                // this should probably pass the multiname as-is? See above
-                &[name.local_name().unwrap().into()],
-                activation,
-            )?
+            .call_proxy_method("hasProperty", &[local_name.into()], activation)?
             .coerce_to_boolean())
     }
 
@@ -235,12 +232,8 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Option<u32>, Error<'gc>> {
         Ok(Some(
-            self.call_property(
-                &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "nextNameIndex"),
-                &[last_index.into()],
-                activation,
-            )?
-            .coerce_to_u32(activation)?,
+            self.call_proxy_method("nextNameIndex", &[last_index.into()], activation)?
+                .coerce_to_u32(activation)?,
         ))
     }
 
@@ -249,11 +242,7 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         index: u32,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        self.call_property(
-            &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "nextName"),
-            &[index.into()],
-            activation,
-        )
+        self.call_proxy_method("nextName", &[index.into()], activation)
     }
 
     fn get_enumerant_value(
@@ -261,10 +250,10 @@ impl<'gc> TObject<'gc> for ProxyObject<'gc> {
         index: u32,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        self.call_property(
-            &Multiname::new(Namespace::Namespace(NS_FLASH_PROXY.into()), "nextValue"),
-            &[index.into()],
-            activation,
-        )
+        self.call_proxy_method("nextValue", &[index.into()], activation)
+    }
+
+    fn as_proxy_object(&self) -> Option<ProxyObject<'gc>> {
+        Some(*self)
     }
 }
@@ -19,7 +19,11 @@ pub fn qname_allocator<'gc>(
 
     Ok(QNameObject(GcCell::allocate(
         activation.context.gc_context,
-        QNameObjectData { base, qname: None },
+        QNameObjectData {
+            base,
+            qname: None,
+            is_attribute: false,
+        },
     ))
     .into())
 }
@@ -45,6 +49,12 @@ pub struct QNameObjectData<'gc> {
 
     /// The QName name this object is associated with.
     qname: Option<QName<'gc>>,
+
+    /// Whether this name was used to access an attribute.
+    ///
+    /// This is only set on names passed to `flash.utils.Proxy` methods, and
+    /// can be queried with `flash_proxy::isAttribute`.
+    is_attribute: bool,
 }
 
 impl<'gc> QNameObject<'gc> {
@@ -61,6 +71,7 @@ impl<'gc> QNameObject<'gc> {
             QNameObjectData {
                 base,
                 qname: Some(qname),
+                is_attribute: false,
             },
         ))
         .into();
@@ -81,6 +92,14 @@ impl<'gc> QNameObject<'gc> {
     pub fn init_qname(self, mc: MutationContext<'gc, '_>, qname: QName<'gc>) {
         self.0.write(mc).qname = Some(qname);
     }
+
+    pub fn is_attribute(&self) -> bool {
+        self.0.read().is_attribute
+    }
+
+    pub fn set_is_attribute(self, mc: MutationContext<'gc, '_>, is_attribute: bool) {
+        self.0.write(mc).is_attribute = is_attribute;
+    }
 }
 
 impl<'gc> TObject<'gc> for QNameObject<'gc> {
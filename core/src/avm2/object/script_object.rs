@@ -445,6 +445,21 @@ impl<'gc> ScriptObjectData<'gc> {
         *self.bound_methods.get_mut(disp_id as usize).unwrap() = Some(function);
     }
 
+    /// The objects stored in the dynamic properties and slots of this object,
+    /// with the names they are stored under.
+    pub fn stored_objects(&self) -> Vec<(String, Object<'gc>)> {
+        let values = self
+            .values
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.as_object()?)));
+        let slots = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(id, value)| Some((format!("[slot {id}]"), value.as_object()?)));
+        values.chain(slots).collect()
+    }
+
     /// Get the class object for this object, if it has one.
     pub fn instance_of(&self) -> Option<ClassObject<'gc>> {
         self.instance_of
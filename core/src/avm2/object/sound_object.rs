@@ -2,13 +2,16 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::object::script_object::ScriptObjectData;
-use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, SoundChannelObject, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::backend::audio::SoundHandle;
+use crate::context::UpdateContext;
+use crate::string::AvmString;
 use core::fmt;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
+use swf::SoundInfo;
 
 /// A class instance allocator that allocates Sound objects.
 pub fn sound_allocator<'gc>(
@@ -19,7 +22,15 @@ pub fn sound_allocator<'gc>(
 
     Ok(SoundObject(GcCell::allocate(
         activation.context.gc_context,
-        SoundObjectData { base, sound: None },
+        SoundObjectData {
+            base,
+            sound: None,
+            extract_position: 0,
+            load_progress: None,
+            url: None,
+            id3: None,
+            queued_plays: Vec::new(),
+        },
     ))
     .into())
 }
@@ -45,6 +56,36 @@ pub struct SoundObjectData<'gc> {
     /// The sound this object holds.
     #[collect(require_static)]
     sound: Option<SoundHandle>,
+
+    /// The 44.1KHz sample frame following the last one read by `Sound.extract`.
+    extract_position: u32,
+
+    /// The number of bytes downloaded by `Sound.load`, and the size of the whole file.
+    ///
+    /// `None` unless the sound is loaded from a URL.
+    load_progress: Option<(u32, u32)>,
+
+    /// The URL of the file loaded by `Sound.load`.
+    url: Option<AvmString<'gc>>,
+
+    /// The ID3 metadata of the loaded MP3, as an `ID3Info` object.
+    id3: Option<Object<'gc>>,
+
+    /// The `Sound.play` calls made before the loading sound had anything to play.
+    queued_plays: Vec<QueuedPlay<'gc>>,
+}
+
+/// A `Sound.play` call waiting for the sound to receive its first data.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct QueuedPlay<'gc> {
+    #[collect(require_static)]
+    pub settings: SoundInfo,
+
+    /// The channel returned by the call, which gets the sound instance once it starts.
+    ///
+    /// Its sound transform is applied to the sound once it starts.
+    pub sound_channel: SoundChannelObject<'gc>,
 }
 
 impl<'gc> SoundObject<'gc> {
@@ -65,6 +106,11 @@ impl<'gc> SoundObject<'gc> {
             SoundObjectData {
                 base,
                 sound: Some(sound),
+                extract_position: 0,
+                load_progress: None,
+                url: None,
+                id3: None,
+                queued_plays: Vec::new(),
             },
         ))
         .into();
@@ -74,6 +120,71 @@ impl<'gc> SoundObject<'gc> {
 
         Ok(sound_object)
     }
+
+    /// The 44.1KHz sample frame that `Sound.extract` continues reading from by default.
+    pub fn extract_position(self) -> u32 {
+        self.0.read().extract_position
+    }
+
+    pub fn set_extract_position(self, mc: MutationContext<'gc, '_>, position: u32) {
+        self.0.write(mc).extract_position = position;
+    }
+
+    pub fn load_progress(self) -> Option<(u32, u32)> {
+        self.0.read().load_progress
+    }
+
+    pub fn set_load_progress(self, mc: MutationContext<'gc, '_>, loaded: u32, total: u32) {
+        self.0.write(mc).load_progress = Some((loaded, total));
+    }
+
+    pub fn url(self) -> Option<AvmString<'gc>> {
+        self.0.read().url
+    }
+
+    pub fn set_url(self, mc: MutationContext<'gc, '_>, url: AvmString<'gc>) {
+        self.0.write(mc).url = Some(url);
+    }
+
+    pub fn id3(self) -> Option<Object<'gc>> {
+        self.0.read().id3
+    }
+
+    pub fn set_id3(self, mc: MutationContext<'gc, '_>, id3: Object<'gc>) {
+        self.0.write(mc).id3 = Some(id3);
+    }
+
+    /// Whether `Sound.load` was called, but no data was received yet.
+    pub fn is_waiting_for_data(self) -> bool {
+        let read = self.0.read();
+        read.load_progress.is_some() && read.sound.is_none()
+    }
+
+    /// Remembers a `Sound.play` call to start once the sound receives its first data.
+    pub fn queue_play(self, mc: MutationContext<'gc, '_>, play: QueuedPlay<'gc>) {
+        self.0.write(mc).queued_plays.push(play);
+    }
+
+    /// Starts the sounds queued by `Sound.play` calls made before the sound had any data.
+    ///
+    /// Calls the sound can't be started for are dropped, like when the load failed.
+    pub fn start_queued_plays(self, context: &mut UpdateContext<'_, 'gc>) {
+        let queued_plays = std::mem::take(&mut self.0.write(context.gc_context).queued_plays);
+        let sound = match self.0.read().sound {
+            Some(sound) => sound,
+            None => return,
+        };
+
+        for play in queued_plays {
+            if let Some(instance) = context.start_avm2_sound(sound, &play.settings) {
+                let sound_transform = play.sound_channel.sound_transform();
+                context.set_local_sound_transform(instance, sound_transform);
+                play.sound_channel
+                    .set_sound_instance(context.gc_context, instance);
+                context.attach_avm2_sound_channel(instance, play.sound_channel);
+            }
+        }
+    }
 }
 
 impl<'gc> TObject<'gc> for SoundObject<'gc> {
@@ -97,6 +208,10 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
         self.0.read().sound
     }
 
+    fn as_sound_object(self) -> Option<SoundObject<'gc>> {
+        Some(self)
+    }
+
     /// Associate the object with a particular sound handle.
     ///
     /// This does nothing if the object is not a sound.
@@ -6,6 +6,7 @@ use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::backend::audio::SoundInstanceHandle;
+use crate::display_object::SoundTransform;
 use core::fmt;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
@@ -23,6 +24,7 @@ pub fn soundchannel_allocator<'gc>(
             base,
             sound: None,
             position: 0.0,
+            sound_transform: SoundTransform::default(),
         },
     ))
     .into())
@@ -52,6 +54,12 @@ pub struct SoundChannelObjectData<'gc> {
 
     /// Position of the last playing sound in milliseconds.
     position: f64,
+
+    /// The sound transform of this channel.
+    ///
+    /// This is kept here rather than only on the sound instance, so that it can be read and
+    /// changed before the sound starts and after it stops.
+    sound_transform: SoundTransform,
 }
 
 impl<'gc> SoundChannelObject<'gc> {
@@ -59,6 +67,22 @@ impl<'gc> SoundChannelObject<'gc> {
     pub fn from_sound_instance(
         activation: &mut Activation<'_, 'gc>,
         sound: SoundInstanceHandle,
+    ) -> Result<Self, Error<'gc>> {
+        Self::new(activation, Some(sound))
+    }
+
+    /// Creates a channel for a sound instance that isn't started yet.
+    ///
+    /// The instance is given to it with `set_sound_instance` once it starts.
+    pub fn without_sound_instance(
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Self, Error<'gc>> {
+        Self::new(activation, None)
+    }
+
+    fn new(
+        activation: &mut Activation<'_, 'gc>,
+        sound: Option<SoundInstanceHandle>,
     ) -> Result<Self, Error<'gc>> {
         let class = activation.avm2().classes().soundchannel;
         let base = ScriptObjectData::new(class);
@@ -67,8 +91,9 @@ impl<'gc> SoundChannelObject<'gc> {
             activation.context.gc_context,
             SoundChannelObjectData {
                 base,
-                sound: Some(sound),
+                sound,
                 position: 0.0,
+                sound_transform: SoundTransform::default(),
             },
         ));
         sound_object.install_instance_slots(activation);
@@ -92,6 +117,23 @@ impl<'gc> SoundChannelObject<'gc> {
     pub fn set_position(self, mc: MutationContext<'gc, '_>, value: f64) {
         self.0.write(mc).position = value;
     }
+
+    /// Return the sound transform of this channel.
+    pub fn sound_transform(self) -> SoundTransform {
+        self.0.read().sound_transform.clone()
+    }
+
+    /// Set the sound transform of this channel.
+    ///
+    /// This doesn't apply it to the playing sound instance, which is done with
+    /// `UpdateContext::set_local_sound_transform`.
+    pub fn set_sound_transform(
+        self,
+        mc: MutationContext<'gc, '_>,
+        sound_transform: SoundTransform,
+    ) {
+        self.0.write(mc).sound_transform = sound_transform;
+    }
 }
 
 impl<'gc> TObject<'gc> for SoundChannelObject<'gc> {
@@ -0,0 +1,160 @@
+//! Object representation for StageVideo objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::streams::NetStream;
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+use swf::{Rectangle, Twips};
+
+/// A class instance allocator that allocates StageVideo objects.
+pub fn stage_video_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(StageVideoObject(GcCell::allocate(
+        activation.context.gc_context,
+        StageVideoObjectData {
+            base,
+            stream: None,
+            view_port: Default::default(),
+            depth: 0,
+            pan: (0.0, 0.0),
+            zoom: (1.0, 1.0),
+            reported_size: None,
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct StageVideoObject<'gc>(GcCell<'gc, StageVideoObjectData<'gc>>);
+
+impl fmt::Debug for StageVideoObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StageVideoObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+impl<'gc> StageVideoObject<'gc> {
+    pub fn stream(self) -> Option<NetStream<'gc>> {
+        self.0.read().stream
+    }
+
+    pub fn set_stream(self, mc: MutationContext<'gc, '_>, stream: Option<NetStream<'gc>>) {
+        let mut write = self.0.write(mc);
+        write.stream = stream;
+        write.reported_size = None;
+    }
+
+    /// The area of the stage the video is drawn in.
+    pub fn view_port(self) -> Rectangle<Twips> {
+        self.0.read().view_port.clone()
+    }
+
+    pub fn set_view_port(self, mc: MutationContext<'gc, '_>, view_port: Rectangle<Twips>) {
+        self.0.write(mc).view_port = view_port;
+    }
+
+    /// The drawing order of this object among the other StageVideo objects.
+    pub fn depth(self) -> i32 {
+        self.0.read().depth
+    }
+
+    pub fn set_depth(self, mc: MutationContext<'gc, '_>, depth: i32) {
+        self.0.write(mc).depth = depth;
+    }
+
+    pub fn pan(self) -> (f64, f64) {
+        self.0.read().pan
+    }
+
+    pub fn set_pan(self, mc: MutationContext<'gc, '_>, pan: (f64, f64)) {
+        self.0.write(mc).pan = pan;
+    }
+
+    pub fn zoom(self) -> (f64, f64) {
+        self.0.read().zoom
+    }
+
+    pub fn set_zoom(self, mc: MutationContext<'gc, '_>, zoom: (f64, f64)) {
+        self.0.write(mc).zoom = zoom;
+    }
+
+    /// Records the size of the video playing in this object.
+    ///
+    /// Returns `true` if it changed since the last call, which requires a
+    /// `renderState` event.
+    pub fn update_reported_size(
+        self,
+        mc: MutationContext<'gc, '_>,
+        size: Option<(u16, u16)>,
+    ) -> bool {
+        let mut write = self.0.write(mc);
+        if size.is_none() || write.reported_size == size {
+            return false;
+        }
+        write.reported_size = size;
+        true
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct StageVideoObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The stream attached with `attachNetStream`, if any.
+    stream: Option<NetStream<'gc>>,
+
+    /// The area of the stage the video is drawn in.
+    #[collect(require_static)]
+    view_port: Rectangle<Twips>,
+
+    /// The drawing order among the other StageVideo objects; higher depths are drawn on top.
+    depth: i32,
+
+    /// The part of the zoomed video shown in the view port, which isn't applied yet.
+    #[collect(require_static)]
+    pan: (f64, f64),
+
+    /// The magnification of the video, which isn't applied yet.
+    #[collect(require_static)]
+    zoom: (f64, f64),
+
+    /// The video size last reported with a `renderState` event.
+    #[collect(require_static)]
+    reported_size: Option<(u16, u16)>,
+}
+
+impl<'gc> TObject<'gc> for StageVideoObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_stage_video(&self) -> Option<StageVideoObject<'gc>> {
+        Some(*self)
+    }
+}
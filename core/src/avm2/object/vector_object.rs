@@ -109,10 +109,7 @@ impl<'gc> TObject<'gc> for VectorObject<'gc> {
         if name.contains_public_namespace() {
             if let Some(name) = name.local_name() {
                 if let Ok(index) = name.parse::<usize>() {
-                    return Ok(read
-                        .vector
-                        .get(index, activation)
-                        .unwrap_or(Value::Undefined));
+                    return read.vector.get(index, activation);
                 }
             }
         }
@@ -0,0 +1,463 @@
+//! Object representation for XMLList objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::e4x::{self, E4XNode, XmlSettings};
+use crate::avm2::error::type_error;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::xml_object::{
+    call_xml_method, name_to_index, nodes_from_value, set_text_content, Replacement,
+};
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject, XmlObject};
+use crate::avm2::value::Value;
+use crate::avm2::{Error, Multiname};
+use crate::string::{AvmString, WString};
+use core::fmt;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates XMLList objects.
+pub fn xml_list_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(XmlListObject(GcCell::allocate(
+        activation.context.gc_context,
+        XmlListObjectData {
+            base,
+            children: Vec::new(),
+            target_object: None,
+            target_property: None,
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct XmlListObject<'gc>(GcCell<'gc, XmlListObjectData<'gc>>);
+
+impl fmt::Debug for XmlListObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XmlListObject")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct XmlListObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The nodes in this list.
+    children: Vec<E4XNode<'gc>>,
+
+    /// The XML or XMLList object this list was read from, if any.
+    ///
+    /// Assigning to an index past the end of the list adds the new node to this object.
+    target_object: Option<Object<'gc>>,
+
+    /// The name this list was read with from `target_object`.
+    target_property: Option<Multiname<'gc>>,
+}
+
+impl<'gc> XmlListObject<'gc> {
+    pub fn new(
+        activation: &mut Activation<'_, 'gc>,
+        children: Vec<E4XNode<'gc>>,
+        target_object: Option<Object<'gc>>,
+        target_property: Option<Multiname<'gc>>,
+    ) -> Self {
+        let class = activation.avm2().classes().xml_list;
+        let mut this = XmlListObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlListObjectData {
+                base: ScriptObjectData::new(class),
+                children,
+                target_object,
+                target_property,
+            },
+        ));
+        this.install_instance_slots(activation);
+
+        this
+    }
+
+    pub fn length(&self) -> usize {
+        self.0.read().children.len()
+    }
+
+    pub fn children(&self) -> Vec<E4XNode<'gc>> {
+        self.0.read().children.clone()
+    }
+
+    pub fn set_children(&self, mc: MutationContext<'gc, '_>, children: Vec<E4XNode<'gc>>) {
+        self.0.write(mc).children = children;
+    }
+
+    pub fn push(&self, mc: MutationContext<'gc, '_>, child: E4XNode<'gc>) {
+        self.0.write(mc).children.push(child);
+    }
+
+    /// Returns an XML object for the item at an index.
+    pub fn item(
+        &self,
+        index: usize,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Option<XmlObject<'gc>> {
+        let node = self.0.read().children.get(index).copied()?;
+        Some(XmlObject::new(node, activation))
+    }
+
+    /// Implements the `ToString` conversion of E4X.
+    ///
+    /// A list with simple content is converted to the text of its items, otherwise to markup.
+    pub fn xml_to_string(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let children = self.children();
+        if children.iter().all(|child| child.has_simple_content()) {
+            let mc = activation.context.gc_context;
+            let settings = XmlSettings::from_xml_class(activation)?;
+            let mut text = WString::new();
+            for child in children {
+                if !child.is_comment() && !child.is_processing_instruction() {
+                    text.push_str(&child.xml_to_string(mc, &settings));
+                }
+            }
+            return Ok(AvmString::new(mc, text));
+        }
+
+        self.xml_to_xml_string(activation)
+    }
+
+    /// Implements the `ToXMLString` conversion of E4X, which puts each item on its own line.
+    pub fn xml_to_xml_string(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let mc = activation.context.gc_context;
+        let settings = XmlSettings::from_xml_class(activation)?;
+        let mut out = WString::new();
+        for (index, child) in self.children().into_iter().enumerate() {
+            if index > 0 && settings.pretty_printing {
+                out.push_byte(b'\n');
+            }
+            out.push_str(&child.xml_to_xml_string(mc, &settings));
+        }
+        Ok(AvmString::new(mc, out))
+    }
+
+    /// Returns the children, or attributes, of the items of this list matching a name.
+    pub fn get_matching(
+        self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> XmlListObject<'gc> {
+        let name = e4x::node_name(activation.context.gc_context, name);
+        let mut matching = Vec::new();
+        for child in self.children() {
+            matching.extend(child.get_matching(&name));
+        }
+        XmlListObject::new(activation, matching, Some(self.into()), Some(name))
+    }
+
+    /// Returns the element this list was read from.
+    fn target_node(&self, mc: MutationContext<'gc, '_>) -> Option<E4XNode<'gc>> {
+        let target = self.0.read().target_object?;
+        if let Some(xml) = target.as_xml_object() {
+            return Some(xml.node());
+        }
+        target.as_xml_list_object()?.resolve_value(mc)
+    }
+
+    /// Implements `ResolveValue` from ECMA-357 (9.2.1.10).
+    ///
+    /// This returns the single item of the list. An empty list read from an element, like
+    /// `xml.missing`, gets a new element with the name it was read with, so that it can be
+    /// assigned to.
+    fn resolve_value(&self, mc: MutationContext<'gc, '_>) -> Option<E4XNode<'gc>> {
+        match self.length() {
+            0 => {}
+            1 => return self.children().first().copied(),
+            _ => return None,
+        }
+
+        let target = self.target_node(mc)?;
+        let name = self.0.read().target_property.clone()?;
+        if name.is_attribute() {
+            return None;
+        }
+        let element = E4XNode::element(mc, e4x::namespace_of_name(&name), name.local_name()?, None);
+        target.append_child(mc, element);
+        self.push(mc, element);
+        Some(element)
+    }
+
+    /// Implements assignment to an index of this list, as in `list[index] = value`.
+    fn put_index(
+        self,
+        index: usize,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let mc = activation.context.gc_context;
+        let existing = self.0.read().children.get(index).copied();
+
+        if let Some(existing) = existing {
+            let replacement = nodes_from_value(value, activation)?;
+            match (existing.parent(), replacement) {
+                (Some(parent), Replacement::Nodes(nodes)) if !existing.is_attribute() => {
+                    if let Some(child_index) = existing.child_index() {
+                        parent.replace_child(mc, child_index, &nodes);
+                    }
+                    let mut write = self.0.write(mc);
+                    write.children.splice(index..=index, nodes);
+                }
+                (_, Replacement::Nodes(nodes)) => {
+                    let mut write = self.0.write(mc);
+                    write.children.splice(index..=index, nodes);
+                }
+                (_, Replacement::Text(text)) => set_text_content(mc, existing, text),
+            }
+            return Ok(());
+        }
+
+        // Items added to a list that was read from an element are added to that element, after
+        // the last item of the list.
+        let target = self.target_node(mc);
+        let target_property = self.0.read().target_property.clone();
+        let nodes = match (target, target_property) {
+            (Some(target), Some(name)) if !name.is_attribute() => {
+                let nodes = match nodes_from_value(value, activation)? {
+                    Replacement::Nodes(nodes) => nodes,
+                    Replacement::Text(text) => {
+                        let local_name = match name.local_name() {
+                            Some(local_name) => local_name,
+                            None => return Ok(()),
+                        };
+                        let element =
+                            E4XNode::element(mc, e4x::namespace_of_name(&name), local_name, None);
+                        set_text_content(mc, element, text);
+                        vec![element]
+                    }
+                };
+
+                let mut insert_at = self
+                    .children()
+                    .last()
+                    .and_then(|last| last.child_index())
+                    .map(|last_index| last_index + 1)
+                    .unwrap_or_else(|| target.length());
+                for node in &nodes {
+                    target.insert_child(mc, insert_at, *node);
+                    insert_at += 1;
+                }
+                nodes
+            }
+            // Lists without a target, like the result of a filter, keep the nodes they are given.
+            _ => match value.as_object() {
+                Some(object) if object.as_xml_object().is_some() => {
+                    vec![object.as_xml_object().unwrap().node()]
+                }
+                Some(object) if object.as_xml_list_object().is_some() => {
+                    object.as_xml_list_object().unwrap().children()
+                }
+                _ => vec![E4XNode::text(mc, value.coerce_to_string(activation)?, None)],
+            },
+        };
+
+        self.0.write(mc).children.extend(nodes);
+        Ok(())
+    }
+
+    fn single_item_error(
+        &self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Error<'gc> {
+        let message = format!(
+            "Error #1086: The {} method only works on lists containing one item.",
+            name.local_name().unwrap_or_default()
+        );
+        match type_error(activation, &message, 1086) {
+            Ok(error) => Error::AvmError(error),
+            Err(error) => error,
+        }
+    }
+}
+
+impl<'gc> TObject<'gc> for XmlListObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn get_property_local(
+        self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(index) = name_to_index(name) {
+            return Ok(self
+                .item(index, activation)
+                .map(Value::from)
+                .unwrap_or(Value::Undefined));
+        }
+
+        Ok(self.get_matching(name, activation).into())
+    }
+
+    fn set_property_local(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        if let Some(index) = name_to_index(name) {
+            return self.put_index(index, value, activation);
+        }
+
+        if self.resolve_value(activation.context.gc_context).is_none() && self.length() == 0 {
+            return Ok(());
+        }
+
+        match self.item(0, activation) {
+            Some(item) if self.length() == 1 => {
+                Object::from(item).set_property(name, value, activation)
+            }
+            _ => Err(Error::AvmError(type_error(
+                activation,
+                "Error #1089: Assignment to lists with more than one item is not supported.",
+                1089,
+            )?)),
+        }
+    }
+
+    fn init_property_local(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        self.set_property_local(name, value, activation)
+    }
+
+    fn call_property_local(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(result) = call_xml_method(self.into(), multiname, arguments, activation)? {
+            return Ok(result);
+        }
+
+        // Methods of `XML` can be called on lists with a single item.
+        match self.item(0, activation) {
+            Some(item) if self.length() == 1 => {
+                item.call_property(multiname, arguments, activation)
+            }
+            _ => Err(self.single_item_error(multiname, activation)),
+        }
+    }
+
+    fn delete_property_local(
+        self,
+        activation: &mut Activation<'_, 'gc>,
+        name: &Multiname<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        let mc = activation.context.gc_context;
+        if let Some(index) = name_to_index(name) {
+            let removed = {
+                let mut write = self.0.write(mc);
+                if index < write.children.len() {
+                    Some(write.children.remove(index))
+                } else {
+                    None
+                }
+            };
+            if let Some(removed) = removed {
+                if let Some(parent) = removed.parent() {
+                    parent.remove_child(mc, removed);
+                }
+            }
+            return Ok(true);
+        }
+
+        let name = e4x::node_name(mc, name);
+        for child in self.children() {
+            for matching in child.get_matching(&name) {
+                child.remove_child(mc, matching);
+            }
+        }
+        Ok(true)
+    }
+
+    fn has_own_property(self, name: &Multiname<'gc>) -> bool {
+        if let Some(index) = name_to_index(name) {
+            return index < self.length();
+        }
+
+        self.children().into_iter().any(|child| {
+            let nodes = if name.is_attribute() {
+                child.attributes()
+            } else {
+                child.children()
+            };
+            nodes.iter().any(|node| node.matches_name(name))
+        })
+    }
+
+    fn get_next_enumerant(
+        self,
+        last_index: u32,
+        _activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Option<u32>, Error<'gc>> {
+        if (last_index as usize) < self.length() {
+            Ok(Some(last_index.saturating_add(1)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn get_enumerant_name(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if (index as usize) <= self.length() {
+            Ok(index
+                .checked_sub(1)
+                .map(|index| index.into())
+                .unwrap_or(Value::Undefined))
+        } else {
+            Ok(Value::Undefined)
+        }
+    }
+
+    fn property_is_enumerable(&self, name: AvmString<'gc>) -> bool {
+        name.parse::<usize>()
+            .map(|index| index < self.length())
+            .unwrap_or(false)
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_xml_list_object(&self) -> Option<Self> {
+        Some(*self)
+    }
+}
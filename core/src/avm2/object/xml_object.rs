@@ -1,10 +1,13 @@
 //! Object representation for XML objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::e4x::{self, E4XNode, XmlSettings};
+use crate::avm2::error::type_error;
 use crate::avm2::object::script_object::ScriptObjectData;
-use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject, XmlListObject};
 use crate::avm2::value::Value;
-use crate::avm2::Error;
+use crate::avm2::{Error, Multiname, Namespace};
+use crate::string::{AvmString, WString};
 use core::fmt;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
@@ -15,10 +18,11 @@ pub fn xml_allocator<'gc>(
     activation: &mut Activation<'_, 'gc>,
 ) -> Result<Object<'gc>, Error<'gc>> {
     let base = ScriptObjectData::new(class);
+    let node = E4XNode::text(activation.context.gc_context, "".into(), None);
 
     Ok(XmlObject(GcCell::allocate(
         activation.context.gc_context,
-        XmlObjectData { base },
+        XmlObjectData { base, node },
     ))
     .into())
 }
@@ -40,6 +44,232 @@ impl fmt::Debug for XmlObject<'_> {
 pub struct XmlObjectData<'gc> {
     /// Base script object
     base: ScriptObjectData<'gc>,
+
+    /// The node this object refers to.
+    node: E4XNode<'gc>,
+}
+
+impl<'gc> XmlObject<'gc> {
+    /// Create an XML object referring to a node.
+    pub fn new(node: E4XNode<'gc>, activation: &mut Activation<'_, 'gc>) -> Self {
+        let class = activation.avm2().classes().xml;
+        let mut this = XmlObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlObjectData {
+                base: ScriptObjectData::new(class),
+                node,
+            },
+        ));
+        this.install_instance_slots(activation);
+
+        this
+    }
+
+    pub fn node(&self) -> E4XNode<'gc> {
+        self.0.read().node
+    }
+
+    pub fn set_node(&self, mc: MutationContext<'gc, '_>, node: E4XNode<'gc>) {
+        self.0.write(mc).node = node;
+    }
+
+    /// Implements the `ToString` conversion of E4X.
+    pub fn xml_to_string(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let settings = XmlSettings::from_xml_class(activation)?;
+        Ok(self
+            .node()
+            .xml_to_string(activation.context.gc_context, &settings))
+    }
+
+    /// Implements the `ToXMLString` conversion of E4X.
+    pub fn xml_to_xml_string(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let settings = XmlSettings::from_xml_class(activation)?;
+        Ok(self
+            .node()
+            .xml_to_xml_string(activation.context.gc_context, &settings))
+    }
+
+    /// Returns the children, or attributes, of this XML object matching a name as a list.
+    pub fn get_matching(
+        self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> XmlListObject<'gc> {
+        let name = e4x::node_name(activation.context.gc_context, name);
+        let children = self.node().get_matching(&name);
+        XmlListObject::new(activation, children, Some(self.into()), Some(name))
+    }
+
+    /// Implements the E4X `[[Put]]` operation for the children and attributes of this element.
+    fn put(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let mc = activation.context.gc_context;
+        let node = self.node();
+        if !node.is_element() {
+            return Ok(());
+        }
+
+        let name = e4x::node_name(mc, name);
+        if name.is_attribute() {
+            // An attribute set to a list takes the values of the list, separated by spaces.
+            let value = match value.as_object().and_then(|o| o.as_xml_list_object()) {
+                Some(list) => {
+                    let mut joined = WString::new();
+                    for (index, child) in list.children().into_iter().enumerate() {
+                        if index > 0 {
+                            joined.push_byte(b' ');
+                        }
+                        joined.push_str(&child.xml_to_string(mc, &XmlSettings::default()));
+                    }
+                    AvmString::new(mc, joined)
+                }
+                None => value.coerce_to_string(activation)?,
+            };
+            node.set_attribute(mc, &name, value);
+            return Ok(());
+        }
+
+        let replacement = nodes_from_value(value, activation)?;
+        let matching = node.get_matching(&name);
+
+        // Only the last child with the name is kept, and replaced with the value.
+        let index = match matching.split_last() {
+            Some((last, rest)) => {
+                for child in rest {
+                    node.remove_child(mc, *child);
+                }
+                last.child_index()
+            }
+            None => None,
+        };
+
+        match (index, replacement) {
+            (Some(index), Replacement::Nodes(nodes)) => node.replace_child(mc, index, &nodes),
+            (Some(index), Replacement::Text(text)) => {
+                let child = node.children()[index];
+                set_text_content(mc, child, text);
+            }
+            (None, Replacement::Nodes(nodes)) if name.local_name().is_none() => {
+                for child in nodes {
+                    node.append_child(mc, child);
+                }
+            }
+            (None, replacement) => {
+                let local_name = match name.local_name() {
+                    Some(local_name) => local_name,
+                    None => return Ok(()),
+                };
+                let child = E4XNode::element(mc, e4x::namespace_of_name(&name), local_name, None);
+                node.append_child(mc, child);
+                match replacement {
+                    Replacement::Nodes(nodes) => node.replace_child(mc, node.length() - 1, &nodes),
+                    Replacement::Text(text) => set_text_content(mc, child, text),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What a child of an element is replaced with when it's assigned to.
+pub enum Replacement<'gc> {
+    /// The child keeps its name, and has only this text as content.
+    Text(AvmString<'gc>),
+
+    /// The child is replaced by these nodes.
+    Nodes(Vec<E4XNode<'gc>>),
+}
+
+/// Converts a value assigned to a child of an element to what it should be replaced with.
+///
+/// XML values are copied, unless they are text or attributes, which are assigned as text.
+pub fn nodes_from_value<'gc>(
+    value: Value<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Replacement<'gc>, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    if let Some(object) = value.as_object() {
+        if let Some(xml) = object.as_xml_object() {
+            let node = xml.node();
+            if !node.is_text() && !node.is_attribute() {
+                return Ok(Replacement::Nodes(vec![node.deep_copy(mc)]));
+            }
+        } else if let Some(list) = object.as_xml_list_object() {
+            return Ok(Replacement::Nodes(
+                list.children()
+                    .into_iter()
+                    .map(|child| child.deep_copy(mc))
+                    .collect(),
+            ));
+        }
+    }
+
+    Ok(Replacement::Text(value.coerce_to_string(activation)?))
+}
+
+/// Replaces the content of a node with some text.
+pub fn set_text_content<'gc>(
+    mc: MutationContext<'gc, '_>,
+    node: E4XNode<'gc>,
+    text: AvmString<'gc>,
+) {
+    if node.is_element() {
+        node.remove_children(mc);
+        if !text.is_empty() {
+            node.append_child(mc, E4XNode::text(mc, text, None));
+        }
+    } else {
+        node.set_value(mc, text);
+    }
+}
+
+/// Returns the index named by a property name, like `xml[0]`.
+pub fn name_to_index(name: &Multiname<'_>) -> Option<usize> {
+    if name.is_attribute() || !name.contains_public_namespace() {
+        return None;
+    }
+    name.local_name()?.parse::<usize>().ok()
+}
+
+/// Calls a method on an XML or XMLList object.
+///
+/// The methods of `XML` and `XMLList` are declared in the `AS3` namespace, which code doesn't
+/// always open, so they are looked up there first. Methods defined on the prototype come next.
+pub fn call_xml_method<'gc>(
+    this: Object<'gc>,
+    multiname: &Multiname<'gc>,
+    arguments: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc>,
+) -> Result<Option<Value<'gc>>, Error<'gc>> {
+    if let Some(local_name) = multiname.local_name() {
+        let as3_name = Multiname::new(Namespace::as3_namespace(), local_name);
+        if let Some(property) = this.vtable().and_then(|vtable| vtable.get_trait(&as3_name)) {
+            return this
+                .call_resolved_property(Some(property), &as3_name, arguments, activation)
+                .map(Some);
+        }
+    }
+
+    if let Some(proto) = this.proto() {
+        if let Value::Object(method) = proto.get_property(multiname, activation)? {
+            if method.as_executable().is_some() {
+                return method.call(Some(this), arguments, activation).map(Some);
+            }
+        }
+    }
+
+    Ok(None)
 }
 
 impl<'gc> TObject<'gc> for XmlObject<'gc> {
@@ -55,11 +285,133 @@ impl<'gc> TObject<'gc> for XmlObject<'gc> {
         self.0.as_ptr() as *const ObjectPtr
     }
 
+    fn get_property_local(
+        self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        // An XML object acts like a list containing itself.
+        if let Some(index) = name_to_index(name) {
+            return Ok(if index == 0 {
+                self.into()
+            } else {
+                Value::Undefined
+            });
+        }
+
+        Ok(self.get_matching(name, activation).into())
+    }
+
+    fn set_property_local(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        if name_to_index(name).is_some() {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #1087: Assignment to indexed XML is not allowed.",
+                1087,
+            )?));
+        }
+
+        self.put(name, value, activation)
+    }
+
+    fn init_property_local(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        self.set_property_local(name, value, activation)
+    }
+
+    fn call_property_local(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(result) = call_xml_method(self.into(), multiname, arguments, activation)? {
+            return Ok(result);
+        }
+
+        // Elements with simple content can be used like strings, as in `xml.name.charAt(0)`.
+        if self.node().has_simple_content() {
+            let string = Value::from(self.xml_to_string(activation)?);
+            return string
+                .coerce_to_object(activation)?
+                .call_property(multiname, arguments, activation);
+        }
+
+        Value::Undefined
+            .as_callable(activation, Some(multiname), Some(self.into()))?
+            .call(Some(self.into()), arguments, activation)
+    }
+
+    fn delete_property_local(
+        self,
+        activation: &mut Activation<'_, 'gc>,
+        name: &Multiname<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        if name_to_index(name).is_some() {
+            return Ok(false);
+        }
+
+        let mc = activation.context.gc_context;
+        let node = self.node();
+        for child in node.get_matching(&e4x::node_name(mc, name)) {
+            node.remove_child(mc, child);
+        }
+        Ok(true)
+    }
+
+    fn has_own_property(self, name: &Multiname<'gc>) -> bool {
+        if let Some(index) = name_to_index(name) {
+            return index == 0;
+        }
+
+        let node = self.node();
+        let nodes = if name.is_attribute() {
+            node.attributes()
+        } else {
+            node.children()
+        };
+        // Names given as strings aren't handled here, as converting them requires allocation.
+        nodes.iter().any(|child| child.matches_name(name))
+    }
+
+    fn get_next_enumerant(
+        self,
+        last_index: u32,
+        _activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Option<u32>, Error<'gc>> {
+        Ok(if last_index == 0 { Some(1) } else { None })
+    }
+
+    fn get_enumerant_name(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(if index == 1 {
+            0.into()
+        } else {
+            Value::Undefined
+        })
+    }
+
+    fn property_is_enumerable(&self, name: AvmString<'gc>) -> bool {
+        &name == b"0"
+    }
+
     fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
         Ok(Value::Object(Object::from(*self)))
     }
 
-    fn as_xml(&self) -> Option<Self> {
+    fn as_xml_object(&self) -> Option<Self> {
         Some(*self)
     }
 }
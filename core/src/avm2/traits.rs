@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::metadata::Metadata;
 use crate::avm2::method::Method;
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::value::{abc_default_value, Value};
@@ -50,6 +51,9 @@ pub struct Trait<'gc> {
 
     /// The kind of trait in use.
     kind: TraitKind<'gc>,
+
+    /// The metadata tags declared on this trait.
+    metadata: Vec<Metadata<'gc>>,
 }
 
 fn trait_attribs_from_abc_traits(abc_trait: &AbcTrait) -> TraitAttributes {
@@ -112,6 +116,7 @@ impl<'gc> Trait<'gc> {
             name,
             attributes: TraitAttributes::empty(),
             kind: TraitKind::Class { slot_id: 0, class },
+            metadata: Vec::new(),
         }
     }
 
@@ -120,6 +125,7 @@ impl<'gc> Trait<'gc> {
             name,
             attributes: TraitAttributes::empty(),
             kind: TraitKind::Method { disp_id: 0, method },
+            metadata: Vec::new(),
         }
     }
 
@@ -128,6 +134,7 @@ impl<'gc> Trait<'gc> {
             name,
             attributes: TraitAttributes::empty(),
             kind: TraitKind::Getter { disp_id: 0, method },
+            metadata: Vec::new(),
         }
     }
 
@@ -136,6 +143,7 @@ impl<'gc> Trait<'gc> {
             name,
             attributes: TraitAttributes::empty(),
             kind: TraitKind::Setter { disp_id: 0, method },
+            metadata: Vec::new(),
         }
     }
 
@@ -147,6 +155,7 @@ impl<'gc> Trait<'gc> {
                 slot_id: 0,
                 function,
             },
+            metadata: Vec::new(),
         }
     }
 
@@ -164,6 +173,7 @@ impl<'gc> Trait<'gc> {
                 type_name,
                 unit: None,
             },
+            metadata: Vec::new(),
         }
     }
 
@@ -181,6 +191,7 @@ impl<'gc> Trait<'gc> {
                 type_name,
                 unit: None,
             },
+            metadata: Vec::new(),
         }
     }
 
@@ -192,6 +203,7 @@ impl<'gc> Trait<'gc> {
     ) -> Result<Self, Error<'gc>> {
         let mc = activation.context.gc_context;
         let name = QName::from_abc_multiname(unit, abc_trait.name, mc)?;
+        let metadata = Metadata::from_abc_indices(unit, &abc_trait.metadata, mc)?;
 
         Ok(match &abc_trait.kind {
             AbcTraitKind::Slot {
@@ -207,6 +219,7 @@ impl<'gc> Trait<'gc> {
                 Trait {
                     name,
                     attributes: trait_attribs_from_abc_traits(abc_trait),
+                    metadata,
                     kind: TraitKind::Slot {
                         slot_id: *slot_id,
                         type_name,
@@ -218,6 +231,7 @@ impl<'gc> Trait<'gc> {
             AbcTraitKind::Method { disp_id, method } => Trait {
                 name,
                 attributes: trait_attribs_from_abc_traits(abc_trait),
+                metadata,
                 kind: TraitKind::Method {
                     disp_id: *disp_id,
                     method: unit.load_method(*method, false, activation)?,
@@ -226,6 +240,7 @@ impl<'gc> Trait<'gc> {
             AbcTraitKind::Getter { disp_id, method } => Trait {
                 name,
                 attributes: trait_attribs_from_abc_traits(abc_trait),
+                metadata,
                 kind: TraitKind::Getter {
                     disp_id: *disp_id,
                     method: unit.load_method(*method, false, activation)?,
@@ -234,22 +249,29 @@ impl<'gc> Trait<'gc> {
             AbcTraitKind::Setter { disp_id, method } => Trait {
                 name,
                 attributes: trait_attribs_from_abc_traits(abc_trait),
+                metadata,
                 kind: TraitKind::Setter {
                     disp_id: *disp_id,
                     method: unit.load_method(*method, false, activation)?,
                 },
             },
-            AbcTraitKind::Class { slot_id, class } => Trait {
-                name,
-                attributes: trait_attribs_from_abc_traits(abc_trait),
-                kind: TraitKind::Class {
-                    slot_id: *slot_id,
-                    class: unit.load_class(class.0, activation)?,
-                },
-            },
+            AbcTraitKind::Class { slot_id, class } => {
+                let class = unit.load_class(class.0, activation)?;
+                class.write(mc).set_metadata(metadata.clone());
+                Trait {
+                    name,
+                    attributes: trait_attribs_from_abc_traits(abc_trait),
+                    metadata,
+                    kind: TraitKind::Class {
+                        slot_id: *slot_id,
+                        class,
+                    },
+                }
+            }
             AbcTraitKind::Function { slot_id, function } => Trait {
                 name,
                 attributes: trait_attribs_from_abc_traits(abc_trait),
+                metadata,
                 kind: TraitKind::Function {
                     slot_id: *slot_id,
                     function: unit.load_method(*function, true, activation)?,
@@ -268,6 +290,7 @@ impl<'gc> Trait<'gc> {
                 Trait {
                     name,
                     attributes: trait_attribs_from_abc_traits(abc_trait),
+                    metadata,
                     kind: TraitKind::Const {
                         slot_id: *slot_id,
                         type_name,
@@ -283,6 +306,11 @@ impl<'gc> Trait<'gc> {
         self.name
     }
 
+    /// The metadata tags declared on this trait.
+    pub fn metadata(&self) -> &[Metadata<'gc>] {
+        &self.metadata
+    }
+
     pub fn kind(&self) -> &TraitKind<'gc> {
         &self.kind
     }
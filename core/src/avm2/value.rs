@@ -1,6 +1,9 @@
 //! AVM2 values
 
 use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::e4x::{xml_nodes, E4XNode};
+use crate::avm2::error::type_error;
 use crate::avm2::globals::NS_VECTOR;
 use crate::avm2::object::{ClassObject, NamespaceObject, Object, PrimitiveObject, TObject};
 use crate::avm2::script::TranslationUnit;
@@ -9,8 +12,8 @@ use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
 use crate::ecma_conversions::{f64_to_wrapping_i32, f64_to_wrapping_u32};
-use crate::string::{AvmString, WStr};
-use gc_arena::{Collect, MutationContext};
+use crate::string::{AvmString, WStr, WString};
+use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::Ref;
 use swf::avm2::types::{DefaultValue as AbcDefaultValue, Index};
 
@@ -157,7 +160,14 @@ impl PartialEq for Value<'_> {
             (Value::Integer(a), Value::Number(b)) => *a as f64 == *b,
             (Value::Integer(a), Value::Integer(b)) => a == b,
             (Value::String(a), Value::String(b)) => a == b,
-            (Value::Object(a), Value::Object(b)) => Object::ptr_eq(*a, *b),
+            (Value::Object(a), Value::Object(b)) => {
+                // XML objects are handles to nodes, so two of them are the same if they refer to
+                // the same node.
+                match (a.as_xml_object(), b.as_xml_object()) {
+                    (Some(a), Some(b)) => E4XNode::ptr_eq(a.node(), b.node()),
+                    _ => Object::ptr_eq(*a, *b),
+                }
+            }
             _ => false,
         }
     }
@@ -537,6 +547,26 @@ pub fn abc_default_value<'gc>(
     }
 }
 
+/// Format the name of a class the way Flash Player does in error messages,
+/// e.g. `__AS3__.vec.Vector.<flash.display.Sprite>`.
+fn class_name_for_error<'gc>(
+    class: GcCell<'gc, Class<'gc>>,
+    mc: MutationContext<'gc, '_>,
+) -> AvmString<'gc> {
+    let class = class.read();
+    let name = class.generic_name().to_qualified_name_err_message(mc);
+    let param = match class.params().first() {
+        Some(param) => *param,
+        None => return name,
+    };
+
+    let mut buf = WString::from(name.as_wstr());
+    buf.push_str(WStr::from_units(b".<"));
+    buf.push_str(&class_name_for_error(param, mc));
+    buf.push_char('>');
+    AvmString::new(mc, buf)
+}
+
 impl<'gc> Value<'gc> {
     pub fn as_namespace(&self) -> Result<Ref<Namespace<'gc>>, Error<'gc>> {
         match self {
@@ -570,6 +600,19 @@ impl<'gc> Value<'gc> {
         }
     }
 
+    /// Returns the value of an `int`, `uint` or `Number` primitive.
+    ///
+    /// Unlike `as_number`, boxed numbers are not unwrapped. This is used by the
+    /// interpreter's fast paths, which fall back to full coercion on `None`.
+    #[inline]
+    pub fn as_primitive_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(num) => Some(*num),
+            Value::Integer(num) => Some(f64::from(*num)),
+            _ => None,
+        }
+    }
+
     /// Like `as_number`, but for `i32`
     pub fn as_integer(&self, mc: MutationContext<'gc, '_>) -> Result<i32, Error<'gc>> {
         match self {
@@ -724,6 +767,9 @@ impl<'gc> Value<'gc> {
     /// Numerical conversions occur according to ECMA-262 3rd Edition's
     /// ToUint32 algorithm which appears to match AVM2.
     pub fn coerce_to_u32(&self, activation: &mut Activation<'_, 'gc>) -> Result<u32, Error<'gc>> {
+        if let Value::Integer(i) = self {
+            return Ok(*i as u32);
+        }
         Ok(f64_to_wrapping_u32(self.coerce_to_number(activation)?))
     }
 
@@ -735,6 +781,9 @@ impl<'gc> Value<'gc> {
     /// Numerical conversions occur according to ECMA-262 3rd Edition's
     /// ToInt32 algorithm which appears to match AVM2.
     pub fn coerce_to_i32(&self, activation: &mut Activation<'_, 'gc>) -> Result<i32, Error<'gc>> {
+        if let Value::Integer(i) = self {
+            return Ok(*i);
+        }
         Ok(f64_to_wrapping_i32(self.coerce_to_number(activation)?))
     }
 
@@ -982,7 +1031,7 @@ impl<'gc> Value<'gc> {
                         && vector.value_type() == activation.avm2().classes().int)
                     || (name == QName::new(Namespace::internal(NS_VECTOR), "Vector$uint")
                         && vector.value_type() == activation.avm2().classes().uint)
-                    || (name == QName::new(Namespace::internal(NS_VECTOR), "Vector$number")
+                    || (name == QName::new(Namespace::internal(NS_VECTOR), "Vector$double")
                         && vector.value_type() == activation.avm2().classes().number)
                     || (name == QName::new(Namespace::internal(NS_VECTOR), "Vector$object")
                         && vector.value_type() == activation.avm2().classes().object)
@@ -992,9 +1041,26 @@ impl<'gc> Value<'gc> {
             }
         }
 
-        let name = class.inner_class_definition().read().name();
+        let from = match self {
+            Value::Object(object) => object
+                .instance_of_class_definition()
+                .map(|class| class_name_for_error(class, activation.context.gc_context))
+                .unwrap_or_else(|| "Object".into()),
+            Value::String(string) => {
+                AvmString::new_utf8(activation.context.gc_context, format!("\"{string}\""))
+            }
+            value => value.coerce_to_string(activation)?,
+        };
+        let to = class_name_for_error(
+            class.inner_class_definition(),
+            activation.context.gc_context,
+        );
 
-        Err(format!("Cannot coerce {self:?} to an {name:?}").into())
+        Err(Error::AvmError(type_error(
+            activation,
+            &format!("Error #1034: Type Coercion failed: cannot convert {from} to {to}."),
+            1034,
+        )?))
     }
 
     /// Determine if this value is any kind of number.
@@ -1093,7 +1159,16 @@ impl<'gc> Value<'gc> {
             }
             (Value::String(a), Value::String(b)) => Ok(a == b),
             (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
-            (Value::Object(a), Value::Object(b)) => Ok(Object::ptr_eq(*a, *b)),
+            (Value::Object(_), Value::Object(_)) => {
+                // XML values are compared by their contents, and a list with one item is
+                // compared like the item.
+                match (xml_nodes(*self), xml_nodes(*other)) {
+                    (Some(a), Some(b)) => {
+                        Ok(a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.equals(*b)))
+                    }
+                    _ => Ok(self == other),
+                }
+            }
             (Value::Undefined, Value::Null) => Ok(true),
             (Value::Null, Value::Undefined) => Ok(true),
             (Value::Number(_) | Value::Integer(_), Value::String(_)) => {
@@ -1143,6 +1218,16 @@ impl<'gc> Value<'gc> {
         other: &Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Option<bool>, Error<'gc>> {
+        if let (Value::Integer(a), Value::Integer(b)) = (self, other) {
+            return Ok(Some(a < b));
+        }
+        if let (Some(a), Some(b)) = (self.as_primitive_number(), other.as_primitive_number()) {
+            if a.is_nan() || b.is_nan() {
+                return Ok(None);
+            }
+            return Ok(Some(a < b));
+        }
+
         let prim_self = self.coerce_to_primitive(Some(Hint::Number), activation)?;
         let prim_other = other.coerce_to_primitive(Some(Hint::Number), activation)?;
 
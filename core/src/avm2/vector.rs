@@ -5,10 +5,173 @@ use crate::avm2::error::range_error;
 use crate::avm2::object::{ClassObject, Object};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::ecma_conversions::{f64_to_wrapping_i32, f64_to_wrapping_u32};
 use gc_arena::Collect;
 use std::cmp::{max, min};
-use std::ops::{Index, RangeBounds};
-use std::slice::SliceIndex;
+use std::ops::Range;
+
+/// The values of a vector.
+///
+/// Vectors of `int`, `uint` and `Number` store their values unboxed. This
+/// keeps the hot arrays of games compact, and avoids having to check the type
+/// of each element when reading it back.
+#[derive(Collect, Clone)]
+#[collect(no_drop)]
+enum VectorData<'gc> {
+    Int(Vec<i32>),
+    Uint(Vec<u32>),
+    Number(Vec<f64>),
+    Value(Vec<Value<'gc>>),
+}
+
+/// Convert a value that was already coerced to `int` into one.
+fn unbox_i32(value: Value<'_>) -> i32 {
+    match value {
+        Value::Integer(i) => i,
+        value => value
+            .as_primitive_number()
+            .map(f64_to_wrapping_i32)
+            .unwrap_or(0),
+    }
+}
+
+/// Convert a value that was already coerced to `uint` into one.
+fn unbox_u32(value: Value<'_>) -> u32 {
+    match value {
+        Value::Integer(i) => i as u32,
+        value => value
+            .as_primitive_number()
+            .map(f64_to_wrapping_u32)
+            .unwrap_or(0),
+    }
+}
+
+/// Convert a value that was already coerced to `Number` into one.
+fn unbox_f64(value: Value<'_>) -> f64 {
+    value.as_primitive_number().unwrap_or(f64::NAN)
+}
+
+impl<'gc> VectorData<'gc> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Int(data) => data.len(),
+            Self::Uint(data) => data.len(),
+            Self::Number(data) => data.len(),
+            Self::Value(data) => data.len(),
+        }
+    }
+
+    /// The value holes in this data are filled with.
+    fn default_value(&self) -> Value<'gc> {
+        match self {
+            Self::Int(_) | Self::Uint(_) => Value::Integer(0),
+            Self::Number(_) => Value::Number(0.0),
+            Self::Value(_) => Value::Null,
+        }
+    }
+
+    fn get(&self, pos: usize) -> Option<Value<'gc>> {
+        match self {
+            Self::Int(data) => data.get(pos).map(|v| (*v).into()),
+            Self::Uint(data) => data.get(pos).map(|v| (*v).into()),
+            Self::Number(data) => data.get(pos).map(|v| Value::Number(*v)),
+            Self::Value(data) => data.get(pos).copied(),
+        }
+    }
+
+    fn set(&mut self, pos: usize, value: Value<'gc>) -> bool {
+        match self {
+            Self::Int(data) => data.get_mut(pos).map(|v| *v = unbox_i32(value)),
+            Self::Uint(data) => data.get_mut(pos).map(|v| *v = unbox_u32(value)),
+            Self::Number(data) => data.get_mut(pos).map(|v| *v = unbox_f64(value)),
+            Self::Value(data) => data.get_mut(pos).map(|v| *v = value),
+        }
+        .is_some()
+    }
+
+    fn insert(&mut self, pos: usize, value: Value<'gc>) {
+        match self {
+            Self::Int(data) => data.insert(pos, unbox_i32(value)),
+            Self::Uint(data) => data.insert(pos, unbox_u32(value)),
+            Self::Number(data) => data.insert(pos, unbox_f64(value)),
+            Self::Value(data) => data.insert(pos, value),
+        }
+    }
+
+    fn remove(&mut self, pos: usize) -> Value<'gc> {
+        match self {
+            Self::Int(data) => data.remove(pos).into(),
+            Self::Uint(data) => data.remove(pos).into(),
+            Self::Number(data) => Value::Number(data.remove(pos)),
+            Self::Value(data) => data.remove(pos),
+        }
+    }
+
+    fn push(&mut self, value: Value<'gc>) {
+        let len = self.len();
+        self.insert(len, value);
+    }
+
+    fn pop(&mut self) -> Option<Value<'gc>> {
+        match self.len() {
+            0 => None,
+            len => Some(self.remove(len - 1)),
+        }
+    }
+
+    fn resize(&mut self, new_length: usize) {
+        match self {
+            Self::Int(data) => data.resize(new_length, 0),
+            Self::Uint(data) => data.resize(new_length, 0),
+            Self::Number(data) => data.resize(new_length, 0.0),
+            Self::Value(data) => data.resize(new_length, Value::Null),
+        }
+    }
+
+    fn reserve_exact(&mut self, additional: usize) {
+        match self {
+            Self::Int(data) => data.reserve_exact(additional),
+            Self::Uint(data) => data.reserve_exact(additional),
+            Self::Number(data) => data.reserve_exact(additional),
+            Self::Value(data) => data.reserve_exact(additional),
+        }
+    }
+
+    fn reverse(&mut self) {
+        match self {
+            Self::Int(data) => data.reverse(),
+            Self::Uint(data) => data.reverse(),
+            Self::Number(data) => data.reverse(),
+            Self::Value(data) => data.reverse(),
+        }
+    }
+
+    /// Replace the values in `range` with `replace_with`, returning the
+    /// removed values.
+    fn splice(&mut self, range: Range<usize>, replace_with: Vec<Value<'gc>>) -> Vec<Value<'gc>> {
+        match self {
+            Self::Int(data) => data
+                .splice(range, replace_with.into_iter().map(unbox_i32))
+                .map(Value::from)
+                .collect(),
+            Self::Uint(data) => data
+                .splice(range, replace_with.into_iter().map(unbox_u32))
+                .map(Value::from)
+                .collect(),
+            Self::Number(data) => data
+                .splice(range, replace_with.into_iter().map(unbox_f64))
+                .map(Value::Number)
+                .collect(),
+            Self::Value(data) => data.splice(range, replace_with).collect(),
+        }
+    }
+
+    /// Replace all values of this data, keeping the element type.
+    fn replace(&mut self, values: Vec<Value<'gc>>) {
+        let len = self.len();
+        self.splice(0..len, values);
+    }
+}
 
 /// The vector storage portion of a vector object.
 ///
@@ -23,7 +186,7 @@ use std::slice::SliceIndex;
 #[collect(no_drop)]
 pub struct VectorStorage<'gc> {
     /// The storage for vector values.
-    storage: Vec<Value<'gc>>,
+    storage: VectorData<'gc>,
 
     /// Whether or not the array length is fixed.
     is_fixed: bool,
@@ -46,19 +209,31 @@ impl<'gc> VectorStorage<'gc> {
         value_type: ClassObject<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Self {
-        let storage = Vec::new();
+        let mut storage = Self::empty_data(value_type, activation);
+        storage.resize(length);
 
-        let mut self_vec = VectorStorage {
+        VectorStorage {
             storage,
             is_fixed,
             value_type,
-        };
-
-        self_vec
-            .storage
-            .resize(length, self_vec.default(activation));
+        }
+    }
 
-        self_vec
+    /// Create empty storage of the kind used for values of a given type.
+    fn empty_data(
+        value_type: ClassObject<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> VectorData<'gc> {
+        let classes = activation.avm2().classes();
+        if Object::ptr_eq(value_type, classes.int) {
+            VectorData::Int(Vec::new())
+        } else if Object::ptr_eq(value_type, classes.uint) {
+            VectorData::Uint(Vec::new())
+        } else if Object::ptr_eq(value_type, classes.number) {
+            VectorData::Number(Vec::new())
+        } else {
+            VectorData::Value(Vec::new())
+        }
     }
 
     fn check_fixed(&self, activation: &mut Activation<'_, 'gc>) -> Result<(), Error<'gc>> {
@@ -72,6 +247,21 @@ impl<'gc> VectorStorage<'gc> {
         Ok(())
     }
 
+    fn out_of_range_error(
+        &self,
+        pos: impl std::fmt::Display,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Error<'gc>, Error<'gc>> {
+        Ok(Error::AvmError(range_error(
+            activation,
+            &format!(
+                "Error #1125: The index {pos} is out of range {}.",
+                self.length()
+            ),
+            1125,
+        )?))
+    }
+
     /// Create a new vector storage from a list of values.
     ///
     /// The values are assumed to already have been coerced to the value type
@@ -80,7 +270,16 @@ impl<'gc> VectorStorage<'gc> {
         storage: Vec<Value<'gc>>,
         is_fixed: bool,
         value_type: ClassObject<'gc>,
+        activation: &mut Activation<'_, 'gc>,
     ) -> Self {
+        let storage = match Self::empty_data(value_type, activation) {
+            VectorData::Value(_) => VectorData::Value(storage),
+            mut data => {
+                data.replace(storage);
+                data
+            }
+        };
+
         VectorStorage {
             storage,
             is_fixed,
@@ -110,22 +309,14 @@ impl<'gc> VectorStorage<'gc> {
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
         self.check_fixed(activation)?;
-        self.storage.resize(new_length, self.default(activation));
+        self.storage.resize(new_length);
 
         Ok(())
     }
 
     /// Get the default value for this vector.
-    pub fn default(&self, activation: &mut Activation<'_, 'gc>) -> Value<'gc> {
-        if Object::ptr_eq(self.value_type, activation.avm2().classes().int)
-            || Object::ptr_eq(self.value_type, activation.avm2().classes().uint)
-        {
-            Value::Integer(0)
-        } else if Object::ptr_eq(self.value_type, activation.avm2().classes().number) {
-            Value::Number(0.0)
-        } else {
-            Value::Null
-        }
+    pub fn default(&self, _activation: &mut Activation<'_, 'gc>) -> Value<'gc> {
+        self.storage.default_value()
     }
 
     /// Get the value type this vector coerces things to.
@@ -157,14 +348,10 @@ impl<'gc> VectorStorage<'gc> {
         pos: usize,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        if let Some(val) = self.storage.get(pos).cloned() {
+        if let Some(val) = self.storage.get(pos) {
             Ok(val)
         } else {
-            Err(Error::AvmError(range_error(
-                activation,
-                &format!("{pos} is outside the range of the vector"),
-                0,
-            )?))
+            Err(self.out_of_range_error(pos, activation)?)
         }
     }
 
@@ -184,18 +371,14 @@ impl<'gc> VectorStorage<'gc> {
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
         if !self.is_fixed && pos == self.length() {
-            self.storage.resize(pos + 1, self.default(activation));
+            self.storage.push(value);
+            return Ok(());
         }
 
-        if let Some(v) = self.storage.get_mut(pos) {
-            *v = value;
+        if self.storage.set(pos, value) {
             Ok(())
         } else {
-            Err(Error::AvmError(range_error(
-                activation,
-                &format!("{pos} is outside the range of the vector"),
-                0,
-            )?))
+            Err(self.out_of_range_error(pos, activation)?)
         }
     }
 
@@ -226,10 +409,7 @@ impl<'gc> VectorStorage<'gc> {
 
         match self.storage.pop() {
             Some(v) => Ok(v),
-            None if Object::ptr_eq(self.value_type(), activation.avm2().classes().uint) => {
-                Ok(Value::Integer(0))
-            }
-            None if Object::ptr_eq(self.value_type(), activation.avm2().classes().int) => {
+            None if matches!(self.storage, VectorData::Int(_) | VectorData::Uint(_)) => {
                 Ok(Value::Integer(0))
             }
             None => Ok(Value::Undefined),
@@ -265,21 +445,14 @@ impl<'gc> VectorStorage<'gc> {
     ) -> Result<Value<'gc>, Error<'gc>> {
         self.check_fixed(activation)?;
 
-        let unshifted = if self.storage.is_empty() {
-            None
-        } else {
-            Some(self.storage.remove(0))
-        };
+        if self.storage.len() > 0 {
+            return Ok(self.storage.remove(0));
+        }
 
-        match unshifted {
-            Some(v) => Ok(v),
-            None if Object::ptr_eq(self.value_type(), activation.avm2().classes().uint) => {
-                Ok(Value::Integer(0))
-            }
-            None if Object::ptr_eq(self.value_type(), activation.avm2().classes().int) => {
-                Ok(Value::Integer(0))
-            }
-            None => Ok(Value::Undefined),
+        if matches!(self.storage, VectorData::Int(_) | VectorData::Uint(_)) {
+            Ok(Value::Integer(0))
+        } else {
+            Ok(Value::Undefined)
         }
     }
 
@@ -303,11 +476,7 @@ impl<'gc> VectorStorage<'gc> {
         self.check_fixed(activation)?;
 
         let position = self.clamp_parameter_index(position);
-        if position >= self.storage.len() {
-            self.storage.push(value);
-        } else {
-            self.storage.insert(position, value);
-        }
+        self.storage.insert(position, value);
 
         Ok(())
     }
@@ -334,7 +503,7 @@ impl<'gc> VectorStorage<'gc> {
         };
 
         if position >= self.storage.len() {
-            Err(format!("RangeError: Index {position} extends beyond the end of the vector").into())
+            Err(self.out_of_range_error(position, activation)?)
         } else {
             Ok(self.storage.remove(position))
         }
@@ -350,26 +519,31 @@ impl<'gc> VectorStorage<'gc> {
         &'a self,
     ) -> impl DoubleEndedIterator<Item = Value<'gc>> + ExactSizeIterator<Item = Value<'gc>> + 'a
     {
-        self.storage.iter().cloned()
+        (0..self.storage.len()).map(move |pos| {
+            self.storage
+                .get(pos)
+                .expect("Vector index within its length")
+        })
     }
 
     /// Replace this vector's storage with new values.
+    ///
+    /// The values are assumed to already have been coerced to the value type
+    /// of this vector.
     pub fn replace_storage(&mut self, new_storage: Vec<Value<'gc>>) {
-        self.storage = new_storage;
+        self.storage.replace(new_storage);
     }
 
-    pub fn splice<R>(
+    pub fn splice(
         &mut self,
-        range: R,
+        range: Range<usize>,
         replace_with: Vec<Value<'gc>>,
-    ) -> Result<Vec<Value<'gc>>, Error<'gc>>
-    where
-        R: Clone + SliceIndex<[Value<'gc>], Output = [Value<'gc>]> + RangeBounds<usize>,
-    {
-        if self.is_fixed && self.storage.index(range.clone()).len() != replace_with.len() {
-            return Err("RangeError: Vector is fixed".into());
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+        if self.is_fixed && range.len() != replace_with.len() {
+            self.check_fixed(activation)?;
         }
 
-        Ok(self.storage.splice(range, replace_with).collect())
+        Ok(self.storage.splice(range, replace_with))
     }
 }
@@ -10,6 +10,7 @@ use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
+use crate::string::AvmString;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::Ref;
 use std::ops::DerefMut;
@@ -38,6 +39,10 @@ pub struct VTableData<'gc> {
     method_table: Vec<ClassBoundMethod<'gc>>,
 
     default_slots: Vec<Option<Value<'gc>>>,
+
+    /// Incremented whenever traits are added, so that inline caches know when their lookups
+    /// are stale.
+    generation: u32,
 }
 
 // TODO: it might make more sense to just bind the Method to the VTable (and this its class and scope) directly
@@ -63,6 +68,7 @@ impl<'gc> VTable<'gc> {
                 slot_classes: vec![],
                 method_table: vec![],
                 default_slots: vec![],
+                generation: 0,
             },
         ))
     }
@@ -87,6 +93,7 @@ impl<'gc> VTable<'gc> {
                 // -1 shift.
                 default_slots: vec![None, None],
                 slot_classes: vec![PropertyClass::Any, PropertyClass::Any],
+                generation: 0,
             },
         ));
 
@@ -97,6 +104,15 @@ impl<'gc> VTable<'gc> {
         VTable(GcCell::allocate(mc, self.0.read().clone()))
     }
 
+    pub fn ptr_eq(a: Self, b: Self) -> bool {
+        GcCell::ptr_eq(a.0, b.0)
+    }
+
+    /// Returns the number of times traits were added to this vtable.
+    pub fn generation(self) -> u32 {
+        self.0.read().generation
+    }
+
     pub fn get_trait(self, name: &Multiname<'gc>) -> Option<Property> {
         self.0
             .read()
@@ -125,6 +141,29 @@ impl<'gc> VTable<'gc> {
         Ok(value)
     }
 
+    /// Returns the names of the public variables, constants and getters of this vtable, sorted
+    /// by name.
+    pub fn public_properties(self) -> Vec<AvmString<'gc>> {
+        let mut names: Vec<_> = self
+            .0
+            .read()
+            .resolved_traits
+            .iter()
+            .filter(|(_, ns, prop)| {
+                ns.is_public()
+                    && matches!(
+                        prop,
+                        Property::Slot { .. }
+                            | Property::ConstSlot { .. }
+                            | Property::Virtual { get: Some(_), .. }
+                    )
+            })
+            .map(|(name, _, _)| name)
+            .collect();
+        names.sort_by(|a, b| a.as_wstr().cmp(b.as_wstr()));
+        names
+    }
+
     pub fn has_trait(self, name: &Multiname<'gc>) -> bool {
         self.0
             .read()
@@ -218,6 +257,7 @@ impl<'gc> VTable<'gc> {
         let mut write = self.0.write(activation.context.gc_context);
         let write = write.deref_mut();
 
+        write.generation = write.generation.wrapping_add(1);
         write.defining_class = Some(defining_class);
         write.scope = Some(scope);
 
@@ -439,6 +479,7 @@ impl<'gc> VTable<'gc> {
     ) -> u32 {
         let mut write = self.0.write(mc);
 
+        write.generation = write.generation.wrapping_add(1);
         write.default_slots.push(Some(value));
         let new_slot_id = write.default_slots.len() as u32 - 1;
         write
@@ -462,6 +503,7 @@ impl<'gc> VTable<'gc> {
         let prop = write.resolved_traits.get(public_name).cloned();
 
         if let Some(prop) = prop {
+            write.generation = write.generation.wrapping_add(1);
             write.resolved_traits.insert(interface_name, prop);
         }
     }
@@ -1,4 +1,6 @@
 pub mod audio;
+pub mod dialog;
+pub mod local_connection;
 pub mod log;
 pub mod navigator;
 pub mod storage;
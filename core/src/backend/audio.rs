@@ -1,6 +1,7 @@
 use crate::{
     avm1::SoundObject,
-    avm2::SoundChannelObject,
+    avm2::{Object as Avm2Object, SoundChannelObject},
+    config::ResamplerQuality,
     display_object::{self, DisplayObject, MovieClip, TDisplayObject},
 };
 use downcast_rs::Downcast;
@@ -20,6 +21,12 @@ pub mod swf {
 mod mixer;
 #[cfg(feature = "audio")]
 pub use mixer::*;
+#[cfg(feature = "audio")]
+mod resampler;
+
+pub mod id3;
+mod microphone;
+pub use microphone::*;
 
 #[cfg(not(feature = "audio"))]
 mod decoders {
@@ -40,6 +47,18 @@ pub enum RegisterError {
     ShortMp3,
 }
 
+#[derive(Debug, Error)]
+pub enum MicrophoneError {
+    #[error("Microphone capture is not supported")]
+    Unsupported,
+
+    #[error("No microphone with index {0}")]
+    NotFound(usize),
+
+    #[error("Couldn't open the microphone: {0}")]
+    Backend(String),
+}
+
 pub trait AudioBackend: Downcast {
     fn play(&mut self);
     fn pause(&mut self);
@@ -50,6 +69,24 @@ pub trait AudioBackend: Downcast {
     /// Registers MP3 audio from an external source.
     fn register_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, DecodeError>;
 
+    /// Registers the beginning of an external MP3 that is still being downloaded.
+    ///
+    /// The rest of it is handed over with `append_mp3_data` as it arrives. Backends that can't
+    /// play sounds while they download only play the data given here.
+    fn register_downloading_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, DecodeError> {
+        self.register_mp3(data)
+    }
+
+    /// Appends downloaded data to an MP3 registered with `register_downloading_mp3`.
+    ///
+    /// `is_complete` is set along with the last of the data.
+    fn append_mp3_data(&mut self, _sound: SoundHandle, _data: &[u8], _is_complete: bool) {}
+
+    /// Returns whether a playing instance of a downloading sound is waiting for more data.
+    fn is_sound_buffering(&self, _sound: SoundHandle) -> bool {
+        false
+    }
+
     /// Plays a sound.
     fn start_sound(
         &mut self,
@@ -69,6 +106,34 @@ pub trait AudioBackend: Downcast {
         handle: &swf::SoundStreamHead,
     ) -> Result<SoundInstanceHandle, DecodeError>;
 
+    /// Starts playing a "dynamic" sound, whose 44.1KHz samples are generated by ActionScript
+    /// through `SampleDataEvent`.
+    /// The sound plays silence while it waits for more samples.
+    fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, DecodeError>;
+
+    /// Appends stereo sample frames to a playing dynamic sound.
+    /// If `is_last` is set, the sound ends once these samples have been played.
+    fn append_dynamic_sound_samples(
+        &mut self,
+        instance: SoundInstanceHandle,
+        samples: &[[f32; 2]],
+        is_last: bool,
+    );
+
+    /// Get the number of sample frames of a dynamic sound that have not been played yet.
+    /// Returns `None` if the sound is not/no longer playing.
+    fn get_dynamic_sound_buffered(&self, instance: SoundInstanceHandle) -> Option<u32>;
+
+    /// Decodes part of a registered sound into 44.1KHz stereo sample frames, starting from the
+    /// 44.1KHz sample frame `start_sample_frame`.
+    /// Returns fewer than `num_sample_frames` frames if the sound ends first.
+    fn extract_sound(
+        &self,
+        sound: SoundHandle,
+        start_sample_frame: u32,
+        num_sample_frames: u32,
+    ) -> Result<Vec<[f32; 2]>, DecodeError>;
+
     /// Stops a playing sound instance.
     /// No-op if the sound is not playing.
     fn stop_sound(&mut self, sound: SoundInstanceHandle);
@@ -122,14 +187,38 @@ pub trait AudioBackend: Downcast {
         None
     }
 
+    /// Sets how sounds are converted to the output sample rate, for backends that do so
+    /// themselves.
+    fn set_resampler_quality(&mut self, _quality: ResamplerQuality) {}
+
     /// Returns the master volume of the audio backend.
     fn volume(&self) -> f32;
 
     /// Sets the master volume of the audio backend.
     fn set_volume(&mut self, volume: f32);
 
-    /// Returns the last whole window of output samples.
+    /// Returns the last 1024 frames of mixed output audio, oldest first.
     fn get_sample_history(&self) -> [[f32; 2]; 1024];
+
+    /// Returns the names of the microphones that audio can be captured from.
+    /// The system's default microphone comes first.
+    fn microphone_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Starts capturing audio from the microphone with the given index in `microphone_names`.
+    /// Any previous capture is stopped.
+    fn start_microphone(&mut self, _index: usize) -> Result<(), MicrophoneError> {
+        Err(MicrophoneError::Unsupported)
+    }
+
+    /// Stops capturing audio from the microphone.
+    fn stop_microphone(&mut self) {}
+
+    /// Returns the mono audio captured since the last call, at `MICROPHONE_SAMPLE_RATE`.
+    fn take_microphone_samples(&mut self) -> Vec<f32> {
+        Vec::new()
+    }
 }
 
 impl_downcast!(AudioBackend);
@@ -215,6 +304,31 @@ impl AudioBackend for NullAudioBackend {
         Ok(SoundInstanceHandle::from_raw_parts(0, 0))
     }
 
+    fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, DecodeError> {
+        Ok(SoundInstanceHandle::from_raw_parts(0, 0))
+    }
+
+    fn append_dynamic_sound_samples(
+        &mut self,
+        _instance: SoundInstanceHandle,
+        _samples: &[[f32; 2]],
+        _is_last: bool,
+    ) {
+    }
+
+    fn get_dynamic_sound_buffered(&self, _instance: SoundInstanceHandle) -> Option<u32> {
+        None
+    }
+
+    fn extract_sound(
+        &self,
+        _sound: SoundHandle,
+        _start_sample_frame: u32,
+        _num_sample_frames: u32,
+    ) -> Result<Vec<[f32; 2]>, DecodeError> {
+        Ok(Vec::new())
+    }
+
     fn stop_sound(&mut self, _sound: SoundInstanceHandle) {}
 
     fn stop_all_sounds(&mut self) {}
@@ -289,6 +403,10 @@ impl<'gc> AudioManager<'gc> {
     /// The maximum number of sound instances that can play at once.
     pub const MAX_SOUNDS: usize = 32;
 
+    /// The number of sample frames that a `sampleData` handler must provide for a dynamic sound
+    /// to keep playing.
+    pub const MIN_DYNAMIC_SAMPLES: usize = 2048;
+
     /// The default timeline stream buffer time in seconds.
     pub const DEFAULT_STREAM_BUFFER_TIME: i32 = 5;
 
@@ -329,10 +447,14 @@ impl<'gc> AudioManager<'gc> {
                 true
             } else {
                 // Sound ended.
-                let duration = sound
-                    .sound
-                    .and_then(|sound| audio.get_sound_duration(sound))
-                    .unwrap_or_default();
+                let duration = if let Some(dynamic_sound) = &sound.dynamic_sound {
+                    f64::from(dynamic_sound.position) * 1000.0 / 44100.0
+                } else {
+                    sound
+                        .sound
+                        .and_then(|sound| audio.get_sound_duration(sound))
+                        .unwrap_or_default()
+                };
                 if let Some(object) = sound.avm1_object {
                     object.set_position(gc_context, duration.round() as u32);
 
@@ -371,6 +493,31 @@ impl<'gc> AudioManager<'gc> {
         self.update_sound_transforms(audio);
     }
 
+    /// Makes room for a new sound if `MAX_SOUNDS` sounds are already playing.
+    ///
+    /// Like in Flash, the oldest event sound started by the timeline, a button or an AVM1
+    /// `Sound` is stopped to make room, unless `steal` is false. Sounds controlled by an AVM2
+    /// `SoundChannel` and timeline streams are never stopped.
+    ///
+    /// Returns whether there is room for the new sound.
+    fn make_room(&mut self, audio: &mut dyn AudioBackend, steal: bool) -> bool {
+        if self.sounds.len() < Self::MAX_SOUNDS {
+            return true;
+        }
+        if !steal {
+            return false;
+        }
+        match self.sounds.iter().position(SoundInstance::is_stealable) {
+            Some(i) => {
+                let sound = self.sounds.remove(i);
+                audio.stop_sound(sound.instance);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Starts an event sound, stopping the oldest event sound if too many sounds are playing.
     pub fn start_sound(
         &mut self,
         audio: &mut dyn AudioBackend,
@@ -379,7 +526,32 @@ impl<'gc> AudioManager<'gc> {
         display_object: Option<DisplayObject<'gc>>,
         avm1_object: Option<SoundObject<'gc>>,
     ) -> Option<SoundInstanceHandle> {
-        if self.sounds.len() < Self::MAX_SOUNDS {
+        self.start_event_sound(audio, sound, settings, display_object, avm1_object, true)
+    }
+
+    /// Starts an event sound for an AVM2 `Sound`.
+    ///
+    /// Like in Flash, no other sound is stopped to make room for it, so this fails if too
+    /// many sounds are playing.
+    pub fn start_avm2_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+    ) -> Option<SoundInstanceHandle> {
+        self.start_event_sound(audio, sound, settings, None, None, false)
+    }
+
+    fn start_event_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+        display_object: Option<DisplayObject<'gc>>,
+        avm1_object: Option<SoundObject<'gc>>,
+        steal: bool,
+    ) -> Option<SoundInstanceHandle> {
+        if self.make_room(audio, steal) {
             let handle = audio.start_sound(sound, settings).ok()?;
             let instance = SoundInstance {
                 sound: Some(sound),
@@ -389,6 +561,7 @@ impl<'gc> AudioManager<'gc> {
                 avm1_object,
                 avm2_object: None,
                 stream_start_frame: None,
+                dynamic_sound: None,
             };
             audio.set_sound_transform(handle, self.transform_for_sound(&instance));
             self.sounds.push(instance);
@@ -398,6 +571,83 @@ impl<'gc> AudioManager<'gc> {
         }
     }
 
+    /// Starts a dynamic sound, which dispatches `sampleData` events to `sound_object` for its
+    /// samples.
+    pub fn start_dynamic_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sound_object: Avm2Object<'gc>,
+    ) -> Option<SoundInstanceHandle> {
+        if self.make_room(audio, false) {
+            let handle = audio.start_dynamic_sound().ok()?;
+            let instance = SoundInstance {
+                sound: None,
+                instance: handle,
+                display_object: None,
+                transform: display_object::SoundTransform::default(),
+                avm1_object: None,
+                avm2_object: None,
+                stream_start_frame: None,
+                dynamic_sound: Some(DynamicSound {
+                    sound_object,
+                    position: 0,
+                    is_finished: false,
+                }),
+            };
+            audio.set_sound_transform(handle, self.transform_for_sound(&instance));
+            self.sounds.push(instance);
+            Some(handle)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the next dynamic sound that is running low on samples, with its `Sound` object
+    /// and the position of the samples it needs.
+    ///
+    /// `min_buffered` is the number of sample frames that should be waiting to be played.
+    pub fn dynamic_sound_needing_samples(
+        &self,
+        audio: &dyn AudioBackend,
+        min_buffered: u32,
+    ) -> Option<(SoundInstanceHandle, Avm2Object<'gc>, u32)> {
+        self.sounds.iter().find_map(|sound| {
+            let dynamic_sound = sound.dynamic_sound.as_ref()?;
+            if dynamic_sound.is_finished {
+                return None;
+            }
+            let buffered = audio.get_dynamic_sound_buffered(sound.instance)?;
+            (buffered < min_buffered).then_some((
+                sound.instance,
+                dynamic_sound.sound_object,
+                dynamic_sound.position,
+            ))
+        })
+    }
+
+    /// Queues samples generated for a dynamic sound.
+    ///
+    /// Like in Flash, providing less than `MIN_DYNAMIC_SAMPLES` ends the sound after these
+    /// samples have played.
+    pub fn append_dynamic_sound_samples(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        instance: SoundInstanceHandle,
+        samples: &[[f32; 2]],
+    ) {
+        if let Some(dynamic_sound) = self
+            .sounds
+            .iter_mut()
+            .find(|other| other.instance == instance)
+            .and_then(|sound| sound.dynamic_sound.as_mut())
+        {
+            let is_last = samples.len() < Self::MIN_DYNAMIC_SAMPLES;
+            dynamic_sound.position += samples.len() as u32;
+            dynamic_sound.is_finished = is_last;
+            audio.append_dynamic_sound_samples(instance, samples, is_last);
+        }
+    }
+
     pub fn attach_avm2_sound_channel(
         &mut self,
         instance: SoundInstanceHandle,
@@ -419,9 +669,9 @@ impl<'gc> AudioManager<'gc> {
             .iter()
             .position(|other| other.instance == instance)
         {
-            let instance = &self.sounds[i];
+            // Keep the sounds in the order they started, to know which one is the oldest.
+            let instance = self.sounds.remove(i);
             audio.stop_sound(instance.instance);
-            self.sounds.swap_remove(i);
         }
     }
 
@@ -474,7 +724,7 @@ impl<'gc> AudioManager<'gc> {
         data: crate::tag_utils::SwfSlice,
         stream_info: &swf::SoundStreamHead,
     ) -> Option<SoundInstanceHandle> {
-        if self.sounds.len() < Self::MAX_SOUNDS {
+        if self.make_room(audio, true) {
             let handle = audio
                 .start_stream(stream_handle, clip_frame, data, stream_info)
                 .ok()?;
@@ -486,6 +736,7 @@ impl<'gc> AudioManager<'gc> {
                 avm1_object: None,
                 avm2_object: None,
                 stream_start_frame: Some(clip_frame),
+                dynamic_sound: None,
             };
             audio.set_sound_transform(handle, self.transform_for_sound(&instance));
             self.sounds.push(instance);
@@ -530,9 +781,9 @@ impl<'gc> AudioManager<'gc> {
         if skew.abs() >= Self::STREAM_RESTART_THRESHOLD {
             // Way out of sync, let's stop the entire stream.
             // The movie clip will probably restart it naturally on the next frame.
-            let instance = &self.sounds[i];
+            // Keep the sounds in the order they started, to know which one is the oldest.
+            let instance = self.sounds.remove(i);
             audio.stop_sound(instance.instance);
-            self.sounds.swap_remove(i);
             0.0
         } else if skew.abs() >= sync_threshold {
             // Slightly out of sync, adjust player speed to compensate.
@@ -570,8 +821,12 @@ impl<'gc> AudioManager<'gc> {
     }
 
     /// Set the local sound transform of a single sound instance.
+    ///
+    /// The transform is applied to the sound right away, so that sounds started
+    /// with a transform never play a few samples without it.
     pub fn set_local_sound_transform(
         &mut self,
+        audio: &mut dyn AudioBackend,
         instance: SoundInstanceHandle,
         sound_transform: display_object::SoundTransform,
     ) {
@@ -580,10 +835,9 @@ impl<'gc> AudioManager<'gc> {
             .iter()
             .position(|other| other.instance == instance)
         {
-            let instance = &mut self.sounds[i];
-
-            instance.transform = sound_transform;
-            self.transforms_dirty = true;
+            self.sounds[i].transform = sound_transform;
+            let transform = self.transform_for_sound(&self.sounds[i]);
+            audio.set_sound_transform(instance, transform);
         }
     }
 
@@ -605,6 +859,8 @@ impl<'gc> AudioManager<'gc> {
         self.transforms_dirty = true;
     }
 
+    /// The transform of a sound, composed with the transforms of the display
+    /// objects it plays in and the global transform.
     fn transform_for_sound(&self, sound: &SoundInstance<'gc>) -> SoundTransform {
         let mut transform = sound.transform.clone();
         let mut parent = sound.display_object;
@@ -668,6 +924,33 @@ pub struct SoundInstance<'gc> {
     avm2_object: Option<SoundChannelObject<'gc>>,
 
     stream_start_frame: Option<u16>,
+
+    /// The state of a dynamic sound, if this is one.
+    dynamic_sound: Option<DynamicSound<'gc>>,
+}
+
+impl<'gc> SoundInstance<'gc> {
+    /// Whether this sound may be stopped to make room for a new sound.
+    fn is_stealable(&self) -> bool {
+        self.sound.is_some()
+            && self.avm2_object.is_none()
+            && self.stream_start_frame.is_none()
+            && self.dynamic_sound.is_none()
+    }
+}
+
+/// The state of a sound whose samples are generated by ActionScript.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct DynamicSound<'gc> {
+    /// The AVM2 `Sound` object that `sampleData` events are dispatched to.
+    sound_object: Avm2Object<'gc>,
+
+    /// The number of sample frames generated so far.
+    position: u32,
+
+    /// Whether the last samples of the sound have been generated.
+    is_finished: bool,
 }
 
 /// A sound transform for a playing sound, for use by audio backends.
@@ -707,3 +990,78 @@ impl Default for SoundTransform {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register_sound(audio: &mut NullAudioBackend) -> SoundHandle {
+        let sound = swf::Sound {
+            id: 0,
+            format: swf::SoundFormat {
+                compression: swf::AudioCompression::Uncompressed,
+                sample_rate: 44100,
+                is_stereo: false,
+                is_16_bit: true,
+            },
+            num_samples: 1,
+            data: &[0, 0],
+        };
+        audio.register_sound(&sound).unwrap()
+    }
+
+    fn sound_info() -> swf::SoundInfo {
+        swf::SoundInfo {
+            event: swf::SoundEvent::Event,
+            in_sample: None,
+            out_sample: None,
+            num_loops: 1,
+            envelope: None,
+        }
+    }
+
+    #[test]
+    fn oldest_event_sound_is_stolen() {
+        let mut audio = NullAudioBackend::new();
+        let mut manager = AudioManager::new();
+        let sounds: Vec<_> = (0..=AudioManager::MAX_SOUNDS)
+            .map(|_| register_sound(&mut audio))
+            .collect();
+
+        for &sound in &sounds[..AudioManager::MAX_SOUNDS] {
+            assert!(manager
+                .start_sound(&mut audio, sound, &sound_info(), None, None)
+                .is_some());
+        }
+        let newest = sounds[AudioManager::MAX_SOUNDS];
+        assert!(manager
+            .start_sound(&mut audio, newest, &sound_info(), None, None)
+            .is_some());
+
+        assert!(!manager.is_sound_playing_with_handle(sounds[0]));
+        assert!(manager.is_sound_playing_with_handle(sounds[1]));
+        assert!(manager.is_sound_playing_with_handle(newest));
+    }
+
+    #[test]
+    fn avm2_sound_does_not_steal() {
+        let mut audio = NullAudioBackend::new();
+        let mut manager = AudioManager::new();
+        let sounds: Vec<_> = (0..=AudioManager::MAX_SOUNDS)
+            .map(|_| register_sound(&mut audio))
+            .collect();
+
+        for &sound in &sounds[..AudioManager::MAX_SOUNDS] {
+            assert!(manager
+                .start_sound(&mut audio, sound, &sound_info(), None, None)
+                .is_some());
+        }
+        let newest = sounds[AudioManager::MAX_SOUNDS];
+        assert!(manager
+            .start_avm2_sound(&mut audio, newest, &sound_info())
+            .is_none());
+
+        assert!(manager.is_sound_playing_with_handle(sounds[0]));
+        assert!(!manager.is_sound_playing_with_handle(newest));
+    }
+}
@@ -72,14 +72,28 @@ pub fn make_decoder<R: 'static + Read + Send + Sync>(
         #[cfg(feature = "mp3")]
         AudioCompression::Mp3 => Box::new(Mp3Decoder::new(data)?),
         #[cfg(feature = "nellymoser")]
-        AudioCompression::Nellymoser => {
-            Box::new(NellymoserDecoder::new(data, format.sample_rate.into()))
+        AudioCompression::Nellymoser
+        | AudioCompression::Nellymoser16Khz
+        | AudioCompression::Nellymoser8Khz => {
+            Box::new(NellymoserDecoder::new(data, sample_rate(format).into()))
         }
         _ => return Err(Error::UnhandledCompression(format.compression)),
     };
     Ok(decoder)
 }
 
+/// The sample rate of the audio in `format`.
+///
+/// The 8kHz and 16kHz variants of Nellymoser always play at that rate, whatever rate the sound
+/// declares.
+pub fn sample_rate(format: &SoundFormat) -> u16 {
+    match format.compression {
+        AudioCompression::Nellymoser16Khz => 16000,
+        AudioCompression::Nellymoser8Khz => 8000,
+        _ => format.sample_rate,
+    }
+}
+
 impl<T: Decoder + ?Sized> Decoder for Box<T> {
     #[inline]
     fn num_channels(&self) -> u8 {
@@ -370,3 +384,30 @@ pub struct Mp3Metadata {
     pub sample_rate: u16,
     pub num_sample_frames: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "nellymoser")]
+    fn nellymoser_fixed_sample_rates() {
+        for (compression, expected) in [
+            (AudioCompression::Nellymoser16Khz, 16000),
+            (AudioCompression::Nellymoser8Khz, 8000),
+            (AudioCompression::Nellymoser, 22050),
+        ] {
+            let format = SoundFormat {
+                compression,
+                sample_rate: 22050,
+                is_stereo: false,
+                is_16_bit: true,
+            };
+            // A single silent block, which decodes to 256 samples.
+            let decoder = make_decoder(&format, Cursor::new(vec![0; 64])).unwrap();
+            assert_eq!(decoder.num_channels(), 1);
+            assert_eq!(decoder.sample_rate(), expected);
+            assert_eq!(decoder.count(), 256);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Reading the ID3 metadata of MP3 files.
+//!
+//! The metadata is returned as the properties of a `flash.media.ID3Info` object: every text frame
+//! is named after its frame ID, and the common ones are also given their friendly name, such as
+//! `songName` for `TIT2`.
+
+/// The length of the header of an ID3v2 tag, and of its optional footer.
+const ID3V2_HEADER_LEN: usize = 10;
+
+/// The length of an ID3v1 tag, found at the very end of the file.
+pub const ID3V1_LEN: usize = 128;
+
+/// The `ID3Info` properties of the frames read from ID3 tags.
+const FRIENDLY_NAMES: &[(&str, &str)] = &[
+    ("TALB", "album"),
+    ("TAL", "album"),
+    ("TPE1", "artist"),
+    ("TP1", "artist"),
+    ("COMM", "comment"),
+    ("COM", "comment"),
+    ("TCON", "genre"),
+    ("TCO", "genre"),
+    ("TIT2", "songName"),
+    ("TT2", "songName"),
+    ("TRCK", "track"),
+    ("TRK", "track"),
+    ("TYER", "year"),
+    ("TDRC", "year"),
+    ("TYE", "year"),
+];
+
+/// Returns the length of the ID3v2 tag at the start of `data`, including its header and footer.
+///
+/// Returns `Some(0)` if `data` doesn't start with a tag, and `None` if more data is needed to
+/// tell.
+pub fn id3v2_len(data: &[u8]) -> Option<usize> {
+    if data.len() < ID3V2_HEADER_LEN {
+        return if b"ID3".starts_with(&data[..data.len().min(3)]) {
+            None
+        } else {
+            Some(0)
+        };
+    }
+    if &data[..3] != b"ID3" {
+        return Some(0);
+    }
+
+    let has_footer = data[5] & 0x10 != 0;
+    let len = ID3V2_HEADER_LEN + syncsafe_int(&data[6..10]);
+    Some(if has_footer {
+        len + ID3V2_HEADER_LEN
+    } else {
+        len
+    })
+}
+
+/// Reads the text frames of the ID3v2 tag `tag`, as returned by `id3v2_len`.
+pub fn parse_id3v2(tag: &[u8]) -> Vec<(String, String)> {
+    let mut properties = Vec::new();
+    if tag.len() < ID3V2_HEADER_LEN {
+        return properties;
+    }
+
+    let version = tag[3];
+    let flags = tag[5];
+    let end = (ID3V2_HEADER_LEN + syncsafe_int(&tag[6..10])).min(tag.len());
+    let mut frames = tag[ID3V2_HEADER_LEN..end].to_vec();
+
+    // Unsynchronisation inserts a zero byte after every 0xFF.
+    if flags & 0x80 != 0 {
+        let mut previous = 0;
+        frames.retain(|&byte| {
+            let is_inserted = previous == 0xFF && byte == 0;
+            previous = byte;
+            !is_inserted
+        });
+    }
+
+    let mut pos = 0;
+    if flags & 0x40 != 0 && version >= 3 {
+        // Skip the extended header. Its size doesn't include itself in ID3v2.3.
+        let size = match frames.get(..4) {
+            Some(size) if version == 3 => {
+                u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize + 4
+            }
+            Some(size) => syncsafe_int(size),
+            None => return properties,
+        };
+        pos = size;
+    }
+
+    let (id_len, header_len) = if version == 2 { (3, 6) } else { (4, 10) };
+    while let Some(header) = frames.get(pos..pos + header_len) {
+        // The frames are followed by padding.
+        if header[0] == 0 {
+            break;
+        }
+
+        let id = String::from_utf8_lossy(&header[..id_len]).into_owned();
+        let size = match version {
+            2 => u32::from_be_bytes([0, header[3], header[4], header[5]]) as usize,
+            3 => u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize,
+            _ => syncsafe_int(&header[4..8]),
+        };
+        let body = match frames.get(pos + header_len..pos + header_len + size) {
+            Some(body) => body,
+            None => break,
+        };
+        pos += header_len + size;
+
+        if let Some(text) = frame_text(&id, body) {
+            if let Some((_, name)) = FRIENDLY_NAMES.iter().find(|(frame, _)| *frame == id) {
+                properties.push((name.to_string(), text.clone()));
+            }
+            properties.push((id, text));
+        }
+    }
+
+    properties
+}
+
+/// Reads the ID3v1 tag in `tail`, the last `ID3V1_LEN` bytes of a file.
+///
+/// Returns `None` if there is no tag.
+pub fn parse_id3v1(tail: &[u8]) -> Option<Vec<(String, String)>> {
+    if tail.len() != ID3V1_LEN || &tail[..3] != b"TAG" {
+        return None;
+    }
+
+    // ID3v1.1 stores the track number in the last byte of the comment.
+    let (comment, track) = if tail[125] == 0 && tail[126] != 0 {
+        (&tail[97..125], Some(tail[126]))
+    } else {
+        (&tail[97..127], None)
+    };
+
+    let mut properties = vec![
+        ("songName".to_string(), latin1_field(&tail[3..33])),
+        ("artist".to_string(), latin1_field(&tail[33..63])),
+        ("album".to_string(), latin1_field(&tail[63..93])),
+        ("year".to_string(), latin1_field(&tail[93..97])),
+        ("comment".to_string(), latin1_field(comment)),
+    ];
+    if let Some(track) = track {
+        properties.push(("track".to_string(), track.to_string()));
+    }
+    // The genre is an index into a list of genres, written like ID3v2 does.
+    if tail[127] != 0xFF {
+        properties.push(("genre".to_string(), format!("({})", tail[127])));
+    }
+    Some(properties)
+}
+
+/// Reads a big endian integer made of the low 7 bits of each byte.
+fn syncsafe_int(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 7) | usize::from(byte & 0x7F))
+}
+
+/// Returns the text of a text or comment frame.
+fn frame_text(id: &str, body: &[u8]) -> Option<String> {
+    let (&encoding, text) = body.split_first()?;
+    match id {
+        "TXXX" | "TXX" => None,
+        "COMM" | "COM" => {
+            // Skip the language and the short description.
+            let (_, text) = split_terminated(encoding, text.get(3..)?);
+            Some(decode_text(encoding, text))
+        }
+        _ if id.starts_with('T') => Some(decode_text(encoding, text)),
+        _ => None,
+    }
+}
+
+/// Splits `bytes` after the first string terminator of the given text encoding.
+fn split_terminated(encoding: u8, bytes: &[u8]) -> (&[u8], &[u8]) {
+    let end = if encoding == 1 || encoding == 2 {
+        (0..bytes.len() / 2)
+            .map(|i| i * 2)
+            .find(|&i| bytes[i] == 0 && bytes[i + 1] == 0)
+            .map(|i| (i, i + 2))
+    } else {
+        bytes.iter().position(|&byte| byte == 0).map(|i| (i, i + 1))
+    };
+    match end {
+        Some((end, rest)) => (&bytes[..end], &bytes[rest..]),
+        None => (bytes, &[]),
+    }
+}
+
+/// Decodes the first string of `bytes` with the given text encoding.
+fn decode_text(encoding: u8, bytes: &[u8]) -> String {
+    let (text, _) = split_terminated(encoding, bytes);
+    match encoding {
+        1 | 2 => {
+            let (is_little_endian, text) = match text {
+                [0xFF, 0xFE, rest @ ..] => (true, rest),
+                [0xFE, 0xFF, rest @ ..] => (false, rest),
+                _ => (false, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|unit| {
+                    if is_little_endian {
+                        u16::from_le_bytes([unit[0], unit[1]])
+                    } else {
+                        u16::from_be_bytes([unit[0], unit[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(text).into_owned(),
+        _ => text.iter().map(|&byte| char::from(byte)).collect(),
+    }
+}
+
+/// Decodes a fixed-size ISO-8859-1 field of an ID3v1 tag, padded with zeros or spaces.
+fn latin1_field(bytes: &[u8]) -> String {
+    let (text, _) = split_terminated(0, bytes);
+    let text: String = text.iter().map(|&byte| char::from(byte)).collect();
+    text.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_frame(id: &[u8; 4], encoding: u8, text: &[u8]) -> Vec<u8> {
+        let mut frame = id.to_vec();
+        frame.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+        frame.extend_from_slice(&[0, 0, encoding]);
+        frame.extend_from_slice(text);
+        frame
+    }
+
+    #[test]
+    fn id3v2() {
+        let mut frames = text_frame(b"TIT2", 0, b"Song\0");
+        frames.extend(text_frame(b"TPE1", 1, &[0xFF, 0xFE, b'M', 0, b'e', 0]));
+        frames.extend(text_frame(b"COMM", 3, b"eng\0Nice"));
+        frames.extend([0; 16]);
+
+        let mut tag = b"ID3\x03\x00\x00".to_vec();
+        tag.extend([0, 0, (frames.len() >> 7) as u8, (frames.len() & 0x7F) as u8]);
+        tag.extend(&frames);
+
+        assert_eq!(id3v2_len(&tag[..5]), None);
+        assert_eq!(id3v2_len(&tag), Some(tag.len()));
+        assert_eq!(id3v2_len(b"\xFF\xFB"), Some(0));
+
+        let properties = parse_id3v2(&tag);
+        let get = |name: &str| {
+            properties
+                .iter()
+                .find(|(property, _)| property == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("songName"), Some("Song"));
+        assert_eq!(get("TIT2"), Some("Song"));
+        assert_eq!(get("artist"), Some("Me"));
+        assert_eq!(get("comment"), Some("Nice"));
+        assert_eq!(get("album"), None);
+    }
+
+    #[test]
+    fn id3v1() {
+        let mut tail = vec![0; ID3V1_LEN];
+        tail[..3].copy_from_slice(b"TAG");
+        tail[3..7].copy_from_slice(b"Song");
+        tail[93..97].copy_from_slice(b"1999");
+        tail[126] = 7;
+        tail[127] = 17;
+
+        let properties = parse_id3v1(&tail).unwrap();
+        assert!(properties.contains(&("songName".to_string(), "Song".to_string())));
+        assert!(properties.contains(&("year".to_string(), "1999".to_string())));
+        assert!(properties.contains(&("track".to_string(), "7".to_string())));
+        assert!(properties.contains(&("genre".to_string(), "(17)".to_string())));
+        assert_eq!(parse_id3v1(&tail[1..]), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Helpers for audio backends that capture audio from a microphone.
+
+use std::sync::{Arc, Mutex};
+
+/// The sample rate of the audio returned by `AudioBackend::take_microphone_samples`.
+pub const MICROPHONE_SAMPLE_RATE: u32 = 44100;
+
+/// Converts mono audio from one sample rate to another using linear interpolation.
+///
+/// Audio may be passed in chunks of any size; the position is kept between chunks.
+#[derive(Clone, Debug)]
+pub struct MonoResampler {
+    /// The number of input samples per output sample.
+    step: f64,
+
+    /// The position of the next output sample, in input samples after `last`.
+    position: f64,
+
+    /// The last input sample.
+    last: f32,
+}
+
+impl MonoResampler {
+    pub fn new(input_sample_rate: u32, output_sample_rate: u32) -> Self {
+        Self {
+            step: f64::from(input_sample_rate) / f64::from(output_sample_rate),
+            position: 1.0,
+            last: 0.0,
+        }
+    }
+
+    /// Resamples `input`, appending the resulting samples to `output`.
+    pub fn process(&mut self, input: impl IntoIterator<Item = f32>, output: &mut Vec<f32>) {
+        for sample in input {
+            while self.position <= 1.0 {
+                output.push(self.last + (sample - self.last) * self.position as f32);
+                self.position += self.step;
+            }
+            self.position -= 1.0;
+            self.last = sample;
+        }
+    }
+}
+
+/// Collects audio captured by a microphone, which is usually delivered on another thread.
+///
+/// The audio is mixed down to mono and resampled to `MICROPHONE_SAMPLE_RATE`.
+#[derive(Clone)]
+pub struct MicrophoneBuffer(Arc<Mutex<MicrophoneBufferData>>);
+
+struct MicrophoneBufferData {
+    samples: Vec<f32>,
+    resampler: MonoResampler,
+    num_channels: usize,
+}
+
+impl MicrophoneBuffer {
+    /// The number of samples that are kept when they aren't taken, so that a movie that
+    /// doesn't read from the microphone doesn't leak memory.
+    const MAX_SAMPLES: usize = MICROPHONE_SAMPLE_RATE as usize;
+
+    /// Creates a buffer for audio with the given format.
+    pub fn new(num_channels: u16, sample_rate: u32) -> Self {
+        Self(Arc::new(Mutex::new(MicrophoneBufferData {
+            samples: Vec::new(),
+            resampler: MonoResampler::new(sample_rate, MICROPHONE_SAMPLE_RATE),
+            num_channels: num_channels.max(1).into(),
+        })))
+    }
+
+    /// Appends captured audio, with the channels of each frame interleaved.
+    pub fn push(&self, input: &[f32]) {
+        let mut data = self.0.lock().expect("Cannot be called reentrant");
+        let data = &mut *data;
+        let frames = input
+            .chunks(data.num_channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32);
+        data.resampler.process(frames, &mut data.samples);
+        if data.samples.len() > Self::MAX_SAMPLES {
+            let excess = data.samples.len() - Self::MAX_SAMPLES;
+            data.samples.drain(..excess);
+        }
+    }
+
+    /// Takes all samples collected since the last call.
+    pub fn take(&self) -> Vec<f32> {
+        std::mem::take(&mut self.0.lock().expect("Cannot be called reentrant").samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resample_across_chunks() {
+        let mut resampler = MonoResampler::new(4, 2);
+        let mut output = Vec::new();
+        resampler.process([0.0, 0.25, 0.5], &mut output);
+        resampler.process([0.75, 1.0], &mut output);
+        assert_eq!(output, vec![0.0, 0.5, 1.0]);
+
+        let mut resampler = MonoResampler::new(1, 2);
+        let mut output = Vec::new();
+        resampler.process([0.0, 1.0], &mut output);
+        assert_eq!(output, vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn buffer_mixes_down_to_mono() {
+        let buffer = MicrophoneBuffer::new(2, MICROPHONE_SAMPLE_RATE);
+        buffer.push(&[1.0, 0.0, -0.5, -0.5]);
+        assert_eq!(buffer.take(), vec![0.5, -0.5]);
+        assert_eq!(buffer.take(), vec![]);
+    }
+}
@@ -1,14 +1,17 @@
 use super::decoders::{self, AdpcmDecoder, Decoder, PcmDecoder, SeekableDecoder};
+use super::resampler::Resampler;
 use super::{SoundHandle, SoundInstanceHandle, SoundTransform};
 use crate::backend::audio::{DecodeError, RegisterError};
+use crate::config::ResamplerQuality;
 use crate::tag_utils::SwfSlice;
 use generational_arena::Arena;
+use std::collections::VecDeque;
 use std::io::Cursor;
 use std::sync::{Arc, Mutex, RwLock};
 use swf::AudioCompression;
 
 /// Holds the last 2048 output audio frames. Frames can be written to it one by
-/// one, and the last completely filled 1024-wide window can be read from it.
+/// one, and the most recent 1024 of them can be read from it as a rolling window.
 struct CircBuf {
     pub samples: [[f32; 2]; 2048],
     pub pos: usize,
@@ -29,18 +32,14 @@ impl CircBuf {
         self.pos = (self.pos + 1) % 2048;
     }
 
-    /// Returns one half of the inner buffer, the one that is not currently
-    /// being written to.
-    pub fn get(&self) -> &[[f32; 2]; 1024] {
-        if self.pos < 1024 {
-            self.samples[1024..2048]
-                .try_into()
-                .expect("Length is 1024, cast is infallible")
-        } else {
-            self.samples[0..1024]
-                .try_into()
-                .expect("Length is 1024, cast is infallible")
+    /// Returns the last 1024 frames written to the buffer, oldest first.
+    pub fn get(&self) -> [[f32; 2]; 1024] {
+        let mut window = [[0.0; 2]; 1024];
+        let start = (self.pos + 1024) % 2048;
+        for (i, frame) in window.iter_mut().enumerate() {
+            *frame = self.samples[(start + i) % 2048];
         }
+        window
     }
 }
 
@@ -69,6 +68,9 @@ pub struct AudioMixer {
 
     /// The last two windows of output samples.
     output_memory: Arc<RwLock<CircBuf>>,
+
+    /// How sounds are converted to the output sample rate.
+    resampler_quality: ResamplerQuality,
 }
 
 /// An audio stream.
@@ -158,6 +160,32 @@ struct Sound {
     /// `skip_sample_frames` indicates how many sample frames to skip to bypass the delay.
     /// This is `0` unless `format.compression` is `AudioCompression::Mp3`.
     skip_sample_frames: u16,
+
+    /// The download of this sound, if it is an MP3 that is still being downloaded.
+    download: Option<SoundDownload>,
+}
+
+/// An MP3 sound that is still being downloaded.
+struct SoundDownload {
+    /// All of the data received so far.
+    ///
+    /// `Sound::data` is a copy of it that is only updated once in a while, as copying it is costly.
+    data: Vec<u8>,
+
+    /// The state shared with the streams playing the sound.
+    state: Arc<Mutex<DownloadState>>,
+}
+
+/// The state of a downloading sound, watched by the streams playing it.
+struct DownloadState {
+    /// The latest copy of the data received so far.
+    data: Arc<[u8]>,
+
+    /// Whether the whole sound was downloaded.
+    is_complete: bool,
+
+    /// Whether a stream reached the end of `data`, and is waiting for more.
+    is_buffering: bool,
 }
 
 /// An actively playing instance of a sound.
@@ -194,6 +222,10 @@ struct SoundInstance {
     /// (respectively) of this sound over the buffer currently being
     /// mixed. Used to compute `peak`, and is reset after every time.
     range: ([f32; 2], [f32; 2]),
+
+    /// The buffer that ActionScript writes samples into, for dynamic sounds.
+    /// `None` for any other sound.
+    dynamic_buffer: Option<Arc<Mutex<DynamicSoundBuffer>>>,
 }
 
 impl SoundInstance {
@@ -207,6 +239,7 @@ impl SoundInstance {
             right_transform: [0.0, 1.0],
             peak: [0.0, 0.0],
             range: ([std::f32::INFINITY; 2], [std::f32::NEG_INFINITY; 2]),
+            dynamic_buffer: None,
         }
     }
 
@@ -220,6 +253,21 @@ impl SoundInstance {
             right_transform: [0.0, 1.0],
             peak: [0.0, 0.0],
             range: ([std::f32::INFINITY; 2], [std::f32::NEG_INFINITY; 2]),
+            dynamic_buffer: None,
+        }
+    }
+
+    /// Creates a new `SoundInstance` from a `Stream` reading from `buffer`, for dynamic sounds.
+    fn new_dynamic(stream: Box<dyn Stream>, buffer: Arc<Mutex<DynamicSoundBuffer>>) -> Self {
+        SoundInstance {
+            handle: None,
+            stream,
+            active: true,
+            left_transform: [1.0, 0.0],
+            right_transform: [0.0, 1.0],
+            peak: [0.0, 0.0],
+            range: ([std::f32::INFINITY; 2], [std::f32::NEG_INFINITY; 2]),
+            dynamic_buffer: Some(buffer),
         }
     }
 
@@ -233,6 +281,10 @@ impl SoundInstance {
 }
 
 impl AudioMixer {
+    /// The amount of new data a downloading sound needs before the streams playing it get to see
+    /// it.
+    const DOWNLOAD_UPDATE_INTERVAL: usize = 64 * 1024;
+
     /// Creates a new `AudioMixer` with the given number of channels and sample rate.
     pub fn new(num_output_channels: u8, output_sample_rate: u32) -> Self {
         Self {
@@ -242,9 +294,26 @@ impl AudioMixer {
             num_output_channels,
             output_sample_rate,
             output_memory: Arc::new(RwLock::new(CircBuf::new())),
+            resampler_quality: ResamplerQuality::default(),
         }
     }
 
+    /// Changes the format of the output stream, such as when the audio device changes.
+    ///
+    /// Sounds that are already playing stay resampled to the previous sample rate.
+    /// Proxies created before this call keep mixing to the previous number of channels.
+    pub fn set_output_format(&mut self, num_output_channels: u8, output_sample_rate: u32) {
+        self.num_output_channels = num_output_channels;
+        self.output_sample_rate = output_sample_rate;
+    }
+
+    /// Sets how sounds are converted to the output sample rate.
+    ///
+    /// This only affects sounds that start playing afterwards.
+    pub fn set_resampler_quality(&mut self, quality: ResamplerQuality) {
+        self.resampler_quality = quality;
+    }
+
     /// Creates a proxy that may be sent to a different thread.
     pub fn proxy(&self) -> AudioMixerProxy {
         AudioMixerProxy {
@@ -319,9 +388,11 @@ impl AudioMixer {
             #[cfg(feature = "mp3")]
             AudioCompression::Mp3 => Box::new(decoders::Mp3Decoder::new_seekable(data)?),
             #[cfg(feature = "nellymoser")]
-            AudioCompression::Nellymoser => Box::new(decoders::NellymoserDecoder::new(
+            AudioCompression::Nellymoser
+            | AudioCompression::Nellymoser16Khz
+            | AudioCompression::Nellymoser8Khz => Box::new(decoders::NellymoserDecoder::new(
                 data,
-                format.sample_rate.into(),
+                decoders::sample_rate(format).into(),
             )),
             _ => return Err(decoders::Error::UnhandledCompression(format.compression)),
         };
@@ -329,17 +400,20 @@ impl AudioMixer {
     }
 
     /// Transforms a `Stream` into a new `Stream` that matches the output sample rate.
-    fn make_resampler(&self, mut stream: impl Stream) -> impl Stream {
-        // TODO: Allow interpolator to be user-configurable?
-        let left = stream.next();
-        let right = stream.next();
-        let interpolator = dasp::interpolate::linear::Linear::new(left, right);
-        let sample_rate = stream.source_sample_rate().into();
+    fn make_resampler(&self, stream: impl Stream) -> impl Stream {
+        Self::resample(stream, self.output_sample_rate, self.resampler_quality)
+    }
+
+    /// Transforms a `Stream` into a new `Stream` with the given sample rate.
+    fn resample(stream: impl Stream, sample_rate: u32, quality: ResamplerQuality) -> impl Stream {
+        let source_sample_rate: u32 = stream.source_sample_rate().into();
+        let mut stream = PaddedStream::new(stream, Resampler::lookahead(quality));
+        let interpolator = Resampler::new(quality, &mut stream, source_sample_rate, sample_rate);
         ConverterStream(dasp::signal::interpolate::Converter::from_hz_to_hz(
             stream,
             interpolator,
-            sample_rate,
-            self.output_sample_rate.into(),
+            source_sample_rate.into(),
+            sample_rate.into(),
         ))
     }
 
@@ -373,6 +447,38 @@ impl AudioMixer {
         }
     }
 
+    /// Creates a `Stream` for an MP3 that is still being downloaded, which decodes and resamples
+    /// the data as it arrives.
+    ///
+    /// Only the start point and looping parameters from `settings` are applied.
+    fn make_stream_from_downloading_sound(
+        &self,
+        sound: &Sound,
+        download: &SoundDownload,
+        settings: &swf::SoundInfo,
+        data: Cursor<ArcAsRef>,
+    ) -> Result<Box<dyn Stream>, DecodeError> {
+        let mut decoder = Self::make_seekable_decoder(&sound.format, data)?;
+        let start_sample_frame = (u64::from(settings.in_sample.unwrap_or(0))
+            * u64::from(decoder.sample_rate())
+            / 44100) as u32;
+        if start_sample_frame > 0 {
+            decoder.seek_to_sample_frame(start_sample_frame);
+        }
+
+        let stream = DownloadingSoundStream {
+            format: sound.format.clone(),
+            state: Arc::clone(&download.state),
+            decoder,
+            data_len: sound.data.len(),
+            num_loops: settings.num_loops,
+            start_sample_frame,
+            position: start_sample_frame,
+            is_exhausted: false,
+        };
+        Ok(Box::new(self.make_resampler(stream)))
+    }
+
     /// Creates a `Stream` for a simple "event" sound that decodes and resamples the audio stream
     /// to the output format.
     ///
@@ -477,13 +583,16 @@ impl AudioMixer {
         sound_instances.retain(|_, sound| sound.active);
     }
 
+    /// Returns the last 1024 frames of mixed output audio, oldest first.
+    ///
+    /// This is what `SoundMixer.computeSpectrum` analyzes.
     pub fn get_sample_history(&self) -> [[f32; 2]; 1024] {
         let output_memory = self
             .output_memory
             .read()
             .expect("Cannot be called reentrant");
 
-        *output_memory.get()
+        output_memory.get()
     }
 
     /// Registers an embedded SWF sound with the audio mixer.
@@ -504,6 +613,7 @@ impl AudioMixer {
             data: Arc::from(data),
             num_sample_frames: swf_sound.num_samples,
             skip_sample_frames,
+            download: None,
         };
         Ok(self.sounds.insert(sound))
     }
@@ -524,6 +634,7 @@ impl AudioMixer {
             data,
             num_sample_frames: metadata.num_sample_frames,
             skip_sample_frames: 0,
+            download: None,
         };
         Ok(self.sounds.insert(sound))
     }
@@ -533,6 +644,77 @@ impl AudioMixer {
         Err(decoders::Error::UnhandledCompression(AudioCompression::Mp3))
     }
 
+    /// Registers the beginning of an external MP3 that is still being downloaded.
+    ///
+    /// The rest of it is handed over with `AudioMixer::append_mp3_data` as it arrives. Instances
+    /// of the sound wait for more data when they reach the end of what was received, instead of
+    /// ending there.
+    pub fn register_downloading_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, DecodeError> {
+        let handle = self.register_mp3(data)?;
+        let sound = &mut self.sounds[handle];
+        sound.download = Some(SoundDownload {
+            data: data.to_vec(),
+            state: Arc::new(Mutex::new(DownloadState {
+                data: Arc::clone(&sound.data),
+                is_complete: false,
+                is_buffering: false,
+            })),
+        });
+        Ok(handle)
+    }
+
+    /// Appends downloaded data to an MP3 registered with `AudioMixer::register_downloading_mp3`.
+    ///
+    /// `is_complete` is set along with the last of the data.
+    pub fn append_mp3_data(&mut self, sound: SoundHandle, data: &[u8], is_complete: bool) {
+        let sound = match self.sounds.get_mut(sound) {
+            Some(sound) => sound,
+            None => return,
+        };
+        let download = match &mut sound.download {
+            Some(download) => download,
+            None => return,
+        };
+
+        download.data.extend_from_slice(data);
+        if !is_complete && download.data.len() < sound.data.len() + Self::DOWNLOAD_UPDATE_INTERVAL {
+            return;
+        }
+
+        let data: Arc<[u8]> = Arc::from(&download.data[..]);
+        // The duration is an estimate until the whole sound is downloaded.
+        #[cfg(feature = "mp3")]
+        if let Ok(metadata) = decoders::mp3_metadata(&data) {
+            sound.num_sample_frames = metadata.num_sample_frames;
+        }
+        sound.data = Arc::clone(&data);
+
+        let mut state = download.state.lock().expect("Cannot be called reentrant");
+        state.data = data;
+        state.is_complete = is_complete;
+        state.is_buffering = false;
+        drop(state);
+
+        if is_complete {
+            sound.download = None;
+        }
+    }
+
+    /// Returns whether an instance of a downloading sound reached the end of the data received so
+    /// far, and is waiting for more.
+    pub fn is_sound_buffering(&self, sound: SoundHandle) -> bool {
+        self.sounds
+            .get(sound)
+            .and_then(|sound| sound.download.as_ref())
+            .map_or(false, |download| {
+                download
+                    .state
+                    .lock()
+                    .expect("Cannot be called reentrant")
+                    .is_buffering
+            })
+    }
+
     /// Starts a timeline audio stream.
     pub fn start_stream(
         &mut self,
@@ -565,7 +747,9 @@ impl AudioMixer {
         let sound = &self.sounds[sound_handle];
         let data = Cursor::new(ArcAsRef(Arc::clone(&sound.data)));
         // Create a stream that decodes and resamples the sound.
-        let stream = if sound.skip_sample_frames == 0
+        let stream = if let Some(download) = &sound.download {
+            self.make_stream_from_downloading_sound(sound, download, settings, data)?
+        } else if sound.skip_sample_frames == 0
             && settings.in_sample.is_none()
             && settings.out_sample.is_none()
             && settings.num_loops <= 1
@@ -587,6 +771,122 @@ impl AudioMixer {
         Ok(handle)
     }
 
+    /// Starts a dynamic sound, which plays the samples that ActionScript generates for it.
+    ///
+    /// The sound plays silence until samples are appended with
+    /// `AudioMixer::append_dynamic_sound_samples`, and ends once the last ones are played.
+    pub fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, DecodeError> {
+        let buffer = Arc::new(Mutex::new(DynamicSoundBuffer::default()));
+        let stream = DynamicSoundStream {
+            buffer: Arc::clone(&buffer),
+            position: 0,
+            is_exhausted: false,
+        };
+        let stream = Box::new(self.make_resampler(stream));
+
+        let mut sound_instances = self
+            .sound_instances
+            .lock()
+            .expect("Cannot be called reentrant");
+        let handle = sound_instances.insert(SoundInstance::new_dynamic(stream, buffer));
+        Ok(handle)
+    }
+
+    /// Appends 44.1KHz stereo sample frames to a dynamic sound.
+    ///
+    /// If `is_last` is set, the sound ends after these samples instead of waiting for more.
+    pub fn append_dynamic_sound_samples(
+        &mut self,
+        instance: SoundInstanceHandle,
+        samples: &[[f32; 2]],
+        is_last: bool,
+    ) {
+        use dasp::Sample;
+
+        let sound_instances = self
+            .sound_instances
+            .lock()
+            .expect("Cannot be called reentrant");
+        if let Some(buffer) = sound_instances
+            .get(instance)
+            .and_then(|instance| instance.dynamic_buffer.as_ref())
+        {
+            let mut buffer = buffer.lock().expect("Cannot be called reentrant");
+            buffer.frames.extend(
+                samples
+                    .iter()
+                    .map(|[left, right]| [left.to_sample::<i16>(), right.to_sample::<i16>()]),
+            );
+            buffer.is_finished |= is_last;
+        }
+    }
+
+    /// Returns the number of sample frames waiting to be played by a dynamic sound.
+    ///
+    /// Returns `None` if the sound is not playing, or is not a dynamic sound.
+    pub fn get_dynamic_sound_buffered(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        let sound_instances = self
+            .sound_instances
+            .lock()
+            .expect("Cannot be called reentrant");
+        let buffer = sound_instances.get(instance)?.dynamic_buffer.as_ref()?;
+        let buffer = buffer.lock().expect("Cannot be called reentrant");
+        Some(buffer.frames.len() as u32)
+    }
+
+    /// Decodes up to `num_sample_frames` sample frames of a registered sound, resampled to 44.1KHz,
+    /// starting from the 44.1KHz sample frame `start_sample_frame`.
+    ///
+    /// Fewer sample frames are returned if the sound ends first.
+    pub fn extract_sound(
+        &self,
+        sound_handle: SoundHandle,
+        start_sample_frame: u32,
+        num_sample_frames: u32,
+    ) -> Result<Vec<[f32; 2]>, DecodeError> {
+        use dasp::{signal::Signal, Sample};
+
+        let sound = match self.sounds.get(sound_handle) {
+            Some(sound) => sound,
+            None => return Ok(Vec::new()),
+        };
+        if start_sample_frame >= Self::sample_frames_at_44khz(sound) {
+            return Ok(Vec::new());
+        }
+
+        let data = Cursor::new(ArcAsRef(Arc::clone(&sound.data)));
+        let decoder = Self::make_seekable_decoder(&sound.format, data)?;
+        let settings = swf::SoundInfo {
+            event: swf::SoundEvent::Start,
+            in_sample: Some(start_sample_frame),
+            out_sample: None,
+            num_loops: 1,
+            envelope: None,
+        };
+        let stream = EventSoundStream::new_with_settings(
+            decoder,
+            &settings,
+            sound.num_sample_frames,
+            sound.skip_sample_frames,
+        );
+        let stream = Self::resample(stream, 44100, self.resampler_quality);
+
+        let num_sample_frames = num_sample_frames
+            .min(Self::sample_frames_at_44khz(sound) - start_sample_frame)
+            as usize;
+        Ok(stream
+            .until_exhausted()
+            .take(num_sample_frames)
+            .map(|[left, right]| [left.to_sample(), right.to_sample()])
+            .collect())
+    }
+
+    /// The length of a registered sound in 44.1KHz sample frames.
+    fn sample_frames_at_44khz(sound: &Sound) -> u32 {
+        let sample_rate = decoders::sample_rate(&sound.format);
+        (u64::from(sound.num_sample_frames) * 44100 / u64::from(sample_rate)) as u32
+    }
+
     /// Stops a playing sound instance.
     pub fn stop_sound(&mut self, sound: SoundInstanceHandle) {
         let mut sound_instances = self
@@ -642,7 +942,7 @@ impl AudioMixer {
         if let Some(sound) = self.sounds.get(sound) {
             // AS duration does not subtract `skip_sample_frames`.
             let num_sample_frames: f64 = sound.num_sample_frames.into();
-            let sample_rate: f64 = sound.format.sample_rate.into();
+            let sample_rate: f64 = decoders::sample_rate(&sound.format).into();
             let ms = num_sample_frames * 1000.0 / sample_rate;
             Some(ms)
         } else {
@@ -846,6 +1146,133 @@ impl Stream for EventSoundStream {
     }
 }
 
+/// A stream that plays an MP3 that is still being downloaded.
+///
+/// When it reaches the end of the data received so far, it plays silence until more arrives,
+/// without advancing its position.
+struct DownloadingSoundStream {
+    format: swf::SoundFormat,
+    state: Arc<Mutex<DownloadState>>,
+    decoder: Box<dyn SeekableDecoder>,
+
+    /// The length of the data that `decoder` reads from.
+    data_len: usize,
+
+    num_loops: u16,
+    start_sample_frame: u32,
+    position: u32,
+    is_exhausted: bool,
+}
+
+impl dasp::signal::Signal for DownloadingSoundStream {
+    type Frame = [i16; 2];
+
+    fn next(&mut self) -> Self::Frame {
+        if self.is_exhausted {
+            return [0, 0];
+        }
+        if let Some(frame) = self.decoder.next() {
+            self.position += 1;
+            return frame;
+        }
+
+        let mut state = self.state.lock().expect("Cannot be called reentrant");
+        if state.data.len() > self.data_len {
+            // Continue from the same position in the newer data.
+            let data = Cursor::new(ArcAsRef(Arc::clone(&state.data)));
+            if let Ok(mut decoder) = AudioMixer::make_seekable_decoder(&self.format, data) {
+                decoder.seek_to_sample_frame(self.position);
+                self.decoder = decoder;
+                self.data_len = state.data.len();
+                if let Some(frame) = self.decoder.next() {
+                    self.position += 1;
+                    return frame;
+                }
+            }
+        }
+
+        if !state.is_complete {
+            state.is_buffering = true;
+        } else if self.num_loops > 1 {
+            self.num_loops -= 1;
+            self.decoder.seek_to_sample_frame(self.start_sample_frame);
+            self.position = self.start_sample_frame;
+        } else {
+            self.is_exhausted = true;
+        }
+        [0, 0]
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.is_exhausted
+    }
+}
+
+impl Stream for DownloadingSoundStream {
+    #[inline]
+    fn source_position(&self) -> u32 {
+        self.position
+    }
+
+    #[inline]
+    fn source_sample_rate(&self) -> u16 {
+        self.decoder.sample_rate()
+    }
+}
+
+/// The sample frames generated by ActionScript for a dynamic sound, waiting to be played.
+#[derive(Default)]
+struct DynamicSoundBuffer {
+    frames: VecDeque<[i16; 2]>,
+
+    /// Whether no more frames will be appended after the current ones.
+    is_finished: bool,
+}
+
+/// A stream that plays the frames of a `DynamicSoundBuffer` as they are appended.
+///
+/// When it runs out of frames, it plays silence until more are appended, without advancing its
+/// position.
+struct DynamicSoundStream {
+    buffer: Arc<Mutex<DynamicSoundBuffer>>,
+    position: u32,
+    is_exhausted: bool,
+}
+
+impl dasp::signal::Signal for DynamicSoundStream {
+    type Frame = [i16; 2];
+
+    #[inline]
+    fn next(&mut self) -> Self::Frame {
+        let mut buffer = self.buffer.lock().expect("Cannot be called reentrant");
+        if let Some(frame) = buffer.frames.pop_front() {
+            self.position += 1;
+            frame
+        } else {
+            self.is_exhausted = buffer.is_finished;
+            [0, 0]
+        }
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.is_exhausted
+    }
+}
+
+impl Stream for DynamicSoundStream {
+    #[inline]
+    fn source_position(&self) -> u32 {
+        self.position
+    }
+
+    #[inline]
+    fn source_sample_rate(&self) -> u16 {
+        44100
+    }
+}
+
 /// A stream that converts a source stream to a different sample rate.
 struct ConverterStream<S, I>(dasp::signal::interpolate::Converter<S, I>)
 where
@@ -886,6 +1313,52 @@ where
     }
 }
 
+/// A stream that plays some silence after the end of a source stream, so that resamplers
+/// that read ahead of the current position reach the last frames of the source.
+struct PaddedStream<S: Stream> {
+    stream: S,
+
+    /// The number of frames of silence left to play after `stream` is exhausted.
+    padding: usize,
+}
+
+impl<S: Stream> PaddedStream<S> {
+    fn new(stream: S, padding: usize) -> Self {
+        Self { stream, padding }
+    }
+}
+
+impl<S: Stream> Stream for PaddedStream<S> {
+    #[inline]
+    fn source_position(&self) -> u32 {
+        self.stream.source_position()
+    }
+
+    #[inline]
+    fn source_sample_rate(&self) -> u16 {
+        self.stream.source_sample_rate()
+    }
+}
+
+impl<S: Stream> dasp::signal::Signal for PaddedStream<S> {
+    type Frame = [i16; 2];
+
+    #[inline]
+    fn next(&mut self) -> [i16; 2] {
+        if self.stream.is_exhausted() {
+            self.padding = self.padding.saturating_sub(1);
+            [0, 0]
+        } else {
+            self.stream.next()
+        }
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.stream.is_exhausted() && self.padding == 0
+    }
+}
+
 /// A stream that multiples a source stream by an amplitude stream to produce an enveloped stream.
 struct MulAmpStream<S, E>
 where
@@ -1050,6 +1523,26 @@ macro_rules! impl_audio_mixer_backend {
             self.$mixer.register_mp3(data)
         }
 
+        #[inline]
+        fn register_downloading_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, DecodeError> {
+            self.$mixer.register_downloading_mp3(data)
+        }
+
+        #[inline]
+        fn append_mp3_data(&mut self, sound: SoundHandle, data: &[u8], is_complete: bool) {
+            self.$mixer.append_mp3_data(sound, data, is_complete)
+        }
+
+        #[inline]
+        fn is_sound_buffering(&self, sound: SoundHandle) -> bool {
+            self.$mixer.is_sound_buffering(sound)
+        }
+
+        #[inline]
+        fn set_resampler_quality(&mut self, quality: $crate::config::ResamplerQuality) {
+            self.$mixer.set_resampler_quality(quality)
+        }
+
         #[inline]
         fn start_stream(
             &mut self,
@@ -1071,6 +1564,38 @@ macro_rules! impl_audio_mixer_backend {
             self.$mixer.start_sound(sound_handle, settings)
         }
 
+        #[inline]
+        fn start_dynamic_sound(&mut self) -> Result<SoundInstanceHandle, DecodeError> {
+            self.$mixer.start_dynamic_sound()
+        }
+
+        #[inline]
+        fn append_dynamic_sound_samples(
+            &mut self,
+            instance: SoundInstanceHandle,
+            samples: &[[f32; 2]],
+            is_last: bool,
+        ) {
+            self.$mixer
+                .append_dynamic_sound_samples(instance, samples, is_last)
+        }
+
+        #[inline]
+        fn get_dynamic_sound_buffered(&self, instance: SoundInstanceHandle) -> Option<u32> {
+            self.$mixer.get_dynamic_sound_buffered(instance)
+        }
+
+        #[inline]
+        fn extract_sound(
+            &self,
+            sound: SoundHandle,
+            start_sample_frame: u32,
+            num_sample_frames: u32,
+        ) -> Result<Vec<[f32; 2]>, DecodeError> {
+            self.$mixer
+                .extract_sound(sound, start_sample_frame, num_sample_frames)
+        }
+
         #[inline]
         fn stop_sound(&mut self, sound: SoundInstanceHandle) {
             self.$mixer.stop_sound(sound)
@@ -1130,3 +1655,71 @@ macro_rules! impl_audio_mixer_backend {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_sound_from_position() {
+        let mut mixer = AudioMixer::new(2, 22050);
+        let data: Vec<u8> = [0i16, 16384, -16384, 8192]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        let sound = mixer
+            .register_sound(&swf::Sound {
+                id: 1,
+                format: swf::SoundFormat {
+                    compression: AudioCompression::Uncompressed,
+                    sample_rate: 44100,
+                    is_stereo: false,
+                    is_16_bit: true,
+                },
+                num_samples: 4,
+                data: &data,
+            })
+            .unwrap();
+
+        assert_eq!(
+            mixer.extract_sound(sound, 1, 10).unwrap(),
+            vec![[0.5, 0.5], [-0.5, -0.5], [0.25, 0.25]]
+        );
+        assert_eq!(mixer.extract_sound(sound, 4, 10).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn sample_history_is_rolling_window() {
+        let mut history = CircBuf::new();
+        for i in 0..3000 {
+            history.push([i as f32, -(i as f32)]);
+        }
+
+        let window = history.get();
+        assert_eq!(window[0], [1976.0, -1976.0]);
+        assert_eq!(window[1023], [2999.0, -2999.0]);
+    }
+
+    #[test]
+    fn dynamic_sound_waits_for_samples() {
+        let mut mixer = AudioMixer::new(2, 44100);
+        let instance = mixer.start_dynamic_sound().unwrap();
+        let mut output = [0i16; 16];
+
+        mixer.append_dynamic_sound_samples(instance, &[[0.5, -0.5]; 4], false);
+        assert_eq!(mixer.get_dynamic_sound_buffered(instance), Some(4));
+        mixer.mix(&mut output);
+        assert_eq!(mixer.get_dynamic_sound_buffered(instance), Some(0));
+        assert_eq!(
+            mixer.get_sound_position(instance),
+            Some(4.0 * 1000.0 / 44100.0)
+        );
+
+        // Running out of samples only pauses the sound until the last ones are appended.
+        mixer.mix(&mut output);
+        assert!(mixer.get_sound_position(instance).is_some());
+        mixer.append_dynamic_sound_samples(instance, &[[0.5, -0.5]; 2], true);
+        mixer.mix(&mut output);
+        assert_eq!(mixer.get_sound_position(instance), None);
+    }
+}
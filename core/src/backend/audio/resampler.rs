@@ -0,0 +1,201 @@
+//! Interpolators used by the audio mixer to convert sounds to the output sample rate.
+
+use crate::config::ResamplerQuality;
+use dasp::interpolate::{linear::Linear, Interpolator};
+use dasp::signal::Signal;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+/// The number of sub-sample positions that a sinc filter is precomputed for.
+/// Positions in between are linearly interpolated.
+const SINC_PHASES: usize = 128;
+
+/// Interpolates between source frames to produce frames at a different sample rate.
+pub enum Resampler {
+    Linear(Linear<[i16; 2]>),
+    Sinc(Sinc),
+}
+
+impl Resampler {
+    /// Creates an interpolator for converting `source` from `source_sample_rate` to
+    /// `output_sample_rate`, taking as many frames from `source` as the interpolator needs
+    /// to produce the first output frame.
+    pub fn new(
+        quality: ResamplerQuality,
+        source: &mut impl Signal<Frame = [i16; 2]>,
+        source_sample_rate: u32,
+        output_sample_rate: u32,
+    ) -> Self {
+        if quality == ResamplerQuality::Low {
+            let left = source.next();
+            let right = source.next();
+            return Self::Linear(Linear::new(left, right));
+        }
+        let half_taps = Self::lookahead(quality);
+        // When downsampling, frequencies above the output's Nyquist frequency are filtered
+        // out to avoid aliasing.
+        let cutoff = (f64::from(output_sample_rate) / f64::from(source_sample_rate)).min(1.0);
+        Self::Sinc(Sinc::new(source, half_taps, cutoff))
+    }
+
+    /// The number of frames that an interpolator of the given quality reads ahead of the
+    /// frame it interpolates from.
+    ///
+    /// The source needs to be padded by this many frames of silence for its last frames
+    /// to be played.
+    pub fn lookahead(quality: ResamplerQuality) -> usize {
+        match quality {
+            ResamplerQuality::Low => 0,
+            ResamplerQuality::Medium => 8,
+            ResamplerQuality::High => 32,
+        }
+    }
+}
+
+impl Interpolator for Resampler {
+    type Frame = [i16; 2];
+
+    #[inline]
+    fn interpolate(&self, x: f64) -> [i16; 2] {
+        match self {
+            Self::Linear(linear) => linear.interpolate(x),
+            Self::Sinc(sinc) => sinc.interpolate(x),
+        }
+    }
+
+    #[inline]
+    fn next_source_frame(&mut self, source_frame: [i16; 2]) {
+        match self {
+            Self::Linear(linear) => linear.next_source_frame(source_frame),
+            Self::Sinc(sinc) => sinc.next_source_frame(source_frame),
+        }
+    }
+}
+
+/// A polyphase windowed sinc interpolator.
+pub struct Sinc {
+    /// The most recent source frames. The frame being interpolated from is at
+    /// `half_taps - 1`, with `half_taps` frames after it.
+    frames: VecDeque<[f32; 2]>,
+
+    /// The filter coefficients for each of the `SINC_PHASES + 1` sub-sample positions
+    /// from 0.0 to 1.0, each `2 * half_taps` long.
+    coefficients: Vec<f32>,
+
+    /// The number of source frames on each side of the interpolated position.
+    half_taps: usize,
+}
+
+impl Sinc {
+    fn new(source: &mut impl Signal<Frame = [i16; 2]>, half_taps: usize, cutoff: f64) -> Self {
+        let taps = 2 * half_taps;
+        let mut coefficients = Vec::with_capacity((SINC_PHASES + 1) * taps);
+        for phase in 0..=SINC_PHASES {
+            let x = phase as f64 / SINC_PHASES as f64;
+            let start = coefficients.len();
+            coefficients.extend((0..taps).map(|i| {
+                // The distance from the interpolated position to this tap, in source frames.
+                let t = i as f64 - (half_taps - 1) as f64 - x;
+                cutoff * sinc(cutoff * t) * blackman(t / half_taps as f64)
+            }));
+            // Normalize so that the filter has no gain.
+            let sum: f64 = coefficients[start..].iter().sum();
+            for coefficient in &mut coefficients[start..] {
+                *coefficient /= sum;
+            }
+        }
+        let coefficients = coefficients.into_iter().map(|c| c as f32).collect();
+
+        let mut frames = VecDeque::with_capacity(taps);
+        frames.extend(std::iter::repeat([0.0; 2]).take(half_taps - 1));
+        frames.extend((0..=half_taps).map(|_| frame_to_f32(source.next())));
+
+        Self {
+            frames,
+            coefficients,
+            half_taps,
+        }
+    }
+
+    fn filter(&self, phase: usize) -> &[f32] {
+        let taps = 2 * self.half_taps;
+        &self.coefficients[phase * taps..(phase + 1) * taps]
+    }
+
+    fn interpolate(&self, x: f64) -> [i16; 2] {
+        let position = x * SINC_PHASES as f64;
+        let phase = (position as usize).min(SINC_PHASES - 1);
+        let fraction = (position - phase as f64) as f32;
+
+        let mut out = [0.0f32; 2];
+        for ((frame, a), b) in self
+            .frames
+            .iter()
+            .zip(self.filter(phase))
+            .zip(self.filter(phase + 1))
+        {
+            let coefficient = a + (b - a) * fraction;
+            out[0] += frame[0] * coefficient;
+            out[1] += frame[1] * coefficient;
+        }
+        out.map(|sample| {
+            sample
+                .round()
+                .clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+        })
+    }
+
+    fn next_source_frame(&mut self, source_frame: [i16; 2]) {
+        self.frames.pop_front();
+        self.frames.push_back(frame_to_f32(source_frame));
+    }
+}
+
+fn frame_to_f32(frame: [i16; 2]) -> [f32; 2] {
+    frame.map(f32::from)
+}
+
+/// The normalized sinc function.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// The Blackman window, centered on 0 and reaching 0 at -1 and 1.
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        0.0
+    } else {
+        0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sinc_passes_through_source_frames() {
+        let mut source = dasp::signal::from_iter([[100, -100], [200, -200], [300, -300]]);
+        let resampler = Resampler::new(ResamplerQuality::High, &mut source, 22050, 44100);
+        assert_eq!(resampler.interpolate(0.0), [100, -100]);
+    }
+
+    #[test]
+    fn sinc_keeps_constant_signal() {
+        let mut source = dasp::signal::gen(|| [1000, -1000]);
+        let mut resampler = Resampler::new(ResamplerQuality::Medium, &mut source, 11025, 44100);
+        // Skip the frames influenced by the silence before the start of the source.
+        for _ in 0..Resampler::lookahead(ResamplerQuality::Medium) {
+            resampler.next_source_frame(source.next());
+        }
+        for x in [0.0, 0.25, 0.5, 0.75] {
+            let [left, right] = resampler.interpolate(x);
+            assert!((left - 1000).abs() <= 1, "{left} at {x}");
+            assert!((right + 1000).abs() <= 1, "{right} at {x}");
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Dialogs shown to the user on behalf of the movie, such as permission prompts and file pickers.
+
+/// Identifies a dialog, so that its result can be reported back with `Player::resolve_dialog`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DialogId(pub u32);
+
+/// A dialog to show to the user.
+#[derive(Clone, Debug)]
+pub enum Dialog {
+    /// A message with a single "OK" button, resolved with `DialogResult::Ok`.
+    Alert { title: String, message: String },
+
+    /// A question the user may accept (`DialogResult::Ok`) or decline
+    /// (`DialogResult::Cancelled`).
+    Confirm { title: String, message: String },
+
+    /// A question answered with some text (`DialogResult::Text`).
+    Prompt {
+        title: String,
+        message: String,
+        default_text: String,
+    },
+
+    /// A picker for one or more files to open, resolved with `DialogResult::Files`.
+    OpenFiles {
+        /// The kinds of files that may be picked. Any file may be picked if this is empty.
+        filters: Vec<FileFilter>,
+
+        /// Whether more than one file may be picked.
+        multiple: bool,
+    },
+
+    /// A picker for where to save `data`, resolved with `DialogResult::Saved` once the data
+    /// has been written.
+    SaveFile { default_name: String, data: Vec<u8> },
+}
+
+/// A kind of file that may be picked in a `Dialog::OpenFiles`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileFilter {
+    /// The name of the filter shown to the user, such as `Images`.
+    pub description: String,
+
+    /// The file extensions matched by the filter, without the leading `*.`, such as `png`.
+    /// A filter without extensions matches any file.
+    pub extensions: Vec<String>,
+}
+
+impl FileFilter {
+    /// Parses a filter in the format of `flash.net.FileFilter`, where extensions are written like
+    /// `*.jpg;*.png`.
+    pub fn from_flash(description: &str, extensions: &str) -> Self {
+        Self {
+            description: description.to_string(),
+            extensions: extensions
+                .split(';')
+                .map(|extension| {
+                    extension
+                        .trim()
+                        .trim_start_matches('*')
+                        .trim_start_matches('.')
+                })
+                .filter(|extension| !extension.is_empty() && *extension != "*")
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// A file picked by the user in a `Dialog::OpenFiles`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickedFile {
+    /// The name of the file, without its directory.
+    pub name: String,
+
+    /// The contents of the file.
+    pub data: Vec<u8>,
+
+    /// The time the file was last modified, in milliseconds since the Unix epoch.
+    pub modified: Option<f64>,
+}
+
+impl PickedFile {
+    /// Returns the extension of the file, which Flash reports as its type.
+    pub fn extension(&self) -> Option<&str> {
+        self.name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension)
+            .filter(|extension| !extension.is_empty())
+    }
+}
+
+/// The user's response to a dialog.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogResult {
+    /// The dialog was dismissed, declined or closed.
+    Cancelled,
+
+    /// The dialog was accepted.
+    Ok,
+
+    /// The text entered in a `Dialog::Prompt`.
+    Text(String),
+
+    /// The files picked in a `Dialog::OpenFiles`.
+    Files(Vec<PickedFile>),
+
+    /// The data of a `Dialog::SaveFile` was saved in the file with the given name.
+    Saved { name: String },
+}
+
+pub trait DialogBackend {
+    /// Shows a dialog to the user.
+    ///
+    /// This must not block on the answer; instead, the frontend reports the user's response
+    /// with `Player::resolve_dialog` once it is known.
+    fn show_dialog(&mut self, id: DialogId, dialog: Dialog);
+}
+
+/// Dialog backend that shows nothing.
+///
+/// Its dialogs are never resolved, like a user that never answers.
+#[derive(Default)]
+pub struct NullDialogBackend;
+
+impl NullDialogBackend {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DialogBackend for NullDialogBackend {
+    fn show_dialog(&mut self, _id: DialogId, _dialog: Dialog) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flash_file_filter() {
+        let filter = FileFilter::from_flash("Images", "*.jpg; *.png;*.");
+        assert_eq!(filter.description, "Images");
+        assert_eq!(
+            filter.extensions,
+            vec!["jpg".to_string(), "png".to_string()]
+        );
+        assert!(FileFilter::from_flash("All", "*.*").extensions.is_empty());
+    }
+}
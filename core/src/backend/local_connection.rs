@@ -0,0 +1,200 @@
+//! Passing `LocalConnection` messages between players.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A message sent with `LocalConnection.send`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalConnectionMessage {
+    /// The full name of the receiving connection, such as `example.com:game` or `_game`.
+    pub connection_name: String,
+
+    /// The name of the method to call on the receiver.
+    pub method_name: String,
+
+    /// The domain of the movie that sent the message.
+    pub sender_domain: String,
+
+    /// The arguments of the call, encoded as AMF0.
+    pub arguments: Vec<u8>,
+}
+
+impl LocalConnectionMessage {
+    /// Encodes the message, for backends that pass messages along as bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for field in [
+            self.connection_name.as_bytes(),
+            self.method_name.as_bytes(),
+            self.sender_domain.as_bytes(),
+            &self.arguments,
+        ] {
+            bytes.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(field);
+        }
+        bytes
+    }
+
+    /// Decodes a message encoded with `to_bytes`.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let mut next_field = || {
+            let rest = bytes;
+            let len = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+            let field = rest.get(4..4 + len)?;
+            bytes = &rest[4 + len..];
+            Some(field.to_vec())
+        };
+
+        Some(Self {
+            connection_name: String::from_utf8(next_field()?).ok()?,
+            method_name: String::from_utf8(next_field()?).ok()?,
+            sender_domain: String::from_utf8(next_field()?).ok()?,
+            arguments: next_field()?,
+        })
+    }
+}
+
+pub trait LocalConnectionBackend {
+    /// Claims a connection name for this player.
+    ///
+    /// Returns `false` if the name is already used by a connection, in this player or another one.
+    fn connect(&mut self, name: &str) -> bool;
+
+    /// Releases a connection name claimed with `connect`.
+    fn close(&mut self, name: &str);
+
+    /// Passes a message along to the player connected with its connection name.
+    ///
+    /// Returns `false` if no player is connected with that name.
+    fn send(&mut self, message: LocalConnectionMessage) -> bool;
+
+    /// Takes the messages sent to the connections of this player since the last call.
+    fn receive(&mut self) -> Vec<LocalConnectionMessage>;
+}
+
+/// The connections of a group of players that may talk to each other, such as all the
+/// players on a web page.
+#[derive(Clone, Default)]
+pub struct LocalConnectionHub(Rc<RefCell<HubState>>);
+
+#[derive(Default)]
+struct HubState {
+    next_id: u32,
+
+    /// The player connected with each connection name.
+    owners: HashMap<String, u32>,
+
+    /// The messages waiting to be received by each player.
+    inboxes: HashMap<u32, Vec<LocalConnectionMessage>>,
+}
+
+/// Routes messages between the players of the same `LocalConnectionHub`.
+///
+/// A player with a hub of its own can only talk to itself.
+pub struct MemoryLocalConnectionBackend {
+    hub: LocalConnectionHub,
+    id: u32,
+}
+
+impl MemoryLocalConnectionBackend {
+    pub fn new(hub: LocalConnectionHub) -> Self {
+        let id = {
+            let mut state = hub.0.borrow_mut();
+            state.next_id += 1;
+            state.next_id
+        };
+        Self { hub, id }
+    }
+}
+
+impl Default for MemoryLocalConnectionBackend {
+    fn default() -> Self {
+        Self::new(LocalConnectionHub::default())
+    }
+}
+
+impl Drop for MemoryLocalConnectionBackend {
+    fn drop(&mut self) {
+        let mut state = self.hub.0.borrow_mut();
+        state.owners.retain(|_, owner| *owner != self.id);
+        state.inboxes.remove(&self.id);
+    }
+}
+
+impl LocalConnectionBackend for MemoryLocalConnectionBackend {
+    fn connect(&mut self, name: &str) -> bool {
+        let mut state = self.hub.0.borrow_mut();
+        if state.owners.contains_key(name) {
+            return false;
+        }
+        state.owners.insert(name.to_string(), self.id);
+        true
+    }
+
+    fn close(&mut self, name: &str) {
+        let mut state = self.hub.0.borrow_mut();
+        if state.owners.get(name) == Some(&self.id) {
+            state.owners.remove(name);
+        }
+    }
+
+    fn send(&mut self, message: LocalConnectionMessage) -> bool {
+        let mut state = self.hub.0.borrow_mut();
+        match state.owners.get(&message.connection_name).copied() {
+            Some(owner) => {
+                state.inboxes.entry(owner).or_default().push(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn receive(&mut self) -> Vec<LocalConnectionMessage> {
+        self.hub
+            .0
+            .borrow_mut()
+            .inboxes
+            .remove(&self.id)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(connection_name: &str) -> LocalConnectionMessage {
+        LocalConnectionMessage {
+            connection_name: connection_name.to_string(),
+            method_name: "ping".to_string(),
+            sender_domain: "localhost".to_string(),
+            arguments: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let message = message("_game");
+        let bytes = message.to_bytes();
+        assert_eq!(LocalConnectionMessage::from_bytes(&bytes), Some(message));
+        assert_eq!(LocalConnectionMessage::from_bytes(&bytes[..5]), None);
+    }
+
+    #[test]
+    fn hub_routes_between_players() {
+        let hub = LocalConnectionHub::default();
+        let mut loader = MemoryLocalConnectionBackend::new(hub.clone());
+        let mut game = MemoryLocalConnectionBackend::new(hub);
+
+        assert!(game.connect("_game"));
+        assert!(!loader.connect("_game"));
+        assert!(loader.send(message("_game")));
+        assert!(!loader.send(message("_loader")));
+        assert!(loader.receive().is_empty());
+        assert_eq!(game.receive(), vec![message("_game")]);
+
+        drop(game);
+        assert!(loader.connect("_game"));
+    }
+}
@@ -2,6 +2,8 @@
 
 use crate::loader::Error;
 use crate::string::WStr;
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures::Stream;
 use indexmap::IndexMap;
 use std::future::Future;
 use std::path::{Path, PathBuf};
@@ -17,6 +19,18 @@ pub enum NavigationMethod {
 
     /// Indicates that navigation should generate a POST request.
     Post,
+
+    /// Indicates that navigation should generate a PUT request.
+    Put,
+
+    /// Indicates that navigation should generate a DELETE request.
+    Delete,
+
+    /// Indicates that navigation should generate a HEAD request.
+    Head,
+
+    /// Indicates that navigation should generate an OPTIONS request.
+    Options,
 }
 
 impl NavigationMethod {
@@ -38,6 +52,36 @@ impl NavigationMethod {
             None
         }
     }
+
+    /// Parses any of the HTTP methods that `URLRequest` accepts.
+    ///
+    /// Unlike `from_method_str`, this also accepts the methods only supported
+    /// by AIR.
+    pub fn from_http_method_str(method: &WStr) -> Option<Self> {
+        if method == b"PUT" {
+            Some(Self::Put)
+        } else if method == b"DELETE" {
+            Some(Self::Delete)
+        } else if method == b"HEAD" {
+            Some(Self::Head)
+        } else if method == b"OPTIONS" {
+            Some(Self::Options)
+        } else {
+            Self::from_method_str(method)
+        }
+    }
+
+    /// The name of this method in an HTTP request.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Delete => "DELETE",
+            Self::Head => "HEAD",
+            Self::Options => "OPTIONS",
+        }
+    }
 }
 
 /// A fetch request.
@@ -53,6 +97,13 @@ pub struct Request {
     ///
     /// The body consists of data and a mime type.
     body: Option<(Vec<u8>, String)>,
+
+    /// Additional headers to send along with the request.
+    headers: Vec<(String, String)>,
+
+    /// Whether redirects should be followed, rather than returned as the
+    /// response.
+    follow_redirects: bool,
 }
 
 impl Request {
@@ -62,6 +113,8 @@ impl Request {
             url,
             method: NavigationMethod::Get,
             body: None,
+            headers: Vec::new(),
+            follow_redirects: true,
         }
     }
 
@@ -71,13 +124,21 @@ impl Request {
             url,
             method: NavigationMethod::Post,
             body,
+            headers: Vec::new(),
+            follow_redirects: true,
         }
     }
 
     /// Construct a request with the given method and data
     #[allow(clippy::self_named_constructors)]
     pub fn request(method: NavigationMethod, url: String, body: Option<(Vec<u8>, String)>) -> Self {
-        Self { url, method, body }
+        Self {
+            url,
+            method,
+            body,
+            headers: Vec::new(),
+            follow_redirects: true,
+        }
     }
 
     /// Retrieve the URL of this request.
@@ -94,6 +155,26 @@ impl Request {
     pub fn body(&self) -> &Option<(Vec<u8>, String)> {
         &self.body
     }
+
+    /// Retrieve the additional headers of this request.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Set the additional headers of this request.
+    pub fn set_headers(&mut self, headers: Vec<(String, String)>) {
+        self.headers = headers;
+    }
+
+    /// Retrieve whether redirects should be followed for this request.
+    pub fn follow_redirects(&self) -> bool {
+        self.follow_redirects
+    }
+
+    /// Set whether redirects should be followed for this request.
+    pub fn set_follow_redirects(&mut self, follow_redirects: bool) {
+        self.follow_redirects = follow_redirects;
+    }
 }
 
 /// A response to a fetch request.
@@ -105,6 +186,145 @@ pub struct Response {
     pub body: Vec<u8>,
 }
 
+/// The body of a streaming response, in the order it is received.
+pub type ResponseBody = Pin<Box<dyn Stream<Item = Result<Vec<u8>, Error>>>>;
+
+/// A response to a fetch request whose body is handed over as it arrives, so
+/// that videos can be played while they are still downloading.
+pub struct StreamingResponse {
+    /// The final URL obtained after any redirects.
+    pub url: String,
+
+    /// The HTTP status code of the response, or 0 if it wasn't received over
+    /// HTTP.
+    pub status: u16,
+
+    /// The headers of the response, as far as the backend can read them.
+    pub headers: Vec<(String, String)>,
+
+    /// The position of the first byte of `body` in the requested file.
+    ///
+    /// This is only non-zero if the backend honored the requested offset.
+    pub offset: usize,
+
+    /// The length of the whole file, if known.
+    pub total_length: Option<usize>,
+
+    /// The contents of the response body.
+    pub body: ResponseBody,
+}
+
+impl StreamingResponse {
+    /// Hands over a completely received response as a single chunk.
+    pub fn from_response(response: Response) -> Self {
+        let total_length = Some(response.body.len());
+        Self {
+            url: response.url,
+            status: 0,
+            headers: Vec::new(),
+            offset: 0,
+            total_length,
+            body: Box::pin(futures::stream::iter([Ok(response.body)])),
+        }
+    }
+}
+
+/// Returns the script of a `javascript:` URL, with its percent-encoding decoded.
+///
+/// Returns `None` for any other kind of URL.
+pub fn javascript_url_script(url: &str) -> Option<String> {
+    let url = url.trim_start();
+    let scheme = url.get(.."javascript:".len())?;
+    if !scheme.eq_ignore_ascii_case("javascript:") {
+        return None;
+    }
+    let script = percent_encoding::percent_decode_str(&url[scheme.len()..]);
+    Some(script.decode_utf8_lossy().into_owned())
+}
+
+/// Parses the value of a `Content-Range` header, such as `bytes 10-99/100`.
+///
+/// Returns the position of the first byte, and the length of the whole file
+/// if the server knows it.
+pub fn parse_content_range(value: &str) -> Option<(usize, Option<usize>)> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (range, total_length) = range.split_once('/')?;
+    let (start, _end) = range.split_once('-')?;
+    let start = start.trim().parse().ok()?;
+    let total_length = match total_length.trim() {
+        "*" => None,
+        total_length => Some(total_length.parse().ok()?),
+    };
+    Some((start, total_length))
+}
+
+/// An open TCP connection, as used by RTMP.
+///
+/// The connection is closed by the backend when `outgoing` is closed, and by
+/// the remote end when `incoming` ends.
+pub struct SocketConnection {
+    /// The data received from the remote end.
+    pub incoming: UnboundedReceiver<Vec<u8>>,
+
+    /// The data to send to the remote end.
+    pub outgoing: UnboundedSender<Vec<u8>>,
+}
+
+/// A connection accepted by a `ListeningSocket`.
+pub struct AcceptedSocket {
+    /// The address of the remote end.
+    pub address: String,
+
+    /// The port of the remote end.
+    pub port: u16,
+
+    pub connection: SocketConnection,
+}
+
+/// A TCP socket listening for connections, as used by AIR's `ServerSocket`.
+///
+/// The socket is closed by the backend when `incoming` is closed.
+pub struct ListeningSocket {
+    /// The address the socket is actually bound to.
+    pub local_address: String,
+
+    /// The port the socket is actually bound to, which is chosen by the
+    /// system if 0 was asked for.
+    pub local_port: u16,
+
+    /// The connections accepted.
+    pub incoming: UnboundedReceiver<AcceptedSocket>,
+}
+
+/// A datagram received from, or to be sent to, a remote end of a UDP socket.
+pub struct Datagram {
+    /// The address of the remote end.
+    pub address: String,
+
+    /// The port of the remote end.
+    pub port: u16,
+
+    pub data: Vec<u8>,
+}
+
+/// A bound UDP socket, as used by AIR's `DatagramSocket`.
+///
+/// The socket is closed by the backend when `outgoing` is closed.
+pub struct DatagramConnection {
+    /// The address the socket is actually bound to.
+    pub local_address: String,
+
+    /// The port the socket is actually bound to, which is chosen by the
+    /// system if 0 was asked for.
+    pub local_port: u16,
+
+    /// The datagrams received from any remote end.
+    pub incoming: UnboundedReceiver<Datagram>,
+
+    /// The datagrams to send.
+    pub outgoing: UnboundedSender<Datagram>,
+}
+
 /// Type alias for pinned, boxed, and owned futures that output a falliable
 /// result of type `Result<T, E>`.
 pub type OwnedFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + 'static>>;
@@ -113,9 +333,8 @@ pub type OwnedFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + 'static
 pub trait NavigatorBackend {
     /// Cause a browser navigation to a given URL.
     ///
-    /// The URL given may be any URL scheme a browser can support. This may not
-    /// be meaningful for all environments: for example, `javascript:` URLs may
-    /// not be executable in a desktop context.
+    /// The URL given may be any URL scheme a browser can support, except for
+    /// `javascript:` URLs, which are given to `run_javascript` instead.
     ///
     /// The `target` parameter, should be treated identically to the `target`
     /// parameter on an HTML `<a>nchor` tag.
@@ -140,9 +359,63 @@ pub trait NavigatorBackend {
         vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
     );
 
+    /// Run the script of a `javascript:` URL in the page hosting the movie.
+    ///
+    /// Environments without a hosting page can't run it; embedders still hear
+    /// about the script through `EmbedderEvent::RunJavascript`.
+    fn run_javascript(&self, _script: &str) {
+        tracing::warn!("SWF tried to run a script, but there is no page to run it in");
+    }
+
     /// Fetch data and return it some time in the future.
     fn fetch(&self, request: Request) -> OwnedFuture<Response, Error>;
 
+    /// Fetch data starting at byte `offset` of the file, handing over the
+    /// body as it is received.
+    ///
+    /// Backends that can't make range requests or stream responses may
+    /// return the whole file at once, which is what this default does.
+    fn fetch_streaming(
+        &self,
+        request: Request,
+        _offset: usize,
+    ) -> OwnedFuture<StreamingResponse, Error> {
+        let fetch = self.fetch(request);
+        Box::pin(async move { Ok(StreamingResponse::from_response(fetch.await?)) })
+    }
+
+    /// Open a TCP connection to `host` on `port`.
+    ///
+    /// Environments without direct socket access may tunnel the connection
+    /// through a proxy instead.
+    fn connect_socket(&self, host: &str, port: u16) -> OwnedFuture<SocketConnection, Error>;
+
+    /// Listen for TCP connections on `address` and `port`, or on a free port
+    /// if `port` is 0.
+    ///
+    /// Environments without direct socket access can't do this, which is what
+    /// this default reports.
+    fn listen_socket(&self, _address: &str, _port: u16) -> Result<ListeningSocket, Error> {
+        Err(Error::ConnectError(
+            "Server sockets are not supported".to_string(),
+        ))
+    }
+
+    /// Bind a UDP socket to `address` on `port`, or on a free port if `port`
+    /// is 0.
+    ///
+    /// Environments without direct socket access can't do this, which is what
+    /// this default reports.
+    fn bind_datagram_socket(
+        &self,
+        _address: &str,
+        _port: u16,
+    ) -> Result<DatagramConnection, Error> {
+        Err(Error::ConnectError(
+            "Datagram sockets are not supported".to_string(),
+        ))
+    }
+
     /// Arrange for a future to be run at some point in the... well, future.
     ///
     /// This function must be called to ensure a future is actually computed.
@@ -176,6 +449,12 @@ impl NullExecutor {
     pub fn run(&mut self) {
         self.0.run();
     }
+
+    /// Runs the futures until none of them can make progress, without
+    /// waiting for the ones that are still pending.
+    pub fn run_until_stalled(&mut self) {
+        self.0.run_until_stalled();
+    }
 }
 
 impl Default for NullExecutor {
@@ -213,6 +492,8 @@ impl NullExecutor {
     }
 
     pub fn run(&mut self) {}
+
+    pub fn run_until_stalled(&mut self) {}
 }
 
 #[cfg(target_family = "wasm")]
@@ -297,6 +578,10 @@ impl NavigatorBackend for NullNavigatorBackend {
         })
     }
 
+    fn connect_socket(&self, _host: &str, _port: u16) -> OwnedFuture<SocketConnection, Error> {
+        Box::pin(async { Err(Error::ConnectError("Sockets are not supported".to_string())) })
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         self.spawner.spawn_local(future);
     }
@@ -305,3 +590,45 @@ impl NavigatorBackend for NullNavigatorBackend {
         url
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_range() {
+        assert_eq!(
+            parse_content_range("bytes 200-999/1000"),
+            Some((200, Some(1000)))
+        );
+        assert_eq!(parse_content_range("bytes 0-99/*"), Some((0, None)));
+        assert_eq!(parse_content_range("bytes */1000"), None);
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn http_methods() {
+        for method in ["GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS"] {
+            let parsed =
+                NavigationMethod::from_http_method_str(WStr::from_units(method.as_bytes()));
+            assert_eq!(parsed.map(NavigationMethod::as_str), Some(method));
+        }
+        assert!(NavigationMethod::from_http_method_str(WStr::from_units(b"PATCH")).is_none());
+        // Movies for Flash Player may only use GET and POST.
+        assert!(NavigationMethod::from_method_str(WStr::from_units(b"PUT")).is_none());
+    }
+
+    #[test]
+    fn javascript_url() {
+        assert_eq!(
+            javascript_url_script("javascript:alert('hi%20there')"),
+            Some("alert('hi there')".to_string())
+        );
+        assert_eq!(
+            javascript_url_script("  JavaScript:void(0)"),
+            Some("void(0)".to_string())
+        );
+        assert_eq!(javascript_url_script("javascript"), None);
+        assert_eq!(javascript_url_script("http://example.com/"), None);
+    }
+}
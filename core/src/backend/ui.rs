@@ -1,9 +1,50 @@
 use crate::events::{KeyCode, PlayerEvent};
 use std::borrow::Cow;
 use std::collections::HashSet;
+use thiserror::Error;
 
 pub type FullscreenError = Cow<'static, str>;
 
+#[derive(Debug, Error)]
+pub enum CameraError {
+    #[error("Camera capture is not supported")]
+    Unsupported,
+
+    #[error("No camera with index {0}")]
+    NotFound(usize),
+
+    #[error("Couldn't open the camera: {0}")]
+    Backend(String),
+}
+
+/// An image captured by a camera.
+#[derive(Clone, Debug)]
+pub struct CameraFrame {
+    pub width: u32,
+    pub height: u32,
+
+    /// The pixels of the image as opaque RGBA, row by row.
+    pub rgba: Vec<u8>,
+}
+
+/// A font installed on the system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceFont {
+    pub name: String,
+    pub is_bold: bool,
+    pub is_italic: bool,
+}
+
+/// The file of a font installed on the system.
+#[derive(Clone, Debug)]
+pub struct DeviceFontFile {
+    /// The TrueType or OpenType data of the file.
+    pub data: Vec<u8>,
+
+    /// The index of the font in the file, if it's a font collection.
+    pub index: u32,
+}
+
 pub trait UiBackend {
     fn mouse_visible(&self) -> bool;
 
@@ -12,6 +53,9 @@ pub trait UiBackend {
     /// Changes the mouse cursor image.
     fn set_mouse_cursor(&mut self, cursor: MouseCursor);
 
+    /// Returns the text on the clipboard, or an empty string if there's none.
+    fn clipboard_content(&mut self) -> String;
+
     /// Sets the clipboard to the given content.
     fn set_clipboard_content(&mut self, content: String);
 
@@ -26,6 +70,69 @@ pub trait UiBackend {
     /// by providing a direct .swf link instead.
     fn display_root_movie_download_failed_message(&self);
 
+    /// Returns the names of the available cameras, with the default camera first.
+    fn camera_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Starts capturing video from the camera with the given index in `camera_names`,
+    /// preferably at the given size and frame rate. Any previous capture is stopped.
+    fn start_camera(
+        &mut self,
+        _index: usize,
+        _width: u32,
+        _height: u32,
+        _fps: f64,
+    ) -> Result<(), CameraError> {
+        Err(CameraError::Unsupported)
+    }
+
+    /// Stops capturing video from the camera.
+    fn stop_camera(&mut self) {}
+
+    /// Returns the latest image captured by the camera, if one arrived since the last call.
+    fn take_camera_frame(&mut self) -> Option<CameraFrame> {
+        None
+    }
+
+    /// Returns the fonts installed on the system, which device text can be drawn with.
+    fn device_fonts(&self) -> Vec<DeviceFont> {
+        Vec::new()
+    }
+
+    /// Reads the file of a font returned by `device_fonts`.
+    fn load_device_font(&self, _font: &DeviceFont) -> Option<DeviceFontFile> {
+        None
+    }
+
+    /// Hands a request of the movie to open `url` in the window named `target` over to the
+    /// embedder, as the navigation policy forwards it. Returns `false` if it wasn't handled.
+    fn forward_navigation(&mut self, _url: &str, _target: &str) -> bool {
+        false
+    }
+
+    /// Hands an `fscommand` of the movie over to the embedder. Returns `false` if it wasn't
+    /// handled.
+    ///
+    /// Standalone players also get the projector commands here, including the `quit` and
+    /// `trapallkeys` commands that only they can carry out.
+    fn fs_command(&mut self, _command: &str, _args: &str) -> bool {
+        false
+    }
+
+    /// Enables input methods while an input text field is focused, so that their composed text
+    /// arrives as `PlayerEvent::ImePreedit` and `PlayerEvent::ImeCommit`.
+    fn set_ime_allowed(&mut self, _allowed: bool) {}
+
+    /// Moves the candidate window of the input method to a position in the viewport, in pixels.
+    fn set_ime_position(&mut self, _x: f64, _y: f64) {}
+
+    /// Returns how many touch points the screen can track at once, or 0 if there's no touch
+    /// screen. Touches arrive as `PlayerEvent::Touch`.
+    fn max_touch_points(&self) -> u32 {
+        0
+    }
+
     // Unused, but kept in case we need it later.
     fn message(&self, message: &str);
 }
@@ -137,6 +244,10 @@ impl UiBackend for NullUiBackend {
 
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
+    fn clipboard_content(&mut self) -> String {
+        String::new()
+    }
+
     fn set_clipboard_content(&mut self, _content: String) {}
 
     fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), FullscreenError> {
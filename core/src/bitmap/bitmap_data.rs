@@ -1302,6 +1302,7 @@ impl<'gc> BitmapData<'gc> {
             transform_stack: &mut transform_stack,
             is_offscreen: true,
             stage: context.stage,
+            focus_tracker: context.focus_tracker,
             clip_depth_stack: vec![],
             allow_mask: true,
         };
@@ -0,0 +1,857 @@
+//! Capturing audio and video from the user's microphone and camera, for
+//! `flash.media.Microphone` and `flash.media.Camera`.
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::globals::flash::events::eventdispatcher::has_event_listener;
+use crate::avm2::object::{ByteArrayObject, CameraObject, MicrophoneObject};
+use crate::avm2::{Activation as Avm2Activation, Avm2, Error as Avm2Error, Object as Avm2Object};
+use crate::backend::audio::{MonoResampler, SoundInstanceHandle, MICROPHONE_SAMPLE_RATE};
+use crate::backend::dialog::Dialog;
+use crate::backend::ui::CameraFrame;
+use crate::context::UpdateContext;
+use crate::dialog::DialogPurpose;
+use gc_arena::Collect;
+use ruffle_render::backend::RenderBackend;
+use ruffle_render::bitmap::{Bitmap, BitmapFormat, BitmapInfo};
+
+/// Whether the user allowed the movie to access their microphone and camera.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivacyPermission {
+    /// The user hasn't been asked yet.
+    Unknown,
+
+    /// The user has been asked, but hasn't answered yet.
+    Pending,
+
+    Granted,
+    Denied,
+}
+
+/// The settings and capture state of a `flash.media.Microphone`.
+#[derive(Clone, Debug)]
+pub struct Microphone {
+    /// The index of the microphone in `Microphone.names`.
+    pub index: usize,
+
+    pub name: String,
+
+    /// The amount the captured audio is amplified by, from 0 to 100. 50 leaves it unchanged.
+    pub gain: f64,
+
+    /// The sample rate of the captured audio in kHz, as it's reported to ActionScript.
+    pub rate: i32,
+
+    /// The activity level that counts as sound, from 0 to 100.
+    pub silence_level: f64,
+
+    /// The number of milliseconds without sound before the microphone becomes inactive.
+    pub silence_timeout: i32,
+
+    /// Whether the captured audio is played back through the speakers.
+    pub loopback: bool,
+
+    /// The amount of sound the microphone is detecting, from 0 to 100.
+    /// This is -1 while the microphone isn't in use.
+    pub activity_level: f64,
+
+    /// Whether the microphone detected sound within the last `silence_timeout` milliseconds.
+    is_active: bool,
+
+    /// The number of milliseconds since the microphone last detected sound.
+    silent_time: f64,
+
+    /// The number of samples delivered through `sampleData` events so far.
+    position: f64,
+
+    /// Converts the captured audio to `rate`.
+    resampler: Option<MonoResampler>,
+
+    /// The sound instance playing the captured audio, if `loopback` is set.
+    loopback_instance: Option<SoundInstanceHandle>,
+}
+
+impl Default for Microphone {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            name: String::new(),
+            gain: 50.0,
+            rate: 8,
+            silence_level: 10.0,
+            silence_timeout: 2000,
+            loopback: false,
+            activity_level: -1.0,
+            is_active: false,
+            silent_time: 0.0,
+            position: 0.0,
+            resampler: None,
+            loopback_instance: None,
+        }
+    }
+}
+
+impl Microphone {
+    /// The rates supported by Flash Player, in kHz, and their sample rates.
+    const RATES: [(i32, u32); 6] = [
+        (5, 5512),
+        (8, 8000),
+        (11, 11025),
+        (16, 16000),
+        (22, 22050),
+        (44, 44100),
+    ];
+
+    /// Sets the rate of the captured audio in kHz, rounding to the closest supported rate.
+    pub fn set_rate(&mut self, rate: i32) {
+        let closest = Self::RATES
+            .iter()
+            .min_by_key(|(khz, _)| (khz - rate).abs())
+            .map_or(8, |(khz, _)| *khz);
+        if closest != self.rate {
+            self.rate = closest;
+            self.resampler = None;
+        }
+    }
+
+    /// The sample rate of the captured audio.
+    pub fn sample_rate(&self) -> u32 {
+        Self::RATES
+            .iter()
+            .find(|(khz, _)| *khz == self.rate)
+            .map_or(8000, |(_, sample_rate)| *sample_rate)
+    }
+
+    /// Applies the gain to captured audio, and measures the activity level from it.
+    fn amplify(&mut self, samples: &mut [f32]) {
+        let gain = (self.gain / 50.0) as f32;
+        let mut peak: f32 = 0.0;
+        for sample in samples.iter_mut() {
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+            peak = peak.max(sample.abs());
+        }
+        self.activity_level = (f64::from(peak) * 100.0).round();
+    }
+
+    /// Updates whether sound was detected, returning the new state if it changed.
+    fn update_activity(&mut self, duration: f64) -> Option<bool> {
+        if self.silence_level <= 0.0 || self.activity_level > self.silence_level {
+            self.silent_time = 0.0;
+            if !self.is_active {
+                self.is_active = true;
+                return Some(true);
+            }
+        } else {
+            self.silent_time += duration;
+            if self.is_active
+                && self.silence_timeout >= 0
+                && self.silent_time >= f64::from(self.silence_timeout)
+            {
+                self.is_active = false;
+                return Some(false);
+            }
+        }
+        None
+    }
+}
+
+/// The settings and capture state of a `flash.media.Camera`.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// The index of the camera in `Camera.names`.
+    pub index: usize,
+
+    pub name: String,
+
+    /// The size of the images requested from the camera, in pixels.
+    /// The camera may deliver another size if it doesn't support this one.
+    pub width: u32,
+    pub height: u32,
+
+    /// The requested number of images per second.
+    pub fps: f64,
+
+    /// The number of images per second the camera is actually delivering.
+    pub current_fps: f64,
+
+    /// The settings used when the video is sent to a server, which Ruffle doesn't do.
+    pub bandwidth: i32,
+    pub quality: i32,
+    pub key_frame_interval: i32,
+    pub loopback: bool,
+
+    /// The amount of motion that counts as activity, from 0 to 100.
+    pub motion_level: i32,
+
+    /// The number of milliseconds without motion before the camera becomes inactive.
+    pub motion_timeout: i32,
+
+    /// The amount of motion the camera is detecting, from 0 to 100.
+    /// This is -1 while the camera isn't in use.
+    pub activity_level: f64,
+
+    /// The number of `Video` objects showing this camera.
+    pub attachments: usize,
+
+    /// The latest captured image.
+    image: Option<CameraFrame>,
+
+    /// The latest captured image, uploaded to the renderer.
+    bitmap: Option<BitmapInfo>,
+
+    /// A low-resolution copy of the brightness of the previous image, to detect motion.
+    luma: Vec<u8>,
+
+    /// Whether the camera detected motion within the last `motion_timeout` milliseconds.
+    is_active: bool,
+
+    /// The number of milliseconds since the camera last detected motion.
+    still_time: f64,
+
+    /// The number of images captured and the time passed since `current_fps` was updated.
+    frame_count: u32,
+    frame_time: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            name: String::new(),
+            width: 160,
+            height: 120,
+            fps: 15.0,
+            current_fps: 0.0,
+            bandwidth: 16384,
+            quality: 0,
+            key_frame_interval: 15,
+            loopback: false,
+            motion_level: 50,
+            motion_timeout: 2000,
+            activity_level: -1.0,
+            attachments: 0,
+            image: None,
+            bitmap: None,
+            luma: Vec::new(),
+            is_active: false,
+            still_time: 0.0,
+            frame_count: 0,
+            frame_time: 0.0,
+        }
+    }
+}
+
+impl Camera {
+    /// The distance between the pixels sampled for motion detection.
+    const MOTION_GRID: usize = 8;
+
+    /// The latest captured image, or `None` while the camera isn't in use.
+    pub fn image(&self) -> Option<&CameraFrame> {
+        self.image.as_ref()
+    }
+
+    /// The latest captured image as it can be rendered.
+    pub fn bitmap(&self) -> Option<BitmapInfo> {
+        self.bitmap.clone()
+    }
+
+    /// Stores a newly captured image, uploading it to the renderer and measuring the motion in it.
+    fn set_image(&mut self, image: CameraFrame, renderer: &mut dyn RenderBackend) {
+        let width = image.width.min(u16::MAX.into()) as u16;
+        let height = image.height.min(u16::MAX.into()) as u16;
+        let bitmap = match self.bitmap.take() {
+            Some(bitmap) if bitmap.width == width && bitmap.height == height => renderer
+                .update_texture(
+                    &bitmap.handle,
+                    image.width,
+                    image.height,
+                    image.rgba.clone(),
+                )
+                .map(|_| bitmap),
+            _ => renderer
+                .register_bitmap(Bitmap::new(
+                    image.width,
+                    image.height,
+                    BitmapFormat::Rgba,
+                    image.rgba.clone(),
+                ))
+                .map(|handle| BitmapInfo {
+                    handle,
+                    width,
+                    height,
+                }),
+        };
+        match bitmap {
+            Ok(bitmap) => self.bitmap = Some(bitmap),
+            Err(e) => tracing::error!("Couldn't upload camera image: {}", e),
+        }
+
+        self.measure_motion(&image);
+        self.image = Some(image);
+    }
+
+    /// Compares the brightness of the image with the previous one to find the activity level.
+    fn measure_motion(&mut self, image: &CameraFrame) {
+        let (width, height) = (image.width as usize, image.height as usize);
+        let mut luma =
+            Vec::with_capacity((width / Self::MOTION_GRID + 1) * (height / Self::MOTION_GRID + 1));
+        for y in (0..height).step_by(Self::MOTION_GRID) {
+            for x in (0..width).step_by(Self::MOTION_GRID) {
+                let i = (y * width + x) * 4;
+                match image.rgba.get(i..i + 3) {
+                    Some([r, g, b]) => luma.push(
+                        ((u32::from(*r) * 299 + u32::from(*g) * 587 + u32::from(*b) * 114) / 1000)
+                            as u8,
+                    ),
+                    _ => luma.push(0),
+                }
+            }
+        }
+
+        self.activity_level = if luma.len() == self.luma.len() && !luma.is_empty() {
+            let difference: u32 = luma
+                .iter()
+                .zip(&self.luma)
+                .map(|(a, b)| u32::from(a.abs_diff(*b)))
+                .sum();
+            // Motion usually only affects part of the image, so the average difference
+            // is amplified to make the level comparable to Flash Player's.
+            let average = f64::from(difference) / luma.len() as f64 / 255.0;
+            (average * 400.0).min(100.0).round()
+        } else {
+            0.0
+        };
+        self.luma = luma;
+    }
+
+    /// Updates whether motion was detected, returning the new state if it changed.
+    fn update_activity(&mut self, duration: f64) -> Option<bool> {
+        if self.motion_level <= 0 || self.activity_level > f64::from(self.motion_level) {
+            self.still_time = 0.0;
+            if !self.is_active {
+                self.is_active = true;
+                return Some(true);
+            }
+        } else {
+            self.still_time += duration;
+            if self.is_active && self.still_time >= f64::from(self.motion_timeout) {
+                self.is_active = false;
+                return Some(false);
+            }
+        }
+        None
+    }
+
+    /// Counts the images that arrived during the last `duration` milliseconds
+    /// towards `current_fps`.
+    fn update_fps(&mut self, images: u32, duration: f64) {
+        self.frame_count += images;
+        self.frame_time += duration;
+        if self.frame_time >= 1000.0 {
+            self.current_fps = f64::from(self.frame_count) * 1000.0 / self.frame_time;
+            self.frame_count = 0;
+            self.frame_time = 0.0;
+        }
+    }
+
+    /// Forgets the capture state once the camera is no longer in use.
+    fn reset(&mut self) {
+        self.activity_level = -1.0;
+        self.current_fps = 0.0;
+        self.image = None;
+        self.bitmap = None;
+        self.luma.clear();
+        self.is_active = false;
+        self.still_time = 0.0;
+        self.frame_count = 0;
+        self.frame_time = 0.0;
+    }
+}
+
+/// The camera being captured from, and the mode it was asked for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CameraMode {
+    index: usize,
+    width: u32,
+    height: u32,
+    fps: f64,
+}
+
+/// Manages the microphones and cameras used by the movie, and the permission to use them.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct CaptureManager<'gc> {
+    #[collect(require_static)]
+    permission: PrivacyPermission,
+
+    /// The microphones returned by `Microphone.getMicrophone`, one per device.
+    microphones: Vec<MicrophoneObject<'gc>>,
+
+    /// The index of the microphone device being captured from.
+    capturing: Option<usize>,
+
+    /// The cameras returned by `Camera.getCamera`, one per device.
+    cameras: Vec<CameraObject<'gc>>,
+
+    /// The camera device being captured from.
+    #[collect(require_static)]
+    capturing_camera: Option<CameraMode>,
+
+    /// The time of the last update on the player's clock, in milliseconds.
+    last_update: Option<f64>,
+}
+
+impl<'gc> CaptureManager<'gc> {
+    pub fn new() -> Self {
+        Self {
+            permission: PrivacyPermission::Unknown,
+            microphones: Vec::new(),
+            capturing: None,
+            cameras: Vec::new(),
+            capturing_camera: None,
+            last_update: None,
+        }
+    }
+
+    pub fn permission(&self) -> PrivacyPermission {
+        self.permission
+    }
+
+    /// Returns the microphone object that was created for the given device.
+    pub fn microphone(&self, index: usize) -> Option<MicrophoneObject<'gc>> {
+        self.microphones
+            .iter()
+            .find(|microphone| microphone.microphone().index == index)
+            .copied()
+    }
+
+    pub fn add_microphone(&mut self, microphone: MicrophoneObject<'gc>) {
+        self.microphones.push(microphone);
+    }
+
+    /// Returns the camera object that was created for the given device.
+    pub fn camera(&self, index: usize) -> Option<CameraObject<'gc>> {
+        self.cameras
+            .iter()
+            .find(|camera| camera.camera().index == index)
+            .copied()
+    }
+
+    pub fn add_camera(&mut self, camera: CameraObject<'gc>) {
+        self.cameras.push(camera);
+    }
+
+    /// Asks the user for access to their microphone and camera, unless they were asked already.
+    pub fn request_permission(context: &mut UpdateContext<'_, 'gc>) {
+        if context.capture_manager.permission != PrivacyPermission::Unknown {
+            return;
+        }
+        context.capture_manager.permission = PrivacyPermission::Pending;
+
+        let domain = context
+            .swf
+            .url()
+            .and_then(|url| url::Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string))
+            .filter(|host| !host.is_empty())
+            .unwrap_or_else(|| "localhost".to_string());
+        context.dialog_manager.show(
+            context.dialogs,
+            Dialog::Confirm {
+                title: "Camera and microphone access".to_string(),
+                message: format!(
+                    "{domain} is requesting access to your camera and microphone.\n\nAllow?"
+                ),
+            },
+            DialogPurpose::Privacy,
+        );
+    }
+
+    /// Applies the user's answer to the permission prompt, and tells the microphones about it.
+    pub fn resolve_permission(context: &mut UpdateContext<'_, 'gc>, granted: bool) {
+        if context.capture_manager.permission != PrivacyPermission::Pending {
+            return;
+        }
+        context.capture_manager.permission = if granted {
+            PrivacyPermission::Granted
+        } else {
+            PrivacyPermission::Denied
+        };
+
+        let (microphone_code, camera_code) = if granted {
+            ("Microphone.Unmuted", "Camera.Unmuted")
+        } else {
+            ("Microphone.Muted", "Camera.Muted")
+        };
+        let targets = context
+            .capture_manager
+            .microphones
+            .iter()
+            .map(|microphone| (Avm2Object::from(*microphone), microphone_code))
+            .chain(
+                context
+                    .capture_manager
+                    .cameras
+                    .iter()
+                    .map(|camera| (Avm2Object::from(*camera), camera_code)),
+            )
+            .collect::<Vec<_>>();
+        for (target, code) in targets {
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let event = activation.avm2().classes().statusevent.construct(
+                &mut activation,
+                &[
+                    "status".into(),
+                    false.into(),
+                    false.into(),
+                    code.into(),
+                    "status".into(),
+                ],
+            );
+            dispatch(&mut activation.context, event, target, "status");
+        }
+    }
+
+    /// Captures from the microphone and camera that are in use, and dispatches their events.
+    pub fn update(context: &mut UpdateContext<'_, 'gc>) {
+        let now = context.clock.elapsed().as_secs_f64() * 1000.0;
+        let dt = context
+            .capture_manager
+            .last_update
+            .map_or(0.0, |last_update| (now - last_update).max(0.0));
+        context.capture_manager.last_update = Some(now);
+
+        Self::update_microphones(context);
+        Self::update_cameras(context, dt);
+    }
+
+    /// Captures audio for the microphone that's in use, and dispatches its events.
+    ///
+    /// A microphone is in use while it's looped back or has `sampleData` or `activity`
+    /// listeners. Only one device is captured from at a time.
+    fn update_microphones(context: &mut UpdateContext<'_, 'gc>) {
+        let microphones = context.capture_manager.microphones.clone();
+        if microphones.is_empty() {
+            return;
+        }
+
+        let mut in_use = None;
+        if context.capture_manager.permission == PrivacyPermission::Granted {
+            for microphone in &microphones {
+                let loopback = microphone.microphone().loopback;
+                if loopback
+                    || has_listener(context, (*microphone).into(), "sampleData")
+                    || has_listener(context, (*microphone).into(), "activity")
+                {
+                    in_use = Some(*microphone);
+                    break;
+                }
+            }
+        }
+
+        let device = in_use.map(|microphone| microphone.microphone().index);
+        if device != context.capture_manager.capturing {
+            context.audio.stop_microphone();
+            if let Some(index) = device {
+                if let Err(e) = context.audio.start_microphone(index) {
+                    tracing::warn!("Couldn't capture from microphone {}: {}", index, e);
+                }
+            }
+            context.capture_manager.capturing = device;
+        }
+
+        for microphone in &microphones {
+            if in_use.map_or(true, |in_use| !Avm2Object::ptr_eq(in_use, *microphone)) {
+                let mut microphone = microphone.microphone_mut(context.gc_context);
+                microphone.activity_level = -1.0;
+                microphone.is_active = false;
+                if let Some(instance) = microphone.loopback_instance.take() {
+                    context.audio.stop_sound(instance);
+                }
+            }
+        }
+
+        if let Some(microphone) = in_use {
+            let samples = context.audio.take_microphone_samples();
+            if !samples.is_empty() {
+                Self::process_samples(context, microphone, samples);
+            }
+        }
+    }
+
+    fn process_samples(
+        context: &mut UpdateContext<'_, 'gc>,
+        object: MicrophoneObject<'gc>,
+        mut samples: Vec<f32>,
+    ) {
+        let duration = samples.len() as f64 * 1000.0 / f64::from(MICROPHONE_SAMPLE_RATE);
+        let (activity_change, loopback) = {
+            let mut microphone = object.microphone_mut(context.gc_context);
+            microphone.amplify(&mut samples);
+            (microphone.update_activity(duration), microphone.loopback)
+        };
+
+        if loopback {
+            let instance = object.microphone().loopback_instance;
+            let instance = match instance {
+                Some(instance) => Some(instance),
+                None => match context.audio.start_dynamic_sound() {
+                    Ok(instance) => {
+                        object.microphone_mut(context.gc_context).loopback_instance =
+                            Some(instance);
+                        Some(instance)
+                    }
+                    Err(e) => {
+                        tracing::error!("Couldn't play back microphone: {}", e);
+                        None
+                    }
+                },
+            };
+            if let Some(instance) = instance {
+                let frames: Vec<[f32; 2]> = samples.iter().map(|sample| [*sample; 2]).collect();
+                context
+                    .audio
+                    .append_dynamic_sound_samples(instance, &frames, false);
+            }
+        } else {
+            let instance = object
+                .microphone_mut(context.gc_context)
+                .loopback_instance
+                .take();
+            if let Some(instance) = instance {
+                context.audio.stop_sound(instance);
+            }
+        }
+
+        if let Some(activating) = activity_change {
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let event = activation.avm2().classes().activityevent.construct(
+                &mut activation,
+                &[
+                    "activity".into(),
+                    false.into(),
+                    false.into(),
+                    activating.into(),
+                ],
+            );
+            dispatch(&mut activation.context, event, object.into(), "activity");
+        }
+
+        if has_listener(context, object.into(), "sampleData") {
+            let (resampled, position) = {
+                let mut microphone = object.microphone_mut(context.gc_context);
+                let sample_rate = microphone.sample_rate();
+                let resampler = microphone
+                    .resampler
+                    .get_or_insert_with(|| MonoResampler::new(MICROPHONE_SAMPLE_RATE, sample_rate));
+                let mut resampled = Vec::with_capacity(samples.len());
+                resampler.process(samples, &mut resampled);
+                let position = microphone.position;
+                microphone.position += resampled.len() as f64;
+                (resampled, position)
+            };
+            if resampled.is_empty() {
+                return;
+            }
+
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let event = sample_data_event(&mut activation, &resampled, position);
+            dispatch(&mut activation.context, event, object.into(), "sampleData");
+        }
+    }
+
+    /// Captures images for the camera that's in use, and dispatches its events.
+    ///
+    /// A camera is in use while it's shown by a `Video` or has `activity` listeners.
+    /// Only one device is captured from at a time.
+    fn update_cameras(context: &mut UpdateContext<'_, 'gc>, dt: f64) {
+        let cameras = context.capture_manager.cameras.clone();
+        if cameras.is_empty() {
+            return;
+        }
+
+        let mut in_use = None;
+        if context.capture_manager.permission == PrivacyPermission::Granted {
+            for camera in &cameras {
+                let attachments = camera.camera().attachments;
+                if attachments > 0 || has_listener(context, (*camera).into(), "activity") {
+                    in_use = Some(*camera);
+                    break;
+                }
+            }
+        }
+
+        let mode = in_use.map(|camera| {
+            let camera = camera.camera();
+            CameraMode {
+                index: camera.index,
+                width: camera.width,
+                height: camera.height,
+                fps: camera.fps,
+            }
+        });
+        if mode != context.capture_manager.capturing_camera {
+            context.ui.stop_camera();
+            if let Some(mode) = mode {
+                if let Err(e) =
+                    context
+                        .ui
+                        .start_camera(mode.index, mode.width, mode.height, mode.fps)
+                {
+                    tracing::warn!("Couldn't capture from camera {}: {}", mode.index, e);
+                }
+            }
+            context.capture_manager.capturing_camera = mode;
+        }
+
+        for camera in &cameras {
+            if in_use.map_or(true, |in_use| !Avm2Object::ptr_eq(in_use, *camera)) {
+                camera.camera_mut(context.gc_context).reset();
+            }
+        }
+
+        let object = match in_use {
+            Some(object) => object,
+            None => return,
+        };
+        let image = context.ui.take_camera_frame();
+        let activity_change = {
+            let mut camera = object.camera_mut(context.gc_context);
+            camera.update_fps(image.is_some().into(), dt);
+            if let Some(image) = image {
+                camera.set_image(image, context.renderer);
+            }
+            camera.update_activity(dt)
+        };
+
+        if let Some(activating) = activity_change {
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let event = activation.avm2().classes().activityevent.construct(
+                &mut activation,
+                &[
+                    "activity".into(),
+                    false.into(),
+                    false.into(),
+                    activating.into(),
+                ],
+            );
+            dispatch(&mut activation.context, event, object.into(), "activity");
+        }
+    }
+}
+
+impl<'gc> Default for CaptureManager<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a `sampleData` event holding captured samples.
+fn sample_data_event<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc>,
+    samples: &[f32],
+    position: f64,
+) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+    let mut storage = ByteArrayStorage::new();
+    for sample in samples {
+        storage.write_float(*sample)?;
+    }
+    storage.set_position(0);
+    let data = ByteArrayObject::from_storage(activation, storage)?;
+    activation.avm2().classes().sampledataevent.construct(
+        activation,
+        &[
+            "sampleData".into(),
+            false.into(),
+            false.into(),
+            position.into(),
+            data.into(),
+        ],
+    )
+}
+
+/// Returns whether the microphone or camera has listeners for the given event.
+fn has_listener<'gc>(
+    context: &mut UpdateContext<'_, 'gc>,
+    target: Avm2Object<'gc>,
+    event_type: &'static str,
+) -> bool {
+    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+    has_event_listener(&mut activation, Some(target), &[event_type.into()])
+        .map_or(false, |has_listener| has_listener.coerce_to_boolean())
+}
+
+/// Dispatches an event on a microphone or camera, logging any error.
+fn dispatch<'gc>(
+    context: &mut UpdateContext<'_, 'gc>,
+    event: Result<Avm2Object<'gc>, Avm2Error<'gc>>,
+    target: Avm2Object<'gc>,
+    event_type: &str,
+) {
+    let result = event.and_then(|event| Avm2::dispatch_event(context, event, target));
+    if let Err(e) = result {
+        tracing::error!(
+            "Encountered AVM2 error when dispatching {} event: {}",
+            event_type,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_rounds_to_supported_rate() {
+        let mut microphone = Microphone::default();
+        microphone.set_rate(20);
+        assert_eq!(microphone.rate, 22);
+        assert_eq!(microphone.sample_rate(), 22050);
+        microphone.set_rate(0);
+        assert_eq!(microphone.rate, 5);
+        assert_eq!(microphone.sample_rate(), 5512);
+    }
+
+    #[test]
+    fn activity_times_out_after_silence() {
+        let mut microphone = Microphone {
+            silence_timeout: 100,
+            ..Default::default()
+        };
+
+        microphone.amplify(&mut [0.25, -0.5]);
+        assert_eq!(microphone.activity_level, 50.0);
+        assert_eq!(microphone.update_activity(60.0), Some(true));
+
+        microphone.amplify(&mut [0.0, 0.01]);
+        assert_eq!(microphone.activity_level, 1.0);
+        assert_eq!(microphone.update_activity(60.0), None);
+        assert_eq!(microphone.update_activity(60.0), Some(false));
+    }
+
+    #[test]
+    fn camera_detects_motion() {
+        let frame = |value: u8| CameraFrame {
+            width: 16,
+            height: 16,
+            rgba: [value, value, value, 255].repeat(16 * 16),
+        };
+        let mut camera = Camera {
+            motion_timeout: 100,
+            ..Default::default()
+        };
+
+        camera.measure_motion(&frame(0));
+        assert_eq!(camera.activity_level, 0.0);
+        assert_eq!(camera.update_activity(60.0), None);
+
+        camera.measure_motion(&frame(51));
+        assert_eq!(camera.activity_level, 80.0);
+        assert_eq!(camera.update_activity(60.0), Some(true));
+
+        camera.measure_motion(&frame(51));
+        assert_eq!(camera.activity_level, 0.0);
+        assert_eq!(camera.update_activity(60.0), None);
+        assert_eq!(camera.update_activity(60.0), Some(false));
+    }
+}
@@ -0,0 +1,109 @@
+//! The clock that movies observe through `getTimer`, `Date` and friends.
+//!
+//! By default this follows the system clock, but an embedder may instead drive it with
+//! its own timestamps (see `Player::tick_to`), for example when rendering a movie to video
+//! faster or slower than real time.
+
+use crate::locale::get_current_date_time;
+use chrono::{DateTime, Utc};
+use instant::Instant;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Clock {
+    /// Time passes in real time, starting at the given instant.
+    System { start: Instant },
+
+    /// Time only passes when the embedder advances it.
+    External {
+        /// The date and time at which the player was created.
+        start_date: DateTime<Utc>,
+
+        /// The time passed since the player was created, as last reported by the embedder.
+        elapsed: Duration,
+    },
+}
+
+impl Clock {
+    /// Creates a clock that follows the system clock.
+    pub fn system() -> Self {
+        Self::System {
+            start: Instant::now(),
+        }
+    }
+
+    /// Creates a clock that is driven by the embedder, starting at zero on `start_date`.
+    pub fn external(start_date: DateTime<Utc>) -> Self {
+        Self::External {
+            start_date,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn is_external(&self) -> bool {
+        matches!(self, Self::External { .. })
+    }
+
+    /// The time passed since the player was created.
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            Self::System { start } => Instant::now().duration_since(*start),
+            Self::External { elapsed, .. } => *elapsed,
+        }
+    }
+
+    /// The current date and time.
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Self::System { .. } => get_current_date_time(),
+            Self::External {
+                start_date,
+                elapsed,
+            } => {
+                *start_date
+                    + chrono::Duration::from_std(*elapsed)
+                        .unwrap_or_else(|_| chrono::Duration::zero())
+            }
+        }
+    }
+
+    /// Moves an external clock forward to `timestamp` since the player was created,
+    /// returning how much time passed.
+    ///
+    /// The clock never goes backwards; an earlier timestamp is ignored. A system clock
+    /// can't be moved, so nothing happens for it.
+    pub fn advance_to(&mut self, timestamp: Duration) -> Duration {
+        match self {
+            Self::System { .. } => Duration::ZERO,
+            Self::External { elapsed, .. } => {
+                let dt = timestamp.saturating_sub(*elapsed);
+                *elapsed += dt;
+                dt
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn external_clock_is_monotonic() {
+        let start_date = Utc.timestamp_opt(1_000_000_000, 0).unwrap();
+        let mut clock = Clock::external(start_date);
+        assert_eq!(clock.now(), start_date);
+        assert_eq!(
+            clock.advance_to(Duration::from_millis(40)),
+            Duration::from_millis(40)
+        );
+        assert_eq!(clock.advance_to(Duration::from_millis(30)), Duration::ZERO);
+        assert_eq!(
+            clock.advance_to(Duration::from_millis(50)),
+            Duration::from_millis(10)
+        );
+        assert_eq!(clock.elapsed(), Duration::from_millis(50));
+        assert_eq!((clock.now() - start_date).num_milliseconds(), 50);
+    }
+}
@@ -0,0 +1,237 @@
+//! A report of everything that kept a movie from playing the way it would in Flash Player.
+//!
+//! This gathers the unimplemented APIs a movie called, the media that couldn't be decoded and
+//! the compatibility quirks that were turned on for it, so that a report of a broken movie comes
+//! with a list of what to look into.
+
+use crate::stub::StubCollection;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+
+/// The kind of media that failed to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaKind {
+    Audio,
+    Image,
+    Video,
+}
+
+impl Display for MediaKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MediaKind::Audio => "audio",
+            MediaKind::Image => "image",
+            MediaKind::Video => "video",
+        })
+    }
+}
+
+/// Media that couldn't be decoded, and so was skipped or replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecoderFailure {
+    pub kind: MediaKind,
+
+    /// The error reported by the decoder.
+    pub message: String,
+
+    /// How many times the same error happened.
+    pub count: u32,
+}
+
+/// Keeps track of the problems encountered while a movie plays, other than stubs.
+#[derive(Debug, Default)]
+pub struct CompatibilityTracker {
+    decoder_failures: BTreeMap<(MediaKind, String), u32>,
+    quirks: BTreeSet<Cow<'static, str>>,
+}
+
+impl CompatibilityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that some media couldn't be decoded.
+    pub fn decoder_failed(&mut self, kind: MediaKind, error: &dyn Display) {
+        *self
+            .decoder_failures
+            .entry((kind, error.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Records that a compatibility quirk, which makes Ruffle behave differently from Flash
+    /// Player to get a movie working, was turned on.
+    pub fn quirk_activated(&mut self, quirk: impl Into<Cow<'static, str>>) {
+        let quirk = quirk.into();
+        if !self.quirks.contains(&quirk) {
+            tracing::info!("Activated compatibility quirk: {quirk}");
+            self.quirks.insert(quirk);
+        }
+    }
+}
+
+/// The problems encountered while a movie played.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    /// The URL of the movie.
+    pub url: String,
+
+    pub swf_version: u8,
+
+    pub is_action_script_3: bool,
+
+    /// The unimplemented APIs the movie called, in alphabetical order.
+    pub unimplemented_apis: Vec<String>,
+
+    pub decoder_failures: Vec<DecoderFailure>,
+
+    /// The compatibility quirks that were turned on.
+    pub quirks: Vec<String>,
+}
+
+impl CompatibilityReport {
+    pub fn new(
+        url: String,
+        swf_version: u8,
+        is_action_script_3: bool,
+        stubs: &StubCollection,
+        tracker: &CompatibilityTracker,
+    ) -> Self {
+        let mut unimplemented_apis: Vec<_> = stubs.iter().map(|stub| stub.to_string()).collect();
+        unimplemented_apis.sort();
+        Self {
+            url,
+            swf_version,
+            is_action_script_3,
+            unimplemented_apis,
+            decoder_failures: tracker
+                .decoder_failures
+                .iter()
+                .map(|((kind, message), count)| DecoderFailure {
+                    kind: *kind,
+                    message: message.clone(),
+                    count: *count,
+                })
+                .collect(),
+            quirks: tracker
+                .quirks
+                .iter()
+                .map(|quirk| quirk.to_string())
+                .collect(),
+        }
+    }
+
+    /// Returns whether nothing went wrong.
+    pub fn is_empty(&self) -> bool {
+        self.unimplemented_apis.is_empty()
+            && self.decoder_failures.is_empty()
+            && self.quirks.is_empty()
+    }
+
+    /// Serializes the report to pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+impl Display for CompatibilityReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Movie: {}", self.url)?;
+        writeln!(
+            f,
+            "SWF version: {} ({})",
+            self.swf_version,
+            if self.is_action_script_3 {
+                "AVM2"
+            } else {
+                "AVM1"
+            }
+        )?;
+        if self.is_empty() {
+            return writeln!(f, "No problems were encountered.");
+        }
+
+        if !self.unimplemented_apis.is_empty() {
+            writeln!(f, "\nUnimplemented APIs:")?;
+            for api in &self.unimplemented_apis {
+                writeln!(f, "- {api}")?;
+            }
+        }
+        if !self.decoder_failures.is_empty() {
+            writeln!(f, "\nDecoder failures:")?;
+            for failure in &self.decoder_failures {
+                write!(f, "- {}: {}", failure.kind, failure.message)?;
+                if failure.count > 1 {
+                    write!(f, " (x{})", failure.count)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if !self.quirks.is_empty() {
+            writeln!(f, "\nCompatibility quirks:")?;
+            for quirk in &self.quirks {
+                writeln!(f, "- {quirk}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::Stub;
+
+    #[test]
+    fn report() {
+        let mut stubs = StubCollection::new();
+        stubs.encounter(&Stub::Avm2Method {
+            class: "flash.display.Stage",
+            method: "invalidate",
+            specifics: None,
+        });
+        stubs.encounter(&Stub::Avm2Constructor {
+            class: "flash.media.Camera",
+        });
+
+        let mut tracker = CompatibilityTracker::new();
+        tracker.decoder_failed(MediaKind::Video, &"Unsupported video codec type: H264");
+        tracker.decoder_failed(MediaKind::Video, &"Unsupported video codec type: H264");
+        tracker.decoder_failed(MediaKind::Audio, &"Invalid MP3 data");
+        tracker.quirk_activated("Site lock");
+        tracker.quirk_activated("Site lock");
+
+        let report =
+            CompatibilityReport::new("file:///game.swf".to_string(), 10, true, &stubs, &tracker);
+        assert_eq!(
+            report.unimplemented_apis,
+            vec![
+                "AVM2 flash.display.Stage.invalidate()".to_string(),
+                "AVM2 flash.media.Camera constructor".to_string(),
+            ]
+        );
+        assert_eq!(
+            report.decoder_failures,
+            vec![
+                DecoderFailure {
+                    kind: MediaKind::Audio,
+                    message: "Invalid MP3 data".to_string(),
+                    count: 1,
+                },
+                DecoderFailure {
+                    kind: MediaKind::Video,
+                    message: "Unsupported video codec type: H264".to_string(),
+                    count: 2,
+                },
+            ]
+        );
+        assert_eq!(report.quirks, vec!["Site lock".to_string()]);
+        assert!(report
+            .to_string()
+            .contains("- video: Unsupported video codec type: H264 (x2)"));
+    }
+}
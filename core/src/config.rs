@@ -1,5 +1,6 @@
 use gc_arena::Collect;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Controls whether the content is letterboxed or pillarboxed when the
 /// player's aspect ratio does not match the movie's aspect ratio.
@@ -22,3 +23,110 @@ pub enum Letterbox {
     #[serde(rename = "on")]
     On,
 }
+
+/// How sounds are resampled when their sample rate differs from the audio output's.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "resamplerQuality")]
+pub enum ResamplerQuality {
+    /// Linear interpolation. This is the cheapest, but muffles sounds and adds aliasing.
+    #[serde(rename = "low")]
+    Low,
+
+    /// A windowed sinc filter with 16 taps.
+    #[default]
+    #[serde(rename = "medium")]
+    Medium,
+
+    /// A windowed sinc filter with 64 taps.
+    #[serde(rename = "high")]
+    High,
+}
+
+/// The runtime that the player presents itself as to the movie.
+///
+/// AIR-only APIs, such as `DatagramSocket` and `ServerSocket`, are only available to movies
+/// running in AIR, as they let the movie reach outside of the usual Flash Player sandbox.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "playerRuntime")]
+pub enum PlayerRuntime {
+    /// The Flash Player, either as a browser plugin or as a standalone projector.
+    #[default]
+    #[serde(rename = "flashPlayer")]
+    FlashPlayer,
+
+    /// Adobe AIR, for desktop applications.
+    #[serde(rename = "air")]
+    Air,
+}
+
+/// How the garbage collector paces its work.
+///
+/// Garbage is collected a slice at a time between frames and events, rather than all at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GcSettings {
+    /// How much the heap may grow after a collection, relative to the memory that survived it,
+    /// before the next collection starts. `1.0` lets the heap double in size.
+    pub heap_growth: f64,
+
+    /// How much collection work is done for every byte allocated while a collection is running.
+    /// Higher values finish collections sooner, at the cost of longer slices.
+    pub work_factor: f64,
+
+    /// The most time spent collecting garbage each frame.
+    ///
+    /// Once exceeded, collection is put off until the next frame, unless the collector has
+    /// fallen so far behind that the heap would grow without bound.
+    pub frame_budget: Duration,
+}
+
+impl Default for GcSettings {
+    fn default() -> Self {
+        Self {
+            heap_growth: 0.5,
+            work_factor: 1.6,
+            frame_budget: Duration::from_millis(4),
+        }
+    }
+}
+
+impl GcSettings {
+    /// Whether a slice of garbage collection should run, when `spent` was already spent
+    /// collecting this frame and the collector owes `debt` bytes of work on a heap of
+    /// `heap_size` bytes.
+    pub fn should_collect(&self, spent: Duration, debt: f64, heap_size: usize) -> bool {
+        // Once the budget is spent, only collect if the collector has fallen so far behind that
+        // the debt exceeds the size of the heap itself.
+        debt > 0.0 && (spent < self.frame_budget || debt >= heap_size as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_within_frame_budget() {
+        let settings = GcSettings::default();
+        assert!(settings.should_collect(Duration::ZERO, 100.0, 1000));
+        assert!(settings.should_collect(Duration::from_millis(3), 100.0, 1000));
+        assert!(!settings.should_collect(Duration::from_millis(4), 100.0, 1000));
+    }
+
+    #[test]
+    fn collects_past_frame_budget_when_far_behind() {
+        let settings = GcSettings::default();
+        assert!(settings.should_collect(Duration::from_millis(10), 1000.0, 1000));
+    }
+
+    #[test]
+    fn skips_without_debt() {
+        let settings = GcSettings {
+            frame_budget: Duration::MAX,
+            ..Default::default()
+        };
+        assert!(!settings.should_collect(Duration::ZERO, 0.0, 0));
+        assert!(!settings.should_collect(Duration::ZERO, -10.0, 1000));
+    }
+}
@@ -1,30 +1,56 @@
 //! Contexts and helper types passed between functions.
 
 use crate::avm1::Avm1;
-use crate::avm1::SystemProperties;
 use crate::avm1::{Object as Avm1Object, Value as Avm1Value};
-use crate::avm2::{Avm2, Object as Avm2Object, SoundChannelObject, Value as Avm2Value};
+use crate::avm1::{PlayerType, SystemProperties};
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::object::ByteArrayObject;
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Error as Avm2Error, Object as Avm2Object,
+    SoundChannelObject, TObject as Avm2TObject, Value as Avm2Value,
+};
 use crate::backend::{
     audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    dialog::DialogBackend,
+    local_connection::LocalConnectionBackend,
     log::LogBackend,
-    navigator::NavigatorBackend,
+    navigator::{javascript_url_script, NavigationMethod, NavigatorBackend},
     storage::StorageBackend,
     ui::{InputManager, UiBackend},
 };
+use crate::capture::CaptureManager;
+use crate::clock::Clock;
+use crate::compatibility::CompatibilityTracker;
 use crate::context_menu::ContextMenuState;
-use crate::display_object::{EditText, InteractiveObject, MovieClip, SoundTransform, Stage};
+use crate::cursor::CursorManager;
+use crate::dialog::DialogManager;
+use crate::display_object::{
+    EditText, InteractiveObject, MovieClip, SoundTransform, Stage, StageDisplayState,
+    StageScaleMode,
+};
+use crate::events::{EmbedderEvent, EmbedderEvents};
 use crate::external::ExternalInterface;
 use crate::focus_tracker::FocusTracker;
+use crate::font::Font;
 use crate::frame_lifecycle::FramePhase;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnectionManager;
+use crate::local_storage::StorageQuota;
+use crate::navigation::{NavigationAction, NavigationManager};
 use crate::player::Player;
+use crate::policy_file::PolicyManager;
 use crate::prelude::*;
+use crate::socket::SocketManager;
+use crate::streams::StreamManager;
 use crate::stub::StubCollection;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::timer::Timers;
+use crate::touch::TouchManager;
+use crate::worker::WorkerManager;
 use core::fmt;
 use gc_arena::{Collect, MutationContext};
+use indexmap::IndexMap;
 use instant::Instant;
 use rand::rngs::SmallRng;
 use ruffle_render::backend::RenderBackend;
@@ -49,6 +75,9 @@ pub struct UpdateContext<'a, 'gc> {
     /// A collection of stubs encountered during this movie.
     pub stub_tracker: &'a mut StubCollection,
 
+    /// The problems other than stubs encountered during this movie.
+    pub compatibility: &'a mut CompatibilityTracker,
+
     /// The library containing character definitions for this SWF.
     /// Used to instantiate a `DisplayObject` of a given ID.
     pub library: &'a mut Library<'gc>,
@@ -72,6 +101,12 @@ pub struct UpdateContext<'a, 'gc> {
     /// The audio manager, manging all actively playing sounds.
     pub audio_manager: &'a mut AudioManager<'gc>,
 
+    /// Manager of the `NetStream`s that are playing.
+    pub stream_manager: &'a mut StreamManager<'gc>,
+
+    /// Manager of the microphones used by the movie.
+    pub capture_manager: &'a mut CaptureManager<'gc>,
+
     /// The navigator backend, used by the AVM to make HTTP requests and visit webpages.
     pub navigator: &'a mut (dyn NavigatorBackend + 'a),
 
@@ -81,9 +116,45 @@ pub struct UpdateContext<'a, 'gc> {
     /// The UI backend, used to detect user interactions.
     pub ui: &'a mut dyn UiBackend,
 
+    /// The dialog backend, used to show dialogs such as permission prompts and file pickers.
+    pub dialogs: &'a mut dyn DialogBackend,
+
+    /// Manager of the dialogs waiting for the user's response.
+    pub dialog_manager: &'a mut DialogManager<'gc>,
+
+    /// The worker run by the player, and the other workers it knows of.
+    pub worker_manager: &'a mut WorkerManager<'gc>,
+
+    /// The local connection backend, used to pass `LocalConnection` messages between players.
+    pub local_connection: &'a mut dyn LocalConnectionBackend,
+
+    /// Manager of the connections opened with `LocalConnection`.
+    pub local_connection_manager: &'a mut LocalConnectionManager<'gc>,
+
+    /// Manager of the sockets opened by the movie.
+    pub socket_manager: &'a mut SocketManager<'gc>,
+
+    /// The fingers on the screen, and how they're reported to the movie.
+    pub touch_manager: &'a mut TouchManager<'gc>,
+
     /// The storage backend, used for storing persistent state
     pub storage: &'a mut dyn StorageBackend,
 
+    /// The storage quota granted to each domain, and any pending request for more.
+    pub storage_quota: &'a mut StorageQuota,
+
+    /// The policy files of the servers the movie loads data from.
+    pub policy_manager: &'a mut PolicyManager,
+
+    /// The policy for the URLs the movie opens, and the ones waiting for the user's confirmation.
+    pub navigation: &'a mut NavigationManager,
+
+    /// The cursor chosen by the movie, and the bitmap cursors it registered.
+    pub cursors: &'a mut CursorManager,
+
+    /// The embedders subscribed to the events of the player.
+    pub embedder_events: &'a mut EmbedderEvents,
+
     /// The logging backend, used for trace output capturing.
     ///
     /// **DO NOT** use this field directly, use the `avm_trace` method instead.
@@ -154,8 +225,8 @@ pub struct UpdateContext<'a, 'gc> {
     /// External interface for (for example) JavaScript <-> ActionScript interaction
     pub external_interface: &'a mut ExternalInterface<'gc>,
 
-    /// The instant at which the SWF was launched.
-    pub start_time: Instant,
+    /// The clock observed by the movie, which starts when the SWF is launched.
+    pub clock: Clock,
 
     /// The instant at which the current update started.
     pub update_start: Instant,
@@ -188,6 +259,7 @@ pub struct UpdateContext<'a, 'gc> {
 /// Convenience methods for controlling audio.
 impl<'a, 'gc> UpdateContext<'a, 'gc> {
     pub fn update_sounds(&mut self) {
+        self.update_dynamic_sounds();
         self.audio_manager.update_sounds(
             self.audio,
             self.gc_context,
@@ -217,7 +289,7 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
         sound_transform: SoundTransform,
     ) {
         self.audio_manager
-            .set_local_sound_transform(instance, sound_transform);
+            .set_local_sound_transform(self.audio, instance, sound_transform);
     }
 
     pub fn start_sound(
@@ -231,6 +303,89 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
             .start_sound(self.audio, sound, settings, owner, avm1_object)
     }
 
+    /// Starts a sound for an AVM2 `Sound`, which fails rather than stopping another sound if
+    /// too many sounds are playing.
+    pub fn start_avm2_sound(
+        &mut self,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+    ) -> Option<SoundInstanceHandle> {
+        self.audio_manager
+            .start_avm2_sound(self.audio, sound, settings)
+    }
+
+    /// Starts a dynamic sound, whose samples are generated by `sampleData` handlers of the given
+    /// AVM2 `Sound` object.
+    ///
+    /// Like in Flash, the first `sampleData` event is dispatched asynchronously, when the sounds
+    /// are next updated.
+    pub fn start_dynamic_sound(
+        &mut self,
+        sound_object: Avm2Object<'gc>,
+    ) -> Option<SoundInstanceHandle> {
+        self.audio_manager
+            .start_dynamic_sound(self.audio, sound_object)
+    }
+
+    /// Dispatches `sampleData` events for the dynamic sounds running low on samples, and queues
+    /// the samples that the handlers generate.
+    fn update_dynamic_sounds(&mut self) {
+        // Keep enough samples queued to last until the next frame.
+        let min_buffered = (2.0 * 44100.0 / *self.frame_rate).clamp(8192.0, 44100.0) as u32;
+        while let Some((instance, sound_object, position)) = self
+            .audio_manager
+            .dynamic_sound_needing_samples(self.audio, min_buffered)
+        {
+            let samples = match self.dispatch_sample_data(sound_object, position) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    tracing::error!("Encountered AVM2 error when dispatching sampleData: {}", e);
+                    // End the sound, instead of asking for samples again.
+                    Vec::new()
+                }
+            };
+            self.audio_manager
+                .append_dynamic_sound_samples(self.audio, instance, &samples);
+        }
+    }
+
+    /// Dispatches a `sampleData` event to `sound_object`, and returns the stereo sample frames
+    /// that were written to it.
+    fn dispatch_sample_data(
+        &mut self,
+        sound_object: Avm2Object<'gc>,
+        position: u32,
+    ) -> Result<Vec<[f32; 2]>, Avm2Error<'gc>> {
+        let mut activation = Avm2Activation::from_nothing(self.reborrow());
+        let data = ByteArrayObject::from_storage(&mut activation, ByteArrayStorage::new())?;
+        let sample_data_event_cls = activation.avm2().classes().sampledataevent;
+        let event = sample_data_event_cls.construct(
+            &mut activation,
+            &[
+                "sampleData".into(),
+                false.into(),
+                false.into(),
+                position.into(),
+                data.into(),
+            ],
+        )?;
+        drop(activation);
+
+        Avm2::dispatch_event(self, event, sound_object)?;
+
+        let storage = data
+            .as_bytearray()
+            .ok_or("SampleDataEvent data should be a ByteArray")?;
+        (0..storage.len() / 8)
+            .map(|i| {
+                Ok([
+                    storage.read_float_at(i * 8)?,
+                    storage.read_float_at(i * 8 + 4)?,
+                ])
+            })
+            .collect()
+    }
+
     pub fn attach_avm2_sound_channel(
         &mut self,
         instance: SoundInstanceHandle,
@@ -305,18 +460,33 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
             action_queue: self.action_queue,
             gc_context: self.gc_context,
             stub_tracker: self.stub_tracker,
+            compatibility: self.compatibility,
             library: self.library,
             player_version: self.player_version,
             needs_render: self.needs_render,
             swf: self.swf,
             audio: self.audio,
             audio_manager: self.audio_manager,
+            stream_manager: self.stream_manager,
+            capture_manager: self.capture_manager,
             navigator: self.navigator,
             renderer: self.renderer,
             log: self.log,
             ui: self.ui,
+            dialogs: self.dialogs,
+            dialog_manager: self.dialog_manager,
+            worker_manager: self.worker_manager,
+            local_connection: self.local_connection,
+            local_connection_manager: self.local_connection_manager,
+            socket_manager: self.socket_manager,
+            touch_manager: self.touch_manager,
             video: self.video,
             storage: self.storage,
+            storage_quota: self.storage_quota,
+            policy_manager: self.policy_manager,
+            navigation: self.navigation,
+            cursors: self.cursors,
+            embedder_events: self.embedder_events,
             rng: self.rng,
             stage: self.stage,
             mouse_over_object: self.mouse_over_object,
@@ -336,7 +506,7 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
             avm1: self.avm1,
             avm2: self.avm2,
             external_interface: self.external_interface,
-            start_time: self.start_time,
+            clock: self.clock,
             update_start: self.update_start,
             max_execution_duration: self.max_execution_duration,
             focus_tracker: self.focus_tracker,
@@ -355,6 +525,117 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
     pub fn avm_trace(&self, message: &str) {
         self.log.avm_trace(&message.replace('\r', "\n"));
     }
+
+    /// Returns the font that device text in the font named `name` is drawn with.
+    pub fn device_font(&mut self, name: &str, is_bold: bool, is_italic: bool) -> Option<Font<'gc>> {
+        self.library
+            .get_device_font(self.gc_context, self.ui, name, is_bold, is_italic)
+    }
+
+    /// Returns a font installed on the system to draw `c` with, for device text whose font has
+    /// no glyph for it.
+    pub fn fallback_font(&mut self, c: char, is_bold: bool, is_italic: bool) -> Option<Font<'gc>> {
+        self.library
+            .get_fallback_font(self.gc_context, self.ui, c, is_bold, is_italic)
+    }
+
+    /// Handles a request of the movie to open `url` in the window named `target`, as the
+    /// navigation policy says.
+    pub fn navigate_to_url(
+        &mut self,
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        let target = self.navigation.policy().target(target);
+        match self.navigation.policy().action(&url) {
+            NavigationAction::Allow => self.open_url(url, target, vars_method),
+            NavigationAction::Deny => {
+                tracing::warn!("Movie tried to open {}, which isn't allowed", url);
+            }
+            NavigationAction::Confirm => {
+                NavigationManager::confirm(self, url, target, vars_method);
+            }
+            NavigationAction::Forward => {
+                let notified = self.embedder_events.send(EmbedderEvent::NavigateToUrl {
+                    url: url.clone(),
+                    target: target.clone(),
+                });
+                let handled = self.ui.forward_navigation(&url, &target);
+                if !notified && !handled {
+                    tracing::warn!("Nothing handled the request to open {}", url);
+                }
+            }
+        }
+    }
+
+    /// Handles an `fscommand` of the movie, handing it over to the embedders.
+    ///
+    /// Like the Flash Player projector, a standalone player also carries out the projector
+    /// commands that change the stage itself. Returns `false` if nothing handled the command.
+    pub fn fs_command(&mut self, command: &str, args: &str) -> bool {
+        let builtin = self.system.player_type == PlayerType::StandAlone
+            && self.projector_command(command, args);
+        let notified = self.embedder_events.send(EmbedderEvent::FsCommand {
+            command: command.to_string(),
+            args: args.to_string(),
+        });
+        let forwarded = self.ui.fs_command(command, args);
+        let handled = self.external_interface.invoke_fs_command(command, args);
+        builtin || notified || forwarded || handled
+    }
+
+    /// Carries out the projector commands `fullscreen` and `allowscale`.
+    ///
+    /// `quit` and `trapallkeys` concern the window of the player, so they are left to
+    /// `UiBackend::fs_command`.
+    fn projector_command(&mut self, command: &str, args: &str) -> bool {
+        let enabled = args.trim().eq_ignore_ascii_case("true");
+        if command.eq_ignore_ascii_case("fullscreen") {
+            let display_state = if enabled {
+                StageDisplayState::FullScreen
+            } else {
+                StageDisplayState::Normal
+            };
+            self.stage.set_display_state(self, display_state);
+            true
+        } else if command.eq_ignore_ascii_case("allowscale") {
+            let scale_mode = if enabled {
+                StageScaleMode::ShowAll
+            } else {
+                StageScaleMode::NoScale
+            };
+            self.stage.set_scale_mode(self, scale_mode);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Opens `url` in the window named `target` with the navigator, and tells the embedders
+    /// about it.
+    ///
+    /// `javascript:` URLs don't navigate anywhere: their script is run in the hosting page.
+    pub fn open_url(
+        &mut self,
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        if let Some(script) = javascript_url_script(&url) {
+            self.embedder_events.send(EmbedderEvent::RunJavascript {
+                script: script.clone(),
+            });
+            self.navigator.run_javascript(&script);
+            return;
+        }
+
+        self.embedder_events.send(EmbedderEvent::NavigateToUrl {
+            url: url.clone(),
+            target: target.clone(),
+        });
+        self.navigator.navigate_to_url(url, target, vars_method);
+    }
 }
 
 /// A queued ActionScript call.
@@ -452,6 +733,9 @@ pub struct RenderContext<'a, 'gc> {
     /// The current player's stage (including all loaded levels)
     pub stage: Stage<'gc>,
 
+    /// The display object with keyboard focus, which may be surrounded by a focus rectangle.
+    pub focus_tracker: FocusTracker<'gc>,
+
     /// The stack of clip depths, used in masking.
     pub clip_depth_stack: Vec<Depth>,
 
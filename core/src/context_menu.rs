@@ -6,8 +6,8 @@
 
 use crate::avm1;
 use crate::avm2;
-use crate::display_object::Stage;
 use crate::display_object::TDisplayObject;
+use crate::display_object::{InteractiveObject, Stage, StageQuality};
 use gc_arena::Collect;
 use serde::Serialize;
 
@@ -16,6 +16,12 @@ use serde::Serialize;
 pub struct ContextMenuState<'gc> {
     info: Vec<ContextMenuItem>,
     callbacks: Vec<ContextMenuCallback<'gc>>,
+
+    /// The object that was under the mouse when the menu was opened.
+    mouse_target: Option<InteractiveObject<'gc>>,
+
+    /// The object whose menu is shown: the mouse target, or its closest ancestor with a menu.
+    owner: Option<InteractiveObject<'gc>>,
 }
 
 impl<'gc> ContextMenuState<'gc> {
@@ -32,8 +38,42 @@ impl<'gc> ContextMenuState<'gc> {
     pub fn callback(&self, index: usize) -> &ContextMenuCallback<'gc> {
         &self.callbacks[index]
     }
+    pub fn mouse_target(&self) -> Option<InteractiveObject<'gc>> {
+        self.mouse_target
+    }
+    pub fn owner(&self) -> Option<InteractiveObject<'gc>> {
+        self.owner
+    }
+    pub fn set_targets(
+        &mut self,
+        mouse_target: Option<InteractiveObject<'gc>>,
+        owner: Option<InteractiveObject<'gc>>,
+    ) {
+        self.mouse_target = mouse_target;
+        self.owner = owner;
+    }
     pub fn build_builtin_items(&mut self, item_flags: BuiltInItemFlags, stage: Stage<'gc>) {
         let root_mc = stage.root_clip().as_movie_clip();
+        if item_flags.quality {
+            // Flash shows these in a "Quality" submenu.
+            let current_quality = stage.quality();
+            let qualities = [
+                (StageQuality::Low, "Low"),
+                (StageQuality::Medium, "Medium"),
+                (StageQuality::High, "High"),
+            ];
+            for (i, (quality, name)) in qualities.into_iter().enumerate() {
+                self.push(
+                    ContextMenuItem {
+                        enabled: true,
+                        separator_before: i == 0,
+                        caption: format!("Quality: {name}"),
+                        checked: current_quality == quality,
+                    },
+                    ContextMenuCallback::Quality(quality),
+                );
+            }
+        }
         if item_flags.play {
             let is_playing_root_movie = root_mc.unwrap().playing();
             self.push(
@@ -96,7 +136,7 @@ pub struct ContextMenuItem {
 #[collect(no_drop)]
 pub enum ContextMenuCallback<'gc> {
     Zoom,
-    Quality,
+    Quality(StageQuality),
     Play,
     Loop,
     Rewind,
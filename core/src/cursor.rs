@@ -0,0 +1,398 @@
+//! The mouse cursors that movies choose with `Mouse.cursor`, including the bitmap cursors they
+//! register with `Mouse.registerCursor`.
+
+use crate::backend::ui::{MouseCursor, UiBackend};
+use ruffle_render::bitmap::BitmapHandle;
+use ruffle_render::commands::{CommandHandler, CommandList};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::transform::Transform;
+use std::collections::HashMap;
+use std::time::Duration;
+use swf::Twips;
+
+/// The cursor name that picks the cursor for the object under the mouse.
+pub const AUTO_CURSOR: &str = "auto";
+
+/// A cursor registered with `Mouse.registerCursor`.
+///
+/// Bitmap cursors are drawn over the stage by the player, with the system cursor hidden.
+pub struct BitmapCursor {
+    /// The frames of the cursor.
+    pub frames: Vec<BitmapHandle>,
+
+    /// The point of the frames placed at the mouse position, in pixels.
+    pub hot_spot: (f64, f64),
+
+    /// How many frames are shown per second, or 0 if only the first frame is.
+    pub frame_rate: f64,
+}
+
+impl BitmapCursor {
+    fn is_animated(&self) -> bool {
+        self.frames.len() > 1 && self.frame_rate > 0.0
+    }
+}
+
+/// Keeps track of the cursor chosen by the movie.
+pub struct CursorManager {
+    /// The name of the cursor set with `Mouse.cursor`.
+    cursor: String,
+
+    /// The cursor picked for the object under the mouse, shown for the `auto` cursor.
+    automatic: MouseCursor,
+
+    /// Whether the cursor was hidden with `Mouse.hide`.
+    hidden: bool,
+
+    bitmap_cursors: HashMap<String, BitmapCursor>,
+
+    /// When the current bitmap cursor was chosen on the player's clock, which its animation
+    /// starts at.
+    chosen_at: Duration,
+}
+
+impl CursorManager {
+    pub fn new() -> Self {
+        Self {
+            cursor: AUTO_CURSOR.to_string(),
+            automatic: MouseCursor::Arrow,
+            hidden: false,
+            bitmap_cursors: HashMap::new(),
+            chosen_at: Duration::ZERO,
+        }
+    }
+
+    pub fn cursor(&self) -> &str {
+        &self.cursor
+    }
+
+    /// Whether `cursor` is one of the names of `flash.ui.MouseCursor` or the name of a registered
+    /// bitmap cursor.
+    pub fn is_known(&self, cursor: &str) -> bool {
+        matches!(cursor, "arrow" | "auto" | "button" | "hand" | "ibeam")
+            || self.bitmap_cursors.contains_key(cursor)
+    }
+
+    /// Chooses the cursor named `cursor`, which is either one of the names of `flash.ui.MouseCursor`
+    /// or the name of a registered bitmap cursor. `now` is the time on the player's clock.
+    pub fn set_cursor(&mut self, cursor: String, now: Duration, ui: &mut dyn UiBackend) {
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.chosen_at = now;
+            self.update_system_cursor(ui);
+        }
+    }
+
+    /// Sets the cursor picked for the object under the mouse.
+    pub fn set_automatic(&mut self, automatic: MouseCursor, ui: &mut dyn UiBackend) {
+        self.automatic = automatic;
+        self.update_system_cursor(ui);
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool, ui: &mut dyn UiBackend) {
+        self.hidden = hidden;
+        self.update_system_cursor(ui);
+    }
+
+    pub fn register(
+        &mut self,
+        name: String,
+        cursor: BitmapCursor,
+        now: Duration,
+        ui: &mut dyn UiBackend,
+    ) {
+        if name == self.cursor {
+            self.chosen_at = now;
+        }
+        self.bitmap_cursors.insert(name, cursor);
+        self.update_system_cursor(ui);
+    }
+
+    /// Removes a registered bitmap cursor. If it was chosen, the `auto` cursor is chosen instead.
+    pub fn unregister(&mut self, name: &str, ui: &mut dyn UiBackend) {
+        if self.bitmap_cursors.remove(name).is_some() && name == self.cursor {
+            self.cursor = AUTO_CURSOR.to_string();
+            self.update_system_cursor(ui);
+        }
+    }
+
+    /// The bitmap cursor that is shown, if any.
+    fn bitmap_cursor(&self) -> Option<&BitmapCursor> {
+        if self.hidden {
+            return None;
+        }
+        self.bitmap_cursors.get(&self.cursor)
+    }
+
+    /// Whether the player needs to draw the cursor, and redraw it when the mouse moves.
+    pub fn is_drawn(&self) -> bool {
+        self.bitmap_cursor().is_some()
+    }
+
+    /// Whether the player needs to keep redrawing the cursor to animate it.
+    pub fn is_animated(&self) -> bool {
+        self.bitmap_cursor()
+            .map_or(false, BitmapCursor::is_animated)
+    }
+
+    fn update_system_cursor(&self, ui: &mut dyn UiBackend) {
+        let system_cursor = match self.cursor.as_str() {
+            "arrow" => MouseCursor::Arrow,
+            "button" => MouseCursor::Hand,
+            "hand" => MouseCursor::Grab,
+            "ibeam" => MouseCursor::IBeam,
+            _ => self.automatic,
+        };
+        ui.set_mouse_cursor(system_cursor);
+        let has_bitmap_cursor = self.bitmap_cursors.contains_key(&self.cursor);
+        ui.set_mouse_visible(!self.hidden && !has_bitmap_cursor);
+    }
+
+    /// Draws the bitmap cursor, if one is shown, with its hot spot at `position` in the viewport.
+    /// `now` is the time on the player's clock, which animated cursors follow.
+    pub fn render(&self, commands: &mut CommandList, position: (Twips, Twips), now: Duration) {
+        let cursor = match self.bitmap_cursor() {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        let frame = if cursor.is_animated() {
+            let elapsed = now.saturating_sub(self.chosen_at).as_secs_f64();
+            (elapsed * cursor.frame_rate) as usize % cursor.frames.len()
+        } else {
+            0
+        };
+        if let Some(bitmap) = cursor.frames.get(frame) {
+            let matrix = Matrix::translate(
+                position.0 - Twips::from_pixels(cursor.hot_spot.0),
+                position.1 - Twips::from_pixels(cursor.hot_spot.1),
+            );
+            commands.render_bitmap(
+                bitmap.clone(),
+                Transform {
+                    matrix,
+                    ..Default::default()
+                },
+                false,
+            );
+        }
+    }
+}
+
+impl Default for CursorManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::ui::FullscreenError;
+    use ruffle_render::backend::null::NullRenderer;
+    use ruffle_render::backend::{RenderBackend, ViewportDimensions};
+    use ruffle_render::bitmap::{Bitmap, BitmapFormat};
+    use ruffle_render::commands::Command;
+    use std::sync::Arc;
+
+    /// Keeps the system cursor that the player shows.
+    struct CursorUi {
+        cursor: MouseCursor,
+        visible: bool,
+    }
+
+    impl UiBackend for CursorUi {
+        fn mouse_visible(&self) -> bool {
+            self.visible
+        }
+
+        fn set_mouse_visible(&mut self, visible: bool) {
+            self.visible = visible;
+        }
+
+        fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
+            self.cursor = cursor;
+        }
+
+        fn clipboard_content(&mut self) -> String {
+            String::new()
+        }
+
+        fn set_clipboard_content(&mut self, _content: String) {}
+
+        fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), FullscreenError> {
+            Ok(())
+        }
+
+        fn display_unsupported_message(&self) {}
+
+        fn display_root_movie_download_failed_message(&self) {}
+
+        fn message(&self, _message: &str) {}
+    }
+
+    fn cursor_ui() -> CursorUi {
+        CursorUi {
+            cursor: MouseCursor::Arrow,
+            visible: true,
+        }
+    }
+
+    /// A bitmap cursor with `count` frames, whose hot spot is at (3, 4).
+    fn bitmap_cursor(count: usize, frame_rate: f64) -> BitmapCursor {
+        let mut renderer = NullRenderer::new(ViewportDimensions {
+            width: 0,
+            height: 0,
+            scale_factor: 1.0,
+        });
+        let frames = (0..count)
+            .map(|_| {
+                renderer
+                    .register_bitmap(Bitmap::new(8, 8, BitmapFormat::Rgba, vec![0; 8 * 8 * 4]))
+                    .unwrap()
+            })
+            .collect();
+        BitmapCursor {
+            frames,
+            hot_spot: (3.0, 4.0),
+            frame_rate,
+        }
+    }
+
+    /// The index of the frame in `frames` that's drawn, and where it's drawn.
+    fn rendered_frame(
+        manager: &CursorManager,
+        frames: &[BitmapHandle],
+        now: Duration,
+    ) -> Option<(usize, Twips, Twips)> {
+        let mut commands = CommandList::new();
+        manager.render(&mut commands, (Twips::new(100), Twips::new(200)), now);
+        assert!(commands.commands.len() <= 1);
+        match commands.commands.pop()? {
+            Command::RenderBitmap {
+                bitmap, transform, ..
+            } => {
+                let frame = frames
+                    .iter()
+                    .position(|frame| Arc::ptr_eq(&frame.0, &bitmap.0))?;
+                Some((frame, transform.matrix.tx, transform.matrix.ty))
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn system_cursors() {
+        let mut ui = cursor_ui();
+        let mut manager = CursorManager::new();
+        assert_eq!(manager.cursor(), AUTO_CURSOR);
+
+        for (name, cursor) in [
+            ("button", MouseCursor::Hand),
+            ("hand", MouseCursor::Grab),
+            ("ibeam", MouseCursor::IBeam),
+            ("arrow", MouseCursor::Arrow),
+        ] {
+            assert!(manager.is_known(name));
+            manager.set_cursor(name.to_string(), Duration::ZERO, &mut ui);
+            assert_eq!(ui.cursor, cursor);
+        }
+
+        // The automatic cursor is only shown for `auto`.
+        manager.set_automatic(MouseCursor::IBeam, &mut ui);
+        assert_eq!(ui.cursor, MouseCursor::Arrow);
+        manager.set_cursor(AUTO_CURSOR.to_string(), Duration::ZERO, &mut ui);
+        assert_eq!(ui.cursor, MouseCursor::IBeam);
+        manager.set_automatic(MouseCursor::Hand, &mut ui);
+        assert_eq!(ui.cursor, MouseCursor::Hand);
+
+        assert!(!manager.is_known("custom"));
+        assert!(!manager.is_drawn());
+    }
+
+    #[test]
+    fn hide_and_show() {
+        let mut ui = cursor_ui();
+        let mut manager = CursorManager::new();
+        manager.set_hidden(true, &mut ui);
+        assert!(manager.is_hidden());
+        assert!(!ui.visible);
+
+        manager.set_hidden(false, &mut ui);
+        assert!(!manager.is_hidden());
+        assert!(ui.visible);
+    }
+
+    #[test]
+    fn bitmap_cursor_replaces_system_cursor() {
+        let mut ui = cursor_ui();
+        let mut manager = CursorManager::new();
+        let cursor = bitmap_cursor(1, 0.0);
+        let frames = cursor.frames.clone();
+        manager.register("custom".to_string(), cursor, Duration::ZERO, &mut ui);
+        assert!(manager.is_known("custom"));
+        assert!(ui.visible);
+        assert!(!manager.is_drawn());
+
+        manager.set_cursor("custom".to_string(), Duration::ZERO, &mut ui);
+        assert!(!ui.visible);
+        assert!(manager.is_drawn());
+        assert!(!manager.is_animated());
+
+        // The hot spot is placed at the mouse position.
+        assert_eq!(
+            rendered_frame(&manager, &frames, Duration::ZERO),
+            Some((0, Twips::new(100 - 60), Twips::new(200 - 80)))
+        );
+
+        // Hidden bitmap cursors aren't drawn.
+        manager.set_hidden(true, &mut ui);
+        assert!(!manager.is_drawn());
+        assert_eq!(rendered_frame(&manager, &frames, Duration::ZERO), None);
+        manager.set_hidden(false, &mut ui);
+
+        // Removing the chosen cursor goes back to `auto`.
+        manager.unregister("custom", &mut ui);
+        assert_eq!(manager.cursor(), AUTO_CURSOR);
+        assert!(ui.visible);
+        assert!(!manager.is_known("custom"));
+        assert_eq!(rendered_frame(&manager, &frames, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn animated_bitmap_cursor() {
+        let mut ui = cursor_ui();
+        let mut manager = CursorManager::new();
+        let cursor = bitmap_cursor(2, 2.0);
+        let frames = cursor.frames.clone();
+        manager.register("custom".to_string(), cursor, Duration::ZERO, &mut ui);
+
+        // The animation starts when the cursor is chosen, at two frames per second.
+        let chosen_at = Duration::from_secs(10);
+        manager.set_cursor("custom".to_string(), chosen_at, &mut ui);
+        assert!(manager.is_animated());
+        let frame_at = |millis| {
+            rendered_frame(&manager, &frames, chosen_at + Duration::from_millis(millis))
+                .map(|(frame, _, _)| frame)
+        };
+        assert_eq!(frame_at(0), Some(0));
+        assert_eq!(frame_at(499), Some(0));
+        assert_eq!(frame_at(500), Some(1));
+        assert_eq!(frame_at(1000), Some(0));
+        assert_eq!(frame_at(1750), Some(1));
+
+        // Cursors that don't change frames aren't animated.
+        manager.register(
+            "still".to_string(),
+            bitmap_cursor(2, 0.0),
+            Duration::ZERO,
+            &mut ui,
+        );
+        manager.set_cursor("still".to_string(), chosen_at, &mut ui);
+        assert!(manager.is_drawn());
+        assert!(!manager.is_animated());
+    }
+}
@@ -0,0 +1,223 @@
+//! Dialogs shown to the user on behalf of the movie, and what to do with the user's response.
+
+use crate::avm1::{Activation as Avm1Activation, ActivationIdentifier};
+use crate::avm2::globals::flash::net::{file_reference, file_reference_list, shared_object};
+use crate::avm2::{Activation as Avm2Activation, Object as Avm2Object};
+use crate::backend::dialog::{Dialog, DialogBackend, DialogId, DialogResult};
+use crate::capture::CaptureManager;
+use crate::context::UpdateContext;
+use crate::local_storage::{QuotaRequest, UNLIMITED_QUOTA};
+use crate::navigation::NavigationManager;
+use crate::policy_file::PolicyManager;
+use gc_arena::Collect;
+
+/// What a dialog was shown for.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum DialogPurpose<'gc> {
+    /// Asking for a larger storage quota, so that `SharedObject`s may be flushed.
+    StorageQuota,
+
+    /// Asking for access to the microphone and camera.
+    Privacy,
+
+    /// Asking whether to load data from a server whose policy files don't allow it.
+    CrossDomain,
+
+    /// Asking whether to open a URL that the movie requested.
+    Navigate,
+
+    /// Picking the file of a `FileReference`, for `FileReference.browse`.
+    Browse(Avm2Object<'gc>),
+
+    /// Picking the files of a `FileReferenceList`, for `FileReferenceList.browse`.
+    BrowseList(Avm2Object<'gc>),
+
+    /// Saving data from a `FileReference`, for `FileReference.save`.
+    Save(Avm2Object<'gc>),
+
+    /// Saving a heap snapshot, taken with Ctrl+Alt+H.
+    HeapSnapshot,
+}
+
+impl<'gc> DialogPurpose<'gc> {
+    fn is_file_dialog(&self) -> bool {
+        matches!(
+            self,
+            DialogPurpose::Browse(_) | DialogPurpose::BrowseList(_) | DialogPurpose::Save(_)
+        )
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct PendingDialog<'gc> {
+    #[collect(require_static)]
+    id: DialogId,
+
+    purpose: DialogPurpose<'gc>,
+}
+
+/// Keeps track of the dialogs waiting for the user's response.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct DialogManager<'gc> {
+    next_id: u32,
+
+    pending: Vec<PendingDialog<'gc>>,
+}
+
+impl<'gc> DialogManager<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows a dialog, and remembers what to do with its result.
+    pub fn show(
+        &mut self,
+        backend: &mut dyn DialogBackend,
+        dialog: Dialog,
+        purpose: DialogPurpose<'gc>,
+    ) -> DialogId {
+        let id = DialogId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.pending.push(PendingDialog { id, purpose });
+        backend.show_dialog(id, dialog);
+        id
+    }
+
+    /// Asks the user whether the domain of `request` may store more local data.
+    pub fn show_storage_quota_prompt(
+        &mut self,
+        backend: &mut dyn DialogBackend,
+        request: &QuotaRequest,
+    ) {
+        let amount = match request.tier() {
+            UNLIMITED_QUOTA => "an unlimited amount".to_string(),
+            tier => format!("up to {} KB", tier / 1024),
+        };
+        let message = format!(
+            "{} is requesting to store {} of information on your computer.\n\nAllow?",
+            request.domain, amount
+        );
+        self.show(
+            backend,
+            Dialog::Confirm {
+                title: "Local storage".to_string(),
+                message,
+            },
+            DialogPurpose::StorageQuota,
+        );
+    }
+
+    /// Returns whether a file picker is waiting for the user. Flash only allows one at a time.
+    pub fn has_file_dialog(&self) -> bool {
+        self.pending
+            .iter()
+            .any(|dialog| dialog.purpose.is_file_dialog())
+    }
+
+    /// Applies the user's response to the dialog with the given ID.
+    pub fn resolve(context: &mut UpdateContext<'_, 'gc>, id: DialogId, result: DialogResult) {
+        let index = match context
+            .dialog_manager
+            .pending
+            .iter()
+            .position(|dialog| dialog.id == id)
+        {
+            Some(index) => index,
+            None => {
+                tracing::warn!("Response to unknown dialog {:?}", id);
+                return;
+            }
+        };
+        let purpose = context.dialog_manager.pending.remove(index).purpose;
+
+        match purpose {
+            DialogPurpose::StorageQuota => {
+                resolve_storage_quota(context, result == DialogResult::Ok)
+            }
+            DialogPurpose::Privacy => {
+                CaptureManager::resolve_permission(context, result == DialogResult::Ok)
+            }
+            DialogPurpose::CrossDomain => {
+                PolicyManager::resolve_prompt(context, result == DialogResult::Ok)
+            }
+            DialogPurpose::Navigate => {
+                NavigationManager::resolve_prompt(context, result == DialogResult::Ok)
+            }
+            DialogPurpose::Browse(target) => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                if let Err(e) = file_reference::resolve_browse(&mut activation, target, result) {
+                    tracing::error!("Error resolving FileReference.browse: {:?}", e);
+                }
+            }
+            DialogPurpose::BrowseList(target) => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                if let Err(e) = file_reference_list::resolve_browse(&mut activation, target, result)
+                {
+                    tracing::error!("Error resolving FileReferenceList.browse: {:?}", e);
+                }
+            }
+            DialogPurpose::Save(target) => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                if let Err(e) = file_reference::resolve_save(&mut activation, target, result) {
+                    tracing::error!("Error resolving FileReference.save: {:?}", e);
+                }
+            }
+            DialogPurpose::HeapSnapshot => {
+                if let DialogResult::Saved { name } = result {
+                    tracing::info!("Saved heap snapshot to {}", name);
+                }
+            }
+        }
+    }
+}
+
+/// Resolves the pending storage quota request.
+///
+/// All shared objects that were waiting on it are flushed if `granted` is true, and are notified
+/// of the outcome either way. If a request of another domain was queued, it is shown next.
+fn resolve_storage_quota(context: &mut UpdateContext<'_, '_>, granted: bool) {
+    let request = match context.storage_quota.resolve(context.storage, granted) {
+        Some(request) => request,
+        None => return,
+    };
+    if let Some(next) = context.storage_quota.current_request() {
+        context
+            .dialog_manager
+            .show_storage_quota_prompt(context.dialogs, next);
+    }
+
+    let mut avm1_activation = Avm1Activation::from_stub(
+        context.reborrow(),
+        ActivationIdentifier::root("[Storage Quota]"),
+    );
+    for name in &request.shared_objects {
+        let so = avm1_activation
+            .context
+            .avm1_shared_objects
+            .get(name)
+            .copied();
+        if let Some(so) = so {
+            if let Err(e) = crate::avm1::resolve_pending_flush(&mut avm1_activation, so, granted) {
+                tracing::error!("Error flushing AVM1 shared object `{}`: {:?}", name, e);
+            }
+        }
+    }
+
+    let mut avm2_activation = Avm2Activation::from_nothing(avm1_activation.context.reborrow());
+    for name in &request.shared_objects {
+        let so = avm2_activation
+            .context
+            .avm2_shared_objects
+            .get(name)
+            .copied();
+        if let Some(so) = so {
+            if let Err(e) = shared_object::resolve_pending_flush(&mut avm2_activation, so, granted)
+            {
+                tracing::error!("Error flushing AVM2 shared object `{}`: {:?}", name, e);
+            }
+        }
+    }
+}
@@ -51,7 +51,7 @@ pub use morph_shape::{MorphShape, MorphShapeStatic};
 pub use movie_clip::{MovieClip, Scene};
 use ruffle_render::commands::CommandHandler;
 pub use stage::{Stage, StageAlign, StageDisplayState, StageQuality, StageScaleMode, WindowMode};
-pub use text::Text;
+pub use text::{Text, TextChar};
 pub use video::Video;
 
 #[derive(Clone, Collect)]
@@ -600,6 +600,33 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
     context.transform_stack.pop();
 }
 
+/// The unloading shared by all display objects, which `TDisplayObject::unload` runs by default.
+///
+/// Overrides of `unload` that only add their own cleanup call this afterwards.
+pub fn unload_base<'gc>(this: DisplayObject<'gc>, context: &mut UpdateContext<'_, 'gc>) {
+    // Unload children.
+    if let Some(ctr) = this.as_container() {
+        for child in ctr.iter_render_list() {
+            child.unload(context);
+        }
+    }
+
+    if let Some(node) = this.maskee() {
+        node.set_masker(context.gc_context, None, true);
+    } else if let Some(node) = this.masker() {
+        node.set_maskee(context.gc_context, None, true);
+    }
+
+    // Unregister any text field variable bindings, and replace them on the unbound list.
+    if let Avm1Value::Object(object) = this.object() {
+        if let Some(stage_object) = object.as_stage_object() {
+            stage_object.unregister_text_field_bindings(context);
+        }
+    }
+
+    this.set_removed(context.gc_context, true);
+}
+
 #[enum_trait_object(
     #[derive(Clone, Collect, Debug, Copy)]
     #[collect(no_drop)]
@@ -1446,27 +1473,7 @@ pub trait TDisplayObject<'gc>:
     }
 
     fn unload(&self, context: &mut UpdateContext<'_, 'gc>) {
-        // Unload children.
-        if let Some(ctr) = self.as_container() {
-            for child in ctr.iter_render_list() {
-                child.unload(context);
-            }
-        }
-
-        if let Some(node) = self.maskee() {
-            node.set_masker(context.gc_context, None, true);
-        } else if let Some(node) = self.masker() {
-            node.set_maskee(context.gc_context, None, true);
-        }
-
-        // Unregister any text field variable bindings, and replace them on the unbound list.
-        if let Avm1Value::Object(object) = self.object() {
-            if let Some(stage_object) = object.as_stage_object() {
-                stage_object.unregister_text_field_bindings(context);
-            }
-        }
-
-        self.set_removed(context.gc_context, true);
+        unload_base((*self).into(), context)
     }
 
     fn as_stage(&self) -> Option<Stage<'gc>> {
@@ -1829,6 +1836,9 @@ impl SoundTransform {
     pub const MAX_VOLUME: i32 = 100;
 
     /// Applies another SoundTransform on top of this SoundTransform.
+    ///
+    /// `other` is the outer transform (e.g. of a parent clip), so it mixes the
+    /// channels that this transform outputs.
     pub fn concat(&mut self, other: &SoundTransform) {
         const MAX_VOLUME: i64 = SoundTransform::MAX_VOLUME as i64;
 
@@ -1839,8 +1849,9 @@ impl SoundTransform {
 
         self.volume = (i64::from(self.volume) * i64::from(other.volume) / MAX_VOLUME) as i32 & MASK;
 
-        // This is a 2x2 matrix multiply between the transforms.
-        // Done with integer math to match Flash behavior.
+        // This is a 2x2 matrix multiply between the transforms, where the
+        // channels routed to the other side by this transform are routed again
+        // by `other`. Done with integer math to match Flash behavior.
         let ll0: i64 = self.left_to_left.into();
         let lr0: i64 = self.left_to_right.into();
         let rl0: i64 = self.right_to_left.into();
@@ -1849,10 +1860,10 @@ impl SoundTransform {
         let lr1: i64 = other.left_to_right.into();
         let rl1: i64 = other.right_to_left.into();
         let rr1: i64 = other.right_to_right.into();
-        self.left_to_left = ((ll0 * ll1 + rl0 * lr1) / MAX_VOLUME) as i32 & MASK;
-        self.left_to_right = ((lr0 * ll1 + rr0 * lr1) / MAX_VOLUME) as i32 & MASK;
-        self.right_to_left = ((ll0 * rl1 + rl0 * rr1) / MAX_VOLUME) as i32 & MASK;
-        self.right_to_right = ((lr0 * rl1 + rr0 * rr1) / MAX_VOLUME) as i32 & MASK;
+        self.left_to_left = ((ll0 * ll1 + lr0 * rl1) / MAX_VOLUME) as i32 & MASK;
+        self.left_to_right = ((ll0 * lr1 + lr0 * rr1) / MAX_VOLUME) as i32 & MASK;
+        self.right_to_left = ((rl0 * ll1 + rr0 * rl1) / MAX_VOLUME) as i32 & MASK;
+        self.right_to_right = ((rl0 * lr1 + rr0 * rr1) / MAX_VOLUME) as i32 & MASK;
     }
 
     /// Returns the pan of this transform.
@@ -1962,3 +1973,129 @@ impl Default for SoundTransform {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SoundTransform;
+
+    /// Sends every channel to the other side.
+    fn swapped() -> SoundTransform {
+        SoundTransform {
+            left_to_left: 0,
+            left_to_right: 100,
+            right_to_left: 100,
+            right_to_right: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Only plays the left channel.
+    fn left_only() -> SoundTransform {
+        SoundTransform {
+            right_to_right: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn concat_identity() {
+        let mut transform = swapped();
+        transform.concat(&SoundTransform::default());
+        assert_eq!(transform, swapped());
+
+        let mut transform = SoundTransform::default();
+        transform.concat(&swapped());
+        assert_eq!(transform, swapped());
+    }
+
+    #[test]
+    fn concat_multiplies_volume() {
+        let mut transform = SoundTransform {
+            volume: 50,
+            ..Default::default()
+        };
+        transform.concat(&SoundTransform {
+            volume: 50,
+            ..Default::default()
+        });
+        assert_eq!(transform.volume, 25);
+    }
+
+    #[test]
+    fn concat_applies_outer_transform_last() {
+        // The right input is swapped to the left, and kept by the outer transform.
+        let mut transform = swapped();
+        transform.concat(&left_only());
+        assert_eq!(
+            transform,
+            SoundTransform {
+                left_to_left: 0,
+                left_to_right: 0,
+                right_to_left: 100,
+                right_to_right: 0,
+                ..Default::default()
+            }
+        );
+
+        // The left input is kept, then swapped to the right by the outer transform.
+        let mut transform = left_only();
+        transform.concat(&swapped());
+        assert_eq!(
+            transform,
+            SoundTransform {
+                left_to_left: 0,
+                left_to_right: 100,
+                right_to_left: 0,
+                right_to_right: 0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn concat_mixes_channels() {
+        let mut transform = SoundTransform {
+            left_to_left: 50,
+            left_to_right: 50,
+            right_to_left: 50,
+            right_to_right: 50,
+            ..Default::default()
+        };
+        transform.concat(&SoundTransform {
+            left_to_left: 100,
+            left_to_right: 30,
+            right_to_left: 20,
+            right_to_right: 100,
+            ..Default::default()
+        });
+        assert_eq!(
+            transform,
+            SoundTransform {
+                left_to_left: 60,
+                left_to_right: 65,
+                right_to_left: 60,
+                right_to_right: 65,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn concat_masks_to_30_bits() {
+        let mut transform = SoundTransform {
+            volume: 0x4000_0000,
+            ..Default::default()
+        };
+        transform.concat(&SoundTransform::default());
+        assert_eq!(transform.volume, 0);
+    }
+
+    #[test]
+    fn pan_round_trips() {
+        for pan in [-100, -40, 0, 40, 100] {
+            let mut transform = SoundTransform::default();
+            transform.set_pan(pan);
+            assert_eq!(transform.pan(), pan);
+        }
+    }
+}
@@ -293,10 +293,10 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
             return;
         }
 
+        // Bitmaps are never smoothed at low quality.
         let bitmap_data = self.0.read();
-        bitmap_data
-            .bitmap_data
-            .render(bitmap_data.smoothing, context);
+        let smoothing = bitmap_data.smoothing && context.stage.quality().allows_smoothing();
+        bitmap_data.bitmap_data.render(smoothing, context);
     }
 
     fn object2(&self) -> Avm2Value<'gc> {
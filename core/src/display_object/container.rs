@@ -281,6 +281,8 @@ pub trait TDisplayObjectContainer<'gc>:
             .insert_at_id(child, index);
 
         if parent_changed {
+            // Sounds playing in the child are now mixed by other parents.
+            context.set_sound_transforms_dirty();
             dispatch_added_event(this, child, child_was_on_stage, context);
         }
     }
@@ -8,18 +8,22 @@ use crate::avm1::{
     Value as Avm1Value,
 };
 use crate::avm2::{
-    Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
+    Activation as Avm2Activation, Avm2, Object as Avm2Object, StageObject as Avm2StageObject,
 };
+use crate::backend::navigator::Request;
 use crate::backend::ui::MouseCursor;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::interactive::{
     InteractiveObject, InteractiveObjectBase, TInteractiveObject,
 };
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
+use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, MovieClip, TDisplayObject};
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode};
 use crate::font::{round_down_to_pixel, Glyph, TextRenderSettings};
-use crate::html::{BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutMetrics, TextFormat};
+use crate::html::{
+    BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutMetrics, Size, StyleSheet, TextFormat,
+    TextImage,
+};
 use crate::prelude::*;
 use crate::string::{utils as string_utils, AvmString, WStr, WString};
 use crate::tag_utils::SwfMovie;
@@ -139,17 +143,47 @@ pub struct EditTextData<'gc> {
 
     /// Flags indicating the text field's settings.
     flags: EditTextFlag,
+
+    /// The display objects of the images placed into the text with `<img>`
+    /// tags, in the same order as the images of `text_spans`.
+    images: Vec<DisplayObject<'gc>>,
+
+    /// The style sheet that HTML text is styled with.
+    style_sheet: Option<StyleSheet>,
+
+    /// The script object that `style_sheet` was read from (AVM2 only).
+    style_sheet_object: Option<Avm2Object<'gc>>,
+
+    /// The text being composed with an input method, if any.
+    composition: Option<Composition<'gc>>,
+}
+
+impl<'gc> EditTextData<'gc> {
+    /// How far up the text is moved by scrolling it down.
+    fn scroll_offset(&self) -> Twips {
+        if self.scroll > 1 {
+            self.line_data
+                .get(self.scroll - 1)
+                .map_or(Twips::ZERO, |line_data| line_data.offset)
+        } else {
+            Twips::ZERO
+        }
+    }
 }
 
 // TODO: would be nicer to compute (and return) this during layout, instead of afterwards
 /// Compute line (index, offset, extent) from the layout data.
 fn get_line_data(layout: &[LayoutBox]) -> Vec<LineData> {
-    // if there are no boxes, there are no lines
-    if layout.is_empty() {
-        return Vec::new();
-    }
+    // images float beside the lines, so they aren't part of any
+    let mut boxes = layout
+        .iter()
+        .filter(|layout_box| layout_box.as_image().is_none());
 
-    let first_box = &layout[0];
+    // if there are no boxes, there are no lines
+    let first_box = match boxes.next() {
+        Some(first_box) => first_box,
+        None => return Vec::new(),
+    };
 
     let mut index = 1;
     let mut offset = first_box.bounds().offset_y();
@@ -157,7 +191,7 @@ fn get_line_data(layout: &[LayoutBox]) -> Vec<LineData> {
 
     let mut line_data = Vec::new();
 
-    for layout_box in layout.get(1..).unwrap() {
+    for layout_box in boxes {
         let bounds = layout_box.bounds();
 
         // if the top of the new box is lower than the bottom of the old box, it's a new line
@@ -202,7 +236,7 @@ impl<'gc> EditText<'gc> {
 
         let text = WString::from_utf8(&text.to_str_lossy(encoding));
         let mut text_spans = if swf_tag.is_html() {
-            FormatSpans::from_html(&text, default_format, swf_tag.is_multiline())
+            FormatSpans::from_html(&text, default_format, None, swf_tag.is_multiline())
         } else {
             FormatSpans::from_text(text, default_format)
         };
@@ -226,6 +260,7 @@ impl<'gc> EditText<'gc> {
             bounds.width() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0),
             swf_tag.is_word_wrap(),
             !swf_tag.use_outlines(),
+            &[],
         );
         let line_data = get_line_data(&layout);
 
@@ -283,6 +318,10 @@ impl<'gc> EditText<'gc> {
                 line_data,
                 scroll: 1,
                 max_chars: 0,
+                images: Vec::new(),
+                style_sheet: None,
+                style_sheet_object: None,
+                composition: None,
             },
         ));
 
@@ -339,6 +378,7 @@ impl<'gc> EditText<'gc> {
         edit_text.text_spans = FormatSpans::from_text(text.into(), default_format);
         drop(edit_text);
 
+        self.load_images(context);
         self.relayout(context);
     }
 
@@ -358,10 +398,12 @@ impl<'gc> EditText<'gc> {
             write.text_spans = FormatSpans::from_html(
                 text,
                 default_format,
+                write.style_sheet.as_ref(),
                 write.flags.contains(EditTextFlag::MULTILINE),
             );
             drop(write);
 
+            self.load_images(context);
             self.relayout(context);
         } else {
             self.set_text(text, context);
@@ -372,6 +414,119 @@ impl<'gc> EditText<'gc> {
         self.0.read().text_spans.text().len()
     }
 
+    /// The script object of the style sheet that HTML text is styled with.
+    pub fn style_sheet_object(self) -> Option<Avm2Object<'gc>> {
+        self.0.read().style_sheet_object
+    }
+
+    /// Set the style sheet that HTML text set from now on is styled with.
+    pub fn set_style_sheet(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        style_sheet: Option<(StyleSheet, Avm2Object<'gc>)>,
+    ) {
+        let mut write = self.0.write(gc_context);
+        match style_sheet {
+            Some((style_sheet, object)) => {
+                write.style_sheet = Some(style_sheet);
+                write.style_sheet_object = Some(object);
+            }
+            None => {
+                write.style_sheet = None;
+                write.style_sheet_object = None;
+            }
+        }
+    }
+
+    /// The display object of the image placed into the text with the given
+    /// `id`.
+    pub fn image_reference(self, id: &WStr) -> Option<DisplayObject<'gc>> {
+        let read = self.0.read();
+        read.text_spans
+            .images()
+            .iter()
+            .zip(read.images.iter())
+            .find(|(image, _)| image.id.as_deref() == Some(id))
+            .map(|(_, object)| *object)
+    }
+
+    /// Replace the display objects of the images with ones for the images
+    /// currently in the text.
+    ///
+    /// Images naming a symbol of the library are instantiated from it, and
+    /// any others are loaded from their URL.
+    fn load_images(self, context: &mut UpdateContext<'_, 'gc>) {
+        let old_images = std::mem::take(&mut self.0.write(context.gc_context).images);
+        for object in old_images {
+            object.unload(context);
+        }
+
+        let images = self.0.read().text_spans.images().to_vec();
+        let movie = self.movie();
+        let mut objects = Vec::with_capacity(images.len());
+        for image in images {
+            let src = AvmString::new(context.gc_context, image.src.clone());
+            let symbol = context
+                .library
+                .library_for_movie(movie.clone())
+                .and_then(|library| {
+                    library
+                        .instantiate_by_export_name(src, context.gc_context)
+                        .ok()
+                });
+            let object =
+                symbol.unwrap_or_else(|| MovieClip::new(movie.clone(), context.gc_context).into());
+
+            object.set_parent(context.gc_context, Some(self.into()));
+            if let Some(id) = image.id {
+                object.set_name(context.gc_context, AvmString::new(context.gc_context, id));
+            }
+            object.post_instantiation(context, None, Instantiator::Movie, false);
+
+            if symbol.is_none() {
+                let future = context.load_manager.load_movie_into_clip(
+                    context.player.clone(),
+                    object,
+                    Request::get(image.src.to_utf8_lossy().into_owned()),
+                    None,
+                    None,
+                    None,
+                );
+                context.navigator.spawn_future(future);
+            }
+
+            objects.push(object);
+        }
+
+        self.0.write(context.gc_context).images = objects;
+    }
+
+    /// The size an image is laid out with: the one given by its tag, or else
+    /// the size of its content.
+    fn image_size(image: &TextImage, object: Option<&DisplayObject<'gc>>) -> Size<Twips> {
+        let bounds = object
+            .map(|object| object.bounds_with_transform(&Matrix::IDENTITY))
+            .unwrap_or_default();
+        Size::from((
+            image.width.map_or(bounds.width(), Twips::from_pixels),
+            image.height.map_or(bounds.height(), Twips::from_pixels),
+        ))
+    }
+
+    /// Whether an image has a different size than the one it was laid out
+    /// with, such as after its content has loaded.
+    fn has_resized_images(self) -> bool {
+        let read = self.0.read();
+        let images = read.text_spans.images();
+        read.layout.iter().any(|layout_box| {
+            layout_box.as_image().map_or(false, |index| {
+                let size = Self::image_size(&images[index], read.images.get(index));
+                layout_box.bounds().width() != size.width()
+                    || layout_box.bounds().height() != size.height()
+            })
+        })
+    }
+
     pub fn new_text_format(self) -> TextFormat {
         self.0.read().text_spans.default_format().clone()
     }
@@ -546,10 +701,27 @@ impl<'gc> EditText<'gc> {
         text: &WStr,
         context: &mut UpdateContext<'_, 'gc>,
     ) {
-        self.0
-            .write(context.gc_context)
+        let mut write = self.0.write(context.gc_context);
+        let image_count = write.text_spans.images().len();
+        let first_replaced = write
             .text_spans
-            .replace_text(from, to, text, None);
+            .images()
+            .iter()
+            .position(|image| image.position > from)
+            .unwrap_or(image_count);
+        write.text_spans.replace_text(from, to, text, None);
+
+        // The images within the replaced text have been removed along with it.
+        let removed_count = image_count - write.text_spans.images().len();
+        let removed: Vec<_> = write
+            .images
+            .drain(first_replaced..first_replaced + removed_count)
+            .collect();
+        drop(write);
+
+        for object in removed {
+            object.unload(context);
+        }
         self.relayout(context);
     }
 
@@ -682,7 +854,7 @@ impl<'gc> EditText<'gc> {
 
     /// Internal padding between the bounds of the EditText and the text.
     /// Applies to each side.
-    const INTERNAL_PADDING: f64 = 2.0;
+    pub const INTERNAL_PADDING: f64 = 2.0;
 
     /// Relayout the `EditText`.
     ///
@@ -705,6 +877,13 @@ impl<'gc> EditText<'gc> {
             edit_text.text_spans.clear_displayed_text();
         }
 
+        let image_sizes: Vec<_> = edit_text
+            .text_spans
+            .images()
+            .iter()
+            .enumerate()
+            .map(|(index, image)| Self::image_size(image, edit_text.images.get(index)))
+            .collect();
         let (new_layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
             &edit_text.text_spans,
             context,
@@ -712,6 +891,7 @@ impl<'gc> EditText<'gc> {
             edit_text.bounds.width() - padding,
             is_word_wrap,
             !edit_text.flags.contains(EditTextFlag::USE_OUTLINES),
+            &image_sizes,
         );
 
         edit_text.line_data = get_line_data(&new_layout);
@@ -827,6 +1007,75 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// Render the text being composed with an input method over the text at `(x, y)`.
+    fn render_composition(
+        self,
+        context: &mut RenderContext<'_, 'gc>,
+        composition: &Composition<'gc>,
+        x: Twips,
+        y: Twips,
+    ) {
+        let edit_text = self.0.read();
+        context.transform_stack.push(&Transform {
+            matrix: Matrix::translate(x, y),
+            ..Default::default()
+        });
+
+        let bounds = composition.bounds;
+        let background = context.transform_stack.transform().matrix
+            * Matrix::create_box(
+                bounds.width().to_pixels() as f32,
+                bounds.height().to_pixels() as f32,
+                0.0,
+                bounds.offset_x(),
+                bounds.offset_y(),
+            );
+        context
+            .commands
+            .draw_rect(edit_text.background_color.clone(), background);
+
+        for layout_box in composition.layout.iter() {
+            if let Some((text, _tf, font, params, color)) =
+                layout_box.as_renderable_text(&composition.text)
+            {
+                let origin = layout_box.bounds().origin();
+                context.transform_stack.push(&Transform {
+                    matrix: Matrix::translate(origin.x(), origin.y()),
+                    ..Default::default()
+                });
+                let baseline_adjustment =
+                    font.get_baseline_for_height(params.height()) - params.height();
+                font.evaluate(
+                    text,
+                    self.text_transform(color.clone(), baseline_adjustment),
+                    params,
+                    |_pos, transform, glyph: &Glyph, _advance, _x| {
+                        glyph.render(
+                            context,
+                            transform,
+                            &edit_text.render_settings,
+                            params.height(),
+                        );
+                    },
+                );
+
+                // Input methods underline the text until it's committed.
+                let underline = context.transform_stack.transform().matrix
+                    * Matrix::create_box(
+                        layout_box.bounds().width().to_pixels() as f32,
+                        1.0,
+                        0.0,
+                        Twips::ZERO,
+                        params.height() + Twips::from_pixels(1.0),
+                    );
+                context.commands.draw_rect(color, underline);
+                context.transform_stack.pop();
+            }
+        }
+
+        context.transform_stack.pop();
+    }
+
     /// Render a layout box, plus its children.
     fn render_layout_box(self, context: &mut RenderContext<'_, 'gc>, lbox: &LayoutBox<'gc>) {
         let origin = lbox.bounds().origin();
@@ -892,41 +1141,47 @@ impl<'gc> EditText<'gc> {
                             context.commands.draw_rect(Color::BLACK, selection_box);
 
                             // Set text color to white
-                            context.transform_stack.push(&Transform {
-                                matrix: transform.matrix,
-                                color_transform: ColorTransform::default(),
-                            });
+                            glyph.render(
+                                context,
+                                &Transform {
+                                    matrix: transform.matrix,
+                                    color_transform: ColorTransform::default(),
+                                },
+                                &edit_text.render_settings,
+                                params.height(),
+                            );
                         }
                         _ => {
-                            context.transform_stack.push(transform);
+                            glyph.render(
+                                context,
+                                transform,
+                                &edit_text.render_settings,
+                                params.height(),
+                            );
                         }
                     }
 
-                    // Render glyph.
-                    let glyph_shape_handle = glyph.shape_handle(context.renderer);
-                    context
-                        .commands
-                        .render_shape(glyph_shape_handle, context.transform_stack.transform());
-                    context.transform_stack.pop();
-
                     if let Some((caret_pos, length)) = caret {
-                        if caret_pos == pos {
-                            let caret = context.transform_stack.transform().matrix
-                                * Matrix::create_box(
-                                    1.0,
-                                    params.height().to_pixels() as f32,
-                                    0.0,
-                                    x + Twips::from_pixels(-1.0),
-                                    Twips::from_pixels(2.0),
-                                );
-                            context.commands.draw_rect(color.clone(), caret);
+                        // Right-to-left glyphs start at their right edge.
+                        let (start_x, end_x) = if params.is_right_to_left() {
+                            (x + advance, x + Twips::from_pixels(-1.0))
+                        } else {
+                            (x + Twips::from_pixels(-1.0), x + advance)
+                        };
+                        let caret_x = if caret_pos == pos {
+                            Some(start_x)
                         } else if pos == length - 1 && caret_pos == length {
+                            Some(end_x)
+                        } else {
+                            None
+                        };
+                        if let Some(caret_x) = caret_x {
                             let caret = context.transform_stack.transform().matrix
                                 * Matrix::create_box(
                                     1.0,
                                     params.height().to_pixels() as f32,
                                     0.0,
-                                    x + advance,
+                                    caret_x,
                                     Twips::from_pixels(2.0),
                                 );
                             context.commands.draw_rect(color.clone(), caret);
@@ -940,6 +1195,30 @@ impl<'gc> EditText<'gc> {
             drawing.render(context);
         }
 
+        if let Some(image) = lbox
+            .as_image()
+            .and_then(|index| edit_text.images.get(index))
+        {
+            // Scale the content of the image to the size it was laid out with.
+            let content = image.bounds_with_transform(&Matrix::IDENTITY);
+            let scale = |size: Twips, content_size: Twips| {
+                if content_size > Twips::ZERO {
+                    size.get() as f32 / content_size.get() as f32
+                } else {
+                    1.0
+                }
+            };
+            context.transform_stack.push(&Transform {
+                matrix: Matrix::scale(
+                    scale(lbox.bounds().width(), content.width()),
+                    scale(lbox.bounds().height(), content.height()),
+                ),
+                ..Default::default()
+            });
+            image.render(context);
+            context.transform_stack.pop();
+        }
+
         context.transform_stack.pop();
     }
 
@@ -1165,7 +1444,10 @@ impl<'gc> EditText<'gc> {
                             && local_position.1 >= Twips::ZERO
                             && local_position.1 <= params.height()
                         {
-                            if local_position.0 >= x + (advance / 2) {
+                            // Right-to-left glyphs start at their right edge.
+                            let is_after = (local_position.0 >= x + (advance / 2))
+                                != params.is_right_to_left();
+                            if is_after {
                                 result = Some(string_utils::next_char_boundary(text, pos));
                             } else {
                                 result = Some(pos);
@@ -1182,6 +1464,228 @@ impl<'gc> EditText<'gc> {
         None
     }
 
+    /// Find the URL and target window of the link under a point, if any.
+    fn link_at_screen_position(self, position: (Twips, Twips)) -> Option<(WString, WString)> {
+        let text = self.0.read();
+        let position = self.global_to_local(position);
+        let position = (
+            position.0 + Twips::from_pixels(Self::INTERNAL_PADDING),
+            position.1 + Twips::from_pixels(Self::INTERNAL_PADDING),
+        );
+
+        for layout_box in text.layout.iter() {
+            let (url, target) = match layout_box.content() {
+                LayoutContent::Text { text_format, .. } => match &text_format.url {
+                    Some(url) if !url.is_empty() => (url, &text_format.target),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            let origin = layout_box.bounds().origin();
+            let mut matrix = Matrix::translate(origin.x(), origin.y());
+            matrix.invert();
+            let local_position = matrix * position;
+
+            if let Some((box_text, _tf, font, params, color)) =
+                layout_box.as_renderable_text(text.text_spans.text())
+            {
+                let mut is_over = false;
+                let baseline_adjustment =
+                    font.get_baseline_for_height(params.height()) - params.height();
+                font.evaluate(
+                    box_text,
+                    self.text_transform(color, baseline_adjustment),
+                    params,
+                    |_pos, _transform, _glyph: &Glyph, advance, x| {
+                        is_over |= local_position.0 >= x
+                            && local_position.0 <= x + advance
+                            && local_position.1 >= Twips::ZERO
+                            && local_position.1 <= params.height();
+                    },
+                );
+                if is_over {
+                    return Some((url.clone(), target.clone().unwrap_or_default()));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Follow a link of the text.
+    ///
+    /// `event:` links dispatch a `link` `TextEvent` with the rest of the URL
+    /// (AVM2), `asfunction:` links call a function of the parent clip with an
+    /// argument (AVM1), and any other links are navigated to.
+    fn open_link(self, context: &mut UpdateContext<'_, 'gc>, url: &WStr, target: &WStr) {
+        if let Some(text) = url.strip_prefix(WStr::from_units(b"event:")) {
+            if let Avm2Value::Object(object) = self.object2() {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                let text = AvmString::new(activation.context.gc_context, text);
+                let text_event_cls = activation.avm2().classes().textevent;
+                match text_event_cls.construct(
+                    &mut activation,
+                    &["link".into(), true.into(), false.into(), text.into()],
+                ) {
+                    Ok(event) => {
+                        if let Err(e) = Avm2::dispatch_event(&mut activation.context, event, object)
+                        {
+                            tracing::error!(
+                                "Encountered AVM2 error when dispatching `link` event: {}",
+                                e
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Encountered AVM2 error when creating `link` event: {}", e);
+                    }
+                }
+            }
+        } else if let Some(call) = url.strip_prefix(WStr::from_units(b"asfunction:")) {
+            let (name, argument) = match call.find(b',') {
+                Some(comma) => (&call[..comma], &call[comma + 1..]),
+                None => (call, WStr::from_units(b"")),
+            };
+            if let Some(parent) = self.avm1_parent() {
+                if let Avm1Value::Object(object) = parent.object() {
+                    let name = AvmString::new(context.gc_context, name);
+                    let argument = AvmString::new(context.gc_context, argument);
+                    Avm1::run_stack_frame_for_method(
+                        parent,
+                        object,
+                        context,
+                        name,
+                        &[argument.into()],
+                    );
+                }
+            }
+        } else {
+            context.navigate_to_url(
+                url.to_utf8_lossy().into_owned(),
+                target.to_utf8_lossy().into_owned(),
+                None,
+            );
+        }
+    }
+
+    /// Returns where the caret is in the laid out text, and the height of the text it's in.
+    fn caret_position(self) -> Option<(Twips, Twips, Twips)> {
+        let edit_text = self.0.read();
+        let caret = edit_text.selection?.to();
+        if edit_text.layout.is_empty() {
+            let size = edit_text
+                .text_spans
+                .default_format()
+                .size
+                .unwrap_or_default();
+            return Some((Twips::ZERO, Twips::ZERO, Twips::from_pixels(size)));
+        }
+
+        for layout_box in edit_text.layout.iter() {
+            let (start, end) = match layout_box.content() {
+                LayoutContent::Text { start, end, .. } => (*start, *end),
+                _ => continue,
+            };
+            if caret < start || caret > end {
+                continue;
+            }
+            if let Some((text, _tf, font, params, color)) =
+                layout_box.as_renderable_text(edit_text.text_spans.displayed_text())
+            {
+                let mut caret_x = None;
+                let mut end_x = Twips::ZERO;
+                let baseline_adjustment =
+                    font.get_baseline_for_height(params.height()) - params.height();
+                font.evaluate(
+                    text,
+                    self.text_transform(color, baseline_adjustment),
+                    params,
+                    |pos, _transform, _glyph: &Glyph, advance, x| {
+                        // Right-to-left glyphs start at their right edge.
+                        let (glyph_start, glyph_end) = if params.is_right_to_left() {
+                            (x + advance, x)
+                        } else {
+                            (x, x + advance)
+                        };
+                        if start + pos == caret {
+                            caret_x = Some(glyph_start);
+                        }
+                        end_x = glyph_end;
+                    },
+                );
+                let origin = layout_box.bounds().origin();
+                let x = caret_x.unwrap_or(end_x);
+                return Some((origin.x() + x, origin.y(), params.height()));
+            }
+        }
+
+        None
+    }
+
+    /// Shows the text being composed with an input method at the caret, or stops showing it if
+    /// `text` is empty.
+    pub fn set_composition(self, text: &WStr, context: &mut UpdateContext<'_, 'gc>) {
+        if text.is_empty() || !self.is_editable() {
+            self.0.write(context.gc_context).composition = None;
+            return;
+        }
+
+        let edit_text = self.0.read();
+        // The composed text looks like text typed at the caret.
+        let caret = edit_text.selection.map_or(0, |selection| selection.start());
+        let format = if caret > 0 {
+            edit_text.text_spans.get_text_format(caret - 1, caret)
+        } else {
+            edit_text.text_spans.default_format().clone()
+        };
+        let spans = FormatSpans::from_text(text.into(), format);
+        let movie = edit_text.static_data.swf.clone();
+        let width = edit_text.bounds.width();
+        let is_device_font = !edit_text.flags.contains(EditTextFlag::USE_OUTLINES);
+        drop(edit_text);
+
+        let (layout, bounds) = LayoutBox::lower_from_text_spans(
+            &spans,
+            context,
+            movie,
+            width,
+            false,
+            is_device_font,
+            &[],
+        );
+        self.0.write(context.gc_context).composition = Some(Composition {
+            text: text.into(),
+            layout,
+            bounds,
+        });
+        self.update_ime_position(context);
+    }
+
+    /// Takes the text composed with an input method.
+    pub fn commit_composition(self, text: &WStr, context: &mut UpdateContext<'_, 'gc>) {
+        self.0.write(context.gc_context).composition = None;
+        self.insert_user_text(text, context);
+    }
+
+    /// Moves the candidate window of the input method below the caret.
+    pub fn update_ime_position(self, context: &mut UpdateContext<'_, 'gc>) {
+        let (x, y, height) = match self.caret_position() {
+            Some(position) => position,
+            None => return,
+        };
+        let edit_text = self.0.read();
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING);
+        let position = (
+            edit_text.bounds.x_min + padding - Twips::from_pixels(edit_text.hscroll) + x,
+            edit_text.bounds.y_min + padding - edit_text.scroll_offset() + y + height,
+        );
+        drop(edit_text);
+
+        let (x, y) = *context.stage.base().matrix() * self.local_to_global(position);
+        context.ui.set_ime_position(x.to_pixels(), y.to_pixels());
+    }
+
     pub fn text_input(self, character: char, context: &mut UpdateContext<'_, 'gc>) {
         if self.0.read().flags.contains(EditTextFlag::READ_ONLY) {
             return;
@@ -1254,15 +1758,95 @@ impl<'gc> EditText<'gc> {
             }
 
             if changed {
-                let mut activation = Avm1Activation::from_nothing(
-                    context.reborrow(),
-                    ActivationIdentifier::root("[Propagate Text Binding]"),
-                    self.into(),
+                self.on_user_change(context);
+            }
+        }
+    }
+
+    /// Tells scripts that the user changed the text.
+    fn on_user_change(self, context: &mut UpdateContext<'_, 'gc>) {
+        let mut activation = Avm1Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[Propagate Text Binding]"),
+            self.into(),
+        );
+        self.propagate_text_binding(&mut activation);
+        self.on_changed(&mut activation);
+    }
+
+    /// Replaces the selection with text entered by the user, as much of it as `maxChars` allows.
+    pub fn insert_user_text(self, text: &WStr, context: &mut UpdateContext<'_, 'gc>) {
+        if !self.is_editable() {
+            return;
+        }
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return,
+        };
+
+        let max_chars = self.0.read().max_chars;
+        let text = if max_chars > 0 {
+            let kept = self.text_length() - (selection.end() - selection.start());
+            let room = (max_chars as usize).saturating_sub(kept);
+            text.slice(..room.min(text.len())).unwrap_or_default()
+        } else {
+            text
+        };
+        if text.is_empty() && selection.is_caret() {
+            return;
+        }
+
+        self.replace_text(selection.start(), selection.end(), text, context);
+        self.set_selection(
+            Some(TextSelection::for_position(selection.start() + text.len())),
+            context.gc_context,
+        );
+        self.on_user_change(context);
+    }
+
+    /// Puts the selected text on the clipboard, and removes it if `cut` is set.
+    ///
+    /// The text of password fields can't be copied.
+    pub fn copy_selection(self, cut: bool, context: &mut UpdateContext<'_, 'gc>) {
+        let selection = match self.selection() {
+            Some(selection) if !selection.is_caret() && !self.is_password() => selection,
+            _ => return,
+        };
+        let text = self.text();
+        if let Some(selected) = text.slice(selection.start()..selection.end()) {
+            context
+                .ui
+                .set_clipboard_content(selected.to_utf8_lossy().into_owned());
+        }
+        if cut {
+            self.insert_user_text(WStr::empty(), context);
+        }
+    }
+
+    /// Carries out the clipboard shortcuts of text fields. Returns `false` if `key_code` isn't
+    /// one of them.
+    pub fn handle_clipboard_shortcut(
+        self,
+        key_code: KeyCode,
+        context: &mut UpdateContext<'_, 'gc>,
+    ) -> bool {
+        match key_code {
+            KeyCode::A if self.is_selectable() => {
+                let length = self.text_length();
+                self.set_selection(
+                    Some(TextSelection::for_range(0, length)),
+                    context.gc_context,
                 );
-                self.propagate_text_binding(&mut activation);
-                self.on_changed(&mut activation);
             }
+            KeyCode::C => self.copy_selection(false, context),
+            KeyCode::X => self.copy_selection(self.is_editable(), context),
+            KeyCode::V => {
+                let text = WString::from_utf8(&context.ui.clipboard_content());
+                self.insert_user_text(&text, context);
+            }
+            _ => return false,
         }
+        true
     }
 
     /// Listens for keyboard text control commands.
@@ -1426,6 +2010,10 @@ impl<'gc> EditText<'gc> {
         let read = self.0.read();
 
         for layout_box in read.layout.iter() {
+            if layout_box.as_image().is_some() {
+                continue;
+            }
+
             if let Some(line) = line {
                 if layout_box.bounds().offset_y() < line.offset
                     || layout_box.bounds().extent_y() > line.extent
@@ -1458,7 +2046,7 @@ impl<'gc> EditText<'gc> {
                         font = Some(box_font);
                         text_format = Some(box_text_format);
                     }
-                    LayoutContent::Drawing { .. } => {}
+                    LayoutContent::Drawing { .. } | LayoutContent::Image { .. } => {}
                 }
             }
         }
@@ -1479,6 +2067,24 @@ impl<'gc> EditText<'gc> {
             x: union_bounds.offset_x() + Twips::from_pixels(EditText::INTERNAL_PADDING),
         })
     }
+
+    /// Returns the index of the character after the last one laid out on a given line.
+    pub fn line_end(self, line: usize) -> Option<usize> {
+        let read = self.0.read();
+        let line = read.line_data.get(line)?;
+
+        read.layout
+            .iter()
+            .filter(|layout_box| {
+                layout_box.bounds().offset_y() >= line.offset
+                    && layout_box.bounds().extent_y() <= line.extent
+            })
+            .filter_map(|layout_box| match layout_box.content() {
+                LayoutContent::Text { end, .. } => Some(*end),
+                _ => None,
+            })
+            .max()
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
@@ -1512,10 +2118,28 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
             self.construct_as_avm2_object(context, (*self).into());
             self.on_construction_complete(context);
         }
+
+        if context.is_action_script_3() {
+            let images = self.0.read().images.clone();
+            for image in images {
+                image.construct_frame(context);
+            }
+        }
     }
 
-    fn run_frame(&self, _context: &mut UpdateContext) {
-        // Noop
+    fn run_frame(&self, context: &mut UpdateContext<'_, 'gc>) {
+        // AVM1 images run their frames from the execution list.
+        if context.is_action_script_3() {
+            let images = self.0.read().images.clone();
+            for image in images {
+                image.run_frame_avm2(context);
+            }
+        }
+
+        // The text flows around images at their size once they have loaded.
+        if self.has_resized_images() {
+            self.relayout(context);
+        }
     }
 
     fn as_edit_text(&self) -> Option<EditText<'gc>> {
@@ -1544,6 +2168,11 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         if !self.movie().is_action_script_3() {
             self.construct_as_avm1_object(context, run_frame);
         }
+
+        if !self.0.read().text_spans.images().is_empty() {
+            self.load_images(context);
+            self.relayout(context);
+        }
     }
 
     fn object(&self) -> Avm1Value<'gc> {
@@ -1674,17 +2303,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         );
         context.commands.activate_mask();
 
-        let scroll_offset = if edit_text.scroll > 1 {
-            let line_data = &edit_text.line_data;
-
-            if let Some(line_data) = line_data.get(edit_text.scroll - 1) {
-                line_data.offset
-            } else {
-                Twips::ZERO
-            }
-        } else {
-            Twips::ZERO
-        };
+        let scroll_offset = edit_text.scroll_offset();
         // TODO: Where does this come from? How is this different than INTERNAL_PADDING? Does this apply to y as well?
         // If this is actually right, offset the border in `redraw_border` instead of doing an extra push.
         context.transform_stack.push(&Transform {
@@ -1723,6 +2342,12 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
             }
         }
 
+        if let Some(composition) = &edit_text.composition {
+            if let Some((x, y, _)) = self.caret_position() {
+                self.render_composition(context, composition, x, y);
+            }
+        }
+
         context.transform_stack.pop();
 
         context.commands.deactivate_mask();
@@ -1769,6 +2394,11 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
                 .retain(|&text_field| !DisplayObject::ptr_eq(text_field.into(), (*self).into()));
         }
 
+        let images = std::mem::take(&mut self.0.write(context.gc_context).images);
+        for image in images {
+            image.unload(context);
+        }
+
         self.set_removed(context.gc_context, true);
     }
 
@@ -1777,6 +2407,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         text.flags.set(EditTextFlag::HAS_FOCUS, focused);
         if !focused {
             text.selection = None;
+            text.composition = None;
         }
     }
 
@@ -1815,16 +2446,22 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
         context: &mut UpdateContext<'_, 'gc>,
         event: ClipEvent<'gc>,
     ) -> ClipEventResult {
-        let tracker = context.focus_tracker;
-        tracker.set(Some(self.into()), context);
-        if let Some(position) = self
-            .screen_position_to_index(*context.mouse_position)
-            .map(TextSelection::for_position)
-        {
-            self.0.write(context.gc_context).selection = Some(position);
-        } else {
-            self.0.write(context.gc_context).selection =
-                Some(TextSelection::for_position(self.text_length()));
+        if let Some((url, target)) = self.link_at_screen_position(*context.mouse_position) {
+            self.open_link(context, &url, &target);
+        }
+
+        if self.is_selectable() {
+            let tracker = context.focus_tracker;
+            tracker.set(Some(self.into()), context);
+            if let Some(position) = self
+                .screen_position_to_index(*context.mouse_position)
+                .map(TextSelection::for_position)
+            {
+                self.0.write(context.gc_context).selection = Some(position);
+            } else {
+                self.0.write(context.gc_context).selection =
+                    Some(TextSelection::for_position(self.text_length()));
+            }
         }
 
         self.event_dispatch_to_avm2(context, event);
@@ -1839,9 +2476,10 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
         _require_button_mode: bool,
     ) -> Option<InteractiveObject<'gc>> {
         // The button is hovered if the mouse is over any child nodes.
+        // Links can be clicked even if the text can't be selected.
         if self.visible()
             && self.mouse_enabled()
-            && self.is_selectable()
+            && (self.is_selectable() || self.link_at_screen_position(point).is_some())
             && self.hit_test_shape(context, point, HitTestOptions::MOUSE_PICK)
         {
             Some((*self).into())
@@ -1850,8 +2488,15 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
         }
     }
 
-    fn mouse_cursor(self, _context: &mut UpdateContext<'_, 'gc>) -> MouseCursor {
-        MouseCursor::IBeam
+    fn mouse_cursor(self, context: &mut UpdateContext<'_, 'gc>) -> MouseCursor {
+        if self
+            .link_at_screen_position(*context.mouse_position)
+            .is_some()
+        {
+            MouseCursor::Hand
+        } else {
+            MouseCursor::IBeam
+        }
     }
 }
 
@@ -1896,6 +2541,18 @@ pub struct TextSelection {
     to: usize,
 }
 
+/// Text being composed with an input method.
+///
+/// It isn't part of the text of the field until it's committed, and is drawn over the text at the
+/// caret in the meantime.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+struct Composition<'gc> {
+    text: WString,
+    layout: Vec<LayoutBox<'gc>>,
+    bounds: BoxBounds<Twips>,
+}
+
 /// Information about the start and end y-coordinates of a given line of text
 #[derive(Copy, Clone, Debug, Collect)]
 #[collect(require_static)]
@@ -16,7 +16,6 @@ use crate::display_object::{
 use crate::events::{ClipEvent, ClipEventResult};
 use bitflags::bitflags;
 use gc_arena::{Collect, MutationContext};
-use instant::Instant;
 use ruffle_macros::enum_trait_object;
 use std::cell::{Ref, RefMut};
 use std::fmt::Debug;
@@ -82,12 +81,17 @@ pub struct InteractiveObjectBase<'gc> {
     flags: InteractiveObjectFlags,
     context_menu: Avm2Value<'gc>,
 
-    /// The time of the last click registered on this object.
+    /// Whether this object shows a focus rectangle when focused.
+    ///
+    /// `None` defers to `Stage.stageFocusRect` (the `_focusrect` global in AVM1).
+    focus_rect: Option<bool>,
+
+    /// The time of the last click registered on this object, on the player's clock.
     ///
     /// This should be cleared to `None` when the mouse leaves the current
     /// display object.
     #[collect(require_static)]
-    last_click: Option<Instant>,
+    last_click: Option<Duration>,
 }
 
 impl<'gc> Default for InteractiveObjectBase<'gc> {
@@ -96,6 +100,7 @@ impl<'gc> Default for InteractiveObjectBase<'gc> {
             base: Default::default(),
             flags: InteractiveObjectFlags::MOUSE_ENABLED,
             context_menu: Avm2Value::Null,
+            focus_rect: None,
             last_click: None,
         }
     }
@@ -161,6 +166,16 @@ pub trait TInteractiveObject<'gc>:
         self.raw_interactive_mut(mc).context_menu = value;
     }
 
+    /// Whether this object shows a focus rectangle when focused, or `None` if the global
+    /// setting of the stage applies.
+    fn focus_rect(self) -> Option<bool> {
+        self.raw_interactive().focus_rect
+    }
+
+    fn set_focus_rect(self, mc: MutationContext<'gc, '_>, value: Option<bool>) {
+        self.raw_interactive_mut(mc).focus_rect = value;
+    }
+
     /// Filter the incoming clip event.
     ///
     /// If this returns `Handled`, then the rest of the event handling
@@ -259,13 +274,13 @@ pub trait TInteractiveObject<'gc>:
             ClipEvent::Release => {
                 let read = self.raw_interactive();
                 let last_click = read.last_click;
-                let this_click = Instant::now();
+                let this_click = activation.context.clock.elapsed();
 
                 let is_double_click = read
                     .flags
                     .contains(InteractiveObjectFlags::DOUBLE_CLICK_ENABLED)
                     && last_click
-                        .map(|lc| this_click - lc < Duration::from_secs(1))
+                        .map(|lc| this_click.saturating_sub(lc) < Duration::from_secs(1))
                         .unwrap_or(false);
 
                 drop(read);
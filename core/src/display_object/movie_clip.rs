@@ -15,6 +15,7 @@ use crate::avm1::Avm1;
 use crate::avm1::{Activation as Avm1Activation, ActivationIdentifier};
 use crate::binary_data::BinaryData;
 use crate::character::Character;
+use crate::compatibility::MediaKind;
 use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::container::{
     dispatch_removed_event, ChildContainer, TDisplayObjectContainer,
@@ -510,6 +511,14 @@ impl<'gc> MovieClip<'gc> {
                     .0
                     .write(context.gc_context)
                     .define_font_4(context, reader),
+                TagCode::DefineFontInfo => self
+                    .0
+                    .write(context.gc_context)
+                    .define_font_info(context, reader, 1),
+                TagCode::DefineFontInfo2 => self
+                    .0
+                    .write(context.gc_context)
+                    .define_font_info(context, reader, 2),
                 TagCode::DefineMorphShape => self
                     .0
                     .write(context.gc_context)
@@ -861,6 +870,19 @@ impl<'gc> MovieClip<'gc> {
         self.0.read().programmatically_played()
     }
 
+    /// Whether an image was loaded into this clip in place of a movie.
+    pub fn loaded_image(self) -> bool {
+        self.0.read().flags.contains(MovieClipFlags::LOADED_IMAGE)
+    }
+
+    /// Marks this clip as holding a loaded image. Loading a movie into the clip clears this.
+    pub fn set_loaded_image(self, gc_context: MutationContext<'gc, '_>) {
+        self.0
+            .write(gc_context)
+            .flags
+            .insert(MovieClipFlags::LOADED_IMAGE);
+    }
+
     pub fn drop_target(self) -> Option<DisplayObject<'gc>> {
         self.0.read().drop_target
     }
@@ -1446,6 +1468,75 @@ impl<'gc> MovieClip<'gc> {
         }
     }
 
+    /// The child at `depth` that is controlled by the timeline, if any.
+    ///
+    /// Clips created by ActionScript are never moved or replaced by PlaceObject tags, even when
+    /// they occupy a depth used by the timeline. See `is_depth_held_by_script`.
+    fn timeline_child_by_depth(self, depth: Depth) -> Option<DisplayObject<'gc>> {
+        self.child_by_depth(depth)
+            .filter(|child| child.instantiated_by_timeline())
+    }
+
+    /// Whether `depth` is occupied by a clip created by ActionScript.
+    ///
+    /// PlaceObject tags that would place a new character there are ignored, and the clip stays.
+    fn is_depth_held_by_script(self, depth: Depth) -> bool {
+        self.child_by_depth(depth)
+            .map_or(false, |child| !child.instantiated_by_timeline())
+    }
+
+    /// Replace the character of the timeline child `child` as told by a PlaceObject tag.
+    ///
+    /// Shapes, morph shapes and static text swap their art in place when the new character is
+    /// of the same kind, so they keep their instance and any ratio they were given. A character
+    /// of another kind takes over the depth as a new instance, which inherits the transforms,
+    /// name and clip depth of the old one unless the tag sets them. Other objects are only moved.
+    fn replace_child(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        child: DisplayObject<'gc>,
+        id: CharacterId,
+        place_object: &swf::PlaceObject,
+    ) -> Option<DisplayObject<'gc>> {
+        let is_static = matches!(
+            child,
+            DisplayObject::Graphic(_) | DisplayObject::MorphShape(_) | DisplayObject::Text(_)
+        );
+        let is_other_kind = match context
+            .library
+            .library_for_movie(self.movie())
+            .and_then(|library| library.character_by_id(id))
+        {
+            Some(Character::Graphic(_)) => !matches!(child, DisplayObject::Graphic(_)),
+            Some(Character::MorphShape(_)) => !matches!(child, DisplayObject::MorphShape(_)),
+            Some(Character::Text(_)) => !matches!(child, DisplayObject::Text(_)),
+            Some(_) => true,
+            None => false,
+        };
+
+        if !is_static || !is_other_kind {
+            child.replace_with(context, id);
+            child.apply_place_object(context, place_object);
+            return Some(child);
+        }
+
+        let new_child = self.instantiate_child(context, id, child.depth(), place_object)?;
+        if place_object.matrix.is_none() {
+            new_child.set_matrix(context.gc_context, *child.base().matrix());
+        }
+        if place_object.color_transform.is_none() {
+            new_child
+                .set_color_transform(context.gc_context, child.base().color_transform().clone());
+        }
+        if place_object.name.is_none() {
+            new_child.set_name(context.gc_context, child.name());
+        }
+        if place_object.clip_depth.is_none() {
+            new_child.set_clip_depth(context.gc_context, child.clip_depth());
+        }
+        Some(new_child)
+    }
+
     /// Instantiate a given child object on the timeline at a given depth.
     fn instantiate_child(
         self,
@@ -1748,7 +1839,7 @@ impl<'gc> MovieClip<'gc> {
                                 context: &mut UpdateContext<'_, 'gc>,
                                 params: &GotoPlaceObject<'_>| {
             use swf::PlaceObjectAction;
-            let child_entry = clip.child_by_depth(params.depth());
+            let child_entry = clip.timeline_child_by_depth(params.depth());
             if context.is_action_script_3() && is_implicit && child_entry.is_none() {
                 // Looping gotos do not run their PlaceObject commands at goto
                 // time. They are instead held to frameConstructed like normal
@@ -1783,10 +1874,14 @@ impl<'gc> MovieClip<'gc> {
                     prev_child.apply_place_object(context, &params.place_object);
                 }
                 (swf::PlaceObjectAction::Replace(id), Some(prev_child), _) => {
-                    prev_child.replace_with(context, id);
-                    prev_child.apply_place_object(context, &params.place_object);
-                    prev_child.set_place_frame(context.gc_context, params.frame);
+                    if let Some(child) =
+                        clip.replace_child(context, prev_child, id, &params.place_object)
+                    {
+                        child.set_place_frame(context.gc_context, params.frame);
+                    }
                 }
+                (PlaceObjectAction::Place(_) | PlaceObjectAction::Replace(_), None, _)
+                    if clip.is_depth_held_by_script(params.depth()) => {}
                 (PlaceObjectAction::Place(id), _, _)
                 | (swf::PlaceObjectAction::Replace(id), _, _) => {
                     if let Some(child) =
@@ -2969,7 +3064,11 @@ impl<'gc, 'a> MovieClipData<'gc> {
         version: u8,
     ) -> Result<(), Error> {
         let define_bits_lossless = reader.read_define_bits_lossless(version)?;
-        let bitmap = ruffle_render::utils::decode_define_bits_lossless(&define_bits_lossless)?;
+        let bitmap = ruffle_render::utils::decode_define_bits_lossless(&define_bits_lossless)
+            .map_err(|e| {
+                context.compatibility.decoder_failed(MediaKind::Image, &e);
+                e
+            })?;
         let bitmap = Bitmap::new(context, define_bits_lossless.id, bitmap)?;
         context
             .library
@@ -3086,7 +3185,11 @@ impl<'gc, 'a> MovieClipData<'gc> {
             .library_for_movie_mut(self.movie())
             .jpeg_tables();
         let jpeg_data = ruffle_render::utils::glue_tables_to_jpeg(jpeg_data, jpeg_tables);
-        let bitmap = ruffle_render::utils::decode_define_bits_jpeg(&jpeg_data, None)?;
+        let bitmap =
+            ruffle_render::utils::decode_define_bits_jpeg(&jpeg_data, None).map_err(|e| {
+                context.compatibility.decoder_failed(MediaKind::Image, &e);
+                e
+            })?;
         let bitmap = Bitmap::new(context, id, bitmap)?;
         context
             .library
@@ -3103,7 +3206,11 @@ impl<'gc, 'a> MovieClipData<'gc> {
     ) -> Result<(), Error> {
         let id = reader.read_u16()?;
         let jpeg_data = reader.read_slice_to_end();
-        let bitmap = ruffle_render::utils::decode_define_bits_jpeg(jpeg_data, None)?;
+        let bitmap =
+            ruffle_render::utils::decode_define_bits_jpeg(jpeg_data, None).map_err(|e| {
+                context.compatibility.decoder_failed(MediaKind::Image, &e);
+                e
+            })?;
         let bitmap = Bitmap::new(context, id, bitmap)?;
         context
             .library
@@ -3126,7 +3233,11 @@ impl<'gc, 'a> MovieClipData<'gc> {
         }
         let jpeg_data = reader.read_slice(jpeg_len)?;
         let alpha_data = reader.read_slice_to_end();
-        let bitmap = ruffle_render::utils::decode_define_bits_jpeg(jpeg_data, Some(alpha_data))?;
+        let bitmap = ruffle_render::utils::decode_define_bits_jpeg(jpeg_data, Some(alpha_data))
+            .map_err(|e| {
+                context.compatibility.decoder_failed(MediaKind::Image, &e);
+                e
+            })?;
         let bitmap = Bitmap::new(context, id, bitmap)?;
         context
             .library
@@ -3357,6 +3468,25 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_font_info(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc>,
+        reader: &mut SwfStream<'a>,
+        version: u8,
+    ) -> Result<(), Error> {
+        let font_info = reader.read_define_font_info(version)?;
+        let library = context.library.library_for_movie_mut(self.movie());
+        match library.get_font(font_info.id) {
+            Some(font) => {
+                let font = font.with_font_info(context.gc_context, &font_info, reader.encoding());
+                library.replace_font(font_info.id, font);
+            }
+            None => tracing::warn!("DefineFontInfo: Font ID {} doesn't exist", font_info.id),
+        }
+        Ok(())
+    }
+
     #[inline]
     fn define_sound(
         &mut self,
@@ -3364,16 +3494,18 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<'a>,
     ) -> Result<(), Error> {
         let sound = reader.read_define_sound()?;
-        if let Ok(handle) = context.audio.register_sound(&sound) {
-            context
+        match context.audio.register_sound(&sound) {
+            Ok(handle) => context
                 .library
                 .library_for_movie_mut(self.movie())
-                .register_character(sound.id, Character::Sound(handle));
-        } else {
-            tracing::error!(
-                "MovieClip::define_sound: Unable to register sound ID {}",
-                sound.id
-            );
+                .register_character(sound.id, Character::Sound(handle)),
+            Err(e) => {
+                tracing::error!(
+                    "MovieClip::define_sound: Unable to register sound ID {}",
+                    sound.id
+                );
+                context.compatibility.decoder_failed(MediaKind::Audio, &e);
+            }
         }
         Ok(())
     }
@@ -3667,22 +3799,23 @@ impl<'gc, 'a> MovieClip<'gc> {
             reader.read_place_object_2_or_3(version)
         }?;
         use swf::PlaceObjectAction;
-        match place_object.action {
-            PlaceObjectAction::Place(id) => {
-                self.instantiate_child(context, id, place_object.depth.into(), &place_object);
-            }
-            PlaceObjectAction::Replace(id) => {
-                if let Some(child) = self.child_by_depth(place_object.depth.into()) {
-                    child.replace_with(context, id);
-                    child.apply_place_object(context, &place_object);
+        let depth = place_object.depth.into();
+        match (place_object.action, self.timeline_child_by_depth(depth)) {
+            (PlaceObjectAction::Replace(id), Some(child)) => {
+                if let Some(child) = self.replace_child(context, child, id, &place_object) {
                     child.set_place_frame(context.gc_context, self.current_frame());
                 }
             }
-            PlaceObjectAction::Modify => {
-                if let Some(child) = self.child_by_depth(place_object.depth.into()) {
-                    child.apply_place_object(context, &place_object);
-                }
+            (PlaceObjectAction::Modify, Some(child)) => {
+                child.apply_place_object(context, &place_object);
             }
+            (PlaceObjectAction::Place(_) | PlaceObjectAction::Replace(_), None)
+                if self.is_depth_held_by_script(depth) => {}
+            // Replacing an empty depth places the new character, like a goto would.
+            (PlaceObjectAction::Place(id) | PlaceObjectAction::Replace(id), _) => {
+                self.instantiate_child(context, id, depth, &place_object);
+            }
+            (PlaceObjectAction::Modify, None) => {}
         }
 
         Ok(())
@@ -4173,6 +4306,11 @@ bitflags! {
         /// Because AVM2 queues PlaceObject tags to run later, explicit gotos
         /// that happen while those tags run should cancel the loop.
         const LOOP_QUEUED = 1 << 4;
+
+        /// Whether an image was loaded into this `MovieClip` with AVM1 `loadMovie`.
+        ///
+        /// The clip keeps the movie it comes from, but has no SWF version of its own.
+        const LOADED_IMAGE = 1 << 5;
     }
 }
 
@@ -17,7 +17,7 @@ use crate::display_object::interactive::{
 use crate::display_object::{
     render_base, DisplayObject, DisplayObjectBase, DisplayObjectPtr, TDisplayObject,
 };
-use crate::events::{ClipEvent, ClipEventResult};
+use crate::events::{ClipEvent, ClipEventResult, EmbedderEvent};
 use crate::prelude::*;
 use crate::string::{FromWStr, WStr};
 use crate::tag_utils::SwfMovie;
@@ -26,6 +26,8 @@ use bitflags::bitflags;
 use gc_arena::{Collect, GcCell, MutationContext};
 use ruffle_render::backend::ViewportDimensions;
 use ruffle_render::commands::CommandHandler;
+pub use ruffle_render::quality::StageQuality;
+use ruffle_render::transform::Transform;
 use std::cell::{Ref, RefMut};
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
@@ -100,10 +102,10 @@ pub struct StageData<'gc> {
     #[collect(require_static)]
     view_bounds: BoundingBox,
 
-    /// The window mode of the viewport.
+    /// The window mode of the viewport, or `None` if it was never set.
     ///
     /// Only used on web to control how the Flash content layers with other content on the page.
-    window_mode: WindowMode,
+    window_mode: Option<WindowMode>,
 
     /// Whether or not objects display a glowing border when they have focus.
     stage_focus_rect: bool,
@@ -120,6 +122,14 @@ pub struct StageData<'gc> {
     /// An array of AVM2 'Stage3D' instances
     stage3ds: Vec<Avm2Object<'gc>>,
 
+    /// An array of AVM2 'StageVideo' instances
+    stage_videos: Vec<Avm2Object<'gc>>,
+
+    /// Whether a `stageVideoAvailability` event should be dispatched on the next frame.
+    ///
+    /// Flash Player reports the availability of stage video to every new listener of that event.
+    stage_video_availability_pending: bool,
+
     /// The swf that registered this stage
     movie: Arc<SwfMovie>,
 }
@@ -151,12 +161,14 @@ impl<'gc> Stage<'gc> {
                 align: Default::default(),
                 use_bitmap_downsampling: false,
                 view_bounds: Default::default(),
-                window_mode: Default::default(),
+                window_mode: None,
                 show_menu: true,
                 stage_focus_rect: true,
                 avm2_object: Avm2ScriptObject::custom_object(gc_context, None, None),
                 loader_info: Avm2ScriptObject::custom_object(gc_context, None, None),
                 stage3ds: vec![],
+                stage_videos: vec![],
+                stage_video_availability_pending: false,
                 movie,
             },
         ));
@@ -172,8 +184,13 @@ impl<'gc> Stage<'gc> {
         self.0.write(gc_context).background_color = color;
     }
 
+    /// The matrix from stage coordinates to the viewport.
+    pub fn view_matrix(self) -> Matrix {
+        *self.base().matrix()
+    }
+
     pub fn inverse_view_matrix(self) -> Matrix {
-        let mut inverse_view_matrix = *(self.base().matrix());
+        let mut inverse_view_matrix = self.view_matrix();
         inverse_view_matrix.invert();
 
         inverse_view_matrix
@@ -211,20 +228,19 @@ impl<'gc> Stage<'gc> {
 
     /// Returns the quality setting of the stage.
     ///
-    /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
-    /// This setting is currently ignored in Ruffle.
+    /// The quality setting affects anti-aliasing and smoothing of bitmaps.
     /// Used by AVM1 `stage.quality` and AVM2 `Stage.quality` properties.
     pub fn quality(self) -> StageQuality {
         self.0.read().quality
     }
 
-    /// Sets the quality setting of the stage.
+    /// Sets the quality setting of the stage, and reconfigures the renderer to match.
     ///
-    /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
-    /// This setting is currently ignored in Ruffle.
+    /// The quality setting affects anti-aliasing and smoothing of bitmaps.
     /// Used by AVM1 `stage.quality` and AVM2 `Stage.quality` properties.
-    pub fn set_quality(self, gc_context: MutationContext<'gc, '_>, quality: StageQuality) {
-        let mut this = self.0.write(gc_context);
+    pub fn set_quality(self, context: &mut UpdateContext<'_, 'gc>, quality: StageQuality) {
+        let mut this = self.0.write(context.gc_context);
+        let changed = this.quality != quality;
         this.quality = quality;
         this.use_bitmap_downsampling = matches!(
             quality,
@@ -234,24 +250,39 @@ impl<'gc> Stage<'gc> {
                 | StageQuality::High16x16
                 | StageQuality::High16x16Linear
         );
+        drop(this);
+
+        if changed {
+            context.renderer.set_quality(quality);
+            context
+                .embedder_events
+                .send(EmbedderEvent::QualityChanged { quality });
+        }
     }
 
     pub fn stage3ds(&self) -> Ref<Vec<Avm2Object<'gc>>> {
         Ref::map(self.0.read(), |this| &this.stage3ds)
     }
 
+    pub fn stage_videos(&self) -> Ref<Vec<Avm2Object<'gc>>> {
+        Ref::map(self.0.read(), |this| &this.stage_videos)
+    }
+
+    /// Schedules a `stageVideoAvailability` event for the next frame.
+    pub fn queue_stage_video_availability(self, gc_context: MutationContext<'gc, '_>) {
+        self.0.write(gc_context).stage_video_availability_pending = true;
+    }
+
     /// Get the boolean flag which determines whether or not objects display a glowing border
     /// when they have focus.
     ///
-    /// This setting is currently ignored in Ruffle.
+    /// Individual objects may override this with their own `focusRect`.
     pub fn stage_focus_rect(self) -> bool {
         self.0.read().stage_focus_rect
     }
 
     /// Set the boolean flag which determines whether or not objects display a glowing border
     /// when they have focus.
-    ///
-    /// This setting is currently ignored in Ruffle.
     pub fn set_stage_focus_rect(self, gc_context: MutationContext<'gc, '_>, fr: bool) {
         let mut this = self.0.write(gc_context);
         this.stage_focus_rect = fr
@@ -357,12 +388,17 @@ impl<'gc> Stage<'gc> {
     /// This controls how the content layers with other content on the page.
     /// Only used on web.
     pub fn window_mode(self) -> WindowMode {
-        self.0.read().window_mode
+        self.0.read().window_mode.unwrap_or_default()
+    }
+
+    /// Returns whether a window mode was set, either by the embedder or by the movie.
+    pub fn has_window_mode(self) -> bool {
+        self.0.read().window_mode.is_some()
     }
 
     /// Sets the window mode.
     pub fn set_window_mode(self, context: &mut UpdateContext<'_, 'gc>, window_mode: WindowMode) {
-        self.0.write(context.gc_context).window_mode = window_mode;
+        self.0.write(context.gc_context).window_mode = Some(window_mode);
     }
 
     pub fn view_bounds(self) -> BoundingBox {
@@ -386,7 +422,7 @@ impl<'gc> Stage<'gc> {
         let stage = self.0.read();
         stage.scale_mode == StageScaleMode::ShowAll
             && stage.align.is_empty()
-            && stage.window_mode != WindowMode::Transparent
+            && stage.window_mode != Some(WindowMode::Transparent)
             && (stage.letterbox == Letterbox::On
                 || (stage.letterbox == Letterbox::Fullscreen && self.is_fullscreen()))
     }
@@ -509,6 +545,44 @@ impl<'gc> Stage<'gc> {
         }
     }
 
+    /// Draws the yellow rectangle around the object that has keyboard focus.
+    fn draw_focus_rect(&self, context: &mut RenderContext<'_, 'gc>) {
+        const FOCUS_RECT_COLOR: Color = Color::from_rgb(0xFFFF00, 255);
+        const FOCUS_RECT_WIDTH: f32 = 2.0;
+
+        let focus = match context.focus_tracker.highlighted(*self) {
+            Some(focus) => focus,
+            None => return,
+        };
+        let bounds = focus.world_bounds().transform(&self.base().matrix());
+        if !bounds.valid {
+            return;
+        }
+
+        let x = bounds.x_min.to_pixels() as f32;
+        let y = bounds.y_min.to_pixels() as f32;
+        let width = (bounds.x_max - bounds.x_min).to_pixels() as f32;
+        let height = (bounds.y_max - bounds.y_min).to_pixels() as f32;
+        let edges = [
+            (x, y, width, FOCUS_RECT_WIDTH),
+            (x, y + height - FOCUS_RECT_WIDTH, width, FOCUS_RECT_WIDTH),
+            (x, y, FOCUS_RECT_WIDTH, height),
+            (x + width - FOCUS_RECT_WIDTH, y, FOCUS_RECT_WIDTH, height),
+        ];
+        for (x, y, width, height) in edges {
+            context.commands.draw_rect(
+                FOCUS_RECT_COLOR,
+                Matrix::create_box(
+                    width,
+                    height,
+                    0.0,
+                    Twips::from_pixels(x as f64),
+                    Twips::from_pixels(y as f64),
+                ),
+            );
+        }
+    }
+
     /// Draw the stage's letterbox.
     fn draw_letterbox(&self, context: &mut RenderContext<'_, 'gc>) {
         let ViewportDimensions {
@@ -646,6 +720,103 @@ impl<'gc> Stage<'gc> {
             }
         }
     }
+
+    /// Dispatches the pending `stageVideoAvailability` event, and a `renderState` event for
+    /// every StageVideo whose video changed size.
+    fn fire_stage_video_events(self, context: &mut UpdateContext<'_, 'gc>) {
+        let mut write = self.0.write(context.gc_context);
+        let availability_pending = std::mem::take(&mut write.stage_video_availability_pending);
+        let stage_videos = write.stage_videos.clone();
+        drop(write);
+
+        if let (true, Avm2Value::Object(stage)) = (availability_pending, self.object2()) {
+            let availability_event_cls = context.avm2.classes().stagevideoavailabilityevent;
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let availability_event = availability_event_cls
+                .construct(
+                    &mut activation,
+                    &[
+                        "stageVideoAvailability".into(),
+                        false.into(),
+                        false.into(),
+                        "available".into(),
+                    ],
+                )
+                .unwrap(); // we don't expect to break here
+
+            if let Err(e) = Avm2::dispatch_event(context, availability_event, stage) {
+                tracing::error!("Encountered AVM2 error when dispatching event: {}", e);
+            }
+        }
+
+        for stage_video in stage_videos {
+            let object = stage_video.as_stage_video().unwrap();
+            let size = object.stream().and_then(|stream| stream.video_size());
+            if !object.update_reported_size(context.gc_context, size) {
+                continue;
+            }
+
+            let render_state_event_cls = context.avm2.classes().stagevideoevent;
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let render_state_event = render_state_event_cls
+                .construct(
+                    &mut activation,
+                    &[
+                        "renderState".into(),
+                        false.into(),
+                        false.into(),
+                        "software".into(),
+                        "BT.601".into(),
+                    ],
+                )
+                .unwrap(); // we don't expect to break here
+
+            if let Err(e) = Avm2::dispatch_event(context, render_state_event, stage_video) {
+                tracing::error!("Encountered AVM2 error when dispatching event: {}", e);
+            }
+        }
+    }
+
+    /// Draws the latest frame of the stream attached to every StageVideo in its view port.
+    ///
+    /// StageVideos with a higher depth are drawn on top of the others.
+    fn render_stage_videos(&self, context: &mut RenderContext<'_, 'gc>) {
+        let view_matrix = *self.base().matrix();
+        let mut stage_videos: Vec<_> = self
+            .stage_videos()
+            .iter()
+            .map(|stage_video| stage_video.as_stage_video().unwrap())
+            .collect();
+        stage_videos.sort_by_key(|object| object.depth());
+        for object in stage_videos {
+            let bitmap = match object
+                .stream()
+                .and_then(|stream| stream.last_decoded_bitmap())
+            {
+                Some(bitmap) => bitmap,
+                None => continue,
+            };
+            let view_port = object.view_port();
+            if view_port.width() <= Twips::ZERO || view_port.height() <= Twips::ZERO {
+                continue;
+            }
+
+            let matrix = view_matrix
+                * Matrix::translate(view_port.x_min, view_port.y_min)
+                * Matrix::scale(
+                    view_port.width().to_pixels() as f32 / bitmap.width as f32,
+                    view_port.height().to_pixels() as f32 / bitmap.height as f32,
+                );
+            let transform = Transform {
+                matrix,
+                color_transform: Default::default(),
+            };
+            // Stage video is always scaled by the video hardware, which smooths it.
+            context
+                .commands
+                .render_bitmap(bitmap.handle, transform, true);
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
@@ -697,11 +868,20 @@ impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
             .construct(&mut activation, &[])
             .expect("Failed to construct Stage3D");
 
+        // Likewise, a single StageVideo
+        let stage_video = activation
+            .avm2()
+            .classes()
+            .stagevideo
+            .construct(&mut activation, &[])
+            .expect("Failed to construct StageVideo");
+
         match avm2_stage {
             Ok(avm2_stage) => {
                 let mut write = self.0.write(activation.context.gc_context);
                 write.avm2_object = avm2_stage.into();
                 write.stage3ds = vec![stage3d];
+                write.stage_videos = vec![stage_video];
             }
             Err(e) => tracing::error!("Unable to construct AVM2 Stage: {}", e),
         }
@@ -732,9 +912,12 @@ impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
     }
 
     fn render(&self, context: &mut RenderContext<'_, 'gc>) {
-        // All of our Stage3D instances get rendered *underneath* the main stage.
+        // All of our StageVideo and Stage3D instances get rendered *underneath* the main stage,
+        // with the videos at the bottom.
         // Note that the stage background color is actually the lowest possible layer,
         // and get applied when we start the frame (before `render` is called).
+        self.render_stage_videos(context);
+
         for stage3d in self.stage3ds().iter() {
             if let Some(context3d) = stage3d.as_stage_3d().unwrap().context3d() {
                 context3d.as_context_3d().unwrap().render(context);
@@ -743,6 +926,10 @@ impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
 
         render_base((*self).into(), context);
 
+        if !context.is_offscreen {
+            self.draw_focus_rect(context);
+        }
+
         if self.should_letterbox() {
             self.draw_letterbox(context);
         }
@@ -753,6 +940,8 @@ impl<'gc> TDisplayObject<'gc> for Stage<'gc> {
             child.enter_frame(context);
         }
 
+        self.fire_stage_video_events(context);
+
         let enter_frame_evt = Avm2EventObject::bare_default_event(context, "enterFrame");
 
         let dobject_constr = context.avm2.classes().display_object;
@@ -1022,101 +1211,6 @@ impl FromWStr for StageAlign {
     }
 }
 
-/// The quality setting of the `Stage`.
-///
-/// In the Flash Player, this settings affects anti-aliasing and bitmap smoothing.
-/// These settings currently have no effect in Ruffle, but the active setting is still stored.
-/// [StageQuality in the AS3 Reference](https://help.adobe.com/en_US/FlashPlatform/reference/actionscript/3/flash/display/StageQuality.html)
-#[derive(Default, Clone, Collect, Copy, Debug, Eq, PartialEq)]
-#[collect(require_static)]
-pub enum StageQuality {
-    /// No anti-aliasing, and bitmaps are never smoothed.
-    Low,
-
-    /// 2x anti-aliasing.
-    Medium,
-
-    /// 4x anti-aliasing.
-    #[default]
-    High,
-
-    /// 4x anti-aliasing with high quality downsampling.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    /// Despite the name, this is not the best quality setting as 8x8 and 16x16 modes were added to
-    /// Flash Player 11.3.
-    Best,
-
-    /// 8x anti-aliasing.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High8x8,
-
-    /// 8x anti-aliasing done in linear sRGB space.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High8x8Linear,
-
-    /// 16x anti-aliasing.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High16x16,
-
-    /// 16x anti-aliasing done in linear sRGB space.
-    /// Bitmaps will use high quality downsampling when scaled down.
-    High16x16Linear,
-}
-
-impl StageQuality {
-    /// Returns the string representing the quality setting as returned by AVM1 `_quality` and
-    /// AVM2 `Stage.quality`.
-    pub fn into_avm_str(self) -> &'static str {
-        // Flash Player always returns quality in uppercase, despite the AVM2 `StageQuality` being
-        // lowercase.
-        match self {
-            StageQuality::Low => "LOW",
-            StageQuality::Medium => "MEDIUM",
-            StageQuality::High => "HIGH",
-            StageQuality::Best => "BEST",
-            // The linear sRGB quality settings are not returned even if they are active.
-            StageQuality::High8x8 | StageQuality::High8x8Linear => "8X8",
-            StageQuality::High16x16 | StageQuality::High16x16Linear => "16X16",
-        }
-    }
-}
-
-impl Display for StageQuality {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        // Match string values returned by AS.
-        let s = match *self {
-            StageQuality::Low => "low",
-            StageQuality::Medium => "medium",
-            StageQuality::High => "high",
-            StageQuality::Best => "best",
-            StageQuality::High8x8 => "8x8",
-            StageQuality::High8x8Linear => "8x8linear",
-            StageQuality::High16x16 => "16x16",
-            StageQuality::High16x16Linear => "16x16linear",
-        };
-        f.write_str(s)
-    }
-}
-
-impl FromStr for StageQuality {
-    type Err = ParseEnumError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let quality = match s.to_ascii_lowercase().as_str() {
-            "low" => StageQuality::Low,
-            "medium" => StageQuality::Medium,
-            "high" => StageQuality::High,
-            "best" => StageQuality::Best,
-            "8x8" => StageQuality::High8x8,
-            "8x8linear" => StageQuality::High8x8Linear,
-            "16x16" => StageQuality::High16x16,
-            "16x16linear" => StageQuality::High16x16Linear,
-            _ => return Err(ParseEnumError),
-        };
-        Ok(quality)
-    }
-}
-
 impl FromWStr for StageQuality {
     type Err = ParseEnumError;
 
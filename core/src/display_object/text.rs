@@ -3,7 +3,7 @@ use crate::avm2::{
 };
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
-use crate::font::TextRenderSettings;
+use crate::font::{Font, TextRenderSettings};
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
@@ -12,6 +12,7 @@ use gc_arena::{Collect, GcCell, MutationContext};
 use ruffle_render::commands::CommandHandler;
 use ruffle_render::transform::Transform;
 use std::cell::{Ref, RefMut};
+use std::ops::Range;
 use std::sync::Arc;
 
 #[derive(Clone, Collect, Copy)]
@@ -33,6 +34,28 @@ pub struct TextData<'gc> {
     static_data: gc_arena::Gc<'gc, TextStatic>,
     render_settings: TextRenderSettings,
     avm2_object: Option<Avm2Object<'gc>>,
+
+    /// Which characters are selected with `TextSnapshot.setSelected`, by
+    /// index. Characters past the end aren't selected.
+    selection: Vec<bool>,
+
+    /// The color selected characters are highlighted with.
+    #[collect(require_static)]
+    select_color: swf::Color,
+}
+
+/// A character of static text, as read by a `TextSnapshot`.
+#[derive(Debug, Clone)]
+pub struct TextChar {
+    /// The UTF-16 code unit that the glyph of the character stands for.
+    pub code: u16,
+
+    /// The line of the text the character is on.
+    pub line: usize,
+
+    /// The rectangle the glyph is drawn in, in the coordinates of the parent
+    /// of the text.
+    pub bounds: BoundingBox,
 }
 
 impl<'gc> Text<'gc> {
@@ -57,6 +80,8 @@ impl<'gc> Text<'gc> {
                 ),
                 render_settings: Default::default(),
                 avm2_object: None,
+                selection: Vec::new(),
+                select_color: swf::Color::from_rgb(0xFFFF00, 0xFF),
             },
         ))
     }
@@ -68,6 +93,98 @@ impl<'gc> Text<'gc> {
     ) {
         self.0.write(gc_context).render_settings = settings
     }
+
+    /// Read the characters of the text, in the order they are drawn.
+    ///
+    /// Glyphs missing from their font aren't drawn, and aren't read either.
+    pub fn chars(self, context: &UpdateContext<'_, 'gc>) -> Vec<TextChar> {
+        let tf = self.0.read();
+        let library = match context.library.library_for_movie(self.movie()) {
+            Some(library) => library,
+            None => return vec![],
+        };
+        let matrix = *tf.base.matrix() * tf.static_data.text_transform;
+
+        let mut chars = vec![];
+        let mut font_id = 0;
+        let mut height = Twips::ZERO;
+        let mut x = Twips::ZERO;
+        let mut y = Twips::ZERO;
+        let mut line = 0;
+        for block in &tf.static_data.text_blocks {
+            if let Some(x_offset) = block.x_offset {
+                x = x_offset;
+            }
+            if let Some(y_offset) = block.y_offset {
+                if y_offset != y && !chars.is_empty() {
+                    line += 1;
+                }
+                y = y_offset;
+            }
+            font_id = block.font_id.unwrap_or(font_id);
+            height = block.height.unwrap_or(height);
+            if let Some(font) = library.get_font(font_id) {
+                let (ascent, descent) = glyph_extent(font, height);
+                for c in &block.glyphs {
+                    if let Some(glyph) = font.get_glyph(c.index as usize) {
+                        let advance = Twips::new(c.advance);
+                        let bounds = BoundingBox {
+                            x_min: x,
+                            y_min: y - ascent,
+                            x_max: x + advance,
+                            y_max: y + descent,
+                            valid: true,
+                        };
+                        chars.push(TextChar {
+                            code: glyph.character(),
+                            line,
+                            bounds: bounds.transform(&matrix),
+                        });
+                        x += advance;
+                    }
+                }
+            }
+        }
+        chars
+    }
+
+    /// Determine whether the character at `index` is selected.
+    pub fn is_selected(self, index: usize) -> bool {
+        self.0.read().selection.get(index).copied().unwrap_or(false)
+    }
+
+    /// Select or deselect a range of characters, which are then highlighted.
+    pub fn set_selected(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        range: Range<usize>,
+        selected: bool,
+    ) {
+        let mut tf = self.0.write(gc_context);
+        if tf.selection.len() < range.end {
+            tf.selection.resize(range.end, false);
+        }
+        tf.selection[range].fill(selected);
+    }
+
+    /// Set the color selected characters are highlighted with.
+    pub fn set_select_color(self, gc_context: MutationContext<'gc, '_>, color: swf::Color) {
+        self.0.write(gc_context).select_color = color;
+    }
+}
+
+/// Determine how far glyphs of a font reach above and below their baseline at
+/// a given height.
+///
+/// Fonts without layout information are assumed to reach up by their height.
+fn glyph_extent(font: Font<'_>, height: Twips) -> (Twips, Twips) {
+    let ascent = font.get_baseline_for_height(height);
+    let descent = font.get_descent_for_height(height);
+    if ascent + descent == Twips::ZERO {
+        (height, Twips::ZERO)
+    } else {
+        (ascent, descent)
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Text<'gc> {
@@ -127,6 +244,7 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
         let mut font_id = 0;
         let mut height = Twips::ZERO;
         let mut transform: Transform = Default::default();
+        let mut char_index = 0;
         for block in &tf.static_data.text_blocks {
             if let Some(x) = block.x_offset {
                 transform.matrix.tx = x;
@@ -147,14 +265,25 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
                 transform.matrix.a = scale;
                 transform.matrix.d = scale;
                 transform.color_transform.set_mult_color(&color);
+                let (ascent, descent) = glyph_extent(font, height);
                 for c in &block.glyphs {
                     if let Some(glyph) = font.get_glyph(c.index as usize) {
-                        context.transform_stack.push(&transform);
-                        let glyph_shape_handle = glyph.shape_handle(context.renderer);
-                        context
-                            .commands
-                            .render_shape(glyph_shape_handle, context.transform_stack.transform());
-                        context.transform_stack.pop();
+                        if tf.selection.get(char_index).copied().unwrap_or(false) {
+                            let highlight = context.transform_stack.transform().matrix
+                                * Matrix::create_box(
+                                    Twips::new(c.advance).to_pixels() as f32,
+                                    (ascent + descent).to_pixels() as f32,
+                                    0.0,
+                                    transform.matrix.tx,
+                                    transform.matrix.ty - ascent,
+                                );
+                            context
+                                .commands
+                                .draw_rect(tf.select_color.clone(), highlight);
+                        }
+                        char_index += 1;
+
+                        glyph.render(context, &transform, &tf.render_settings, height);
                         transform.matrix.tx += Twips::new(c.advance);
                     }
                 }
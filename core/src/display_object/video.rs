@@ -1,12 +1,15 @@
 //! Video player display object
 
 use crate::avm1::{Object as Avm1Object, StageObject as Avm1StageObject};
+use crate::avm2::object::CameraObject;
 use crate::avm2::{
     Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
 };
+use crate::compatibility::MediaKind;
 use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
+use crate::display_object::{unload_base, DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
 use crate::prelude::*;
+use crate::streams::NetStream;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::vminterface::{AvmObject, Instantiator};
 use core::fmt;
@@ -21,7 +24,7 @@ use std::borrow::{Borrow, BorrowMut};
 use std::cell::{Ref, RefMut};
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
-use swf::{CharacterId, DefineVideoStream, VideoFrame};
+use swf::{CharacterId, DefineVideoStream, VideoDeblocking, VideoFrame};
 
 use super::StageQuality;
 
@@ -49,7 +52,7 @@ pub struct VideoData<'gc> {
     base: DisplayObjectBase<'gc>,
 
     /// The source of the video data (e.g. an external file, a SWF bitstream)
-    source: GcCell<'gc, VideoSource>,
+    source: GcCell<'gc, VideoSource<'gc>>,
 
     /// The decoder stream that this video source is associated to.
     stream: VideoStream,
@@ -68,6 +71,16 @@ pub struct VideoData<'gc> {
     /// the prior keyframe. The first frame in the stream will always be
     /// treated as a keyframe regardless of it being flagged as one.
     keyframes: BTreeSet<u32>,
+
+    /// Whether the frames are smoothed when scaled, if set by ActionScript.
+    ///
+    /// Otherwise, the flag of the video stream definition is used.
+    smoothing: Option<bool>,
+
+    /// The deblocking filter applied to decoded frames, if set by
+    /// ActionScript.
+    #[collect(require_static)]
+    deblocking: Option<VideoDeblocking>,
 }
 
 /// An optionally-instantiated video stream.
@@ -85,22 +98,52 @@ pub enum VideoStream {
 }
 
 #[derive(Clone, Debug, Collect)]
-#[collect(require_static)]
-pub enum VideoSource {
+#[collect(no_drop)]
+pub enum VideoSource<'gc> {
     /// A video bitstream embedded inside of a SWF movie.
     Swf {
         /// The movie that defined this video stream.
+        #[collect(require_static)]
         movie: Arc<SwfMovie>,
 
         /// The video stream definition.
+        #[collect(require_static)]
         streamdef: DefineVideoStream,
 
         /// The locations of each embedded sub-bitstream for each video frame.
         ///
         /// Each frame consists of a start and end parameter which can be used
         /// to reconstruct a reference to the embedded bitstream.
+        #[collect(require_static)]
         frames: BTreeMap<u32, (usize, usize)>,
     },
+
+    /// A video player created by ActionScript, showing an external file
+    /// played by a `NetStream`.
+    NetStream {
+        /// The movie that created this video player.
+        #[collect(require_static)]
+        movie: Arc<SwfMovie>,
+
+        /// The displayed size of the video, in pixels.
+        size: (i32, i32),
+
+        /// The attached stream, if any.
+        stream: Option<NetStream<'gc>>,
+    },
+
+    /// A video player created by ActionScript, showing the images captured
+    /// by a `Camera`.
+    Camera {
+        /// The movie that created this video player.
+        #[collect(require_static)]
+        movie: Arc<SwfMovie>,
+
+        /// The displayed size of the video, in pixels.
+        size: (i32, i32),
+
+        camera: CameraObject<'gc>,
+    },
 }
 
 impl<'gc> Video<'gc> {
@@ -128,10 +171,203 @@ impl<'gc> Video<'gc> {
                 decoded_frame: None,
                 object: None,
                 keyframes: BTreeSet::new(),
+                smoothing: None,
+                deblocking: None,
+            },
+        ))
+    }
+
+    /// Construct a Video object for ActionScript, with no attached stream.
+    pub fn new_with_avm2(
+        context: &mut UpdateContext<'_, 'gc>,
+        width: i32,
+        height: i32,
+        avm2_object: Avm2Object<'gc>,
+    ) -> Self {
+        let source = GcCell::allocate(
+            context.gc_context,
+            VideoSource::NetStream {
+                movie: context.swf.clone(),
+                size: (width, height),
+                stream: None,
+            },
+        );
+
+        Video(GcCell::allocate(
+            context.gc_context,
+            VideoData {
+                base: Default::default(),
+                source,
+                stream: VideoStream::Uninstantiated(0),
+                decoded_frame: None,
+                object: Some(avm2_object.into()),
+                keyframes: BTreeSet::new(),
+                smoothing: None,
+                deblocking: None,
             },
         ))
     }
 
+    /// Show the frames of a `NetStream` in this video player, or stop showing
+    /// any stream if `None` is given.
+    pub fn attach_netstream(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        netstream: Option<NetStream<'gc>>,
+    ) {
+        let (movie, size) = self.movie_and_size();
+        self.replace_source(
+            context,
+            VideoSource::NetStream {
+                movie,
+                size,
+                stream: netstream,
+            },
+        );
+
+        let deblocking = self.0.read().deblocking;
+        if let (Some(stream), Some(deblocking)) = (netstream, deblocking) {
+            stream.set_deblocking(context, deblocking);
+        }
+    }
+
+    /// Show the images captured by a `Camera` in this video player, or stop
+    /// showing any camera if `None` is given.
+    pub fn attach_camera(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        camera: Option<CameraObject<'gc>>,
+    ) {
+        let (movie, size) = self.movie_and_size();
+        let new_source = match camera {
+            Some(camera) => {
+                camera.camera_mut(context.gc_context).attachments += 1;
+                VideoSource::Camera {
+                    movie,
+                    size,
+                    camera,
+                }
+            }
+            None => VideoSource::NetStream {
+                movie,
+                size,
+                stream: None,
+            },
+        };
+        self.replace_source(context, new_source);
+    }
+
+    /// The movie that created this video player, and its displayed size.
+    fn movie_and_size(self) -> (Arc<SwfMovie>, (i32, i32)) {
+        match &*self.0.read().source.read() {
+            VideoSource::Swf {
+                movie, streamdef, ..
+            } => (
+                movie.clone(),
+                (streamdef.width.into(), streamdef.height.into()),
+            ),
+            VideoSource::NetStream { movie, size, .. }
+            | VideoSource::Camera { movie, size, .. } => (movie.clone(), *size),
+        }
+    }
+
+    /// Replaces the source of this video player, letting go of any camera it
+    /// was showing.
+    fn replace_source(self, context: &mut UpdateContext<'_, 'gc>, new_source: VideoSource<'gc>) {
+        let old_source = self.0.read().source;
+        if let VideoSource::Camera { camera, .. } = &*old_source.read() {
+            let mut camera = camera.camera_mut(context.gc_context);
+            camera.attachments = camera.attachments.saturating_sub(1);
+        }
+
+        // The source is shared with the other instances of a timeline video,
+        // so it's replaced instead of modified.
+        let mut write = self.0.write(context.gc_context);
+        write.source = GcCell::allocate(context.gc_context, new_source);
+        write.decoded_frame = None;
+    }
+
+    /// Whether the frames are smoothed when the video is scaled.
+    pub fn smoothing(self) -> bool {
+        let read = self.0.read();
+        let flag = match &*read.source.read() {
+            VideoSource::Swf { streamdef, .. } => streamdef.is_smoothed,
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => false,
+        };
+        read.smoothing.unwrap_or(flag)
+    }
+
+    pub fn set_smoothing(self, gc_context: MutationContext<'gc, '_>, smoothing: bool) {
+        self.0.write(gc_context).smoothing = Some(smoothing);
+    }
+
+    /// The deblocking filter applied to the decoded frames.
+    pub fn deblocking(self) -> VideoDeblocking {
+        let read = self.0.read();
+        let filter = match &*read.source.read() {
+            VideoSource::Swf { streamdef, .. } => streamdef.deblocking,
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => {
+                VideoDeblocking::UseVideoPacketValue
+            }
+        };
+        read.deblocking.unwrap_or(filter)
+    }
+
+    /// Changes the deblocking filter applied to the frames decoded from now
+    /// on.
+    pub fn set_deblocking(self, context: &mut UpdateContext<'_, 'gc>, deblocking: VideoDeblocking) {
+        let mut write = self.0.write(context.gc_context);
+        write.deblocking = Some(deblocking);
+        if let VideoStream::Instantiated(stream) = write.stream {
+            if let Err(e) = context
+                .video
+                .set_video_stream_deblocking(stream, deblocking)
+            {
+                tracing::warn!("Unable to change video deblocking filter: {}", e);
+            }
+        }
+        let netstream = match &*write.source.read() {
+            VideoSource::NetStream { stream, .. } => *stream,
+            VideoSource::Swf { .. } | VideoSource::Camera { .. } => None,
+        };
+        drop(write);
+
+        if let Some(stream) = netstream {
+            stream.set_deblocking(context, deblocking);
+        }
+    }
+
+    /// Clear the displayed frame.
+    pub fn clear(self, context: &mut UpdateContext<'_, 'gc>) {
+        self.0.write(context.gc_context).decoded_frame = None;
+        if let VideoSource::NetStream {
+            stream: Some(stream),
+            ..
+        } = &*self.0.read().source.read()
+        {
+            stream.clear_frame(context);
+        }
+    }
+
+    /// The size of the decoded video frames, or zero if no frame was decoded.
+    pub fn video_size(self) -> (u32, u32) {
+        let read = self.0.read();
+        let size = match &*read.source.read() {
+            VideoSource::Swf { .. } => read
+                .decoded_frame
+                .as_ref()
+                .map(|(_, bitmap)| (bitmap.width.into(), bitmap.height.into())),
+            VideoSource::NetStream { stream, .. } => stream
+                .and_then(|stream| stream.video_size())
+                .map(|(width, height)| (width.into(), height.into())),
+            VideoSource::Camera { camera, .. } => camera
+                .camera()
+                .bitmap()
+                .map(|bitmap| (bitmap.width.into(), bitmap.height.into())),
+        };
+        size.unwrap_or_default()
+    }
+
     /// Preload frame data from an SWF.
     ///
     /// This function yields an error if this video player is not playing an
@@ -157,6 +393,9 @@ impl<'gc> Video<'gc> {
 
                 frames.insert(tag.frame_num.into(), (subslice.start, subslice.end));
             }
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => {
+                tracing::warn!("Ignoring SWF frame of a video player created by ActionScript");
+            }
         }
     }
 
@@ -180,6 +419,8 @@ impl<'gc> Video<'gc> {
 
         let num_frames = match &*read.source.read() {
             VideoSource::Swf { streamdef, .. } => Some(streamdef.num_frames),
+            // Streams are played by their `NetStream`, not by the timeline.
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => return,
         };
 
         if let Some(num_frames) = num_frames {
@@ -273,6 +514,7 @@ impl<'gc> Video<'gc> {
                     }
                 }
             },
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => return,
         };
 
         drop(read);
@@ -281,7 +523,12 @@ impl<'gc> Video<'gc> {
             Ok(bitmap) => {
                 self.0.write(context.gc_context).decoded_frame = Some((frame_id, bitmap));
             }
-            Err(e) => tracing::error!("Got error when seeking to video frame {}: {}", frame_id, e),
+            Err(e) => {
+                tracing::error!("Got error when seeking to video frame {}: {}", frame_id, e);
+                if !matches!(e, Error::SeekingBeforeDecoding(_)) {
+                    context.compatibility.decoder_failed(MediaKind::Video, &e);
+                }
+            }
         }
     }
 }
@@ -332,17 +579,16 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                     streamdef.num_frames.into(),
                     (streamdef.width, streamdef.height),
                     streamdef.codec,
-                    streamdef.deblocking,
+                    write.deblocking.unwrap_or(streamdef.deblocking),
                 );
-                if stream.is_err() {
-                    tracing::error!(
-                        "Got error when post-instantiating video: {}",
-                        stream.unwrap_err()
-                    );
-                    return;
-                }
-
-                let stream = stream.unwrap();
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        tracing::error!("Got error when post-instantiating video: {}", e);
+                        context.compatibility.decoder_failed(MediaKind::Video, &e);
+                        return;
+                    }
+                };
                 let mut keyframes = BTreeSet::new();
 
                 for (frame_id, (frame_start, frame_end)) in frames {
@@ -368,6 +614,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
 
                 (stream, movie.clone(), keyframes)
             }
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => return,
         };
 
         let starting_seek = if let VideoStream::Uninstantiated(seek_to) = write.stream {
@@ -423,6 +670,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
     fn id(&self) -> CharacterId {
         match (*self.0.read().source.read()).borrow() {
             VideoSource::Swf { streamdef, .. } => streamdef.id,
+            VideoSource::NetStream { .. } | VideoSource::Camera { .. } => 0,
         }
     }
 
@@ -434,6 +682,17 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 bounding_box.set_width(Twips::from_pixels(streamdef.width as f64));
                 bounding_box.set_height(Twips::from_pixels(streamdef.height as f64));
             }
+            VideoSource::NetStream {
+                size: (width, height),
+                ..
+            }
+            | VideoSource::Camera {
+                size: (width, height),
+                ..
+            } => {
+                bounding_box.set_width(Twips::from_pixels(*width as f64));
+                bounding_box.set_height(Twips::from_pixels(*height as f64));
+            }
         }
 
         bounding_box
@@ -449,7 +708,18 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
 
         let read = self.0.read();
 
-        if let Some((_frame_id, ref bitmap)) = read.decoded_frame {
+        let bitmap = match &*read.source.read() {
+            VideoSource::Swf { .. } => read
+                .decoded_frame
+                .as_ref()
+                .map(|(_, bitmap)| bitmap.clone()),
+            VideoSource::NetStream { stream, .. } => {
+                stream.and_then(|stream| stream.last_decoded_bitmap())
+            }
+            VideoSource::Camera { camera, .. } => camera.camera().bitmap(),
+        };
+
+        if let Some(bitmap) = bitmap {
             let mut transform = context.transform_stack.transform();
             let bounds = self.self_bounds();
 
@@ -465,12 +735,20 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                     streamdef,
                     frames,
                     movie,
-                } => (streamdef.is_smoothed, frames.len(), movie.version()),
+                } => (
+                    read.smoothing.unwrap_or(streamdef.is_smoothed),
+                    frames.len(),
+                    movie.version(),
+                ),
+                VideoSource::NetStream { movie, .. } | VideoSource::Camera { movie, .. } => {
+                    (read.smoothing.unwrap_or(false), 0, movie.version())
+                }
             };
 
             let smoothing = match (context.stage.quality(), version) {
                 (StageQuality::Low, _) => false,
                 (_, 8..) => smoothed_flag,
+                (_, _) if read.smoothing.is_some() => smoothed_flag,
                 (StageQuality::Medium, _) => false,
                 (StageQuality::High, _) => num_frames == 1,
                 (_, _) => true,
@@ -479,20 +757,41 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
             context
                 .commands
                 .render_bitmap(bitmap.handle.clone(), transform, smoothing);
-        } else {
+        } else if matches!(&*read.source.read(), VideoSource::Swf { .. }) {
             tracing::warn!("Video has no decoded frame to render.");
         }
 
         context.transform_stack.pop();
     }
 
+    fn object2(&self) -> Avm2Value<'gc> {
+        self.0
+            .read()
+            .object
+            .and_then(|o| o.as_avm2_object())
+            .map(Avm2Value::from)
+            .unwrap_or(Avm2Value::Null)
+    }
+
     fn set_object2(&mut self, mc: MutationContext<'gc, '_>, to: Avm2Object<'gc>) {
         self.0.write(mc).object = Some(to.into());
     }
 
     fn movie(&self) -> Arc<SwfMovie> {
         match &*self.0.read().source.read() {
-            VideoSource::Swf { movie, .. } => movie.clone(),
+            VideoSource::Swf { movie, .. }
+            | VideoSource::NetStream { movie, .. }
+            | VideoSource::Camera { movie, .. } => movie.clone(),
+        }
+    }
+
+    fn unload(&self, context: &mut UpdateContext<'_, 'gc>) {
+        // A removed video stops showing its camera, so that the camera isn't kept in use.
+        let shows_camera = matches!(&*self.0.read().source.read(), VideoSource::Camera { .. });
+        if shows_camera {
+            self.attach_camera(context, None);
         }
+
+        unload_base((*self).into(), context);
     }
 }
@@ -1,4 +1,5 @@
-use crate::display_object::InteractiveObject;
+use crate::display_object::{InteractiveObject, StageQuality};
+use futures::channel::mpsc::{self, Receiver, Sender};
 use swf::ClipEventFlag;
 
 #[derive(Debug)]
@@ -32,6 +33,121 @@ pub enum PlayerEvent {
     TextInput {
         codepoint: char,
     },
+
+    /// The text being composed with an input method changed. It's empty when the composition
+    /// ended without text.
+    ImePreedit {
+        text: String,
+    },
+
+    /// The text composed with an input method was entered.
+    ImeCommit {
+        text: String,
+    },
+
+    /// The user copied the selection of the focused text field through the embedder, such as
+    /// with the Edit menu of the browser. The text is handed to `UiBackend::set_clipboard_content`.
+    Copy,
+
+    /// The user cut the selection of the focused text field through the embedder.
+    Cut,
+
+    /// The user pasted `text` into the focused text field through the embedder.
+    Paste {
+        text: String,
+    },
+
+    /// A finger touched, moved on or left the screen.
+    ///
+    /// `id` tells the touch points apart, and may be reused once the touch ended. `pressure`
+    /// is between 0 and 1, if the device measures it.
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+        pressure: Option<f64>,
+    },
+}
+
+/// The stage of a touch reported with `PlayerEvent::Touch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPhase {
+    Start,
+    Move,
+    End,
+
+    /// The system took the touch over, for example for a gesture.
+    Cancel,
+}
+
+/// A notification sent by the player to the embedders subscribed with
+/// `Player::subscribe_events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbedderEvent {
+    /// A frame was run. `frame` is the current frame of the root movie, starting at 1.
+    FrameRun { frame: u16 },
+
+    /// The movie asked to open `url` in the window named `target`.
+    NavigateToUrl { url: String, target: String },
+
+    /// The movie opened a `javascript:` URL. `script` is the decoded script.
+    RunJavascript { script: String },
+
+    /// The movie called `fscommand`.
+    FsCommand { command: String, args: String },
+
+    /// The quality of the stage changed.
+    QualityChanged { quality: StageQuality },
+}
+
+/// The channels of the embedders subscribed to `EmbedderEvent`s.
+#[derive(Default)]
+pub struct EmbedderEvents {
+    subscribers: Vec<Sender<EmbedderEvent>>,
+}
+
+impl EmbedderEvents {
+    /// How many events a subscriber may leave unread before newer ones are dropped.
+    pub const BUFFER_SIZE: usize = 64;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a channel receiving the events sent from now on.
+    ///
+    /// The channel holds up to `BUFFER_SIZE` unread events; events sent while it's full are
+    /// dropped, so that an embedder that stopped reading doesn't grow it forever.
+    pub fn subscribe(&mut self) -> Receiver<EmbedderEvent> {
+        let (sender, receiver) = mpsc::channel(Self::BUFFER_SIZE);
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Whether any embedder is subscribed, to skip preparing events nobody receives.
+    pub fn has_subscribers(&self) -> bool {
+        !self.subscribers.is_empty()
+    }
+
+    /// Sends `event` to every subscriber, forgetting those that dropped their channel.
+    ///
+    /// Returns `false` if nobody is subscribed anymore.
+    pub fn send(&mut self, event: EmbedderEvent) -> bool {
+        if self.subscribers.is_empty() {
+            return false;
+        }
+        self.subscribers
+            .retain_mut(|subscriber| match subscriber.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(error) if error.is_full() => {
+                    tracing::debug!("Dropping {:?}, the embedder isn't reading events", event);
+                    true
+                }
+                Err(_) => false,
+            });
+        !self.subscribers.is_empty()
+    }
 }
 
 /// The distance scrolled by the mouse wheel.
@@ -611,3 +727,46 @@ pub fn key_code_to_button_key_code(key_code: KeyCode) -> Option<ButtonKeyCode> {
     };
     Some(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_events_once_full() {
+        let mut events = EmbedderEvents::new();
+        assert!(!events.send(EmbedderEvent::FrameRun { frame: 1 }));
+
+        let mut receiver = events.subscribe();
+        // A channel also has room for one message per sender.
+        for frame in 0..=EmbedderEvents::BUFFER_SIZE as u16 {
+            assert!(events.send(EmbedderEvent::FrameRun { frame }));
+        }
+        assert!(events.send(EmbedderEvent::FrameRun { frame: 1000 }));
+
+        let mut received = 0;
+        while let Ok(Some(event)) = receiver.try_next() {
+            assert_ne!(event, EmbedderEvent::FrameRun { frame: 1000 });
+            received += 1;
+        }
+        assert_eq!(received, EmbedderEvents::BUFFER_SIZE + 1);
+
+        // Reading made room again.
+        assert!(events.send(EmbedderEvent::FrameRun { frame: 2000 }));
+        assert_eq!(
+            receiver.try_next().ok().flatten(),
+            Some(EmbedderEvent::FrameRun { frame: 2000 })
+        );
+    }
+
+    #[test]
+    fn forgets_dropped_receivers() {
+        let mut events = EmbedderEvents::new();
+        let receiver = events.subscribe();
+        assert!(events.has_subscribers());
+
+        drop(receiver);
+        assert!(!events.send(EmbedderEvent::FrameRun { frame: 1 }));
+        assert!(!events.has_subscribers());
+    }
+}
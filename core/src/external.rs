@@ -8,9 +8,14 @@ use crate::avm1::{
 use crate::avm2::activation::Activation as Avm2Activation;
 use crate::avm2::object::TObject as _;
 use crate::avm2::Value as Avm2Value;
-use crate::avm2::{ArrayObject as Avm2ArrayObject, Object as Avm2Object};
+use crate::avm2::{
+    ArrayObject as Avm2ArrayObject, Error as Avm2Error, Multiname as Avm2Multiname,
+    Object as Avm2Object,
+};
 use crate::context::UpdateContext;
+use crate::site_lock::SiteLock;
 use crate::string::AvmString;
+use crate::tag_utils::SwfMovie;
 use gc_arena::Collect;
 use std::collections::BTreeMap;
 
@@ -185,30 +190,46 @@ impl Value {
         }
     }
 
-    pub fn from_avm2(value: Avm2Value) -> Value {
-        match value {
+    pub fn from_avm2<'gc>(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        value: Avm2Value<'gc>,
+    ) -> Result<Value, Avm2Error<'gc>> {
+        Ok(match value {
             Avm2Value::Undefined | Avm2Value::Null => Value::Null,
             Avm2Value::Bool(value) => value.into(),
             Avm2Value::Number(value) => value.into(),
             Avm2Value::Integer(value) => value.into(),
             Avm2Value::String(value) => Value::String(value.to_string()),
             Avm2Value::Object(object) => {
-                if let Some(array) = object.as_array_storage() {
-                    let length = array.length();
-                    let values = (0..length)
-                        .map(|i| {
-                            // FIXME - is this right?
-                            let element = array.get(i).unwrap_or(Avm2Value::Null);
-                            Value::from_avm2(element)
-                        })
-                        .collect();
+                let elements: Option<Vec<_>> = object.as_array_storage().map(|array| {
+                    (0..array.length())
+                        .map(|i| array.get(i).unwrap_or(Avm2Value::Undefined))
+                        .collect()
+                });
+                if let Some(elements) = elements {
+                    let mut values = Vec::with_capacity(elements.len());
+                    for element in elements {
+                        values.push(Value::from_avm2(activation, element)?);
+                    }
                     Value::List(values)
-                } else {
-                    tracing::warn!("from_avm2 needs to be implemented for Avm2Value::Object");
+                } else if object.as_executable().is_some() {
                     Value::Null
+                } else {
+                    // Like Flash Player, only the enumerable properties are passed along.
+                    let mut values = BTreeMap::new();
+                    let mut last_index = object.get_next_enumerant(0, activation)?;
+                    while let Some(index) = last_index {
+                        let name = object
+                            .get_enumerant_name(index, activation)?
+                            .coerce_to_string(activation)?;
+                        let value = object.get_enumerant_value(index, activation)?;
+                        values.insert(name.to_string(), Value::from_avm2(activation, value)?);
+                        last_index = object.get_next_enumerant(index, activation)?;
+                    }
+                    Value::Object(values)
                 }
             }
-        }
+        })
     }
 
     pub fn into_avm2<'gc>(self, activation: &mut Avm2Activation<'_, 'gc>) -> Avm2Value<'gc> {
@@ -219,9 +240,22 @@ impl Value {
             Value::String(value) => {
                 Avm2Value::String(AvmString::new_utf8(activation.context.gc_context, value))
             }
-            Value::Object(_values) => {
-                tracing::warn!("into_avm2 needs to be implemented for Value::Object");
-                Avm2Value::Undefined
+            Value::Object(values) => {
+                let mut object = match activation
+                    .avm2()
+                    .classes()
+                    .object
+                    .construct(activation, &[])
+                {
+                    Ok(object) => object,
+                    Err(_) => return Avm2Value::Null,
+                };
+                for (key, value) in values {
+                    let key = AvmString::new_utf8(activation.context.gc_context, key);
+                    let value = value.into_avm2(activation);
+                    let _ = object.set_property(&Avm2Multiname::public(key), value, activation);
+                }
+                object.into()
             }
             Value::List(values) => {
                 let storage = values
@@ -235,6 +269,277 @@ impl Value {
     }
 }
 
+impl Value {
+    /// Encodes the value in the XML format of Flash Player's external API, which containers
+    /// such as the ActiveX control exchange with the movie.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        self.write_xml(&mut xml);
+        xml
+    }
+
+    fn write_xml(&self, xml: &mut String) {
+        match self {
+            Value::Null => xml.push_str("<null/>"),
+            Value::Bool(true) => xml.push_str("<true/>"),
+            Value::Bool(false) => xml.push_str("<false/>"),
+            Value::Number(value) => {
+                let value = if value.is_nan() {
+                    "NaN".to_string()
+                } else if value.is_infinite() {
+                    if *value > 0.0 {
+                        "Infinity"
+                    } else {
+                        "-Infinity"
+                    }
+                    .to_string()
+                } else {
+                    value.to_string()
+                };
+                xml.push_str(&format!("<number>{value}</number>"));
+            }
+            Value::String(value) => {
+                xml.push_str(&format!("<string>{}</string>", escape_xml(value)));
+            }
+            Value::Object(values) => {
+                xml.push_str("<object>");
+                for (key, value) in values {
+                    xml.push_str(&format!("<property id=\"{}\">", escape_xml(key)));
+                    value.write_xml(xml);
+                    xml.push_str("</property>");
+                }
+                xml.push_str("</object>");
+            }
+            Value::List(values) => {
+                xml.push_str("<array>");
+                for (i, value) in values.iter().enumerate() {
+                    xml.push_str(&format!("<property id=\"{i}\">"));
+                    value.write_xml(xml);
+                    xml.push_str("</property>");
+                }
+                xml.push_str("</array>");
+            }
+        }
+    }
+
+    /// Decodes a value encoded with `to_xml`, or `None` if it is malformed.
+    pub fn from_xml(xml: &str) -> Option<Value> {
+        let mut parser = XmlParser { rest: xml };
+        let value = parser.value()?;
+        parser.end().then_some(value)
+    }
+}
+
+/// Encodes a call to `name` in the XML format of Flash Player's external API.
+pub fn invoke_to_xml(name: &str, args: &[Value]) -> String {
+    let mut xml = format!(
+        "<invoke name=\"{}\" returntype=\"xml\"><arguments>",
+        escape_xml(name)
+    );
+    for arg in args {
+        arg.write_xml(&mut xml);
+    }
+    xml.push_str("</arguments></invoke>");
+    xml
+}
+
+/// Decodes a call encoded with `invoke_to_xml` into its name and arguments, or `None` if it
+/// is malformed.
+pub fn invoke_from_xml(xml: &str) -> Option<(String, Vec<Value>)> {
+    let mut parser = XmlParser { rest: xml };
+    if !parser.eat("<invoke") {
+        return None;
+    }
+    let name = parser.attribute("name")?;
+    if parser.rest.trim_start().starts_with("returntype") {
+        parser.attribute("returntype")?;
+    }
+
+    let mut args = Vec::new();
+    if parser.eat("/>") {
+        return parser.end().then_some((name, args));
+    }
+    if !parser.eat(">") {
+        return None;
+    }
+    if !parser.eat("<arguments/>") {
+        if !parser.eat("<arguments>") {
+            return None;
+        }
+        while !parser.eat("</arguments>") {
+            args.push(parser.value()?);
+        }
+    }
+    (parser.eat("</invoke>") && parser.end()).then_some((name, args))
+}
+
+/// Escapes the characters of `text` that can't appear as is in XML text or attributes.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Replaces the entities of XML text, such as `&amp;`, by the characters they stand for.
+pub fn unescape_xml(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// A reader of the small subset of XML used by the external API.
+struct XmlParser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> XmlParser<'a> {
+    /// Consumes `expected`, after any whitespace, if the input continues with it.
+    fn eat(&mut self, expected: &str) -> bool {
+        match self.rest.trim_start().strip_prefix(expected) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether only whitespace is left.
+    fn end(&self) -> bool {
+        self.rest.trim().is_empty()
+    }
+
+    /// Reads an attribute such as `name="value"`.
+    fn attribute(&mut self, name: &str) -> Option<String> {
+        if !(self.eat(name) && self.eat("=\"")) {
+            return None;
+        }
+        let end = self.rest.find('"')?;
+        let value = unescape_xml(&self.rest[..end]);
+        self.rest = &self.rest[end + 1..];
+        Some(value)
+    }
+
+    /// Reads the text up to the next tag, then that tag, which must be `closing`.
+    fn text(&mut self, closing: &str) -> Option<String> {
+        let end = self.rest.find('<')?;
+        let text = unescape_xml(&self.rest[..end]);
+        self.rest = &self.rest[end..];
+        self.eat(closing).then_some(text)
+    }
+
+    /// Reads the `<property>` children of an array or object, up to the tag `closing`.
+    fn properties(&mut self, closing: &str) -> Option<Vec<(String, Value)>> {
+        let mut properties = Vec::new();
+        while !self.eat(closing) {
+            if !self.eat("<property") {
+                return None;
+            }
+            let id = self.attribute("id")?;
+            let value = if self.eat("/>") {
+                Value::Null
+            } else if self.eat(">") {
+                let value = self.value()?;
+                if !self.eat("</property>") {
+                    return None;
+                }
+                value
+            } else {
+                return None;
+            };
+            properties.push((id, value));
+        }
+        Some(properties)
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        if self.eat("<null/>") || self.eat("<undefined/>") {
+            Some(Value::Null)
+        } else if self.eat("<true/>") {
+            Some(Value::Bool(true))
+        } else if self.eat("<false/>") {
+            Some(Value::Bool(false))
+        } else if self.eat("<string/>") {
+            Some(Value::String(String::new()))
+        } else if self.eat("<string>") {
+            self.text("</string>").map(Value::String)
+        } else if self.eat("<number>") {
+            let number = self.text("</number>")?;
+            let number = match number.trim() {
+                "NaN" => f64::NAN,
+                "Infinity" => f64::INFINITY,
+                "-Infinity" => f64::NEG_INFINITY,
+                number => number.parse().ok()?,
+            };
+            Some(Value::Number(number))
+        } else if self.eat("<date>") {
+            // Dates are passed along as milliseconds since the epoch.
+            self.text("</date>")?.trim().parse().ok().map(Value::Number)
+        } else if self.eat("<array/>") {
+            Some(Value::List(Vec::new()))
+        } else if self.eat("<array>") {
+            let mut values = Vec::new();
+            for (id, value) in self.properties("</array>")? {
+                let index: usize = id.parse().ok()?;
+                if index >= values.len() {
+                    values.resize(index + 1, Value::Null);
+                }
+                values[index] = value;
+            }
+            Some(Value::List(values))
+        } else if self.eat("<object/>") {
+            Some(Value::Object(BTreeMap::new()))
+        } else if self.eat("<object>") {
+            Some(Value::Object(
+                self.properties("</object>")?.into_iter().collect(),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Collect, Clone)]
 #[collect(no_drop)]
 pub enum Callback<'gc> {
@@ -283,8 +588,11 @@ impl<'gc> Callback<'gc> {
                     .into_iter()
                     .map(|v| v.into_avm2(&mut activation))
                     .collect();
-                if let Ok(result) = method.call(None, &args, &mut activation) {
-                    Value::from_avm2(result)
+                if let Ok(result) = method
+                    .call(None, &args, &mut activation)
+                    .and_then(|value| Value::from_avm2(&mut activation, value))
+                {
+                    result
                 } else {
                     Value::Null
                 }
@@ -299,6 +607,12 @@ pub trait ExternalInterfaceProvider {
     fn on_callback_available(&self, name: &str);
 
     fn on_fs_command(&self, command: &str, args: &str) -> bool;
+
+    /// The `id` of the element the movie is embedded with, reported by
+    /// `ExternalInterface.objectID`.
+    fn object_id(&self) -> Option<String> {
+        None
+    }
 }
 
 pub trait ExternalInterfaceMethod {
@@ -320,6 +634,11 @@ pub struct ExternalInterface<'gc> {
     #[collect(require_static)]
     providers: Vec<Box<dyn ExternalInterfaceProvider>>,
     callbacks: BTreeMap<String, Callback<'gc>>,
+
+    /// The site lock answering queries of the page location by the root
+    /// movie, if enabled.
+    #[collect(require_static)]
+    site_lock: Option<SiteLock>,
 }
 
 impl<'gc> ExternalInterface<'gc> {
@@ -338,21 +657,51 @@ impl<'gc> ExternalInterface<'gc> {
         }
     }
 
+    pub fn remove_callback(&mut self, name: &str) {
+        self.callbacks.remove(name);
+    }
+
     pub fn get_callback(&self, name: &str) -> Option<Callback<'gc>> {
         self.callbacks.get(name).cloned()
     }
 
-    pub fn get_method_for(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
-        for provider in &self.providers {
-            if let Some(method) = provider.get_method(name) {
-                return Some(method);
-            }
+    pub fn set_site_lock(&mut self, site_lock: Option<SiteLock>) {
+        self.site_lock = site_lock;
+    }
+
+    /// Returns the method called by `movie` through `ExternalInterface.call`.
+    pub fn get_method_for(
+        &self,
+        name: &str,
+        movie: &SwfMovie,
+    ) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        let method = self
+            .providers
+            .iter()
+            .find_map(|provider| provider.get_method(name));
+        match self.site_lock_for(movie) {
+            Some(site_lock) => site_lock.method_for(name, method),
+            None => method,
         }
-        None
     }
 
-    pub fn available(&self) -> bool {
-        !self.providers.is_empty()
+    /// Whether `ExternalInterface` is available to `movie`.
+    pub fn available(&self, movie: &SwfMovie) -> bool {
+        // Site-locked movies commonly check for `ExternalInterface` before
+        // asking for the page location.
+        !self.providers.is_empty() || self.site_lock_for(movie).is_some()
+    }
+
+    fn site_lock_for(&self, movie: &SwfMovie) -> Option<&SiteLock> {
+        self.site_lock
+            .as_ref()
+            .filter(|site_lock| site_lock.applies_to(movie))
+    }
+
+    pub fn object_id(&self) -> Option<String> {
+        self.providers
+            .iter()
+            .find_map(|provider| provider.object_id())
     }
 
     pub fn invoke_fs_command(&self, command: &str, args: &str) -> bool {
@@ -364,3 +713,74 @@ impl<'gc> ExternalInterface<'gc> {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_round_trip() {
+        let mut object = BTreeMap::new();
+        object.insert("name".to_string(), Value::from("<Ruffle & \"friends\">"));
+        object.insert(
+            "scores".to_string(),
+            Value::List(vec![1.5.into(), f64::NAN.into(), Value::Null]),
+        );
+        object.insert("empty".to_string(), Value::Object(BTreeMap::new()));
+        let value = Value::List(vec![Value::Object(object), true.into(), "".into()]);
+
+        let xml = value.to_xml();
+        assert!(xml.starts_with("<array><property id=\"0\"><object>"));
+        match Value::from_xml(&xml) {
+            Some(Value::List(values)) => {
+                assert_eq!(values.len(), 3);
+                assert_eq!(values[1], Value::Bool(true));
+                assert_eq!(values[2], Value::from(""));
+                match &values[0] {
+                    Value::Object(object) => {
+                        assert_eq!(object["name"], Value::from("<Ruffle & \"friends\">"));
+                        assert!(
+                            matches!(&object["scores"], Value::List(scores) if scores.len() == 3)
+                        );
+                    }
+                    other => panic!("Expected an object, got {other:?}"),
+                }
+            }
+            other => panic!("Expected a list, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn invoke_xml() {
+        let xml = invoke_to_xml("game.save", &[Value::from("slot1"), 3.0.into()]);
+        assert_eq!(
+            xml,
+            "<invoke name=\"game.save\" returntype=\"xml\"><arguments>\
+             <string>slot1</string><number>3</number></arguments></invoke>"
+        );
+        assert_eq!(
+            invoke_from_xml(&xml),
+            Some((
+                "game.save".to_string(),
+                vec![Value::from("slot1"), 3.0.into()]
+            ))
+        );
+        assert_eq!(
+            invoke_from_xml("<invoke name=\"ping\" returntype=\"xml\"><arguments/></invoke>"),
+            Some(("ping".to_string(), vec![]))
+        );
+        assert_eq!(invoke_from_xml("<invoke name=\"ping\">"), None);
+    }
+
+    #[test]
+    fn xml_entities() {
+        assert_eq!(
+            Value::from_xml("<string>a&#x41;&#66;&unknown;</string>"),
+            Some(Value::from("aAB&unknown;"))
+        );
+        assert_eq!(
+            Value::from_xml("<array><property id=\"2\"><true/></property></array>"),
+            Some(Value::List(vec![Value::Null, Value::Null, true.into()]))
+        );
+    }
+}
@@ -2,19 +2,61 @@ use crate::avm1::Avm1;
 use crate::avm1::Value;
 use crate::context::UpdateContext;
 pub use crate::display_object::{DisplayObject, TDisplayObject, TDisplayObjectContainer};
+use crate::display_object::{Stage, TInteractiveObject};
 use gc_arena::{Collect, GcCell, MutationContext};
 
 #[derive(Clone, Copy, Collect)]
 #[collect(no_drop)]
-pub struct FocusTracker<'gc>(GcCell<'gc, Option<DisplayObject<'gc>>>);
+pub struct FocusTracker<'gc>(GcCell<'gc, FocusTrackerData<'gc>>);
+
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+struct FocusTrackerData<'gc> {
+    focus: Option<DisplayObject<'gc>>,
+
+    /// Whether the focused object should be surrounded by the yellow focus rectangle.
+    ///
+    /// Flash only shows the rectangle when focus was moved by the keyboard or by script, and hides
+    /// it again as soon as focus changes through the mouse.
+    highlight: bool,
+}
 
 impl<'gc> FocusTracker<'gc> {
     pub fn new(gc_context: MutationContext<'gc, '_>) -> Self {
-        Self(GcCell::allocate(gc_context, None))
+        Self(GcCell::allocate(
+            gc_context,
+            FocusTrackerData {
+                focus: None,
+                highlight: false,
+            },
+        ))
     }
 
     pub fn get(&self) -> Option<DisplayObject<'gc>> {
-        *self.0.read()
+        self.0.read().focus
+    }
+
+    /// Returns the object that should currently be surrounded by a focus rectangle, if any.
+    ///
+    /// This takes `Stage.stageFocusRect` and the `focusRect` property of the focused object
+    /// into account.
+    pub fn highlighted(&self, stage: Stage<'gc>) -> Option<DisplayObject<'gc>> {
+        let data = self.0.read();
+        let focus = data.focus.filter(|_| data.highlight)?;
+        if focus.as_edit_text().is_some() {
+            // Text fields show their caret instead.
+            return None;
+        }
+        let enabled = focus
+            .as_interactive()
+            .and_then(|interactive| interactive.focus_rect())
+            .unwrap_or_else(|| stage.stage_focus_rect());
+        enabled.then_some(focus)
+    }
+
+    /// Shows the focus rectangle around the currently focused object.
+    pub fn highlight(&self, gc_context: MutationContext<'gc, '_>) {
+        self.0.write(gc_context).highlight = true;
     }
 
     pub fn set(
@@ -22,7 +64,10 @@ impl<'gc> FocusTracker<'gc> {
         focused_element: Option<DisplayObject<'gc>>,
         context: &mut UpdateContext<'_, 'gc>,
     ) {
-        let old = std::mem::replace(&mut *self.0.write(context.gc_context), focused_element);
+        let mut data = self.0.write(context.gc_context);
+        let old = std::mem::replace(&mut data.focus, focused_element);
+        data.highlight = false;
+        drop(data);
 
         if old.is_none() && focused_element.is_none() {
             // We didn't have anything, we still don't, no change.
@@ -42,6 +87,14 @@ impl<'gc> FocusTracker<'gc> {
             new.on_focus_changed(context.gc_context, true);
         }
 
+        let editable_text = focused_element
+            .and_then(|o| o.as_edit_text())
+            .filter(|text| text.is_editable());
+        context.ui.set_ime_allowed(editable_text.is_some());
+        if let Some(text) = editable_text {
+            text.update_ime_position(context);
+        }
+
         tracing::info!("Focus is now on {:?}", focused_element);
 
         let level0 = context.stage.root_clip();
@@ -57,3 +110,46 @@ impl<'gc> FocusTracker<'gc> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_object::MovieClip;
+    use crate::tag_utils::SwfMovie;
+    use gc_arena::rootless_arena;
+    use std::sync::Arc;
+
+    #[test]
+    fn highlight_follows_focus_rect_settings() {
+        rootless_arena(|mc| {
+            let movie = Arc::new(SwfMovie::empty(10));
+            let stage = Stage::empty(mc, false, movie.clone());
+            let clip: DisplayObject = MovieClip::new(movie, mc).into();
+            let tracker = FocusTracker::new(mc);
+            tracker.0.write(mc).focus = Some(clip);
+            let is_highlighted = || {
+                tracker
+                    .highlighted(stage)
+                    .map_or(false, |focus| DisplayObject::ptr_eq(focus, clip))
+            };
+
+            // Focus moved by the mouse isn't highlighted.
+            assert!(!is_highlighted());
+
+            tracker.highlight(mc);
+            assert!(is_highlighted());
+
+            stage.set_stage_focus_rect(mc, false);
+            assert!(!is_highlighted());
+
+            // The object's own setting overrides the stage's.
+            let interactive = clip.as_interactive().unwrap();
+            interactive.set_focus_rect(mc, Some(true));
+            assert!(is_highlighted());
+
+            stage.set_stage_focus_rect(mc, true);
+            interactive.set_focus_rect(mc, Some(false));
+            assert!(!is_highlighted());
+        })
+    }
+}
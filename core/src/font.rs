@@ -1,11 +1,21 @@
+use crate::context::RenderContext;
 use crate::html::TextSpan;
 use crate::prelude::*;
-use crate::string::WStr;
+use crate::string::{WStr, WString};
 use gc_arena::{Collect, Gc, MutationContext};
+use once_cell::unsync::OnceCell;
+use ouroboros::self_referencing;
 use ruffle_render::backend::{RenderBackend, ShapeHandle};
+use ruffle_render::commands::CommandHandler;
+use ruffle_render::shape_utils::calculate_shape_bounds;
 use ruffle_render::transform::Transform;
+use serde::{Deserialize, Serialize};
 use std::cell::{Cell, Ref, RefCell};
 use std::cmp::max;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use swf::Fixed8;
 
 pub use swf::TextGridFit;
 
@@ -31,6 +41,12 @@ pub struct EvalParameters {
     /// pairs of letters, separate from the ordinary width between glyphs. This
     /// parameter allows enabling or disabling that feature.
     kerning: bool,
+
+    /// Whether the text runs from right to left.
+    ///
+    /// The glyphs of right-to-left text are laid out from its last character
+    /// to its first, so that it reads correctly from the right.
+    is_rtl: bool,
 }
 
 impl EvalParameters {
@@ -41,6 +57,7 @@ impl EvalParameters {
             height,
             letter_spacing,
             kerning,
+            is_rtl: false,
         }
     }
 
@@ -51,29 +68,49 @@ impl EvalParameters {
             height: Twips::from_pixels(span.size),
             letter_spacing: Twips::from_pixels(span.letter_spacing),
             kerning: span.kerning,
+            is_rtl: false,
         }
     }
 
+    /// Lay the text out from right to left.
+    pub fn with_right_to_left(self, is_rtl: bool) -> Self {
+        Self { is_rtl, ..self }
+    }
+
     /// Get the height that the font would be evaluated at.
     pub fn height(&self) -> Twips {
         self.height
     }
+
+    /// Whether the text is laid out from right to left.
+    pub fn is_right_to_left(&self) -> bool {
+        self.is_rtl
+    }
 }
 
 #[derive(Debug, Clone, Collect, Copy)]
 #[collect(no_drop)]
 pub struct Font<'gc>(Gc<'gc, FontData>);
 
-#[derive(Debug, Clone, Collect)]
+#[derive(Debug, Collect)]
 #[collect(require_static)]
 struct FontData {
     /// The list of glyphs defined in the font.
     /// Used directly by `DefineText` tags.
-    glyphs: Vec<Glyph>,
+    ///
+    /// The glyphs of font files are only converted when they're first used.
+    glyphs: Vec<OnceCell<Glyph>>,
 
     /// A map from a Unicode code point to glyph in the `glyphs` array.
     /// Used by `DefineEditText` tags.
-    code_point_to_glyph: fnv::FnvHashMap<u16, usize>,
+    code_point_to_glyph: fnv::FnvHashMap<u32, usize>,
+
+    /// The font file that the glyphs are converted from, for fonts installed on the system.
+    file: Option<FontFile>,
+
+    /// The glyphs that recently evaluated strings were shaped into, by string,
+    /// direction and whether kerning was applied.
+    shaped_text: RefCell<fnv::FnvHashMap<(WString, bool, bool), Rc<[ShapedGlyph]>>>,
 
     /// The scaling applied to the font height to render at the proper size.
     /// This depends on the DefineFont tag version.
@@ -97,6 +134,20 @@ struct FontData {
 
     /// The identity of the font.
     descriptor: FontDescriptor,
+
+    /// Whether the font is embedded in a movie or installed on the system.
+    font_type: FontType,
+}
+
+/// Where a font comes from, as reported by `Font.fontType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+pub enum FontType {
+    /// A font defined by a `DefineFont` tag.
+    Embedded,
+
+    /// A font installed on the system.
+    Device,
 }
 
 impl<'gc> Font<'gc> {
@@ -125,15 +176,9 @@ impl<'gc> Font<'gc> {
                 None
             };
             let glyph_code = swf_glyph.code;
-            let glyph = Glyph {
-                shape_handle: Cell::new(handle),
-                advance: swf_glyph.advance,
-                shape: RefCell::new(None),
-                swf_glyph,
-            };
             let index = glyphs.len();
-            glyphs.push(glyph);
-            code_point_to_glyph.insert(glyph_code, index);
+            glyphs.push(OnceCell::from(Glyph::new(swf_glyph, handle)));
+            code_point_to_glyph.insert(glyph_code.into(), index);
         }
         let kerning_pairs: fnv::FnvHashMap<(u16, u16), Twips> = if let Some(layout) = &tag.layout {
             layout
@@ -150,6 +195,8 @@ impl<'gc> Font<'gc> {
             FontData {
                 glyphs,
                 code_point_to_glyph,
+                file: None,
+                shaped_text: Default::default(),
 
                 /// DefineFont3 stores coordinates at 20x the scale of DefineFont1/2.
                 /// (SWF19 p.164)
@@ -159,10 +206,186 @@ impl<'gc> Font<'gc> {
                 descent,
                 leading,
                 descriptor,
+                font_type: FontType::Embedded,
             },
         ))
     }
 
+    /// Loads a TrueType or OpenType font file, such as one of a font installed on the system.
+    ///
+    /// The outlines of the glyphs are converted to shapes at the scale of `DefineFont3` glyphs
+    /// as they're first used. Returns `None` if the file can't be parsed.
+    pub fn from_font_file(
+        gc_context: MutationContext<'gc, '_>,
+        descriptor: FontDescriptor,
+        data: Vec<u8>,
+        index: u32,
+    ) -> Option<Font<'gc>> {
+        let file = FontFile::parse(data, index)?;
+        let face = file.borrow_face();
+        let scale = file.scale();
+        let to_em = |value: f32| (value * scale).round() as i32;
+
+        // The glyphs are kept in the order of the font file, so that a glyph can be converted
+        // given its index.
+        let glyphs = vec![OnceCell::new(); face.number_of_glyphs().into()];
+        let mut code_point_to_glyph = fnv::FnvHashMap::default();
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables {
+                if !subtable.is_unicode() {
+                    continue;
+                }
+                subtable.codepoints(|code_point| {
+                    if let Some(glyph_id) = subtable.glyph_index(code_point) {
+                        code_point_to_glyph
+                            .entry(code_point)
+                            .or_insert_with(|| usize::from(glyph_id.0));
+                    }
+                });
+            }
+        }
+
+        // Only the kerning of printable ASCII characters is read, as the kerning table is
+        // indexed by glyph rather than by character.
+        let mut kerning_pairs = fnv::FnvHashMap::default();
+        if let Some(kern) = face.tables().kern {
+            for subtable in kern.subtables {
+                if !subtable.horizontal || subtable.variable {
+                    continue;
+                }
+                for left in ' '..='~' {
+                    for right in ' '..='~' {
+                        let adjustment = face
+                            .glyph_index(left)
+                            .zip(face.glyph_index(right))
+                            .and_then(|(left, right)| subtable.glyphs_kerning(left, right));
+                        if let Some(adjustment) = adjustment {
+                            kerning_pairs.insert(
+                                (left as u16, right as u16),
+                                Twips::new(to_em(adjustment.into())),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let ascent = to_em(face.ascender().into()).clamp(0, u16::MAX.into()) as u16;
+        let descent = to_em((-face.descender()).into()).clamp(0, u16::MAX.into()) as u16;
+        let leading = to_em(face.line_gap().into()).clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+        Some(Font(Gc::allocate(
+            gc_context,
+            FontData {
+                glyphs,
+                code_point_to_glyph,
+                file: Some(file),
+                shaped_text: Default::default(),
+                scale: DEFINE_FONT_3_EM_SQUARE,
+                kerning_pairs,
+                ascent,
+                descent,
+                leading,
+                descriptor,
+                font_type: FontType::Device,
+            },
+        )))
+    }
+
+    /// Creates a font without glyphs that only names a font installed on the system.
+    ///
+    /// This is what `Font.enumerateFonts` lists device fonts as, so that all of them needn't
+    /// be loaded. Text is drawn with the font loaded by `Library::get_device_font` instead.
+    pub fn from_device_font_name(
+        gc_context: MutationContext<'gc, '_>,
+        descriptor: FontDescriptor,
+    ) -> Font<'gc> {
+        Font(Gc::allocate(
+            gc_context,
+            FontData {
+                glyphs: vec![],
+                code_point_to_glyph: Default::default(),
+                file: None,
+                shaped_text: Default::default(),
+                scale: DEFINE_FONT_3_EM_SQUARE,
+                kerning_pairs: Default::default(),
+                ascent: 0,
+                descent: 0,
+                leading: 0,
+                descriptor,
+                font_type: FontType::Device,
+            },
+        ))
+    }
+
+    /// Applies a `DefineFontInfo` tag to a font defined by a `DefineFont` tag.
+    ///
+    /// `DefineFont` only contains glyph shapes, so this is what names the font and maps
+    /// its glyphs to characters. There is no layout information either, so the advances
+    /// and metrics of the font are synthesized from the glyph shapes.
+    pub fn with_font_info(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        info: &swf::FontInfo,
+        encoding: &'static swf::Encoding,
+    ) -> Font<'gc> {
+        let mut glyphs = self.0.glyphs.clone();
+        let mut code_point_to_glyph = fnv::FnvHashMap::default();
+        let mut ascent = 0;
+        let mut descent = 0;
+
+        for (index, (glyph, &code)) in glyphs.iter_mut().zip(&info.code_table).enumerate() {
+            // `DefineFont` glyphs are all converted when the tag is loaded.
+            let glyph = match glyph.get_mut() {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let code = font_info_code_point(code, info.flags);
+            glyph.swf_glyph.code = code;
+            code_point_to_glyph.insert(code.into(), index);
+
+            let bounds = calculate_shape_bounds(&glyph.swf_glyph.shape_records);
+            if glyph.advance == 0 {
+                glyph.advance = if bounds == Default::default() {
+                    // An empty glyph, such as a space.
+                    DEFINE_FONT_1_EM_SQUARE / 4
+                } else {
+                    (bounds.x_max.get() + i32::from(DEFINE_FONT_1_EM_SQUARE / 16))
+                        .clamp(0, i16::MAX.into()) as i16
+                };
+            }
+            // Glyphs are drawn upwards from the baseline at y = 0.
+            ascent = ascent.max(-bounds.y_min.get());
+            descent = descent.max(bounds.y_max.get());
+        }
+
+        let name = info.name.to_string_lossy(encoding);
+        Font(Gc::allocate(
+            gc_context,
+            FontData {
+                glyphs,
+                code_point_to_glyph,
+                file: None,
+                shaped_text: Default::default(),
+                scale: self.0.scale,
+                kerning_pairs: self.0.kerning_pairs.clone(),
+                ascent: ascent.clamp(0, u16::MAX.into()) as u16,
+                descent: descent.clamp(0, u16::MAX.into()) as u16,
+                leading: self.0.leading,
+                descriptor: FontDescriptor::from_parts(
+                    &name,
+                    info.flags.contains(swf::FontInfoFlag::IS_BOLD),
+                    info.flags.contains(swf::FontInfoFlag::IS_ITALIC),
+                ),
+                font_type: self.0.font_type,
+            },
+        ))
+    }
+
+    /// Returns whether both fonts are the same font.
+    pub fn ptr_eq(a: Font<'gc>, b: Font<'gc>) -> bool {
+        Gc::ptr_eq(a.0, b.0)
+    }
+
     /// Returns whether this font contains glyph shapes.
     /// If not, this font should be rendered as a device font.
     pub fn has_glyphs(&self) -> bool {
@@ -172,14 +395,28 @@ impl<'gc> Font<'gc> {
     /// Returns a glyph entry by index.
     /// Used by `Text` display objects.
     pub fn get_glyph(&self, i: usize) -> Option<&Glyph> {
-        self.0.glyphs.get(i)
+        let glyph = self.0.glyphs.get(i)?;
+        Some(glyph.get_or_init(|| {
+            self.0
+                .file
+                .as_ref()
+                .and_then(|file| file.glyph(i))
+                .unwrap_or_else(|| {
+                    let empty_glyph = swf::Glyph {
+                        shape_records: vec![],
+                        code: 0,
+                        advance: 0,
+                        bounds: None,
+                    };
+                    Glyph::new(empty_glyph, None)
+                })
+        }))
     }
 
     /// Returns a glyph entry by character.
     /// Used by `EditText` display objects.
     pub fn get_glyph_for_char(&self, c: char) -> Option<&Glyph> {
-        // TODO: Properly handle UTF-16/out-of-bounds code points.
-        let code_point = c as u16;
+        let code_point = u32::from(c);
         if let Some(index) = self.0.code_point_to_glyph.get(&code_point) {
             self.get_glyph(*index)
         } else {
@@ -254,6 +491,100 @@ impl<'gc> Font<'gc> {
     pub fn evaluate<FGlyph>(
         &self,
         text: &WStr, // TODO: take an `IntoIterator<Item=char>`, to not depend on string representation?
+        transform: Transform,
+        params: EvalParameters,
+        glyph_func: FGlyph,
+    ) where
+        FGlyph: FnMut(usize, &Transform, &Glyph, Twips, Twips),
+    {
+        if let Some(file) = &self.0.file {
+            return self.evaluate_shaped(file, text, transform, params, glyph_func);
+        }
+
+        let char_indices = text
+            .char_indices()
+            .map(|(pos, c)| (pos, c.unwrap_or(char::REPLACEMENT_CHARACTER)));
+        if params.is_rtl {
+            // Right-to-left text is drawn from its last character, but the
+            // positions passed on still index into `text`.
+            let char_indices: Vec<_> = char_indices.collect();
+            self.evaluate_chars(
+                char_indices.into_iter().rev(),
+                transform,
+                params,
+                glyph_func,
+            );
+        } else {
+            self.evaluate_chars(char_indices, transform, params, glyph_func);
+        }
+    }
+
+    /// Evaluate the glyphs that a font file shapes a string into, in the order
+    /// they are drawn, from left to right.
+    ///
+    /// Shaping picks the glyphs for whole clusters of characters, such as the
+    /// joining forms of Arabic letters, Indic conjuncts or letters with
+    /// combining marks, and places them relative to each other.
+    fn evaluate_shaped<FGlyph>(
+        &self,
+        file: &FontFile,
+        text: &WStr,
+        mut transform: Transform,
+        params: EvalParameters,
+        mut glyph_func: FGlyph,
+    ) where
+        FGlyph: FnMut(usize, &Transform, &Glyph, Twips, Twips),
+    {
+        transform.matrix.ty += params.height;
+        let scale = params.height.get() as f32 / self.scale();
+        let to_twips = |value: i32| Twips::new((value as f32 * scale) as i32);
+
+        transform.matrix.a = scale;
+        transform.matrix.d = scale;
+        let key = (text.into(), params.is_rtl, params.kerning);
+        let cached = self.0.shaped_text.borrow().get(&key).cloned();
+        let shaped = cached.unwrap_or_else(|| {
+            let shaped: Rc<[ShapedGlyph]> = file.shape(text, params.is_rtl, params.kerning).into();
+            let mut shaped_text = self.0.shaped_text.borrow_mut();
+            if shaped_text.len() >= MAX_SHAPED_TEXT {
+                shaped_text.clear();
+            }
+            shaped_text.insert(key, shaped.clone());
+            shaped
+        });
+
+        let mut x = Twips::ZERO;
+        for (i, shaped_glyph) in shaped.iter().enumerate() {
+            // Characters without a glyph in the font are left out, as when text isn't shaped.
+            if shaped_glyph.index == 0 {
+                continue;
+            }
+            if let Some(glyph) = self.get_glyph(shaped_glyph.index) {
+                let mut twips_advance = to_twips(shaped_glyph.advance);
+                // Letters are spaced apart, not the glyphs of one cluster.
+                let ends_cluster = shaped
+                    .get(i + 1)
+                    .map_or(true, |next| next.pos != shaped_glyph.pos);
+                if ends_cluster {
+                    twips_advance += params.letter_spacing;
+                }
+
+                let mut glyph_transform = transform.clone();
+                glyph_transform.matrix.tx += to_twips(shaped_glyph.x_offset);
+                glyph_transform.matrix.ty -= to_twips(shaped_glyph.y_offset);
+                glyph_func(shaped_glyph.pos, &glyph_transform, glyph, twips_advance, x);
+
+                // Step horizontally.
+                transform.matrix.tx += twips_advance;
+                x += twips_advance;
+            }
+        }
+    }
+
+    /// Evaluate characters in the order they are drawn, from left to right.
+    fn evaluate_chars<FGlyph>(
+        &self,
+        char_indices: impl Iterator<Item = (usize, char)>,
         mut transform: Transform,
         params: EvalParameters,
         mut glyph_func: FGlyph,
@@ -265,16 +596,14 @@ impl<'gc> Font<'gc> {
 
         transform.matrix.a = scale;
         transform.matrix.d = scale;
-        let mut char_indices = text.char_indices().peekable();
+        let mut char_indices = char_indices.peekable();
         let has_kerning_info = self.has_kerning_info();
         let mut x = Twips::ZERO;
         while let Some((pos, c)) = char_indices.next() {
-            let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
             if let Some(glyph) = self.get_glyph_for_char(c) {
                 let mut advance = Twips::new(glyph.advance);
                 if has_kerning_info && params.kerning {
-                    let next_char = char_indices.peek().cloned().unwrap_or((0, Ok('\0'))).1;
-                    let next_char = next_char.unwrap_or(char::REPLACEMENT_CHARACTER);
+                    let next_char = char_indices.peek().map_or('\0', |(_, c)| *c);
                     advance += self.get_kerning_offset(c, next_char);
                 }
                 let twips_advance =
@@ -412,6 +741,340 @@ impl<'gc> Font<'gc> {
     pub fn descriptor(&self) -> &FontDescriptor {
         &self.0.descriptor
     }
+
+    pub fn font_type(&self) -> FontType {
+        self.0.font_type
+    }
+}
+
+/// The size of the EM square of `DefineFont` and `DefineFont2` glyphs.
+const DEFINE_FONT_1_EM_SQUARE: i16 = 1024;
+
+/// The size of the EM square of `DefineFont3` glyphs, in twips.
+const DEFINE_FONT_3_EM_SQUARE: f32 = 20480.0;
+
+/// The number of strings whose shaped glyphs are kept by a font before they're
+/// all shaped again.
+const MAX_SHAPED_TEXT: usize = 1024;
+
+/// A glyph that a string was shaped into.
+///
+/// The advance and offsets are given in the units of `DefineFont3` glyphs.
+#[derive(Debug, Clone, Copy)]
+struct ShapedGlyph {
+    /// The index of the glyph in the font.
+    index: usize,
+
+    /// The position in the string of the first character of the cluster the
+    /// glyph belongs to.
+    pos: usize,
+
+    advance: i32,
+    x_offset: i32,
+    y_offset: i32,
+}
+
+/// A TrueType or OpenType font file, whose glyphs are converted to shapes as they're used.
+#[self_referencing]
+struct FontFile {
+    data: Vec<u8>,
+
+    /// The font parsed from `data`, which is kept so that the font isn't parsed again for
+    /// each glyph and string.
+    #[borrows(data)]
+    #[covariant]
+    face: rustybuzz::Face<'this>,
+}
+
+impl fmt::Debug for FontFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontFile").finish_non_exhaustive()
+    }
+}
+
+impl FontFile {
+    /// Parses the font at `index` in a font file, which is a font collection if it's not 0.
+    fn parse(data: Vec<u8>, index: u32) -> Option<Self> {
+        FontFile::try_new(data, |data| {
+            rustybuzz::Face::from_slice(data, index).ok_or(())
+        })
+        .ok()
+    }
+
+    /// The scale from the units of the font file to the EM square of `DefineFont3` glyphs.
+    fn scale(&self) -> f32 {
+        DEFINE_FONT_3_EM_SQUARE / f32::from(self.borrow_face().units_per_em())
+    }
+
+    /// Converts the glyph with the given index.
+    fn glyph(&self, index: usize) -> Option<Glyph> {
+        let face = self.borrow_face();
+        let scale = self.scale();
+        let glyph_id = ttf_parser::GlyphId(u16::try_from(index).ok()?);
+        let mut outline = GlyphOutline::new(scale);
+        face.outline_glyph(glyph_id, &mut outline);
+        let advance = f32::from(face.glyph_hor_advance(glyph_id).unwrap_or_default());
+        let swf_glyph = swf::Glyph {
+            shape_records: outline.records,
+            // The glyph may be used for several characters.
+            code: 0,
+            advance: ((advance * scale).round() as i32).clamp(0, i16::MAX.into()) as i16,
+            bounds: None,
+        };
+        Some(Glyph::new(swf_glyph, None))
+    }
+
+    /// Shapes a string into the glyphs of the font, in the order they are
+    /// drawn from left to right.
+    fn shape(&self, text: &WStr, is_rtl: bool, kerning: bool) -> Vec<ShapedGlyph> {
+        // The shaper works on UTF-8, so the positions of its clusters are
+        // mapped back to the UTF-16 string.
+        let mut utf8 = String::with_capacity(text.len());
+        let mut positions = Vec::with_capacity(text.len() + 1);
+        for (pos, c) in text.char_indices() {
+            let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+            positions.extend(std::iter::repeat(pos).take(c.len_utf8()));
+            utf8.push(c);
+        }
+        positions.push(text.len());
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(&utf8);
+        buffer.set_direction(if is_rtl {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+        buffer.guess_segment_properties();
+
+        let features = if kerning {
+            vec![]
+        } else {
+            vec![rustybuzz::Feature::new(
+                ttf_parser::Tag::from_bytes(b"kern"),
+                0,
+                ..,
+            )]
+        };
+        let output = rustybuzz::shape(self.borrow_face(), &features, buffer);
+
+        let scale = self.scale();
+        let to_em = |value: i32| (value as f32 * scale).round() as i32;
+        output
+            .glyph_infos()
+            .iter()
+            .zip(output.glyph_positions())
+            .map(|(info, position)| ShapedGlyph {
+                index: info.glyph_id as usize,
+                pos: positions
+                    .get(info.cluster as usize)
+                    .copied()
+                    .unwrap_or_default(),
+                advance: to_em(position.x_advance),
+                x_offset: to_em(position.x_offset),
+                y_offset: to_em(position.y_offset),
+            })
+            .collect()
+    }
+}
+
+/// Converts the outline of a glyph in a font file to shape records.
+///
+/// Font files are drawn upwards from the baseline, while shapes are drawn downwards, so the
+/// outline is flipped vertically.
+struct GlyphOutline {
+    /// The scale from the units of the font file to twips.
+    scale: f32,
+
+    records: Vec<swf::ShapeRecord>,
+
+    /// The start of the current contour, in font units.
+    start: (f32, f32),
+
+    /// The current point, in font units.
+    position: (f32, f32),
+
+    /// The current point, in twips, from which the next edge is drawn.
+    twips_position: (Twips, Twips),
+}
+
+impl GlyphOutline {
+    fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            records: vec![],
+            start: (0.0, 0.0),
+            position: (0.0, 0.0),
+            twips_position: (Twips::ZERO, Twips::ZERO),
+        }
+    }
+
+    /// Converts a point of the font file to twips, and makes it the current point. Returns
+    /// the offset to the point from the previous current point.
+    fn advance_to(&mut self, x: f32, y: f32) -> (Twips, Twips) {
+        let point = (
+            Twips::new((x * self.scale).round() as i32),
+            Twips::new((-y * self.scale).round() as i32),
+        );
+        let delta = (
+            point.0 - self.twips_position.0,
+            point.1 - self.twips_position.1,
+        );
+        self.position = (x, y);
+        self.twips_position = point;
+        delta
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.advance_to(x, y);
+        self.start = (x, y);
+        // The first contour sets the fill, and the others only move the pen.
+        let fill_style_1 = self.records.is_empty().then_some(1);
+        self.records.push(swf::ShapeRecord::StyleChange(Box::new(
+            swf::StyleChangeData {
+                move_to: Some(self.twips_position),
+                fill_style_0: None,
+                fill_style_1,
+                line_style: None,
+                new_styles: None,
+            },
+        )));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (delta_x, delta_y) = self.advance_to(x, y);
+        self.records
+            .push(swf::ShapeRecord::StraightEdge { delta_x, delta_y });
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (control_delta_x, control_delta_y) = self.advance_to(x1, y1);
+        let (anchor_delta_x, anchor_delta_y) = self.advance_to(x, y);
+        self.records.push(swf::ShapeRecord::CurvedEdge {
+            control_delta_x,
+            control_delta_y,
+            anchor_delta_x,
+            anchor_delta_y,
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        // Shapes have no cubic curves, so the curve is split in half, and each half is drawn
+        // as the quadratic curve closest to it.
+        let (x0, y0) = self.position;
+        let mid = |a: f32, b: f32| (a + b) / 2.0;
+        let (x01, y01) = (mid(x0, x1), mid(y0, y1));
+        let (x12, y12) = (mid(x1, x2), mid(y1, y2));
+        let (x23, y23) = (mid(x2, x), mid(y2, y));
+        let (x012, y012) = (mid(x01, x12), mid(y01, y12));
+        let (x123, y123) = (mid(x12, x23), mid(y12, y23));
+        let (xm, ym) = (mid(x012, x123), mid(y012, y123));
+        let control = |p0: f32, p1: f32, p2: f32, p3: f32| (3.0 * (p1 + p2) - p0 - p3) / 4.0;
+        self.quad_to(
+            control(x0, x01, x012, xm),
+            control(y0, y01, y012, ym),
+            xm,
+            ym,
+        );
+        self.quad_to(control(xm, x123, x23, x), control(ym, y123, y23, y), x, y);
+    }
+
+    fn close(&mut self) {
+        if self.position != self.start {
+            let (x, y) = self.start;
+            self.line_to(x, y);
+        }
+    }
+}
+
+/// Converts a character code from the code table of a `DefineFontInfo` tag to UTF-16.
+fn font_info_code_point(code: u16, flags: swf::FontInfoFlag) -> u16 {
+    let encoding = if flags.contains(swf::FontInfoFlag::IS_SHIFT_JIS) {
+        swf::SHIFT_JIS
+    } else if flags.contains(swf::FontInfoFlag::HAS_WIDE_CODES) {
+        return code;
+    } else {
+        swf::WINDOWS_1252
+    };
+    let bytes = code.to_be_bytes();
+    let bytes = if code > 0xFF { &bytes[..] } else { &bytes[1..] };
+    let (decoded, _) = encoding.decode_without_bom_handling(bytes);
+    decoded.encode_utf16().next().unwrap_or(code)
+}
+
+/// The size of text on screen, in pixels, up to which the advanced text
+/// rendering engine adjusts the thickness and sharpness of glyphs.
+///
+/// The adjustments fade out towards this size, as larger text is readable
+/// without them.
+const MAX_HINTED_SIZE: f64 = 48.0;
+
+/// The transforms that a glyph is drawn with, relative to the screen, to
+/// approximate the hinting of the advanced text rendering engine.
+///
+/// `stack_matrix` is the matrix of the text the glyph is in, which scales its
+/// `height` on screen.
+fn hinted_transforms(
+    mut transform: Transform,
+    stack_matrix: &Matrix,
+    settings: &TextRenderSettings,
+    height: Twips,
+) -> Vec<Transform> {
+    let (grid_fit, thickness, sharpness) = match settings {
+        TextRenderSettings::Advanced {
+            grid_fit,
+            thickness,
+            sharpness,
+        } => (*grid_fit, *thickness, *sharpness),
+        TextRenderSettings::Normal { .. } => return vec![transform],
+    };
+
+    // The transform is relative to the screen, where a pixel is 20 twips.
+    let snap = |value: Twips, step: f64| {
+        Twips::new(((f64::from(value.get()) / step).round() * step) as i32)
+    };
+    match grid_fit {
+        TextGridFit::None => {}
+        TextGridFit::Pixel => {
+            transform.matrix.tx = snap(transform.matrix.tx, 20.0);
+            transform.matrix.ty = snap(transform.matrix.ty, 20.0);
+        }
+        TextGridFit::SubPixel => {
+            transform.matrix.tx = snap(transform.matrix.tx, 20.0 / 3.0);
+            transform.matrix.ty = snap(transform.matrix.ty, 20.0);
+        }
+    }
+
+    let stack_scale = f64::from(stack_matrix.a * stack_matrix.d - stack_matrix.b * stack_matrix.c)
+        .abs()
+        .sqrt();
+    let weight = (1.0 - height.to_pixels() * stack_scale / MAX_HINTED_SIZE).clamp(0.0, 1.0);
+    // Thickness ranges from -200 to 200, and sharpness from -400 to 400.
+    let thickness = f64::from(thickness) / 200.0 * weight;
+    let sharpness = f64::from(sharpness) / 400.0 * weight;
+
+    // Thinner or softer glyphs are drawn more transparently.
+    let alpha = f64::from(transform.color_transform.a_mult.to_f32());
+    let fade = (1.0 + (thickness.min(0.0) + sharpness.min(0.0)) / 2.0).clamp(0.0, 1.0);
+    transform.color_transform.a_mult = Fixed8::from_f32((alpha * fade) as f32);
+    let mut transforms = vec![transform.clone()];
+
+    // Thicker glyphs are drawn again up to half a pixel to the right.
+    if thickness > 0.0 {
+        let mut bold_transform = transform.clone();
+        bold_transform.matrix.tx += Twips::from_pixels(thickness / 2.0);
+        transforms.push(bold_transform);
+    }
+
+    // Sharper glyphs are drawn again on top, which makes their antialiased
+    // edges more opaque.
+    if sharpness > 0.0 {
+        transform.color_transform.a_mult = Fixed8::from_f32((alpha * sharpness) as f32);
+        transforms.push(transform);
+    }
+    transforms
 }
 
 #[derive(Debug, Clone)]
@@ -430,6 +1093,15 @@ pub struct Glyph {
 }
 
 impl Glyph {
+    fn new(swf_glyph: swf::Glyph, shape_handle: Option<ShapeHandle>) -> Self {
+        Self {
+            shape_handle: Cell::new(shape_handle),
+            advance: swf_glyph.advance,
+            shape: RefCell::new(None),
+            swf_glyph,
+        }
+    }
+
     pub fn shape_handle(&self, renderer: &mut dyn RenderBackend) -> ShapeHandle {
         if self.shape_handle.get().is_none() {
             self.shape_handle
@@ -438,6 +1110,36 @@ impl Glyph {
         self.shape_handle.get().unwrap()
     }
 
+    /// Render the glyph with a transform relative to the top of the transform
+    /// stack, at a given font height.
+    ///
+    /// Glyphs rendered with the advanced text rendering engine approximate
+    /// the hinting of Flash Player: they are placed on the pixel grid as their
+    /// grid fitting asks, and drawn bolder or lighter, and with sharper or
+    /// softer edges, as their thickness and sharpness ask.
+    pub fn render(
+        &self,
+        context: &mut RenderContext<'_, '_>,
+        transform: &Transform,
+        settings: &TextRenderSettings,
+        height: Twips,
+    ) {
+        let stack_matrix = context.transform_stack.transform().matrix;
+        context.transform_stack.push(transform);
+        let transform = context.transform_stack.transform();
+        context.transform_stack.pop();
+        let handle = self.shape_handle(context.renderer);
+
+        for transform in hinted_transforms(transform, &stack_matrix, settings, height) {
+            context.commands.render_shape(handle, transform);
+        }
+    }
+
+    /// The UTF-16 code unit that the glyph stands for.
+    pub fn character(&self) -> u16 {
+        self.swf_glyph.code
+    }
+
     pub fn as_shape(&self) -> Ref<'_, swf::Shape> {
         let mut write = self.shape.borrow_mut();
         if write.is_none() {
@@ -503,6 +1205,142 @@ impl FontDescriptor {
     }
 }
 
+/// The fonts installed on the system that device text in other fonts is drawn with, such as
+/// `Noto Sans` for `_sans`, by the name of the font the movie asks for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FontMap(HashMap<String, String>);
+
+impl FontMap {
+    pub fn new(fonts: HashMap<String, String>) -> Self {
+        Self(fonts)
+    }
+
+    /// Returns the name of the font that device text in the font named `name` is drawn with.
+    ///
+    /// Font names are compared case-insensitively.
+    pub fn substitute<'a>(&'a self, name: &'a str) -> &'a str {
+        self.0
+            .iter()
+            .find(|(from, _)| from.eq_ignore_ascii_case(name))
+            .map_or(name, |(_, to)| to.as_str())
+    }
+}
+
+/// The scripts, or writing systems, whose characters device text falls back to fonts of their
+/// own for.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FontScript {
+    /// Hiragana and katakana.
+    #[serde(rename = "japanese")]
+    Japanese,
+
+    /// Hangul.
+    #[serde(rename = "korean")]
+    Korean,
+
+    /// Chinese characters, which are also used in Japanese and Korean, and CJK punctuation.
+    #[serde(rename = "han")]
+    Han,
+
+    /// Emoji and other pictographic symbols.
+    #[serde(rename = "emoji")]
+    Emoji,
+
+    /// All other characters.
+    #[serde(rename = "other")]
+    Other,
+}
+
+impl FontScript {
+    /// Returns the script of a character.
+    pub fn of(c: char) -> Self {
+        match u32::from(c) {
+            0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF65..=0xFF9F => Self::Japanese,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xA960..=0xA97F | 0xAC00..=0xD7FF => Self::Korean,
+            0x2E80..=0x2FDF
+            | 0x3000..=0x303F
+            | 0x3190..=0x31EF
+            | 0x3200..=0x9FFF
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF64
+            | 0x20000..=0x3FFFF => Self::Han,
+            0x2190..=0x21FF
+            | 0x2300..=0x23FF
+            | 0x2460..=0x27BF
+            | 0x2B00..=0x2BFF
+            | 0x1F000..=0x1FAFF => Self::Emoji,
+            _ => Self::Other,
+        }
+    }
+
+    /// The fonts that device text falls back to for characters of this script, unless
+    /// configured otherwise.
+    ///
+    /// Color emoji fonts that only contain bitmaps can't be drawn, so they're left out.
+    fn default_fallbacks(self) -> &'static [&'static str] {
+        match self {
+            Self::Japanese => &[
+                "Noto Sans CJK JP",
+                "Yu Gothic",
+                "MS Gothic",
+                "Hiragino Sans",
+                "Hiragino Kaku Gothic ProN",
+                "IPAGothic",
+            ],
+            Self::Korean => &[
+                "Noto Sans CJK KR",
+                "Malgun Gothic",
+                "Gulim",
+                "Apple SD Gothic Neo",
+            ],
+            Self::Han => &[
+                "Noto Sans CJK SC",
+                "Microsoft YaHei",
+                "SimSun",
+                "PingFang SC",
+                "Noto Sans CJK JP",
+                "MS Gothic",
+            ],
+            Self::Emoji => &["Segoe UI Emoji", "Segoe UI Symbol", "Noto Emoji", "Symbola"],
+            Self::Other => &[
+                "Noto Sans",
+                "Arial Unicode MS",
+                "Segoe UI Symbol",
+                "DejaVu Sans",
+            ],
+        }
+    }
+}
+
+/// The fonts installed on the system that device text falls back to, in order, for the
+/// characters of each script that its font has no glyphs for.
+///
+/// Scripts without a chain of their own use a default chain of common system fonts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FontFallbacks(HashMap<FontScript, Vec<String>>);
+
+impl FontFallbacks {
+    pub fn new(fallbacks: HashMap<FontScript, Vec<String>>) -> Self {
+        Self(fallbacks)
+    }
+
+    /// Returns the names of the fonts that characters of a script fall back to, in order.
+    pub fn chain(&self, script: FontScript) -> Vec<String> {
+        match self.0.get(&script) {
+            Some(chain) => chain.clone(),
+            None => script
+                .default_fallbacks()
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+}
+
 /// The text rendering engine that a text field should use.
 /// This is controlled by the "Anti-alias" setting in the Flash IDE.
 /// Using "Anti-alias for readibility" switches to the "Advanced" text
@@ -525,8 +1363,8 @@ pub enum TextRenderSettings {
     /// This text should render with the advanced rendering engine.
     /// Set via "Anti-alias for readibility" in the Flash IDE.
     /// The parameters are set via the CSMTextSettings SWF tag.
-    /// Ruffle does not support this currently, but this also affects
-    /// hit-testing behavior.
+    /// Ruffle approximates the hinting of this engine when rendering glyphs
+    /// (see `Glyph::render`). This also affects hit-testing behavior.
     Advanced {
         grid_fit: TextGridFit,
         thickness: f32,
@@ -686,12 +1524,18 @@ impl Default for TextRenderSettings {
 
 #[cfg(test)]
 mod tests {
-    use crate::font::{EvalParameters, Font};
+    use crate::font::{
+        hinted_transforms, EvalParameters, Font, FontDescriptor, FontFallbacks, FontMap,
+        FontScript, TextGridFit, TextRenderSettings,
+    };
     use crate::player::Player;
-    use crate::string::WStr;
+    use crate::string::{WStr, WString};
     use gc_arena::{rootless_arena, MutationContext};
     use ruffle_render::backend::{null::NullRenderer, ViewportDimensions};
-    use swf::Twips;
+    use ruffle_render::matrix::Matrix;
+    use ruffle_render::transform::Transform;
+    use std::collections::HashMap;
+    use swf::{Fixed8, ShapeRecord, StyleChangeData, SwfStr, Twips, WINDOWS_1252};
 
     fn with_device_font<F>(callback: F)
     where
@@ -709,6 +1553,244 @@ mod tests {
         })
     }
 
+    #[test]
+    fn evaluate_right_to_left() {
+        with_device_font(|_mc, df| {
+            let text = WStr::from_units(b"abc");
+            // Without kerning, which depends on the order of the characters.
+            let params = EvalParameters::from_parts(
+                Twips::from_pixels(12.0),
+                Twips::from_pixels(0.0),
+                false,
+            );
+            let glyphs = |params: EvalParameters| {
+                let mut glyphs = vec![];
+                df.evaluate(
+                    text,
+                    Default::default(),
+                    params,
+                    |pos, transform, _glyph, advance, x| {
+                        assert_eq!(transform.matrix.tx, x);
+                        glyphs.push((pos, x, advance));
+                    },
+                );
+                glyphs
+            };
+
+            let ltr = glyphs(params);
+            let rtl = glyphs(params.with_right_to_left(true));
+            assert_eq!(
+                ltr.iter().map(|(pos, _, _)| *pos).collect::<Vec<_>>(),
+                [0, 1, 2]
+            );
+
+            // The last character is drawn first, at the left.
+            assert_eq!(
+                rtl.iter().map(|(pos, _, _)| *pos).collect::<Vec<_>>(),
+                [2, 1, 0]
+            );
+            assert_eq!(rtl[0].1, Twips::ZERO);
+            assert_eq!(rtl[1].1, rtl[0].2);
+            assert_eq!(rtl[2].1, rtl[0].2 + rtl[1].2);
+            assert_eq!(
+                df.measure(text, params, false),
+                df.measure(text, params.with_right_to_left(true), false)
+            );
+        });
+    }
+
+    /// Evaluates text with the font in `shaping-test-font.ttf`, returning the position and the
+    /// advance of each glyph.
+    ///
+    /// The font has 1024 units per EM, so at a height of 20480 twips, each unit is 20 twips.
+    fn shape_with_test_font(text: &str, is_rtl: bool) -> Vec<(usize, Twips)> {
+        rootless_arena(|mc| {
+            let font = Font::from_font_file(
+                mc,
+                FontDescriptor::from_parts("Shaping Test", false, false),
+                include_bytes!("../assets/shaping-test-font.ttf").to_vec(),
+                0,
+            )
+            .unwrap();
+            let params = EvalParameters::from_parts(Twips::new(20480), Twips::ZERO, true)
+                .with_right_to_left(is_rtl);
+            let mut glyphs = vec![];
+            font.evaluate(
+                &WString::from_utf8(text),
+                Default::default(),
+                params,
+                |pos, _transform, _glyph, advance, _x| glyphs.push((pos, advance)),
+            );
+            glyphs
+        })
+    }
+
+    #[test]
+    fn shape_arabic_joining_forms() {
+        // Beh has a different glyph at the start, in the middle and at the end of a word.
+        assert_eq!(
+            shape_with_test_font("\u{628}\u{628}\u{628}", true),
+            [
+                (2, Twips::new(630 * 20)),
+                (1, Twips::new(620 * 20)),
+                (0, Twips::new(610 * 20)),
+            ]
+        );
+        assert_eq!(
+            shape_with_test_font("\u{628}\u{627}", true),
+            [(1, Twips::new(310 * 20)), (0, Twips::new(610 * 20))]
+        );
+        assert_eq!(
+            shape_with_test_font("\u{628}", true),
+            [(0, Twips::new(600 * 20))]
+        );
+    }
+
+    #[test]
+    fn shape_combining_marks() {
+        // The mark doesn't break the joining of the letters around it, and is drawn in the
+        // cluster of the letter it's on without advancing.
+        assert_eq!(
+            shape_with_test_font("\u{628}\u{64e}\u{628}", true),
+            [
+                (2, Twips::new(630 * 20)),
+                (0, Twips::ZERO),
+                (0, Twips::new(610 * 20)),
+            ]
+        );
+        assert_eq!(
+            shape_with_test_font("e\u{301}", false),
+            [(0, Twips::new(500 * 20)), (0, Twips::ZERO)]
+        );
+    }
+
+    #[test]
+    fn shape_missing_glyphs() {
+        // The font has no glyph for 'x'.
+        assert_eq!(
+            shape_with_test_font("xe", false),
+            [(1, Twips::new(500 * 20))]
+        );
+    }
+
+    fn advanced(grid_fit: TextGridFit, thickness: f32, sharpness: f32) -> TextRenderSettings {
+        TextRenderSettings::Advanced {
+            grid_fit,
+            thickness,
+            sharpness,
+        }
+    }
+
+    /// The positions and alpha multipliers that a glyph at (1234, 1251) is drawn with.
+    fn hint(
+        stack_matrix: Matrix,
+        settings: TextRenderSettings,
+        height: f64,
+    ) -> Vec<(Twips, Twips, Fixed8)> {
+        let transform = Transform {
+            matrix: Matrix::translate(Twips::new(1234), Twips::new(1251)),
+            ..Default::default()
+        };
+        hinted_transforms(
+            transform,
+            &stack_matrix,
+            &settings,
+            Twips::from_pixels(height),
+        )
+        .into_iter()
+        .map(|t| (t.matrix.tx, t.matrix.ty, t.color_transform.a_mult))
+        .collect()
+    }
+
+    #[test]
+    fn hint_grid_fit() {
+        let unchanged = [(Twips::new(1234), Twips::new(1251), Fixed8::ONE)];
+        let normal = TextRenderSettings::Normal {
+            grid_fit: TextGridFit::Pixel,
+            thickness: 0.0,
+            sharpness: 0.0,
+        };
+        assert_eq!(hint(Matrix::IDENTITY, normal, 12.0), unchanged);
+        assert_eq!(
+            hint(
+                Matrix::IDENTITY,
+                advanced(TextGridFit::None, 0.0, 0.0),
+                12.0
+            ),
+            unchanged
+        );
+
+        // Glyphs are moved to the nearest pixel, or horizontally to the nearest third of a pixel.
+        assert_eq!(
+            hint(
+                Matrix::IDENTITY,
+                advanced(TextGridFit::Pixel, 0.0, 0.0),
+                12.0
+            ),
+            [(Twips::new(1240), Twips::new(1260), Fixed8::ONE)]
+        );
+        assert_eq!(
+            hint(
+                Matrix::IDENTITY,
+                advanced(TextGridFit::SubPixel, 0.0, 0.0),
+                12.0
+            ),
+            [(Twips::new(1233), Twips::new(1260), Fixed8::ONE)]
+        );
+    }
+
+    #[test]
+    fn hint_thickness() {
+        let settings = advanced(TextGridFit::None, 200.0, 0.0);
+
+        // At half the largest hinted size, glyphs are drawn again a quarter of a pixel across.
+        assert_eq!(
+            hint(Matrix::IDENTITY, settings.clone(), 24.0),
+            [
+                (Twips::new(1234), Twips::new(1251), Fixed8::ONE),
+                (Twips::new(1239), Twips::new(1251), Fixed8::ONE),
+            ]
+        );
+
+        // Large text isn't hinted, including text that's scaled up.
+        assert_eq!(hint(Matrix::IDENTITY, settings.clone(), 48.0).len(), 1);
+        assert_eq!(hint(Matrix::scale(2.0, 2.0), settings, 24.0).len(), 1);
+
+        // Thinner glyphs are drawn more transparently.
+        assert_eq!(
+            hint(
+                Matrix::IDENTITY,
+                advanced(TextGridFit::None, -200.0, 0.0),
+                24.0
+            ),
+            [(Twips::new(1234), Twips::new(1251), Fixed8::from_f32(0.75))]
+        );
+    }
+
+    #[test]
+    fn hint_sharpness() {
+        // Sharper glyphs are drawn again on top, softer glyphs more transparently.
+        assert_eq!(
+            hint(
+                Matrix::IDENTITY,
+                advanced(TextGridFit::None, 0.0, 400.0),
+                24.0
+            ),
+            [
+                (Twips::new(1234), Twips::new(1251), Fixed8::ONE),
+                (Twips::new(1234), Twips::new(1251), Fixed8::from_f32(0.5)),
+            ]
+        );
+        assert_eq!(
+            hint(
+                Matrix::IDENTITY,
+                advanced(TextGridFit::None, 0.0, -400.0),
+                24.0
+            ),
+            [(Twips::new(1234), Twips::new(1251), Fixed8::from_f32(0.75))]
+        );
+    }
+
     #[test]
     fn wrap_line_no_breakpoint() {
         with_device_font(|_mc, df| {
@@ -866,4 +1948,104 @@ mod tests {
             assert_eq!(None, breakpoint5);
         });
     }
+
+    #[test]
+    fn define_font_info_synthesizes_metrics() {
+        rootless_arena(|mc| {
+            let mut renderer = NullRenderer::new(ViewportDimensions {
+                width: 0,
+                height: 0,
+                scale_factor: 1.0,
+            });
+            let edge = |x: i32, y: i32| ShapeRecord::StraightEdge {
+                delta_x: Twips::new(x),
+                delta_y: Twips::new(y),
+            };
+            // A 500x700 square sitting on the baseline, and an empty glyph.
+            let square = vec![
+                ShapeRecord::StyleChange(Box::new(StyleChangeData {
+                    move_to: Some((Twips::new(100), Twips::new(-700))),
+                    fill_style_0: None,
+                    fill_style_1: Some(1),
+                    line_style: None,
+                    new_styles: None,
+                })),
+                edge(500, 0),
+                edge(0, 700),
+                edge(-500, 0),
+                edge(0, -700),
+            ];
+            let glyphs = [square, vec![]]
+                .into_iter()
+                .map(|shape_records| swf::Glyph {
+                    shape_records,
+                    code: 0,
+                    advance: 0,
+                    bounds: None,
+                })
+                .collect();
+            let font = Font::from_swf_tag(
+                mc,
+                &mut renderer,
+                swf::Font {
+                    id: 1,
+                    version: 0,
+                    name: "".into(),
+                    glyphs,
+                    language: swf::Language::Unknown,
+                    layout: None,
+                    flags: swf::FontFlag::empty(),
+                },
+                WINDOWS_1252,
+            );
+            let font = font.with_font_info(
+                mc,
+                &swf::FontInfo {
+                    id: 1,
+                    version: 1,
+                    name: SwfStr::from_utf8_str("Legacy"),
+                    flags: swf::FontInfoFlag::IS_BOLD,
+                    language: swf::Language::Unknown,
+                    code_table: vec![b'A'.into(), b' '.into()],
+                },
+                WINDOWS_1252,
+            );
+
+            assert_eq!(font.descriptor().class(), "Legacy");
+            assert!(font.descriptor().bold());
+            assert_eq!(font.get_glyph_for_char('A').unwrap().advance, 664);
+            assert_eq!(font.get_glyph_for_char(' ').unwrap().advance, 256);
+            assert_eq!(
+                font.get_baseline_for_height(Twips::new(1024)),
+                Twips::new(700)
+            );
+        });
+    }
+
+    #[test]
+    fn font_map_substitute() {
+        let font_map = FontMap::new(HashMap::from([
+            ("_sans".to_string(), "Noto Sans".to_string()),
+            ("MS Gothic".to_string(), "Noto Sans CJK JP".to_string()),
+        ]));
+        assert_eq!(font_map.substitute("_sans"), "Noto Sans");
+        assert_eq!(font_map.substitute("ms gothic"), "Noto Sans CJK JP");
+        assert_eq!(font_map.substitute("Arial"), "Arial");
+    }
+
+    #[test]
+    fn font_fallbacks_chain() {
+        assert_eq!(FontScript::of('a'), FontScript::Other);
+        assert_eq!(FontScript::of('あ'), FontScript::Japanese);
+        assert_eq!(FontScript::of('한'), FontScript::Korean);
+        assert_eq!(FontScript::of('漢'), FontScript::Han);
+        assert_eq!(FontScript::of('😀'), FontScript::Emoji);
+
+        let fallbacks = FontFallbacks::new(HashMap::from([(
+            FontScript::Japanese,
+            vec!["IPAexGothic".to_string()],
+        )]));
+        assert_eq!(fallbacks.chain(FontScript::Japanese), ["IPAexGothic"]);
+        assert_eq!(fallbacks.chain(FontScript::Korean)[0], "Noto Sans CJK KR");
+    }
 }
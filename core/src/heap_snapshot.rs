@@ -0,0 +1,683 @@
+//! Snapshots of the objects reachable by ActionScript, to find out what keeps
+//! memory alive in movies that run for a long time.
+//!
+//! A snapshot records every display object, AVM1 object and AVM2 object
+//! reachable from the roots of the player, with the references between them.
+//! It can be saved as JSON and analyzed later with `HeapSnapshot::report`,
+//! which lists the most common types and the objects retaining the most
+//! memory, with a path from a root to each of them.
+//!
+//! The walk only follows the references that ActionScript can create, such as
+//! properties, prototypes, closures and event listeners, so the sizes are
+//! estimates rather than the exact memory use of the GC arena.
+
+use crate::avm1::{Object as Avm1Object, TObject as Avm1TObject, Value as Avm1Value};
+use crate::avm2::object::TObject as Avm2TObject;
+use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject, TDisplayObjectContainer};
+use crate::timer::TimerCallback;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+/// The estimated size of an object without its properties, in bytes.
+const OBJECT_SIZE: usize = 64;
+
+/// The estimated size of a display object without its properties, in bytes.
+const DISPLAY_OBJECT_SIZE: usize = 512;
+
+/// The estimated size of each reference held by an object, in bytes.
+const REFERENCE_SIZE: usize = 32;
+
+/// The objects reachable from the roots of a player, and the references
+/// between them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapSnapshot {
+    pub roots: Vec<HeapRoot>,
+    pub nodes: Vec<HeapNode>,
+}
+
+/// A reference from outside of the object graph, such as the stage or the
+/// AVM1 `_global` object.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapRoot {
+    pub name: String,
+    pub node: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HeapNodeKind {
+    DisplayObject,
+    Avm1Object,
+    Avm2Object,
+}
+
+/// A single object of a heap snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapNode {
+    pub kind: HeapNodeKind,
+
+    /// The class or type of the object, such as `flash.display::Sprite`.
+    pub type_name: String,
+
+    /// The estimated size of the object itself, in bytes.
+    pub size: usize,
+
+    pub edges: Vec<HeapEdge>,
+}
+
+/// A reference from one object to another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapEdge {
+    /// The property or relation the object is referenced by, such as `_x` or
+    /// `[proto]`.
+    pub name: String,
+    pub to: usize,
+}
+
+/// The objects of a single type in a heap snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeSummary {
+    pub kind: HeapNodeKind,
+    pub type_name: String,
+    pub count: usize,
+
+    /// The estimated size of all objects of this type, in bytes.
+    pub size: usize,
+}
+
+impl HeapSnapshot {
+    /// Records the objects currently reachable in the player.
+    pub fn take(context: &mut UpdateContext<'_, '_>) -> Self {
+        let mut builder = SnapshotBuilder::default();
+
+        let stage = context.stage;
+        builder.add_root("stage", Reference::Display(stage.into()));
+        builder.add_root("_global", Reference::Avm1(context.avm1.global_object()));
+
+        if context.is_action_script_3() {
+            let movie = stage.root_clip().movie();
+            if let Some(library) = context.library.library_for_movie(movie) {
+                for script in library.avm2_domain().defining_scripts() {
+                    let (_, globals, _) = script.init();
+                    builder.add_root("[AVM2 globals]", Reference::Avm2(globals));
+                }
+            }
+        }
+
+        for callback in context.timers.callbacks() {
+            match callback {
+                TimerCallback::Avm1Function { func, .. } => {
+                    builder.add_root("[timer]", Reference::Avm1(*func))
+                }
+                TimerCallback::Avm1Method { this, .. } => {
+                    builder.add_root("[timer]", Reference::Avm1(*this))
+                }
+                TimerCallback::Avm2Callback { closure, .. } => {
+                    builder.add_root("[timer]", Reference::Avm2(*closure))
+                }
+            }
+        }
+
+        while let Some((id, reference)) = builder.queue.pop_front() {
+            let node = match reference {
+                Reference::Display(object) => builder.display_object_node(object),
+                Reference::Avm1(object) => builder.avm1_node(object),
+                Reference::Avm2(object) => builder.avm2_node(object, context.gc_context),
+            };
+            builder.snapshot.nodes[id] = node;
+        }
+        builder.snapshot
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// The number and size of the objects of every type, largest first.
+    pub fn type_summaries(&self) -> Vec<TypeSummary> {
+        let mut summaries: HashMap<(HeapNodeKind, &str), TypeSummary> = HashMap::new();
+        for node in &self.nodes {
+            let summary = summaries
+                .entry((node.kind, node.type_name.as_str()))
+                .or_insert_with(|| TypeSummary {
+                    kind: node.kind,
+                    type_name: node.type_name.clone(),
+                    count: 0,
+                    size: 0,
+                });
+            summary.count += 1;
+            summary.size += node.size;
+        }
+
+        let mut summaries: Vec<_> = summaries.into_values().collect();
+        summaries.sort_by(|a, b| {
+            b.size
+                .cmp(&a.size)
+                .then_with(|| a.type_name.cmp(&b.type_name))
+        });
+        summaries
+    }
+
+    /// The estimated memory kept alive by every node, in bytes.
+    ///
+    /// This is the size of the node and of every node that can only be
+    /// reached from the roots through it, and would be freed along with it.
+    pub fn retained_sizes(&self) -> Vec<usize> {
+        let graph = Graph::new(self);
+        let dominators = graph.immediate_dominators();
+
+        let mut retained: Vec<_> = self.nodes.iter().map(|node| node.size).collect();
+        retained.push(0);
+        // Children come before their dominators in postorder.
+        for &node in &graph.postorder {
+            if let Some(dominator) = dominators[node] {
+                if dominator != node {
+                    retained[dominator] += retained[node];
+                }
+            }
+        }
+        retained.truncate(self.nodes.len());
+        retained
+    }
+
+    /// The shortest path from a root to `node`, as the names of the root and
+    /// the references followed.
+    ///
+    /// Returns `None` if `node` can't be reached from any root.
+    pub fn path_to(&self, node: usize) -> Option<Vec<String>> {
+        // The node each node was first reached from, and the name of the reference.
+        let mut reached_from: Vec<Option<(Option<usize>, &str)>> = vec![None; self.nodes.len()];
+        let mut queue = VecDeque::new();
+        for root in &self.roots {
+            if reached_from[root.node].is_none() {
+                reached_from[root.node] = Some((None, &root.name));
+                queue.push_back(root.node);
+            }
+        }
+        while let Some(current) = queue.pop_front() {
+            if current == node {
+                break;
+            }
+            for edge in &self.nodes[current].edges {
+                if reached_from[edge.to].is_none() {
+                    reached_from[edge.to] = Some((Some(current), &edge.name));
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut current = node;
+        loop {
+            let (from, name) = (*reached_from.get(current)?)?;
+            path.push(name.to_string());
+            match from {
+                Some(from) => current = from,
+                None => break,
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// A readable summary of this snapshot, listing the `limit` largest types
+    /// and the `limit` objects retaining the most memory.
+    pub fn report(&self, limit: usize) -> String {
+        let total_size: usize = self.nodes.iter().map(|node| node.size).sum();
+        let mut report = format!(
+            "{} objects, {} bytes (estimated)\n\nLargest types:\n",
+            self.nodes.len(),
+            total_size
+        );
+        for summary in self.type_summaries().iter().take(limit) {
+            let _ = writeln!(
+                report,
+                "  {:>10} bytes {:>8} x {} ({:?})",
+                summary.size, summary.count, summary.type_name, summary.kind
+            );
+        }
+
+        let retained = self.retained_sizes();
+        let mut largest: Vec<_> = (0..self.nodes.len()).collect();
+        largest.sort_by(|a, b| retained[*b].cmp(&retained[*a]).then(a.cmp(b)));
+        report.push_str("\nLargest retainers:\n");
+        for node in largest.into_iter().take(limit) {
+            let path = self
+                .path_to(node)
+                .map_or_else(|| "<unreachable>".to_string(), |path| path.join("."));
+            let _ = writeln!(
+                report,
+                "  {:>10} bytes #{} {} at {}",
+                retained[node], node, self.nodes[node].type_name, path
+            );
+        }
+        report
+    }
+}
+
+/// An object found while walking the object graph.
+#[derive(Clone, Copy)]
+enum Reference<'gc> {
+    Display(DisplayObject<'gc>),
+    Avm1(Avm1Object<'gc>),
+    Avm2(Avm2Object<'gc>),
+}
+
+impl<'gc> Reference<'gc> {
+    fn as_ptr(self) -> *const () {
+        match self {
+            Reference::Display(object) => object.as_ptr() as *const (),
+            Reference::Avm1(object) => object.as_ptr() as *const (),
+            Reference::Avm2(object) => object.as_ptr() as *const (),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SnapshotBuilder<'gc> {
+    snapshot: HeapSnapshot,
+
+    /// The node of every object found so far.
+    ids: HashMap<*const (), usize>,
+
+    /// The objects found whose references weren't followed yet.
+    queue: VecDeque<(usize, Reference<'gc>)>,
+}
+
+impl<'gc> SnapshotBuilder<'gc> {
+    /// The node of `reference`, which is added if it wasn't found before.
+    fn node(&mut self, reference: Reference<'gc>) -> usize {
+        let next_id = self.snapshot.nodes.len();
+        let id = *self.ids.entry(reference.as_ptr()).or_insert(next_id);
+        if id == next_id {
+            // Filled in once the references of the object are followed.
+            self.snapshot.nodes.push(HeapNode {
+                kind: HeapNodeKind::DisplayObject,
+                type_name: String::new(),
+                size: 0,
+                edges: Vec::new(),
+            });
+            self.queue.push_back((id, reference));
+        }
+        id
+    }
+
+    fn add_root(&mut self, name: &str, reference: Reference<'gc>) {
+        let node = self.node(reference);
+        let roots = &mut self.snapshot.roots;
+        if !roots
+            .iter()
+            .any(|root| root.node == node && root.name == name)
+        {
+            roots.push(HeapRoot {
+                name: name.to_string(),
+                node,
+            });
+        }
+    }
+
+    fn edge(&mut self, edges: &mut Vec<HeapEdge>, name: impl Into<String>, to: Reference<'gc>) {
+        let to = self.node(to);
+        edges.push(HeapEdge {
+            name: name.into(),
+            to,
+        });
+    }
+
+    fn display_object_node(&mut self, object: DisplayObject<'gc>) -> HeapNode {
+        let mut edges = Vec::new();
+        if let Some(container) = object.as_container() {
+            for child in container.iter_render_list() {
+                let name = child.name();
+                let name = if name.is_empty() {
+                    format!("[child {}]", child.depth())
+                } else {
+                    name.to_string()
+                };
+                self.edge(&mut edges, name, Reference::Display(child));
+            }
+        }
+        if let Avm1Value::Object(avm1_object) = object.object() {
+            self.edge(&mut edges, "[AVM1 object]", Reference::Avm1(avm1_object));
+        }
+        if let Avm2Value::Object(avm2_object) = object.object2() {
+            self.edge(&mut edges, "[AVM2 object]", Reference::Avm2(avm2_object));
+        }
+
+        HeapNode {
+            kind: HeapNodeKind::DisplayObject,
+            type_name: variant_name(&object),
+            size: DISPLAY_OBJECT_SIZE + REFERENCE_SIZE * edges.len(),
+            edges,
+        }
+    }
+
+    fn avm1_node(&mut self, object: Avm1Object<'gc>) -> HeapNode {
+        let mut edges = Vec::new();
+        // This includes the prototype, which is stored as `__proto__`.
+        for (name, value) in object.raw_script_object().stored_objects() {
+            self.edge(&mut edges, name.to_string(), Reference::Avm1(value));
+        }
+        if let Some(display_object) = object.as_display_object() {
+            self.edge(
+                &mut edges,
+                "[display object]",
+                Reference::Display(display_object),
+            );
+        }
+
+        let executable = object.as_executable();
+        let mut scope = executable
+            .as_ref()
+            .and_then(|executable| executable.scope());
+        while let Some(current) = scope {
+            let locals = *current.locals();
+            self.edge(&mut edges, "[closure]", Reference::Avm1(locals));
+            scope = current.parent();
+        }
+
+        let type_name = if executable.is_some() {
+            "Function".to_string()
+        } else {
+            variant_name(&object)
+        };
+        HeapNode {
+            kind: HeapNodeKind::Avm1Object,
+            type_name,
+            size: OBJECT_SIZE + REFERENCE_SIZE * edges.len(),
+            edges,
+        }
+    }
+
+    fn avm2_node(
+        &mut self,
+        object: Avm2Object<'gc>,
+        gc_context: gc_arena::MutationContext<'gc, '_>,
+    ) -> HeapNode {
+        let mut edges = Vec::new();
+        let stored_objects = object.base().stored_objects();
+        for (name, value) in stored_objects {
+            self.edge(&mut edges, name, Reference::Avm2(value));
+        }
+        if let Some(proto) = object.proto() {
+            self.edge(&mut edges, "[proto]", Reference::Avm2(proto));
+        }
+        if let Some(class) = object.instance_of() {
+            self.edge(&mut edges, "[class]", Reference::Avm2(class.into()));
+        }
+        if let Some(class) = object.as_class_object() {
+            self.edge(
+                &mut edges,
+                "[prototype]",
+                Reference::Avm2(class.prototype()),
+            );
+        }
+        if let Some(display_object) = object.as_display_object() {
+            self.edge(
+                &mut edges,
+                "[display object]",
+                Reference::Display(display_object),
+            );
+        }
+
+        let mut values = Vec::new();
+        if let Some(array) = object.as_array_storage() {
+            values.extend(
+                array
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, value)| Some((i, value?))),
+            );
+        }
+        if let Some(vector) = object.as_vector_storage() {
+            values.extend(vector.iter().enumerate());
+        }
+        for (index, value) in values {
+            if let Some(value) = value.as_object() {
+                self.edge(&mut edges, format!("[{index}]"), Reference::Avm2(value));
+            }
+        }
+        if let Some(dictionary) = object.as_dictionary_object() {
+            for (key, value) in dictionary.object_entries() {
+                self.edge(&mut edges, "[key]", Reference::Avm2(key));
+                if let Some(value) = value.as_object() {
+                    self.edge(&mut edges, "[value]", Reference::Avm2(value));
+                }
+            }
+        }
+        let listeners: Vec<_> = object
+            .as_dispatch()
+            .map(|dispatch| dispatch.iter_all_handlers().collect())
+            .unwrap_or_default();
+        for (event, handler) in listeners {
+            self.edge(
+                &mut edges,
+                format!("[{event} listener]"),
+                Reference::Avm2(handler),
+            );
+        }
+        let scope = object.as_executable().map(|executable| executable.scope());
+        if let Some(scope) = scope {
+            let mut index = 0;
+            while let Some(current) = scope.get(index) {
+                self.edge(&mut edges, "[closure]", Reference::Avm2(current.values()));
+                index += 1;
+            }
+        }
+
+        let mut size = OBJECT_SIZE + REFERENCE_SIZE * edges.len();
+        if let Some(bitmap_data) = object.as_bitmap_data_wrapper() {
+            size += 4 * bitmap_data.width() as usize * bitmap_data.height() as usize;
+        }
+        if let Some(bytes) = object.as_bytearray() {
+            size += bytes.len();
+        }
+        HeapNode {
+            kind: HeapNodeKind::Avm2Object,
+            type_name: object.instance_of_class_name(gc_context).to_string(),
+            size,
+            edges,
+        }
+    }
+}
+
+/// The name of the enum variant of an object, such as `MovieClip`.
+fn variant_name(object: &impl std::fmt::Debug) -> String {
+    let debug = format!("{object:?}");
+    match debug.find(|c: char| !c.is_alphanumeric() && c != '_') {
+        Some(end) => debug[..end].to_string(),
+        None => debug,
+    }
+}
+
+/// The nodes of a snapshot in a form suited to computing dominators, with a
+/// virtual node after the last one referencing all roots.
+struct Graph {
+    predecessors: Vec<Vec<usize>>,
+
+    /// The nodes reachable from the roots, in postorder.
+    postorder: Vec<usize>,
+
+    /// The position of every node in `postorder`.
+    postorder_index: Vec<Option<usize>>,
+}
+
+impl Graph {
+    fn new(snapshot: &HeapSnapshot) -> Self {
+        let root = snapshot.nodes.len();
+        let successors = |node: usize| -> Vec<usize> {
+            if node == root {
+                snapshot.roots.iter().map(|root| root.node).collect()
+            } else {
+                snapshot.nodes[node]
+                    .edges
+                    .iter()
+                    .map(|edge| edge.to)
+                    .collect()
+            }
+        };
+
+        let mut predecessors = vec![Vec::new(); root + 1];
+        let mut postorder = Vec::new();
+        let mut visited = vec![false; root + 1];
+        // An explicit stack, as object graphs are often too deep for recursion.
+        let mut stack = vec![(root, successors(root), 0)];
+        visited[root] = true;
+        while let Some((node, children, next)) = stack.last_mut() {
+            match children.get(*next) {
+                Some(&child) => {
+                    *next += 1;
+                    predecessors[child].push(*node);
+                    if !visited[child] {
+                        visited[child] = true;
+                        stack.push((child, successors(child), 0));
+                    }
+                }
+                None => {
+                    postorder.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+
+        let mut postorder_index = vec![None; root + 1];
+        for (index, node) in postorder.iter().enumerate() {
+            postorder_index[*node] = Some(index);
+        }
+        Self {
+            predecessors,
+            postorder,
+            postorder_index,
+        }
+    }
+
+    /// The immediate dominator of every reachable node, using the algorithm of
+    /// "A Simple, Fast Dominance Algorithm" by Cooper, Harvey and Kennedy.
+    ///
+    /// The virtual root is its own dominator.
+    fn immediate_dominators(&self) -> Vec<Option<usize>> {
+        let root = self.predecessors.len() - 1;
+        let mut dominators = vec![None; root + 1];
+        dominators[root] = Some(root);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &node in self.postorder.iter().rev().skip(1) {
+                let mut new_dominator = None;
+                for &predecessor in &self.predecessors[node] {
+                    if dominators[predecessor].is_none() {
+                        continue;
+                    }
+                    new_dominator = Some(match new_dominator {
+                        Some(current) => self.intersect(&dominators, current, predecessor),
+                        None => predecessor,
+                    });
+                }
+                if new_dominator.is_some() && dominators[node] != new_dominator {
+                    dominators[node] = new_dominator;
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    fn intersect(&self, dominators: &[Option<usize>], mut a: usize, mut b: usize) -> usize {
+        let index = |node: usize| self.postorder_index[node].unwrap_or_default();
+        while a != b {
+            while index(a) < index(b) {
+                a = dominators[a].unwrap_or(a);
+            }
+            while index(b) < index(a) {
+                b = dominators[b].unwrap_or(b);
+            }
+        }
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(type_name: &str, size: usize, edges: &[(&str, usize)]) -> HeapNode {
+        HeapNode {
+            kind: HeapNodeKind::Avm2Object,
+            type_name: type_name.to_string(),
+            size,
+            edges: edges
+                .iter()
+                .map(|(name, to)| HeapEdge {
+                    name: name.to_string(),
+                    to: *to,
+                })
+                .collect(),
+        }
+    }
+
+    /// A stage holding a cache of two entries sharing a bitmap, and a
+    /// listener whose closure references the first entry.
+    fn snapshot() -> HeapSnapshot {
+        HeapSnapshot {
+            roots: vec![HeapRoot {
+                name: "stage".to_string(),
+                node: 0,
+            }],
+            nodes: vec![
+                node("Stage", 10, &[("cache", 1), ("listener", 4)]),
+                node("Array", 20, &[("[0]", 2), ("[1]", 3)]),
+                node("Entry", 30, &[("bitmap", 5)]),
+                node("Entry", 30, &[("bitmap", 5)]),
+                node("Function", 5, &[("[closure]", 2)]),
+                node("BitmapData", 1000, &[]),
+                node("Orphan", 7, &[]),
+            ],
+        }
+    }
+
+    #[test]
+    fn retained_sizes() {
+        let retained = snapshot().retained_sizes();
+        assert_eq!(retained, vec![1095, 50, 30, 30, 5, 1000, 7]);
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let snapshot = snapshot();
+        assert_eq!(
+            snapshot.path_to(5),
+            Some(vec![
+                "stage".to_string(),
+                "cache".to_string(),
+                "[0]".to_string(),
+                "bitmap".to_string()
+            ])
+        );
+        assert_eq!(snapshot.path_to(6), None);
+    }
+
+    #[test]
+    fn type_summaries() {
+        let summaries = snapshot().type_summaries();
+        assert_eq!(summaries[0].type_name, "BitmapData");
+        let entries = summaries
+            .iter()
+            .find(|summary| summary.type_name == "Entry")
+            .unwrap();
+        assert_eq!((entries.count, entries.size), (2, 60));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let snapshot = snapshot();
+        let json = snapshot.to_json().unwrap();
+        assert_eq!(HeapSnapshot::from_json(&json).unwrap(), snapshot);
+    }
+}
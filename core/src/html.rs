@@ -9,7 +9,7 @@ pub use dimensions::BoxBounds;
 pub use dimensions::Position;
 pub use dimensions::Size;
 pub use layout::{LayoutBox, LayoutContent, LayoutMetrics};
-pub use text_format::{FormatSpans, TextFormat, TextSpan};
+pub use text_format::{FormatSpans, StyleSheet, TextFormat, TextImage, TextSpan};
 
 #[cfg(test)]
 mod test;
@@ -4,7 +4,7 @@ use crate::context::UpdateContext;
 use crate::drawing::Drawing;
 use crate::font::{EvalParameters, Font};
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::text_format::{FormatSpans, TextFormat, TextImage, TextSpan};
 use crate::string::{utils as string_utils, WStr};
 use crate::tag_utils::SwfMovie;
 use gc_arena::Collect;
@@ -12,6 +12,7 @@ use ruffle_render::shape_utils::DrawCommand;
 use std::cmp::{max, min};
 use std::sync::Arc;
 use swf::Twips;
+use unicode_bidi::{bidi_class, BidiClass, BidiInfo, Level};
 
 /// Draw an underline on a particular drawing.
 ///
@@ -33,6 +34,84 @@ fn draw_underline(drawing: &mut Drawing, starting_pos: Position<Twips>, width: T
     });
 }
 
+/// Resolve the embedding level of each code unit of `text` and the start and
+/// base level of each of its paragraphs, as per the Unicode Bidirectional
+/// Algorithm.
+///
+/// Both are empty if the text has no right-to-left characters.
+pub(crate) fn bidi_levels(text: &WStr) -> (Vec<Level>, Vec<(usize, Level)>) {
+    let utf8 = text.to_utf8_lossy();
+    let info = BidiInfo::new(&utf8, None);
+    if !info.has_rtl() {
+        return Default::default();
+    }
+
+    // The levels are given per byte of UTF-8, not per UTF-16 code unit.
+    let mut levels = Vec::with_capacity(text.len());
+    let mut paragraphs = Vec::with_capacity(info.paragraphs.len());
+    let mut next_paragraphs = info.paragraphs.iter().peekable();
+    let mut byte = 0;
+    for (pos, c) in text.char_indices() {
+        let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+        if let Some(paragraph) = next_paragraphs.next_if(|p| p.range.start <= byte) {
+            paragraphs.push((pos, paragraph.level));
+        }
+        levels.extend(std::iter::repeat(info.levels[byte]).take(c.len_utf16()));
+        byte += c.len_utf8();
+    }
+    (levels, paragraphs)
+}
+
+/// Split `text`, which starts at `start` in the text being laid out, into runs
+/// of characters drawn with the same font, returning the start of each run,
+/// its text and its font.
+///
+/// Characters that `font` has no glyphs for are drawn with the font that
+/// `fallback_font` gives for them. Whitespace stays in the run it's in, so
+/// that words aren't split at it, and so do combining marks and joiners, so
+/// that the clusters of characters that text is shaped in aren't split.
+pub(crate) fn split_font_runs<'t, 'gc>(
+    font: Font<'gc>,
+    start: usize,
+    text: &'t WStr,
+    mut fallback_font: impl FnMut(char) -> Option<Font<'gc>>,
+) -> Vec<(usize, &'t WStr, Font<'gc>)> {
+    let mut runs = vec![];
+    let mut run_start = 0;
+    let mut run_font = font;
+    let mut last_fallback: Option<Font<'gc>> = None;
+    for (index, c) in text.char_indices() {
+        let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+        let continues_cluster = matches!(bidi_class(c), BidiClass::NSM | BidiClass::BN);
+        let char_font = if continues_cluster || matches!(c, ' ' | '\n' | '\r' | '\t') {
+            run_font
+        } else if font.get_glyph_for_char(c).is_some() {
+            font
+        } else if let Some(fallback) =
+            last_fallback.filter(|fallback| fallback.get_glyph_for_char(c).is_some())
+        {
+            // Stay with the last fallback font, so that text in one script is drawn in one
+            // font even where the chain of another script would be used.
+            fallback
+        } else if let Some(fallback) = fallback_font(c) {
+            last_fallback = Some(fallback);
+            fallback
+        } else {
+            font
+        };
+
+        if !Font::ptr_eq(char_font, run_font) {
+            if index > run_start {
+                runs.push((start + run_start, &text[run_start..index], run_font));
+            }
+            run_start = index;
+            run_font = char_font;
+        }
+    }
+    runs.push((start + run_start, &text[run_start..], run_font));
+    runs
+}
+
 /// Contains information relating to the current layout operation.
 pub struct LayoutContext<'a, 'gc> {
     /// The movie this layout context is pulling fonts from.
@@ -51,6 +130,19 @@ pub struct LayoutContext<'a, 'gc> {
     /// The underlying bundle of text being formatted.
     text: &'a WStr,
 
+    /// The embedding level of each code unit of `text`, which tells the
+    /// direction it runs in.
+    ///
+    /// Empty if all of the text runs from left to right.
+    levels: Vec<Level>,
+
+    /// The start of each paragraph of `text` and the level of the direction
+    /// it runs in, empty if all of the text runs from left to right.
+    paragraphs: Vec<(usize, Level)>,
+
+    /// Whether the text being appended runs from right to left.
+    is_rtl: bool,
+
     /// The highest font size observed within the current line.
     max_font_size: Twips,
 
@@ -87,15 +179,29 @@ pub struct LayoutContext<'a, 'gc> {
 
     /// The total width of the text field being laid out.
     max_bounds: Twips,
+
+    /// The images floating beside the text, as the side they float at, the
+    /// width they take from the lines beside them, and the bottom of the
+    /// space they take up.
+    floats: Vec<(swf::TextAlign, Twips, Twips)>,
+
+    /// The layout boxes of the images, which are kept apart from the lines
+    /// until layout has finished.
+    image_boxes: Vec<LayoutBox<'gc>>,
 }
 
 impl<'a, 'gc> LayoutContext<'a, 'gc> {
     fn new(movie: Arc<SwfMovie>, max_bounds: Twips, text: &'a WStr) -> Self {
+        let (levels, paragraphs) = bidi_levels(text);
+
         Self {
             movie,
             cursor: Default::default(),
             font: None,
             text,
+            levels,
+            paragraphs,
+            is_rtl: false,
             max_font_size: Default::default(),
             boxes: Vec::new(),
             exterior_bounds: None,
@@ -104,6 +210,8 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             current_line: 0,
             current_line_span: Default::default(),
             max_bounds,
+            floats: Vec::new(),
+            image_boxes: Vec::new(),
         }
     }
 
@@ -128,15 +236,119 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     /// Determine the effective alignment mode for the current line of text.
     ///
     /// This function primarily exists to ensure all bulleted lists are
-    /// left-aligned, as no other alignment is respected otherwise.
+    /// left-aligned, as no other alignment is respected otherwise. Left
+    /// aligned lines of right-to-left paragraphs start at the right instead.
     fn effective_alignment(&self) -> swf::TextAlign {
         if self.current_line_span.bullet {
             swf::TextAlign::Left
+        } else if self.current_line_span.align == swf::TextAlign::Left
+            && self.is_right_to_left_line()
+        {
+            swf::TextAlign::Right
         } else {
             self.current_line_span.align
         }
     }
 
+    /// Get the embedding level of the code unit at `pos`.
+    fn level(&self, pos: usize) -> Level {
+        self.levels.get(pos).copied().unwrap_or_else(Level::ltr)
+    }
+
+    /// Determine whether the current line belongs to a paragraph that runs
+    /// from right to left.
+    fn is_right_to_left_line(&self) -> bool {
+        if self.paragraphs.is_empty() {
+            return false;
+        }
+
+        let line_start = self
+            .boxes
+            .get(self.current_line..)
+            .unwrap_or_default()
+            .iter()
+            .find_map(|linebox| match linebox.content {
+                LayoutContent::Text { start, .. } => Some(start),
+                _ => None,
+            });
+        line_start
+            .and_then(|line_start| {
+                self.paragraphs
+                    .iter()
+                    .rev()
+                    .find(|(start, _)| *start <= line_start)
+            })
+            .map_or(false, |(_, level)| level.is_rtl())
+    }
+
+    /// Split a run of text where its embedding level changes, returning the
+    /// start of each run in the text being laid out, its text and whether it
+    /// runs from right to left.
+    fn level_runs<'t>(&self, start: usize, text: &'t WStr) -> Vec<(usize, &'t WStr, bool)> {
+        if self.levels.is_empty() {
+            return vec![(start, text, false)];
+        }
+
+        let mut runs = vec![];
+        let mut run_start = 0;
+        for index in 1..text.len() {
+            if self.level(start + index) != self.level(start + run_start) {
+                runs.push((
+                    start + run_start,
+                    &text[run_start..index],
+                    self.level(start + run_start).is_rtl(),
+                ));
+                run_start = index;
+            }
+        }
+        runs.push((
+            start + run_start,
+            &text[run_start..],
+            self.level(start + run_start).is_rtl(),
+        ));
+        runs
+    }
+
+    /// Determine the order the boxes of the current line are displayed in
+    /// from left to right, as indices into the line.
+    ///
+    /// Runs of text at higher embedding levels are reversed, as per rule L2
+    /// of the Unicode Bidirectional Algorithm.
+    fn visual_order(&self) -> Vec<usize> {
+        let line = self.boxes.get(self.current_line..).unwrap_or_default();
+        let mut order: Vec<usize> = (0..line.len()).collect();
+        if self.levels.is_empty() {
+            return order;
+        }
+
+        let levels: Vec<u8> = line
+            .iter()
+            .map(|linebox| match linebox.content {
+                LayoutContent::Text { start, .. } => self.level(start).number(),
+                _ => 0,
+            })
+            .collect();
+        let highest_level = levels.iter().copied().max().unwrap_or_default();
+        let lowest_odd_level = levels
+            .iter()
+            .copied()
+            .filter(|level| level % 2 == 1)
+            .min()
+            .unwrap_or(highest_level + 1);
+        for level in (lowest_odd_level..=highest_level).rev() {
+            let mut index = 0;
+            while index < order.len() {
+                let run_start = index;
+                while index < order.len() && levels[order[index]] >= level {
+                    index += 1;
+                }
+                order[run_start..index].reverse();
+                index += 1;
+            }
+        }
+        order
+    }
+
     /// Construct an underline drawing for the current line of text and add it
     /// to the line.
     fn append_underlines(&mut self) {
@@ -152,6 +364,11 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         ));
 
         if let Some(linelist) = self.boxes.get(self.current_line..) {
+            // Right-to-left text may have been moved before the boxes logically
+            // preceding it.
+            let mut linelist: Vec<_> = linelist.iter().collect();
+            linelist.sort_by_key(|linebox| linebox.bounds().origin().x());
+
             for linebox in linelist {
                 if linebox.is_text_box() {
                     if let Some((_t, tf, font, params, _color)) =
@@ -233,14 +450,17 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             return;
         }
 
+        //Flash ignores trailing spaces when aligning lines, so should we
+        let trim_trailing_spaces =
+            self.current_line_span.align != swf::TextAlign::Left || self.is_right_to_left_line();
+
         let mut line_bounds = None;
         let mut box_count: i32 = 0;
         for linebox in self.boxes.get_mut(self.current_line..).unwrap() {
             let (text, _tf, font, params, _color) =
                 linebox.as_renderable_text(self.text).expect("text");
 
-            //Flash ignores trailing spaces when aligning lines, so should we
-            if self.current_line_span.align != swf::TextAlign::Left {
+            if trim_trailing_spaces {
                 linebox.bounds = linebox
                     .bounds
                     .with_size(font.measure(text.trim_end(), params, false).into());
@@ -257,9 +477,26 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
 
         let mut line_bounds = line_bounds.unwrap_or_default();
 
+        // Place the boxes next to each other in the order they are displayed
+        // in, if right-to-left text changes it.
+        let order = self.visual_order();
+        let mut visual_positions: Vec<usize> = (0..order.len()).collect();
+        if order.iter().enumerate().any(|(index, i)| index != *i) {
+            let mut x = line_bounds.origin().x();
+            for (position, &index) in order.iter().enumerate() {
+                visual_positions[index] = position;
+
+                let linebox = &mut self.boxes[self.current_line + index];
+                linebox.bounds += Position::from((x - linebox.bounds.origin().x(), Twips::ZERO));
+                x += linebox.bounds.width();
+            }
+        }
+
         let left_adjustment =
-            Self::left_alignment_offset(&self.current_line_span, self.is_first_line);
-        let right_adjustment = Twips::from_pixels(self.current_line_span.right_margin);
+            Self::left_alignment_offset(&self.current_line_span, self.is_first_line)
+                + self.float_width(swf::TextAlign::Left);
+        let right_adjustment = Twips::from_pixels(self.current_line_span.right_margin)
+            + self.float_width(swf::TextAlign::Right);
 
         let misalignment =
             self.max_bounds - left_adjustment - right_adjustment - line_bounds.width();
@@ -290,15 +527,24 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         }
 
         box_count = 0;
-        for linebox in self.boxes.get_mut(self.current_line..).unwrap() {
+        for (index, linebox) in self
+            .boxes
+            .get_mut(self.current_line..)
+            .unwrap()
+            .iter_mut()
+            .enumerate()
+        {
             // TODO: This attempts to keep text of multiple font sizes vertically
             // aligned correctly. It does not consider the baseline of the font,
             // which is information we don't have yet.
             let font_size_adjustment = self.max_font_size - linebox.bounds.height();
 
             if linebox.is_text_box() {
+                let visual_position = visual_positions.get(index).copied().unwrap_or(index);
                 linebox.bounds += Position::from((
-                    left_adjustment + align_adjustment + (interim_adjustment * box_count),
+                    left_adjustment
+                        + align_adjustment
+                        + (interim_adjustment * visual_position as i32),
                     font_size_adjustment,
                 ));
             } else if linebox.is_bullet() {
@@ -428,14 +674,15 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         is_device_font: bool,
     ) -> Option<Font<'gc>> {
         let library = context.library.library_for_movie_mut(self.movie.clone());
+        let font_name = span.font.to_utf8_lossy();
 
-        // If this text field is set to use device fonts, fallback to using our embedded Noto Sans.
+        // If this text field is set to use device fonts, fall back to a font installed on the
+        // system, or our embedded Noto Sans if there's none.
         // Note that the SWF can still contain a DefineFont tag with no glyphs/layout info in this case (see #451).
-        // In an ideal world, device fonts would search for a matching font on the system and render it in some way.
         if let Some(font) = library
-            .get_font_by_name(&span.font.to_utf8_lossy(), span.bold, span.italic)
+            .get_font_by_name(&font_name, span.bold, span.italic)
             .filter(|f| !is_device_font && f.has_glyphs())
-            .or_else(|| context.library.device_font())
+            .or_else(|| context.device_font(&font_name, span.bold, span.italic))
         {
             self.font = Some(font);
             return self.font;
@@ -471,10 +718,10 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     /// This function bypasses the text fragmentation necessary for justify to
     /// work and it should only be called internally.
     fn append_text_fragment(&mut self, text: &'a WStr, start: usize, end: usize, span: &TextSpan) {
-        let params = EvalParameters::from_span(span);
+        let params = EvalParameters::from_span(span).with_right_to_left(self.is_rtl);
         let text_size = Size::from(self.font.unwrap().measure(text, params, false));
         let text_bounds = BoxBounds::from_position_and_size(self.cursor, text_size);
-        let mut new_text = LayoutBox::from_text(start, end, self.font.unwrap(), span);
+        let mut new_text = LayoutBox::from_text(start, end, self.font.unwrap(), params, span);
 
         new_text.bounds = text_bounds;
 
@@ -489,11 +736,12 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     /// cursor is moved down.
     fn append_bullet(&mut self, context: &mut UpdateContext<'_, 'gc>, span: &TextSpan) {
         let library = context.library.library_for_movie_mut(self.movie.clone());
+        let font_name = span.font.to_utf8_lossy();
 
         if let Some(bullet_font) = library
-            .get_font_by_name(&span.font.to_utf8_lossy(), span.bold, span.italic)
+            .get_font_by_name(&font_name, span.bold, span.italic)
             .filter(|f| f.has_glyphs())
-            .or_else(|| context.library.device_font())
+            .or_else(|| context.device_font(&font_name, span.bold, span.italic))
             .or(self.font)
         {
             let mut bullet_cursor = self.cursor;
@@ -515,6 +763,49 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         }
     }
 
+    /// Float an image at the side of the text, starting at the current line.
+    ///
+    /// Images at the same side are placed next to each other, and the lines
+    /// beside them are narrowed until the text has passed their bottom.
+    fn append_image(&mut self, index: usize, image: &TextImage, size: Size<Twips>) {
+        let hspace = Twips::from_pixels(image.hspace);
+        let vspace = Twips::from_pixels(image.vspace);
+        let side = if image.align == swf::TextAlign::Right {
+            swf::TextAlign::Right
+        } else {
+            swf::TextAlign::Left
+        };
+
+        let inset = self.float_width(side);
+        let x = if side == swf::TextAlign::Right {
+            self.max_bounds - inset - size.width()
+        } else {
+            inset
+        };
+
+        let mut image_box = LayoutBox::from_image(index);
+        image_box.bounds =
+            BoxBounds::from_position_and_size(Position::from((x, self.cursor.y())), size);
+        self.image_boxes.push(image_box);
+
+        self.floats.push((
+            side,
+            size.width() + hspace,
+            self.cursor.y() + size.height() + vspace,
+        ));
+    }
+
+    /// Calculate how much of the current line is taken by the images floating
+    /// at one side of the text.
+    fn float_width(&self, side: swf::TextAlign) -> Twips {
+        self.floats
+            .iter()
+            .filter(|(float_side, _, bottom)| *float_side == side && *bottom > self.cursor.y())
+            .fold(Twips::ZERO, |width, (_, float_width, _)| {
+                width + *float_width
+            })
+    }
+
     /// Add a box to the current line of text.
     ///
     /// The box should have been positioned according to the current cursor
@@ -559,8 +850,11 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ///
     /// Offsets returned by this function should not be considered final;
     fn wrap_dimensions(&self, current_span: &TextSpan) -> (Twips, Twips) {
-        let width = self.max_bounds - Twips::from_pixels(self.current_line_span.right_margin);
-        let offset = Self::left_alignment_offset(current_span, self.is_first_line);
+        let width = self.max_bounds
+            - Twips::from_pixels(self.current_line_span.right_margin)
+            - self.float_width(swf::TextAlign::Right);
+        let offset = Self::left_alignment_offset(current_span, self.is_first_line)
+            + self.float_width(swf::TextAlign::Left);
 
         (width, offset + self.cursor.x())
     }
@@ -579,7 +873,13 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
                 .map(|ls| (fs.displayed_text(), fs.displayed_text().len(), ls)),
         );
 
-        (self.boxes, self.exterior_bounds.unwrap_or_default())
+        let mut exterior_bounds = self.exterior_bounds.unwrap_or_default();
+        for image_box in self.image_boxes.iter() {
+            exterior_bounds += image_box.bounds;
+        }
+        self.boxes.append(&mut self.image_boxes);
+
+        (self.boxes, exterior_bounds)
     }
 
     fn is_start_of_line(&self) -> bool {
@@ -659,13 +959,24 @@ pub enum LayoutContent<'gc> {
     /// layout box's bounds. The size of those bounds do not affect the
     /// rendering of the drawing.
     Drawing(Drawing),
+
+    /// A layout box containing an image placed with an `<img>` tag.
+    ///
+    /// The index refers to the images of the same `FormatSpans` that
+    /// generated this layout box. The image is scaled to the size of the
+    /// layout box's bounds by its owner.
+    Image(usize),
 }
 
 impl<'gc> LayoutBox<'gc> {
     /// Construct a text box for a text node.
-    pub fn from_text(start: usize, end: usize, font: Font<'gc>, span: &TextSpan) -> Self {
-        let params = EvalParameters::from_span(span);
-
+    pub fn from_text(
+        start: usize,
+        end: usize,
+        font: Font<'gc>,
+        params: EvalParameters,
+        span: &TextSpan,
+    ) -> Self {
         Self {
             bounds: Default::default(),
             content: LayoutContent::Text {
@@ -702,10 +1013,22 @@ impl<'gc> LayoutBox<'gc> {
         }
     }
 
+    /// Construct an image box.
+    pub fn from_image(index: usize) -> Self {
+        Self {
+            bounds: Default::default(),
+            content: LayoutContent::Image(index),
+        }
+    }
+
     /// Construct a new layout hierarchy from text spans.
     ///
     /// The returned bounds will include both the text bounds itself, as well
     /// as left and right margins on any of the lines.
+    ///
+    /// `image_sizes` holds the size of each image of the spans, which the
+    /// text is wrapped around.
+    #[allow(clippy::too_many_arguments)]
     pub fn lower_from_text_spans(
         fs: &FormatSpans,
         context: &mut UpdateContext<'_, 'gc>,
@@ -713,113 +1036,190 @@ impl<'gc> LayoutBox<'gc> {
         bounds: Twips,
         is_word_wrap: bool,
         is_device_font: bool,
+        image_sizes: &[Size<Twips>],
     ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>) {
         let mut layout_context = LayoutContext::new(movie, bounds, fs.displayed_text());
+        let images = fs.images();
+        let mut next_image = 0;
+
+        // Images split the spans they are anchored in, so that they start
+        // floating at the line of the text they are anchored before.
+        let chunks = fs.iter_spans().flat_map(move |(start, end, text, span)| {
+            let breaks = images
+                .iter()
+                .map(|image| image.position)
+                .filter(move |position| *position > start && *position < end)
+                .chain(std::iter::once(end));
+            let mut chunk_start = start;
+            breaks.map(move |chunk_end| {
+                let chunk = (
+                    chunk_start,
+                    &text[chunk_start - start..chunk_end - start],
+                    span,
+                );
+                chunk_start = chunk_end;
+                chunk
+            })
+        });
+
+        for (span_start, span_text, span) in chunks {
+            while let Some(image) = images
+                .get(next_image)
+                .filter(|image| image.position <= span_start)
+            {
+                let size = image_sizes.get(next_image).copied().unwrap_or_default();
+                layout_context.append_image(next_image, image, size);
+                next_image += 1;
+            }
 
-        for (span_start, _end, span_text, span) in fs.iter_spans() {
             if let Some(font) = layout_context.resolve_font(context, span, is_device_font) {
                 layout_context.newspan(span);
 
                 let params = EvalParameters::from_span(span);
 
-                for text in span_text.split(&[b'\n', b'\r', b'\t'][..]) {
-                    let slice_start = text.offset_in(span_text).unwrap();
-                    let delimiter = if slice_start > 0 {
-                        span_text
-                            .get(slice_start - 1)
-                            .and_then(|c| u8::try_from(c).ok())
-                    } else {
-                        None
-                    };
-
-                    match delimiter {
-                        Some(b'\n' | b'\r') => {
-                            layout_context.explicit_newline(context, text, 0, span)
+                // Runs of text are split where their font or direction changes.
+                let runs: Vec<_> = Self::font_runs(context, font, span, span_start, span_text)
+                    .into_iter()
+                    .flat_map(|(run_start, run_text, run_font)| {
+                        layout_context
+                            .level_runs(run_start, run_text)
+                            .into_iter()
+                            .map(move |(start, text, is_rtl)| (start, text, run_font, is_rtl))
+                    })
+                    .collect();
+
+                for (run_start, run_text, run_font, is_rtl) in runs {
+                    layout_context.font = Some(run_font);
+                    layout_context.is_rtl = is_rtl;
+
+                    for text in run_text.split(&[b'\n', b'\r', b'\t'][..]) {
+                        let slice_start = text.offset_in(run_text).unwrap();
+                        let delimiter = if slice_start > 0 {
+                            run_text
+                                .get(slice_start - 1)
+                                .and_then(|c| u8::try_from(c).ok())
+                        } else {
+                            None
+                        };
+
+                        match delimiter {
+                            Some(b'\n' | b'\r') => {
+                                layout_context.explicit_newline(context, text, 0, span)
+                            }
+                            Some(b'\t') => layout_context.tab(),
+                            _ => {}
                         }
-                        Some(b'\t') => layout_context.tab(),
-                        _ => {}
-                    }
 
-                    let start = span_start + slice_start;
-
-                    let mut last_breakpoint = 0;
-
-                    if is_word_wrap {
-                        let (mut width, mut offset) = layout_context.wrap_dimensions(span);
-
-                        while let Some(breakpoint) = font.wrap_line(
-                            &text[last_breakpoint..],
-                            params,
-                            width,
-                            offset,
-                            layout_context.is_start_of_line(),
-                        ) {
-                            // This ensures that the space causing the line break
-                            // is included in the line it broke.
-                            let next_breakpoint = string_utils::next_char_boundary(
-                                text,
-                                last_breakpoint + breakpoint,
-                            );
+                        let start = run_start + slice_start;
+
+                        let mut last_breakpoint = 0;
+
+                        if is_word_wrap {
+                            let (mut width, mut offset) = layout_context.wrap_dimensions(span);
+
+                            while let Some(breakpoint) = run_font.wrap_line(
+                                &text[last_breakpoint..],
+                                params,
+                                width,
+                                offset,
+                                layout_context.is_start_of_line(),
+                            ) {
+                                // This ensures that the space causing the line break
+                                // is included in the line it broke.
+                                let next_breakpoint = string_utils::next_char_boundary(
+                                    text,
+                                    last_breakpoint + breakpoint,
+                                );
 
-                            // If text doesn't fit at the start of a line, it
-                            // won't fit on the next either, abort and put the
-                            // whole text on the line (will be cut-off). This
-                            // can happen for small text fields with single
-                            // characters.
-                            if breakpoint == 0 && layout_context.is_start_of_line() {
-                                break;
-                            } else if breakpoint == 0 {
-                                layout_context.newline(context, text, next_breakpoint, span);
+                                // If text doesn't fit at the start of a line, it
+                                // won't fit on the next either, abort and put the
+                                // whole text on the line (will be cut-off). This
+                                // can happen for small text fields with single
+                                // characters.
+                                if breakpoint == 0 && layout_context.is_start_of_line() {
+                                    break;
+                                } else if breakpoint == 0 {
+                                    layout_context.newline(context, text, next_breakpoint, span);
 
-                                let next_dim = layout_context.wrap_dimensions(span);
+                                    let next_dim = layout_context.wrap_dimensions(span);
 
-                                width = next_dim.0;
-                                offset = next_dim.1;
+                                    width = next_dim.0;
+                                    offset = next_dim.1;
 
+                                    if last_breakpoint >= text.len() {
+                                        break;
+                                    } else {
+                                        continue;
+                                    }
+                                }
+
+                                layout_context.append_text(
+                                    &text[last_breakpoint..next_breakpoint],
+                                    start + last_breakpoint,
+                                    start + next_breakpoint,
+                                    span,
+                                );
+
+                                last_breakpoint = next_breakpoint;
                                 if last_breakpoint >= text.len() {
                                     break;
-                                } else {
-                                    continue;
                                 }
+
+                                layout_context.newline(context, text, next_breakpoint, span);
+                                let next_dim = layout_context.wrap_dimensions(span);
+
+                                width = next_dim.0;
+                                offset = next_dim.1;
                             }
+                        }
 
+                        let span_end = text.len();
+
+                        if last_breakpoint < span_end {
                             layout_context.append_text(
-                                &text[last_breakpoint..next_breakpoint],
+                                &text[last_breakpoint..span_end],
                                 start + last_breakpoint,
-                                start + next_breakpoint,
+                                start + span_end,
                                 span,
                             );
-
-                            last_breakpoint = next_breakpoint;
-                            if last_breakpoint >= text.len() {
-                                break;
-                            }
-
-                            layout_context.newline(context, text, next_breakpoint, span);
-                            let next_dim = layout_context.wrap_dimensions(span);
-
-                            width = next_dim.0;
-                            offset = next_dim.1;
                         }
                     }
-
-                    let span_end = text.len();
-
-                    if last_breakpoint < span_end {
-                        layout_context.append_text(
-                            &text[last_breakpoint..span_end],
-                            start + last_breakpoint,
-                            start + span_end,
-                            span,
-                        );
-                    }
                 }
+                layout_context.font = Some(font);
+                layout_context.is_rtl = false;
             }
         }
 
+        while let Some(image) = images.get(next_image) {
+            let size = image_sizes.get(next_image).copied().unwrap_or_default();
+            layout_context.append_image(next_image, image, size);
+            next_image += 1;
+        }
+
         layout_context.end_layout(context, fs)
     }
 
+    /// Splits the text of a span into runs of characters drawn with the same font, returning
+    /// the start of each run in the text of the field, its text and its font.
+    ///
+    /// Device text falls back to other fonts installed on the system for the characters its
+    /// font has no glyphs for, such as Japanese or emoji, rather than leaving them out.
+    fn font_runs<'t>(
+        context: &mut UpdateContext<'_, 'gc>,
+        font: Font<'gc>,
+        span: &TextSpan,
+        span_start: usize,
+        text: &'t WStr,
+    ) -> Vec<(usize, &'t WStr, Font<'gc>)> {
+        if !context.library.is_device_font(font) {
+            return vec![(span_start, text, font)];
+        }
+
+        split_font_runs(font, span_start, text, |c| {
+            context.fallback_font(c, span.bold, span.italic)
+        })
+    }
+
     pub fn bounds(&self) -> BoxBounds<Twips> {
         self.bounds
     }
@@ -861,7 +1261,7 @@ impl<'gc> LayoutBox<'gc> {
                 *params,
                 swf::Color::from_rgb(color.to_rgb(), 0xFF),
             )),
-            LayoutContent::Drawing(..) => None,
+            LayoutContent::Drawing(..) | LayoutContent::Image(..) => None,
         }
     }
 
@@ -871,6 +1271,15 @@ impl<'gc> LayoutBox<'gc> {
             LayoutContent::Text { .. } => None,
             LayoutContent::Bullet { .. } => None,
             LayoutContent::Drawing(drawing) => Some(drawing),
+            LayoutContent::Image(..) => None,
+        }
+    }
+
+    /// Returns the index of the image this box contains, if it has one.
+    pub fn as_image(&self) -> Option<usize> {
+        match &self.content {
+            LayoutContent::Image(index) => Some(*index),
+            _ => None,
         }
     }
 
@@ -1,9 +1,15 @@
 //! Tests for HTML module
 
+use crate::font::{Font, FontDescriptor};
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::layout::{bidi_levels, split_font_runs};
+use crate::html::text_format::{FormatSpans, StyleSheet, TextFormat, TextSpan};
+use crate::player::Player;
 use crate::string::{WStr, WString};
+use gc_arena::rootless_arena;
+use ruffle_render::backend::{null::NullRenderer, ViewportDimensions};
 use swf::{Rectangle, Twips};
+use unicode_bidi::Level;
 
 #[test]
 fn position_add() {
@@ -864,3 +870,160 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+#[test]
+fn formatspans_from_html_images() {
+    let mut fs = FormatSpans::from_html(
+        WStr::from_units(b"ab<img src=\"pic.png\" id=\"pic\" width=\"20\" align=\"right\">cd"),
+        Default::default(),
+        None,
+        true,
+    );
+
+    assert_eq!(WStr::from_units(b"abcd"), fs.text());
+    assert_eq!(1, fs.images().len());
+
+    let image = &fs.images()[0];
+    assert_eq!(2, image.position);
+    assert_eq!(WString::from_utf8("pic.png"), image.src);
+    assert_eq!(Some(WString::from_utf8("pic")), image.id);
+    assert_eq!(Some(20.0), image.width);
+    assert_eq!(None, image.height);
+    assert_eq!(swf::TextAlign::Right, image.align);
+    assert_eq!(8.0, image.hspace);
+
+    fs.replace_text(0, 1, WStr::from_units(b"123"), None);
+    assert_eq!(4, fs.images()[0].position);
+
+    fs.replace_text(3, 5, WStr::from_units(b""), None);
+    assert!(fs.images().is_empty());
+}
+
+#[test]
+fn formatspans_from_html_style_sheet() {
+    let mut style_sheet = StyleSheet::new();
+    style_sheet.set_style(
+        WStr::from_units(b".Title"),
+        TextFormat {
+            bold: Some(true),
+            ..Default::default()
+        },
+    );
+    style_sheet.set_style(
+        WStr::from_units(b"p"),
+        TextFormat {
+            size: Some(20.0),
+            ..Default::default()
+        },
+    );
+
+    let fs = FormatSpans::from_html(
+        WStr::from_units(b"<p>a<span class=\"title\">b</span></p>"),
+        Default::default(),
+        Some(&style_sheet),
+        true,
+    );
+
+    let plain = fs.get_text_format(0, 1);
+    assert_eq!(Some(20.0), plain.size);
+    assert_eq!(Some(false), plain.bold);
+
+    let title = fs.get_text_format(1, 2);
+    assert_eq!(Some(20.0), title.size);
+    assert_eq!(Some(true), title.bold);
+}
+
+#[test]
+fn bidi_levels_left_to_right() {
+    let (levels, paragraphs) = bidi_levels(WStr::from_units(b"Hello, world!\r123"));
+    assert!(levels.is_empty());
+    assert!(paragraphs.is_empty());
+}
+
+#[test]
+fn bidi_levels_mixed() {
+    let ltr = Level::ltr();
+    let rtl = Level::rtl();
+    let embedded_ltr = Level::new(2).unwrap();
+
+    // Hebrew in a left-to-right paragraph.
+    let (levels, paragraphs) = bidi_levels(&WString::from_utf8("abc \u{5d0}\u{5d1}\u{5d2}"));
+    assert_eq!(levels, [ltr, ltr, ltr, ltr, rtl, rtl, rtl]);
+    assert_eq!(paragraphs, [(0, ltr)]);
+
+    // Latin in a right-to-left paragraph.
+    let (levels, paragraphs) = bidi_levels(&WString::from_utf8("\u{5d0}\u{5d1} ab"));
+    assert_eq!(levels, [rtl, rtl, rtl, embedded_ltr, embedded_ltr]);
+    assert_eq!(paragraphs, [(0, rtl)]);
+}
+
+#[test]
+fn bidi_levels_paragraphs() {
+    // Each paragraph gets the direction of its first strong character, and
+    // starts at a code unit index rather than a UTF-8 byte index.
+    let (levels, paragraphs) = bidi_levels(&WString::from_utf8("\u{5d0}\u{5d1}\nabc\ndef"));
+    assert_eq!(levels.len(), 10);
+    assert_eq!(&levels[..2], [Level::rtl(), Level::rtl()]);
+    assert_eq!(&levels[3..6], [Level::ltr(), Level::ltr(), Level::ltr()]);
+    assert_eq!(
+        paragraphs,
+        [(0, Level::rtl()), (3, Level::ltr()), (7, Level::ltr())]
+    );
+}
+
+#[test]
+fn bidi_levels_surrogate_pairs() {
+    // Characters outside of the Basic Multilingual Plane have a level for
+    // each of their two code units.
+    let (levels, paragraphs) = bidi_levels(&WString::from_utf8("\u{5d0}\u{1d400}\u{5d1}"));
+    let embedded_ltr = Level::new(2).unwrap();
+    assert_eq!(
+        levels,
+        [Level::rtl(), embedded_ltr, embedded_ltr, Level::rtl()]
+    );
+    assert_eq!(paragraphs, [(0, Level::rtl())]);
+}
+
+#[test]
+fn split_font_runs_fallback() {
+    rootless_arena(|mc| {
+        let mut renderer = NullRenderer::new(ViewportDimensions {
+            width: 0,
+            height: 0,
+            scale_factor: 1.0,
+        });
+        let device_font = Player::load_device_font(mc, &mut renderer);
+        let arabic_font = Font::from_font_file(
+            mc,
+            FontDescriptor::from_parts("Shaping Test", false, false),
+            include_bytes!("../../assets/shaping-test-font.ttf").to_vec(),
+            0,
+        )
+        .unwrap();
+
+        // The device font has no Arabic letters, nor a glyph for the combining acute accent.
+        let text = WString::from_utf8("ab \u{628}\u{64e}\u{628} c\u{301}");
+        let runs = split_font_runs(device_font, 10, &text, |c| {
+            ('\u{600}'..='\u{6ff}').contains(&c).then_some(arabic_font)
+        });
+
+        // Marks stay with the letters they're on, and spaces with the word before them.
+        let expected = [
+            (10, WString::from_utf8("ab "), device_font),
+            (
+                13,
+                WString::from_utf8("\u{628}\u{64e}\u{628} "),
+                arabic_font,
+            ),
+            (17, WString::from_utf8("c\u{301}"), device_font),
+        ];
+        assert_eq!(runs.len(), expected.len());
+        for ((start, text, font), (expected_start, expected_text, expected_font)) in
+            runs.into_iter().zip(expected)
+        {
+            assert_eq!(start, expected_start);
+            assert_eq!(text, &*expected_text);
+            assert!(Font::ptr_eq(font, expected_font));
+        }
+    })
+}
@@ -8,8 +8,9 @@ use gc_arena::Collect;
 use quick_xml::{escape::escape, events::Event, Reader};
 use std::borrow::Cow;
 use std::cmp::{min, Ordering};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Write;
+use std::iter::Peekable;
 use std::sync::Arc;
 
 /// Replace HTML entities with their equivalent characters.
@@ -515,6 +516,76 @@ impl TextSpan {
     }
 }
 
+/// An image, SWF or library symbol placed into the text with an `<img>` tag.
+///
+/// Images float at the left or right side of the text, starting at the line
+/// they are anchored in, and the text wraps around them.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct TextImage {
+    /// The position in the text that the image is anchored before.
+    pub position: usize,
+
+    /// The URL of the image, or the linkage name of a library symbol.
+    pub src: WString,
+
+    /// The instance name of the display object the image is loaded into.
+    pub id: Option<WString>,
+
+    /// The width the image is scaled to, in pixels, or its own if `None`.
+    pub width: Option<f64>,
+
+    /// The height the image is scaled to, in pixels, or its own if `None`.
+    pub height: Option<f64>,
+
+    /// The side the image floats at, which is either `Left` or `Right`.
+    pub align: swf::TextAlign,
+
+    /// The space kept free of text beside the image, in pixels.
+    pub hspace: f64,
+
+    /// The space kept free of text below the image, in pixels.
+    pub vspace: f64,
+}
+
+/// The text formats given to tags and classes by a `StyleSheet` object.
+///
+/// Selectors are either tag names, such as `p`, or class names prefixed with
+/// a dot, such as `.title`, and are matched case-insensitively.
+#[derive(Clone, Debug, Default, Collect)]
+#[collect(require_static)]
+pub struct StyleSheet {
+    styles: HashMap<WString, TextFormat>,
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn set_style(&mut self, selector: &WStr, format: TextFormat) {
+        self.styles.insert(selector.to_ascii_lowercase(), format);
+    }
+
+    /// Apply the styles of a tag, and then of its `class` attribute, over the
+    /// formatting of the tag's presentational attributes.
+    fn apply(&self, tag: &[u8], class: Option<WString>, mut format: TextFormat) -> TextFormat {
+        if let Some(style) = self.styles.get(WStr::from_units(tag)) {
+            format = style.clone().mix_with(format);
+        }
+
+        if let Some(class) = class {
+            let mut selector = WString::from_buf(b".".to_vec());
+            selector.push_str(&class.to_ascii_lowercase());
+            if let Some(style) = self.styles.get(&*selector) {
+                format = style.clone().mix_with(format);
+            }
+        }
+
+        format
+    }
+}
+
 /// Struct which contains text formatted by `TextSpan`s.
 #[derive(Clone, Debug, Collect)]
 #[collect(require_static)]
@@ -523,6 +594,9 @@ pub struct FormatSpans {
     displayed_text: WString,
     spans: Vec<TextSpan>,
     default_format: TextFormat,
+
+    /// The images placed into the text, in the order of their positions.
+    images: Vec<TextImage>,
 }
 
 impl Default for FormatSpans {
@@ -538,6 +612,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::default()],
             default_format: TextFormat::default(),
+            images: Vec::new(),
         }
     }
 
@@ -549,6 +624,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: spans.to_vec(),
             default_format: Default::default(),
+            images: Vec::new(),
         }
     }
 
@@ -559,19 +635,25 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::with_length_and_format(len, format.clone())],
             default_format: format,
+            images: Vec::new(),
         }
     }
 
     /// Lower an HTML tree into text-span representation.
     ///
-    /// This is the "legacy" implementation of this process: it only looks for
-    /// a handful of presentational attributes in the HTML tree to generate
-    /// styling. There's also a `lower_from_css` that respects both
-    /// presentational markup and CSS stylesheets.
-    pub fn from_html(html: &WStr, default_format: TextFormat, is_multiline: bool) -> Self {
+    /// Styling comes from a handful of presentational attributes in the HTML
+    /// tree, and from the tag and class styles of the given style sheet, which
+    /// take precedence over them.
+    pub fn from_html(
+        html: &WStr,
+        default_format: TextFormat,
+        style_sheet: Option<&StyleSheet>,
+        is_multiline: bool,
+    ) -> Self {
         let mut format_stack = vec![default_format.clone()];
         let mut text = WString::new();
         let mut spans: Vec<TextSpan> = Vec::new();
+        let mut images: Vec<TextImage> = Vec::new();
 
         // quick_xml::Reader requires a [u8] slice, but doesn't actually care about Unicode;
         // this means we can pass the raw buffer in the Latin1 case.
@@ -631,7 +713,8 @@ impl FormatSpans {
                         })
                     };
                     let mut format = format_stack.last().unwrap().clone();
-                    match &e.name().to_ascii_lowercase()[..] {
+                    let name = e.name().to_ascii_lowercase();
+                    match &name[..] {
                         b"br" => {
                             if is_multiline {
                                 text.push_byte(b'\n');
@@ -654,6 +737,37 @@ impl FormatSpans {
                             // Skip push to `format_stack`.
                             continue;
                         }
+                        b"img" => {
+                            if let Some(src) = attribute(b"src") {
+                                let number = |name: &[u8]| {
+                                    attribute(name).and_then(|value| value.trim().parse().ok())
+                                };
+                                let is_right = attribute(b"align").map_or(false, |align| {
+                                    align.eq_ignore_case(WStr::from_units(b"right"))
+                                });
+
+                                images.push(TextImage {
+                                    position: text.len(),
+                                    src,
+                                    id: attribute(b"id"),
+                                    width: number(b"width"),
+                                    height: number(b"height"),
+                                    align: if is_right {
+                                        swf::TextAlign::Right
+                                    } else {
+                                        swf::TextAlign::Left
+                                    },
+                                    hspace: number(b"hspace").unwrap_or(8.0),
+                                    vspace: number(b"vspace").unwrap_or(8.0),
+                                });
+                            }
+
+                            // `<img>` has no content, so it's never closed in HTML.
+                            opened_buffer.truncate(opened_starts.pop().unwrap_or_default());
+
+                            // Skip push to `format_stack`.
+                            continue;
+                        }
                         b"p" if is_multiline => {
                             if let Some(align) = attribute(b"align") {
                                 if align == WStr::from_units(b"left") {
@@ -759,6 +873,9 @@ impl FormatSpans {
                         }
                         _ => {}
                     }
+                    if let Some(style_sheet) = style_sheet {
+                        format = style_sheet.apply(&name, attribute(b"class"), format);
+                    }
                     format_stack.push(format);
                 }
                 Ok(Event::Text(e)) if !e.is_empty() => {
@@ -811,6 +928,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans,
             default_format,
+            images,
         }
     }
 
@@ -834,6 +952,11 @@ impl FormatSpans {
         !self.displayed_text.is_empty()
     }
 
+    /// Retrieve the images placed into the text with `<img>` tags.
+    pub fn images(&self) -> &[TextImage] {
+        &self.images
+    }
+
     /// Retrieve the text backing the format spans.
     pub fn text(&self) -> &WStr {
         &self.text
@@ -1098,6 +1221,17 @@ impl FormatSpans {
             return;
         }
 
+        // Images within the replaced text are removed, and those after it
+        // move along with the text.
+        let (image_from, image_to) = (min(from, self.text.len()), min(to, self.text.len()));
+        self.images
+            .retain(|image| image.position <= image_from || image.position >= image_to);
+        for image in self.images.iter_mut() {
+            if image.position > image_from {
+                image.position = image.position - (image_to - image_from) + with.len();
+            }
+        }
+
         if from < self.text.len() {
             self.ensure_span_break_at(from);
             self.ensure_span_break_at(to);
@@ -1158,22 +1292,27 @@ impl FormatSpans {
 
     pub fn to_html(&self) -> WString {
         let mut spans = self.iter_spans();
-        let mut state = if let Some((_start, _end, text, span)) = spans.next() {
+        let mut images = self.images.iter().peekable();
+        let mut state = if let Some((start, end, text, span)) = spans.next() {
             let mut state = FormatState {
                 result: WString::new(),
                 font_stack: VecDeque::new(),
                 span,
                 is_open: false,
             };
-            state.push_text(text);
+            state.push_text_and_images(text, start, end, &mut images);
             state
         } else {
             return WString::new();
         };
 
-        for (_start, _end, text, span) in spans {
+        for (start, end, text, span) in spans {
             state.set_span(span);
-            state.push_text(text);
+            state.push_text_and_images(text, start, end, &mut images);
+        }
+
+        for image in images {
+            state.push_image(image);
         }
 
         state.close_tags();
@@ -1415,6 +1554,52 @@ impl<'a> FormatState<'a> {
         self.span = span;
     }
 
+    /// Push the text of a span, with the images anchored within it.
+    fn push_text_and_images(
+        &mut self,
+        text: &WStr,
+        start: usize,
+        end: usize,
+        images: &mut Peekable<std::slice::Iter<'_, TextImage>>,
+    ) {
+        let mut position = start;
+        while let Some(image) = images.next_if(|image| image.position < end) {
+            let image_position = image.position.max(position);
+            self.push_text(&text[position - start..image_position - start]);
+            self.push_image(image);
+            position = image_position;
+        }
+        self.push_text(&text[position - start..]);
+    }
+
+    fn push_image(&mut self, image: &TextImage) {
+        self.open_tags();
+
+        let escape_value = |value: &WStr| {
+            let value = value.to_utf8_lossy();
+            String::from_utf8_lossy(&escape(value.as_bytes())).into_owned()
+        };
+        let _ = write!(self.result, "<IMG SRC=\"{}\"", escape_value(&image.src));
+        if let Some(id) = &image.id {
+            let _ = write!(self.result, " ID=\"{}\"", escape_value(id));
+        }
+        if let Some(width) = image.width {
+            let _ = write!(self.result, " WIDTH=\"{width}\"");
+        }
+        if let Some(height) = image.height {
+            let _ = write!(self.result, " HEIGHT=\"{height}\"");
+        }
+        let align = match image.align {
+            swf::TextAlign::Right => "right",
+            _ => "left",
+        };
+        let _ = write!(
+            self.result,
+            " ALIGN=\"{align}\" HSPACE=\"{}\" VSPACE=\"{}\">",
+            image.hspace, image.vspace
+        );
+    }
+
     fn push_text(&mut self, text: &WStr) {
         for (i, text) in text.split(&[b'\n', b'\r'][..]).enumerate() {
             self.open_tags();
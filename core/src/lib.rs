@@ -2,7 +2,7 @@
 
 #[macro_use]
 mod display_object;
-pub use display_object::StageDisplayState;
+pub use display_object::{StageDisplayState, StageQuality};
 
 #[macro_use]
 extern crate smallvec;
@@ -18,28 +18,44 @@ mod avm1;
 mod avm2;
 mod binary_data;
 pub mod bitmap;
+mod capture;
 mod character;
+mod clock;
+pub mod compatibility;
 pub mod context;
 pub mod context_menu;
+mod cursor;
+mod dialog;
 mod drawing;
 mod ecma_conversions;
 pub(crate) mod either;
 pub mod events;
 pub mod focus_tracker;
-mod font;
+pub mod font;
 mod frame_lifecycle;
+pub mod heap_snapshot;
 mod html;
 mod library;
 pub mod limits;
 pub mod loader;
+mod local_connection;
+pub mod local_storage;
 mod locale;
+pub mod navigation;
 mod player;
+pub mod policy_file;
 mod prelude;
+mod rtmp;
+pub mod site_lock;
+mod socket;
+mod streams;
 pub mod string;
 pub mod tag_utils;
 pub mod timer;
+pub mod touch;
 mod types;
 mod vminterface;
+mod worker;
 mod xml;
 
 pub mod backend;
@@ -47,8 +63,9 @@ pub mod config;
 pub mod external;
 pub mod stub;
 
+pub use avm1::PlayerType;
 pub use context_menu::ContextMenuItem;
-pub use events::PlayerEvent;
+pub use events::{EmbedderEvent, PlayerEvent};
 pub use indexmap;
 pub use loader::LoadBehavior;
 pub use player::{Player, PlayerBuilder, StaticCallstack};
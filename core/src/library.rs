@@ -1,10 +1,11 @@
 use crate::avm1::PropertyMap as Avm1PropertyMap;
 use crate::avm2::{ClassObject as Avm2ClassObject, Domain as Avm2Domain};
 use crate::backend::audio::SoundHandle;
+use crate::backend::ui::UiBackend;
 use crate::character::Character;
 
 use crate::display_object::{Bitmap, Graphic, MorphShape, TDisplayObject, Text};
-use crate::font::{Font, FontDescriptor};
+use crate::font::{Font, FontDescriptor, FontFallbacks, FontMap, FontScript, FontType};
 use crate::prelude::*;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
@@ -155,6 +156,20 @@ impl<'gc> MovieLibrary<'gc> {
         self.characters.contains_key(&id)
     }
 
+    /// Replaces a font that was already registered, such as when a `DefineFontInfo` tag
+    /// names the font of a `DefineFont` tag.
+    pub fn replace_font(&mut self, id: CharacterId, font: Font<'gc>) {
+        if let Some(&Character::Font(old_font)) = self.characters.get(&id) {
+            if let Some(&registered) = self.fonts.get(old_font.descriptor()) {
+                if Font::ptr_eq(registered, old_font) {
+                    self.fonts.remove(old_font.descriptor());
+                }
+            }
+        }
+        self.fonts.insert(font.descriptor().clone(), font);
+        self.characters.insert(id, Character::Font(font));
+    }
+
     pub fn character_by_id(&self, id: CharacterId) -> Option<&Character<'gc>> {
         self.characters.get(&id)
     }
@@ -245,6 +260,14 @@ impl<'gc> MovieLibrary<'gc> {
         self.fonts.get(&descriptor).copied()
     }
 
+    /// Returns the fonts of this movie that have glyphs embedded in it.
+    pub fn embedded_fonts(&self) -> impl Iterator<Item = Font<'gc>> + '_ {
+        self.fonts
+            .values()
+            .copied()
+            .filter(|font| font.has_glyphs())
+    }
+
     /// Returns the `Graphic` with the given character ID.
     /// Returns `None` if the ID does not exist or is not a `Graphic`.
     pub fn get_graphic(&self, id: CharacterId) -> Option<Graphic<'gc>> {
@@ -357,6 +380,16 @@ pub struct Library<'gc> {
     /// The embedded device font.
     device_font: Option<Font<'gc>>,
 
+    /// The fonts installed on the system, loaded as device text asks for them. `None` marks
+    /// the fonts that aren't installed or failed to load.
+    device_fonts: HashMap<FontDescriptor, Option<Font<'gc>>>,
+
+    /// The fonts that device text in other fonts is drawn with.
+    font_map: FontMap,
+
+    /// The fonts that device text falls back to for characters its font has no glyphs for.
+    font_fallbacks: FontFallbacks,
+
     /// A list of the symbols associated with specific AVM2 constructor
     /// prototypes.
     avm2_class_registry: Avm2ClassRegistry<'gc>,
@@ -369,15 +402,21 @@ unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
             val.trace(cc);
         }
         self.device_font.trace(cc);
+        for font in self.device_fonts.values() {
+            font.trace(cc);
+        }
         self.avm2_class_registry.trace(cc);
     }
 }
 
 impl<'gc> Library<'gc> {
-    pub fn empty() -> Self {
+    pub fn new(font_map: FontMap, font_fallbacks: FontFallbacks) -> Self {
         Self {
             movie_libraries: PtrWeakKeyHashMap::new(),
             device_font: None,
+            device_fonts: HashMap::new(),
+            font_map,
+            font_fallbacks,
             avm2_class_registry: Default::default(),
         }
     }
@@ -392,6 +431,26 @@ impl<'gc> Library<'gc> {
             .or_insert_with(MovieLibrary::new)
     }
 
+    /// Returns a movie whose code runs in the given AVM2 domain.
+    pub fn movie_for_avm2_domain(&self, domain: Avm2Domain<'gc>) -> Option<Arc<SwfMovie>> {
+        self.movie_libraries
+            .iter()
+            .find(|(_, library)| library.avm2_domain == Some(domain))
+            .map(|(movie, _)| movie)
+    }
+
+    /// Returns the fonts embedded in all loaded movies, sorted by name and style.
+    pub fn embedded_fonts(&self) -> Vec<Font<'gc>> {
+        let mut fonts: Vec<_> = self
+            .movie_libraries
+            .iter()
+            .flat_map(|(_, library)| library.embedded_fonts())
+            .collect();
+        fonts.sort_by(|a, b| a.descriptor().cmp(b.descriptor()));
+        fonts.dedup_by(|a, b| a.descriptor() == b.descriptor());
+        fonts
+    }
+
     /// Returns the device font for use when a font is unavailable.
     pub fn device_font(&self) -> Option<Font<'gc>> {
         self.device_font
@@ -402,6 +461,85 @@ impl<'gc> Library<'gc> {
         self.device_font = Some(font);
     }
 
+    /// Returns the font that device text in the font named `name` is drawn with.
+    ///
+    /// The name is looked up in the font map, and then among the fonts installed on the
+    /// system. The built-in device font stands in for fonts that aren't installed.
+    pub fn get_device_font(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        ui: &dyn UiBackend,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        let name = self.font_map.substitute(name).to_string();
+        self.load_device_font(gc_context, ui, &name, is_bold, is_italic)
+            .or(self.device_font)
+    }
+
+    /// Returns a font installed on the system to draw `c` with, for device text whose font
+    /// has no glyph for it.
+    ///
+    /// The first font in the fallback chain of the script of the character that's installed
+    /// and has a glyph for it is used.
+    pub fn get_fallback_font(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        ui: &dyn UiBackend,
+        c: char,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        let chain = self.font_fallbacks.chain(FontScript::of(c));
+        chain.iter().find_map(|name| {
+            self.load_device_font(gc_context, ui, name, is_bold, is_italic)
+                .filter(|font| font.get_glyph_for_char(c).is_some())
+        })
+    }
+
+    /// Returns the font installed on the system with the given name, of which the one closest
+    /// in style is loaded the first time it's asked for.
+    fn load_device_font(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        ui: &dyn UiBackend,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+        *self.device_fonts.entry(descriptor).or_insert_with(|| {
+            let device_font = ui
+                .device_fonts()
+                .into_iter()
+                .filter(|font| font.name.eq_ignore_ascii_case(name))
+                .min_by_key(|font| {
+                    u8::from(font.is_bold != is_bold) + u8::from(font.is_italic != is_italic)
+                })?;
+            let file = ui.load_device_font(&device_font)?;
+            let descriptor = FontDescriptor::from_parts(
+                &device_font.name,
+                device_font.is_bold,
+                device_font.is_italic,
+            );
+            let font = Font::from_font_file(gc_context, descriptor, file.data, file.index);
+            if font.is_none() {
+                tracing::warn!("Unable to load device font {}", device_font.name);
+            }
+            font
+        })
+    }
+
+    /// Returns whether text in the font is drawn with a font of the system, or the built-in
+    /// device font.
+    pub fn is_device_font(&self, font: Font<'gc>) -> bool {
+        font.font_type() == FontType::Device
+            || self
+                .device_font
+                .map_or(false, |device_font| Font::ptr_eq(font, device_font))
+    }
+
     /// Get the AVM2 class registry.
     pub fn avm2_class_registry(&self) -> &Avm2ClassRegistry<'gc> {
         &self.avm2_class_registry
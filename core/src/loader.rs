@@ -5,15 +5,21 @@ use crate::avm1::ExecutionReason;
 use crate::avm1::{Activation, ActivationIdentifier};
 use crate::avm1::{Object, SoundObject, TObject, Value};
 use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::globals::flash::net::url_loader;
+use crate::avm2::globals::flash::net::url_stream::{self, UrlStreamEvent};
 use crate::avm2::object::ByteArrayObject;
 use crate::avm2::object::EventObject as Avm2EventObject;
 use crate::avm2::object::LoaderStream;
+use crate::avm2::object::SoundObject as Avm2SoundObject;
 use crate::avm2::object::TObject as _;
 use crate::avm2::{
     Activation as Avm2Activation, Avm2, Domain as Avm2Domain, Multiname as Avm2Multiname,
     Object as Avm2Object, Value as Avm2Value,
 };
+use crate::backend::audio::id3::{self, ID3V1_LEN};
+use crate::backend::audio::SoundHandle;
 use crate::backend::navigator::{OwnedFuture, Request};
+use crate::compatibility::MediaKind;
 use crate::context::{ActionQueue, ActionType, UpdateContext};
 use crate::display_object::{
     Bitmap, DisplayObject, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
@@ -22,10 +28,15 @@ use crate::events::ClipEvent;
 use crate::frame_lifecycle::catchup_display_object_to_frame;
 use crate::limits::ExecutionLimit;
 use crate::player::Player;
+use crate::policy_file;
+use crate::rtmp::{RtmpConnection, RtmpRequest, RtmpSession, RtmpUrl};
+use crate::streams::NetStream;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
 use encoding_rs::UTF_8;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::{stream, StreamExt};
 use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
 use ruffle_render::utils::{determine_jpeg_tag_format, JpegTagFormat};
@@ -165,9 +176,27 @@ pub enum Error {
     #[error("Non-sound loader spawned as sound loader")]
     NotSoundLoader,
 
+    #[error("Non-NetStream loader spawned as NetStream loader")]
+    NotNetStreamLoader,
+
+    #[error("Non-RTMP loader spawned as RTMP loader")]
+    NotRtmpLoader,
+
+    #[error("Non-URLStream loader spawned as URLStream loader")]
+    NotUrlStreamLoader,
+
     #[error("Could not fetch: {0}")]
     FetchError(String),
 
+    #[error("HTTP status is not ok, got {0}")]
+    HttpNotOk(u16),
+
+    #[error("Security sandbox violation: cannot load data from {0}")]
+    SecurityViolation(String),
+
+    #[error("Could not connect: {0}")]
+    ConnectError(String),
+
     #[error("Invalid SWF: {0}")]
     InvalidSwf(#[from] crate::tag_utils::Error),
 
@@ -228,7 +257,10 @@ impl<'gc> LoadManager<'gc> {
             | Loader::LoadVars { self_handle, .. }
             | Loader::LoadURLLoader { self_handle, .. }
             | Loader::SoundAvm1 { self_handle, .. }
-            | Loader::SoundAvm2 { self_handle, .. } => *self_handle = Some(handle),
+            | Loader::SoundAvm2 { self_handle, .. }
+            | Loader::NetStream { self_handle, .. }
+            | Loader::Rtmp { self_handle, .. }
+            | Loader::UrlStream { self_handle, .. } => *self_handle = Some(handle),
         }
         handle
     }
@@ -384,6 +416,41 @@ impl<'gc> LoadManager<'gc> {
         loader.load_url_loader(player, request, data_format)
     }
 
+    /// Kick off a download into a `URLStream`, handing the data over to it as
+    /// it arrives.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_url_stream(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        request: Request,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::UrlStream {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.url_stream_loader(player, request)
+    }
+
+    /// Stops the downloads into a `URLStream`.
+    pub fn cancel_url_stream(&mut self, target: Avm2Object<'gc>) {
+        let handles: Vec<_> = self
+            .0
+            .iter()
+            .filter(|(_, loader)| {
+                matches!(loader, Loader::UrlStream { target_object, .. }
+                    if Avm2Object::ptr_eq(*target_object, target))
+            })
+            .map(|(handle, _)| handle)
+            .collect();
+        for handle in handles {
+            self.0.remove(handle);
+        }
+    }
+
     /// Kick off an AVM1 audio load.
     ///
     /// Returns the loader's async process, which you will need to spawn.
@@ -421,6 +488,50 @@ impl<'gc> LoadManager<'gc> {
         loader.sound_loader_avm2(player, request)
     }
 
+    /// Kick off a load of a video file into a `NetStream`, from byte `offset`
+    /// of the file onwards.
+    ///
+    /// The load stops once `target_stream` starts another load, as `load_id`
+    /// no longer matches then.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_netstream(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_stream: NetStream<'gc>,
+        load_id: u32,
+        request: Request,
+        offset: usize,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::NetStream {
+            self_handle: None,
+            target_stream,
+        };
+        let handle = self.add_loader(loader);
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.netstream_loader(player, load_id, request, offset)
+    }
+
+    /// Kick off an RTMP connection of a `NetConnection`.
+    ///
+    /// The connection stays open until the server closes it, or the
+    /// `requests` channel asks to close it.
+    pub fn connect_rtmp(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_connection: RtmpConnection<'gc>,
+        url: RtmpUrl,
+        requests: UnboundedReceiver<RtmpRequest>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::Rtmp {
+            self_handle: None,
+            target_connection,
+        };
+        let handle = self.add_loader(loader);
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.rtmp_loader(player, url, requests)
+    }
+
     /// Process tags on all loaders in the Parsing phase.
     ///
     /// Returns true if *all* loaders finished preloading.
@@ -549,6 +660,17 @@ pub enum Loader<'gc> {
         target_object: Avm2Object<'gc>,
     },
 
+    /// Loader that is downloading data into a `URLStream`, which can read it
+    /// as it arrives.
+    UrlStream {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The target `URLStream` to hand the data over to.
+        target_object: Avm2Object<'gc>,
+    },
+
     /// Loader that is loading an MP3 into an AVM1 Sound object.
     SoundAvm1 {
         /// The handle to refer to this loader instance.
@@ -568,6 +690,26 @@ pub enum Loader<'gc> {
         /// The target AVM1 object to load the audio into.
         target_object: Avm2Object<'gc>,
     },
+
+    /// Loader that is loading a video file into a `NetStream`.
+    NetStream {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The stream to play the file with.
+        target_stream: NetStream<'gc>,
+    },
+
+    /// Loader that is running the RTMP connection of a `NetConnection`.
+    Rtmp {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The connection receiving the events of the session.
+        target_connection: RtmpConnection<'gc>,
+    },
 }
 
 impl<'gc> Loader<'gc> {
@@ -854,6 +996,7 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
+            policy_file::check_access(&player, request.url()).await?;
             let fetch = player.lock().unwrap().navigator().fetch(request);
 
             let response = fetch.await?;
@@ -917,9 +1060,13 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
-            let fetch = player.lock().unwrap().navigator().fetch(request);
-
-            let data = fetch.await;
+            let data = match policy_file::check_access(&player, request.url()).await {
+                Ok(()) => {
+                    let fetch = player.lock().unwrap().navigator().fetch(request);
+                    fetch.await
+                }
+                Err(e) => Err(e),
+            };
 
             // Fire the load handler.
             player.lock().unwrap().update(|uc| {
@@ -995,6 +1142,9 @@ impl<'gc> Loader<'gc> {
     }
 
     /// Creates a future for a LoadURLLoader load call.
+    ///
+    /// The progress of the download is reported as it arrives, but the `data` property is only
+    /// set once it completes.
     fn load_url_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
@@ -1013,122 +1163,357 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
-            let fetch = player.lock().unwrap().navigator().fetch(request);
-            let response = fetch.await;
-
-            player.lock().unwrap().update(|uc| {
-                let loader = uc.load_manager.get_loader(handle);
-                let target = match loader {
-                    Some(&Loader::LoadURLLoader { target_object, .. }) => target_object,
-                    // We would have already returned after the previous 'update' call
-                    _ => unreachable!(),
-                };
-
-                let mut activation = Avm2Activation::from_nothing(uc.reborrow());
-
-                fn set_data<'a, 'gc: 'a>(
-                    body: Vec<u8>,
-                    activation: &mut Avm2Activation<'a, 'gc>,
-                    mut target: Avm2Object<'gc>,
-                    data_format: DataFormat,
-                ) {
-                    let data_object = match data_format {
-                        DataFormat::Binary => {
-                            let storage = ByteArrayStorage::from_vec(body);
-                            let bytearray =
-                                ByteArrayObject::from_storage(activation, storage).unwrap();
-                            bytearray.into()
-                        }
-                        DataFormat::Text => Avm2Value::String(AvmString::new_utf8_bytes(
-                            activation.context.gc_context,
-                            &body,
-                        )),
-                        DataFormat::Variables => {
-                            tracing::warn!(
-                                "Support for URLLoaderDataFormat.VARIABLES not yet implemented"
-                            );
-                            Avm2Value::Undefined
-                        }
-                    };
-
-                    target
-                        .set_property(&Avm2Multiname::public("data"), data_object, activation)
-                        .unwrap();
+            let response = match policy_file::check_access(&player, request.url()).await {
+                Ok(()) => {
+                    let fetch = player
+                        .lock()
+                        .unwrap()
+                        .navigator()
+                        .fetch_streaming(request, 0);
+                    fetch.await
                 }
+                Err(e) => Err(e),
+            };
 
+            let response = player.lock().unwrap().update(|uc| {
+                let target = Loader::url_loader_target(handle, uc)?;
                 match response {
                     Ok(response) => {
-                        // FIXME - the "open" event should be fired earlier, just before
-                        // we start to fetch the data.
-                        // However, the "open" event should not be fired if an IO error
-                        // occurs opening the connection (e.g. if a file does not exist on disk).
-                        // We currently have no way of detecting this, so we settle for firing
-                        // the event after the entire fetch is complete. This causes there
-                        // to a longer delay between the initial load triggered by the script
-                        // and the "load" event firing, but it ensures that we match
-                        // the Flash behavior w.r.t when an event is fired vs not fired.
-                        let open_evt =
-                            Avm2EventObject::bare_default_event(&mut activation.context, "open");
-
-                        if let Err(e) =
-                            Avm2::dispatch_event(&mut activation.context, open_evt, target)
-                        {
+                        // The "open" event isn't fired if the connection can't be opened at all
+                        // (e.g. if a file does not exist on disk).
+                        let open_evt = Avm2EventObject::bare_default_event(uc, "open");
+                        if let Err(e) = Avm2::dispatch_event(uc, open_evt, target) {
                             tracing::error!(
                                 "Encountered AVM2 error when broadcasting `open` event: {}",
                                 e
                             );
                         }
 
-                        set_data(response.body, &mut activation, target, data_format);
-
-                        let complete_evt = Avm2EventObject::bare_default_event(
-                            &mut activation.context,
-                            "complete",
-                        );
-
-                        if let Err(e) = Avm2::dispatch_event(uc, complete_evt, target) {
+                        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                        if let Err(e) = url_loader::dispatch_http_status(
+                            &mut activation,
+                            target,
+                            "httpResponseStatus",
+                            response.status,
+                            &response.url,
+                            &response.headers,
+                        ) {
                             tracing::error!(
-                                "Encountered AVM2 error when broadcasting `complete` event: {}",
+                                "Encountered AVM2 error when broadcasting `httpResponseStatus` event: {}",
                                 e
                             );
                         }
+                        Ok(Some(response))
+                    }
+                    Err(e) => {
+                        Loader::url_loader_error(uc, target, data_format, e);
+                        Ok(None)
+                    }
+                }
+            })?;
+            let response = match response {
+                Some(response) => response,
+                None => return Ok(()),
+            };
+
+            let mut data = Vec::new();
+            let mut body = response.body;
+            while let Some(chunk) = body.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        tracing::warn!("URLLoader download was interrupted: {}", e);
+                        return player.lock().unwrap().update(|uc| {
+                            let target = Loader::url_loader_target(handle, uc)?;
+                            Loader::url_loader_error(uc, target, data_format, e);
+                            Ok(())
+                        });
                     }
-                    Err(_err) => {
-                        // Testing with Flash shoes that the 'data' property is cleared
-                        // when an error occurs
+                };
 
-                        set_data(Vec::new(), &mut activation, target, data_format);
+                data.extend_from_slice(&chunk);
+                player.lock().unwrap().update(|uc| {
+                    let target = Loader::url_loader_target(handle, uc)?;
+                    let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                    if let Err(e) = url_loader::dispatch_progress(
+                        &mut activation,
+                        target,
+                        data.len(),
+                        response.total_length,
+                    ) {
+                        tracing::error!(
+                            "Encountered AVM2 error when broadcasting `progress` event: {}",
+                            e
+                        );
+                    }
+                    Ok(())
+                })?;
+            }
 
-                        // FIXME - Match the exact error message generated by Flash
+            player.lock().unwrap().update(|uc| {
+                let target = Loader::url_loader_target(handle, uc)?;
+                let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                Loader::url_loader_set_data(&mut activation, target, data, data_format);
 
-                        let io_error_evt_cls = activation.avm2().classes().ioerrorevent;
-                        let io_error_evt = io_error_evt_cls
-                            .construct(
-                                &mut activation,
-                                &[
-                                    "ioError".into(),
-                                    false.into(),
-                                    false.into(),
-                                    "Error #2032: Stream Error".into(),
-                                    2032.into(),
-                                ],
-                            )
-                            .map_err(|e| Error::Avm2Error(e.to_string()))?;
+                if let Err(e) = url_loader::dispatch_http_status(
+                    &mut activation,
+                    target,
+                    "httpStatus",
+                    response.status,
+                    &response.url,
+                    &response.headers,
+                ) {
+                    tracing::error!(
+                        "Encountered AVM2 error when broadcasting `httpStatus` event: {}",
+                        e
+                    );
+                }
 
-                        if let Err(e) = Avm2::dispatch_event(uc, io_error_evt, target) {
-                            tracing::error!(
-                                "Encountered AVM2 error when broadcasting `ioError` event: {}",
-                                e
-                            );
-                        }
+                let complete_evt = Avm2EventObject::bare_default_event(uc, "complete");
+                if let Err(e) = Avm2::dispatch_event(uc, complete_evt, target) {
+                    tracing::error!(
+                        "Encountered AVM2 error when broadcasting `complete` event: {}",
+                        e
+                    );
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Returns the `URLLoader` a loader loads data into.
+    fn url_loader_target(
+        handle: Index,
+        uc: &mut UpdateContext<'_, 'gc>,
+    ) -> Result<Avm2Object<'gc>, Error> {
+        match uc.load_manager.get_loader(handle) {
+            Some(&Loader::LoadURLLoader { target_object, .. }) => Ok(target_object),
+            None => Err(Error::Cancelled),
+            _ => Err(Error::NotLoadDataLoader),
+        }
+    }
+
+    /// Sets the `data` property of a `URLLoader` to the downloaded data, in the given format.
+    fn url_loader_set_data(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        mut target: Avm2Object<'gc>,
+        body: Vec<u8>,
+        data_format: DataFormat,
+    ) {
+        let data_object = match data_format {
+            DataFormat::Binary => {
+                let storage = ByteArrayStorage::from_vec(body);
+                let bytearray = ByteArrayObject::from_storage(activation, storage).unwrap();
+                bytearray.into()
+            }
+            DataFormat::Text => Avm2Value::String(AvmString::new_utf8_bytes(
+                activation.context.gc_context,
+                &body,
+            )),
+            DataFormat::Variables => {
+                tracing::warn!("Support for URLLoaderDataFormat.VARIABLES not yet implemented");
+                Avm2Value::Undefined
+            }
+        };
+
+        target
+            .set_property(&Avm2Multiname::public("data"), data_object, activation)
+            .unwrap();
+    }
+
+    /// Dispatches the events of a `URLLoader` whose data couldn't be downloaded.
+    fn url_loader_error(
+        uc: &mut UpdateContext<'_, 'gc>,
+        target: Avm2Object<'gc>,
+        data_format: DataFormat,
+        error: Error,
+    ) {
+        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+
+        // Testing with Flash shoes that the 'data' property is cleared
+        // when an error occurs
+        Loader::url_loader_set_data(&mut activation, target, Vec::new(), data_format);
+
+        if let Error::SecurityViolation(url) = &error {
+            if let Err(e) = url_loader::dispatch_security_error(&mut activation, target, url) {
+                tracing::error!(
+                    "Encountered AVM2 error when broadcasting `securityError` event: {}",
+                    e
+                );
+            }
+            return;
+        }
+
+        // FIXME - Match the exact error message generated by Flash
+        let (text, error_id) = match error {
+            Error::HttpNotOk(status) => {
+                if let Err(e) = url_loader::dispatch_http_status(
+                    &mut activation,
+                    target,
+                    "httpStatus",
+                    status,
+                    "",
+                    &[],
+                ) {
+                    tracing::error!(
+                        "Encountered AVM2 error when broadcasting `httpStatus` event: {}",
+                        e
+                    );
+                }
+
+                match status {
+                    404 | 410 => ("Error #2035: URL Not Found", 2035),
+                    _ => ("Error #2032: Stream Error", 2032),
+                }
+            }
+            _ => ("Error #2032: Stream Error", 2032),
+        };
+
+        let io_error_evt_cls = activation.avm2().classes().ioerrorevent;
+        let io_error_evt = match io_error_evt_cls.construct(
+            &mut activation,
+            &[
+                "ioError".into(),
+                false.into(),
+                false.into(),
+                text.into(),
+                error_id.into(),
+            ],
+        ) {
+            Ok(io_error_evt) => io_error_evt,
+            Err(e) => {
+                tracing::error!(
+                    "Encountered AVM2 error when creating `ioError` event: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Err(e) = Avm2::dispatch_event(uc, io_error_evt, target) {
+            tracing::error!(
+                "Encountered AVM2 error when broadcasting `ioError` event: {}",
+                e
+            );
+        }
+    }
+
+    /// Creates a future for a URLStream load call.
+    fn url_stream_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        request: Request,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::UrlStream { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotUrlStreamLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let response = match policy_file::check_access(&player, request.url()).await {
+                Ok(()) => {
+                    let fetch = player
+                        .lock()
+                        .unwrap()
+                        .navigator()
+                        .fetch_streaming(request, 0);
+                    fetch.await
+                }
+                Err(e) => Err(e),
+            };
+
+            let response = player.lock().unwrap().update(|uc| {
+                let target = Loader::url_stream_target(handle, uc)?;
+                match response {
+                    Ok(response) => {
+                        Loader::url_stream_event(uc, target, UrlStreamEvent::Open);
+                        Ok(Some(response))
+                    }
+                    Err(Error::SecurityViolation(url)) => {
+                        Loader::url_stream_event(uc, target, UrlStreamEvent::SecurityError(url));
+                        Ok(None)
+                    }
+                    Err(_) => {
+                        Loader::url_stream_event(uc, target, UrlStreamEvent::IoError);
+                        Ok(None)
                     }
                 }
+            })?;
+            let response = match response {
+                Some(response) => response,
+                None => return Ok(()),
+            };
+
+            let bytes_total = response.total_length;
+            let mut bytes_loaded = 0;
+            let mut body = response.body;
+            while let Some(chunk) = body.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        tracing::warn!("URLStream download was interrupted: {}", e);
+                        return player.lock().unwrap().update(|uc| {
+                            let target = Loader::url_stream_target(handle, uc)?;
+                            Loader::url_stream_event(uc, target, UrlStreamEvent::IoError);
+                            Ok(())
+                        });
+                    }
+                };
+
+                bytes_loaded += chunk.len();
+                player.lock().unwrap().update(|uc| {
+                    let target = Loader::url_stream_target(handle, uc)?;
+                    let event = UrlStreamEvent::Data {
+                        data: chunk,
+                        bytes_loaded,
+                        bytes_total,
+                    };
+                    Loader::url_stream_event(uc, target, event);
+                    Ok(())
+                })?;
+            }
 
+            player.lock().unwrap().update(|uc| {
+                let target = Loader::url_stream_target(handle, uc)?;
+                Loader::url_stream_event(uc, target, UrlStreamEvent::Complete);
                 Ok(())
             })
         })
     }
 
+    /// Returns the `URLStream` a loader downloads into.
+    fn url_stream_target(
+        handle: Index,
+        uc: &mut UpdateContext<'_, 'gc>,
+    ) -> Result<Avm2Object<'gc>, Error> {
+        match uc.load_manager.get_loader(handle) {
+            Some(&Loader::UrlStream { target_object, .. }) => Ok(target_object),
+            None => Err(Error::Cancelled),
+            _ => Err(Error::NotUrlStreamLoader),
+        }
+    }
+
+    /// Reports something that happened to the download of a `URLStream`.
+    fn url_stream_event(
+        uc: &mut UpdateContext<'_, 'gc>,
+        target: Avm2Object<'gc>,
+        event: UrlStreamEvent,
+    ) {
+        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+        if let Err(e) = url_stream::dispatch_event(&mut activation, target, event) {
+            tracing::error!(
+                "Encountered AVM2 error when reporting URLStream event: {}",
+                e
+            );
+        }
+    }
+
     /// Creates a future for a Sound load call.
     fn sound_loader_avm1(
         &mut self,
@@ -1162,7 +1547,10 @@ impl<'gc> Loader<'gc> {
 
                 let success = data
                     .and_then(|data| {
-                        let handle = uc.audio.register_mp3(&data.body)?;
+                        let handle = uc.audio.register_mp3(&data.body).map_err(|e| {
+                            uc.compatibility.decoder_failed(MediaKind::Audio, &e);
+                            e
+                        })?;
                         sound_object.set_sound(uc.gc_context, Some(handle));
                         let duration = uc
                             .audio
@@ -1192,7 +1580,10 @@ impl<'gc> Loader<'gc> {
         })
     }
 
-    /// Creates a future for a LoadURLLoader load call.
+    /// Creates a future for a Sound load call.
+    ///
+    /// The MP3 is handed over to the audio backend as it downloads, so that it can be played
+    /// before the download completes.
     fn sound_loader_avm2(
         &mut self,
         player: Weak<Mutex<Player>>,
@@ -1210,77 +1601,338 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
-            let fetch = player.lock().unwrap().navigator().fetch(request);
+            let fetch = player
+                .lock()
+                .unwrap()
+                .navigator()
+                .fetch_streaming(request, 0);
             let response = fetch.await;
 
-            player.lock().unwrap().update(|uc| {
-                let loader = uc.load_manager.get_loader(handle);
-                let sound_object = match loader {
-                    Some(&Loader::SoundAvm2 { target_object, .. }) => target_object,
-                    None => return Err(Error::Cancelled),
-                    _ => return Err(Error::NotSoundLoader),
-                };
-
+            let response = player.lock().unwrap().update(|uc| {
+                let sound_object = Loader::sound_avm2_target(handle, uc)?;
                 match response {
                     Ok(response) => {
-                        let handle = uc.audio.register_mp3(&response.body)?;
-                        sound_object.set_sound(uc.gc_context, handle);
+                        let bytes_total = response.total_length.unwrap_or_default() as u32;
+                        sound_object.set_load_progress(uc.gc_context, 0, bytes_total);
 
-                        // FIXME - the "open" event should be fired earlier, and not fired in case of ioerror.
-                        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
-                        let open_evt =
-                            Avm2EventObject::bare_default_event(&mut activation.context, "open");
-                        if let Err(e) =
-                            Avm2::dispatch_event(&mut activation.context, open_evt, sound_object)
-                        {
+                        let open_evt = Avm2EventObject::bare_default_event(uc, "open");
+                        if let Err(e) = Avm2::dispatch_event(uc, open_evt, sound_object.into()) {
                             tracing::error!(
                                 "Encountered AVM2 error when broadcasting `open` event: {}",
                                 e
                             );
                         }
+                        Ok(Some(response))
+                    }
+                    Err(_) => {
+                        Loader::sound_avm2_io_error(uc, sound_object)?;
+                        Ok(None)
+                    }
+                }
+            })?;
+            let response = match response {
+                Some(response) => response,
+                None => return Ok(()),
+            };
 
-                        let complete_evt = Avm2EventObject::bare_default_event(
-                            &mut activation.context,
-                            "complete",
-                        );
-                        if let Err(e) = Avm2::dispatch_event(uc, complete_evt, sound_object) {
-                            tracing::error!(
-                                "Encountered AVM2 error when broadcasting `complete` event: {}",
-                                e
-                            );
-                        }
+            let mut download = Mp3Download::new(response.total_length);
+            let mut body = response.body;
+            while let Some(chunk) = body.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        tracing::warn!("Sound download was interrupted: {}", e);
+                        return player.lock().unwrap().update(|uc| {
+                            let sound_object = Loader::sound_avm2_target(handle, uc)?;
+                            Loader::sound_avm2_io_error(uc, sound_object)
+                        });
                     }
-                    Err(_err) => {
-                        // FIXME: Match the exact error message generated by Flash.
-                        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
-                        let io_error_evt_cls = activation.avm2().classes().ioerrorevent;
-                        let io_error_evt = io_error_evt_cls
-                            .construct(
-                                &mut activation,
-                                &[
-                                    "ioError".into(),
-                                    false.into(),
-                                    false.into(),
-                                    "Error #2032: Stream Error".into(),
-                                    2032.into(),
-                                ],
-                            )
-                            .map_err(|e| Error::Avm2Error(e.to_string()))?;
+                };
 
-                        if let Err(e) = Avm2::dispatch_event(uc, io_error_evt, sound_object) {
-                            tracing::error!(
-                                "Encountered AVM2 error when broadcasting `ioError` event: {}",
-                                e
-                            );
-                        }
+                player.lock().unwrap().update(|uc| {
+                    let sound_object = Loader::sound_avm2_target(handle, uc)?;
+                    download.receive(uc, sound_object, &chunk, false)
+                })?;
+            }
+
+            player.lock().unwrap().update(|uc| {
+                let sound_object = Loader::sound_avm2_target(handle, uc)?;
+                download.receive(uc, sound_object, &[], true)?;
+
+                let complete_evt = Avm2EventObject::bare_default_event(uc, "complete");
+                if let Err(e) = Avm2::dispatch_event(uc, complete_evt, sound_object.into()) {
+                    tracing::error!(
+                        "Encountered AVM2 error when broadcasting `complete` event: {}",
+                        e
+                    );
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Returns the `Sound` an AVM2 sound loader loads into.
+    fn sound_avm2_target(
+        handle: Index,
+        uc: &mut UpdateContext<'_, 'gc>,
+    ) -> Result<Avm2SoundObject<'gc>, Error> {
+        match uc.load_manager.get_loader(handle) {
+            Some(&Loader::SoundAvm2 { target_object, .. }) => {
+                target_object.as_sound_object().ok_or(Error::NotSoundLoader)
+            }
+            None => Err(Error::Cancelled),
+            _ => Err(Error::NotSoundLoader),
+        }
+    }
+
+    /// Dispatches the `ioError` event of a `Sound` whose file couldn't be downloaded.
+    fn sound_avm2_io_error(
+        uc: &mut UpdateContext<'_, 'gc>,
+        sound_object: Avm2SoundObject<'gc>,
+    ) -> Result<(), Error> {
+        // FIXME: Match the exact error message generated by Flash.
+        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+        let io_error_evt_cls = activation.avm2().classes().ioerrorevent;
+        let io_error_evt = io_error_evt_cls
+            .construct(
+                &mut activation,
+                &[
+                    "ioError".into(),
+                    false.into(),
+                    false.into(),
+                    "Error #2032: Stream Error".into(),
+                    2032.into(),
+                ],
+            )
+            .map_err(|e| Error::Avm2Error(e.to_string()))?;
+
+        if let Err(e) = Avm2::dispatch_event(uc, io_error_evt, sound_object.into()) {
+            tracing::error!(
+                "Encountered AVM2 error when broadcasting `ioError` event: {}",
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// Creates a future for a NetStream load call.
+    ///
+    /// The file data is handed over to the stream as it arrives.
+    fn netstream_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        load_id: u32,
+        request: Request,
+        offset: usize,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::NetStream { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotNetStreamLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let fetch = player
+                .lock()
+                .unwrap()
+                .navigator()
+                .fetch_streaming(request, offset);
+            let response = fetch.await;
+
+            let response = player.lock().unwrap().update(|uc| {
+                let stream = match Loader::netstream_target(handle, load_id, uc)? {
+                    Some(stream) => stream,
+                    None => return Ok(None),
+                };
+
+                match response {
+                    Ok(response) => {
+                        stream.set_bytes_total(uc, response.total_length);
+                        Ok(Some(response))
                     }
+                    Err(_) => {
+                        stream.report_not_found(uc);
+                        Ok(None)
+                    }
+                }
+            })?;
+            let response = match response {
+                Some(response) => response,
+                None => return Ok(()),
+            };
+
+            // Backends without range requests start at the beginning of the
+            // file, so the data before `offset` is skipped here instead.
+            let mut skip = offset.saturating_sub(response.offset);
+            let mut body = response.body;
+            while let Some(chunk) = body.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        tracing::warn!("NetStream download was interrupted: {}", e);
+                        break;
+                    }
+                };
+                let skipped = skip.min(chunk.len());
+                skip -= skipped;
+                if skipped == chunk.len() {
+                    continue;
+                }
+
+                let loading =
+                    player.lock().unwrap().update(|uc| {
+                        match Loader::netstream_target(handle, load_id, uc)? {
+                            Some(stream) => {
+                                stream.load_buffer(uc, &chunk[skipped..]);
+                                Ok(true)
+                            }
+                            None => Ok(false),
+                        }
+                    })?;
+                if !loading {
+                    return Ok(());
                 }
+            }
 
+            player.lock().unwrap().update(|uc| {
+                if let Some(stream) = Loader::netstream_target(handle, load_id, uc)? {
+                    stream.finish_loading(uc);
+                }
                 Ok(())
             })
         })
     }
 
+    /// Returns the stream a NetStream loader loads into, or `None` if the
+    /// stream started another load since.
+    fn netstream_target(
+        handle: Index,
+        load_id: u32,
+        uc: &mut UpdateContext<'_, 'gc>,
+    ) -> Result<Option<NetStream<'gc>>, Error> {
+        let stream = match uc.load_manager.get_loader(handle) {
+            Some(&Loader::NetStream { target_stream, .. }) => target_stream,
+            None => return Err(Error::Cancelled),
+            _ => return Err(Error::NotNetStreamLoader),
+        };
+        Ok(Some(stream).filter(|stream| stream.is_loading(load_id)))
+    }
+
+    /// Creates a future running an RTMP session over a socket.
+    fn rtmp_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        url: RtmpUrl,
+        requests: UnboundedReceiver<RtmpRequest>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Rtmp { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
+            _ => return Box::pin(async { Err(Error::NotRtmpLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        enum Input {
+            Received(Vec<u8>),
+            Disconnected,
+            Request(RtmpRequest),
+        }
+
+        Box::pin(async move {
+            let connect = player
+                .lock()
+                .unwrap()
+                .navigator()
+                .connect_socket(&url.host, url.port);
+            let socket = match connect.await {
+                Ok(socket) => socket,
+                Err(e) => {
+                    tracing::error!("Unable to connect to RTMP server: {}", e);
+                    return Loader::rtmp_closed(&player, handle, false);
+                }
+            };
+
+            let incoming = socket
+                .incoming
+                .map(Input::Received)
+                .chain(stream::iter([Input::Disconnected]));
+            // A dropped connection closes the session like an explicit close.
+            let requests = requests
+                .map(Input::Request)
+                .chain(stream::iter([Input::Request(RtmpRequest::Close)]));
+            let mut inputs = stream::select(incoming, requests);
+
+            let mut session = RtmpSession::new(url);
+            let _ = socket.outgoing.unbounded_send(session.start());
+            while let Some(input) = inputs.next().await {
+                let events = match input {
+                    Input::Received(data) => match session.receive(&data) {
+                        Ok(events) => events,
+                        Err(e) => {
+                            tracing::error!("RTMP connection failed: {}", e);
+                            break;
+                        }
+                    },
+                    Input::Disconnected => break,
+                    // The connection was closed by ActionScript.
+                    Input::Request(RtmpRequest::Close) => return Ok(()),
+                    Input::Request(request) => {
+                        session.request(request);
+                        Vec::new()
+                    }
+                };
+
+                let output = session.take_output();
+                if !output.is_empty() && socket.outgoing.unbounded_send(output).is_err() {
+                    break;
+                }
+                if events.is_empty() {
+                    continue;
+                }
+
+                player.lock().unwrap().update(|uc| {
+                    let connection = match uc.load_manager.get_loader(handle) {
+                        Some(&Loader::Rtmp {
+                            target_connection, ..
+                        }) => target_connection,
+                        None => return Err(Error::Cancelled),
+                        _ => return Err(Error::NotRtmpLoader),
+                    };
+
+                    for event in events {
+                        connection.handle_event(uc, event);
+                    }
+                    Ok(())
+                })?;
+            }
+
+            Loader::rtmp_closed(&player, handle, true)
+        })
+    }
+
+    /// Reports the end of an RTMP connection to its `NetConnection`.
+    fn rtmp_closed(player: &Mutex<Player>, handle: Handle, was_opened: bool) -> Result<(), Error> {
+        player.lock().unwrap().update(|uc| {
+            let connection = match uc.load_manager.get_loader(handle) {
+                Some(&Loader::Rtmp {
+                    target_connection, ..
+                }) => target_connection,
+                None => return Err(Error::Cancelled),
+                _ => return Err(Error::NotRtmpLoader),
+            };
+
+            connection.report_closed(uc, was_opened);
+            Ok(())
+        })
+    }
+
     /// Report a movie loader start event to script code.
     fn movie_loader_start(handle: Index, uc: &mut UpdateContext<'_, 'gc>) -> Result<(), Error> {
         let me = uc.load_manager.get_loader_mut(handle);
@@ -1452,6 +2104,12 @@ impl<'gc> Loader<'gc> {
                     let bitmap_obj = Bitmap::new(uc, 0, bitmap)?;
 
                     if let Some(mc) = clip.as_movie_clip() {
+                        if !matches!(
+                            event_handler,
+                            Some(MovieLoaderEventHandler::Avm2LoaderInfo(_))
+                        ) {
+                            mc.set_loaded_image(uc.gc_context);
+                        }
                         mc.replace_at_depth(uc, bitmap_obj.into(), 1);
                     }
                 }
@@ -1690,3 +2348,164 @@ impl<'gc> Loader<'gc> {
         }
     }
 }
+
+/// An MP3 being downloaded by `Sound.load`.
+struct Mp3Download {
+    /// The size of the whole file, if known.
+    bytes_total: Option<usize>,
+
+    /// The number of bytes received so far.
+    bytes_loaded: usize,
+
+    /// The data that wasn't handed over to the audio backend yet.
+    pending: Vec<u8>,
+
+    /// The last bytes received, which may be an ID3v1 tag once the download completes.
+    tail: Vec<u8>,
+
+    /// The sound registered with the audio backend, once there was enough data for it.
+    sound: Option<SoundHandle>,
+
+    /// Whether the ID3v2 tag at the start of the file was read, or found to be missing.
+    read_id3v2: bool,
+
+    /// Whether the ID3 metadata was already given to the `Sound`.
+    has_id3: bool,
+}
+
+impl Mp3Download {
+    fn new(bytes_total: Option<usize>) -> Self {
+        Self {
+            bytes_total,
+            bytes_loaded: 0,
+            pending: Vec::new(),
+            tail: Vec::new(),
+            sound: None,
+            read_id3v2: false,
+            has_id3: false,
+        }
+    }
+
+    /// Hands over received data to the audio backend, and dispatches the `id3` and `progress`
+    /// events of the `Sound`.
+    ///
+    /// `is_complete` is set once the whole file was received.
+    fn receive<'gc>(
+        &mut self,
+        uc: &mut UpdateContext<'_, 'gc>,
+        sound_object: Avm2SoundObject<'gc>,
+        data: &[u8],
+        is_complete: bool,
+    ) -> Result<(), Error> {
+        self.bytes_loaded += data.len();
+        self.pending.extend_from_slice(data);
+        self.tail.extend_from_slice(data);
+        if self.tail.len() > ID3V1_LEN {
+            self.tail.drain(..self.tail.len() - ID3V1_LEN);
+        }
+
+        // The ID3v2 tag is at the start of the file, so nothing is handed over before it's read.
+        let mut id3 = None;
+        if !self.read_id3v2 {
+            match id3::id3v2_len(&self.pending) {
+                Some(0) => self.read_id3v2 = true,
+                Some(len) if len <= self.pending.len() => {
+                    id3 = Some(id3::parse_id3v2(&self.pending[..len]));
+                    self.read_id3v2 = true;
+                }
+                _ => self.read_id3v2 = is_complete,
+            }
+        }
+        if is_complete && !self.has_id3 && id3.is_none() {
+            id3 = id3::parse_id3v1(&self.tail);
+        }
+
+        if self.read_id3v2 {
+            match self.sound {
+                Some(sound) => {
+                    uc.audio.append_mp3_data(sound, &self.pending, is_complete);
+                    self.pending.clear();
+                }
+                None => match uc.audio.register_downloading_mp3(&self.pending) {
+                    Ok(sound) => {
+                        if is_complete {
+                            uc.audio.append_mp3_data(sound, &[], true);
+                        }
+                        self.pending.clear();
+                        self.sound = Some(sound);
+                        sound_object.set_sound(uc.gc_context, sound);
+                        sound_object.start_queued_plays(uc);
+                    }
+                    Err(e) if is_complete => {
+                        uc.compatibility.decoder_failed(MediaKind::Audio, &e);
+                        return Err(Error::InvalidSound(e));
+                    }
+                    // Wait for the data to contain a whole MP3 frame.
+                    Err(_) => {}
+                },
+            }
+        }
+
+        let bytes_total = match self.bytes_total {
+            Some(bytes_total) if !is_complete => bytes_total,
+            _ => self.bytes_loaded,
+        };
+        sound_object.set_load_progress(uc.gc_context, self.bytes_loaded as u32, bytes_total as u32);
+
+        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+        if let Some(properties) = id3 {
+            self.has_id3 = true;
+            let mut id3_info = activation
+                .avm2()
+                .classes()
+                .id3info
+                .construct(&mut activation, &[])
+                .map_err(|e| Error::Avm2Error(e.to_string()))?;
+            for (name, value) in properties {
+                let name = AvmString::new_utf8(activation.context.gc_context, name);
+                let value = AvmString::new_utf8(activation.context.gc_context, value);
+                id3_info
+                    .set_property(&Avm2Multiname::public(name), value.into(), &mut activation)
+                    .map_err(|e| Error::Avm2Error(e.to_string()))?;
+            }
+            sound_object.set_id3(activation.context.gc_context, id3_info);
+
+            let id3_evt = Avm2EventObject::bare_default_event(&mut activation.context, "id3");
+            if let Err(e) =
+                Avm2::dispatch_event(&mut activation.context, id3_evt, sound_object.into())
+            {
+                tracing::error!(
+                    "Encountered AVM2 error when broadcasting `id3` event: {}",
+                    e
+                );
+            }
+        }
+
+        if !data.is_empty() {
+            let progress_evt = activation
+                .avm2()
+                .classes()
+                .progressevent
+                .construct(
+                    &mut activation,
+                    &[
+                        "progress".into(),
+                        false.into(),
+                        false.into(),
+                        self.bytes_loaded.into(),
+                        bytes_total.into(),
+                    ],
+                )
+                .map_err(|e| Error::Avm2Error(e.to_string()))?;
+
+            if let Err(e) = Avm2::dispatch_event(uc, progress_evt, sound_object.into()) {
+                tracing::error!(
+                    "Encountered AVM2 error when broadcasting `progress` event: {}",
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
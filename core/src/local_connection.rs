@@ -0,0 +1,275 @@
+//! Connections opened with `LocalConnection`, and the delivery of the messages sent to them.
+
+use crate::avm1::{Activation as Avm1Activation, ActivationIdentifier, Object as Avm1Object};
+use crate::avm2::globals::flash::net::local_connection as avm2_local_connection;
+use crate::avm2::{Activation as Avm2Activation, Object as Avm2Object};
+use crate::backend::local_connection::LocalConnectionMessage;
+use crate::context::UpdateContext;
+use flash_lso::types::{AMFVersion, Element, Lso, Value as AmfValue};
+use gc_arena::Collect;
+use std::collections::HashMap;
+
+/// The largest size of the encoded arguments of a message.
+pub const MAX_ARGUMENTS_SIZE: usize = 40 * 1024;
+
+/// The method names that messages may not call, as they belong to `LocalConnection` itself.
+pub const RESERVED_METHOD_NAMES: &[&str] = &[
+    "send",
+    "connect",
+    "close",
+    "allowDomain",
+    "allowInsecureDomain",
+    "client",
+    "domain",
+];
+
+/// A `LocalConnection` object of either AVM.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum LocalConnectionObject<'gc> {
+    Avm1(Avm1Object<'gc>),
+    Avm2(Avm2Object<'gc>),
+}
+
+impl<'gc> LocalConnectionObject<'gc> {
+    fn ptr_eq(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Avm1(a), Self::Avm1(b)) => Avm1Object::ptr_eq(a, b),
+            (Self::Avm2(a), Self::Avm2(b)) => Avm2Object::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Keeps track of the connections opened by the movie, and of the sent messages whose status
+/// has yet to be reported.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct LocalConnectionManager<'gc> {
+    /// The object connected with each full connection name.
+    connections: HashMap<String, LocalConnectionObject<'gc>>,
+
+    /// The senders of the messages sent since the last update, and whether each message
+    /// reached a connection.
+    statuses: Vec<(LocalConnectionObject<'gc>, bool)>,
+}
+
+impl<'gc> LocalConnectionManager<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the given object is connected.
+    pub fn is_connected(&self, object: LocalConnectionObject<'gc>) -> bool {
+        self.connections
+            .values()
+            .any(|connected| connected.ptr_eq(object))
+    }
+
+    /// Connects an object with a full connection name, as made by `connect_name`.
+    ///
+    /// Returns `false` if the name is already in use, by this player or another one.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc>,
+        name: String,
+        object: LocalConnectionObject<'gc>,
+    ) -> bool {
+        if !context.local_connection.connect(&name) {
+            return false;
+        }
+        context
+            .local_connection_manager
+            .connections
+            .insert(name, object);
+        true
+    }
+
+    /// Closes the connection of an object.
+    ///
+    /// Returns `false` if the object wasn't connected.
+    pub fn close(context: &mut UpdateContext<'_, 'gc>, object: LocalConnectionObject<'gc>) -> bool {
+        let name = context
+            .local_connection_manager
+            .connections
+            .iter()
+            .find(|(_, connected)| connected.ptr_eq(object))
+            .map(|(name, _)| name.clone());
+        match name {
+            Some(name) => {
+                context.local_connection_manager.connections.remove(&name);
+                context.local_connection.close(&name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sends a message. Its status is reported to the sender on the next update.
+    pub fn send(
+        context: &mut UpdateContext<'_, 'gc>,
+        sender: LocalConnectionObject<'gc>,
+        message: LocalConnectionMessage,
+    ) {
+        let delivered = context.local_connection.send(message);
+        context
+            .local_connection_manager
+            .statuses
+            .push((sender, delivered));
+    }
+
+    /// Delivers the messages received since the last update, then reports the status of the
+    /// messages that were sent.
+    pub fn update(context: &mut UpdateContext<'_, 'gc>) {
+        for message in context.local_connection.receive() {
+            let receiver = context
+                .local_connection_manager
+                .connections
+                .get(&message.connection_name)
+                .copied();
+            match receiver {
+                Some(LocalConnectionObject::Avm1(object)) => {
+                    let mut activation = Avm1Activation::from_stub(
+                        context.reborrow(),
+                        ActivationIdentifier::root("[LocalConnection]"),
+                    );
+                    if let Err(e) = crate::avm1::receive_local_connection_message(
+                        &mut activation,
+                        object,
+                        message,
+                    ) {
+                        tracing::error!("Error receiving AVM1 LocalConnection message: {:?}", e);
+                    }
+                }
+                Some(LocalConnectionObject::Avm2(object)) => {
+                    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                    if let Err(e) =
+                        avm2_local_connection::receive_message(&mut activation, object, message)
+                    {
+                        tracing::error!("Error receiving AVM2 LocalConnection message: {:?}", e);
+                    }
+                }
+                // The connection was closed while the message was on its way.
+                None => {}
+            }
+        }
+
+        let statuses = std::mem::take(&mut context.local_connection_manager.statuses);
+        for (sender, delivered) in statuses {
+            match sender {
+                LocalConnectionObject::Avm1(object) => {
+                    let mut activation = Avm1Activation::from_stub(
+                        context.reborrow(),
+                        ActivationIdentifier::root("[LocalConnection]"),
+                    );
+                    crate::avm1::dispatch_local_connection_status(
+                        &mut activation,
+                        object,
+                        delivered,
+                    );
+                }
+                LocalConnectionObject::Avm2(object) => {
+                    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                    if let Err(e) =
+                        avm2_local_connection::dispatch_status(&mut activation, object, delivered)
+                    {
+                        tracing::error!("Error dispatching LocalConnection status: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The domain of a movie, as reported by `LocalConnection.domain`.
+///
+/// Local movies, and movies without a URL, are in the `localhost` domain. Returns `None` if the
+/// URL can't be parsed.
+pub fn movie_domain(url: Option<&str>) -> Option<String> {
+    let url = match url {
+        Some(url) => url,
+        None => return Some("localhost".to_string()),
+    };
+
+    let url = url::Url::parse(url).ok()?;
+    match url.domain() {
+        Some(domain) if url.scheme() != "file" => Some(domain.to_string()),
+        _ => Some("localhost".to_string()),
+    }
+}
+
+/// The full name of a connection opened with `LocalConnection.connect`.
+///
+/// Names starting with an underscore are shared by all domains; others are prefixed with the
+/// domain of the movie.
+pub fn connect_name(domain: &str, name: &str) -> String {
+    if name.starts_with('_') {
+        name.to_string()
+    } else {
+        format!("{domain}:{name}")
+    }
+}
+
+/// The full name of the connection that `LocalConnection.send` sends to.
+///
+/// Like `connect_name`, except that names which already include a domain are kept as they are.
+pub fn send_name(domain: &str, name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        connect_name(domain, name)
+    }
+}
+
+/// Encodes the arguments of a message as AMF0.
+pub fn encode_arguments(arguments: Vec<AmfValue>) -> Vec<u8> {
+    let elements = arguments
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| Element::new(i.to_string(), value))
+        .collect();
+    let mut lso = Lso::new(elements, "", AMFVersion::AMF0);
+    flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default()
+}
+
+/// Decodes the arguments of a message encoded with `encode_arguments`.
+pub fn decode_arguments(bytes: &[u8]) -> Vec<AmfValue> {
+    match flash_lso::read::Reader::default().parse(bytes) {
+        Ok(lso) => lso
+            .body
+            .iter()
+            .map(|element| element.value().clone())
+            .collect(),
+        Err(_) => {
+            tracing::warn!("Unable to decode the arguments of a LocalConnection message");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_names() {
+        assert_eq!(connect_name("example.com", "game"), "example.com:game");
+        assert_eq!(connect_name("example.com", "_game"), "_game");
+        assert_eq!(send_name("example.com", "game"), "example.com:game");
+        assert_eq!(send_name("example.com", "other.com:game"), "other.com:game");
+        assert_eq!(send_name("example.com", "_game"), "_game");
+    }
+
+    #[test]
+    fn movie_domains() {
+        assert_eq!(movie_domain(None).as_deref(), Some("localhost"));
+        assert_eq!(
+            movie_domain(Some("file:///C:/games/game.swf")).as_deref(),
+            Some("localhost")
+        );
+        assert_eq!(
+            movie_domain(Some("https://www.example.com/game.swf")).as_deref(),
+            Some("www.example.com")
+        );
+        assert_eq!(movie_domain(Some("not a url")), None);
+    }
+}
@@ -0,0 +1,672 @@
+//! Local storage bookkeeping shared by the AVM1 and AVM2 `SharedObject` implementations.
+
+use crate::backend::storage::StorageBackend;
+use flash_lso::types::{AMFVersion, Element, Lso, Value as AmfValue};
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+
+/// The amount of storage a domain may use before the user is asked for more.
+/// This matches the default setting of Flash Player.
+pub const DEFAULT_QUOTA: usize = 100 * 1024;
+
+/// The quota granted for requests larger than every limited tier.
+pub const UNLIMITED_QUOTA: usize = usize::MAX;
+
+/// The quota sizes offered by the Flash Player settings dialog.
+/// A request is always rounded up to the next tier.
+const QUOTA_TIERS: [usize; 5] = [
+    10 * 1024,
+    100 * 1024,
+    1024 * 1024,
+    10 * 1024 * 1024,
+    UNLIMITED_QUOTA,
+];
+
+/// The result of asking for room to store a shared object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushStatus {
+    /// The data fits into the quota of the domain and may be written immediately.
+    Flushed,
+
+    /// The data does not fit; the user has to be asked for a larger quota first.
+    Pending,
+}
+
+/// A request for a larger quota that is waiting for the user's response.
+#[derive(Debug)]
+pub struct QuotaRequest {
+    /// The domain the request was made for, e.g. `localhost` or `example.com`.
+    pub domain: String,
+
+    /// The number of bytes that were requested.
+    pub requested: usize,
+
+    /// The full names of all shared objects whose flush is waiting on this request.
+    pub shared_objects: Vec<String>,
+}
+
+impl QuotaRequest {
+    /// Returns the quota tier that granting this request results in.
+    pub fn tier(&self) -> usize {
+        QUOTA_TIERS
+            .iter()
+            .copied()
+            .find(|tier| *tier >= self.requested)
+            .unwrap_or(UNLIMITED_QUOTA)
+    }
+}
+
+/// Tracks the storage quota granted to each domain.
+///
+/// Granted quotas are persisted via the storage backend, so the user is only asked once per
+/// domain (unless the movie asks for even more space later).
+#[derive(Default)]
+pub struct StorageQuota {
+    /// The requests waiting for the user's response, at most one per domain.
+    /// Only the first one is shown to the user; the others are queued behind it.
+    pending: VecDeque<QuotaRequest>,
+}
+
+impl StorageQuota {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the domain part of a full shared object name (`example.com/path/game.swf/name`).
+    pub fn domain_of(full_name: &str) -> &str {
+        full_name.split('/').next().unwrap_or_default()
+    }
+
+    fn settings_key(domain: &str) -> String {
+        format!("#settings/{domain}")
+    }
+
+    /// Returns the number of bytes the given domain may currently use.
+    pub fn quota(&self, storage: &dyn StorageBackend, domain: &str) -> usize {
+        storage
+            .get(&Self::settings_key(domain))
+            .and_then(|data| String::from_utf8(data).ok())
+            .and_then(|data| data.trim().parse().ok())
+            .unwrap_or(DEFAULT_QUOTA)
+    }
+
+    /// Checks whether `requested` bytes may be stored for the given shared object.
+    ///
+    /// If they may not, the shared object is attached to the pending quota request of its domain.
+    /// `on_new_request` is only invoked if no request was pending before, so that the user is
+    /// only asked one question at a time. Requests of other domains are queued behind it, see
+    /// [`StorageQuota::current_request`].
+    pub fn request(
+        &mut self,
+        storage: &dyn StorageBackend,
+        full_name: &str,
+        requested: usize,
+        on_new_request: impl FnOnce(&QuotaRequest),
+    ) -> FlushStatus {
+        let domain = Self::domain_of(full_name);
+        if requested <= self.quota(storage, domain) {
+            return FlushStatus::Flushed;
+        }
+
+        match self
+            .pending
+            .iter_mut()
+            .find(|pending| pending.domain == domain)
+        {
+            Some(pending) => {
+                // Flash only asks once per domain; later requests piggyback on the pending one.
+                pending.requested = pending.requested.max(requested);
+                if !pending.shared_objects.iter().any(|name| name == full_name) {
+                    pending.shared_objects.push(full_name.to_string());
+                }
+            }
+            None => {
+                let request = QuotaRequest {
+                    domain: domain.to_string(),
+                    requested,
+                    shared_objects: vec![full_name.to_string()],
+                };
+                if self.pending.is_empty() {
+                    on_new_request(&request);
+                }
+                self.pending.push_back(request);
+            }
+        }
+
+        FlushStatus::Pending
+    }
+
+    /// Resolves the quota request that is currently shown to the user.
+    ///
+    /// If the request was granted, the new quota is persisted for its domain. The request is
+    /// returned so that the waiting shared objects can be flushed and notified; afterwards, the
+    /// next queued request (if any) becomes [`StorageQuota::current_request`].
+    pub fn resolve(
+        &mut self,
+        storage: &mut dyn StorageBackend,
+        granted: bool,
+    ) -> Option<QuotaRequest> {
+        let request = self.pending.pop_front()?;
+        if granted {
+            let key = Self::settings_key(&request.domain);
+            if !storage.put(&key, request.tier().to_string().as_bytes()) {
+                tracing::warn!("Unable to persist storage quota for {}", request.domain);
+            }
+        }
+        Some(request)
+    }
+
+    /// Returns the request that is currently shown to the user.
+    pub fn current_request(&self) -> Option<&QuotaRequest> {
+        self.pending.front()
+    }
+
+    pub fn has_pending_request(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+/// Characters that may not appear in the name of a local shared object.
+const INVALID_NAME_CHARS: &str = "~%&\\;:\"',<>?# ";
+
+/// Splits a URL path into its normalized segments.
+///
+/// Empty segments are dropped (so `/a//b/` and `a/b` are equivalent), and each segment is
+/// percent-decoded, so that `my%20game.swf` and `my game.swf` map to the same storage.
+fn path_segments(path: &str) -> Vec<Cow<'_, str>> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let decoded = percent_decode_str(segment).decode_utf8_lossy();
+            // A decoded separator would change the directory structure; keep those encoded.
+            if decoded.contains(['/', '\\']) {
+                Cow::Borrowed(segment)
+            } else {
+                decoded
+            }
+        })
+        .collect()
+}
+
+/// Determines the storage path of the local shared object `name`, as created by
+/// `SharedObject.getLocal(name, local_path, secure)` in the movie loaded from `movie_url`.
+///
+/// The path is `host/path/to/game.swf/name`, and follows the normalization rules of Flash Player,
+/// so that a movie finds its data again when it is loaded through an equivalent URL:
+/// * The query string, fragment, port and scheme of the movie URL are ignored.
+/// * The host is case-insensitive; local files always use the `localhost` host, and the drive
+///   letter of Windows paths is dropped.
+/// * Duplicate, leading and trailing slashes are ignored, and path segments are percent-decoded.
+/// * `local_path` may scope the shared object to any parent directory of the movie (or `/` for
+///   the whole domain), but must be a prefix of the movie path.
+/// * `secure` shared objects may only be used by movies served over HTTPS.
+///
+/// Returns `None` if the shared object may not be accessed, in which case `getLocal` returns
+/// `null`.
+pub fn shared_object_path(
+    movie_url: Option<&str>,
+    name: &str,
+    local_path: Option<&str>,
+    secure: bool,
+) -> Option<String> {
+    // TODO: It appears that Flash does some kind of escaping here:
+    // the name "foo\uD800" correspond to a file named "fooE#FB#FB#D.sol".
+    if name.contains(|c| INVALID_NAME_CHARS.contains(c)) {
+        tracing::error!("SharedObject.getLocal: Invalid character in name");
+        return None;
+    }
+
+    let movie_url = match movie_url {
+        Some(url) => match url::Url::parse(url) {
+            Ok(url) => url,
+            Err(_) => {
+                tracing::error!("SharedObject.getLocal: Unable to parse movie URL");
+                return None;
+            }
+        },
+        // No URL (loading local data). Use a dummy URL to allow SharedObjects to work.
+        None => url::Url::parse("file://localhost").unwrap(),
+    };
+
+    // Secure parameter disallows using the shared object from non-HTTPS.
+    if secure && movie_url.scheme() != "https" {
+        tracing::warn!(
+            "SharedObject.getLocal: Tried to load a secure shared object from non-HTTPS origin"
+        );
+        return None;
+    }
+
+    // Shared objects are sandboxed per-domain.
+    // By default, they are keyed based on the SWF URL, but the `localPath` parameter can modify this path.
+    let mut movie_path = path_segments(movie_url.path());
+    let movie_host = if movie_url.scheme() == "file" {
+        // Remove drive letter on Windows (TODO: move this logic into DiskStorageBackend?)
+        if let Some(drive) = movie_path.first() {
+            if let [letter, b':' | b'|'] = drive.as_bytes() {
+                if letter.is_ascii_alphabetic() {
+                    movie_path.remove(0);
+                }
+            }
+        }
+        "localhost"
+    } else {
+        // `url` already lowercases the host; a trailing dot names the same host.
+        let host = movie_url.host_str().unwrap_or_default();
+        host.strip_suffix('.').unwrap_or(host)
+    };
+
+    let path = match local_path {
+        // Empty local path always fails.
+        Some("") => return None,
+        Some(local_path) => {
+            let local_path = path_segments(local_path);
+            // Verify that local_path is a prefix of the SWF path.
+            if !movie_path.starts_with(&local_path) {
+                tracing::warn!(
+                    "SharedObject.getLocal: localPath parameter does not match SWF path"
+                );
+                return None;
+            }
+            local_path
+        }
+        None => movie_path,
+    };
+
+    // Final SO path: foo.com/folder/game.swf/SOName
+    // SOName may be a path containing slashes. In this case, prefix with # to mimic Flash Player behavior.
+    let prefix = if name.contains('/') { "#" } else { "" };
+    let path = path.join("/");
+    let full_name = format!("{movie_host}/{path}/{prefix}{name}");
+
+    // Avoid any paths with `..` to prevent SWFs from crawling the file system on desktop.
+    // Flash will generally fail to save shared objects with a path component starting with `.`,
+    // so let's disallow them altogether.
+    if full_name.split('/').any(|s| s.starts_with('.')) {
+        tracing::error!("SharedObject.getLocal: Invalid path with .. segments");
+        return None;
+    }
+
+    Some(full_name)
+}
+
+/// Determines the storage path that versions of Ruffle before the normalization rules of
+/// `shared_object_path` used for the same shared object.
+///
+/// Those paths took the movie path as it was: percent-encoded, with duplicate slashes and the
+/// trailing dot of the host kept.
+fn legacy_shared_object_path(
+    movie_url: Option<&str>,
+    name: &str,
+    local_path: Option<&str>,
+) -> Option<String> {
+    let movie_url = match movie_url {
+        Some(url) => url::Url::parse(url).ok()?,
+        None => url::Url::parse("file://localhost").unwrap(),
+    };
+
+    let mut movie_path = movie_url.path();
+    movie_path = movie_path.strip_prefix('/').unwrap_or(movie_path);
+    movie_path = movie_path.strip_suffix('/').unwrap_or(movie_path);
+    let movie_host = if movie_url.scheme() == "file" {
+        if let [_, b':', b'/', ..] = movie_path.as_bytes() {
+            movie_path = &movie_path[3..];
+        }
+        "localhost"
+    } else {
+        movie_url.host_str().unwrap_or_default()
+    };
+
+    let path = match local_path {
+        Some(local_path) => {
+            let local_path = local_path.strip_suffix('/').unwrap_or(local_path);
+            let local_path = local_path.strip_prefix('/').unwrap_or(local_path);
+            let is_prefix = movie_path.starts_with(local_path)
+                && (local_path.is_empty()
+                    || movie_path.len() == local_path.len()
+                    || movie_path[local_path.len()..].starts_with('/'));
+            if !is_prefix {
+                return None;
+            }
+            local_path
+        }
+        None => movie_path,
+    };
+
+    let prefix = if name.contains('/') { "#" } else { "" };
+    let full_name = format!("{movie_host}/{path}/{prefix}{name}");
+    if full_name.split('/').any(|s| s.starts_with('.')) {
+        return None;
+    }
+    Some(full_name)
+}
+
+/// Reads the stored data of the shared object `full_name`, as returned by `shared_object_path`
+/// for the same arguments.
+///
+/// Data that an older version of Ruffle stored under the legacy path of the shared object is
+/// moved to `full_name` first, so that saves aren't lost to the normalization of paths.
+pub fn load_shared_object(
+    storage: &mut dyn StorageBackend,
+    full_name: &str,
+    movie_url: Option<&str>,
+    name: &str,
+    local_path: Option<&str>,
+) -> Option<Vec<u8>> {
+    if let Some(data) = storage.get(full_name) {
+        return Some(data);
+    }
+
+    let legacy_name = legacy_shared_object_path(movie_url, name, local_path)?;
+    if legacy_name == full_name {
+        return None;
+    }
+    let data = storage.get(&legacy_name)?;
+    if storage.put(full_name, &data) {
+        storage.remove_key(&legacy_name);
+    }
+    Some(data)
+}
+
+/// The bytes every `.sol` file starts with.
+const SOL_MAGIC: [u8; 2] = [0x00, 0xBF];
+
+/// The signature following the length of a `.sol` file.
+const SOL_SIGNATURE: [u8; 10] = *b"TCSO\x00\x04\x00\x00\x00\x00";
+
+/// The header of a `.sol` file, as written by Flash Player.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SolHeader {
+    /// The name of the shared object.
+    pub name: String,
+
+    /// The AMF version the properties of the shared object are encoded with; 0 or 3.
+    pub amf_version: u8,
+}
+
+/// The name stored in the header of the `.sol` file of a shared object, given its full name.
+pub fn sol_name(full_name: &str) -> &str {
+    full_name.rsplit('/').next().unwrap_or("<unknown>")
+}
+
+/// Splits a `.sol` file into its header and the encoded properties following it.
+///
+/// The properties are a sequence of names and values, each followed by a zero byte.
+pub fn parse_sol(bytes: &[u8]) -> Option<(SolHeader, &[u8])> {
+    let rest = bytes.strip_prefix(&SOL_MAGIC)?;
+    let rest = rest.get(4..)?.strip_prefix(&SOL_SIGNATURE)?;
+    let name_length = u16::from_be_bytes(rest.get(..2)?.try_into().ok()?) as usize;
+    let name = std::str::from_utf8(rest.get(2..2 + name_length)?).ok()?;
+    let rest = rest.get(2 + name_length..)?;
+    let amf_version = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?);
+    if amf_version != 0 && amf_version != 3 {
+        return None;
+    }
+
+    let header = SolHeader {
+        name: name.to_string(),
+        amf_version: amf_version as u8,
+    };
+    Some((header, &rest[4..]))
+}
+
+/// Builds a `.sol` file from a header and the encoded properties of the shared object.
+pub fn write_sol(header: &SolHeader, body: &[u8]) -> Vec<u8> {
+    let name = header.name.as_bytes();
+    let length = SOL_SIGNATURE.len() + 2 + name.len() + 4 + body.len();
+
+    let mut bytes = Vec::with_capacity(SOL_MAGIC.len() + 4 + length);
+    bytes.extend_from_slice(&SOL_MAGIC);
+    bytes.extend_from_slice(&(length as u32).to_be_bytes());
+    bytes.extend_from_slice(&SOL_SIGNATURE);
+    bytes.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(name);
+    bytes.extend_from_slice(&(header.amf_version as u32).to_be_bytes());
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+/// Converts a JSON value, as stored by old versions of Ruffle, into AMF.
+fn amf_from_json(value: &serde_json::Value) -> AmfValue {
+    match value {
+        serde_json::Value::Null => AmfValue::Null,
+        serde_json::Value::Bool(b) => AmfValue::Bool(*b),
+        serde_json::Value::Number(n) => AmfValue::Number(n.as_f64().unwrap_or(f64::NAN)),
+        serde_json::Value::String(s) => AmfValue::String(s.clone()),
+        serde_json::Value::Array(values) => {
+            let elements = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| Element::new(i.to_string(), amf_from_json(value)))
+                .collect();
+            AmfValue::ECMAArray(vec![], elements, values.len() as u32)
+        }
+        serde_json::Value::Object(properties) => AmfValue::Object(
+            properties
+                .iter()
+                .map(|(name, value)| Element::new(name.clone(), amf_from_json(value)))
+                .collect(),
+            None,
+        ),
+    }
+}
+
+/// Parses the stored data of the shared object with the given full name.
+///
+/// Besides `.sol` files, this accepts the JSON that versions of Ruffle before the switch to
+/// `.sol` files stored, so that those saves are migrated the next time they are flushed.
+pub fn read_lso(full_name: &str, bytes: &[u8]) -> Option<Lso> {
+    if let Ok(lso) = flash_lso::read::Reader::default().parse(bytes) {
+        return Some(lso);
+    }
+
+    match serde_json::from_slice(bytes) {
+        Ok(serde_json::Value::Object(properties)) => {
+            let elements = properties
+                .iter()
+                .map(|(name, value)| Element::new(name.clone(), amf_from_json(value)))
+                .collect();
+            Some(Lso::new(elements, sol_name(full_name), AMFVersion::AMF0))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::storage::MemoryStorageBackend;
+
+    #[test]
+    fn sol_header_round_trip() {
+        let header = SolHeader {
+            name: "save".to_string(),
+            amf_version: 3,
+        };
+        let bytes = write_sol(&header, &[1, 2, 3]);
+        assert_eq!(&bytes[..2], &SOL_MAGIC);
+        assert_eq!(
+            u32::from_be_bytes(bytes[2..6].try_into().unwrap()) as usize,
+            bytes.len() - 6
+        );
+        assert_eq!(parse_sol(&bytes), Some((header, &[1, 2, 3][..])));
+        assert_eq!(parse_sol(b"{\"score\": 1}"), None);
+    }
+
+    #[test]
+    fn legacy_json_is_migrated() {
+        let lso = read_lso(
+            "localhost/game.swf/save",
+            br#"{"score": 10, "names": ["a"]}"#,
+        )
+        .expect("JSON data should be migrated");
+        assert_eq!(lso.body.len(), 2);
+        assert_eq!(lso.body[0].name, "score");
+    }
+
+    #[test]
+    fn small_flush_fits_default_quota() {
+        let storage = MemoryStorageBackend::new();
+        let mut quota = StorageQuota::new();
+        let status = quota.request(&storage, "localhost/game.swf/save", 1024, |_| {
+            panic!("should not prompt")
+        });
+        assert_eq!(status, FlushStatus::Flushed);
+        assert!(!quota.has_pending_request());
+    }
+
+    #[test]
+    fn granted_request_raises_quota() {
+        let mut storage = MemoryStorageBackend::new();
+        let mut quota = StorageQuota::new();
+        let mut prompts = 0;
+        for name in ["example.com/a.swf/one", "example.com/a.swf/two"] {
+            let status = quota.request(&storage, name, 200 * 1024, |_| prompts += 1);
+            assert_eq!(status, FlushStatus::Pending);
+        }
+        assert_eq!(prompts, 1);
+
+        let request = quota.resolve(&mut storage, true).unwrap();
+        assert_eq!(request.shared_objects.len(), 2);
+        assert_eq!(quota.quota(&storage, "example.com"), 1024 * 1024);
+        assert_eq!(quota.quota(&storage, "localhost"), DEFAULT_QUOTA);
+    }
+
+    #[test]
+    fn denied_request_keeps_quota() {
+        let mut storage = MemoryStorageBackend::new();
+        let mut quota = StorageQuota::new();
+        quota.request(&storage, "example.com/a.swf/one", 200 * 1024, |_| ());
+        assert!(quota.resolve(&mut storage, false).is_some());
+        assert_eq!(quota.quota(&storage, "example.com"), DEFAULT_QUOTA);
+        assert!(quota.resolve(&mut storage, false).is_none());
+    }
+
+    #[test]
+    fn requests_of_other_domains_are_queued() {
+        let mut storage = MemoryStorageBackend::new();
+        let mut quota = StorageQuota::new();
+        let mut prompts = 0;
+        for name in ["example.com/a.swf/one", "other.com/b.swf/two"] {
+            let status = quota.request(&storage, name, 200 * 1024, |_| prompts += 1);
+            assert_eq!(status, FlushStatus::Pending);
+        }
+        assert_eq!(prompts, 1);
+
+        let request = quota.resolve(&mut storage, true).unwrap();
+        assert_eq!(request.shared_objects, ["example.com/a.swf/one"]);
+        assert_eq!(quota.quota(&storage, "other.com"), DEFAULT_QUOTA);
+        assert_eq!(quota.current_request().unwrap().domain, "other.com");
+
+        let request = quota.resolve(&mut storage, false).unwrap();
+        assert_eq!(request.shared_objects, ["other.com/b.swf/two"]);
+        assert_eq!(quota.quota(&storage, "other.com"), DEFAULT_QUOTA);
+        assert!(!quota.has_pending_request());
+    }
+
+    #[test]
+    fn huge_request_grants_unlimited_tier() {
+        let mut storage = MemoryStorageBackend::new();
+        let mut quota = StorageQuota::new();
+        quota.request(
+            &storage,
+            "example.com/a.swf/one",
+            50 * 1024 * 1024,
+            |request| assert_eq!(request.tier(), UNLIMITED_QUOTA),
+        );
+        quota.resolve(&mut storage, true).unwrap();
+        assert_eq!(quota.quota(&storage, "example.com"), UNLIMITED_QUOTA);
+    }
+
+    #[test]
+    fn equivalent_urls_share_storage() {
+        let expected = Some("example.com/games/my game.swf/save".to_string());
+        for url in [
+            "http://example.com/games/my%20game.swf",
+            "https://EXAMPLE.com:8080/games/my%20game.swf?v=2#top",
+            "http://example.com.//games//my game.swf",
+        ] {
+            assert_eq!(shared_object_path(Some(url), "save", None, false), expected);
+        }
+    }
+
+    #[test]
+    fn local_files_ignore_drive_letter() {
+        assert_eq!(
+            shared_object_path(Some("file:///C:/games/game.swf"), "save", None, false),
+            Some("localhost/games/game.swf/save".to_string())
+        );
+        assert_eq!(
+            shared_object_path(None, "save", None, false),
+            Some("localhost//save".to_string())
+        );
+    }
+
+    #[test]
+    fn local_path_must_be_prefix() {
+        let url = Some("http://example.com/games/game.swf");
+        assert_eq!(
+            shared_object_path(url, "save", Some("/"), false),
+            Some("example.com//save".to_string())
+        );
+        assert_eq!(
+            shared_object_path(url, "save", Some("/games/"), false),
+            Some("example.com/games/save".to_string())
+        );
+        assert_eq!(shared_object_path(url, "save", Some("/gam"), false), None);
+        assert_eq!(shared_object_path(url, "save", Some(""), false), None);
+    }
+
+    #[test]
+    fn legacy_paths_keep_url_as_is() {
+        assert_eq!(
+            legacy_shared_object_path(
+                Some("http://example.com./games//my%20game.swf"),
+                "save",
+                None
+            ),
+            Some("example.com./games//my%20game.swf/save".to_string())
+        );
+        assert_eq!(
+            legacy_shared_object_path(Some("file:///C:/games/game.swf"), "save", None),
+            Some("localhost/games/game.swf/save".to_string())
+        );
+        assert_eq!(
+            legacy_shared_object_path(
+                Some("http://example.com/games/game.swf"),
+                "save",
+                Some("/games/")
+            ),
+            Some("example.com/games/save".to_string())
+        );
+    }
+
+    #[test]
+    fn legacy_data_is_moved() {
+        let url = Some("http://example.com/my%20game.swf");
+        let full_name = shared_object_path(url, "save", None, false).unwrap();
+        let mut storage = MemoryStorageBackend::new();
+        storage.put("example.com/my%20game.swf/save", b"data");
+
+        let data = load_shared_object(&mut storage, &full_name, url, "save", None);
+        assert_eq!(data.as_deref(), Some(&b"data"[..]));
+        assert_eq!(storage.get(&full_name).as_deref(), Some(&b"data"[..]));
+        assert_eq!(storage.get("example.com/my%20game.swf/save"), None);
+
+        // Data stored under the current path wins over legacy data.
+        storage.put("example.com/my%20game.swf/save", b"old");
+        let data = load_shared_object(&mut storage, &full_name, url, "save", None);
+        assert_eq!(data.as_deref(), Some(&b"data"[..]));
+    }
+
+    #[test]
+    fn secure_requires_https() {
+        assert_eq!(
+            shared_object_path(Some("http://example.com/game.swf"), "save", None, true),
+            None
+        );
+        assert!(
+            shared_object_path(Some("https://example.com/game.swf"), "save", None, true).is_some()
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! What happens when movies ask to open URLs with `navigateToURL` or `getURL`.
+
+use crate::backend::dialog::Dialog;
+use crate::backend::navigator::NavigationMethod;
+use crate::context::UpdateContext;
+use crate::dialog::DialogPurpose;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// What happens to a request of the movie to open a URL.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NavigationAction {
+    /// Open the URL.
+    #[default]
+    #[serde(rename = "allow")]
+    Allow,
+
+    /// Ignore the request.
+    #[serde(rename = "deny")]
+    Deny,
+
+    /// Ask the user whether to open the URL.
+    #[serde(rename = "confirm")]
+    Confirm,
+
+    /// Leave the request to the embedder, without opening the URL.
+    ///
+    /// The embedder is told with `EmbedderEvent::NavigateToUrl` and
+    /// `UiBackend::forward_navigation`.
+    #[serde(rename = "forward")]
+    Forward,
+}
+
+/// How the requests of the movie to open URLs are handled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NavigationPolicy {
+    /// What happens to the URLs whose scheme has no action of its own.
+    pub action: NavigationAction,
+
+    /// The actions for the URLs with particular schemes, such as `javascript` or `mailto`, by
+    /// lowercase scheme.
+    pub schemes: HashMap<String, NavigationAction>,
+
+    /// Window names replaced with others, such as `_blank` with `_self` to open all URLs in the
+    /// same window.
+    pub targets: HashMap<String, String>,
+}
+
+impl NavigationPolicy {
+    /// Returns what happens to a request to open `url`.
+    pub fn action(&self, url: &str) -> NavigationAction {
+        scheme(url)
+            .and_then(|scheme| self.schemes.get(&scheme.to_ascii_lowercase()))
+            .copied()
+            .unwrap_or(self.action)
+    }
+
+    /// Returns the window that a request for the window named `target` opens its URL in.
+    pub fn target(&self, target: String) -> String {
+        self.targets.get(&target).cloned().unwrap_or(target)
+    }
+}
+
+/// Returns the scheme of `url`, or `None` if it's relative.
+fn scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.trim_start().split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next()?.is_ascii_alphabetic()
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+/// A request to open a URL, waiting for the user's confirmation.
+struct PendingNavigation {
+    url: String,
+    target: String,
+    vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+}
+
+/// Applies the navigation policy to the requests of the movie to open URLs.
+#[derive(Default)]
+pub struct NavigationManager {
+    policy: NavigationPolicy,
+
+    /// The requests waiting for the user's confirmation, of which only the first one is asked
+    /// about at a time.
+    pending: VecDeque<PendingNavigation>,
+}
+
+impl NavigationManager {
+    pub fn new(policy: NavigationPolicy) -> Self {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    pub fn policy(&self) -> &NavigationPolicy {
+        &self.policy
+    }
+
+    /// Asks the user whether to open `url` in the window named `target`.
+    pub fn confirm(
+        context: &mut UpdateContext<'_, '_>,
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        context.navigation.pending.push_back(PendingNavigation {
+            url,
+            target,
+            vars_method,
+        });
+        if context.navigation.pending.len() == 1 {
+            Self::show_prompt(context);
+        }
+    }
+
+    fn show_prompt(context: &mut UpdateContext<'_, '_>) {
+        let url = match context.navigation.pending.front() {
+            Some(navigation) => &navigation.url,
+            None => return,
+        };
+        let message = format!("The movie is trying to open {url}.\n\nAllow?");
+        context.dialog_manager.show(
+            context.dialogs,
+            Dialog::Confirm {
+                title: "Open link".to_string(),
+                message,
+            },
+            DialogPurpose::Navigate,
+        );
+    }
+
+    /// Opens the URL asked about last if the user allowed it, and asks about the next one.
+    pub fn resolve_prompt(context: &mut UpdateContext<'_, '_>, granted: bool) {
+        if let Some(navigation) = context.navigation.pending.pop_front() {
+            if granted {
+                context.open_url(navigation.url, navigation.target, navigation.vars_method);
+            }
+        }
+        Self::show_prompt(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigation_policy() {
+        let policy = NavigationPolicy {
+            action: NavigationAction::Confirm,
+            schemes: HashMap::from([
+                ("javascript".to_string(), NavigationAction::Deny),
+                ("mailto".to_string(), NavigationAction::Forward),
+            ]),
+            targets: HashMap::from([("_blank".to_string(), "_self".to_string())]),
+        };
+        assert_eq!(
+            policy.action("https://example.com/"),
+            NavigationAction::Confirm
+        );
+        assert_eq!(policy.action("page.html"), NavigationAction::Confirm);
+        assert_eq!(
+            policy.action(" JavaScript:alert(1)"),
+            NavigationAction::Deny
+        );
+        assert_eq!(
+            policy.action("mailto:someone@example.com"),
+            NavigationAction::Forward
+        );
+        assert_eq!(policy.target("_blank".to_string()), "_self");
+        assert_eq!(policy.target("_top".to_string()), "_top");
+    }
+}
@@ -1,9 +1,10 @@
 use crate::avm1::Attribute;
 use crate::avm1::Avm1;
 use crate::avm1::Object;
-use crate::avm1::SystemProperties;
+use crate::avm1::SandboxType;
 use crate::avm1::VariableDumper;
 use crate::avm1::{Activation, ActivationIdentifier};
+use crate::avm1::{PlayerType, SystemProperties};
 use crate::avm1::{ScriptObject, TObject, Value};
 use crate::avm2::{
     object::LoaderInfoObject, object::TObject as _, Activation as Avm2Activation, Avm2, CallStack,
@@ -11,36 +12,60 @@ use crate::avm2::{
 };
 use crate::backend::{
     audio::{AudioBackend, AudioManager},
+    dialog::{Dialog, DialogBackend, DialogId, DialogResult},
+    local_connection::LocalConnectionBackend,
     log::LogBackend,
     navigator::{NavigatorBackend, Request},
     storage::StorageBackend,
     ui::{InputManager, MouseCursor, UiBackend},
 };
-use crate::config::Letterbox;
+use crate::capture::CaptureManager;
+use crate::clock::Clock;
+use crate::compatibility::{CompatibilityReport, CompatibilityTracker};
+use crate::config::{GcSettings, Letterbox, PlayerRuntime, ResamplerQuality};
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
 use crate::context_menu::{
     BuiltInItemFlags, ContextMenuCallback, ContextMenuItem, ContextMenuState,
 };
+use crate::cursor::CursorManager;
+use crate::dialog::{DialogManager, DialogPurpose};
 use crate::display_object::{
     EditText, InteractiveObject, MovieClip, Stage, StageAlign, StageDisplayState, StageQuality,
     StageScaleMode, TInteractiveObject, WindowMode,
 };
-use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, MouseButton, PlayerEvent};
+use crate::events::{
+    ButtonKeyCode, ClipEvent, ClipEventResult, EmbedderEvent, EmbedderEvents, KeyCode, MouseButton,
+    PlayerEvent, TouchPhase,
+};
 use crate::external::Value as ExternalValue;
 use crate::external::{ExternalInterface, ExternalInterfaceProvider};
 use crate::focus_tracker::FocusTracker;
-use crate::font::Font;
+use crate::font::{Font, FontFallbacks, FontMap};
 use crate::frame_lifecycle::{run_all_phases_avm2, FramePhase};
+use crate::heap_snapshot::HeapSnapshot;
 use crate::library::Library;
 use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
+use crate::local_connection::LocalConnectionManager;
+use crate::local_storage::StorageQuota;
 use crate::locale::get_current_date_time;
+use crate::navigation::{NavigationManager, NavigationPolicy};
+use crate::policy_file::{PolicyManager, PolicyMode};
 use crate::prelude::*;
-use crate::string::AvmString;
+use crate::site_lock::SiteLock;
+use crate::socket::SocketManager;
+use crate::streams::StreamManager;
+use crate::string::{AvmString, WString};
 use crate::stub::StubCollection;
 use crate::tag_utils::SwfMovie;
 use crate::timer::Timers;
+use crate::touch::TouchManager;
 use crate::vminterface::Instantiator;
+use crate::worker::{
+    PrimordialWorker, WorkerGroup, WorkerGroupHandle, WorkerId, WorkerManager, WorkerSettings,
+};
+use chrono::{DateTime, Utc};
+use futures::channel::mpsc::Receiver;
 use gc_arena::{ArenaParameters, Collect, GcCell};
 use instant::Instant;
 use rand::{rngs::SmallRng, SeedableRng};
@@ -48,6 +73,7 @@ use ruffle_render::backend::{null::NullRenderer, RenderBackend, ViewportDimensio
 use ruffle_render::commands::CommandList;
 use ruffle_render::transform::TransformStack;
 use ruffle_video::backend::VideoBackend;
+use serde::Serialize;
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 use std::ops::DerefMut;
@@ -149,6 +175,27 @@ struct GcRootData<'gc> {
 
     /// Manager of active sound instances.
     audio_manager: AudioManager<'gc>,
+
+    /// Manager of playing `NetStream`s.
+    stream_manager: StreamManager<'gc>,
+
+    /// Manager of the microphones used by the movie.
+    capture_manager: CaptureManager<'gc>,
+
+    /// Manager of the dialogs waiting for the user's response.
+    dialog_manager: DialogManager<'gc>,
+
+    /// The worker run by this player, and the other workers it knows of.
+    worker_manager: WorkerManager<'gc>,
+
+    /// Manager of the connections opened with `LocalConnection`.
+    local_connection_manager: LocalConnectionManager<'gc>,
+
+    /// Manager of the sockets opened by the movie.
+    socket_manager: SocketManager<'gc>,
+
+    /// The fingers on the screen, and how they're reported to the movie.
+    touch_manager: TouchManager<'gc>,
 }
 
 impl<'gc> GcRootData<'gc> {
@@ -172,6 +219,13 @@ impl<'gc> GcRootData<'gc> {
         &mut Option<ContextMenuState<'gc>>,
         &mut ExternalInterface<'gc>,
         &mut AudioManager<'gc>,
+        &mut StreamManager<'gc>,
+        &mut CaptureManager<'gc>,
+        &mut DialogManager<'gc>,
+        &mut WorkerManager<'gc>,
+        &mut LocalConnectionManager<'gc>,
+        &mut SocketManager<'gc>,
+        &mut TouchManager<'gc>,
     ) {
         (
             self.stage,
@@ -188,6 +242,13 @@ impl<'gc> GcRootData<'gc> {
             &mut self.current_context_menu,
             &mut self.external_interface,
             &mut self.audio_manager,
+            &mut self.stream_manager,
+            &mut self.capture_manager,
+            &mut self.dialog_manager,
+            &mut self.worker_manager,
+            &mut self.local_connection_manager,
+            &mut self.socket_manager,
+            &mut self.touch_manager,
         )
     }
 }
@@ -195,6 +256,8 @@ impl<'gc> GcRootData<'gc> {
 type GcArena = gc_arena::Arena<gc_arena::Rootable![GcRoot<'gc>]>;
 
 type Audio = Box<dyn AudioBackend>;
+type Dialogs = Box<dyn DialogBackend>;
+type LocalConnection = Box<dyn LocalConnectionBackend>;
 type Navigator = Box<dyn NavigatorBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Storage = Box<dyn StorageBackend>;
@@ -202,6 +265,38 @@ type Log = Box<dyn LogBackend>;
 type Ui = Box<dyn UiBackend>;
 type Video = Box<dyn VideoBackend>;
 
+/// The frame labels and scenes of the main timeline, for embedders offering navigation.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Timeline {
+    #[serde(rename = "totalFrames")]
+    pub total_frames: u16,
+
+    /// The frame labels, sorted by frame number.
+    pub labels: Vec<TimelineLabel>,
+
+    /// The scenes, in playback order.
+    pub scenes: Vec<TimelineScene>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimelineLabel {
+    pub name: String,
+
+    /// The labeled frame. The first frame is frame 1.
+    pub frame: u16,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimelineScene {
+    pub name: String,
+
+    /// The first frame of the scene. The first frame of the movie is frame 1.
+    pub start: u16,
+
+    /// The number of frames in the scene.
+    pub length: u16,
+}
+
 pub struct Player {
     /// The version of the player we're emulating.
     ///
@@ -229,6 +324,23 @@ pub struct Player {
     log: Log,
     ui: Ui,
     video: Video,
+    dialogs: Dialogs,
+    local_connection: LocalConnection,
+
+    /// The storage quotas granted by the user, and any quota request awaiting a response.
+    storage_quota: StorageQuota,
+
+    /// The policy files of the servers the movie loads data from.
+    policy_manager: PolicyManager,
+
+    /// The policy for the URLs the movie opens, and the ones waiting for the user's confirmation.
+    navigation: NavigationManager,
+
+    /// The cursor chosen by the movie, and the bitmap cursors it registered.
+    cursors: CursorManager,
+
+    /// The embedders subscribed to the events of this player.
+    embedder_events: EmbedderEvents,
 
     transform_stack: TransformStack,
 
@@ -236,6 +348,12 @@ pub struct Player {
 
     gc_arena: Rc<RefCell<GcArena>>,
 
+    /// How the garbage collector paces its work.
+    gc_settings: GcSettings,
+
+    /// The time spent collecting garbage since the current frame started.
+    gc_time_this_frame: Duration,
+
     frame_rate: f64,
     actions_since_timeout_check: u16,
 
@@ -243,6 +361,8 @@ pub struct Player {
 
     stub_tracker: StubCollection,
 
+    compatibility: CompatibilityTracker,
+
     /// A time budget for executing frames.
     /// Gained by passage of time between host frames, spent by executing SWF frames.
     /// This is how we support custom SWF framerates
@@ -269,8 +389,8 @@ pub struct Player {
     /// Time remaining until the next timer will fire.
     time_til_next_timer: Option<f64>,
 
-    /// The instant at which the SWF was launched.
-    start_time: Instant,
+    /// The clock observed by the movie, which starts when the SWF is launched.
+    clock: Clock,
 
     /// The maximum amount of time that can be called before a `Error::ExecutionTimeout`
     /// is raised. This defaults to 15 seconds but can be changed.
@@ -293,6 +413,15 @@ pub struct Player {
     /// The root SWF URL provided to ActionScript. If None,
     /// the actual loaded url will be used
     spoofed_url: Option<String>,
+
+    /// The workers of the movie, shared with the players running them.
+    worker_group: WorkerGroupHandle,
+
+    /// The worker run by this player.
+    worker_id: WorkerId,
+
+    /// Stops the other workers once this player is dropped, if it runs the primordial one.
+    _primordial_worker: Option<PrimordialWorker>,
 }
 
 impl Player {
@@ -334,6 +463,12 @@ impl Player {
         self.swf = Arc::new(movie);
         self.instance_counter = 0;
 
+        // Movies pretending to run on a website are in the sandbox of that website.
+        let url = self.spoofed_url.as_deref().or_else(|| self.swf.url());
+        self.system.sandbox_type = self
+            .policy_manager
+            .sandbox_type(url, self.swf.use_network_sandbox());
+
         self.mutate_with_update_context(|context| {
             context.stage.set_movie_size(
                 context.gc_context,
@@ -344,6 +479,22 @@ impl Player {
                 .stage
                 .set_movie(context.gc_context, context.swf.clone());
 
+            // Without an explicit `wmode`, honour the presentation the movie asks for in its
+            // FileAttributes tag, like standalone players do.
+            if !context.stage.has_window_mode() {
+                let requested = if context.swf.use_gpu() {
+                    Some(WindowMode::Gpu)
+                } else if context.swf.use_direct_blit() {
+                    Some(WindowMode::Direct)
+                } else {
+                    None
+                };
+                if let Some(window_mode) = requested {
+                    info!("Movie requests {} window mode", window_mode);
+                    context.stage.set_window_mode(context, window_mode);
+                }
+            }
+
             let mut activation = Avm2Activation::from_nothing(context.reborrow());
             let global_domain = activation.avm2().global_domain();
             let domain = Avm2Domain::movie_domain(&mut activation, global_domain);
@@ -353,7 +504,8 @@ impl Player {
                 .library
                 .library_for_movie_mut(activation.context.swf.clone())
                 .set_avm2_domain(domain);
-            activation.context.ui.set_mouse_visible(true);
+            let context = &mut activation.context;
+            context.cursors.set_hidden(false, context.ui);
 
             let swf = activation.context.swf.clone();
             let root: DisplayObject =
@@ -460,6 +612,30 @@ impl Player {
         }
     }
 
+    /// Runs the player up to `timestamp`, the time passed since the player was created
+    /// according to an external clock.
+    ///
+    /// This is the alternative to `tick` for players built with
+    /// `PlayerBuilder::with_external_clock`. Timestamps earlier than the last one are ignored.
+    pub fn tick_to(&mut self, timestamp: Duration) {
+        if !self.clock.is_external() {
+            tracing::warn!("Player::tick_to called on a player using the system clock");
+            return;
+        }
+
+        let dt = self.advance_clock_to(timestamp);
+        self.tick(dt.as_secs_f64() * 1000.0);
+    }
+
+    /// Moves an external clock forward to `timestamp`, without running the player,
+    /// and returns how much time passed.
+    ///
+    /// This is for embedders that run frames themselves with `run_frame`, such as the
+    /// exporter, so that the movie observes the nominal time of each frame.
+    pub fn advance_clock_to(&mut self, timestamp: Duration) -> Duration {
+        self.clock.advance_to(timestamp)
+    }
+
     pub fn tick(&mut self, dt: f64) {
         // Don't run until preloading is complete.
         // TODO: Eventually we want to stream content similar to the Flash player.
@@ -467,6 +643,10 @@ impl Player {
             return;
         }
 
+        // Ticks that don't run a frame still collect garbage after timers and events, so each
+        // tick gets its own budget too.
+        self.gc_time_this_frame = Duration::ZERO;
+
         if self.is_playing() {
             self.frame_accumulator += dt;
             let frame_rate = self.frame_rate;
@@ -509,16 +689,37 @@ impl Player {
             }
 
             // Adjust playback speed for next frame to stay in sync with timeline audio tracks ("stream" sounds).
-            let cur_frame_offset = self.frame_accumulator;
-            self.frame_accumulator += self.mutate_with_update_context(|context| {
-                context
-                    .audio_manager
-                    .audio_skew_time(context.audio, cur_frame_offset)
-                    * 1000.0
-            });
+            // An external clock is authoritative, so the audio has to follow it instead.
+            if !self.clock.is_external() {
+                let cur_frame_offset = self.frame_accumulator;
+                self.frame_accumulator += self.mutate_with_update_context(|context| {
+                    context
+                        .audio_manager
+                        .audio_skew_time(context.audio, cur_frame_offset)
+                        * 1000.0
+                });
+            }
 
             self.update_timers(dt);
+            self.update_local_connections();
+            self.update_sockets(dt);
+            self.update_streams(dt);
             self.audio.tick();
+
+            if self.cursors.is_animated() {
+                self.needs_render = true;
+            }
+        }
+    }
+
+    /// Serves what the other workers asked the primordial one to do, and dispatches the events
+    /// of what happened to them since the last frame.
+    fn update_workers(&mut self) {
+        if self.worker_id.is_primordial() && self.worker_group.lock().has_requests() {
+            self.mutate_with_update_context(|context| WorkerManager::serve_requests(context));
+        }
+        if self.worker_group.lock().has_notifications(self.worker_id) {
+            self.update(|context| WorkerManager::dispatch_notifications(context));
         }
     }
 
@@ -567,40 +768,45 @@ impl Player {
                 return vec![];
             }
 
-            // TODO: This should use a pointed display object with `.menu`
             let root_dobj = context.stage.root_clip();
+            let mouse_target = context.mouse_over_object;
 
-            let menu = if let Value::Object(obj) = root_dobj.object() {
+            let mut menu = if let Value::Object(_) = root_dobj.object() {
                 let mut activation = Activation::from_stub(
                     context.reborrow(),
                     ActivationIdentifier::root("[ContextMenu]"),
                 );
-                let menu_object = if let Ok(Value::Object(menu)) = obj.get("menu", &mut activation)
-                {
+                let (owner, menu_object) =
+                    Self::avm1_context_menu_owner(mouse_target, root_dobj, &mut activation);
+                if let Some(menu) = menu_object {
                     if let Ok(Value::Object(on_select)) = menu.get("onSelect", &mut activation) {
                         Self::run_context_menu_custom_callback(
+                            owner,
                             menu,
                             on_select,
                             &mut activation.context,
                         );
                     }
-                    Some(menu)
-                } else {
-                    None
-                };
-                crate::avm1::make_context_menu_state(menu_object, &mut activation)
-            } else if let Avm2Value::Object(_obj) = root_dobj.object2() {
-                // TODO: send "menuSelect" event
-                tracing::warn!("AVM2 Context menu callbacks are not implemented");
+                }
+                let mut menu = crate::avm1::make_context_menu_state(menu_object, &mut activation);
+                menu.set_targets(mouse_target, owner.as_interactive());
+                menu
+            } else if let Avm2Value::Object(_) = root_dobj.object2() {
+                let (owner, menu_object) = Self::avm2_context_menu_owner(mouse_target, root_dobj);
+                if let Some(menu) = menu_object {
+                    Self::dispatch_context_menu_event(
+                        context,
+                        "menuSelect",
+                        menu,
+                        mouse_target,
+                        owner,
+                    );
+                }
 
                 let mut activation = Avm2Activation::from_nothing(context.reborrow());
-
-                let menu_object = root_dobj
-                    .as_interactive()
-                    .map(|iobj| iobj.context_menu())
-                    .and_then(|v| v.as_object());
-
-                crate::avm2::make_context_menu_state(menu_object, &mut activation)
+                let mut menu = crate::avm2::make_context_menu_state(menu_object, &mut activation);
+                menu.set_targets(mouse_target, owner);
+                menu
             } else {
                 // no AVM1 or AVM2 object - so just prepare the builtin items
                 let mut menu = ContextMenuState::new();
@@ -615,6 +821,91 @@ impl Player {
         })
     }
 
+    /// Finds the AVM1 object whose menu is shown for a right click on `mouse_target`: the mouse
+    /// target itself or its closest ancestor with a `menu`, or else the root movie.
+    fn avm1_context_menu_owner<'gc>(
+        mouse_target: Option<InteractiveObject<'gc>>,
+        root: DisplayObject<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> (DisplayObject<'gc>, Option<Object<'gc>>) {
+        let mut current = mouse_target.map(|target| target.as_displayobject());
+        while let Some(dobj) = current {
+            if let Value::Object(obj) = dobj.object() {
+                if let Ok(Value::Object(menu)) = obj.get("menu", activation) {
+                    return (dobj, Some(menu));
+                }
+            }
+            current = dobj.parent();
+        }
+        (root, None)
+    }
+
+    /// Finds the AVM2 object whose menu is shown for a right click on `mouse_target`: the mouse
+    /// target itself or its closest ancestor with a `contextMenu`, or else the root movie.
+    fn avm2_context_menu_owner<'gc>(
+        mouse_target: Option<InteractiveObject<'gc>>,
+        root: DisplayObject<'gc>,
+    ) -> (Option<InteractiveObject<'gc>>, Option<Avm2Object<'gc>>) {
+        let mut current = mouse_target.map(|target| target.as_displayobject());
+        while let Some(dobj) = current {
+            if let Some(interactive) = dobj.as_interactive() {
+                if let Some(menu) = interactive.context_menu().as_object() {
+                    return (Some(interactive), Some(menu));
+                }
+            }
+            current = dobj.parent();
+        }
+        (root.as_interactive(), None)
+    }
+
+    /// Dispatches a `ContextMenuEvent` of the given type to `target`, which is the `ContextMenu`
+    /// for `menuSelect` and the chosen `ContextMenuItem` for `menuItemSelect`.
+    fn dispatch_context_menu_event<'gc>(
+        context: &mut UpdateContext<'_, 'gc>,
+        event_type: &'static str,
+        target: Avm2Object<'gc>,
+        mouse_target: Option<InteractiveObject<'gc>>,
+        owner: Option<InteractiveObject<'gc>>,
+    ) {
+        let to_value = |object: Option<InteractiveObject<'gc>>| match object
+            .map(|object| object.as_displayobject().object2())
+        {
+            Some(value @ Avm2Value::Object(_)) => value,
+            _ => Avm2Value::Null,
+        };
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let context_menu_event_cls = activation.avm2().classes().contextmenuevent;
+        let event = context_menu_event_cls.construct(
+            &mut activation,
+            &[
+                event_type.into(),
+                false.into(),
+                false.into(),
+                to_value(mouse_target),
+                to_value(owner),
+            ],
+        );
+        match event {
+            Ok(event) => {
+                if let Err(e) = Avm2::dispatch_event(&mut activation.context, event, target) {
+                    tracing::error!(
+                        "Encountered AVM2 error when dispatching `{}` event: {}",
+                        event_type,
+                        e
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Encountered AVM2 error when creating `{}` event: {}",
+                    event_type,
+                    e
+                );
+            }
+        }
+    }
+
     pub fn clear_custom_menu_items(&mut self) {
         self.gc_arena.borrow().mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.data.write(gc_context);
@@ -626,16 +917,33 @@ impl Player {
         self.mutate_with_update_context(|context| {
             let menu = &context.current_context_menu;
             if let Some(ref menu) = menu {
+                let mouse_target = menu.mouse_target();
+                let owner = menu.owner();
                 match menu.callback(index) {
                     ContextMenuCallback::Avm1 { item, callback } => {
-                        Self::run_context_menu_custom_callback(*item, *callback, context)
+                        let owner = owner
+                            .map(|owner| owner.as_displayobject())
+                            .unwrap_or_else(|| context.stage.root_clip());
+                        Self::run_context_menu_custom_callback(owner, *item, *callback, context)
+                    }
+                    ContextMenuCallback::Quality(quality) => {
+                        let quality = *quality;
+                        let stage = context.stage;
+                        stage.set_quality(context, quality);
                     }
                     ContextMenuCallback::Play => Self::toggle_play_root_movie(context),
                     ContextMenuCallback::Forward => Self::forward_root_movie(context),
                     ContextMenuCallback::Back => Self::back_root_movie(context),
                     ContextMenuCallback::Rewind => Self::rewind_root_movie(context),
-                    ContextMenuCallback::Avm2 { .. } => {
-                        // TODO: Send menuItemSelect event
+                    ContextMenuCallback::Avm2 { item } => {
+                        let item = *item;
+                        Self::dispatch_context_menu_event(
+                            context,
+                            "menuItemSelect",
+                            item,
+                            mouse_target,
+                            owner,
+                        )
                     }
                     _ => {}
                 }
@@ -644,21 +952,21 @@ impl Player {
         });
     }
 
+    /// Calls the `onSelect` callback of an AVM1 `ContextMenu` or `ContextMenuItem` with the
+    /// object whose menu is shown and the menu or item.
     fn run_context_menu_custom_callback<'gc>(
+        owner: DisplayObject<'gc>,
         item: Object<'gc>,
         callback: Object<'gc>,
         context: &mut UpdateContext<'_, 'gc>,
     ) {
-        let root_clip = context.stage.root_clip();
         let mut activation = Activation::from_nothing(
             context.reborrow(),
             ActivationIdentifier::root("[Context Menu Callback]"),
-            root_clip,
+            owner,
         );
 
-        // TODO: Remember to also change the first arg
-        // when we support contextmenu on non-root-movie
-        let params = vec![root_clip.object(), Value::Object(item)];
+        let params = vec![owner.object(), Value::Object(item)];
 
         let _ = callback.call(
             "[Context Menu Callback]".into(),
@@ -779,7 +1087,7 @@ impl Player {
         self.mutate_with_update_context(|context| {
             let stage = context.stage;
             if let Ok(quality) = StageQuality::from_str(quality) {
-                stage.set_quality(context.gc_context, quality);
+                stage.set_quality(context, quality);
             }
         })
     }
@@ -812,6 +1120,8 @@ impl Player {
     ///    If Ctrl-Alt-V is pressed, dump all AVM1 variables in the player.
     ///    If Ctrl-Alt-D is pressed, toggle debug output for AVM1 and AVM2.
     ///    If Ctrl-Alt-F is pressed, dump the display object tree.
+    ///    If Ctrl-Alt-H is pressed, save a heap snapshot to the working
+    ///    directory and print its report.
     /// 2. If the incoming event is text input or key input that could be
     ///    related to text input (e.g. pressing a letter key), we dispatch a
     ///    key press event onto the stage.
@@ -827,6 +1137,18 @@ impl Player {
     /// 8. Mouse state is updated. This triggers button rollovers, which are a
     ///    second wave of event processing.
     pub fn handle_event(&mut self, event: PlayerEvent) {
+        if let PlayerEvent::Touch {
+            id,
+            phase,
+            x,
+            y,
+            pressure,
+        } = event
+        {
+            self.handle_touch(id, phase, x, y, pressure);
+            return;
+        }
+
         let prev_is_mouse_down = self.input.is_mouse_down();
         self.input.handle_event(&event);
         let is_mouse_button_changed = self.input.is_mouse_down() != prev_is_mouse_down;
@@ -899,6 +1221,33 @@ impl Player {
                         context.stage.display_render_tree(0);
                     });
                 }
+                PlayerEvent::KeyDown {
+                    key_code: KeyCode::H,
+                    ..
+                } if self.input.is_key_down(KeyCode::Control)
+                    && self.input.is_key_down(KeyCode::Alt) =>
+                {
+                    let snapshot = self.heap_snapshot();
+                    match snapshot.to_json() {
+                        // The embedder decides where the snapshot goes.
+                        Ok(json) => self.mutate_with_update_context(|context| {
+                            let default_name = format!(
+                                "ruffle-heap-{}.json",
+                                get_current_date_time().timestamp_millis()
+                            );
+                            context.dialog_manager.show(
+                                context.dialogs,
+                                Dialog::SaveFile {
+                                    default_name,
+                                    data: json.into_bytes(),
+                                },
+                                DialogPurpose::HeapSnapshot,
+                            );
+                        }),
+                        Err(e) => tracing::error!("Unable to serialize heap snapshot: {}", e),
+                    }
+                    tracing::info!("Heap snapshot:\n{}", snapshot.report(20));
+                }
                 _ => {}
             }
         }
@@ -1014,8 +1363,20 @@ impl Player {
                 }
             }
 
+            // Characters typed with Ctrl are shortcuts rather than text, unless Alt is held too,
+            // which is how AltGr is reported.
+            let is_shortcut = context.input.is_key_down(KeyCode::Control)
+                && !context.input.is_key_down(KeyCode::Alt);
+            if is_shortcut {
+                if let PlayerEvent::KeyDown { key_code, .. } = event {
+                    if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
+                        text.handle_clipboard_shortcut(key_code, context);
+                    }
+                }
+            }
+
             // keyPress events take precedence over text input.
-            if !key_press_handled {
+            if !key_press_handled && !is_shortcut {
                 if let PlayerEvent::TextInput { codepoint } = event {
                     if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
                         text.text_input(codepoint, context);
@@ -1023,6 +1384,23 @@ impl Player {
                 }
             }
 
+            if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
+                match &event {
+                    PlayerEvent::ImePreedit { text: composition } => {
+                        text.set_composition(&WString::from_utf8(composition), context);
+                    }
+                    PlayerEvent::ImeCommit { text: composition } => {
+                        text.commit_composition(&WString::from_utf8(composition), context);
+                    }
+                    PlayerEvent::Copy => text.copy_selection(false, context),
+                    PlayerEvent::Cut => text.copy_selection(text.is_editable(), context),
+                    PlayerEvent::Paste { text: pasted } => {
+                        text.insert_user_text(&WString::from_utf8(pasted), context);
+                    }
+                    _ => {}
+                }
+            }
+
             // Propagate clip events.
             let (clip_event, listener) = match event {
                 PlayerEvent::KeyDown { .. } => {
@@ -1105,6 +1483,9 @@ impl Player {
             });
 
             let is_mouse_moved = old_pos != self.mouse_pos;
+            if is_mouse_moved && self.cursors.is_drawn() {
+                self.needs_render = true;
+            }
 
             // This fires button rollover/press events, which should run after the above mouseMove events.
             if self.update_mouse_state(is_mouse_button_changed, is_mouse_moved) {
@@ -1168,6 +1549,31 @@ impl Player {
         }
     }
 
+    /// Updates a touch point, and turns the primary touch point into mouse events.
+    fn handle_touch(&mut self, id: u64, phase: TouchPhase, x: f64, y: f64, pressure: Option<f64>) {
+        let is_primary = self.mutate_with_update_context(|context| {
+            let position = context.stage.inverse_view_matrix()
+                * (Twips::from_pixels(x), Twips::from_pixels(y));
+            TouchManager::handle_touch(context, id, phase, position, pressure)
+        });
+        if !is_primary {
+            return;
+        }
+
+        let button = MouseButton::Left;
+        match phase {
+            TouchPhase::Start => {
+                // Move the mouse first, so that the touched object is the one pressed.
+                self.handle_event(PlayerEvent::MouseMove { x, y });
+                self.handle_event(PlayerEvent::MouseDown { x, y, button });
+            }
+            TouchPhase::Move => self.handle_event(PlayerEvent::MouseMove { x, y }),
+            TouchPhase::End | TouchPhase::Cancel => {
+                self.handle_event(PlayerEvent::MouseUp { x, y, button })
+            }
+        }
+    }
+
     /// Updates the hover state of buttons.
     fn update_mouse_state(&mut self, is_mouse_button_changed: bool, is_mouse_moved: bool) -> bool {
         let mut new_cursor = self.mouse_cursor;
@@ -1371,7 +1777,7 @@ impl Player {
         // Update mouse cursor if it has changed.
         if new_cursor != self.mouse_cursor {
             self.mouse_cursor = new_cursor;
-            self.ui.set_mouse_cursor(new_cursor)
+            self.cursors.set_automatic(new_cursor, self.ui.deref_mut());
         }
         self.mouse_cursor_needs_check = mouse_cursor_needs_check;
 
@@ -1455,11 +1861,14 @@ impl Player {
             LoadBehavior::Blocking => (ExecutionLimit::none(), false),
         };
         let preload_finished = self.preload(&mut execution_limit);
+        self.gc_time_this_frame = Duration::ZERO;
 
         if !preload_finished && !may_execute_while_streaming {
             return;
         }
 
+        self.update_workers();
+
         self.update(|context| {
             if context.is_action_script_3() {
                 run_all_phases_avm2(context);
@@ -1467,6 +1876,18 @@ impl Player {
                 Avm1::run_frame(context);
             }
             context.update_sounds();
+            CaptureManager::update(context);
+
+            if context.embedder_events.has_subscribers() {
+                let frame = context
+                    .stage
+                    .root_clip()
+                    .as_movie_clip()
+                    .map_or(0, |clip| clip.current_frame());
+                context
+                    .embedder_events
+                    .send(EmbedderEvent::FrameRun { frame });
+            }
         });
 
         self.needs_render = true;
@@ -1474,8 +1895,14 @@ impl Player {
 
     #[instrument(level = "debug", skip_all)]
     pub fn render(&mut self) {
-        let (renderer, ui, transform_stack) =
-            (&mut self.renderer, &mut self.ui, &mut self.transform_stack);
+        let (renderer, ui, transform_stack, cursors) = (
+            &mut self.renderer,
+            &mut self.ui,
+            &mut self.transform_stack,
+            &self.cursors,
+        );
+        let mouse_pos = self.mouse_pos;
+        let now = self.clock.elapsed();
         let mut background_color = Color::WHITE;
 
         let commands = self.gc_arena.borrow().mutate(|gc_context, gc_root| {
@@ -1491,11 +1918,17 @@ impl Player {
                 transform_stack,
                 is_offscreen: false,
                 stage,
+                focus_tracker: root_data.focus_tracker,
                 clip_depth_stack: vec![],
                 allow_mask: true,
             };
 
             stage.render(&mut render_context);
+            cursors.render(
+                &mut render_context.commands,
+                stage.view_matrix() * mouse_pos,
+                now,
+            );
 
             background_color =
                 if stage.window_mode() != WindowMode::Transparent || stage.is_fullscreen() {
@@ -1518,6 +1951,73 @@ impl Player {
         self.current_frame
     }
 
+    /// The frame labels and scenes of the main timeline, if available.
+    pub fn timeline(&mut self) -> Option<Timeline> {
+        self.mutate_with_update_context(|context| {
+            let root = context.stage.root_clip().as_movie_clip()?;
+            let total_frames = root.total_frames();
+            let labels = root
+                .labels_in_range(1, total_frames.saturating_add(1))
+                .into_iter()
+                .map(|(name, frame)| TimelineLabel {
+                    name: name.to_utf8_lossy().into_owned(),
+                    frame,
+                })
+                .collect();
+            let scenes = root
+                .scenes()
+                .into_iter()
+                .map(|scene| TimelineScene {
+                    name: scene.name.to_utf8_lossy().into_owned(),
+                    start: scene.start,
+                    length: scene.length,
+                })
+                .collect();
+            Some(Timeline {
+                total_frames,
+                labels,
+                scenes,
+            })
+        })
+    }
+
+    /// Jumps to a frame of the main timeline, keeping it playing or stopped.
+    /// The first frame is frame 1.
+    pub fn goto_frame(&mut self, frame: u16) {
+        self.mutate_with_update_context(|context| {
+            if let Some(root) = context.stage.root_clip().as_movie_clip() {
+                let frame = frame.clamp(1, root.total_frames().max(1));
+                root.goto_frame(context, frame, !root.playing());
+                Self::run_actions(context);
+                *context.needs_render = true;
+            }
+        });
+    }
+
+    /// Jumps to a frame label or scene of the main timeline, keeping it playing or stopped.
+    ///
+    /// Returns `false` if there is no such label or scene.
+    pub fn goto_label(&mut self, label: &str) -> bool {
+        self.mutate_with_update_context(|context| {
+            let root = match context.stage.root_clip().as_movie_clip() {
+                Some(root) => root,
+                None => return false,
+            };
+            let label = WString::from_utf8(label);
+            let frame = match root
+                .frame_label_to_number(&label, context)
+                .or_else(|| root.scene_label_to_number(&label))
+            {
+                Some(frame) => frame,
+                None => return false,
+            };
+            root.goto_frame(context, frame, !root.playing());
+            Self::run_actions(context);
+            *context.needs_render = true;
+            true
+        })
+    }
+
     pub fn audio(&self) -> &Audio {
         &self.audio
     }
@@ -1700,6 +2200,13 @@ impl Player {
                 current_context_menu,
                 external_interface,
                 audio_manager,
+                stream_manager,
+                capture_manager,
+                dialog_manager,
+                worker_manager,
+                local_connection_manager,
+                socket_manager,
+                touch_manager,
             ) = root_data.update_context_params();
 
             let mut update_context = UpdateContext {
@@ -1711,6 +2218,8 @@ impl Player {
                 audio: self.audio.deref_mut(),
                 navigator: self.navigator.deref_mut(),
                 ui: self.ui.deref_mut(),
+                dialogs: self.dialogs.deref_mut(),
+                local_connection: self.local_connection.deref_mut(),
                 action_queue,
                 gc_context,
                 stage,
@@ -1724,6 +2233,11 @@ impl Player {
                 system: &mut self.system,
                 instance_counter: &mut self.instance_counter,
                 storage: self.storage.deref_mut(),
+                storage_quota: &mut self.storage_quota,
+                policy_manager: &mut self.policy_manager,
+                navigation: &mut self.navigation,
+                cursors: &mut self.cursors,
+                embedder_events: &mut self.embedder_events,
                 log: self.log.deref_mut(),
                 video: self.video.deref_mut(),
                 avm1_shared_objects,
@@ -1735,17 +2249,25 @@ impl Player {
                 avm1,
                 avm2,
                 external_interface,
-                start_time: self.start_time,
+                clock: self.clock,
                 update_start: Instant::now(),
                 max_execution_duration: self.max_execution_duration,
                 focus_tracker,
                 times_get_time_called: 0,
                 time_offset: &mut self.time_offset,
                 audio_manager,
+                stream_manager,
+                capture_manager,
+                dialog_manager,
+                worker_manager,
+                local_connection_manager,
+                socket_manager,
+                touch_manager,
                 frame_rate: &mut self.frame_rate,
                 actions_since_timeout_check: &mut self.actions_since_timeout_check,
                 frame_phase: &mut self.frame_phase,
                 stub_tracker: &mut self.stub_tracker,
+                compatibility: &mut self.compatibility,
             };
 
             let old_frame_rate = *update_context.frame_rate;
@@ -1818,18 +2340,36 @@ impl Player {
         });
         self.update_mouse_state(false, false);
 
-        // GC
-        self.gc_arena.borrow_mut().collect_debt();
+        self.collect_garbage();
 
         rval
     }
 
+    /// Does the garbage collection work that is due, within the time budget of the frame.
+    fn collect_garbage(&mut self) {
+        let mut gc_arena = self.gc_arena.borrow_mut();
+        if !self.gc_settings.should_collect(
+            self.gc_time_this_frame,
+            gc_arena.allocation_debt(),
+            gc_arena.total_allocated(),
+        ) {
+            return;
+        }
+
+        let start = Instant::now();
+        gc_arena.collect_debt();
+        self.gc_time_this_frame += start.elapsed();
+    }
+
+    /// Writes all shared objects to storage.
+    ///
+    /// This ignores the storage quota, and is meant to be called when the player shuts down.
     pub fn flush_shared_objects(&mut self) {
         self.update(|context| {
             let mut avm1_activation =
                 Activation::from_stub(context.reborrow(), ActivationIdentifier::root("[Flush]"));
             for so in avm1_activation.context.avm1_shared_objects.clone().values() {
-                if let Err(e) = crate::avm1::flush(&mut avm1_activation, *so, &[]) {
+                if let Err(e) = crate::avm1::write_to_storage(&mut avm1_activation, *so) {
                     tracing::error!("Error flushing AVM1 shared object `{:?}`: {:?}", so, e);
                 }
             }
@@ -1837,10 +2377,9 @@ impl Player {
             let mut avm2_activation =
                 Avm2Activation::from_nothing(avm1_activation.context.reborrow());
             for so in avm2_activation.context.avm2_shared_objects.clone().values() {
-                if let Err(e) = crate::avm2::globals::flash::net::shared_object::flush(
+                if let Err(e) = crate::avm2::globals::flash::net::shared_object::write_to_storage(
                     &mut avm2_activation,
-                    Some(*so),
-                    &[],
+                    *so,
                 ) {
                     tracing::error!("Error flushing AVM2 shared object `{:?}`: {:?}", so, e);
                 }
@@ -1848,6 +2387,27 @@ impl Player {
         });
     }
 
+    /// Removes all shared objects opened by the movie from storage.
+    ///
+    /// This is meant to be called before the player is discarded, so that the next instance of
+    /// the movie starts without the data saved by this one.
+    pub fn remove_shared_objects(&mut self) {
+        self.update(|context| {
+            for name in context
+                .avm1_shared_objects
+                .keys()
+                .chain(context.avm2_shared_objects.keys())
+            {
+                context.storage.remove_key(name);
+            }
+        });
+    }
+
+    /// Reports the user's response to a dialog (see `DialogBackend::show_dialog`).
+    pub fn resolve_dialog(&mut self, id: DialogId, result: DialogResult) {
+        self.update(|context| DialogManager::resolve(context, id, result));
+    }
+
     /// Update all AVM-based timers (such as created via setInterval).
     /// Returns the approximate amount of time until the next timer tick.
     pub fn update_timers(&mut self, dt: f64) {
@@ -1855,6 +2415,23 @@ impl Player {
             self.mutate_with_update_context(|context| Timers::update_timers(context, dt));
     }
 
+    /// Delivers the `LocalConnection` messages received since the last tick, and reports the
+    /// status of the messages that were sent.
+    pub fn update_local_connections(&mut self) {
+        self.update(|context| LocalConnectionManager::update(context));
+    }
+
+    /// Reports what happened to the sockets of the movie since the last tick, `dt`
+    /// milliseconds ago.
+    pub fn update_sockets(&mut self, dt: f64) {
+        self.update(|context| SocketManager::update(context, dt));
+    }
+
+    /// Advance all playing `NetStream`s by `dt` milliseconds.
+    pub fn update_streams(&mut self, dt: f64) {
+        self.mutate_with_update_context(|context| StreamManager::tick(context, dt));
+    }
+
     /// Returns whether this player consumes mouse wheel events.
     /// Used by web to prevent scrolling.
     pub fn should_prevent_scrolling(&mut self) -> bool {
@@ -1885,10 +2462,52 @@ impl Player {
         self.spoofed_url.as_deref()
     }
 
+    /// Enables the site lock compatibility answers for the root movie, which
+    /// pretend that it runs on its original website.
+    ///
+    /// This must be called before the root movie is loaded, as it also
+    /// replaces the spoofed URL.
+    pub fn set_site_lock(&mut self, site_lock: SiteLock) {
+        self.spoofed_url = Some(site_lock.original_url().to_string());
+        self.system.sandbox_type = SandboxType::Remote;
+        self.compatibility.quirk_activated("Site lock");
+        self.mutate_with_update_context(|context| {
+            context.external_interface.set_site_lock(Some(site_lock))
+        });
+    }
+
     pub fn log_backend(&self) -> &Log {
         &self.log
     }
 
+    /// The unimplemented APIs this player's content has used so far.
+    pub fn stubs(&self) -> &StubCollection {
+        &self.stub_tracker
+    }
+
+    /// Lists everything that kept this player's content from playing as it would in Flash
+    /// Player so far: unimplemented APIs, media that couldn't be decoded and compatibility quirks.
+    pub fn compatibility_report(&self) -> CompatibilityReport {
+        CompatibilityReport::new(
+            self.swf.url().unwrap_or_default().to_string(),
+            self.swf.version(),
+            self.swf.is_action_script_3(),
+            &self.stub_tracker,
+            &self.compatibility,
+        )
+    }
+
+    /// Records the objects currently reachable by the content of this player,
+    /// to find out what keeps memory alive.
+    pub fn heap_snapshot(&mut self) -> HeapSnapshot {
+        self.mutate_with_update_context(HeapSnapshot::take)
+    }
+
+    /// Returns a channel receiving the events of this player from now on.
+    pub fn subscribe_events(&mut self) -> Receiver<EmbedderEvent> {
+        self.embedder_events.subscribe()
+    }
+
     pub fn max_execution_duration(&self) -> Duration {
         self.max_execution_duration
     }
@@ -1916,19 +2535,35 @@ pub struct PlayerBuilder {
     storage: Option<Storage>,
     ui: Option<Ui>,
     video: Option<Video>,
+    dialogs: Option<Dialogs>,
+    local_connection: Option<LocalConnection>,
 
     // Misc. player configuration
     autoplay: bool,
     fullscreen: bool,
     letterbox: Letterbox,
     max_execution_duration: Duration,
+    gc_settings: GcSettings,
+    avm2_jit: bool,
     viewport_width: u32,
     viewport_height: u32,
     viewport_scale_factor: f64,
     warn_on_unsupported_content: bool,
     load_behavior: LoadBehavior,
+    policy_mode: PolicyMode,
+    skip_socket_policy: bool,
+    trusted_locations: Vec<String>,
+    navigation_policy: NavigationPolicy,
+    font_map: FontMap,
+    font_fallbacks: FontFallbacks,
+    player_type: PlayerType,
+    player_runtime: PlayerRuntime,
     spoofed_url: Option<String>,
+    site_lock: Option<SiteLock>,
     player_version: Option<u8>,
+    external_clock: Option<DateTime<Utc>>,
+    resampler_quality: ResamplerQuality,
+    worker: Option<(WorkerGroupHandle, WorkerId)>,
 }
 
 impl PlayerBuilder {
@@ -1948,6 +2583,8 @@ impl PlayerBuilder {
             storage: None,
             ui: None,
             video: None,
+            dialogs: None,
+            local_connection: None,
 
             autoplay: false,
             fullscreen: false,
@@ -1958,13 +2595,27 @@ impl PlayerBuilder {
             } else {
                 15
             }),
+            gc_settings: GcSettings::default(),
+            avm2_jit: false,
             viewport_width: 550,
             viewport_height: 400,
             viewport_scale_factor: 1.0,
             warn_on_unsupported_content: true,
             load_behavior: LoadBehavior::Streaming,
+            policy_mode: PolicyMode::default(),
+            skip_socket_policy: false,
+            trusted_locations: Vec::new(),
+            navigation_policy: NavigationPolicy::default(),
+            font_map: FontMap::default(),
+            font_fallbacks: FontFallbacks::default(),
+            player_type: PlayerType::StandAlone,
+            player_runtime: PlayerRuntime::FlashPlayer,
             spoofed_url: None,
+            site_lock: None,
             player_version: None,
+            external_clock: None,
+            resampler_quality: ResamplerQuality::default(),
+            worker: None,
         }
     }
 
@@ -2024,6 +2675,26 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the dialog backend of the player.
+    #[inline]
+    pub fn with_dialogs(mut self, dialogs: impl 'static + DialogBackend) -> Self {
+        self.dialogs = Some(Box::new(dialogs));
+        self
+    }
+
+    /// Sets the local connection backend of the player.
+    ///
+    /// Players with backends sharing the same transport can talk to each other with
+    /// `LocalConnection`.
+    #[inline]
+    pub fn with_local_connection(
+        mut self,
+        local_connection: impl 'static + LocalConnectionBackend,
+    ) -> Self {
+        self.local_connection = Some(Box::new(local_connection));
+        self
+    }
+
     /// Sets whether the movie will start playing immediately upon load.
     #[inline]
     pub fn with_autoplay(mut self, autoplay: bool) -> Self {
@@ -2078,25 +2749,127 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets how the policy files of other servers are honored.
+    pub fn with_policy_mode(mut self, policy_mode: PolicyMode) -> Self {
+        self.policy_mode = policy_mode;
+        self
+    }
+
+    /// Sets whether sockets connect without asking the server for a socket policy file first.
+    pub fn with_skip_socket_policy(mut self, skip_socket_policy: bool) -> Self {
+        self.skip_socket_policy = skip_socket_policy;
+        self
+    }
+
+    /// Sets the URLs of the local files and directories whose movies run in the `localTrusted`
+    /// sandbox when Flash's security model is emulated.
+    pub fn with_trusted_locations(mut self, trusted_locations: Vec<String>) -> Self {
+        self.trusted_locations = trusted_locations;
+        self
+    }
+
+    /// Sets what happens when the movie asks to open URLs.
+    pub fn with_navigation_policy(mut self, navigation_policy: NavigationPolicy) -> Self {
+        self.navigation_policy = navigation_policy;
+        self
+    }
+
+    /// Sets the fonts installed on the system that device text in other fonts is drawn with.
+    pub fn with_font_map(mut self, font_map: FontMap) -> Self {
+        self.font_map = font_map;
+        self
+    }
+
+    /// Sets the fonts installed on the system that device text falls back to for the
+    /// characters of each script that its font has no glyphs for.
+    pub fn with_font_fallbacks(mut self, font_fallbacks: FontFallbacks) -> Self {
+        self.font_fallbacks = font_fallbacks;
+        self
+    }
+
+    /// Sets the type of player reported to ActionScript.
+    ///
+    /// Only standalone players carry out the projector commands of `fscommand` themselves.
+    pub fn with_player_type(mut self, player_type: PlayerType) -> Self {
+        self.player_type = player_type;
+        self
+    }
+
+    /// Sets the runtime that the player presents itself as.
+    ///
+    /// The APIs that only AIR has are only available to movies when this is `PlayerRuntime::Air`.
+    pub fn with_player_runtime(mut self, player_runtime: PlayerRuntime) -> Self {
+        self.player_runtime = player_runtime;
+        self
+    }
+
     /// Sets the root SWF URL provided to ActionScript.
     pub fn with_spoofed_url(mut self, url: Option<String>) -> Self {
         self.spoofed_url = url;
         self
     }
 
+    /// Enables the site lock compatibility answers for the root movie.
+    ///
+    /// This replaces the spoofed URL, see `Player::set_site_lock`.
+    pub fn with_site_lock(mut self, site_lock: Option<SiteLock>) -> Self {
+        if let Some(site_lock) = &site_lock {
+            self.spoofed_url = Some(site_lock.original_url().to_string());
+        }
+        self.site_lock = site_lock;
+        self
+    }
+
     // Configures the target player version.
     pub fn with_player_version(mut self, version: Option<u8>) -> Self {
         self.player_version = version;
         self
     }
 
+    /// Sets the date and time at which an external clock starts. Time is then supplied by
+    /// the embedder through `Player::tick_to`, instead of following the system clock.
+    ///
+    /// Timers, `getTimer`, dates and audio sync all follow that clock.
+    pub fn with_external_clock(mut self, start_date: Option<DateTime<Utc>>) -> Self {
+        self.external_clock = start_date;
+        self
+    }
+
+    /// Sets how sounds are converted to the sample rate of the audio output.
+    pub fn with_resampler_quality(mut self, quality: ResamplerQuality) -> Self {
+        self.resampler_quality = quality;
+        self
+    }
+
+    /// Sets how the garbage collector paces its work.
+    pub fn with_gc_settings(mut self, settings: GcSettings) -> Self {
+        self.gc_settings = settings;
+        self
+    }
+
+    /// Sets whether hot AVM2 loops are compiled to machine code.
+    ///
+    /// This needs the `avm2_jit` feature; without it, the setting is ignored with a warning.
+    pub fn with_avm2_jit(mut self, enabled: bool) -> Self {
+        self.avm2_jit = enabled;
+        self
+    }
+
+    /// Makes the player run a worker of an existing worker group, instead of being the
+    /// primordial player of a group of its own.
+    pub(crate) fn with_worker(mut self, group: WorkerGroupHandle, id: WorkerId) -> Self {
+        self.worker = Some((group, id));
+        self
+    }
+
     /// Builds the player, wiring up the backends and configuring the specified settings.
     pub fn build(self) -> Arc<Mutex<Player>> {
         use crate::backend::*;
         use ruffle_video::null;
-        let audio = self
+        let mut audio = self
             .audio
             .unwrap_or_else(|| Box::new(audio::NullAudioBackend::new()));
+        audio.set_resampler_quality(self.resampler_quality);
         let log = self
             .log
             .unwrap_or_else(|| Box::new(log::NullLogBackend::new()));
@@ -2119,8 +2892,28 @@ impl PlayerBuilder {
         let video = self
             .video
             .unwrap_or_else(|| Box::new(null::NullVideoBackend::new()));
+        let dialogs = self
+            .dialogs
+            .unwrap_or_else(|| Box::new(dialog::NullDialogBackend::new()));
+        let local_connection = self
+            .local_connection
+            .unwrap_or_else(|| Box::new(local_connection::MemoryLocalConnectionBackend::default()));
 
         let player_version = self.player_version.unwrap_or(NEWEST_PLAYER_VERSION);
+        let clock = match self.external_clock {
+            Some(start_date) => Clock::external(start_date),
+            None => Clock::system(),
+        };
+
+        let (worker_group, worker_id) = self.worker.unwrap_or_else(|| {
+            let settings = WorkerSettings {
+                max_execution_duration: self.max_execution_duration,
+                player_version,
+                player_runtime: self.player_runtime,
+                avm2_jit: self.avm2_jit,
+            };
+            (WorkerGroup::new(settings), WorkerId::PRIMORDIAL)
+        });
 
         // Instantiate the player.
         let fake_movie = Arc::new(SwfMovie::empty(player_version));
@@ -2135,6 +2928,8 @@ impl PlayerBuilder {
                 storage,
                 ui,
                 video,
+                dialogs,
+                local_connection,
 
                 // SWF info
                 swf: fake_movie.clone(),
@@ -2145,7 +2940,7 @@ impl PlayerBuilder {
                 frame_phase: Default::default(),
                 frame_accumulator: 0.0,
                 recent_run_frame_timings: VecDeque::with_capacity(10),
-                start_time: Instant::now(),
+                clock,
                 time_offset: 0,
                 time_til_next_timer: None,
                 max_execution_duration: self.max_execution_duration,
@@ -2158,8 +2953,12 @@ impl PlayerBuilder {
                 mouse_cursor_needs_check: false,
 
                 // Misc. state
-                rng: SmallRng::seed_from_u64(get_current_date_time().timestamp_millis() as u64),
-                system: SystemProperties::default(),
+                rng: SmallRng::seed_from_u64(clock.now().timestamp_millis() as u64),
+                system: SystemProperties {
+                    player_type: self.player_type,
+                    player_runtime: self.player_runtime,
+                    ..Default::default()
+                },
                 transform_stack: TransformStack::new(),
                 instance_counter: 0,
                 player_version,
@@ -2169,11 +2968,28 @@ impl PlayerBuilder {
                 self_reference: self_ref.clone(),
                 load_behavior: self.load_behavior,
                 spoofed_url: self.spoofed_url.clone(),
+                worker_group: worker_group.clone(),
+                worker_id,
+                _primordial_worker: worker_id
+                    .is_primordial()
+                    .then(|| PrimordialWorker::new(worker_group.clone())),
                 stub_tracker: StubCollection::new(),
+                compatibility: CompatibilityTracker::new(),
+                storage_quota: StorageQuota::new(),
+                policy_manager: PolicyManager::new(
+                    self.policy_mode,
+                    self.skip_socket_policy,
+                    self.trusted_locations,
+                ),
+                navigation: NavigationManager::new(self.navigation_policy),
+                cursors: CursorManager::new(),
+                embedder_events: EmbedderEvents::new(),
 
                 // GC data
                 gc_arena: Rc::new(RefCell::new(GcArena::new(
-                    ArenaParameters::default(),
+                    ArenaParameters::default()
+                        .set_pause_factor(self.gc_settings.heap_growth)
+                        .set_timing_factor(self.gc_settings.work_factor),
                     |gc_context| GcRoot {
                         callstack: GcCell::allocate(gc_context, GcCallstack::default()),
                         data: GcCell::allocate(
@@ -2187,12 +3003,19 @@ impl PlayerBuilder {
                                 drag_object: None,
                                 external_interface: ExternalInterface::new(),
                                 focus_tracker: FocusTracker::new(gc_context),
-                                library: Library::empty(),
+                                library: Library::new(self.font_map, self.font_fallbacks),
                                 load_manager: LoadManager::new(),
                                 mouse_hovered_object: None,
                                 mouse_pressed_object: None,
                                 avm1_shared_objects: HashMap::new(),
                                 avm2_shared_objects: HashMap::new(),
+                                stream_manager: StreamManager::new(),
+                                capture_manager: CaptureManager::new(),
+                                dialog_manager: DialogManager::new(),
+                                worker_manager: WorkerManager::new(worker_group.clone(), worker_id),
+                                local_connection_manager: LocalConnectionManager::new(),
+                                socket_manager: SocketManager::new(),
+                                touch_manager: TouchManager::new(),
                                 stage: Stage::empty(
                                     gc_context,
                                     self.fullscreen,
@@ -2204,6 +3027,8 @@ impl PlayerBuilder {
                         ),
                     },
                 ))),
+                gc_settings: self.gc_settings,
+                gc_time_this_frame: Duration::ZERO,
             })
         });
 
@@ -2215,6 +3040,12 @@ impl PlayerBuilder {
             fake_root.post_instantiation(context, None, Instantiator::Movie, false);
             context.stage.replace_at_depth(context, fake_root.into(), 0);
             Avm2::load_player_globals(context).expect("Unable to load AVM2 globals");
+            if self.avm2_jit {
+                #[cfg(feature = "avm2_jit")]
+                context.avm2.enable_jit();
+                #[cfg(not(feature = "avm2_jit"))]
+                tracing::warn!("The AVM2 JIT was requested, but this build doesn't include it");
+            }
             let stage = context.stage;
             stage.post_instantiation(context, None, Instantiator::Movie, false);
             stage.build_matrices(context);
@@ -2224,6 +3055,9 @@ impl PlayerBuilder {
             root.callstack.write(context).avm2 = Some(call_stack);
         });
         player_lock.audio.set_frame_rate(frame_rate);
+        if let Some(site_lock) = self.site_lock {
+            player_lock.set_site_lock(site_lock);
+        }
         player_lock.set_letterbox(self.letterbox);
         player_lock.set_viewport_dimensions(ViewportDimensions {
             width: self.viewport_width,
@@ -2233,6 +3067,9 @@ impl PlayerBuilder {
         if let Some(mut movie) = self.movie {
             if let Some(url) = self.spoofed_url.clone() {
                 movie.set_url(Some(url));
+                player_lock
+                    .compatibility
+                    .quirk_activated("Spoofed movie URL");
             }
             player_lock.set_root_movie(movie);
         }
@@ -0,0 +1,802 @@
+//! Cross-domain policy files, with which servers allow movies from other domains to load their
+//! data.
+
+use crate::avm1::SandboxType;
+use crate::backend::dialog::Dialog;
+use crate::backend::navigator::{Request, SocketConnection};
+use crate::context::UpdateContext;
+use crate::dialog::DialogPurpose;
+use crate::loader::Error;
+use crate::Player;
+use futures::channel::oneshot;
+use futures::StreamExt;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use url::Url;
+
+/// How Ruffle decides whether movies may load data from other domains.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PolicyMode {
+    /// Only allow the loads that the policy files of the servers permit, like Flash Player.
+    ///
+    /// Movies may only script movies from other domains that allow it with
+    /// `Security.allowDomain`, and local movies only run in the `localTrusted` sandbox if their
+    /// location is trusted.
+    Strict,
+
+    /// Allow all loads, ignoring policy files, and trust all local movies.
+    #[default]
+    Permissive,
+
+    /// Like `Strict`, but ask the user whether to allow the loads that no policy file permits.
+    Prompt,
+}
+
+/// How a policy file limits the other policy files of its server, as set by `<site-control>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SiteControl {
+    /// No policy file of the server permits anything.
+    None,
+
+    /// Only the policy file at the root of the server is honored.
+    MasterOnly,
+
+    /// All policy files of the server are honored.
+    All,
+}
+
+/// A domain allowed by `<allow-access-from>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowAccessFrom {
+    /// The domain, which may be `*` or start with `*.` to match subdomains.
+    domain: String,
+
+    /// Whether movies served over plain HTTP are excluded, if the policy file is served over
+    /// HTTPS.
+    secure: bool,
+
+    /// The ranges of ports that sockets may connect to, as set by `to-ports` in socket policy
+    /// files.
+    to_ports: Vec<(u16, u16)>,
+}
+
+impl AllowAccessFrom {
+    fn matches(&self, domain: &str) -> bool {
+        if self.domain == "*" {
+            return true;
+        }
+        match self.domain.strip_prefix("*.") {
+            Some(parent) => {
+                domain.eq_ignore_ascii_case(parent)
+                    || domain
+                        .to_ascii_lowercase()
+                        .ends_with(&format!(".{}", parent.to_ascii_lowercase()))
+            }
+            None => domain.eq_ignore_ascii_case(&self.domain),
+        }
+    }
+
+    fn allows_port(&self, port: u16) -> bool {
+        self.to_ports
+            .iter()
+            .any(|(first, last)| (*first..=*last).contains(&port))
+    }
+}
+
+/// Parses the `to-ports` attribute of a socket policy file, such as `80,443,1000-2000` or `*`.
+fn parse_ports(ports: &str) -> Vec<(u16, u16)> {
+    ports
+        .split(',')
+        .filter_map(|range| {
+            let range = range.trim();
+            if range == "*" {
+                return Some((0, u16::MAX));
+            }
+            match range.split_once('-') {
+                Some((first, last)) => {
+                    Some((first.trim().parse().ok()?, last.trim().parse().ok()?))
+                }
+                None => {
+                    let port = range.parse().ok()?;
+                    Some((port, port))
+                }
+            }
+        })
+        .collect()
+}
+
+/// A parsed `crossdomain.xml` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyFile {
+    site_control: SiteControl,
+    allow_access_from: Vec<AllowAccessFrom>,
+}
+
+impl PolicyFile {
+    /// Parses a policy file, returning `None` if it isn't one.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader::from_reader(data);
+        reader.expand_empty_elements(true);
+        reader.trim_text(true);
+
+        let mut policy = Self {
+            site_control: SiteControl::All,
+            allow_access_from: Vec::new(),
+        };
+        let mut is_policy = false;
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let start = match reader.read_event(&mut buf) {
+                Ok(Event::Start(start)) => start,
+                Ok(Event::Eof) => break,
+                Ok(_) => continue,
+                Err(_) => return None,
+            };
+            let attribute = |name: &[u8]| {
+                start
+                    .attributes()
+                    .with_checks(false)
+                    .filter_map(Result::ok)
+                    .find(|attribute| attribute.key == name)
+                    .map(|attribute| String::from_utf8_lossy(&attribute.value).into_owned())
+            };
+            match start.name() {
+                b"cross-domain-policy" => is_policy = true,
+                b"site-control" => {
+                    policy.site_control =
+                        match attribute(b"permitted-cross-domain-policies").as_deref() {
+                            Some("none") => SiteControl::None,
+                            Some("master-only") => SiteControl::MasterOnly,
+                            _ => SiteControl::All,
+                        };
+                }
+                b"allow-access-from" => {
+                    if let Some(domain) = attribute(b"domain") {
+                        policy.allow_access_from.push(AllowAccessFrom {
+                            domain,
+                            secure: attribute(b"secure").as_deref() != Some("false"),
+                            to_ports: attribute(b"to-ports")
+                                .map(|ports| parse_ports(&ports))
+                                .unwrap_or_default(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        is_policy.then_some(policy)
+    }
+
+    /// Returns whether this policy file allows movies from `url` to load data.
+    ///
+    /// `secure` is whether this policy file was served over HTTPS.
+    fn allows(&self, url: &Url, secure: bool) -> bool {
+        let domain = match url.host_str() {
+            Some(domain) => domain,
+            None => return false,
+        };
+        self.site_control != SiteControl::None
+            && self.allow_access_from.iter().any(|allow| {
+                allow.matches(domain) && (!secure || !allow.secure || url.scheme() == "https")
+            })
+    }
+
+    /// Returns whether this socket policy file allows movies from `url` to connect to `port`.
+    fn allows_socket(&self, url: &Url, port: u16) -> bool {
+        let domain = match url.host_str() {
+            Some(domain) => domain,
+            None => return false,
+        };
+        self.site_control != SiteControl::None
+            && self
+                .allow_access_from
+                .iter()
+                .any(|allow| allow.matches(domain) && allow.allows_port(port))
+    }
+}
+
+/// Whether a movie may load data from a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Access {
+    Allowed,
+    Denied,
+
+    /// These policy files have to be fetched before it can be decided.
+    Fetch(Vec<String>),
+
+    /// The user has to be asked about the server with the given origin.
+    Ask(String),
+}
+
+/// A domain whose movies may script a movie, as allowed by `Security.allowDomain`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowedDomain {
+    /// The domain, which may be `*` to allow all domains.
+    domain: String,
+
+    /// Whether movies served over plain HTTP may script a movie served over HTTPS, as allowed by
+    /// `Security.allowInsecureDomain`.
+    insecure: bool,
+}
+
+/// A question to the user about a server, and the loads waiting for the answer.
+struct Prompt {
+    origin: String,
+    waiters: Vec<oneshot::Sender<bool>>,
+}
+
+/// Keeps track of the policy files of the servers the movie loads data from.
+#[derive(Default)]
+pub struct PolicyManager {
+    mode: PolicyMode,
+
+    /// Whether sockets connect without asking the server for a socket policy file first.
+    skip_socket_policy: bool,
+
+    /// The URLs of the local files and directories whose movies run in the `localTrusted`
+    /// sandbox.
+    trusted_locations: Vec<String>,
+
+    /// The domains that movies allowed to script them, by the URL of the movie.
+    allowed_domains: HashMap<String, Vec<AllowedDomain>>,
+
+    /// The policy files fetched so far by their URL, or `None` if there was no valid one.
+    files: HashMap<String, Option<PolicyFile>>,
+
+    /// The socket policy files fetched so far by the host and port serving them, or `None` if
+    /// there was no valid one.
+    socket_files: HashMap<(String, u16), Option<PolicyFile>>,
+
+    /// The policy files the movie pointed to with `Security.loadPolicyFile`.
+    requested: Vec<String>,
+
+    /// The user's answers about servers without a permitting policy file, by origin.
+    answers: HashMap<String, bool>,
+
+    /// The questions to the user, of which only the first one is shown at a time.
+    prompts: VecDeque<Prompt>,
+}
+
+impl PolicyManager {
+    pub fn new(mode: PolicyMode, skip_socket_policy: bool, trusted_locations: Vec<String>) -> Self {
+        Self {
+            mode,
+            skip_socket_policy,
+            trusted_locations,
+            ..Default::default()
+        }
+    }
+
+    pub fn mode(&self) -> PolicyMode {
+        self.mode
+    }
+
+    /// Returns the sandbox that the movie at `movie_url` runs in.
+    ///
+    /// `use_network_sandbox` is whether the movie asks to access the network rather than local
+    /// files, which decides the sandbox of untrusted local movies.
+    pub fn sandbox_type(&self, movie_url: Option<&str>, use_network_sandbox: bool) -> SandboxType {
+        let movie_url = match movie_url.and_then(|movie_url| Url::parse(movie_url).ok()) {
+            Some(movie_url) => movie_url,
+            None => return SandboxType::LocalTrusted,
+        };
+        if is_http(&movie_url) {
+            SandboxType::Remote
+        } else if self.mode == PolicyMode::Permissive || self.is_trusted(&movie_url) {
+            // Like in the standalone player, all local movies are trusted unless Flash's
+            // security model is emulated.
+            SandboxType::LocalTrusted
+        } else if use_network_sandbox {
+            SandboxType::LocalWithNetwork
+        } else {
+            SandboxType::LocalWithFile
+        }
+    }
+
+    /// Returns whether `url` is one of the trusted locations, or inside one of them.
+    fn is_trusted(&self, url: &Url) -> bool {
+        self.trusted_locations.iter().any(|location| {
+            url.as_str()
+                .strip_prefix(location.as_str())
+                .map_or(false, |rest| {
+                    rest.is_empty() || rest.starts_with('/') || location.ends_with('/')
+                })
+        })
+    }
+
+    /// Lets movies from `domain` script the movie at `movie_url`.
+    ///
+    /// With `insecure`, movies served over plain HTTP may script it even if it's served over
+    /// HTTPS.
+    pub fn allow_domain(&mut self, movie_url: Option<&str>, domain: &str, insecure: bool) {
+        let movie_url = match movie_url {
+            Some(movie_url) => movie_url.to_string(),
+            None => return,
+        };
+        // Full URLs may be given instead of domains.
+        let domain = Url::parse(domain)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| domain.to_string());
+
+        let allowed = self.allowed_domains.entry(movie_url).or_default();
+        match allowed.iter_mut().find(|allowed| allowed.domain == domain) {
+            Some(allowed) => allowed.insecure |= insecure,
+            None => allowed.push(AllowedDomain { domain, insecure }),
+        }
+    }
+
+    /// Returns whether code of the movie at `accessor_url` may script the movie at `target_url`.
+    pub fn allows_scripting(&self, target_url: Option<&str>, accessor_url: Option<&str>) -> bool {
+        if self.mode == PolicyMode::Permissive {
+            return true;
+        }
+        let (target, accessor) = match (
+            target_url.and_then(|url| Url::parse(url).ok()),
+            accessor_url.and_then(|url| Url::parse(url).ok()),
+        ) {
+            (Some(target), Some(accessor)) => (target, accessor),
+            _ => return true,
+        };
+        // Movies from the same server, and local movies among each other, may always script
+        // each other.
+        if target.origin() == accessor.origin() || (!is_http(&target) && !is_http(&accessor)) {
+            return true;
+        }
+
+        let domain = accessor.host_str().unwrap_or_default();
+        let downgrade = target.scheme() == "https" && accessor.scheme() != "https";
+        self.allowed_domains
+            .get(target_url.unwrap_or_default())
+            .map_or(false, |allowed| {
+                allowed.iter().any(|allowed| {
+                    (allowed.domain == "*" || allowed.domain.eq_ignore_ascii_case(domain))
+                        && (allowed.insecure || !downgrade)
+                })
+            })
+    }
+
+    /// Remembers a policy file that is consulted for loads from its server, besides
+    /// `/crossdomain.xml`.
+    pub fn load_policy_file(&mut self, movie_url: Option<&str>, url: &str) {
+        let url = match resolve(movie_url, url) {
+            Some(url) => url.to_string(),
+            None => return,
+        };
+        if !self.requested.contains(&url) {
+            self.requested.push(url);
+        }
+    }
+
+    /// Decides whether the movie at `movie_url` may load data from `url`, as far as the policy
+    /// files fetched so far tell.
+    fn access(&self, movie_url: Option<&str>, url: &str) -> Access {
+        if self.mode == PolicyMode::Permissive {
+            return Access::Allowed;
+        }
+        let movie_url = match movie_url.and_then(|movie_url| Url::parse(movie_url).ok()) {
+            Some(movie_url) => movie_url,
+            None => return Access::Allowed,
+        };
+        // Local movies are trusted.
+        if !is_http(&movie_url) {
+            return Access::Allowed;
+        }
+        let url = match movie_url.join(url) {
+            Ok(url) => url,
+            Err(_) => return Access::Allowed,
+        };
+        if url.origin() == movie_url.origin() {
+            return Access::Allowed;
+        }
+        if !is_http(&url) {
+            return Access::Denied;
+        }
+
+        let master = match url.join("/crossdomain.xml") {
+            Ok(master) => master.to_string(),
+            Err(_) => return Access::Denied,
+        };
+        // Policy files only apply to their own directory and below.
+        let others = self.requested.iter().filter(|policy_url| {
+            Url::parse(policy_url).map_or(false, |policy_url| {
+                let path = policy_url.path();
+                let directory = &path[..path.rfind('/').map_or(0, |index| index + 1)];
+                policy_url.origin() == url.origin() && url.path().starts_with(directory)
+            })
+        });
+        let candidates: Vec<&String> = std::iter::once(&master).chain(others).collect();
+
+        let missing: Vec<String> = candidates
+            .iter()
+            .filter(|policy_url| !self.files.contains_key(**policy_url))
+            .map(|policy_url| policy_url.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Access::Fetch(missing);
+        }
+
+        let secure = url.scheme() == "https";
+        let site_control = self
+            .files
+            .get(&master)
+            .and_then(Option::as_ref)
+            .map_or(SiteControl::All, |policy| policy.site_control);
+        let allowed = site_control != SiteControl::None
+            && candidates
+                .iter()
+                .filter(|policy_url| site_control == SiteControl::All || **policy_url == &master)
+                .filter_map(|policy_url| self.files.get(*policy_url).and_then(Option::as_ref))
+                .any(|policy| policy.allows(&movie_url, secure));
+
+        if allowed {
+            Access::Allowed
+        } else if self.mode == PolicyMode::Prompt {
+            let origin = url.origin().ascii_serialization();
+            match self.answers.get(&origin) {
+                Some(true) => Access::Allowed,
+                Some(false) => Access::Denied,
+                None => Access::Ask(origin),
+            }
+        } else {
+            Access::Denied
+        }
+    }
+
+    /// Returns whether the movie at `movie_url` needs a socket policy file to connect sockets.
+    pub fn needs_socket_policy(&self, movie_url: Option<&str>) -> bool {
+        self.mode != PolicyMode::Permissive
+            && !self.skip_socket_policy
+            && movie_url
+                .and_then(|movie_url| Url::parse(movie_url).ok())
+                .map_or(false, |movie_url| is_http(&movie_url))
+    }
+
+    /// Returns where to ask for the socket policy file covering `host`, in order: the locations
+    /// given to `Security.loadPolicyFile`, the policy port 843 and the destination `port`
+    /// itself.
+    fn socket_policy_locations(&self, host: &str, port: u16) -> Vec<u16> {
+        let requested = self.requested.iter().filter_map(|url| {
+            let url = Url::parse(url).ok()?;
+            if url.scheme() == "xmlsocket" && url.host_str() == Some(host) {
+                url.port()
+            } else {
+                None
+            }
+        });
+        let mut locations: Vec<u16> = requested.chain([SOCKET_POLICY_PORT, port]).collect();
+        locations.dedup();
+        locations
+    }
+
+    /// Asks the user whether the movie may access the server with the given origin, or the
+    /// socket server with the given address.
+    fn ask(context: &mut UpdateContext<'_, '_>, origin: String) -> oneshot::Receiver<bool> {
+        let (sender, receiver) = oneshot::channel();
+        let policy_manager = &mut context.policy_manager;
+        if let Some(prompt) = policy_manager
+            .prompts
+            .iter_mut()
+            .find(|prompt| prompt.origin == origin)
+        {
+            prompt.waiters.push(sender);
+            return receiver;
+        }
+
+        policy_manager.prompts.push_back(Prompt {
+            origin,
+            waiters: vec![sender],
+        });
+        if policy_manager.prompts.len() == 1 {
+            Self::show_prompt(context);
+        }
+        receiver
+    }
+
+    fn show_prompt(context: &mut UpdateContext<'_, '_>) {
+        let origin = match context.policy_manager.prompts.front() {
+            Some(prompt) => &prompt.origin,
+            None => return,
+        };
+        let message = format!(
+            "The movie is trying to access {origin}, which doesn't allow it.\n\nAllow anyway?"
+        );
+        context.dialog_manager.show(
+            context.dialogs,
+            Dialog::Confirm {
+                title: "Cross-domain access".to_string(),
+                message,
+            },
+            DialogPurpose::CrossDomain,
+        );
+    }
+
+    /// Applies the user's answer to the question shown last, and shows the next one.
+    pub fn resolve_prompt(context: &mut UpdateContext<'_, '_>, granted: bool) {
+        if let Some(prompt) = context.policy_manager.prompts.pop_front() {
+            context
+                .policy_manager
+                .answers
+                .insert(prompt.origin, granted);
+            for waiter in prompt.waiters {
+                let _ = waiter.send(granted);
+            }
+        }
+        Self::show_prompt(context);
+    }
+}
+
+/// The port on which servers answer requests for their socket policy file.
+const SOCKET_POLICY_PORT: u16 = 843;
+
+/// The request for a socket policy file, sent to the server before connecting a socket.
+const SOCKET_POLICY_REQUEST: &[u8] = b"<policy-file-request/>\0";
+
+/// The text of the `SecurityError` reported when the movie at `movie_url` may not load data from
+/// `url`.
+pub fn violation_message(movie_url: Option<&str>, url: &str) -> String {
+    format!(
+        "Error #2048: Security sandbox violation: {} cannot load data from {}.",
+        movie_url.unwrap_or_default(),
+        url
+    )
+}
+
+fn is_http(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+fn resolve(movie_url: Option<&str>, url: &str) -> Option<Url> {
+    match movie_url.and_then(|movie_url| Url::parse(movie_url).ok()) {
+        Some(movie_url) => movie_url.join(url).ok(),
+        None => Url::parse(url).ok(),
+    }
+}
+
+/// Waits until it's known whether the root movie may load data from `url`, fetching the policy
+/// files of its server as needed.
+pub async fn check_access(player: &Mutex<Player>, url: &str) -> Result<(), Error> {
+    loop {
+        let access = player
+            .lock()
+            .unwrap()
+            .update(|uc| uc.policy_manager.access(uc.swf.url(), url));
+        match access {
+            Access::Allowed => return Ok(()),
+            Access::Denied => return Err(Error::SecurityViolation(url.to_string())),
+            Access::Fetch(policy_urls) => {
+                for policy_url in policy_urls {
+                    let fetch = player
+                        .lock()
+                        .unwrap()
+                        .navigator()
+                        .fetch(Request::get(policy_url.clone()));
+                    let policy = match fetch.await {
+                        Ok(response) => PolicyFile::parse(&response.body),
+                        Err(_) => None,
+                    };
+                    if policy.is_none() {
+                        tracing::warn!("No valid policy file at {}", policy_url);
+                    }
+                    player.lock().unwrap().update(|uc| {
+                        uc.policy_manager.files.insert(policy_url, policy);
+                    });
+                }
+            }
+            Access::Ask(origin) => {
+                let answer = player
+                    .lock()
+                    .unwrap()
+                    .update(|uc| PolicyManager::ask(uc, origin));
+                if !answer.await.unwrap_or(false) {
+                    return Err(Error::SecurityViolation(url.to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// Asks the server at `host` for its socket policy file on `port`, returning `None` if it doesn't
+/// answer with a valid one.
+async fn fetch_socket_policy(player: &Mutex<Player>, host: &str, port: u16) -> Option<PolicyFile> {
+    let connect = player
+        .lock()
+        .unwrap()
+        .navigator()
+        .connect_socket(host, port);
+    let SocketConnection {
+        mut incoming,
+        outgoing,
+    } = connect.await.ok()?;
+    outgoing
+        .unbounded_send(SOCKET_POLICY_REQUEST.to_vec())
+        .ok()?;
+
+    // The policy file is terminated by a zero byte, after which the server closes the connection.
+    let mut data = Vec::new();
+    while let Some(chunk) = incoming.next().await {
+        match chunk.iter().position(|&byte| byte == 0) {
+            Some(end) => {
+                data.extend_from_slice(&chunk[..end]);
+                break;
+            }
+            None => data.extend_from_slice(&chunk),
+        }
+    }
+    PolicyFile::parse(&data)
+}
+
+/// Waits until it's known whether the root movie may connect a socket to `port` of `host`,
+/// asking the server for its socket policy file as needed.
+pub async fn check_socket_access(
+    player: &Mutex<Player>,
+    host: &str,
+    port: u16,
+) -> Result<(), Error> {
+    let (movie_url, locations) = player.lock().unwrap().update(|uc| {
+        (
+            uc.swf.url().and_then(|url| Url::parse(url).ok()),
+            uc.policy_manager.socket_policy_locations(host, port),
+        )
+    });
+    let movie_url = match movie_url {
+        Some(movie_url) => movie_url,
+        None => return Ok(()),
+    };
+
+    let mut allowed = false;
+    for policy_port in locations {
+        let key = (host.to_string(), policy_port);
+        let cached = player
+            .lock()
+            .unwrap()
+            .update(|uc| uc.policy_manager.socket_files.get(&key).cloned());
+        let policy = match cached {
+            Some(policy) => policy,
+            None => {
+                let policy = fetch_socket_policy(player, host, policy_port).await;
+                let stored = policy.clone();
+                player.lock().unwrap().update(|uc| {
+                    uc.policy_manager.socket_files.insert(key, stored);
+                });
+                policy
+            }
+        };
+        // The first policy file found is the one that counts.
+        if let Some(policy) = policy {
+            allowed = policy.allows_socket(&movie_url, port);
+            break;
+        }
+    }
+
+    let address = format!("{host}:{port}");
+    if allowed {
+        return Ok(());
+    }
+    let (mode, answer) = player.lock().unwrap().update(|uc| {
+        (
+            uc.policy_manager.mode,
+            uc.policy_manager.answers.get(&address).copied(),
+        )
+    });
+    let granted = match (mode, answer) {
+        (PolicyMode::Prompt, Some(answer)) => answer,
+        (PolicyMode::Prompt, None) => {
+            let origin = address.clone();
+            let answer = player
+                .lock()
+                .unwrap()
+                .update(|uc| PolicyManager::ask(uc, origin));
+            answer.await.unwrap_or(false)
+        }
+        _ => false,
+    };
+    if granted {
+        Ok(())
+    } else {
+        Err(Error::SecurityViolation(address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE cross-domain-policy SYSTEM "http://www.adobe.com/xml/dtds/cross-domain-policy.dtd">
+<cross-domain-policy>
+    <site-control permitted-cross-domain-policies="master-only"/>
+    <allow-access-from domain="*.example.com"/>
+    <allow-access-from domain="games.test" secure="false"/>
+</cross-domain-policy>"#;
+
+    #[test]
+    fn parse_policy_file() {
+        let policy = PolicyFile::parse(POLICY.as_bytes()).unwrap();
+        assert_eq!(policy.site_control, SiteControl::MasterOnly);
+        assert_eq!(policy.allow_access_from.len(), 2);
+        assert!(PolicyFile::parse(b"<html><body>Not found</body></html>").is_none());
+    }
+
+    #[test]
+    fn socket_policy_ports() {
+        let policy = PolicyFile::parse(
+            br#"<cross-domain-policy>
+    <allow-access-from domain="*.example.com" to-ports="80,5000-5010"/>
+    <allow-access-from domain="games.test" to-ports="*"/>
+    <allow-access-from domain="chat.test"/>
+</cross-domain-policy>"#,
+        )
+        .unwrap();
+        let allows = |url: &str, port| policy.allows_socket(&Url::parse(url).unwrap(), port);
+        assert!(allows("http://www.example.com/game.swf", 80));
+        assert!(allows("http://www.example.com/game.swf", 5005));
+        assert!(!allows("http://www.example.com/game.swf", 5011));
+        assert!(allows("http://games.test/game.swf", 1234));
+        assert!(!allows("http://chat.test/game.swf", 80));
+    }
+
+    #[test]
+    fn sandbox_types() {
+        let manager = PolicyManager::new(
+            PolicyMode::Strict,
+            false,
+            vec!["file:///home/user/games".to_string()],
+        );
+        let sandbox_type = |url: &str, network| manager.sandbox_type(Some(url), network);
+        assert_eq!(
+            sandbox_type("https://example.com/game.swf", false),
+            SandboxType::Remote
+        );
+        assert_eq!(
+            sandbox_type("file:///home/user/games/game.swf", false),
+            SandboxType::LocalTrusted
+        );
+        assert_eq!(
+            sandbox_type("file:///home/user/games2/game.swf", false),
+            SandboxType::LocalWithFile
+        );
+        assert_eq!(
+            sandbox_type("file:///tmp/game.swf", true),
+            SandboxType::LocalWithNetwork
+        );
+        assert_eq!(
+            PolicyManager::default().sandbox_type(Some("file:///tmp/game.swf"), true),
+            SandboxType::LocalTrusted
+        );
+    }
+
+    #[test]
+    fn allow_domain() {
+        let mut manager = PolicyManager::new(PolicyMode::Strict, false, Vec::new());
+        let game = Some("https://games.test/game.swf");
+        let ad = Some("http://ads.example.com/ad.swf");
+        assert!(manager.allows_scripting(game, Some("https://games.test/other/level.swf")));
+        assert!(!manager.allows_scripting(game, ad));
+
+        manager.allow_domain(game, "ads.example.com", false);
+        assert!(!manager.allows_scripting(game, ad));
+        assert!(manager.allows_scripting(game, Some("https://ads.example.com/ad.swf")));
+
+        manager.allow_domain(game, "http://ads.example.com/", true);
+        assert!(manager.allows_scripting(game, ad));
+        assert!(!manager.allows_scripting(ad, game));
+    }
+
+    #[test]
+    fn policy_file_domains() {
+        let policy = PolicyFile::parse(POLICY.as_bytes()).unwrap();
+        let allows = |url: &str, secure| policy.allows(&Url::parse(url).unwrap(), secure);
+        assert!(allows("http://example.com/game.swf", false));
+        assert!(allows("http://cdn.example.com/game.swf", false));
+        assert!(!allows("http://badexample.com/game.swf", false));
+        assert!(!allows("http://cdn.example.com/game.swf", true));
+        assert!(allows("https://cdn.example.com/game.swf", true));
+        assert!(allows("http://games.test/game.swf", true));
+    }
+}
@@ -0,0 +1,304 @@
+//! RTMP connections of `NetConnection`, to play streams from media servers.
+//!
+//! The protocol itself is implemented by `RtmpSession`, which is driven by a
+//! future of the `LoadManager` over a socket of the navigator backend. The
+//! player side only exchanges `RtmpRequest`s and `SessionEvent`s with it.
+
+pub mod amf0;
+mod chunk;
+mod session;
+
+pub use crate::rtmp::amf0::Amf0Value;
+pub use crate::rtmp::session::{status, RtmpRequest, RtmpSession, RtmpUrl, SessionEvent};
+
+use crate::avm2::object::TObject;
+use crate::avm2::{
+    Activation as Avm2Activation, ArrayObject as Avm2ArrayObject, Avm2, Error as Avm2Error,
+    Multiname, Object as Avm2Object, Value as Avm2Value,
+};
+use crate::context::UpdateContext;
+use crate::streams::NetStream;
+use crate::string::AvmString;
+use futures::channel::mpsc::{self, UnboundedSender};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::fmt;
+
+/// A connection to an RTMP server, made by `NetConnection.connect`.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct RtmpConnection<'gc>(GcCell<'gc, RtmpConnectionData<'gc>>);
+
+impl fmt::Debug for RtmpConnection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RtmpConnection")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct RtmpConnectionData<'gc> {
+    /// The requests to the session of the open connection, if any.
+    #[collect(require_static)]
+    requests: Option<UnboundedSender<RtmpRequest>>,
+
+    /// Whether the server accepted the connection.
+    connected: bool,
+
+    /// The streams playing over this connection, by their local ID.
+    streams: Vec<(u32, NetStream<'gc>)>,
+
+    next_local_id: u32,
+
+    /// The `flash.net.NetConnection` object of this connection.
+    avm_object: Option<Avm2Object<'gc>>,
+}
+
+impl<'gc> RtmpConnection<'gc> {
+    pub fn new(gc_context: MutationContext<'gc, '_>, avm_object: Option<Avm2Object<'gc>>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            RtmpConnectionData {
+                requests: None,
+                connected: false,
+                streams: Vec::new(),
+                next_local_id: 1,
+                avm_object,
+            },
+        ))
+    }
+
+    /// Sets the ActionScript object that receives the status events of this connection.
+    pub fn set_avm_object(self, gc_context: MutationContext<'gc, '_>, avm_object: Avm2Object<'gc>) {
+        self.0.write(gc_context).avm_object = Some(avm_object);
+    }
+
+    pub fn is_connected(self) -> bool {
+        self.0.read().connected
+    }
+
+    /// Starts connecting to the server at `url`, dropping any previous
+    /// connection.
+    ///
+    /// Returns `false` if `url` isn't a supported RTMP URL.
+    pub fn connect(self, context: &mut UpdateContext<'_, 'gc>, url: &str) -> bool {
+        let url = match RtmpUrl::parse(url) {
+            Some(url) => url,
+            None => return false,
+        };
+
+        let (sender, receiver) = mpsc::unbounded();
+        let mut write = self.0.write(context.gc_context);
+        if let Some(requests) = write.requests.replace(sender) {
+            let _ = requests.unbounded_send(RtmpRequest::Close);
+        }
+        write.connected = false;
+        write.streams.clear();
+        drop(write);
+
+        let future = context
+            .load_manager
+            .connect_rtmp(context.player.clone(), self, url, receiver);
+        context.navigator.spawn_future(future);
+        true
+    }
+
+    /// Closes the connection, reporting it if it was established.
+    pub fn close(self, context: &mut UpdateContext<'_, 'gc>) {
+        let mut write = self.0.write(context.gc_context);
+        if let Some(requests) = write.requests.take() {
+            let _ = requests.unbounded_send(RtmpRequest::Close);
+        }
+        let was_connected = std::mem::replace(&mut write.connected, false);
+        write.streams.clear();
+        let object = write.avm_object;
+        drop(write);
+
+        if let (true, Some(object)) = (was_connected, object) {
+            dispatch_status(
+                context,
+                object,
+                &status("NetConnection.Connect.Closed", "status"),
+            );
+        }
+    }
+
+    /// Plays the server stream `name` with `stream`, starting at `start`
+    /// seconds.
+    pub fn play(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        stream: NetStream<'gc>,
+        name: String,
+        start: f64,
+    ) {
+        let mut write = self.0.write(context.gc_context);
+        let existing = write
+            .streams
+            .iter()
+            .find(|(_, playing)| NetStream::ptr_eq(*playing, stream))
+            .map(|(local_id, _)| *local_id);
+        let local_id = match existing {
+            Some(local_id) => local_id,
+            None => {
+                let local_id = write.next_local_id;
+                write.next_local_id += 1;
+                write.streams.push((local_id, stream));
+                local_id
+            }
+        };
+        drop(write);
+
+        stream.play_rtmp(context, self, local_id);
+        self.request(RtmpRequest::Play {
+            local_id,
+            name,
+            start,
+        });
+    }
+
+    /// Stops playing a stream over this connection.
+    pub fn close_stream(self, context: &mut UpdateContext<'_, 'gc>, local_id: u32) {
+        self.0
+            .write(context.gc_context)
+            .streams
+            .retain(|(id, _)| *id != local_id);
+        self.request(RtmpRequest::CloseStream { local_id });
+    }
+
+    /// Sends a request to the session of this connection.
+    pub fn request(self, request: RtmpRequest) {
+        if let Some(requests) = &self.0.read().requests {
+            let _ = requests.unbounded_send(request);
+        }
+    }
+
+    /// Handles something that happened on the connection.
+    pub fn handle_event(self, context: &mut UpdateContext<'_, 'gc>, event: SessionEvent) {
+        match event {
+            SessionEvent::ConnectionStatus(info) => {
+                let code = info.get("code").and_then(Amf0Value::as_str);
+                let mut write = self.0.write(context.gc_context);
+                match code {
+                    Some("NetConnection.Connect.Success") => write.connected = true,
+                    Some(
+                        "NetConnection.Connect.Closed"
+                        | "NetConnection.Connect.Failed"
+                        | "NetConnection.Connect.Rejected",
+                    ) => {
+                        write.connected = false;
+                        write.requests = None;
+                        write.streams.clear();
+                    }
+                    _ => {}
+                }
+                let object = write.avm_object;
+                drop(write);
+
+                if let Some(object) = object {
+                    dispatch_status(context, object, &info);
+                }
+            }
+            SessionEvent::StreamStatus { local_id, info } => {
+                let object = self.stream(local_id).and_then(|stream| stream.avm_object());
+                if let Some(object) = object {
+                    dispatch_status(context, object, &info);
+                }
+            }
+            SessionEvent::StreamData { local_id, tag } => {
+                if let Some(stream) = self.stream(local_id) {
+                    stream.load_buffer(context, &tag);
+                }
+            }
+        }
+    }
+
+    /// Reports a connection that ended, or could never be opened.
+    pub fn report_closed(self, context: &mut UpdateContext<'_, 'gc>, was_opened: bool) {
+        if self.0.read().requests.is_none() {
+            // The connection was already closed by ActionScript or the server.
+            return;
+        }
+        let code = if was_opened {
+            "NetConnection.Connect.Closed"
+        } else {
+            "NetConnection.Connect.Failed"
+        };
+        let level = if was_opened { "status" } else { "error" };
+        self.handle_event(context, SessionEvent::ConnectionStatus(status(code, level)));
+    }
+
+    fn stream(self, local_id: u32) -> Option<NetStream<'gc>> {
+        self.0
+            .read()
+            .streams
+            .iter()
+            .find(|(id, _)| *id == local_id)
+            .map(|(_, stream)| *stream)
+    }
+}
+
+/// Dispatches a `netStatus` event with the info object sent by the server.
+fn dispatch_status<'gc>(
+    context: &mut UpdateContext<'_, 'gc>,
+    target: Avm2Object<'gc>,
+    info: &Amf0Value,
+) {
+    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+    let event = to_avm2(&mut activation, info).and_then(|info| {
+        activation.avm2().classes().netstatusevent.construct(
+            &mut activation,
+            &["netStatus".into(), false.into(), false.into(), info],
+        )
+    });
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            tracing::error!("Unable to create netStatus event: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = Avm2::dispatch_event(&mut activation.context, event, target) {
+        tracing::error!(
+            "Encountered AVM2 error when dispatching netStatus event: {}",
+            e
+        );
+    }
+}
+
+/// Converts a value sent by a server or stored in a stream to ActionScript.
+pub(crate) fn to_avm2<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc>,
+    value: &Amf0Value,
+) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+    Ok(match value {
+        Amf0Value::Number(number) => (*number).into(),
+        Amf0Value::Boolean(boolean) => (*boolean).into(),
+        Amf0Value::String(string) => {
+            AvmString::new_utf8(activation.context.gc_context, string).into()
+        }
+        Amf0Value::Null => Avm2Value::Null,
+        Amf0Value::Undefined => Avm2Value::Undefined,
+        Amf0Value::Object(properties) | Amf0Value::EcmaArray(properties) => {
+            let mut object = activation
+                .avm2()
+                .classes()
+                .object
+                .construct(activation, &[])?;
+            for (name, value) in properties {
+                let value = to_avm2(activation, value)?;
+                let name = AvmString::new_utf8(activation.context.gc_context, name);
+                object.set_property(&Multiname::public(name), value, activation)?;
+            }
+            object.into()
+        }
+        Amf0Value::StrictArray(values) => {
+            let values = values
+                .iter()
+                .map(|value| to_avm2(activation, value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Avm2ArrayObject::from_storage(activation, values.into_iter().collect())?.into()
+        }
+    })
+}
@@ -0,0 +1,244 @@
+//! The subset of AMF0 used by RTMP command and data messages.
+//!
+//! Commands are written as a sequence of bare values, without the framing of
+//! the shared object format, so they are encoded here directly.
+
+use byteorder::{BigEndian, ByteOrder};
+
+const NUMBER: u8 = 0x00;
+const BOOLEAN: u8 = 0x01;
+const STRING: u8 = 0x02;
+const OBJECT: u8 = 0x03;
+const NULL: u8 = 0x05;
+const UNDEFINED: u8 = 0x06;
+const ECMA_ARRAY: u8 = 0x08;
+const OBJECT_END: u8 = 0x09;
+const STRICT_ARRAY: u8 = 0x0A;
+const DATE: u8 = 0x0B;
+const LONG_STRING: u8 = 0x0C;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Amf0Value {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    Object(Vec<(String, Amf0Value)>),
+    Null,
+    Undefined,
+    EcmaArray(Vec<(String, Amf0Value)>),
+    StrictArray(Vec<Amf0Value>),
+}
+
+impl Amf0Value {
+    /// Looks up a property of an object or associative array.
+    pub fn get(&self, key: &str) -> Option<&Amf0Value> {
+        match self {
+            Amf0Value::Object(properties) | Amf0Value::EcmaArray(properties) => properties
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Amf0Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Amf0Value::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Amf0Value {
+    fn from(string: &str) -> Self {
+        Amf0Value::String(string.to_string())
+    }
+}
+
+impl From<f64> for Amf0Value {
+    fn from(number: f64) -> Self {
+        Amf0Value::Number(number)
+    }
+}
+
+impl From<bool> for Amf0Value {
+    fn from(boolean: bool) -> Self {
+        Amf0Value::Boolean(boolean)
+    }
+}
+
+/// Appends the encoding of `value` to `out`.
+pub fn write(out: &mut Vec<u8>, value: &Amf0Value) {
+    match value {
+        Amf0Value::Number(number) => {
+            out.push(NUMBER);
+            out.extend_from_slice(&number.to_be_bytes());
+        }
+        Amf0Value::Boolean(boolean) => {
+            out.push(BOOLEAN);
+            out.push(u8::from(*boolean));
+        }
+        Amf0Value::String(string) => {
+            if string.len() > usize::from(u16::MAX) {
+                out.push(LONG_STRING);
+                out.extend_from_slice(&(string.len() as u32).to_be_bytes());
+            } else {
+                out.push(STRING);
+                out.extend_from_slice(&(string.len() as u16).to_be_bytes());
+            }
+            out.extend_from_slice(string.as_bytes());
+        }
+        Amf0Value::Object(properties) => {
+            out.push(OBJECT);
+            write_properties(out, properties);
+        }
+        Amf0Value::Null => out.push(NULL),
+        Amf0Value::Undefined => out.push(UNDEFINED),
+        Amf0Value::EcmaArray(properties) => {
+            out.push(ECMA_ARRAY);
+            out.extend_from_slice(&(properties.len() as u32).to_be_bytes());
+            write_properties(out, properties);
+        }
+        Amf0Value::StrictArray(values) => {
+            out.push(STRICT_ARRAY);
+            out.extend_from_slice(&(values.len() as u32).to_be_bytes());
+            for value in values {
+                write(out, value);
+            }
+        }
+    }
+}
+
+fn write_properties(out: &mut Vec<u8>, properties: &[(String, Amf0Value)]) {
+    for (name, value) in properties {
+        out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        out.extend_from_slice(name.as_bytes());
+        write(out, value);
+    }
+    out.extend_from_slice(&[0, 0, OBJECT_END]);
+}
+
+/// Reads all values of a command or data message.
+///
+/// Returns `None` if the data is invalid or uses unsupported types.
+pub fn read_all(mut data: &[u8]) -> Option<Vec<Amf0Value>> {
+    let mut values = Vec::new();
+    while !data.is_empty() {
+        values.push(read(&mut data)?);
+    }
+    Some(values)
+}
+
+/// Reads a single value, advancing `data` past it.
+pub fn read(data: &mut &[u8]) -> Option<Amf0Value> {
+    let marker = take(data, 1)?[0];
+    let value = match marker {
+        NUMBER => Amf0Value::Number(BigEndian::read_f64(take(data, 8)?)),
+        BOOLEAN => Amf0Value::Boolean(take(data, 1)?[0] != 0),
+        STRING => {
+            let length = BigEndian::read_u16(take(data, 2)?);
+            Amf0Value::String(read_utf8(data, length.into())?)
+        }
+        LONG_STRING => {
+            let length = BigEndian::read_u32(take(data, 4)?);
+            Amf0Value::String(read_utf8(data, length as usize)?)
+        }
+        OBJECT => Amf0Value::Object(read_properties(data)?),
+        NULL => Amf0Value::Null,
+        UNDEFINED => Amf0Value::Undefined,
+        ECMA_ARRAY => {
+            // The count is only a hint, the properties are terminated like an object's.
+            take(data, 4)?;
+            Amf0Value::EcmaArray(read_properties(data)?)
+        }
+        STRICT_ARRAY => {
+            let count = BigEndian::read_u32(take(data, 4)?);
+            let mut values = Vec::new();
+            for _ in 0..count {
+                values.push(read(data)?);
+            }
+            Amf0Value::StrictArray(values)
+        }
+        DATE => {
+            let time = BigEndian::read_f64(take(data, 8)?);
+            // The time zone is reserved, and always ignored.
+            take(data, 2)?;
+            Amf0Value::Number(time)
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn read_properties(data: &mut &[u8]) -> Option<Vec<(String, Amf0Value)>> {
+    let mut properties = Vec::new();
+    loop {
+        let length = BigEndian::read_u16(take(data, 2)?);
+        if length == 0 && data.first() == Some(&OBJECT_END) {
+            take(data, 1)?;
+            return Some(properties);
+        }
+        let name = read_utf8(data, length.into())?;
+        properties.push((name, read(data)?));
+    }
+}
+
+fn read_utf8(data: &mut &[u8], length: usize) -> Option<String> {
+    Some(String::from_utf8_lossy(take(data, length)?).into_owned())
+}
+
+fn take<'a>(data: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if data.len() < length {
+        return None;
+    }
+    let (taken, rest) = data.split_at(length);
+    *data = rest;
+    Some(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let values = vec![
+            Amf0Value::from("connect"),
+            Amf0Value::from(1.0),
+            Amf0Value::Object(vec![
+                ("app".to_string(), Amf0Value::from("live")),
+                ("fpad".to_string(), Amf0Value::from(false)),
+                (
+                    "list".to_string(),
+                    Amf0Value::StrictArray(vec![Amf0Value::Null, Amf0Value::Undefined]),
+                ),
+            ]),
+            Amf0Value::EcmaArray(vec![("duration".to_string(), Amf0Value::from(12.5))]),
+        ];
+
+        let mut data = Vec::new();
+        for value in &values {
+            write(&mut data, value);
+        }
+        assert_eq!(read_all(&data), Some(values));
+    }
+
+    #[test]
+    fn read_string() {
+        let data = [STRING, 0, 3, b'a', b'b', b'c'];
+        assert_eq!(read_all(&data), Some(vec![Amf0Value::from("abc")]));
+    }
+
+    #[test]
+    fn reject_truncated_data() {
+        assert_eq!(read_all(&[NUMBER, 0, 0]), None);
+        assert_eq!(read_all(&[OBJECT, 0, 1, b'a']), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! The RTMP chunk stream, which splits messages into interleaved chunks.
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::collections::HashMap;
+
+/// The chunk size both sides start with, until changed by a `SetChunkSize`
+/// message.
+pub const DEFAULT_CHUNK_SIZE: usize = 128;
+
+/// The value of a timestamp field announcing an extended timestamp.
+const EXTENDED_TIMESTAMP: u32 = 0xFF_FFFF;
+
+/// A complete RTMP message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub type_id: u8,
+
+    /// The message stream this message belongs to, 0 for the connection
+    /// itself.
+    pub stream_id: u32,
+
+    /// The timestamp of the message, in milliseconds.
+    pub timestamp: u32,
+
+    pub payload: Vec<u8>,
+}
+
+/// The state of a chunk stream, which later chunks inherit header fields
+/// from.
+#[derive(Clone, Debug, Default)]
+struct ChunkStream {
+    timestamp: u32,
+    timestamp_delta: u32,
+    has_extended_timestamp: bool,
+    length: usize,
+    type_id: u8,
+    stream_id: u32,
+
+    /// The data of the message being received.
+    payload: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct InvalidChunk;
+
+/// Reassembles the messages of received chunks.
+#[derive(Debug)]
+pub struct ChunkReader {
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    streams: HashMap<u32, ChunkStream>,
+}
+
+impl Default for ChunkReader {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            buffer: Vec::new(),
+            streams: HashMap::new(),
+        }
+    }
+}
+
+impl ChunkReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size.max(1);
+    }
+
+    /// Appends received data.
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Returns the next complete message, or `None` if more data is needed.
+    pub fn next_message(&mut self) -> Result<Option<Message>, InvalidChunk> {
+        loop {
+            let (consumed, message) = match self.read_chunk()? {
+                Some(chunk) => chunk,
+                None => return Ok(None),
+            };
+            self.buffer.drain(..consumed);
+            if message.is_some() {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Reads a single chunk from the buffer.
+    ///
+    /// Returns the length of the chunk, and the message it completes, if
+    /// any. The chunk stream state is only changed once the whole chunk was
+    /// received.
+    fn read_chunk(&mut self) -> Result<Option<(usize, Option<Message>)>, InvalidChunk> {
+        let data = &self.buffer[..];
+        let first = match data.first() {
+            Some(first) => *first,
+            None => return Ok(None),
+        };
+        let format = first >> 6;
+        let (chunk_stream_id, mut position) = match first & 0b11_1111 {
+            0 => match data.get(1) {
+                Some(id) => (64 + u32::from(*id), 2),
+                None => return Ok(None),
+            },
+            1 => match data.get(1..3) {
+                Some(id) => (64 + u32::from(LittleEndian::read_u16(id)), 3),
+                None => return Ok(None),
+            },
+            id => (u32::from(id), 1),
+        };
+
+        let mut stream = self
+            .streams
+            .get(&chunk_stream_id)
+            .cloned()
+            .unwrap_or_default();
+        if format != 0 && !self.streams.contains_key(&chunk_stream_id) {
+            // Compressed headers need a previous chunk to inherit from.
+            return Err(InvalidChunk);
+        }
+        let header_length = [11, 7, 3, 0][usize::from(format)];
+        let header = match data.get(position..position + header_length) {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        position += header_length;
+
+        let is_new_message = stream.payload.is_empty();
+        let timestamp_field = if format < 3 {
+            let field = BigEndian::read_u24(&header[0..3]);
+            stream.has_extended_timestamp = field == EXTENDED_TIMESTAMP;
+            Some(field)
+        } else {
+            None
+        };
+        if format < 2 {
+            stream.length = BigEndian::read_u24(&header[3..6]) as usize;
+            stream.type_id = header[6];
+        }
+        if format == 0 {
+            stream.stream_id = LittleEndian::read_u32(&header[7..11]);
+        }
+
+        let timestamp_field = if stream.has_extended_timestamp {
+            match data.get(position..position + 4) {
+                Some(extended) => {
+                    position += 4;
+                    Some(BigEndian::read_u32(extended))
+                }
+                None => return Ok(None),
+            }
+        } else {
+            timestamp_field
+        };
+
+        match (format, timestamp_field) {
+            (0, Some(timestamp)) => {
+                stream.timestamp = timestamp;
+                // A following type 3 chunk starting a message reuses this as its delta.
+                stream.timestamp_delta = timestamp;
+            }
+            (_, Some(delta)) if format < 3 => {
+                stream.timestamp_delta = delta;
+                stream.timestamp = stream.timestamp.wrapping_add(delta);
+            }
+            _ if is_new_message => {
+                stream.timestamp = stream.timestamp.wrapping_add(stream.timestamp_delta);
+            }
+            _ => {}
+        }
+
+        let remaining = stream.length.saturating_sub(stream.payload.len());
+        let chunk_length = remaining.min(self.chunk_size);
+        let chunk = match data.get(position..position + chunk_length) {
+            Some(chunk) => chunk,
+            None => return Ok(None),
+        };
+        position += chunk_length;
+        stream.payload.extend_from_slice(chunk);
+
+        let message = if stream.payload.len() >= stream.length {
+            Some(Message {
+                type_id: stream.type_id,
+                stream_id: stream.stream_id,
+                timestamp: stream.timestamp,
+                payload: std::mem::take(&mut stream.payload),
+            })
+        } else {
+            None
+        };
+        self.streams.insert(chunk_stream_id, stream);
+
+        Ok(Some((position, message)))
+    }
+}
+
+/// Splits messages to send into chunks.
+#[derive(Debug)]
+pub struct ChunkWriter {
+    chunk_size: usize,
+}
+
+impl Default for ChunkWriter {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl ChunkWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the chunks of `message` to `out`, on the chunk stream
+    /// `chunk_stream_id`, which must be between 2 and 63.
+    pub fn write(&self, out: &mut Vec<u8>, chunk_stream_id: u8, message: &Message) {
+        let extended = message.timestamp >= EXTENDED_TIMESTAMP;
+        let timestamp_field = message.timestamp.min(EXTENDED_TIMESTAMP);
+
+        out.push(chunk_stream_id & 0b11_1111);
+        out.extend_from_slice(&timestamp_field.to_be_bytes()[1..]);
+        out.extend_from_slice(&(message.payload.len() as u32).to_be_bytes()[1..]);
+        out.push(message.type_id);
+        out.extend_from_slice(&message.stream_id.to_le_bytes());
+        if extended {
+            out.extend_from_slice(&message.timestamp.to_be_bytes());
+        }
+
+        for (index, chunk) in message.payload.chunks(self.chunk_size).enumerate() {
+            if index > 0 {
+                out.push(0b1100_0000 | (chunk_stream_id & 0b11_1111));
+                if extended {
+                    out.extend_from_slice(&message.timestamp.to_be_bytes());
+                }
+            }
+            out.extend_from_slice(chunk);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(type_id: u8, timestamp: u32, length: usize) -> Message {
+        Message {
+            type_id,
+            stream_id: 1,
+            timestamp,
+            payload: (0..length).map(|i| i as u8).collect(),
+        }
+    }
+
+    #[test]
+    fn round_trip_split_message() {
+        let sent = message(9, 40, 300);
+        let mut data = Vec::new();
+        ChunkWriter::new().write(&mut data, 6, &sent);
+        // One full header, and two continuation chunks.
+        assert_eq!(data.len(), 12 + 300 + 2);
+
+        let mut reader = ChunkReader::new();
+        reader.push(&data[..100]);
+        assert_eq!(reader.next_message().unwrap(), None);
+        reader.push(&data[100..]);
+        assert_eq!(reader.next_message().unwrap(), Some(sent));
+        assert_eq!(reader.next_message().unwrap(), None);
+    }
+
+    #[test]
+    fn round_trip_extended_timestamp() {
+        let sent = message(8, 0x0100_0000, 200);
+        let mut data = Vec::new();
+        ChunkWriter::new().write(&mut data, 4, &sent);
+
+        let mut reader = ChunkReader::new();
+        reader.push(&data);
+        assert_eq!(reader.next_message().unwrap(), Some(sent));
+    }
+
+    #[test]
+    fn read_compressed_headers() {
+        let mut data = Vec::new();
+        // Type 0: timestamp 10, length 2, type 8, stream 1.
+        data.extend_from_slice(&[0x04, 0, 0, 10, 0, 0, 2, 8, 1, 0, 0, 0, 0xAA, 0xBB]);
+        // Type 2: timestamp delta 20.
+        data.extend_from_slice(&[0x84, 0, 0, 20, 0xCC, 0xDD]);
+        // Type 3: same delta again.
+        data.extend_from_slice(&[0xC4, 0xEE, 0xFF]);
+
+        let mut reader = ChunkReader::new();
+        reader.push(&data);
+        let timestamps: Vec<_> = std::iter::from_fn(|| reader.next_message().unwrap())
+            .map(|message| (message.timestamp, message.payload))
+            .collect();
+        assert_eq!(
+            timestamps,
+            vec![
+                (10, vec![0xAA, 0xBB]),
+                (30, vec![0xCC, 0xDD]),
+                (50, vec![0xEE, 0xFF])
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_compressed_header_without_previous_chunk() {
+        let mut reader = ChunkReader::new();
+        reader.push(&[0xC4, 0xEE]);
+        assert!(reader.next_message().is_err());
+    }
+}
@@ -0,0 +1,831 @@
+//! The client side of the RTMP protocol, independent of the transport.
+//!
+//! The session is fed with the data received from the server, and collects
+//! the data to send back, so it can be driven by any kind of socket.
+
+use crate::rtmp::amf0::{self, Amf0Value};
+use crate::rtmp::chunk::{ChunkReader, ChunkWriter, InvalidChunk, Message};
+use byteorder::{BigEndian, ByteOrder};
+use thiserror::Error;
+
+/// The only version of the handshake defined by the specification.
+const RTMP_VERSION: u8 = 3;
+
+/// The length of the random part of the handshake.
+const HANDSHAKE_LENGTH: usize = 1536;
+
+const SET_CHUNK_SIZE: u8 = 1;
+const ACKNOWLEDGEMENT: u8 = 3;
+const USER_CONTROL: u8 = 4;
+const WINDOW_ACK_SIZE: u8 = 5;
+const SET_PEER_BANDWIDTH: u8 = 6;
+const AUDIO: u8 = 8;
+const VIDEO: u8 = 9;
+const DATA_AMF3: u8 = 15;
+const COMMAND_AMF3: u8 = 17;
+const DATA_AMF0: u8 = 18;
+const COMMAND_AMF0: u8 = 20;
+const AGGREGATE: u8 = 22;
+
+const SET_BUFFER_LENGTH: u16 = 3;
+const PING_REQUEST: u16 = 6;
+const PING_RESPONSE: u16 = 7;
+
+/// The chunk stream used for protocol control messages.
+const CONTROL_CHUNK_STREAM: u8 = 2;
+
+/// The chunk stream used for command messages.
+const COMMAND_CHUNK_STREAM: u8 = 3;
+
+/// The buffer length announced to the server for every stream, in
+/// milliseconds.
+const BUFFER_LENGTH: u32 = 3000;
+
+#[derive(Debug, Error)]
+pub enum RtmpError {
+    #[error("Unsupported RTMP version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Invalid RTMP chunk")]
+    InvalidChunk,
+}
+
+impl From<InvalidChunk> for RtmpError {
+    fn from(_: InvalidChunk) -> Self {
+        RtmpError::InvalidChunk
+    }
+}
+
+/// The parts of an `rtmp://host:port/app/instance` URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RtmpUrl {
+    pub host: String,
+    pub port: u16,
+
+    /// The application to connect to on the server.
+    pub app: String,
+
+    /// The whole URL, as sent to the server.
+    pub tc_url: String,
+}
+
+impl RtmpUrl {
+    /// The port of RTMP servers if none is given.
+    pub const DEFAULT_PORT: u16 = 1935;
+
+    /// Parses an RTMP URL, returning `None` if it isn't a valid one.
+    ///
+    /// Only plain `rtmp` connections are supported, not the encrypted or
+    /// tunneled variants.
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = url::Url::parse(url).ok()?;
+        if parsed.scheme() != "rtmp" {
+            return None;
+        }
+        let host = parsed.host_str()?.to_string();
+        let port = parsed.port().unwrap_or(Self::DEFAULT_PORT);
+        let mut app = parsed.path().trim_start_matches('/').to_string();
+        if let Some(query) = parsed.query() {
+            app.push('?');
+            app.push_str(query);
+        }
+
+        Some(Self {
+            host,
+            port,
+            app,
+            tc_url: url.to_string(),
+        })
+    }
+}
+
+/// Something happening on an RTMP connection.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionEvent {
+    /// A status of the connection itself, such as the result of connecting.
+    ConnectionStatus(Amf0Value),
+
+    /// A status of a stream, such as the start of playback.
+    StreamStatus { local_id: u32, info: Amf0Value },
+
+    /// An FLV tag received for a stream.
+    StreamData { local_id: u32, tag: Vec<u8> },
+}
+
+/// Something the player asks an RTMP connection to do.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RtmpRequest {
+    /// Play the stream `name`, starting at `start` seconds, or at -2 for a
+    /// live stream if there is one and the recorded stream otherwise.
+    Play {
+        local_id: u32,
+        name: String,
+        start: f64,
+    },
+
+    Pause {
+        local_id: u32,
+        paused: bool,
+
+        /// The playback position, in milliseconds.
+        time: f64,
+    },
+
+    Seek {
+        local_id: u32,
+
+        /// The position to jump to, in milliseconds.
+        time: f64,
+    },
+
+    CloseStream {
+        local_id: u32,
+    },
+
+    /// Close the whole connection.
+    Close,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Waiting for the server's part of the handshake.
+    Handshake,
+
+    /// Waiting for the result of the `connect` command.
+    Connecting,
+
+    Connected,
+
+    /// The connection was rejected.
+    Failed,
+}
+
+/// A command waiting for its `_result` or `_error`.
+#[derive(Clone, Copy, Debug)]
+enum Transaction {
+    Connect,
+    CreateStream { local_id: u32 },
+}
+
+/// A stream of the player, and the server stream it is mapped to.
+#[derive(Debug)]
+struct Stream {
+    local_id: u32,
+
+    /// The ID of the stream on the server, once it was created.
+    stream_id: Option<u32>,
+
+    /// The request to play, waiting for the stream to be created.
+    pending_play: Option<(String, f64)>,
+}
+
+/// An RTMP client connection.
+#[derive(Debug)]
+pub struct RtmpSession {
+    url: RtmpUrl,
+    state: State,
+
+    /// The received data of an incomplete handshake.
+    handshake: Vec<u8>,
+
+    reader: ChunkReader,
+    writer: ChunkWriter,
+
+    /// The data waiting to be sent.
+    output: Vec<u8>,
+
+    next_transaction: f64,
+    transactions: Vec<(f64, Transaction)>,
+    streams: Vec<Stream>,
+
+    /// Requests made before the connection was established.
+    queued: Vec<RtmpRequest>,
+
+    /// The amount of received data after which the server expects an
+    /// acknowledgement.
+    window_ack_size: u32,
+    bytes_received: u32,
+    bytes_acknowledged: u32,
+}
+
+impl RtmpSession {
+    pub fn new(url: RtmpUrl) -> Self {
+        Self {
+            url,
+            state: State::Handshake,
+            handshake: Vec::new(),
+            reader: ChunkReader::new(),
+            writer: ChunkWriter::new(),
+            output: Vec::new(),
+            next_transaction: 1.0,
+            transactions: Vec::new(),
+            streams: Vec::new(),
+            queued: Vec::new(),
+            window_ack_size: 0,
+            bytes_received: 0,
+            bytes_acknowledged: 0,
+        }
+    }
+
+    /// Returns the first part of the handshake, to be sent once the
+    /// connection is open.
+    pub fn start(&mut self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(1 + HANDSHAKE_LENGTH);
+        data.push(RTMP_VERSION);
+        // The time and zero fields, followed by the random data, which isn't
+        // checked by servers.
+        data.resize(1 + HANDSHAKE_LENGTH, 0);
+        for (index, byte) in data[9..].iter_mut().enumerate() {
+            *byte = (index as u32).wrapping_mul(0x9E37_79B1).to_be_bytes()[0];
+        }
+        data
+    }
+
+    /// Takes the data that should be sent to the server.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Handles data received from the server.
+    pub fn receive(&mut self, mut data: &[u8]) -> Result<Vec<SessionEvent>, RtmpError> {
+        let mut events = Vec::new();
+        if self.state == State::Handshake {
+            let needed = 1 + 2 * HANDSHAKE_LENGTH - self.handshake.len();
+            let (handshake, rest) = data.split_at(needed.min(data.len()));
+            self.handshake.extend_from_slice(handshake);
+            data = rest;
+            if self.handshake.len() < 1 + 2 * HANDSHAKE_LENGTH {
+                return Ok(events);
+            }
+
+            let version = self.handshake[0];
+            if version != RTMP_VERSION {
+                return Err(RtmpError::UnsupportedVersion(version));
+            }
+            // Echo the server's random data back.
+            let server_random = self.handshake[1..1 + HANDSHAKE_LENGTH].to_vec();
+            self.output.extend_from_slice(&server_random);
+            self.handshake = Vec::new();
+            self.state = State::Connecting;
+            self.send_connect();
+        }
+
+        self.bytes_received = self.bytes_received.wrapping_add(data.len() as u32);
+        self.reader.push(data);
+        while let Some(message) = self.reader.next_message()? {
+            self.handle_message(message, &mut events);
+        }
+        self.acknowledge();
+
+        Ok(events)
+    }
+
+    /// Handles a request of the player.
+    pub fn request(&mut self, request: RtmpRequest) {
+        if self.state != State::Connected {
+            self.queued.push(request);
+            return;
+        }
+
+        match request {
+            RtmpRequest::Play {
+                local_id,
+                name,
+                start,
+            } => {
+                if let Some(stream_id) = self.stream_id(local_id) {
+                    self.send_play(stream_id, &name, start);
+                    return;
+                }
+                if let Some(stream) = self.streams.iter_mut().find(|s| s.local_id == local_id) {
+                    // The stream is still being created.
+                    stream.pending_play = Some((name, start));
+                    return;
+                }
+                self.streams.push(Stream {
+                    local_id,
+                    stream_id: None,
+                    pending_play: Some((name, start)),
+                });
+                let transaction = self.transaction(Transaction::CreateStream { local_id });
+                self.send_command(
+                    0,
+                    &["createStream".into(), transaction.into(), Amf0Value::Null],
+                );
+            }
+            RtmpRequest::Pause {
+                local_id,
+                paused,
+                time,
+            } => {
+                if let Some(stream_id) = self.stream_id(local_id) {
+                    self.send_command(
+                        stream_id,
+                        &[
+                            "pause".into(),
+                            0.0.into(),
+                            Amf0Value::Null,
+                            paused.into(),
+                            time.into(),
+                        ],
+                    );
+                }
+            }
+            RtmpRequest::Seek { local_id, time } => {
+                if let Some(stream_id) = self.stream_id(local_id) {
+                    self.send_command(
+                        stream_id,
+                        &["seek".into(), 0.0.into(), Amf0Value::Null, time.into()],
+                    );
+                }
+            }
+            RtmpRequest::CloseStream { local_id } => {
+                if let Some(stream_id) = self.stream_id(local_id) {
+                    self.send_command(
+                        stream_id,
+                        &["closeStream".into(), 0.0.into(), Amf0Value::Null],
+                    );
+                    self.send_command(
+                        0,
+                        &[
+                            "deleteStream".into(),
+                            0.0.into(),
+                            Amf0Value::Null,
+                            f64::from(stream_id).into(),
+                        ],
+                    );
+                }
+                self.streams.retain(|stream| stream.local_id != local_id);
+            }
+            RtmpRequest::Close => {}
+        }
+    }
+
+    fn stream_id(&self, local_id: u32) -> Option<u32> {
+        self.streams
+            .iter()
+            .find(|stream| stream.local_id == local_id)
+            .and_then(|stream| stream.stream_id)
+    }
+
+    fn local_id(&self, stream_id: u32) -> Option<u32> {
+        self.streams
+            .iter()
+            .find(|stream| stream.stream_id == Some(stream_id))
+            .map(|stream| stream.local_id)
+    }
+
+    fn transaction(&mut self, transaction: Transaction) -> f64 {
+        let id = self.next_transaction;
+        self.next_transaction += 1.0;
+        self.transactions.push((id, transaction));
+        id
+    }
+
+    fn send_connect(&mut self) {
+        let transaction = self.transaction(Transaction::Connect);
+        let properties = vec![
+            ("app".to_string(), self.url.app.as_str().into()),
+            ("flashVer".to_string(), "WIN 32,0,0,465".into()),
+            ("tcUrl".to_string(), self.url.tc_url.as_str().into()),
+            ("fpad".to_string(), false.into()),
+            ("capabilities".to_string(), 15.0.into()),
+            ("audioCodecs".to_string(), 4095.0.into()),
+            ("videoCodecs".to_string(), 255.0.into()),
+            ("videoFunction".to_string(), 1.0.into()),
+            ("objectEncoding".to_string(), 0.0.into()),
+        ];
+        self.send_command(
+            0,
+            &[
+                "connect".into(),
+                transaction.into(),
+                Amf0Value::Object(properties),
+            ],
+        );
+    }
+
+    fn send_play(&mut self, stream_id: u32, name: &str, start: f64) {
+        self.send_command(
+            stream_id,
+            &[
+                "play".into(),
+                0.0.into(),
+                Amf0Value::Null,
+                name.into(),
+                start.into(),
+            ],
+        );
+    }
+
+    fn send_command(&mut self, stream_id: u32, values: &[Amf0Value]) {
+        let mut payload = Vec::new();
+        for value in values {
+            amf0::write(&mut payload, value);
+        }
+        self.send(COMMAND_CHUNK_STREAM, COMMAND_AMF0, stream_id, payload);
+    }
+
+    fn send_user_control(&mut self, event: u16, data: &[u8]) {
+        let mut payload = event.to_be_bytes().to_vec();
+        payload.extend_from_slice(data);
+        self.send(CONTROL_CHUNK_STREAM, USER_CONTROL, 0, payload);
+    }
+
+    fn send(&mut self, chunk_stream_id: u8, type_id: u8, stream_id: u32, payload: Vec<u8>) {
+        let message = Message {
+            type_id,
+            stream_id,
+            timestamp: 0,
+            payload,
+        };
+        self.writer
+            .write(&mut self.output, chunk_stream_id, &message);
+    }
+
+    /// Acknowledges the received data, if the server asked for it.
+    fn acknowledge(&mut self) {
+        if self.window_ack_size == 0
+            || self.bytes_received.wrapping_sub(self.bytes_acknowledged) < self.window_ack_size
+        {
+            return;
+        }
+        self.bytes_acknowledged = self.bytes_received;
+        let payload = self.bytes_received.to_be_bytes().to_vec();
+        self.send(CONTROL_CHUNK_STREAM, ACKNOWLEDGEMENT, 0, payload);
+    }
+
+    fn handle_message(&mut self, message: Message, events: &mut Vec<SessionEvent>) {
+        let payload = &message.payload[..];
+        match message.type_id {
+            SET_CHUNK_SIZE if payload.len() >= 4 => {
+                let chunk_size = BigEndian::read_u32(payload) & 0x7FFF_FFFF;
+                self.reader.set_chunk_size(chunk_size as usize);
+            }
+            WINDOW_ACK_SIZE if payload.len() >= 4 => {
+                self.window_ack_size = BigEndian::read_u32(payload);
+            }
+            SET_PEER_BANDWIDTH if payload.len() >= 4 => {
+                // Ask the server to acknowledge our data at the same rate.
+                let payload = payload[..4].to_vec();
+                self.send(CONTROL_CHUNK_STREAM, WINDOW_ACK_SIZE, 0, payload);
+            }
+            USER_CONTROL if payload.len() >= 2 => {
+                // The other events only inform about the state of streams.
+                if BigEndian::read_u16(payload) == PING_REQUEST {
+                    let timestamp = payload[2..].to_vec();
+                    self.send_user_control(PING_RESPONSE, &timestamp);
+                }
+            }
+            COMMAND_AMF0 | COMMAND_AMF3 => {
+                // AMF3 commands start with a format byte, followed by AMF0 values.
+                let payload = match message.type_id {
+                    COMMAND_AMF3 => payload.get(1..).unwrap_or_default(),
+                    _ => payload,
+                };
+                match amf0::read_all(payload) {
+                    Some(values) => self.handle_command(message.stream_id, values, events),
+                    None => tracing::warn!("Invalid RTMP command"),
+                }
+            }
+            AUDIO | VIDEO | DATA_AMF0 | DATA_AMF3 => {
+                let local_id = match self.local_id(message.stream_id) {
+                    Some(local_id) => local_id,
+                    None => return,
+                };
+                let (tag_type, data) = match message.type_id {
+                    DATA_AMF3 => (DATA_AMF0, payload.get(1..).unwrap_or_default()),
+                    tag_type => (tag_type, payload),
+                };
+                if data.is_empty() {
+                    return;
+                }
+                let mut tag = Vec::with_capacity(data.len() + 15);
+                write_flv_tag(&mut tag, tag_type, message.timestamp, data);
+                events.push(SessionEvent::StreamData { local_id, tag });
+            }
+            AGGREGATE => {
+                let local_id = match self.local_id(message.stream_id) {
+                    Some(local_id) => local_id,
+                    None => return,
+                };
+                let tag = rebase_aggregate(payload, message.timestamp);
+                if !tag.is_empty() {
+                    events.push(SessionEvent::StreamData { local_id, tag });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_command(
+        &mut self,
+        stream_id: u32,
+        values: Vec<Amf0Value>,
+        events: &mut Vec<SessionEvent>,
+    ) {
+        let name = values.first().and_then(Amf0Value::as_str).unwrap_or("");
+        let transaction = values.get(1).and_then(Amf0Value::as_number);
+        // The values after the transaction ID and the command object.
+        let info = values.get(3).cloned().unwrap_or(Amf0Value::Null);
+
+        match name {
+            "_result" | "_error" => {
+                let index = match self
+                    .transactions
+                    .iter()
+                    .position(|(id, _)| Some(*id) == transaction)
+                {
+                    Some(index) => index,
+                    None => return,
+                };
+                let (_, pending) = self.transactions.remove(index);
+                let success = name == "_result";
+                match pending {
+                    Transaction::Connect => {
+                        events.push(SessionEvent::ConnectionStatus(info));
+                        if success {
+                            self.state = State::Connected;
+                            for request in std::mem::take(&mut self.queued) {
+                                self.request(request);
+                            }
+                        } else {
+                            self.state = State::Failed;
+                        }
+                    }
+                    Transaction::CreateStream { local_id } => {
+                        let stream_id = info.as_number().filter(|_| success);
+                        self.stream_created(local_id, stream_id.map(|id| id as u32), events);
+                    }
+                }
+            }
+            "onStatus" if stream_id == 0 => events.push(SessionEvent::ConnectionStatus(info)),
+            "onStatus" => {
+                if let Some(local_id) = self.local_id(stream_id) {
+                    events.push(SessionEvent::StreamStatus { local_id, info });
+                }
+            }
+            "close" => events.push(SessionEvent::ConnectionStatus(status(
+                "NetConnection.Connect.Closed",
+                "status",
+            ))),
+            // Bandwidth checks and other calls of the server aren't answered.
+            _ => {}
+        }
+    }
+
+    fn stream_created(
+        &mut self,
+        local_id: u32,
+        stream_id: Option<u32>,
+        events: &mut Vec<SessionEvent>,
+    ) {
+        let index = match self.streams.iter().position(|s| s.local_id == local_id) {
+            Some(index) => index,
+            None => return,
+        };
+        let stream_id = match stream_id {
+            Some(stream_id) => stream_id,
+            None => {
+                self.streams.remove(index);
+                events.push(SessionEvent::StreamStatus {
+                    local_id,
+                    info: status("NetStream.Play.Failed", "error"),
+                });
+                return;
+            }
+        };
+
+        self.streams[index].stream_id = Some(stream_id);
+        let mut buffer_length = stream_id.to_be_bytes().to_vec();
+        buffer_length.extend_from_slice(&BUFFER_LENGTH.to_be_bytes());
+        self.send_user_control(SET_BUFFER_LENGTH, &buffer_length);
+        if let Some((name, start)) = self.streams[index].pending_play.take() {
+            self.send_play(stream_id, &name, start);
+        }
+    }
+}
+
+/// Creates a status info object, as sent in `onStatus` commands.
+pub fn status(code: &str, level: &str) -> Amf0Value {
+    Amf0Value::Object(vec![
+        ("code".to_string(), code.into()),
+        ("level".to_string(), level.into()),
+    ])
+}
+
+/// Appends an FLV tag, with its trailing tag size.
+fn write_flv_tag(out: &mut Vec<u8>, tag_type: u8, timestamp: u32, data: &[u8]) {
+    out.push(tag_type);
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+    out.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+    out.push(timestamp.to_be_bytes()[0]);
+    out.extend_from_slice(&[0, 0, 0]);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&(data.len() as u32 + 11).to_be_bytes());
+}
+
+/// Converts an aggregate message, which holds FLV tags timed relative to
+/// their first one, to FLV tags timed like the message itself.
+fn rebase_aggregate(mut data: &[u8], timestamp: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut first_timestamp = None;
+    while data.len() >= 11 {
+        let tag_type = data[0];
+        let length = BigEndian::read_u24(&data[1..4]) as usize;
+        let tag_timestamp = BigEndian::read_u24(&data[4..7]) | (u32::from(data[7]) << 24);
+        let tag_data = match data.get(11..11 + length) {
+            Some(tag_data) => tag_data,
+            None => break,
+        };
+        let first_timestamp = *first_timestamp.get_or_insert(tag_timestamp);
+        let tag_timestamp = timestamp.wrapping_add(tag_timestamp.wrapping_sub(first_timestamp));
+        write_flv_tag(&mut out, tag_type, tag_timestamp, tag_data);
+        // Skip the tag and its back pointer.
+        data = data.get(11 + length + 4..).unwrap_or_default();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> RtmpSession {
+        RtmpSession::new(RtmpUrl::parse("rtmp://example.com/live").unwrap())
+    }
+
+    /// Reads the messages sent by the session.
+    fn sent_messages(data: &[u8]) -> Vec<Message> {
+        let mut reader = ChunkReader::new();
+        reader.push(data);
+        std::iter::from_fn(|| reader.next_message().unwrap()).collect()
+    }
+
+    fn server_message(type_id: u8, stream_id: u32, values: &[Amf0Value]) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for value in values {
+            amf0::write(&mut payload, value);
+        }
+        let mut data = Vec::new();
+        ChunkWriter::new().write(
+            &mut data,
+            3,
+            &Message {
+                type_id,
+                stream_id,
+                timestamp: 0,
+                payload,
+            },
+        );
+        data
+    }
+
+    /// Completes the handshake, returning what was sent in response.
+    fn handshake(session: &mut RtmpSession) -> Vec<u8> {
+        assert_eq!(session.start().len(), 1 + HANDSHAKE_LENGTH);
+        let mut server = vec![RTMP_VERSION];
+        server.extend((0..2 * HANDSHAKE_LENGTH).map(|i| i as u8));
+        assert_eq!(session.receive(&server).unwrap(), vec![]);
+        session.take_output()
+    }
+
+    fn connect(session: &mut RtmpSession) {
+        handshake(session);
+        let events = session
+            .receive(&server_message(
+                COMMAND_AMF0,
+                0,
+                &[
+                    "_result".into(),
+                    1.0.into(),
+                    Amf0Value::Null,
+                    status("NetConnection.Connect.Success", "status"),
+                ],
+            ))
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![SessionEvent::ConnectionStatus(status(
+                "NetConnection.Connect.Success",
+                "status"
+            ))]
+        );
+    }
+
+    #[test]
+    fn parse_url() {
+        assert_eq!(
+            RtmpUrl::parse("rtmp://example.com/vod/app?key=1"),
+            Some(RtmpUrl {
+                host: "example.com".to_string(),
+                port: 1935,
+                app: "vod/app?key=1".to_string(),
+                tc_url: "rtmp://example.com/vod/app?key=1".to_string(),
+            })
+        );
+        assert_eq!(
+            RtmpUrl::parse("rtmp://example.com:1936/live").map(|url| url.port),
+            Some(1936)
+        );
+        assert_eq!(RtmpUrl::parse("http://example.com/live"), None);
+    }
+
+    #[test]
+    fn handshake_sends_connect() {
+        let mut session = session();
+        let output = handshake(&mut session);
+
+        // The server's random data is echoed, followed by the connect command.
+        assert_eq!(&output[..4], &[0, 1, 2, 3]);
+        let messages = sent_messages(&output[HANDSHAKE_LENGTH..]);
+        assert_eq!(messages.len(), 1);
+        let values = amf0::read_all(&messages[0].payload).unwrap();
+        assert_eq!(values[0], "connect".into());
+        assert_eq!(values[2].get("app"), Some(&"live".into()));
+        assert_eq!(
+            values[2].get("tcUrl"),
+            Some(&"rtmp://example.com/live".into())
+        );
+    }
+
+    #[test]
+    fn reject_unknown_version() {
+        let mut session = session();
+        let server = vec![6; 1 + 2 * HANDSHAKE_LENGTH];
+        assert!(session.receive(&server).is_err());
+    }
+
+    #[test]
+    fn play_creates_stream_then_plays() {
+        let mut session = session();
+        session.request(RtmpRequest::Play {
+            local_id: 1,
+            name: "camera".to_string(),
+            start: -2.0,
+        });
+        connect(&mut session);
+
+        let messages = sent_messages(&session.take_output());
+        let values = amf0::read_all(&messages[0].payload).unwrap();
+        assert_eq!(values[0], "createStream".into());
+        assert_eq!(values[1], 2.0.into());
+
+        session
+            .receive(&server_message(
+                COMMAND_AMF0,
+                0,
+                &["_result".into(), 2.0.into(), Amf0Value::Null, 5.0.into()],
+            ))
+            .unwrap();
+        let messages = sent_messages(&session.take_output());
+        assert_eq!(messages[0].type_id, USER_CONTROL);
+        let play = &messages[1];
+        assert_eq!(play.stream_id, 5);
+        assert_eq!(
+            amf0::read_all(&play.payload).unwrap()[3..],
+            [Amf0Value::from("camera"), Amf0Value::from(-2.0)]
+        );
+
+        // Media of the server stream is converted to FLV tags.
+        let mut data = Vec::new();
+        ChunkWriter::new().write(
+            &mut data,
+            6,
+            &Message {
+                type_id: VIDEO,
+                stream_id: 5,
+                timestamp: 0x0102_0304,
+                payload: vec![0x12, 0x34],
+            },
+        );
+        assert_eq!(
+            session.receive(&data).unwrap(),
+            vec![SessionEvent::StreamData {
+                local_id: 1,
+                tag: vec![9, 0, 0, 2, 2, 3, 4, 1, 0, 0, 0, 0x12, 0x34, 0, 0, 0, 13],
+            }]
+        );
+    }
+
+    #[test]
+    fn answer_ping() {
+        let mut session = session();
+        handshake(&mut session);
+
+        let mut data = Vec::new();
+        ChunkWriter::new().write(
+            &mut data,
+            2,
+            &Message {
+                type_id: USER_CONTROL,
+                stream_id: 0,
+                timestamp: 0,
+                payload: vec![0, 6, 0, 0, 1, 0],
+            },
+        );
+        session.receive(&data).unwrap();
+        let messages = sent_messages(&session.take_output());
+        assert_eq!(messages[0].payload, vec![0, 7, 0, 0, 1, 0]);
+    }
+}
@@ -0,0 +1,196 @@
+//! Opt-in compatibility answers for site-locked movies.
+//!
+//! Many movies refuse to play unless they are hosted on their original
+//! website, which they check by reading their own URL, the security sandbox
+//! or the location of the page through `ExternalInterface`. When a site lock
+//! is configured for a movie, all of these report the original website
+//! instead.
+//!
+//! Only the root movie is answered this way; movies it loads from elsewhere
+//! are identified by their own URL, and get the real answers.
+//!
+//! This is only ever enabled explicitly by the embedder, as it lies to the
+//! movie about where it runs. It doesn't change where requests are sent, nor
+//! which browser origin they are sent from.
+
+use crate::context::UpdateContext;
+use crate::external::{ExternalInterfaceMethod, Value};
+use crate::tag_utils::SwfMovie;
+use url::Url;
+
+/// The original website of a site-locked movie.
+#[derive(Clone, Debug)]
+pub struct SiteLock {
+    original_url: Url,
+}
+
+impl SiteLock {
+    /// Creates a site lock answering with the given original URL of the
+    /// movie, or `None` if it isn't an absolute URL.
+    pub fn new(original_url: &str) -> Option<Self> {
+        Url::parse(original_url)
+            .ok()
+            .map(|original_url| Self { original_url })
+    }
+
+    pub fn original_url(&self) -> &str {
+        self.original_url.as_str()
+    }
+
+    /// Whether this site lock answers the queries of the given movie.
+    ///
+    /// The root movie is the one with the original URL, as that replaces the
+    /// URL it was loaded from.
+    pub fn applies_to(&self, movie: &SwfMovie) -> bool {
+        movie.url() == Some(self.original_url())
+    }
+
+    /// Returns the `ExternalInterface` method to call for `name`, answering
+    /// queries of the page location instead of `fallback`.
+    pub fn method_for(
+        &self,
+        name: &str,
+        fallback: Option<Box<dyn ExternalInterfaceMethod>>,
+    ) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        if name == "eval" {
+            return Some(Box::new(EvalLocation {
+                site_lock: self.clone(),
+                fallback,
+            }));
+        }
+        match self.answer(name) {
+            Some(answer) => Some(Box::new(
+                move |_: &mut UpdateContext<'_, '_>, _: &[Value]| answer.clone(),
+            )),
+            None => fallback,
+        }
+    }
+
+    /// Answers a JavaScript expression reading the page location, such as
+    /// `window.location.href.toString` or `function() { return document.domain; }`.
+    fn answer(&self, expression: &str) -> Option<Value> {
+        let mut expression: String = expression.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if let Some(body) = expression
+            .strip_prefix("function(){return")
+            .and_then(|body| body.strip_suffix('}'))
+        {
+            expression = body.trim_end_matches(';').to_string();
+        }
+        let mut expression = expression.trim_end_matches(';');
+        for suffix in [".toString()", ".toString", "()"] {
+            expression = expression.strip_suffix(suffix).unwrap_or(expression);
+        }
+        loop {
+            let stripped = ["window.", "top.", "parent.", "self."]
+                .iter()
+                .find_map(|prefix| expression.strip_prefix(prefix));
+            match stripped {
+                Some(stripped) => expression = stripped,
+                None => break,
+            }
+        }
+
+        let url = &self.original_url;
+        let answer = match expression {
+            "location"
+            | "location.href"
+            | "document.location"
+            | "document.location.href"
+            | "document.URL"
+            | "document.documentURI" => url.to_string(),
+            "location.host" | "document.location.host" => match url.port() {
+                Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+                None => url.host_str().unwrap_or_default().to_string(),
+            },
+            "location.hostname" | "document.location.hostname" | "document.domain" => {
+                url.host_str().unwrap_or_default().to_string()
+            }
+            "location.protocol" | "document.location.protocol" => format!("{}:", url.scheme()),
+            "location.pathname" | "document.location.pathname" => url.path().to_string(),
+            "location.origin" | "document.location.origin" => url.origin().ascii_serialization(),
+            _ => return None,
+        };
+        Some(Value::String(answer))
+    }
+}
+
+/// Answers `ExternalInterface.call("eval", expression)` for location queries.
+struct EvalLocation {
+    site_lock: SiteLock,
+    fallback: Option<Box<dyn ExternalInterfaceMethod>>,
+}
+
+impl ExternalInterfaceMethod for EvalLocation {
+    fn call(&self, context: &mut UpdateContext<'_, '_>, args: &[Value]) -> Value {
+        if let Some(Value::String(expression)) = args.first() {
+            if let Some(answer) = self.site_lock.answer(expression) {
+                return answer;
+            }
+        }
+        match &self.fallback {
+            Some(fallback) => fallback.call(context, args),
+            None => Value::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(expression: &str) -> Option<Value> {
+        SiteLock::new("https://games.example.com:8080/play/game.swf?x=1")
+            .unwrap()
+            .answer(expression)
+    }
+
+    #[test]
+    fn answers_location_queries() {
+        assert_eq!(
+            answer("window.location.href.toString"),
+            Some(
+                "https://games.example.com:8080/play/game.swf?x=1"
+                    .to_string()
+                    .into()
+            )
+        );
+        assert_eq!(
+            answer("function() { return document.domain; }"),
+            Some("games.example.com".to_string().into())
+        );
+        assert_eq!(
+            answer("top.location.host"),
+            Some("games.example.com:8080".to_string().into())
+        );
+        assert_eq!(
+            answer("window.location.protocol"),
+            Some("https:".to_string().into())
+        );
+    }
+
+    #[test]
+    fn ignores_other_calls() {
+        assert_eq!(answer("submitScore"), None);
+        assert_eq!(answer("window.open"), None);
+    }
+
+    #[test]
+    fn only_applies_to_root_movie() {
+        let site_lock = SiteLock::new("https://games.example.com/game.swf").unwrap();
+
+        let mut root = SwfMovie::empty(10);
+        root.set_url(Some(site_lock.original_url().to_string()));
+        assert!(site_lock.applies_to(&root));
+
+        let mut child = SwfMovie::empty(10);
+        child.set_url(Some("https://cdn.example.net/level1.swf".to_string()));
+        assert!(!site_lock.applies_to(&child));
+        assert!(!site_lock.applies_to(&SwfMovie::empty(10)));
+    }
+
+    #[test]
+    fn requires_absolute_url() {
+        assert!(SiteLock::new("game.swf").is_none());
+    }
+}
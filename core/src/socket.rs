@@ -0,0 +1,710 @@
+//! Sockets opened by the movie, and the data passing through them.
+
+use crate::avm1::{
+    dispatch_xml_socket_event as avm1_dispatch_event, Activation as Avm1Activation,
+    ActivationIdentifier, Object as Avm1Object,
+};
+use crate::avm2::globals::flash::net::socket as avm2_socket;
+use crate::avm2::{Activation as Avm2Activation, Object as Avm2Object};
+use crate::backend::navigator::{AcceptedSocket, Datagram, OwnedFuture, SocketConnection};
+use crate::config::PlayerRuntime;
+use crate::context::UpdateContext;
+use crate::loader::Error;
+use crate::policy_file;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use gc_arena::Collect;
+use std::collections::HashMap;
+
+pub type SocketHandle = u32;
+
+/// The host that sockets connect to when none is given: the one the movie at `url` came from.
+pub fn movie_host(url: Option<&str>) -> String {
+    url.and_then(|url| url::Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// A socket object of either AVM.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum SocketObject<'gc> {
+    Avm1(Avm1Object<'gc>),
+    Avm2(Avm2Object<'gc>),
+}
+
+impl<'gc> SocketObject<'gc> {
+    fn ptr_eq(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Avm1(a), Self::Avm1(b)) => Avm1Object::ptr_eq(a, b),
+            (Self::Avm2(a), Self::Avm2(b)) => Avm2Object::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// How the data passing through a socket is handed to its object.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SocketKind {
+    /// Binary data, handed over as soon as it's received, as by `Socket`.
+    Binary,
+
+    /// Messages terminated by a zero byte, handed over once complete, as by `XMLSocket`.
+    Xml,
+}
+
+/// Something that happened to a socket, to be reported to its object.
+pub enum SocketEvent {
+    /// The connection was established.
+    Connect,
+
+    /// Data was received by a binary socket.
+    Data(Vec<u8>),
+
+    /// A complete message was received by an XML socket.
+    Message(String),
+
+    /// A datagram was received by a datagram socket.
+    Datagram(Datagram),
+
+    /// A connection was accepted by a listening socket.
+    Accept(AcceptedSocket),
+
+    /// The connection was closed by the remote end.
+    Close,
+
+    /// The connection couldn't be established.
+    IoError { host: String, port: u16 },
+
+    /// The connection wasn't established before the timeout.
+    Timeout { host: String, port: u16 },
+
+    /// The server's socket policy file doesn't allow the connection.
+    SecurityError { host: String, port: u16 },
+}
+
+/// A message from the future running a connection.
+enum SocketAction {
+    Connected(SocketHandle, UnboundedSender<Vec<u8>>),
+    ConnectFailed(SocketHandle),
+    Denied(SocketHandle),
+    Data(SocketHandle, Vec<u8>),
+    Closed(SocketHandle),
+    Datagram(SocketHandle, Datagram),
+}
+
+enum SocketState {
+    /// Waiting for the connection, for at most the given number of milliseconds.
+    Connecting(f64),
+
+    /// Connected, sending data through the given channel.
+    Connected(UnboundedSender<Vec<u8>>),
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Socket<'gc> {
+    target: SocketObject<'gc>,
+
+    #[collect(require_static)]
+    host: String,
+
+    port: u16,
+
+    #[collect(require_static)]
+    kind: SocketKind,
+
+    /// The start of an XML message whose end wasn't received yet.
+    #[collect(require_static)]
+    partial_message: Vec<u8>,
+
+    #[collect(require_static)]
+    state: SocketState,
+}
+
+/// A UDP socket bound by the movie.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct DatagramSocket<'gc> {
+    target: SocketObject<'gc>,
+
+    #[collect(require_static)]
+    local_address: String,
+
+    local_port: u16,
+
+    /// The datagrams received, until the movie starts receiving them.
+    #[collect(require_static)]
+    incoming: Option<UnboundedReceiver<Datagram>>,
+
+    #[collect(require_static)]
+    outgoing: UnboundedSender<Datagram>,
+}
+
+/// A TCP socket bound by the movie to listen for connections.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct Listener<'gc> {
+    target: SocketObject<'gc>,
+
+    #[collect(require_static)]
+    local_address: String,
+
+    local_port: u16,
+
+    /// Whether accepted connections are reported; until then, they wait in `incoming`.
+    listening: bool,
+
+    #[collect(require_static)]
+    incoming: UnboundedReceiver<AcceptedSocket>,
+}
+
+/// Keeps track of the sockets opened by the movie.
+///
+/// Connections run as futures of the navigator, which hand what happens to them over to the
+/// manager. It's reported to the socket objects on the next update.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct SocketManager<'gc> {
+    sockets: HashMap<SocketHandle, Socket<'gc>>,
+
+    datagram_sockets: HashMap<SocketHandle, DatagramSocket<'gc>>,
+
+    listeners: HashMap<SocketHandle, Listener<'gc>>,
+
+    next_handle: SocketHandle,
+
+    #[collect(require_static)]
+    sender: UnboundedSender<SocketAction>,
+
+    #[collect(require_static)]
+    receiver: UnboundedReceiver<SocketAction>,
+}
+
+impl<'gc> SocketManager<'gc> {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        Self {
+            sockets: HashMap::new(),
+            datagram_sockets: HashMap::new(),
+            listeners: HashMap::new(),
+            next_handle: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    fn handle_of(&self, target: SocketObject<'gc>) -> Option<SocketHandle> {
+        self.sockets
+            .iter()
+            .find(|(_, socket)| socket.target.ptr_eq(target))
+            .map(|(handle, _)| *handle)
+    }
+
+    fn datagram_handle_of(&self, target: SocketObject<'gc>) -> Option<SocketHandle> {
+        self.datagram_sockets
+            .iter()
+            .find(|(_, socket)| socket.target.ptr_eq(target))
+            .map(|(handle, _)| *handle)
+    }
+
+    fn listener_handle_of(&self, target: SocketObject<'gc>) -> Option<SocketHandle> {
+        self.listeners
+            .iter()
+            .find(|(_, listener)| listener.target.ptr_eq(target))
+            .map(|(handle, _)| *handle)
+    }
+
+    fn next_handle(&mut self) -> SocketHandle {
+        let handle = self.next_handle;
+        self.next_handle = self.next_handle.wrapping_add(1);
+        handle
+    }
+
+    /// Whether the given object has an established connection.
+    pub fn is_connected(&self, target: SocketObject<'gc>) -> bool {
+        self.handle_of(target)
+            .and_then(|handle| self.sockets.get(&handle))
+            .map_or(false, |socket| {
+                matches!(socket.state, SocketState::Connected(_))
+            })
+    }
+
+    /// Connects an object to `host` on `port`, replacing any connection it already has.
+    ///
+    /// The connection fails if it isn't established within `timeout` milliseconds.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+        host: String,
+        port: u16,
+        kind: SocketKind,
+        timeout: u32,
+    ) {
+        Self::close(context, target);
+
+        let manager = &mut *context.socket_manager;
+        let handle = manager.next_handle();
+
+        // Movies from the web may only connect once the server's socket policy file allows it,
+        // so the connection can only be started after asking for that.
+        let connect: OwnedFuture<SocketConnection, Error> = if context
+            .policy_manager
+            .needs_socket_policy(context.swf.url())
+        {
+            let player = context
+                .player
+                .upgrade()
+                .expect("Could not upgrade weak reference to player");
+            let host = host.clone();
+            Box::pin(async move {
+                policy_file::check_socket_access(&player, &host, port).await?;
+                let connect = player
+                    .lock()
+                    .unwrap()
+                    .navigator()
+                    .connect_socket(&host, port);
+                connect.await
+            })
+        } else {
+            context.navigator.connect_socket(&host, port)
+        };
+        let sender = manager.sender.clone();
+        context.navigator.spawn_future(Box::pin(async move {
+            let SocketConnection { incoming, outgoing } = match connect.await {
+                Ok(connection) => connection,
+                Err(Error::SecurityViolation(_)) => {
+                    let _ = sender.unbounded_send(SocketAction::Denied(handle));
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!("Unable to connect socket: {}", e);
+                    let _ = sender.unbounded_send(SocketAction::ConnectFailed(handle));
+                    return Ok(());
+                }
+            };
+
+            if sender
+                .unbounded_send(SocketAction::Connected(handle, outgoing))
+                .is_ok()
+            {
+                forward_incoming(handle, incoming, sender).await;
+            }
+            Ok(())
+        }));
+
+        manager.sockets.insert(
+            handle,
+            Socket {
+                target,
+                host,
+                port,
+                kind,
+                partial_message: Vec::new(),
+                state: SocketState::Connecting(timeout.into()),
+            },
+        );
+    }
+
+    /// Closes the connection of an object, stops establishing it, or unbinds its datagram or
+    /// listening socket.
+    ///
+    /// Returns `false` if the object had no connection.
+    pub fn close(context: &mut UpdateContext<'_, 'gc>, target: SocketObject<'gc>) -> bool {
+        let manager = &mut *context.socket_manager;
+        // Dropping the sender of the outgoing data closes the connection, and dropping the
+        // receiver of the accepted connections stops listening.
+        if let Some(handle) = manager.handle_of(target) {
+            manager.sockets.remove(&handle).is_some()
+        } else if let Some(handle) = manager.datagram_handle_of(target) {
+            manager.datagram_sockets.remove(&handle).is_some()
+        } else if let Some(handle) = manager.listener_handle_of(target) {
+            manager.listeners.remove(&handle).is_some()
+        } else {
+            false
+        }
+    }
+
+    /// Sends data through the connection of an object.
+    ///
+    /// Returns `false` if the object has no established connection.
+    pub fn send(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+        data: Vec<u8>,
+    ) -> bool {
+        let manager = &*context.socket_manager;
+        let socket = manager
+            .handle_of(target)
+            .and_then(|handle| manager.sockets.get(&handle));
+        match socket.map(|socket| &socket.state) {
+            Some(SocketState::Connected(outgoing)) => {
+                let _ = outgoing.unbounded_send(data);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the sockets that only AIR has are available to the movie.
+    pub fn is_air_supported(context: &UpdateContext<'_, 'gc>) -> bool {
+        context.system.player_runtime == PlayerRuntime::Air
+    }
+
+    /// The local address and port that the datagram socket of an object is bound to.
+    pub fn datagram_local_address(&self, target: SocketObject<'gc>) -> Option<(&str, u16)> {
+        self.datagram_handle_of(target)
+            .and_then(|handle| self.datagram_sockets.get(&handle))
+            .map(|socket| (socket.local_address.as_str(), socket.local_port))
+    }
+
+    /// Binds a datagram socket for an object to `address` on `port`, replacing any socket it
+    /// already has.
+    pub fn bind_datagram(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+        address: &str,
+        port: u16,
+    ) -> Result<(), Error> {
+        if !Self::is_air_supported(context) {
+            return Err(Error::ConnectError(
+                "Datagram sockets are only available in AIR".to_string(),
+            ));
+        }
+        Self::close(context, target);
+
+        let connection = context.navigator.bind_datagram_socket(address, port)?;
+        let manager = &mut *context.socket_manager;
+        let handle = manager.next_handle();
+        manager.datagram_sockets.insert(
+            handle,
+            DatagramSocket {
+                target,
+                local_address: connection.local_address,
+                local_port: connection.local_port,
+                incoming: Some(connection.incoming),
+                outgoing: connection.outgoing,
+            },
+        );
+        Ok(())
+    }
+
+    /// Starts reporting the datagrams received by the datagram socket of an object.
+    ///
+    /// Returns `false` if the object has no datagram socket.
+    pub fn receive_datagrams(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+    ) -> bool {
+        let manager = &mut *context.socket_manager;
+        let handle = match manager.datagram_handle_of(target) {
+            Some(handle) => handle,
+            None => return false,
+        };
+        let incoming = manager
+            .datagram_sockets
+            .get_mut(&handle)
+            .and_then(|socket| socket.incoming.take());
+
+        if let Some(mut incoming) = incoming {
+            let sender = manager.sender.clone();
+            context.navigator.spawn_future(Box::pin(async move {
+                while let Some(datagram) = incoming.next().await {
+                    if sender
+                        .unbounded_send(SocketAction::Datagram(handle, datagram))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(())
+            }));
+        }
+        true
+    }
+
+    /// Sends a datagram through the datagram socket of an object.
+    ///
+    /// Returns `false` if the object has no datagram socket.
+    pub fn send_datagram(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+        datagram: Datagram,
+    ) -> bool {
+        let manager = &*context.socket_manager;
+        match manager
+            .datagram_handle_of(target)
+            .and_then(|handle| manager.datagram_sockets.get(&handle))
+        {
+            Some(socket) => {
+                let _ = socket.outgoing.unbounded_send(datagram);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The local address and port that the listening socket of an object is bound to.
+    pub fn listener_local_address(&self, target: SocketObject<'gc>) -> Option<(&str, u16)> {
+        self.listener_handle_of(target)
+            .and_then(|handle| self.listeners.get(&handle))
+            .map(|listener| (listener.local_address.as_str(), listener.local_port))
+    }
+
+    /// Whether the listening socket of an object reports the connections it accepts.
+    pub fn is_listening(&self, target: SocketObject<'gc>) -> bool {
+        self.listener_handle_of(target)
+            .and_then(|handle| self.listeners.get(&handle))
+            .map_or(false, |listener| listener.listening)
+    }
+
+    /// Binds a listening socket for an object to `address` on `port`, replacing any socket it
+    /// already has.
+    ///
+    /// The connections it accepts are only reported once `listen` is called.
+    pub fn bind_listener(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+        address: &str,
+        port: u16,
+    ) -> Result<(), Error> {
+        if !Self::is_air_supported(context) {
+            return Err(Error::ConnectError(
+                "Server sockets are only available in AIR".to_string(),
+            ));
+        }
+        Self::close(context, target);
+
+        let listening_socket = context.navigator.listen_socket(address, port)?;
+        let manager = &mut *context.socket_manager;
+        let handle = manager.next_handle();
+        manager.listeners.insert(
+            handle,
+            Listener {
+                target,
+                local_address: listening_socket.local_address,
+                local_port: listening_socket.local_port,
+                listening: false,
+                incoming: listening_socket.incoming,
+            },
+        );
+        Ok(())
+    }
+
+    /// Starts reporting the connections accepted by the listening socket of an object.
+    ///
+    /// Returns `false` if the object has no listening socket.
+    pub fn listen(context: &mut UpdateContext<'_, 'gc>, target: SocketObject<'gc>) -> bool {
+        let manager = &mut *context.socket_manager;
+        match manager
+            .listener_handle_of(target)
+            .and_then(|handle| manager.listeners.get_mut(&handle))
+        {
+            Some(listener) => {
+                listener.listening = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Hands a connection accepted by a listening socket over to an object, as if it had
+    /// established the connection itself.
+    pub fn adopt(
+        context: &mut UpdateContext<'_, 'gc>,
+        target: SocketObject<'gc>,
+        accepted: AcceptedSocket,
+    ) {
+        Self::close(context, target);
+
+        let manager = &mut *context.socket_manager;
+        let handle = manager.next_handle();
+        let SocketConnection { incoming, outgoing } = accepted.connection;
+        let sender = manager.sender.clone();
+        context.navigator.spawn_future(Box::pin(async move {
+            forward_incoming(handle, incoming, sender).await;
+            Ok(())
+        }));
+
+        manager.sockets.insert(
+            handle,
+            Socket {
+                target,
+                host: accepted.address,
+                port: accepted.port,
+                kind: SocketKind::Binary,
+                partial_message: Vec::new(),
+                state: SocketState::Connected(outgoing),
+            },
+        );
+    }
+
+    /// Reports what happened to the sockets since the last update, `dt` milliseconds ago.
+    pub fn update(context: &mut UpdateContext<'_, 'gc>, dt: f64) {
+        let manager = &mut *context.socket_manager;
+        let mut events = Vec::new();
+
+        while let Ok(Some(action)) = manager.receiver.try_next() {
+            match action {
+                SocketAction::Connected(handle, outgoing) => {
+                    // Sockets closed in the meantime are gone, and so is their connection
+                    // once `outgoing` is dropped.
+                    if let Some(socket) = manager.sockets.get_mut(&handle) {
+                        socket.state = SocketState::Connected(outgoing);
+                        events.push((socket.target, SocketEvent::Connect));
+                    }
+                }
+                SocketAction::ConnectFailed(handle) => {
+                    if let Some(socket) = manager.sockets.remove(&handle) {
+                        let event = SocketEvent::IoError {
+                            host: socket.host,
+                            port: socket.port,
+                        };
+                        events.push((socket.target, event));
+                    }
+                }
+                SocketAction::Denied(handle) => {
+                    if let Some(socket) = manager.sockets.remove(&handle) {
+                        let event = SocketEvent::SecurityError {
+                            host: socket.host,
+                            port: socket.port,
+                        };
+                        events.push((socket.target, event));
+                    }
+                }
+                SocketAction::Data(handle, data) => {
+                    if let Some(socket) = manager.sockets.get_mut(&handle) {
+                        match socket.kind {
+                            SocketKind::Binary => {
+                                events.push((socket.target, SocketEvent::Data(data)));
+                            }
+                            SocketKind::Xml => {
+                                for message in split_messages(&mut socket.partial_message, &data) {
+                                    let message = String::from_utf8_lossy(&message).into_owned();
+                                    events.push((socket.target, SocketEvent::Message(message)));
+                                }
+                            }
+                        }
+                    }
+                }
+                SocketAction::Closed(handle) => {
+                    if let Some(socket) = manager.sockets.remove(&handle) {
+                        events.push((socket.target, SocketEvent::Close));
+                    }
+                }
+                SocketAction::Datagram(handle, datagram) => {
+                    if let Some(socket) = manager.datagram_sockets.get(&handle) {
+                        events.push((socket.target, SocketEvent::Datagram(datagram)));
+                    }
+                }
+            }
+        }
+
+        for listener in manager.listeners.values_mut() {
+            if !listener.listening {
+                continue;
+            }
+            while let Ok(Some(accepted)) = listener.incoming.try_next() {
+                events.push((listener.target, SocketEvent::Accept(accepted)));
+            }
+        }
+
+        let mut timed_out = Vec::new();
+        for (handle, socket) in manager.sockets.iter_mut() {
+            if let SocketState::Connecting(remaining) = &mut socket.state {
+                *remaining -= dt;
+                if *remaining <= 0.0 {
+                    timed_out.push(*handle);
+                }
+            }
+        }
+        for handle in timed_out {
+            if let Some(socket) = manager.sockets.remove(&handle) {
+                let event = SocketEvent::Timeout {
+                    host: socket.host,
+                    port: socket.port,
+                };
+                events.push((socket.target, event));
+            }
+        }
+
+        for (target, event) in events {
+            match target {
+                SocketObject::Avm1(object) => {
+                    let mut activation = Avm1Activation::from_stub(
+                        context.reborrow(),
+                        ActivationIdentifier::root("[XMLSocket]"),
+                    );
+                    if let Err(e) = avm1_dispatch_event(&mut activation, object, event) {
+                        tracing::error!("Error dispatching XMLSocket event: {:?}", e);
+                    }
+                }
+                SocketObject::Avm2(object) => {
+                    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                    if let Err(e) = avm2_socket::dispatch_event(&mut activation, object, event) {
+                        tracing::error!("Error dispatching Socket event: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'gc> Default for SocketManager<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hands the data received on a connection over to the manager, until the connection is closed.
+async fn forward_incoming(
+    handle: SocketHandle,
+    mut incoming: UnboundedReceiver<Vec<u8>>,
+    sender: UnboundedSender<SocketAction>,
+) {
+    while let Some(data) = incoming.next().await {
+        if sender
+            .unbounded_send(SocketAction::Data(handle, data))
+            .is_err()
+        {
+            return;
+        }
+    }
+    let _ = sender.unbounded_send(SocketAction::Closed(handle));
+}
+
+/// Appends received data to the start of a message, returning the messages it completes.
+///
+/// XML socket messages are terminated by a zero byte, which isn't part of the message.
+fn split_messages(partial_message: &mut Vec<u8>, data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut parts = data.split(|byte| *byte == 0);
+    if let Some(first) = parts.next() {
+        partial_message.extend_from_slice(first);
+    }
+    for part in parts {
+        messages.push(std::mem::replace(partial_message, part.to_vec()));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_messages() {
+        let mut partial_message = Vec::new();
+        assert!(split_messages(&mut partial_message, b"<a/").is_empty());
+        assert_eq!(
+            split_messages(&mut partial_message, b">\0<b/>\0<c"),
+            vec![b"<a/>".to_vec(), b"<b/>".to_vec()]
+        );
+        assert_eq!(partial_message, b"<c");
+        assert_eq!(
+            split_messages(&mut partial_message, b"/>\0\0"),
+            vec![b"<c/>".to_vec(), b"".to_vec()]
+        );
+        assert!(partial_message.is_empty());
+    }
+}
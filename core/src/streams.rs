@@ -0,0 +1,1249 @@
+//! `NetStream` playback of external FLV files and of RTMP streams.
+//!
+//! MP4 and F4V files are demuxed as well, but no video backend decodes H.264 yet, so their
+//! video stays blank. Audio is decoded by `audio`, and played through the audio backend.
+
+mod audio;
+mod avc;
+mod flv;
+mod mp4;
+
+use crate::avm2::object::TObject;
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Error as Avm2Error, Multiname, Object as Avm2Object,
+    Value as Avm2Value,
+};
+use crate::backend::audio::AudioBackend;
+use crate::backend::navigator::Request;
+use crate::compatibility::MediaKind;
+use crate::context::UpdateContext;
+use crate::rtmp::{self, Amf0Value, RtmpConnection, RtmpRequest};
+use crate::streams::audio::StreamAudio;
+use crate::streams::avc::AvcConfig;
+use crate::streams::flv::{read_script_message, FlvDemuxer};
+use crate::streams::mp4::Mp4Demuxer;
+use crate::string::AvmString;
+use gc_arena::{Collect, GcCell, MutationContext};
+use ruffle_render::bitmap::BitmapInfo;
+use ruffle_video::frame::EncodedFrame;
+use ruffle_video::VideoStreamHandle;
+use std::fmt;
+use swf::{AudioCompression, VideoCodec, VideoDeblocking};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DemuxError {
+    #[error("Unknown container format")]
+    UnknownFormat,
+
+    #[error("Invalid {0} data")]
+    Invalid(&'static str),
+
+    #[error("{0} is not supported")]
+    Unsupported(&'static str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackKind {
+    Video,
+    Audio,
+
+    /// Script data messages, such as `onMetaData` and cue points.
+    Script,
+}
+
+/// A single encoded frame of a demuxed stream.
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub kind: TrackKind,
+
+    /// The decoding time, in milliseconds.
+    pub time: f64,
+
+    /// The presentation time minus the decoding time, in milliseconds.
+    pub composition_offset: f64,
+
+    pub keyframe: bool,
+
+    /// The location of the sample data in the stream.
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct VideoTrack {
+    pub codec: VideoCodec,
+
+    /// The displayed size of the frames, if the container declares it.
+    pub size: Option<(u16, u16)>,
+
+    /// The decoder configuration of H.264 streams.
+    pub avc_config: Option<AvcConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AudioTrack {
+    pub compression: AudioCompression,
+    pub sample_rate: u32,
+    pub is_stereo: bool,
+    pub is_16_bit: bool,
+
+    /// The `AudioSpecificConfig` of AAC streams.
+    pub aac_config: Option<Vec<u8>>,
+}
+
+/// Everything demuxed from a stream so far.
+#[derive(Clone, Debug, Default)]
+pub struct MediaInfo {
+    pub video: Option<VideoTrack>,
+    pub audio: Option<AudioTrack>,
+
+    /// The duration of the stream in milliseconds, if the container declares it.
+    pub duration: Option<f64>,
+
+    /// The time in milliseconds and file position of every keyframe, if the
+    /// container declares them, so that seeking can download the file from
+    /// a keyframe onwards.
+    pub keyframes: Vec<(f64, usize)>,
+
+    /// The samples of all tracks, in decoding order.
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Debug)]
+enum Demuxer {
+    Flv(FlvDemuxer),
+    Mp4(Mp4Demuxer),
+}
+
+impl Demuxer {
+    /// Picks the demuxer for the container format of `data`.
+    ///
+    /// Returns `Ok(None)` if not enough data was received to tell.
+    fn detect(data: &[u8]) -> Result<Option<Self>, DemuxError> {
+        if data.len() < 8 {
+            Ok(None)
+        } else if data.starts_with(b"FLV") {
+            Ok(Some(Demuxer::Flv(FlvDemuxer::new())))
+        } else if mp4::is_mp4(data) {
+            Ok(Some(Demuxer::Mp4(Mp4Demuxer::new())))
+        } else {
+            Err(DemuxError::UnknownFormat)
+        }
+    }
+
+    fn parse(&mut self, data: &[u8], info: &mut MediaInfo) -> Result<(), DemuxError> {
+        match self {
+            Demuxer::Flv(demuxer) => demuxer.parse(data, info),
+            Demuxer::Mp4(demuxer) => demuxer.parse(data, info),
+        }
+    }
+}
+
+/// The ways to continue a stream in data generation mode, as given to
+/// `NetStream.appendBytesAction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppendBytesAction {
+    /// The next appended data starts a new file, with an FLV header.
+    ResetBegin,
+
+    /// The next appended data continues at a new position, starting with an
+    /// FLV tag.
+    ResetSeek,
+
+    /// No more data will be appended.
+    EndSequence,
+}
+
+/// The header of an FLV file with audio and video, which is fed to the
+/// demuxer before the tags of RTMP streams.
+const FLV_HEADER: [u8; 13] = [b'F', b'L', b'V', 1, 0b101, 0, 0, 0, 9, 0, 0, 0, 0];
+
+/// The default `NetStream.bufferTime`, in milliseconds.
+const DEFAULT_BUFFER_TIME: f64 = 100.0;
+
+/// Where the data of a stream comes from.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+enum StreamSource<'gc> {
+    /// A file loaded by `play`.
+    File,
+
+    /// File data handed over by `NetStream.appendBytes`.
+    DataGeneration,
+
+    /// A stream of an RTMP server, received as FLV tags.
+    Rtmp {
+        connection: RtmpConnection<'gc>,
+        local_id: u32,
+    },
+}
+
+/// Keeps track of the streams that are playing, so they can be ticked.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct StreamManager<'gc> {
+    playing_streams: Vec<NetStream<'gc>>,
+}
+
+impl<'gc> StreamManager<'gc> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn activate(&mut self, stream: NetStream<'gc>) {
+        if !self
+            .playing_streams
+            .iter()
+            .any(|playing| NetStream::ptr_eq(*playing, stream))
+        {
+            self.playing_streams.push(stream);
+        }
+    }
+
+    fn deactivate(&mut self, stream: NetStream<'gc>) {
+        self.playing_streams
+            .retain(|playing| !NetStream::ptr_eq(*playing, stream));
+    }
+
+    /// Advances all playing streams by `dt` milliseconds.
+    pub fn tick(context: &mut UpdateContext<'_, 'gc>, dt: f64) {
+        let streams = context.stream_manager.playing_streams.clone();
+        for stream in streams {
+            stream.tick(context, dt);
+        }
+    }
+}
+
+/// A stream of video and audio, received from a file.
+///
+/// `NetStream`s are created by ActionScript, and shown by attaching them to
+/// `Video` display objects.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct NetStream<'gc>(GcCell<'gc, NetStreamData<'gc>>);
+
+impl fmt::Debug for NetStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NetStream")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct NetStreamData<'gc> {
+    /// All data received so far.
+    buffer: Vec<u8>,
+
+    /// The position of the first byte of `buffer` in the stream, as already
+    /// played data may have been dropped, and seeking may download a file
+    /// from the middle.
+    buffer_start: usize,
+
+    /// Whether the whole file was received.
+    complete: bool,
+
+    /// Where the data of the stream comes from.
+    source: StreamSource<'gc>,
+
+    /// The URL of the file played by `play`.
+    #[collect(require_static)]
+    url: Option<String>,
+
+    /// The length of the whole file, if the server reported it.
+    total_length: Option<usize>,
+
+    /// Identifies the current download of the file, so that data of
+    /// abandoned downloads is ignored.
+    load_id: u32,
+
+    /// Whether the playback position jumps to the next sample, as the first
+    /// sample after a reset of data generation mode.
+    resync_time: bool,
+
+    #[collect(require_static)]
+    demuxer: Option<Demuxer>,
+
+    #[collect(require_static)]
+    media: MediaInfo,
+
+    /// The index of the next sample to play.
+    next_sample: usize,
+
+    /// The playback position, in milliseconds.
+    stream_time: f64,
+
+    playing: bool,
+
+    /// Whether playback waits for `buffer_time` milliseconds of data to be
+    /// received, as it started, seeked or ran out of data.
+    buffering: bool,
+
+    /// How much data to receive ahead of the playback position before
+    /// playing, in milliseconds.
+    buffer_time: f64,
+
+    /// Whether the end of the data was reported with `NetStream.Buffer.Flush`.
+    flushed: bool,
+
+    /// Whether the end of the stream was reported.
+    finished: bool,
+
+    /// The decoder stream of the video track, if it was registered.
+    #[collect(require_static)]
+    video_stream: Option<VideoStreamHandle>,
+
+    /// Whether the video backend failed to decode the video track.
+    video_failed: bool,
+
+    /// The deblocking filter requested by the video player showing this
+    /// stream.
+    #[collect(require_static)]
+    deblocking: VideoDeblocking,
+
+    /// The decoder and sound of the audio track, created with its first sample.
+    #[collect(require_static)]
+    audio: Option<StreamAudio>,
+
+    /// Whether the audio track couldn't be decoded.
+    audio_failed: bool,
+
+    /// The script data messages reached by playback, to be sent to the
+    /// client object.
+    #[collect(require_static)]
+    script_messages: Vec<(String, Vec<Amf0Value>)>,
+
+    /// The last decoded frame of the video track.
+    #[collect(require_static)]
+    last_decoded_bitmap: Option<BitmapInfo>,
+
+    /// The `flash.net.NetStream` object of this stream.
+    avm_object: Option<Avm2Object<'gc>>,
+}
+
+impl<'gc> NetStream<'gc> {
+    pub fn new(gc_context: MutationContext<'gc, '_>, avm_object: Option<Avm2Object<'gc>>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            NetStreamData {
+                buffer: Vec::new(),
+                buffer_start: 0,
+                complete: false,
+                source: StreamSource::File,
+                url: None,
+                total_length: None,
+                load_id: 0,
+                resync_time: false,
+                demuxer: None,
+                media: MediaInfo::default(),
+                next_sample: 0,
+                stream_time: 0.0,
+                playing: false,
+                buffering: false,
+                buffer_time: DEFAULT_BUFFER_TIME,
+                flushed: false,
+                finished: false,
+                video_stream: None,
+                video_failed: false,
+                deblocking: VideoDeblocking::UseVideoPacketValue,
+                audio: None,
+                audio_failed: false,
+                script_messages: Vec::new(),
+                last_decoded_bitmap: None,
+                avm_object,
+            },
+        ))
+    }
+
+    pub fn ptr_eq(a: Self, b: Self) -> bool {
+        GcCell::ptr_eq(a.0, b.0)
+    }
+
+    /// The `flash.net.NetStream` object of this stream.
+    pub fn avm_object(self) -> Option<Avm2Object<'gc>> {
+        self.0.read().avm_object
+    }
+
+    /// Sets the ActionScript object that receives the status events of this stream.
+    pub fn set_avm_object(self, gc_context: MutationContext<'gc, '_>, avm_object: Avm2Object<'gc>) {
+        self.0.write(gc_context).avm_object = Some(avm_object);
+    }
+
+    /// Starts playing the file at `url`, discarding the previous one.
+    ///
+    /// Playback starts while the file is still downloading, as its data is
+    /// handed over with `load_buffer`.
+    pub fn play(self, context: &mut UpdateContext<'_, 'gc>, url: String) {
+        let mut write = self.0.write(context.gc_context);
+        write.stop_audio(context.audio);
+        write.reset();
+        write.source = StreamSource::File;
+        write.url = Some(url);
+        drop(write);
+
+        context.stream_manager.activate(self);
+        self.dispatch_status(context, "NetStream.Play.Start", "status");
+        self.start_loading(context, 0);
+    }
+
+    /// Starts downloading the played file from byte `offset` onwards,
+    /// abandoning the previous download.
+    fn start_loading(self, context: &mut UpdateContext<'_, 'gc>, offset: usize) {
+        let mut write = self.0.write(context.gc_context);
+        let url = match &write.url {
+            Some(url) => url.clone(),
+            None => return,
+        };
+        write.load_id = write.load_id.wrapping_add(1);
+        let load_id = write.load_id;
+        drop(write);
+
+        let future = context.load_manager.load_netstream(
+            context.player.clone(),
+            self,
+            load_id,
+            Request::get(url),
+            offset,
+        );
+        context.navigator.spawn_future(future);
+    }
+
+    /// Whether the download identified by `load_id` is still wanted.
+    pub fn is_loading(self, load_id: u32) -> bool {
+        let read = self.0.read();
+        read.url.is_some() && read.load_id == load_id
+    }
+
+    /// Switches to data generation mode, discarding the previous file.
+    ///
+    /// The file data is then handed over by ActionScript with `append_bytes`.
+    pub fn play_data_generation(self, context: &mut UpdateContext<'_, 'gc>) {
+        let mut write = self.0.write(context.gc_context);
+        write.stop_audio(context.audio);
+        write.reset();
+        write.source = StreamSource::DataGeneration;
+        write.resync_time = true;
+        drop(write);
+
+        context.stream_manager.activate(self);
+    }
+
+    /// Starts playing a stream of an RTMP server, discarding the previous file.
+    ///
+    /// The server reports the start of playback itself, and the stream data
+    /// is handed over as FLV tags with `load_buffer`.
+    pub fn play_rtmp(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        connection: RtmpConnection<'gc>,
+        local_id: u32,
+    ) {
+        let mut write = self.0.write(context.gc_context);
+        write.stop_audio(context.audio);
+        write.reset();
+        write.source = StreamSource::Rtmp {
+            connection,
+            local_id,
+        };
+        // Live streams don't start at time zero.
+        write.resync_time = true;
+        drop(write);
+
+        context.stream_manager.activate(self);
+        self.load_buffer(context, &FLV_HEADER);
+    }
+
+    /// Appends FLV data in data generation mode.
+    pub fn append_bytes(self, context: &mut UpdateContext<'_, 'gc>, data: &[u8]) {
+        if !matches!(self.0.read().source, StreamSource::DataGeneration) {
+            tracing::warn!("NetStream.appendBytes called outside of data generation mode");
+            return;
+        }
+        self.load_buffer(context, data);
+    }
+
+    /// Changes how the data appended next in data generation mode is read.
+    pub fn append_bytes_action(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        action: AppendBytesAction,
+    ) {
+        let mut write = self.0.write(context.gc_context);
+        if !matches!(write.source, StreamSource::DataGeneration) {
+            tracing::warn!("NetStream.appendBytesAction called outside of data generation mode");
+            return;
+        }
+
+        match action {
+            AppendBytesAction::ResetBegin => {
+                write.stop_audio(context.audio);
+                write.flush();
+                write.demuxer = None;
+                write.media = MediaInfo::default();
+                write.video_stream = None;
+                write.video_failed = false;
+            }
+            AppendBytesAction::ResetSeek => {
+                write.stop_audio(context.audio);
+                write.flush();
+                write.demuxer = Some(Demuxer::Flv(FlvDemuxer::headerless()));
+            }
+            AppendBytesAction::EndSequence => write.complete = true,
+        }
+    }
+
+    /// Appends received file data to the stream.
+    pub fn load_buffer(self, context: &mut UpdateContext<'_, 'gc>, data: &[u8]) {
+        let result = self.0.write(context.gc_context).demux(data);
+        if let Err(e) = result {
+            tracing::error!("Unable to play stream: {}", e);
+            let mut write = self.0.write(context.gc_context);
+            write.buffer.clear();
+            write.demuxer = None;
+            write.playing = false;
+            drop(write);
+
+            let code = match e {
+                DemuxError::Unsupported(_) => "NetStream.Play.NoSupportedTrackFound",
+                _ => "NetStream.Play.FileStructureInvalid",
+            };
+            self.stop_with_status(context, code);
+        }
+    }
+
+    /// Records the length of the whole file, as reported by the server.
+    pub fn set_bytes_total(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        total_length: Option<usize>,
+    ) {
+        if let Some(total_length) = total_length {
+            self.0.write(context.gc_context).total_length = Some(total_length);
+        }
+    }
+
+    /// Marks the whole file as received.
+    pub fn finish_loading(self, context: &mut UpdateContext<'_, 'gc>) {
+        self.0.write(context.gc_context).complete = true;
+    }
+
+    /// Reports a file that couldn't be loaded.
+    pub fn report_not_found(self, context: &mut UpdateContext<'_, 'gc>) {
+        self.0.write(context.gc_context).playing = false;
+        self.stop_with_status(context, "NetStream.Play.StreamNotFound");
+    }
+
+    pub fn pause(self, context: &mut UpdateContext<'_, 'gc>) {
+        self.set_paused(context, true);
+    }
+
+    pub fn resume(self, context: &mut UpdateContext<'_, 'gc>) {
+        self.set_paused(context, false);
+    }
+
+    fn set_paused(self, context: &mut UpdateContext<'_, 'gc>, paused: bool) {
+        let mut write = self.0.write(context.gc_context);
+        write.playing = !paused;
+        if paused {
+            write.stop_audio(context.audio);
+        }
+        if let StreamSource::Rtmp {
+            connection,
+            local_id,
+        } = write.source
+        {
+            // The server reports the pause itself.
+            connection.request(RtmpRequest::Pause {
+                local_id,
+                paused,
+                time: write.stream_time,
+            });
+            return;
+        }
+        drop(write);
+
+        let code = if paused {
+            "NetStream.Pause.Notify"
+        } else {
+            "NetStream.Unpause.Notify"
+        };
+        self.dispatch_status(context, code, "status");
+    }
+
+    pub fn toggle_paused(self, context: &mut UpdateContext<'_, 'gc>) {
+        if self.0.read().playing {
+            self.pause(context);
+        } else {
+            self.resume(context);
+        }
+    }
+
+    /// Jumps to the keyframe closest before `offset` milliseconds.
+    ///
+    /// If the container declares where its keyframes are, and that keyframe
+    /// wasn't received, the file is downloaded again from there, so that long
+    /// videos don't need to be downloaded up to the new position.
+    ///
+    /// In data generation mode, the received data is discarded instead, and
+    /// ActionScript is expected to append the data for the new position.
+    /// RTMP streams ask the server to send the data of the new position.
+    pub fn seek(self, context: &mut UpdateContext<'_, 'gc>, offset: f64) {
+        let mut write = self.0.write(context.gc_context);
+        let source = write.source;
+        match source {
+            StreamSource::File => {
+                let keyframe = write
+                    .media
+                    .keyframes
+                    .iter()
+                    .filter(|(time, _)| *time <= offset)
+                    .last()
+                    .copied();
+                if let Some((time, position)) = keyframe {
+                    let received = write.buffer_start..write.buffer_start + write.buffer.len();
+                    if !received.contains(&position) && write.url.is_some() {
+                        write.stop_audio(context.audio);
+                        write.restart_at(position, time);
+                        drop(write);
+
+                        self.start_loading(context, position);
+                        self.dispatch_status(context, "NetStream.Seek.Notify", "status");
+                        return;
+                    }
+                }
+            }
+            StreamSource::DataGeneration => {
+                write.stop_audio(context.audio);
+                write.flush();
+                write.stream_time = offset;
+                drop(write);
+
+                self.dispatch_status(context, "NetStream.Seek.Notify", "status");
+                return;
+            }
+            StreamSource::Rtmp {
+                connection,
+                local_id,
+            } => {
+                write.stop_audio(context.audio);
+                write.flush();
+                write.stream_time = offset;
+                // The server reports the seek itself.
+                connection.request(RtmpRequest::Seek {
+                    local_id,
+                    time: offset,
+                });
+                return;
+            }
+        }
+
+        let has_video = write.media.video.is_some();
+        let target = write
+            .media
+            .samples
+            .iter()
+            .enumerate()
+            .filter(|(_, sample)| {
+                sample.time <= offset && (!has_video || sample.kind == TrackKind::Video)
+            })
+            .filter(|(_, sample)| sample.keyframe)
+            .last()
+            .map(|(index, sample)| (index, sample.time));
+        let (index, time) = target.unwrap_or((0, 0.0));
+        write.stop_audio(context.audio);
+        write.next_sample = index;
+        write.stream_time = time;
+        write.buffering = true;
+        write.finished = false;
+        drop(write);
+
+        self.dispatch_status(context, "NetStream.Seek.Notify", "status");
+    }
+
+    /// Stops playback and discards the file.
+    pub fn close(self, context: &mut UpdateContext<'_, 'gc>) {
+        if let StreamSource::Rtmp {
+            connection,
+            local_id,
+        } = self.0.read().source
+        {
+            connection.close_stream(context, local_id);
+        }
+
+        let mut write = self.0.write(context.gc_context);
+        write.stop_audio(context.audio);
+        write.buffer.clear();
+        write.source = StreamSource::File;
+        write.url = None;
+        write.demuxer = None;
+        write.media = MediaInfo::default();
+        write.playing = false;
+        write.last_decoded_bitmap = None;
+        drop(write);
+
+        context.stream_manager.deactivate(self);
+    }
+
+    /// The playback position, in seconds.
+    pub fn time(self) -> f64 {
+        self.0.read().stream_time / 1000.0
+    }
+
+    /// How much data is received ahead of the playback position, in seconds.
+    pub fn buffer_length(self) -> f64 {
+        self.0.read().buffer_length() / 1000.0
+    }
+
+    /// How much data to receive ahead of the playback position before
+    /// playing, in seconds.
+    pub fn buffer_time(self) -> f64 {
+        self.0.read().buffer_time / 1000.0
+    }
+
+    pub fn set_buffer_time(self, context: &mut UpdateContext<'_, 'gc>, buffer_time: f64) {
+        let buffer_time = if buffer_time.is_finite() {
+            buffer_time.max(0.0) * 1000.0
+        } else {
+            0.0
+        };
+        self.0.write(context.gc_context).buffer_time = buffer_time;
+    }
+
+    pub fn bytes_loaded(self) -> usize {
+        let read = self.0.read();
+        read.buffer_start + read.buffer.len()
+    }
+
+    pub fn bytes_total(self) -> usize {
+        let loaded = self.bytes_loaded();
+        self.0
+            .read()
+            .total_length
+            .map_or(loaded, |total_length| total_length.max(loaded))
+    }
+
+    /// The size of the video frames, if known.
+    pub fn video_size(self) -> Option<(u16, u16)> {
+        let read = self.0.read();
+        read.media
+            .video
+            .as_ref()
+            .and_then(|video| video.size)
+            .or_else(|| {
+                read.last_decoded_bitmap
+                    .as_ref()
+                    .map(|bitmap| (bitmap.width, bitmap.height))
+            })
+    }
+
+    pub fn last_decoded_bitmap(self) -> Option<BitmapInfo> {
+        self.0.read().last_decoded_bitmap.clone()
+    }
+
+    /// Changes the deblocking filter applied to the decoded video frames.
+    pub fn set_deblocking(self, context: &mut UpdateContext<'_, 'gc>, deblocking: VideoDeblocking) {
+        let mut write = self.0.write(context.gc_context);
+        write.deblocking = deblocking;
+        if let Some(stream) = write.video_stream {
+            if let Err(e) = context
+                .video
+                .set_video_stream_deblocking(stream, deblocking)
+            {
+                tracing::warn!("Unable to change NetStream deblocking filter: {}", e);
+            }
+        }
+    }
+
+    /// Forgets the last decoded frame, until the next one is decoded.
+    pub fn clear_frame(self, context: &mut UpdateContext<'_, 'gc>) {
+        self.0.write(context.gc_context).last_decoded_bitmap = None;
+    }
+
+    /// Advances playback by `dt` milliseconds, decoding every frame reached.
+    fn tick(self, context: &mut UpdateContext<'_, 'gc>, dt: f64) {
+        let mut write = self.0.write(context.gc_context);
+        let statuses = write.advance(context, dt);
+        let script_messages = std::mem::take(&mut write.script_messages);
+        drop(write);
+
+        for (name, arguments) in script_messages {
+            self.call_client(context, &name, &arguments);
+        }
+        for code in statuses {
+            self.dispatch_status(context, code, "status");
+        }
+    }
+
+    /// Reports an error that stopped playback.
+    fn stop_with_status(self, context: &mut UpdateContext<'_, 'gc>, code: &'static str) {
+        self.dispatch_status(context, code, "error");
+    }
+
+    /// Calls the handler `name` of the client object of this stream, as
+    /// requested by a script data message of the stream.
+    ///
+    /// Messages without a handler on the client are ignored.
+    fn call_client(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        name: &str,
+        arguments: &[Amf0Value],
+    ) {
+        let object = match self.0.read().avm_object {
+            Some(object) => object,
+            None => return,
+        };
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        if let Err(e) = call_client_handler(&mut activation, object, name, arguments) {
+            tracing::error!(
+                "Encountered AVM2 error when calling NetStream client handler {}: {}",
+                name,
+                e
+            );
+        }
+    }
+
+    /// Dispatches a `netStatus` event on the ActionScript object of this stream.
+    fn dispatch_status(
+        self,
+        context: &mut UpdateContext<'_, 'gc>,
+        code: &'static str,
+        level: &'static str,
+    ) {
+        let object = match self.0.read().avm_object {
+            Some(object) => object,
+            None => return,
+        };
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let event = match net_status_event(&mut activation, code, level) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::error!("Unable to create netStatus event: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = Avm2::dispatch_event(&mut activation.context, event, object) {
+            tracing::error!(
+                "Encountered AVM2 error when dispatching netStatus event: {}",
+                e
+            );
+        }
+    }
+}
+
+impl<'gc> NetStreamData<'gc> {
+    /// Forgets the previous file, to start playing a new one.
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.buffer_start = 0;
+        self.complete = false;
+        self.url = None;
+        self.total_length = None;
+        self.resync_time = false;
+        self.demuxer = None;
+        self.media = MediaInfo::default();
+        self.next_sample = 0;
+        self.stream_time = 0.0;
+        self.playing = true;
+        self.buffering = true;
+        self.flushed = false;
+        self.finished = false;
+        self.video_stream = None;
+        self.video_failed = false;
+        self.audio = None;
+        self.audio_failed = false;
+        self.script_messages.clear();
+        self.last_decoded_bitmap = None;
+    }
+
+    /// Drops the data received in data generation mode, keeping the decoders.
+    fn flush(&mut self) {
+        self.buffer_start += self.buffer.len();
+        self.buffer.clear();
+        self.media.samples.clear();
+        self.next_sample = 0;
+        self.complete = false;
+        self.buffering = true;
+        self.flushed = false;
+        self.finished = false;
+        self.resync_time = true;
+    }
+
+    /// Drops the data of the samples played in data generation mode, as
+    /// ActionScript appends new data rather than seeking in the old one.
+    fn discard_played(&mut self) {
+        if self.next_sample < self.media.samples.len() {
+            return;
+        }
+        if let Some(Demuxer::Flv(demuxer)) = &mut self.demuxer {
+            let length = demuxer.discard_parsed();
+            self.buffer.drain(..length);
+            self.buffer_start += length;
+            self.media.samples.clear();
+            self.next_sample = 0;
+        }
+    }
+
+    /// Drops the received data, to download the file again from byte
+    /// `position`, where the keyframe at `time` milliseconds starts.
+    fn restart_at(&mut self, position: usize, time: f64) {
+        self.buffer.clear();
+        self.buffer_start = position;
+        self.complete = false;
+        self.buffering = true;
+        self.flushed = false;
+        self.finished = false;
+        self.stream_time = time;
+        if let Some(Demuxer::Flv(demuxer)) = &mut self.demuxer {
+            // The tags are parsed again as they arrive.
+            *demuxer = FlvDemuxer::headerless();
+            self.media.samples.clear();
+            self.next_sample = 0;
+            self.resync_time = true;
+        } else {
+            self.next_sample = self
+                .media
+                .samples
+                .iter()
+                .position(|sample| sample.offset == position)
+                .unwrap_or(0);
+        }
+    }
+
+    /// Appends received data and demuxes it.
+    fn demux(&mut self, data: &[u8]) -> Result<(), DemuxError> {
+        self.buffer.extend_from_slice(data);
+        if self.demuxer.is_none() {
+            self.demuxer = Demuxer::detect(&self.buffer)?;
+        }
+        let parsed_samples = self.media.samples.len();
+        if let Some(demuxer) = &mut self.demuxer {
+            demuxer.parse(&self.buffer, &mut self.media)?;
+        }
+        // The demuxers locate new samples in the buffer, not in the stream.
+        for sample in &mut self.media.samples[parsed_samples..] {
+            sample.offset += self.buffer_start;
+        }
+        Ok(())
+    }
+
+    /// The data of `sample`, or `None` if it wasn't received.
+    fn sample_data(&self, sample: &Sample) -> Option<&[u8]> {
+        let start = sample.offset.checked_sub(self.buffer_start)?;
+        self.buffer.get(start..start.checked_add(sample.length)?)
+    }
+
+    /// The time of the data received ahead of the playback position, in
+    /// milliseconds.
+    fn buffer_length(&self) -> f64 {
+        let upcoming = self
+            .media
+            .samples
+            .get(self.next_sample..)
+            .unwrap_or_default();
+        upcoming
+            .iter()
+            .take_while(|sample| self.sample_data(sample).is_some())
+            .last()
+            .map_or(0.0, |sample| (sample.time - self.stream_time).max(0.0))
+    }
+
+    /// Plays the samples up to `dt` milliseconds later.
+    ///
+    /// Playback waits while less than `buffer_time` milliseconds of data is
+    /// received ahead of it, unless the whole stream was received.
+    ///
+    /// Returns the codes of the status events to report, such as
+    /// `NetStream.Buffer.Empty` or `NetStream.Play.Stop`.
+    fn advance(&mut self, context: &mut UpdateContext<'_, 'gc>, dt: f64) -> Vec<&'static str> {
+        let mut statuses = Vec::new();
+        if !self.playing || self.demuxer.is_none() {
+            return statuses;
+        }
+        if self.complete && !self.flushed && !self.finished {
+            self.flushed = true;
+            statuses.push("NetStream.Buffer.Flush");
+        }
+        if self.resync_time {
+            match self.media.samples.get(self.next_sample) {
+                Some(sample) => {
+                    self.stream_time = sample.time;
+                    self.resync_time = false;
+                }
+                None => return statuses,
+            }
+        }
+        if self.buffering {
+            if !self.complete && self.buffer_length() < self.buffer_time {
+                return statuses;
+            }
+            self.buffering = false;
+            statuses.push("NetStream.Buffer.Full");
+        }
+
+        let target_time = self.stream_time + dt;
+        while let Some(sample) = self.media.samples.get(self.next_sample) {
+            if sample.time > target_time {
+                break;
+            }
+            if sample.offset < self.buffer_start {
+                // The file was downloaded again from after this sample.
+                self.next_sample += 1;
+                continue;
+            }
+            if self.sample_data(sample).is_none() {
+                // Wait for the rest of the file, keeping the position on the
+                // missing sample.
+                self.stream_time = sample.time;
+                if !self.complete {
+                    self.buffering = true;
+                    self.finish_audio(context);
+                    statuses.push("NetStream.Buffer.Empty");
+                }
+                return statuses;
+            }
+
+            let frame_id = self.next_sample as u32;
+            match sample.kind {
+                TrackKind::Video => {
+                    let sample = sample.clone();
+                    self.decode_video(context, &sample, frame_id);
+                }
+                TrackKind::Audio => {
+                    let sample = sample.clone();
+                    self.play_audio(context, &sample);
+                }
+                TrackKind::Script => {
+                    let message = self.sample_data(sample).and_then(read_script_message);
+                    if let Some(message) = message {
+                        self.script_messages.push(message);
+                    }
+                }
+            }
+            self.next_sample += 1;
+        }
+
+        let ran_out = self.next_sample >= self.media.samples.len();
+        if ran_out && !self.complete {
+            // Playback can't continue past the last received sample.
+            let last_time = self.media.samples.last().map(|sample| sample.time);
+            self.stream_time =
+                last_time.map_or(self.stream_time, |time| time.max(self.stream_time));
+            self.buffering = true;
+            self.finish_audio(context);
+            statuses.push("NetStream.Buffer.Empty");
+        } else {
+            self.stream_time = target_time;
+        }
+        if !matches!(self.source, StreamSource::File) {
+            self.discard_played();
+        }
+
+        if self.finished || !self.complete || !ran_out {
+            return statuses;
+        }
+        self.finished = true;
+        self.playing = false;
+        if let Some(duration) = self.media.duration {
+            self.stream_time = self.stream_time.min(duration);
+        }
+        self.finish_audio(context);
+        statuses.push("NetStream.Play.Stop");
+        statuses.push("NetStream.Buffer.Empty");
+        statuses
+    }
+
+    /// Decodes an audio sample, and queues it to be played.
+    fn play_audio(&mut self, context: &mut UpdateContext<'_, 'gc>, sample: &Sample) {
+        if self.audio_failed {
+            return;
+        }
+        if self.audio.is_none() {
+            let track = match &self.media.audio {
+                Some(track) => track,
+                None => return,
+            };
+            match StreamAudio::new(track) {
+                Ok(audio) => self.audio = Some(audio),
+                Err(e) => {
+                    tracing::error!("Unable to decode NetStream audio: {}", e);
+                    context.compatibility.decoder_failed(MediaKind::Audio, &e);
+                    self.audio_failed = true;
+                    return;
+                }
+            }
+        }
+
+        // Not `sample_data`, as the buffer is borrowed alongside the decoder.
+        let start = match sample.offset.checked_sub(self.buffer_start) {
+            Some(start) => start,
+            None => return,
+        };
+        let data = match self.buffer.get(start..start + sample.length) {
+            Some(data) => data,
+            None => return,
+        };
+        if let Some(audio) = &mut self.audio {
+            audio.play(context.audio, data);
+        }
+    }
+
+    /// Lets the queued audio play out, as playback ran out of data or reached the end.
+    ///
+    /// The next audio sample starts a new sound.
+    fn finish_audio(&mut self, context: &mut UpdateContext<'_, 'gc>) {
+        if let Some(audio) = &mut self.audio {
+            audio.finish(context.audio);
+        }
+    }
+
+    /// Stops the audio immediately, as playback stopped or jumped elsewhere.
+    ///
+    /// The decoder is recreated with the next audio sample, so that it doesn't carry over the
+    /// state of the previous position.
+    fn stop_audio(&mut self, audio: &mut dyn AudioBackend) {
+        if let Some(mut stream_audio) = self.audio.take() {
+            stream_audio.stop(audio);
+        }
+    }
+
+    fn decode_video(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc>,
+        sample: &Sample,
+        frame_id: u32,
+    ) {
+        if self.video_failed {
+            return;
+        }
+        let track = match &self.media.video {
+            Some(track) => track,
+            None => return,
+        };
+
+        let stream = match self.video_stream {
+            Some(stream) => stream,
+            None => {
+                match context.video.register_video_stream(
+                    0,
+                    track.size.unwrap_or_default(),
+                    track.codec,
+                    self.deblocking,
+                ) {
+                    Ok(stream) => {
+                        self.video_stream = Some(stream);
+                        stream
+                    }
+                    Err(e) => {
+                        tracing::error!("Unable to decode NetStream video: {}", e);
+                        context.compatibility.decoder_failed(MediaKind::Video, &e);
+                        self.video_failed = true;
+                        return;
+                    }
+                }
+            }
+        };
+
+        let data = match self.sample_data(sample) {
+            Some(data) => data,
+            None => return,
+        };
+        let annex_b;
+        let data = if track.codec == VideoCodec::H264 {
+            match &track.avc_config {
+                Some(config) => {
+                    annex_b = config.to_annex_b(data, sample.keyframe);
+                    &annex_b[..]
+                }
+                None => return,
+            }
+        } else {
+            data
+        };
+
+        let preloaded = context.video.preload_video_stream_frame(
+            stream,
+            EncodedFrame {
+                codec: track.codec,
+                data,
+                frame_id,
+            },
+        );
+        if let Err(e) = preloaded {
+            tracing::warn!("Unable to preload NetStream video frame: {}", e);
+            context.compatibility.decoder_failed(MediaKind::Video, &e);
+            return;
+        }
+
+        match context.video.decode_video_stream_frame(
+            stream,
+            EncodedFrame {
+                codec: track.codec,
+                data,
+                frame_id,
+            },
+            context.renderer,
+        ) {
+            Ok(bitmap) => {
+                self.last_decoded_bitmap = Some(bitmap);
+                *context.needs_render = true;
+            }
+            Err(e) => {
+                tracing::warn!("Unable to decode NetStream video frame: {}", e);
+                context.compatibility.decoder_failed(MediaKind::Video, &e);
+            }
+        }
+    }
+}
+
+/// Calls the handler `name` of the `client` of a `flash.net.NetStream` object,
+/// if it has one.
+fn call_client_handler<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc>,
+    object: Avm2Object<'gc>,
+    name: &str,
+    arguments: &[Amf0Value],
+) -> Result<(), Avm2Error<'gc>> {
+    let client = object.get_property(&Multiname::public("client"), activation)?;
+    let client = match client.as_object() {
+        Some(client) => client,
+        None => return Ok(()),
+    };
+    let name = Multiname::public(AvmString::new_utf8(activation.context.gc_context, name));
+    let handler = client.get_property(&name, activation)?;
+    if matches!(handler, Avm2Value::Undefined | Avm2Value::Null) {
+        return Ok(());
+    }
+
+    let arguments = arguments
+        .iter()
+        .map(|argument| rtmp::to_avm2(activation, argument))
+        .collect::<Result<Vec<_>, _>>()?;
+    handler
+        .as_callable(activation, Some(&name), Some(client))?
+        .call(Some(client), &arguments, activation)?;
+    Ok(())
+}
+
+fn net_status_event<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc>,
+    code: &'static str,
+    level: &'static str,
+) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+    let mut info = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    info.set_property(&Multiname::public("code"), code.into(), activation)?;
+    info.set_property(&Multiname::public("level"), level.into(), activation)?;
+
+    activation.avm2().classes().netstatusevent.construct(
+        activation,
+        &["netStatus".into(), false.into(), false.into(), info.into()],
+    )
+}
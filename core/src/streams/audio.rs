@@ -0,0 +1,355 @@
+//! Decoding of `NetStream` audio, which is played through the audio backend as a dynamic sound.
+//!
+//! MP3 and AAC packets are decoded with the codecs of Symphonia, one MP3 frame or AAC access
+//! unit at a time, so that the decoder state carries over between the packets. The other
+//! codecs are decoded packet by packet with the decoders of SWF sounds.
+
+use crate::backend::audio::{AudioBackend, SoundInstanceHandle};
+use crate::streams::{AudioTrack, DemuxError};
+use swf::AudioCompression;
+
+#[cfg(feature = "audio")]
+use crate::backend::audio::decoders;
+#[cfg(feature = "audio")]
+use std::io::Cursor;
+#[cfg(feature = "audio")]
+use swf::SoundFormat;
+#[cfg(any(feature = "mp3", feature = "aac"))]
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{self, CodecParameters},
+    formats::Packet,
+};
+
+/// The sample rate of dynamic sounds.
+const OUTPUT_SAMPLE_RATE: u32 = 44100;
+
+/// Decodes the audio packets of a stream, and plays them as a dynamic sound.
+pub struct StreamAudio {
+    decoder: PacketDecoder,
+
+    resampler: Resampler,
+
+    /// The dynamic sound playing the decoded audio, started with the first packet.
+    instance: Option<SoundInstanceHandle>,
+}
+
+impl StreamAudio {
+    /// Creates the decoder for the audio track of a stream.
+    pub fn new(track: &AudioTrack) -> Result<Self, DemuxError> {
+        Ok(Self {
+            decoder: PacketDecoder::new(track)?,
+            resampler: Resampler::default(),
+            instance: None,
+        })
+    }
+
+    /// Decodes a packet of the audio track, and queues it for playback.
+    pub fn play(&mut self, audio: &mut dyn AudioBackend, data: &[u8]) {
+        let mut frames = Vec::new();
+        let sample_rate = match self.decoder.decode(data, &mut frames) {
+            Some(sample_rate) => sample_rate,
+            None => return,
+        };
+        let samples = self.resampler.resample(&frames, sample_rate);
+        if samples.is_empty() {
+            return;
+        }
+
+        let instance = match self.instance {
+            Some(instance) => instance,
+            None => match audio.start_dynamic_sound() {
+                Ok(instance) => *self.instance.insert(instance),
+                Err(e) => {
+                    tracing::error!("Couldn't play NetStream audio: {}", e);
+                    return;
+                }
+            },
+        };
+        audio.append_dynamic_sound_samples(instance, &samples, false);
+    }
+
+    /// Lets the sound end once the queued audio has been played.
+    pub fn finish(&mut self, audio: &mut dyn AudioBackend) {
+        if let Some(instance) = self.instance.take() {
+            audio.append_dynamic_sound_samples(instance, &[], true);
+        }
+    }
+
+    /// Stops the sound immediately, discarding the queued audio.
+    pub fn stop(&mut self, audio: &mut dyn AudioBackend) {
+        if let Some(instance) = self.instance.take() {
+            audio.stop_sound(instance);
+        }
+    }
+}
+
+enum PacketDecoder {
+    /// A Symphonia decoder, which takes a single MP3 frame or AAC access unit at a time.
+    #[cfg(any(feature = "mp3", feature = "aac"))]
+    Codec {
+        decoder: Box<dyn codecs::Decoder>,
+        compression: AudioCompression,
+    },
+
+    /// The decoders of SWF sounds, which decode each packet on its own.
+    #[cfg(feature = "audio")]
+    Swf(SoundFormat),
+}
+
+impl PacketDecoder {
+    fn new(track: &AudioTrack) -> Result<Self, DemuxError> {
+        match track.compression {
+            #[cfg(feature = "mp3")]
+            AudioCompression::Mp3 => {
+                let params = CodecParameters::new()
+                    .for_codec(codecs::CODEC_TYPE_MP3)
+                    .clone();
+                Self::codec(&params, track.compression)
+            }
+            #[cfg(feature = "aac")]
+            AudioCompression::Aac => {
+                let config = track.aac_config.clone().ok_or(DemuxError::Invalid("AAC"))?;
+                let params = CodecParameters::new()
+                    .for_codec(codecs::CODEC_TYPE_AAC)
+                    .with_extra_data(config.into_boxed_slice())
+                    .clone();
+                Self::codec(&params, track.compression)
+            }
+            #[cfg(feature = "audio")]
+            AudioCompression::Uncompressed
+            | AudioCompression::UncompressedUnknownEndian
+            | AudioCompression::Adpcm => Ok(Self::Swf(Self::sound_format(track))),
+            #[cfg(all(feature = "audio", feature = "nellymoser"))]
+            AudioCompression::Nellymoser
+            | AudioCompression::Nellymoser16Khz
+            | AudioCompression::Nellymoser8Khz => Ok(Self::Swf(Self::sound_format(track))),
+            _ => Err(DemuxError::Unsupported("This audio codec")),
+        }
+    }
+
+    #[cfg(any(feature = "mp3", feature = "aac"))]
+    fn codec(params: &CodecParameters, compression: AudioCompression) -> Result<Self, DemuxError> {
+        let decoder = symphonia::default::get_codecs()
+            .make(params, &Default::default())
+            .map_err(|e| {
+                tracing::warn!("Couldn't create NetStream audio decoder: {}", e);
+                DemuxError::Unsupported("This audio configuration")
+            })?;
+        Ok(Self::Codec {
+            decoder,
+            compression,
+        })
+    }
+
+    #[cfg(feature = "audio")]
+    fn sound_format(track: &AudioTrack) -> SoundFormat {
+        SoundFormat {
+            compression: track.compression,
+            sample_rate: track.sample_rate.try_into().unwrap_or(u16::MAX),
+            is_stereo: track.is_stereo,
+            is_16_bit: track.is_16_bit,
+        }
+    }
+
+    /// Decodes a packet into stereo sample frames, appended to `frames`.
+    ///
+    /// Returns the sample rate of the frames, or `None` if nothing could be decoded.
+    fn decode(&mut self, data: &[u8], frames: &mut Vec<[i16; 2]>) -> Option<u32> {
+        match *self {
+            #[cfg(any(feature = "mp3", feature = "aac"))]
+            Self::Codec {
+                ref mut decoder,
+                compression,
+            } => {
+                let units = if compression == AudioCompression::Mp3 {
+                    split_mp3_frames(data)
+                } else {
+                    vec![data]
+                };
+                let mut sample_rate = None;
+                for unit in units {
+                    let packet = Packet::new_from_slice(0, 0, 0, unit);
+                    let decoded = match decoder.decode(&packet) {
+                        Ok(decoded) => decoded,
+                        Err(e) => {
+                            tracing::warn!("Couldn't decode NetStream audio packet: {}", e);
+                            continue;
+                        }
+                    };
+                    let spec = *decoded.spec();
+                    let mut buffer = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+                    buffer.copy_interleaved_ref(decoded);
+                    if spec.channels.count() == 2 {
+                        frames.extend(buffer.samples().chunks_exact(2).map(|f| [f[0], f[1]]));
+                    } else {
+                        frames.extend(buffer.samples().iter().map(|sample| [*sample; 2]));
+                    }
+                    sample_rate = Some(spec.rate);
+                }
+                sample_rate
+            }
+            #[cfg(feature = "audio")]
+            Self::Swf(ref format) => {
+                match decoders::make_decoder(format, Cursor::new(data.to_vec())) {
+                    Ok(decoder) => {
+                        let sample_rate = decoder.sample_rate().into();
+                        frames.extend(decoder);
+                        Some(sample_rate)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Couldn't decode NetStream audio packet: {}", e);
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Splits MP3 data into its frames, as FLV and MP4 files may store several in one packet.
+///
+/// Data after the last complete frame is dropped.
+#[cfg(feature = "mp3")]
+fn split_mp3_frames(mut data: &[u8]) -> Vec<&[u8]> {
+    let mut frames = Vec::new();
+    while let Some(length) = data.get(..4).and_then(mp3_frame_length) {
+        if length > data.len() {
+            break;
+        }
+        let (frame, rest) = data.split_at(length);
+        frames.push(frame);
+        data = rest;
+    }
+    frames
+}
+
+#[cfg(all(feature = "aac", not(feature = "mp3")))]
+fn split_mp3_frames(_data: &[u8]) -> Vec<&[u8]> {
+    Vec::new()
+}
+
+/// Reads the length of an MPEG audio layer III frame from its 4 byte header.
+///
+/// Returns `None` if the header is invalid, or declares a free format bitrate.
+#[cfg(feature = "mp3")]
+fn mp3_frame_length(header: &[u8]) -> Option<usize> {
+    const MPEG1_BITRATES: [usize; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const MPEG2_BITRATES: [usize; 15] =
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    let header = u32::from_be_bytes(header.try_into().ok()?);
+    let version = (header >> 19) & 0b11;
+    let layer = (header >> 17) & 0b11;
+    if header >> 21 != 0x7FF || version == 0b01 || layer != 0b01 {
+        return None;
+    }
+    let is_mpeg1 = version == 0b11;
+    let bitrates = if is_mpeg1 {
+        MPEG1_BITRATES
+    } else {
+        MPEG2_BITRATES
+    };
+    let bitrate = bitrates.get(((header >> 12) & 0b1111) as usize)? * 1000;
+    if bitrate == 0 {
+        return None;
+    }
+    let sample_rates: [usize; 3] = match version {
+        0b11 => [44100, 48000, 32000],
+        0b10 => [22050, 24000, 16000],
+        _ => [11025, 12000, 8000],
+    };
+    let sample_rate = sample_rates.get(((header >> 10) & 0b11) as usize)?;
+    let padding = ((header >> 9) & 0b1) as usize;
+    // A frame holds 1152 samples in MPEG-1, and 576 in MPEG-2 and 2.5. Its length is its
+    // duration times the bitrate in bytes per second.
+    let samples = if is_mpeg1 { 1152 } else { 576 };
+    Some(samples / 8 * bitrate / sample_rate + padding)
+}
+
+/// Converts decoded audio to the sample rate of dynamic sounds by linear interpolation.
+///
+/// The position carries over between packets, so that they are joined seamlessly.
+#[derive(Default)]
+struct Resampler {
+    /// The last frame of the previous packet.
+    previous: [f32; 2],
+
+    /// The position of the next output frame, in source frames after `previous`.
+    position: f64,
+}
+
+impl Resampler {
+    fn resample(&mut self, frames: &[[i16; 2]], sample_rate: u32) -> Vec<[f32; 2]> {
+        let to_f32 =
+            |frame: [i16; 2]| [f32::from(frame[0]) / 32768.0, f32::from(frame[1]) / 32768.0];
+        let step = f64::from(sample_rate) / f64::from(OUTPUT_SAMPLE_RATE);
+        let mut output = Vec::with_capacity((frames.len() as f64 / step) as usize + 1);
+        while self.position < frames.len() as f64 {
+            let index = self.position as usize;
+            let from = match index {
+                0 => self.previous,
+                _ => to_f32(frames[index - 1]),
+            };
+            let to = to_f32(frames[index]);
+            let fraction = (self.position - index as f64) as f32;
+            output.push([
+                from[0] + (to[0] - from[0]) * fraction,
+                from[1] + (to[1] - from[1]) * fraction,
+            ]);
+            self.position += step;
+        }
+        if let Some(last) = frames.last() {
+            self.previous = to_f32(*last);
+            self.position -= frames.len() as f64;
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "mp3")]
+    fn mp3_frame_lengths() {
+        // MPEG-1 layer III, 128 kbps, 44.1 kHz, without and with padding.
+        assert_eq!(mp3_frame_length(&[0xFF, 0xFB, 0x90, 0x00]), Some(417));
+        assert_eq!(mp3_frame_length(&[0xFF, 0xFB, 0x92, 0x00]), Some(418));
+        // MPEG-2 layer III, 64 kbps, 22.05 kHz.
+        assert_eq!(mp3_frame_length(&[0xFF, 0xF3, 0x80, 0x00]), Some(208));
+        // Layer II, and a free format bitrate.
+        assert_eq!(mp3_frame_length(&[0xFF, 0xFD, 0x90, 0x00]), None);
+        assert_eq!(mp3_frame_length(&[0xFF, 0xFB, 0x00, 0x00]), None);
+
+        let mut data = vec![0; 417 + 418 + 10];
+        data[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        data[417..421].copy_from_slice(&[0xFF, 0xFB, 0x92, 0x00]);
+        let frames = split_mp3_frames(&data);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].len(), 418);
+    }
+
+    #[test]
+    fn resampling_joins_packets() {
+        let mut resampler = Resampler::default();
+        // 22.05 kHz audio takes two output frames per source frame.
+        let first = resampler.resample(&[[16384, 16384]; 2], 22050);
+        let second = resampler.resample(&[[16384, 16384]; 2], 22050);
+        assert_eq!(first.len(), 4);
+        assert_eq!(second.len(), 4);
+        // The first frame is faded in from silence, the rest is constant.
+        assert_eq!(first[0], [0.0, 0.0]);
+        assert_eq!(first[1], [0.25, 0.25]);
+        assert!(first[2..].iter().chain(&second).all(|f| *f == [0.5, 0.5]));
+
+        // 44.1 kHz audio passes through, one frame behind.
+        let mut resampler = Resampler::default();
+        let output = resampler.resample(&[[0, 0], [32767, 32767], [0, 0]], 44100);
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[2][0], 32767.0 / 32768.0);
+    }
+}
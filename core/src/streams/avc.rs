@@ -0,0 +1,117 @@
+//! AVC (H.264) sample conversion.
+//!
+//! FLV and MP4 files store H.264 frames as length-prefixed NAL units, with the
+//! parameter sets kept separately in the stream header. Video backends expect
+//! self-contained Annex B frames instead.
+
+use byteorder::{BigEndian, ByteOrder};
+
+const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+/// The decoder configuration of an H.264 stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AvcConfig {
+    /// The size in bytes of the length prefix of each NAL unit.
+    nal_length_size: usize,
+
+    /// The SPS and PPS NAL units of the stream.
+    parameter_sets: Vec<Vec<u8>>,
+}
+
+impl AvcConfig {
+    /// Parses an `AVCDecoderConfigurationRecord`, as found in FLV sequence
+    /// headers and MP4 `avcC` boxes.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 6 || data[0] != 1 {
+            return None;
+        }
+
+        let nal_length_size = usize::from(data[4] & 0b11) + 1;
+        let mut parameter_sets = Vec::new();
+        let mut position = 5;
+
+        // The SPS count shares its byte with reserved bits, the PPS count doesn't.
+        for count_mask in [0b1_1111, 0xFF] {
+            let count = data.get(position)? & count_mask;
+            position += 1;
+            for _ in 0..count {
+                let length = usize::from(BigEndian::read_u16(data.get(position..position + 2)?));
+                position += 2;
+                parameter_sets.push(data.get(position..position + length)?.to_vec());
+                position += length;
+            }
+        }
+
+        Some(Self {
+            nal_length_size,
+            parameter_sets,
+        })
+    }
+
+    /// Converts a sample of length-prefixed NAL units to Annex B.
+    ///
+    /// The parameter sets are prepended to keyframes, so that decoding can
+    /// start at any of them.
+    pub fn to_annex_b(&self, sample: &[u8], keyframe: bool) -> Vec<u8> {
+        let mut out = Vec::with_capacity(sample.len() + 64);
+        if keyframe {
+            for parameter_set in &self.parameter_sets {
+                out.extend_from_slice(&START_CODE);
+                out.extend_from_slice(parameter_set);
+            }
+        }
+
+        let mut position = 0;
+        while position + self.nal_length_size <= sample.len() {
+            let length = sample[position..position + self.nal_length_size]
+                .iter()
+                .fold(0usize, |length, byte| (length << 8) | usize::from(*byte));
+            position += self.nal_length_size;
+            let end = position.saturating_add(length).min(sample.len());
+            out.extend_from_slice(&START_CODE);
+            out.extend_from_slice(&sample[position..end]);
+            position = end;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: [u8; 17] = [
+        1, 0x42, 0xC0, 0x1E, 0xFF, // version, profile, compatibility, level, length size
+        0xE1, 0, 3, 0x67, 0x42, 0xC0, // one SPS
+        1, 0, 2, 0x68, 0xCE, // one PPS
+        0xFF, // trailing data is ignored
+    ];
+
+    #[test]
+    fn parse_config() {
+        let config = AvcConfig::parse(&CONFIG).unwrap();
+        assert_eq!(config.nal_length_size, 4);
+        assert_eq!(
+            config.parameter_sets,
+            vec![vec![0x67, 0x42, 0xC0], vec![0x68, 0xCE]]
+        );
+        assert_eq!(AvcConfig::parse(&CONFIG[..10]), None);
+    }
+
+    #[test]
+    fn convert_to_annex_b() {
+        let config = AvcConfig::parse(&CONFIG).unwrap();
+        let sample = [0, 0, 0, 2, 0x65, 0x88, 0, 0, 0, 1, 0x06];
+        assert_eq!(
+            config.to_annex_b(&sample, false),
+            vec![0, 0, 0, 1, 0x65, 0x88, 0, 0, 0, 1, 0x06]
+        );
+        assert_eq!(
+            config.to_annex_b(&sample, true),
+            vec![
+                0, 0, 0, 1, 0x67, 0x42, 0xC0, 0, 0, 0, 1, 0x68, 0xCE, 0, 0, 0, 1, 0x65, 0x88, 0, 0,
+                0, 1, 0x06
+            ]
+        );
+    }
+}
@@ -0,0 +1,533 @@
+//! Incremental FLV (and F4V-in-FLV) demuxer.
+
+use crate::rtmp::amf0::{self, Amf0Value};
+use crate::streams::avc::AvcConfig;
+use crate::streams::{AudioTrack, DemuxError, MediaInfo, Sample, TrackKind, VideoTrack};
+use byteorder::{BigEndian, ByteOrder};
+use swf::{AudioCompression, VideoCodec};
+
+const TAG_HEADER_LENGTH: usize = 11;
+const PREVIOUS_TAG_SIZE_LENGTH: usize = 4;
+
+const TAG_AUDIO: u8 = 8;
+const TAG_VIDEO: u8 = 9;
+const TAG_SCRIPT_DATA: u8 = 18;
+
+/// Splits FLV tags into samples as the file arrives.
+#[derive(Debug, Default)]
+pub struct FlvDemuxer {
+    /// The offset of the next tag to parse, or `None` if the header wasn't
+    /// parsed yet.
+    position: Option<usize>,
+}
+
+impl FlvDemuxer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a demuxer for data that starts at a tag, without the FLV
+    /// header, as appended after seeking in data generation mode.
+    pub fn headerless() -> Self {
+        Self { position: Some(0) }
+    }
+
+    /// Forgets all parsed tags, so the data before the next tag can be
+    /// dropped.
+    ///
+    /// Returns the length of the data that can be dropped. The data passed to
+    /// `parse` must start after it from now on.
+    pub fn discard_parsed(&mut self) -> usize {
+        match self.position {
+            Some(position) => {
+                self.position = Some(0);
+                position
+            }
+            None => 0,
+        }
+    }
+
+    /// Parses every complete tag in `data` that wasn't parsed yet.
+    ///
+    /// `data` must start with the FLV header and keep all previously parsed
+    /// data.
+    pub fn parse(&mut self, data: &[u8], info: &mut MediaInfo) -> Result<(), DemuxError> {
+        let mut position = match self.position {
+            Some(position) => position,
+            None => {
+                if data.len() < 9 {
+                    return Ok(());
+                }
+                if &data[0..3] != b"FLV" {
+                    return Err(DemuxError::Invalid("FLV"));
+                }
+                BigEndian::read_u32(&data[5..9]) as usize + PREVIOUS_TAG_SIZE_LENGTH
+            }
+        };
+
+        while let Some(header) = data.get(position..position + TAG_HEADER_LENGTH) {
+            let length = BigEndian::read_u24(&header[1..4]) as usize;
+            let start = position + TAG_HEADER_LENGTH;
+            let end = start + length;
+            if end + PREVIOUS_TAG_SIZE_LENGTH > data.len() {
+                break;
+            }
+
+            let timestamp =
+                f64::from(BigEndian::read_u24(&header[4..7]) | (u32::from(header[7]) << 24));
+            let payload = &data[start..end];
+            if header[0] & 0b10_0000 != 0 {
+                tracing::warn!("Skipping encrypted FLV tag");
+            } else {
+                match header[0] & 0b1_1111 {
+                    TAG_AUDIO => parse_audio_tag(payload, start, timestamp, info),
+                    TAG_VIDEO => parse_video_tag(payload, start, timestamp, info),
+                    TAG_SCRIPT_DATA => parse_script_tag(payload, start, timestamp, info),
+                    other => tracing::warn!("Skipping unknown FLV tag type {}", other),
+                }
+            }
+
+            position = end + PREVIOUS_TAG_SIZE_LENGTH;
+        }
+
+        self.position = Some(position);
+        Ok(())
+    }
+}
+
+/// Reads the handler name and arguments of a script data message.
+///
+/// Values that can't be read end the arguments early, as some encoders write
+/// malformed data after the values players look at.
+pub fn read_script_message(mut payload: &[u8]) -> Option<(String, Vec<Amf0Value>)> {
+    let name = match amf0::read(&mut payload)? {
+        Amf0Value::String(name) if !name.is_empty() => name,
+        _ => return None,
+    };
+    let arguments = std::iter::from_fn(|| amf0::read(&mut payload)).collect();
+    Some((name, arguments))
+}
+
+/// Records a script data message, such as `onMetaData` or `onCuePoint`, as a
+/// sample to send to the client object when playback reaches it.
+///
+/// The duration and keyframe index of `onMetaData` are read right away. The
+/// keyframe index isn't part of the FLV format, but is added by most tools
+/// preparing files for HTTP pseudo-streaming.
+fn parse_script_tag(payload: &[u8], offset: usize, timestamp: f64, info: &mut MediaInfo) {
+    let (name, arguments) = match read_script_message(payload) {
+        Some(message) => message,
+        None => return,
+    };
+    info.samples.push(Sample {
+        kind: TrackKind::Script,
+        time: timestamp,
+        composition_offset: 0.0,
+        keyframe: false,
+        offset,
+        length: payload.len(),
+    });
+
+    if name != "onMetaData" {
+        return;
+    }
+    let metadata = match arguments.first() {
+        Some(metadata) => metadata,
+        None => return,
+    };
+
+    if info.duration.is_none() {
+        info.duration = metadata
+            .get("duration")
+            .and_then(Amf0Value::as_number)
+            .map(|duration| duration * 1000.0);
+    }
+
+    let keyframes = metadata.get("keyframes");
+    let times = keyframes.and_then(|keyframes| keyframes.get("times"));
+    let positions = keyframes.and_then(|keyframes| keyframes.get("filepositions"));
+    if let (Some(Amf0Value::StrictArray(times)), Some(Amf0Value::StrictArray(positions))) =
+        (times, positions)
+    {
+        info.keyframes = times
+            .iter()
+            .zip(positions)
+            .filter_map(|(time, position)| {
+                Some((time.as_number()? * 1000.0, position.as_number()? as usize))
+            })
+            .collect();
+    }
+}
+
+fn parse_video_tag(payload: &[u8], offset: usize, timestamp: f64, info: &mut MediaInfo) {
+    let flags = match payload.first() {
+        Some(flags) => *flags,
+        None => return,
+    };
+    let frame_type = flags >> 4;
+    if frame_type == 5 {
+        // Video info/command frames carry no picture.
+        return;
+    }
+    let codec = match VideoCodec::from_u8(flags & 0b1111) {
+        Some(codec) => codec,
+        None => {
+            tracing::warn!(
+                "Skipping FLV video tag with unknown codec {}",
+                flags & 0b1111
+            );
+            return;
+        }
+    };
+    let keyframe = frame_type == 1 || frame_type == 4;
+
+    let (skip, composition_offset) = match codec {
+        // VP6 frames are preceded by a byte giving the cropping of the encoded size.
+        VideoCodec::Vp6 | VideoCodec::Vp6WithAlpha => (2, 0.0),
+        VideoCodec::H264 => {
+            if payload.len() < 5 {
+                return;
+            }
+            let composition_time = (BigEndian::read_i32(&payload[1..5]) << 8) >> 8;
+            match payload[1] {
+                // Sequence header
+                0 => {
+                    match AvcConfig::parse(&payload[5..]) {
+                        Some(config) => {
+                            video_track(info, codec).avc_config = Some(config);
+                        }
+                        None => tracing::warn!("Invalid AVC sequence header in FLV"),
+                    }
+                    return;
+                }
+                1 => (5, f64::from(composition_time)),
+                // End of sequence
+                _ => return,
+            }
+        }
+        _ => (1, 0.0),
+    };
+    if payload.len() <= skip {
+        return;
+    }
+
+    let track = video_track(info, codec);
+    if track.size.is_none() && keyframe {
+        match codec {
+            VideoCodec::Vp6 => track.size = vp6_frame_size(&payload[1..], false),
+            VideoCodec::Vp6WithAlpha => track.size = vp6_frame_size(&payload[1..], true),
+            _ => {}
+        }
+    }
+
+    info.samples.push(Sample {
+        kind: TrackKind::Video,
+        time: timestamp,
+        composition_offset,
+        keyframe,
+        offset: offset + skip,
+        length: payload.len() - skip,
+    });
+}
+
+fn parse_audio_tag(payload: &[u8], offset: usize, timestamp: f64, info: &mut MediaInfo) {
+    let flags = match payload.first() {
+        Some(flags) => *flags,
+        None => return,
+    };
+    let compression = match AudioCompression::from_u8(flags >> 4) {
+        Some(compression) => compression,
+        None => {
+            tracing::warn!("Skipping FLV audio tag with unknown format {}", flags >> 4);
+            return;
+        }
+    };
+
+    let skip = if compression == AudioCompression::Aac {
+        match payload.get(1) {
+            // AudioSpecificConfig
+            Some(0) => {
+                audio_track(info, compression, flags).aac_config = Some(payload[2..].to_vec());
+                return;
+            }
+            Some(1) => 2,
+            _ => return,
+        }
+    } else {
+        1
+    };
+    if payload.len() <= skip {
+        return;
+    }
+
+    audio_track(info, compression, flags);
+    info.samples.push(Sample {
+        kind: TrackKind::Audio,
+        time: timestamp,
+        composition_offset: 0.0,
+        keyframe: true,
+        offset: offset + skip,
+        length: payload.len() - skip,
+    });
+}
+
+fn video_track(info: &mut MediaInfo, codec: VideoCodec) -> &mut VideoTrack {
+    info.video.get_or_insert_with(|| VideoTrack {
+        codec,
+        size: None,
+        avc_config: None,
+    })
+}
+
+fn audio_track(info: &mut MediaInfo, compression: AudioCompression, flags: u8) -> &mut AudioTrack {
+    info.audio.get_or_insert_with(|| {
+        let sample_rate = match compression {
+            AudioCompression::Nellymoser16Khz => 16000,
+            AudioCompression::Nellymoser8Khz => 8000,
+            // AAC streams always declare 44kHz, the real rate is in the AudioSpecificConfig.
+            AudioCompression::Aac => 44100,
+            _ => [5512, 11025, 22050, 44100][usize::from((flags >> 2) & 0b11)],
+        };
+        AudioTrack {
+            compression,
+            sample_rate,
+            is_stereo: flags & 0b1 != 0,
+            is_16_bit: flags & 0b10 != 0,
+            aac_config: None,
+        }
+    })
+}
+
+/// Reads the displayed size of a VP6 keyframe.
+///
+/// `data` starts with the FLV cropping byte, followed by the alpha offset of
+/// VP6 with alpha frames, and then the VP6 frame itself.
+fn vp6_frame_size(data: &[u8], with_alpha: bool) -> Option<(u16, u16)> {
+    let cropping = *data.first()?;
+    let frame = data.get(if with_alpha { 4 } else { 1 }..)?;
+
+    let is_keyframe = frame.first()? & 0b1000_0000 == 0;
+    if !is_keyframe {
+        return None;
+    }
+    let separated_coefficients = frame[0] & 0b1 != 0;
+    let filter_header = frame.get(1)? & 0b110;
+    let dimensions = if separated_coefficients || filter_header == 0 {
+        frame.get(4..6)?
+    } else {
+        frame.get(2..4)?
+    };
+
+    // The encoded size is in whole macroblocks.
+    let height = (u16::from(dimensions[0]) * 16).saturating_sub(u16::from(cropping & 0b1111));
+    let width = (u16::from(dimensions[1]) * 16).saturating_sub(u16::from(cropping >> 4));
+    Some((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(tag_type: u8, timestamp: u32, payload: &[u8]) -> Vec<u8> {
+        let mut tag = vec![tag_type];
+        tag.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+        tag.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+        tag.push((timestamp >> 24) as u8);
+        tag.extend_from_slice(&[0, 0, 0]);
+        tag.extend_from_slice(payload);
+        tag.extend_from_slice(&(payload.len() as u32 + 11).to_be_bytes());
+        tag
+    }
+
+    fn flv(tags: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![b'F', b'L', b'V', 1, 0b101, 0, 0, 0, 9, 0, 0, 0, 0];
+        for tag in tags {
+            data.extend_from_slice(tag);
+        }
+        data
+    }
+
+    #[test]
+    fn parse_tags() {
+        let data = flv(&[
+            tag(TAG_SCRIPT_DATA, 0, &[2, 0, 0]),
+            tag(TAG_VIDEO, 0, &[0x12, 0xAA, 0xBB]),
+            tag(TAG_AUDIO, 0, &[0x2E, 0xCC]),
+            tag(TAG_VIDEO, 42, &[0x22, 0xDD]),
+        ]);
+
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data, &mut info).unwrap();
+
+        let video = info.video.as_ref().unwrap();
+        assert_eq!(video.codec, VideoCodec::H263);
+        let audio = info.audio.as_ref().unwrap();
+        assert_eq!(audio.compression, AudioCompression::Mp3);
+        assert_eq!(audio.sample_rate, 44100);
+        assert!(audio.is_16_bit);
+        assert!(!audio.is_stereo);
+
+        assert_eq!(info.samples.len(), 3);
+        let first = &info.samples[0];
+        assert!(first.keyframe);
+        assert_eq!(
+            &data[first.offset..first.offset + first.length],
+            &[0xAA, 0xBB]
+        );
+        assert_eq!(info.samples[1].kind, TrackKind::Audio);
+        assert_eq!(info.samples[2].time, 42.0);
+        assert!(!info.samples[2].keyframe);
+    }
+
+    #[test]
+    fn parse_incrementally() {
+        let data = flv(&[
+            tag(TAG_VIDEO, 0, &[0x12, 0xAA]),
+            tag(TAG_VIDEO, 40, &[0x22, 0xBB]),
+        ]);
+
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data[..5], &mut info).unwrap();
+        assert!(info.samples.is_empty());
+        demuxer.parse(&data[..data.len() - 3], &mut info).unwrap();
+        assert_eq!(info.samples.len(), 1);
+        demuxer.parse(&data, &mut info).unwrap();
+        assert_eq!(info.samples.len(), 2);
+    }
+
+    #[test]
+    fn parse_headerless() {
+        let header_length = flv(&[]).len();
+        let data = flv(&[
+            tag(TAG_VIDEO, 0, &[0x12, 0xAA]),
+            tag(TAG_VIDEO, 40, &[0x22, 0xBB]),
+        ]);
+
+        let mut demuxer = FlvDemuxer::headerless();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data[header_length..], &mut info).unwrap();
+        assert_eq!(info.samples.len(), 2);
+        assert_eq!(info.samples[1].time, 40.0);
+    }
+
+    #[test]
+    fn discard_parsed_tags() {
+        let first = tag(TAG_VIDEO, 0, &[0x12, 0xAA]);
+        let second = tag(TAG_VIDEO, 40, &[0x22, 0xBB]);
+        let mut data = flv(&[first]);
+        let split = data.len();
+        data.extend_from_slice(&second[..5]);
+
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data, &mut info).unwrap();
+        assert_eq!(info.samples.len(), 1);
+
+        assert_eq!(demuxer.discard_parsed(), split);
+        data.drain(..split);
+        data.extend_from_slice(&second[5..]);
+        info.samples.clear();
+        demuxer.parse(&data, &mut info).unwrap();
+        assert_eq!(info.samples.len(), 1);
+        let sample = &info.samples[0];
+        assert_eq!(&data[sample.offset..sample.offset + sample.length], &[0xBB]);
+    }
+
+    #[test]
+    fn parse_avc() {
+        let mut sequence_header = vec![0x17, 0, 0, 0, 0];
+        sequence_header
+            .extend_from_slice(&[1, 0x42, 0xC0, 0x1E, 0xFF, 0xE1, 0, 1, 0x67, 1, 0, 1, 0x68]);
+        let data = flv(&[
+            tag(TAG_VIDEO, 0, &sequence_header),
+            tag(TAG_VIDEO, 0, &[0x17, 1, 0, 0, 40, 0, 0, 0, 1, 0x65]),
+        ]);
+
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data, &mut info).unwrap();
+
+        let video = info.video.as_ref().unwrap();
+        assert_eq!(video.codec, VideoCodec::H264);
+        assert!(video.avc_config.is_some());
+        assert_eq!(info.samples.len(), 1);
+        assert_eq!(info.samples[0].composition_offset, 40.0);
+        assert_eq!(info.samples[0].length, 5);
+    }
+
+    #[test]
+    fn parse_keyframe_index() {
+        let metadata = Amf0Value::EcmaArray(vec![
+            ("duration".to_string(), 2.5.into()),
+            (
+                "keyframes".to_string(),
+                Amf0Value::Object(vec![
+                    (
+                        "times".to_string(),
+                        Amf0Value::StrictArray(vec![0.0.into(), 1.5.into()]),
+                    ),
+                    (
+                        "filepositions".to_string(),
+                        Amf0Value::StrictArray(vec![400.0.into(), 9000.0.into()]),
+                    ),
+                ]),
+            ),
+        ]);
+        let mut payload = Vec::new();
+        amf0::write(&mut payload, &"onMetaData".into());
+        amf0::write(&mut payload, &metadata);
+        let data = flv(&[tag(TAG_SCRIPT_DATA, 0, &payload)]);
+
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data, &mut info).unwrap();
+
+        assert_eq!(info.duration, Some(2500.0));
+        assert_eq!(info.keyframes, vec![(0.0, 400), (1500.0, 9000)]);
+    }
+
+    #[test]
+    fn parse_cue_points() {
+        let cue_point = Amf0Value::Object(vec![
+            ("name".to_string(), "chapter".into()),
+            ("time".to_string(), 1.2.into()),
+        ]);
+        let mut payload = Vec::new();
+        amf0::write(&mut payload, &"onCuePoint".into());
+        amf0::write(&mut payload, &cue_point);
+        // Trailing garbage written by some encoders.
+        payload.push(0xFF);
+        let data = flv(&[
+            tag(TAG_VIDEO, 0, &[0x12, 0xAA]),
+            tag(TAG_SCRIPT_DATA, 1200, &payload),
+        ]);
+
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data, &mut info).unwrap();
+
+        assert_eq!(info.samples.len(), 2);
+        let sample = &info.samples[1];
+        assert_eq!(sample.kind, TrackKind::Script);
+        assert_eq!(sample.time, 1200.0);
+        assert!(!sample.keyframe);
+        let message = read_script_message(&data[sample.offset..sample.offset + sample.length]);
+        assert_eq!(message, Some(("onCuePoint".to_string(), vec![cue_point])));
+    }
+
+    #[test]
+    fn read_vp6_size() {
+        // Cropping of 8x4 pixels, keyframe with 15x20 macroblocks.
+        let data = [0x84, 0x00, 0x06, 0x0F, 0x14, 0x0F, 0x14];
+        assert_eq!(vp6_frame_size(&data, false), Some((312, 236)));
+    }
+
+    #[test]
+    fn reject_other_formats() {
+        let mut demuxer = FlvDemuxer::new();
+        let mut info = MediaInfo::default();
+        assert!(demuxer.parse(b"\0\0\0\x18ftypmp42", &mut info).is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! MP4/F4V (ISO base media file) demuxer.
+//!
+//! Only non-fragmented files are supported. Their sample tables are stored in
+//! the `moov` box, which may come before or after the media data, so samples
+//! only become available once the whole `moov` box was received.
+//!
+//! The samples are only split out of the file here. Their AAC and MP3 audio is decoded by
+//! `streams::audio`, while their H.264 video is left to video backends, none of which
+//! supports that codec yet.
+
+use crate::streams::avc::AvcConfig;
+use crate::streams::{AudioTrack, DemuxError, MediaInfo, Sample, TrackKind, VideoTrack};
+use byteorder::{BigEndian, ByteOrder};
+use std::iter;
+use swf::{AudioCompression, VideoCodec};
+
+const INVALID: DemuxError = DemuxError::Invalid("MP4");
+
+/// Checks the first box of a file, to tell MP4 files from other containers.
+pub fn is_mp4(data: &[u8]) -> bool {
+    matches!(data.get(4..8), Some(b"ftyp" | b"styp" | b"moov"))
+}
+
+#[derive(Debug, Default)]
+pub struct Mp4Demuxer {
+    /// Whether the sample tables were read.
+    parsed: bool,
+}
+
+impl Mp4Demuxer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks for the `moov` box in `data`, and reads all samples from it once
+    /// it's complete.
+    pub fn parse(&mut self, data: &[u8], info: &mut MediaInfo) -> Result<(), DemuxError> {
+        if self.parsed {
+            return Ok(());
+        }
+
+        let mut position = 0;
+        while let Some((box_type, header_length, length)) = read_box_header(&data[position..])? {
+            let end = match length {
+                Some(length) => position.saturating_add(length),
+                None => usize::MAX,
+            };
+            match &box_type {
+                b"moov" => {
+                    if end > data.len() {
+                        return Ok(());
+                    }
+                    parse_moov(&data[position + header_length..end], info)?;
+                    self.parsed = true;
+
+                    let has_video = info.video.is_some();
+                    info.keyframes = info
+                        .samples
+                        .iter()
+                        .filter(|sample| {
+                            sample.keyframe && (!has_video || sample.kind == TrackKind::Video)
+                        })
+                        .map(|sample| (sample.time, sample.offset))
+                        .collect();
+                    return Ok(());
+                }
+                b"moof" => return Err(DemuxError::Unsupported("Fragmented MP4")),
+                _ => {}
+            }
+            if end >= data.len() {
+                break;
+            }
+            position = end;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the type, header length and total length of a box.
+///
+/// Returns `None` if the header is incomplete, and a `None` length for a box
+/// extending to the end of the file.
+fn read_box_header(data: &[u8]) -> Result<Option<([u8; 4], usize, Option<usize>)>, DemuxError> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let box_type = [data[4], data[5], data[6], data[7]];
+    let (header_length, length) = match BigEndian::read_u32(&data[0..4]) {
+        0 => (8, None),
+        1 => {
+            if data.len() < 16 {
+                return Ok(None);
+            }
+            let length = usize::try_from(BigEndian::read_u64(&data[8..16])).map_err(|_| INVALID)?;
+            (16, Some(length))
+        }
+        length => (8, Some(length as usize)),
+    };
+    if length.map_or(false, |length| length < header_length) {
+        return Err(INVALID);
+    }
+    Ok(Some((box_type, header_length, length)))
+}
+
+/// Iterates over the child boxes of a completely received box.
+struct Boxes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = ([u8; 4], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (box_type, header_length, length) = read_box_header(self.data).ok()??;
+        let length = length.unwrap_or(self.data.len()).min(self.data.len());
+        let body = &self.data[header_length..length];
+        self.data = &self.data[length..];
+        Some((box_type, body))
+    }
+}
+
+fn boxes(data: &[u8]) -> Boxes<'_> {
+    Boxes { data }
+}
+
+fn find_box<'a>(data: &'a [u8], box_type: &[u8; 4]) -> Option<&'a [u8]> {
+    boxes(data)
+        .find(|(t, _)| t == box_type)
+        .map(|(_, body)| body)
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16, DemuxError> {
+    data.get(at..at + 2).map(BigEndian::read_u16).ok_or(INVALID)
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32, DemuxError> {
+    data.get(at..at + 4).map(BigEndian::read_u32).ok_or(INVALID)
+}
+
+fn read_u64(data: &[u8], at: usize) -> Result<u64, DemuxError> {
+    data.get(at..at + 8).map(BigEndian::read_u64).ok_or(INVALID)
+}
+
+/// Reads a table of a "full box", with a version and flags followed by an
+/// entry count.
+fn read_table<T>(
+    data: &[u8],
+    entry_length: usize,
+    read: impl Fn(&[u8]) -> Result<T, DemuxError>,
+) -> Result<Vec<T>, DemuxError> {
+    let count = read_u32(data, 4)? as usize;
+    let entries = data.get(8..).ok_or(INVALID)?;
+    if entries.len() / entry_length < count {
+        return Err(INVALID);
+    }
+    entries
+        .chunks_exact(entry_length)
+        .take(count)
+        .map(read)
+        .collect()
+}
+
+fn parse_moov(moov: &[u8], info: &mut MediaInfo) -> Result<(), DemuxError> {
+    let mut samples = Vec::new();
+    for (box_type, body) in boxes(moov) {
+        match &box_type {
+            b"mvhd" => {
+                let (timescale, duration) = if body.first() == Some(&1) {
+                    (read_u32(body, 20)?, read_u64(body, 24)?)
+                } else {
+                    (read_u32(body, 12)?, u64::from(read_u32(body, 16)?))
+                };
+                if timescale != 0 {
+                    info.duration = Some(duration as f64 * 1000.0 / f64::from(timescale));
+                }
+            }
+            b"trak" => parse_trak(body, info, &mut samples)?,
+            _ => {}
+        }
+    }
+
+    // Interleave the tracks by decoding time; the sort is stable, so the
+    // samples of each track stay in order.
+    samples.sort_by(|a: &Sample, b: &Sample| a.time.total_cmp(&b.time));
+    info.samples = samples;
+    Ok(())
+}
+
+fn parse_trak(
+    trak: &[u8],
+    info: &mut MediaInfo,
+    samples: &mut Vec<Sample>,
+) -> Result<(), DemuxError> {
+    let mdia = find_box(trak, b"mdia").ok_or(INVALID)?;
+    let mdhd = find_box(mdia, b"mdhd").ok_or(INVALID)?;
+    let timescale = if mdhd.first() == Some(&1) {
+        read_u32(mdhd, 20)?
+    } else {
+        read_u32(mdhd, 12)?
+    };
+    if timescale == 0 {
+        return Err(INVALID);
+    }
+    let handler = find_box(mdia, b"hdlr")
+        .and_then(|hdlr| hdlr.get(8..12))
+        .ok_or(INVALID)?;
+    let stbl = find_box(mdia, b"minf")
+        .and_then(|minf| find_box(minf, b"stbl"))
+        .ok_or(INVALID)?;
+    let stsd = find_box(stbl, b"stsd").ok_or(INVALID)?;
+    let (entry_type, entry) = boxes(stsd.get(8..).ok_or(INVALID)?).next().ok_or(INVALID)?;
+
+    let kind = match handler {
+        b"vide" => {
+            if info.video.is_some() {
+                tracing::warn!("Ignoring additional MP4 video track");
+                return Ok(());
+            }
+            if !matches!(&entry_type, b"avc1" | b"avc3") {
+                tracing::warn!(
+                    "Unsupported MP4 video codec {}",
+                    String::from_utf8_lossy(&entry_type)
+                );
+                return Ok(());
+            }
+            let avc_config = entry
+                .get(78..)
+                .and_then(|children| find_box(children, b"avcC"))
+                .and_then(AvcConfig::parse);
+            if avc_config.is_none() {
+                tracing::warn!("MP4 video track has no valid AVC configuration");
+            }
+            info.video = Some(VideoTrack {
+                codec: VideoCodec::H264,
+                size: Some((read_u16(entry, 24)?, read_u16(entry, 26)?)),
+                avc_config,
+            });
+            TrackKind::Video
+        }
+        b"soun" => {
+            if info.audio.is_some() {
+                tracing::warn!("Ignoring additional MP4 audio track");
+                return Ok(());
+            }
+            let (compression, aac_config) = match &entry_type {
+                b"mp4a" => {
+                    // QuickTime sound descriptions are longer in later versions.
+                    let children_offset = match read_u16(entry, 8)? {
+                        1 => 44,
+                        2 => 64,
+                        _ => 28,
+                    };
+                    let decoder_config = entry
+                        .get(children_offset..)
+                        .and_then(|children| find_box(children, b"esds"))
+                        .and_then(|esds| esds.get(4..))
+                        .and_then(read_decoder_config);
+                    match decoder_config {
+                        // MPEG-1 and MPEG-2 audio
+                        Some((0x69 | 0x6B, _)) => (AudioCompression::Mp3, None),
+                        Some((_, specific_info)) => (AudioCompression::Aac, specific_info),
+                        None => (AudioCompression::Aac, None),
+                    }
+                }
+                b".mp3" => (AudioCompression::Mp3, None),
+                other => {
+                    tracing::warn!(
+                        "Unsupported MP4 audio codec {}",
+                        String::from_utf8_lossy(other)
+                    );
+                    return Ok(());
+                }
+            };
+            info.audio = Some(AudioTrack {
+                compression,
+                sample_rate: u32::from(read_u16(entry, 24)?),
+                is_stereo: read_u16(entry, 16)? > 1,
+                is_16_bit: read_u16(entry, 18)? == 16,
+                aac_config,
+            });
+            TrackKind::Audio
+        }
+        _ => return Ok(()),
+    };
+
+    read_sample_table(stbl, kind, timescale, samples)
+}
+
+/// Reads the object type and decoder specific info of an `ES_Descriptor`.
+fn read_decoder_config(data: &[u8]) -> Option<(u8, Option<Vec<u8>>)> {
+    /// Reads a descriptor tag and its variable-length size.
+    fn descriptor(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+        let tag = *data.first()?;
+        let mut length = 0usize;
+        let mut position = 1;
+        loop {
+            let byte = *data.get(position)?;
+            position += 1;
+            length = (length << 7) | usize::from(byte & 0x7F);
+            if byte & 0x80 == 0 || position == 5 {
+                break;
+            }
+        }
+        let body = data.get(position..position.checked_add(length)?)?;
+        Some((tag, body, &data[position + length..]))
+    }
+
+    let (tag, es_descriptor, _) = descriptor(data)?;
+    if tag != 0x03 {
+        return None;
+    }
+    let flags = *es_descriptor.get(2)?;
+    let mut position = 3;
+    if flags & 0x80 != 0 {
+        // Depends on another stream.
+        position += 2;
+    }
+    if flags & 0x40 != 0 {
+        // URL
+        position += 1 + usize::from(*es_descriptor.get(position)?);
+    }
+    if flags & 0x20 != 0 {
+        // OCR stream
+        position += 2;
+    }
+
+    let mut remaining = es_descriptor.get(position..)?;
+    while !remaining.is_empty() {
+        let (tag, body, rest) = descriptor(remaining)?;
+        if tag == 0x04 {
+            let object_type = *body.first()?;
+            let specific_info = body
+                .get(13..)
+                .and_then(descriptor)
+                .filter(|(tag, _, _)| *tag == 0x05)
+                .map(|(_, specific_info, _)| specific_info.to_vec());
+            return Some((object_type, specific_info));
+        }
+        remaining = rest;
+    }
+    None
+}
+
+fn read_sample_table(
+    stbl: &[u8],
+    kind: TrackKind,
+    timescale: u32,
+    samples: &mut Vec<Sample>,
+) -> Result<(), DemuxError> {
+    let stsz =
+        find_box(stbl, b"stsz").ok_or(DemuxError::Unsupported("MP4 compact sample sizes"))?;
+    let constant_size = read_u32(stsz, 4)?;
+    let count = read_u32(stsz, 8)? as usize;
+    let size_table: Vec<u32> = if constant_size == 0 {
+        let entries = stsz.get(12..).ok_or(INVALID)?;
+        if entries.len() / 4 < count {
+            return Err(INVALID);
+        }
+        entries
+            .chunks_exact(4)
+            .take(count)
+            .map(BigEndian::read_u32)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let sample_size = |index: usize| {
+        if constant_size != 0 {
+            (index < count).then_some(constant_size as usize)
+        } else {
+            size_table.get(index).map(|size| *size as usize)
+        }
+    };
+
+    let chunk_offsets = if let Some(stco) = find_box(stbl, b"stco") {
+        read_table(stco, 4, |entry| Ok(u64::from(BigEndian::read_u32(entry))))?
+    } else {
+        let co64 = find_box(stbl, b"co64").ok_or(INVALID)?;
+        read_table(co64, 8, |entry| Ok(BigEndian::read_u64(entry)))?
+    };
+    let samples_per_chunk = read_table(find_box(stbl, b"stsc").ok_or(INVALID)?, 12, |entry| {
+        Ok((BigEndian::read_u32(entry), BigEndian::read_u32(&entry[4..])))
+    })?;
+    let time_deltas = read_table(find_box(stbl, b"stts").ok_or(INVALID)?, 8, |entry| {
+        Ok((BigEndian::read_u32(entry), BigEndian::read_u32(&entry[4..])))
+    })?;
+    // Version 0 offsets are unsigned, but negative ones are written anyway.
+    let composition_offsets = match find_box(stbl, b"ctts") {
+        Some(ctts) => read_table(ctts, 8, |entry| {
+            Ok((BigEndian::read_u32(entry), BigEndian::read_i32(&entry[4..])))
+        })?,
+        None => Vec::new(),
+    };
+    // Without a sync sample table, every sample is a keyframe.
+    let keyframes = match find_box(stbl, b"stss") {
+        Some(stss) => Some(read_table(stss, 4, |entry| Ok(BigEndian::read_u32(entry)))?),
+        None => None,
+    };
+
+    let mut offsets = Vec::new();
+    'chunks: for (index, chunk_offset) in chunk_offsets.into_iter().enumerate() {
+        let chunk = index as u32 + 1;
+        let count = samples_per_chunk
+            .iter()
+            .rev()
+            .find(|(first_chunk, _)| *first_chunk <= chunk)
+            .map_or(0, |(_, count)| *count);
+        let mut offset = usize::try_from(chunk_offset).map_err(|_| INVALID)?;
+        for _ in 0..count {
+            let size = match sample_size(offsets.len()) {
+                Some(size) => size,
+                None => break 'chunks,
+            };
+            offsets.push(offset);
+            offset = offset.checked_add(size).ok_or(INVALID)?;
+        }
+    }
+
+    let mut time_deltas = time_deltas
+        .into_iter()
+        .flat_map(|(count, delta)| iter::repeat(delta).take(count as usize));
+    let mut composition_offsets = composition_offsets
+        .into_iter()
+        .flat_map(|(count, offset)| iter::repeat(offset).take(count as usize));
+    let to_milliseconds = |time: f64| time * 1000.0 / f64::from(timescale);
+
+    let mut time = 0u64;
+    for (index, offset) in offsets.into_iter().enumerate() {
+        let keyframe = keyframes.as_ref().map_or(true, |keyframes| {
+            keyframes.binary_search(&(index as u32 + 1)).is_ok()
+        });
+        samples.push(Sample {
+            kind,
+            time: to_milliseconds(time as f64),
+            composition_offset: to_milliseconds(f64::from(composition_offsets.next().unwrap_or(0))),
+            keyframe,
+            offset,
+            length: sample_size(index).unwrap_or_default(),
+        });
+        time += u64::from(time_deltas.next().unwrap_or(0));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(box_type: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = (body.len() as u32 + 8).to_be_bytes().to_vec();
+        data.extend_from_slice(box_type);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn table_box(box_type: &[u8; 4], entries: &[&[u32]]) -> Vec<u8> {
+        let mut body = vec![0, 0, 0, 0];
+        body.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for value in entries.iter().copied().flatten() {
+            body.extend_from_slice(&value.to_be_bytes());
+        }
+        mp4_box(box_type, &body)
+    }
+
+    /// A video track of three 100 byte samples in two chunks, the first one
+    /// being the only keyframe.
+    fn video_trak(first_chunk_offset: u32) -> Vec<u8> {
+        let mut mdhd = vec![0; 12];
+        mdhd.extend_from_slice(&1000u32.to_be_bytes());
+        mdhd.extend_from_slice(&120u32.to_be_bytes());
+
+        let mut hdlr = vec![0; 8];
+        hdlr.extend_from_slice(b"vide");
+        hdlr.extend_from_slice(&[0; 12]);
+
+        let mut avc1 = vec![0; 24];
+        avc1.extend_from_slice(&320u16.to_be_bytes());
+        avc1.extend_from_slice(&240u16.to_be_bytes());
+        avc1.extend_from_slice(&[0; 50]);
+        avc1.extend(mp4_box(
+            b"avcC",
+            &[1, 0x42, 0xC0, 0x1E, 0xFF, 0xE1, 0, 1, 0x67, 1, 0, 1, 0x68],
+        ));
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend(mp4_box(b"avc1", &avc1));
+
+        let mut stsz = vec![0; 8];
+        stsz.extend_from_slice(&3u32.to_be_bytes());
+        for _ in 0..3 {
+            stsz.extend_from_slice(&100u32.to_be_bytes());
+        }
+
+        let stbl = [
+            mp4_box(b"stsd", &stsd),
+            table_box(b"stts", &[&[3, 40]]),
+            table_box(b"stss", &[&[1]]),
+            table_box(b"stsc", &[&[1, 2, 1]]),
+            mp4_box(b"stsz", &stsz),
+            table_box(
+                b"stco",
+                &[&[first_chunk_offset], &[first_chunk_offset + 1000]],
+            ),
+        ]
+        .concat();
+
+        mp4_box(
+            b"trak",
+            &mp4_box(
+                b"mdia",
+                &[
+                    mp4_box(b"mdhd", &mdhd),
+                    mp4_box(b"hdlr", &hdlr),
+                    mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+                ]
+                .concat(),
+            ),
+        )
+    }
+
+    #[test]
+    fn parse_video_track() {
+        let data = [
+            mp4_box(b"ftyp", b"isom\0\0\0\0"),
+            mp4_box(b"moov", &video_trak(5000)),
+        ]
+        .concat();
+        assert!(is_mp4(&data));
+
+        let mut demuxer = Mp4Demuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data[..data.len() - 1], &mut info).unwrap();
+        assert!(info.samples.is_empty());
+        demuxer.parse(&data, &mut info).unwrap();
+
+        let video = info.video.as_ref().unwrap();
+        assert_eq!(video.codec, VideoCodec::H264);
+        assert_eq!(video.size, Some((320, 240)));
+        assert!(video.avc_config.is_some());
+
+        let samples: Vec<_> = info
+            .samples
+            .iter()
+            .map(|sample| (sample.time, sample.keyframe, sample.offset))
+            .collect();
+        assert_eq!(
+            samples,
+            vec![(0.0, true, 5000), (40.0, false, 5100), (80.0, false, 6000)]
+        );
+    }
+
+    #[test]
+    fn parse_moov_after_mdat() {
+        let mdat = mp4_box(b"mdat", &[0; 32]);
+        let data = [
+            mp4_box(b"ftyp", b"isom\0\0\0\0"),
+            mdat,
+            mp4_box(b"moov", &video_trak(24)),
+        ]
+        .concat();
+
+        let mut demuxer = Mp4Demuxer::new();
+        let mut info = MediaInfo::default();
+        demuxer.parse(&data[..40], &mut info).unwrap();
+        assert!(info.video.is_none());
+        demuxer.parse(&data, &mut info).unwrap();
+        assert_eq!(info.samples.len(), 3);
+    }
+
+    #[test]
+    fn read_aac_decoder_config() {
+        let esds = [
+            0x03, 0x19, 0x00, 0x01, 0x00, // ES_Descriptor
+            0x04, 0x11, 0x40, 0x15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, // DecoderConfigDescriptor
+            0x05, 0x02, 0x12, 0x10, // DecoderSpecificInfo
+            0x06, 0x01, 0x02, // SLConfigDescriptor
+        ];
+        assert_eq!(
+            read_decoder_config(&esds),
+            Some((0x40, Some(vec![0x12, 0x10])))
+        );
+    }
+}
@@ -177,6 +177,26 @@ impl SwfMovie {
         self.header.is_action_script_3()
     }
 
+    /// Whether this SWF requests hardware acceleration to blit to the screen.
+    pub fn use_direct_blit(&self) -> bool {
+        self.header.use_direct_blit()
+    }
+
+    /// Whether this SWF requests hardware acceleration for compositing.
+    pub fn use_gpu(&self) -> bool {
+        self.header.use_gpu()
+    }
+
+    /// Whether this SWF contains XMP metadata in a Metadata tag.
+    pub fn has_metadata(&self) -> bool {
+        self.header.has_metadata()
+    }
+
+    /// Whether this SWF should be placed in the network sandbox when run locally.
+    pub fn use_network_sandbox(&self) -> bool {
+        self.header.use_network_sandbox()
+    }
+
     pub fn stage_size(&self) -> &Rectangle<Twips> {
         self.header.stage_size()
     }
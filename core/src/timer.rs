@@ -182,6 +182,14 @@ impl<'gc> Timers<'gc> {
         self.timers.len()
     }
 
+    /// The callbacks of the timers that weren't removed.
+    pub fn callbacks(&self) -> impl Iterator<Item = &TimerCallback<'gc>> {
+        self.timers
+            .iter()
+            .filter(|timer| timer.is_alive.get())
+            .map(|timer| &timer.callback)
+    }
+
     /// Registers a new timer and returns the timer ID.
     pub fn add_timer(
         &mut self,
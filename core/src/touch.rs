@@ -0,0 +1,266 @@
+//! Touch points on the screen, and the `TouchEvent`s they cause.
+//!
+//! The primary touch point, the first one put down while no other touch is, also moves the
+//! mouse; the player turns it into mouse events whatever `Multitouch.inputMode` is.
+
+use crate::avm2::{Activation as Avm2Activation, Avm2, Value as Avm2Value};
+use crate::context::UpdateContext;
+use crate::display_object::{InteractiveObject, TDisplayObject, TInteractiveObject};
+use crate::events::{KeyCode, TouchPhase};
+use gc_arena::Collect;
+use std::collections::HashMap;
+use std::str::FromStr;
+use swf::Twips;
+
+/// How touches are reported to the movie, set with `Multitouch.inputMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultitouchInputMode {
+    /// Touches are only seen as mouse events.
+    None,
+
+    /// Gestures are reported with `GestureEvent`s, and touches are seen as mouse events.
+    Gesture,
+
+    /// Every touch point is reported with `TouchEvent`s.
+    TouchPoint,
+}
+
+impl MultitouchInputMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gesture => "gesture",
+            Self::TouchPoint => "touchPoint",
+        }
+    }
+}
+
+impl FromStr for MultitouchInputMode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "gesture" => Ok(Self::Gesture),
+            "touchPoint" => Ok(Self::TouchPoint),
+            _ => Err("Not a valid multitouch input mode"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+struct TouchPoint<'gc> {
+    /// The `touchPointID` of the touch.
+    id: i32,
+
+    is_primary: bool,
+
+    /// The object the touch began on.
+    begin_object: Option<InteractiveObject<'gc>>,
+
+    /// The object under the touch now.
+    over_object: Option<InteractiveObject<'gc>>,
+}
+
+/// Keeps track of the fingers on the screen.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct TouchManager<'gc> {
+    #[collect(require_static)]
+    input_mode: MultitouchInputMode,
+
+    /// The touch points, by the IDs the embedder gave them.
+    points: HashMap<u64, TouchPoint<'gc>>,
+
+    /// The `touchPointID` of the next touch. IDs aren't reused, so they tell touches apart
+    /// even if the embedder reuses its own IDs.
+    next_id: i32,
+}
+
+impl<'gc> TouchManager<'gc> {
+    pub fn new() -> Self {
+        Self {
+            input_mode: MultitouchInputMode::Gesture,
+            points: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn input_mode(&self) -> MultitouchInputMode {
+        self.input_mode
+    }
+
+    pub fn set_input_mode(&mut self, input_mode: MultitouchInputMode) {
+        self.input_mode = input_mode;
+    }
+
+    /// Updates the touch point `id` of the embedder, at `position` on the stage, and
+    /// dispatches the `TouchEvent`s it causes.
+    ///
+    /// Returns whether it's the primary touch point, which the player also turns into
+    /// mouse events.
+    pub fn handle_touch(
+        context: &mut UpdateContext<'_, 'gc>,
+        id: u64,
+        phase: TouchPhase,
+        position: (Twips, Twips),
+        pressure: Option<f64>,
+    ) -> bool {
+        let new_over_object = context.stage.iter_render_list().rev().find_map(|level| {
+            level
+                .as_interactive()
+                .and_then(|l| l.mouse_pick(context, position, true))
+        });
+
+        let manager = &mut *context.touch_manager;
+        let point = match phase {
+            TouchPhase::Start => {
+                let point = TouchPoint {
+                    id: manager.next_id,
+                    is_primary: !manager.points.values().any(|point| point.is_primary),
+                    begin_object: new_over_object,
+                    over_object: new_over_object,
+                };
+                manager.next_id = manager.next_id.wrapping_add(1);
+                manager.points.insert(id, point);
+                point
+            }
+            TouchPhase::Move => match manager.points.get_mut(&id) {
+                Some(point) => {
+                    let old_point = *point;
+                    point.over_object = new_over_object;
+                    old_point
+                }
+                None => return false,
+            },
+            TouchPhase::End | TouchPhase::Cancel => match manager.points.remove(&id) {
+                Some(point) => point,
+                None => return false,
+            },
+        };
+
+        if manager.input_mode == MultitouchInputMode::TouchPoint {
+            let stage: InteractiveObject<'gc> = context.stage.into();
+            let target = new_over_object.unwrap_or(stage);
+            let touch = Touch {
+                point: &point,
+                position,
+                pressure,
+            };
+            match phase {
+                TouchPhase::Start => touch.dispatch(context, "touchBegin", target, None),
+                TouchPhase::Move => {
+                    touch.dispatch(context, "touchMove", target, None);
+                    if !InteractiveObject::option_ptr_eq(point.over_object, new_over_object) {
+                        if let Some(old_over_object) = point.over_object {
+                            touch.dispatch(context, "touchOut", old_over_object, new_over_object);
+                        }
+                        if let Some(new_over_object) = new_over_object {
+                            touch.dispatch(
+                                context,
+                                "touchOver",
+                                new_over_object,
+                                point.over_object,
+                            );
+                        }
+                    }
+                }
+                TouchPhase::End => {
+                    touch.dispatch(context, "touchEnd", target, None);
+                    if InteractiveObject::option_ptr_eq(point.begin_object, new_over_object) {
+                        touch.dispatch(context, "touchTap", target, None);
+                    }
+                }
+                TouchPhase::Cancel => touch.dispatch(context, "touchEnd", target, None),
+            }
+        }
+
+        point.is_primary
+    }
+}
+
+impl<'gc> Default for TouchManager<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What's reported about a touch point in its `TouchEvent`s.
+struct Touch<'a, 'gc> {
+    point: &'a TouchPoint<'gc>,
+    position: (Twips, Twips),
+    pressure: Option<f64>,
+}
+
+impl<'a, 'gc> Touch<'a, 'gc> {
+    fn dispatch(
+        &self,
+        context: &mut UpdateContext<'_, 'gc>,
+        event_type: &'static str,
+        target: InteractiveObject<'gc>,
+        related_object: Option<InteractiveObject<'gc>>,
+    ) {
+        let target = target.as_displayobject();
+        let target_object = match target.object2() {
+            Avm2Value::Object(object) => object,
+            _ => return,
+        };
+        let local_position = target.global_to_local(self.position);
+        let related_object = related_object
+            .map(|object| object.as_displayobject().object2())
+            .unwrap_or(Avm2Value::Null);
+        let is_control_down = context.input.is_key_down(KeyCode::Control);
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let touch_event_cls = activation.avm2().classes().touchevent;
+        let event = touch_event_cls.construct(
+            &mut activation,
+            &[
+                event_type.into(),
+                // bubbles
+                true.into(),
+                // cancelable
+                false.into(),
+                self.point.id.into(),
+                self.point.is_primary.into(),
+                local_position.0.to_pixels().into(),
+                local_position.1.to_pixels().into(),
+                // sizeX and sizeY, which no backend reports
+                f64::NAN.into(),
+                f64::NAN.into(),
+                self.pressure.unwrap_or(f64::NAN).into(),
+                related_object,
+                // ctrlKey
+                is_control_down.into(),
+                // altKey
+                activation.context.input.is_key_down(KeyCode::Alt).into(),
+                // shiftKey
+                activation.context.input.is_key_down(KeyCode::Shift).into(),
+                // commandKey
+                false.into(),
+                // controlKey
+                is_control_down.into(),
+            ],
+        );
+        match event {
+            Ok(event) => {
+                if let Err(e) = Avm2::dispatch_event(&mut activation.context, event, target_object)
+                {
+                    tracing::error!(
+                        "Encountered AVM2 error when dispatching `{}` event: {}",
+                        event_type,
+                        e
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Encountered AVM2 error when creating `{}` event: {}",
+                    event_type,
+                    e
+                );
+            }
+        }
+    }
+}
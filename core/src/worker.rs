@@ -0,0 +1,895 @@
+//! Workers, which run other SWFs alongside the main movie.
+//!
+//! Every worker runs in a player of its own, on a thread of its own, with its own heap and
+//! display list. Workers talk to each other through shared properties and message channels.
+//! Values sent between them are copied through AMF3, except for workers, message channels and
+//! shareable byte arrays, which are passed by reference.
+//!
+//! The backends of the primordial (main) player can't be used from other threads, so workers
+//! send it requests to log, fetch and store things for them. It serves them before each frame,
+//! and while it waits for a message.
+//!
+//! Where threads aren't available, as on the web, workers aren't supported.
+
+#[cfg(not(target_family = "wasm"))]
+mod thread;
+
+use crate::avm2::amf::{Amf3Reader, Amf3Writer};
+use crate::avm2::bytearray::{ByteArrayStorage, SharedBytes};
+use crate::avm2::object::{ByteArrayObject, TObject};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, ClassObject, Error as Avm2Error,
+    EventObject as Avm2EventObject, Multiname, Namespace, Object as Avm2Object, Value as Avm2Value,
+};
+use crate::backend::navigator::{NavigationMethod, Request, Response};
+use crate::config::PlayerRuntime;
+use crate::context::UpdateContext;
+use crate::tag_utils::SwfMovie;
+use fnv::FnvHashMap;
+use futures::channel::oneshot;
+use gc_arena::Collect;
+use indexmap::IndexMap;
+use std::collections::{HashMap, VecDeque};
+use std::ops::{Deref, DerefMut};
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use url::Url;
+
+/// Identifies a worker within its worker group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Collect)]
+#[collect(require_static)]
+pub struct WorkerId(u32);
+
+impl WorkerId {
+    /// The worker of the main movie, which created all others.
+    pub const PRIMORDIAL: Self = Self(0);
+
+    pub fn is_primordial(self) -> bool {
+        self == Self::PRIMORDIAL
+    }
+}
+
+/// Identifies a message channel within its worker group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Collect)]
+#[collect(require_static)]
+pub struct ChannelId(u32);
+
+/// The lifecycle of a worker, as reported by `Worker.state`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerState {
+    New,
+    Running,
+    Terminated,
+}
+
+impl WorkerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WorkerState::New => "new",
+            WorkerState::Running => "running",
+            WorkerState::Terminated => "terminated",
+        }
+    }
+}
+
+/// The lifecycle of a message channel, as reported by `MessageChannel.state`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelState {
+    Open,
+
+    /// The channel was closed, but messages remain to be received.
+    Closing,
+
+    Closed,
+}
+
+impl ChannelState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChannelState::Open => "open",
+            ChannelState::Closing => "closing",
+            ChannelState::Closed => "closed",
+        }
+    }
+}
+
+/// A value passed from one worker to another.
+#[derive(Clone, Debug)]
+pub enum WorkerValue {
+    /// A copy of the value, as serialized with AMF3.
+    Amf(Vec<u8>),
+
+    /// The memory of a shareable byte array.
+    SharedBytes(SharedBytes),
+
+    Worker(WorkerId),
+
+    Channel(ChannelId),
+}
+
+/// Something a worker asks the primordial player to do with its backends.
+pub enum WorkerRequest {
+    Trace(String),
+
+    NavigateToUrl {
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    },
+
+    Fetch(Request, oneshot::Sender<Result<Response, String>>),
+
+    PreProcessUrl(Url, mpsc::Sender<Url>),
+
+    GetStorage(String, mpsc::Sender<Option<Vec<u8>>>),
+
+    PutStorage(String, Vec<u8>, mpsc::Sender<bool>),
+
+    RemoveStorage(String),
+}
+
+/// The settings of the primordial player that the players of its workers share.
+#[derive(Clone, Copy, Debug)]
+pub struct WorkerSettings {
+    pub max_execution_duration: Duration,
+    pub player_version: u8,
+    pub player_runtime: PlayerRuntime,
+    pub avm2_jit: bool,
+}
+
+/// Something that happened to a worker or a channel, to be dispatched as an event by the
+/// players that have an object for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Notification {
+    WorkerState(WorkerId),
+    ChannelMessage(ChannelId),
+    ChannelState(ChannelId),
+}
+
+struct Worker {
+    state: WorkerState,
+
+    /// The movie to run, until the worker starts.
+    movie: Option<SwfMovie>,
+
+    shared_properties: HashMap<String, WorkerValue>,
+}
+
+impl Worker {
+    fn new(state: WorkerState, movie: Option<SwfMovie>) -> Self {
+        Self {
+            state,
+            movie,
+            shared_properties: HashMap::new(),
+        }
+    }
+}
+
+struct Channel {
+    sender: WorkerId,
+    receiver: WorkerId,
+    state: ChannelState,
+    messages: VecDeque<WorkerValue>,
+}
+
+/// The workers created by a movie, and the channels between them.
+pub struct WorkerGroup {
+    /// All workers, indexed by their ID. Terminated workers are kept, as they may still be
+    /// referred to.
+    workers: Vec<Worker>,
+
+    channels: Vec<Channel>,
+
+    /// The notifications waiting to be dispatched by each player.
+    notifications: HashMap<WorkerId, Vec<Notification>>,
+
+    /// The requests waiting to be served by the primordial player.
+    requests: VecDeque<WorkerRequest>,
+
+    settings: WorkerSettings,
+}
+
+/// A worker group, shared by the players of all of its workers.
+pub struct SharedWorkerGroup {
+    group: Mutex<WorkerGroup>,
+
+    /// Signalled whenever the group is changed, for the workers waiting for a message.
+    changed: Condvar,
+}
+
+pub type WorkerGroupHandle = Arc<SharedWorkerGroup>;
+
+impl SharedWorkerGroup {
+    pub fn lock(&self) -> WorkerGroupGuard<'_> {
+        WorkerGroupGuard {
+            guard: Some(self.group.lock().unwrap_or_else(PoisonError::into_inner)),
+            changed: &self.changed,
+            modified: false,
+        }
+    }
+}
+
+/// A locked worker group. Other workers are woken up once it is unlocked, if it was changed.
+pub struct WorkerGroupGuard<'a> {
+    /// The lock, which is only ever missing while waiting.
+    guard: Option<MutexGuard<'a, WorkerGroup>>,
+
+    changed: &'a Condvar,
+
+    modified: bool,
+}
+
+impl WorkerGroupGuard<'_> {
+    /// Unlocks the group until another worker changes it, or `timeout` passes.
+    pub fn wait(&mut self, timeout: Duration) {
+        if std::mem::take(&mut self.modified) {
+            self.changed.notify_all();
+        }
+        if let Some(guard) = self.guard.take() {
+            let (guard, _) = self
+                .changed
+                .wait_timeout(guard, timeout)
+                .unwrap_or_else(PoisonError::into_inner);
+            self.guard = Some(guard);
+        }
+    }
+}
+
+impl Deref for WorkerGroupGuard<'_> {
+    type Target = WorkerGroup;
+
+    fn deref(&self) -> &WorkerGroup {
+        self.guard.as_ref().expect("Worker group should be locked")
+    }
+}
+
+impl DerefMut for WorkerGroupGuard<'_> {
+    fn deref_mut(&mut self) -> &mut WorkerGroup {
+        self.modified = true;
+        self.guard.as_mut().expect("Worker group should be locked")
+    }
+}
+
+impl Drop for WorkerGroupGuard<'_> {
+    fn drop(&mut self) {
+        if self.modified {
+            // Unlock first, so that the woken workers don't have to wait for it.
+            self.guard = None;
+            self.changed.notify_all();
+        }
+    }
+}
+
+impl WorkerGroup {
+    /// Creates the worker group of a primordial player.
+    pub fn new(settings: WorkerSettings) -> WorkerGroupHandle {
+        Arc::new(SharedWorkerGroup {
+            group: Mutex::new(Self {
+                workers: vec![Worker::new(WorkerState::Running, None)],
+                channels: Vec::new(),
+                notifications: HashMap::new(),
+                requests: VecDeque::new(),
+                settings,
+            }),
+            changed: Condvar::new(),
+        })
+    }
+
+    pub fn settings(&self) -> WorkerSettings {
+        self.settings
+    }
+
+    fn worker(&self, id: WorkerId) -> Option<&Worker> {
+        self.workers.get(id.0 as usize)
+    }
+
+    fn worker_mut(&mut self, id: WorkerId) -> Option<&mut Worker> {
+        self.workers.get_mut(id.0 as usize)
+    }
+
+    fn channel_mut(&mut self, id: ChannelId) -> Option<&mut Channel> {
+        self.channels.get_mut(id.0 as usize)
+    }
+
+    /// Notifies every running worker.
+    fn notify_all(&mut self, notification: Notification) {
+        for (index, worker) in self.workers.iter().enumerate() {
+            if worker.state == WorkerState::Running {
+                self.notifications
+                    .entry(WorkerId(index as u32))
+                    .or_default()
+                    .push(notification);
+            }
+        }
+    }
+
+    fn notify(&mut self, id: WorkerId, notification: Notification) {
+        self.notifications.entry(id).or_default().push(notification);
+    }
+
+    /// Creates a new worker running `movie`, which runs once started.
+    pub fn create_worker(&mut self, movie: SwfMovie) -> WorkerId {
+        let id = WorkerId(self.workers.len() as u32);
+        self.workers
+            .push(Worker::new(WorkerState::New, Some(movie)));
+        id
+    }
+
+    /// Returns the IDs of the workers that are running.
+    pub fn running_workers(&self) -> Vec<WorkerId> {
+        self.workers
+            .iter()
+            .enumerate()
+            .filter(|(_, worker)| worker.state == WorkerState::Running)
+            .map(|(index, _)| WorkerId(index as u32))
+            .collect()
+    }
+
+    pub fn state(&self, id: WorkerId) -> WorkerState {
+        self.worker(id)
+            .map_or(WorkerState::Terminated, |worker| worker.state)
+    }
+
+    /// Marks a new worker as running, and returns the movie to run in it.
+    pub fn start(&mut self, id: WorkerId) -> Option<SwfMovie> {
+        let worker = self.worker_mut(id)?;
+        if worker.state != WorkerState::New {
+            return None;
+        }
+        worker.state = WorkerState::Running;
+        let movie = worker.movie.take();
+        self.notify_all(Notification::WorkerState(id));
+        movie
+    }
+
+    /// Stops a worker. Returns whether it was running.
+    pub fn terminate(&mut self, id: WorkerId) -> bool {
+        if id.is_primordial() {
+            return false;
+        }
+        let was_running = match self.worker_mut(id) {
+            Some(worker) if worker.state != WorkerState::Terminated => {
+                let was_running = worker.state == WorkerState::Running;
+                worker.state = WorkerState::Terminated;
+                worker.movie = None;
+                was_running
+            }
+            _ => return false,
+        };
+        self.notify_all(Notification::WorkerState(id));
+
+        // Nobody is left to receive what's sent to the worker, nor to send what it would have.
+        let channels: Vec<_> = self
+            .channels
+            .iter()
+            .enumerate()
+            .filter(|(_, channel)| {
+                channel.state != ChannelState::Closed
+                    && (channel.sender == id || channel.receiver == id)
+            })
+            .map(|(index, _)| ChannelId(index as u32))
+            .collect();
+        for channel in channels {
+            self.close_channel(channel);
+        }
+        self.notifications.remove(&id);
+        was_running
+    }
+
+    /// Stops all workers, as the primordial one is gone.
+    pub fn terminate_all(&mut self) {
+        for index in 1..self.workers.len() {
+            self.terminate(WorkerId(index as u32));
+        }
+        // Nobody is left to serve them, so the workers waiting for a reply get none.
+        self.requests.clear();
+    }
+
+    pub fn shared_property(&self, id: WorkerId, key: &str) -> Option<WorkerValue> {
+        self.worker(id)
+            .and_then(|worker| worker.shared_properties.get(key).cloned())
+    }
+
+    pub fn set_shared_property(&mut self, id: WorkerId, key: String, value: WorkerValue) {
+        if let Some(worker) = self.worker_mut(id) {
+            worker.shared_properties.insert(key, value);
+        }
+    }
+
+    /// Creates a channel for `sender` to send messages to `receiver`.
+    pub fn create_channel(&mut self, sender: WorkerId, receiver: WorkerId) -> ChannelId {
+        let id = ChannelId(self.channels.len() as u32);
+        self.channels.push(Channel {
+            sender,
+            receiver,
+            state: ChannelState::Open,
+            messages: VecDeque::new(),
+        });
+        id
+    }
+
+    pub fn channel_state(&self, id: ChannelId) -> ChannelState {
+        self.channels
+            .get(id.0 as usize)
+            .map_or(ChannelState::Closed, |channel| channel.state)
+    }
+
+    pub fn message_available(&self, id: ChannelId) -> bool {
+        self.channels
+            .get(id.0 as usize)
+            .map_or(false, |channel| !channel.messages.is_empty())
+    }
+
+    /// Queues a message on an open channel. Returns whether it was queued.
+    ///
+    /// A non-negative `queue_limit` drops the message if that many messages are waiting already.
+    pub fn send(&mut self, id: ChannelId, value: WorkerValue, queue_limit: i32) -> bool {
+        let receiver = match self.channel_mut(id) {
+            Some(channel) if channel.state == ChannelState::Open => {
+                if queue_limit >= 0 && channel.messages.len() >= queue_limit as usize {
+                    return false;
+                }
+                channel.messages.push_back(value);
+                channel.receiver
+            }
+            _ => return false,
+        };
+        self.notify(receiver, Notification::ChannelMessage(id));
+        true
+    }
+
+    /// Takes the oldest message of a channel.
+    pub fn receive(&mut self, id: ChannelId) -> Option<WorkerValue> {
+        let channel = self.channel_mut(id)?;
+        let message = channel.messages.pop_front();
+        if channel.state == ChannelState::Closing && channel.messages.is_empty() {
+            channel.state = ChannelState::Closed;
+            let (sender, receiver) = (channel.sender, channel.receiver);
+            self.notify(sender, Notification::ChannelState(id));
+            self.notify(receiver, Notification::ChannelState(id));
+        }
+        message
+    }
+
+    /// Closes a channel. Messages that were already sent may still be received.
+    pub fn close_channel(&mut self, id: ChannelId) {
+        let channel = match self.channel_mut(id) {
+            Some(channel) if channel.state == ChannelState::Open => channel,
+            _ => return,
+        };
+        channel.state = if channel.messages.is_empty() {
+            ChannelState::Closed
+        } else {
+            ChannelState::Closing
+        };
+        let (sender, receiver) = (channel.sender, channel.receiver);
+        self.notify(sender, Notification::ChannelState(id));
+        self.notify(receiver, Notification::ChannelState(id));
+    }
+
+    pub fn has_notifications(&self, id: WorkerId) -> bool {
+        self.notifications
+            .get(&id)
+            .map_or(false, |notifications| !notifications.is_empty())
+    }
+
+    fn take_notifications(&mut self, id: WorkerId) -> Vec<Notification> {
+        self.notifications.remove(&id).unwrap_or_default()
+    }
+
+    /// Asks the primordial player to do something for a worker.
+    pub fn request(&mut self, request: WorkerRequest) {
+        self.requests.push_back(request);
+    }
+
+    pub fn has_requests(&self) -> bool {
+        !self.requests.is_empty()
+    }
+
+    fn take_requests(&mut self) -> VecDeque<WorkerRequest> {
+        std::mem::take(&mut self.requests)
+    }
+}
+
+/// Stops the other workers of a group once dropped, as they can't outlive the primordial one.
+pub struct PrimordialWorker(WorkerGroupHandle);
+
+impl PrimordialWorker {
+    pub fn new(group: WorkerGroupHandle) -> Self {
+        Self(group)
+    }
+}
+
+impl Drop for PrimordialWorker {
+    fn drop(&mut self) {
+        self.0.lock().terminate_all();
+    }
+}
+
+/// The worker a player runs, and the objects it has for the workers and channels of its group.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct WorkerManager<'gc> {
+    #[collect(require_static)]
+    group: WorkerGroupHandle,
+
+    current: WorkerId,
+
+    workers: FnvHashMap<WorkerId, Avm2Object<'gc>>,
+
+    channels: FnvHashMap<ChannelId, Avm2Object<'gc>>,
+}
+
+impl<'gc> WorkerManager<'gc> {
+    pub fn new(group: WorkerGroupHandle, current: WorkerId) -> Self {
+        Self {
+            group,
+            current,
+            workers: Default::default(),
+            channels: Default::default(),
+        }
+    }
+
+    pub fn group(&self) -> &WorkerGroupHandle {
+        &self.group
+    }
+
+    /// The worker this player runs.
+    pub fn current(&self) -> WorkerId {
+        self.current
+    }
+
+    /// Returns the `Worker` object of a worker, creating it if needed.
+    pub fn worker_object(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        id: WorkerId,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        if let Some(object) = activation.context.worker_manager.workers.get(&id) {
+            return Ok(*object);
+        }
+        let class = activation.avm2().classes().worker;
+        let object = new_object(activation, class, id.0)?;
+        activation.context.worker_manager.workers.insert(id, object);
+        Ok(object)
+    }
+
+    /// Returns the `MessageChannel` object of a channel, creating it if needed.
+    pub fn channel_object(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        id: ChannelId,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        if let Some(object) = activation.context.worker_manager.channels.get(&id) {
+            return Ok(*object);
+        }
+        let class = activation.avm2().classes().messagechannel;
+        let object = new_object(activation, class, id.0)?;
+        activation
+            .context
+            .worker_manager
+            .channels
+            .insert(id, object);
+        Ok(object)
+    }
+
+    /// Returns the worker of a `Worker` object.
+    pub fn worker_id(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+    ) -> Result<WorkerId, Avm2Error<'gc>> {
+        Ok(WorkerId(object_id(activation, object)?))
+    }
+
+    /// Returns the channel of a `MessageChannel` object.
+    pub fn channel_id(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+    ) -> Result<ChannelId, Avm2Error<'gc>> {
+        Ok(ChannelId(object_id(activation, object)?))
+    }
+
+    /// Whether a value is a shareable byte array, which other workers should see the changes of.
+    pub fn is_shareable_bytearray(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        value: Avm2Value<'gc>,
+    ) -> Result<bool, Avm2Error<'gc>> {
+        match value {
+            Avm2Value::Object(object) if object.as_bytearray().is_some() => Ok(object
+                .get_property(&shareable_name(), activation)?
+                .coerce_to_boolean()),
+            _ => Ok(false),
+        }
+    }
+
+    /// Starts a new worker, on a thread of its own.
+    pub fn start(context: &mut UpdateContext<'_, 'gc>, id: WorkerId) {
+        let group = context.worker_manager.group.clone();
+        let movie = group.lock().start(id);
+        if let Some(movie) = movie {
+            #[cfg(not(target_family = "wasm"))]
+            thread::spawn(group, id, movie);
+
+            #[cfg(target_family = "wasm")]
+            {
+                let _ = movie;
+                tracing::warn!("Worker.start: workers are not supported on this platform");
+            }
+        }
+    }
+
+    /// Converts a value to be passed to another worker.
+    pub fn serialize(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        value: Avm2Value<'gc>,
+    ) -> Result<WorkerValue, Avm2Error<'gc>> {
+        if let Avm2Value::Object(object) = value {
+            let worker_class = activation.avm2().classes().worker;
+            let channel_class = activation.avm2().classes().messagechannel;
+            match object.instance_of() {
+                Some(class) if Avm2Object::ptr_eq(class, worker_class) => {
+                    return Ok(WorkerValue::Worker(Self::worker_id(activation, object)?));
+                }
+                Some(class) if Avm2Object::ptr_eq(class, channel_class) => {
+                    return Ok(WorkerValue::Channel(Self::channel_id(activation, object)?));
+                }
+                _ => {}
+            }
+            if Self::is_shareable_bytearray(activation, value)? {
+                if let Some(mut bytes) = object.as_bytearray_mut(activation.context.gc_context) {
+                    return Ok(WorkerValue::SharedBytes(bytes.share()));
+                }
+            }
+        }
+        let buffer = ByteArrayObject::from_storage(activation, ByteArrayStorage::new())?;
+        Amf3Writer::new(buffer).write_value(activation, value)?;
+        let bytes = buffer
+            .as_bytearray()
+            .map(|bytes| bytes.bytes().to_vec())
+            .unwrap_or_default();
+        Ok(WorkerValue::Amf(bytes))
+    }
+
+    /// Converts a value passed from another worker.
+    pub fn deserialize(
+        activation: &mut Avm2Activation<'_, 'gc>,
+        value: &WorkerValue,
+    ) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+        Ok(match value {
+            WorkerValue::Amf(bytes) => {
+                let buffer = ByteArrayObject::from_storage(
+                    activation,
+                    ByteArrayStorage::from_vec(bytes.clone()),
+                )?;
+                Amf3Reader::new(buffer).read_value(activation)?
+            }
+            WorkerValue::SharedBytes(bytes) => {
+                let mut object = ByteArrayObject::from_storage(
+                    activation,
+                    ByteArrayStorage::from_shared(bytes.clone()),
+                )?;
+                object.set_property(&shareable_name(), true.into(), activation)?;
+                object.into()
+            }
+            WorkerValue::Worker(id) => Self::worker_object(activation, *id)?.into(),
+            WorkerValue::Channel(id) => Self::channel_object(activation, *id)?.into(),
+        })
+    }
+
+    /// Takes the oldest message of a channel.
+    ///
+    /// With `block`, this waits for a message to be sent, unless the channel is closed or this
+    /// worker is terminated meanwhile.
+    pub fn receive(
+        context: &mut UpdateContext<'_, 'gc>,
+        id: ChannelId,
+        block: bool,
+    ) -> Option<WorkerValue> {
+        let group = context.worker_manager.group.clone();
+        let current = context.worker_manager.current;
+        loop {
+            let mut guard = group.lock();
+            if let Some(message) = guard.receive(id) {
+                return Some(message);
+            }
+            if !block
+                || guard.channel_state(id) == ChannelState::Closed
+                || guard.state(current) == WorkerState::Terminated
+            {
+                return None;
+            }
+            if current.is_primordial() && guard.has_requests() {
+                // The sender may be waiting for the primordial worker to serve it.
+                drop(guard);
+                Self::serve_requests(context);
+                continue;
+            }
+            // Changes wake this up straight away, the timeout is just a safety net.
+            guard.wait(Duration::from_millis(100));
+        }
+    }
+
+    /// Does what the other workers asked the primordial player to do with its backends.
+    pub fn serve_requests(context: &mut UpdateContext<'_, 'gc>) {
+        let requests = context.worker_manager.group.lock().take_requests();
+        for request in requests {
+            // A worker that stopped waiting for a reply doesn't need it anymore.
+            match request {
+                WorkerRequest::Trace(message) => context.log.avm_trace(&message),
+                WorkerRequest::NavigateToUrl {
+                    url,
+                    target,
+                    vars_method,
+                } => context.navigator.navigate_to_url(url, target, vars_method),
+                WorkerRequest::Fetch(request, reply) => {
+                    let fetch = context.navigator.fetch(request);
+                    context.navigator.spawn_future(Box::pin(async move {
+                        let _ = reply.send(fetch.await.map_err(|e| e.to_string()));
+                        Ok(())
+                    }));
+                }
+                WorkerRequest::PreProcessUrl(url, reply) => {
+                    let _ = reply.send(context.navigator.pre_process_url(url));
+                }
+                WorkerRequest::GetStorage(name, reply) => {
+                    let _ = reply.send(context.storage.get(&name));
+                }
+                WorkerRequest::PutStorage(name, value, reply) => {
+                    let _ = reply.send(context.storage.put(&name, &value));
+                }
+                WorkerRequest::RemoveStorage(name) => context.storage.remove_key(&name),
+            }
+        }
+    }
+
+    /// Dispatches the events of what happened to workers and channels since the last call.
+    pub fn dispatch_notifications(context: &mut UpdateContext<'_, 'gc>) {
+        let current = context.worker_manager.current;
+        let notifications = context
+            .worker_manager
+            .group
+            .lock()
+            .take_notifications(current);
+        for notification in notifications {
+            let (target, event_type) = match notification {
+                Notification::WorkerState(id) => {
+                    (context.worker_manager.workers.get(&id), "workerState")
+                }
+                Notification::ChannelMessage(id) => {
+                    (context.worker_manager.channels.get(&id), "channelMessage")
+                }
+                Notification::ChannelState(id) => {
+                    (context.worker_manager.channels.get(&id), "channelState")
+                }
+            };
+            // Events are only dispatched to the objects the movie knows of.
+            let target = match target {
+                Some(target) => *target,
+                None => continue,
+            };
+            let event = Avm2EventObject::bare_default_event(context, event_type);
+            if let Err(e) = Avm2::dispatch_event(context, event, target) {
+                tracing::error!("Error dispatching {} event: {:?}", event_type, e);
+            }
+        }
+    }
+}
+
+fn id_name<'gc>() -> Multiname<'gc> {
+    Multiname::new(Namespace::Namespace("__ruffle__".into()), "_id")
+}
+
+fn shareable_name<'gc>() -> Multiname<'gc> {
+    Multiname::new(Namespace::Namespace("__ruffle__".into()), "_shareable")
+}
+
+fn new_object<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc>,
+    class: ClassObject<'gc>,
+    id: u32,
+) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+    let mut object = class.construct(activation, &[])?;
+    object.set_property(&id_name(), id.into(), activation)?;
+    Ok(object)
+}
+
+fn object_id<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc>,
+    object: Avm2Object<'gc>,
+) -> Result<u32, Avm2Error<'gc>> {
+    object
+        .get_property(&id_name(), activation)?
+        .coerce_to_u32(activation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> WorkerSettings {
+        WorkerSettings {
+            max_execution_duration: Duration::from_secs(15),
+            player_version: 32,
+            player_runtime: PlayerRuntime::FlashPlayer,
+            avm2_jit: false,
+        }
+    }
+
+    #[test]
+    fn channel_lifecycle() {
+        let group = WorkerGroup::new(settings());
+        let mut group = group.lock();
+        let worker = group.create_worker(SwfMovie::empty(19));
+        assert_eq!(group.state(worker), WorkerState::New);
+
+        let channel = group.create_channel(WorkerId::PRIMORDIAL, worker);
+        assert!(group.send(channel, WorkerValue::Amf(vec![1]), -1));
+        assert!(!group.send(channel, WorkerValue::Amf(vec![2]), 1));
+        assert!(group.message_available(channel));
+        assert_eq!(
+            group.take_notifications(worker),
+            vec![Notification::ChannelMessage(channel)]
+        );
+
+        group.close_channel(channel);
+        assert_eq!(group.channel_state(channel), ChannelState::Closing);
+        assert!(!group.send(channel, WorkerValue::Amf(vec![3]), -1));
+        assert!(matches!(
+            group.receive(channel),
+            Some(WorkerValue::Amf(bytes)) if bytes == [1]
+        ));
+        assert_eq!(group.channel_state(channel), ChannelState::Closed);
+        assert!(group.receive(channel).is_none());
+    }
+
+    #[test]
+    fn terminate() {
+        let group = WorkerGroup::new(settings());
+        let mut group = group.lock();
+        let worker = group.create_worker(SwfMovie::empty(19));
+        assert!(group.start(worker).is_some());
+        assert!(group.start(worker).is_none());
+        assert_eq!(group.state(worker), WorkerState::Running);
+
+        let channel = group.create_channel(worker, WorkerId::PRIMORDIAL);
+        assert!(!group.terminate(WorkerId::PRIMORDIAL));
+        assert!(group.terminate(worker));
+        assert!(!group.terminate(worker));
+        assert_eq!(group.state(worker), WorkerState::Terminated);
+        assert_eq!(group.channel_state(channel), ChannelState::Closed);
+        assert!(!group.has_notifications(worker));
+        assert_eq!(
+            group.take_notifications(WorkerId::PRIMORDIAL),
+            vec![
+                Notification::WorkerState(worker),
+                Notification::WorkerState(worker),
+                Notification::ChannelState(channel)
+            ]
+        );
+    }
+
+    #[test]
+    fn wait_for_message() {
+        let group = WorkerGroup::new(settings());
+        let channel = {
+            let mut group = group.lock();
+            let worker = group.create_worker(SwfMovie::empty(19));
+            group.create_channel(worker, WorkerId::PRIMORDIAL)
+        };
+
+        let sender = {
+            let group = group.clone();
+            std::thread::spawn(move || {
+                group.lock().send(channel, WorkerValue::Amf(vec![1]), -1);
+            })
+        };
+        let mut guard = group.lock();
+        let message = loop {
+            if let Some(message) = guard.receive(channel) {
+                break message;
+            }
+            guard.wait(Duration::from_secs(1));
+        };
+        drop(guard);
+        sender.join().unwrap();
+        assert!(matches!(message, WorkerValue::Amf(bytes) if bytes == [1]));
+    }
+}
@@ -0,0 +1,178 @@
+//! The threads that workers run on, and the backends that let them use those of the primordial
+//! player.
+
+use super::{WorkerGroupHandle, WorkerId, WorkerRequest, WorkerState};
+use crate::backend::log::LogBackend;
+use crate::backend::navigator::{
+    NavigationMethod, NavigatorBackend, NullExecutor, NullSpawner, OwnedFuture, Request, Response,
+    SocketConnection,
+};
+use crate::backend::storage::StorageBackend;
+use crate::loader::Error;
+use crate::player::PlayerBuilder;
+use crate::tag_utils::SwfMovie;
+use futures::channel::oneshot;
+use indexmap::IndexMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::time::Instant;
+use url::Url;
+
+/// Runs a worker on a new thread, until it is terminated.
+pub fn spawn(group: WorkerGroupHandle, id: WorkerId, movie: SwfMovie) {
+    let thread_group = group.clone();
+    let result = std::thread::Builder::new()
+        .name(format!("worker {}", id.0))
+        .spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| run(&thread_group, id, movie)));
+            if result.is_err() {
+                // Don't leave the other workers waiting for this one.
+                thread_group.lock().terminate(id);
+            }
+        });
+    if let Err(e) = result {
+        tracing::error!("Couldn't start a thread for worker {}: {}", id.0, e);
+        group.lock().terminate(id);
+    }
+}
+
+fn run(group: &WorkerGroupHandle, id: WorkerId, movie: SwfMovie) {
+    let settings = group.lock().settings();
+    let mut executor = NullExecutor::new();
+    let player = PlayerBuilder::new()
+        .with_log(WorkerLogBackend {
+            group: group.clone(),
+        })
+        .with_navigator(WorkerNavigatorBackend {
+            group: group.clone(),
+            spawner: executor.spawner(),
+        })
+        .with_storage(WorkerStorageBackend {
+            group: group.clone(),
+        })
+        .with_max_execution_duration(settings.max_execution_duration)
+        .with_player_version(Some(settings.player_version))
+        .with_player_runtime(settings.player_runtime)
+        .with_avm2_jit(settings.avm2_jit)
+        .with_worker(group.clone(), id)
+        .with_movie(movie)
+        .with_autoplay(true)
+        .build();
+
+    let mut last_tick = Instant::now();
+    while group.lock().state(id) == WorkerState::Running {
+        let now = Instant::now();
+        let dt = now.duration_since(last_tick).as_secs_f64() * 1000.0;
+        last_tick = now;
+
+        let time_til_next_frame = {
+            let mut player = player.lock().expect("Worker player should not be poisoned");
+            player.tick(dt);
+            player.time_til_next_frame()
+        };
+        executor.run_until_stalled();
+        std::thread::sleep(time_til_next_frame);
+    }
+}
+
+/// Sends the traces of a worker to the log of the primordial player.
+struct WorkerLogBackend {
+    group: WorkerGroupHandle,
+}
+
+impl LogBackend for WorkerLogBackend {
+    fn avm_trace(&self, message: &str) {
+        self.group
+            .lock()
+            .request(WorkerRequest::Trace(message.to_string()));
+    }
+}
+
+/// Makes the requests of a worker through the navigator of the primordial player.
+struct WorkerNavigatorBackend {
+    group: WorkerGroupHandle,
+
+    /// Runs the futures of the worker, on its own thread.
+    spawner: NullSpawner,
+}
+
+impl NavigatorBackend for WorkerNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        self.group.lock().request(WorkerRequest::NavigateToUrl {
+            url,
+            target,
+            vars_method,
+        });
+    }
+
+    fn fetch(&self, request: Request) -> OwnedFuture<Response, Error> {
+        let (sender, receiver) = oneshot::channel();
+        self.group
+            .lock()
+            .request(WorkerRequest::Fetch(request, sender));
+        Box::pin(async move {
+            match receiver.await {
+                Ok(response) => response.map_err(Error::FetchError),
+                Err(_) => Err(Error::FetchError(
+                    "The primordial worker is gone".to_string(),
+                )),
+            }
+        })
+    }
+
+    fn connect_socket(&self, _host: &str, _port: u16) -> OwnedFuture<SocketConnection, Error> {
+        Box::pin(async {
+            Err(Error::ConnectError(
+                "Sockets are not supported in workers".to_string(),
+            ))
+        })
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
+        self.spawner.spawn_local(future);
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        let (sender, receiver) = mpsc::channel();
+        self.group
+            .lock()
+            .request(WorkerRequest::PreProcessUrl(url.clone(), sender));
+        receiver.recv().unwrap_or(url)
+    }
+}
+
+/// Stores the shared objects of a worker with the storage of the primordial player.
+struct WorkerStorageBackend {
+    group: WorkerGroupHandle,
+}
+
+impl StorageBackend for WorkerStorageBackend {
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        let (sender, receiver) = mpsc::channel();
+        self.group
+            .lock()
+            .request(WorkerRequest::GetStorage(name.to_string(), sender));
+        receiver.recv().unwrap_or_default()
+    }
+
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        let (sender, receiver) = mpsc::channel();
+        self.group.lock().request(WorkerRequest::PutStorage(
+            name.to_string(),
+            value.to_vec(),
+            sender,
+        ));
+        receiver.recv().unwrap_or(false)
+    }
+
+    fn remove_key(&mut self, name: &str) {
+        self.group
+            .lock()
+            .request(WorkerRequest::RemoveStorage(name.to_string()));
+    }
+}
@@ -1,81 +1,287 @@
 use anyhow::{anyhow, Context, Error};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, AudioMixer, DecodeError, RegisterError, SoundHandle, SoundInstanceHandle,
-    SoundTransform,
+    swf, AudioBackend, AudioMixer, DecodeError, MicrophoneBuffer, MicrophoneError, RegisterError,
+    SoundHandle, SoundInstanceHandle, SoundTransform,
 };
 use ruffle_core::impl_audio_mixer_backend;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often to check whether the system's default audio device changed.
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Which audio device to play to, and how.
+#[derive(Clone, Debug, Default)]
+pub struct AudioSettings {
+    /// The name of the output device, or `None` for the system's default device.
+    pub device: Option<String>,
+
+    /// The sample rate in Hz, or `None` for the device's default.
+    pub sample_rate: Option<u32>,
+
+    /// The size of the output buffer in sample frames, or `None` for the device's default.
+    /// Smaller buffers have less latency, but may crackle on slower machines.
+    pub buffer_size: Option<u32>,
+}
 
 pub struct CpalAudioBackend {
-    #[allow(dead_code)]
-    device: cpal::Device,
-    #[allow(dead_code)]
+    settings: AudioSettings,
+
+    /// The name of the device that `stream` plays to.
+    device_name: Option<String>,
     config: cpal::StreamConfig,
     stream: cpal::Stream,
     mixer: AudioMixer,
+    is_playing: bool,
+
+    /// Set when the device of `stream` goes away, so that the stream is rebuilt.
+    device_lost: Arc<AtomicBool>,
+
+    /// When the default device was last checked for changes.
+    last_device_check: Instant,
+
+    /// The stream capturing from the microphone, if any.
+    microphone: Option<(cpal::Stream, MicrophoneBuffer)>,
 }
 
 impl CpalAudioBackend {
-    pub fn new() -> Result<Self, Error> {
-        // Create CPAL audio device.
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or_else(|| anyhow!("No audio devices available"))?;
-
-        // Create audio stream for device.
-        let config = device
-            .default_output_config()
-            .context("Failed to get default output config")?;
-        let sample_format = config.sample_format();
-        let config = cpal::StreamConfig::from(config);
+    pub fn new(settings: AudioSettings) -> Result<Self, Error> {
+        let device = output_device(&settings)?;
+        let (config, sample_format) = output_config(&device, &settings, settings.sample_rate)?;
         let mixer = AudioMixer::new(config.channels as u8, config.sample_rate.0);
+        let device_lost = Arc::new(AtomicBool::new(false));
 
         // Start the audio stream.
-        let stream = {
-            let mixer = mixer.proxy();
-            let error_handler = move |err| tracing::error!("Audio stream error: {}", err);
-
-            match sample_format {
-                cpal::SampleFormat::F32 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<f32>(buffer),
-                    error_handler,
-                    None,
-                ),
-                cpal::SampleFormat::I16 => device.build_output_stream(
-                    &config,
-                    move |buffer, _| mixer.mix::<i16>(buffer),
-                    error_handler,
-                    None,
-                ),
-                cpal::SampleFormat::U16 => device.build_output_stream(
-                    &config,
-                    move |buffer: &mut [u16], _| {
-                        // Since I couldn't easily make `mixer` work with `u16` samples,
-                        // we fill the buffer as if it was `&[i16]`, and then rotate
-                        // the sample values to make 32768 the equilibrium.
-                        mixer.mix::<i16>(bytemuck::cast_slice_mut(buffer));
-                        for s in buffer.iter_mut() {
-                            *s = (*s).wrapping_add(32768);
-                        }
-                    },
-                    error_handler,
-                    None,
-                ),
-                _ => anyhow::bail!("Unsupported sample format {sample_format:?}"),
-            }?
-        };
-
+        let stream =
+            build_output_stream(&device, &config, sample_format, &mixer, device_lost.clone())?;
         stream.play().context("Couldn't play the audio stream")?;
 
         Ok(Self {
-            device,
+            settings,
+            device_name: device.name().ok(),
             config,
             stream,
             mixer,
+            is_playing: true,
+            device_lost,
+            last_device_check: Instant::now(),
+            microphone: None,
         })
     }
+
+    /// Replaces the output stream with one to the device chosen by the settings,
+    /// keeping the sample rate if the device supports it.
+    fn rebuild_stream(&mut self) -> Result<(), Error> {
+        let device = output_device(&self.settings)?;
+        let (config, sample_format) =
+            output_config(&device, &self.settings, Some(self.config.sample_rate.0))?;
+        self.mixer
+            .set_output_format(config.channels as u8, config.sample_rate.0);
+        let stream = build_output_stream(
+            &device,
+            &config,
+            sample_format,
+            &self.mixer,
+            self.device_lost.clone(),
+        )?;
+        if self.is_playing {
+            stream.play().context("Couldn't play the audio stream")?;
+        }
+
+        tracing::info!(
+            "Playing audio to {}",
+            device.name().as_deref().unwrap_or("unknown device")
+        );
+        self.device_name = device.name().ok();
+        self.config = config;
+        self.stream = stream;
+        Ok(())
+    }
+}
+
+/// Returns the device named by the settings, or the system's default one.
+fn output_device(settings: &AudioSettings) -> Result<cpal::Device, Error> {
+    let host = cpal::default_host();
+    if let Some(name) = &settings.device {
+        let device = host
+            .output_devices()
+            .into_iter()
+            .flatten()
+            .find(|device| device.name().ok().as_ref() == Some(name));
+        match device {
+            Some(device) => return Ok(device),
+            None => {
+                let names: Vec<_> = host
+                    .output_devices()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|device| device.name().ok())
+                    .collect();
+                tracing::warn!(
+                    "No audio device named {}, using the default one. Available devices are: {}",
+                    name,
+                    names.join(", ")
+                );
+            }
+        }
+    }
+    host.default_output_device()
+        .ok_or_else(|| anyhow!("No audio devices available"))
+}
+
+/// Chooses the configuration of an output stream, using the sample rate and buffer size
+/// given by the settings when the device supports them.
+fn output_config(
+    device: &cpal::Device,
+    settings: &AudioSettings,
+    sample_rate: Option<u32>,
+) -> Result<(cpal::StreamConfig, cpal::SampleFormat), Error> {
+    let default_config = device
+        .default_output_config()
+        .context("Failed to get default output config")?;
+    let sample_format = default_config.sample_format();
+    let mut config = cpal::StreamConfig::from(default_config);
+
+    if let Some(sample_rate) = sample_rate {
+        let supported = device
+            .supported_output_configs()
+            .context("Failed to get supported output configs")?
+            .any(|range| {
+                range.channels() == config.channels
+                    && range.sample_format() == sample_format
+                    && (range.min_sample_rate().0..=range.max_sample_rate().0)
+                        .contains(&sample_rate)
+            });
+        if supported {
+            config.sample_rate = cpal::SampleRate(sample_rate);
+        } else {
+            tracing::warn!(
+                "Audio device doesn't support a sample rate of {} Hz, using {} Hz",
+                sample_rate,
+                config.sample_rate.0
+            );
+        }
+    }
+
+    if let Some(buffer_size) = settings.buffer_size {
+        config.buffer_size = cpal::BufferSize::Fixed(buffer_size);
+    }
+
+    Ok((config, sample_format))
+}
+
+/// Creates a stream playing the output of `mixer`.
+fn build_output_stream(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sample_format: cpal::SampleFormat,
+    mixer: &AudioMixer,
+    device_lost: Arc<AtomicBool>,
+) -> Result<cpal::Stream, Error> {
+    let mixer = mixer.proxy();
+    let error_handler = move |err: cpal::StreamError| {
+        tracing::error!("Audio stream error: {}", err);
+        if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+            device_lost.store(true, Ordering::Relaxed);
+        }
+    };
+
+    let stream = match sample_format {
+        cpal::SampleFormat::F32 => device.build_output_stream(
+            config,
+            move |buffer, _| mixer.mix::<f32>(buffer),
+            error_handler,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_output_stream(
+            config,
+            move |buffer, _| mixer.mix::<i16>(buffer),
+            error_handler,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_output_stream(
+            config,
+            move |buffer: &mut [u16], _| {
+                // Since I couldn't easily make `mixer` work with `u16` samples,
+                // we fill the buffer as if it was `&[i16]`, and then rotate
+                // the sample values to make 32768 the equilibrium.
+                mixer.mix::<i16>(bytemuck::cast_slice_mut(buffer));
+                for s in buffer.iter_mut() {
+                    *s = (*s).wrapping_add(32768);
+                }
+            },
+            error_handler,
+            None,
+        ),
+        _ => anyhow::bail!("Unsupported sample format {sample_format:?}"),
+    }?;
+    Ok(stream)
+}
+
+/// Returns the devices that audio can be captured from, the default one first.
+fn input_devices() -> Vec<cpal::Device> {
+    let host = cpal::default_host();
+    let default = host.default_input_device();
+    let default_name = default.as_ref().and_then(|device| device.name().ok());
+    let others = host
+        .input_devices()
+        .into_iter()
+        .flatten()
+        .filter(|device| default_name.is_none() || device.name().ok() != default_name);
+    default.into_iter().chain(others).collect()
+}
+
+/// Starts capturing audio from an input device.
+fn start_input_stream(device: &cpal::Device) -> Result<(cpal::Stream, MicrophoneBuffer), Error> {
+    let config = device
+        .default_input_config()
+        .context("Failed to get default input config")?;
+    let sample_format = config.sample_format();
+    let config = cpal::StreamConfig::from(config);
+    let buffer = MicrophoneBuffer::new(config.channels, config.sample_rate.0);
+
+    let stream = {
+        let buffer = buffer.clone();
+        let error_handler = move |err| tracing::error!("Microphone stream error: {}", err);
+
+        match sample_format {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config,
+                move |data: &[f32], _| buffer.push(data),
+                error_handler,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config,
+                move |data: &[i16], _| {
+                    let data: Vec<f32> = data.iter().map(|s| f32::from(*s) / 32768.0).collect();
+                    buffer.push(&data);
+                },
+                error_handler,
+                None,
+            ),
+            cpal::SampleFormat::U16 => device.build_input_stream(
+                &config,
+                move |data: &[u16], _| {
+                    let data: Vec<f32> = data
+                        .iter()
+                        .map(|s| (f32::from(*s) - 32768.0) / 32768.0)
+                        .collect();
+                    buffer.push(&data);
+                },
+                error_handler,
+                None,
+            ),
+            _ => anyhow::bail!("Unsupported sample format {sample_format:?}"),
+        }?
+    };
+
+    stream
+        .play()
+        .context("Couldn't start the microphone stream")?;
+    Ok((stream, buffer))
 }
 
 impl AudioBackend for CpalAudioBackend {
@@ -83,9 +289,65 @@ impl AudioBackend for CpalAudioBackend {
 
     fn play(&mut self) {
         self.stream.play().expect("Error trying to resume CPAL audio stream. This feature may not be supported by your audio device.");
+        self.is_playing = true;
     }
 
     fn pause(&mut self) {
         self.stream.pause().expect("Error trying to pause CPAL audio stream. This feature may not be supported by your audio device.");
+        self.is_playing = false;
+    }
+
+    fn tick(&mut self) {
+        let rebuild = if self.device_lost.swap(false, Ordering::Relaxed) {
+            tracing::warn!("Audio device was disconnected");
+            true
+        } else if self.settings.device.is_none()
+            && self.last_device_check.elapsed() >= DEVICE_CHECK_INTERVAL
+        {
+            // Follow the system when its default device changes.
+            self.last_device_check = Instant::now();
+            let default_name = cpal::default_host()
+                .default_output_device()
+                .and_then(|device| device.name().ok());
+            default_name.is_some() && default_name != self.device_name
+        } else {
+            false
+        };
+
+        if rebuild {
+            if let Err(e) = self.rebuild_stream() {
+                tracing::error!("Unable to switch audio device: {:#}", e);
+            }
+        }
+    }
+
+    fn microphone_names(&self) -> Vec<String> {
+        input_devices()
+            .iter()
+            .filter_map(|device| device.name().ok())
+            .collect()
+    }
+
+    fn start_microphone(&mut self, index: usize) -> Result<(), MicrophoneError> {
+        self.stop_microphone();
+        let device = input_devices()
+            .into_iter()
+            .nth(index)
+            .ok_or(MicrophoneError::NotFound(index))?;
+        let microphone =
+            start_input_stream(&device).map_err(|e| MicrophoneError::Backend(format!("{e:#}")))?;
+        self.microphone = Some(microphone);
+        Ok(())
+    }
+
+    fn stop_microphone(&mut self) {
+        self.microphone = None;
+    }
+
+    fn take_microphone_samples(&mut self) -> Vec<f32> {
+        self.microphone
+            .as_ref()
+            .map(|(_, buffer)| buffer.take())
+            .unwrap_or_default()
     }
 }
@@ -0,0 +1,226 @@
+//! Camera capture, which is supported on Linux through Video4Linux.
+
+use ruffle_core::backend::ui::{CameraError, CameraFrame};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Returns the names of the cameras, with the default camera first.
+pub fn camera_names() -> Vec<String> {
+    devices().into_iter().map(|(name, _)| name).collect()
+}
+
+/// Video captured from a camera on a background thread.
+pub struct Camera {
+    /// The latest image captured by the camera, until it's taken.
+    frame: Arc<Mutex<Option<CameraFrame>>>,
+
+    /// Tells the capture thread to stop.
+    stopped: Arc<AtomicBool>,
+}
+
+impl Camera {
+    /// Starts capturing from the camera with the given index in `camera_names`.
+    pub fn start(index: usize, width: u32, height: u32, fps: f64) -> Result<Self, CameraError> {
+        let (_, path) = devices()
+            .into_iter()
+            .nth(index)
+            .ok_or(CameraError::NotFound(index))?;
+        let frame = Arc::new(Mutex::new(None));
+        let stopped = Arc::new(AtomicBool::new(false));
+        start_capture(path, width, height, fps, frame.clone(), stopped.clone())?;
+        Ok(Self { frame, stopped })
+    }
+
+    /// Returns the latest image captured by the camera, if one arrived since the last call.
+    pub fn take_frame(&mut self) -> Option<CameraFrame> {
+        self.frame
+            .lock()
+            .expect("Camera frame lock poisoned")
+            .take()
+    }
+}
+
+impl Drop for Camera {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Lists the device nodes that capture video, ordered by their number.
+#[cfg(target_os = "linux")]
+fn devices() -> Vec<(String, PathBuf)> {
+    use v4l::capability::Flags;
+    use v4l::prelude::*;
+
+    let mut devices: Vec<_> = v4l::context::enum_devices()
+        .into_iter()
+        .filter_map(|node| {
+            let number = node
+                .path()
+                .file_name()?
+                .to_str()?
+                .strip_prefix("video")?
+                .parse::<u32>()
+                .ok()?;
+            // Cameras usually also have a node for metadata, which doesn't capture video.
+            let caps = Device::with_path(node.path()).ok()?.query_caps().ok()?;
+            if !caps.capabilities.contains(Flags::VIDEO_CAPTURE) {
+                return None;
+            }
+            let name = node.name().unwrap_or(caps.card);
+            Some((number, name, node.path().to_owned()))
+        })
+        .collect();
+    devices.sort_by_key(|(number, _, _)| *number);
+    devices
+        .into_iter()
+        .map(|(_, name, path)| (name, path))
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn devices() -> Vec<(String, PathBuf)> {
+    Vec::new()
+}
+
+/// Opens the camera at `path`, and captures from it on a new thread until `stopped` is set.
+#[cfg(target_os = "linux")]
+fn start_capture(
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    fps: f64,
+    frame: Arc<Mutex<Option<CameraFrame>>>,
+    stopped: Arc<AtomicBool>,
+) -> Result<(), CameraError> {
+    use std::io;
+    use std::time::Duration;
+    use v4l::buffer::Type;
+    use v4l::format::FourCC;
+    use v4l::io::traits::CaptureStream;
+    use v4l::prelude::*;
+    use v4l::video::capture::Parameters;
+    use v4l::video::Capture;
+
+    let backend_error = |e: io::Error| CameraError::Backend(e.to_string());
+    let device = Device::with_path(path).map_err(backend_error)?;
+
+    // The camera picks the mode closest to the one that was asked for.
+    let yuyv = FourCC::new(b"YUYV");
+    let mut format = device.format().map_err(backend_error)?;
+    format.width = width;
+    format.height = height;
+    format.fourcc = yuyv;
+    let format = device.set_format(&format).map_err(backend_error)?;
+    if format.fourcc != yuyv {
+        return Err(CameraError::Backend(format!(
+            "Unsupported pixel format {}",
+            format.fourcc
+        )));
+    }
+    // Not every camera lets the frame rate be chosen.
+    let fps = fps.round().clamp(1.0, 120.0) as u32;
+    if let Err(e) = device.set_params(&Parameters::with_fps(fps)) {
+        tracing::warn!("Couldn't set the frame rate of the camera: {}", e);
+    }
+
+    let mut stream =
+        MmapStream::with_buffers(&device, Type::VideoCapture, 4).map_err(backend_error)?;
+    // Wake up regularly to notice when the capture is stopped.
+    stream.set_timeout(Duration::from_millis(500));
+    std::thread::Builder::new()
+        .name("camera".to_string())
+        .spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match stream.next() {
+                    Ok((data, _)) => {
+                        let rgba = yuyv_to_rgba(data, format.width, format.height, format.stride);
+                        *frame.lock().expect("Camera frame lock poisoned") = Some(CameraFrame {
+                            width: format.width,
+                            height: format.height,
+                            rgba,
+                        });
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+                    Err(e) => {
+                        tracing::error!("Couldn't capture from the camera: {}", e);
+                        break;
+                    }
+                }
+            }
+        })
+        .map_err(backend_error)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn start_capture(
+    _path: PathBuf,
+    _width: u32,
+    _height: u32,
+    _fps: f64,
+    _frame: Arc<Mutex<Option<CameraFrame>>>,
+    _stopped: Arc<AtomicBool>,
+) -> Result<(), CameraError> {
+    Err(CameraError::Unsupported)
+}
+
+/// Converts a YUYV image, which stores two pixels as 4 bytes of Y0 U Y1 V, to opaque RGBA.
+///
+/// `stride` is the length of each row in bytes. Rows missing from `data` are left black.
+#[cfg(target_os = "linux")]
+fn yuyv_to_rgba(data: &[u8], width: u32, height: u32, stride: u32) -> Vec<u8> {
+    // BT.601 limited range, in fixed point with 8 fractional bits.
+    fn pixel(y: u8, u: u8, v: u8) -> [u8; 4] {
+        let c = 298 * (i32::from(y) - 16);
+        let d = i32::from(u) - 128;
+        let e = i32::from(v) - 128;
+        let clamp = |value: i32| ((value + 128) >> 8).clamp(0, 255) as u8;
+        [
+            clamp(c + 409 * e),
+            clamp(c - 100 * d - 208 * e),
+            clamp(c + 516 * d),
+            255,
+        ]
+    }
+
+    let (width, height, stride) = (width as usize, height as usize, stride as usize);
+    let mut rgba = vec![0; width * height * 4];
+    if width == 0 {
+        return rgba;
+    }
+    for (row, output) in rgba.chunks_exact_mut(width * 4).enumerate() {
+        let input = match data.get(row * stride..row * stride + width * 2) {
+            Some(input) => input,
+            None => break,
+        };
+        for (pixels, output) in input.chunks_exact(4).zip(output.chunks_exact_mut(8)) {
+            let [y0, u, y1, v] = [pixels[0], pixels[1], pixels[2], pixels[3]];
+            output[..4].copy_from_slice(&pixel(y0, u, v));
+            output[4..].copy_from_slice(&pixel(y1, u, v));
+        }
+    }
+    rgba
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yuyv_conversion() {
+        // Black and white, then a bright and a dark red, with padding at the end of each row.
+        let data = [
+            16, 128, 235, 128, 0, 0, //
+            81, 90, 41, 240, 0, 0,
+        ];
+        let rgba = yuyv_to_rgba(&data, 2, 2, 6);
+        assert_eq!(&rgba[..8], &[0, 0, 0, 255, 255, 255, 255, 255]);
+        // Both pixels of a pair share their color, so only their luma differs.
+        assert_eq!(&rgba[8..], &[255, 0, 0, 255, 208, 0, 0, 255]);
+
+        // Missing rows stay black.
+        assert_eq!(yuyv_to_rgba(&data[..6], 2, 2, 6)[8..], [0; 8]);
+    }
+}
@@ -0,0 +1,127 @@
+//! The context menu of the player, made of the items of the movie followed by our own.
+
+use ruffle_core::context_menu::ContextMenuItem;
+use winit::window::Window;
+
+/// What to do when an entry of the context menu is chosen.
+#[derive(Clone, Copy)]
+pub enum ContextMenuAction {
+    /// Runs the item of the movie at this index, with `Player::run_context_menu_callback`.
+    Movie(usize),
+    ToggleFullscreen,
+    CompatibilityReport,
+}
+
+pub struct ContextMenuEntry {
+    pub caption: String,
+    pub enabled: bool,
+    pub checked: bool,
+    pub separator_before: bool,
+    pub action: ContextMenuAction,
+}
+
+/// Lists the entries of the context menu: the items prepared by the movie, then ours.
+pub fn entries(movie_items: Vec<ContextMenuItem>, is_fullscreen: bool) -> Vec<ContextMenuEntry> {
+    let mut entries: Vec<_> = movie_items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| ContextMenuEntry {
+            caption: item.caption,
+            enabled: item.enabled,
+            checked: item.checked,
+            separator_before: item.separator_before,
+            action: ContextMenuAction::Movie(index),
+        })
+        .collect();
+    entries.push(ContextMenuEntry {
+        caption: if is_fullscreen {
+            "Exit fullscreen".to_string()
+        } else {
+            "Enter fullscreen".to_string()
+        },
+        enabled: true,
+        checked: false,
+        separator_before: true,
+        action: ContextMenuAction::ToggleFullscreen,
+    });
+    entries.push(ContextMenuEntry {
+        caption: "Show compatibility report".to_string(),
+        enabled: true,
+        checked: false,
+        separator_before: false,
+        action: ContextMenuAction::CompatibilityReport,
+    });
+    entries
+}
+
+/// Whether context menus can be shown on this platform.
+///
+/// winit has no popup menus, so only the native menus of Windows are used for now.
+pub fn is_supported() -> bool {
+    cfg!(windows)
+}
+
+/// Shows the context menu at the mouse position, and waits for the user to choose an entry
+/// or to dismiss the menu.
+pub fn show(window: &Window, entries: &[ContextMenuEntry]) -> Option<ContextMenuAction> {
+    show_popup_menu(window, entries).map(|index| entries[index].action)
+}
+
+#[cfg(windows)]
+fn show_popup_menu(window: &Window, entries: &[ContextMenuEntry]) -> Option<usize> {
+    use std::ffi::OsStr;
+    use std::iter::once;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr::null;
+    use winapi::shared::windef::{HWND, POINT};
+    use winapi::um::winuser::{
+        AppendMenuW, CreatePopupMenu, DestroyMenu, GetCursorPos, TrackPopupMenu, MF_CHECKED,
+        MF_GRAYED, MF_SEPARATOR, MF_STRING, TPM_RETURNCMD, TPM_RIGHTBUTTON,
+    };
+    use winit::platform::windows::WindowExtWindows;
+
+    unsafe {
+        let menu = CreatePopupMenu();
+        if menu.is_null() {
+            return None;
+        }
+        for (index, entry) in entries.iter().enumerate() {
+            if entry.separator_before && index > 0 {
+                AppendMenuW(menu, MF_SEPARATOR, 0, null());
+            }
+            let mut flags = MF_STRING;
+            if !entry.enabled {
+                flags |= MF_GRAYED;
+            }
+            if entry.checked {
+                flags |= MF_CHECKED;
+            }
+            let caption: Vec<u16> = OsStr::new(&entry.caption)
+                .encode_wide()
+                .chain(once(0))
+                .collect();
+            // Item IDs start at 1, since `TrackPopupMenu` returns 0 when the menu is dismissed.
+            AppendMenuW(menu, flags, index + 1, caption.as_ptr());
+        }
+
+        let mut position = POINT { x: 0, y: 0 };
+        GetCursorPos(&mut position);
+        let chosen = TrackPopupMenu(
+            menu,
+            TPM_RETURNCMD | TPM_RIGHTBUTTON,
+            position.x,
+            position.y,
+            0,
+            window.hwnd() as HWND,
+            null(),
+        );
+        DestroyMenu(menu);
+
+        (chosen > 0).then(|| chosen as usize - 1)
+    }
+}
+
+#[cfg(not(windows))]
+fn show_popup_menu(_window: &Window, _entries: &[ContextMenuEntry]) -> Option<usize> {
+    None
+}
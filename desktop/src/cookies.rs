@@ -0,0 +1,478 @@
+//! Keeps the cookies set by servers, so that logins survive between requests and runs.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use publicsuffix::{List, Psl};
+use std::fs;
+use std::path::PathBuf;
+use url::Url;
+
+/// A cookie set by a server, as described by RFC 6265.
+#[derive(Clone, Debug)]
+struct Cookie {
+    name: String,
+    value: String,
+
+    /// The domain the cookie is sent to, without a leading dot.
+    domain: String,
+
+    /// Whether the cookie is only sent to `domain` itself, and not to its subdomains.
+    host_only: bool,
+
+    path: String,
+
+    /// Whether the cookie is only sent over HTTPS.
+    secure: bool,
+
+    /// When the cookie expires, in seconds since the Unix epoch, or `None` if it only lasts for
+    /// the current session.
+    expires: Option<i64>,
+}
+
+impl Cookie {
+    /// Parses a `Set-Cookie` header received in response to a request to `url`.
+    fn parse(header: &str, url: &Url, now: i64) -> Option<Self> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut attributes = header.split(';');
+        let (name, value) = attributes.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Self {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url),
+            secure: false,
+            expires: None,
+        };
+
+        let mut max_age = None;
+        for attribute in attributes {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    // Servers may only set cookies for themselves and their parent domains.
+                    if !domain_matches(&host, &domain) {
+                        return None;
+                    }
+                    // Nor may they set them for everyone under a public suffix, such as `com`,
+                    // unless they are that suffix themselves.
+                    if is_public_suffix(&domain) {
+                        if domain != host {
+                            return None;
+                        }
+                        continue;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    if let Some(expires) = parse_date(value) {
+                        cookie.expires = Some(expires);
+                    }
+                }
+                _ => {}
+            }
+        }
+        // `Max-Age` takes precedence over `Expires`.
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(now.saturating_add(max_age));
+        }
+        Some(cookie)
+    }
+
+    /// Parses a line of a Netscape `cookies.txt` file.
+    fn from_line(line: &str) -> Option<Self> {
+        if line.starts_with('#') {
+            return None;
+        }
+        let mut fields = line.splitn(7, '\t');
+        let domain = fields.next()?;
+        let include_subdomains = fields.next()? == "TRUE";
+        let path = fields.next()?;
+        let secure = fields.next()? == "TRUE";
+        let expires = fields.next()?.parse::<i64>().ok()?;
+        let name = fields.next()?;
+        let value = fields.next()?;
+        Some(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.trim_start_matches('.').to_string(),
+            host_only: !include_subdomains,
+            path: path.to_string(),
+            secure,
+            expires: (expires != 0).then_some(expires),
+        })
+    }
+
+    fn to_line(&self) -> String {
+        let flag = |value| if value { "TRUE" } else { "FALSE" };
+        format!(
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if self.host_only { "" } else { "." },
+            self.domain,
+            flag(!self.host_only),
+            self.path,
+            flag(self.secure),
+            self.expires.unwrap_or(0),
+            self.name,
+            self.value
+        )
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.map_or(false, |expires| expires <= now)
+    }
+
+    /// Whether this cookie should be sent along with a request to `url`.
+    fn matches(&self, url: &Url) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_ascii_lowercase(),
+            None => return false,
+        };
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+        domain_ok
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .map_or(false, |prefix| prefix.ends_with('.'))
+}
+
+/// The Public Suffix List, which names the domains under which anyone may register their own,
+/// such as `co.uk` or `github.io`.
+static PUBLIC_SUFFIXES: Lazy<List> = Lazy::new(|| {
+    List::from_bytes(include_bytes!("../assets/public_suffix_list.dat"))
+        .expect("Bundled Public Suffix List should be valid")
+});
+
+/// Whether `domain` is a public suffix, under which anyone may register a domain.
+///
+/// Every domain of a single label, such as `com` or `localhost`, counts as one as well.
+fn is_public_suffix(domain: &str) -> bool {
+    !domain.contains('.')
+        || PUBLIC_SUFFIXES
+            .suffix(domain.as_bytes())
+            .map_or(false, |suffix| {
+                suffix.is_known() && suffix.as_bytes() == domain.as_bytes()
+            })
+}
+
+/// Whether a request for `request_path` falls under the cookie path `cookie_path`.
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => cookie_path.ends_with('/') || rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// The path of a cookie set without a `Path` attribute: the directory of the request.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => url.path()[..index].to_string(),
+    }
+}
+
+/// Parses the date of an `Expires` attribute, such as `Wed, 21 Oct 2015 07:28:00 GMT`.
+fn parse_date(date: &str) -> Option<i64> {
+    // Older servers separate the parts of the date with dashes.
+    let date = date.replace('-', " ");
+    DateTime::parse_from_rfc2822(&date)
+        .ok()
+        .map(|date| date.timestamp())
+}
+
+/// All cookies received so far, saved in the Netscape `cookies.txt` format.
+pub struct CookieJar {
+    path: Option<PathBuf>,
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    /// Loads the cookies saved by earlier runs.
+    ///
+    /// With `fresh_session`, the cookies that only last for a session are forgotten, as if the
+    /// browser had been restarted.
+    pub fn load(fresh_session: bool) -> Self {
+        let path = dirs::data_local_dir().map(|dir| dir.join("ruffle").join("cookies.txt"));
+        let now = Utc::now().timestamp();
+        let cookies = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|data| {
+                data.lines()
+                    .filter_map(Cookie::from_line)
+                    .filter(|cookie| !cookie.is_expired(now))
+                    .filter(|cookie| !fresh_session || cookie.expires.is_some())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let jar = Self { path, cookies };
+        if fresh_session {
+            jar.save();
+        }
+        jar
+    }
+
+    /// Returns the value of the `Cookie` header to send along with a request to `url`.
+    pub fn header(&self, url: &Url) -> Option<String> {
+        let now = Utc::now().timestamp();
+        let mut cookies: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now) && cookie.matches(url))
+            .collect();
+        if cookies.is_empty() {
+            return None;
+        }
+
+        // Cookies with longer paths are listed first.
+        cookies.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+        let pairs: Vec<String> = cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect();
+        Some(pairs.join("; "))
+    }
+
+    /// Stores the cookies of the `Set-Cookie` headers received in response to a request to `url`.
+    pub fn store<'a>(&mut self, url: &Url, headers: impl Iterator<Item = &'a str>) {
+        let now = Utc::now().timestamp();
+        let mut changed = false;
+        for header in headers {
+            let cookie = match Cookie::parse(header, url, now) {
+                Some(cookie) => cookie,
+                None => continue,
+            };
+            self.cookies.retain(|existing| {
+                existing.name != cookie.name
+                    || existing.domain != cookie.domain
+                    || existing.path != cookie.path
+            });
+            // An expiry date in the past deletes the cookie.
+            if !cookie.is_expired(now) {
+                self.cookies.push(cookie);
+            }
+            changed = true;
+        }
+        if changed {
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let mut data = String::from("# Netscape HTTP Cookie File\n");
+        for cookie in &self.cookies {
+            data.push_str(&cookie.to_line());
+            data.push('\n');
+        }
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                tracing::warn!("Unable to create cookie dir: {}", e);
+                return;
+            }
+        }
+        if let Err(e) = fs::write(path, data) {
+            tracing::warn!("Unable to save cookies: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_000_000_000;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    fn parse(header: &str, request_url: &str) -> Option<Cookie> {
+        Cookie::parse(header, &url(request_url), NOW)
+    }
+
+    #[test]
+    fn parse_attributes() {
+        let cookie = parse(
+            "session=abc; Path=/game; Secure; Max-Age=60",
+            "https://example.com/login/form",
+        )
+        .unwrap();
+        assert_eq!(cookie.name, "session");
+        assert_eq!(cookie.value, "abc");
+        assert_eq!(cookie.domain, "example.com");
+        assert!(cookie.host_only);
+        assert_eq!(cookie.path, "/game");
+        assert!(cookie.secure);
+        assert_eq!(cookie.expires, Some(NOW + 60));
+
+        // Without a path, the cookie is for the directory of the request.
+        let cookie = parse("a=1", "http://example.com/login/form").unwrap();
+        assert_eq!(cookie.path, "/login");
+        assert_eq!(cookie.expires, None);
+        let cookie = parse("a=1", "http://example.com/form").unwrap();
+        assert_eq!(cookie.path, "/");
+    }
+
+    #[test]
+    fn parse_expiry() {
+        let cookie = parse(
+            "a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            "http://example.com/",
+        )
+        .unwrap();
+        assert_eq!(cookie.expires, Some(1445412480));
+
+        // `Max-Age` wins, whatever the order.
+        let cookie = parse(
+            "a=1; Max-Age=10; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            "http://example.com/",
+        )
+        .unwrap();
+        assert_eq!(cookie.expires, Some(NOW + 10));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("no value", "http://example.com/").is_none());
+        assert!(parse("=value", "http://example.com/").is_none());
+        assert!(parse("a=1", "file:///game.swf").is_none());
+    }
+
+    #[test]
+    fn parse_domain() {
+        let cookie = parse("a=1; Domain=.Example.com", "http://www.example.com/").unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert!(!cookie.host_only);
+
+        // Servers can't set cookies for other domains, or for their subdomains.
+        assert!(parse("a=1; Domain=other.com", "http://www.example.com/").is_none());
+        assert!(parse("a=1; Domain=a.www.example.com", "http://www.example.com/").is_none());
+        assert!(parse("a=1; Domain=ample.com", "http://example.com/").is_none());
+    }
+
+    #[test]
+    fn parse_public_suffix() {
+        assert!(parse("a=1; Domain=com", "http://example.com/").is_none());
+        assert!(parse("a=1; Domain=.co.uk", "http://example.co.uk/").is_none());
+
+        let cookie = parse("a=1; Domain=example.co.uk", "http://www.example.co.uk/").unwrap();
+        assert_eq!(cookie.domain, "example.co.uk");
+        assert!(!cookie.host_only);
+
+        // Hosting services are public suffixes too, so their users can't share cookies.
+        assert!(parse("a=1; Domain=github.io", "http://game.github.io/").is_none());
+        assert!(parse("a=1; Domain=blogspot.com", "http://game.blogspot.com/").is_none());
+        let cookie = parse("a=1; Domain=game.github.io", "http://www.game.github.io/").unwrap();
+        assert_eq!(cookie.domain, "game.github.io");
+
+        // A server that is a public suffix itself only gets a cookie for itself.
+        let cookie = parse("a=1; Domain=co.uk", "http://co.uk/").unwrap();
+        assert_eq!(cookie.domain, "co.uk");
+        assert!(cookie.host_only);
+    }
+
+    #[test]
+    fn matches() {
+        let host_only = parse("a=1", "http://example.com/").unwrap();
+        assert!(host_only.matches(&url("http://example.com/game.swf")));
+        assert!(host_only.matches(&url("http://EXAMPLE.com/")));
+        assert!(!host_only.matches(&url("http://www.example.com/")));
+        assert!(!host_only.matches(&url("http://other.com/")));
+
+        let domain = parse("a=1; Domain=example.com", "http://example.com/").unwrap();
+        assert!(domain.matches(&url("http://example.com/")));
+        assert!(domain.matches(&url("http://www.example.com/")));
+        assert!(!domain.matches(&url("http://notexample.com/")));
+
+        let secure = parse("a=1; Secure", "https://example.com/").unwrap();
+        assert!(secure.matches(&url("https://example.com/")));
+        assert!(!secure.matches(&url("http://example.com/")));
+
+        let path = parse("a=1; Path=/game", "http://example.com/").unwrap();
+        assert!(path.matches(&url("http://example.com/game/level1")));
+        assert!(!path.matches(&url("http://example.com/other")));
+    }
+
+    #[test]
+    fn path_matching() {
+        assert!(path_matches("/", "/"));
+        assert!(path_matches("/game", "/"));
+        assert!(path_matches("/game", "/game"));
+        assert!(path_matches("/game/", "/game"));
+        assert!(path_matches("/game/level1", "/game"));
+        assert!(path_matches("/game/level1", "/game/"));
+        assert!(!path_matches("/games", "/game"));
+        assert!(!path_matches("/", "/game"));
+        assert!(!path_matches("/Game", "/game"));
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(
+            parse_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(1445412480)
+        );
+        // Older servers separate the parts of the date with dashes.
+        assert_eq!(
+            parse_date("Wed, 21-Oct-2015 07:28:00 GMT"),
+            Some(1445412480)
+        );
+        assert_eq!(
+            parse_date("Tue, 01 Jan 2030 00:00:00 +0000"),
+            Some(1893456000)
+        );
+        assert_eq!(parse_date("tomorrow"), None);
+    }
+
+    #[test]
+    fn lines() {
+        let cookie = parse(
+            "a=1; Domain=example.com; Path=/game; Secure; Max-Age=60",
+            "https://example.com/",
+        )
+        .unwrap();
+        let line = cookie.to_line();
+        assert_eq!(
+            line,
+            format!(".example.com\tTRUE\t/game\tTRUE\t{}\ta\t1", NOW + 60)
+        );
+
+        let read = Cookie::from_line(&line).unwrap();
+        assert_eq!(read.domain, "example.com");
+        assert!(!read.host_only);
+        assert_eq!(read.path, "/game");
+        assert!(read.secure);
+        assert_eq!(read.expires, Some(NOW + 60));
+        assert_eq!((read.name.as_str(), read.value.as_str()), ("a", "1"));
+
+        assert!(Cookie::from_line("# Netscape HTTP Cookie File").is_none());
+    }
+}
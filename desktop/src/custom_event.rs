@@ -7,4 +7,29 @@ pub enum RuffleEvent {
 
     /// Indicates that an asynchronous SWF metadata load has been completed.
     OnMetadata(ruffle_core::swf::HeaderExt),
+
+    /// Indicates that the user has answered a dialog.
+    DialogResponse {
+        id: ruffle_core::backend::dialog::DialogId,
+        result: ruffle_core::backend::dialog::DialogResult,
+    },
+
+    /// Indicates that more of the root movie was downloaded.
+    RootMovieProgress {
+        /// The number of bytes received so far.
+        loaded: u64,
+
+        /// The size of the movie, if the server declared it.
+        total: Option<u64>,
+    },
+
+    /// Indicates that the watched movie file was modified on disk.
+    MovieChanged,
+
+    /// Indicates that the movie asked to close the player with `fscommand("quit")`.
+    Quit,
+
+    /// Indicates that the movie asked with `fscommand("trapallkeys")` for the keyboard
+    /// shortcuts of the player, such as Escape to leave fullscreen, to be left to the movie.
+    TrapAllKeys(bool),
 }
@@ -0,0 +1,177 @@
+//! Dialogs shown on behalf of the movie, using the native dialogs of the system.
+
+use crate::custom_event::RuffleEvent;
+use futures::executor::block_on;
+use futures::future::{self, BoxFuture};
+use rfd::{
+    AsyncFileDialog, AsyncMessageDialog, FileDialog, FileHandle, MessageButtons, MessageDialog,
+    MessageLevel,
+};
+use ruffle_core::backend::dialog::{Dialog, DialogBackend, DialogId, DialogResult, PickedFile};
+use ruffle_core::compatibility::CompatibilityReport;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::UNIX_EPOCH;
+use tracing::{error, warn};
+use winit::event_loop::EventLoopProxy;
+
+pub struct DesktopDialogBackend {
+    event_loop: EventLoopProxy<RuffleEvent>,
+}
+
+impl DesktopDialogBackend {
+    pub fn new(event_loop: EventLoopProxy<RuffleEvent>) -> Self {
+        Self { event_loop }
+    }
+}
+
+impl DialogBackend for DesktopDialogBackend {
+    fn show_dialog(&mut self, id: DialogId, dialog: Dialog) {
+        let response: BoxFuture<'static, DialogResult> = match dialog {
+            Dialog::Alert { title, message } => {
+                let shown = message_dialog(&title, &message, MessageButtons::Ok).show();
+                Box::pin(async move {
+                    shown.await;
+                    DialogResult::Ok
+                })
+            }
+            Dialog::Confirm { title, message } => {
+                let shown = message_dialog(&title, &message, MessageButtons::YesNo).show();
+                Box::pin(async move {
+                    if shown.await {
+                        DialogResult::Ok
+                    } else {
+                        DialogResult::Cancelled
+                    }
+                })
+            }
+            Dialog::Prompt { .. } => {
+                // rfd has no dialog to enter text in.
+                warn!("Text prompts are not supported on desktop");
+                Box::pin(future::ready(DialogResult::Cancelled))
+            }
+            Dialog::OpenFiles { filters, multiple } => {
+                let mut file_dialog = AsyncFileDialog::new();
+                for filter in filters
+                    .iter()
+                    .filter(|filter| !filter.extensions.is_empty())
+                {
+                    let extensions: Vec<_> = filter.extensions.iter().map(String::as_str).collect();
+                    file_dialog = file_dialog.add_filter(&filter.description, &extensions);
+                }
+                let picked: BoxFuture<'static, Option<Vec<FileHandle>>> = if multiple {
+                    Box::pin(file_dialog.pick_files())
+                } else {
+                    let picked = file_dialog.pick_file();
+                    Box::pin(async move { picked.await.map(|file| vec![file]) })
+                };
+                Box::pin(async move {
+                    let files: Vec<_> = picked
+                        .await
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|file| read_file(file.path()))
+                        .collect();
+                    if files.is_empty() {
+                        DialogResult::Cancelled
+                    } else {
+                        DialogResult::Files(files)
+                    }
+                })
+            }
+            Dialog::SaveFile { default_name, data } => {
+                let picked = AsyncFileDialog::new()
+                    .set_file_name(&default_name)
+                    .save_file();
+                Box::pin(async move {
+                    let file = match picked.await {
+                        Some(file) => file,
+                        None => return DialogResult::Cancelled,
+                    };
+                    let path = file.path();
+                    match fs::write(path, data) {
+                        Ok(()) => DialogResult::Saved {
+                            name: file_name(path),
+                        },
+                        Err(e) => {
+                            error!("Couldn't save {}: {}", path.display(), e);
+                            DialogResult::Cancelled
+                        }
+                    }
+                })
+            }
+        };
+
+        // The movie keeps running while the dialog is open, and gets the response once the
+        // user closes it.
+        let event_loop = self.event_loop.clone();
+        thread::spawn(move || {
+            let result = block_on(response);
+            if event_loop
+                .send_event(RuffleEvent::DialogResponse { id, result })
+                .is_err()
+            {
+                error!("Couldn't report dialog response: event loop closed");
+            }
+        });
+    }
+}
+
+fn message_dialog(title: &str, message: &str, buttons: MessageButtons) -> AsyncMessageDialog {
+    AsyncMessageDialog::new()
+        .set_level(MessageLevel::Info)
+        .set_title(&format!("Ruffle - {title}"))
+        .set_description(message)
+        .set_buttons(buttons)
+}
+
+/// Shows the compatibility report of the movie, offering to save it as JSON.
+pub fn show_compatibility_report(report: &CompatibilityReport) {
+    let save = MessageDialog::new()
+        .set_level(MessageLevel::Info)
+        .set_title("Ruffle - Compatibility report")
+        .set_description(&format!("{report}\nSave this report to a file?"))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    if !save {
+        return;
+    }
+
+    if let Some(path) = FileDialog::new()
+        .set_file_name("compatibility-report.json")
+        .add_filter("JSON", &["json"])
+        .save_file()
+    {
+        if let Err(e) = fs::write(&path, report.to_json()) {
+            error!("Couldn't save {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Reads a file picked by the user.
+fn read_file(path: &Path) -> Option<PickedFile> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            error!("Couldn't read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| modified.as_secs_f64() * 1000.0);
+    Some(PickedFile {
+        name: file_name(path),
+        data,
+        modified,
+    })
+}
@@ -0,0 +1,120 @@
+//! `ExternalInterface` on desktop, where there is no page around the movie to call into.
+//!
+//! Calls are handed to hooks in the XML format of Flash Player's external API, the same way the
+//! ActiveX control handed them to the application hosting it.
+
+use ruffle_core::context::UpdateContext;
+use ruffle_core::external::{
+    invoke_to_xml, ExternalInterfaceMethod, ExternalInterfaceProvider, Value as ExternalValue,
+};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+/// Services the `ExternalInterface.call`s of a movie.
+pub trait ExternalInterfaceHook {
+    /// Answers an `<invoke>` request with the XML encoding of the return value, or `None` if
+    /// this hook doesn't handle the call.
+    fn call(&self, request: &str) -> Option<String>;
+
+    /// Tells the hook that the movie exposed a callback with `ExternalInterface.addCallback`.
+    fn on_callback_available(&self, _name: &str) {}
+}
+
+/// Runs a command for every call, writing the request to its standard input and reading the
+/// return value from its standard output. Empty output leaves the call to the other hooks.
+pub struct CommandHook {
+    command: PathBuf,
+}
+
+impl CommandHook {
+    pub fn new(command: PathBuf) -> Self {
+        Self { command }
+    }
+}
+
+impl ExternalInterfaceHook for CommandHook {
+    fn call(&self, request: &str) -> Option<String> {
+        let mut child = match Command::new(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::warn!("Unable to run ExternalInterface command: {}", e);
+                return None;
+            }
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(request.as_bytes()) {
+                tracing::warn!("Unable to pass ExternalInterface request: {}", e);
+            }
+        }
+
+        let output = match child.wait_with_output() {
+            Ok(output) => output,
+            Err(e) => {
+                tracing::warn!("ExternalInterface command failed: {}", e);
+                return None;
+            }
+        };
+        let response = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Some(response).filter(|response| !response.is_empty())
+    }
+}
+
+/// Makes `ExternalInterface` available to the movie, servicing calls with the given hooks.
+pub struct DesktopExternalInterfaceProvider {
+    hooks: Rc<Vec<Box<dyn ExternalInterfaceHook>>>,
+}
+
+impl DesktopExternalInterfaceProvider {
+    pub fn new(hooks: Vec<Box<dyn ExternalInterfaceHook>>) -> Self {
+        Self {
+            hooks: Rc::new(hooks),
+        }
+    }
+}
+
+impl ExternalInterfaceProvider for DesktopExternalInterfaceProvider {
+    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        Some(Box::new(HookMethod {
+            hooks: self.hooks.clone(),
+            name: name.to_string(),
+        }))
+    }
+
+    fn on_callback_available(&self, name: &str) {
+        for hook in self.hooks.iter() {
+            hook.on_callback_available(name);
+        }
+    }
+
+    fn on_fs_command(&self, _command: &str, _args: &str) -> bool {
+        false
+    }
+}
+
+struct HookMethod {
+    hooks: Rc<Vec<Box<dyn ExternalInterfaceHook>>>,
+    name: String,
+}
+
+impl ExternalInterfaceMethod for HookMethod {
+    fn call(&self, _context: &mut UpdateContext<'_, '_>, args: &[ExternalValue]) -> ExternalValue {
+        let request = invoke_to_xml(&self.name, args);
+        match self.hooks.iter().find_map(|hook| hook.call(&request)) {
+            Some(response) => ExternalValue::from_xml(&response).unwrap_or_else(|| {
+                tracing::warn!("Invalid ExternalInterface return value: {}", response);
+                ExternalValue::Null
+            }),
+            None => {
+                tracing::info!("Unhandled ExternalInterface call: {}", request);
+                ExternalValue::Null
+            }
+        }
+    }
+}
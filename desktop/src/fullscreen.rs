@@ -0,0 +1,76 @@
+//! Choosing how and on which monitor the window goes fullscreen.
+
+use winit::monitor::{MonitorHandle, VideoMode};
+use winit::window::{Fullscreen, Window};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FullscreenMode {
+    /// A borderless window covering the whole monitor.
+    #[default]
+    Borderless,
+
+    /// Exclusive use of the monitor, at its native resolution and highest refresh rate.
+    Exclusive,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FullscreenSettings {
+    /// The index of the monitor to use, in the order the system lists them.
+    /// The monitor the window is on is used if this is `None`.
+    pub monitor: Option<usize>,
+
+    pub mode: FullscreenMode,
+}
+
+impl FullscreenSettings {
+    /// Returns the fullscreen state to give to `window`.
+    pub fn fullscreen(&self, window: &Window) -> Fullscreen {
+        let monitor = match self.monitor {
+            Some(index) => window.available_monitors().nth(index).or_else(|| {
+                let names: Vec<_> = window
+                    .available_monitors()
+                    .enumerate()
+                    .map(|(i, monitor)| format!("{i}: {}", monitor.name().unwrap_or_default()))
+                    .collect();
+                tracing::warn!(
+                    "No monitor with index {}, using the current one. Available monitors are: {}",
+                    index,
+                    names.join(", ")
+                );
+                window.current_monitor()
+            }),
+            None => window.current_monitor(),
+        };
+
+        match (self.mode, monitor) {
+            (FullscreenMode::Exclusive, Some(monitor)) => match best_video_mode(&monitor) {
+                Some(video_mode) => Fullscreen::Exclusive(video_mode),
+                None => {
+                    tracing::warn!("Monitor has no video modes, using borderless fullscreen");
+                    Fullscreen::Borderless(Some(monitor))
+                }
+            },
+            (_, monitor) => Fullscreen::Borderless(monitor),
+        }
+    }
+}
+
+/// Picks the video mode at the monitor's current resolution with the highest refresh rate,
+/// or the largest video mode if the current resolution isn't listed.
+fn best_video_mode(monitor: &MonitorHandle) -> Option<VideoMode> {
+    let size = monitor.size();
+    monitor
+        .video_modes()
+        .filter(|video_mode| video_mode.size() == size)
+        .max_by_key(|video_mode| (video_mode.refresh_rate_millihertz(), video_mode.bit_depth()))
+        .or_else(|| {
+            monitor.video_modes().max_by_key(|video_mode| {
+                let size = video_mode.size();
+                (
+                    u64::from(size.width) * u64::from(size.height),
+                    video_mode.refresh_rate_millihertz(),
+                    video_mode.bit_depth(),
+                )
+            })
+        })
+}
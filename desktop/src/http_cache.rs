@@ -0,0 +1,364 @@
+//! Keeps the responses of HTTP servers on disk, so that large assets aren't downloaded again
+//! every session.
+
+use isahc::http::request::Builder as RequestBuilder;
+use isahc::http::StatusCode;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use url::Url;
+
+/// The headers that tell whether a cached response is still current.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    /// Reads the validators of a response, or returns `None` if it mustn't or can't be cached.
+    pub fn of_response<T>(response: &isahc::Response<T>) -> Option<Self> {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let no_store = header("Cache-Control").map_or(false, |value| {
+            value.to_ascii_lowercase().contains("no-store")
+        });
+        let validators = Self {
+            etag: header("ETag"),
+            last_modified: header("Last-Modified"),
+        };
+        let validated = validators.etag.is_some() || validators.last_modified.is_some();
+        (validated && !no_store).then_some(validators)
+    }
+
+    /// Asks the server to answer `304 Not Modified` if the cached response is still current.
+    pub fn add_to(&self, mut builder: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            builder = builder.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            builder = builder.header("If-Modified-Since", last_modified);
+        }
+        builder
+    }
+}
+
+/// A response kept in the cache.
+pub struct CachedResponse {
+    pub validators: Validators,
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    /// Returns the cached body if the server answered `304 Not Modified` to its validators.
+    pub fn revalidate<T>(self, response: &isahc::Response<T>) -> Option<Vec<u8>> {
+        (response.status() == StatusCode::NOT_MODIFIED).then_some(self.body)
+    }
+}
+
+/// The responses kept on disk, up to a total size.
+///
+/// Each response is stored in two files named after the hash of its URL: `.meta` holds the URL
+/// and the validators, one per line, and `.body` holds the body. The responses used least
+/// recently are removed first when the cache grows too large.
+pub struct HttpCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl HttpCache {
+    /// Opens the cache in Ruffle's cache directory, holding up to `max_size` bytes.
+    pub fn new(max_size: u64) -> Option<Self> {
+        let dir = dirs::cache_dir()?.join("ruffle").join("http");
+        Some(Self::with_dir(dir, max_size))
+    }
+
+    /// Opens the cache in `dir`, holding up to `max_size` bytes.
+    pub fn with_dir(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        match fs::remove_dir_all(&self.dir) {
+            Ok(()) => tracing::info!("Cleared HTTP cache at {}", self.dir.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Unable to clear HTTP cache: {}", e),
+        }
+    }
+
+    fn paths(&self, url: &Url) -> (PathBuf, PathBuf) {
+        let key = format!("{:016x}", fnv1a(url.as_str().as_bytes()));
+        (
+            self.dir.join(format!("{key}.meta")),
+            self.dir.join(format!("{key}.body")),
+        )
+    }
+
+    /// Returns the response cached for `url`, if any.
+    pub fn get(&self, url: &Url) -> Option<CachedResponse> {
+        let (meta_path, body_path) = self.paths(url);
+        let meta = fs::read_to_string(&meta_path).ok()?;
+        let mut lines = meta.lines();
+        // Different URLs may share a hash.
+        if lines.next()? != url.as_str() {
+            return None;
+        }
+        let non_empty = |line: &str| (!line.is_empty()).then(|| line.to_string());
+        let validators = Validators {
+            etag: lines.next().and_then(non_empty),
+            last_modified: lines.next().and_then(non_empty),
+        };
+        let body = fs::read(body_path).ok()?;
+
+        // Rewriting the metadata marks the response as recently used.
+        let _ = fs::write(meta_path, &meta);
+        Some(CachedResponse { validators, body })
+    }
+
+    /// Caches the response received from `url`, and makes room for it.
+    pub fn store(&self, url: &Url, validators: &Validators, body: &[u8]) {
+        if body.len() as u64 > self.max_size {
+            return;
+        }
+        if let Err(e) = fs::create_dir_all(&self.dir) {
+            tracing::warn!("Unable to create HTTP cache dir: {}", e);
+            return;
+        }
+
+        let (meta_path, body_path) = self.paths(url);
+        let meta = format!(
+            "{}\n{}\n{}\n",
+            url,
+            validators.etag.as_deref().unwrap_or_default(),
+            validators.last_modified.as_deref().unwrap_or_default()
+        );
+        // The body is written first, so that a metadata file always has a complete body.
+        if let Err(e) = fs::write(&body_path, body).and_then(|_| fs::write(&meta_path, meta)) {
+            tracing::warn!("Unable to save {} to HTTP cache: {}", url, e);
+            let _ = fs::remove_file(meta_path);
+            let _ = fs::remove_file(body_path);
+            return;
+        }
+        self.evict();
+    }
+
+    /// Removes the least recently used responses until the cache fits into its size.
+    fn evict(&self) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut responses: Vec<(SystemTime, PathBuf, u64)> = entries
+            .filter_map(|entry| {
+                let meta_path = entry.ok()?.path();
+                if meta_path.extension()? != "meta" {
+                    return None;
+                }
+                let used = fs::metadata(&meta_path).ok()?.modified().ok()?;
+                let size = fs::metadata(meta_path.with_extension("body"))
+                    .map(|metadata| metadata.len())
+                    .unwrap_or_default();
+                Some((used, meta_path, size))
+            })
+            .collect();
+
+        let mut total: u64 = responses.iter().map(|(_, _, size)| size).sum();
+        responses.sort_by_key(|(used, _, _)| *used);
+        for (_, meta_path, size) in responses {
+            if total <= self.max_size {
+                break;
+            }
+            let _ = fs::remove_file(meta_path.with_extension("body"));
+            let _ = fs::remove_file(meta_path);
+            total -= size;
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash of `data`, which stays the same across runs and Rust versions.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use isahc::http::Response;
+
+    /// A cache in a fresh directory, removed when the test ends.
+    struct TestCache(HttpCache);
+
+    impl TestCache {
+        fn new(name: &str, max_size: u64) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "ruffle-http-cache-test-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            Self(HttpCache::with_dir(dir, max_size))
+        }
+    }
+
+    impl Drop for TestCache {
+        fn drop(&mut self) {
+            self.0.clear();
+        }
+    }
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)]) -> isahc::Response<()> {
+        let mut builder = Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    fn validators(etag: Option<&str>, last_modified: Option<&str>) -> Validators {
+        Validators {
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn validators_of_response() {
+        assert_eq!(
+            Validators::of_response(&response(
+                200,
+                &[
+                    ("ETag", "\"v1\""),
+                    ("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+                ]
+            )),
+            Some(validators(
+                Some("\"v1\""),
+                Some("Wed, 21 Oct 2015 07:28:00 GMT")
+            ))
+        );
+        assert_eq!(
+            Validators::of_response(&response(200, &[("ETag", "\"v1\"")])),
+            Some(validators(Some("\"v1\""), None))
+        );
+
+        // Responses that can't be revalidated, or mustn't be stored, aren't cached.
+        assert_eq!(Validators::of_response(&response(200, &[])), None);
+        assert_eq!(
+            Validators::of_response(&response(
+                200,
+                &[("ETag", "\"v1\""), ("Cache-Control", "private, No-Store")]
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn validators_are_sent() {
+        let request = validators(Some("\"v1\""), Some("Wed, 21 Oct 2015 07:28:00 GMT"))
+            .add_to(isahc::Request::get("https://example.com/assets.swf"))
+            .body(())
+            .unwrap();
+        assert_eq!(request.headers()["If-None-Match"], "\"v1\"");
+        assert_eq!(
+            request.headers()["If-Modified-Since"],
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+
+        let request = Validators::default()
+            .add_to(isahc::Request::get("https://example.com/assets.swf"))
+            .body(())
+            .unwrap();
+        assert!(request.headers().is_empty());
+    }
+
+    #[test]
+    fn store_and_revalidate() {
+        let cache = TestCache::new("revalidate", 1024);
+        let assets = url("https://example.com/assets.swf");
+        assert!(cache.0.get(&assets).is_none());
+
+        cache
+            .0
+            .store(&assets, &validators(Some("\"v1\""), None), b"assets");
+        let cached = cache.0.get(&assets).unwrap();
+        assert_eq!(cached.validators, validators(Some("\"v1\""), None));
+        assert_eq!(cached.body, b"assets");
+
+        // Only `304 Not Modified` keeps the cached body.
+        assert_eq!(
+            cache
+                .0
+                .get(&assets)
+                .unwrap()
+                .revalidate(&response(304, &[])),
+            Some(b"assets".to_vec())
+        );
+        assert_eq!(
+            cache
+                .0
+                .get(&assets)
+                .unwrap()
+                .revalidate(&response(200, &[])),
+            None
+        );
+        assert_eq!(
+            cache
+                .0
+                .get(&assets)
+                .unwrap()
+                .revalidate(&response(404, &[])),
+            None
+        );
+
+        // A new response replaces the cached one.
+        cache.0.store(
+            &assets,
+            &validators(None, Some("Wed, 21 Oct 2015 07:28:00 GMT")),
+            b"new assets",
+        );
+        let cached = cache.0.get(&assets).unwrap();
+        assert_eq!(
+            cached.validators,
+            validators(None, Some("Wed, 21 Oct 2015 07:28:00 GMT"))
+        );
+        assert_eq!(cached.body, b"new assets");
+
+        assert!(cache.0.get(&url("https://example.com/other.swf")).is_none());
+
+        cache.0.clear();
+        assert!(cache.0.get(&assets).is_none());
+    }
+
+    #[test]
+    fn size_limit() {
+        let cache = TestCache::new("size-limit", 8);
+        let v1 = validators(Some("\"v1\""), None);
+
+        // A response larger than the whole cache isn't stored.
+        cache
+            .0
+            .store(&url("https://example.com/big"), &v1, b"123456789");
+        assert!(cache.0.get(&url("https://example.com/big")).is_none());
+
+        let urls: Vec<_> = (0..3)
+            .map(|i| url(&format!("https://example.com/{i}")))
+            .collect();
+        for url in &urls {
+            cache.0.store(url, &v1, b"1234");
+        }
+        let kept = urls.iter().filter(|url| cache.0.get(url).is_some()).count();
+        assert_eq!(kept, 2);
+    }
+}
@@ -0,0 +1,357 @@
+//! Passing `LocalConnection` messages between the Ruffle processes of this machine.
+//!
+//! Each connection is a directory in a directory that only the current user can access. The
+//! process owning a connection keeps an `owner` file in it up to date, and other processes drop
+//! their messages there as files.
+
+use anyhow::{Context, Error};
+use ruffle_core::backend::local_connection::{LocalConnectionBackend, LocalConnectionMessage};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How often an owner refreshes the `owner` files of its connections.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long after its last refresh a connection is considered abandoned, such as when its
+/// process crashed.
+const STALE_AFTER: Duration = Duration::from_secs(10);
+
+pub struct DiskLocalConnectionBackend {
+    base_path: PathBuf,
+
+    /// Identifies this backend in the `owner` files of its connections.
+    id: String,
+
+    /// The names of the connections owned by this backend.
+    connections: HashSet<String>,
+
+    /// The messages sent to this player's own connections, which don't need a trip to disk.
+    own_messages: Vec<LocalConnectionMessage>,
+
+    last_heartbeat: Instant,
+    next_message_id: u64,
+}
+
+/// Creates a directory and any missing parents, so that only the current user can access it.
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(path)?;
+
+    // The directory may have been created with other permissions before.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+impl DiskLocalConnectionBackend {
+    pub fn new() -> Result<Self, Error> {
+        // The runtime dir is private to the user. Where there is none, such as on Windows and
+        // macOS, the cache dir of the user is used instead.
+        let base_path = dirs::runtime_dir()
+            .or_else(dirs::cache_dir)
+            .context("Couldn't find a valid runtime or cache dir")?
+            .join("ruffle")
+            .join("local-connection");
+        Ok(Self::with_base_path(base_path))
+    }
+
+    /// Creates a backend that keeps its connections in the given directory.
+    fn with_base_path(base_path: PathBuf) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        Self {
+            base_path,
+            id: format!("{}-{}", std::process::id(), nanos),
+            connections: HashSet::new(),
+            own_messages: Vec::new(),
+            last_heartbeat: Instant::now(),
+            next_message_id: 0,
+        }
+    }
+
+    /// The directory of a connection. Names are hex-encoded, as they may contain characters
+    /// that aren't allowed in file names.
+    fn connection_path(&self, name: &str) -> PathBuf {
+        let encoded: String = name.bytes().map(|b| format!("{b:02x}")).collect();
+        self.base_path.join(encoded)
+    }
+
+    fn owner_path(&self, name: &str) -> PathBuf {
+        self.connection_path(name).join("owner")
+    }
+
+    /// Whether a connection is owned by a process that's still refreshing it.
+    fn is_owned(&self, name: &str) -> bool {
+        match fs::metadata(self.owner_path(name)).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified.elapsed().unwrap_or_default() < STALE_AFTER,
+            Err(_) => false,
+        }
+    }
+
+    fn write_owner(&self, name: &str) {
+        if let Err(e) = fs::write(self.owner_path(name), &self.id) {
+            tracing::warn!("Unable to refresh LocalConnection {}: {}", name, e);
+        }
+    }
+}
+
+impl Drop for DiskLocalConnectionBackend {
+    fn drop(&mut self) {
+        for name in std::mem::take(&mut self.connections) {
+            self.close(&name);
+        }
+    }
+}
+
+impl LocalConnectionBackend for DiskLocalConnectionBackend {
+    fn connect(&mut self, name: &str) -> bool {
+        if self.connections.contains(name) {
+            return false;
+        }
+
+        let path = self.connection_path(name);
+        if let Err(e) = create_private_dir(&self.base_path).and_then(|_| fs::create_dir_all(&path))
+        {
+            tracing::warn!("Unable to create LocalConnection dir {:?}: {}", path, e);
+            return false;
+        }
+
+        // Take over connections abandoned by processes that are gone.
+        let owner_path = self.owner_path(name);
+        if owner_path.exists() && !self.is_owned(name) {
+            let _ = fs::remove_file(&owner_path);
+        }
+
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&owner_path)
+        {
+            Ok(mut file) => {
+                if let Err(e) = file.write_all(self.id.as_bytes()) {
+                    tracing::warn!("Unable to write LocalConnection owner: {}", e);
+                }
+                self.connections.insert(name.to_string());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        self.connections.remove(name);
+        let owner_path = self.owner_path(name);
+        if fs::read(&owner_path).ok().as_deref() == Some(self.id.as_bytes()) {
+            let _ = fs::remove_file(owner_path);
+        }
+    }
+
+    fn send(&mut self, message: LocalConnectionMessage) -> bool {
+        if self.connections.contains(&message.connection_name) {
+            self.own_messages.push(message);
+            return true;
+        }
+        if !self.is_owned(&message.connection_name) {
+            return false;
+        }
+
+        // Messages are written under a temporary name first, so that the receiver never reads
+        // a partially written one.
+        let path = self.connection_path(&message.connection_name);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let file_name = format!("{nanos:032}-{}-{}", self.id, self.next_message_id);
+        self.next_message_id += 1;
+        let temp_path = path.join(format!("{file_name}.tmp"));
+        let result = fs::write(&temp_path, message.to_bytes())
+            .and_then(|_| fs::rename(&temp_path, path.join(format!("{file_name}.msg"))));
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Unable to send LocalConnection message: {}", e);
+                false
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Vec<LocalConnectionMessage> {
+        if self.last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+            for name in &self.connections {
+                self.write_owner(name);
+            }
+            self.last_heartbeat = Instant::now();
+        }
+
+        let mut messages = std::mem::take(&mut self.own_messages);
+        for name in &self.connections {
+            let entries = match fs::read_dir(self.connection_path(name)) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map_or(false, |ext| ext == "msg"))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let bytes = fs::read(&path);
+                let _ = fs::remove_file(&path);
+                match bytes
+                    .ok()
+                    .and_then(|bytes| LocalConnectionMessage::from_bytes(&bytes))
+                {
+                    Some(message) => messages.push(message),
+                    None => tracing::warn!("Unable to read LocalConnection message {:?}", path),
+                }
+            }
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A backend keeping its connections in a fresh directory, removed when the test ends.
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "ruffle-local-connection-test-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            Self(path)
+        }
+
+        fn backend(&self) -> DiskLocalConnectionBackend {
+            DiskLocalConnectionBackend::with_base_path(self.0.clone())
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn message(connection_name: &str, method_name: &str) -> LocalConnectionMessage {
+        LocalConnectionMessage {
+            connection_name: connection_name.to_string(),
+            method_name: method_name.to_string(),
+            sender_domain: "localhost".to_string(),
+            arguments: vec![0x02, 0x00, 0x02, b'h', b'i'],
+        }
+    }
+
+    #[test]
+    fn connections_are_exclusive() {
+        let dir = TestDir::new("exclusive");
+        let mut first = dir.backend();
+        let mut second = dir.backend();
+
+        assert!(first.connect("_game"));
+        assert!(!first.connect("_game"));
+        assert!(!second.connect("_game"));
+        assert!(second.connect("_other"));
+
+        first.close("_game");
+        assert!(second.connect("_game"));
+        assert!(!first.connect("_game"));
+
+        // A closed connection isn't released by a backend that doesn't own it.
+        first.close("_game");
+        assert!(second.is_owned("_game"));
+
+        drop(second);
+        assert!(first.connect("_game"));
+        assert!(first.connect("_other"));
+    }
+
+    #[test]
+    fn messages_are_delivered_in_order() {
+        let dir = TestDir::new("delivery");
+        let mut receiver = dir.backend();
+        let mut sender = dir.backend();
+
+        assert!(!sender.send(message("_game", "early")));
+        assert!(receiver.connect("_game"));
+        assert!(sender.send(message("_game", "first")));
+        assert!(sender.send(message("_game", "second")));
+        assert!(!sender.send(message("_missing", "lost")));
+        assert!(sender.receive().is_empty());
+
+        assert_eq!(
+            receiver.receive(),
+            vec![message("_game", "first"), message("_game", "second")]
+        );
+        assert!(receiver.receive().is_empty());
+
+        receiver.close("_game");
+        assert!(!sender.send(message("_game", "late")));
+    }
+
+    #[test]
+    fn own_messages_skip_the_disk() {
+        let dir = TestDir::new("own");
+        let mut backend = dir.backend();
+
+        assert!(backend.connect("_game"));
+        assert!(backend.send(message("_game", "hello")));
+        let files: Vec<_> = fs::read_dir(backend.connection_path("_game"))
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name())
+            .collect();
+        assert_eq!(files, vec!["owner"]);
+        assert_eq!(backend.receive(), vec![message("_game", "hello")]);
+    }
+
+    #[test]
+    fn names_are_encoded() {
+        let dir = TestDir::new("names");
+        let mut backend = dir.backend();
+
+        assert!(backend.connect("example.com:a/b\\c"));
+        assert_eq!(
+            backend.connection_path("example.com:a/b\\c"),
+            dir.0.join("6578616d706c652e636f6d3a612f625c63")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn base_dir_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TestDir::new("private");
+        fs::create_dir_all(&dir.0).unwrap();
+        fs::set_permissions(&dir.0, fs::Permissions::from_mode(0o777)).unwrap();
+
+        let mut backend = dir.backend();
+        assert!(backend.connect("_game"));
+        let mode = fs::metadata(&dir.0).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+}
@@ -1,16 +1,29 @@
 //! Navigator backend for web
 
+use crate::cookies::CookieJar;
 use crate::custom_event::RuffleEvent;
+use crate::http_cache::{HttpCache, Validators};
+use crate::proxy::Proxy;
+use futures::channel::{mpsc, oneshot};
+use futures::AsyncReadExt;
 use isahc::{
-    config::RedirectPolicy, prelude::*, AsyncReadResponseExt, HttpClient, Request as IsahcRequest,
+    config::RedirectPolicy, http::request::Builder as RequestBuilder, http::StatusCode, prelude::*,
+    AsyncReadResponseExt, HttpClient, Request as IsahcRequest,
 };
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, Request, Response,
+    parse_content_range, AcceptedSocket, Datagram, DatagramConnection, ListeningSocket,
+    NavigationMethod, NavigatorBackend, OwnedFuture, Request, Response, SocketConnection,
+    StreamingResponse,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
+use std::cell::RefCell;
+use std::net::{Shutdown, TcpListener, TcpStream, UdpSocket};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 use winit::event_loop::EventLoopProxy;
 
@@ -24,11 +37,26 @@ pub struct ExternalNavigatorBackend {
     event_loop: EventLoopProxy<RuffleEvent>,
 
     /// The url to use for all relative fetches.
-    base_url: Url,
+    ///
+    /// This follows the redirects of the root movie download, so that relative
+    /// fetches are made next to where the movie actually is.
+    base_url: Rc<RefCell<Url>>,
+
+    /// The url of the root movie, whose download progress is reported.
+    movie_url: Url,
 
     // Client to use for network requests
     client: Option<Rc<HttpClient>>,
 
+    /// The cookies that are sent along with network requests.
+    cookies: Rc<RefCell<CookieJar>>,
+
+    /// The responses kept on disk, if caching is enabled.
+    cache: Option<Rc<HttpCache>>,
+
+    /// The proxy that socket connections are made through.
+    proxy: Option<Proxy>,
+
     upgrade_to_https: bool,
 }
 
@@ -38,33 +66,93 @@ impl ExternalNavigatorBackend {
         movie_url: Url,
         channel: Sender<OwnedFuture<(), Error>>,
         event_loop: EventLoopProxy<RuffleEvent>,
-        proxy: Option<Url>,
+        proxy: Option<Proxy>,
         upgrade_to_https: bool,
+        cookies: Rc<RefCell<CookieJar>>,
+        cache: Option<Rc<HttpCache>>,
     ) -> Self {
-        let proxy = proxy.and_then(|url| url.as_str().parse().ok());
-        let builder = HttpClient::builder()
-            .proxy(proxy)
-            .redirect_policy(RedirectPolicy::Follow);
+        let mut builder = HttpClient::builder().redirect_policy(RedirectPolicy::Follow);
+        if let Some(proxy) = &proxy {
+            builder = proxy.configure(builder);
+        }
 
         let client = builder.build().ok().map(Rc::new);
-        let mut base_url = movie_url;
-
-        // Force replace the last segment with empty. //
-
-        if let Ok(mut base_url) = base_url.path_segments_mut() {
-            base_url.pop_if_empty().pop().push("");
-        }
+        let base_url = Rc::new(RefCell::new(base_url_of(movie_url.clone())));
 
-        Self {
+        let mut backend = Self {
             channel,
             event_loop,
             client,
+            cookies,
+            cache,
+            proxy,
             base_url,
+            movie_url,
             upgrade_to_https,
-        }
+        };
+        backend.movie_url = backend.pre_process_url(backend.movie_url.clone());
+        backend
+    }
+
+    /// Resolves the URL of a fetch against the base url.
+    fn resolve_url(&self, url: &str) -> Result<Url, Error> {
+        let full_url = self
+            .base_url
+            .borrow()
+            .join(url)
+            .map_err(|e| Error::FetchError(format!("Invalid URL {url}: {e}")))?;
+        Ok(self.pre_process_url(full_url))
     }
 }
 
+/// Starts building the HTTP request for `request`, sent to `url`.
+fn request_builder(url: &Url, request: &Request, cookies: &CookieJar) -> RequestBuilder {
+    let mut builder = IsahcRequest::builder()
+        .method(request.method().as_str())
+        .uri(url.as_str());
+    if let Some((_, mime)) = request.body() {
+        builder = builder.header("Content-Type", mime);
+    }
+    for (name, value) in request.headers() {
+        builder = builder.header(name, value);
+    }
+    if let Some(cookie) = cookies.header(url) {
+        builder = builder.header("Cookie", cookie);
+    }
+    if !request.follow_redirects() {
+        builder = builder.redirect_policy(RedirectPolicy::None);
+    }
+    builder
+}
+
+/// Remembers the cookies set by the response to a request sent to `url`.
+fn store_cookies<T>(cookies: &RefCell<CookieJar>, url: &Url, response: &isahc::Response<T>) {
+    let url = response
+        .effective_uri()
+        .and_then(|uri| Url::parse(&uri.to_string()).ok())
+        .unwrap_or_else(|| url.clone());
+    let headers = response
+        .headers()
+        .get_all("Set-Cookie")
+        .iter()
+        .filter_map(|value| value.to_str().ok());
+    cookies.borrow_mut().store(&url, headers);
+}
+
+/// Returns the url that fetches relative to `url` are resolved against.
+fn base_url_of(mut url: Url) -> Url {
+    // Force replace the last segment with empty. //
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().pop().push("");
+    }
+    url
+}
+
+/// Whether the response to `request` may be cached: only plain `GET` requests are.
+fn is_cacheable(request: &Request) -> bool {
+    matches!(request.method(), NavigationMethod::Get) && request.body().is_none()
+}
+
 impl NavigatorBackend for ExternalNavigatorBackend {
     fn navigate_to_url(
         &self,
@@ -114,17 +202,17 @@ impl NavigatorBackend for ExternalNavigatorBackend {
 
     fn fetch(&self, request: Request) -> OwnedFuture<Response, Error> {
         // TODO: honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
-        let full_url = match self.base_url.join(request.url()) {
+        let processed_url = match self.resolve_url(request.url()) {
             Ok(url) => url,
-            Err(e) => {
-                let msg = format!("Invalid URL {}: {e}", request.url());
-                return Box::pin(async move { Err(Error::FetchError(msg)) });
-            }
+            Err(e) => return Box::pin(async move { Err(e) }),
         };
 
-        let processed_url = self.pre_process_url(full_url);
-
         let client = self.client.clone();
+        let cookies = self.cookies.clone();
+        let cache = self.cache.clone();
+        let is_root_movie = processed_url == self.movie_url;
+        let event_loop = self.event_loop.clone();
+        let base_url = self.base_url.clone();
 
         match processed_url.scheme() {
             "file" => Box::pin(async move {
@@ -161,13 +249,14 @@ impl NavigatorBackend for ExternalNavigatorBackend {
                 let client =
                     client.ok_or_else(|| Error::FetchError("Network unavailable".to_string()))?;
 
-                let isahc_request = match request.method() {
-                    NavigationMethod::Get => IsahcRequest::get(processed_url.to_string()),
-                    NavigationMethod::Post => IsahcRequest::post(processed_url.to_string()),
-                };
-
+                let cache = cache.filter(|_| is_cacheable(&request));
+                let cached = cache.as_ref().and_then(|cache| cache.get(&processed_url));
+                let mut builder = request_builder(&processed_url, &request, &cookies.borrow());
+                if let Some(cached) = &cached {
+                    builder = cached.validators.add_to(builder);
+                }
                 let (body_data, _) = request.body().clone().unwrap_or_default();
-                let body = isahc_request
+                let body = builder
                     .body(body_data)
                     .map_err(|e| Error::FetchError(e.to_string()))?;
 
@@ -175,31 +264,257 @@ impl NavigatorBackend for ExternalNavigatorBackend {
                     .send_async(body)
                     .await
                     .map_err(|e| Error::FetchError(e.to_string()))?;
+                store_cookies(&cookies, &processed_url, &response);
 
+                let url = if let Some(uri) = response.effective_uri() {
+                    uri.to_string()
+                } else {
+                    processed_url.to_string()
+                };
+                if is_root_movie {
+                    if let Ok(redirected_url) = Url::parse(&url) {
+                        *base_url.borrow_mut() = base_url_of(redirected_url);
+                    }
+                }
+
+                if let Some(body) = cached.and_then(|cached| cached.revalidate(&response)) {
+                    return Ok(Response { url, body });
+                }
                 if !response.status().is_success() {
                     return Err(Error::FetchError(format!(
                         "HTTP status is not ok, got {}",
                         response.status()
                     )));
                 }
+                let validators = Validators::of_response(&response);
 
-                let url = if let Some(uri) = response.effective_uri() {
-                    uri.to_string()
+                let mut body = vec![];
+                if is_root_movie {
+                    // Read the movie in chunks, to report the progress of large downloads.
+                    let total = response.body().len();
+                    let mut chunk = vec![0; 64 * 1024];
+                    loop {
+                        let read = response
+                            .body_mut()
+                            .read(&mut chunk)
+                            .await
+                            .map_err(|e| Error::FetchError(e.to_string()))?;
+                        if read == 0 {
+                            break;
+                        }
+                        body.extend_from_slice(&chunk[..read]);
+                        let _ = event_loop.send_event(RuffleEvent::RootMovieProgress {
+                            loaded: body.len() as u64,
+                            total,
+                        });
+                    }
                 } else {
-                    processed_url.into()
-                };
+                    response
+                        .copy_to(&mut body)
+                        .await
+                        .map_err(|e| Error::FetchError(e.to_string()))?;
+                }
 
-                let mut body = vec![];
-                response
-                    .copy_to(&mut body)
-                    .await
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
+                if let (Some(cache), Some(validators)) = (cache, validators) {
+                    cache.store(&processed_url, &validators, &body);
+                }
 
                 Ok(Response { url, body })
             }),
         }
     }
 
+    fn fetch_streaming(
+        &self,
+        request: Request,
+        offset: usize,
+    ) -> OwnedFuture<StreamingResponse, Error> {
+        let processed_url = match self.resolve_url(request.url()) {
+            Ok(url) => url,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+
+        if processed_url.scheme() == "file" {
+            // Local files are read at once.
+            let fetch = self.fetch(request);
+            return Box::pin(async move { Ok(StreamingResponse::from_response(fetch.await?)) });
+        }
+
+        let client = self.client.clone();
+        let cookies = self.cookies.clone();
+        // Only whole responses are cached.
+        let cache = self
+            .cache
+            .clone()
+            .filter(|_| offset == 0 && is_cacheable(&request));
+        Box::pin(async move {
+            let client =
+                client.ok_or_else(|| Error::FetchError("Network unavailable".to_string()))?;
+
+            let cached = cache.as_ref().and_then(|cache| cache.get(&processed_url));
+            let mut isahc_request = request_builder(&processed_url, &request, &cookies.borrow());
+            if let Some(cached) = &cached {
+                isahc_request = cached.validators.add_to(isahc_request);
+            }
+            if offset > 0 {
+                isahc_request = isahc_request.header("Range", format!("bytes={offset}-"));
+            }
+
+            let (body_data, _) = request.body().clone().unwrap_or_default();
+            let body = isahc_request
+                .body(body_data)
+                .map_err(|e| Error::FetchError(e.to_string()))?;
+
+            let response = client
+                .send_async(body)
+                .await
+                .map_err(|e| Error::FetchError(e.to_string()))?;
+            store_cookies(&cookies, &processed_url, &response);
+
+            let url = if let Some(uri) = response.effective_uri() {
+                uri.to_string()
+            } else {
+                processed_url.to_string()
+            };
+
+            if let Some(body) = cached.and_then(|cached| cached.revalidate(&response)) {
+                return Ok(StreamingResponse {
+                    status: StatusCode::OK.as_u16(),
+                    ..StreamingResponse::from_response(Response { url, body })
+                });
+            }
+            if !response.status().is_success() {
+                return Err(Error::HttpNotOk(response.status().as_u16()));
+            }
+            // The body is cached once all of it has arrived.
+            let cache = cache
+                .zip(Validators::of_response(&response))
+                .map(|(cache, validators)| (cache, processed_url, validators, Vec::new()));
+
+            let (offset, total_length) = if response.status() == StatusCode::PARTIAL_CONTENT {
+                response
+                    .headers()
+                    .get("Content-Range")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_content_range)
+                    .unwrap_or((offset, None))
+            } else {
+                (0, response.body().len().map(|length| length as usize))
+            };
+
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+
+            let state = Some((response.into_body(), cache));
+            let body = futures::stream::unfold(state, |state| async move {
+                let (mut body, mut cache) = state?;
+                let mut chunk = vec![0; 64 * 1024];
+                match body.read(&mut chunk).await {
+                    Ok(0) => {
+                        if let Some((cache, url, validators, data)) = cache {
+                            cache.store(&url, &validators, &data);
+                        }
+                        None
+                    }
+                    Ok(read) => {
+                        chunk.truncate(read);
+                        if let Some((_, _, _, data)) = &mut cache {
+                            data.extend_from_slice(&chunk);
+                        }
+                        Some((Ok(chunk), Some((body, cache))))
+                    }
+                    Err(e) => Some((Err(Error::FetchError(e.to_string())), None)),
+                }
+            });
+
+            Ok(StreamingResponse {
+                url,
+                status,
+                headers,
+                offset,
+                total_length,
+                body: Box::pin(body),
+            })
+        })
+    }
+
+    fn connect_socket(&self, host: &str, port: u16) -> OwnedFuture<SocketConnection, Error> {
+        let host = host.to_string();
+        let proxy = self.proxy.clone();
+        let (result_sender, result) = oneshot::channel();
+
+        // Sockets are blocking, so they are served by their own threads.
+        std::thread::spawn(move || {
+            let stream = match &proxy {
+                Some(proxy) => proxy.connect(&host, port),
+                None => TcpStream::connect((host.as_str(), port)),
+            };
+            let connection = stream
+                .and_then(serve_socket)
+                .map_err(|e| Error::ConnectError(e.to_string()));
+            let _ = result_sender.send(connection);
+        });
+
+        Box::pin(async move {
+            result
+                .await
+                .map_err(|_| Error::ConnectError("Connection was cancelled".to_string()))?
+        })
+    }
+
+    fn listen_socket(&self, address: &str, port: u16) -> Result<ListeningSocket, Error> {
+        let listen = || {
+            let listener = TcpListener::bind((address, port))?;
+            // Accepting doesn't block, so that the listener notices when it is closed.
+            listener.set_nonblocking(true)?;
+            let local_address = listener.local_addr()?;
+            Ok::<_, std::io::Error>((listener, local_address))
+        };
+        let (listener, local_address) = listen().map_err(|e| Error::ConnectError(e.to_string()))?;
+
+        let (incoming_sender, incoming) = mpsc::unbounded();
+        std::thread::spawn(move || accept_sockets(listener, incoming_sender));
+
+        Ok(ListeningSocket {
+            local_address: local_address.ip().to_string(),
+            local_port: local_address.port(),
+            incoming,
+        })
+    }
+
+    fn bind_datagram_socket(&self, address: &str, port: u16) -> Result<DatagramConnection, Error> {
+        let bind = || {
+            let socket = UdpSocket::bind((address, port))?;
+            // Reads time out regularly, so that the reader notices when the socket is closed.
+            socket.set_read_timeout(Some(DATAGRAM_READ_TIMEOUT))?;
+            let writer = socket.try_clone()?;
+            let local_address = socket.local_addr()?;
+            Ok::<_, std::io::Error>((socket, writer, local_address))
+        };
+        let (reader, writer, local_address) =
+            bind().map_err(|e| Error::ConnectError(e.to_string()))?;
+
+        let (incoming_sender, incoming) = mpsc::unbounded();
+        let (outgoing, outgoing_receiver) = mpsc::unbounded();
+        let closed = Arc::new(AtomicBool::new(false));
+        let reader_closed = closed.clone();
+        std::thread::spawn(move || write_datagrams(writer, outgoing_receiver, closed));
+        std::thread::spawn(move || read_datagrams(reader, incoming_sender, reader_closed));
+
+        Ok(DatagramConnection {
+            local_address: local_address.ip().to_string(),
+            local_port: local_address.port(),
+            incoming,
+            outgoing,
+        })
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         self.channel.send(future).expect("working channel send");
 
@@ -217,3 +532,125 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         url
     }
 }
+
+/// Serves a connected socket with threads forwarding the data passing through it.
+fn serve_socket(socket: TcpStream) -> std::io::Result<SocketConnection> {
+    let writer = socket.try_clone()?;
+    let (incoming_sender, incoming) = mpsc::unbounded();
+    let (outgoing, outgoing_receiver) = mpsc::unbounded();
+    std::thread::spawn(move || write_socket(writer, outgoing_receiver));
+    std::thread::spawn(move || read_socket(socket, incoming_sender));
+    Ok(SocketConnection { incoming, outgoing })
+}
+
+/// How long a listening socket waits for a connection before checking whether it was closed.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Forwards the connections accepted by a socket, until the player closes it.
+fn accept_sockets(listener: TcpListener, incoming: mpsc::UnboundedSender<AcceptedSocket>) {
+    use std::io::ErrorKind;
+
+    while !incoming.is_closed() {
+        match listener.accept() {
+            Ok((socket, address)) => {
+                let connection = socket
+                    .set_nonblocking(false)
+                    .and_then(|()| serve_socket(socket));
+                let connection = match connection {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        tracing::warn!("Unable to serve accepted socket: {}", e);
+                        continue;
+                    }
+                };
+                let accepted = AcceptedSocket {
+                    address: address.ip().to_string(),
+                    port: address.port(),
+                    connection,
+                };
+                if incoming.unbounded_send(accepted).is_err() {
+                    break;
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_INTERVAL),
+            Err(_) => break,
+        }
+    }
+}
+
+/// Forwards the data received on a socket, until it is closed.
+fn read_socket(mut socket: TcpStream, incoming: mpsc::UnboundedSender<Vec<u8>>) {
+    use std::io::Read;
+
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        match socket.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(length) => {
+                if incoming.unbounded_send(buffer[..length].to_vec()).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Sends the data of the player, closing the socket once the player is done.
+fn write_socket(mut socket: TcpStream, mut outgoing: mpsc::UnboundedReceiver<Vec<u8>>) {
+    use futures::StreamExt;
+    use std::io::Write;
+
+    while let Some(data) = futures::executor::block_on(outgoing.next()) {
+        if socket.write_all(&data).is_err() {
+            break;
+        }
+    }
+    let _ = socket.shutdown(Shutdown::Both);
+}
+
+/// How long a datagram socket waits for a datagram before checking whether it was closed.
+const DATAGRAM_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Forwards the datagrams received on a socket, until it is closed.
+fn read_datagrams(
+    socket: UdpSocket,
+    incoming: mpsc::UnboundedSender<Datagram>,
+    closed: Arc<AtomicBool>,
+) {
+    use std::io::ErrorKind;
+
+    let mut buffer = vec![0; 64 * 1024];
+    while !closed.load(Ordering::Relaxed) {
+        match socket.recv_from(&mut buffer) {
+            Ok((length, address)) => {
+                let datagram = Datagram {
+                    address: address.ip().to_string(),
+                    port: address.port(),
+                    data: buffer[..length].to_vec(),
+                };
+                if incoming.unbounded_send(datagram).is_err() {
+                    break;
+                }
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+/// Sends the datagrams of the player, closing the socket once the player is done.
+fn write_datagrams(
+    socket: UdpSocket,
+    mut outgoing: mpsc::UnboundedReceiver<Datagram>,
+    closed: Arc<AtomicBool>,
+) {
+    use futures::StreamExt;
+
+    while let Some(datagram) = futures::executor::block_on(outgoing.next()) {
+        let address = (datagram.address.as_str(), datagram.port);
+        if let Err(e) = socket.send_to(&datagram.data, address) {
+            tracing::warn!("Unable to send datagram: {}", e);
+        }
+    }
+    closed.store(true, Ordering::Relaxed);
+}
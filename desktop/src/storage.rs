@@ -8,6 +8,7 @@ use std::path::{Component, Path, PathBuf};
 pub struct DiskStorageBackend {
     base_path: PathBuf,
     shared_objects_path: PathBuf,
+    flash_player_paths: Vec<PathBuf>,
 }
 
 impl DiskStorageBackend {
@@ -28,9 +29,43 @@ impl DiskStorageBackend {
         Ok(DiskStorageBackend {
             base_path,
             shared_objects_path,
+            flash_player_paths: Self::find_flash_player_paths(),
         })
     }
 
+    /// Finds the directories that Flash Player stored shared objects in, so that existing
+    /// saves can be imported.
+    ///
+    /// Flash Player keeps them under `#SharedObjects/<random id>/`, followed by the same
+    /// `domain/path/name.sol` layout that Ruffle uses.
+    fn find_flash_player_paths() -> Vec<PathBuf> {
+        let flash_player_dir = if cfg!(windows) {
+            dirs::data_dir().map(|dir| dir.join("Macromedia").join("Flash Player"))
+        } else if cfg!(target_os = "macos") {
+            dirs::home_dir().map(|dir| {
+                dir.join("Library")
+                    .join("Preferences")
+                    .join("Macromedia")
+                    .join("Flash Player")
+            })
+        } else {
+            dirs::home_dir().map(|dir| dir.join(".macromedia").join("Flash_Player"))
+        };
+
+        let shared_objects_dir = match flash_player_dir {
+            Some(dir) => dir.join("#SharedObjects"),
+            None => return Vec::new(),
+        };
+        match fs::read_dir(shared_objects_dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
     /// Verifies that the path contains no `..` components to prevent accessing files outside of the Ruffle directory.
     fn is_path_allowed(path: &Path) -> bool {
         path.components().all(|c| c != Component::ParentDir)
@@ -54,23 +89,27 @@ impl StorageBackend for DiskStorageBackend {
         if !Self::is_path_allowed(&path) {
             return None;
         }
-        match std::fs::read(path) {
-            Ok(data) => Some(data),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                let path = self.get_back_compat_shared_object_path(name);
-                match std::fs::read(path) {
-                    Ok(data) => Some(data),
-                    Err(e) => {
-                        tracing::warn!("Unable to read file {:?}", e);
-                        None
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Unable to read file {:?}", e);
-                None
+
+        // Ruffle's own saves take priority. Otherwise, import a save left by Flash Player;
+        // it is written back to Ruffle's directory on the next flush.
+        let candidates = std::iter::once(path)
+            .chain(std::iter::once(
+                self.get_back_compat_shared_object_path(name),
+            ))
+            .chain(
+                self.flash_player_paths
+                    .iter()
+                    .map(|dir| dir.join(format!("{name}.sol"))),
+            );
+        for path in candidates {
+            match std::fs::read(&path) {
+                Ok(data) => return Some(data),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("Unable to read file {:?}: {:?}", path, e),
             }
         }
+
+        None
     }
 
     fn put(&mut self, name: &str, value: &[u8]) -> bool {
@@ -0,0 +1,38 @@
+//! The local files and directories whose movies are trusted, like the `FlashPlayerTrust`
+//! configuration of Flash Player.
+
+use std::fs;
+use std::path::PathBuf;
+use url::Url;
+
+/// Returns the URLs of the trusted locations: the paths listed in `trust.cfg`, one per line, and
+/// the `extra` ones given on the command line.
+///
+/// Paths that don't exist are ignored.
+pub fn trusted_locations(extra: &[PathBuf]) -> Vec<String> {
+    let path = dirs::data_local_dir().map(|dir| dir.join("ruffle").join("trust.cfg"));
+    let listed: Vec<PathBuf> = path
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|data| {
+            data.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    listed
+        .iter()
+        .chain(extra)
+        .filter_map(|path| {
+            let path = path.canonicalize().ok()?;
+            let url = if path.is_dir() {
+                Url::from_directory_path(&path)
+            } else {
+                Url::from_file_path(&path)
+            };
+            url.ok().map(String::from)
+        })
+        .collect()
+}
@@ -1,25 +1,62 @@
+use crate::camera::{self, Camera};
+use crate::custom_event::RuffleEvent;
+use crate::fullscreen::FullscreenSettings;
 use anyhow::{Context, Error};
 use arboard::Clipboard;
 use rfd::{MessageButtons, MessageDialog, MessageLevel};
-use ruffle_core::backend::ui::{FullscreenError, MouseCursor, UiBackend};
+use ruffle_core::backend::ui::{
+    CameraError, CameraFrame, DeviceFont, DeviceFontFile, FullscreenError, MouseCursor, UiBackend,
+};
 use std::rc::Rc;
 use tracing::error;
-use winit::window::{Fullscreen, Window};
+use winit::event_loop::EventLoopProxy;
+use winit::window::Window;
 
 pub struct DesktopUiBackend {
     window: Rc<Window>,
     cursor_visible: bool,
     clipboard: Clipboard,
+    fullscreen: FullscreenSettings,
+    event_loop: EventLoopProxy<RuffleEvent>,
+
+    /// The fonts installed on the system.
+    fonts: fontdb::Database,
+
+    /// The camera being captured from, if any.
+    camera: Option<Camera>,
 }
 
 impl DesktopUiBackend {
-    pub fn new(window: Rc<Window>) -> Result<Self, Error> {
+    pub fn new(
+        window: Rc<Window>,
+        fullscreen: FullscreenSettings,
+        event_loop: EventLoopProxy<RuffleEvent>,
+    ) -> Result<Self, Error> {
+        let mut fonts = fontdb::Database::new();
+        fonts.load_system_fonts();
         Ok(Self {
             window,
             cursor_visible: true,
             clipboard: Clipboard::new().context("Couldn't get platform clipboard")?,
+            fullscreen,
+            event_loop,
+            fonts,
+            camera: None,
         })
     }
+
+    fn device_font(face: &fontdb::FaceInfo) -> DeviceFont {
+        DeviceFont {
+            // The first family name is the English one, if the font has one.
+            name: face
+                .families
+                .first()
+                .map(|(name, _)| name.clone())
+                .unwrap_or_default(),
+            is_bold: face.weight >= fontdb::Weight::SEMIBOLD,
+            is_italic: face.style != fontdb::Style::Normal,
+        }
+    }
 }
 
 // TODO: Move link to https://ruffle.rs/faq or similar
@@ -53,6 +90,10 @@ impl UiBackend for DesktopUiBackend {
         self.window.set_cursor_icon(icon);
     }
 
+    fn clipboard_content(&mut self) -> String {
+        self.clipboard.get_text().unwrap_or_default()
+    }
+
     fn set_clipboard_content(&mut self, content: String) {
         if let Err(e) = self.clipboard.set_text(content) {
             error!("Couldn't set clipboard contents: {:?}", e);
@@ -61,7 +102,7 @@ impl UiBackend for DesktopUiBackend {
 
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
         self.window.set_fullscreen(if is_full {
-            Some(Fullscreen::Borderless(None))
+            Some(self.fullscreen.fullscreen(&self.window))
         } else {
             None
         });
@@ -86,6 +127,68 @@ impl UiBackend for DesktopUiBackend {
         dialog.show();
     }
 
+    fn camera_names(&self) -> Vec<String> {
+        camera::camera_names()
+    }
+
+    fn start_camera(
+        &mut self,
+        index: usize,
+        width: u32,
+        height: u32,
+        fps: f64,
+    ) -> Result<(), CameraError> {
+        self.stop_camera();
+        self.camera = Some(Camera::start(index, width, height, fps)?);
+        Ok(())
+    }
+
+    fn stop_camera(&mut self) {
+        self.camera = None;
+    }
+
+    fn take_camera_frame(&mut self) -> Option<CameraFrame> {
+        self.camera.as_mut().and_then(Camera::take_frame)
+    }
+
+    fn device_fonts(&self) -> Vec<DeviceFont> {
+        self.fonts.faces().map(Self::device_font).collect()
+    }
+
+    fn load_device_font(&self, font: &DeviceFont) -> Option<DeviceFontFile> {
+        let face = self
+            .fonts
+            .faces()
+            .find(|face| Self::device_font(face) == *font)?;
+        self.fonts
+            .with_face_data(face.id, |data, index| DeviceFontFile {
+                data: data.to_vec(),
+                index,
+            })
+    }
+
+    fn fs_command(&mut self, command: &str, args: &str) -> bool {
+        // The window is left to the event loop.
+        let event = if command.eq_ignore_ascii_case("quit") {
+            RuffleEvent::Quit
+        } else if command.eq_ignore_ascii_case("trapallkeys") {
+            RuffleEvent::TrapAllKeys(args.trim().eq_ignore_ascii_case("true"))
+        } else {
+            return false;
+        };
+        let _ = self.event_loop.send_event(event);
+        true
+    }
+
+    fn set_ime_allowed(&mut self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+
+    fn set_ime_position(&mut self, x: f64, y: f64) {
+        self.window
+            .set_ime_position(winit::dpi::PhysicalPosition::new(x, y));
+    }
+
     fn message(&self, message: &str) {
         let dialog = MessageDialog::new()
             .set_level(MessageLevel::Info)
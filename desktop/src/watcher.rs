@@ -0,0 +1,43 @@
+//! Watches the movie file, to reload it while it's being developed.
+
+use crate::custom_event::RuffleEvent;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use winit::event_loop::EventLoopProxy;
+
+/// How often the file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Starts a thread that sends `RuffleEvent::MovieChanged` whenever the file at `path` changes.
+///
+/// A change is only reported once the file stays the same between two checks, so that a movie
+/// still being written by the authoring tool isn't loaded half-way through.
+pub fn watch_movie(path: PathBuf, event_loop: EventLoopProxy<RuffleEvent>) {
+    thread::spawn(move || {
+        let mut reported = stamp(&path);
+        let mut last = reported;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = stamp(&path);
+            if current != last {
+                last = current;
+                continue;
+            }
+            if current.is_some() && current != reported {
+                reported = current;
+                if event_loop.send_event(RuffleEvent::MovieChanged).is_err() {
+                    // The event loop is gone, so the app is shutting down.
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// The modification time and size of a file, or `None` if it can't be read.
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
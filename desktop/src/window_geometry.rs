@@ -0,0 +1,103 @@
+//! Remembers the position and size of the window for each movie.
+
+use std::fs;
+use std::path::PathBuf;
+use url::Url;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::window::Window;
+
+/// The position and size of a window, in physical pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+}
+
+impl WindowGeometry {
+    /// Reads the geometry of a window, unless it's minimized or fullscreen.
+    pub fn of_window(window: &Window) -> Option<Self> {
+        if window.fullscreen().is_some() {
+            return None;
+        }
+        let size = window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return None;
+        }
+        Some(Self {
+            position: window.outer_position().ok()?,
+            size,
+        })
+    }
+
+    /// Parses a line of the geometry file, in the form `x y width height url`.
+    fn parse(line: &str) -> Option<(Self, &str)> {
+        let mut parts = line.splitn(5, ' ');
+        let x = parts.next()?.parse().ok()?;
+        let y = parts.next()?.parse().ok()?;
+        let width = parts.next()?.parse().ok()?;
+        let height = parts.next()?.parse().ok()?;
+        let url = parts.next()?;
+        Some((
+            Self {
+                position: PhysicalPosition::new(x, y),
+                size: PhysicalSize::new(width, height),
+            },
+            url,
+        ))
+    }
+
+    fn to_line(self, url: &str) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.position.x, self.position.y, self.size.width, self.size.height, url
+        )
+    }
+}
+
+/// The window geometries of all movies, stored in a single file.
+pub struct WindowGeometryStore {
+    path: Option<PathBuf>,
+}
+
+impl WindowGeometryStore {
+    pub fn new() -> Self {
+        let path = dirs::data_local_dir().map(|dir| dir.join("ruffle").join("window_geometry.txt"));
+        Self { path }
+    }
+
+    /// Returns the geometry the window had when the movie at `url` was last closed.
+    pub fn load(&self, url: &Url) -> Option<WindowGeometry> {
+        let data = fs::read_to_string(self.path.as_ref()?).ok()?;
+        data.lines()
+            .filter_map(WindowGeometry::parse)
+            .find(|(_, line_url)| *line_url == url.as_str())
+            .map(|(geometry, _)| geometry)
+    }
+
+    /// Remembers the geometry of the window for the movie at `url`.
+    pub fn save(&self, url: &Url, geometry: WindowGeometry) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let data = fs::read_to_string(path).unwrap_or_default();
+        let mut lines: Vec<String> = data
+            .lines()
+            .filter(|line| {
+                WindowGeometry::parse(line).map_or(false, |(_, line_url)| line_url != url.as_str())
+            })
+            .map(str::to_string)
+            .collect();
+        lines.push(geometry.to_line(url.as_str()));
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                tracing::warn!("Unable to create window geometry dir: {}", e);
+                return;
+            }
+        }
+        if let Err(e) = fs::write(path, lines.join("\n")) {
+            tracing::warn!("Unable to save window geometry: {}", e);
+        }
+    }
+}
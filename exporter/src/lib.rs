@@ -0,0 +1,262 @@
+//! Headless capturing of SWF frames, without a window or an event loop.
+//!
+//! The exporter is built on top of this, but it can also be used directly by services that
+//! generate thumbnails or previews of movies:
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! use exporter::{FrameRenderer, ThumbnailOptions};
+//! use ruffle_core::tag_utils::SwfMovie;
+//! use ruffle_render_wgpu::wgpu;
+//!
+//! let renderer = FrameRenderer::software(wgpu::Backends::all())?;
+//! let movie = SwfMovie::from_path("movie.swf", None).map_err(|e| anyhow::anyhow!("{e}"))?;
+//! let thumbnail = renderer.thumbnail(movie, &ThumbnailOptions::default())?;
+//! thumbnail.save(std::path::Path::new("movie.png"))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use image::RgbaImage;
+use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::PlayerBuilder;
+use ruffle_render_svg::SvgRenderBackend;
+use ruffle_render_wgpu::backend::WgpuRenderBackend;
+use ruffle_render_wgpu::descriptors::Descriptors;
+use ruffle_render_wgpu::target::TextureTarget;
+use ruffle_render_wgpu::wgpu;
+use std::panic::catch_unwind;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// When the clock of captured movies starts, which is the mock time of the `deterministic`
+/// feature, so that captures don't depend on when they are made.
+fn start_date() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2001, 2, 2, 22, 20, 6).unwrap()
+}
+
+/// The renderer used to capture frames.
+///
+/// This is cheap to clone, and a single renderer can be shared by many captures running in
+/// parallel.
+#[derive(Clone)]
+pub enum FrameRenderer {
+    Wgpu(Arc<Descriptors>),
+    Svg,
+}
+
+impl FrameRenderer {
+    /// Creates a wgpu renderer on a hardware adapter.
+    pub fn hardware(
+        backends: wgpu::Backends,
+        power_preference: wgpu::PowerPreference,
+        trace_path: Option<&Path>,
+    ) -> Result<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            dx12_shader_compiler: wgpu::Dx12Compiler::default(),
+        });
+        let (adapter, device, queue) =
+            futures::executor::block_on(WgpuRenderBackend::<TextureTarget>::request_device(
+                backends,
+                instance,
+                None,
+                power_preference,
+                trace_path,
+            ))
+            .map_err(|e| anyhow!(e.to_string()))?;
+        Ok(FrameRenderer::Wgpu(Arc::new(Descriptors::new(
+            adapter, device, queue,
+        ))))
+    }
+
+    /// Creates a wgpu renderer on a software adapter, for machines without a GPU.
+    pub fn software(backends: wgpu::Backends) -> Result<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            dx12_shader_compiler: wgpu::Dx12Compiler::default(),
+        });
+        let request =
+            WgpuRenderBackend::<TextureTarget>::request_software_device(backends, instance, None);
+        let (adapter, device, queue) =
+            futures::executor::block_on(request).map_err(|e| anyhow!(e.to_string()))?;
+        Ok(FrameRenderer::Wgpu(Arc::new(Descriptors::new(
+            adapter, device, queue,
+        ))))
+    }
+
+    /// Runs a movie and captures the requested frames.
+    ///
+    /// `on_frame` is called with the index of every frame that was run, captured or not.
+    pub fn capture(
+        &self,
+        movie: SwfMovie,
+        options: &CaptureOptions,
+        mut on_frame: impl FnMut(u32),
+    ) -> Result<Vec<Frame>> {
+        let width = options
+            .width
+            .map(f64::from)
+            .unwrap_or_else(|| movie.width().to_pixels());
+        let width = (width * options.scale).round() as u32;
+
+        let height = options
+            .height
+            .map(f64::from)
+            .unwrap_or_else(|| movie.height().to_pixels());
+        let height = (height * options.scale).round() as u32;
+
+        let builder = match self {
+            FrameRenderer::Wgpu(descriptors) => {
+                let target = TextureTarget::new(&descriptors.device, (width, height))
+                    .map_err(|e| anyhow!(e.to_string()))?;
+                PlayerBuilder::new().with_renderer(
+                    WgpuRenderBackend::new(descriptors.clone(), target, 4)
+                        .map_err(|e| anyhow!(e.to_string()))?,
+                )
+            }
+            FrameRenderer::Svg => {
+                PlayerBuilder::new().with_renderer(SvgRenderBackend::new(width, height))
+            }
+        };
+        // The clock moves a frame at a time, so that the movie sees the time it would have
+        // taken to play, however long rendering takes.
+        let frame_time = 1000.0 / movie.frame_rate().to_f64();
+        let player = builder
+            .with_movie(movie)
+            .with_viewport_dimensions(width, height, options.scale)
+            .with_external_clock(Some(start_date()))
+            .build();
+
+        let mut result = Vec::new();
+        let totalframes = options.frames + options.skip_frames;
+
+        for i in 0..totalframes {
+            player.lock().unwrap().preload(&mut ExecutionLimit::none());
+
+            player.lock().unwrap().run_frame();
+            let timestamp = f64::from(i + 1) * frame_time * 1000.0;
+            player
+                .lock()
+                .unwrap()
+                .advance_clock_to(Duration::from_micros(timestamp.round() as u64));
+            if i >= options.skip_frames {
+                match catch_unwind(|| {
+                    player.lock().unwrap().render();
+                    let mut player = player.lock().unwrap();
+                    let renderer = player.renderer_mut();
+                    if let Some(renderer) = renderer.downcast_ref::<SvgRenderBackend>() {
+                        return renderer.frame().map(|svg| Frame::Svg(svg.to_owned()));
+                    }
+                    let renderer = renderer
+                        .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
+                        .unwrap();
+                    // Use straight alpha
+                    renderer.capture_frame(false).map(Frame::Rgba)
+                }) {
+                    Ok(Some(image)) => result.push(image),
+                    Ok(None) => return Err(anyhow!("Unable to capture frame {}", i)),
+                    Err(e) => return Err(anyhow!("Unable to capture frame {}: {:?}", i, e)),
+                }
+            }
+
+            on_frame(i);
+        }
+        Ok(result)
+    }
+
+    /// Runs a movie and captures a single frame, scaled down to fit the requested size.
+    pub fn thumbnail(&self, movie: SwfMovie, options: &ThumbnailOptions) -> Result<Frame> {
+        let movie_width = movie.width().to_pixels();
+        let movie_height = movie.height().to_pixels();
+        let largest_side = movie_width.max(movie_height);
+        let scale = if largest_side > f64::from(options.max_size) {
+            f64::from(options.max_size) / largest_side
+        } else {
+            1.0
+        };
+
+        let capture_options = CaptureOptions {
+            frames: 1,
+            skip_frames: options.skip_frames,
+            scale,
+            ..Default::default()
+        };
+        self.capture(movie, &capture_options, |_| {})?
+            .pop()
+            .ok_or_else(|| anyhow!("No frame was captured"))
+    }
+}
+
+/// Which frames of a movie to capture, and at which size.
+#[derive(Clone, Copy, Debug)]
+pub struct CaptureOptions {
+    /// The number of frames to capture.
+    pub frames: u32,
+
+    /// The number of frames to run before the first captured one.
+    pub skip_frames: u32,
+
+    /// The amount to scale the stage size with.
+    pub scale: f64,
+
+    /// Overrides the width of the captured frames, before scaling.
+    pub width: Option<u32>,
+
+    /// Overrides the height of the captured frames, before scaling.
+    pub height: Option<u32>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            frames: 1,
+            skip_frames: 0,
+            scale: 1.0,
+            width: None,
+            height: None,
+        }
+    }
+}
+
+/// How to capture the thumbnail of a movie.
+#[derive(Clone, Copy, Debug)]
+pub struct ThumbnailOptions {
+    /// The largest width or height of the thumbnail. Smaller movies aren't scaled up.
+    pub max_size: u32,
+
+    /// The number of frames to run before the captured one.
+    ///
+    /// Many movies only show a preloader in their first frames.
+    pub skip_frames: u32,
+}
+
+impl Default for ThumbnailOptions {
+    fn default() -> Self {
+        Self {
+            max_size: 256,
+            skip_frames: 0,
+        }
+    }
+}
+
+/// A captured frame.
+pub enum Frame {
+    /// An image with straight (non-premultiplied) alpha.
+    Rgba(RgbaImage),
+    Svg(String),
+}
+
+impl Frame {
+    pub fn save(&self, path: &Path) -> Result<()> {
+        match self {
+            Frame::Rgba(image) => image.save(path)?,
+            Frame::Svg(svg) => std::fs::write(path, svg)?,
+        }
+        Ok(())
+    }
+}
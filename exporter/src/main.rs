@@ -1,20 +1,12 @@
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use image::RgbaImage;
+use exporter::{CaptureOptions, Frame, FrameRenderer, ThumbnailOptions};
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use ruffle_core::limits::ExecutionLimit;
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::PlayerBuilder;
-use ruffle_render_wgpu::backend::WgpuRenderBackend;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
-use ruffle_render_wgpu::descriptors::Descriptors;
-use ruffle_render_wgpu::target::TextureTarget;
-use ruffle_render_wgpu::wgpu;
 use std::fs::create_dir_all;
-use std::panic::catch_unwind;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 use walkdir::{DirEntry, WalkDir};
 
 #[derive(Parser, Debug, Copy, Clone)]
@@ -32,16 +24,35 @@ struct SizeOpt {
     height: Option<u32>,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum OutputFormat {
+    Png,
+    Svg,
+}
+
+impl OutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Svg => "svg",
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[clap(name = "Ruffle Exporter", author, version)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Opt {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// The file or directory of files to export frames from
-    #[clap(name = "swf")]
-    swf: PathBuf,
+    #[clap(name = "swf", required = true)]
+    swf: Option<PathBuf>,
 
     /// The file or directory (if multiple frames/files) to store the capture in.
     /// The default value will either be:
-    /// - If given one swf and one frame, the name of the swf + ".png"
+    /// - If given one swf and one frame, the name of the swf + ".png" (or ".svg")
     /// - If given one swf and multiple frames, the name of the swf as a directory
     /// - If given multiple swfs, this field is required.
     #[clap(name = "output")]
@@ -59,6 +70,11 @@ struct Opt {
     #[clap(short, long, action)]
     silent: bool,
 
+    /// The format of the captured frames. SVG keeps shapes as vectors, instead of
+    /// rasterizing them.
+    #[clap(long, default_value = "png")]
+    format: OutputFormat,
+
     #[clap(flatten)]
     size: SizeOpt,
 
@@ -72,6 +88,10 @@ struct Opt {
     #[clap(long, short, default_value = "high")]
     power: PowerPreference,
 
+    /// Render on a software adapter, for machines without a GPU.
+    #[clap(long, action)]
+    software: bool,
+
     /// Location to store a wgpu trace output
     #[clap(long)]
     #[cfg(feature = "render_trace")]
@@ -82,88 +102,81 @@ struct Opt {
     skip_unsupported: bool,
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Capture a single frame of a movie, scaled down to be used as a thumbnail or preview
+    Thumbnail(ThumbnailOpt),
+}
+
+#[derive(clap::Args, Debug)]
+struct ThumbnailOpt {
+    /// The file to capture a thumbnail of
+    #[clap(name = "swf")]
+    swf: PathBuf,
+
+    /// The file to store the thumbnail in.
+    /// The default value is the name of the swf + ".png"
+    #[clap(name = "output")]
+    output_path: Option<PathBuf>,
+
+    /// The largest width or height of the thumbnail. Smaller movies aren't scaled up
+    #[clap(long, default_value = "256")]
+    size: u32,
+
+    /// Number of frames to run before capturing the thumbnail
+    #[clap(long = "skipframes", default_value = "0")]
+    skipframes: u32,
+
+    /// Type of graphics backend to use. Not all options may be supported by your current system.
+    /// Default will attempt to pick the most supported graphics backend.
+    #[clap(long, short, default_value = "default")]
+    graphics: GraphicsBackend,
+
+    /// Power preference for the graphics device used. High power usage tends to prefer dedicated GPUs,
+    /// whereas a low power usage tends prefer integrated GPUs.
+    #[clap(long, short, default_value = "high")]
+    power: PowerPreference,
+
+    /// Render on a software adapter, for machines without a GPU.
+    #[clap(long, action)]
+    software: bool,
+}
+
 /// Captures a screenshot. The resulting image uses straight alpha
 fn take_screenshot(
-    descriptors: Arc<Descriptors>,
+    renderer: FrameRenderer,
     swf_path: &Path,
     frames: u32,
     skipframes: u32,
     progress: &Option<ProgressBar>,
     size: SizeOpt,
     skip_unsupported: bool,
-) -> Result<Vec<RgbaImage>> {
+) -> Result<Vec<Frame>> {
     let movie = SwfMovie::from_path(&swf_path, None).map_err(|e| anyhow!(e.to_string()))?;
 
     if movie.is_action_script_3() && skip_unsupported {
         return Err(anyhow!("Skipping unsupported movie"));
     }
 
-    let width = size
-        .width
-        .map(f64::from)
-        .unwrap_or_else(|| movie.width().to_pixels());
-    let width = (width * size.scale).round() as u32;
-
-    let height = size
-        .height
-        .map(f64::from)
-        .unwrap_or_else(|| movie.height().to_pixels());
-    let height = (height * size.scale).round() as u32;
-
-    let target = TextureTarget::new(&descriptors.device, (width, height))
-        .map_err(|e| anyhow!(e.to_string()))?;
-    let player = PlayerBuilder::new()
-        .with_renderer(
-            WgpuRenderBackend::new(descriptors, target, 4).map_err(|e| anyhow!(e.to_string()))?,
-        )
-        .with_movie(movie)
-        .with_viewport_dimensions(width, height, size.scale)
-        .build();
-
-    let mut result = Vec::new();
-    let totalframes = frames + skipframes;
-
-    for i in 0..totalframes {
-        if let Some(progress) = &progress {
-            progress.set_message(format!(
-                "{} frame {}",
-                swf_path.file_stem().unwrap().to_string_lossy(),
-                i
-            ));
-        }
-
-        player.lock().unwrap().preload(&mut ExecutionLimit::none());
-
-        player.lock().unwrap().run_frame();
-        if i >= skipframes {
-            match catch_unwind(|| {
-                player.lock().unwrap().render();
-                let mut player = player.lock().unwrap();
-                let renderer = player
-                    .renderer_mut()
-                    .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
-                    .unwrap();
-                // Use straight alpha
-                renderer.capture_frame(false)
-            }) {
-                Ok(Some(image)) => result.push(image),
-                Ok(None) => return Err(anyhow!("Unable to capture frame {} of {:?}", i, swf_path)),
-                Err(e) => {
-                    return Err(anyhow!(
-                        "Unable to capture frame {} of {:?}: {:?}",
-                        i,
-                        swf_path,
-                        e
-                    ))
-                }
+    let options = CaptureOptions {
+        frames,
+        skip_frames: skipframes,
+        scale: size.scale,
+        width: size.width,
+        height: size.height,
+    };
+    renderer
+        .capture(movie, &options, |i| {
+            if let Some(progress) = &progress {
+                progress.set_message(format!(
+                    "{} frame {}",
+                    swf_path.file_stem().unwrap().to_string_lossy(),
+                    i
+                ));
+                progress.inc(1);
             }
-        }
-
-        if let Some(progress) = &progress {
-            progress.inc(1);
-        }
-    }
-    Ok(result)
+        })
+        .map_err(|e| anyhow!("{} of {:?}", e, swf_path))
 }
 
 fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
@@ -196,12 +209,12 @@ fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
     results
 }
 
-fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
+fn capture_single_swf(renderer: FrameRenderer, swf: &Path, opt: &Opt) -> Result<()> {
     let output = opt.output_path.clone().unwrap_or_else(|| {
         let mut result = PathBuf::new();
-        result.set_file_name(opt.swf.file_stem().unwrap());
+        result.set_file_name(swf.file_stem().unwrap());
         if opt.frames == 1 {
-            result.set_extension("png");
+            result.set_extension(opt.format.extension());
         }
         result
     });
@@ -225,8 +238,8 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
     };
 
     let frames = take_screenshot(
-        descriptors,
-        &opt.swf,
+        renderer,
+        swf,
         opt.frames,
         opt.skipframes,
         &progress,
@@ -235,7 +248,7 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
     )?;
 
     if let Some(progress) = &progress {
-        progress.set_message(opt.swf.file_stem().unwrap().to_string_lossy().into_owned());
+        progress.set_message(swf.file_stem().unwrap().to_string_lossy().into_owned());
     }
 
     if frames.len() == 1 {
@@ -243,7 +256,7 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
     } else {
         for (frame, image) in frames.iter().enumerate() {
             let mut path: PathBuf = (&output).into();
-            path.push(format!("{frame}.png"));
+            path.push(format!("{frame}.{}", opt.format.extension()));
             image.save(&path)?;
         }
     }
@@ -251,14 +264,14 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
     let message = if frames.len() == 1 {
         format!(
             "Saved first frame of {} to {}",
-            opt.swf.to_string_lossy(),
+            swf.to_string_lossy(),
             output.to_string_lossy()
         )
     } else {
         format!(
             "Saved first {} frames of {} to {}",
             frames.len(),
-            opt.swf.to_string_lossy(),
+            swf.to_string_lossy(),
             output.to_string_lossy()
         )
     };
@@ -273,9 +286,9 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
 }
 
 #[allow(clippy::branches_sharing_code)]
-fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
+fn capture_multiple_swfs(renderer: FrameRenderer, swf: &Path, opt: &Opt) -> Result<()> {
     let output = opt.output_path.clone().unwrap();
-    let files = find_files(&opt.swf, !opt.silent);
+    let files = find_files(swf, !opt.silent);
 
     let progress = if !opt.silent {
         let progress = ProgressBar::new((files.len() as u64) * (opt.frames as u64));
@@ -302,7 +315,7 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
             );
         }
         if let Ok(frames) = take_screenshot(
-            descriptors.clone(),
+            renderer.clone(),
             file.path(),
             opt.frames,
             opt.skipframes,
@@ -312,13 +325,13 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
         ) {
             let mut relative_path = file
                 .path()
-                .strip_prefix(&opt.swf)
+                .strip_prefix(swf)
                 .unwrap_or_else(|_| file.path())
                 .to_path_buf();
 
             if frames.len() == 1 {
                 let mut destination: PathBuf = (&output).into();
-                relative_path.set_extension("png");
+                relative_path.set_extension(opt.format.extension());
                 destination.push(relative_path);
                 if let Some(parent) = destination.parent() {
                     let _ = create_dir_all(parent);
@@ -331,7 +344,7 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
                 let _ = create_dir_all(&parent);
                 for (frame, image) in frames.iter().enumerate() {
                     let mut destination = parent.clone();
-                    destination.push(format!("{frame}.png"));
+                    destination.push(format!("{frame}.{}", opt.format.extension()));
                     image.save(&destination)?;
                 }
             }
@@ -379,28 +392,55 @@ fn trace_path(_opt: &Opt) -> Option<&Path> {
     None
 }
 
+fn capture_thumbnail(opt: &ThumbnailOpt) -> Result<()> {
+    let renderer = if opt.software {
+        FrameRenderer::software(opt.graphics.into())?
+    } else {
+        FrameRenderer::hardware(opt.graphics.into(), opt.power.into(), None)?
+    };
+    let output = opt
+        .output_path
+        .clone()
+        .unwrap_or_else(|| opt.swf.with_extension("png"));
+
+    let movie = SwfMovie::from_path(&opt.swf, None).map_err(|e| anyhow!(e.to_string()))?;
+    let options = ThumbnailOptions {
+        max_size: opt.size,
+        skip_frames: opt.skipframes,
+    };
+    renderer.thumbnail(movie, &options)?.save(&output)?;
+
+    println!(
+        "Saved thumbnail of {} to {}",
+        opt.swf.to_string_lossy(),
+        output.to_string_lossy()
+    );
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let opt: Opt = Opt::parse();
-    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-        backends: opt.graphics.into(),
-        dx12_shader_compiler: wgpu::Dx12Compiler::default(),
-    });
-    let (adapter, device, queue) =
-        futures::executor::block_on(WgpuRenderBackend::<TextureTarget>::request_device(
-            opt.graphics.into(),
-            instance,
-            None,
-            opt.power.into(),
-            trace_path(&opt),
-        ))
-        .map_err(|e| anyhow!(e.to_string()))?;
-
-    let descriptors = Arc::new(Descriptors::new(adapter, device, queue));
-
-    if opt.swf.is_file() {
-        capture_single_swf(descriptors, &opt)?;
+    if let Some(Command::Thumbnail(thumbnail_opt)) = &opt.command {
+        return capture_thumbnail(thumbnail_opt);
+    }
+    let swf = opt
+        .swf
+        .as_deref()
+        .ok_or_else(|| anyhow!("No swf was given"))?;
+
+    let renderer = match opt.format {
+        OutputFormat::Png if opt.software => FrameRenderer::software(opt.graphics.into())?,
+        OutputFormat::Png => {
+            FrameRenderer::hardware(opt.graphics.into(), opt.power.into(), trace_path(&opt))?
+        }
+        // SVG frames don't need a graphics device.
+        OutputFormat::Svg => FrameRenderer::Svg,
+    };
+
+    if swf.is_file() {
+        capture_single_swf(renderer, swf, &opt)?;
     } else if opt.output_path.is_some() {
-        capture_multiple_swfs(descriptors, &opt)?;
+        capture_multiple_swfs(renderer, swf, &opt)?;
     } else {
         return Err(anyhow!(
             "Output directory is required when exporting multiple files."
@@ -0,0 +1,257 @@
+//! Packing of small bitmaps into shared texture pages.
+//!
+//! Movies often use thousands of tiny bitmaps (e.g. tiles). Giving each of them its own texture
+//! means switching textures (and bind groups) for every draw, so backends instead copy small
+//! bitmaps into a few large atlas pages and draw them with an adjusted texture matrix.
+
+/// The width and height of an atlas page, in pixels.
+pub const ATLAS_PAGE_SIZE: u32 = 1024;
+
+/// Bitmaps larger than this (in either dimension) get their own texture.
+pub const MAX_ATLAS_BITMAP_SIZE: u32 = 64;
+
+/// Every bitmap is surrounded by a border of this many pixels, which repeats its edge pixels.
+/// This prevents neighbouring bitmaps from bleeding in when sampling with linear filtering.
+pub const ATLAS_PADDING: u32 = 1;
+
+/// Shelf heights are rounded up to a multiple of this, so that freed space can be reused by
+/// bitmaps of slightly different sizes.
+const SHELF_GRANULARITY: u32 = 8;
+
+/// The area of an atlas page that holds a bitmap (excluding its padding).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    /// Returns the texture matrix that maps the unit square onto this region of a page,
+    /// in the same column-major layout as the bitmap matrices of the tessellator.
+    pub fn uv_matrix(&self, page_size: u32) -> [[f32; 3]; 3] {
+        let page_size = page_size as f32;
+        [
+            [self.width as f32 / page_size, 0.0, 0.0],
+            [0.0, self.height as f32 / page_size, 0.0],
+            [self.x as f32 / page_size, self.y as f32 / page_size, 1.0],
+        ]
+    }
+}
+
+#[derive(Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// The start of the unused space at the end of this shelf.
+    end: u32,
+    /// Freed ranges of this shelf, as `(x, width)`.
+    free: Vec<(u32, u32)>,
+    allocations: usize,
+}
+
+/// A shelf allocator for the space of a single atlas page.
+#[derive(Debug)]
+pub struct AtlasAllocator {
+    size: u32,
+    shelves: Vec<Shelf>,
+    shelves_end: u32,
+}
+
+impl AtlasAllocator {
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            shelves: Vec::new(),
+            shelves_end: 0,
+        }
+    }
+
+    /// Returns whether a bitmap of the given size should be stored in an atlas at all.
+    pub fn accepts(width: u32, height: u32) -> bool {
+        width > 0 && height > 0 && width <= MAX_ATLAS_BITMAP_SIZE && height <= MAX_ATLAS_BITMAP_SIZE
+    }
+
+    /// Returns whether nothing is currently allocated on this page.
+    pub fn is_empty(&self) -> bool {
+        self.shelves.iter().all(|shelf| shelf.allocations == 0)
+    }
+
+    /// Reserves room for a bitmap of the given size, or returns `None` if the page is full.
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<AtlasRegion> {
+        let padded_width = width + 2 * ATLAS_PADDING;
+        let padded_height = height + 2 * ATLAS_PADDING;
+        if padded_width > self.size || padded_height > self.size {
+            return None;
+        }
+
+        // Prefer the tightest existing shelf, but don't waste more than half of a shelf's height.
+        let mut best: Option<usize> = None;
+        for (i, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height < padded_height || shelf.height > padded_height * 2 {
+                continue;
+            }
+            let has_room = shelf.end + padded_width <= self.size
+                || shelf.free.iter().any(|(_, w)| *w >= padded_width);
+            let is_tighter = match best {
+                Some(best) => shelf.height < self.shelves[best].height,
+                None => true,
+            };
+            if has_room && is_tighter {
+                best = Some(i);
+            }
+        }
+
+        let index = match best {
+            Some(index) => index,
+            None => {
+                let height = round_up(padded_height, SHELF_GRANULARITY).min(self.size);
+                if self.shelves_end + height > self.size {
+                    return None;
+                }
+                self.shelves.push(Shelf {
+                    y: self.shelves_end,
+                    height,
+                    end: 0,
+                    free: Vec::new(),
+                    allocations: 0,
+                });
+                self.shelves_end += height;
+                self.shelves.len() - 1
+            }
+        };
+
+        let size = self.size;
+        let shelf = &mut self.shelves[index];
+        let x = if let Some(slot) = shelf.free.iter().position(|(_, w)| *w >= padded_width) {
+            let (x, free_width) = shelf.free[slot];
+            if free_width > padded_width {
+                shelf.free[slot] = (x + padded_width, free_width - padded_width);
+            } else {
+                shelf.free.swap_remove(slot);
+            }
+            x
+        } else {
+            debug_assert!(shelf.end + padded_width <= size);
+            let x = shelf.end;
+            shelf.end += padded_width;
+            x
+        };
+        shelf.allocations += 1;
+
+        Some(AtlasRegion {
+            x: x + ATLAS_PADDING,
+            y: shelf.y + ATLAS_PADDING,
+            width,
+            height,
+        })
+    }
+
+    /// Releases a region previously returned by `allocate`.
+    pub fn deallocate(&mut self, region: AtlasRegion) {
+        let shelf_y = region.y - ATLAS_PADDING;
+        let shelf = match self.shelves.iter_mut().find(|shelf| shelf.y == shelf_y) {
+            Some(shelf) => shelf,
+            None => return,
+        };
+        shelf.allocations = shelf.allocations.saturating_sub(1);
+        if shelf.allocations == 0 {
+            shelf.end = 0;
+            shelf.free.clear();
+        } else {
+            shelf
+                .free
+                .push((region.x - ATLAS_PADDING, region.width + 2 * ATLAS_PADDING));
+        }
+    }
+}
+
+fn round_up(value: u32, multiple: u32) -> u32 {
+    match value % multiple {
+        0 => value,
+        remainder => value + multiple - remainder,
+    }
+}
+
+/// Surrounds RGBA pixel data with `ATLAS_PADDING` pixels on each side, repeating the edge pixels.
+///
+/// The result is the data to upload at `(region.x - ATLAS_PADDING, region.y - ATLAS_PADDING)`.
+pub fn pad_rgba(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let padding = ATLAS_PADDING as usize;
+    let padded_width = width + 2 * padding;
+    let padded_height = height + 2 * padding;
+
+    let mut padded = Vec::with_capacity(padded_width * padded_height * 4);
+    for y in 0..padded_height {
+        let src_y = y.saturating_sub(padding).min(height - 1);
+        let row = &rgba[src_y * width * 4..(src_y + 1) * width * 4];
+        for _ in 0..padding {
+            padded.extend_from_slice(&row[..4]);
+        }
+        padded.extend_from_slice(row);
+        for _ in 0..padding {
+            padded.extend_from_slice(&row[row.len() - 4..]);
+        }
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: &AtlasRegion, b: &AtlasRegion) -> bool {
+        let p = ATLAS_PADDING;
+        a.x - p < b.x + b.width + p
+            && b.x - p < a.x + a.width + p
+            && a.y - p < b.y + b.height + p
+            && b.y - p < a.y + a.height + p
+    }
+
+    #[test]
+    fn allocations_do_not_overlap() {
+        let mut allocator = AtlasAllocator::new(128);
+        let mut regions = Vec::new();
+        while let Some(region) = allocator.allocate(13, 7) {
+            regions.push(region);
+        }
+        assert!(!regions.is_empty());
+        for (i, a) in regions.iter().enumerate() {
+            assert!(a.x + a.width + ATLAS_PADDING <= 128);
+            assert!(a.y + a.height + ATLAS_PADDING <= 128);
+            for b in &regions[i + 1..] {
+                assert!(!overlaps(a, b), "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn freed_space_is_reused() {
+        let mut allocator = AtlasAllocator::new(64);
+        let mut regions = Vec::new();
+        while let Some(region) = allocator.allocate(14, 14) {
+            regions.push(region);
+        }
+        let freed = regions.swap_remove(1);
+        allocator.deallocate(freed);
+        assert_eq!(allocator.allocate(14, 14), Some(freed));
+        assert_eq!(allocator.allocate(14, 14), None);
+
+        for region in regions.drain(..) {
+            allocator.deallocate(region);
+        }
+        allocator.deallocate(freed);
+        assert!(allocator.is_empty());
+    }
+
+    #[test]
+    fn padding_repeats_edges() {
+        let rgba = [1, 1, 1, 1, 2, 2, 2, 2];
+        let padded = pad_rgba(2, 1, &rgba);
+        assert_eq!(padded.len(), 4 * 3 * 4);
+        let pixels: Vec<u8> = padded.chunks(4).map(|p| p[0]).collect();
+        assert_eq!(pixels, [1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2]);
+    }
+}
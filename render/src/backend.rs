@@ -3,6 +3,7 @@ pub mod null;
 use crate::bitmap::{Bitmap, BitmapHandle, BitmapSource, SyncHandle};
 use crate::commands::CommandList;
 use crate::error::Error;
+use crate::quality::StageQuality;
 use crate::shape_utils::DistilledShape;
 use downcast_rs::{impl_downcast, Downcast};
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -66,6 +67,12 @@ pub trait RenderBackend: Downcast {
     ) -> Result<(), Error>;
 
     fn debug_info(&self) -> Cow<'static, str>;
+
+    /// Changes the quality that future frames are rendered with, such as the level of
+    /// anti-aliasing.
+    ///
+    /// Do not call this method directly - use `Stage::set_quality`.
+    fn set_quality(&mut self, quality: StageQuality);
 }
 impl_downcast!(RenderBackend);
 
@@ -1,11 +1,13 @@
 #![deny(clippy::unwrap_used)]
 
+pub mod atlas;
 pub mod backend;
 pub mod bitmap;
 pub mod bounding_box;
 pub mod color_transform;
 pub mod error;
 pub mod matrix;
+pub mod quality;
 pub mod shape_utils;
 pub mod transform;
 pub mod utils;
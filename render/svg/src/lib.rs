@@ -0,0 +1,861 @@
+//! A render backend that produces SVG documents instead of pixels.
+//!
+//! Every submitted frame is converted into a standalone SVG document, with shapes kept as vector
+//! paths. This lets the exporter extract resolution-independent artwork from a movie.
+//!
+//! Some features of the Flash renderer have no SVG equivalent, and are approximated:
+//! * Color transforms are applied to solid colors and gradients, but only affect the alpha
+//!   of bitmaps.
+//! * Blend modes without a CSS equivalent (such as add, subtract or erase) are drawn normally.
+//! * Bitmap fills always repeat, as SVG patterns always tile.
+#![deny(clippy::unwrap_used)]
+
+use base64::Engine;
+use gc_arena::MutationContext;
+use ruffle_render::backend::null::NullBitmapSource;
+use ruffle_render::backend::{
+    Context3D, Context3DCommand, RenderBackend, ShapeHandle, ViewportDimensions,
+};
+use ruffle_render::bitmap::{Bitmap, BitmapHandle, BitmapHandleImpl, BitmapSource, SyncHandle};
+use ruffle_render::color_transform::ColorTransform;
+use ruffle_render::commands::{CommandHandler, CommandList};
+use ruffle_render::error::Error;
+use ruffle_render::matrix::Matrix;
+use ruffle_render::quality::StageQuality;
+use ruffle_render::shape_utils::{
+    DistilledShape, DrawCommand, DrawPath, LineScaleMode, LineScales,
+};
+use ruffle_render::transform::Transform;
+use std::borrow::Cow;
+use std::cell::{Ref, RefCell};
+use std::fmt::Write;
+use std::sync::Arc;
+use swf::{BlendMode, Color, FillStyle, GradientInterpolation, GradientSpread};
+
+pub struct SvgRenderBackend {
+    viewport_dimensions: ViewportDimensions,
+    shapes: Vec<ShapeData>,
+    frame: Option<String>,
+}
+
+impl SvgRenderBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            viewport_dimensions: ViewportDimensions {
+                width,
+                height,
+                scale_factor: 1.0,
+            },
+            shapes: Vec::new(),
+            frame: None,
+        }
+    }
+
+    /// Returns the SVG document of the last submitted frame.
+    pub fn frame(&self) -> Option<&str> {
+        self.frame.as_deref()
+    }
+}
+
+/// A shape converted to SVG path data.
+struct ShapeData {
+    paths: Vec<SvgPath>,
+}
+
+struct SvgPath {
+    /// The path data, in pixels.
+    data: String,
+    paint: Paint,
+    /// The style of the outline, or `None` if this path is filled.
+    stroke: Option<Stroke>,
+}
+
+enum Paint {
+    Color(Color),
+    LinearGradient(swf::Gradient),
+    RadialGradient(swf::Gradient, f32),
+    Bitmap {
+        bitmap: BitmapHandle,
+        matrix: swf::Matrix,
+        is_smoothed: bool,
+    },
+    /// A bitmap fill referring to a missing bitmap.
+    None,
+}
+
+struct Stroke {
+    width: f32,
+    line_cap: &'static str,
+    line_join: &'static str,
+    miter_limit: f32,
+    scale_mode: LineScaleMode,
+}
+
+#[derive(Debug)]
+struct SvgBitmap(RefCell<SvgBitmapData>);
+
+#[derive(Debug)]
+struct SvgBitmapData {
+    width: u32,
+    height: u32,
+    /// The bitmap as a PNG `data:` URL.
+    data_url: String,
+}
+
+impl BitmapHandleImpl for SvgBitmap {}
+
+fn as_svg_bitmap(handle: &BitmapHandle) -> Ref<'_, SvgBitmapData> {
+    <dyn BitmapHandleImpl>::downcast_ref::<SvgBitmap>(&*handle.0)
+        .expect("Bitmap handle must be an SvgBitmap")
+        .0
+        .borrow()
+}
+
+/// Encodes RGBA pixels as a PNG `data:` URL.
+fn png_data_url(width: u32, height: u32, rgba: &[u8]) -> String {
+    let mut png_data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_data, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    if let Err(e) = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+    {
+        log::warn!("Couldn't encode bitmap as PNG: {}", e);
+        return String::new();
+    }
+    format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png_data)
+    )
+}
+
+fn path_data(commands: &[DrawCommand], is_closed: bool) -> String {
+    let mut data = String::new();
+    for command in commands {
+        let _ = match command {
+            DrawCommand::MoveTo { x, y } => write!(data, "M{} {}", x.to_pixels(), y.to_pixels()),
+            DrawCommand::LineTo { x, y } => write!(data, "L{} {}", x.to_pixels(), y.to_pixels()),
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => write!(
+                data,
+                "Q{} {} {} {}",
+                x1.to_pixels(),
+                y1.to_pixels(),
+                x2.to_pixels(),
+                y2.to_pixels()
+            ),
+        };
+    }
+    if is_closed {
+        data.push('Z');
+    }
+    data
+}
+
+fn swf_shape_to_svg(
+    shape: DistilledShape,
+    bitmap_source: &dyn BitmapSource,
+    backend: &mut SvgRenderBackend,
+) -> ShapeData {
+    use swf::{LineCapStyle, LineJoinStyle};
+
+    let mut convert_fill = |style: &FillStyle| match style {
+        FillStyle::Color(color) => Paint::Color(color.clone()),
+        FillStyle::LinearGradient(gradient) => Paint::LinearGradient(gradient.clone()),
+        FillStyle::RadialGradient(gradient) => Paint::RadialGradient(gradient.clone(), 0.0),
+        FillStyle::FocalGradient {
+            gradient,
+            focal_point,
+        } => Paint::RadialGradient(gradient.clone(), focal_point.to_f32()),
+        FillStyle::Bitmap {
+            id,
+            matrix,
+            is_smoothed,
+            ..
+        } => match bitmap_source.bitmap_handle(*id, backend) {
+            Some(bitmap) => Paint::Bitmap {
+                bitmap,
+                matrix: *matrix,
+                is_smoothed: *is_smoothed,
+            },
+            None => {
+                log::warn!("Couldn't fill shape with unknown bitmap {}", id);
+                Paint::None
+            }
+        },
+    };
+
+    let mut paths = Vec::with_capacity(shape.paths.len());
+    for path in &shape.paths {
+        match path {
+            DrawPath::Fill { style, commands } => paths.push(SvgPath {
+                data: path_data(commands, false),
+                paint: convert_fill(style),
+                stroke: None,
+            }),
+            DrawPath::Stroke {
+                style,
+                is_closed,
+                commands,
+            } => {
+                let line_cap = match style.start_cap() {
+                    LineCapStyle::Round => "round",
+                    LineCapStyle::Square => "square",
+                    LineCapStyle::None => "butt",
+                };
+                let (line_join, miter_limit) = match style.join_style() {
+                    LineJoinStyle::Round => ("round", 1.0),
+                    LineJoinStyle::Bevel => ("bevel", 1.0),
+                    LineJoinStyle::Miter(limit) => ("miter", limit.to_f32().max(1.0)),
+                };
+                paths.push(SvgPath {
+                    data: path_data(commands, *is_closed),
+                    paint: convert_fill(style.fill_style()),
+                    stroke: Some(Stroke {
+                        width: style.width().to_pixels() as f32,
+                        line_cap,
+                        line_join,
+                        miter_limit,
+                        scale_mode: match (style.allow_scale_x(), style.allow_scale_y()) {
+                            (false, false) => LineScaleMode::None,
+                            (true, false) => LineScaleMode::Horizontal,
+                            (false, true) => LineScaleMode::Vertical,
+                            (true, true) => LineScaleMode::Both,
+                        },
+                    }),
+                });
+            }
+        }
+    }
+    ShapeData { paths }
+}
+
+fn color_value(color: &Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+fn matrix_value(matrix: &Matrix) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        matrix.a,
+        matrix.b,
+        matrix.c,
+        matrix.d,
+        matrix.tx.to_pixels(),
+        matrix.ty.to_pixels()
+    )
+}
+
+/// Formats the matrix of a gradient or bitmap fill, which maps into twips, for use in
+/// path coordinates (which are in pixels).
+fn fill_matrix_value(matrix: &swf::Matrix) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        matrix.a.to_f32() / 20.0,
+        matrix.b.to_f32() / 20.0,
+        matrix.c.to_f32() / 20.0,
+        matrix.d.to_f32() / 20.0,
+        matrix.tx.to_pixels(),
+        matrix.ty.to_pixels()
+    )
+}
+
+fn blend_mode_value(blend_mode: BlendMode) -> Option<&'static str> {
+    match blend_mode {
+        BlendMode::Multiply => Some("multiply"),
+        BlendMode::Screen => Some("screen"),
+        BlendMode::Lighten => Some("lighten"),
+        BlendMode::Darken => Some("darken"),
+        BlendMode::Difference => Some("difference"),
+        BlendMode::Overlay => Some("overlay"),
+        BlendMode::HardLight => Some("hard-light"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum LayerKind {
+    /// Visible content.
+    Content,
+    /// The content of a mask, only its shape matters.
+    Mask,
+    /// Content drawn to clear a mask, which SVG does not need.
+    Discard,
+}
+
+struct Layer {
+    kind: LayerKind,
+    data: String,
+}
+
+/// Builds the SVG document of a single frame.
+struct SvgFrame<'a> {
+    shapes: &'a [ShapeData],
+    defs: String,
+    layers: Vec<Layer>,
+    next_id: usize,
+}
+
+impl<'a> SvgFrame<'a> {
+    fn new(shapes: &'a [ShapeData]) -> Self {
+        Self {
+            shapes,
+            defs: String::new(),
+            layers: vec![Layer {
+                kind: LayerKind::Content,
+                data: String::new(),
+            }],
+            next_id: 0,
+        }
+    }
+
+    fn layer_kind(&self) -> &LayerKind {
+        self.layers
+            .last()
+            .map(|layer| &layer.kind)
+            .unwrap_or(&LayerKind::Discard)
+    }
+
+    fn output(&mut self) -> &mut String {
+        &mut self
+            .layers
+            .last_mut()
+            .expect("Frame must have a layer")
+            .data
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Defines the given paint (if necessary), and returns the value to use for a `fill` or
+    /// `stroke` attribute, along with its opacity.
+    fn paint(&mut self, paint: &Paint, color_transform: &ColorTransform) -> (String, f32) {
+        match paint {
+            Paint::Color(color) => {
+                let color = *color_transform * color.clone();
+                (color_value(&color), f32::from(color.a) / 255.0)
+            }
+            Paint::LinearGradient(gradient) => {
+                let id = self.next_id();
+                let _ = write!(
+                    self.defs,
+                    r#"<linearGradient id="g{}" gradientUnits="userSpaceOnUse" x1="-16384" y1="0" x2="16384" y2="0""#,
+                    id
+                );
+                self.gradient_body(gradient, color_transform, "linearGradient");
+                (format!("url(#g{id})"), 1.0)
+            }
+            Paint::RadialGradient(gradient, focal_point) => {
+                let id = self.next_id();
+                let _ = write!(
+                    self.defs,
+                    r#"<radialGradient id="g{}" gradientUnits="userSpaceOnUse" cx="0" cy="0" r="16384" fx="{}" fy="0""#,
+                    id,
+                    focal_point * 16384.0
+                );
+                self.gradient_body(gradient, color_transform, "radialGradient");
+                (format!("url(#g{id})"), 1.0)
+            }
+            Paint::Bitmap {
+                bitmap,
+                matrix,
+                is_smoothed,
+            } => {
+                let id = self.next_id();
+                let bitmap = as_svg_bitmap(bitmap);
+                let _ = write!(
+                    self.defs,
+                    r#"<pattern id="p{}" patternUnits="userSpaceOnUse" width="{}" height="{}" patternTransform="{}"><image width="{}" height="{}"{} xlink:href="{}"/></pattern>"#,
+                    id,
+                    bitmap.width,
+                    bitmap.height,
+                    fill_matrix_value(matrix),
+                    bitmap.width,
+                    bitmap.height,
+                    if *is_smoothed {
+                        ""
+                    } else {
+                        r#" style="image-rendering:pixelated""#
+                    },
+                    bitmap.data_url
+                );
+                (format!("url(#p{id})"), color_transform.a_mult.to_f32())
+            }
+            Paint::None => ("none".to_string(), 1.0),
+        }
+    }
+
+    /// Writes the remaining attributes and the stops of a gradient definition.
+    fn gradient_body(
+        &mut self,
+        gradient: &swf::Gradient,
+        color_transform: &ColorTransform,
+        element: &str,
+    ) {
+        let spread = match gradient.spread {
+            GradientSpread::Pad => "pad",
+            GradientSpread::Reflect => "reflect",
+            GradientSpread::Repeat => "repeat",
+        };
+        let interpolation = match gradient.interpolation {
+            GradientInterpolation::Rgb => "sRGB",
+            GradientInterpolation::LinearRgb => "linearRGB",
+        };
+        let _ = write!(
+            self.defs,
+            r#" spreadMethod="{}" color-interpolation="{}" gradientTransform="{}">"#,
+            spread,
+            interpolation,
+            fill_matrix_value(&gradient.matrix)
+        );
+        for record in &gradient.records {
+            let color = *color_transform * record.color.clone();
+            let _ = write!(
+                self.defs,
+                r#"<stop offset="{}" stop-color="{}" stop-opacity="{}"/>"#,
+                f32::from(record.ratio) / 255.0,
+                color_value(&color),
+                f32::from(color.a) / 255.0
+            );
+        }
+        let _ = write!(self.defs, "</{element}>");
+    }
+
+    fn finish(mut self, dimensions: ViewportDimensions, clear: Color) -> String {
+        let content = self.layers.swap_remove(0).data;
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            dimensions.width, dimensions.height
+        );
+        if !self.defs.is_empty() {
+            let _ = write!(svg, "<defs>{}</defs>", self.defs);
+        }
+        if clear.a > 0 {
+            let _ = write!(
+                svg,
+                r#"<rect width="100%" height="100%" fill="{}" fill-opacity="{}"/>"#,
+                color_value(&clear),
+                f32::from(clear.a) / 255.0
+            );
+        }
+        svg.push_str(&content);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl<'a> CommandHandler for SvgFrame<'a> {
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: Transform, smoothing: bool) {
+        let bitmap = as_svg_bitmap(&bitmap);
+        match self.layer_kind() {
+            LayerKind::Content => {
+                let element = format!(
+                    r#"<image width="{}" height="{}" transform="{}" opacity="{}"{} xlink:href="{}"/>"#,
+                    bitmap.width,
+                    bitmap.height,
+                    matrix_value(&transform.matrix),
+                    transform.color_transform.a_mult.to_f32(),
+                    if smoothing {
+                        ""
+                    } else {
+                        r#" style="image-rendering:pixelated""#
+                    },
+                    bitmap.data_url
+                );
+                self.output().push_str(&element);
+            }
+            LayerKind::Mask => {
+                // Bitmaps mask their entire bounds.
+                let element = format!(
+                    r#"<rect width="{}" height="{}" transform="{}"/>"#,
+                    bitmap.width,
+                    bitmap.height,
+                    matrix_value(&transform.matrix)
+                );
+                self.output().push_str(&element);
+            }
+            LayerKind::Discard => (),
+        }
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: Transform) {
+        let shapes = self.shapes;
+        let shape = match shapes.get(shape.0) {
+            Some(shape) => shape,
+            None => return,
+        };
+        let is_mask = match self.layer_kind() {
+            LayerKind::Content => false,
+            LayerKind::Mask => true,
+            LayerKind::Discard => return,
+        };
+
+        let mut line_scales = LineScales::new(&transform.matrix);
+        let mut element = format!(r#"<g transform="{}">"#, matrix_value(&transform.matrix));
+        for path in &shape.paths {
+            match &path.stroke {
+                None if is_mask => {
+                    let _ = write!(element, r#"<path d="{}" fill-rule="evenodd"/>"#, path.data);
+                }
+                None => {
+                    let (paint, opacity) = self.paint(&path.paint, &transform.color_transform);
+                    let _ = write!(
+                        element,
+                        r#"<path d="{}" fill="{}" fill-opacity="{}" fill-rule="evenodd"/>"#,
+                        path.data, paint, opacity
+                    );
+                }
+                // Strokes are ignored by masks.
+                Some(_) if is_mask => (),
+                Some(stroke) => {
+                    let (paint, opacity) = self.paint(&path.paint, &transform.color_transform);
+                    // The width is transformed here, as the stroke itself should not be
+                    // distorted by the transform of the shape.
+                    let width = line_scales.transform_width(stroke.width, stroke.scale_mode);
+                    let _ = write!(
+                        element,
+                        r#"<path d="{}" fill="none" stroke="{}" stroke-opacity="{}" stroke-width="{}" stroke-linecap="{}" stroke-linejoin="{}" stroke-miterlimit="{}" vector-effect="non-scaling-stroke"/>"#,
+                        path.data,
+                        paint,
+                        opacity,
+                        width,
+                        stroke.line_cap,
+                        stroke.line_join,
+                        stroke.miter_limit
+                    );
+                }
+            }
+        }
+        element.push_str("</g>");
+        self.output().push_str(&element);
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: Matrix) {
+        let element = match self.layer_kind() {
+            LayerKind::Content => format!(
+                r#"<rect width="1" height="1" transform="{}" fill="{}" fill-opacity="{}"/>"#,
+                matrix_value(&matrix),
+                color_value(&color),
+                f32::from(color.a) / 255.0
+            ),
+            LayerKind::Mask => format!(
+                r#"<rect width="1" height="1" transform="{}"/>"#,
+                matrix_value(&matrix)
+            ),
+            LayerKind::Discard => return,
+        };
+        self.output().push_str(&element);
+    }
+
+    fn push_mask(&mut self) {
+        self.layers.push(Layer {
+            kind: LayerKind::Mask,
+            data: String::new(),
+        });
+    }
+
+    fn activate_mask(&mut self) {
+        if self.layers.len() <= 1 {
+            return;
+        }
+        let mask = match self.layers.pop() {
+            Some(layer) => layer.data,
+            None => return,
+        };
+        let id = self.next_id();
+        let _ = write!(
+            self.defs,
+            r#"<mask id="m{id}" mask-type="alpha">{mask}</mask>"#
+        );
+        let element = format!(r#"<g mask="url(#m{id})">"#);
+        self.output().push_str(&element);
+    }
+
+    fn deactivate_mask(&mut self) {
+        self.output().push_str("</g>");
+        self.layers.push(Layer {
+            kind: LayerKind::Discard,
+            data: String::new(),
+        });
+    }
+
+    fn pop_mask(&mut self) {
+        if self.layers.len() > 1 {
+            self.layers.pop();
+        }
+    }
+
+    fn blend(&mut self, commands: CommandList, blend_mode: BlendMode) {
+        if *self.layer_kind() == LayerKind::Discard {
+            return;
+        }
+        let element = match blend_mode_value(blend_mode) {
+            Some(mode) => format!(r#"<g style="mix-blend-mode:{mode}">"#),
+            None => "<g>".to_string(),
+        };
+        self.output().push_str(&element);
+        commands.execute(self);
+        self.output().push_str("</g>");
+    }
+}
+
+impl RenderBackend for SvgRenderBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        self.viewport_dimensions
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        self.viewport_dimensions = dimensions;
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        let handle = ShapeHandle(self.shapes.len());
+        let data = swf_shape_to_svg(shape, bitmap_source, self);
+        self.shapes.push(data);
+        handle
+    }
+
+    fn replace_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+        handle: ShapeHandle,
+    ) {
+        let data = swf_shape_to_svg(shape, bitmap_source, self);
+        if let Some(shape) = self.shapes.get_mut(handle.0) {
+            *shape = data;
+        }
+    }
+
+    fn register_glyph_shape(&mut self, glyph: &swf::Glyph) -> ShapeHandle {
+        let shape = ruffle_render::shape_utils::swf_glyph_to_shape(glyph);
+        self.register_shape((&shape).into(), &NullBitmapSource)
+    }
+
+    fn render_offscreen(
+        &mut self,
+        _handle: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _commands: CommandList,
+    ) -> Result<Box<dyn SyncHandle>, Error> {
+        Err(Error::Unimplemented)
+    }
+
+    fn submit_frame(&mut self, clear: Color, commands: CommandList) {
+        let mut frame = SvgFrame::new(&self.shapes);
+        commands.execute(&mut frame);
+        self.frame = Some(frame.finish(self.viewport_dimensions, clear));
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapHandle, Error> {
+        let bitmap = bitmap.to_rgba();
+        Ok(BitmapHandle(Arc::new(SvgBitmap(RefCell::new(
+            SvgBitmapData {
+                width: bitmap.width(),
+                height: bitmap.height(),
+                data_url: png_data_url(bitmap.width(), bitmap.height(), bitmap.data()),
+            },
+        )))))
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        let bitmap = <dyn BitmapHandleImpl>::downcast_ref::<SvgBitmap>(&*handle.0)
+            .ok_or_else(|| Error::UnknownHandle(handle.clone()))?;
+        *bitmap.0.borrow_mut() = SvgBitmapData {
+            width,
+            height,
+            data_url: png_data_url(width, height, &rgba),
+        };
+        Ok(())
+    }
+
+    fn create_context3d(&mut self) -> Result<Box<dyn Context3D>, Error> {
+        Err(Error::Unimplemented)
+    }
+
+    fn context3d_present<'gc>(
+        &mut self,
+        _context: &mut dyn Context3D,
+        _commands: Vec<Context3DCommand<'gc>>,
+        _mc: MutationContext<'gc, '_>,
+    ) -> Result<(), Error> {
+        Err(Error::Unimplemented)
+    }
+
+    fn debug_info(&self) -> Cow<'static, str> {
+        Cow::Borrowed("Renderer: SVG")
+    }
+
+    fn set_quality(&mut self, _quality: StageQuality) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::Twips;
+
+    fn px(pixels: i32) -> Twips {
+        Twips::from_pixels_i32(pixels)
+    }
+
+    /// A 10x10 triangle.
+    fn triangle() -> Vec<DrawCommand> {
+        vec![
+            DrawCommand::MoveTo { x: px(0), y: px(0) },
+            DrawCommand::LineTo {
+                x: px(10),
+                y: px(0),
+            },
+            DrawCommand::LineTo {
+                x: px(10),
+                y: px(10),
+            },
+        ]
+    }
+
+    fn register_fill(backend: &mut SvgRenderBackend, style: &FillStyle) -> ShapeHandle {
+        let shape = DistilledShape {
+            paths: vec![DrawPath::Fill {
+                style,
+                commands: triangle(),
+            }],
+            shape_bounds: Default::default(),
+            edge_bounds: Default::default(),
+            id: 1,
+        };
+        backend.register_shape(shape, &NullBitmapSource)
+    }
+
+    #[test]
+    fn path_data_formats_commands() {
+        let commands = vec![
+            DrawCommand::MoveTo { x: px(0), y: px(0) },
+            DrawCommand::LineTo {
+                x: px(10),
+                y: px(0),
+            },
+            DrawCommand::CurveTo {
+                x1: px(10),
+                y1: px(10),
+                x2: px(0),
+                y2: px(10),
+            },
+        ];
+        assert_eq!(path_data(&commands, false), "M0 0L10 0Q10 10 0 10");
+        assert_eq!(path_data(&commands, true), "M0 0L10 0Q10 10 0 10Z");
+        assert_eq!(path_data(&[], false), "");
+    }
+
+    #[test]
+    fn frame_contains_filled_shape() {
+        let mut backend = SvgRenderBackend::new(20, 10);
+        assert_eq!(backend.frame(), None);
+
+        let fill = FillStyle::Color(Color::from_rgb(0xff0000, 255));
+        let shape = register_fill(&mut backend, &fill);
+        let mut commands = CommandList::new();
+        commands.render_shape(
+            shape,
+            Transform {
+                matrix: Matrix::translate(px(5), px(0)),
+                ..Default::default()
+            },
+        );
+        backend.submit_frame(Color::WHITE, commands);
+
+        assert_eq!(
+            backend.frame(),
+            Some(concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="20" height="10" viewBox="0 0 20 10">"#,
+                r#"<rect width="100%" height="100%" fill="#ffffff" fill-opacity="1"/>"#,
+                r#"<g transform="matrix(1 0 0 1 5 0)">"#,
+                r#"<path d="M0 0L10 0L10 10" fill="#ff0000" fill-opacity="1" fill-rule="evenodd"/>"#,
+                "</g></svg>\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn transparent_clear_color_is_omitted() {
+        let mut backend = SvgRenderBackend::new(20, 10);
+        backend.submit_frame(Color::from_rgba(0), CommandList::new());
+        let frame = backend.frame().expect("Frame was submitted");
+        assert!(!frame.contains("<rect"));
+    }
+
+    #[test]
+    fn color_transform_applies_to_solid_fill() {
+        let mut backend = SvgRenderBackend::new(20, 10);
+        let fill = FillStyle::Color(Color::from_rgb(0xff0000, 255));
+        let shape = register_fill(&mut backend, &fill);
+        let mut commands = CommandList::new();
+        commands.render_shape(
+            shape,
+            Transform {
+                color_transform: ColorTransform {
+                    g_add: 255,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        backend.submit_frame(Color::WHITE, commands);
+
+        let frame = backend.frame().expect("Frame was submitted");
+        assert!(frame.contains(r#"fill="#ffff00" fill-opacity="1""#));
+        assert!(!frame.contains("#ff0000"));
+    }
+
+    #[test]
+    fn masks_become_mask_definitions() {
+        let mut backend = SvgRenderBackend::new(20, 10);
+        let fill = FillStyle::Color(Color::from_rgb(0xff0000, 255));
+        let shape = register_fill(&mut backend, &fill);
+        let mut commands = CommandList::new();
+        commands.push_mask();
+        commands.render_shape(shape, Default::default());
+        commands.activate_mask();
+        commands.draw_rect(Color::from_rgb(0x0000ff, 255), Matrix::scale(20.0, 10.0));
+        commands.deactivate_mask();
+        // The shape drawn to clear the mask must not show up in the document.
+        commands.render_shape(shape, Default::default());
+        commands.pop_mask();
+        backend.submit_frame(Color::from_rgba(0), commands);
+
+        assert_eq!(
+            backend.frame(),
+            Some(concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="20" height="10" viewBox="0 0 20 10">"#,
+                r#"<defs><mask id="m1" mask-type="alpha">"#,
+                r#"<g transform="matrix(1 0 0 1 0 0)"><path d="M0 0L10 0L10 10" fill-rule="evenodd"/></g>"#,
+                "</mask></defs>",
+                r#"<g mask="url(#m1)">"#,
+                r#"<rect width="1" height="1" transform="matrix(20 0 0 10 0 0)" fill="#0000ff" fill-opacity="1"/>"#,
+                "</g></svg>\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn unknown_shape_is_ignored() {
+        let mut backend = SvgRenderBackend::new(20, 10);
+        let mut commands = CommandList::new();
+        commands.render_shape(ShapeHandle(7), Default::default());
+        backend.submit_frame(Color::from_rgba(0), commands);
+        let frame = backend.frame().expect("Frame was submitted");
+        assert!(!frame.contains("<g"));
+    }
+}
@@ -5,6 +5,7 @@ use bytemuck::{Pod, Zeroable};
 use std::borrow::Cow;
 
 use gc_arena::MutationContext;
+use ruffle_render::atlas::{pad_rgba, AtlasAllocator, AtlasRegion, ATLAS_PADDING, ATLAS_PAGE_SIZE};
 use ruffle_render::backend::null::NullBitmapSource;
 use ruffle_render::backend::{
     Context3D, Context3DCommand, RenderBackend, ShapeHandle, ViewportDimensions,
@@ -14,12 +15,15 @@ use ruffle_render::bitmap::{
 };
 use ruffle_render::commands::{CommandHandler, CommandList};
 use ruffle_render::error::Error as BitmapError;
+use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::DistilledShape;
 use ruffle_render::tessellator::{
     Gradient as TessGradient, GradientType, ShapeTessellator, Vertex as TessVertex,
 };
 use ruffle_render::transform::Transform;
 use ruffle_web_common::{JsError, JsResult};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 use swf::{BlendMode, Color};
 use thiserror::Error;
@@ -122,6 +126,10 @@ pub struct WebGlRenderBackend {
     msaa_buffers: Option<MsaaBuffers>,
     msaa_sample_count: u32,
 
+    // The highest MSAA sample count that the stage quality may ask for, chosen when the context
+    // was created.
+    max_msaa_sample_count: u32,
+
     color_program: ShaderProgram,
     bitmap_program: ShaderProgram,
     gradient_program: ShaderProgram,
@@ -150,6 +158,9 @@ pub struct WebGlRenderBackend {
     // This is currently unused - we just hold on to it
     // to expose via `get_viewport_dimensions`
     viewport_scale_factor: f64,
+
+    /// Shared textures holding small bitmaps, see `ruffle_render::atlas`.
+    atlas_pages: Vec<Rc<AtlasPage>>,
 }
 
 #[derive(Debug)]
@@ -157,6 +168,8 @@ struct RegistryData {
     gl: Gl,
     bitmap: Bitmap,
     texture: WebGlTexture,
+    /// A copy of this bitmap in an atlas page, used when drawing it as a bitmap.
+    atlas_entry: RefCell<Option<AtlasEntry>>,
 }
 
 impl Drop for RegistryData {
@@ -165,6 +178,32 @@ impl Drop for RegistryData {
     }
 }
 
+#[derive(Debug)]
+struct AtlasPage {
+    gl: Gl,
+    texture: WebGlTexture,
+    allocator: RefCell<AtlasAllocator>,
+}
+
+impl Drop for AtlasPage {
+    fn drop(&mut self) {
+        self.gl.delete_texture(Some(&self.texture));
+    }
+}
+
+#[derive(Debug)]
+struct AtlasEntry {
+    page: Rc<AtlasPage>,
+    region: AtlasRegion,
+    texture_matrix: [[f32; 3]; 3],
+}
+
+impl Drop for AtlasEntry {
+    fn drop(&mut self) {
+        self.page.allocator.borrow_mut().deallocate(self.region);
+    }
+}
+
 impl BitmapHandleImpl for RegistryData {}
 
 fn as_registry_data(handle: &BitmapHandle) -> &RegistryData {
@@ -300,6 +339,7 @@ impl WebGlRenderBackend {
 
             msaa_buffers: None,
             msaa_sample_count,
+            max_msaa_sample_count: msaa_sample_count,
 
             color_program,
             gradient_program,
@@ -325,6 +365,7 @@ impl WebGlRenderBackend {
             add_color: None,
 
             viewport_scale_factor: 1.0,
+            atlas_pages: Vec::new(),
         };
 
         renderer.push_blend_mode(BlendMode::Normal);
@@ -453,16 +494,10 @@ impl WebGlRenderBackend {
     }
 
     fn build_msaa_buffers(&mut self) -> Result<(), Error> {
-        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
-            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
-            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
-            return Ok(());
-        }
-
-        let gl = self.gl2.as_ref().expect("gl2 must exist at this point");
-
         // Delete previous buffers, if they exist.
+        // This is also needed when MSAA was just turned off by a quality change.
         if let Some(msaa_buffers) = self.msaa_buffers.take() {
+            let gl = &self.gl;
             gl.delete_renderbuffer(Some(&msaa_buffers.color_renderbuffer));
             gl.delete_renderbuffer(Some(&msaa_buffers.stencil_renderbuffer));
             gl.delete_framebuffer(Some(&msaa_buffers.render_framebuffer));
@@ -470,6 +505,14 @@ impl WebGlRenderBackend {
             gl.delete_texture(Some(&msaa_buffers.framebuffer_texture));
         }
 
+        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
+            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+            return Ok(());
+        }
+
+        let gl = self.gl2.as_ref().expect("gl2 must exist at this point");
+
         // Create frame and render buffers.
         let render_framebuffer = gl
             .create_framebuffer()
@@ -934,6 +977,108 @@ impl WebGlRenderBackend {
             self.apply_blend_mode(current);
         }
     }
+
+    fn create_atlas_page(&self) -> Result<AtlasPage, BitmapError> {
+        let texture = self
+            .gl
+            .create_texture()
+            .ok_or(BitmapError::JavascriptError(
+                "Unable to create texture".into(),
+            ))?;
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        self.gl
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                Gl::TEXTURE_2D,
+                0,
+                Gl::RGBA as i32,
+                ATLAS_PAGE_SIZE as i32,
+                ATLAS_PAGE_SIZE as i32,
+                0,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                None,
+            )
+            .into_js_result()
+            .map_err(|e| BitmapError::JavascriptError(e.into()))?;
+        self.gl
+            .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        self.gl
+            .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+
+        Ok(AtlasPage {
+            gl: self.gl.clone(),
+            texture,
+            allocator: RefCell::new(AtlasAllocator::new(ATLAS_PAGE_SIZE)),
+        })
+    }
+
+    /// Copies a small bitmap into an atlas page, so that it can be drawn without switching textures.
+    fn insert_into_atlas(&mut self, bitmap: &Bitmap) -> Result<Option<AtlasEntry>, BitmapError> {
+        if !AtlasAllocator::accepts(bitmap.width(), bitmap.height()) {
+            return Ok(None);
+        }
+
+        // Entries keep their page alive; release pages that became empty (but keep the first).
+        let mut is_first = true;
+        self.atlas_pages.retain(|page| {
+            let keep = is_first || Rc::strong_count(page) > 1;
+            is_first = false;
+            keep
+        });
+
+        let mut allocation = None;
+        for page in &self.atlas_pages {
+            let region = page
+                .allocator
+                .borrow_mut()
+                .allocate(bitmap.width(), bitmap.height());
+            if let Some(region) = region {
+                allocation = Some((page.clone(), region));
+                break;
+            }
+        }
+        let (page, region) = match allocation {
+            Some(allocation) => allocation,
+            None => {
+                let page = Rc::new(self.create_atlas_page()?);
+                self.atlas_pages.push(page.clone());
+                let region = page
+                    .allocator
+                    .borrow_mut()
+                    .allocate(bitmap.width(), bitmap.height());
+                match region {
+                    Some(region) => (page, region),
+                    None => return Ok(None),
+                }
+            }
+        };
+        let entry = AtlasEntry {
+            texture_matrix: region.uv_matrix(ATLAS_PAGE_SIZE),
+            page,
+            region,
+        };
+
+        let rgba = bitmap.clone().to_rgba();
+        let padded = pad_rgba(rgba.width(), rgba.height(), rgba.data());
+        self.gl
+            .bind_texture(Gl::TEXTURE_2D, Some(&entry.page.texture));
+        self.gl
+            .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+                Gl::TEXTURE_2D,
+                0,
+                (region.x - ATLAS_PADDING) as i32,
+                (region.y - ATLAS_PADDING) as i32,
+                (region.width + 2 * ATLAS_PADDING) as i32,
+                (region.height + 2 * ATLAS_PADDING) as i32,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                Some(&padded),
+            )
+            .into_js_result()
+            .map_err(|e| BitmapError::JavascriptError(e.into()))?;
+
+        Ok(Some(entry))
+    }
 }
 
 impl RenderBackend for WebGlRenderBackend {
@@ -1060,10 +1205,13 @@ impl RenderBackend for WebGlRenderBackend {
         self.gl
             .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::LINEAR as i32);
 
+        let atlas_entry = self.insert_into_atlas(&bitmap)?;
+
         Ok(BitmapHandle(Arc::new(RegistryData {
             gl: self.gl.clone(),
             bitmap,
             texture,
+            atlas_entry: RefCell::new(atlas_entry),
         })))
     }
 
@@ -1074,7 +1222,10 @@ impl RenderBackend for WebGlRenderBackend {
         height: u32,
         rgba: Vec<u8>,
     ) -> Result<(), BitmapError> {
-        let texture = &as_registry_data(handle).texture;
+        let entry = as_registry_data(handle);
+        // The atlas copy is now outdated.
+        entry.atlas_entry.replace(None);
+        let texture = &entry.texture;
 
         self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
 
@@ -1138,6 +1289,16 @@ impl RenderBackend for WebGlRenderBackend {
 
         return Cow::Owned(result.join("\n"));
     }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        let sample_count = quality.sample_count().min(self.max_msaa_sample_count);
+        if sample_count != self.msaa_sample_count {
+            self.msaa_sample_count = sample_count;
+            if let Err(e) = self.build_msaa_buffers() {
+                log::error!("Unable to rebuild MSAA buffers: {:?}", e);
+            }
+        }
+    }
 }
 
 impl CommandHandler for WebGlRenderBackend {
@@ -1202,11 +1363,17 @@ impl CommandHandler for WebGlRenderBackend {
             self.add_color = Some(add_color);
         }
 
-        program.uniform_matrix3fv(&self.gl, ShaderUniform::TextureMatrix, bitmap_matrix);
+        let atlas_entry = entry.atlas_entry.borrow();
+        let (texture, texture_matrix) = match &*atlas_entry {
+            Some(atlas_entry) => (&atlas_entry.page.texture, &atlas_entry.texture_matrix),
+            None => (&entry.texture, bitmap_matrix),
+        };
+
+        program.uniform_matrix3fv(&self.gl, ShaderUniform::TextureMatrix, texture_matrix);
 
         // Bind texture.
         self.gl.active_texture(Gl::TEXTURE0);
-        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&entry.texture));
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(texture));
         program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
 
         // Set texture parameters.
@@ -0,0 +1,280 @@
+use crate::descriptors::Descriptors;
+use crate::mesh::BitmapBinds;
+use crate::TextureTransforms;
+use ruffle_render::atlas::{pad_rgba, AtlasAllocator, AtlasRegion, ATLAS_PADDING, ATLAS_PAGE_SIZE};
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+
+/// The maximum number of bitmaps on a single page.
+/// Every bitmap needs its own texture transform, so this bounds the size of that buffer.
+const MAX_ENTRIES_PER_PAGE: u32 = 1024;
+
+#[derive(Debug)]
+struct PageAllocator {
+    space: AtlasAllocator,
+    free_slots: Vec<u32>,
+    next_slot: u32,
+}
+
+/// A texture shared by many small bitmaps.
+///
+/// All bitmaps on a page are drawn with the same bind group; the texture transform of each
+/// bitmap is selected with a dynamic offset into `texture_transforms`.
+#[derive(Debug)]
+pub struct AtlasPage {
+    texture: wgpu::Texture,
+    texture_transforms: wgpu::Buffer,
+    transforms_stride: u32,
+    bind_linear: BitmapBinds,
+    bind_nearest: BitmapBinds,
+    allocator: Mutex<PageAllocator>,
+}
+
+impl AtlasPage {
+    fn new(descriptors: &Descriptors) -> Self {
+        let extent = wgpu::Extent3d {
+            width: ATLAS_PAGE_SIZE,
+            height: ATLAS_PAGE_SIZE,
+            depth_or_array_layers: 1,
+        };
+        let texture_label = create_debug_label!("Bitmap atlas page");
+        let texture = descriptors.device.create_texture(&wgpu::TextureDescriptor {
+            label: texture_label.as_deref(),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+
+        let align_mask = descriptors.limits.min_uniform_buffer_offset_alignment - 1;
+        let transforms_stride =
+            (std::mem::size_of::<TextureTransforms>() as u32 + align_mask) & !align_mask;
+        let transforms_label = create_debug_label!("Bitmap atlas page texture transforms");
+        let texture_transforms = descriptors.device.create_buffer(&wgpu::BufferDescriptor {
+            label: transforms_label.as_deref(),
+            size: u64::from(transforms_stride * MAX_ENTRIES_PER_PAGE),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let create_binds = |smoothed| {
+            BitmapBinds::new(
+                &descriptors.device,
+                &descriptors.bind_layouts.bitmap,
+                descriptors.bitmap_samplers.get_sampler(false, smoothed),
+                &texture_transforms,
+                texture.create_view(&Default::default()),
+                create_debug_label!("Bitmap atlas page bind group (smoothed: {})", smoothed),
+            )
+        };
+        let bind_linear = create_binds(true);
+        let bind_nearest = create_binds(false);
+
+        Self {
+            texture,
+            texture_transforms,
+            transforms_stride,
+            bind_linear,
+            bind_nearest,
+            allocator: Mutex::new(PageAllocator {
+                space: AtlasAllocator::new(ATLAS_PAGE_SIZE),
+                free_slots: Vec::new(),
+                next_slot: 0,
+            }),
+        }
+    }
+
+    pub fn bind_group(&self, smoothed: bool) -> &wgpu::BindGroup {
+        match smoothed {
+            true => &self.bind_linear.bind_group,
+            false => &self.bind_nearest.bind_group,
+        }
+    }
+
+    fn insert(
+        self: &Arc<Self>,
+        descriptors: &Descriptors,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Option<AtlasEntry> {
+        let mut allocator = self.allocator.lock().expect("Atlas page lock poisoned");
+        let slot = match allocator.free_slots.pop() {
+            Some(slot) => slot,
+            None if allocator.next_slot < MAX_ENTRIES_PER_PAGE => {
+                allocator.next_slot += 1;
+                allocator.next_slot - 1
+            }
+            None => return None,
+        };
+        let region = match allocator.space.allocate(width, height) {
+            Some(region) => region,
+            None => {
+                allocator.free_slots.push(slot);
+                return None;
+            }
+        };
+        drop(allocator);
+
+        self.write(descriptors, region, rgba);
+
+        let matrix = region.uv_matrix(ATLAS_PAGE_SIZE);
+        let mut u_matrix = [[0.0; 4]; 4];
+        u_matrix[0][..3].copy_from_slice(&matrix[0]);
+        u_matrix[1][..3].copy_from_slice(&matrix[1]);
+        u_matrix[2][..3].copy_from_slice(&matrix[2]);
+        u_matrix[3][3] = 1.0;
+        descriptors.queue.write_buffer(
+            &self.texture_transforms,
+            u64::from(slot * self.transforms_stride),
+            bytemuck::cast_slice(&[TextureTransforms { u_matrix }]),
+        );
+
+        Some(AtlasEntry {
+            page: self.clone(),
+            region,
+            slot,
+        })
+    }
+
+    /// Uploads the pixels of a bitmap into its region, along with the padding around it.
+    fn write(&self, descriptors: &Descriptors, region: AtlasRegion, rgba: &[u8]) {
+        let padded_extent = wgpu::Extent3d {
+            width: region.width + 2 * ATLAS_PADDING,
+            height: region.height + 2 * ATLAS_PADDING,
+            depth_or_array_layers: 1,
+        };
+        descriptors.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.x - ATLAS_PADDING,
+                    y: region.y - ATLAS_PADDING,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &pad_rgba(region.width, region.height, rgba),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(4 * padded_extent.width),
+                rows_per_image: None,
+            },
+            padded_extent,
+        );
+    }
+}
+
+/// A bitmap stored on an atlas page. Its space is released once this is dropped.
+#[derive(Debug)]
+pub struct AtlasEntry {
+    page: Arc<AtlasPage>,
+    region: AtlasRegion,
+    slot: u32,
+}
+
+impl AtlasEntry {
+    pub fn page(&self) -> &AtlasPage {
+        &self.page
+    }
+
+    /// The dynamic offset that selects the texture transform of this bitmap.
+    pub fn transforms_offset(&self) -> u32 {
+        self.slot * self.page.transforms_stride
+    }
+
+    /// Replaces the pixels of this bitmap, which must keep its size.
+    pub fn write(&self, descriptors: &Descriptors, rgba: &[u8]) {
+        self.page.write(descriptors, self.region, rgba);
+    }
+
+    /// Copies this bitmap into a texture of its own size.
+    pub fn copy_to(&self, descriptors: &Descriptors, texture: &wgpu::Texture) {
+        let label = create_debug_label!("Bitmap atlas copy encoder");
+        let mut encoder =
+            descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                });
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.page.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: self.region.x,
+                    y: self.region.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: self.region.width,
+                height: self.region.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        descriptors.queue.submit(Some(encoder.finish()));
+    }
+}
+
+impl Drop for AtlasEntry {
+    fn drop(&mut self) {
+        if let Ok(mut allocator) = self.page.allocator.lock() {
+            allocator.space.deallocate(self.region);
+            allocator.free_slots.push(self.slot);
+        }
+    }
+}
+
+/// Packs small bitmaps into shared textures, see `ruffle_render::atlas`.
+#[derive(Debug, Default)]
+pub struct TextureAtlas {
+    pages: Vec<Arc<AtlasPage>>,
+}
+
+impl TextureAtlas {
+    /// Copies a bitmap into the atlas, returning `None` if it is too large to be put into one.
+    pub fn insert(
+        &mut self,
+        descriptors: &Descriptors,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Option<AtlasEntry> {
+        if !AtlasAllocator::accepts(width, height) {
+            return None;
+        }
+
+        // Entries keep their page alive; release pages that became empty (but keep the first).
+        let mut is_first = true;
+        self.pages.retain(|page| {
+            let keep = is_first || Arc::strong_count(page) > 1;
+            is_first = false;
+            keep
+        });
+
+        for page in &self.pages {
+            if let Some(entry) = page.insert(descriptors, width, height, rgba) {
+                return Some(entry);
+            }
+        }
+
+        let page = Arc::new(AtlasPage::new(descriptors));
+        let entry = page.insert(descriptors, width, height, rgba);
+        self.pages.push(page);
+        entry
+    }
+}
@@ -1,3 +1,4 @@
+use crate::atlas::TextureAtlas;
 use crate::buffer_pool::TexturePool;
 use crate::context3d::WgpuContext3D;
 use crate::mesh::{Draw, Mesh};
@@ -6,21 +7,23 @@ use crate::target::RenderTargetFrame;
 use crate::target::TextureTarget;
 use crate::uniform_buffer::BufferStorage;
 use crate::{
-    as_texture, format_list, get_backend_names, ColorAdjustments, Descriptors, Error,
-    QueueSyncHandle, RenderTarget, SwapChainTarget, Texture, Transforms,
+    as_texture, create_bitmap_texture, format_list, get_backend_names, write_bitmap_texture,
+    ColorAdjustments, Descriptors, Error, QueueSyncHandle, RenderTarget, SwapChainTarget, Texture,
+    Transforms,
 };
 use gc_arena::MutationContext;
+use once_cell::sync::OnceCell;
 use ruffle_render::backend::{Context3D, Context3DCommand};
 use ruffle_render::backend::{RenderBackend, ShapeHandle, ViewportDimensions};
 use ruffle_render::bitmap::{Bitmap, BitmapHandle, BitmapSource, SyncHandle};
 use ruffle_render::commands::CommandList;
 use ruffle_render::error::Error as BitmapError;
+use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::DistilledShape;
 use ruffle_render::tessellator::ShapeTessellator;
 use std::borrow::Cow;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::mem;
-use std::num::NonZeroU32;
 use std::path::Path;
 use std::sync::Arc;
 use swf::Color;
@@ -38,9 +41,13 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     // This is currently unused - we just store it to report in
     // `get_viewport_dimensions`
     viewport_scale_factor: f64,
+    /// The highest MSAA sample count that the stage quality may ask for.
     preferred_sample_count: u32,
+    /// The MSAA sample count currently used, as chosen by the stage quality.
+    sample_count: u32,
     texture_pool: TexturePool,
     offscreen_texture_pool: TexturePool,
+    texture_atlas: TextureAtlas,
 }
 
 impl WgpuRenderBackend<SwapChainTarget> {
@@ -191,8 +198,10 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             shape_tessellator: ShapeTessellator::new(),
             viewport_scale_factor: 1.0,
             preferred_sample_count,
+            sample_count: preferred_sample_count,
             texture_pool: TexturePool::new(),
             offscreen_texture_pool: TexturePool::new(),
+            texture_atlas: TextureAtlas::default(),
         })
     }
 
@@ -223,6 +232,31 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         Ok((adapter, device, queue))
     }
 
+    /// Requests a device on a software adapter (such as WARP or llvmpipe), for rendering on
+    /// machines without a GPU.
+    pub async fn request_software_device(
+        backend: wgpu::Backends,
+        instance: wgpu::Instance,
+        trace_path: Option<&Path>,
+    ) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue), Error> {
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::LowPower,
+                compatible_surface: None,
+                force_fallback_adapter: true,
+            })
+            .await
+            .ok_or_else(|| {
+                format!(
+                    "No software renderer was found supporting {}",
+                    format_list(&get_backend_names(backend), "or")
+                )
+            })?;
+
+        let (device, queue) = request_device(&adapter, trace_path).await?;
+        Ok((adapter, device, queue))
+    }
+
     fn register_shape_internal(
         &mut self,
         shape: DistilledShape,
@@ -279,7 +313,7 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
         self.surface = Surface::new(
             &self.descriptors,
-            self.preferred_sample_count,
+            self.sample_count,
             width,
             height,
             self.target.format(),
@@ -315,11 +349,12 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         let handle = BitmapHandle(Arc::new(Texture {
             bind_linear: Default::default(),
             bind_nearest: Default::default(),
-            texture: Arc::new(dummy_texture),
+            texture: OnceCell::with_value(Arc::new(dummy_texture)),
             texture_offscreen: Default::default(),
             width: 0,
             height: 0,
             copy_count: Cell::new(0),
+            atlas_entry: Default::default(),
         }));
         Ok(Box::new(WgpuContext3D::new(
             self.descriptors.clone(),
@@ -366,6 +401,29 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         Cow::Owned(result.join("\n"))
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        // Only 1x and 4x MSAA are guaranteed to be supported by every device, so any
+        // anti-aliasing uses the preferred sample count instead of the exact one of the quality.
+        let sample_count = if quality.sample_count() > 1 {
+            self.preferred_sample_count
+        } else {
+            1
+        };
+        if sample_count == self.sample_count {
+            return;
+        }
+
+        self.sample_count = sample_count;
+        self.surface = Surface::new(
+            &self.descriptors,
+            sample_count,
+            self.target.width(),
+            self.target.height(),
+            self.target.format(),
+        );
+        self.texture_pool = TexturePool::new();
+    }
+
     fn viewport_dimensions(&self) -> ViewportDimensions {
         ViewportDimensions {
             width: self.target.width(),
@@ -461,54 +519,41 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         }
 
         let bitmap = bitmap.to_rgba();
-        let extent = wgpu::Extent3d {
-            width: bitmap.width(),
-            height: bitmap.height(),
-            depth_or_array_layers: 1,
-        };
 
-        let texture_label = create_debug_label!("Bitmap");
-        let texture = self
-            .descriptors
-            .device
-            .create_texture(&wgpu::TextureDescriptor {
-                label: texture_label.as_deref(),
-                size: extent,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8Unorm,
-                view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
-                usage: wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::COPY_DST
-                    | wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::COPY_SRC,
-            });
-
-        self.descriptors.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: Default::default(),
-                aspect: wgpu::TextureAspect::All,
-            },
+        // Small bitmaps are only uploaded to an atlas page, until they need a texture of their own.
+        let atlas_entry = self.texture_atlas.insert(
+            &self.descriptors,
+            bitmap.width(),
+            bitmap.height(),
             bitmap.data(),
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: NonZeroU32::new(4 * extent.width),
-                rows_per_image: None,
-            },
-            extent,
         );
+        let texture = OnceCell::new();
+        if atlas_entry.is_none() {
+            let texture = texture.get_or_init(|| {
+                Arc::new(create_bitmap_texture(
+                    &self.descriptors.device,
+                    bitmap.width(),
+                    bitmap.height(),
+                ))
+            });
+            write_bitmap_texture(
+                &self.descriptors,
+                texture,
+                bitmap.width(),
+                bitmap.height(),
+                bitmap.data(),
+            );
+        }
 
         let handle = BitmapHandle(Arc::new(Texture {
-            texture: Arc::new(texture),
+            texture,
             bind_linear: Default::default(),
             bind_nearest: Default::default(),
             texture_offscreen: Default::default(),
             width: bitmap.width(),
             height: bitmap.height(),
             copy_count: Cell::new(0),
+            atlas_entry: RefCell::new(atlas_entry.map(Arc::new)),
         }));
 
         Ok(handle)
@@ -523,28 +568,16 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         rgba: Vec<u8>,
     ) -> Result<(), BitmapError> {
         let texture = as_texture(handle);
+        if texture.is_only_in_atlas() && width == texture.width && height == texture.height {
+            if let Some(entry) = texture.atlas_entry() {
+                entry.write(&self.descriptors, &rgba);
+                return Ok(());
+            }
+        }
 
-        let extent = wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
-
-        self.descriptors.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture.texture,
-                mip_level: 0,
-                origin: Default::default(),
-                aspect: wgpu::TextureAspect::All,
-            },
-            &rgba,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: NonZeroU32::new(4 * extent.width),
-                rows_per_image: None,
-            },
-            extent,
-        );
+        let wgpu_texture = texture.texture(&self.descriptors);
+        texture.remove_from_atlas();
+        write_bitmap_texture(&self.descriptors, wgpu_texture, width, height, &rgba);
 
         Ok(())
     }
@@ -558,6 +591,9 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         commands: CommandList,
     ) -> Result<Box<dyn SyncHandle>, ruffle_render::error::Error> {
         let texture = as_texture(&handle);
+        // Drawing starts from the current pixels, so those are copied out of the atlas first.
+        let wgpu_texture = texture.texture(&self.descriptors).clone();
+        texture.remove_from_atlas();
 
         let extent = wgpu::Extent3d {
             width,
@@ -569,7 +605,7 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
         let mut target = TextureTarget {
             size: extent,
-            texture: texture.texture.clone(),
+            texture: wgpu_texture,
             format: wgpu::TextureFormat::Rgba8Unorm,
             buffer: texture_offscreen.map(|t| (t.buffer.clone(), t.buffer_dimensions.clone())),
         };
@@ -580,7 +616,7 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
         let mut surface = Surface::new(
             &self.descriptors,
-            self.preferred_sample_count,
+            self.sample_count,
             width,
             height,
             wgpu::TextureFormat::Rgba8Unorm,
@@ -14,6 +14,7 @@ use wgpu::{CommandEncoder, Extent3d, RenderPass};
 use crate::descriptors::Descriptors;
 use crate::Texture;
 use gc_arena::{Collect, MutationContext};
+use once_cell::sync::OnceCell;
 
 use std::num::NonZeroU64;
 use std::rc::Rc;
@@ -291,13 +292,14 @@ impl WgpuContext3D {
                     self.texture_view = Some(wgpu_texture.create_view(&Default::default()));
 
                     self.raw_texture_handle = BitmapHandle(Arc::new(Texture {
-                        texture: Arc::new(wgpu_texture),
+                        texture: OnceCell::with_value(Arc::new(wgpu_texture)),
                         bind_linear: Default::default(),
                         bind_nearest: Default::default(),
                         texture_offscreen: Default::default(),
                         width: *width,
                         height: *height,
                         copy_count: Cell::new(0),
+                        atlas_entry: Default::default(),
                     }));
                 }
                 Context3DCommand::UploadToIndexBuffer {
@@ -1,5 +1,4 @@
-use crate::bitmaps::BitmapSamplers;
-use crate::descriptors::Quad;
+use crate::atlas::AtlasEntry;
 use crate::mesh::BitmapBinds;
 use crate::pipelines::Pipelines;
 use crate::target::{RenderTarget, SwapChainTarget};
@@ -14,7 +13,8 @@ use once_cell::sync::OnceCell;
 use ruffle_render::bitmap::{Bitmap, BitmapHandle, BitmapHandleImpl, SyncHandle};
 use ruffle_render::color_transform::ColorTransform;
 use ruffle_render::tessellator::{Gradient as TessGradient, GradientType, Vertex as TessVertex};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::num::NonZeroU32;
 use std::sync::Arc;
 pub use wgpu;
 
@@ -23,6 +23,7 @@ type Error = Box<dyn std::error::Error>;
 #[macro_use]
 mod utils;
 
+mod atlas;
 mod bitmaps;
 mod context3d;
 mod globals;
@@ -262,7 +263,7 @@ impl QueueSyncHandle {
                         });
                 encoder.copy_texture_to_buffer(
                     wgpu::ImageCopyTexture {
-                        texture: &texture.texture,
+                        texture: texture.texture(&descriptors),
                         mip_level: 0,
                         origin: wgpu::Origin3d::ZERO,
                         aspect: wgpu::TextureAspect::All,
@@ -306,24 +307,51 @@ impl QueueSyncHandle {
 
 #[derive(Debug)]
 pub struct Texture {
-    texture: Arc<wgpu::Texture>,
+    /// The texture of this bitmap. Bitmaps on an atlas page only get one once they're used for
+    /// something the page can't do, see `Texture::texture`.
+    texture: OnceCell<Arc<wgpu::Texture>>,
     bind_linear: OnceCell<BitmapBinds>,
     bind_nearest: OnceCell<BitmapBinds>,
     texture_offscreen: OnceCell<TextureOffscreen>,
     copy_count: Cell<u8>,
     width: u32,
     height: u32,
+    /// The place of this bitmap on a shared atlas page, used when drawing it as a bitmap.
+    atlas_entry: RefCell<Option<Arc<AtlasEntry>>>,
 }
 
 impl Texture {
+    /// Returns the texture of this bitmap, needed to fill shapes with it, draw into it or read it
+    /// back. A bitmap on an atlas page is copied out of it the first time.
+    pub fn texture(&self, descriptors: &Descriptors) -> &Arc<wgpu::Texture> {
+        self.texture.get_or_init(|| {
+            let texture = create_bitmap_texture(&descriptors.device, self.width, self.height);
+            if let Some(entry) = self.atlas_entry() {
+                entry.copy_to(descriptors, &texture);
+            }
+            Arc::new(texture)
+        })
+    }
+
+    /// Whether this bitmap only lives on its atlas page so far.
+    pub fn is_only_in_atlas(&self) -> bool {
+        self.texture.get().is_none() && self.atlas_entry.borrow().is_some()
+    }
+
+    pub fn atlas_entry(&self) -> Option<Arc<AtlasEntry>> {
+        self.atlas_entry.borrow().clone()
+    }
+
+    /// Stops drawing this texture from the atlas, as its contents have changed.
+    pub fn remove_from_atlas(&self) {
+        self.atlas_entry.replace(None);
+    }
+
     pub fn bind_group(
         &self,
         smoothed: bool,
-        device: &wgpu::Device,
-        layout: &wgpu::BindGroupLayout,
-        quad: &Quad,
+        descriptors: &Descriptors,
         handle: BitmapHandle,
-        samplers: &BitmapSamplers,
     ) -> &BitmapBinds {
         let bind = match smoothed {
             true => &self.bind_linear,
@@ -331,17 +359,69 @@ impl Texture {
         };
         bind.get_or_init(|| {
             BitmapBinds::new(
-                &device,
-                &layout,
-                samplers.get_sampler(false, smoothed),
-                &quad.texture_transforms,
-                self.texture.create_view(&Default::default()),
+                &descriptors.device,
+                &descriptors.bind_layouts.bitmap,
+                descriptors.bitmap_samplers.get_sampler(false, smoothed),
+                &descriptors.quad.texture_transforms,
+                self.texture(descriptors).create_view(&Default::default()),
                 create_debug_label!("Bitmap {:?} bind group (smoothed: {})", handle.0, smoothed),
             )
         })
     }
 }
 
+/// Creates the texture of a bitmap, which may be drawn, drawn into and read back.
+fn create_bitmap_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    let texture_label = create_debug_label!("Bitmap");
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: texture_label.as_deref(),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC,
+    })
+}
+
+/// Uploads the pixels of a whole bitmap into its texture.
+fn write_bitmap_texture(
+    descriptors: &Descriptors,
+    texture: &wgpu::Texture,
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+) {
+    let extent = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    descriptors.queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: Default::default(),
+            aspect: wgpu::TextureAspect::All,
+        },
+        rgba,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: NonZeroU32::new(4 * width),
+            rows_per_image: None,
+        },
+        extent,
+    );
+}
+
 #[derive(Debug)]
 struct TextureOffscreen {
     buffer: Arc<wgpu::Buffer>,
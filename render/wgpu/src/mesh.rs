@@ -1,7 +1,8 @@
 use crate::backend::WgpuRenderBackend;
 use crate::target::RenderTarget;
 use crate::{
-    as_texture, create_buffer_with_data, Descriptors, GradientStorage, GradientUniforms, Vertex,
+    as_texture, create_buffer_with_data, Descriptors, GradientStorage, GradientUniforms,
+    TextureTransforms, Vertex,
 };
 
 use ruffle_render::backend::RenderBackend;
@@ -185,7 +186,9 @@ impl DrawType {
     ) -> Option<Self> {
         let handle = source.bitmap_handle(bitmap.bitmap_id, backend)?;
         let texture = as_texture(&handle);
-        let texture_view = texture.texture.create_view(&Default::default());
+        let texture_view = texture
+            .texture(descriptors)
+            .create_view(&Default::default());
         let texture_transforms = create_texture_transforms(
             &descriptors.device,
             &bitmap.matrix,
@@ -230,12 +233,18 @@ impl BitmapBinds {
         texture_view: wgpu::TextureView,
         label: Option<String>,
     ) -> Self {
+        // Bound with a dynamic offset, see `crate::atlas::AtlasPage`.
+        let texture_transforms = wgpu::BufferBinding {
+            buffer: texture_transforms,
+            offset: 0,
+            size: wgpu::BufferSize::new(std::mem::size_of::<TextureTransforms>() as u64),
+        };
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: texture_transforms.as_entire_binding(),
+                    resource: wgpu::BindingResource::Buffer(texture_transforms),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
@@ -160,10 +160,10 @@ impl Surface {
                     ],
                 }]),
             );
-            render_pass.set_bind_group(1, &copy_bind_group, &[]);
+            render_pass.set_bind_group(1, &copy_bind_group, &[0]);
         } else {
             render_pass.set_bind_group(1, target.whole_frame_bind_group(descriptors), &[0]);
-            render_pass.set_bind_group(2, &copy_bind_group, &[]);
+            render_pass.set_bind_group(2, &copy_bind_group, &[0]);
         }
 
         render_pass.set_vertex_buffer(0, descriptors.quad.vertices.slice(..));
@@ -1,3 +1,4 @@
+use crate::atlas::AtlasEntry;
 use crate::blend::TrivialBlend;
 use crate::blend::{BlendType, ComplexBlend};
 use crate::buffer_pool::{PoolEntry, TexturePool};
@@ -15,6 +16,7 @@ use ruffle_render::commands::Command;
 use ruffle_render::matrix::Matrix;
 use ruffle_render::tessellator::GradientType;
 use ruffle_render::transform::Transform;
+use std::sync::Arc;
 use swf::{BlendMode, Color, Fixed8, GradientSpread};
 
 pub struct CommandRenderer<'pass, 'frame: 'pass, 'global: 'frame> {
@@ -77,10 +79,17 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
         match command {
             DrawCommand::RenderBitmap {
                 bitmap,
+                atlas_entry,
                 transform,
                 smoothing,
                 blend_mode,
-            } => self.render_bitmap(bitmap, transform, *smoothing, *blend_mode),
+            } => self.render_bitmap(
+                bitmap,
+                atlas_entry.as_deref(),
+                transform,
+                *smoothing,
+                *blend_mode,
+            ),
             DrawCommand::RenderTexture {
                 _texture,
                 binds,
@@ -131,7 +140,12 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
         );
     }
 
-    pub fn prep_bitmap(&mut self, bind_group: &'pass wgpu::BindGroup, blend_mode: TrivialBlend) {
+    pub fn prep_bitmap(
+        &mut self,
+        bind_group: &'pass wgpu::BindGroup,
+        texture_transforms_offset: u32,
+        blend_mode: TrivialBlend,
+    ) {
         if self.needs_depth {
             self.render_pass
                 .set_pipeline(self.pipelines.bitmap[blend_mode].pipeline_for(self.mask_state));
@@ -147,7 +161,7 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
                 3
             },
             bind_group,
-            &[],
+            &[texture_transforms_offset],
         );
     }
 
@@ -218,6 +232,7 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
     pub fn render_bitmap(
         &mut self,
         bitmap: &'frame BitmapHandle,
+        atlas_entry: Option<&'frame AtlasEntry>,
         transform: &Transform,
         smoothing: bool,
         blend_mode: TrivialBlend,
@@ -228,16 +243,17 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
         }
         let texture = as_texture(bitmap);
 
-        let descriptors = self.descriptors;
-        let bind = texture.bind_group(
-            smoothing,
-            &descriptors.device,
-            &descriptors.bind_layouts.bitmap,
-            &descriptors.quad,
-            bitmap.clone(),
-            &descriptors.bitmap_samplers,
-        );
-        self.prep_bitmap(&bind.bind_group, blend_mode);
+        if let Some(entry) = atlas_entry {
+            self.prep_bitmap(
+                entry.page().bind_group(smoothing),
+                entry.transforms_offset(),
+                blend_mode,
+            );
+        } else {
+            let descriptors = self.descriptors;
+            let bind = texture.bind_group(smoothing, descriptors, bitmap.clone());
+            self.prep_bitmap(&bind.bind_group, 0, blend_mode);
+        }
         self.apply_transform(
             &(transform.matrix
                 * Matrix {
@@ -267,7 +283,7 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
         if cfg!(feature = "render_debug_labels") {
             self.render_pass.push_debug_group("render_texture");
         }
-        self.prep_bitmap(bind_group, blend_mode);
+        self.prep_bitmap(bind_group, 0, blend_mode);
         self.apply_transform(&transform.matrix, &transform.color_transform);
 
         self.draw(
@@ -313,7 +329,7 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
                     self.prep_gradient(bind_group, *mode, *spread);
                 }
                 DrawType::Bitmap { binds, .. } => {
-                    self.prep_bitmap(&binds.bind_group, TrivialBlend::Normal);
+                    self.prep_bitmap(&binds.bind_group, 0, TrivialBlend::Normal);
                 }
             }
             self.apply_transform(&transform.matrix, &transform.color_transform);
@@ -414,6 +430,7 @@ pub enum Chunk {
 pub enum DrawCommand {
     RenderBitmap {
         bitmap: BitmapHandle,
+        atlas_entry: Option<Arc<AtlasEntry>>,
         transform: Transform,
         smoothing: bool,
         blend_mode: TrivialBlend,
@@ -551,6 +568,7 @@ pub fn chunk_blends<'a>(
                 transform,
                 smoothing,
             } => current.push(DrawCommand::RenderBitmap {
+                atlas_entry: as_texture(&bitmap).atlas_entry(),
                 bitmap,
                 transform,
                 smoothing,
@@ -13,10 +13,21 @@ pub fn analyze(results: impl Iterator<Item = FileResults>) {
     let mut parse = 0;
     let mut execute = 0;
     let mut complete = 0;
+    let mut uses_stubs = 0;
+    let mut executed = 0;
+    let mut total_frame_time = 0.0;
 
     for result in results {
         total += 1;
 
+        if result.stubs.is_some() {
+            uses_stubs += 1;
+        }
+        if let Some(average_frame_time) = result.average_frame_time {
+            executed += 1;
+            total_frame_time += average_frame_time;
+        }
+
         match result.progress {
             Step::Start => start += 1,
             Step::Read => read += 1,
@@ -48,6 +59,15 @@ pub fn analyze(results: impl Iterator<Item = FileResults>) {
     println!("{execute:>digits$} movies failed to execute");
     println!("{complete:>digits$} movies completed without errors");
     println!();
+
+    if executed > 0 {
+        println!("{uses_stubs:>digits$} movies used unimplemented APIs");
+        println!(
+            "{:>digits$.2} ms average frame time",
+            total_frame_time / executed as f64
+        );
+        println!();
+    }
 }
 
 pub fn analyze_main(opt: AnalyzeOpt) -> Result<(), std::io::Error> {
@@ -35,6 +35,14 @@ pub struct ScanOpt {
     /// Filenames to ignore
     #[clap(short = 'i', long = "ignore", action = clap::ArgAction::Append)]
     pub ignore: Vec<String>,
+
+    /// The number of frames to run each movie for
+    #[clap(short = 'f', long = "frames", default_value_t = 1)]
+    pub frames: u32,
+
+    /// The number of seconds a single movie may take before it is killed
+    #[clap(short = 't', long = "timeout", default_value_t = 60)]
+    pub timeout: u64,
 }
 
 #[derive(Parser, Debug)]
@@ -49,4 +57,8 @@ pub struct ExecuteReportOpt {
     /// The single SWF file to parse and run
     #[clap(name = "file")]
     pub input_path: PathBuf,
+
+    /// The number of frames to run the movie for
+    #[clap(short = 'f', long = "frames", default_value_t = 1)]
+    pub frames: u32,
 }
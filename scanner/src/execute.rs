@@ -14,9 +14,18 @@ use std::panic::catch_unwind;
 use std::path::Path;
 use std::time::{Duration, Instant};
 
-fn execute_swf(file: &Path) {
+/// What was observed while running a movie.
+struct ExecutionReport {
+    /// The average time taken by each frame, in milliseconds.
+    average_frame_time: f64,
+
+    /// Every unimplemented API the movie used.
+    stubs: Vec<String>,
+}
+
+fn execute_swf(file: &Path, frames: u32) -> ExecutionReport {
     let base_path = file.parent().unwrap();
-    let executor = NullExecutor::new();
+    let mut executor = NullExecutor::new();
     let movie = SwfMovie::from_path(file, None).unwrap();
     let frame_time = 1000.0 / movie.frame_rate().to_f64();
     let player = PlayerBuilder::new()
@@ -28,9 +37,29 @@ fn execute_swf(file: &Path) {
 
     player.lock().unwrap().preload(&mut ExecutionLimit::none());
 
-    player.lock().unwrap().run_frame();
-    player.lock().unwrap().update_timers(frame_time);
-    //executor.poll_all().unwrap();
+    let frames = frames.max(1);
+    let mut total_frame_time = Duration::ZERO;
+    for _ in 0..frames {
+        let frame_start = Instant::now();
+        player.lock().unwrap().run_frame();
+        player.lock().unwrap().update_timers(frame_time);
+        executor.run();
+        total_frame_time += frame_start.elapsed();
+    }
+
+    let mut stubs: Vec<String> = player
+        .lock()
+        .unwrap()
+        .stubs()
+        .iter()
+        .map(|stub| stub.to_string())
+        .collect();
+    stubs.sort();
+
+    ExecutionReport {
+        average_frame_time: total_frame_time.as_secs_f64() * 1000.0 / f64::from(frames),
+        stubs,
+    }
 }
 
 fn checkpoint<W: Write>(
@@ -124,6 +153,7 @@ pub fn execute_report_main(execute_report_opt: ExecuteReportOpt) -> Result<(), s
                 file_result.use_direct_blit = Some(swf.header.use_direct_blit());
                 file_result.use_gpu = Some(swf.header.use_gpu());
                 file_result.use_network_sandbox = Some(swf.header.use_network_sandbox());
+                file_result.has_metadata = Some(swf.header.has_metadata());
                 file_result.vm_type = Some(match swf.header.is_action_script_3() {
                     true => AvmType::Avm2,
                     false => AvmType::Avm1,
@@ -149,9 +179,16 @@ pub fn execute_report_main(execute_report_opt: ExecuteReportOpt) -> Result<(), s
     checkpoint(&mut file_result, &start, &mut writer)?;
     file_result.progress = Step::Execute;
 
-    //Run one frame of the movie in Ruffle.
-    if let Err(e) = catch_unwind(|| execute_swf(&file_path)) {
-        match e.downcast::<String>() {
+    //Run the requested number of frames of the movie in Ruffle.
+    match catch_unwind(|| execute_swf(&file_path, execute_report_opt.frames)) {
+        Ok(report) => {
+            file_result.frames_executed = Some(execute_report_opt.frames.max(1));
+            file_result.average_frame_time = Some(report.average_frame_time);
+            if !report.stubs.is_empty() {
+                file_result.stubs = Some(report.stubs.join("\n"));
+            }
+        }
+        Err(e) => match e.downcast::<String>() {
             Ok(e) => {
                 file_result.error = Some(format!("PANIC: {e}"));
                 checkpoint(&mut file_result, &start, &mut writer)?;
@@ -160,7 +197,7 @@ pub fn execute_report_main(execute_report_opt: ExecuteReportOpt) -> Result<(), s
                 file_result.error = Some("PANIC".to_string());
                 checkpoint(&mut file_result, &start, &mut writer)?;
             }
-        }
+        },
     }
 
     let errors = LOCAL_LOGGER.with(|log_buffer| {
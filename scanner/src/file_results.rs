@@ -123,9 +123,25 @@ pub struct FileResults {
     #[serde(rename = "Network Sandbox")]
     pub use_network_sandbox: Option<bool>,
 
+    /// Whether or not the SWF claims to contain XMP metadata.
+    #[serde(rename = "Has Metadata")]
+    pub has_metadata: Option<bool>,
+
     /// The AVM type of the movie.
     #[serde(rename = "AVM Version")]
     pub vm_type: Option<AvmType>,
+
+    /// The number of frames the movie was run for.
+    #[serde(rename = "Frames Executed")]
+    pub frames_executed: Option<u32>,
+
+    /// The average time taken by each executed frame, in milliseconds.
+    #[serde(rename = "Average Frame Time")]
+    pub average_frame_time: Option<f64>,
+
+    /// The unimplemented APIs used by the movie, one per line.
+    #[serde(rename = "Stubs")]
+    pub stubs: Option<String>,
 }
 
 impl Default for FileResults {
@@ -152,7 +168,11 @@ impl FileResults {
             use_direct_blit: None,
             use_gpu: None,
             use_network_sandbox: None,
+            has_metadata: None,
             vm_type: None,
+            frames_executed: None,
+            average_frame_time: None,
+            stubs: None,
         }
     }
 }
@@ -14,8 +14,10 @@ use walkdir::{DirEntry, WalkDir};
 
 use std::env;
 use std::ffi::OsStr;
-use std::process::Command;
-use std::time::Instant;
+use std::io::Read;
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub fn find_files(root: &Path, ignore: &[String]) -> Vec<DirEntry> {
     let progress = ProgressBar::new_spinner();
@@ -38,15 +40,71 @@ pub fn find_files(root: &Path, ignore: &[String]) -> Vec<DirEntry> {
     results
 }
 
-pub fn scan_file<P: AsRef<OsStr>>(exec_path: P, file: &DirEntry, name: &str) -> FileResults {
+/// Reads everything from a pipe of a child process on another thread, so that
+/// the child never blocks on a full pipe.
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut data = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut data);
+        }
+        data
+    })
+}
+
+/// Waits for `child` to exit, killing it once `timeout` has passed.
+///
+/// Also returns whether the child had to be killed, in which case the output
+/// is whatever it wrote until then.
+fn wait_with_timeout(mut child: Child, timeout: Duration) -> std::io::Result<(Output, bool)> {
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let deadline = Instant::now() + timeout;
+
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status, false);
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            break (child.wait()?, true);
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let output = Output {
+        status,
+        stdout,
+        stderr,
+    };
+    Ok((output, timed_out))
+}
+
+pub fn scan_file<P: AsRef<OsStr>>(
+    exec_path: P,
+    file: &DirEntry,
+    name: &str,
+    frames: u32,
+    timeout: Duration,
+) -> FileResults {
     let start = Instant::now();
     let mut file_results = FileResults::new(name);
 
     let subproc = Command::new(exec_path)
-        .args(&["execute-report", &file.path().to_string_lossy()])
-        .output();
+        .args([
+            "execute-report",
+            "--frames",
+            &frames.to_string(),
+            &file.path().to_string_lossy(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|child| wait_with_timeout(child, timeout));
     match subproc {
-        Ok(output) => {
+        Ok((output, timed_out)) => {
             let mut reader = csv::Reader::from_reader(&output.stdout[..]);
             for row in reader.deserialize::<FileResults>() {
                 match row {
@@ -67,7 +125,11 @@ pub fn scan_file<P: AsRef<OsStr>>(exec_path: P, file: &DirEntry, name: &str) ->
                             use_direct_blit,
                             use_gpu,
                             use_network_sandbox,
+                            has_metadata,
                             vm_type,
+                            frames_executed,
+                            average_frame_time,
+                            stubs,
                         } = child_results;
 
                         file_results.hash = hash;
@@ -84,7 +146,11 @@ pub fn scan_file<P: AsRef<OsStr>>(exec_path: P, file: &DirEntry, name: &str) ->
                         file_results.use_direct_blit = use_direct_blit;
                         file_results.use_gpu = use_gpu;
                         file_results.use_network_sandbox = use_network_sandbox;
+                        file_results.has_metadata = has_metadata;
                         file_results.vm_type = vm_type;
+                        file_results.frames_executed = frames_executed;
+                        file_results.average_frame_time = average_frame_time;
+                        file_results.stubs = stubs;
                     }
                     Err(e) => {
                         file_results.error = Some(e.to_string());
@@ -101,6 +167,16 @@ pub fn scan_file<P: AsRef<OsStr>>(exec_path: P, file: &DirEntry, name: &str) ->
                         .unwrap_or(panic_error),
                 );
             }
+
+            if timed_out {
+                let timeout_error = format!("Timed out after {} seconds", timeout.as_secs());
+                file_results.error = Some(
+                    file_results
+                        .error
+                        .map(|e| format!("{e}\n{timeout_error}"))
+                        .unwrap_or(timeout_error),
+                );
+            }
         }
         Err(e) => file_results.error = Some(e.to_string()),
     }
@@ -117,6 +193,7 @@ pub fn scan_main(opt: ScanOpt) -> Result<(), std::io::Error> {
     let binary_path = env::current_exe()?;
     let to_scan = find_files(&opt.input_path, &opt.ignore);
     let mut writer = csv::Writer::from_path(opt.output_path.clone())?;
+    let timeout = Duration::from_secs(opt.timeout);
 
     let progress = ProgressBar::new(to_scan.len() as u64);
     progress.set_style(
@@ -135,7 +212,7 @@ pub fn scan_main(opt: ScanOpt) -> Result<(), std::io::Error> {
                 .strip_prefix(&opt.input_path)
                 .unwrap_or_else(|_| file.path())
                 .to_slash_lossy();
-            let result = scan_file(&binary_path, &file, &name);
+            let result = scan_file(&binary_path, &file, &name, opt.frames, timeout);
 
             progress.inc(1);
             progress.set_message(name.into_owned());
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use swf::read::Strictness;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(swf_buf) = swf::decompress_swf(data) {
+        let _ = swf::parse_swf(&swf_buf);
+        let _ = swf::read::parse_swf_with_strictness(&swf_buf, Strictness::Lenient);
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use swf::read::{Reader, Strictness};
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte picks the SWF version, the rest is a stream of tags.
+    if let Some((&version, tags)) = data.split_first() {
+        let mut reader = Reader::new(tags, version);
+        reader.set_strictness(Strictness::Lenient);
+        while let Ok(tag) = reader.read_tag() {
+            if tag == swf::Tag::End {
+                break;
+            }
+        }
+    }
+});
@@ -29,6 +29,38 @@ pub fn parse_swf(swf_buf: &SwfBuf) -> Result<Swf<'_>> {
     })
 }
 
+/// Parse a decompressed SWF, handling malformed tags according to `strictness`.
+///
+/// Along with the SWF, this returns the errors of the tags that were skipped,
+/// which only happens with `Strictness::Lenient`.
+///
+/// # Example
+/// ```
+/// # std::env::set_current_dir(env!("CARGO_MANIFEST_DIR"));
+/// use swf::read::Strictness;
+///
+/// let data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
+/// let stream = swf::decompress_swf(&data[..]).unwrap();
+/// let (swf, errors) = swf::read::parse_swf_with_strictness(&stream, Strictness::Lenient).unwrap();
+/// for error in errors {
+///     println!("Skipped malformed tag: {}", error);
+/// }
+/// println!("Number of tags: {}", swf.tags.len());
+/// ```
+pub fn parse_swf_with_strictness(
+    swf_buf: &SwfBuf,
+    strictness: Strictness,
+) -> Result<(Swf<'_>, Vec<Error>)> {
+    let mut reader = Reader::new(&swf_buf.data[..], swf_buf.header.version());
+    reader.set_strictness(strictness);
+
+    let swf = Swf {
+        header: swf_buf.header.clone(),
+        tags: reader.read_tag_list()?,
+    };
+    Ok((swf, reader.take_skipped_tag_errors()))
+}
+
 /// Extracts an SWF inside of an SWZ file.
 pub fn extract_swz(input: &[u8]) -> Result<Vec<u8>> {
     let asn1_blocks =
@@ -293,9 +325,29 @@ impl<'a, 'b> BitReader<'a, 'b> {
     }
 }
 
+/// How the reader handles malformed tags.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Strictness {
+    /// Tags containing more data than they should are errors, like any other malformed tag.
+    Strict,
+
+    /// Malformed tags are errors, but tags containing more data than they should are
+    /// accepted, as some tools export them.
+    #[default]
+    Standard,
+
+    /// Malformed tags in tag lists are skipped, and parsing continues with the next tag.
+    /// The errors of skipped tags are collected, see `Reader::take_skipped_tag_errors`.
+    ///
+    /// Only a truncated tag ends the list early, as there is no next tag to continue with.
+    Lenient,
+}
+
 pub struct Reader<'a> {
     input: &'a [u8],
     version: u8,
+    strictness: Strictness,
+    skipped_tag_errors: Vec<Error>,
 }
 
 impl<'a> ReadSwfExt<'a> for Reader<'a> {
@@ -313,7 +365,30 @@ impl<'a> ReadSwfExt<'a> for Reader<'a> {
 impl<'a> Reader<'a> {
     #[inline]
     pub const fn new(input: &'a [u8], version: u8) -> Self {
-        Self { input, version }
+        Self {
+            input,
+            version,
+            strictness: Strictness::Standard,
+            skipped_tag_errors: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub const fn strictness(&self) -> Strictness {
+        self.strictness
+    }
+
+    /// Sets how malformed tags are handled. This also applies to the tags nested inside of
+    /// DefineSprite tags.
+    #[inline]
+    pub fn set_strictness(&mut self, strictness: Strictness) {
+        self.strictness = strictness;
+    }
+
+    /// Returns the errors of the tags skipped so far by a lenient reader, including tags
+    /// nested inside of DefineSprite tags.
+    pub fn take_skipped_tag_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.skipped_tag_errors)
     }
 
     /// Returns the suggested string encoding for this SWF.
@@ -383,6 +458,7 @@ impl<'a> Reader<'a> {
 
     fn read_tag_with_code(&mut self, tag_code: TagCode, length: usize) -> Result<Tag<'a>> {
         let mut tag_reader = Reader::new(self.read_slice(length)?, self.version);
+        tag_reader.strictness = self.strictness;
         let tag = match tag_code {
             TagCode::End => Tag::End,
             TagCode::ShowFrame => Tag::ShowFrame,
@@ -611,12 +687,17 @@ impl<'a> Reader<'a> {
             TagCode::NameCharacter => Tag::NameCharacter(tag_reader.read_name_character()?),
         };
 
+        // Errors of tags skipped inside of a DefineSprite.
+        self.skipped_tag_errors
+            .append(&mut tag_reader.skipped_tag_errors);
+
         if !tag_reader.input.is_empty() {
             // There should be no data remaining in the tag if we read it correctly.
             // If there is data remaining, the most likely scenario is we screwed up parsing.
             // But sometimes tools will export SWF tags that are larger than they should be.
-            // TODO: It might be worthwhile to have a "strict mode" to determine
-            // whether this should error or not.
+            if self.strictness == Strictness::Strict {
+                return Err(Error::invalid_data("Data remaining in tag"));
+            }
             log::warn!("Data remaining in buffer when parsing {:?}", tag_code);
         }
 
@@ -709,7 +790,27 @@ impl<'a> Reader<'a> {
     fn read_tag_list(&mut self) -> Result<Vec<Tag<'a>>> {
         let mut tags = Vec::new();
         loop {
-            let tag = self.read_tag()?;
+            let tag_start = self.input;
+            let tag = match self.read_tag() {
+                Ok(tag) => tag,
+                Err(e) if self.strictness == Strictness::Lenient => {
+                    log::warn!("Skipping malformed tag: {}", e);
+                    self.skipped_tag_errors.push(e);
+                    // Continue after the tag, if it is complete.
+                    let mut header_reader = Reader::new(tag_start, self.version);
+                    match header_reader.read_tag_code_and_length() {
+                        Ok((_, length)) if length <= header_reader.input.len() => {
+                            self.input = &header_reader.input[length..];
+                            continue;
+                        }
+                        _ => {
+                            self.input = &[];
+                            break;
+                        }
+                    }
+                }
+                Err(e) => return Err(e),
+            };
             if tag == Tag::End {
                 break;
             }
@@ -1190,7 +1291,7 @@ impl<'a> Reader<'a> {
         Ok(zone)
     }
 
-    fn read_define_font_info(&mut self, version: u8) -> Result<FontInfo<'a>> {
+    pub fn read_define_font_info(&mut self, version: u8) -> Result<FontInfo<'a>> {
         let id = self.read_u16()?;
         let name = self.read_str_with_len()?;
         let flags = FontInfoFlag::from_bits_truncate(self.read_u8()?);
@@ -3045,6 +3146,62 @@ pub mod tests {
         }
     }
 
+    /// Ensure that a lenient reader skips malformed tags and continues with the next one.
+    #[test]
+    fn read_tag_list_lenient() {
+        // ShowFrame, a truncated RemoveObject2, ShowFrame, End
+        let buf = [
+            0b01_000000,
+            0b00000000,
+            0b00_000001,
+            0b00000111,
+            0xff,
+            0b01_000000,
+            0b00000000,
+            0,
+            0,
+        ];
+
+        let mut reader = Reader::new(&buf[..], 1);
+        assert!(reader.read_tag_list().is_err());
+
+        let mut reader = Reader::new(&buf[..], 1);
+        reader.set_strictness(Strictness::Lenient);
+        assert_eq!(
+            reader.read_tag_list().unwrap(),
+            [Tag::ShowFrame, Tag::ShowFrame]
+        );
+        let errors = reader.take_skipped_tag_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            Error::SwfParseError { tag_code: 28, .. }
+        ));
+    }
+
+    /// Ensure that a lenient reader stops at a tag that is longer than the remaining data.
+    #[test]
+    fn read_tag_list_lenient_truncated() {
+        // ShowFrame, a DefineBinaryData claiming 16 bytes with only 2 remaining
+        let buf = [0b01_000000, 0b00000000, 0b11_010000, 0b00010101, 0, 0];
+        let mut reader = Reader::new(&buf[..], 1);
+        reader.set_strictness(Strictness::Lenient);
+        assert_eq!(reader.read_tag_list().unwrap(), [Tag::ShowFrame]);
+        assert_eq!(reader.take_skipped_tag_errors().len(), 1);
+    }
+
+    /// Ensure that a strict reader rejects tags with data remaining.
+    #[test]
+    fn read_tag_strict_remaining_data() {
+        // ShowFrame with a stray byte
+        let buf = [0b01_000001, 0b00000000, 0];
+        assert_eq!(Reader::new(&buf[..], 1).read_tag().unwrap(), Tag::ShowFrame);
+
+        let mut reader = Reader::new(&buf[..], 1);
+        reader.set_strictness(Strictness::Strict);
+        assert!(reader.read_tag().is_err());
+    }
+
     /// Ensure that we can read a PlaceObject3 tag that
     /// inccorrectly omits the 'is_bitmap_cached' u8
     /// Extracted from #7098
@@ -1856,6 +1856,17 @@ pub fn tag_tests() -> Vec<TagTestData> {
             Tag::FileAttributes(FileAttributes::USE_GPU | FileAttributes::IS_ACTION_SCRIPT_3),
             vec![0b01_000100, 0b00010001, 0b00101000, 0, 0, 0],
         ),
+        (
+            9,
+            Tag::FileAttributes(
+                FileAttributes::HAS_METADATA
+                    | FileAttributes::IS_ACTION_SCRIPT_3
+                    | FileAttributes::NO_CROSS_DOMAIN_CACHE
+                    | FileAttributes::SWF_RELATIVE_URLS
+                    | FileAttributes::USE_NETWORK_SANDBOX,
+            ),
+            vec![0b01_000100, 0b00010001, 0b00011111, 0, 0, 0],
+        ),
         (
             3,
             Tag::FrameLabel(FrameLabel {
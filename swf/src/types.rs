@@ -130,12 +130,27 @@ impl HeaderExt {
         self.header.frame_rate
     }
 
+    /// The flags from the FileAttributes tag of this SWF.
+    ///
+    /// SWFs without a FileAttributes tag (SWF7 and earlier) have no flags set.
+    #[inline]
+    pub fn file_attributes(&self) -> FileAttributes {
+        self.file_attributes
+    }
+
     /// Whether this SWF contains XMP metadata in a Metadata tag.
     #[inline]
-    pub fn has_metdata(&self) -> bool {
+    pub fn has_metadata(&self) -> bool {
         self.file_attributes.contains(FileAttributes::HAS_METADATA)
     }
 
+    /// Whether this SWF contains XMP metadata in a Metadata tag.
+    #[inline]
+    #[deprecated(note = "Use `has_metadata` instead")]
+    pub fn has_metdata(&self) -> bool {
+        self.has_metadata()
+    }
+
     /// Returns the basic SWF header.
     #[inline]
     pub fn swf_header(&self) -> &Header {
@@ -195,6 +210,20 @@ impl HeaderExt {
         self.file_attributes
             .contains(FileAttributes::USE_NETWORK_SANDBOX)
     }
+
+    /// Whether this SWF asks the player not to cache cross-domain policy files it loads.
+    #[inline]
+    pub fn no_cross_domain_cache(&self) -> bool {
+        self.file_attributes
+            .contains(FileAttributes::NO_CROSS_DOMAIN_CACHE)
+    }
+
+    /// Whether relative URLs should be resolved against the SWF's URL.
+    #[inline]
+    pub fn swf_relative_urls(&self) -> bool {
+        self.file_attributes
+            .contains(FileAttributes::SWF_RELATIVE_URLS)
+    }
 }
 
 /// The compression format used internally by the SWF file.
@@ -274,6 +303,17 @@ bitflags! {
         /// Whether this SWF uses ActionScript 3 (AVM2).
         const IS_ACTION_SCRIPT_3 = 1 << 3;
 
+        /// Whether this SWF asks the player not to cache cross-domain policy files it loads.
+        ///
+        /// Not part of the SWF19 spec, but written by the Flex SDK.
+        const NO_CROSS_DOMAIN_CACHE = 1 << 2;
+
+        /// Whether relative URLs should be resolved against the SWF's URL instead of the
+        /// embedding page's URL.
+        ///
+        /// Not part of the SWF19 spec, but written by the Flex SDK.
+        const SWF_RELATIVE_URLS = 1 << 1;
+
         /// Whether this SWF should be placed in the network sandbox when run locally.
         ///
         /// SWFs in the network sandbox can only access network resources,  not local resources.
@@ -1013,6 +1053,8 @@ pub enum AudioCompression {
     Nellymoser16Khz = 4,
     Nellymoser8Khz = 5,
     Nellymoser = 6,
+    /// Only found in FLV and MP4 files, never in SWF sound definitions.
+    Aac = 10,
     Speex = 11,
 }
 
@@ -1698,6 +1740,8 @@ pub enum VideoCodec {
     Vp6 = 4,
     Vp6WithAlpha = 5,
     ScreenVideoV2 = 6,
+    /// Only found in FLV and MP4 files, never in SWF video streams.
+    H264 = 7,
 }
 
 impl VideoCodec {
@@ -0,0 +1,43 @@
+use crate::set_logger;
+use crate::util::options::TestOptions;
+use crate::util::test::Test;
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+pub fn embedder_events() -> Result<(), libtest_mimic::Failed> {
+    run_with_events(2, "tests/swfs/avm1/embedder_events/", "embedder_events")
+}
+
+pub fn fscommand_events() -> Result<(), libtest_mimic::Failed> {
+    run_with_events(1, "tests/swfs/avm2/fscommand_events/", "fscommand_events")
+}
+
+/// Runs a test, then traces the `EmbedderEvent`s it sent after its own output.
+fn run_with_events(num_frames: u32, path: &str, name: &str) -> Result<(), libtest_mimic::Failed> {
+    set_logger();
+    let events = Rc::new(RefCell::new(None));
+    Test::from_options(
+        TestOptions {
+            num_frames,
+            ..Default::default()
+        },
+        Path::new(path),
+        name.to_string(),
+    )?
+    .run(
+        |player| {
+            *events.borrow_mut() = Some(player.lock().unwrap().subscribe_events());
+            Ok(())
+        },
+        |player| {
+            // Trace the received events, so that they're compared with the expected output.
+            let player = player.lock().unwrap();
+            let mut events = events.borrow_mut().take().expect("Subscribed to events");
+            while let Ok(Some(event)) = events.try_next() {
+                player.log_backend().avm_trace(&format!("{event:?}"));
+            }
+            Ok(())
+        },
+    )
+}
@@ -2,16 +2,20 @@
 //!
 //! Trace output can be compared with correct output from the official Flash Player.
 
+use crate::embedder_events::{embedder_events, fscommand_events};
 use crate::external_interface::tests::{external_interface_avm1, external_interface_avm2};
 use crate::shared_object::{shared_object_avm1, shared_object_avm2};
+use crate::ui::{clipboard, context_menu_owner, ime_composition, touch_events};
 use anyhow::Context;
 use anyhow::Result;
 use libtest_mimic::{Arguments, Trial};
 use std::path::Path;
 use util::test::Test;
 
+mod embedder_events;
 mod external_interface;
 mod shared_object;
+mod ui;
 mod util;
 
 fn set_logger() {
@@ -78,6 +82,12 @@ fn main() {
         .collect();
 
     // Manual tests here, since #[test] doesn't work once we use our own test harness
+    tests.push(Trial::test("embedder_events", embedder_events));
+    tests.push(Trial::test("fscommand_events", fscommand_events));
+    tests.push(Trial::test("ime_composition", ime_composition));
+    tests.push(Trial::test("clipboard", clipboard));
+    tests.push(Trial::test("context_menu_owner", context_menu_owner));
+    tests.push(Trial::test("touch_events", touch_events));
     tests.push(Trial::test("shared_object_avm1", shared_object_avm1));
     tests.push(Trial::test("shared_object_avm2", shared_object_avm2));
     tests.push(Trial::test(
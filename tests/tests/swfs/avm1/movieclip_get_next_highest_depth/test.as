@@ -0,0 +1,27 @@
+// Compiled to AVM1 frame actions. The timeline of the root clip is:
+//   character 1: DefineSprite, an empty clip exported as "clip".
+
+trace("// getNextHighestDepth()");
+trace(this.getNextHighestDepth());
+
+this.createTextField("field", 5, 0, 0, 100, 20);
+trace("// getNextHighestDepth() after createTextField(\"field\", 5)");
+trace(this.getNextHighestDepth());
+
+this.attachMovie("clip", "attached", 10);
+trace("// getNextHighestDepth() after attachMovie(\"clip\", \"attached\", 10)");
+trace(this.getNextHighestDepth());
+
+attached.removeMovieClip();
+trace("// getNextHighestDepth() after attached.removeMovieClip()");
+trace(this.getNextHighestDepth());
+
+field.removeTextField();
+trace("// getNextHighestDepth() after field.removeTextField()");
+trace(this.getNextHighestDepth());
+
+// Depths below 0 are never returned.
+this.attachMovie("clip", "low", -100);
+trace("// getNextHighestDepth() after attachMovie(\"clip\", \"low\", -100)");
+trace(this.getNextHighestDepth());
+stop();
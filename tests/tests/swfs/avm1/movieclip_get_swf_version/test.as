@@ -0,0 +1,16 @@
+// Compiled to AVM1 frame actions. child.swf is an empty SWF 6 movie, and image.png is a 1x1 image.
+
+// Frame 1
+trace("// this.getSWFVersion()");
+trace(this.getSWFVersion());
+this.createEmptyMovieClip("child", 1);
+child.loadMovie("child.swf");
+this.createEmptyMovieClip("image", 2);
+image.loadMovie("image.png");
+
+// Frame 5
+trace("// child.getSWFVersion()");
+trace(child.getSWFVersion());
+trace("// image.getSWFVersion()");
+trace(image.getSWFVersion());
+this.stop();
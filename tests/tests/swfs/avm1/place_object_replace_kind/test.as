@@ -0,0 +1,36 @@
+// Compiled to AVM1 frame actions. The timeline of the root clip is:
+//   character 1: DefineShape, a 20x20 rectangle.
+//   character 2: DefineMorphShape, a 40x20 rectangle morphing into a 100x20 one.
+//   character 3: DefineMorphShape, a 60x20 rectangle morphing into a 200x20 one.
+// Each frame traces the bounds of the root clip after its PlaceObject2 tag.
+
+function traceBounds() {
+	var bounds = _root.getBounds(_root);
+	trace(bounds.xMin + " " + bounds.xMax);
+}
+
+// Frame 1
+// PlaceObject2 (place, character 1) at depth 1, translated by (10, 10).
+trace("// Shape placed");
+traceBounds();
+
+// Frame 2
+// PlaceObject2 (replace, character 2) at depth 1 with a ratio of 16384.
+trace("// Replaced by a morph shape");
+traceBounds();
+
+// Frame 3
+// PlaceObject2 (move) at depth 1 with a ratio of 65535.
+trace("// Ratio moved to the end");
+traceBounds();
+
+// Frame 4
+// PlaceObject2 (replace, character 3) at depth 1.
+trace("// Replaced by another morph shape");
+traceBounds();
+
+// Frame 5
+// PlaceObject2 (replace, character 1) at depth 1.
+trace("// Replaced by the shape");
+traceBounds();
+stop();
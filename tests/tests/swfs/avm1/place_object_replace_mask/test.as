@@ -0,0 +1,15 @@
+// Compiled to AVM1 frame actions. The timeline of the root clip is:
+//   character 1: DefineShape, a 20x20 rectangle.
+//   character 2: DefineMorphShape, a 40x20 rectangle morphing into a 100x20 one.
+//   character 3: DefineShape, a 100x100 blue rectangle.
+// The stage should show the part of the blue rectangle under the 40x20 morph shape.
+
+// Frame 1
+// PlaceObject2 (place, character 1) at depth 1, clipping up to depth 2.
+// PlaceObject2 (place, character 3) at depth 2.
+trace("// Masked by the shape");
+
+// Frame 2
+// PlaceObject2 (replace, character 2) at depth 1.
+trace("// Masked by the morph shape");
+stop();
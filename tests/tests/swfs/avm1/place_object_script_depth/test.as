@@ -0,0 +1,18 @@
+// Compiled to AVM1 frame actions. The timeline of the root clip is:
+//   character 1: DefineSprite, an empty clip exported as "clip".
+
+// Frame 1
+// -16383 is timeline depth 1.
+this.attachMovie("clip", "attached", -16383);
+trace("// attached.getDepth()");
+trace(attached.getDepth());
+
+// Frame 2
+// PlaceObject2 (place, character 1) at depth 1, named "timeline", with a translation of 100px.
+trace("// typeof timeline");
+trace(typeof timeline);
+trace("// attached.getDepth()");
+trace(attached.getDepth());
+trace("// attached._x");
+trace(attached._x);
+stop();
@@ -0,0 +1,18 @@
+// Compiled to AVM1 frame actions. The timeline of the root clip is:
+//   character 1: DefineSprite, an empty clip.
+
+// Frame 1
+// PlaceObject2 (replace, character 1) at the empty depth 1, named "replaced".
+trace("// typeof replaced");
+trace(typeof replaced);
+// -16382 is timeline depth 2.
+this.createEmptyMovieClip("dynamic", -16382);
+trace("// dynamic._x");
+trace(dynamic._x);
+
+// Frame 2
+// PlaceObject2 (move) at depths 1 and 2, with a translation of 100px.
+trace("// replaced._x after move");
+trace(replaced._x);
+trace("// dynamic._x after move");
+trace(dynamic._x);
@@ -0,0 +1,11 @@
+package {
+	public class Child {
+	}
+}
+
+// The parent movie already defines this class.
+package {
+	public class Shared {
+		public static const origin:String = "child";
+	}
+}
@@ -0,0 +1,29 @@
+package {
+	public class Test {
+	}
+}
+
+package {
+	public class Shared {
+		public static const origin:String = "parent";
+	}
+}
+
+import flash.display.Loader;
+import flash.events.Event;
+import flash.net.URLRequest;
+import flash.system.ApplicationDomain;
+import flash.system.LoaderContext;
+
+trace("Parent sees Shared.origin = " + Shared.origin);
+
+var childDomain:ApplicationDomain = new ApplicationDomain(ApplicationDomain.currentDomain);
+trace("childDomain.parentDomain has Shared: " + childDomain.parentDomain.hasDefinition("Shared"));
+
+var loader:Loader = new Loader();
+loader.contentLoaderInfo.addEventListener(Event.COMPLETE, function(e:Event):void {
+	var childShared:Object = loader.contentLoaderInfo.applicationDomain.getDefinition("Shared");
+	trace("Child domain resolves Shared to the parent's class: " + (childShared === Shared));
+	trace("Child domain's Shared.origin = " + childShared.origin);
+});
+loader.load(new URLRequest("child.swf"), new LoaderContext(false, childDomain));
@@ -0,0 +1,61 @@
+package {
+	import flash.display.Bitmap;
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+	import flash.geom.Rectangle;
+
+	public class Test extends MovieClip {
+		private var red:BitmapData = new BitmapData(10, 10, false, 0xFF0000);
+		private var stripes:BitmapData = new BitmapData(8, 8, false, 0x0000FF);
+		private var black:BitmapData = new BitmapData(10, 10, false, 0x000000);
+
+		public function Test() {
+			super();
+
+			// Small bitmaps that are drawn as they are, these fit on an atlas page.
+			stripes.fillRect(new Rectangle(4, 0, 4, 8), 0xFFFF00);
+			addBitmap(red, 10, 10);
+			addBitmap(stripes, 110, 10);
+			addBitmap(black, 160, 10);
+
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		private function addBitmap(bitmapData:BitmapData, x:Number, y:Number):void {
+			var bitmap:Bitmap = new Bitmap(bitmapData);
+			bitmap.x = x;
+			bitmap.y = y;
+			bitmap.scaleX = 4;
+			bitmap.scaleY = 4;
+			addChild(bitmap);
+		}
+
+		private function onEnterFrame(event:Event):void {
+			removeEventListener(Event.ENTER_FRAME, onEnterFrame);
+
+			trace("/// red.fillRect(new Rectangle(0, 0, 10, 10), 0x00FF00)");
+			red.fillRect(new Rectangle(0, 0, 10, 10), 0x00FF00);
+			trace(red.getPixel(0, 0).toString(16));
+
+			trace("/// beginBitmapFill(stripes)");
+			var fill:Shape = new Shape();
+			fill.graphics.beginBitmapFill(stripes, null, true, false);
+			fill.graphics.drawRect(60, 10, 40, 40);
+			fill.graphics.endFill();
+			addChild(fill);
+			trace(stripes.getPixel(0, 0).toString(16));
+			trace(stripes.getPixel(7, 0).toString(16));
+
+			trace("/// black.draw(half)");
+			var half:Shape = new Shape();
+			half.graphics.beginFill(0xFF00FF);
+			half.graphics.drawRect(0, 0, 5, 10);
+			half.graphics.endFill();
+			black.draw(half);
+			trace(black.getPixel(0, 0).toString(16));
+			trace(black.getPixel(9, 0).toString(16));
+		}
+	}
+}
@@ -0,0 +1,15 @@
+package {
+	public class Item {
+		public var name:String;
+		public var count:int;
+
+		[Transient]
+		public var cache:Object;
+
+		private var secret:String = "hidden";
+
+		public function get label():String {
+			return name + " x" + count;
+		}
+	}
+}
@@ -0,0 +1,20 @@
+package {
+	import flash.utils.IDataInput;
+	import flash.utils.IDataOutput;
+	import flash.utils.IExternalizable;
+
+	public class Packet implements IExternalizable {
+		public var id:int;
+		public var payload:Object;
+
+		public function writeExternal(output:IDataOutput):void {
+			output.writeShort(id);
+			output.writeObject(payload);
+		}
+
+		public function readExternal(input:IDataInput):void {
+			id = input.readShort();
+			payload = input.readObject();
+		}
+	}
+}
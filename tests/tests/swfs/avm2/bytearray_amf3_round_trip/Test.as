@@ -0,0 +1,105 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.net.getClassByAlias;
+import flash.net.registerClassAlias;
+import flash.utils.ByteArray;
+import flash.utils.Dictionary;
+
+function write(value:*):ByteArray {
+	var bytes:ByteArray = new ByteArray();
+	bytes.writeObject(value);
+	bytes.position = 0;
+	return bytes;
+}
+
+function roundTrip(value:*):* {
+	var bytes:ByteArray = write(value);
+	var result:* = bytes.readObject();
+	if (bytes.bytesAvailable != 0) {
+		trace("  " + bytes.bytesAvailable + " bytes were not read");
+	}
+	return result;
+}
+
+trace("// string references");
+var strings:Array = ["hello", "hello", "", ""];
+trace(write(strings).length, roundTrip(strings).join(","));
+
+trace("// object references");
+var shared:Object = {n: 1};
+var pair:Array = [shared, shared];
+var pairCopy:Array = roundTrip(pair);
+trace(write(pair).length, pairCopy[0] === pairCopy[1], pairCopy[0].n);
+var cyclic:Object = {};
+cyclic.self = cyclic;
+var cyclicCopy:Object = roundTrip(cyclic);
+trace(write(cyclic).length, cyclicCopy.self === cyclicCopy);
+
+trace("// aliases");
+registerClassAlias("com.example.Item", Item);
+trace(getClassByAlias("com.example.Item") == Item);
+var item:Item = new Item();
+item.name = "apple";
+item.count = 3;
+item.cache = {};
+var itemCopy:* = roundTrip(item);
+trace(itemCopy is Item, itemCopy.name, itemCopy.count, itemCopy.cache, itemCopy.label);
+var items:Array = roundTrip([item, new Item()]);
+trace(items[0] is Item, items[1] is Item, items[0] === items[1], items[1].name);
+
+trace("// IExternalizable");
+registerClassAlias("com.example.Packet", Packet);
+var packet:Packet = new Packet();
+packet.id = 7;
+packet.payload = {text: "hi"};
+var packetCopy:* = roundTrip(packet);
+trace(packetCopy is Packet, packetCopy.id, packetCopy.payload.text);
+
+trace("// Date");
+var date:Date = new Date(Date.UTC(2020, 0, 2, 3, 4, 5, 6));
+var dateCopy:* = roundTrip(date);
+trace(dateCopy is Date, dateCopy.time);
+
+trace("// XML");
+var xml:XML = new XML("<root><item id=\"1\">one</item></root>");
+var xmlCopy:* = roundTrip(xml);
+trace(xmlCopy is XML, xmlCopy.item.@id, xmlCopy.item);
+
+trace("// ByteArray");
+var bytes:ByteArray = new ByteArray();
+bytes.writeUTFBytes("bytes");
+var bytesCopy:* = roundTrip(bytes);
+trace(bytesCopy is ByteArray, bytesCopy.length, bytesCopy.toString());
+
+trace("// Vector");
+var ints:Vector.<int> = new <int>[1, -2, 3];
+ints.fixed = true;
+var intsCopy:* = roundTrip(ints);
+trace(intsCopy is Vector.<int>, intsCopy.fixed, intsCopy);
+var uints:* = roundTrip(new <uint>[4294967295, 0]);
+trace(uints is Vector.<uint>, uints.fixed, uints);
+var numbers:* = roundTrip(new <Number>[0.5, NaN]);
+trace(numbers is Vector.<Number>, numbers);
+var names:* = roundTrip(new <String>["a", "b"]);
+trace(names is Vector.<String>, names);
+var itemVector:* = roundTrip(new <Item>[item, item]);
+trace(itemVector is Vector.<Item>, itemVector[0] === itemVector[1], itemVector[0].name);
+
+trace("// Dictionary");
+var key:Object = {k: 1};
+var dictionary:Dictionary = new Dictionary();
+dictionary[key] = "object key";
+dictionary["name"] = "string key";
+var dictionaryCopy:* = roundTrip(dictionary);
+var count:int = 0;
+var objectEntry:String;
+for (var entryKey:* in dictionaryCopy) {
+	count++;
+	if (!(entryKey is String)) {
+		objectEntry = dictionaryCopy[entryKey] + " " + entryKey.k;
+	}
+}
+trace(dictionaryCopy is Dictionary, count, dictionaryCopy["name"], objectEntry);
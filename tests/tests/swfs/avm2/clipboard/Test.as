@@ -0,0 +1,64 @@
+package {
+	import flash.desktop.Clipboard;
+	import flash.desktop.ClipboardFormats;
+	import flash.display.Sprite;
+	import flash.events.Event;
+	import flash.text.TextField;
+	import flash.text.TextFieldType;
+
+	// The test copies, cuts and pastes in this field with `PlayerEvent`s, running a frame after
+	// each event.
+	public class Test extends Sprite {
+		public function Test() {
+			trace("/// generalClipboard");
+			var general: Clipboard = Clipboard.generalClipboard;
+			trace("Same clipboard: " + (general == Clipboard.generalClipboard));
+			trace("formats: " + general.formats);
+			trace("setData: " + general.setData(ClipboardFormats.TEXT_FORMAT, "from script"));
+			trace("getData: " + general.getData(ClipboardFormats.TEXT_FORMAT));
+			trace("formats: " + general.formats);
+			trace("hasFormat: " + general.hasFormat(ClipboardFormats.TEXT_FORMAT));
+			general.clearData(ClipboardFormats.TEXT_FORMAT);
+			trace("hasFormat after clearData: " + general.hasFormat(ClipboardFormats.TEXT_FORMAT));
+
+			trace("/// new Clipboard()");
+			var own: Clipboard = new Clipboard();
+			trace("setData: " + own.setData(ClipboardFormats.HTML_FORMAT, "<b>bold</b>"));
+			var calls: int = 0;
+			own.setDataHandler(ClipboardFormats.RICH_TEXT_FORMAT, function(): String {
+				calls++;
+				return "{\\rtf1}";
+			});
+			trace("Handler calls: " + calls);
+			trace("getData: " + own.getData(ClipboardFormats.RICH_TEXT_FORMAT));
+			trace("getData: " + own.getData(ClipboardFormats.RICH_TEXT_FORMAT));
+			trace("Handler calls: " + calls);
+			trace("getData: " + own.getData(ClipboardFormats.HTML_FORMAT));
+			trace("getData of a missing format: " + own.getData(ClipboardFormats.TEXT_FORMAT));
+			// The standalone player takes the place of AIR.
+			trace("setData of an AIR format: " + own.setData(ClipboardFormats.URL_FORMAT, "https://example.org"));
+			trace("formats: " + own.formats.sort());
+			own.clear();
+			trace("formats after clear: " + own.formats.length);
+			trace("General clipboard unchanged: " + general.formats);
+
+			trace("/// Text field");
+			var field: TextField = new TextField();
+			field.type = TextFieldType.INPUT;
+			field.width = 200;
+			field.height = 50;
+			field.text = "hello world";
+			field.setSelection(0, 5);
+			addChild(field);
+			stage.focus = field;
+
+			var lastText: String = field.text;
+			addEventListener(Event.ENTER_FRAME, function(e: Event): void {
+				if (field.text != lastText) {
+					trace("text: \"" + field.text + "\"");
+					lastText = field.text;
+				}
+			});
+		}
+	}
+}
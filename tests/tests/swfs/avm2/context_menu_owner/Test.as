@@ -0,0 +1,54 @@
+package {
+	import flash.display.Sprite;
+	import flash.events.ContextMenuEvent;
+	import flash.ui.ContextMenu;
+	import flash.ui.ContextMenuItem;
+
+	// The test moves the mouse over `inner`, which has no menu of its own, and
+	// then over `plain`, opening the context menu and choosing its item each time.
+	public class Test extends Sprite {
+		public function Test() {
+			this.contextMenu = makeMenu("Root item");
+
+			var outer: Sprite = makeSquare("outer", 0);
+			outer.contextMenu = makeMenu("Outer item");
+			addChild(outer);
+
+			var inner: Sprite = makeSquare("inner", 0);
+			outer.addChild(inner);
+
+			var plain: Sprite = makeSquare("plain", 100);
+			addChild(plain);
+		}
+
+		private function makeSquare(name: String, x: Number): Sprite {
+			var square: Sprite = new Sprite();
+			square.name = name;
+			square.x = x;
+			square.graphics.beginFill(0xFF0000);
+			square.graphics.drawRect(0, 0, 50, 50);
+			square.graphics.endFill();
+			return square;
+		}
+
+		private function makeMenu(caption: String): ContextMenu {
+			var menu: ContextMenu = new ContextMenu();
+			menu.hideBuiltInItems();
+			menu.addEventListener(ContextMenuEvent.MENU_SELECT, traceEvent);
+
+			var item: ContextMenuItem = new ContextMenuItem(caption);
+			item.addEventListener(ContextMenuEvent.MENU_ITEM_SELECT, traceEvent);
+			menu.customItems.push(item);
+			return menu;
+		}
+
+		private function traceEvent(e: ContextMenuEvent): void {
+			var target: String = e.target is ContextMenuItem ? ContextMenuItem(e.target).caption : "menu";
+			trace(e.type + " on " + target + ": mouseTarget = " + nameOf(e.mouseTarget) + ", contextMenuOwner = " + nameOf(e.contextMenuOwner));
+		}
+
+		private function nameOf(object: Object): String {
+			return object == this ? "root" : object.name;
+		}
+	}
+}
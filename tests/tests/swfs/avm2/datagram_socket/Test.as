@@ -0,0 +1,73 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.events.DatagramSocketDataEvent;
+import flash.net.DatagramSocket;
+import flash.utils.ByteArray;
+
+function bytesOf(text:String):ByteArray {
+	var bytes:ByteArray = new ByteArray();
+	bytes.writeUTFBytes(text);
+	return bytes;
+}
+
+trace("isSupported: " + DatagramSocket.isSupported);
+
+var socket:DatagramSocket = new DatagramSocket();
+var received:int = 0;
+trace("bound: " + socket.bound + ", connected: " + socket.connected);
+
+try {
+	socket.send(bytesOf("ping"));
+} catch (e:Error) {
+	trace("send without address: " + e);
+}
+
+socket.bind(0, "127.0.0.1");
+trace("bound: " + socket.bound + ", localAddress: " + socket.localAddress + ", localPort: " + socket.localPort);
+
+try {
+	socket.bind(0, "127.0.0.1");
+} catch (e:Error) {
+	trace("bind twice: " + e);
+}
+
+try {
+	socket.send(bytesOf("ping"), 0, 0, "127.0.0.1", 70000);
+} catch (e:Error) {
+	trace("send to invalid port: " + e);
+}
+
+socket.addEventListener(DatagramSocketDataEvent.DATA, function(event:DatagramSocketDataEvent):void {
+	received++;
+	var text:String = event.data.readUTFBytes(event.data.bytesAvailable);
+	trace("data from " + event.srcAddress + ":" + event.srcPort + " to " + event.dstAddress + ":" + event.dstPort + ": " + text);
+
+	if (received == 1) {
+		socket.connect("127.0.0.1", 9001);
+		trace("connected: " + socket.connected + ", remote: " + socket.remoteAddress + ":" + socket.remotePort);
+
+		try {
+			socket.send(bytesOf("ping"), 0, 0, "127.0.0.1", 9002);
+		} catch (e:Error) {
+			trace("send to another address while connected: " + e);
+		}
+
+		// Only "pong" is sent.
+		socket.send(bytesOf("[pong]"), 1, 4);
+	} else {
+		socket.close();
+		trace("bound: " + socket.bound + ", connected: " + socket.connected);
+
+		try {
+			socket.close();
+		} catch (e:Error) {
+			trace("close twice: " + e);
+		}
+	}
+});
+socket.receive();
+socket.send(bytesOf("ping"), 0, 0, "127.0.0.1", 9000);
+trace("sent");
@@ -0,0 +1,25 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.net.DatagramSocket;
+import flash.utils.ByteArray;
+
+trace("isSupported: " + DatagramSocket.isSupported);
+
+var socket:DatagramSocket = new DatagramSocket();
+try {
+	socket.bind(0, "127.0.0.1");
+} catch (e:Error) {
+	trace("bind: " + e);
+}
+trace("bound: " + socket.bound);
+
+var bytes:ByteArray = new ByteArray();
+bytes.writeUTFBytes("ping");
+try {
+	socket.send(bytes, 0, 0, "127.0.0.1", 9000);
+} catch (e:Error) {
+	trace("send: " + e);
+}
@@ -0,0 +1,9 @@
+package {
+	public class Base implements IGreeter {
+		public var baseVar:int;
+
+		public function greet(name:String):String {
+			return "Hello, " + name;
+		}
+	}
+}
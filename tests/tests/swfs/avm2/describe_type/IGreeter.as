@@ -0,0 +1,5 @@
+package {
+	public interface IGreeter {
+		function greet(name:String):String;
+	}
+}
@@ -0,0 +1,38 @@
+package {
+	[Entity(table="samples")]
+	public dynamic class Sample extends Base {
+		[Inject(name="service")]
+		public var service:Object;
+
+		public const MAX:Number = 10;
+
+		private var hidden:int;
+
+		public static var counter:int;
+
+		public function Sample(a:int, b:String = "b") {
+		}
+
+		public function get readOnly():uint {
+			return 1;
+		}
+
+		public function get readWrite():String {
+			return "";
+		}
+
+		public function set readWrite(value:String):void {
+		}
+
+		public function set writeOnly(value:Boolean):void {
+		}
+
+		public function sum(a:Number, b:int = 2):Number {
+			return a + b;
+		}
+
+		public static function create():Sample {
+			return new Sample(0);
+		}
+	}
+}
@@ -0,0 +1,77 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.describeType;
+
+function find(list:XMLList, name:String):XML {
+	for (var i:int = 0; i < list.length(); i++) {
+		if (String(list[i].@name) == name) {
+			return list[i];
+		}
+	}
+	return null;
+}
+
+function traceParameters(list:XMLList):void {
+	for (var i:int = 0; i < list.length(); i++) {
+		trace(list[i].@index, list[i].@type, list[i].@optional);
+	}
+}
+
+var info:XML = describeType(new Sample(1));
+trace("// type");
+trace(info.@name, info.@base, info.@isDynamic, info.@isFinal, info.@isStatic);
+trace("// extendsClass");
+for (var i:int = 0; i < info.extendsClass.length(); i++) {
+	trace(info.extendsClass[i].@type);
+}
+trace("// implementsInterface");
+trace(info.implementsInterface.length(), info.implementsInterface[0].@type);
+trace("// constructor");
+traceParameters(info.constructor.parameter);
+trace("// class metadata");
+trace(info.metadata[0].@name, info.metadata[0].arg[0].@key, info.metadata[0].arg[0].@value);
+
+trace("// variable with metadata");
+var service:XML = find(info.variable, "service");
+trace(service.@type, service.metadata[0].@name, service.metadata[0].arg[0].@key, service.metadata[0].arg[0].@value);
+trace("// inherited variable");
+trace(find(info.variable, "baseVar").@type);
+trace("// constant");
+trace(find(info.constant, "MAX").@type);
+
+trace("// accessors");
+var accessors:Array = ["readOnly", "readWrite", "writeOnly"];
+for each (var name:String in accessors) {
+	var accessor:XML = find(info.accessor, name);
+	trace(name, accessor.@access, accessor.@type, accessor.@declaredBy);
+}
+
+trace("// method");
+var sum:XML = find(info.method, "sum");
+trace(sum.@declaredBy, sum.@returnType);
+traceParameters(sum.parameter);
+trace("// inherited method");
+var greet:XML = find(info.method, "greet");
+trace(greet.@declaredBy, greet.@returnType);
+traceParameters(greet.parameter);
+
+trace("// private and static traits are not listed for instances");
+trace(find(info.variable, "hidden") == null, find(info.variable, "counter") == null, find(info.method, "create") == null);
+
+var classInfo:XML = describeType(Sample);
+trace("// class");
+trace(classInfo.@name, classInfo.@base, classInfo.@isDynamic, classInfo.@isFinal, classInfo.@isStatic);
+trace("// static traits");
+trace(find(classInfo.variable, "counter").@type);
+var create:XML = find(classInfo.method, "create");
+trace(create.@declaredBy, create.@returnType);
+trace(find(classInfo.variable, "service") == null);
+trace("// factory");
+trace(classInfo.factory.@type, classInfo.factory.extendsClass.length(), find(classInfo.factory.accessor, "readOnly").@access);
+
+trace("// null and undefined");
+trace(describeType(null).toXMLString());
+trace(describeType(undefined).toXMLString());
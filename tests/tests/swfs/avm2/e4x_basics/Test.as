@@ -0,0 +1,42 @@
+package {
+	public class Test {
+	}
+}
+
+var xml:XML = <root><item id="1">one</item><item id="2">two</item><empty/></root>;
+
+trace("// xml.toXMLString()");
+trace(xml.toXMLString());
+trace("// xml.item.length()");
+trace(xml.item.length());
+trace("// xml.item[1]");
+trace(xml.item[1]);
+trace("// xml.item.@id");
+trace(xml.item.@id);
+trace("// xml.@missing.length()");
+trace(xml.@missing.length());
+trace("// xml.children().length()");
+trace(xml.children().length());
+trace("// xml..item.length()");
+trace(xml..item.length());
+trace("// xml.empty[0].nodeKind()");
+trace(xml.empty[0].nodeKind());
+trace("// xml.item[0].attributes()[0].nodeKind()");
+trace(xml.item[0].attributes()[0].nodeKind());
+trace("// xml.hasComplexContent()");
+trace(xml.hasComplexContent());
+trace("// xml.item[0].hasSimpleContent()");
+trace(xml.item[0].hasSimpleContent());
+
+xml.appendChild(<extra>three</extra>);
+xml.item[0].@id = "10";
+trace("// after appendChild and setting @id");
+trace(xml.toXMLString());
+
+trace("// new XMLList(\"<a/><b/>\").length()");
+trace(new XMLList("<a/><b/>").length());
+
+XML.prettyPrinting = false;
+trace("// xml.toXMLString() without pretty printing");
+trace(xml.toXMLString());
+XML.prettyPrinting = true;
@@ -0,0 +1,42 @@
+package {
+	public class Test {
+	}
+}
+
+function withNamespace():XML {
+	default xml namespace = "http://example.com/";
+	return <item><child/></item>;
+}
+
+function withNamespaceObject(ns:*):XML {
+	default xml namespace = ns;
+	return new XML("<item/>");
+}
+
+function withOverrides():XML {
+	default xml namespace = "http://example.com/";
+	return <root><plain xmlns=""/><p:prefixed xmlns:p="urn:prefixed"/></root>;
+}
+
+trace("// new XML(\"<item/>\").name().uri");
+trace(new XML("<item/>").name().uri);
+
+var xml:XML = withNamespace();
+trace("// withNamespace().toXMLString()");
+trace(xml.toXMLString());
+trace("// withNamespace().name().uri");
+trace(xml.name().uri);
+trace("// withNamespace().children()[0].name().uri");
+trace(xml.children()[0].name().uri);
+
+trace("// withNamespaceObject(new Namespace(\"http://example.org/\")).name().uri");
+trace(withNamespaceObject(new Namespace("http://example.org/")).name().uri);
+
+var overrides:XML = withOverrides();
+trace("// withOverrides().children()[0].name().uri");
+trace(overrides.children()[0].name().uri);
+trace("// withOverrides().children()[1].name().uri");
+trace(overrides.children()[1].name().uri);
+
+trace("// new XML(\"<item/>\").name().uri after returning");
+trace(new XML("<item/>").name().uri);
@@ -0,0 +1,34 @@
+package {
+	public class Test {
+	}
+}
+
+var xml:XML = <root><a>1</a><b>1</b></root>;
+
+trace("// xml.a[0] === xml.a[0]");
+trace(xml.a[0] === xml.a[0]);
+trace("// xml.children()[0] === xml.a[0]");
+trace(xml.children()[0] === xml.a[0]);
+trace("// xml.a[0].parent() === xml");
+trace(xml.a[0].parent() === xml);
+trace("// xml.a[0] === xml.b[0]");
+trace(xml.a[0] === xml.b[0]);
+trace("// xml.a[0] == xml.b[0]");
+trace(xml.a[0] == xml.b[0]);
+trace("// xml.a[0] == new XML(\"<a>1</a>\")");
+trace(xml.a[0] == new XML("<a>1</a>"));
+trace("// xml.a[0] === new XML(\"<a>1</a>\")");
+trace(xml.a[0] === new XML("<a>1</a>"));
+
+var copy:XML = xml.copy();
+trace("// copy == xml");
+trace(copy == xml);
+trace("// copy === xml");
+trace(copy === xml);
+trace("// copy.a[0] === xml.a[0]");
+trace(copy.a[0] === xml.a[0]);
+
+trace("// xml.a == xml.a[0]");
+trace(xml.a == xml.a[0]);
+trace("// xml.a === xml.a");
+trace(xml.a === xml.a);
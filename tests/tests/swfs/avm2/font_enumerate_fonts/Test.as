@@ -0,0 +1,33 @@
+package {
+    public class Test {}
+}
+
+import flash.text.Font;
+
+// The fonts are the ones of the font_embedded test, without classes linked to them.
+
+function describe(font:Font):String {
+    return font.fontName + ", " + font.fontStyle + ", " + font.fontType;
+}
+
+trace("// Font.enumerateFonts()");
+var fonts = Font.enumerateFonts();
+trace(fonts.length);
+for each (var font in fonts) {
+    trace(describe(font));
+}
+
+trace("// Font.enumerateFonts(true)");
+fonts = Font.enumerateFonts(true);
+trace(fonts.length);
+for each (font in fonts) {
+    trace(describe(font));
+}
+
+trace("// Font.enumerateFonts()[0].hasGlyphs(\"ABC\")");
+trace(Font.enumerateFonts()[0].hasGlyphs("ABC"));
+
+trace("// new Font()");
+var empty = new Font();
+trace(describe(empty));
+trace(empty.hasGlyphs("ABC"));
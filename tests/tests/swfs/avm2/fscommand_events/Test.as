@@ -0,0 +1,35 @@
+package {
+	import flash.display.Sprite;
+	import flash.events.FullScreenEvent;
+	import flash.system.fscommand;
+
+	public class Test extends Sprite {
+		public function Test() {
+			stage.addEventListener(FullScreenEvent.FULL_SCREEN, function(e:FullScreenEvent):void {
+				trace("fullScreen event: " + e.fullScreen);
+			});
+
+			trace("Calling fscommand(\"allowscale\", \"false\")");
+			fscommand("allowscale", "false");
+			trace("stage.scaleMode: " + stage.scaleMode);
+
+			trace("Calling fscommand(\"AllowScale\", \" true \")");
+			fscommand("AllowScale", " true ");
+			trace("stage.scaleMode: " + stage.scaleMode);
+
+			trace("Calling fscommand(\"fullscreen\", \"true\")");
+			fscommand("fullscreen", "true");
+			trace("stage.displayState: " + stage.displayState);
+
+			trace("Calling fscommand(\"fullscreen\", \"false\")");
+			fscommand("fullscreen", "false");
+			trace("stage.displayState: " + stage.displayState);
+
+			trace("Calling fscommand(\"quit\")");
+			fscommand("quit");
+
+			trace("Calling fscommand(\"custom\", \"some args\")");
+			fscommand("custom", "some args");
+		}
+	}
+}
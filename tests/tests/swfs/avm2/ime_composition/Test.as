@@ -0,0 +1,36 @@
+package {
+	import flash.display.Sprite;
+	import flash.events.Event;
+	import flash.text.TextField;
+	import flash.text.TextFieldType;
+
+	// The test composes text in this field with `PlayerEvent::ImePreedit` and
+	// `PlayerEvent::ImeCommit`, running a frame after each event.
+	public class Test extends Sprite {
+		public function Test() {
+			var field: TextField = new TextField();
+			field.type = TextFieldType.INPUT;
+			field.x = 10;
+			field.y = 10;
+			field.width = 200;
+			field.height = 50;
+			field.maxChars = 7;
+			field.text = "ab";
+			field.setSelection(2, 2);
+			addChild(field);
+			stage.focus = field;
+
+			// Composed text isn't part of the text until it's committed.
+			var lastText: String = field.text;
+			addEventListener(Event.ENTER_FRAME, function(e: Event): void {
+				if (field.text != lastText) {
+					trace("text: " + field.text);
+					lastText = field.text;
+				}
+				if (field.length == field.maxChars && stage.focus == field) {
+					stage.focus = null;
+				}
+			});
+		}
+	}
+}
@@ -0,0 +1,82 @@
+package {
+    public class Test {
+    }
+}
+
+import flash.utils.getQualifiedClassName;
+
+// Locals are untyped so that the generic (non `_i`) opcodes are used.
+function run() {
+    var max = 2147483647;
+    var min = -2147483648;
+    var zero = 0;
+    var three = 3;
+    var four = 4;
+    var minusThree = -3;
+    var big = 65536;
+
+    trace("// add");
+    trace(three + four);
+    trace(max + 1);
+    trace(min + minusThree);
+    trace(three + 0.5);
+    trace(0.5 + three);
+
+    trace("// subtract");
+    trace(four - three);
+    trace(min - 1);
+    trace(max - min);
+
+    trace("// multiply");
+    trace(three * four);
+    trace(max * 2);
+    trace(big * big);
+    trace(1 / (zero * minusThree));
+    trace(1 / (minusThree * zero));
+    trace(1 / (zero * three));
+
+    trace("// modulo");
+    trace(four % three);
+    trace(minusThree % four);
+    trace(1 / (minusThree % three));
+    trace(four % zero);
+
+    trace("// negate");
+    trace(-three);
+    trace(-max);
+    trace(-min);
+    trace(1 / -zero);
+
+    trace("// increment and decrement");
+    var i = max;
+    i++;
+    trace(i);
+    var j = min;
+    j--;
+    trace(j);
+    var k = three;
+    k++;
+    k++;
+    trace(k);
+    var l = zero;
+    l--;
+    trace(l);
+
+    trace("// typed int wraps");
+    var n:int = max;
+    n++;
+    trace(n);
+    var m:int = min;
+    m = m - 1;
+    trace(m);
+
+    trace("// loop sum overflows into Number");
+    var sum = 0;
+    for (var x = 0; x < 100000; x++) {
+        sum += x;
+    }
+    trace(sum);
+    trace(getQualifiedClassName(sum));
+}
+
+run();
@@ -0,0 +1,68 @@
+package {
+	public class Test {
+	}
+}
+
+// Each loop runs long enough to be compiled.
+
+function sumInts(n:int):int {
+	var total:int = 0;
+	for (var i:int = 0; i < n; i++) {
+		total += i;
+	}
+	return total;
+}
+
+function halves(n:int):Number {
+	var x:Number = 0;
+	for (var i:int = 0; i < n; i++) {
+		x += 0.5;
+	}
+	return x;
+}
+
+function countUp(n:int):int {
+	var i:int = 0;
+	while (i < n) {
+		i++;
+	}
+	return i;
+}
+
+// Concatenating strings isn't compiled, so this leaves the compiled loop every 1000 iterations.
+function everyThousand(n:int):String {
+	var s:String = "";
+	var count:int = 0;
+	for (var i:int = 0; i < n; i++) {
+		count += 2;
+		if (i % 1000 == 999) {
+			s += i + ",";
+		}
+	}
+	return s + count;
+}
+
+// The compiled loop can't run once `x` isn't a number anymore.
+function changesType(n:int):* {
+	var x:* = 0;
+	for (var i:int = 0; i < n; i++) {
+		if (i == n - 3) {
+			x = "x";
+		}
+		x = x + 1;
+	}
+	return x;
+}
+
+trace("// sumInts(5000)");
+trace(sumInts(5000));
+trace("// halves(3000)");
+trace(halves(3000));
+trace("// countUp(3000)");
+trace(countUp(3000));
+trace("// everyThousand(5000)");
+trace(everyThousand(5000));
+trace("// changesType(3000)");
+trace(changesType(3000));
+trace("// changesType(2000)");
+trace(changesType(2000));
@@ -0,0 +1,85 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.getQualifiedClassName;
+
+class Point {
+	public var x:int = 1;
+	private var hidden:int = 2;
+}
+
+class Named {
+	public function get name():String {
+		return "named";
+	}
+	public function method():void {
+	}
+}
+
+function describe(value:*):String {
+	return getQualifiedClassName(value) + " " + value;
+}
+
+trace("// Parse errors");
+try {
+	JSON.parse("{bad}");
+} catch (e:Error) {
+	trace(getQualifiedClassName(e) + " " + e.errorID);
+}
+
+trace("// Cyclic structures");
+var cyclic:Object = {};
+cyclic.self = cyclic;
+try {
+	JSON.stringify(cyclic);
+} catch (e:Error) {
+	trace(getQualifiedClassName(e) + " " + e.errorID);
+}
+
+trace("// Bad replacer");
+try {
+	JSON.stringify({}, {});
+} catch (e:Error) {
+	trace(getQualifiedClassName(e) + " " + e.errorID);
+}
+
+trace("// Numbers");
+trace(describe(JSON.parse("1")));
+trace(describe(JSON.parse("1.5")));
+trace(describe(JSON.parse("4294967296")));
+trace(JSON.stringify([NaN, Infinity, 0.5]));
+
+trace("// Class instances");
+trace(JSON.stringify(new Point()));
+trace(JSON.stringify(new Named()));
+
+trace("// Functions and undefined");
+trace(JSON.stringify({f: function():void {}}));
+trace(JSON.stringify([function():void {}, undefined]));
+
+trace("// Vectors");
+trace(JSON.stringify(new <int>[1, 2, 3]));
+
+trace("// Escapes");
+trace(JSON.stringify("a\"b\\c\n\u0001"));
+
+trace("// Indentation");
+trace(JSON.stringify({a: [1]}, null, 2));
+trace(JSON.stringify([], null, 2));
+
+trace("// Reviver");
+var revived:Array = JSON.parse("[1, 2, 3]", function(key:String, value:*):* {
+	if (key == "1") {
+		return undefined;
+	}
+	return value;
+});
+trace(revived.length + " " + (1 in revived) + " " + revived[2]);
+JSON.parse("{\"a\": 1}", function(key:String, value:*):* {
+	if (key == "a") {
+		trace(this.a);
+	}
+	return value;
+});
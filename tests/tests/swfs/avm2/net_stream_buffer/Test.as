@@ -0,0 +1,89 @@
+package {
+    public class Test {}
+}
+
+import flash.display.Sprite;
+import flash.events.Event;
+import flash.events.NetStatusEvent;
+import flash.net.NetConnection;
+import flash.net.NetStream;
+import flash.net.NetStreamAppendBytesAction;
+import flash.utils.ByteArray;
+
+// Feeds a stream of script data tags in data generation mode, a bit at a
+// time, and reports the buffer state as playback catches up with it.
+
+function header():ByteArray {
+    var bytes = new ByteArray();
+    bytes.writeUTFBytes("FLV");
+    bytes.writeByte(1);
+    bytes.writeByte(0);
+    bytes.writeUnsignedInt(9);
+    bytes.writeUnsignedInt(0);
+    return bytes;
+}
+
+// A script data tag calling `onTick(time)` on the client at `time` milliseconds.
+function tick(bytes:ByteArray, time:uint):void {
+    var payload = new ByteArray();
+    payload.writeByte(2);
+    payload.writeUTF("onTick");
+    payload.writeByte(0);
+    payload.writeDouble(time);
+
+    bytes.writeByte(18);
+    bytes.writeByte(payload.length >> 16);
+    bytes.writeShort(payload.length);
+    bytes.writeByte(time >> 16);
+    bytes.writeShort(time);
+    bytes.writeByte(0);
+    bytes.writeByte(0);
+    bytes.writeShort(0);
+    bytes.writeBytes(payload);
+    bytes.writeUnsignedInt(11 + payload.length);
+}
+
+var connection = new NetConnection();
+connection.connect(null);
+var stream = new NetStream(connection);
+
+trace("default bufferTime: " + stream.bufferTime);
+stream.bufferTime = -1;
+trace("negative bufferTime: " + stream.bufferTime);
+stream.bufferTime = 0.2;
+trace("bufferTime: " + stream.bufferTime);
+
+stream.client = {
+    onTick: function(time:Number):void {
+        trace("onTick " + time);
+    }
+};
+stream.addEventListener(NetStatusEvent.NET_STATUS, function(event:NetStatusEvent):void {
+    trace(event.info.code + ", bufferLength: " + stream.bufferLength);
+});
+
+stream.play(null);
+var data = header();
+tick(data, 0);
+tick(data, 100);
+stream.appendBytes(data);
+trace("bufferLength before playback: " + stream.bufferLength);
+
+var frame = 0;
+var ticker = new Sprite();
+ticker.addEventListener(Event.ENTER_FRAME, function(event:Event):void {
+    frame++;
+    trace("// frame " + frame + ", bufferLength: " + stream.bufferLength);
+    if (frame == 2) {
+        data = new ByteArray();
+        tick(data, 200);
+        stream.appendBytes(data);
+    } else if (frame == 4) {
+        data = new ByteArray();
+        tick(data, 300);
+        tick(data, 400);
+        tick(data, 500);
+        stream.appendBytes(data);
+        stream.appendBytesAction(NetStreamAppendBytesAction.END_SEQUENCE);
+    }
+});
@@ -0,0 +1,42 @@
+package {
+	public class Test {}
+}
+
+import flash.utils.Proxy;
+import flash.utils.flash_proxy;
+
+dynamic class TestProxy extends Proxy {
+	flash_proxy override function getProperty(name:*):* {
+		trace("///getProperty:", name.localName, "isAttribute:", flash_proxy::isAttribute(name));
+		return "property " + name.localName;
+	}
+
+	flash_proxy override function setProperty(name:*, value:*):void {
+		trace("///setProperty:", name.localName, "isAttribute:", flash_proxy::isAttribute(name), "value:", value);
+	}
+
+	flash_proxy override function getDescendants(name:*):* {
+		trace("///getDescendants:", name.localName, "isAttribute:", flash_proxy::isAttribute(name));
+		return "descendants " + name.localName;
+	}
+}
+
+var p = new TestProxy();
+
+trace("///p.normal");
+trace(p.normal);
+
+trace("///p.@attr");
+trace(p.@attr);
+
+trace("///p.@attr = 5");
+p.@attr = 5;
+
+trace("///p..child");
+trace(p..child);
+
+trace("///p..@attr");
+trace(p..@attr);
+
+trace("///p.flash_proxy::isAttribute(\"attr\")");
+trace(p.flash_proxy::isAttribute("attr"));
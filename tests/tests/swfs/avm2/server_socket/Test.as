@@ -0,0 +1,59 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.events.ProgressEvent;
+import flash.events.ServerSocketConnectEvent;
+import flash.net.ServerSocket;
+import flash.net.Socket;
+
+trace("isSupported: " + ServerSocket.isSupported);
+
+var server:ServerSocket = new ServerSocket();
+trace("bound: " + server.bound + ", listening: " + server.listening);
+
+try {
+	server.bind(70000, "127.0.0.1");
+} catch (e:Error) {
+	trace("bind to invalid port: " + e);
+}
+
+try {
+	server.listen();
+} catch (e:Error) {
+	trace("listen before bind: " + e);
+}
+
+server.bind(0, "127.0.0.1");
+trace("bound: " + server.bound + ", localAddress: " + server.localAddress + ", localPort: " + server.localPort);
+
+try {
+	server.bind(0, "127.0.0.1");
+} catch (e:Error) {
+	trace("bind twice: " + e);
+}
+
+server.addEventListener(ServerSocketConnectEvent.CONNECT, function(event:ServerSocketConnectEvent):void {
+	var client:Socket = event.socket;
+	trace("accepted, connected: " + client.connected);
+
+	client.addEventListener(ProgressEvent.SOCKET_DATA, function(event:ProgressEvent):void {
+		trace("received: " + client.readUTFBytes(client.bytesAvailable));
+
+		client.close();
+		server.close();
+		trace("client connected: " + client.connected);
+		trace("bound: " + server.bound + ", listening: " + server.listening);
+
+		try {
+			server.close();
+		} catch (e:Error) {
+			trace("close twice: " + e);
+		}
+	});
+	client.writeUTFBytes("hello");
+	client.flush();
+});
+server.listen();
+trace("listening: " + server.listening);
@@ -0,0 +1,22 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.net.ServerSocket;
+
+trace("isSupported: " + ServerSocket.isSupported);
+
+var server:ServerSocket = new ServerSocket();
+try {
+	server.bind(8080, "127.0.0.1");
+} catch (e:Error) {
+	trace("bind: " + e);
+}
+trace("bound: " + server.bound + ", listening: " + server.listening);
+
+try {
+	server.listen();
+} catch (e:Error) {
+	trace("listen: " + e);
+}
@@ -0,0 +1,19 @@
+package {
+	import flash.display.MovieClip;
+	import flash.external.ExternalInterface;
+
+	public class Child extends MovieClip {
+		public function Child() {
+			super();
+
+			trace("/// Child movie");
+			trace(loaderInfo.url);
+			trace(ExternalInterface.available);
+			try {
+				trace(ExternalInterface.call("eval", "window.location.href"));
+			} catch (error:Error) {
+				trace(error.errorID);
+			}
+		}
+	}
+}
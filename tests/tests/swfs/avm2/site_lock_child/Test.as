@@ -0,0 +1,26 @@
+package {
+	import flash.display.Loader;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.external.ExternalInterface;
+	import flash.net.URLRequest;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			super();
+
+			trace("/// Root movie");
+			trace(loaderInfo.url);
+			trace(ExternalInterface.available);
+			trace(ExternalInterface.call("eval", "window.location.href"));
+			trace(ExternalInterface.call("function() { return document.domain; }"));
+
+			var loader:Loader = new Loader();
+			loader.contentLoaderInfo.addEventListener(Event.COMPLETE, function(event:Event):void {
+				trace("/// Child loaded");
+				trace(ExternalInterface.call("eval", "window.location.href"));
+			});
+			loader.load(new URLRequest("child.swf"));
+		}
+	}
+}
@@ -0,0 +1,70 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.errors.IOError;
+import flash.events.Event;
+import flash.events.IOErrorEvent;
+import flash.events.SecurityErrorEvent;
+import flash.net.Socket;
+
+function listen(name:String, socket:Socket, next:Function):void {
+	function report(event:Event):void {
+		var text:String = name + ": " + event.type;
+		if (event is IOErrorEvent) {
+			text += " " + IOErrorEvent(event).errorID;
+		} else if (event is SecurityErrorEvent) {
+			text += " " + SecurityErrorEvent(event).errorID;
+		}
+		trace(text + ", connected: " + socket.connected);
+		if (event.type != Event.CONNECT && next != null) {
+			next();
+		}
+	}
+	socket.addEventListener(Event.CONNECT, report);
+	socket.addEventListener(Event.CLOSE, report);
+	socket.addEventListener(IOErrorEvent.IO_ERROR, report);
+	socket.addEventListener(SecurityErrorEvent.SECURITY_ERROR, report);
+}
+
+function attempt(name:String, f:Function):void {
+	try {
+		f();
+		trace(name + ": done");
+	} catch (e:Error) {
+		trace(name + ": " + e);
+	}
+}
+
+var accepting:Socket = new Socket();
+var refusing:Socket = new Socket();
+var unresponsive:Socket = new Socket();
+
+listen("accepting", accepting, function():void {
+	attempt("write after close", function():void {
+		accepting.writeByte(1);
+	});
+	refusing.connect("localhost", 8002);
+});
+listen("refusing", refusing, function():void {
+	unresponsive.timeout = 100;
+	trace("timeout: " + unresponsive.timeout);
+	unresponsive.connect("localhost", 8003);
+});
+listen("unresponsive", unresponsive, function():void {
+	attempt("close after timeout", function():void {
+		unresponsive.close();
+	});
+});
+
+trace("default timeout: " + accepting.timeout);
+trace("connected before connect: " + accepting.connected);
+attempt("invalid port", function():void {
+	accepting.connect("localhost", 65536);
+});
+accepting.connect("localhost", 8001);
+trace("connected while connecting: " + accepting.connected);
+attempt("write while connecting", function():void {
+	accepting.writeByte(1);
+});
@@ -0,0 +1,64 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.display.Sprite;
+import flash.events.Event;
+import flash.events.ProgressEvent;
+import flash.net.Socket;
+import flash.utils.Endian;
+
+var socket:Socket = new Socket();
+var ticker:Sprite = new Sprite();
+var framesWaited:int = 0;
+var echoed:Boolean = false;
+
+function waitForFlush(event:Event):void {
+	framesWaited++;
+	if (framesWaited < 3) {
+		return;
+	}
+
+	ticker.removeEventListener(Event.ENTER_FRAME, waitForFlush);
+	trace("bytesAvailable before flush: " + socket.bytesAvailable);
+	socket.flush();
+	trace("flushed");
+}
+
+socket.addEventListener(Event.CONNECT, function(event:Event):void {
+	trace("connected, endian: " + socket.endian);
+});
+
+socket.addEventListener(ProgressEvent.SOCKET_DATA, function(event:ProgressEvent):void {
+	trace("socketData: bytesLoaded " + event.bytesLoaded + ", bytesTotal " + event.bytesTotal + ", bytesAvailable " + socket.bytesAvailable);
+
+	if (echoed) {
+		var bytes:Array = [];
+		while (socket.bytesAvailable > 0) {
+			bytes.push(socket.readUnsignedByte());
+		}
+		trace("echoed: " + bytes);
+		socket.close();
+		trace("connected after close: " + socket.connected);
+		return;
+	}
+
+	if (socket.bytesAvailable < 10) {
+		return;
+	}
+
+	trace("readInt (big endian): " + socket.readInt());
+	socket.endian = Endian.LITTLE_ENDIAN;
+	trace("readShort (little endian): " + socket.readShort());
+	trace("readUnsignedInt (little endian): " + socket.readUnsignedInt());
+	trace("bytesAvailable after reading: " + socket.bytesAvailable);
+
+	socket.writeInt(0x01020304);
+	socket.endian = Endian.BIG_ENDIAN;
+	socket.writeShort(0x0506);
+	echoed = true;
+	ticker.addEventListener(Event.ENTER_FRAME, waitForFlush);
+});
+
+socket.connect("localhost", 8004);
@@ -0,0 +1,42 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.media.Sound;
+import flash.utils.ByteArray;
+
+var floats = new ByteArray();
+floats.writeFloat(0.5);
+floats.writeFloat(-0.5);
+floats.writeFloat(0.25);
+floats.writeFloat(-0.25);
+// Half of another sample.
+floats.writeFloat(1.0);
+floats.position = 0;
+
+trace("// loadPCMFromByteArray(floats, uint.MAX_VALUE, 'float', true, 1000)");
+var sound = new Sound();
+sound.loadPCMFromByteArray(floats, uint.MAX_VALUE, "float", true, 1000);
+trace("length: " + sound.length);
+trace("position: " + floats.position);
+trace("");
+
+var shorts = new ByteArray();
+shorts.writeShort(100);
+shorts.writeShort(-100);
+shorts.writeShort(200);
+shorts.position = 0;
+
+trace("// loadPCMFromByteArray(shorts, 1, 'short', false, 1000)");
+sound = new Sound();
+sound.loadPCMFromByteArray(shorts, 1, "short", false, 1000);
+trace("length: " + sound.length);
+trace("position: " + shorts.position);
+trace("");
+
+trace("// loadPCMFromByteArray(shorts, 1000000, 'short', false, 1000)");
+sound = new Sound();
+sound.loadPCMFromByteArray(shorts, 1000000, "short", false, 1000);
+trace("length: " + sound.length);
+trace("position: " + shorts.position);
@@ -0,0 +1,42 @@
+package {
+    public class Test {}
+}
+
+import flash.display.Sprite;
+import flash.events.Event;
+import flash.events.SampleDataEvent;
+import flash.media.Sound;
+
+// A dynamic sound keeps playing while its handlers write at least 2048
+// samples, and ends after the first shorter write has been played.
+
+var writes = [8192, 2048, 1000];
+var events = 0;
+
+var sound = new Sound();
+sound.addEventListener(SampleDataEvent.SAMPLE_DATA, function(event:SampleDataEvent):void {
+    var count = events < writes.length ? writes[events] : 0;
+    events++;
+    trace("sampleData at " + event.position + ", data length " + event.data.length +
+          ", writing " + count + " samples");
+    for (var i = 0; i < count; i++) {
+        event.data.writeFloat(0);
+        event.data.writeFloat(0);
+    }
+});
+
+var channel = sound.play();
+trace("play returned a channel: " + (channel != null));
+channel.addEventListener(Event.SOUND_COMPLETE, function(event:Event):void {
+    trace("soundComplete after " + events + " sampleData events");
+});
+
+var frame = 0;
+var ticker = new Sprite();
+ticker.addEventListener(Event.ENTER_FRAME, function(event:Event):void {
+    frame++;
+    trace("// frame " + frame);
+});
+
+var silent = new Sound();
+trace("play without a sampleData handler: " + silent.play());
@@ -0,0 +1,51 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.events.StageVideoAvailabilityEvent;
+	import flash.geom.Point;
+	import flash.geom.Rectangle;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var stage = this.stage;
+
+			trace("// stageVideos");
+			trace(stage.stageVideos.length);
+			var video = stage.stageVideos[0];
+			trace(video.videoWidth + " " + video.videoHeight);
+			trace(video.colorSpaces);
+
+			trace("// viewPort");
+			trace(video.viewPort);
+			video.viewPort = new Rectangle(10, 20, 320, 240);
+			trace(video.viewPort);
+
+			trace("// depth");
+			trace(video.depth);
+			video.depth = 3;
+			trace(video.depth);
+
+			trace("// pan");
+			trace(video.pan);
+			video.pan = new Point(0.5, -0.5);
+			trace(video.pan);
+			trace(video.pan == video.pan);
+
+			trace("// zoom");
+			trace(video.zoom);
+			video.zoom = new Point(2, 3);
+			trace(video.zoom);
+
+			trace("// availability");
+			stage.addEventListener(StageVideoAvailabilityEvent.STAGE_VIDEO_AVAILABILITY, function(evt) {
+				trace("stage got " + evt.type + ": " + evt.availability + ", target is stage: " + (evt.target === stage));
+			});
+			// Only the stage reports the availability to new listeners.
+			var sprite = new Sprite();
+			sprite.addEventListener(StageVideoAvailabilityEvent.STAGE_VIDEO_AVAILABILITY, function(evt) {
+				trace("sprite got " + evt.type);
+			});
+			trace("listeners added");
+		}
+	}
+}
@@ -0,0 +1,59 @@
+package {
+	public class Test {}
+}
+
+import flash.text.TextField;
+import flash.text.engine.*;
+
+function createLine(text:String, size:Number, width:Number = 1000000):TextLine {
+	var block:TextBlock = new TextBlock(new TextElement(text, new ElementFormat(null, size)));
+	return block.createTextLine(null, width);
+}
+
+trace("// A line of 12px text");
+var small:TextLine = createLine("hello", 12);
+trace("numChildren: " + small.numChildren);
+trace("child is TextField: " + (small.getChildAt(0) is TextField));
+trace("rawTextLength: " + small.rawTextLength);
+trace("ascent > 0: " + (small.ascent > 0));
+trace("descent > 0: " + (small.descent > 0));
+trace("textHeight == ascent + descent: " + (Math.abs(small.textHeight - small.ascent - small.descent) < 0.001));
+trace("textWidth > 0: " + (small.textWidth > 0));
+trace("textWidth < specifiedWidth: " + (small.textWidth < small.specifiedWidth));
+trace("text field y + ascent: " + Math.round(small.getChildAt(0).y + small.ascent));
+trace("getBaselinePosition(ascent) == -ascent: " + (small.getBaselinePosition(TextBaseline.ASCENT) == -small.ascent));
+trace("textBlock.textLineCreationResult: " + small.textBlock.textLineCreationResult);
+trace("next line: " + small.textBlock.createTextLine(small));
+trace("textBlock.textLineCreationResult: " + small.textBlock.textLineCreationResult);
+trace("");
+
+trace("// The same text at 24px");
+var large:TextLine = createLine("hello", 24);
+trace("ascent ratio: " + Math.round(large.ascent / small.ascent));
+trace("textWidth ratio: " + Math.round(large.textWidth / small.textWidth));
+trace("");
+
+trace("// Longer text is wider");
+var longer:TextLine = createLine("hello hello", 12);
+trace("rawTextLength: " + longer.rawTextLength);
+trace("textWidth > 12px hello: " + (longer.textWidth > small.textWidth));
+trace("ascent == 12px hello: " + (longer.ascent == small.ascent));
+trace("");
+
+trace("// Lines wrap at the width");
+var block:TextBlock = new TextBlock(new TextElement("hello world", new ElementFormat(null, 12)));
+var first:TextLine = block.createTextLine(null, small.textWidth + 10);
+trace("first rawTextLength: " + first.rawTextLength);
+trace("first textWidth <= width: " + (first.textWidth <= first.specifiedWidth));
+var second:TextLine = block.createTextLine(first, small.textWidth + 10);
+trace("second textBlockBeginIndex: " + second.textBlockBeginIndex);
+trace("second rawTextLength: " + second.rawTextLength);
+trace("first.nextLine == second: " + (first.nextLine == second));
+trace("block.lastLine == second: " + (block.lastLine == second));
+trace("third line: " + block.createTextLine(second, small.textWidth + 10));
+trace("");
+
+trace("// Nothing fits");
+var narrow:TextBlock = new TextBlock(new TextElement("hello", new ElementFormat(null, 12)));
+trace("line: " + narrow.createTextLine(null, 1));
+trace("textLineCreationResult: " + narrow.textLineCreationResult);
@@ -0,0 +1,55 @@
+package {
+	import flash.display.MovieClip;
+	import flash.text.TextSnapshot;
+
+	// The first static text reads "Hello" and "World" on two lines and is placed at (10, 20),
+	// and the second reads "Foo" and is placed at (100, 20). Each glyph is 10 pixels high
+	// and 5 pixels wide.
+	public class Test extends MovieClip {
+		public function Test() {
+			addFrameScript(0, this.frame1);
+		}
+
+		private function frame1(): void {
+			var snapshot: TextSnapshot = this.textSnapshot;
+			trace("charCount: " + snapshot.charCount);
+
+			trace("/// getText");
+			trace(snapshot.getText(0, snapshot.charCount));
+			trace(snapshot.getText(0, snapshot.charCount, true));
+			trace(snapshot.getText(3, 7));
+			trace(snapshot.getText(-5, 100));
+			trace("empty: \"" + snapshot.getText(8, 2) + "\"");
+
+			trace("/// findText");
+			trace(snapshot.findText(0, "world", false));
+			trace(snapshot.findText(0, "world", true));
+			trace(snapshot.findText(6, "o", true));
+			trace(snapshot.findText(7, "o", true));
+
+			trace("/// setSelected");
+			trace(snapshot.getSelected(0, snapshot.charCount));
+			snapshot.setSelected(1, 3, true);
+			snapshot.setSelected(9, 11, true);
+			trace(snapshot.getSelected(0, snapshot.charCount));
+			trace(snapshot.getSelected(3, 9));
+			trace(snapshot.getSelected(10, 11));
+			trace(snapshot.getSelectedText());
+			trace(snapshot.getSelectedText(true));
+			snapshot.setSelected(0, 10, false);
+			trace(snapshot.getSelectedText());
+
+			trace("/// hitTestTextNearPos");
+			trace(snapshot.hitTestTextNearPos(12, 25));
+			trace(snapshot.hitTestTextNearPos(22, 25));
+			trace(snapshot.hitTestTextNearPos(12, 40));
+			trace(snapshot.hitTestTextNearPos(107, 25));
+			trace(snapshot.hitTestTextNearPos(0, 25));
+			trace(snapshot.hitTestTextNearPos(0, 25, 20));
+
+			trace("/// A new snapshot");
+			trace(this.textSnapshot.getSelectedText());
+			trace(new TextSnapshot().charCount);
+		}
+	}
+}
@@ -0,0 +1,92 @@
+package {
+	public class Test {}
+}
+
+import fl.text.RuntimeManager;
+import fl.text.TLFTextField;
+import flash.display.Sprite;
+import flash.geom.Rectangle;
+import flash.text.TextFormat;
+
+function traceFormat(field:TLFTextField, index:int):void {
+	var format:TextFormat = field.getTextFormat(index, index + 1);
+	trace("  " + field.text.charAt(index) + ": align=" + format.align + " bold=" + format.bold +
+		" italic=" + format.italic + " underline=" + format.underline + " size=" + format.size +
+		" color=" + format.color.toString(16) + " url=" + format.url);
+}
+
+trace("// A TextFlow");
+var flow:TLFTextField = new TLFTextField();
+flow.tlfMarkup = '<TextFlow xmlns="http://ns.adobe.com/textLayout/2008" paddingLeft="10" paddingTop="6" lineBreak="explicit">' +
+	'<p textAlign="center"><span>Plain</span><span fontWeight="bold" color="#FF0000">Bold</span></p>' +
+	'<p textAlign="end"><a href="http://example.com/"><span textDecoration="underline">Link</span></a><span fontSize="20" fontStyle="italic">Big</span></p>' +
+	'</TextFlow>';
+trace("text: " + escape(flow.text));
+traceFormat(flow, 0);
+traceFormat(flow, 5);
+traceFormat(flow, 10);
+traceFormat(flow, 14);
+trace("paddingLeft: " + flow.paddingLeft);
+trace("paddingTop: " + flow.paddingTop);
+trace("lineBreak: " + flow.lineBreak);
+trace("wordWrap: " + flow.wordWrap);
+trace("field x: " + flow.getChildAt(0).x);
+trace("field y: " + flow.getChildAt(0).y);
+trace("textFlow: " + flow.textFlow);
+trace("");
+
+trace("// Vertical alignment");
+flow.verticalAlign = "bottom";
+trace("field y + textHeight: " + Math.round(flow.getChildAt(0).y + flow.textHeight));
+flow.verticalAlign = "top";
+trace("field y: " + flow.getChildAt(0).y);
+trace("");
+
+trace("// Markup that escapes HTML");
+flow.tlfMarkup = '<TextFlow xmlns="http://ns.adobe.com/textLayout/2008"><p><span>1 &lt; 2 &amp;&amp; "a" &gt; b</span></p></TextFlow>';
+trace("text: " + flow.text);
+trace("");
+
+trace("// An editable tlfTextObject");
+var input:TLFTextField = new TLFTextField();
+input.tlfMarkup = '<tlfTextObject type="Input" editPolicy="readWrite">' +
+	'<TextFlow xmlns="http://ns.adobe.com/textLayout/2008"><p><span>Edit</span></p></TextFlow>' +
+	'</tlfTextObject>';
+trace("text: " + input.text);
+trace("type: " + input.type);
+trace("selectable: " + input.selectable);
+trace("");
+
+trace("// A read-only point tlfTextObject");
+var point:TLFTextField = new TLFTextField();
+point.tlfMarkup = '<tlfTextObject type="Point" editPolicy="readOnly">' +
+	'<TextFlow xmlns="http://ns.adobe.com/textLayout/2008"><p><span>Label</span></p></TextFlow>' +
+	'</tlfTextObject>';
+trace("text: " + point.text);
+trace("type: " + point.type);
+trace("selectable: " + point.selectable);
+trace("wordWrap: " + point.wordWrap);
+trace("autoSize: " + point.autoSize);
+trace("");
+
+trace("// RuntimeManager.addInstance");
+var container:Sprite = new Sprite();
+var placeholder:Sprite = new Sprite();
+placeholder.name = "title";
+placeholder.x = 10;
+placeholder.y = 20;
+container.addChild(new Sprite());
+container.addChild(placeholder);
+container.addChild(new Sprite());
+var added:TLFTextField = RuntimeManager.getSingleton().addInstance(container, "title", new Rectangle(5, 5, 200, 50),
+	'<TextFlow xmlns="http://ns.adobe.com/textLayout/2008"><p><span>Title</span></p></TextFlow>');
+trace("name: " + added.name);
+trace("x: " + added.x);
+trace("y: " + added.y);
+trace("text: " + added.text);
+trace("index: " + container.getChildIndex(added));
+trace("numChildren: " + container.numChildren);
+trace("placeholder removed: " + (placeholder.parent == null));
+trace("field width: " + added.getChildAt(0).width);
+trace("field height: " + added.getChildAt(0).height);
+trace("same singleton: " + (RuntimeManager.getSingleton() == RuntimeManager.getSingleton()));
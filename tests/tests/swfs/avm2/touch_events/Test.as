@@ -0,0 +1,75 @@
+package {
+	import flash.display.Sprite;
+	import flash.events.MouseEvent;
+	import flash.events.TouchEvent;
+	import flash.ui.Multitouch;
+	import flash.ui.MultitouchInputMode;
+
+	// The test touches the screen with `PlayerEvent::Touch`, running a frame after
+	// each event.
+	public class Test extends Sprite {
+		private var clicks: int = 0;
+
+		public function Test() {
+			Multitouch.inputMode = MultitouchInputMode.TOUCH_POINT;
+			trace("inputMode: " + Multitouch.inputMode);
+
+			var square: Sprite = makeSquare("square", 10);
+			addChild(square);
+			addChild(makeSquare("other", 150));
+
+			var touchEvents: Array = [
+				TouchEvent.TOUCH_BEGIN,
+				TouchEvent.TOUCH_MOVE,
+				TouchEvent.TOUCH_OVER,
+				TouchEvent.TOUCH_OUT,
+				TouchEvent.TOUCH_END,
+				TouchEvent.TOUCH_TAP
+			];
+			for each (var type: String in touchEvents) {
+				stage.addEventListener(type, traceTouch);
+			}
+
+			square.addEventListener(MouseEvent.MOUSE_DOWN, traceMouse);
+			square.addEventListener(MouseEvent.MOUSE_UP, traceMouse);
+			square.addEventListener(MouseEvent.CLICK, traceMouse);
+		}
+
+		private function makeSquare(name: String, x: Number): Sprite {
+			var square: Sprite = new Sprite();
+			square.name = name;
+			square.x = x;
+			square.y = 10;
+			square.graphics.beginFill(0xFF0000);
+			square.graphics.drawRect(0, 0, 100, 100);
+			square.graphics.endFill();
+			return square;
+		}
+
+		private function traceTouch(e: TouchEvent): void {
+			var message: String = e.type + " #" + e.touchPointID;
+			if (e.isPrimaryTouchPoint) {
+				message += " (primary)";
+			}
+			message += " on " + nameOf(e.target) + " at (" + e.localX + ", " + e.localY + ")";
+			if (e.relatedObject) {
+				message += ", relatedObject = " + nameOf(e.relatedObject);
+			}
+			trace(message);
+		}
+
+		private function traceMouse(e: MouseEvent): void {
+			trace(e.type + " on " + nameOf(e.target) + " at (" + e.localX + ", " + e.localY + ")");
+
+			// Touches are still mouse events when they aren't reported as touches.
+			if (e.type == MouseEvent.CLICK && ++clicks == 2) {
+				Multitouch.inputMode = MultitouchInputMode.NONE;
+				trace("inputMode: " + Multitouch.inputMode);
+			}
+		}
+
+		private function nameOf(object: Object): String {
+			return object == stage ? "stage" : object.name;
+		}
+	}
+}
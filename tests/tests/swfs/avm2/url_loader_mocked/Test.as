@@ -0,0 +1,58 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.display.Sprite;
+import flash.events.Event;
+import flash.events.IOErrorEvent;
+import flash.net.URLLoader;
+import flash.net.URLLoaderDataFormat;
+import flash.net.URLRequest;
+import flash.net.URLRequestMethod;
+import flash.utils.ByteArray;
+
+var frame = 0;
+var ticker = new Sprite();
+ticker.addEventListener(Event.ENTER_FRAME, function(evt:Event):void {
+	frame++;
+	if (frame <= 4) {
+		trace("// frame " + frame);
+	}
+});
+
+function load(url:String, method:String = null, dataFormat:String = "text"):void {
+	var request = new URLRequest(url);
+	if (method != null) {
+		request.method = method;
+		request.data = "x=1";
+	}
+	var loader = new URLLoader();
+	loader.dataFormat = dataFormat;
+	loader.addEventListener(Event.COMPLETE, function(evt:Event):void {
+		if (loader.data is ByteArray) {
+			var bytes = [];
+			for (var i = 0; i < loader.data.length; i++) {
+				bytes.push(loader.data[i]);
+			}
+			trace(url + " loaded " + loader.data.length + " bytes: " + bytes);
+		} else {
+			trace(url + " loaded: " + loader.data);
+		}
+	});
+	loader.addEventListener(IOErrorEvent.IO_ERROR, function(evt:IOErrorEvent):void {
+		trace(url + " failed: " + evt.text);
+	});
+	loader.load(request);
+}
+
+// Responses arrive after their latency, in frames, regardless of the order of the requests.
+load("slow.txt");
+load("medium.bin", null, URLLoaderDataFormat.BINARY);
+load("refused.txt");
+load("fast.txt");
+// Responses may be mocked for a single method.
+load("form.txt", URLRequestMethod.POST);
+load("form.txt");
+// Requests that aren't mocked load the files next to the test.
+load("local.txt");
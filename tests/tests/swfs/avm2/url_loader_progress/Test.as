@@ -0,0 +1,51 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.display.Sprite;
+import flash.events.Event;
+import flash.events.HTTPStatusEvent;
+import flash.events.IOErrorEvent;
+import flash.events.ProgressEvent;
+import flash.net.URLLoader;
+import flash.net.URLRequest;
+
+var frame = 0;
+var ticker = new Sprite();
+ticker.addEventListener(Event.ENTER_FRAME, function(evt:Event):void {
+	frame++;
+	if (frame <= 5) {
+		trace("// frame " + frame);
+	}
+});
+
+function load(url:String):void {
+	var loader:URLLoader = new URLLoader();
+	loader.addEventListener(Event.OPEN, function(evt:Event):void {
+		trace(url + " open");
+	});
+	var onStatus = function(evt:HTTPStatusEvent):void {
+		var headers = [];
+		for each (var header in evt.responseHeaders) {
+			headers.push(header.name + ": " + header.value);
+		}
+		trace(url + " " + evt.type + ": status " + evt.status + ", responseURL " + evt.responseURL + ", headers [" + headers.join(", ") + "]");
+	};
+	loader.addEventListener(HTTPStatusEvent.HTTP_RESPONSE_STATUS, onStatus);
+	loader.addEventListener(HTTPStatusEvent.HTTP_STATUS, onStatus);
+	loader.addEventListener(ProgressEvent.PROGRESS, function(evt:ProgressEvent):void {
+		trace(url + " progress: " + evt.bytesLoaded + "/" + evt.bytesTotal + ", loader: " + loader.bytesLoaded + "/" + loader.bytesTotal + ", data: " + loader.data);
+	});
+	loader.addEventListener(Event.COMPLETE, function(evt:Event):void {
+		trace(url + " complete: " + loader.data + ", loader: " + loader.bytesLoaded + "/" + loader.bytesTotal);
+	});
+	loader.addEventListener(IOErrorEvent.IO_ERROR, function(evt:IOErrorEvent):void {
+		trace(url + " ioError: " + evt.text + " (" + evt.errorID + "), data: " + loader.data);
+	});
+	loader.load(new URLRequest(url));
+	trace(url + " loading: " + loader.bytesLoaded + "/" + loader.bytesTotal);
+}
+
+load("data.txt");
+load("missing.txt");
@@ -0,0 +1,87 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.events.Event;
+import flash.events.IOErrorEvent;
+import flash.net.URLLoader;
+import flash.net.URLRequest;
+import flash.net.URLRequestHeader;
+import flash.net.URLRequestMethod;
+import flash.net.URLVariables;
+import flash.utils.ByteArray;
+
+var requests:Array = [];
+
+function queue(description:String, request:URLRequest):void {
+	requests.push({description: description, request: request});
+}
+
+// The requests are made one at a time, so that their responses arrive in order.
+function next():void {
+	if (requests.length == 0) {
+		return;
+	}
+	var entry:Object = requests.shift();
+	var loader:URLLoader = new URLLoader();
+	loader.addEventListener(Event.COMPLETE, function(evt:Event):void {
+		trace("// " + entry.description);
+		trace(loader.data);
+		trace("");
+		next();
+	});
+	loader.addEventListener(IOErrorEvent.IO_ERROR, function(evt:IOErrorEvent):void {
+		trace("// " + entry.description);
+		trace("ioError: " + evt.text);
+		trace("");
+		next();
+	});
+	loader.load(entry.request);
+}
+
+function variables():URLVariables {
+	var variables:URLVariables = new URLVariables();
+	variables.score = 10;
+	return variables;
+}
+
+var request:URLRequest = new URLRequest("echo");
+request.data = variables();
+queue("GET with variables", request);
+
+request = new URLRequest("echo");
+request.method = URLRequestMethod.POST;
+request.data = variables();
+queue("POST with variables", request);
+
+request = new URLRequest("echo");
+request.method = URLRequestMethod.POST;
+queue("POST without data", request);
+
+request = new URLRequest("echo");
+request.method = URLRequestMethod.POST;
+request.contentType = "application/json";
+request.data = '{"score":10}';
+queue("POST with a string and a content type", request);
+
+var bytes:ByteArray = new ByteArray();
+bytes.writeUTFBytes("BIN");
+request = new URLRequest("echo");
+request.method = URLRequestMethod.PUT;
+request.contentType = "application/octet-stream";
+request.data = bytes;
+queue("PUT with a ByteArray", request);
+
+request = new URLRequest("echo");
+request.method = URLRequestMethod.DELETE;
+request.requestHeaders = [new URLRequestHeader("X-Token", "abc"), new URLRequestHeader("X-Retry", "1")];
+request.followRedirects = false;
+queue("DELETE with headers, without following redirects", request);
+
+request = new URLRequest("echo?page=2");
+request.method = URLRequestMethod.HEAD;
+request.data = "q=1";
+queue("HEAD with data on a URL with a query", request);
+
+next();
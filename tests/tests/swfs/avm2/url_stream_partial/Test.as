@@ -0,0 +1,69 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.display.Sprite;
+import flash.events.Event;
+import flash.events.IOErrorEvent;
+import flash.events.ProgressEvent;
+import flash.net.URLRequest;
+import flash.net.URLStream;
+import flash.utils.ByteArray;
+
+var frame = 0;
+var ticker = new Sprite();
+ticker.addEventListener(Event.ENTER_FRAME, function(evt:Event):void {
+	frame++;
+	if (frame <= 5) {
+		trace("// frame " + frame);
+	}
+});
+
+var stream:URLStream = new URLStream();
+var progress:int = 0;
+
+stream.addEventListener(Event.OPEN, function(evt:Event):void {
+	trace("open, connected: " + stream.connected + ", bytesAvailable: " + stream.bytesAvailable);
+});
+
+stream.addEventListener(ProgressEvent.PROGRESS, function(evt:ProgressEvent):void {
+	progress++;
+	trace("progress: bytesLoaded " + evt.bytesLoaded + ", bytesTotal " + evt.bytesTotal + ", bytesAvailable " + stream.bytesAvailable);
+
+	// Only part of what arrived is read, so the rest has to wait for the next chunk.
+	if (progress == 1) {
+		trace("readUTFBytes(2): " + stream.readUTFBytes(2));
+	} else if (progress == 2) {
+		var bytes:ByteArray = new ByteArray();
+		stream.readBytes(bytes, 0, 3);
+		trace("readBytes(3): " + bytes.toString());
+	} else {
+		trace("readUTFBytes(bytesAvailable): " + stream.readUTFBytes(stream.bytesAvailable));
+	}
+	trace("bytesAvailable after reading: " + stream.bytesAvailable);
+});
+
+stream.addEventListener(Event.COMPLETE, function(evt:Event):void {
+	trace("complete, connected: " + stream.connected + ", bytesAvailable: " + stream.bytesAvailable);
+	stream.close();
+	trace("connected after close: " + stream.connected);
+	try {
+		stream.readByte();
+	} catch (e:Error) {
+		trace("readByte after close: " + e);
+	}
+});
+
+stream.addEventListener(IOErrorEvent.IO_ERROR, function(evt:IOErrorEvent):void {
+	trace("ioError: " + evt.text);
+});
+
+trace("connected before load: " + stream.connected);
+try {
+	stream.close();
+} catch (e:Error) {
+	trace("close before load: " + e);
+}
+stream.load(new URLRequest("stream.txt"));
+trace("connected while loading: " + stream.connected);
@@ -0,0 +1,39 @@
+package {
+	public class Test {
+	}
+}
+
+var v:Vector.<int> = new Vector.<int>(3, true);
+
+function attempt(name:String, f:Function):void {
+	try {
+		trace(name + ": " + f());
+	} catch (e:Error) {
+		trace(name + ": " + e);
+	}
+	trace("  length " + v.length + ", contents " + v);
+}
+
+trace("// new Vector.<int>(3, true)");
+trace(v.fixed, v.length, v);
+
+attempt("v[0] = 5", function():* { return v[0] = 5; });
+attempt("v[3] = 1", function():* { return v[3] = 1; });
+attempt("v[4]", function():* { return v[4]; });
+attempt("v.push(1)", function():* { return v.push(1); });
+attempt("v.pop()", function():* { return v.pop(); });
+attempt("v.shift()", function():* { return v.shift(); });
+attempt("v.unshift(1)", function():* { return v.unshift(1); });
+attempt("v.insertAt(0, 1)", function():* { v.insertAt(0, 1); return "done"; });
+attempt("v.removeAt(0)", function():* { return v.removeAt(0); });
+attempt("v.splice(0, 1)", function():* { return v.splice(0, 1); });
+attempt("v.splice(0, 0, 1)", function():* { return v.splice(0, 0, 1); });
+attempt("v.length = 5", function():* { return v.length = 5; });
+attempt("v.reverse()", function():* { return v.reverse(); });
+
+trace("// v.fixed = false");
+v.fixed = false;
+attempt("v.push(7)", function():* { return v.push(7); });
+attempt("v[4] = 9", function():* { return v[4] = 9; });
+attempt("v[7] = 9", function():* { return v[7] = 9; });
+attempt("v.length = 2", function():* { return v.length = 2; });
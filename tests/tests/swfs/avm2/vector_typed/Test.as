@@ -0,0 +1,82 @@
+package {
+	import flash.display.Sprite;
+
+	public class Test {
+	}
+}
+
+import flash.display.Sprite;
+
+trace("// storing into Vector.<int>");
+var ints:Vector.<int> = new Vector.<int>(5);
+ints[0] = 3.7;
+ints[1] = -2.5;
+ints[2] = "42";
+ints[3] = 4294967297;
+ints[4] = undefined;
+trace(ints);
+
+trace("// storing into Vector.<uint>");
+var uints:Vector.<uint> = new Vector.<uint>(3);
+uints[0] = -1;
+uints[1] = 7.9;
+uints[2] = null;
+trace(uints);
+
+trace("// storing into Vector.<Number>");
+var numbers:Vector.<Number> = new Vector.<Number>(3);
+numbers[0] = "2.5";
+numbers[1] = "abc";
+numbers[2] = true;
+trace(numbers);
+
+trace("// storing into Vector.<String>");
+var strings:Vector.<String> = new Vector.<String>(3);
+strings[0] = 5;
+strings[1] = null;
+strings[2] = false;
+trace(strings[0], strings[1], strings[2]);
+
+trace("// storing into Vector.<Sprite>");
+var sprites:Vector.<Sprite> = new Vector.<Sprite>(1);
+try {
+	sprites[0] = "not a sprite";
+} catch (e:TypeError) {
+	trace(e.errorID, sprites[0]);
+}
+
+trace("// concat");
+var fixed:Vector.<int> = new Vector.<int>(2, true);
+var joined:Vector.<int> = fixed.concat(new <int>[3], new <int>[4, 5]);
+trace(joined, joined.fixed);
+var halves:Vector.<Number> = new <Number>[1.5].concat(new <Number>[2, 2.5]);
+trace(halves);
+try {
+	ints.concat(new <Number>[1.5]);
+} catch (e:TypeError) {
+	trace(e.errorID);
+}
+
+trace("// splice");
+var spliced:Vector.<int> = new <int>[1, 2, 3, 4, 5];
+var removed:Vector.<int> = spliced.splice(1, 2, "7", 8.9);
+trace(spliced, removed);
+removed.push(6.5);
+trace(removed);
+var splicedNumbers:Vector.<Number> = new <Number>[0.5, 1.5];
+trace(splicedNumbers.splice(-1, 1, "2.5", 3), splicedNumbers);
+
+trace("// sort");
+var unsorted:Vector.<int> = new <int>[10, 9, -1, 100];
+trace(unsorted.sort(0));
+trace(unsorted.sort(Array.NUMERIC));
+trace(unsorted.sort(Array.NUMERIC | Array.DESCENDING));
+var unsortedUints:Vector.<uint> = new <uint>[3, 4294967295, 0];
+trace(unsortedUints.sort(Array.NUMERIC));
+var unsortedNumbers:Vector.<Number> = new <Number>[2.5, -0.5, 1];
+trace(unsortedNumbers.sort(function(a:Number, b:Number):Number {
+	return a - b;
+}));
+var words:Vector.<String> = new <String>["b", "C", "a"];
+trace(words.sort(0));
+trace(words.sort(Array.CASEINSENSITIVE));
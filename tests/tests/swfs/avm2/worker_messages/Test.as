@@ -0,0 +1,77 @@
+package {
+	import flash.display.MovieClip;
+	import flash.system.MessageChannel;
+	import flash.system.Worker;
+	import flash.system.WorkerDomain;
+	import flash.utils.ByteArray;
+
+	// The worker runs this same movie. Only the primordial worker traces, and it waits for each
+	// reply of the worker, so that the output doesn't depend on how the threads are scheduled.
+	public class Test extends MovieClip {
+		public function Test() {
+			if (Worker.current.isPrimordial) {
+				runPrimordial();
+			} else {
+				runWorker();
+			}
+		}
+
+		private function runPrimordial():void {
+			trace("Worker.isSupported: " + Worker.isSupported);
+			var worker:Worker = WorkerDomain.current.createWorker(loaderInfo.bytes);
+			trace("state before start: " + worker.state);
+
+			var toWorker:MessageChannel = Worker.current.createMessageChannel(worker);
+			var fromWorker:MessageChannel = worker.createMessageChannel(Worker.current);
+			worker.setSharedProperty("toWorker", toWorker);
+			worker.setSharedProperty("fromWorker", fromWorker);
+			worker.setSharedProperty("greeting", {text: "hello", numbers: [1, 2, 3]});
+			trace("getSharedProperty: " + worker.getSharedProperty("greeting").text);
+
+			var shared:ByteArray = new ByteArray();
+			shared.shareable = true;
+			shared.length = 4;
+			worker.setSharedProperty("shared", shared);
+
+			worker.start();
+			trace("state after start: " + worker.state);
+
+			toWorker.send("ping");
+			trace("received: " + fromWorker.receive(true));
+			trace("shared[0] after the worker set it: " + shared[0]);
+
+			shared[1] = 7;
+			toWorker.send("check");
+			trace("received: " + fromWorker.receive(true));
+			trace("shared property set by the worker: " + worker.getSharedProperty("result"));
+
+			toWorker.close();
+			trace("received: " + fromWorker.receive(true));
+
+			trace("terminate: " + worker.terminate());
+			trace("state after terminate: " + worker.state);
+			trace("fromWorker.state: " + fromWorker.state);
+			trace("receive(true) on a closed channel: " + fromWorker.receive(true));
+		}
+
+		private function runWorker():void {
+			var toWorker:MessageChannel = Worker.current.getSharedProperty("toWorker");
+			var fromWorker:MessageChannel = Worker.current.getSharedProperty("fromWorker");
+			var greeting:Object = Worker.current.getSharedProperty("greeting");
+			var shared:ByteArray = Worker.current.getSharedProperty("shared");
+
+			var message:* = toWorker.receive(true);
+			shared[0] = 42;
+			fromWorker.send("pong to " + message + ", " + greeting.text + " " + greeting.numbers +
+				", shareable " + shared.shareable);
+
+			message = toWorker.receive(true);
+			Worker.current.setSharedProperty("result", "done");
+			fromWorker.send(message + ": shared[1] is " + shared[1]);
+
+			// This waits until the channel is closed.
+			message = toWorker.receive(true);
+			fromWorker.send("toWorker " + toWorker.state + ", received " + message);
+		}
+	}
+}
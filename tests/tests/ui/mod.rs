@@ -0,0 +1,239 @@
+use crate::set_logger;
+use crate::util::options::TestOptions;
+use crate::util::test::Test;
+use ruffle_core::backend::ui::{FullscreenError, MouseCursor, UiBackend};
+use ruffle_core::events::{KeyCode, TouchPhase};
+use ruffle_core::{Player, PlayerEvent};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+/// A UI backend that records the calls the player makes to it, so that they're compared with
+/// the expected output.
+struct TestUiBackend {
+    calls: Rc<RefCell<Vec<String>>>,
+
+    clipboard: String,
+
+    /// Where the candidate window of input methods was last moved to, horizontally.
+    ///
+    /// It's only compared with where it was before, as where the caret is depends on the
+    /// widths of the glyphs of the device font.
+    ime_x: Option<f64>,
+}
+
+impl TestUiBackend {
+    fn record(&self, call: String) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+impl UiBackend for TestUiBackend {
+    fn mouse_visible(&self) -> bool {
+        true
+    }
+
+    fn set_mouse_visible(&mut self, _visible: bool) {}
+
+    fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
+
+    fn clipboard_content(&mut self) -> String {
+        self.clipboard.clone()
+    }
+
+    fn set_clipboard_content(&mut self, content: String) {
+        self.record(format!("set_clipboard_content({content:?})"));
+        self.clipboard = content;
+    }
+
+    fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), FullscreenError> {
+        Ok(())
+    }
+
+    fn display_unsupported_message(&self) {}
+
+    fn display_root_movie_download_failed_message(&self) {}
+
+    fn set_ime_allowed(&mut self, allowed: bool) {
+        self.record(format!("set_ime_allowed({allowed})"));
+    }
+
+    fn set_ime_position(&mut self, x: f64, y: f64) {
+        let moved = match self.ime_x {
+            Some(last_x) if x > last_x => "right",
+            Some(last_x) if x < last_x => "left",
+            Some(_) => "nowhere",
+            None => "below the caret",
+        };
+        self.ime_x = Some(x);
+        self.record(format!("set_ime_position: moved {moved}, y = {y}"));
+    }
+
+    fn message(&self, _message: &str) {}
+}
+
+/// Runs a test with a `TestUiBackend`, then sends it `events`, running a frame after each.
+///
+/// The events and the calls made to the UI backend are traced along with the output of the
+/// movie.
+fn run_with_ui(
+    path: &str,
+    name: &str,
+    events: Vec<PlayerEvent>,
+) -> Result<(), libtest_mimic::Failed> {
+    set_logger();
+    let calls = Rc::new(RefCell::new(vec![]));
+    let trace_calls = |player: &Player| {
+        for call in calls.borrow_mut().drain(..) {
+            player.log_backend().avm_trace(&call);
+        }
+    };
+    Test::from_options(
+        TestOptions {
+            num_frames: 1,
+            ..Default::default()
+        },
+        Path::new(path),
+        name.to_string(),
+    )?
+    .run(
+        |player| {
+            *player.lock().unwrap().ui_mut() = Box::new(TestUiBackend {
+                calls: calls.clone(),
+                clipboard: String::new(),
+                ime_x: None,
+            });
+            Ok(())
+        },
+        |player| {
+            let mut player = player.lock().unwrap();
+            trace_calls(&player);
+            for event in events {
+                player.log_backend().avm_trace(&format!("{event:?}"));
+                player.handle_event(event);
+                trace_calls(&player);
+                player.run_frame();
+                trace_calls(&player);
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Composes text in a focused input text field with an input method.
+pub fn ime_composition() -> Result<(), libtest_mimic::Failed> {
+    run_with_ui(
+        "tests/swfs/avm2/ime_composition/",
+        "ime_composition",
+        vec![
+            PlayerEvent::ImePreedit {
+                text: "にほ".to_string(),
+            },
+            PlayerEvent::ImeCommit {
+                text: "日本go".to_string(),
+            },
+            PlayerEvent::ImePreedit {
+                text: "x".to_string(),
+            },
+            PlayerEvent::ImePreedit {
+                text: "".to_string(),
+            },
+            PlayerEvent::ImeCommit {
+                text: "!?".to_string(),
+            },
+            // The field isn't focused anymore.
+            PlayerEvent::ImeCommit {
+                text: "?".to_string(),
+            },
+        ],
+    )
+}
+
+/// Copies, cuts and pastes the text of a focused input text field, through the embedder and
+/// with shortcuts.
+pub fn clipboard() -> Result<(), libtest_mimic::Failed> {
+    let key_down = |key_code, key_char| PlayerEvent::KeyDown { key_code, key_char };
+    run_with_ui(
+        "tests/swfs/avm2/clipboard/",
+        "clipboard",
+        vec![
+            PlayerEvent::Copy,
+            PlayerEvent::Cut,
+            PlayerEvent::Paste {
+                text: "Hey".to_string(),
+            },
+            key_down(KeyCode::Control, None),
+            key_down(KeyCode::A, Some('a')),
+            key_down(KeyCode::X, Some('x')),
+            key_down(KeyCode::V, Some('v')),
+            // Characters typed with Ctrl aren't text.
+            PlayerEvent::TextInput { codepoint: 'v' },
+            PlayerEvent::KeyUp {
+                key_code: KeyCode::Control,
+                key_char: None,
+            },
+            PlayerEvent::TextInput { codepoint: '!' },
+        ],
+    )
+}
+
+/// Opens the context menu over objects with and without a menu of their own, and chooses the
+/// first item of the menu each time.
+pub fn context_menu_owner() -> Result<(), libtest_mimic::Failed> {
+    Test::from_options(
+        TestOptions {
+            num_frames: 1,
+            ..Default::default()
+        },
+        Path::new("tests/swfs/avm2/context_menu_owner/"),
+        "context_menu_owner".to_string(),
+    )?
+    .run(
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            for (x, y) in [(25.0, 25.0), (125.0, 25.0)] {
+                player.handle_event(PlayerEvent::MouseMove { x, y });
+                let captions: Vec<_> = player
+                    .prepare_context_menu()
+                    .into_iter()
+                    .map(|item| item.caption)
+                    .collect();
+                player
+                    .log_backend()
+                    .avm_trace(&format!("menu at ({x}, {y}): {captions:?}"));
+                player.run_context_menu_callback(0);
+                player.clear_custom_menu_items();
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Touches the screen with two fingers, reusing the ID of the first finger for a later touch.
+pub fn touch_events() -> Result<(), libtest_mimic::Failed> {
+    let touch = |id, phase, x, y| PlayerEvent::Touch {
+        id,
+        phase,
+        x,
+        y,
+        pressure: None,
+    };
+    run_with_ui(
+        "tests/swfs/avm2/touch_events/",
+        "touch_events",
+        vec![
+            touch(7, TouchPhase::Start, 20.0, 20.0),
+            // Only the first finger put down moves the mouse.
+            touch(8, TouchPhase::Start, 200.0, 60.0),
+            touch(8, TouchPhase::Move, 60.0, 60.0),
+            touch(8, TouchPhase::End, 60.0, 60.0),
+            touch(7, TouchPhase::End, 30.0, 30.0),
+            touch(7, TouchPhase::Start, 40.0, 40.0),
+            touch(7, TouchPhase::Cancel, 40.0, 40.0),
+            // Touches are only mouse events when `Multitouch.inputMode` is "none".
+            touch(9, TouchPhase::Start, 50.0, 50.0),
+            touch(9, TouchPhase::End, 50.0, 50.0),
+        ],
+    )
+}
@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Context, Result};
+use ruffle_core::backend::audio::{
+    swf, AudioBackend, AudioMixer, AudioMixerProxy, DecodeError, RegisterError, SoundHandle,
+    SoundInstanceHandle, SoundTransform,
+};
+use ruffle_core::impl_audio_mixer_backend;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use std::path::Path;
+
+/// The number of sample frames in each window of a spectrum.
+const SPECTRUM_WINDOW: usize = 2048;
+
+/// An audio backend that only mixes the sounds of a test when asked to,
+/// instead of playing them on an audio device.
+pub struct TestAudioBackend {
+    mixer: AudioMixer,
+    sample_rate: u32,
+}
+
+impl TestAudioBackend {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            mixer: AudioMixer::new(2, sample_rate),
+            sample_rate,
+        }
+    }
+
+    /// Creates a tap recording the mixed output of this backend.
+    pub fn tap(&self) -> AudioTap {
+        AudioTap {
+            mixer: self.mixer.proxy(),
+            remainder: 0.0,
+            recording: Recording {
+                sample_rate: self.sample_rate,
+                samples: Vec::new(),
+            },
+        }
+    }
+}
+
+impl AudioBackend for TestAudioBackend {
+    impl_audio_mixer_backend!(mixer);
+
+    fn play(&mut self) {}
+
+    fn pause(&mut self) {}
+}
+
+/// Records the audio mixed by a `TestAudioBackend`, as time passes in the test.
+pub struct AudioTap {
+    mixer: AudioMixerProxy,
+
+    /// The fraction of a sample frame not recorded yet.
+    remainder: f64,
+
+    recording: Recording,
+}
+
+impl AudioTap {
+    /// Mixes the audio played during the next `duration` milliseconds.
+    pub fn advance(&mut self, duration: f64) {
+        let frames = self.remainder + duration * f64::from(self.recording.sample_rate) / 1000.0;
+        self.remainder = frames.fract();
+
+        let samples = &mut self.recording.samples;
+        let start = samples.len();
+        samples.resize(start + 2 * frames as usize, 0);
+        self.mixer.mix::<i16>(&mut samples[start..]);
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+/// A stereo 16-bit recording.
+pub struct Recording {
+    pub sample_rate: u32,
+
+    /// The interleaved samples of both channels.
+    pub samples: Vec<i16>,
+}
+
+impl Recording {
+    pub fn read(path: &Path) -> Result<Self> {
+        let mut reader = hound::WavReader::open(path).context("Couldn't open WAV file")?;
+        let spec = reader.spec();
+        if spec.channels != 2
+            || spec.bits_per_sample != 16
+            || spec.sample_format != hound::SampleFormat::Int
+        {
+            return Err(anyhow!("Expected a stereo 16-bit PCM WAV file"));
+        }
+        let samples = reader
+            .samples::<i16>()
+            .collect::<Result<_, _>>()
+            .context("Couldn't read WAV samples")?;
+        Ok(Self {
+            sample_rate: spec.sample_rate,
+            samples,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer =
+            hound::WavWriter::create(path, spec).context("Couldn't create WAV file")?;
+        for sample in &self.samples {
+            writer.write_sample(*sample)?;
+        }
+        writer.finalize().context("Couldn't write WAV file")?;
+        Ok(())
+    }
+
+    /// The root mean square of the difference between both recordings, from 0 to 2.
+    pub fn waveform_difference(&self, other: &Recording) -> f64 {
+        let sum: f64 = self
+            .samples
+            .iter()
+            .zip(&other.samples)
+            .map(|(a, b)| ((f64::from(*a) - f64::from(*b)) / 32768.0).powi(2))
+            .sum();
+        (sum / self.samples.len().max(1) as f64).sqrt()
+    }
+
+    /// The magnitude spectra of consecutive windows of this recording, with both channels mixed
+    /// together.
+    pub fn spectra(&self) -> Vec<Vec<f64>> {
+        let fft = FftPlanner::new().plan_fft_forward(SPECTRUM_WINDOW);
+        self.samples
+            .chunks(2 * SPECTRUM_WINDOW)
+            .map(|chunk| {
+                let mut buffer: Vec<_> = (0..SPECTRUM_WINDOW)
+                    .map(|i| {
+                        let left = chunk.get(2 * i).copied().unwrap_or_default();
+                        let right = chunk.get(2 * i + 1).copied().unwrap_or_default();
+                        // Apply a Hann window, so that the edges of the window don't leak into
+                        // every frequency.
+                        let hann = (std::f64::consts::PI * i as f64 / SPECTRUM_WINDOW as f64)
+                            .sin()
+                            .powi(2);
+                        let sample = (f64::from(left) + f64::from(right)) / 65536.0;
+                        Complex::new(sample * hann, 0.0)
+                    })
+                    .collect();
+                fft.process(&mut buffer);
+                buffer[..SPECTRUM_WINDOW / 2]
+                    .iter()
+                    .map(|value| value.norm())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The frequency in Hz with the most energy in the given spectrum.
+    pub fn dominant_frequency(&self, spectrum: &[f64]) -> f64 {
+        let bin = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(bin, _)| bin);
+        bin as f64 * f64::from(self.sample_rate) / SPECTRUM_WINDOW as f64
+    }
+}
+
+/// The difference between two magnitude spectra, relative to the loudest of them.
+pub fn spectrum_difference(a: &[f64], b: &[f64]) -> f64 {
+    let norm = |spectrum: &[f64]| spectrum.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let difference = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    let loudest = norm(a).max(norm(b));
+    if loudest > f64::EPSILON {
+        difference / loudest
+    } else {
+        0.0
+    }
+}
@@ -1,7 +1,9 @@
 // Despite being the older method of defining modules, this is required for test modules
 // https://doc.rust-lang.org/book/ch11-03-test-organization.html
 
+pub mod audio;
 pub mod environment;
+pub mod navigator;
 pub mod options;
 pub mod runner;
 pub mod test;
@@ -0,0 +1,320 @@
+use crate::util::options::{MockedResponse, MockedSocket};
+use futures::channel::mpsc;
+use futures::future::{pending, poll_fn};
+use futures::{stream, StreamExt};
+use ruffle_core::backend::navigator::{
+    AcceptedSocket, Datagram, DatagramConnection, ListeningSocket, NavigationMethod,
+    NavigatorBackend, NullExecutor, NullNavigatorBackend, NullSpawner, OwnedFuture, Request,
+    Response, SocketConnection, StreamingResponse,
+};
+use ruffle_core::indexmap::IndexMap;
+use ruffle_core::loader::Error;
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::task::{Poll, Waker};
+use url::Url;
+
+/// The port that local sockets are bound to when the movie leaves the choice to the system.
+const EPHEMERAL_PORT: u16 = 49152;
+
+/// The port that the client connecting to a listening socket connects from.
+const CLIENT_PORT: u16 = 50000;
+
+/// Keeps track of the frames run by a test, so that mocked responses can be
+/// delayed by a number of frames.
+#[derive(Default)]
+pub struct NetworkClock {
+    frame: Cell<u32>,
+    waiting: RefCell<Vec<Waker>>,
+}
+
+impl NetworkClock {
+    pub fn new() -> Rc<Self> {
+        Rc::new(Self::default())
+    }
+
+    /// Advances to the next frame, waking up the responses waiting for it.
+    pub fn next_frame(&self) {
+        self.frame.set(self.frame.get() + 1);
+        for waker in self.waiting.take() {
+            waker.wake();
+        }
+    }
+
+    /// Waits until `frames` frames after the one this is first polled in.
+    async fn wait_frames(&self, frames: u32) {
+        let mut ready_at = None;
+        poll_fn(|cx| {
+            let ready_at = *ready_at.get_or_insert(self.frame.get() + frames);
+            if self.frame.get() >= ready_at {
+                Poll::Ready(())
+            } else {
+                self.waiting.borrow_mut().push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// A navigator that serves the responses and sockets mocked by a test, and
+/// falls back to the files next to the test for every other request.
+///
+/// Datagram and listening sockets are bound on a virtual network, where every
+/// remote end echoes what it receives. A single client connects to every
+/// listening socket as soon as it's bound.
+pub struct TestNavigatorBackend {
+    inner: NullNavigatorBackend,
+    base_path: PathBuf,
+    responses: Vec<MockedResponse>,
+    sockets: Vec<MockedSocket>,
+    clock: Rc<NetworkClock>,
+
+    /// Runs the mocked servers that sockets are connected to.
+    spawner: Rc<NullSpawner>,
+}
+
+impl TestNavigatorBackend {
+    pub fn new(
+        base_path: &Path,
+        executor: &NullExecutor,
+        responses: Vec<MockedResponse>,
+        sockets: Vec<MockedSocket>,
+        clock: Rc<NetworkClock>,
+    ) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            inner: NullNavigatorBackend::with_base_path(base_path, executor)?,
+            base_path: base_path.to_path_buf(),
+            responses,
+            sockets,
+            clock,
+            spawner: Rc::new(executor.spawner()),
+        })
+    }
+
+    fn mocked_response(&self, request: &Request) -> Option<&MockedResponse> {
+        self.responses
+            .iter()
+            .find(|response| response.matches(request.url(), request.method()))
+    }
+
+    /// The body of a mocked response to a request, or the error it fails with.
+    fn mocked_body(&self, mocked: &MockedResponse, request: &Request) -> Result<Vec<u8>, String> {
+        if !mocked.echo {
+            return mocked.result(&self.base_path);
+        }
+
+        let mut lines = vec![format!("{} {}", request.method().as_str(), request.url())];
+        if let Some((_, content_type)) = request.body() {
+            lines.push(format!("Content-Type: {content_type}"));
+        }
+        lines.extend(
+            request
+                .headers()
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}")),
+        );
+        if !request.follow_redirects() {
+            lines.push("(redirects not followed)".to_string());
+        }
+        if let Some((data, _)) = request.body() {
+            lines.push(String::new());
+            lines.push(String::from_utf8_lossy(data).into_owned());
+        }
+        Ok(lines.join("\n").into_bytes())
+    }
+}
+
+impl NavigatorBackend for TestNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        self.inner.navigate_to_url(url, target, vars_method)
+    }
+
+    fn fetch(&self, request: Request) -> OwnedFuture<Response, Error> {
+        let mocked = match self.mocked_response(&request) {
+            Some(mocked) => mocked,
+            None => return self.inner.fetch(request),
+        };
+
+        let url = request.url().to_string();
+        let result = self.mocked_body(mocked, &request);
+        let http_error = mocked.http_error();
+        let latency = mocked.latency;
+        let clock = self.clock.clone();
+        Box::pin(async move {
+            clock.wait_frames(latency).await;
+
+            if let Some(status) = http_error {
+                return Err(Error::HttpNotOk(status));
+            }
+            let body = result.map_err(Error::FetchError)?;
+            Ok(Response { url, body })
+        })
+    }
+
+    fn fetch_streaming(
+        &self,
+        request: Request,
+        offset: usize,
+    ) -> OwnedFuture<StreamingResponse, Error> {
+        let mocked = match self.mocked_response(&request) {
+            Some(mocked) => mocked,
+            None => return self.inner.fetch_streaming(request, offset),
+        };
+
+        let url = request.url().to_string();
+        let result = self.mocked_body(mocked, &request);
+        let http_error = mocked.http_error();
+        let status = mocked.status.unwrap_or_default();
+        let headers = mocked.headers.clone();
+        let latency = mocked.latency;
+        let chunk_size = mocked.chunk_size;
+        let clock = self.clock.clone();
+        Box::pin(async move {
+            clock.wait_frames(latency).await;
+
+            if let Some(status) = http_error {
+                return Err(Error::HttpNotOk(status));
+            }
+            let body = result.map_err(Error::FetchError)?;
+            let total_length = Some(body.len());
+            let chunks: Vec<Vec<u8>> = match chunk_size {
+                Some(chunk_size) => body.chunks(chunk_size.max(1)).map(<[u8]>::to_vec).collect(),
+                None => vec![body],
+            };
+
+            // The first chunk arrives with the response, and every other one a frame later.
+            let body = stream::iter(chunks.into_iter().enumerate()).then(move |(i, chunk)| {
+                let clock = clock.clone();
+                async move {
+                    if i > 0 {
+                        clock.wait_frames(1).await;
+                    }
+                    Ok::<_, Error>(chunk)
+                }
+            });
+
+            Ok(StreamingResponse {
+                url,
+                status,
+                headers,
+                offset: 0,
+                total_length,
+                body: Box::pin(body),
+            })
+        })
+    }
+
+    fn connect_socket(&self, host: &str, port: u16) -> OwnedFuture<SocketConnection, Error> {
+        let mocked = match self
+            .sockets
+            .iter()
+            .find(|socket| socket.matches(host, port))
+        {
+            Some(mocked) => mocked.clone(),
+            None => return self.inner.connect_socket(host, port),
+        };
+
+        let clock = self.clock.clone();
+        let spawner = self.spawner.clone();
+        Box::pin(async move {
+            if mocked.unresponsive {
+                pending::<()>().await;
+            }
+            clock.wait_frames(mocked.latency).await;
+            if let Some(error) = mocked.error {
+                return Err(Error::ConnectError(error));
+            }
+
+            let (incoming_sender, incoming) = mpsc::unbounded();
+            let (outgoing, mut outgoing_receiver) = mpsc::unbounded::<Vec<u8>>();
+
+            // The connection stays open until either end closes it.
+            let echo = mocked.echo;
+            let server = incoming_sender.clone();
+            spawner.spawn_local(Box::pin(async move {
+                while let Some(data) = outgoing_receiver.next().await {
+                    if echo {
+                        let _ = server.unbounded_send(data);
+                    }
+                }
+                Ok(())
+            }));
+
+            spawner.spawn_local(Box::pin(async move {
+                for chunk in mocked.chunks {
+                    let _ = incoming_sender.unbounded_send(chunk);
+                    clock.wait_frames(1).await;
+                }
+                if mocked.close {
+                    incoming_sender.close_channel();
+                }
+                Ok(())
+            }));
+
+            Ok(SocketConnection { incoming, outgoing })
+        })
+    }
+
+    fn listen_socket(&self, address: &str, port: u16) -> Result<ListeningSocket, Error> {
+        let (incoming_sender, incoming) = mpsc::unbounded();
+        let (client_sender, client_incoming) = mpsc::unbounded();
+        let (client_outgoing, mut client_receiver) = mpsc::unbounded::<Vec<u8>>();
+
+        self.spawner.spawn_local(Box::pin(async move {
+            while let Some(data) = client_receiver.next().await {
+                let _ = client_sender.unbounded_send(data);
+            }
+            Ok(())
+        }));
+
+        let _ = incoming_sender.unbounded_send(AcceptedSocket {
+            address: "127.0.0.1".to_string(),
+            port: CLIENT_PORT,
+            connection: SocketConnection {
+                incoming: client_incoming,
+                outgoing: client_outgoing,
+            },
+        });
+
+        Ok(ListeningSocket {
+            local_address: address.to_string(),
+            local_port: if port == 0 { EPHEMERAL_PORT } else { port },
+            incoming,
+        })
+    }
+
+    fn bind_datagram_socket(&self, address: &str, port: u16) -> Result<DatagramConnection, Error> {
+        let (incoming_sender, incoming) = mpsc::unbounded();
+        let (outgoing, mut outgoing_receiver) = mpsc::unbounded::<Datagram>();
+
+        // Every remote end sends the datagrams it receives straight back.
+        self.spawner.spawn_local(Box::pin(async move {
+            while let Some(datagram) = outgoing_receiver.next().await {
+                let _ = incoming_sender.unbounded_send(datagram);
+            }
+            Ok(())
+        }));
+
+        Ok(DatagramConnection {
+            local_address: address.to_string(),
+            local_port: if port == 0 { EPHEMERAL_PORT } else { port },
+            incoming,
+            outgoing,
+        })
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
+        self.inner.spawn_future(future)
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        self.inner.pre_process_url(url)
+    }
+}
@@ -1,7 +1,11 @@
+use crate::util::audio::{spectrum_difference, Recording};
 use crate::util::environment::WGPU;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use approx::assert_relative_eq;
 use regex::Regex;
+use ruffle_core::backend::navigator::NavigationMethod;
+use ruffle_core::config::PlayerRuntime;
+use ruffle_core::site_lock::SiteLock;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{PlayerBuilder, ViewportDimensions};
 use serde::Deserialize;
@@ -14,11 +18,13 @@ use std::time::Duration;
 pub struct TestOptions {
     pub num_frames: u32,
     pub output_path: PathBuf,
-    pub sleep_to_meet_frame_rate: bool,
     pub image_comparison: Option<ImageComparison>,
+    pub audio_comparison: Option<AudioComparison>,
     pub ignore: bool,
     pub approximations: Option<Approximations>,
     pub player_options: PlayerOptions,
+    pub mocked_responses: Vec<MockedResponse>,
+    pub mocked_sockets: Vec<MockedSocket>,
 }
 
 impl Default for TestOptions {
@@ -26,11 +32,13 @@ impl Default for TestOptions {
         Self {
             num_frames: 1,
             output_path: PathBuf::from("output.txt"),
-            sleep_to_meet_frame_rate: false,
             image_comparison: None,
+            audio_comparison: None,
             ignore: false,
             approximations: None,
             player_options: PlayerOptions::default(),
+            mocked_responses: Vec::new(),
+            mocked_sockets: Vec::new(),
         }
     }
 }
@@ -78,12 +86,85 @@ impl Approximations {
     }
 }
 
+/// A canned response to the network requests made to a URL.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MockedResponse {
+    url: String,
+    method: Option<String>,
+    body: Option<String>,
+    file: Option<PathBuf>,
+    error: Option<String>,
+    /// The HTTP status of the response. Requests fail if it isn't a success.
+    pub status: Option<u16>,
+    pub headers: Vec<(String, String)>,
+    /// Whether the body describes the request instead, as `METHOD url`
+    /// followed by the request headers and the request body.
+    pub echo: bool,
+    pub latency: u32,
+    /// The size of the chunks that the body arrives in, a chunk per frame.
+    /// The whole body arrives at once if this isn't given.
+    pub chunk_size: Option<usize>,
+}
+
+impl MockedResponse {
+    pub fn matches(&self, url: &str, method: NavigationMethod) -> bool {
+        self.url == url
+            && self.method.as_ref().map_or(true, |expected| {
+                expected.eq_ignore_ascii_case(method.as_str())
+            })
+    }
+
+    /// The HTTP status that a request fails with, if any.
+    pub fn http_error(&self) -> Option<u16> {
+        self.status.filter(|status| !(200..300).contains(status))
+    }
+
+    /// The body of the response, or the error the request fails with.
+    pub fn result(&self, base_path: &Path) -> Result<Vec<u8>, String> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        if let Some(file) = &self.file {
+            return fs::read(base_path.join(file)).map_err(|e| e.to_string());
+        }
+        Ok(self.body.clone().unwrap_or_default().into_bytes())
+    }
+}
+
+/// A canned server for the sockets connecting to a host and port.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MockedSocket {
+    host: String,
+    port: u16,
+    pub error: Option<String>,
+    /// Whether the server never accepts connections, so that they time out.
+    pub unresponsive: bool,
+    pub latency: u32,
+    /// The data sent once connected, a chunk per frame.
+    pub chunks: Vec<Vec<u8>>,
+    /// Whether the data received is sent back.
+    pub echo: bool,
+    /// Whether the connection is closed after the last chunk.
+    pub close: bool,
+}
+
+impl MockedSocket {
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        self.host == host && self.port == port
+    }
+}
+
 #[derive(Deserialize, Default)]
 #[serde(default, deny_unknown_fields)]
 pub struct PlayerOptions {
     max_execution_duration: Option<Duration>,
     viewport_dimensions: Option<ViewportDimensions>,
     with_renderer: Option<RenderOptions>,
+    site_lock: Option<String>,
+    player_runtime: PlayerRuntime,
+    avm2_jit: bool,
 }
 
 impl PlayerOptions {
@@ -96,6 +177,18 @@ impl PlayerOptions {
             player_builder = player_builder.with_max_execution_duration(max_execution_duration);
         }
 
+        player_builder = player_builder.with_player_runtime(self.player_runtime);
+
+        if self.avm2_jit {
+            player_builder = player_builder.with_avm2_jit(true);
+        }
+
+        if let Some(original_url) = &self.site_lock {
+            let site_lock = SiteLock::new(original_url)
+                .with_context(|| format!("Invalid site lock URL {original_url}"))?;
+            player_builder = player_builder.with_site_lock(Some(site_lock));
+        }
+
         let (width, height) = if let Some(viewport_dimensions) = self.viewport_dimensions {
             player_builder = player_builder.with_viewport_dimensions(
                 viewport_dimensions.width,
@@ -129,6 +222,9 @@ impl PlayerOptions {
     }
 
     pub fn can_run(&self, check_renderer: bool) -> bool {
+        if self.avm2_jit && !cfg!(feature = "avm2_jit") {
+            return false;
+        }
         if let Some(render) = &self.with_renderer {
             // If we don't actually want to check the renderer (ie we're just listing potential tests),
             // don't spend the cost to create it
@@ -162,8 +258,6 @@ impl ImageComparison {
         test_path: &Path,
         adapter_info: ruffle_render_wgpu::wgpu::AdapterInfo,
     ) -> Result<()> {
-        use anyhow::Context;
-
         assert_eq!(expected_image.len(), actual_image.len());
 
         let difference_data: Vec<u8> = expected_image
@@ -225,6 +319,100 @@ impl ImageComparison {
     }
 }
 
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioComparison {
+    pub sample_rate: u32,
+    waveform_tolerance: Option<f64>,
+    spectrum_tolerance: Option<f64>,
+}
+
+impl Default for AudioComparison {
+    fn default() -> Self {
+        Self {
+            sample_rate: 44100,
+            waveform_tolerance: None,
+            spectrum_tolerance: None,
+        }
+    }
+}
+
+impl AudioComparison {
+    pub fn test(&self, actual: &Recording, expected: &Recording, test_path: &Path) -> Result<()> {
+        let result = self.compare(actual, expected);
+        if result.is_err() {
+            actual
+                .save(&test_path.join("actual.wav"))
+                .context("Couldn't save actual audio")?;
+        }
+        result
+    }
+
+    fn compare(&self, actual: &Recording, expected: &Recording) -> Result<()> {
+        if actual.sample_rate != expected.sample_rate {
+            return Err(anyhow!(
+                "Sample rate ({}) doesn't match the expected sample rate ({})",
+                actual.sample_rate,
+                expected.sample_rate
+            ));
+        }
+        if actual.samples.len() != expected.samples.len() {
+            return Err(anyhow!(
+                "Length of audio ({} samples) doesn't match the expected length ({} samples)",
+                actual.samples.len(),
+                expected.samples.len()
+            ));
+        }
+
+        if self.waveform_tolerance.is_none() && self.spectrum_tolerance.is_none() {
+            if let Some(position) = actual
+                .samples
+                .iter()
+                .zip(&expected.samples)
+                .position(|(actual, expected)| actual != expected)
+            {
+                return Err(anyhow!("Audio differs from sample frame {}", position / 2));
+            }
+        }
+
+        if let Some(tolerance) = self.waveform_tolerance {
+            let difference = actual.waveform_difference(expected);
+            if difference > tolerance {
+                return Err(anyhow!(
+                    "Waveform difference ({}) is bigger than allowed limit of {}",
+                    difference,
+                    tolerance
+                ));
+            }
+        }
+
+        if let Some(tolerance) = self.spectrum_tolerance {
+            let actual_spectra = actual.spectra();
+            let expected_spectra = expected.spectra();
+            let worst = actual_spectra
+                .iter()
+                .zip(&expected_spectra)
+                .map(|(actual, expected)| spectrum_difference(actual, expected))
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            if let Some((window, difference)) = worst {
+                if difference > tolerance {
+                    return Err(anyhow!(
+                        "Spectrum difference ({}) in window {} is bigger than allowed limit of {}. Dominant frequency is {} Hz, expected {} Hz",
+                        difference,
+                        window,
+                        tolerance,
+                        actual.dominant_frequency(&actual_spectra[window]),
+                        expected.dominant_frequency(&expected_spectra[window])
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RenderOptions {
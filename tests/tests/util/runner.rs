@@ -1,7 +1,10 @@
+use crate::util::audio::{Recording, TestAudioBackend};
+use crate::util::navigator::{NetworkClock, TestNavigatorBackend};
 use crate::util::test::Test;
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
 use ruffle_core::backend::log::LogBackend;
-use ruffle_core::backend::navigator::{NullExecutor, NullNavigatorBackend};
+use ruffle_core::backend::navigator::NullExecutor;
 use ruffle_core::events::MouseButton as RuffleMouseButton;
 use ruffle_core::limits::ExecutionLimit;
 use ruffle_core::tag_utils::SwfMovie;
@@ -30,6 +33,15 @@ impl LogBackend for TestLogBackend {
     }
 }
 
+/// When set, the audio played by tests replaces their `expected.wav` instead of being compared.
+const GENERATE_EXPECTED_AUDIO: &str = "RUFFLE_GENERATE_EXPECTED_AUDIO";
+
+/// When the clock of tests starts, which is the mock time of the `deterministic` feature:
+/// 2001-02-03 04:05:06 in Nepal.
+fn start_date() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2001, 2, 2, 22, 20, 6).unwrap()
+}
+
 /// Loads an SWF and runs it through the Ruffle core for a number of frames.
 /// Tests that the trace output matches the given expected output.
 pub fn run_swf(
@@ -42,19 +54,36 @@ pub fn run_swf(
     let mut executor = NullExecutor::new();
     let movie = SwfMovie::from_path(&test.swf_path, None).map_err(|e| anyhow!(e.to_string()))?;
     let frame_time = 1000.0 / movie.frame_rate().to_f64();
-    let frame_time_duration = Duration::from_millis(frame_time as u64);
     let trace_output = Rc::new(RefCell::new(String::new()));
+    let network_clock = NetworkClock::new();
 
-    let builder = PlayerBuilder::new()
+    let mut builder = PlayerBuilder::new()
         .with_log(TestLogBackend::new(trace_output.clone()))
-        .with_navigator(NullNavigatorBackend::with_base_path(base_path, &executor)?)
+        .with_navigator(TestNavigatorBackend::new(
+            base_path,
+            &executor,
+            test.options.mocked_responses.clone(),
+            test.options.mocked_sockets.clone(),
+            network_clock.clone(),
+        )?)
         .with_max_execution_duration(Duration::from_secs(300))
+        // The clock only moves a frame at a time, so that 'getTimer', dates and timers
+        // all agree however fast the test runs.
+        .with_external_clock(Some(start_date()))
         .with_viewport_dimensions(
             movie.width().to_pixels() as u32,
             movie.height().to_pixels() as u32,
             1.0,
         );
 
+    // Only tests comparing their audio mix it, so that others don't depend on audio decoding.
+    let mut audio_tap = None;
+    if let Some(audio_comparison) = &test.options.audio_comparison {
+        let audio = TestAudioBackend::new(audio_comparison.sample_rate);
+        audio_tap = Some(audio.tap());
+        builder = builder.with_audio(audio);
+    }
+
     // Test player options may override anything set above
     let player = test
         .options
@@ -65,24 +94,7 @@ pub fn run_swf(
 
     before_start(player.clone())?;
 
-    for _ in 0..test.options.num_frames {
-        // If requested, ensure that the 'expected' amount of
-        // time actually elapses between frames. This is useful for
-        // tests that call 'flash.utils.getTimer()' and use
-        // 'setInterval'/'flash.utils.Timer'
-        //
-        // Note that when Ruffle actually runs frames, we can
-        // execute frames faster than this in order to 'catch up'
-        // if we've fallen behind. However, in order to make regression
-        // tests deterministic, we always call 'update_timers' with
-        // an elapsed time of 'frame_time'. By sleeping for 'frame_time_duration',
-        // we ensure that the result of 'flash.utils.getTimer()' is consistent
-        // with timer execution (timers will see an elapsed time of *at least*
-        // the requested timer interval).
-        if test.options.sleep_to_meet_frame_rate {
-            std::thread::sleep(frame_time_duration);
-        }
-
+    for frame in 0..test.options.num_frames {
         while !player
             .lock()
             .unwrap()
@@ -90,8 +102,21 @@ pub fn run_swf(
         {}
 
         player.lock().unwrap().run_frame();
+        // Frame `n` runs at `n * frame_time`, and the timers then catch up to the next frame.
+        let timestamp = (frame + 1) as f64 * frame_time * 1000.0;
+        player
+            .lock()
+            .unwrap()
+            .advance_clock_to(Duration::from_micros(timestamp.round() as u64));
         player.lock().unwrap().update_timers(frame_time);
-        executor.run();
+        player.lock().unwrap().update_streams(frame_time);
+        if let Some(audio_tap) = &mut audio_tap {
+            audio_tap.advance(frame_time);
+        }
+        network_clock.next_frame();
+        // Delayed responses stay pending until a later frame.
+        executor.run_until_stalled();
+        player.lock().unwrap().update_sockets(frame_time);
 
         injector.next(|evt, _btns_down| {
             player.lock().unwrap().handle_event(match evt {
@@ -160,9 +185,25 @@ pub fn run_swf(
         }
     }
 
+    if let (Some(audio_comparison), Some(audio_tap)) = (&test.options.audio_comparison, &audio_tap)
+    {
+        let expected_audio_path = base_path.join("expected.wav");
+        if std::env::var_os(GENERATE_EXPECTED_AUDIO).is_some() {
+            audio_tap.recording().save(&expected_audio_path)?;
+        } else if expected_audio_path.is_file() {
+            let expected_audio = Recording::read(&expected_audio_path)?;
+            audio_comparison.test(audio_tap.recording(), &expected_audio, base_path)?;
+        } else {
+            return Err(anyhow!(
+                "Missing {}, run with {GENERATE_EXPECTED_AUDIO} set to create it",
+                expected_audio_path.display()
+            ));
+        }
+    }
+
     before_end(player)?;
 
-    executor.run();
+    executor.run_until_stalled();
 
     let trace = trace_output.borrow().clone();
     Ok(trace)
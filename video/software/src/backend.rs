@@ -1,3 +1,4 @@
+use crate::deblock;
 use crate::decoder::VideoDecoder;
 use generational_arena::Arena;
 use ruffle_render::backend::RenderBackend;
@@ -35,7 +36,7 @@ impl VideoBackend for SoftwareVideoBackend {
         _num_frames: u32,
         size: (u16, u16),
         codec: VideoCodec,
-        _filter: VideoDeblocking,
+        filter: VideoDeblocking,
     ) -> Result<VideoStreamHandle, Error> {
         let decoder: Box<dyn VideoDecoder> = match codec {
             #[cfg(feature = "h263")]
@@ -48,11 +49,25 @@ impl VideoBackend for SoftwareVideoBackend {
             VideoCodec::ScreenVideo => Box::new(crate::decoder::screen::ScreenVideoDecoder::new()),
             other => return Err(Error::UnsupportedCodec(other)),
         };
-        let stream = VideoStream::new(decoder);
+        let stream = VideoStream::new(decoder, codec, filter);
         let stream_handle = self.streams.insert(stream);
         Ok(stream_handle)
     }
 
+    fn set_video_stream_deblocking(
+        &mut self,
+        stream: VideoStreamHandle,
+        filter: VideoDeblocking,
+    ) -> Result<(), Error> {
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or(Error::VideoStreamIsNotRegistered)?;
+
+        stream.filter = filter;
+        Ok(())
+    }
+
     fn preload_video_stream_frame(
         &mut self,
         stream: VideoStreamHandle,
@@ -77,7 +92,17 @@ impl VideoBackend for SoftwareVideoBackend {
             .get_mut(stream)
             .ok_or(Error::VideoStreamIsNotRegistered)?;
 
-        let frame = stream.decoder.decode_frame(encoded_frame)?;
+        let packet_flag =
+            stream.codec == VideoCodec::H263 && deblock::h263_deblocking_flag(encoded_frame.data);
+        let mut frame = stream.decoder.decode_frame(encoded_frame)?;
+        if let Some(strength) = deblock::filter_strength(stream.codec, stream.filter, packet_flag) {
+            deblock::deblock(
+                &mut frame.rgba,
+                frame.width.into(),
+                frame.height.into(),
+                strength,
+            );
+        }
         let handle = if let Some(bitmap) = stream.bitmap.clone() {
             renderer.update_texture(
                 &bitmap,
@@ -109,12 +134,18 @@ impl VideoBackend for SoftwareVideoBackend {
 pub struct VideoStream {
     bitmap: Option<BitmapHandle>,
     decoder: Box<dyn VideoDecoder>,
+    codec: VideoCodec,
+
+    /// The deblocking filter applied to the decoded frames.
+    filter: VideoDeblocking,
 }
 
 impl VideoStream {
-    fn new(decoder: Box<dyn VideoDecoder>) -> Self {
+    fn new(decoder: Box<dyn VideoDecoder>, codec: VideoCodec, filter: VideoDeblocking) -> Self {
         Self {
             decoder,
+            codec,
+            filter,
             bitmap: None,
         }
     }
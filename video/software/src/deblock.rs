@@ -0,0 +1,253 @@
+//! Post-processing deblocking filter for block-based video codecs.
+//!
+//! Both Sorenson H.263 and VP6 encode pictures as 8x8 pixel blocks, which
+//! become visible as a grid at low bitrates. Flash Player optionally smooths
+//! the block edges of decoded frames, which is emulated here on the final
+//! RGBA frame.
+//!
+//! The filter follows the edge filter of H.263 Annex J: the two pixels on
+//! either side of a block edge are moved towards each other, but only if the
+//! step between them is small enough to likely be a compression artifact
+//! instead of an edge in the picture.
+
+use swf::{VideoCodec, VideoDeblocking};
+
+/// The size of the blocks whose edges are smoothed.
+const BLOCK_SIZE: usize = 8;
+
+/// Returns the strength of the deblocking filter that should be applied to a
+/// decoded frame of the given codec, or `None` if it should not be filtered.
+///
+/// `packet_flag` is the deblocking flag of the encoded frame itself, which is
+/// used if the video player doesn't request a specific filter.
+pub fn filter_strength(
+    codec: VideoCodec,
+    filter: VideoDeblocking,
+    packet_flag: bool,
+) -> Option<i32> {
+    match (codec, filter) {
+        (_, VideoDeblocking::None) => None,
+        (VideoCodec::H263, VideoDeblocking::UseVideoPacketValue) if packet_flag => Some(4),
+        (VideoCodec::H263, VideoDeblocking::UseVideoPacketValue) => None,
+        // The other levels are reserved for VP6, so H.263 always gets the
+        // Sorenson filter.
+        (VideoCodec::H263, _) => Some(4),
+        // VP6 has an in-loop filter already, and only gets post-processed
+        // when explicitly requested.
+        (VideoCodec::Vp6 | VideoCodec::Vp6WithAlpha, VideoDeblocking::Level1) => Some(4),
+        (VideoCodec::Vp6 | VideoCodec::Vp6WithAlpha, VideoDeblocking::Level2) => Some(6),
+        (VideoCodec::Vp6 | VideoCodec::Vp6WithAlpha, VideoDeblocking::Level3) => Some(8),
+        (VideoCodec::Vp6 | VideoCodec::Vp6WithAlpha, VideoDeblocking::Level4) => Some(10),
+        _ => None,
+    }
+}
+
+/// Reads the deblocking flag from the picture header of a Sorenson H.263
+/// frame.
+pub fn h263_deblocking_flag(data: &[u8]) -> bool {
+    let mut reader = BitReader { data, position: 0 };
+    // Picture start code, version and temporal reference.
+    reader.skip(17 + 5 + 8);
+    match reader.read(3) {
+        Some(0) => reader.skip(8 + 8),
+        Some(1) => reader.skip(16 + 16),
+        Some(_) => {}
+        None => return false,
+    }
+    // Picture type.
+    reader.skip(2);
+    reader.read(1) == Some(1)
+}
+
+/// Smooths the edges of the 8x8 blocks of a premultiplied RGBA frame.
+pub fn deblock(rgba: &mut [u8], width: usize, height: usize, strength: i32) {
+    if rgba.len() < width * height * 4 {
+        return;
+    }
+
+    // Vertical edges.
+    for y in 0..height {
+        for x in (BLOCK_SIZE..width.saturating_sub(1)).step_by(BLOCK_SIZE) {
+            let offset = (y * width + x) * 4;
+            filter_edge(rgba, offset, 4, strength);
+        }
+    }
+
+    // Horizontal edges.
+    let stride = width * 4;
+    for y in (BLOCK_SIZE..height.saturating_sub(1)).step_by(BLOCK_SIZE) {
+        for x in 0..width {
+            let offset = (y * width + x) * 4;
+            filter_edge(rgba, offset, stride, strength);
+        }
+    }
+}
+
+/// Filters the color channels of the four pixels around a block edge.
+///
+/// `offset` points to the first pixel after the edge, and `step` is the
+/// distance between the pixels across the edge.
+fn filter_edge(rgba: &mut [u8], offset: usize, step: usize, strength: i32) {
+    let (a, b, c, d) = (offset - 2 * step, offset - step, offset, offset + step);
+    for channel in 0..3 {
+        let pa = i32::from(rgba[a + channel]);
+        let pb = i32::from(rgba[b + channel]);
+        let pc = i32::from(rgba[c + channel]);
+        let pd = i32::from(rgba[d + channel]);
+
+        let delta = (pa - 4 * pb + 4 * pc - pd) / 8;
+        let delta1 = up_down_ramp(delta, strength);
+        let delta2 = ((pa - pd) / 4).clamp(-delta1.abs() / 2, delta1.abs() / 2);
+
+        rgba[a + channel] = clamp_to_alpha(pa - delta2, rgba[a + 3]);
+        rgba[b + channel] = clamp_to_alpha(pb + delta1, rgba[b + 3]);
+        rgba[c + channel] = clamp_to_alpha(pc - delta1, rgba[c + 3]);
+        rgba[d + channel] = clamp_to_alpha(pd + delta2, rgba[d + 3]);
+    }
+}
+
+/// Passes small differences through, and fades larger ones out, so that
+/// actual edges in the picture are left alone.
+fn up_down_ramp(delta: i32, strength: i32) -> i32 {
+    let magnitude = delta.abs();
+    let filtered = (magnitude - (2 * (magnitude - strength)).max(0)).max(0);
+    filtered * delta.signum()
+}
+
+/// Premultiplied colors can't exceed their alpha value.
+fn clamp_to_alpha(value: i32, alpha: u8) -> u8 {
+    value.clamp(0, alpha.into()) as u8
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn skip(&mut self, bits: usize) {
+        self.position += bits;
+    }
+
+    fn read(&mut self, bits: usize) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self.data.get(self.position / 8)?;
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | u32::from(bit);
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A frame one pixel high, with a block edge between pixels 7 and 8.
+    fn row(left: [u8; 4], right: [u8; 4]) -> Vec<u8> {
+        let mut rgba = Vec::new();
+        for x in 0..16 {
+            rgba.extend_from_slice(if x < 8 { &left } else { &right });
+        }
+        rgba
+    }
+
+    fn red(rgba: &[u8]) -> Vec<u8> {
+        rgba.chunks(4).map(|pixel| pixel[0]).collect()
+    }
+
+    /// Builds a Sorenson H.263 picture header from `(value, bits)` fields.
+    fn h263_header(fields: &[(u32, usize)]) -> Vec<u8> {
+        let mut bits = Vec::new();
+        for &(value, count) in fields {
+            for i in (0..count).rev() {
+                bits.push((value >> i) & 1 == 1);
+            }
+        }
+        bits.chunks(8)
+            .map(|byte| {
+                byte.iter()
+                    .enumerate()
+                    .fold(0, |acc, (i, &bit)| acc | (u8::from(bit) << (7 - i)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strength_follows_codec_and_setting() {
+        use VideoCodec::{Vp6, H263};
+
+        let packet = VideoDeblocking::UseVideoPacketValue;
+        assert_eq!(filter_strength(H263, VideoDeblocking::None, true), None);
+        assert_eq!(filter_strength(H263, packet, true), Some(4));
+        assert_eq!(filter_strength(H263, packet, false), None);
+        assert_eq!(
+            filter_strength(H263, VideoDeblocking::Level3, false),
+            Some(4)
+        );
+        assert_eq!(filter_strength(Vp6, packet, true), None);
+        assert_eq!(
+            filter_strength(Vp6, VideoDeblocking::Level1, false),
+            Some(4)
+        );
+        assert_eq!(
+            filter_strength(Vp6, VideoDeblocking::Level4, false),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn h263_flag_is_read_after_picture_size() {
+        let start = [(1, 17), (0, 5), (0, 8)];
+        // A predefined picture size.
+        let header = h263_header(&[&start[..], &[(2, 3), (0, 2), (1, 1)]].concat());
+        assert!(h263_deblocking_flag(&header));
+        let header = h263_header(&[&start[..], &[(2, 3), (0, 2), (0, 1)]].concat());
+        assert!(!h263_deblocking_flag(&header));
+
+        // Custom 8 bit and 16 bit picture sizes.
+        let header = h263_header(&[&start[..], &[(0, 3), (0xffff, 16), (0, 2), (1, 1)]].concat());
+        assert!(h263_deblocking_flag(&header));
+        let header = h263_header(&[&start[..], &[(1, 3), (0, 32), (0, 2), (1, 1)]].concat());
+        assert!(h263_deblocking_flag(&header));
+
+        // Truncated headers are not deblocked.
+        assert!(!h263_deblocking_flag(&header[..3]));
+    }
+
+    #[test]
+    fn small_steps_are_smoothed() {
+        let mut rgba = row([100, 100, 100, 255], [104, 104, 104, 255]);
+        deblock(&mut rgba, 16, 1, 4);
+        assert_eq!(
+            red(&rgba),
+            [100, 100, 100, 100, 100, 100, 100, 101, 103, 104, 104, 104, 104, 104, 104, 104]
+        );
+    }
+
+    #[test]
+    fn large_steps_are_kept() {
+        let original = row([0, 0, 0, 255], [200, 200, 200, 255]);
+        let mut rgba = original.clone();
+        deblock(&mut rgba, 16, 1, 4);
+        assert_eq!(rgba, original);
+    }
+
+    #[test]
+    fn colors_stay_premultiplied() {
+        let mut rgba = row([100, 100, 100, 100], [104, 104, 104, 255]);
+        deblock(&mut rgba, 16, 1, 4);
+        assert_eq!(&rgba[7 * 4..8 * 4], [100, 100, 100, 100]);
+        assert_eq!(&rgba[8 * 4..9 * 4], [103, 103, 103, 255]);
+    }
+
+    #[test]
+    fn short_buffers_are_ignored() {
+        let original = row([100, 100, 100, 255], [104, 104, 104, 255]);
+        let mut rgba = original.clone();
+        deblock(&mut rgba, 16, 2, 4);
+        assert_eq!(rgba, original);
+    }
+}
@@ -42,7 +42,10 @@ pub struct Vp6Decoder {
     decoder: VP56Decoder,
     support: NADecoderSupport,
     bitreader: VP6BR,
-    init_called: bool,
+
+    /// The size of the encoded frames in macroblocks, once the decoder is initialized.
+    encoded_size: Option<(usize, usize)>,
+
     last_frame: Option<NABufferRef<NAVideoBuffer<u8>>>,
 }
 
@@ -64,7 +67,7 @@ impl Vp6Decoder {
             decoder: VP56Decoder::new(6, with_alpha, true),
             support: NADecoderSupport::new(),
             bitreader: VP6BR::new(),
-            init_called: false,
+            encoded_size: None,
             last_frame: None,
         }
     }
@@ -89,38 +92,50 @@ impl VideoDecoder for Vp6Decoder {
     }
 
     fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
-        // If this is the first frame, the decoder needs to be initialized.
+        // The decoder needs to be initialized with the encoded size, which is
+        // only found in the header of keyframes. Streams from FLV files may also
+        // change their size at any keyframe, which needs a new decoder.
+        let flag_index = if self.with_alpha { 3 } else { 0 };
+        let is_keyframe = encoded_frame
+            .data
+            .get(flag_index)
+            .map_or(false, |flags| flags & 0b_1000_0000 == 0);
 
-        if !self.init_called {
-            let mut bool_coder = BoolCoder::new(if self.with_alpha {
-                // The 24 bits alpha offset needs to be skipped first in this case
-                &encoded_frame.data[3..]
-            } else {
-                encoded_frame.data
-            })
-            .map_err(Vp6Error::DecoderError)?;
+        if self.encoded_size.is_none() || is_keyframe {
+            // The 24 bits alpha offset needs to be skipped first in VP6A
+            let mut bool_coder =
+                BoolCoder::new(encoded_frame.data.get(flag_index..).unwrap_or(&[]))
+                    .map_err(Vp6Error::DecoderError)?;
 
             let header = self
                 .bitreader
                 .parse_header(&mut bool_coder)
                 .map_err(Vp6Error::DecoderError)?;
 
-            let video_info = NAVideoInfo::new(
-                header.disp_w as usize * 16,
-                header.disp_h as usize * 16,
-                true,
-                if self.with_alpha {
-                    VP_YUVA420_FORMAT
-                } else {
-                    YUV420_FORMAT
-                },
-            );
-
-            self.decoder
-                .init(&mut self.support, video_info)
-                .map_err(Vp6Error::DecoderError)?;
+            let encoded_size = (header.disp_w as usize, header.disp_h as usize);
+            if self.encoded_size != Some(encoded_size) {
+                if self.encoded_size.is_some() {
+                    self.decoder = VP56Decoder::new(6, self.with_alpha, true);
+                    self.last_frame = None;
+                }
+
+                let video_info = NAVideoInfo::new(
+                    encoded_size.0 * 16,
+                    encoded_size.1 * 16,
+                    true,
+                    if self.with_alpha {
+                        VP_YUVA420_FORMAT
+                    } else {
+                        YUV420_FORMAT
+                    },
+                );
+
+                self.decoder
+                    .init(&mut self.support, video_info)
+                    .map_err(Vp6Error::DecoderError)?;
 
-            self.init_called = true;
+                self.encoded_size = Some(encoded_size);
+            }
         }
 
         let frame = if encoded_frame.data.is_empty()
@@ -199,8 +214,13 @@ impl VideoDecoder for Vp6Decoder {
 
         // Cropping the encoded frame (containing whole macroblocks) to the
         // size requested by the the bounds attribute.
+        // Streams of unknown size (from some FLV files) are shown uncropped.
 
-        let &bounds = &self.bounds;
+        let bounds = if self.bounds == (0, 0) {
+            (width as u16, height as u16)
+        } else {
+            self.bounds
+        };
 
         if width < bounds.0 as usize || height < bounds.1 as usize {
             log::warn!("A VP6 video frame is smaller than the bounds of the stream it belongs in. This is not supported.");
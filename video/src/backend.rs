@@ -26,6 +26,16 @@ pub trait VideoBackend {
         filter: VideoDeblocking,
     ) -> Result<VideoStreamHandle, Error>;
 
+    /// Change the deblocking filter used to post-process decoded frames of a
+    /// video stream.
+    ///
+    /// Only frames decoded after this call are affected.
+    fn set_video_stream_deblocking(
+        &mut self,
+        stream: VideoStreamHandle,
+        filter: VideoDeblocking,
+    ) -> Result<(), Error>;
+
     /// Preload a frame of a given video stream.
     ///
     /// No decoding is intended to happen at this point in time. Instead, the
@@ -6,6 +6,9 @@ pub struct EncodedFrame<'a> {
     pub codec: VideoCodec,
 
     /// The raw bitstream data to funnel into the codec.
+    ///
+    /// H.264 frames are in Annex B format (start code delimited), with the
+    /// stream's SPS and PPS prepended to every keyframe.
     pub data: &'a [u8],
 
     /// A caller-specified frame ID. Frame IDs must be consistent between
@@ -44,6 +44,14 @@ impl VideoBackend for NullVideoBackend {
         Ok(self.streams.insert(()))
     }
 
+    fn set_video_stream_deblocking(
+        &mut self,
+        _stream: VideoStreamHandle,
+        _filter: VideoDeblocking,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn preload_video_stream_frame(
         &mut self,
         _stream: VideoStreamHandle,
@@ -7,4 +7,5 @@ export const buildInfo = {
     versionChannel: "%VERSION_CHANNEL%",
     buildDate: "%BUILD_DATE%",
     commitHash: "%COMMIT_HASH%",
+    webgpu: "%WEBGPU%",
 };
@@ -5,6 +5,7 @@ import {
     WindowMode,
     Letterbox,
     LogLevel,
+    ResamplerQuality,
 } from "./load-options";
 
 /**
@@ -46,6 +47,9 @@ export const DEFAULT_CONFIG: Required<Config> = {
     preloader: true,
     maxExecutionDuration: { secs: 15, nanos: 0 },
     base: null,
+    spoofUrl: null,
+    siteLocks: {},
+    socketProxy: null,
     menu: true,
     salign: "",
     quality: "high",
@@ -54,4 +58,8 @@ export const DEFAULT_CONFIG: Required<Config> = {
     publicPath: null,
     polyfills: true,
     playerVersion: null,
+    resamplerQuality: ResamplerQuality.Medium,
+    navigation: {},
+    fontMap: {},
+    fontFallbacks: {},
 };
@@ -0,0 +1,47 @@
+export interface DialogFileFilter {
+    readonly description: string;
+    /**
+     * The extensions of the files matched by the filter, without the leading dot.
+     * A filter without extensions matches any file.
+     */
+    readonly extensions: string[];
+}
+
+export type Dialog =
+    | {
+          readonly type: "alert" | "confirm";
+          readonly title: string;
+          readonly message: string;
+      }
+    | {
+          readonly type: "prompt";
+          readonly title: string;
+          readonly message: string;
+          readonly defaultText: string;
+      }
+    | {
+          readonly type: "openFiles";
+          readonly filters: DialogFileFilter[];
+          readonly multiple: boolean;
+      }
+    | {
+          readonly type: "saveFile";
+          readonly defaultName: string;
+          readonly data: Uint8Array;
+      };
+
+export interface PickedFile {
+    readonly name: string;
+    readonly data: Uint8Array;
+    /**
+     * The time the file was last modified, in milliseconds since the Unix epoch.
+     */
+    readonly lastModified: number;
+}
+
+export type DialogResult =
+    | { readonly type: "cancelled" }
+    | { readonly type: "ok" }
+    | { readonly type: "text"; readonly text: string }
+    | { readonly type: "files"; readonly files: PickedFile[] }
+    | { readonly type: "saved"; readonly name: string };
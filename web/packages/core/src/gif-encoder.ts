@@ -0,0 +1,169 @@
+/**
+ * The number of levels of red, green and blue in the palette of encoded GIFs.
+ */
+const PALETTE_LEVELS = [6, 7, 6];
+
+/**
+ * Encodes frames of RGBA pixels into an animated GIF.
+ *
+ * Frames are mapped to a fixed palette of 252 colors, which is fast enough to
+ * encode a few seconds of a movie as it plays.
+ */
+export class GifEncoder {
+    private readonly width: number;
+    private readonly height: number;
+    private readonly delay: number;
+    private readonly bytes: number[] = [];
+
+    /**
+     * Starts a GIF.
+     *
+     * @param width The width of the frames, in pixels.
+     * @param height The height of the frames, in pixels.
+     * @param delay The time each frame is shown for, in hundredths of a second.
+     */
+    constructor(width: number, height: number, delay: number) {
+        this.width = width;
+        this.height = height;
+        this.delay = delay;
+
+        this.writeString("GIF89a");
+        this.writeShort(width);
+        this.writeShort(height);
+        // A global color table of 256 entries.
+        this.bytes.push(0xf7, 0, 0);
+        const [levelsR, levelsG, levelsB] = PALETTE_LEVELS;
+        for (let i = 0; i < 256; i++) {
+            if (i < levelsR * levelsG * levelsB) {
+                const r = Math.floor(i / (levelsG * levelsB));
+                const g = Math.floor(i / levelsB) % levelsG;
+                const b = i % levelsB;
+                this.bytes.push(
+                    Math.round((r * 255) / (levelsR - 1)),
+                    Math.round((g * 255) / (levelsG - 1)),
+                    Math.round((b * 255) / (levelsB - 1))
+                );
+            } else {
+                this.bytes.push(0, 0, 0);
+            }
+        }
+        // Loop forever.
+        this.bytes.push(0x21, 0xff, 11);
+        this.writeString("NETSCAPE2.0");
+        this.bytes.push(3, 1, 0, 0, 0);
+    }
+
+    /**
+     * Adds a frame to the GIF.
+     *
+     * @param rgba The pixels of the frame, as returned by `ImageData.data`.
+     */
+    addFrame(rgba: Uint8ClampedArray): void {
+        // Graphic control extension with the frame delay.
+        this.bytes.push(0x21, 0xf9, 4, 0);
+        this.writeShort(this.delay);
+        this.bytes.push(0, 0);
+        // Image descriptor covering the whole GIF, using the global color table.
+        this.bytes.push(0x2c);
+        this.writeShort(0);
+        this.writeShort(0);
+        this.writeShort(this.width);
+        this.writeShort(this.height);
+        this.bytes.push(0);
+
+        const [levelsR, levelsG, levelsB] = PALETTE_LEVELS;
+        const indices = new Uint8Array(this.width * this.height);
+        for (let i = 0; i < indices.length; i++) {
+            const r = Math.round((rgba[i * 4] * (levelsR - 1)) / 255);
+            const g = Math.round((rgba[i * 4 + 1] * (levelsG - 1)) / 255);
+            const b = Math.round((rgba[i * 4 + 2] * (levelsB - 1)) / 255);
+            indices[i] = (r * levelsG + g) * levelsB + b;
+        }
+        this.writeImageData(indices);
+    }
+
+    /**
+     * Ends the GIF.
+     *
+     * @returns The encoded GIF file.
+     */
+    finish(): Uint8Array {
+        this.bytes.push(0x3b);
+        return new Uint8Array(this.bytes);
+    }
+
+    /**
+     * Writes LZW compressed color indices, split into sub-blocks.
+     *
+     * @param indices The palette index of each pixel.
+     */
+    private writeImageData(indices: Uint8Array): void {
+        const minCodeSize = 8;
+        const clearCode = 1 << minCodeSize;
+        const endCode = clearCode + 1;
+
+        const data: number[] = [];
+        let buffer = 0;
+        let bufferBits = 0;
+        let codeSize = minCodeSize + 1;
+        const writeCode = (code: number) => {
+            buffer |= code << bufferBits;
+            bufferBits += codeSize;
+            while (bufferBits >= 8) {
+                data.push(buffer & 0xff);
+                buffer >>= 8;
+                bufferBits -= 8;
+            }
+        };
+
+        let table = new Map<number, number>();
+        let nextCode = endCode + 1;
+        writeCode(clearCode);
+        let prefix = indices[0] ?? 0;
+        for (let i = 1; i < indices.length; i++) {
+            const index = indices[i];
+            const key = (prefix << 8) | index;
+            const code = table.get(key);
+            if (code !== undefined) {
+                prefix = code;
+                continue;
+            }
+            writeCode(prefix);
+            if (nextCode < 4096) {
+                if (nextCode === 1 << codeSize) {
+                    codeSize++;
+                }
+                table.set(key, nextCode++);
+            } else {
+                // The table is full, so start over.
+                writeCode(clearCode);
+                table = new Map();
+                nextCode = endCode + 1;
+                codeSize = minCodeSize + 1;
+            }
+            prefix = index;
+        }
+        writeCode(prefix);
+        writeCode(endCode);
+        if (bufferBits > 0) {
+            data.push(buffer & 0xff);
+        }
+
+        this.bytes.push(minCodeSize);
+        for (let i = 0; i < data.length; i += 255) {
+            const block = data.slice(i, i + 255);
+            this.bytes.push(block.length, ...block);
+        }
+        this.bytes.push(0);
+    }
+
+    private writeShort(value: number): void {
+        this.bytes.push(value & 0xff, (value >> 8) & 0xff);
+    }
+
+    private writeString(value: string): void {
+        for (let i = 0; i < value.length; i++) {
+            this.bytes.push(value.charCodeAt(i));
+        }
+    }
+}
@@ -52,6 +52,114 @@ export const enum Letterbox {
     On = "on",
 }
 
+/**
+ * Controls how sounds are resampled when their sample rate differs from the
+ * sample rate of the audio output.
+ */
+export const enum ResamplerQuality {
+    /**
+     * Linear interpolation. This is the cheapest, but muffles sounds.
+     */
+    Low = "low",
+
+    /**
+     * A windowed sinc filter with 16 taps.
+     */
+    Medium = "medium",
+
+    /**
+     * A windowed sinc filter with 64 taps.
+     */
+    High = "high",
+}
+
+/**
+ * What happens when a movie asks to open a URL with `navigateToURL` or
+ * `getURL`.
+ */
+export const enum NavigationAction {
+    /**
+     * Open the URL.
+     */
+    Allow = "allow",
+
+    /**
+     * Ignore the request.
+     */
+    Deny = "deny",
+
+    /**
+     * Ask the user whether to open the URL.
+     */
+    Confirm = "confirm",
+
+    /**
+     * Don't open the URL, and call the `onNavigateToURL` callback of the
+     * player instead.
+     */
+    Forward = "forward",
+}
+
+/**
+ * Controls how the requests of movies to open URLs are handled.
+ */
+export interface NavigationOptions {
+    /**
+     * What happens to the URLs whose scheme has no action of its own.
+     *
+     * @default NavigationAction.Allow
+     */
+    action?: NavigationAction;
+
+    /**
+     * The actions for the URLs with particular schemes, such as
+     * `javascript` or `mailto`, by lowercase scheme.
+     *
+     * @default {}
+     */
+    schemes?: Record<string, NavigationAction>;
+
+    /**
+     * Window names replaced with others, such as `_blank` with `_self` to
+     * open all URLs in the same window.
+     *
+     * @default {}
+     */
+    targets?: Record<string, string>;
+}
+
+/**
+ * The scripts, or writing systems, whose characters device text falls back
+ * to fonts of their own for.
+ */
+export enum FontScript {
+    /**
+     * Hiragana and katakana.
+     */
+    Japanese = "japanese",
+
+    /**
+     * Hangul.
+     */
+    Korean = "korean",
+
+    /**
+     * Chinese characters, which are also used in Japanese and Korean, and
+     * CJK punctuation.
+     */
+    Han = "han",
+
+    /**
+     * Emoji and other pictographic symbols.
+     */
+    Emoji = "emoji",
+
+    /**
+     * All other characters.
+     */
+    Other = "other",
+}
+
 /**
  * When the player is muted, this controls whether or not Ruffle will show a
  * "click to unmute" overlay on top of the movie.
@@ -245,6 +353,54 @@ export interface BaseLoadOptions {
      */
     base?: string | null;
 
+    /**
+     * The URL reported to the movie as its own location, instead of the URL
+     * it was actually loaded from.
+     *
+     * Some movies refuse to play unless `_url` or `loaderInfo.url` points to
+     * their original website (site-locking). This allows playing them from
+     * an archive or mirror.
+     *
+     * This only changes what the movie sees: requests are still made from,
+     * and relative URLs are still resolved against, the real page location
+     * (or `base`). Movie storage such as shared objects will be keyed by the
+     * spoofed domain, but stays within the browser storage of the real page.
+     * null means the actual URL is reported.
+     *
+     * @default null
+     */
+    spoofUrl?: string | null;
+
+    /**
+     * Pretends that certain movies run on their original website, for movies
+     * that refuse to play anywhere else.
+     *
+     * Keys are the URLs movies are played from, with or without their query,
+     * and values the URLs of the movies on their original websites. For a
+     * matching movie, Ruffle reports the original URL to the movie, reports a
+     * "remote" security sandbox, and answers `ExternalInterface` queries of
+     * the page location with the original website.
+     *
+     * Like `spoofUrl`, this never changes where requests are sent from.
+     *
+     * @default {}
+     */
+    siteLocks?: Record<string, string>;
+
+    /**
+     * The WebSocket URL of a proxy for the TCP connections of movies, such
+     * as RTMP connections to media servers, `Socket`s and `XMLSocket`s.
+     *
+     * Browsers can't open TCP connections, so each connection opens a
+     * WebSocket to this URL instead, with the `host` and `port` of the server
+     * as query parameters. The proxy is expected to connect to the server and
+     * forward the binary messages in both directions.
+     * null means TCP connections always fail.
+     *
+     * @default null
+     */
+    socketProxy?: string | null;
+
     /**
      * If set to true, the built-in context menu items are visible
      *
@@ -293,6 +449,42 @@ export interface BaseLoadOptions {
      * @default null
      */
     playerVersion?: number | null;
+
+    /**
+     * How sounds are resampled when their sample rate differs from the
+     * sample rate of the audio output.
+     *
+     * @default ResamplerQuality.Medium
+     */
+    resamplerQuality?: ResamplerQuality;
+
+    /**
+     * How the requests of the movie to open URLs are handled.
+     *
+     * `javascript:` URLs additionally need `allowScriptAccess`.
+     *
+     * @default {}
+     */
+    navigation?: NavigationOptions;
+
+    /**
+     * The fonts installed on the system that device text in other fonts is
+     * drawn with, by the name of the font the movie asks for, such as
+     * `{ "_sans": "Noto Sans", "MS Gothic": "Noto Sans CJK JP" }`.
+     *
+     * @default {}
+     */
+    fontMap?: Record<string, string>;
+
+    /**
+     * The fonts installed on the system that device text falls back to, in
+     * order, for the characters its font has no glyphs for, by script:
+     * `"japanese"`, `"korean"`, `"han"`, `"emoji"` or `"other"`. Scripts
+     * left out fall back to a list of common fonts.
+     *
+     * @default {}
+     */
+    fontFallbacks?: Partial<Record<FontScript, string[]>>;
 }
 
 /**
@@ -11,6 +11,7 @@ import {
 } from "wasm-feature-detect";
 import { setPolyfillsOnLoad } from "./js-polyfills";
 import { publicPath } from "./public-path";
+import { buildInfo } from "./build-info";
 import type { Config } from "./config";
 
 declare global {
@@ -19,6 +20,48 @@ declare global {
 
 type ProgressCallback = (bytesLoaded: number, bytesTotal: number) => void;
 
+let webgpuSupport: Promise<boolean> | null = null;
+
+/**
+ * Checks whether the browser can render through WebGPU.
+ *
+ * `navigator.gpu` may exist without any usable adapter (e.g. on blocklisted
+ * drivers), so an adapter is requested to be sure. This is only done once,
+ * and never when this build doesn't contain the WebGPU module.
+ *
+ * @returns Whether a WebGPU adapter is available.
+ */
+function webgpuSupported(): Promise<boolean> {
+    if (webgpuSupport === null) {
+        webgpuSupport = requestWebgpuAdapter();
+    }
+    return webgpuSupport;
+}
+
+/**
+ * Requests a WebGPU adapter, if this build can use one.
+ *
+ * @returns Whether an adapter was found.
+ */
+async function requestWebgpuAdapter(): Promise<boolean> {
+    if (buildInfo.webgpu !== "true") {
+        return false;
+    }
+    const gpu = (
+        navigator as {
+            gpu?: { requestAdapter(): Promise<unknown | null> };
+        }
+    ).gpu;
+    if (!gpu) {
+        return false;
+    }
+    try {
+        return (await gpu.requestAdapter()) !== null;
+    } catch {
+        return false;
+    }
+}
+
 /**
  * Load ruffle from an automatically-detected location.
  *
@@ -56,15 +99,22 @@ async function fetchRuffle(
         );
     }
 
+    // The WebGPU module is built with the extensions as well.
+    const webgpuModule = extensionsSupported && (await webgpuSupported());
+
     __webpack_public_path__ = publicPath(config);
 
     // Note: The argument passed to import() has to be a simple string literal,
     // otherwise some bundler will get confused and won't include the module?
-    const { default: init, Ruffle } = await (extensionsSupported
+    const { default: init, Ruffle } = await (webgpuModule
+        ? import("../pkg/ruffle_web-webgpu")
+        : extensionsSupported
         ? import("../pkg/ruffle_web-wasm_extensions")
         : import("../pkg/ruffle_web"));
     let response;
-    const wasmUrl = extensionsSupported
+    const wasmUrl = webgpuModule
+        ? new URL("../pkg/ruffle_web-webgpu_bg.wasm", import.meta.url)
+        : extensionsSupported
         ? new URL("../pkg/ruffle_web-wasm_extensions_bg.wasm", import.meta.url)
         : new URL("../pkg/ruffle_web_bg.wasm", import.meta.url);
     const wasmResponse = await fetch(wasmUrl);
@@ -104,7 +154,8 @@ async function fetchRuffle(
 
 type Ruffle =
     | typeof import("../pkg/ruffle_web")["Ruffle"]
-    | typeof import("../pkg/ruffle_web-wasm_extensions")["Ruffle"];
+    | typeof import("../pkg/ruffle_web-wasm_extensions")["Ruffle"]
+    | typeof import("../pkg/ruffle_web-webgpu")["Ruffle"];
 
 let lastLoaded: Promise<Ruffle> | null = null;
 
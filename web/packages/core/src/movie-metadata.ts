@@ -43,3 +43,58 @@ export interface MovieMetadata {
      */
     readonly uncompressedLength: number;
 }
+
+/**
+ * A frame label on the root timeline of a movie.
+ */
+export interface TimelineLabel {
+    /**
+     * The name of the label.
+     */
+    readonly name: string;
+
+    /**
+     * The labeled frame. The first frame is frame 1.
+     */
+    readonly frame: number;
+}
+
+/**
+ * A scene of a movie.
+ */
+export interface TimelineScene {
+    /**
+     * The name of the scene.
+     */
+    readonly name: string;
+
+    /**
+     * The first frame of the scene. The first frame of the movie is frame 1.
+     */
+    readonly start: number;
+
+    /**
+     * The number of frames in the scene.
+     */
+    readonly length: number;
+}
+
+/**
+ * The frame labels and scenes of the root timeline of a movie.
+ */
+export interface MovieTimeline {
+    /**
+     * The number of frames on the root timeline of the movie.
+     */
+    readonly totalFrames: number;
+
+    /**
+     * The frame labels, sorted by frame number.
+     */
+    readonly labels: TimelineLabel[];
+
+    /**
+     * The scenes, in playback order.
+     */
+    readonly scenes: TimelineScene[];
+}
@@ -11,9 +11,11 @@ import {
     UnmuteOverlay,
     WindowMode,
 } from "./load-options";
-import type { MovieMetadata } from "./movie-metadata";
+import type { MovieMetadata, MovieTimeline } from "./movie-metadata";
 import type { InternalContextMenuItem } from "./context-menu";
-import { swfFileName } from "./swf-file-name";
+import type { Dialog, DialogFileFilter, DialogResult } from "./dialog";
+import { captureFileName, swfFileName } from "./swf-file-name";
+import { GifEncoder } from "./gif-encoder";
 import { buildInfo } from "./build-info";
 
 export const FLASH_MIMETYPE = "application/x-shockwave-flash";
@@ -151,6 +153,13 @@ export class RufflePlayer extends HTMLElement {
 
     private isExtension = false;
 
+    private recorder: MediaRecorder | null = null;
+    private recordingTimeout: ReturnType<typeof setTimeout> | null = null;
+    private gifRecording: {
+        encoder: GifEncoder;
+        interval: ReturnType<typeof setInterval>;
+    } | null = null;
+
     /**
      * Triggered when a movie metadata has been loaded (such as movie width and height).
      *
@@ -165,16 +174,57 @@ export class RufflePlayer extends HTMLElement {
      */
     static LOADED_DATA = "loadeddata";
 
+    /**
+     * Triggered when the movie changes the quality of the stage. The new
+     * quality, such as `"high"`, is the `detail` of the event.
+     *
+     * @event RufflePlayer#qualitychange
+     */
+    static QUALITY_CHANGE = "qualitychange";
+
+    /**
+     * The longest a recording may run for before it's stopped.
+     */
+    static MAX_RECORDING_SECONDS = 30;
+
+    /**
+     * The longest a GIF recording may run for before it's stopped.
+     */
+    static MAX_GIF_SECONDS = 10;
+
+    /**
+     * The number of frames per second captured into GIF recordings.
+     */
+    static GIF_FRAME_RATE = 10;
+
+    /**
+     * The largest width of GIF recordings. Larger movies are scaled down.
+     */
+    static GIF_MAX_WIDTH = 480;
+
     /**
      * A movie can communicate with the hosting page using fscommand
      * as long as script access is allowed.
      *
+     * Unlike the standalone player, the page is also given the projector
+     * commands, such as `fullscreen` and `quit`, to handle as it sees fit.
+     *
      * @param command A string passed to the host application for any use.
      * @param args A string passed to the host application for any use.
      * @returns True if the command was handled.
      */
     onFSCommand: ((command: string, args: string) => boolean) | null;
 
+    /**
+     * Called when the movie asks to open a URL, and the `navigation` config
+     * forwards the request instead of opening the URL.
+     *
+     * @param url The URL the movie asked to open.
+     * @param target The name of the window to open it in.
+     * @returns True if the request was handled.
+     */
+    onNavigateToURL: ((url: string, target: string) => boolean) | null;
+
     /**
      * Any configuration that should apply to this specific player.
      * This will be defaulted with any global configuration.
@@ -235,6 +285,7 @@ export class RufflePlayer extends HTMLElement {
 
         this.instance = null;
         this.onFSCommand = null;
+        this.onNavigateToURL = null;
 
         this._readyState = ReadyState.HaveNothing;
         this._metadata = null;
@@ -529,6 +580,7 @@ export class RufflePlayer extends HTMLElement {
      * Destroys the currently running instance of Ruffle.
      */
     private destroy(): void {
+        this.stopRecording();
         if (this.instance) {
             this.instance.destroy();
             this.instance = null;
@@ -672,6 +724,35 @@ export class RufflePlayer extends HTMLElement {
         return false;
     }
 
+    /**
+     * The frame labels and scenes of the root timeline of the loaded movie.
+     *
+     * This can be used to build chapter navigation together with [[gotoFrame]].
+     *
+     * @returns The timeline, or null if no movie is loaded yet.
+     */
+    get timeline(): MovieTimeline | null {
+        if (this.instance) {
+            return this.instance.timeline() ?? null;
+        }
+        return null;
+    }
+
+    /**
+     * Jumps to a frame of the root timeline, keeping the movie playing or
+     * paused.
+     *
+     * @param labelOrNumber A frame number (the first frame is 1), a frame
+     * label or a scene name.
+     * @returns True if the frame was found.
+     */
+    gotoFrame(labelOrNumber: string | number): boolean {
+        if (this.instance) {
+            return this.instance.goto_frame(labelOrNumber);
+        }
+        return false;
+    }
+
     /**
      * Returns the master volume of the player.
      *
@@ -793,15 +874,7 @@ export class RufflePlayer extends HTMLElement {
                     return;
                 }
                 const blob = await response.blob();
-                const blobUrl = URL.createObjectURL(blob);
-                const swfDownloadA = document.createElement("a");
-                swfDownloadA.style.display = "none";
-                swfDownloadA.href = blobUrl;
-                swfDownloadA.download = swfFileName(this.swfUrl);
-                document.body.appendChild(swfDownloadA);
-                swfDownloadA.click();
-                document.body.removeChild(swfDownloadA);
-                URL.revokeObjectURL(blobUrl);
+                RufflePlayer.downloadBlob(blob, swfFileName(this.swfUrl));
             } else {
                 console.error("SWF download failed");
             }
@@ -810,6 +883,234 @@ export class RufflePlayer extends HTMLElement {
         }
     }
 
+    /**
+     * Downloads a blob as a file, by clicking a hidden link to it.
+     *
+     * @param blob The contents of the file.
+     * @param fileName The name to save the file as.
+     */
+    private static downloadBlob(blob: Blob, fileName: string): void {
+        const blobUrl = URL.createObjectURL(blob);
+        const downloadA = document.createElement("a");
+        downloadA.style.display = "none";
+        downloadA.href = blobUrl;
+        downloadA.download = fileName;
+        document.body.appendChild(downloadA);
+        downloadA.click();
+        document.body.removeChild(downloadA);
+        URL.revokeObjectURL(blobUrl);
+    }
+
+    /**
+     * The canvas the movie is rendered to, if an instance is running.
+     */
+    private get canvas(): HTMLCanvasElement | null {
+        if (!this.instance) {
+            return null;
+        }
+        return this.container.querySelector("canvas");
+    }
+
+    /**
+     * Saves the current frame of the movie as a PNG image.
+     */
+    saveScreenshot(): void {
+        const canvas = this.canvas;
+        if (!canvas) {
+            return;
+        }
+        // WebGL canvases are cleared after being composited, so the frame has to be
+        // redrawn in this task, before it's read back.
+        this.instance?.render();
+        canvas.toBlob((blob) => {
+            if (blob) {
+                RufflePlayer.downloadBlob(
+                    blob,
+                    captureFileName(this.swfUrl, "png")
+                );
+            } else {
+                console.error("Screenshot failed");
+            }
+        }, "image/png");
+    }
+
+    /**
+     * Downloads a JSON report of the unimplemented APIs the movie called, the media that
+     * couldn't be decoded and the compatibility quirks that were turned on.
+     */
+    downloadCompatibilityReport(): void {
+        const report = this.instance?.compatibility_report();
+        if (typeof report !== "string") {
+            console.error("Compatibility report failed");
+            return;
+        }
+        RufflePlayer.downloadBlob(
+            new Blob([report], { type: "application/json" }),
+            captureFileName(this.swfUrl, "json")
+        );
+    }
+
+    /**
+     * Whether the movie is currently being recorded.
+     *
+     * @returns True if a recording is in progress.
+     */
+    get isRecording(): boolean {
+        return this.recorder !== null || this.gifRecording !== null;
+    }
+
+    /**
+     * Starts recording the movie to a WebM video, which is downloaded once the
+     * recording is stopped.
+     *
+     * Recordings stop by themselves after `RufflePlayer.MAX_RECORDING_SECONDS`.
+     */
+    startRecording(): void {
+        const canvas = this.canvas;
+        if (!canvas || this.isRecording || !RufflePlayer.canRecord()) {
+            return;
+        }
+
+        const stream = canvas.captureStream();
+        const mimeType = [
+            "video/webm;codecs=vp9",
+            "video/webm;codecs=vp8",
+            "video/webm",
+        ].find((type) => MediaRecorder.isTypeSupported(type));
+        let recorder: MediaRecorder;
+        try {
+            recorder = new MediaRecorder(stream, { mimeType });
+        } catch (err) {
+            console.error("Recording failed to start", err);
+            return;
+        }
+
+        const chunks: Blob[] = [];
+        recorder.addEventListener("dataavailable", (event) => {
+            if (event.data.size > 0) {
+                chunks.push(event.data);
+            }
+        });
+        recorder.addEventListener("stop", () => {
+            stream.getTracks().forEach((track) => track.stop());
+            if (chunks.length > 0) {
+                const blob = new Blob(chunks, { type: "video/webm" });
+                RufflePlayer.downloadBlob(
+                    blob,
+                    captureFileName(this.swfUrl, "webm")
+                );
+            }
+        });
+
+        recorder.start();
+        this.recorder = recorder;
+        this.recordingTimeout = setTimeout(
+            () => this.stopRecording(),
+            RufflePlayer.MAX_RECORDING_SECONDS * 1000
+        );
+    }
+
+    /**
+     * Starts recording the movie to an animated GIF, which is downloaded once
+     * the recording is stopped.
+     *
+     * Frames are captured `RufflePlayer.GIF_FRAME_RATE` times per second, and
+     * recordings stop by themselves after `RufflePlayer.MAX_GIF_SECONDS`.
+     */
+    startGifRecording(): void {
+        const canvas = this.canvas;
+        if (!canvas || this.isRecording || !canvas.width || !canvas.height) {
+            return;
+        }
+
+        const scale = Math.min(1, RufflePlayer.GIF_MAX_WIDTH / canvas.width);
+        const frameCanvas = document.createElement("canvas");
+        frameCanvas.width = Math.max(1, Math.round(canvas.width * scale));
+        frameCanvas.height = Math.max(1, Math.round(canvas.height * scale));
+        const context = frameCanvas.getContext("2d", {
+            willReadFrequently: true,
+        });
+        if (!context) {
+            console.error("Recording failed to start");
+            return;
+        }
+
+        const encoder = new GifEncoder(
+            frameCanvas.width,
+            frameCanvas.height,
+            Math.round(100 / RufflePlayer.GIF_FRAME_RATE)
+        );
+        const maxFrames =
+            RufflePlayer.MAX_GIF_SECONDS * RufflePlayer.GIF_FRAME_RATE;
+        let frames = 0;
+        const captureFrame = () => {
+            // As with screenshots, WebGL canvases have to be redrawn before
+            // they're read back.
+            this.instance?.render();
+            context.fillStyle = "black";
+            context.fillRect(0, 0, frameCanvas.width, frameCanvas.height);
+            context.drawImage(
+                canvas,
+                0,
+                0,
+                frameCanvas.width,
+                frameCanvas.height
+            );
+            const image = context.getImageData(
+                0,
+                0,
+                frameCanvas.width,
+                frameCanvas.height
+            );
+            encoder.addFrame(image.data);
+            frames++;
+            if (frames >= maxFrames) {
+                this.stopRecording();
+            }
+        };
+
+        this.gifRecording = {
+            encoder,
+            interval: setInterval(
+                captureFrame,
+                1000 / RufflePlayer.GIF_FRAME_RATE
+            ),
+        };
+        captureFrame();
+    }
+
+    /**
+     * Stops the current recording, if any, and downloads it.
+     */
+    stopRecording(): void {
+        if (this.recordingTimeout !== null) {
+            clearTimeout(this.recordingTimeout);
+            this.recordingTimeout = null;
+        }
+        if (this.recorder) {
+            if (this.recorder.state !== "inactive") {
+                this.recorder.stop();
+            }
+            this.recorder = null;
+        }
+        if (this.gifRecording) {
+            clearInterval(this.gifRecording.interval);
+            const gif = this.gifRecording.encoder.finish();
+            this.gifRecording = null;
+            RufflePlayer.downloadBlob(
+                new Blob([gif], { type: "image/gif" }),
+                captureFileName(this.swfUrl, "gif")
+            );
+        }
+    }
+
+    private static canRecord(): boolean {
+        return (
+            typeof MediaRecorder !== "undefined" &&
+            typeof HTMLCanvasElement.prototype.captureStream === "function"
+        );
+    }
+
     private contextMenuItems(): Array<ContextMenuItem | null> {
         const CHECKMARK = String.fromCharCode(0x2713);
         const items = [];
@@ -857,6 +1158,35 @@ export class RufflePlayer extends HTMLElement {
             });
         }
 
+        if (this.instance) {
+            items.push(null);
+            items.push({
+                text: "Save screenshot",
+                onClick: this.saveScreenshot.bind(this),
+            });
+            items.push({
+                text: "Download compatibility report",
+                onClick: this.downloadCompatibilityReport.bind(this),
+            });
+            if (this.isRecording) {
+                items.push({
+                    text: "Stop recording",
+                    onClick: this.stopRecording.bind(this),
+                });
+            } else {
+                if (RufflePlayer.canRecord()) {
+                    items.push({
+                        text: "Record video (WebM)",
+                        onClick: this.startRecording.bind(this),
+                    });
+                }
+                items.push({
+                    text: "Record GIF",
+                    onClick: this.startGifRecording.bind(this),
+                });
+            }
+        }
+
         if (window.isSecureContext) {
             items.push({
                 text: "Copy debug info",
@@ -1535,6 +1865,146 @@ export class RufflePlayer extends HTMLElement {
         };
     }
 
+    /**
+     * Shows a dialog on behalf of the movie, such as a permission prompt or a file picker.
+     *
+     * The user's response is reported back to the player once it is known.
+     *
+     * @param id The ID of the dialog, used to report the response.
+     * @param dialog The dialog to show.
+     */
+    displayDialog(id: number, dialog: Dialog): void {
+        const respond = (result: DialogResult) =>
+            this.instance?.resolve_dialog(id, result);
+        switch (dialog.type) {
+            case "openFiles":
+                RufflePlayer.pickFiles(dialog.filters, dialog.multiple).then(
+                    respond
+                );
+                break;
+            case "saveFile": {
+                const name = dialog.defaultName || "download";
+                RufflePlayer.downloadBlob(new Blob([dialog.data]), name);
+                respond({ type: "saved", name });
+                break;
+            }
+            default:
+                this.displayMessageDialog(dialog, respond);
+                break;
+        }
+    }
+
+    /**
+     * Shows an alert, a confirmation or a text prompt in front of the player.
+     *
+     * @param dialog The dialog to show.
+     * @param respond Called with the user's response once the dialog is closed.
+     */
+    private displayMessageDialog(
+        dialog: Extract<Dialog, { message: string }>,
+        respond: (result: DialogResult) => void
+    ): void {
+        const div = document.createElement("div");
+        div.id = "dialog_overlay";
+        const message = document.createElement("div");
+        message.className = "message";
+        div.appendChild(message);
+
+        const title = document.createElement("p");
+        title.appendChild(document.createElement("strong")).textContent =
+            dialog.title;
+        message.appendChild(title);
+        for (const paragraph of dialog.message.split("\n\n")) {
+            message.appendChild(document.createElement("p")).textContent =
+                paragraph;
+        }
+
+        let input: HTMLInputElement | null = null;
+        if (dialog.type === "prompt") {
+            input = document.createElement("input");
+            input.type = "text";
+            input.value = dialog.defaultText;
+            message.appendChild(input);
+        }
+
+        const buttons = document.createElement("div");
+        message.appendChild(buttons);
+        const addButton = (label: string, result: () => DialogResult) => {
+            const button = document.createElement("button");
+            button.textContent = label;
+            button.onclick = () => {
+                div.parentNode!.removeChild(div);
+                respond(result());
+            };
+            buttons.appendChild(button);
+        };
+        switch (dialog.type) {
+            case "alert":
+                addButton("OK", () => ({ type: "ok" }));
+                break;
+            case "confirm":
+                addButton("No", () => ({ type: "cancelled" }));
+                addButton("Yes", () => ({ type: "ok" }));
+                break;
+            case "prompt":
+                addButton("Cancel", () => ({ type: "cancelled" }));
+                addButton("OK", () => ({ type: "text", text: input!.value }));
+                break;
+        }
+
+        this.container.prepend(div);
+        input?.focus();
+    }
+
+    /**
+     * Lets the user pick files to open.
+     *
+     * @param filters The kinds of files that may be picked.
+     * @param multiple Whether more than one file may be picked.
+     * @returns The picked files, or a cancellation if none were picked.
+     */
+    private static pickFiles(
+        filters: DialogFileFilter[],
+        multiple: boolean
+    ): Promise<DialogResult> {
+        const accept: string[] = [];
+        for (const filter of filters) {
+            if (filter.extensions.length === 0) {
+                // This filter matches any file.
+                accept.length = 0;
+                break;
+            }
+            for (const extension of filter.extensions) {
+                accept.push(`.${extension}`);
+            }
+        }
+
+        return new Promise((resolve) => {
+            const input = document.createElement("input");
+            input.type = "file";
+            input.multiple = multiple;
+            input.accept = accept.join(",");
+            input.addEventListener("change", async () => {
+                const files = await Promise.all(
+                    Array.from(input.files ?? []).map(async (file) => ({
+                        name: file.name,
+                        data: new Uint8Array(await file.arrayBuffer()),
+                        lastModified: file.lastModified,
+                    }))
+                );
+                resolve(
+                    files.length > 0
+                        ? { type: "files", files }
+                        : { type: "cancelled" }
+                );
+            });
+            input.addEventListener("cancel", () =>
+                resolve({ type: "cancelled" })
+            );
+            input.click();
+        });
+    }
+
     protected debugPlayerInfo(): string {
         let result = `Allows script access: ${this.loadedConfig.allowScriptAccess}\n`;
         let renderInfo = `(Cached) ${this._cachedDebugInfo}`;
@@ -1571,6 +2041,12 @@ export class RufflePlayer extends HTMLElement {
         this.dispatchEvent(new Event(RufflePlayer.LOADED_DATA));
     }
 
+    private onQualityChanged(quality: string) {
+        this.dispatchEvent(
+            new CustomEvent(RufflePlayer.QUALITY_CHANGE, { detail: quality })
+        );
+    }
+
     setIsExtension(isExtension: boolean): void {
         this.isExtension = isExtension;
     }
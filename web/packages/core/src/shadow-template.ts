@@ -42,7 +42,8 @@ ruffleShadowTemplate.innerHTML = `
         #unmute_overlay .background,
         #panic,
         #preloader,
-        #message_overlay {
+        #message_overlay,
+        #dialog_overlay {
             position: absolute;
             top: 0;
             bottom: 0;
@@ -127,7 +128,8 @@ ruffleShadowTemplate.innerHTML = `
             justify-content: space-evenly;
         }
 
-        #message_overlay {
+        #message_overlay,
+        #dialog_overlay {
             position: absolute;
             background: var(--ruffle-blue);
             color: var(--ruffle-orange);
@@ -139,7 +141,8 @@ ruffleShadowTemplate.innerHTML = `
             overflow: auto;
         }
 
-        #message_overlay .message {
+        #message_overlay .message,
+        #dialog_overlay .message {
             text-align: center;
             max-height: 100%;
             max-width: 100%;
@@ -147,18 +150,21 @@ ruffleShadowTemplate.innerHTML = `
             font-size: 20px;
         }
 
-        #message_overlay p {
+        #message_overlay p,
+        #dialog_overlay p {
             margin: 0.5em 0;
         }
 
-        #message_overlay .message div {
+        #message_overlay .message div,
+        #dialog_overlay .message div {
             display: flex;
             justify-content: center;
             flex-wrap: wrap;
             column-gap: 1em;
         }
 
-        #message_overlay a, #message_overlay button {
+        #message_overlay a, #message_overlay button,
+        #dialog_overlay button {
             cursor: pointer;
             background: var(--ruffle-blue);
             color: var(--ruffle-orange);
@@ -171,7 +177,8 @@ ruffleShadowTemplate.innerHTML = `
             margin: 2% 0;
         }
 
-        #message_overlay a:hover, #message_overlay button:hover {
+        #message_overlay a:hover, #message_overlay button:hover,
+        #dialog_overlay button:hover {
             background: #ffffff4c;
         }
 
@@ -9,3 +9,24 @@ export function swfFileName(swfUrl: URL): string {
     const name = pathName.substring(pathName.lastIndexOf("/") + 1);
     return name;
 }
+
+/**
+ * Create a filename to save a capture of a movie into, such as a screenshot.
+ *
+ * @param swfUrl The URL of the SWF file, if known.
+ * @param extension The extension of the capture file, without the dot.
+ * @returns The filename the capture can be saved at.
+ */
+export function captureFileName(
+    swfUrl: URL | undefined,
+    extension: string
+): string {
+    let name = swfUrl ? swfFileName(swfUrl) : "";
+    name = name.replace(/\.swf$/i, "") || "ruffle";
+    const date = new Date();
+    const pad = (n: number) => n.toString().padStart(2, "0");
+    const day = [date.getFullYear(), date.getMonth() + 1, date.getDate()];
+    const time = [date.getHours(), date.getMinutes(), date.getSeconds()];
+    const timestamp = `${day.map(pad).join("-")}_${time.map(pad).join("-")}`;
+    return `${name}_${timestamp}.${extension}`;
+}
@@ -0,0 +1,153 @@
+import { strict as assert } from "assert";
+import { GifEncoder } from "../src/gif-encoder";
+
+describe("GifEncoder", function () {
+    it("should write the header, palette and trailer", function () {
+        const gif = new GifEncoder(3, 2, 10).finish();
+        assert.deepEqual(ascii(gif.subarray(0, 6)), "GIF89a");
+        assert.deepEqual(Array.from(gif.subarray(6, 10)), [3, 0, 2, 0]);
+        // The first and last colors of the palette are black and white.
+        assert.deepEqual(Array.from(gif.subarray(13, 16)), [0, 0, 0]);
+        const white = 13 + 251 * 3;
+        assert.deepEqual(
+            Array.from(gif.subarray(white, white + 3)),
+            [255, 255, 255]
+        );
+        assert.deepEqual(gif[gif.length - 1], 0x3b);
+    });
+
+    it("should encode frames that decode to the nearest palette colors", function () {
+        const width = 120;
+        const height = 90;
+        const encoder = new GifEncoder(width, height, 5);
+        const frames = [0, 1, 2].map((n) => pattern(width, height, n));
+        frames.forEach((frame) => encoder.addFrame(frame));
+        const decoded = decodeFrames(encoder.finish(), width * height);
+
+        assert.deepEqual(decoded.length, frames.length);
+        decoded.forEach((frame, n) => {
+            assert.deepEqual(frame.delay, 5);
+            assert.deepEqual(
+                Array.from(frame.indices),
+                Array.from(expectedIndices(frames[n]))
+            );
+        });
+    });
+});
+
+function ascii(bytes: Uint8Array): string {
+    return String.fromCharCode(...bytes);
+}
+
+/**
+ * Makes RGBA pixels with long runs and noise, so that the LZW table fills up
+ * and has to be cleared.
+ */
+function pattern(width: number, height: number, seed: number) {
+    const pixels = new Uint8ClampedArray(width * height * 4);
+    let random = seed + 1;
+    for (let i = 0; i < width * height; i++) {
+        random = (random * 1103515245 + 12345) & 0x7fffffff;
+        const value = i % 7 < 3 ? (random >> 16) & 0xff : (i * 9) & 0xff;
+        pixels.set([value, 255 - value, (value * 3) & 0xff, 255], i * 4);
+    }
+    return pixels;
+}
+
+function expectedIndices(rgba: Uint8ClampedArray) {
+    const indices = new Uint8Array(rgba.length / 4);
+    for (let i = 0; i < indices.length; i++) {
+        const r = Math.round((rgba[i * 4] * 5) / 255);
+        const g = Math.round((rgba[i * 4 + 1] * 6) / 255);
+        const b = Math.round((rgba[i * 4 + 2] * 5) / 255);
+        indices[i] = (r * 7 + g) * 6 + b;
+    }
+    return indices;
+}
+
+/**
+ * A minimal GIF decoder for the files written by `GifEncoder`.
+ */
+function decodeFrames(gif: Uint8Array, pixels: number) {
+    const frames: { delay: number; indices: Uint8Array }[] = [];
+    // Skip the header, logical screen descriptor and global color table.
+    let pos = 13 + 256 * 3;
+    let delay = 0;
+    const readBlocks = () => {
+        const data: number[] = [];
+        while (gif[pos] !== 0) {
+            const length = gif[pos];
+            data.push(...gif.subarray(pos + 1, pos + 1 + length));
+            pos += length + 1;
+        }
+        pos++;
+        return data;
+    };
+    while (gif[pos] !== 0x3b) {
+        if (gif[pos] === 0x21) {
+            const label = gif[pos + 1];
+            pos += 2;
+            const data = readBlocks();
+            if (label === 0xf9) {
+                delay = data[1] | (data[2] << 8);
+            }
+        } else if (gif[pos] === 0x2c) {
+            pos += 10;
+            const minCodeSize = gif[pos++];
+            frames.push({
+                delay,
+                indices: decodeLzw(readBlocks(), minCodeSize, pixels),
+            });
+        } else {
+            assert.fail(`Unexpected block ${gif[pos]}`);
+        }
+    }
+    return frames;
+}
+
+function decodeLzw(data: number[], minCodeSize: number, pixels: number) {
+    const clearCode = 1 << minCodeSize;
+    const endCode = clearCode + 1;
+    const output: number[] = [];
+    const table: number[][] = [];
+    let codeSize = minCodeSize + 1;
+    let previous: number[] | null = null;
+    let bit = 0;
+    for (;;) {
+        let code = 0;
+        for (let i = 0; i < codeSize; i++, bit++) {
+            code |= ((data[bit >> 3] >> (bit & 7)) & 1) << i;
+        }
+        if (code === clearCode) {
+            table.length = 0;
+            for (let i = 0; i < clearCode; i++) {
+                table.push([i]);
+            }
+            table.push([], []);
+            codeSize = minCodeSize + 1;
+            previous = null;
+            continue;
+        }
+        if (code === endCode) {
+            break;
+        }
+        let entry: number[];
+        if (code < table.length) {
+            entry = table[code];
+            if (previous) {
+                table.push([...previous, entry[0]]);
+            }
+        } else {
+            assert.ok(previous, "Unknown first code");
+            entry = [...previous, previous[0]];
+            table.push(entry);
+        }
+        output.push(...entry);
+        previous = entry;
+        if (table.length === 1 << codeSize && codeSize < 12) {
+            codeSize++;
+        }
+    }
+    assert.deepEqual(output.length, pixels);
+    return new Uint8Array(output);
+}
@@ -5,6 +5,8 @@ const fs = require("fs");
 let version_number = process.env.npm_package_version;
 let version_channel = process.env.CFG_RELEASE_CHANNEL || "nightly";
 let build_date = new Date().toISOString();
+// Whether the WebGPU module is a real build, rather than a copy of the one with extensions.
+const webgpu = process.env.ENABLE_WEBGPU === "true" ? "true" : "false";
 
 let commitHash = "unknown";
 
@@ -53,8 +55,16 @@ const options = {
         /%VERSION_CHANNEL%/g,
         /%BUILD_DATE%/g,
         /%COMMIT_HASH%/g,
+        /%WEBGPU%/g,
+    ],
+    to: [
+        version_number,
+        version_name,
+        version_channel,
+        build_date,
+        commitHash,
+        webgpu,
     ],
-    to: [version_number, version_name, version_channel, build_date, commitHash],
 };
 
 replace.sync(options);
@@ -1,13 +1,16 @@
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, AudioMixer, AudioMixerProxy, DecodeError, RegisterError, SoundHandle,
-    SoundInstanceHandle, SoundTransform,
+    swf, AudioBackend, AudioMixer, AudioMixerProxy, DecodeError, MicrophoneBuffer, MicrophoneError,
+    RegisterError, SoundHandle, SoundInstanceHandle, SoundTransform,
 };
 use ruffle_core::impl_audio_mixer_backend;
 use ruffle_web_common::JsResult;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use wasm_bindgen::{closure::Closure, prelude::*, JsCast};
-use web_sys::AudioContext;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AudioContext, AudioProcessingEvent, MediaStream, MediaStreamConstraints};
 
 #[allow(dead_code)]
 pub struct WebAudioBackend {
@@ -16,6 +19,7 @@ pub struct WebAudioBackend {
     buffers: Vec<Arc<RwLock<Buffer>>>,
     time: Arc<RwLock<f64>>,
     position_resolution: Duration,
+    microphone: Option<Microphone>,
 }
 
 impl WebAudioBackend {
@@ -32,6 +36,7 @@ impl WebAudioBackend {
             position_resolution: Duration::from_secs_f64(
                 f64::from(Self::BUFFER_SIZE) / f64::from(sample_rate),
             ),
+            microphone: None,
         };
 
         // Create and start the audio buffers.
@@ -65,6 +70,39 @@ impl AudioBackend for WebAudioBackend {
     fn position_resolution(&self) -> Option<Duration> {
         Some(self.position_resolution)
     }
+
+    fn microphone_names(&self) -> Vec<String> {
+        // Device names are only revealed once the user allowed access,
+        // so only the default microphone is offered.
+        let has_media_devices = web_sys::window()
+            .and_then(|window| window.navigator().media_devices().ok())
+            .is_some();
+        if has_media_devices {
+            vec!["Default".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn start_microphone(&mut self, index: usize) -> Result<(), MicrophoneError> {
+        self.stop_microphone();
+        if index != 0 {
+            return Err(MicrophoneError::NotFound(index));
+        }
+        self.microphone = Some(Microphone::start(&self.context)?);
+        Ok(())
+    }
+
+    fn stop_microphone(&mut self) {
+        self.microphone = None;
+    }
+
+    fn take_microphone_samples(&mut self) -> Vec<f32> {
+        self.microphone
+            .as_ref()
+            .map(|microphone| microphone.buffer.take())
+            .unwrap_or_default()
+    }
 }
 
 impl Drop for WebAudioBackend {
@@ -73,6 +111,123 @@ impl Drop for WebAudioBackend {
     }
 }
 
+/// Audio captured from the user's microphone through `getUserMedia`.
+struct Microphone {
+    buffer: MicrophoneBuffer,
+
+    /// The capture graph, once the user allowed access to the microphone.
+    nodes: Rc<RefCell<Option<MicrophoneNodes>>>,
+
+    /// Whether the capture was stopped, possibly before the user answered.
+    stopped: Rc<Cell<bool>>,
+}
+
+struct MicrophoneNodes {
+    stream: MediaStream,
+    source: web_sys::MediaStreamAudioSourceNode,
+    processor: web_sys::ScriptProcessorNode,
+    _on_audio_process: Closure<dyn FnMut(AudioProcessingEvent)>,
+}
+
+impl Microphone {
+    fn start(context: &AudioContext) -> Result<Self, MicrophoneError> {
+        let media_devices = web_sys::window()
+            .and_then(|window| window.navigator().media_devices().ok())
+            .ok_or(MicrophoneError::Unsupported)?;
+        let user_media = media_devices
+            .get_user_media_with_constraints(MediaStreamConstraints::new().audio(&true.into()))
+            .map_err(|e| MicrophoneError::Backend(format!("{e:?}")))?;
+
+        let microphone = Self {
+            buffer: MicrophoneBuffer::new(1, context.sample_rate() as u32),
+            nodes: Rc::new(RefCell::new(None)),
+            stopped: Rc::new(Cell::new(false)),
+        };
+
+        let context = context.clone();
+        let buffer = microphone.buffer.clone();
+        let nodes = microphone.nodes.clone();
+        let stopped = microphone.stopped.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let stream: MediaStream = match JsFuture::from(user_media).await {
+                Ok(stream) => stream.unchecked_into(),
+                Err(e) => {
+                    tracing::warn!("Couldn't access the microphone: {:?}", e);
+                    return;
+                }
+            };
+            let microphone_nodes = MicrophoneNodes::new(&context, stream, buffer);
+            if stopped.get() {
+                if let Ok(microphone_nodes) = microphone_nodes {
+                    microphone_nodes.stop();
+                }
+                return;
+            }
+            match microphone_nodes {
+                Ok(microphone_nodes) => *nodes.borrow_mut() = Some(microphone_nodes),
+                Err(e) => tracing::error!("Couldn't capture from the microphone: {:?}", e),
+            }
+        });
+
+        Ok(microphone)
+    }
+}
+
+impl Drop for Microphone {
+    fn drop(&mut self) {
+        self.stopped.set(true);
+        if let Some(nodes) = self.nodes.borrow_mut().take() {
+            nodes.stop();
+        }
+    }
+}
+
+impl MicrophoneNodes {
+    fn new(
+        context: &AudioContext,
+        stream: MediaStream,
+        buffer: MicrophoneBuffer,
+    ) -> Result<Self, JsValue> {
+        let source = context.create_media_stream_source(&stream)?;
+        let processor = context
+            .create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(
+                WebAudioBackend::BUFFER_SIZE,
+                1,
+                1,
+            )?;
+        let on_audio_process: Closure<dyn FnMut(AudioProcessingEvent)> =
+            Closure::new(move |event: AudioProcessingEvent| {
+                if let Ok(data) = event
+                    .input_buffer()
+                    .and_then(|input| input.get_channel_data(0))
+                {
+                    buffer.push(&data);
+                }
+            });
+        processor.set_onaudioprocess(Some(on_audio_process.as_ref().unchecked_ref()));
+
+        // The processor only runs while it's connected to the output; it outputs silence.
+        source.connect_with_audio_node(&processor)?;
+        processor.connect_with_audio_node(&context.destination())?;
+
+        Ok(Self {
+            stream,
+            source,
+            processor,
+            _on_audio_process: on_audio_process,
+        })
+    }
+
+    fn stop(&self) {
+        self.processor.set_onaudioprocess(None);
+        let _ = self.source.disconnect();
+        let _ = self.processor.disconnect();
+        for track in self.stream.get_tracks().iter() {
+            track.unchecked_into::<web_sys::MediaStreamTrack>().stop();
+        }
+    }
+}
+
 struct Buffer {
     context: AudioContext,
     mixer_proxy: AudioMixerProxy,
@@ -0,0 +1,103 @@
+//! Dialogs shown on behalf of the movie, as modals in front of the player.
+
+use super::JavascriptPlayer;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use ruffle_core::backend::dialog::{Dialog, DialogBackend, DialogId, DialogResult, PickedFile};
+use wasm_bindgen::JsValue;
+
+pub struct WebDialogBackend {
+    js_player: JavascriptPlayer,
+}
+
+impl WebDialogBackend {
+    pub fn new(js_player: JavascriptPlayer) -> Self {
+        Self { js_player }
+    }
+}
+
+impl DialogBackend for WebDialogBackend {
+    fn show_dialog(&mut self, id: DialogId, dialog: Dialog) {
+        self.js_player
+            .display_dialog(id.0, dialog_to_js(dialog).into());
+    }
+}
+
+fn set(object: &Object, key: &str, value: impl Into<JsValue>) {
+    let _ = Reflect::set(object, &key.into(), &value.into());
+}
+
+/// Converts a dialog to the `Dialog` type of the JavaScript player.
+fn dialog_to_js(dialog: Dialog) -> Object {
+    let object = Object::new();
+    let kind = match &dialog {
+        Dialog::Alert { .. } => "alert",
+        Dialog::Confirm { .. } => "confirm",
+        Dialog::Prompt { .. } => "prompt",
+        Dialog::OpenFiles { .. } => "openFiles",
+        Dialog::SaveFile { .. } => "saveFile",
+    };
+    set(&object, "type", kind);
+    match dialog {
+        Dialog::Alert { title, message } | Dialog::Confirm { title, message } => {
+            set(&object, "title", title);
+            set(&object, "message", message);
+        }
+        Dialog::Prompt {
+            title,
+            message,
+            default_text,
+        } => {
+            set(&object, "title", title);
+            set(&object, "message", message);
+            set(&object, "defaultText", default_text);
+        }
+        Dialog::OpenFiles { filters, multiple } => {
+            let js_filters = Array::new();
+            for filter in &filters {
+                let js_filter = Object::new();
+                set(&js_filter, "description", filter.description.as_str());
+                let extensions: Array = filter
+                    .extensions
+                    .iter()
+                    .map(|extension| JsValue::from_str(extension))
+                    .collect();
+                set(&js_filter, "extensions", extensions);
+                js_filters.push(&js_filter);
+            }
+            set(&object, "filters", js_filters);
+            set(&object, "multiple", multiple);
+        }
+        Dialog::SaveFile { default_name, data } => {
+            set(&object, "defaultName", default_name);
+            set(&object, "data", Uint8Array::from(data.as_slice()));
+        }
+    }
+    object
+}
+
+fn get(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &key.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Reads a `DialogResult` of the JavaScript player.
+pub fn dialog_result_from_js(result: &JsValue) -> DialogResult {
+    match get(result, "type").as_string().as_deref() {
+        Some("ok") => DialogResult::Ok,
+        Some("text") => DialogResult::Text(get(result, "text").as_string().unwrap_or_default()),
+        Some("files") => {
+            let files = Array::from(&get(result, "files"))
+                .iter()
+                .map(|file| PickedFile {
+                    name: get(&file, "name").as_string().unwrap_or_default(),
+                    data: Uint8Array::new(&get(&file, "data")).to_vec(),
+                    modified: get(&file, "lastModified").as_f64(),
+                })
+                .collect();
+            DialogResult::Files(files)
+        }
+        Some("saved") => DialogResult::Saved {
+            name: get(result, "name").as_string().unwrap_or_default(),
+        },
+        _ => DialogResult::Cancelled,
+    }
+}
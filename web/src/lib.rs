@@ -2,21 +2,31 @@
 
 //! Ruffle web frontend.
 mod audio;
+mod dialogs;
 mod log_adapter;
 mod navigator;
 mod storage;
 mod ui;
 
+use futures::channel::mpsc::Receiver;
 use generational_arena::{Arena, Index};
 use js_sys::{Array, Function, Object, Promise, Uint8Array};
-use ruffle_core::config::Letterbox;
+use ruffle_core::backend::dialog::DialogId;
+use ruffle_core::backend::local_connection::{LocalConnectionHub, MemoryLocalConnectionBackend};
+use ruffle_core::config::{Letterbox, ResamplerQuality};
 use ruffle_core::context::UpdateContext;
-use ruffle_core::events::{KeyCode, MouseButton, MouseWheelDelta};
+use ruffle_core::events::{KeyCode, MouseButton, MouseWheelDelta, TouchPhase};
 use ruffle_core::external::{
     ExternalInterfaceMethod, ExternalInterfaceProvider, Value as ExternalValue, Value,
 };
+use ruffle_core::font::{FontFallbacks, FontMap};
+use ruffle_core::navigation::NavigationPolicy;
+use ruffle_core::site_lock::SiteLock;
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::{Color, Player, PlayerBuilder, PlayerEvent, StaticCallstack, ViewportDimensions};
+use ruffle_core::{
+    Color, EmbedderEvent, Player, PlayerBuilder, PlayerEvent, PlayerType, StaticCallstack,
+    ViewportDimensions,
+};
 use ruffle_video_software::backend::SoftwareVideoBackend;
 use ruffle_web_common::JsResult;
 use serde::{Deserialize, Serialize};
@@ -32,8 +42,9 @@ use tracing_wasm::{WASMLayer, WASMLayerConfigBuilder};
 use url::Url;
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
 use web_sys::{
-    AddEventListenerOptions, Element, Event, EventTarget, HtmlCanvasElement, HtmlElement,
-    KeyboardEvent, PointerEvent, WheelEvent, Window,
+    AddEventListenerOptions, ClipboardEvent, CompositionEvent, Element, Event, EventTarget,
+    HtmlCanvasElement, HtmlElement, HtmlInputElement, InputEvent, KeyboardEvent, PointerEvent,
+    WheelEvent, Window,
 };
 
 static RUFFLE_GLOBAL_PANIC: Once = Once::new();
@@ -45,6 +56,9 @@ thread_local! {
     static INSTANCES: RefCell<Arena<RefCell<RuffleInstance>>> = RefCell::new(Arena::new());
 
     static CURRENT_CONTEXT: RefCell<Option<*mut UpdateContext<'static, 'static>>> = RefCell::new(None);
+
+    /// The `LocalConnection`s of all instances on the page, so that they can talk to each other.
+    static LOCAL_CONNECTIONS: LocalConnectionHub = LocalConnectionHub::default();
 }
 
 type AnimationHandler = Closure<dyn FnMut(f64)>;
@@ -67,13 +81,29 @@ struct RuffleInstance {
     player_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     window_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_up_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
+    touch_cancel_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+    composition_update_callback: Option<Closure<dyn FnMut(CompositionEvent)>>,
+    composition_end_callback: Option<Closure<dyn FnMut(CompositionEvent)>>,
+    input_callback: Option<Closure<dyn FnMut(InputEvent)>>,
+    copy_callback: Option<Closure<dyn FnMut(ClipboardEvent)>>,
+    cut_callback: Option<Closure<dyn FnMut(ClipboardEvent)>>,
+    paste_callback: Option<Closure<dyn FnMut(ClipboardEvent)>>,
+    viewport_resize_callback: Option<Closure<dyn FnMut(Event)>>,
     unload_callback: Option<Closure<dyn FnMut(Event)>>,
+    ime_input: HtmlInputElement,
     has_focus: bool,
     trace_observer: Arc<RefCell<JsValue>>,
     log_subscriber: Arc<Layered<WASMLayer, Registry>>,
+
+    /// The original URLs of site-locked movies, keyed by the URLs they are
+    /// played from.
+    site_locks: BTreeMap<String, String>,
+
+    /// The events of the player that the page is told about.
+    events: Option<Receiver<EmbedderEvent>>,
 }
 
 #[wasm_bindgen]
@@ -97,6 +127,16 @@ extern "C" {
     #[wasm_bindgen(method, catch, js_name = "onFSCommand")]
     fn on_fs_command(this: &JavascriptPlayer, command: &str, args: &str) -> Result<bool, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name = "onNavigateToURL")]
+    fn on_navigate_to_url(
+        this: &JavascriptPlayer,
+        url: &str,
+        target: &str,
+    ) -> Result<bool, JsValue>;
+
+    #[wasm_bindgen(method, getter)]
+    fn id(this: &JavascriptPlayer) -> String;
+
     #[wasm_bindgen(method)]
     fn panic(this: &JavascriptPlayer, error: &JsError);
 
@@ -109,6 +149,9 @@ extern "C" {
     #[wasm_bindgen(method, js_name = "displayMessage")]
     fn display_message(this: &JavascriptPlayer, message: &str);
 
+    #[wasm_bindgen(method, js_name = "displayDialog")]
+    fn display_dialog(this: &JavascriptPlayer, id: u32, dialog: JsValue);
+
     #[wasm_bindgen(method, getter, js_name = "isFullscreen")]
     fn is_fullscreen(this: &JavascriptPlayer) -> bool;
 
@@ -117,6 +160,9 @@ extern "C" {
 
     #[wasm_bindgen(method, js_name = "setMetadata")]
     fn set_metadata(this: &JavascriptPlayer, metadata: JsValue);
+
+    #[wasm_bindgen(method, js_name = "onQualityChanged")]
+    fn on_quality_changed(this: &JavascriptPlayer, quality: &str);
 }
 
 struct JavascriptInterface {
@@ -148,6 +194,15 @@ struct Config {
     #[serde(rename = "base")]
     base_url: Option<String>,
 
+    #[serde(rename = "spoofUrl")]
+    spoof_url: Option<String>,
+
+    #[serde(rename = "siteLocks", default)]
+    site_locks: BTreeMap<String, String>,
+
+    #[serde(rename = "socketProxy", default)]
+    socket_proxy: Option<String>,
+
     #[serde(rename = "menu")]
     show_menu: bool,
 
@@ -170,6 +225,18 @@ struct Config {
 
     #[serde(rename = "playerVersion")]
     player_version: Option<u8>,
+
+    #[serde(rename = "resamplerQuality")]
+    resampler_quality: ResamplerQuality,
+
+    #[serde(default)]
+    navigation: NavigationPolicy,
+
+    #[serde(rename = "fontMap", default)]
+    font_map: FontMap,
+
+    #[serde(rename = "fontFallbacks", default)]
+    font_fallbacks: FontFallbacks,
 }
 
 /// Metadata about the playing SWF file to be passed back to JavaScript.
@@ -225,7 +292,12 @@ impl Ruffle {
     ///
     /// This method should only be called once per player.
     pub fn stream_from(&mut self, movie_url: String, parameters: JsValue) -> Result<(), JsValue> {
+        let site_lock = self.site_lock_for(&movie_url);
         let _ = self.with_core_mut(|core| {
+            if let Some(site_lock) = site_lock {
+                core.set_site_lock(site_lock);
+            }
+
             let parameters_to_load = parse_movie_parameters(&parameters);
 
             let ruffle = *self;
@@ -257,7 +329,18 @@ impl Ruffle {
             segments.push(&swf_name);
         }
 
-        let mut movie = SwfMovie::from_data(&swf_data.to_vec(), Some(url.to_string()), None)
+        if let Some(site_lock) = self.site_lock_for(url.as_str()) {
+            let _ = self.with_core_mut(|core| core.set_site_lock(site_lock));
+        }
+
+        // The spoofed root movie URL takes precedence over the actual URL.
+        let url = self
+            .with_core(|core| core.spoofed_url().map(str::to_string))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| url.to_string());
+
+        let mut movie = SwfMovie::from_data(&swf_data.to_vec(), Some(url), None)
             .map_err(|e| format!("Error loading movie: {e}"))?;
         movie.append_parameters(parse_movie_parameters(&parameters));
 
@@ -294,11 +377,47 @@ impl Ruffle {
         let _ = self.with_core_mut(|core| core.set_volume(value));
     }
 
+    /// Returns the frame labels and scenes of the main timeline, or `undefined` if no movie
+    /// is loaded yet.
+    pub fn timeline(&mut self) -> JsValue {
+        self.with_core_mut(|core| {
+            core.timeline()
+                .and_then(|timeline| serde_wasm_bindgen::to_value(&timeline).ok())
+                .unwrap_or(JsValue::UNDEFINED)
+        })
+        .unwrap_or(JsValue::UNDEFINED)
+    }
+
+    /// Jumps to a frame number (starting at 1), frame label or scene name of the main
+    /// timeline. Returns `false` if there is no such frame.
+    pub fn goto_frame(&mut self, frame: JsValue) -> bool {
+        self.with_core_mut(|core| {
+            if let Some(number) = frame.as_f64() {
+                if number < 1.0 || number > f64::from(u16::MAX) {
+                    return false;
+                }
+                core.goto_frame(number as u16);
+                true
+            } else if let Some(label) = frame.as_string() {
+                core.goto_label(&label)
+            } else {
+                false
+            }
+        })
+        .unwrap_or_default()
+    }
+
     pub fn renderer_debug_info(&self) -> JsValue {
         self.with_core(|core| JsValue::from_str(&core.renderer().debug_info()))
             .unwrap_or(JsValue::NULL)
     }
 
+    /// Returns the compatibility report of the movie as JSON.
+    pub fn compatibility_report(&self) -> JsValue {
+        self.with_core(|core| JsValue::from_str(&core.compatibility_report().to_json()))
+            .unwrap_or(JsValue::NULL)
+    }
+
     // after the context menu is closed, remember to call `clear_custom_menu_items`!
     pub fn prepare_context_menu(&mut self) -> JsValue {
         self.with_core_mut(|core| {
@@ -316,14 +435,28 @@ impl Ruffle {
         let _ = self.with_core_mut(|core| core.set_fullscreen(is_fullscreen));
     }
 
+    /// Renders the current frame right away.
+    ///
+    /// The canvas may be cleared once the browser has composited it, so this must be called
+    /// in the same task as anything that reads the canvas back, such as taking a screenshot.
+    pub fn render(&mut self) {
+        let _ = self.with_core_mut(|core| core.render());
+    }
+
     pub fn clear_custom_menu_items(&mut self) {
         let _ = self.with_core_mut(Player::clear_custom_menu_items);
     }
 
+    pub fn resolve_dialog(&mut self, id: u32, result: JsValue) {
+        let result = dialogs::dialog_result_from_js(&result);
+        let _ = self.with_core_mut(|core| core.resolve_dialog(DialogId(id), result));
+    }
+
     pub fn destroy(&mut self) {
         // Remove instance from the active list.
         if let Ok(mut instance) = self.remove_instance() {
             instance.canvas.remove();
+            instance.ime_input.remove();
 
             // Stop all audio playing from the instance.
             let _ = instance.with_core_mut(|core| {
@@ -383,6 +516,16 @@ impl Ruffle {
                     .warn_on_error();
                 instance.mouse_up_callback = None;
             }
+            if let Some(touch_cancel_callback) = &instance.touch_cancel_callback {
+                let canvas_events: &EventTarget = instance.canvas.as_ref();
+                canvas_events
+                    .remove_event_listener_with_callback(
+                        "pointercancel",
+                        touch_cancel_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.touch_cancel_callback = None;
+            }
             if let Some(mouse_wheel_callback) = &instance.mouse_wheel_callback {
                 let canvas_events: &EventTarget = instance.canvas.as_ref();
                 canvas_events
@@ -413,6 +556,77 @@ impl Ruffle {
                     .warn_on_error();
                 instance.key_up_callback = None;
             }
+            if let Some(composition_update_callback) = &instance.composition_update_callback {
+                instance
+                    .ime_input
+                    .remove_event_listener_with_callback(
+                        "compositionupdate",
+                        composition_update_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.composition_update_callback = None;
+            }
+            if let Some(composition_end_callback) = &instance.composition_end_callback {
+                instance
+                    .ime_input
+                    .remove_event_listener_with_callback(
+                        "compositionend",
+                        composition_end_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.composition_end_callback = None;
+            }
+            if let Some(input_callback) = &instance.input_callback {
+                instance
+                    .ime_input
+                    .remove_event_listener_with_callback(
+                        "input",
+                        input_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.input_callback = None;
+            }
+            if let Some(copy_callback) = &instance.copy_callback {
+                instance
+                    .window
+                    .remove_event_listener_with_callback(
+                        "copy",
+                        copy_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.copy_callback = None;
+            }
+            if let Some(cut_callback) = &instance.cut_callback {
+                instance
+                    .window
+                    .remove_event_listener_with_callback(
+                        "cut",
+                        cut_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.cut_callback = None;
+            }
+            if let Some(paste_callback) = &instance.paste_callback {
+                instance
+                    .window
+                    .remove_event_listener_with_callback(
+                        "paste",
+                        paste_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.paste_callback = None;
+            }
+            if let Some(viewport_resize_callback) = &instance.viewport_resize_callback {
+                if let Some(visual_viewport) = instance.window.visual_viewport() {
+                    visual_viewport
+                        .remove_event_listener_with_callback(
+                            "resize",
+                            viewport_resize_callback.as_ref().unchecked_ref(),
+                        )
+                        .warn_on_error();
+                }
+                instance.viewport_resize_callback = None;
+            }
             if let Some(unload_callback) = &instance.unload_callback {
                 instance
                     .window
@@ -509,6 +723,25 @@ impl Ruffle {
             .append_child(&canvas.clone().into())
             .into_js_result()?;
 
+        // Input methods only compose text in editable elements, so an invisible one takes the
+        // keyboard focus while an input text field is focused. Focusing it also brings up the
+        // soft keyboard on touch devices, which iOS zooms in for unless the text is 16px or larger.
+        let ime_input: HtmlInputElement = document
+            .create_element("input")
+            .into_js_result()?
+            .dyn_into()
+            .map_err(|_| "Expected HtmlInputElement")?;
+        ime_input
+            .set_attribute(
+                "style",
+                "position: absolute; width: 1px; height: 1px; padding: 0; border: 0; opacity: 0; pointer-events: none; font-size: 16px;",
+            )
+            .warn_on_error();
+        ime_input.set_tab_index(-1);
+        parent
+            .append_child(&ime_input.clone().into())
+            .into_js_result()?;
+
         if let Ok(audio) = audio::WebAudioBackend::new() {
             builder = builder.with_audio(audio);
         } else {
@@ -518,6 +751,7 @@ impl Ruffle {
             allow_script_access,
             config.upgrade_to_https,
             config.base_url,
+            config.socket_proxy,
         ));
 
         match window.local_storage() {
@@ -532,15 +766,32 @@ impl Ruffle {
         let trace_observer = Arc::new(RefCell::new(JsValue::UNDEFINED));
         let core = builder
             .with_log(log_adapter::WebLogBackend::new(trace_observer.clone()))
-            .with_ui(ui::WebUiBackend::new(js_player.clone(), &canvas))
+            .with_ui(ui::WebUiBackend::new(
+                js_player.clone(),
+                &canvas,
+                &ime_input,
+            ))
+            .with_dialogs(dialogs::WebDialogBackend::new(js_player.clone()))
+            .with_local_connection(MemoryLocalConnectionBackend::new(
+                LOCAL_CONNECTIONS.with(LocalConnectionHub::clone),
+            ))
             .with_video(SoftwareVideoBackend::new())
             .with_letterbox(config.letterbox)
             .with_max_execution_duration(config.max_execution_duration)
             .with_warn_on_unsupported_content(config.warn_on_unsupported_content)
             .with_player_version(config.player_version)
+            .with_resampler_quality(config.resampler_quality)
+            .with_navigation_policy(config.navigation)
+            .with_font_map(config.font_map)
+            .with_font_fallbacks(config.font_fallbacks)
+            // A movie in a page is played by the browser plugin, which leaves the projector
+            // commands of `fscommand` to the page.
+            .with_player_type(PlayerType::PlugIn)
+            .with_spoofed_url(spoofed_url(config.spoof_url.as_deref()))
             .build();
 
         let mut callstack = None;
+        let mut events = None;
         if let Ok(mut core) = core.try_lock() {
             // Set config parameters.
             if let Some(color) = config.background_color.and_then(parse_html_color) {
@@ -550,13 +801,16 @@ impl Ruffle {
             core.set_stage_align(config.salign.as_deref().unwrap_or(""));
             core.set_quality(config.quality.as_deref().unwrap_or("high"));
             core.set_scale_mode(config.scale.as_deref().unwrap_or("showAll"));
-            core.set_window_mode(config.wmode.as_deref().unwrap_or("window"));
+            if let Some(wmode) = &config.wmode {
+                core.set_window_mode(wmode);
+            }
 
             // Create the external interface.
             if allow_script_access {
                 core.add_external_interface(Box::new(JavascriptInterface::new(js_player.clone())));
             }
             callstack = Some(core.callstack());
+            events = Some(core.subscribe_events());
         }
 
         // Create instance.
@@ -576,14 +830,25 @@ impl Ruffle {
             player_mouse_down_callback: None,
             window_mouse_down_callback: None,
             mouse_up_callback: None,
+            touch_cancel_callback: None,
             mouse_wheel_callback: None,
             key_down_callback: None,
             key_up_callback: None,
+            composition_update_callback: None,
+            composition_end_callback: None,
+            input_callback: None,
+            copy_callback: None,
+            cut_callback: None,
+            paste_callback: None,
+            viewport_resize_callback: None,
             unload_callback: None,
+            ime_input: ime_input.clone(),
             timestamp: None,
             has_focus: false,
             trace_observer,
             log_subscriber,
+            site_locks: config.site_locks,
+            events,
         };
 
         // Prevent touch-scrolling on canvas.
@@ -604,10 +869,12 @@ impl Ruffle {
             // Create mouse move handler.
             let mouse_move_callback = Closure::wrap(Box::new(move |js_event: PointerEvent| {
                 let _ = ruffle.with_instance(move |instance| {
-                    let event = PlayerEvent::MouseMove {
-                        x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
-                        y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
-                    };
+                    let device_pixel_ratio = instance.device_pixel_ratio;
+                    let event = touch_event(&js_event, TouchPhase::Move, device_pixel_ratio)
+                        .unwrap_or_else(|| PlayerEvent::MouseMove {
+                            x: f64::from(js_event.offset_x()) * device_pixel_ratio,
+                            y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+                        });
                     let _ = instance.with_core_mut(|core| {
                         core.handle_event(event);
                     });
@@ -636,16 +903,17 @@ impl Ruffle {
                             .set_pointer_capture(js_event.pointer_id());
                     }
                     let device_pixel_ratio = instance.device_pixel_ratio;
-                    let event = PlayerEvent::MouseDown {
-                        x: f64::from(js_event.offset_x()) * device_pixel_ratio,
-                        y: f64::from(js_event.offset_y()) * device_pixel_ratio,
-                        button: match js_event.button() {
-                            0 => MouseButton::Left,
-                            1 => MouseButton::Middle,
-                            2 => MouseButton::Right,
-                            _ => MouseButton::Unknown,
-                        },
-                    };
+                    let event = touch_event(&js_event, TouchPhase::Start, device_pixel_ratio)
+                        .unwrap_or_else(|| PlayerEvent::MouseDown {
+                            x: f64::from(js_event.offset_x()) * device_pixel_ratio,
+                            y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+                            button: match js_event.button() {
+                                0 => MouseButton::Left,
+                                1 => MouseButton::Middle,
+                                2 => MouseButton::Right,
+                                _ => MouseButton::Unknown,
+                            },
+                        });
                     let _ = instance.with_core_mut(|core| {
                         core.handle_event(event);
                     });
@@ -710,16 +978,18 @@ impl Ruffle {
                             .unchecked_ref::<Element>()
                             .release_pointer_capture(js_event.pointer_id());
                     }
-                    let event = PlayerEvent::MouseUp {
-                        x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
-                        y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
-                        button: match js_event.button() {
-                            0 => MouseButton::Left,
-                            1 => MouseButton::Middle,
-                            2 => MouseButton::Right,
-                            _ => MouseButton::Unknown,
-                        },
-                    };
+                    let device_pixel_ratio = instance.device_pixel_ratio;
+                    let event = touch_event(&js_event, TouchPhase::End, device_pixel_ratio)
+                        .unwrap_or_else(|| PlayerEvent::MouseUp {
+                            x: f64::from(js_event.offset_x()) * device_pixel_ratio,
+                            y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+                            button: match js_event.button() {
+                                0 => MouseButton::Left,
+                                1 => MouseButton::Middle,
+                                2 => MouseButton::Right,
+                                _ => MouseButton::Unknown,
+                            },
+                        });
                     let _ = instance.with_core_mut(|core| {
                         core.handle_event(event);
                     });
@@ -739,6 +1009,29 @@ impl Ruffle {
                 .warn_on_error();
             instance.mouse_up_callback = Some(mouse_up_callback);
 
+            // Create touch cancel handler, for touches the browser took over.
+            let touch_cancel_callback = Closure::wrap(Box::new(move |js_event: PointerEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    if let Some(event) =
+                        touch_event(&js_event, TouchPhase::Cancel, instance.device_pixel_ratio)
+                    {
+                        let _ = instance.with_core_mut(|core| {
+                            core.handle_event(event);
+                        });
+                    }
+                });
+            })
+                as Box<dyn FnMut(PointerEvent)>);
+
+            let canvas_events: &EventTarget = canvas.as_ref();
+            canvas_events
+                .add_event_listener_with_callback(
+                    "pointercancel",
+                    touch_cancel_callback.as_ref().unchecked_ref(),
+                )
+                .warn_on_error();
+            instance.touch_cancel_callback = Some(touch_cancel_callback);
+
             // Create mouse wheel handler.
             let mouse_wheel_callback = Closure::wrap(Box::new(move |js_event: WheelEvent| {
                 let _ = ruffle.with_instance(|instance| {
@@ -771,7 +1064,16 @@ impl Ruffle {
             // Create keydown event handler.
             let key_down_callback = Closure::wrap(Box::new(move |js_event: KeyboardEvent| {
                 let _ = ruffle.with_instance(|instance| {
-                    if instance.has_focus {
+                    // Keys typed into an input method arrive as composition events instead, and
+                    // those of soft keyboards as input events.
+                    let is_composing = js_event.is_composing()
+                        || matches!(js_event.key().as_str(), "Process" | "Unidentified");
+                    // The clipboard shortcuts are left to the browser, which only hands over the
+                    // pasted text with the paste event.
+                    let is_clipboard_shortcut = (js_event.ctrl_key() || js_event.meta_key())
+                        && !js_event.alt_key()
+                        && matches!(js_event.code().as_str(), "KeyC" | "KeyX" | "KeyV");
+                    if instance.has_focus && !is_composing && !is_clipboard_shortcut {
                         let _ = instance.with_core_mut(|core| {
                             let key_code = web_to_ruffle_key_code(&js_event.code());
                             let key_char = web_key_to_codepoint(&js_event.key());
@@ -814,6 +1116,146 @@ impl Ruffle {
                 .warn_on_error();
             instance.key_up_callback = Some(key_up_callback);
 
+            // Create composition event handlers.
+            let composition_update_callback =
+                Closure::wrap(Box::new(move |js_event: CompositionEvent| {
+                    let _ = ruffle.with_core_mut(|core| {
+                        core.handle_event(PlayerEvent::ImePreedit {
+                            text: js_event.data().unwrap_or_default(),
+                        });
+                    });
+                }) as Box<dyn FnMut(CompositionEvent)>);
+
+            ime_input
+                .add_event_listener_with_callback(
+                    "compositionupdate",
+                    composition_update_callback.as_ref().unchecked_ref(),
+                )
+                .warn_on_error();
+            instance.composition_update_callback = Some(composition_update_callback);
+
+            let composition_end_callback =
+                Closure::wrap(Box::new(move |js_event: CompositionEvent| {
+                    let _ = ruffle.with_instance(|instance| {
+                        let _ = instance.with_core_mut(|core| {
+                            core.handle_event(PlayerEvent::ImeCommit {
+                                text: js_event.data().unwrap_or_default(),
+                            });
+                        });
+                        instance.ime_input.set_value("");
+                    });
+                }) as Box<dyn FnMut(CompositionEvent)>);
+
+            ime_input
+                .add_event_listener_with_callback(
+                    "compositionend",
+                    composition_end_callback.as_ref().unchecked_ref(),
+                )
+                .warn_on_error();
+            instance.composition_end_callback = Some(composition_end_callback);
+
+            // Create input event handler. Soft keyboards send no key codes for most keys, so the
+            // text typed on them only arrives as it's put into the input element.
+            let input_callback = Closure::wrap(Box::new(move |js_event: InputEvent| {
+                if js_event.is_composing() {
+                    return;
+                }
+                let _ = ruffle.with_instance(|instance| {
+                    let _ = instance.with_core_mut(|core| match js_event.input_type().as_str() {
+                        "insertText" | "insertReplacementText" => {
+                            for codepoint in js_event.data().unwrap_or_default().chars() {
+                                core.handle_event(PlayerEvent::TextInput { codepoint });
+                            }
+                        }
+                        "deleteContentBackward" => {
+                            core.handle_event(PlayerEvent::TextInput { codepoint: '\u{8}' });
+                        }
+                        "deleteContentForward" => {
+                            core.handle_event(PlayerEvent::TextInput {
+                                codepoint: '\u{7f}',
+                            });
+                        }
+                        _ => {}
+                    });
+                    instance.ime_input.set_value("");
+                });
+            }) as Box<dyn FnMut(InputEvent)>);
+
+            ime_input
+                .add_event_listener_with_callback("input", input_callback.as_ref().unchecked_ref())
+                .warn_on_error();
+            instance.input_callback = Some(input_callback);
+
+            // Create clipboard event handlers.
+            let copy_callback = Closure::wrap(Box::new(move |js_event: ClipboardEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    if instance.has_focus {
+                        // The copied text is written to the clipboard by the UI backend.
+                        let _ = instance.with_core_mut(|core| core.handle_event(PlayerEvent::Copy));
+                        js_event.prevent_default();
+                    }
+                });
+            }) as Box<dyn FnMut(ClipboardEvent)>);
+
+            window
+                .add_event_listener_with_callback("copy", copy_callback.as_ref().unchecked_ref())
+                .warn_on_error();
+            instance.copy_callback = Some(copy_callback);
+
+            let cut_callback = Closure::wrap(Box::new(move |js_event: ClipboardEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    if instance.has_focus {
+                        let _ = instance.with_core_mut(|core| core.handle_event(PlayerEvent::Cut));
+                        js_event.prevent_default();
+                    }
+                });
+            }) as Box<dyn FnMut(ClipboardEvent)>);
+
+            window
+                .add_event_listener_with_callback("cut", cut_callback.as_ref().unchecked_ref())
+                .warn_on_error();
+            instance.cut_callback = Some(cut_callback);
+
+            let paste_callback = Closure::wrap(Box::new(move |js_event: ClipboardEvent| {
+                let _ = ruffle.with_instance(|instance| {
+                    if instance.has_focus {
+                        let text = js_event
+                            .clipboard_data()
+                            .and_then(|data| data.get_data("text/plain").ok())
+                            .unwrap_or_default();
+                        let _ = instance
+                            .with_core_mut(|core| core.handle_event(PlayerEvent::Paste { text }));
+                        js_event.prevent_default();
+                    }
+                });
+            }) as Box<dyn FnMut(ClipboardEvent)>);
+
+            window
+                .add_event_listener_with_callback("paste", paste_callback.as_ref().unchecked_ref())
+                .warn_on_error();
+            instance.paste_callback = Some(paste_callback);
+
+            // Create visual viewport resize handler, which keeps the focused text field in view
+            // when the soft keyboard covers part of the page.
+            if let Some(visual_viewport) = window.visual_viewport() {
+                let viewport_resize_callback = Closure::wrap(Box::new(move |_| {
+                    let _ = ruffle.with_instance(|instance| {
+                        if instance.ime_input.matches(":focus").unwrap_or_default() {
+                            ui::scroll_into_view(&instance.ime_input);
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(Event)>);
+
+                visual_viewport
+                    .add_event_listener_with_callback(
+                        "resize",
+                        viewport_resize_callback.as_ref().unchecked_ref(),
+                    )
+                    .warn_on_error();
+                instance.viewport_resize_callback = Some(viewport_resize_callback);
+            }
+
             let unload_callback = Closure::wrap(Box::new(move |_| {
                 let _ = ruffle.with_core_mut(|core| {
                     core.flush_shared_objects();
@@ -856,6 +1298,32 @@ impl Ruffle {
         })?
     }
 
+    /// Finds the site lock configured for the movie at `movie_url`.
+    ///
+    /// Entries match the movie URL either exactly, or without its query.
+    fn site_lock_for(&self, movie_url: &str) -> Option<SiteLock> {
+        let original_url = self
+            .with_instance(|instance| {
+                let without_query = movie_url.split(['?', '#']).next().unwrap_or(movie_url);
+                instance
+                    .site_locks
+                    .get(movie_url)
+                    .or_else(|| instance.site_locks.get(without_query))
+                    .cloned()
+            })
+            .ok()
+            .flatten()?;
+        let site_lock = SiteLock::new(&original_url);
+        if site_lock.is_none() {
+            tracing::warn!(
+                "Ignoring site lock of {} with invalid original URL \"{}\"",
+                movie_url,
+                original_url
+            );
+        }
+        site_lock
+    }
+
     /// Runs the given function on this Ruffle instance.
     fn with_instance<F, O>(&self, f: F) -> Result<O, RuffleInstanceError>
     where
@@ -1041,6 +1509,17 @@ impl Ruffle {
                 core.render();
             }
         });
+
+        // Tell the page about what the movie did.
+        let _ = self.with_instance_mut(|instance| {
+            if let Some(events) = &mut instance.events {
+                while let Ok(Some(event)) = events.try_next() {
+                    if let EmbedderEvent::QualityChanged { quality } = event {
+                        instance.js_player.on_quality_changed(&quality.to_string());
+                    }
+                }
+            }
+        });
     }
 
     fn on_metadata(&self, swf_header: &ruffle_core::swf::HeaderExt) {
@@ -1191,6 +1670,11 @@ impl ExternalInterfaceProvider for JavascriptInterface {
             .on_fs_command(command, args)
             .unwrap_or_default()
     }
+
+    fn object_id(&self) -> Option<String> {
+        // The player element takes the attributes of the `<object>` or `<embed>` it replaces.
+        Some(self.js_player.id()).filter(|id| !id.is_empty())
+    }
 }
 
 fn js_to_external_value(js: &JsValue) -> ExternalValue {
@@ -1296,6 +1780,8 @@ async fn create_renderer(
                 }
                 Err(error) => tracing::error!("Error creating wgpu webgpu renderer: {}", error),
             }
+        } else {
+            tracing::info!("WebGPU is not available in this browser");
         }
     }
     #[cfg(all(feature = "wgpu-webgl", target_family = "wasm"))]
@@ -1404,6 +1890,24 @@ pub fn set_panic_handler() {
     });
 }
 
+/// Turns a pointer event of a finger into a touch for the player, or `None` for a mouse or pen.
+fn touch_event(
+    js_event: &PointerEvent,
+    phase: TouchPhase,
+    device_pixel_ratio: f64,
+) -> Option<PlayerEvent> {
+    if js_event.pointer_type() != "touch" {
+        return None;
+    }
+    Some(PlayerEvent::Touch {
+        id: js_event.pointer_id() as u64,
+        phase,
+        x: f64::from(js_event.offset_x()) * device_pixel_ratio,
+        y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+        pressure: Some(js_event.pressure().into()),
+    })
+}
+
 fn parse_movie_parameters(input: &JsValue) -> Vec<(String, String)> {
     let mut params = Vec::new();
     if let Ok(keys) = js_sys::Reflect::own_keys(input) {
@@ -1418,6 +1922,21 @@ fn parse_movie_parameters(input: &JsValue) -> Vec<(String, String)> {
     params
 }
 
+/// Validates the `spoofUrl` option.
+///
+/// The spoofed URL is only ever reported to the movie, so it doesn't need to
+/// be reachable, but it has to be absolute for the movie to make sense of it.
+fn spoofed_url(url: Option<&str>) -> Option<String> {
+    let url = url?;
+    match Url::parse(url) {
+        Ok(url) => Some(url.to_string()),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid spoofUrl \"{}\": {}", url, e);
+            None
+        }
+    }
+}
+
 fn parse_html_color(color: impl AsRef<str>) -> Option<Color> {
     // Parse classic HTML hex color (XXXXXX or #XXXXXX), attempting to match browser behavior.
     // Optional leading #.
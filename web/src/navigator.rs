@@ -1,22 +1,33 @@
 //! Navigator backend for web
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use futures::channel::{mpsc, oneshot};
+use futures::{stream, StreamExt};
+use js_sys::{Array, ArrayBuffer, Function, Object, Promise, Reflect, Uint8Array};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, Request, Response,
+    parse_content_range, NavigationMethod, NavigatorBackend, OwnedFuture, Request, Response,
+    SocketConnection, StreamingResponse,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
 use url::Url;
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{
-    window, Blob, BlobPropertyBag, Request as WebRequest, RequestInit, Response as WebResponse,
+    window, BinaryType, Blob, BlobPropertyBag, Headers, MessageEvent, Request as WebRequest,
+    RequestInit, RequestRedirect, Response as WebResponse, WebSocket,
 };
 
 pub struct WebNavigatorBackend {
     allow_script_access: bool,
     upgrade_to_https: bool,
     base_url: Option<Url>,
+
+    /// The WebSocket proxy that socket connections are tunneled through, as
+    /// browsers can't open TCP connections.
+    socket_proxy: Option<String>,
 }
 
 impl WebNavigatorBackend {
@@ -24,6 +35,7 @@ impl WebNavigatorBackend {
         allow_script_access: bool,
         upgrade_to_https: bool,
         base_url: Option<String>,
+        socket_proxy: Option<String>,
     ) -> Self {
         let window = web_sys::window().expect("window()");
 
@@ -62,6 +74,7 @@ impl WebNavigatorBackend {
             allow_script_access,
             upgrade_to_https,
             base_url,
+            socket_proxy,
         }
     }
 
@@ -118,7 +131,8 @@ impl NavigatorBackend for WebNavigatorBackend {
                     "method",
                     match navmethod {
                         NavigationMethod::Get => "get",
-                        NavigationMethod::Post => "post",
+                        // Forms can only be submitted with GET or POST.
+                        _ => "post",
                     },
                 );
 
@@ -153,58 +167,23 @@ impl NavigatorBackend for WebNavigatorBackend {
         };
     }
 
+    fn run_javascript(&self, script: &str) {
+        if !self.allow_script_access {
+            tracing::warn!("SWF tried to run a script, but script access is not allowed");
+            return;
+        }
+
+        // Like a `javascript:` URL, the script runs in the global scope of the page.
+        if let Err(e) = js_sys::eval(script) {
+            tracing::warn!("Script run by SWF threw an error: {:?}", e);
+        }
+    }
+
     fn fetch(&self, request: Request) -> OwnedFuture<Response, Error> {
         let url = self.resolve_url(request.url()).into_owned();
 
         Box::pin(async move {
-            let mut init = RequestInit::new();
-
-            init.method(match request.method() {
-                NavigationMethod::Get => "GET",
-                NavigationMethod::Post => "POST",
-            });
-
-            if let Some((data, mime)) = request.body() {
-                let arraydata = ArrayBuffer::new(data.len() as u32);
-                let u8data = Uint8Array::new(&arraydata);
-
-                for (i, byte) in data.iter().enumerate() {
-                    u8data.fill(*byte, i as u32, i as u32 + 1);
-                }
-
-                let blobparts = Array::new();
-                blobparts.push(&arraydata);
-
-                let mut blobprops = BlobPropertyBag::new();
-                blobprops.type_(mime);
-
-                let datablob =
-                    Blob::new_with_buffer_source_sequence_and_options(&blobparts, &blobprops)
-                        .map_err(|_| Error::FetchError("Got JS error".to_string()))?
-                        .dyn_into()
-                        .map_err(|_| Error::FetchError("Got JS error".to_string()))?;
-
-                init.body(Some(&datablob));
-            }
-
-            let request = WebRequest::new_with_str_and_init(&url, &init)
-                .map_err(|_| Error::FetchError(format!("Unable to create request for {url}")))?;
-
-            let window = web_sys::window().expect("window()");
-            let fetchval = JsFuture::from(window.fetch_with_request(&request))
-                .await
-                .map_err(|_| Error::FetchError("Got JS error".to_string()))?;
-
-            let response: WebResponse = fetchval
-                .dyn_into()
-                .map_err(|_| Error::FetchError("Fetch result wasn't a WebResponse".to_string()))?;
-            if !response.ok() {
-                return Err(Error::FetchError(format!(
-                    "HTTP status is not ok, got {}",
-                    response.status_text()
-                )));
-            }
-
+            let response = send_request(&url, &request, 0).await?;
             let url = response.url();
 
             let body: ArrayBuffer = JsFuture::from(
@@ -226,6 +205,152 @@ impl NavigatorBackend for WebNavigatorBackend {
         })
     }
 
+    fn fetch_streaming(
+        &self,
+        request: Request,
+        offset: usize,
+    ) -> OwnedFuture<StreamingResponse, Error> {
+        let url = self.resolve_url(request.url()).into_owned();
+
+        Box::pin(async move {
+            let response = send_request(&url, &request, offset).await?;
+            let url = response.url();
+
+            let header = |name| response.headers().get(name).ok().flatten();
+            let (offset, total_length) = if response.status() == 206 {
+                // Cross-origin servers need to expose `Content-Range` for it
+                // to be readable.
+                header("Content-Range")
+                    .as_deref()
+                    .and_then(parse_content_range)
+                    .unwrap_or((offset, None))
+            } else {
+                let total_length = header("Content-Length").and_then(|length| length.parse().ok());
+                (0, total_length)
+            };
+
+            // Cross-origin servers need to expose headers for them to be
+            // listed.
+            let headers = js_sys::try_iter(&response.headers())
+                .ok()
+                .flatten()
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| {
+                            let entry: Array = entry.ok()?.dyn_into().ok()?;
+                            Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let reader: Object = response
+                .body()
+                .ok_or_else(|| Error::FetchError("Response has no body".to_string()))?
+                .get_reader();
+            let body = stream::unfold(Some(reader), |reader| async move {
+                let reader = reader?;
+                match read_chunk(&reader).await {
+                    Ok(Some(chunk)) => Some((Ok(chunk), Some(reader))),
+                    Ok(None) => None,
+                    Err(e) => Some((Err(e), None)),
+                }
+            });
+
+            Ok(StreamingResponse {
+                url,
+                status: response.status(),
+                headers,
+                offset,
+                total_length,
+                body: Box::pin(body),
+            })
+        })
+    }
+
+    fn connect_socket(&self, host: &str, port: u16) -> OwnedFuture<SocketConnection, Error> {
+        // The proxy is given the target with the `host` and `port` query
+        // parameters, and forwards the binary messages in both directions.
+        let url = self
+            .socket_proxy
+            .as_deref()
+            .ok_or_else(|| Error::ConnectError("No socket proxy is configured".to_string()))
+            .and_then(|proxy| {
+                Url::parse(proxy).map_err(|e| Error::ConnectError(format!("Invalid proxy: {e}")))
+            });
+        let mut url = match url {
+            Ok(url) => url,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+        url.query_pairs_mut()
+            .append_pair("host", host)
+            .append_pair("port", &port.to_string());
+
+        let socket = match WebSocket::new(url.as_str()) {
+            Ok(socket) => socket,
+            Err(_) => {
+                let msg = format!("Unable to open WebSocket to {url}");
+                return Box::pin(async move { Err(Error::ConnectError(msg)) });
+            }
+        };
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let (incoming_sender, incoming) = mpsc::unbounded();
+        let (outgoing, mut outgoing_receiver) = mpsc::unbounded::<Vec<u8>>();
+        let (opened_sender, opened) = oneshot::channel();
+        let opened_sender = Rc::new(RefCell::new(Some(opened_sender)));
+
+        let on_open = {
+            let opened_sender = opened_sender.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                if let Some(sender) = opened_sender.borrow_mut().take() {
+                    let _ = sender.send(true);
+                }
+            })
+        };
+        let on_message = {
+            let incoming_sender = incoming_sender.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                if let Ok(data) = event.data().dyn_into::<ArrayBuffer>() {
+                    let _ = incoming_sender.unbounded_send(Uint8Array::new(&data).to_vec());
+                }
+            })
+        };
+        let on_close = Closure::<dyn FnMut()>::new(move || {
+            if let Some(sender) = opened_sender.borrow_mut().take() {
+                let _ = sender.send(false);
+            }
+            incoming_sender.close_channel();
+        });
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_close.as_ref().unchecked_ref()));
+
+        Box::pin(async move {
+            if !opened.await.unwrap_or(false) {
+                clear_socket_handlers(&socket);
+                return Err(Error::ConnectError(format!(
+                    "Unable to connect through proxy {url}"
+                )));
+            }
+
+            // The handlers are kept alive until the player is done with the socket.
+            spawn_local(async move {
+                while let Some(data) = outgoing_receiver.next().await {
+                    if socket.send_with_u8_array(&data).is_err() {
+                        break;
+                    }
+                }
+                clear_socket_handlers(&socket);
+                let _ = socket.close();
+                drop((on_open, on_message, on_close));
+            });
+
+            Ok(SocketConnection { incoming, outgoing })
+        })
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         spawn_local(async move {
             if let Err(e) = future.await {
@@ -241,3 +366,103 @@ impl NavigatorBackend for WebNavigatorBackend {
         url
     }
 }
+
+/// Sends a request, asking for the response body from byte `offset` onwards.
+async fn send_request(url: &str, request: &Request, offset: usize) -> Result<WebResponse, Error> {
+    let mut init = RequestInit::new();
+
+    init.method(request.method().as_str());
+
+    if !request.follow_redirects() {
+        // Browsers don't expose the redirect itself, so this fails the request instead.
+        init.redirect(RequestRedirect::Manual);
+    }
+
+    if let Some((data, mime)) = request.body() {
+        let arraydata = ArrayBuffer::new(data.len() as u32);
+        let u8data = Uint8Array::new(&arraydata);
+
+        for (i, byte) in data.iter().enumerate() {
+            u8data.fill(*byte, i as u32, i as u32 + 1);
+        }
+
+        let blobparts = Array::new();
+        blobparts.push(&arraydata);
+
+        let mut blobprops = BlobPropertyBag::new();
+        blobprops.type_(mime);
+
+        let datablob = Blob::new_with_buffer_source_sequence_and_options(&blobparts, &blobprops)
+            .map_err(|_| Error::FetchError("Got JS error".to_string()))?
+            .dyn_into()
+            .map_err(|_| Error::FetchError("Got JS error".to_string()))?;
+
+        init.body(Some(&datablob));
+    }
+
+    let headers = Headers::new().map_err(|_| Error::FetchError("Got JS error".to_string()))?;
+    for (name, value) in request.headers() {
+        headers
+            .append(name, value)
+            .map_err(|_| Error::FetchError(format!("Unable to set request header {name}")))?;
+    }
+    if offset > 0 {
+        headers
+            .set("Range", &format!("bytes={offset}-"))
+            .map_err(|_| Error::FetchError("Got JS error".to_string()))?;
+    }
+    init.headers(&headers);
+
+    let request = WebRequest::new_with_str_and_init(url, &init)
+        .map_err(|_| Error::FetchError(format!("Unable to create request for {url}")))?;
+
+    let window = web_sys::window().expect("window()");
+    let fetchval = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(|_| Error::FetchError("Got JS error".to_string()))?;
+
+    let response: WebResponse = fetchval
+        .dyn_into()
+        .map_err(|_| Error::FetchError("Fetch result wasn't a WebResponse".to_string()))?;
+    if !response.ok() {
+        return Err(Error::HttpNotOk(response.status()));
+    }
+
+    Ok(response)
+}
+
+/// Reads the next chunk of a response body from its `ReadableStreamDefaultReader`.
+///
+/// Returns `None` once the whole body was read.
+async fn read_chunk(reader: &Object) -> Result<Option<Vec<u8>>, Error> {
+    let js_error = |_| Error::FetchError("Got JS error".to_string());
+    let read: Function = Reflect::get(reader, &"read".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    let promise: Promise = read
+        .call0(reader)
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    let result = JsFuture::from(promise).await.map_err(js_error)?;
+
+    if Reflect::get(&result, &"done".into())
+        .map_err(js_error)?
+        .is_truthy()
+    {
+        return Ok(None);
+    }
+    let chunk: Uint8Array = Reflect::get(&result, &"value".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    Ok(Some(chunk.to_vec()))
+}
+
+fn clear_socket_handlers(socket: &WebSocket) {
+    socket.set_onopen(None);
+    socket.set_onmessage(None);
+    socket.set_onclose(None);
+    socket.set_onerror(None);
+}
@@ -1,8 +1,16 @@
 use super::JavascriptPlayer;
-use ruffle_core::backend::ui::{FullscreenError, MouseCursor, UiBackend};
+use js_sys::{Function, Object, Promise, Reflect};
+use ruffle_core::backend::ui::{CameraError, CameraFrame, FullscreenError, MouseCursor, UiBackend};
 use ruffle_web_common::JsResult;
 use std::borrow::Cow;
-use web_sys::HtmlCanvasElement;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlInputElement, HtmlVideoElement,
+    MediaStream, MediaStreamConstraints, ScrollIntoViewOptions, ScrollLogicalPosition,
+};
 
 /// An implementation of `UiBackend` utilizing `web_sys` bindings to input APIs.
 pub struct WebUiBackend {
@@ -10,15 +18,29 @@ pub struct WebUiBackend {
     canvas: HtmlCanvasElement,
     cursor_visible: bool,
     cursor: MouseCursor,
+    camera: Option<Camera>,
+
+    /// The invisible element that input methods compose text in.
+    ime_input: HtmlInputElement,
+
+    /// The text last put on the clipboard by the movie.
+    clipboard: String,
 }
 
 impl WebUiBackend {
-    pub fn new(js_player: JavascriptPlayer, canvas: &HtmlCanvasElement) -> Self {
+    pub fn new(
+        js_player: JavascriptPlayer,
+        canvas: &HtmlCanvasElement,
+        ime_input: &HtmlInputElement,
+    ) -> Self {
         Self {
             js_player,
             canvas: canvas.clone(),
             cursor_visible: true,
             cursor: MouseCursor::Arrow,
+            camera: None,
+            ime_input: ime_input.clone(),
+            clipboard: String::new(),
         }
     }
 
@@ -55,10 +77,36 @@ impl UiBackend for WebUiBackend {
         self.update_mouse_cursor();
     }
 
-    fn set_clipboard_content(&mut self, _content: String) {
-        //TODO: in AVM2 FP9+ this only works when called from a button handler due to sandbox
-        // restrictions
-        tracing::warn!("set clipboard not implemented");
+    fn clipboard_content(&mut self) -> String {
+        // The clipboard of the browser can only be read asynchronously, or while it's pasted.
+        self.clipboard.clone()
+    }
+
+    fn set_clipboard_content(&mut self, content: String) {
+        // Browsers only allow writing to the clipboard in response to user input, like Flash
+        // Player does in AVM2 movies.
+        let write_text = web_sys::window()
+            .and_then(|window| Reflect::get(&window.navigator(), &"clipboard".into()).ok())
+            .filter(|clipboard| !clipboard.is_undefined())
+            .and_then(|clipboard| {
+                let write_text = Reflect::get(&clipboard, &"writeText".into()).ok()?;
+                let write_text: &Function = write_text.dyn_ref()?;
+                Some(write_text.call1(&clipboard, &content.as_str().into()))
+            });
+        match write_text {
+            Some(Ok(promise)) => {
+                if let Ok(promise) = promise.dyn_into::<Promise>() {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Err(e) = JsFuture::from(promise).await {
+                            tracing::warn!("Couldn't set clipboard contents: {:?}", e);
+                        }
+                    });
+                }
+            }
+            Some(Err(e)) => tracing::warn!("Couldn't set clipboard contents: {:?}", e),
+            None => tracing::warn!("The clipboard isn't available"),
+        }
+        self.clipboard = content;
     }
 
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
@@ -71,6 +119,34 @@ impl UiBackend for WebUiBackend {
         }
     }
 
+    fn set_ime_allowed(&mut self, allowed: bool) {
+        if allowed {
+            self.ime_input.focus().warn_on_error();
+        } else {
+            self.ime_input.set_value("");
+            self.ime_input.blur().warn_on_error();
+        }
+    }
+
+    fn set_ime_position(&mut self, x: f64, y: f64) {
+        // The viewport is measured in device pixels, and the page in CSS pixels.
+        let device_pixel_ratio =
+            web_sys::window().map_or(1.0, |window| window.device_pixel_ratio());
+        let style = self.ime_input.style();
+        style
+            .set_property("left", &format!("{}px", x / device_pixel_ratio))
+            .warn_on_error();
+        style
+            .set_property("top", &format!("{}px", y / device_pixel_ratio))
+            .warn_on_error();
+
+        // The input element follows the caret, so the text being typed stays above the soft
+        // keyboard.
+        if ruffle_web_common::is_mobile_or_tablet() {
+            scroll_into_view(&self.ime_input);
+        }
+    }
+
     fn display_unsupported_message(&self) {
         self.js_player.display_unsupported_message()
     }
@@ -79,7 +155,207 @@ impl UiBackend for WebUiBackend {
         self.js_player.display_root_movie_download_failed_message()
     }
 
+    fn camera_names(&self) -> Vec<String> {
+        // Device names are only revealed once the user allowed access,
+        // so only the default camera is offered.
+        let has_media_devices = web_sys::window()
+            .and_then(|window| window.navigator().media_devices().ok())
+            .is_some();
+        if has_media_devices {
+            vec!["Default".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn max_touch_points(&self) -> u32 {
+        web_sys::window()
+            .and_then(|window| Reflect::get(&window.navigator(), &"maxTouchPoints".into()).ok())
+            .and_then(|max_touch_points| max_touch_points.as_f64())
+            .map_or(0, |max_touch_points| max_touch_points as u32)
+    }
+
+    fn start_camera(
+        &mut self,
+        index: usize,
+        width: u32,
+        height: u32,
+        fps: f64,
+    ) -> Result<(), CameraError> {
+        self.stop_camera();
+        if index != 0 {
+            return Err(CameraError::NotFound(index));
+        }
+        self.camera = Some(Camera::start(width, height, fps)?);
+        Ok(())
+    }
+
+    fn stop_camera(&mut self) {
+        self.camera = None;
+    }
+
+    fn take_camera_frame(&mut self) -> Option<CameraFrame> {
+        self.camera.as_mut().and_then(Camera::take_frame)
+    }
+
+    fn forward_navigation(&mut self, url: &str, target: &str) -> bool {
+        self.js_player
+            .on_navigate_to_url(url, target)
+            .unwrap_or_default()
+    }
+
     fn message(&self, message: &str) {
         self.js_player.display_message(message);
     }
 }
+
+/// Video captured from the user's camera through `getUserMedia`.
+/// Scrolls the page as little as possible to show `element`.
+pub fn scroll_into_view(element: &Element) {
+    let mut options = ScrollIntoViewOptions::new();
+    options.block(ScrollLogicalPosition::Nearest);
+    options.inline(ScrollLogicalPosition::Nearest);
+    element.scroll_into_view_with_scroll_into_view_options(&options);
+}
+
+struct Camera {
+    /// Plays the captured stream, so that its images can be drawn to `canvas`.
+    video: HtmlVideoElement,
+
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+
+    /// The captured stream, once the user allowed access to the camera.
+    stream: Rc<RefCell<Option<MediaStream>>>,
+
+    /// Whether the capture was stopped, possibly before the user answered.
+    stopped: Rc<Cell<bool>>,
+
+    /// The playback time of the last image that was taken.
+    last_time: f64,
+}
+
+impl Camera {
+    fn start(width: u32, height: u32, fps: f64) -> Result<Self, CameraError> {
+        let backend_error = |e: JsValue| CameraError::Backend(format!("{e:?}"));
+        let window = web_sys::window().ok_or(CameraError::Unsupported)?;
+        let media_devices = window
+            .navigator()
+            .media_devices()
+            .map_err(|_| CameraError::Unsupported)?;
+        let document = window.document().ok_or(CameraError::Unsupported)?;
+
+        // The camera picks the mode closest to the one that was asked for.
+        let constraints = Object::new();
+        for (key, value) in [
+            ("width", f64::from(width)),
+            ("height", f64::from(height)),
+            ("frameRate", fps),
+        ] {
+            let ideal = Object::new();
+            Reflect::set(&ideal, &"ideal".into(), &value.into()).map_err(backend_error)?;
+            Reflect::set(&constraints, &key.into(), &ideal).map_err(backend_error)?;
+        }
+        let user_media = media_devices
+            .get_user_media_with_constraints(MediaStreamConstraints::new().video(&constraints))
+            .map_err(backend_error)?;
+
+        let video: HtmlVideoElement = document
+            .create_element("video")
+            .map_err(backend_error)?
+            .unchecked_into();
+        video.set_muted(true);
+        let canvas: HtmlCanvasElement = document
+            .create_element("canvas")
+            .map_err(backend_error)?
+            .unchecked_into();
+        let context: CanvasRenderingContext2d = canvas
+            .get_context("2d")
+            .map_err(backend_error)?
+            .ok_or(CameraError::Unsupported)?
+            .unchecked_into();
+
+        let camera = Self {
+            video,
+            canvas,
+            context,
+            stream: Rc::new(RefCell::new(None)),
+            stopped: Rc::new(Cell::new(false)),
+            last_time: -1.0,
+        };
+
+        let video = camera.video.clone();
+        let stream = camera.stream.clone();
+        let stopped = camera.stopped.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let media_stream: MediaStream = match JsFuture::from(user_media).await {
+                Ok(media_stream) => media_stream.unchecked_into(),
+                Err(e) => {
+                    tracing::warn!("Couldn't access the camera: {:?}", e);
+                    return;
+                }
+            };
+            if stopped.get() {
+                stop_tracks(&media_stream);
+                return;
+            }
+            video.set_src_object(Some(&media_stream));
+            if let Err(e) = video.play() {
+                tracing::error!("Couldn't capture from the camera: {:?}", e);
+            }
+            *stream.borrow_mut() = Some(media_stream);
+        });
+
+        Ok(camera)
+    }
+
+    /// Returns the image currently shown by the camera, if it changed since the last call.
+    fn take_frame(&mut self) -> Option<CameraFrame> {
+        const HAVE_CURRENT_DATA: u16 = 2;
+        if self.video.ready_state() < HAVE_CURRENT_DATA {
+            return None;
+        }
+        let time = self.video.current_time();
+        if time == self.last_time {
+            return None;
+        }
+        self.last_time = time;
+
+        let (width, height) = (self.video.video_width(), self.video.video_height());
+        if width == 0 || height == 0 {
+            return None;
+        }
+        if self.canvas.width() != width || self.canvas.height() != height {
+            self.canvas.set_width(width);
+            self.canvas.set_height(height);
+        }
+        self.context
+            .draw_image_with_html_video_element(&self.video, 0.0, 0.0)
+            .warn_on_error();
+        let image = self
+            .context
+            .get_image_data(0.0, 0.0, width.into(), height.into())
+            .ok()?;
+        Some(CameraFrame {
+            width,
+            height,
+            rgba: image.data().0,
+        })
+    }
+}
+
+impl Drop for Camera {
+    fn drop(&mut self) {
+        self.stopped.set(true);
+        self.video.set_src_object(None);
+        if let Some(stream) = self.stream.borrow_mut().take() {
+            stop_tracks(&stream);
+        }
+    }
+}
+
+fn stop_tracks(stream: &MediaStream) {
+    for track in stream.get_tracks().iter() {
+        track.unchecked_into::<web_sys::MediaStreamTrack>().stop();
+    }
+}